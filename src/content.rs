@@ -1,45 +1,467 @@
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+//! Content packs
+//!
+//! A content pack is a directory of JSON or TOML files that adds to the
+//! world without touching Rust. Each domain can live in one file named after
+//! it, or in a directory of that name holding any number of files:
+//!
+//! ```text
+//! my_pack/
+//!   locations.toml
+//!   npcs/
+//!     archivists.json
+//!     wardens.toml
+//!   theories.json
+//!   quests.json
+//!   items.toml
+//! ```
+//!
+//! Every file holds a list under its domain's name, so a TOML file reads
+//!
+//! ```toml
+//! [[locations]]
+//! id = "sunken_gallery"
+//! name = "Sunken Gallery"
+//! description = "Water laps at crystal shelves."
+//! exits = { north = "crystalline_archives" }
+//! ```
+//!
+//! and the same in JSON is `{"locations": [{"id": "sunken_gallery", ...}]}`.
+//! The whole pack is checked before anything is written: unknown fields,
+//! duplicate IDs, and references to locations, NPCs or theories that neither
+//! the pack nor the database has are all reported together, each with the
+//! file it came from.
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ContentLoader {
-    data_path: String,
-}
+use crate::core::world_state::Direction;
+use crate::persistence::content_io::ContentReport;
+use crate::persistence::DatabaseManager;
+use crate::systems::placed_items::ItemDefinition;
+use crate::systems::quests::QuestDefinition;
+use crate::GameResult;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
 
-impl ContentLoader {
-    pub fn new(data_path: String) -> Self {
-        Self { data_path }
-    }
+/// Domains a pack can hold, in the order they are written
+const DOMAINS: [&str; 5] = ["locations", "theories", "npcs", "quests", "items"];
 
-    pub fn load_all_content(&self) -> crate::GameResult<()> {
-        Ok(())
-    }
-}
+/// Factions an NPC can belong to
+const FACTIONS: [&str; 5] = [
+    "magisters_council", "underground_network", "order_of_harmony", "industrial_consortium", "neutral_scholars",
+];
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GameContent {
-    pub locations: HashMap<String, LocationContent>,
-    pub items: HashMap<String, ItemContent>,
-    pub npcs: HashMap<String, NpcContent>,
-}
+/// Understanding of a theory an NPC expects before discussing a topic that needs it
+const TOPIC_THEORY_UNDERSTANDING: f32 = 0.3;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A location as a pack writes it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct LocationContent {
+    pub id: String,
     pub name: String,
     pub description: String,
-    pub exits: HashMap<String, String>,
+    #[serde(default = "default_ambient_energy")]
+    pub ambient_energy: f32,
+    #[serde(default)]
+    pub dominant_frequency: Option<i32>,
+    #[serde(default)]
+    pub interference: f32,
+    #[serde(default)]
+    pub phenomena: Vec<String>,
+    /// Destination location by direction
+    #[serde(default)]
+    pub exits: BTreeMap<String, String>,
+}
+
+fn default_ambient_energy() -> f32 {
+    1.0
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ItemContent {
+/// A magic theory as a pack writes it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TheoryContent {
+    pub id: String,
     pub name: String,
     pub description: String,
-    pub value: i32,
+    /// 1 to 10; sets the theory's tier
+    pub complexity: i32,
+    /// Base minutes of study to learn it
+    pub learning_time: i32,
+    #[serde(default)]
+    pub prerequisites: Vec<String>,
+    #[serde(default)]
+    pub applications: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// An NPC as a pack writes it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct NpcContent {
+    pub id: String,
     pub name: String,
     pub description: String,
-    pub dialogue: Vec<String>,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub faction: Option<String>,
+    /// Where the NPC lives
+    pub location: String,
+    /// Lines the NPC greets the player with, one picked each time
+    pub greeting: Vec<String>,
+    #[serde(default)]
+    pub topics: BTreeMap<String, TopicContent>,
+}
+
+/// What an NPC says when asked about a topic
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TopicContent {
+    pub lines: Vec<String>,
+    /// Theory the player must have begun to understand first
+    #[serde(default)]
+    pub requires_theory: Option<String>,
+}
+
+/// Everything read from one pack
+#[derive(Debug, Default)]
+pub struct ContentPack {
+    pub locations: Vec<(PathBuf, LocationContent)>,
+    pub theories: Vec<(PathBuf, TheoryContent)>,
+    pub npcs: Vec<(PathBuf, NpcContent)>,
+    pub quests: Vec<(PathBuf, QuestDefinition)>,
+    pub items: Vec<(PathBuf, ItemDefinition)>,
+}
+
+/// Reads content packs and writes them into the database
+#[derive(Debug, Clone)]
+pub struct ContentLoader {
+    data_path: PathBuf,
+}
+
+impl ContentLoader {
+    pub fn new(data_path: impl Into<PathBuf>) -> Self {
+        Self { data_path: data_path.into() }
+    }
+
+    /// Read every file in the pack, without checking references
+    pub fn read_pack(&self) -> GameResult<ContentPack> {
+        if !self.data_path.is_dir() {
+            return Err(crate::GameError::ContentNotFound(format!(
+                "Content pack {} not found", self.data_path.display()
+            )).into());
+        }
+
+        let mut pack = ContentPack::default();
+        let mut problems = Vec::new();
+        for domain in DOMAINS {
+            for path in self.domain_files(domain)? {
+                let result = match domain {
+                    "locations" => read_entries(&path, domain).map(|entries| extend(&mut pack.locations, &path, entries)),
+                    "theories" => read_entries(&path, domain).map(|entries| extend(&mut pack.theories, &path, entries)),
+                    "npcs" => read_entries(&path, domain).map(|entries| extend(&mut pack.npcs, &path, entries)),
+                    "quests" => read_entries(&path, domain).map(|entries| extend(&mut pack.quests, &path, entries)),
+                    _ => read_entries(&path, domain).map(|entries| extend(&mut pack.items, &path, entries)),
+                };
+                if let Err(problem) = result {
+                    problems.push(problem);
+                }
+            }
+        }
+
+        if !problems.is_empty() {
+            return Err(pack_error(&self.data_path, &problems));
+        }
+        Ok(pack)
+    }
+
+    /// Check the pack against itself and the database, then write it in one
+    /// transaction, replacing entries with the same IDs
+    pub fn load_into(&self, db: &DatabaseManager) -> GameResult<ContentReport> {
+        let pack = self.read_pack()?;
+        let problems = validate(&pack, db)?;
+        if !problems.is_empty() {
+            return Err(pack_error(&self.data_path, &problems));
+        }
+
+        let transaction = db.connection().unchecked_transaction()?;
+        for (_, location) in &pack.locations {
+            db.insert_location(
+                &location.id, &location.name, &location.description, location.ambient_energy,
+                location.dominant_frequency, location.interference, &location.phenomena,
+            )?;
+        }
+        // Exits can lead to locations later in the pack, so they go in once every location is there
+        for (_, location) in &pack.locations {
+            for (direction, destination) in &location.exits {
+                db.insert_exit(&location.id, direction, destination)?;
+            }
+        }
+        for (_, theory) in &pack.theories {
+            db.insert_theory(
+                &theory.id, &theory.name, &theory.description, &theory.prerequisites,
+                theory.complexity, theory.learning_time, &theory.applications,
+            )?;
+        }
+        for (_, npc) in &pack.npcs {
+            db.insert_npc(&npc.id, &npc.name, &npc.description, npc.faction.as_deref(), &dialogue_tree(npc), &npc.location)?;
+        }
+        for (_, quest) in &pack.quests {
+            db.insert_quest_definition(quest)?;
+        }
+        for (_, item) in &pack.items {
+            db.insert_item_definition(item)?;
+        }
+        transaction.commit()?;
+
+        let mut report = ContentReport::default();
+        report.tables.insert("locations".to_string(), pack.locations.len());
+        report.tables.insert("magic_theories".to_string(), pack.theories.len());
+        report.tables.insert("npcs".to_string(), pack.npcs.len());
+        report.tables.insert("quest_definitions".to_string(), pack.quests.len());
+        report.tables.insert("items".to_string(), pack.items.len());
+        Ok(report)
+    }
+
+    /// The files holding one domain: `<domain>.json`/`.toml`, then everything
+    /// in a `<domain>/` directory, in name order
+    fn domain_files(&self, domain: &str) -> GameResult<Vec<PathBuf>> {
+        let mut files: Vec<PathBuf> = ["json", "toml"].iter()
+            .map(|extension| self.data_path.join(format!("{}.{}", domain, extension)))
+            .filter(|path| path.is_file())
+            .collect();
+
+        let dir = self.data_path.join(domain);
+        if dir.is_dir() {
+            let mut entries: Vec<PathBuf> = fs::read_dir(&dir)
+                .map_err(|e| crate::GameError::ContentNotFound(format!("Failed to read {}: {}", dir.display(), e)))?
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| matches!(path.extension().and_then(|e| e.to_str()), Some("json" | "toml")))
+                .collect();
+            entries.sort();
+            files.extend(entries);
+        }
+        Ok(files)
+    }
+}
+
+/// Read the list of entries a file holds under `domain`
+fn read_entries<T: DeserializeOwned>(path: &Path, domain: &str) -> Result<Vec<T>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut file: BTreeMap<String, Vec<T>> = if path.extension().is_some_and(|e| e == "toml") {
+        toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e.message()))?
+    } else {
+        serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?
+    };
+
+    if let Some(other) = file.keys().find(|key| *key != domain) {
+        return Err(format!("{}: expected a list of {}, found '{}'", path.display(), domain, other));
+    }
+    Ok(file.remove(domain).unwrap_or_default())
+}
+
+fn extend<T>(entries: &mut Vec<(PathBuf, T)>, path: &Path, read: Vec<T>) {
+    entries.extend(read.into_iter().map(|entry| (path.to_path_buf(), entry)));
+}
+
+/// Everything wrong with a pack, as one message per problem
+fn validate(pack: &ContentPack, db: &DatabaseManager) -> GameResult<Vec<String>> {
+    let mut problems = Vec::new();
+
+    let mut locations: HashSet<String> = db.load_locations()?.into_keys().collect();
+    let mut theories: HashSet<String> = db.load_theories()?.into_keys().collect();
+    let mut npcs: HashSet<String> = db.load_npcs()?.into_iter().map(|npc| npc.id).collect();
+
+    check_ids(&mut problems, "location", pack.locations.iter().map(|(path, l)| (path, &l.id)));
+    check_ids(&mut problems, "theory", pack.theories.iter().map(|(path, t)| (path, &t.id)));
+    check_ids(&mut problems, "NPC", pack.npcs.iter().map(|(path, n)| (path, &n.id)));
+    check_ids(&mut problems, "quest", pack.quests.iter().map(|(path, q)| (path, &q.id)));
+    check_ids(&mut problems, "item", pack.items.iter().map(|(path, i)| (path, &i.id)));
+
+    locations.extend(pack.locations.iter().map(|(_, location)| location.id.clone()));
+    theories.extend(pack.theories.iter().map(|(_, theory)| theory.id.clone()));
+    npcs.extend(pack.npcs.iter().map(|(_, npc)| npc.id.clone()));
+
+    for (path, location) in &pack.locations {
+        let at = format!("{}: location '{}'", path.display(), location.id);
+        for (direction, destination) in &location.exits {
+            if Direction::from_string(direction).is_none() {
+                problems.push(format!("{} has an exit '{}', which isn't a direction", at, direction));
+            }
+            if !locations.contains(destination) {
+                problems.push(format!("{} has an exit {} to unknown location '{}'", at, direction, destination));
+            }
+        }
+        if !(0.0..=1.0).contains(&location.interference) {
+            problems.push(format!("{} has interference {}, which must be between 0 and 1", at, location.interference));
+        }
+    }
+
+    for (path, theory) in &pack.theories {
+        let at = format!("{}: theory '{}'", path.display(), theory.id);
+        if !(1..=10).contains(&theory.complexity) {
+            problems.push(format!("{} has complexity {}, which must be from 1 to 10", at, theory.complexity));
+        }
+        if theory.learning_time <= 0 {
+            problems.push(format!("{} needs a learning time above zero", at));
+        }
+        for prerequisite in theory.prerequisites.iter().filter(|p| !theories.contains(*p)) {
+            problems.push(format!("{} requires unknown theory '{}'", at, prerequisite));
+        }
+    }
+
+    for (path, npc) in &pack.npcs {
+        let at = format!("{}: NPC '{}'", path.display(), npc.id);
+        if !locations.contains(&npc.location) {
+            problems.push(format!("{} lives at unknown location '{}'", at, npc.location));
+        }
+        if let Some(faction) = npc.faction.as_deref().filter(|f| !FACTIONS.contains(f)) {
+            problems.push(format!("{} belongs to unknown faction '{}' (expected one of {})", at, faction, FACTIONS.join(", ")));
+        }
+        if npc.greeting.is_empty() {
+            problems.push(format!("{} needs at least one greeting", at));
+        }
+        for (topic, content) in &npc.topics {
+            if content.lines.is_empty() {
+                problems.push(format!("{} has nothing to say about '{}'", at, topic));
+            }
+            if let Some(theory) = content.requires_theory.as_ref().filter(|t| !theories.contains(*t)) {
+                problems.push(format!("{} gates '{}' on unknown theory '{}'", at, topic, theory));
+            }
+        }
+    }
+
+    for (path, quest) in &pack.quests {
+        let at = format!("{}: quest '{}'", path.display(), quest.id);
+        for npc in quest.involved_npcs.iter().filter(|n| !npcs.contains(*n)) {
+            problems.push(format!("{} involves unknown NPC '{}'", at, npc));
+        }
+        for location in quest.locations.iter().filter(|l| !locations.contains(*l)) {
+            problems.push(format!("{} takes place at unknown location '{}'", at, location));
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Report empty, malformed or repeated IDs
+fn check_ids<'a>(problems: &mut Vec<String>, kind: &str, ids: impl Iterator<Item = (&'a PathBuf, &'a String)>) {
+    let mut seen: BTreeMap<&str, &Path> = BTreeMap::new();
+    for (path, id) in ids {
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+            problems.push(format!("{}: {} ID '{}' should be lowercase letters, digits and underscores", path.display(), kind, id));
+        }
+        if let Some(first) = seen.insert(id, path) {
+            problems.push(format!("{}: {} '{}' is already defined in {}", path.display(), kind, id, first.display()));
+        }
+    }
+}
+
+/// The dialogue tree the database stores for an NPC
+fn dialogue_tree(npc: &NpcContent) -> String {
+    let topics: serde_json::Map<String, serde_json::Value> = npc.topics.iter()
+        .map(|(topic, content)| {
+            let requirements = match &content.requires_theory {
+                Some(theory) => serde_json::json!({ "theory_requirements": [[theory, TOPIC_THEORY_UNDERSTANDING]] }),
+                None => serde_json::json!({}),
+            };
+            (topic.clone(), serde_json::json!({
+                "text_templates": content.lines,
+                "responses": [],
+                "requirements": requirements
+            }))
+        })
+        .collect();
+
+    serde_json::json!({
+        "greeting": {
+            "text_templates": npc.greeting,
+            "responses": [],
+            "requirements": {}
+        },
+        "topics": topics,
+        "faction_specific": []
+    }).to_string()
+}
+
+fn pack_error(path: &Path, problems: &[String]) -> anyhow::Error {
+    crate::GameError::InvalidInput(format!(
+        "Content pack {} has {} problem{}:\n  - {}",
+        path.display(), problems.len(), if problems.len() == 1 { "" } else { "s" }, problems.join("\n  - ")
+    )).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::{NamedTempFile, TempDir};
+
+    fn create_db() -> (DatabaseManager, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path().to_str().unwrap()).unwrap();
+        db.initialize_schema().unwrap();
+        db.load_default_content().unwrap();
+        (db, temp_file)
+    }
+
+    #[test]
+    fn test_packs_load_from_json_and_toml() {
+        let (db, _file) = create_db();
+        let pack = TempDir::new().unwrap();
+        fs::write(pack.path().join("locations.toml"), r#"
+            [[locations]]
+            id = "sunken_gallery"
+            name = "Sunken Gallery"
+            description = "Water laps at crystal shelves."
+            dominant_frequency = 6
+            exits = { north = "crystalline_archives" }
+        "#).unwrap();
+        fs::create_dir(pack.path().join("npcs")).unwrap();
+        fs::write(pack.path().join("npcs").join("keeper.json"), r#"{"npcs": [{
+            "id": "tide_keeper",
+            "name": "Tide Keeper Ondine",
+            "description": "A keeper in a salt-stained coat.",
+            "faction": "neutral_scholars",
+            "location": "sunken_gallery",
+            "greeting": ["Mind the water."],
+            "topics": {"tides": {"lines": ["The water rises with the bells."], "requires_theory": "harmonic_fundamentals"}}
+        }]}"#).unwrap();
+
+        let report = ContentLoader::new(pack.path()).load_into(&db).unwrap();
+        assert_eq!(report.tables["locations"], 1);
+        assert_eq!(report.tables["npcs"], 1);
+
+        let locations = db.load_locations().unwrap();
+        assert_eq!(locations["sunken_gallery"].exits[&Direction::North], "crystalline_archives");
+        let keeper = db.load_npcs().unwrap().into_iter().find(|npc| npc.id == "tide_keeper").unwrap();
+        assert!(keeper.dialogue_tree.topics.contains_key("tides"));
+    }
+
+    #[test]
+    fn test_pack_problems_are_reported_together_with_their_files() {
+        let (db, _file) = create_db();
+        let pack = TempDir::new().unwrap();
+        fs::write(pack.path().join("theories.json"), r#"{"theories": [
+            {"id": "tidal_resonance", "name": "Tidal Resonance", "description": "x", "complexity": 12, "learning_time": 60, "prerequisites": ["moon_theory"]}
+        ]}"#).unwrap();
+        fs::write(pack.path().join("npcs.toml"), r#"
+            [[npcs]]
+            id = "drifter"
+            name = "Drifter"
+            description = "x"
+            location = "nowhere"
+            greeting = ["Hm."]
+        "#).unwrap();
+
+        let error = ContentLoader::new(pack.path()).load_into(&db).unwrap_err().to_string();
+        assert!(error.contains("3 problems"), "{}", error);
+        assert!(error.contains("theories.json: theory 'tidal_resonance' has complexity 12"), "{}", error);
+        assert!(error.contains("unknown theory 'moon_theory'"), "{}", error);
+        assert!(error.contains("npcs.toml: NPC 'drifter' lives at unknown location 'nowhere'"), "{}", error);
+        assert!(!db.load_theories().unwrap().contains_key("tidal_resonance"));
+
+        // Misspelled fields are caught rather than silently dropped
+        fs::write(pack.path().join("npcs.toml"), "[[npcs]]\nid = \"drifter\"\nnmae = \"Drifter\"\n").unwrap();
+        let error = ContentLoader::new(pack.path()).read_pack().unwrap_err().to_string();
+        assert!(error.contains("npcs.toml") && error.contains("nmae"), "{}", error);
+    }
+}
@@ -0,0 +1,285 @@
+//! The in-game calendar
+//!
+//! Game time is counted in minutes from midnight on the first day of the
+//! run. Days run seven to a week and the seasons turn every
+//! `DAYS_PER_SEASON` days, four to a year. The date decides:
+//! - Which days NPC routines apply on, for routines kept on particular weekdays
+//! - The hours NPCs offer their paid services
+//! - The ambient line added to location descriptions
+//! - When the factions hold their recurring meetings, which the player can sit
+//!   in on for a little standing and where the members can be found
+//! - Which of an NPC's time-of-day greetings they use
+
+use crate::core::world_state::{Season, TimeOfDay};
+use crate::systems::factions::FactionId;
+use serde::{Deserialize, Serialize};
+
+pub const MINUTES_PER_DAY: i32 = 1440;
+pub const DAYS_PER_WEEK: i32 = 7;
+pub const DAYS_PER_SEASON: i32 = 14;
+/// Standing for sitting in on a faction's meeting, once per meeting
+pub const MEETING_STANDING: i32 = 2;
+
+/// Day of the week
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Weekday::Monday => "Monday",
+            Weekday::Tuesday => "Tuesday",
+            Weekday::Wednesday => "Wednesday",
+            Weekday::Thursday => "Thursday",
+            Weekday::Friday => "Friday",
+            Weekday::Saturday => "Saturday",
+            Weekday::Sunday => "Sunday",
+        }
+    }
+
+    /// Parse a weekday name, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|day| day.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// A day on the calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    /// Days since the run began
+    pub day: i32,
+    pub weekday: Weekday,
+    pub season: Season,
+    /// Day within the season, from 1
+    pub day_of_season: i32,
+    /// Year of the run, from 1
+    pub year: i32,
+}
+
+impl Date {
+    /// The date at a game time in minutes
+    pub fn at(minutes: i32) -> Self {
+        let day = minutes.div_euclid(MINUTES_PER_DAY);
+        let season_index = day.div_euclid(DAYS_PER_SEASON);
+        Self {
+            day,
+            weekday: Weekday::ALL[day.rem_euclid(DAYS_PER_WEEK) as usize],
+            season: match season_index.rem_euclid(4) {
+                0 => Season::Spring,
+                1 => Season::Summer,
+                2 => Season::Autumn,
+                _ => Season::Winter,
+            },
+            day_of_season: day.rem_euclid(DAYS_PER_SEASON) + 1,
+            year: season_index.div_euclid(4) + 1,
+        }
+    }
+
+    /// "Monday, day 3 of spring, year 1"
+    pub fn describe(&self) -> String {
+        format!("{}, day {} of {}, year {}", self.weekday.name(), self.day_of_season, self.season.name(), self.year)
+    }
+}
+
+/// Whether an hour falls in a span of the day that may wrap past midnight
+pub fn hour_in(hour: u8, start_hour: u8, end_hour: u8) -> bool {
+    if start_hour <= end_hour {
+        hour >= start_hour && hour < end_hour
+    } else {
+        hour >= start_hour || hour < end_hour
+    }
+}
+
+/// Whether a game time falls in a span of the day, on the given weekday if
+/// any; a span that runs past midnight belongs to the day it started
+pub fn in_span(minutes: i32, weekday: Option<Weekday>, start_hour: u8, end_hour: u8) -> bool {
+    let hour = (minutes.rem_euclid(MINUTES_PER_DAY) / 60) as u8;
+    let started_yesterday = start_hour > end_hour && hour < end_hour;
+    let day = minutes.div_euclid(MINUTES_PER_DAY) - started_yesterday as i32;
+    hour_in(hour, start_hour, end_hour)
+        && weekday.is_none_or(|weekday| Date::at(day * MINUTES_PER_DAY).weekday == weekday)
+}
+
+/// A faction's recurring weekly meeting
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Meeting {
+    pub id: &'static str,
+    /// Name to follow "the" ("Scholars' colloquium")
+    pub name: &'static str,
+    pub faction: FactionId,
+    pub location: &'static str,
+    pub weekday: Weekday,
+    pub start_hour: u8,
+    /// Hour the meeting ends (exclusive)
+    pub end_hour: u8,
+    /// NPCs who attend, and what they're doing there
+    pub attendees: &'static [(&'static str, &'static str)],
+}
+
+/// Every faction's recurring meetings
+pub const MEETINGS: &[Meeting] = &[
+    Meeting {
+        id: "council_session",
+        name: "Magisters' Council session",
+        faction: FactionId::MagistersCouncil,
+        location: "faction_diplomacy_hall",
+        weekday: Weekday::Monday,
+        start_hour: 10,
+        end_hour: 12,
+        attendees: &[
+            ("observer_lyra", "reporting the week's detection logs to the Council session"),
+            ("warden_gareth", "briefing the Council session on containment incidents"),
+        ],
+    },
+    Meeting {
+        id: "consortium_exchange",
+        name: "Consortium exchange",
+        faction: FactionId::IndustrialConsortium,
+        location: "harmonic_testing_chambers",
+        weekday: Weekday::Wednesday,
+        start_hour: 14,
+        end_hour: 16,
+        attendees: &[("technician_marcus", "haggling over crystal contracts at the Consortium exchange")],
+    },
+    Meeting {
+        id: "scholars_colloquium",
+        name: "Scholars' colloquium",
+        faction: FactionId::NeutralScholars,
+        location: "crystalline_archives",
+        weekday: Weekday::Thursday,
+        start_hour: 15,
+        end_hour: 17,
+        attendees: &[
+            ("sage_meridian", "chairing the Scholars' colloquium"),
+            ("assistant_thomas", "taking minutes at the Scholars' colloquium"),
+        ],
+    },
+    Meeting {
+        id: "harmony_vigil",
+        name: "Order of Harmony vigil",
+        faction: FactionId::OrderOfHarmony,
+        location: "crystal_garden_lab",
+        weekday: Weekday::Friday,
+        start_hour: 19,
+        end_hour: 21,
+        attendees: &[("healer_seraphina", "leading the Order's evening vigil among the garden beds")],
+    },
+    Meeting {
+        id: "underground_gathering",
+        name: "Underground gathering",
+        faction: FactionId::UndergroundNetwork,
+        location: "unstable_resonance_site",
+        weekday: Weekday::Saturday,
+        start_hour: 22,
+        end_hour: 2,
+        attendees: &[("mage_kira", "trading forbidden findings at the Underground's gathering")],
+    },
+];
+
+impl Meeting {
+    /// Whether the meeting is in session at a game time
+    pub fn in_session(&self, minutes: i32) -> bool {
+        in_span(minutes, Some(self.weekday), self.start_hour, self.end_hour)
+    }
+
+    /// Game time at which the meeting next begins, at or after the given time
+    pub fn next_start(&self, minutes: i32) -> i32 {
+        next_weekly(minutes, self.weekday, self.start_hour)
+    }
+}
+
+/// Game time at which an hour next comes round on a weekday, at or after the given time
+pub fn next_weekly(minutes: i32, weekday: Weekday, hour: u8) -> i32 {
+    let today = minutes.div_euclid(MINUTES_PER_DAY);
+    (today..=today + DAYS_PER_WEEK)
+        .map(|day| day * MINUTES_PER_DAY + hour as i32 * 60)
+        .find(|&start| start >= minutes && Date::at(start).weekday == weekday)
+        .unwrap_or(minutes)
+}
+
+/// The meeting in session at a location, if any
+pub fn meeting_at(location_id: &str, minutes: i32) -> Option<&'static Meeting> {
+    MEETINGS.iter().find(|meeting| meeting.location == location_id && meeting.in_session(minutes))
+}
+
+/// Hours an NPC offers their paid services, as (opening hour, closing hour)
+pub fn service_hours(npc_id: &str) -> (u8, u8) {
+    match npc_id {
+        // Echo trades at the Site after dark, when the Council's watchers look elsewhere
+        "echo_voidwalker" => (20, 4),
+        "healer_seraphina" | "dr_felix" => (6, 22),
+        _ => (8, 20),
+    }
+}
+
+/// What the time of day and season add to a location's description
+pub fn ambience(time_of_day: &TimeOfDay, season: &Season) -> String {
+    let time = match time_of_day {
+        TimeOfDay::Dawn => "Grey dawn light is only just finding its way in.",
+        TimeOfDay::Morning => "The morning is bright and busy.",
+        TimeOfDay::Midday => "The midday sun flattens every shadow.",
+        TimeOfDay::Afternoon => "The afternoon has settled into a steady hum.",
+        TimeOfDay::Evening => "Evening is drawing in, and the lamps are being lit.",
+        TimeOfDay::Night => "It is night; crystal lamps glow softly against the dark.",
+        TimeOfDay::Midnight => "At this hour almost everything is still.",
+    };
+    let season = match season {
+        Season::Spring => "Spring damp hangs in the air.",
+        Season::Summer => "The summer air is warm and close.",
+        Season::Autumn => "An autumn chill creeps in at the edges.",
+        Season::Winter => "Winter cold bites at your fingers.",
+    };
+    format!("{} {}", time, season)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dates_weeks_and_seasons_turn() {
+        let start = Date::at(0);
+        assert_eq!((start.weekday, start.season, start.day_of_season, start.year), (Weekday::Monday, Season::Spring, 1, 1));
+        assert_eq!(start.describe(), "Monday, day 1 of spring, year 1");
+
+        let later = Date::at(DAYS_PER_SEASON * MINUTES_PER_DAY + 9 * MINUTES_PER_DAY + 600);
+        assert_eq!((later.weekday, later.season, later.day_of_season), (Weekday::Wednesday, Season::Summer, 10));
+        assert_eq!(Date::at(4 * DAYS_PER_SEASON * MINUTES_PER_DAY).year, 2);
+        assert_eq!(Weekday::from_name("saturday"), Some(Weekday::Saturday));
+    }
+
+    #[test]
+    fn test_meetings_recur_weekly_and_run_past_midnight() {
+        let council = &MEETINGS[0];
+        assert!(council.in_session(10 * 60));
+        assert!(!council.in_session(12 * 60));
+        assert!(council.in_session(DAYS_PER_WEEK * MINUTES_PER_DAY + 11 * 60));
+        assert_eq!(council.next_start(12 * 60), DAYS_PER_WEEK * MINUTES_PER_DAY + 10 * 60);
+        assert_eq!(meeting_at("faction_diplomacy_hall", 10 * 60).map(|meeting| meeting.id), Some("council_session"));
+
+        // Saturday night's gathering is still going in the small hours of Sunday
+        let gathering = MEETINGS.iter().find(|meeting| meeting.id == "underground_gathering").unwrap();
+        let saturday = 5 * MINUTES_PER_DAY;
+        assert!(gathering.in_session(saturday + 23 * 60));
+        assert!(gathering.in_session(saturday + MINUTES_PER_DAY + 60));
+        assert!(!gathering.in_session(saturday + 60));
+    }
+}
@@ -0,0 +1,399 @@
+//! World events and the bus that carries them
+//!
+//! World events touch the whole city, or one place in it, for a while rather
+//! than any single conversation or fight. Some come round on the calendar:
+//! - The Magisters' Council audits its registered practitioners every Tuesday
+//! - Market day brings prices down on Saturdays
+//! - A resonance surge swells the Observatory's ambient energy before dawn on Sundays
+//!
+//! Others are set off by what happens in the world, like the backlash that
+//! follows heavy casting in one place. Running events are kept in the world
+//! state. Each start and end goes on the event bus, and after every command
+//! the bus broadcasts them to the systems that listen for them:
+//! - The player's standing with the factions
+//! - Quests, which note what's happening and count events the player saw
+//! - The service market
+//!
+//! Everything they have to say is shown to the player with the event's own
+//! announcement.
+
+use crate::core::calendar::{self, Weekday, MINUTES_PER_DAY};
+use crate::core::world_state::{WorldEvent, WorldState};
+use crate::core::Player;
+use crate::systems::factions::FactionId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Standing with the Council an audit gives or takes
+pub const AUDIT_STANDING: i32 = 1;
+/// Standing with the Council lost for setting off a backlash
+pub const BACKLASH_STANDING: i32 = 1;
+/// Magical signatures lingering at one location that set off a backlash
+pub const BACKLASH_SIGNATURES: usize = 5;
+/// Share of the price service providers knock off on market day
+pub const MARKET_DAY_DISCOUNT: f32 = 0.2;
+/// Broadcast events the bus keeps a record of
+const LOG_LENGTH: usize = 20;
+/// Key for the ambient energy multiplier in a running event's magical effects
+const AMBIENT_ENERGY: &str = "ambient_energy";
+
+/// When a world event happens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Schedule {
+    /// Every week over a span of hours, which may run past midnight
+    Weekly { weekday: Weekday, start_hour: u8, end_hour: u8 },
+    /// When something sets it off, for this many minutes
+    Triggered { minutes: i32 },
+}
+
+/// What a world event does while it runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorldEffect {
+    /// The Council weighs each practitioner's associations
+    Audit,
+    /// Service providers lower their prices
+    MarketDay,
+    /// Ambient energy where the event happens is multiplied
+    Resonance { multiplier: f32 },
+    /// Ambient energy is multiplied, and the Council blames whoever caused it
+    Backlash { multiplier: f32 },
+}
+
+/// A world event
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorldEventDef {
+    pub id: &'static str,
+    /// Name to follow "the" ("Council audit")
+    pub name: &'static str,
+    /// Where it happens: None for the whole city, and for triggered events,
+    /// wherever they were set off
+    pub location: Option<&'static str>,
+    pub schedule: Schedule,
+    pub effect: WorldEffect,
+    /// What the player is told when it starts
+    pub announcement: &'static str,
+    /// What the player is told when it ends
+    pub ending: &'static str,
+}
+
+/// Every world event
+pub const WORLD_EVENTS: &[WorldEventDef] = &[
+    WorldEventDef {
+        id: "council_audit",
+        name: "Council audit",
+        location: Some("faction_diplomacy_hall"),
+        schedule: Schedule::Weekly { weekday: Weekday::Tuesday, start_hour: 9, end_hour: 17 },
+        effect: WorldEffect::Audit,
+        announcement: "Council auditors set up in the Diplomacy Hall and begin going through the practitioners' register.",
+        ending: "The Council auditors close their ledgers for the week.",
+    },
+    WorldEventDef {
+        id: "market_day",
+        name: "market day",
+        location: None,
+        schedule: Schedule::Weekly { weekday: Weekday::Saturday, start_hour: 8, end_hour: 18 },
+        effect: WorldEffect::MarketDay,
+        announcement: "It's market day. Stalls crowd the streets, and everyone offering a service is cutting their prices.",
+        ending: "The market stalls pack up, and prices go back to what they were.",
+    },
+    WorldEventDef {
+        id: "resonance_surge",
+        name: "resonance surge",
+        location: Some("resonance_observatory"),
+        schedule: Schedule::Weekly { weekday: Weekday::Sunday, start_hour: 0, end_hour: 6 },
+        effect: WorldEffect::Resonance { multiplier: 1.5 },
+        announcement: "A resonance surge rolls through the Observatory's arrays, and the air there hums with energy.",
+        ending: "The resonance surge at the Observatory fades with the dawn.",
+    },
+    WorldEventDef {
+        id: "resonance_backlash",
+        name: "resonance backlash",
+        location: None,
+        schedule: Schedule::Triggered { minutes: 120 },
+        effect: WorldEffect::Backlash { multiplier: 0.7 },
+        announcement: "So much casting in one place sets off a backlash; the ambient resonance here sours and thins.",
+        ending: "The resonance backlash settles, and the ambient energy recovers.",
+    },
+];
+
+/// Find a world event by ID
+pub fn definition(id: &str) -> Option<&'static WorldEventDef> {
+    WORLD_EVENTS.iter().find(|def| def.id == id)
+}
+
+impl WorldEventDef {
+    /// Game time at which a weekly event next begins, at or after the given time
+    pub fn next_start(&self, minutes: i32) -> Option<i32> {
+        match self.schedule {
+            Schedule::Weekly { weekday, start_hour, .. } => Some(calendar::next_weekly(minutes, weekday, start_hour)),
+            Schedule::Triggered { .. } => None,
+        }
+    }
+}
+
+/// Whether an event is beginning or ending
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventType {
+    Started,
+    Ended,
+}
+
+/// A world event starting or ending
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub event_type: EventType,
+    /// ID of the world event
+    pub event_id: String,
+    /// Where it happens, if anywhere in particular
+    pub location: Option<String>,
+    /// Whether the player was there to see it
+    pub witnessed: bool,
+    /// When it happened (game time in minutes)
+    pub timestamp: i32,
+}
+
+impl Event {
+    /// The world event this is about
+    pub fn definition(&self) -> Option<&'static WorldEventDef> {
+        definition(&self.event_id)
+    }
+}
+
+/// A system that reacts to world events
+pub trait EventListener {
+    /// React to an event, returning anything the player should be told
+    fn handle_event(&mut self, event: &Event) -> Vec<String>;
+}
+
+/// Events waiting to be broadcast and a record of recent ones, saved with the world
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventBus {
+    pending: Vec<Event>,
+    log: Vec<Event>,
+}
+
+impl EventBus {
+    /// Put an event on the bus for the next broadcast
+    pub fn emit(&mut self, event: Event) {
+        self.pending.push(event);
+    }
+
+    /// Events waiting to be broadcast
+    pub fn pending(&self) -> &[Event] {
+        &self.pending
+    }
+
+    /// The most recently broadcast events, oldest first
+    pub fn log(&self) -> &[Event] {
+        &self.log
+    }
+
+    /// Deliver every pending event to each listener in turn, returning each
+    /// event's announcement followed by what the listeners had to say
+    pub fn broadcast(&mut self, listeners: &mut [&mut dyn EventListener]) -> Vec<String> {
+        let mut messages = Vec::new();
+        for event in std::mem::take(&mut self.pending) {
+            if let Some(def) = event.definition() {
+                messages.push(match event.event_type {
+                    EventType::Started => def.announcement,
+                    EventType::Ended => def.ending,
+                }.to_string());
+            }
+            for listener in listeners.iter_mut() {
+                messages.extend(listener.handle_event(&event));
+            }
+            self.log.push(event);
+        }
+        let excess = self.log.len().saturating_sub(LOG_LENGTH);
+        self.log.drain(..excess);
+        messages
+    }
+}
+
+/// Begin an event, record it as running and put its start on the bus
+fn start(world: &mut WorldState, def: &WorldEventDef, location: Option<String>, ends_at: i32) {
+    let now = world.game_time_minutes;
+    let mut magical_effects = HashMap::new();
+    if let WorldEffect::Resonance { multiplier } | WorldEffect::Backlash { multiplier } = def.effect {
+        magical_effects.insert(AMBIENT_ENERGY.to_string(), multiplier);
+    }
+    world.events.insert(def.id.to_string(), WorldEvent {
+        id: def.id.to_string(),
+        name: def.name.to_string(),
+        progress: 0.0,
+        affected_locations: location.iter().cloned().collect(),
+        magical_effects,
+        active: true,
+        started_at: now,
+        ends_at,
+    });
+    let witnessed = location.as_ref().is_none_or(|location| *location == world.current_location);
+    world.event_bus.emit(Event { event_type: EventType::Started, event_id: def.id.to_string(), location, witnessed, timestamp: now });
+}
+
+/// Start and end world events as time passes
+pub fn update(world: &mut WorldState) {
+    let now = world.game_time_minutes;
+
+    let mut ended: Vec<String> = world.events.values()
+        .filter(|event| event.active && now >= event.ends_at)
+        .map(|event| event.id.clone())
+        .collect();
+    ended.sort();
+    for id in ended {
+        if let Some(event) = world.events.remove(&id) {
+            let location = event.affected_locations.first().cloned();
+            let witnessed = location.as_ref().is_none_or(|location| *location == world.current_location);
+            world.event_bus.emit(Event { event_type: EventType::Ended, event_id: id, location, witnessed, timestamp: now });
+        }
+    }
+
+    for def in WORLD_EVENTS {
+        if let Schedule::Weekly { weekday, start_hour, end_hour } = def.schedule {
+            if !world.events.contains_key(def.id) && calendar::in_span(now, Some(weekday), start_hour, end_hour) {
+                // The span runs until its closing hour next comes round
+                let mut ends_at = now - now.rem_euclid(MINUTES_PER_DAY) + end_hour as i32 * 60;
+                if ends_at <= now {
+                    ends_at += MINUTES_PER_DAY;
+                }
+                start(world, def, def.location.map(str::to_string), ends_at);
+            }
+        }
+    }
+
+    for event in world.events.values_mut() {
+        let length = (event.ends_at - event.started_at).max(1);
+        event.progress = ((now - event.started_at) as f32 / length as f32).clamp(0.0, 1.0);
+    }
+}
+
+/// Set off a triggered event at a location; returns whether it started, which
+/// it won't while it's already running
+pub fn trigger(world: &mut WorldState, id: &str, location_id: &str) -> bool {
+    let Some(def) = definition(id) else {
+        return false;
+    };
+    let Schedule::Triggered { minutes } = def.schedule else {
+        return false;
+    };
+    if world.events.contains_key(id) {
+        return false;
+    }
+    let ends_at = world.game_time_minutes + minutes;
+    start(world, def, Some(location_id.to_string()), ends_at);
+    true
+}
+
+/// Heavy casting in one place sets off a backlash there; returns whether it did
+pub fn check_backlash(world: &mut WorldState) -> bool {
+    let here = world.current_location.clone();
+    let heavy = world.current_location()
+        .is_some_and(|location| location.magical_properties.recent_activity.len() >= BACKLASH_SIGNATURES);
+    heavy && trigger(world, "resonance_backlash", &here)
+}
+
+/// What running events do to ambient energy at a location
+pub fn ambient_multiplier(world: &WorldState, location_id: &str) -> f32 {
+    world.events.values()
+        .filter(|event| event.active)
+        .filter(|event| event.affected_locations.is_empty() || event.affected_locations.iter().any(|id| id == location_id))
+        .filter_map(|event| event.magical_effects.get(AMBIENT_ENERGY))
+        .product()
+}
+
+/// The factions' regard for the player is kept with the player, so the
+/// player hears from them when an audit or backlash begins
+impl EventListener for Player {
+    fn handle_event(&mut self, event: &Event) -> Vec<String> {
+        let council = FactionId::MagistersCouncil;
+        if event.event_type != EventType::Started {
+            return Vec::new();
+        }
+        match event.definition().map(|def| def.effect) {
+            Some(WorldEffect::Audit) => {
+                if self.faction_reputation(FactionId::UndergroundNetwork) > self.faction_reputation(council) {
+                    self.modify_faction_reputation(council, -AUDIT_STANDING);
+                    vec![format!(
+                        "The auditors take note of the company you keep. (-{} standing with the {})",
+                        AUDIT_STANDING, council.display_name()
+                    )]
+                } else {
+                    self.modify_faction_reputation(council, AUDIT_STANDING);
+                    vec![format!(
+                        "Your registration passes the audit without remark. (+{} standing with the {})",
+                        AUDIT_STANDING, council.display_name()
+                    )]
+                }
+            }
+            Some(WorldEffect::Backlash { .. }) => {
+                self.modify_faction_reputation(council, -BACKLASH_STANDING);
+                vec![format!(
+                    "Council watchers log the disturbance you caused. (-{} standing with the {})",
+                    BACKLASH_STANDING, council.display_name()
+                )]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::{Location, MagicalSignature};
+
+    /// Midnight at the start of the first Tuesday
+    fn tuesday() -> i32 {
+        (0..calendar::DAYS_PER_WEEK)
+            .map(|day| day * MINUTES_PER_DAY)
+            .find(|&start| calendar::Date::at(start).weekday == Weekday::Tuesday)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_scheduled_events_start_end_and_are_broadcast() {
+        let mut world = WorldState::new();
+        world.game_time_minutes = tuesday() + 8 * 60;
+        world.advance_time(90);
+        assert!(world.events["council_audit"].active);
+        assert_eq!(world.events["council_audit"].ends_at, tuesday() + 17 * 60);
+        assert!(!world.event_bus.pending()[0].witnessed);
+
+        let mut player = Player::new("Tester".to_string());
+        player.modify_faction_reputation(FactionId::UndergroundNetwork, 10);
+        let messages = world.event_bus.broadcast(&mut [&mut player]);
+        assert!(messages[0].contains("auditors set up"));
+        assert!(messages[1].contains("company you keep"));
+        assert_eq!(player.faction_reputation(FactionId::MagistersCouncil), -AUDIT_STANDING);
+
+        world.advance_time(8 * 60);
+        assert!(!world.events.contains_key("council_audit"));
+        assert_eq!(world.event_bus.pending()[0].event_type, EventType::Ended);
+        assert!(world.event_bus.broadcast(&mut [&mut player])[0].contains("close their ledgers"));
+        assert_eq!(world.event_bus.log().len(), 2);
+    }
+
+    #[test]
+    fn test_heavy_casting_triggers_a_backlash_once() {
+        let mut world = WorldState::new();
+        let mut hall = Location::new("hall".to_string(), "Hall".to_string(), "A hall.".to_string());
+        hall.magical_properties.recent_activity = vec![
+            MagicalSignature { magic_type: "light".to_string(), strength: 0.5, age_minutes: 0, frequency: 4 };
+            BACKLASH_SIGNATURES
+        ];
+        world.add_location(hall);
+        world.current_location = "hall".to_string();
+        // Keep clear of the weekly events
+        world.game_time_minutes = tuesday() + 20 * 60;
+
+        assert!(check_backlash(&mut world));
+        assert!(!check_backlash(&mut world));
+        assert_eq!(ambient_multiplier(&world, "hall"), 0.7);
+        assert_eq!(ambient_multiplier(&world, "elsewhere"), 1.0);
+        assert!(world.event_bus.pending()[0].witnessed);
+
+        world.advance_time(120);
+        assert!(world.events.is_empty());
+        assert_eq!(ambient_multiplier(&world, "hall"), 1.0);
+    }
+}
@@ -0,0 +1,184 @@
+//! Fast travel to places already visited
+//!
+//! Once the player has been somewhere, `travel to <location>` walks them
+//! there without typing every direction. The trip follows the shortest way
+//! through exits the player has already used, one location at a time, so it
+//! takes as long as walking would: each step costs the same minutes and
+//! fatigue as a single move under the player's load and the weather.
+//!
+//! Fast travel respects the same restrictions as walking. It never passes
+//! through a locked, barred, guarded or sealed exit, never into a place a
+//! faction keeps from the player, and never through a place the player
+//! hasn't been. Places only reachable by road still need
+//! the road. Players who'd rather find their own way can switch fast travel
+//! off with `navigation manual`.
+
+use crate::core::world_state::Direction;
+use crate::core::{Player, WorldState};
+use crate::GameResult;
+use std::collections::{HashMap, VecDeque};
+
+/// A fast-travel trip that has been made
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trip {
+    /// Names of the places passed through, ending with the destination
+    pub path: Vec<String>,
+    pub minutes: i32,
+}
+
+/// Find a location by ID or name
+pub fn resolve(world: &WorldState, name: &str) -> Option<String> {
+    let name = name.trim().trim_start_matches("the ").to_lowercase();
+    let mut ids: Vec<&String> = world.locations.keys().collect();
+    ids.sort();
+    ids.into_iter()
+        .find(|id| {
+            let location = &world.locations[*id];
+            **id == name.replace(' ', "_") || location.name.to_lowercase().trim_start_matches("the ") == name
+        })
+        .cloned()
+}
+
+/// Shortest way to a location through visited places, as the directions to
+/// take; locked exits are passed only if `through_locks` is set
+fn shortest_way(world: &WorldState, player: &Player, to: &str, through_locks: bool) -> Option<Vec<Direction>> {
+    let mut came_from: HashMap<String, (String, Direction)> = HashMap::new();
+    let mut queue = VecDeque::from([world.current_location.clone()]);
+    while let Some(here) = queue.pop_front() {
+        if here == to {
+            let mut directions = Vec::new();
+            let mut at = here;
+            while let Some((previous, direction)) = came_from.get(&at) {
+                directions.push(direction.clone());
+                at = previous.clone();
+            }
+            directions.reverse();
+            return Some(directions);
+        }
+        let Some(location) = world.locations.get(&here) else { continue };
+        let mut exits: Vec<(&Direction, &String)> = location.exits.iter().collect();
+        exits.sort_by_key(|(direction, _)| direction.display_name().to_string());
+        for (direction, next) in exits {
+            let open = through_locks || !location.locks.contains_key(direction);
+            let open = open && crate::systems::stealth::barred_from(world, player, next).is_none();
+            let visited = world.locations.get(next).is_some_and(|location| location.visited);
+            if open && visited && *next != world.current_location && !came_from.contains_key(next) {
+                came_from.insert(next.clone(), (here.clone(), direction.clone()));
+                queue.push_back(next.clone());
+            }
+        }
+    }
+    None
+}
+
+/// Walk to a visited location by the shortest open way
+pub fn travel(to: &str, player: &mut Player, world: &mut WorldState) -> GameResult<Trip> {
+    let destination = world.locations.get(to)
+        .ok_or_else(|| crate::GameError::ContentNotFound(format!("Location '{}' not found", to)))?;
+    let name = destination.name.clone();
+    if to == world.current_location {
+        return Err(crate::GameError::InvalidCommand(format!("You're already at the {}.", name)).into());
+    }
+    if !destination.visited {
+        return Err(crate::GameError::InvalidCommand(format!(
+            "You haven't been to the {} yet, so you don't know the way.", name
+        )).into());
+    }
+    if let Some(restriction) = crate::systems::stealth::barred_from(world, player, to) {
+        return Err(crate::GameError::InvalidCommand(format!(
+            "The {} is kept for those the {} trust; you'd have to find your own way in.", name, restriction.faction.display_name()
+        )).into());
+    }
+
+    let Some(directions) = shortest_way(world, player, to, false) else {
+        // Say what's in the way if only a locked exit stands between
+        let lock = shortest_way(world, player, to, true).and_then(|directions| {
+            let mut at = world.current_location.clone();
+            for direction in directions {
+                let location = world.locations.get(&at)?;
+                if let Some(lock) = location.locks.get(&direction) {
+                    return Some(lock.refusal());
+                }
+                at = location.exits.get(&direction)?.clone();
+            }
+            None
+        });
+        return Err(crate::GameError::InvalidCommand(match lock {
+            Some(refusal) => format!("The way to the {} is shut. {}", name, refusal),
+            None => format!("You know no way to the {} on foot from here. It may be a road away.", name),
+        }).into());
+    };
+
+    let mut trip = Trip { path: Vec::new(), minutes: 0 };
+    for direction in directions {
+        let next = world.move_to_location(direction)?;
+        player.current_location = next.clone();
+
+        let load = crate::systems::encumbrance::encumbrance(player);
+        let minutes = load.travel_minutes() + crate::systems::weather::travel_delay(world.environment.weather);
+        world.advance_time(minutes);
+        player.playtime_minutes += minutes;
+        player.mental_state.fatigue = (player.mental_state.fatigue + load.travel_fatigue()).min(100);
+
+        trip.minutes += minutes;
+        trip.path.push(world.locations.get(&next).map_or(next, |location| location.name.clone()));
+    }
+    Ok(trip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::Location;
+    use crate::systems::access::{AccessRequirement, ExitLock, LockKind};
+
+    /// Hall - gallery - tower in a line, with a locked shortcut from the hall
+    fn building() -> WorldState {
+        let mut world = WorldState::new();
+        for (id, name) in [("hall", "Great Hall"), ("gallery", "Gallery"), ("tower", "Tower"), ("attic", "Attic")] {
+            let mut location = Location::new(id.to_string(), name.to_string(), "A room.".to_string());
+            location.visited = id != "attic";
+            world.add_location(location);
+        }
+        let link = |world: &mut WorldState, from: &str, direction: Direction, to: &str| {
+            world.locations.get_mut(from).unwrap().add_exit(direction, to.to_string());
+        };
+        link(&mut world, "hall", Direction::East, "gallery");
+        link(&mut world, "gallery", Direction::West, "hall");
+        link(&mut world, "gallery", Direction::Up, "tower");
+        link(&mut world, "tower", Direction::Up, "attic");
+        world.current_location = "hall".to_string();
+        world
+    }
+
+    #[test]
+    fn test_fast_travel_walks_the_way_through_visited_places() {
+        let mut world = building();
+        let mut player = Player::new("Tester".to_string());
+        assert_eq!(resolve(&world, "the great hall").as_deref(), Some("hall"));
+        assert!(resolve(&world, "cellar").is_none());
+
+        let start = world.game_time_minutes;
+        let trip = travel("tower", &mut player, &mut world).unwrap();
+        assert_eq!(trip.path, vec!["Gallery".to_string(), "Tower".to_string()]);
+        assert_eq!(world.current_location, "tower");
+        assert_eq!(world.game_time_minutes, start + trip.minutes);
+        assert!(travel("tower", &mut player, &mut world).is_err());
+        assert!(travel("attic", &mut player, &mut world).unwrap_err().to_string().contains("haven't been"));
+    }
+
+    #[test]
+    fn test_fast_travel_never_passes_a_lock() {
+        let mut world = building();
+        let mut player = Player::new("Tester".to_string());
+        world.locations.get_mut("hall").unwrap().locks.insert(Direction::East, ExitLock {
+            kind: LockKind::Barred,
+            requirement: AccessRequirement::Key { item: "iron key".to_string() },
+            description: "An iron bar holds the door shut.".to_string(),
+        });
+
+        let refused = travel("tower", &mut player, &mut world).unwrap_err().to_string();
+        assert!(refused.contains("shut"));
+        assert_eq!(world.current_location, "hall");
+    }
+}
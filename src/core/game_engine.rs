@@ -2,12 +2,28 @@
 
 use crate::core::{Player, WorldState};
 use crate::systems::{MagicSystem, FactionSystem, DialogueSystem, KnowledgeSystem, QuestSystem, CombatSystem};
-use crate::input::{CommandParser, execute_command};
+use crate::input::{CommandParser, ParsedCommand, execute_command};
+use crate::input::shortcuts::CommandConfig;
+use crate::input::disambiguation::{self, PendingChoice};
+use crate::input::natural_language::{self, ReferenceTracker};
+use crate::input::completion::Completions;
+use crate::input::input_source::{self, InputLine, InputSource, ReadlineInput};
+use crate::core::undo::{self, Snapshot, UndoHistory, DEFAULT_UNDO_LIMIT, MAX_UNDO_LIMIT};
 use crate::persistence::{DatabaseManager, SaveManager};
+use crate::persistence::content_io::{self, ContentWatcher};
+use crate::persistence::crash_recovery::{self, PanicRecord};
+use crate::persistence::journal::{Journal, Recovery, StateDelta};
+use crate::persistence::query_pool::{Deferred, QueryPool};
+use crate::systems::statistics::{self, StatEvent, Statistics, StatsMark};
+use crate::ui::{self, ColorSupport};
+use crate::ui::tui::StatusPanel;
 use crate::GameResult;
 use std::time::{Instant, Duration};
-use rustyline::error::ReadlineError;
-use rustyline::DefaultEditor;
+
+/// Commands kept in a crash report
+const RECENT_COMMANDS: usize = 20;
+/// Connections kept open for loads run in the background
+const QUERY_WORKERS: usize = 2;
 
 /// Main game engine that coordinates all systems
 pub struct GameEngine {
@@ -45,10 +61,30 @@ pub struct GameEngine {
     autosave_enabled: bool,
     /// Maximum number of autosave files to keep
     max_autosaves: usize,
-    /// Readline editor for command history
-    rl: DefaultEditor,
-    /// History file path
-    history_path: std::path::PathBuf,
+    /// Where commands come from: the line editor, or a script being played back
+    input: Box<dyn InputSource>,
+    /// A command waiting for the player to say which thing they meant
+    pending_choice: Option<PendingChoice>,
+    /// A command guessed from a sentence that didn't parse, waiting for the
+    /// player to confirm it
+    pending_guess: Option<String>,
+    /// What the player last mentioned, for pronouns to point back at
+    references: ReferenceTracker,
+    /// Snapshots taken before the player's last few actions
+    undo: UndoHistory,
+    /// Content directory to import and reload from whenever its files change
+    content_watcher: Option<ContentWatcher>,
+    /// Write-ahead journal of this session's commands, when one is kept
+    journal: Option<Journal>,
+    /// The slot this session last saved to or loaded from
+    session_slot: Option<String>,
+    /// Connections for loads run in the background; none if the database
+    /// can't be shared, such as one held in memory
+    queries: Option<QueryPool>,
+    /// Where what the player does is tallied, opened when first needed
+    statistics: Option<Statistics>,
+    /// What the terminal can show of the colour theme
+    color_support: ColorSupport,
 }
 
 impl GameEngine {
@@ -56,47 +92,74 @@ impl GameEngine {
     pub fn new(database: DatabaseManager) -> GameResult<Self> {
         let player = Player::new("Adventurer".to_string());
         let mut world = WorldState::new();
+        world.run.seed = rand::random();
+        world.run.id = uuid::Uuid::new_v4().to_string();
+
+        // Whole tables load on the pool's connections while the rest loads
+        // here; a database that can't be shared is loaded here throughout
+        let queries = database.query_pool(QUERY_WORKERS)
+            .map_err(|e| log::debug!("Loading without a query pool: {}", e))
+            .ok();
+        let theories = Deferred::start(queries.as_ref(), DatabaseManager::load_theories);
+        let npcs = Deferred::start(queries.as_ref(), DatabaseManager::load_npcs);
+        let enemies = Deferred::start(queries.as_ref(), DatabaseManager::load_enemies);
+        let quests = Deferred::start(queries.as_ref(), DatabaseManager::load_quest_definitions);
 
         // Load locations from database
         let locations = database.load_locations()?;
         world.locations = locations;
+        world.mark_current_visited();
 
         let save_manager = SaveManager::new()?;
-
-        // Initialize rustyline editor
-        let mut rl = DefaultEditor::new()
-            .map_err(|e| anyhow::anyhow!("Failed to create readline editor: {}", e))?;
+        // Aliases and macros follow the player between characters; an
+        // unreadable config file just means starting without them
+        let commands = CommandConfig::load(save_manager.get_save_directory_path()).unwrap_or_default();
+        let mut command_parser = CommandParser::with_commands(commands);
+        // Verbs from content packs; one that clashes with a word the parser
+        // already knows is left out rather than stopping the game
+        for (word, meaning) in database.load_verb_synonyms().unwrap_or_default() {
+            if let Err(e) = command_parser.add_verb(&word, &meaning) {
+                log::warn!("Skipping content verb '{}': {}", word, e);
+            }
+        }
 
         // Configure history file path using platform-specific directory
-        let history_path = if let Some(data_dir) = dirs::data_dir() {
-            let app_dir = data_dir.join("SympatheticResonance");
-            std::fs::create_dir_all(&app_dir)?;
-            app_dir.join("command_history.txt")
-        } else {
-            std::path::PathBuf::from("command_history.txt")
-        };
-
-        // Load existing history if available (silently ignore if file doesn't exist)
-        let _ = rl.load_history(&history_path);
+        let input = ReadlineInput::new(input_source::history_path()?)?;
 
         // Initialize knowledge system
         let mut knowledge_system = KnowledgeSystem::new();
-        knowledge_system.initialize(&database)?;
+        knowledge_system.initialize_with(theories.finish(&database)?)?;
 
         // Initialize dialogue system and load NPCs from database
         let mut dialogue_system = DialogueSystem::new();
         // Try to load NPCs, but don't fail if they don't exist or are malformed
-        if let Ok(npcs) = database.load_npcs() {
+        if let Ok(npcs) = npcs.finish(&database) {
             for npc in npcs {
                 dialogue_system.add_npc(npc);
             }
         }
 
+        // Load enemy definitions, keeping the built-in examples if the table is missing
+        let mut combat_system = CombatSystem::new();
+        if let Ok(enemies) = enemies.finish(&database) {
+            if !enemies.is_empty() {
+                combat_system.load_catalog(enemies);
+            }
+        }
+
         // Initialize quest system with example quests
         let mut quest_system = QuestSystem::new();
         // Load quest definitions from database or create examples
         let example_quests = crate::systems::quest_examples::create_example_quests();
-        for quest in example_quests {
+        for quest in example_quests.into_iter()
+            .chain(crate::systems::quest_examples::create_cleanup_quests())
+            .chain(crate::systems::quest_examples::create_repeatable_quests())
+            .chain(crate::systems::quest_examples::create_bond_quests())
+        {
+            quest_system.add_quest_definition(quest);
+        }
+        // Quests authored in the content database take the place of examples with the same ID
+        for quest in quests.finish(&database).unwrap_or_default().into_values() {
             quest_system.add_quest_definition(quest);
         }
 
@@ -108,8 +171,8 @@ impl GameEngine {
             dialogue_system,
             knowledge_system,
             quest_system,
-            combat_system: CombatSystem::new(),
-            command_parser: CommandParser::new(),
+            combat_system,
+            command_parser,
             database,
             save_manager,
             debug_mode: false,
@@ -118,8 +181,17 @@ impl GameEngine {
             autosave_interval: Duration::from_secs(300), // 5 minutes default
             autosave_enabled: true,
             max_autosaves: 3,
-            rl,
-            history_path,
+            input: Box::new(input),
+            pending_choice: None,
+            pending_guess: None,
+            references: ReferenceTracker::default(),
+            undo: UndoHistory::default(),
+            content_watcher: None,
+            journal: None,
+            session_slot: None,
+            queries,
+            statistics: None,
+            color_support: ColorSupport::detect(),
         })
     }
 
@@ -129,62 +201,88 @@ impl GameEngine {
         self.show_initial_location()?;
 
         while self.running {
-            // Get player input using rustyline for command history
-            let readline = self.rl.readline("> ");
-
-            match readline {
-                Ok(input) => {
+            // Get player input from the line editor or the script
+            let prompt = format!(
+                "{}{}",
+                crate::ui::prompt_status(&self.player, &self.world, &self.quest_system),
+                self.world.coop.prompt(&self.player.name)
+            );
+            // Tab completes over whatever is at hand now
+            self.input.offer_completions(Completions::gather(&self.player, &self.world, &self.dialogue_system, &self.quest_system, &self.knowledge_system));
+            self.input.offer_status(StatusPanel::gather(&self.player, &self.world, &self.quest_system));
+
+            match self.input.read_line(&prompt)? {
+                InputLine::Command(input) => {
                     let input = input.trim();
 
                     if input.is_empty() {
                         continue;
                     }
 
-                    // Add to history
-                    let _ = self.rl.add_history_entry(input);
-
-                    // Process command
-                    match self.process_command(input) {
+                    // Process command; a panic ends the session with an emergency save
+                    self.journal_begin(input);
+                    let result = match crash_recovery::guarded(|| self.process_command(input)) {
+                        Ok(result) => result,
+                        Err(record) => {
+                            let text = format!("{}\n", self.recover_from_panic(input, &record));
+                            self.show(&text);
+                            // The journal is left behind for the next session to recover
+                            self.journal = None;
+                            self.running = false;
+                            break;
+                        }
+                    };
+                    match result {
                         Ok(response) => {
                             if response == "QUIT_GAME" {
                                 self.running = false;
-                                println!("Goodbye!");
+                                self.show("Goodbye!");
                             } else {
-                                println!("{}\n", response);
+                                let text = format!("{}\n", self.styled(&response));
+                                self.show(&text);
                             }
                         }
                         Err(e) => {
-                            println!("Error: {}\n", e);
+                            let text = format!("{}\n", self.styled(&format!("Error: {}", e)));
+                            self.show(&text);
                         }
                     }
 
                     // Check if autosave is needed
                     if let Err(e) = self.check_autosave() {
                         if self.debug_mode {
-                            println!("Autosave error: {}", e);
+                            let text = format!("Autosave error: {}", e);
+                            self.show(&text);
                         }
                     }
+
+                    // Pick up content edited while playing
+                    if let Some(report) = self.check_content_watch() {
+                        let text = format!("{}\n", report);
+                        self.show(&text);
+                    }
+
+                    self.journal_finish();
                 }
-                Err(ReadlineError::Interrupted) => {
+                InputLine::Interrupted => {
                     // Ctrl+C - continue running
-                    println!("(Use 'quit' to exit)");
+                    self.show("(Use 'quit' to exit)");
                     continue;
                 }
-                Err(ReadlineError::Eof) => {
-                    // Ctrl+D - exit gracefully
+                InputLine::Closed => {
+                    // Ctrl+D or the end of a script - exit gracefully
                     self.running = false;
-                    println!("Goodbye!");
-                }
-                Err(err) => {
-                    println!("Error reading input: {}", err);
-                    return Err(anyhow::anyhow!("Readline error: {}", err));
+                    self.show("Goodbye!");
                 }
             }
         }
 
         // Save command history on exit
-        if let Err(e) = self.rl.save_history(&self.history_path) {
-            eprintln!("Failed to save command history: {}", e);
+        self.input.finish();
+
+        // A session that ends normally has nothing to recover
+        if let Some(journal) = self.journal.take() {
+            journal.close()?;
         }
 
         Ok(())
@@ -192,12 +290,150 @@ impl GameEngine {
 
     /// Process a player command
     fn process_command(&mut self, input: &str) -> GameResult<String> {
-        // Parse command
-        let parse_result = self.command_parser.parse_advanced(input);
+        let location = self.world.current_location.clone();
+        let completed = self.completed_quests();
+
+        // Split chained commands, then expand any shortcut or alias in each;
+        // a macro runs its commands in turn
+        let steps: Vec<String> = self.command_parser.split_chain(input).iter()
+            .flat_map(|part| self.command_parser.expand(part, &self.player.preferences.shortcuts))
+            .collect();
+        let result = match steps.as_slice() {
+            [] => self.process_expanded_command(input),
+            [step] => self.process_expanded_command(step),
+            _ => self.process_steps(steps),
+        };
+
+        // Arriving somewhere new or finishing a quest is worth an autosave
+        let event = if self.completed_quests() > completed {
+            Some("quest_complete")
+        } else if self.world.current_location != location {
+            Some("location_change")
+        } else {
+            None
+        };
+        if let Some(event) = event {
+            if let Err(e) = self.trigger_event_autosave(event) {
+                if self.debug_mode {
+                    let text = format!("Autosave error: {}", e);
+                    self.show(&text);
+                }
+            }
+        }
+        result
+    }
+
+    /// How many quests the player has completed
+    fn completed_quests(&self) -> usize {
+        self.quest_system.player_progress.values()
+            .filter(|progress| progress.status == crate::systems::quests::QuestStatus::Completed)
+            .count()
+    }
+
+    /// Run several commands in turn, stopping at the first that fails, and
+    /// report how long they took altogether
+    fn process_steps(&mut self, steps: Vec<String>) -> GameResult<String> {
+        let started = self.world.game_time_minutes;
+        let mut responses = Vec::new();
+        for step in steps {
+            // A step that doesn't parse stops the rest
+            let parsed = self.command_parser.parse_advanced(&self.references.resolve(&step));
+            if let crate::input::CommandResult::Error(message) = parsed {
+                responses.push(format!("> {}\n{}", step, message));
+                break;
+            }
+            let understood = !matches!(parsed, crate::input::CommandResult::Success(ParsedCommand::Unknown { .. }));
+            let moves = matches!(
+                parsed,
+                crate::input::CommandResult::Success(ParsedCommand::Move { .. } | ParsedCommand::Travel { .. } | ParsedCommand::Sneak { .. })
+            );
+            let origin = self.world.current_location.clone();
+
+            match self.process_expanded_command(&step) {
+                Ok(response) if response == "QUIT_GAME" => return Ok(response),
+                Ok(response) => responses.push(format!("> {}\n{}", step, response)),
+                Err(e) => {
+                    responses.push(format!("> {}\nError: {}", step, e));
+                    break;
+                }
+            }
+            // Nor does the rest go ahead if the step wasn't understood, the way
+            // was blocked, or the player has been asked which thing they meant
+            if !understood || (moves && self.world.current_location == origin) || self.pending_choice.is_some() {
+                break;
+            }
+        }
+
+        let minutes = self.world.game_time_minutes - started;
+        if minutes > 0 {
+            responses.push(format!("(All of that took {}.)", crate::systems::quests::describe_minutes(minutes)));
+        }
+        Ok(responses.join("\n\n"))
+    }
+
+    /// Process one command once shortcuts, aliases and macros are expanded
+    fn process_expanded_command(&mut self, input: &str) -> GameResult<String> {
+        // "It", "him", "her" and "them" point back at what was last mentioned
+        let input = self.references.resolve(input);
+
+        // An answer to "which one?" picks up the command that asked it; any
+        // other command sets that one aside
+        if let Some(pending) = self.pending_choice.take() {
+            if disambiguation::cancels(&input) {
+                return Ok("Never mind.".to_string());
+            }
+            if let Some(command) = pending.answer(&input) {
+                self.references.note(&command);
+                return self.execute(command, &input);
+            }
+        }
+
+        // "Did you mean ...?" is answered yes or no; anything else moves on
+        if let Some(guess) = self.pending_guess.take() {
+            match input.trim().to_lowercase().as_str() {
+                "yes" | "y" | "yeah" | "sure" => return self.process_expanded_command(&guess),
+                "no" | "n" | "nope" => return Ok("Never mind.".to_string()),
+                _ => {}
+            }
+        }
+
+        let parse_result = self.command_parser.parse_advanced(&input);
+
+        // A sentence that doesn't parse may still be after a command we know
+        if let crate::input::CommandResult::Success(ParsedCommand::Unknown { original, .. }) = &parse_result {
+            if let Some(guess) = self.guess_command(original) {
+                let prompt = format!("I'm not sure what you mean. Did you mean '{}'? (yes/no)", guess);
+                self.pending_guess = Some(guess);
+                return Ok(prompt);
+            }
+        }
 
         match parse_result {
             crate::input::CommandResult::Success(command) => {
-                execute_command(command, &mut self.player, &mut self.world, &self.database, &mut self.magic_system, &mut self.dialogue_system, &mut self.faction_system, &mut self.knowledge_system, &mut self.quest_system, &mut self.combat_system, &self.save_manager)
+                self.references.note(&command);
+                if matches!(command, ParsedCommand::Alias { .. } | ParsedCommand::Macro { .. }) {
+                    return self.configure_commands(command);
+                }
+                if let ParsedCommand::Undo { steps } = command {
+                    return self.undo(steps);
+                }
+                if let ParsedCommand::ReloadContent { from } = command {
+                    if !self.debug_mode {
+                        return Ok("Reloading content is a debug command; start the game with --debug to use it.".to_string());
+                    }
+                    return self.reload_content(from.as_deref().map(std::path::Path::new));
+                }
+                if self.world.coop.segment.is_some() {
+                    // Turns taken in co-op belong to both players, so neither can take them back
+                    self.undo.clear();
+                    return self.process_coop_command(command);
+                }
+                if let Some(pending) = disambiguation::check(&command, &self.player, &self.world) {
+                    let prompt = pending.prompt();
+                    self.pending_choice = Some(pending);
+                    return Ok(prompt);
+                }
+                self.execute(command, &input)
             }
             crate::input::CommandResult::Error(msg) => {
                 Ok(msg)
@@ -208,29 +444,239 @@ impl GameEngine {
         }
     }
 
+    /// The command a sentence that didn't parse is most likely after, if
+    /// there's one the parser understands
+    fn guess_command(&self, sentence: &str) -> Option<String> {
+        natural_language::guess_intent(sentence).filter(|guess| matches!(
+            self.command_parser.parse_advanced(guess),
+            crate::input::CommandResult::Success(ref command) if !matches!(command, ParsedCommand::Unknown { .. })
+        ))
+    }
+
+    /// Run a command for the player, keeping a snapshot of the game first
+    /// if it's an action that can be undone
+    fn execute(&mut self, command: ParsedCommand, input: &str) -> GameResult<String> {
+        let limit = self.undo_limit();
+        let recorded = limit > 0 && undo::is_undoable(&command) && !self.combat_system.is_in_combat();
+        if recorded {
+            let snapshot = self.snapshot(input);
+            self.undo.record(snapshot, limit);
+        }
+        let loads = matches!(command, ParsedCommand::Load { .. });
+        let talks = matches!(command, ParsedCommand::Talk { .. } | ParsedCommand::Ask { .. } | ParsedCommand::Persuade { .. } | ParsedCommand::Reply { .. });
+        let stats_before = StatsMark::of(&self.player, &self.world, &self.combat_system);
+
+        self.world.run.turns += 1;
+        let result = execute_command(command, &mut self.player, &mut self.world, &self.database, &mut self.magic_system, &mut self.dialogue_system, &mut self.faction_system, &mut self.knowledge_system, &mut self.quest_system, &mut self.combat_system, &self.save_manager);
+
+        // What a load brings in was done in another session
+        let events = self.statistics_events(&stats_before, talks.then_some(input));
+        if !loads && result.is_ok() {
+            self.record_statistics(&events);
+        }
+
+        // Fights can't be rewound, and nothing before a load belongs to the
+        // game that was loaded
+        if self.combat_system.is_in_combat() || (loads && result.is_ok()) {
+            self.undo.clear();
+        } else if recorded && result.is_err() {
+            self.undo.discard_last();
+        }
+        self.undo.truncate(self.undo_limit());
+        self.return_after_defeat(result)
+    }
+
+    /// Go back to the most recent save when a defeat under the reload policy
+    /// has asked to, the same way loading it would
+    fn return_after_defeat(&mut self, result: GameResult<String>) -> GameResult<String> {
+        if !self.world.defeat.reload_pending {
+            return result;
+        }
+        self.world.defeat.reload_pending = false;
+        let mut response = result?;
+
+        self.take_latest_slot();
+        match self.session_slot.clone() {
+            Some(slot) => {
+                self.load_save(&slot)?;
+                self.undo.clear();
+                response.push_str(&format!(
+                    "\n\nGame loaded from slot '{}' successfully!\n\nWelcome back, {}!",
+                    slot, self.player.name
+                ));
+            }
+            None => response.push_str("\n\nThere is no save to return to. You come to where you fell."),
+        }
+        Ok(response)
+    }
+
+    /// What the command just run adds to the player's statistics
+    fn statistics_events(&mut self, before: &StatsMark, said: Option<&str>) -> Vec<StatEvent> {
+        let mut events = before.events(&self.player, &self.world, &self.combat_system);
+        if let Some((spell_type, success)) = self.magic_system.take_last_cast() {
+            events.push(StatEvent::spell(&spell_type, success));
+        }
+        if let Some(said) = said {
+            if let Some(npc) = self.dialogue_system.addressee(&self.world, &self.quest_system) {
+                events.push(StatEvent::conversation(&npc.name, said.split_whitespace().count()));
+            }
+        }
+        events
+    }
+
+    /// Add to the player's statistics; they're a record, so failing to
+    /// write them never gets in the way of play
+    fn record_statistics(&mut self, events: &[StatEvent]) {
+        if events.is_empty() {
+            return;
+        }
+        if self.statistics.is_none() {
+            match Statistics::open(self.save_manager.get_save_directory_path()) {
+                Ok(opened) => self.statistics = Some(opened),
+                Err(e) => {
+                    if self.debug_mode {
+                        let text = format!("Statistics error: {}", e);
+                        self.show(&text);
+                    }
+                    return;
+                }
+            }
+        }
+        let character = statistics::character_key(&self.player, &self.world);
+        if let Some(Err(e)) = self.statistics.as_ref().map(|opened| opened.record(&character, events)) {
+            if self.debug_mode {
+                let text = format!("Statistics error: {}", e);
+                self.show(&text);
+            }
+        }
+    }
+
+    /// How many actions undo keeps
+    fn undo_limit(&self) -> usize {
+        self.player.preferences.undo_limit.unwrap_or(DEFAULT_UNDO_LIMIT).min(MAX_UNDO_LIMIT)
+    }
+
+    /// The game as it stands, before the given command runs
+    fn snapshot(&self, command: &str) -> Snapshot {
+        Snapshot {
+            command: command.to_string(),
+            player: self.player.clone(),
+            world: self.world.clone(),
+            quest_system: self.quest_system.clone(),
+            dialogue_system: self.dialogue_system.clone(),
+            faction_system: self.faction_system.clone(),
+            combat_system: self.combat_system.clone(),
+            knowledge_system: self.knowledge_system.clone(),
+        }
+    }
+
+    /// Take back the player's last few actions, keeping their settings as they are now
+    fn undo(&mut self, steps: usize) -> GameResult<String> {
+        if self.combat_system.is_in_combat() {
+            return Err(crate::GameError::InvalidCommand("There's no taking anything back in the middle of a fight.".to_string()).into());
+        }
+        let Some((snapshot, commands)) = self.undo.rewind(steps) else {
+            return Err(crate::GameError::InvalidCommand("There's nothing to undo.".to_string()).into());
+        };
+
+        let preferences = self.player.preferences.clone();
+        self.player = snapshot.player;
+        self.player.preferences = preferences;
+        self.world = snapshot.world;
+        self.quest_system = snapshot.quest_system;
+        self.dialogue_system = snapshot.dialogue_system;
+        self.faction_system = snapshot.faction_system;
+        self.combat_system = snapshot.combat_system;
+        self.knowledge_system = snapshot.knowledge_system;
+        self.pending_choice = None;
+        self.pending_guess = None;
+
+        let commands: Vec<String> = commands.iter().map(|command| format!("'{}'", command)).collect();
+        let mut response = format!("Undone: {}.", commands.join(", "));
+        if let Some(location) = self.world.current_location() {
+            response.push_str(&format!(" You're back in {}.", location.name));
+        }
+        Ok(response)
+    }
+
+    /// Change the player's aliases or macros, and keep them in the command
+    /// config file for every character
+    fn configure_commands(&mut self, command: ParsedCommand) -> GameResult<String> {
+        let commands = self.command_parser.commands_mut();
+        let (response, changed) = match command {
+            ParsedCommand::Alias { setting } => (commands.configure_alias(setting.as_deref())?, setting.is_some()),
+            ParsedCommand::Macro { setting } => (commands.configure_macro(setting.as_deref())?, setting.is_some()),
+            _ => return Err(crate::GameError::InvalidCommand("Not an alias or macro command".to_string()).into()),
+        };
+        if changed {
+            commands.save(self.save_manager.get_save_directory_path())?;
+        }
+        Ok(response)
+    }
+
+    /// Run a command during a co-op segment for whichever seat is active
+    ///
+    /// The apprentice acts through the same handlers as the lead, with their
+    /// own character swapped in; the world and every system stay shared.
+    fn process_coop_command(&mut self, command: ParsedCommand) -> GameResult<String> {
+
+        let managing_link = matches!(command, ParsedCommand::Coop { .. });
+        let apprentice_turn = self.world.coop.is_apprentice_turn();
+        if apprentice_turn && matches!(command, ParsedCommand::Save { .. } | ParsedCommand::Load { .. }) {
+            return Err(crate::GameError::InvalidCommand("Only the lead player can save or load.".to_string()).into());
+        }
+
+        self.world.run.turns += 1;
+        let apprentice = if apprentice_turn { self.world.coop.apprentice.take() } else { None };
+        let result = match apprentice {
+            Some(mut apprentice) => {
+                apprentice.current_location = self.world.current_location.clone();
+                let result = execute_command(command, &mut apprentice, &mut self.world, &self.database, &mut self.magic_system, &mut self.dialogue_system, &mut self.faction_system, &mut self.knowledge_system, &mut self.quest_system, &mut self.combat_system, &self.save_manager);
+                self.world.coop.apprentice = Some(apprentice);
+                result
+            }
+            None => execute_command(command, &mut self.player, &mut self.world, &self.database, &mut self.magic_system, &mut self.dialogue_system, &mut self.faction_system, &mut self.knowledge_system, &mut self.quest_system, &mut self.combat_system, &self.save_manager),
+        };
+
+        // The link keeps both characters wherever the party went
+        self.player.current_location = self.world.current_location.clone();
+        if let Some(apprentice) = &mut self.world.coop.apprentice {
+            apprentice.current_location = self.world.current_location.clone();
+        }
+
+        let response = self.return_after_defeat(result)?;
+        if managing_link || self.world.coop.segment.is_none() || response == "QUIT_GAME" {
+            return Ok(response);
+        }
+        self.world.coop.pass_turn();
+        Ok(format!("{}\n\n{}'s turn.", response, self.world.coop.active_name(&self.player.name)))
+    }
+
     /// Show the initial location description
-    fn show_initial_location(&self) -> GameResult<()> {
+    fn show_initial_location(&mut self) -> GameResult<()> {
         if let Some(location) = self.world.current_location() {
-            println!("=== Welcome to Sympathetic Resonance ===\n");
-            println!("{}\n", location.description);
+            let mut text = format!("=== Welcome to Sympathetic Resonance ===\n\n{}\n\n", location.description);
 
             if !location.exits.is_empty() {
-                println!("Exits: {}",
-                    location.exits.keys()
-                        .map(|dir| dir.display_name())
-                        .collect::<Vec<_>>()
-                        .join(", "));
+                let mut exits: Vec<&str> = location.exits.keys().map(|dir| dir.display_name()).collect();
+                exits.sort_unstable();
+                text.push_str(&format!("Exits: {}\n", exits.join(", ")));
             }
-            println!();
+            self.show(&text);
         }
         Ok(())
     }
 
     /// Load a save file
     pub fn load_save(&mut self, save_path: &str) -> GameResult<()> {
+        // The content's locations load on the pool while the save is read
+        let locations = Deferred::start(self.queries.as_ref(), DatabaseManager::load_locations);
         let (player, world, quest_system, combat_system, faction_system, knowledge_system, dialogue_system, magic_system) = self.save_manager.load_game(save_path)?;
         self.player = player;
         self.world = world;
+        // Locations follow the current content, with the player's changes on top
+        self.world.merge_content(locations.finish(&self.database)?);
+        self.world.mark_current_visited();
         self.quest_system = quest_system;
         self.combat_system = combat_system;
         self.faction_system = faction_system;
@@ -240,11 +686,142 @@ impl GameEngine {
         Ok(())
     }
 
+    /// Speak in a locale, using the translated dialogue lines content packs ship for it
+    pub fn set_locale(&mut self, locale: &str) -> GameResult<()> {
+        let localization = self.database.load_localization(locale)?;
+        self.dialogue_system.set_localization(localization);
+        Ok(())
+    }
+
+    /// Start the game from a scenario file, returning its introduction
+    pub fn load_scenario(&mut self, path: &str) -> GameResult<String> {
+        let scenario = crate::persistence::scenario::Scenario::load(std::path::Path::new(path))?;
+        let intro = scenario.apply(
+            &mut self.player,
+            &mut self.world,
+            &self.knowledge_system,
+            &mut self.quest_system,
+            &self.faction_system,
+        )?;
+        self.world.mark_current_visited();
+        Ok(intro)
+    }
+
     /// Set debug mode
     pub fn set_debug_mode(&mut self, enabled: bool) {
         self.debug_mode = enabled;
     }
 
+    /// Watch a content directory, importing its JSON files and reloading
+    /// whenever one of them changes
+    pub fn watch_content(&mut self, dir: &str) -> GameResult<()> {
+        let dir = std::path::Path::new(dir);
+        if !dir.is_dir() {
+            return Err(crate::GameError::ContentNotFound(format!("Content directory {} not found", dir.display())).into());
+        }
+        self.content_watcher = Some(ContentWatcher::new(dir));
+        Ok(())
+    }
+
+    /// Re-read world content from the database into the running game,
+    /// first importing the JSON content files in `source` if given
+    ///
+    /// Locations keep what play has changed about them and NPCs keep their
+    /// disposition; the player's own progress is untouched.
+    pub fn reload_content(&mut self, source: Option<&std::path::Path>) -> GameResult<String> {
+        let mut response = String::new();
+        if let Some(dir) = source {
+            let report = content_io::import_content(&self.database, dir)?;
+            response.push_str(&format!("Imported content from {}:\n{}\n", dir.display(), report.summary()));
+        }
+
+        // NPCs, theories and quests load on the pool while locations load here
+        let npcs = Deferred::start(self.queries.as_ref(), DatabaseManager::load_npcs);
+        let theories = Deferred::start(self.queries.as_ref(), DatabaseManager::load_theories);
+        let quests = Deferred::start(self.queries.as_ref(), DatabaseManager::load_quest_definitions);
+
+        let locations = self.database.load_locations()?;
+        let location_count = locations.len();
+        self.world.merge_content(locations);
+
+        let npcs = npcs.finish(&self.database)?;
+        let npc_count = npcs.len();
+        for npc in npcs {
+            self.dialogue_system.refresh_npc(npc);
+        }
+
+        let theories = theories.finish(&self.database)?;
+        let theory_count = theories.len();
+        self.knowledge_system.initialize_with(theories)?;
+
+        let quests = quests.finish(&self.database)?;
+        let quest_count = quests.len();
+        for quest in quests.into_values() {
+            self.quest_system.add_quest_definition(quest);
+        }
+
+        response.push_str(&format!(
+            "Content reloaded: {} locations, {} NPCs, {} theories, {} quests from the database.",
+            location_count, npc_count, theory_count, quest_count
+        ));
+        Ok(response)
+    }
+
+    /// Reload content if the watched directory's files have changed,
+    /// returning what happened
+    fn check_content_watch(&mut self) -> Option<String> {
+        let watcher = self.content_watcher.as_mut()?;
+        if !watcher.poll() {
+            return None;
+        }
+        let dir = watcher.dir().to_path_buf();
+        Some(match self.reload_content(Some(&dir)) {
+            Ok(report) => format!("[Content changed] {}", report),
+            Err(e) => format!("[Content changed] Reload failed: {}", e),
+        })
+    }
+
+    /// Show output through whichever interface is in use
+    pub fn show(&mut self, text: &str) {
+        self.input.show(text);
+    }
+
+    /// Read commands from a different source, such as a script to play back
+    pub fn set_input(&mut self, input: Box<dyn InputSource>) {
+        self.input = input;
+    }
+
+    /// Set the seed for this run so challenges can be shared
+    pub fn set_run_seed(&mut self, seed: u64) {
+        self.world.run.seed = seed;
+        self.world.run.draws = 0;
+    }
+
+    /// Declare a challenge mutator for this run
+    pub fn add_run_mutator(&mut self, mutator: String) {
+        if !self.world.run.mutators.contains(&mutator) {
+            self.world.run.mutators.push(mutator);
+        }
+    }
+
+    /// Choose what happens when the player is defeated this run
+    pub fn set_defeat_policy(&mut self, policy: &str) -> GameResult<()> {
+        let policy = crate::systems::defeat::DefeatPolicy::from_string(policy).ok_or_else(|| {
+            crate::GameError::InvalidInput(format!("Unknown defeat policy: {}", policy))
+        })?;
+        self.world.defeat.policy = policy;
+        Ok(())
+    }
+
+    /// Choose how hard fights are this run
+    pub fn set_difficulty(&mut self, difficulty: &str) -> GameResult<()> {
+        let difficulty = crate::systems::enemy_ai::Difficulty::from_string(difficulty).ok_or_else(|| {
+            crate::GameError::InvalidInput(format!("Unknown difficulty: {}", difficulty))
+        })?;
+        self.combat_system.set_difficulty(difficulty);
+        Ok(())
+    }
+
     /// Get current player reference
     pub fn player(&self) -> &Player {
         &self.player
@@ -337,6 +914,10 @@ impl GameEngine {
 
     /// Handle NPC dialogue with quest integration
     pub fn talk_to_npc(&mut self, npc_id: &str, topic: Option<&str>) -> GameResult<String> {
+        if let Some(message) = self.dialogue_system.unavailability_message(npc_id, &self.world, &self.quest_system) {
+            return Ok(message);
+        }
+
         // First handle the dialogue
         let dialogue_result = self.dialogue_system.talk_to_npc(npc_id, &self.player, &self.faction_system)?;
 
@@ -430,7 +1011,8 @@ impl GameEngine {
         )?;
 
         if !silent && self.debug_mode {
-            println!("[Auto-saved at {}]", chrono::Local::now().format("%H:%M"));
+            let text = format!("[Auto-saved at {}]", chrono::Local::now().format("%H:%M"));
+            self.show(&text);
         }
 
         // Cleanup old autosaves
@@ -439,6 +1021,186 @@ impl GameEngine {
         Ok(())
     }
 
+    /// Keep a journal of the session's commands so progress since the last
+    /// save survives a crash, first offering to recover what an earlier
+    /// session lost; returns a report of the recovery if there was one
+    pub fn start_journal(&mut self) -> GameResult<Option<String>> {
+        let directory = self.save_manager.get_save_directory_path().to_path_buf();
+        let recovery = match Recovery::read(&directory) {
+            Ok(recovery) => recovery.filter(|recovery| !recovery.entries.is_empty()),
+            Err(e) => {
+                // Keep it for whoever wants to look, rather than writing over it
+                let text = match Recovery::set_aside(&directory) {
+                    Ok(path) => format!("The journal of your last session couldn't be read ({}), so it was set aside as {}.", e, path.display()),
+                    Err(moved) => format!("The journal of your last session couldn't be read ({}) or set aside ({}).", e, moved),
+                };
+                self.show(&text);
+                None
+            }
+        };
+
+        if let Some(recovery) = recovery {
+            let since = match &recovery.base {
+                Some(slot) => format!("since the save to '{}'", slot),
+                None => "since it began".to_string(),
+            };
+            let text = format!(
+                "Your last session ended unexpectedly with {} unsaved command{} ({} at {}).",
+                recovery.entries.len(),
+                if recovery.entries.len() == 1 { "" } else { "s" },
+                since,
+                recovery.started.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+            );
+            self.show(&text);
+            let answer = self.input.read_line("Recover unsaved progress? (yes/no) ")?;
+            if matches!(answer, InputLine::Command(ref answer) if matches!(answer.trim().to_lowercase().as_str(), "yes" | "y")) {
+                return self.recover(recovery).map(Some);
+            }
+        }
+
+        let base = self.take_latest_slot();
+        self.journal = Some(Journal::start(&directory, base)?);
+        Ok(None)
+    }
+
+    /// Load the save a journal starts from and replay its commands
+    fn recover(&mut self, recovery: Recovery) -> GameResult<String> {
+        if let Some(slot) = &recovery.base {
+            self.load_save(slot)?;
+        }
+        self.take_latest_slot();
+        self.journal = Some(Journal::start(self.save_manager.get_save_directory_path(), recovery.base.clone())?);
+
+        // Chance can take a replayed command somewhere it didn't go the first time
+        let mut diverged = Vec::new();
+        for entry in &recovery.entries {
+            self.journal_begin(&entry.command);
+            let _ = self.process_command(&entry.command);
+            if let Some(delta) = self.journal_finish() {
+                let differences = delta.differences(&entry.delta);
+                if !differences.is_empty() {
+                    diverged.push(format!("'{}': {}", entry.command, differences.join(", ")));
+                }
+            }
+        }
+
+        let mut report = format!(
+            "Recovered {} command{} of unsaved progress.",
+            recovery.entries.len(),
+            if recovery.entries.len() == 1 { "" } else { "s" }
+        );
+        if !diverged.is_empty() {
+            report.push_str(&format!(" {} of them turned out differently this time:", diverged.len()));
+            for difference in &diverged {
+                report.push_str(&format!("\n  {}", difference));
+            }
+        }
+        if let Some(command) = recovery.interrupted {
+            report.push_str(&format!("\nThe command running when the game stopped ('{}') was left out.", command));
+        }
+        Ok(report)
+    }
+
+    /// Style output with the player's colour theme, as far as the terminal allows
+    fn styled(&self, text: &str) -> String {
+        ui::style_output(text, self.player.preferences.theme, self.color_support)
+    }
+
+    /// Write a command to the journal before it runs, starting the journal
+    /// afresh if the game has been saved or loaded since the last one
+    fn journal_begin(&mut self, input: &str) {
+        self.rebase_journal();
+        if let Some(journal) = &mut self.journal {
+            if let Err(e) = journal.begin(input, &self.player, &self.world) {
+                if self.debug_mode {
+                    let text = format!("Journal error: {}", e);
+                    self.show(&text);
+                }
+            }
+        }
+    }
+
+    /// Write what the command just run changed to the journal
+    fn journal_finish(&mut self) -> Option<StateDelta> {
+        let delta = match self.journal.as_mut()?.finish(&self.player, &self.world) {
+            Ok(delta) => Some(delta),
+            Err(e) => {
+                if self.debug_mode {
+                    let text = format!("Journal error: {}", e);
+                    self.show(&text);
+                }
+                None
+            }
+        };
+        self.rebase_journal();
+        delta
+    }
+
+    /// The slot saved to or loaded from since this was last asked, if any,
+    /// kept as the one a defeat would return to
+    fn take_latest_slot(&mut self) -> Option<String> {
+        let slot = self.save_manager.take_latest_slot();
+        if slot.is_some() {
+            self.session_slot.clone_from(&slot);
+        }
+        slot
+    }
+
+    /// Start the journal from the slot last saved or loaded, if there's a new one
+    fn rebase_journal(&mut self) {
+        let Some(slot) = self.take_latest_slot() else {
+            return;
+        };
+        if self.journal.is_some() {
+            match Journal::start(self.save_manager.get_save_directory_path(), Some(slot)) {
+                Ok(journal) => self.journal = Some(journal),
+                Err(e) => {
+                    if self.debug_mode {
+                        let text = format!("Journal error: {}", e);
+                        self.show(&text);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Save to quarantine and write a crash report after a command panicked,
+    /// returning instructions for the player
+    fn recover_from_panic(&mut self, input: &str, record: &PanicRecord) -> String {
+        let slot = format!("emergency_{}", chrono::Local::now().format("%Y%m%d_%H%M%S"));
+        // The panic may have left the state unable to serialize, so the save is guarded too
+        let saved = crash_recovery::guarded(|| {
+            let quarantine = self.save_manager.quarantine()?;
+            quarantine.save_game(
+                &self.player,
+                &self.world,
+                &self.quest_system,
+                &self.combat_system,
+                &self.faction_system,
+                &self.knowledge_system,
+                &self.dialogue_system,
+                &self.magic_system,
+                Some(slot.clone()),
+                Some("Emergency Save".to_string())
+            )?;
+            Ok(quarantine.slot_path(&slot))
+        })
+        .unwrap_or_else(|panic| Err(anyhow::anyhow!("the save itself failed: {}", panic.message)))
+        .map_err(|e: anyhow::Error| e.to_string());
+
+        let history = self.input.history();
+        let recent = &history[history.len().saturating_sub(RECENT_COMMANDS)..];
+        let save_directory = self.save_manager.get_save_directory_path();
+        let bundle = crash_recovery::write_report(save_directory, record, input, recent, saved.as_deref().map_err(String::as_str))
+            .map_err(|e| e.to_string());
+
+        crash_recovery::recovery_instructions(
+            saved.as_deref().map_err(String::as_str),
+            bundle.as_deref().map_err(String::as_str),
+            save_directory,
+        )
+    }
+
     /// Clean up old autosave files, keeping only the most recent ones
     fn cleanup_old_autosaves(&self) -> GameResult<()> {
         let saves = self.save_manager.list_save_slots()?;
@@ -471,7 +1233,7 @@ impl GameEngine {
         }
 
         match event_type {
-            "quest_complete" | "level_up" | "major_faction_change" | "combat_end" => {
+            "quest_complete" | "location_change" | "level_up" | "major_faction_change" | "combat_end" => {
                 self.perform_autosave(true)?;
                 self.last_autosave = Instant::now();
             }
@@ -545,6 +1307,186 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_ambiguous_commands_wait_for_an_answer() {
+        let mut engine = create_test_engine();
+        let here = engine.world.current_location.clone();
+        engine.world.locations.get_mut(&here).unwrap().items = vec!["cracked_quartz".to_string(), "quartz_crystal".to_string()];
+
+        let question = engine.process_command("take quartz").unwrap();
+        assert_eq!(question, "Which quartz? 1) cracked quartz 2) quartz crystal");
+        engine.process_command("2").unwrap();
+        assert_eq!(engine.world.current_location().unwrap().items, vec!["cracked_quartz".to_string()]);
+
+        engine.world.locations.get_mut(&here).unwrap().items.push("quartz_crystal".to_string());
+        engine.process_command("take quartz").unwrap();
+        assert_eq!(engine.process_command("cancel").unwrap(), "Never mind.");
+        assert!(engine.pending_choice.is_none());
+    }
+
+    #[test]
+    fn test_sentences_are_guessed_and_confirmed() {
+        let mut engine = create_test_engine();
+        let question = engine.process_command("I'm feeling really tired").unwrap();
+        assert_eq!(question, "I'm not sure what you mean. Did you mean 'rest'? (yes/no)");
+        assert_eq!(engine.process_command("no").unwrap(), "Never mind.");
+        assert!(engine.pending_guess.is_none());
+
+        engine.process_command("what am I carrying").unwrap();
+        assert_eq!(engine.pending_guess.as_deref(), Some("inventory"));
+        assert_eq!(engine.process_command("yes").unwrap(), engine.process_command("inventory").unwrap());
+    }
+
+    #[test]
+    fn test_macros_run_their_commands_in_turn() {
+        let (mut engine, temp_dir) = create_test_engine_with_temp_saves();
+        engine.process_command("macro scan look; inventory").unwrap();
+        assert!(temp_dir.path().join(crate::input::shortcuts::CONFIG_FILE).exists());
+
+        let response = engine.process_command("scan").unwrap();
+        assert!(response.starts_with("> look\n"));
+        assert!(response.contains("\n\n> inventory\n"));
+    }
+
+    #[test]
+    fn test_chained_commands_stop_at_the_first_failure() {
+        let mut engine = create_test_engine();
+        let response = engine.process_command("look then inventory and status").unwrap();
+        assert!(response.starts_with("> look\n"));
+        assert!(response.contains("\n\n> inventory\n") && response.contains("\n\n> status\n"));
+
+        let here = engine.world.current_location.clone();
+        let response = engine.process_command("go nowhere; look").unwrap();
+        assert!(!response.contains("> look"));
+        assert_eq!(engine.world.current_location, here);
+    }
+
+    #[test]
+    fn test_scripts_play_back_until_they_run_out() {
+        let mut engine = create_test_engine();
+        let here = engine.world.current_location.clone();
+        engine.world.locations.get_mut(&here).unwrap().items = vec!["quartz_crystal".to_string()];
+        engine.configure_autosave(false, 5, 3);
+        engine.set_input(Box::new(crate::input::input_source::ScriptInput::parse("# pick it up\ntake quartz crystal\ninventory\n")));
+
+        engine.run().unwrap();
+        assert!(engine.world.current_location().unwrap().items.is_empty());
+        assert_eq!(engine.input.history(), vec!["take quartz crystal".to_string(), "inventory".to_string()]);
+        assert!(!engine.running);
+    }
+
+    #[test]
+    fn test_unsaved_progress_is_recovered_from_the_journal() {
+        let (mut engine, saves) = create_test_engine_with_temp_saves();
+        engine.configure_autosave(false, 5, 3);
+        let here = engine.world.current_location.clone();
+        engine.world.locations.get_mut(&here).unwrap().items = vec!["quartz_crystal".to_string()];
+        assert_eq!(engine.start_journal().unwrap(), None);
+
+        // The session stops without ending normally, partway through a command
+        engine.journal_begin("take quartz crystal");
+        engine.process_command("take quartz crystal").unwrap();
+        engine.journal_finish();
+        engine.journal_begin("cast light");
+        drop(engine);
+
+        let (mut engine, _) = create_test_engine_with_temp_saves();
+        engine.save_manager.set_save_directory_for_test(saves.path().to_path_buf());
+        engine.configure_autosave(false, 5, 3);
+        engine.world.locations.get_mut(&here).unwrap().items = vec!["quartz_crystal".to_string()];
+        let shown = std::rc::Rc::default();
+        engine.set_input(Box::new(Transcript {
+            script: crate::input::input_source::ScriptInput::parse("yes\n"),
+            shown: std::rc::Rc::clone(&shown),
+        }));
+
+        let report = engine.start_journal().unwrap().unwrap();
+        assert!(shown.borrow().iter().any(|line| line.starts_with("Your last session ended unexpectedly with 1 unsaved command ")));
+        assert!(report.contains("Recovered 1 command"));
+        assert!(report.contains("('cast light') was left out"));
+        assert!(!report.contains("differently"));
+        assert!(engine.world.current_location().unwrap().items.is_empty());
+
+        // Ending normally leaves nothing behind
+        engine.run().unwrap();
+        assert_eq!(Recovery::read(saves.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_a_replay_that_turns_out_differently_says_how() {
+        use crate::persistence::journal::{BAD_JOURNAL_FILE, JOURNAL_FILE};
+        let (mut engine, saves) = create_test_engine_with_temp_saves();
+        engine.configure_autosave(false, 5, 3);
+        std::fs::write(saves.path().join(JOURNAL_FILE), concat!(
+            "{\"record\":\"base\",\"slot\":null,\"started\":\"2026-03-02T19:04:11Z\"}\n",
+            "{\"record\":\"command\",\"input\":\"look\"}\n",
+            "{\"record\":\"done\",\"delta\":{\"minutes\":5}}\n",
+        )).unwrap();
+        engine.set_input(Box::new(crate::input::input_source::ScriptInput::parse("yes\n")));
+
+        let report = engine.start_journal().unwrap().unwrap();
+        assert!(report.contains("1 of them turned out differently"), "{}", report);
+        assert!(report.contains("'look': minutes +0 instead of +5"), "{}", report);
+
+        // A journal that can't be read is kept to one side, not written over
+        drop(engine);
+        std::fs::write(saves.path().join(JOURNAL_FILE), "not a journal").unwrap();
+        let (mut engine, _) = create_test_engine_with_temp_saves();
+        engine.save_manager.set_save_directory_for_test(saves.path().to_path_buf());
+        assert_eq!(engine.start_journal().unwrap(), None);
+        assert_eq!(std::fs::read_to_string(saves.path().join(BAD_JOURNAL_FILE)).unwrap(), "not a journal");
+    }
+
+    #[test]
+    fn test_what_the_player_does_is_tallied_in_their_statistics() {
+        let (mut engine, saves) = create_test_engine_with_temp_saves();
+        engine.configure_autosave(false, 5, 3);
+        engine.set_run_seed(0);
+        engine.process_command("cast light").unwrap();
+        engine.process_command("go north").unwrap();
+        engine.process_command("look").unwrap();
+
+        let statistics = Statistics::open(saves.path()).unwrap();
+        let character = statistics::character_key(&engine.player, &engine.world);
+        let spells = statistics.tallies(&character, statistics::StatCategory::Spells).unwrap();
+        assert_eq!((spells[0].0.as_str(), spells[0].1), ("light", 1));
+        assert_eq!(statistics.tallies(&character, statistics::StatCategory::Travel).unwrap()[0].1, 1);
+
+        let shown = engine.process_command("stats travel").unwrap();
+        assert!(shown.contains("Places moved through: 1"));
+        assert!(engine.process_command("stats gossip").is_err());
+
+        // A new game on the same seed, as every script plays, starts fresh
+        let (mut next, _) = create_test_engine_with_temp_saves();
+        next.save_manager.set_save_directory_for_test(saves.path().to_path_buf());
+        next.set_run_seed(0);
+        assert!(!next.process_command("stats travel").unwrap().contains("Places moved through"));
+    }
+
+    #[test]
+    fn test_undo_takes_back_the_last_action() {
+        let mut engine = create_test_engine();
+        assert!(engine.process_command("undo").is_err());
+
+        let here = engine.world.current_location.clone();
+        engine.world.locations.get_mut(&here).unwrap().items = vec!["quartz_crystal".to_string()];
+        let turns = engine.world.run.turns;
+        engine.process_command("take quartz crystal").unwrap();
+        engine.process_command("inventory").unwrap();
+        engine.process_command("verbosity terse").unwrap();
+        assert!(engine.world.current_location().unwrap().items.is_empty());
+
+        let response = engine.process_command("undo").unwrap();
+        assert!(response.starts_with("Undone: 'take quartz crystal'."));
+        assert_eq!(engine.world.current_location().unwrap().items, vec!["quartz_crystal".to_string()]);
+        assert_eq!(engine.world.run.turns, turns);
+        assert_eq!(engine.player.preferences.verbosity, crate::ui::Verbosity::Terse);
+
+        engine.process_command("undo limit 0").unwrap();
+        engine.process_command("take quartz crystal").unwrap();
+        assert!(engine.process_command("undo").is_err());
+    }
+
     #[test]
     fn test_debug_mode() {
         let mut engine = create_test_engine();
@@ -767,6 +1709,19 @@ mod tests {
         assert!(autosave.slot_name.len() >= 23); // autosave_20250101_120000 = 23 chars
     }
 
+    #[test]
+    fn test_moving_somewhere_new_autosaves() {
+        let (mut engine, _temp_dir) = create_test_engine_with_temp_saves();
+        let direction = engine.world.current_location().unwrap().exits.keys().next().unwrap().display_name().to_string();
+
+        engine.process_command("look").unwrap();
+        assert!(engine.save_manager.list_save_slots().unwrap().is_empty());
+
+        engine.process_command(&format!("go {}", direction)).unwrap();
+        let saves = engine.save_manager.list_save_slots().unwrap();
+        assert!(saves.iter().any(|save| save.slot_name.starts_with("autosave_")));
+    }
+
     #[test]
     fn test_autosave_cleanup_preserves_newest() {
         let (mut engine, _temp_dir) = create_test_engine_with_temp_saves();
@@ -788,4 +1743,245 @@ mod tests {
         assert!(autosave_count <= 2, "Should keep at most 2 autosaves, found {}", autosave_count);
         assert!(autosave_count > 0, "Should have at least 1 autosave");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_coop_segment_alternates_characters() {
+        let mut engine = create_test_engine();
+        engine.process_command("coop link mira").unwrap();
+        engine.process_command("coop ritual").unwrap();
+        engine.world.coop.apprentice.as_mut().unwrap().inventory.silver = 7;
+
+        let response = engine.process_command("look").unwrap();
+        assert!(response.ends_with("Mira's turn."));
+        assert!(engine.process_command("save").is_err());
+        assert!(engine.world.coop.is_apprentice_turn());
+
+        // The apprentice sees their own purse, then play passes back
+        let response = engine.process_command("inventory").unwrap();
+        assert!(response.contains("Silver: 7 pieces"), "{}", response);
+        assert!(!engine.world.coop.is_apprentice_turn());
+        assert_ne!(engine.player.inventory.silver, 7);
+
+        let summary = engine.process_command("coop end").unwrap();
+        assert!(summary.contains("Lead: 1 command, apprentice: 1 command."));
+
+        // The apprentice travels with the world state
+        let world: crate::core::WorldState = serde_json::from_str(&serde_json::to_string(&engine.world).unwrap()).unwrap();
+        assert_eq!(world.coop.apprentice.unwrap().name, "Mira");
+    }
+
+    /// A script played back, keeping everything the game shows
+    struct Transcript {
+        script: crate::input::input_source::ScriptInput,
+        shown: std::rc::Rc<std::cell::RefCell<Vec<String>>>,
+    }
+
+    impl InputSource for Transcript {
+        fn read_line(&mut self, prompt: &str) -> GameResult<InputLine> {
+            let line = self.script.read_line(prompt)?;
+            if let InputLine::Command(command) = &line {
+                self.shown.borrow_mut().push(format!("> {}", command));
+            }
+            Ok(line)
+        }
+
+        fn show(&mut self, text: &str) {
+            self.shown.borrow_mut().push(text.to_string());
+        }
+
+        fn history(&self) -> Vec<String> {
+            self.script.history()
+        }
+    }
+
+    fn play(script: &str, seed: u64) -> Vec<String> {
+        let (mut engine, _saves) = create_test_engine_with_temp_saves();
+        engine.configure_autosave(false, 5, 3);
+        engine.set_run_seed(seed);
+        let shown = std::rc::Rc::default();
+        engine.set_input(Box::new(Transcript {
+            script: crate::input::input_source::ScriptInput::parse(script),
+            shown: std::rc::Rc::clone(&shown),
+        }));
+        engine.run().unwrap();
+        shown.take()
+    }
+
+    #[test]
+    fn test_the_same_script_and_seed_play_out_the_same_way() {
+        let script = "look\ncast light\ncast light\ngo north\nlook\ncast light\ncast light\nrest\ncast light\n";
+        let first = play(script, 7);
+        assert!(first.iter().any(|line| line.contains("Final Roll")), "{:#?}", first);
+        assert_eq!(first, play(script, 7));
+        assert_ne!(first, play(script, 8));
+    }
+
+    #[test]
+    fn test_studying_a_theory_by_name_spends_the_time() {
+        let (mut engine, _saves) = create_test_engine_with_temp_saves();
+        let before = engine.world.game_time_minutes;
+
+        let response = engine.process_command("study harmonic fundamentals for 2 hours").unwrap();
+        assert!(response.contains("You spend 120 minutes studying Harmonic Fundamentals"), "{}", response);
+        assert_eq!(engine.world.game_time_minutes, before + 120);
+        assert!(engine.player.theory_understanding("harmonic_fundamentals") > 0.0);
+
+        assert!(engine.process_command("study resonance nonsense").is_err());
+        assert!(!engine.player.knowledge.theories.contains_key("resonance_nonsense"));
+    }
+
+    #[test]
+    fn test_the_checklist_only_ticks_off_study_that_happened() {
+        use crate::systems::onboarding::Competency;
+        let (mut engine, _saves) = create_test_engine_with_temp_saves();
+
+        let response = engine.process_command("study resonance amplification").unwrap();
+        assert!(response.contains("aren't ready"), "{}", response);
+        assert!(!response.contains("[Checklist] ✓"), "{}", response);
+        assert!(!engine.player.onboarding.completed.contains(&Competency::Studied));
+
+        // The checklist's own suggestion works as written
+        let response = engine.process_command("study harmonic fundamentals").unwrap();
+        assert!(response.contains("[Checklist] ✓ Study a theory"), "{}", response);
+    }
+
+    #[test]
+    fn test_persuading_someone_finds_them_by_name_where_the_player_is() {
+        let (mut engine, _saves) = create_test_engine_with_temp_saves();
+        let before = engine.world.game_time_minutes;
+
+        // Dr. Felix works in the garden lab, not the tutorial chamber
+        let response = engine.process_command("persuade dr_felix with freedom").unwrap();
+        assert!(response.contains("Felix isn't here"), "{}", response);
+        assert_eq!(engine.world.game_time_minutes, before);
+
+        let response = engine.process_command("persuade elara with freedom").unwrap();
+        assert!(response.starts_with("Tutorial Assistant Elara Starweaver:"), "{}", response);
+    }
+
+    #[test]
+    fn test_fast_travel_finds_the_way_back_to_where_the_game_began() {
+        let (mut engine, _saves) = create_test_engine_with_temp_saves();
+        assert!(engine.world.current_location().unwrap().visited);
+
+        engine.process_command("north").unwrap();
+        engine.process_command("east").unwrap();
+        assert_ne!(engine.world.current_location, "tutorial_chamber");
+
+        let response = engine.process_command("travel to tutorial chamber").unwrap();
+        assert_eq!(engine.world.current_location, "tutorial_chamber", "{}", response);
+    }
+
+    #[test]
+    fn test_the_map_remembers_where_the_game_began() {
+        let (mut engine, _saves) = create_test_engine_with_temp_saves();
+        engine.process_command("north").unwrap();
+
+        let map = engine.process_command("map").unwrap();
+        assert!(map.contains("[ ] Tutorial Chamber"), "{}", map);
+    }
+
+    #[test]
+    fn test_actions_offer_the_way_back_to_where_the_game_began() {
+        let (mut engine, _saves) = create_test_engine_with_temp_saves();
+        engine.process_command("north").unwrap();
+
+        let actions = engine.process_command("actions").unwrap();
+        assert!(actions.contains("go south - back to Tutorial Chamber"), "{}", actions);
+    }
+
+    #[test]
+    fn test_a_defeat_reloads_through_the_engine_load() {
+        let (mut engine, _saves) = create_test_engine_with_temp_saves();
+        engine.configure_autosave(false, 5, 3);
+        engine.process_command("save before").unwrap();
+        engine.process_command("north").unwrap();
+        // A newer save in the folder that this session didn't make
+        let manager = &engine.save_manager;
+        std::fs::copy(manager.slot_path("before"), manager.slot_path("someone_else")).unwrap();
+
+        // Falling under the reload policy leaves the load to the engine
+        engine.world.defeat.reload_pending = true;
+        let response = engine.process_command("look").unwrap();
+        assert!(response.contains("Game loaded from slot 'before'"), "{}", response);
+        assert_eq!(engine.world.current_location, "tutorial_chamber");
+        assert!(!engine.world.defeat.reload_pending);
+        assert!(engine.process_command("undo").is_err());
+    }
+
+    #[test]
+    fn test_a_defeat_without_a_save_of_this_session_stays_put() {
+        let (mut elsewhere, _elsewhere_saves) = create_test_engine_with_temp_saves();
+        elsewhere.configure_autosave(false, 5, 3);
+        elsewhere.process_command("save theirs").unwrap();
+
+        // Another character's save sits in the folder, but this session made none
+        let (mut engine, _saves) = create_test_engine_with_temp_saves();
+        engine.configure_autosave(false, 5, 3);
+        std::fs::copy(elsewhere.save_manager.slot_path("theirs"), engine.save_manager.slot_path("theirs")).unwrap();
+        engine.process_command("north").unwrap();
+
+        engine.world.defeat.reload_pending = true;
+        let response = engine.process_command("look").unwrap();
+        assert!(response.contains("There is no save to return to"), "{}", response);
+        assert_eq!(engine.world.current_location, "practice_hall");
+    }
+
+    #[test]
+    fn test_rich_text_says_more_than_standard() {
+        let (mut engine, _saves) = create_test_engine_with_temp_saves();
+        let standard = engine.process_command("look").unwrap();
+
+        engine.process_command("verbosity rich").unwrap();
+        let rich = engine.process_command("look").unwrap();
+        assert!(!standard.contains("and the weather is"), "{}", standard);
+        assert!(rich.contains("and the weather is"), "{}", rich);
+        assert!(rich.len() > standard.len());
+    }
+
+    #[test]
+    fn test_rereading_an_archive_text_teaches_less_each_time() {
+        let (mut engine, _saves) = create_test_engine_with_temp_saves();
+        engine.world.current_location = "crystalline_archives".to_string();
+        // At the top of its range, acuity makes the treatise, the easiest
+        // text, give itself up on every reading
+        engine.player.attributes.mental_acuity = 100;
+
+        let mut gains = Vec::new();
+        for _ in 0..4 {
+            engine.player.mental_state.current_energy = 50;
+            engine.player.mental_state.fatigue = 0;
+            let before = engine.player.theory_understanding("harmonic_fundamentals");
+            engine.process_command("research standing waves in the archives").unwrap();
+            gains.push(engine.player.theory_understanding("harmonic_fundamentals") - before);
+        }
+        assert!(gains[0] > gains[1] && gains[1] > gains[2] && gains[2] > 0.0, "{:?}", gains);
+        assert_eq!(gains[3], 0.0);
+    }
+
+    #[test]
+    fn test_reload_content_picks_up_database_edits() {
+        // The database has to outlive the engine to be edited underneath it
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path().to_str().unwrap()).unwrap();
+        db.initialize_schema().unwrap();
+        db.load_default_content().unwrap();
+        let mut engine = GameEngine::new(db).unwrap();
+
+        let response = engine.process_command("reload content").unwrap();
+        assert!(response.contains("debug command"), "{}", response);
+
+        engine.set_debug_mode(true);
+        let here = engine.world.current_location.clone();
+        engine.world.locations.get_mut(&here).unwrap().visited = true;
+        engine.database.connection().execute(
+            "UPDATE locations SET description = 'Freshly rewritten.' WHERE id = ?1", [&here],
+        ).unwrap();
+
+        let response = engine.process_command("reload content").unwrap();
+        assert!(response.starts_with("Content reloaded:"), "{}", response);
+        let location = &engine.world.locations[&here];
+        assert_eq!(location.description, "Freshly rewritten.");
+        assert!(location.visited);
+    }
+}
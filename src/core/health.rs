@@ -0,0 +1,249 @@
+//! Physical health and lasting injuries
+//!
+//! Mental energy fuels magic; health is what keeps the player standing.
+//! Enemy attacks and resonance burns wear it down, and some harm lingers as
+//! an injury with its own drawback until it heals:
+//! - Burns: scarring that lowers how far health can recover
+//! - Resonance sickness: channelling while spent makes every cast more tiring
+//! - Exhaustion collapse: pushing fatigue to its limit halves energy recovery
+//!
+//! Health returns and injuries fade while resting, and healing magic can
+//! treat them directly. When health runs out, the run's defeat policy
+//! decides what happens next.
+
+use serde::{Deserialize, Serialize};
+
+/// Health of a new character
+pub const BASE_MAX_HEALTH: i32 = 100;
+/// Worst an injury can get
+pub const MAX_SEVERITY: u32 = 3;
+/// Minutes of rest needed to heal one level of severity
+const MINUTES_PER_SEVERITY: i32 = 240;
+/// Health recovered per hour of rest
+const REST_HEALING_PER_HOUR: i32 = 10;
+/// Health ceiling lost per level of burns
+const BURN_CEILING_PER_SEVERITY: i32 = 10;
+/// Extra fatigue per cast for each level of resonance sickness
+const SICKNESS_FATIGUE_PER_SEVERITY: i32 = 2;
+/// Fatigue at which a failed cast backlashes into resonance sickness
+pub const SICKNESS_FATIGUE_THRESHOLD: i32 = 80;
+/// Health restored by a self-targeted healing spell at power 1.0
+pub const HEALING_SPELL_HEALTH: f32 = 25.0;
+
+/// Kinds of lasting harm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InjuryKind {
+    Burn,
+    ResonanceSickness,
+    ExhaustionCollapse,
+}
+
+impl InjuryKind {
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            InjuryKind::Burn => "Burn",
+            InjuryKind::ResonanceSickness => "Resonance Sickness",
+            InjuryKind::ExhaustionCollapse => "Exhaustion Collapse",
+        }
+    }
+
+    /// What the injury does while it lasts
+    pub fn effect(&self, severity: u32) -> String {
+        match self {
+            InjuryKind::Burn => format!("maximum health -{}", BURN_CEILING_PER_SEVERITY * severity as i32),
+            InjuryKind::ResonanceSickness => format!("+{} fatigue per cast", SICKNESS_FATIGUE_PER_SEVERITY * severity as i32),
+            InjuryKind::ExhaustionCollapse => "energy recovery halved".to_string(),
+        }
+    }
+}
+
+/// An injury and how long it has left to heal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Injury {
+    pub kind: InjuryKind,
+    /// Minutes of rest until fully healed
+    pub remaining_minutes: i32,
+}
+
+impl Injury {
+    /// Current severity (1-3), falling as the injury heals
+    pub fn severity(&self) -> u32 {
+        ((self.remaining_minutes + MINUTES_PER_SEVERITY - 1) / MINUTES_PER_SEVERITY).clamp(1, MAX_SEVERITY as i32) as u32
+    }
+}
+
+/// Player health and injuries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Health {
+    pub current: i32,
+    pub max: i32,
+    #[serde(default)]
+    pub injuries: Vec<Injury>,
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            current: BASE_MAX_HEALTH,
+            max: BASE_MAX_HEALTH,
+            injuries: Vec::new(),
+        }
+    }
+}
+
+impl Health {
+    /// Highest health reachable with current injuries
+    pub fn ceiling(&self) -> i32 {
+        (self.max - BURN_CEILING_PER_SEVERITY * self.severity(InjuryKind::Burn) as i32).max(1)
+    }
+
+    /// Whether the player can no longer stand
+    pub fn is_down(&self) -> bool {
+        self.current <= 0
+    }
+
+    /// Lose health, returning the amount actually lost
+    pub fn take_damage(&mut self, amount: i32) -> i32 {
+        let lost = amount.clamp(0, self.current);
+        self.current -= lost;
+        lost
+    }
+
+    /// Regain health up to the ceiling, returning the amount actually healed
+    pub fn heal(&mut self, amount: i32) -> i32 {
+        let healed = amount.max(0).min((self.ceiling() - self.current).max(0));
+        self.current += healed;
+        healed
+    }
+
+    /// Set health to a fraction of the ceiling, as after being carried to safety
+    pub fn restore_fraction(&mut self, fraction: f32) {
+        self.current = ((self.ceiling() as f32 * fraction) as i32).max(1);
+    }
+
+    /// Severity of an injury, or 0 if the player doesn't have it
+    pub fn severity(&self, kind: InjuryKind) -> u32 {
+        self.injuries.iter()
+            .find(|injury| injury.kind == kind)
+            .map_or(0, Injury::severity)
+    }
+
+    /// Suffer an injury, or worsen one already suffered
+    pub fn injure(&mut self, kind: InjuryKind) -> String {
+        let message = match self.injuries.iter_mut().find(|injury| injury.kind == kind) {
+            Some(injury) => {
+                let severity = (injury.severity() + 1).min(MAX_SEVERITY);
+                injury.remaining_minutes = severity as i32 * MINUTES_PER_SEVERITY;
+                format!("{} worsens (severity {}).", kind.name(), severity)
+            }
+            None => {
+                self.injuries.push(Injury { kind, remaining_minutes: MINUTES_PER_SEVERITY });
+                format!("New injury - {}: {}.", kind.name(), kind.effect(1))
+            }
+        };
+        self.current = self.current.min(self.ceiling());
+        message
+    }
+
+    /// Extra fatigue every cast costs while sick
+    pub fn extra_cast_fatigue(&self) -> i32 {
+        SICKNESS_FATIGUE_PER_SEVERITY * self.severity(InjuryKind::ResonanceSickness) as i32
+    }
+
+    /// Multiplier on energy recovered from any source
+    pub fn energy_recovery_factor(&self) -> f32 {
+        if self.severity(InjuryKind::ExhaustionCollapse) > 0 { 0.5 } else { 1.0 }
+    }
+
+    /// Heal and let injuries fade over a period of rest
+    pub fn rest(&mut self, minutes: i32) -> Vec<String> {
+        let mut messages = Vec::new();
+        for injury in &mut self.injuries {
+            injury.remaining_minutes -= minutes;
+            if injury.remaining_minutes <= 0 {
+                messages.push(format!("{} has healed.", injury.kind.name()));
+            }
+        }
+        self.injuries.retain(|injury| injury.remaining_minutes > 0);
+        self.heal(REST_HEALING_PER_HOUR * minutes / 60);
+        messages
+    }
+
+    /// Treat the worst injury with healing magic, easing it by one level
+    pub fn treat(&mut self) -> Option<String> {
+        let injury = self.injuries.iter_mut().max_by_key(|injury| injury.remaining_minutes)?;
+        injury.remaining_minutes -= MINUTES_PER_SEVERITY;
+        let name = injury.kind.name();
+        let message = if injury.remaining_minutes <= 0 {
+            format!("{} is healed.", name)
+        } else {
+            format!("{} eases (severity {}).", name, injury.severity())
+        };
+        self.injuries.retain(|injury| injury.remaining_minutes > 0);
+        Some(message)
+    }
+
+    /// Health and injury lines for the status screen
+    pub fn report(&self) -> String {
+        let mut output = format!("  Health: {}/{}", self.current, self.max);
+        if self.ceiling() < self.max {
+            output.push_str(&format!(" (limited to {} by injuries)", self.ceiling()));
+        }
+        output.push('\n');
+        for injury in &self.injuries {
+            let hours = (injury.remaining_minutes + 59) / 60;
+            output.push_str(&format!(
+                "  {} (severity {}): {}, about {} hour{} of rest to heal\n",
+                injury.kind.name(), injury.severity(), injury.kind.effect(injury.severity()),
+                hours, if hours == 1 { "" } else { "s" }
+            ));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burns_limit_healing_until_rested() {
+        let mut health = Health::default();
+        assert_eq!(health.take_damage(150), BASE_MAX_HEALTH);
+        assert!(health.is_down());
+
+        health.injure(InjuryKind::Burn);
+        health.injure(InjuryKind::Burn);
+        assert_eq!(health.severity(InjuryKind::Burn), 2);
+        assert_eq!(health.heal(500), BASE_MAX_HEALTH - 20);
+
+        // Four hours of rest heal a level of severity
+        health.rest(MINUTES_PER_SEVERITY);
+        assert_eq!(health.severity(InjuryKind::Burn), 1);
+        health.rest(MINUTES_PER_SEVERITY);
+        assert!(health.injuries.is_empty());
+        assert_eq!(health.ceiling(), BASE_MAX_HEALTH);
+    }
+
+    #[test]
+    fn test_injury_effects_and_treatment() {
+        let mut health = Health::default();
+        for _ in 0..5 {
+            health.injure(InjuryKind::ResonanceSickness);
+        }
+        assert_eq!(health.severity(InjuryKind::ResonanceSickness), MAX_SEVERITY);
+        assert_eq!(health.extra_cast_fatigue(), 6);
+
+        health.injure(InjuryKind::ExhaustionCollapse);
+        assert_eq!(health.energy_recovery_factor(), 0.5);
+        assert!(health.report().contains("Exhaustion Collapse (severity 1)"));
+
+        // Treatment goes to the worst injury first
+        assert!(health.treat().unwrap().contains("Resonance Sickness eases (severity 2)"));
+        assert_eq!(health.treat().unwrap(), "Resonance Sickness eases (severity 1).");
+        health.treat();
+        health.treat();
+        assert_eq!(health.treat(), None);
+    }
+}
@@ -0,0 +1,374 @@
+//! ASCII map rendering of explored locations
+//!
+//! Locations are laid out on a grid by following compass exits outward
+//! from the player's position. Visited locations are expanded; their
+//! unexplored neighbours appear as `?`. Vertical and named exits have no
+//! grid position and are listed below the map instead.
+//!
+//! Doorways that are still locked are drawn with `#`, and secret passages
+//! the player has found with `:`, so both stand out from ordinary exits. The
+//! map covers the region the player is in; the legend groups places by
+//! region, and regions visited earlier are listed beneath it.
+
+use super::overworld::{self, Region};
+use super::world_state::{Direction, WorldState};
+use crate::systems::location_changes::LocationChange;
+use std::collections::{HashMap, VecDeque};
+
+/// Marker for the player's current location
+const CURRENT_MARKER: char = '@';
+/// Marker for locations seen but not yet visited
+const UNEXPLORED_MARKER: char = '?';
+/// Connector for a doorway that is still locked
+const LOCKED_CONNECTOR: char = '#';
+/// Connector for a hidden exit the player has found
+const SECRET_CONNECTOR: char = ':';
+
+/// What kind of way through a doorway is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Passage {
+    Open,
+    Locked,
+    Secret,
+}
+
+/// How the exit from one location to another is drawn, judged from both
+/// sides of the doorway
+fn passage(world: &WorldState, from: &str, direction: &Direction, to: &str) -> Passage {
+    let back = world.locations.get(to)
+        .and_then(|destination| destination.exits.iter().find(|(_, target)| target.as_str() == from))
+        .map(|(direction, _)| direction.clone());
+    let sides = [(from, Some(direction.clone())), (to, back)];
+
+    let locked = sides.iter().any(|(id, direction)| {
+        direction.as_ref().is_some_and(|direction| {
+            world.locations.get(*id).is_some_and(|location| location.locks.contains_key(direction))
+        })
+    });
+    let found = sides.iter().any(|(id, direction)| {
+        direction.as_ref().is_some_and(|direction| {
+            world.location_changes.changes(id).iter()
+                .any(|change| matches!(change, LocationChange::Reveal { direction: revealed } if revealed == direction))
+        })
+    });
+
+    if locked {
+        Passage::Locked
+    } else if found {
+        Passage::Secret
+    } else {
+        Passage::Open
+    }
+}
+
+/// Grid offset for a compass direction, if it has one
+fn grid_offset(direction: &Direction) -> Option<(i32, i32)> {
+    match direction {
+        Direction::North => Some((0, -1)),
+        Direction::South => Some((0, 1)),
+        Direction::East => Some((1, 0)),
+        Direction::West => Some((-1, 0)),
+        Direction::Northeast => Some((1, -1)),
+        Direction::Northwest => Some((-1, -1)),
+        Direction::Southeast => Some((1, 1)),
+        Direction::Southwest => Some((-1, 1)),
+        _ => None,
+    }
+}
+
+/// Place known locations on a grid relative to the current location
+fn layout(world: &WorldState) -> HashMap<String, (i32, i32)> {
+    let mut positions = HashMap::new();
+    let mut occupied = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    positions.insert(world.current_location.clone(), (0, 0));
+    occupied.insert((0, 0), world.current_location.clone());
+    queue.push_back(world.current_location.clone());
+
+    while let Some(id) = queue.pop_front() {
+        let Some(location) = world.locations.get(&id) else { continue };
+        if !location.visited {
+            continue;
+        }
+
+        let (x, y) = positions[&id];
+        let mut exits: Vec<_> = location.exits.iter().collect();
+        exits.sort_by_key(|(direction, _)| direction.display_name().to_string());
+
+        for (direction, destination) in exits {
+            let Some((dx, dy)) = grid_offset(direction) else { continue };
+            if positions.contains_key(destination) || !world.locations.contains_key(destination) {
+                continue;
+            }
+            let position = (x + dx, y + dy);
+            if occupied.contains_key(&position) {
+                continue;
+            }
+            positions.insert(destination.clone(), position);
+            occupied.insert(position, destination.clone());
+            queue.push_back(destination.clone());
+        }
+    }
+
+    positions
+}
+
+/// Character drawn for a location on the map
+fn marker_for(world: &WorldState, id: &str) -> char {
+    if id == world.current_location {
+        return CURRENT_MARKER;
+    }
+    if let Some(annotation) = world.annotations_for(id).last() {
+        return annotation.marker;
+    }
+    match world.locations.get(id) {
+        Some(location) if location.visited => ' ',
+        _ => UNEXPLORED_MARKER,
+    }
+}
+
+/// Draw the grid of explored locations, one string per row, without the legend
+pub fn render_grid(world: &WorldState) -> Vec<String> {
+    draw_grid(world, &layout(world))
+}
+
+fn draw_grid(world: &WorldState, positions: &HashMap<String, (i32, i32)>) -> Vec<String> {
+    let min_x = positions.values().map(|(x, _)| *x).min().unwrap_or(0);
+    let max_x = positions.values().map(|(x, _)| *x).max().unwrap_or(0);
+    let min_y = positions.values().map(|(_, y)| *y).min().unwrap_or(0);
+    let max_y = positions.values().map(|(_, y)| *y).max().unwrap_or(0);
+
+    // Each cell is "[c]" followed by a connector column; rows alternate with connector rows
+    let width = ((max_x - min_x) * 4 + 3) as usize;
+    let height = ((max_y - min_y) * 2 + 1) as usize;
+    let mut canvas = vec![vec![' '; width]; height];
+
+    for (id, (x, y)) in positions {
+        let col = ((x - min_x) * 4) as usize;
+        let row = ((y - min_y) * 2) as usize;
+        canvas[row][col] = '[';
+        canvas[row][col + 1] = marker_for(world, id);
+        canvas[row][col + 2] = ']';
+
+        let Some(location) = world.locations.get(id) else { continue };
+        for (direction, destination) in &location.exits {
+            let Some((dx, dy)) = grid_offset(direction) else { continue };
+            if positions.get(destination) != Some(&(x + dx, y + dy)) {
+                continue;
+            }
+            let special = match passage(world, id, direction, destination) {
+                Passage::Open => None,
+                Passage::Locked => Some(LOCKED_CONNECTOR),
+                Passage::Secret => Some(SECRET_CONNECTOR),
+            };
+            match (dx, dy) {
+                (1, 0) => canvas[row][col + 3] = special.unwrap_or('-'),
+                (0, 1) => canvas[row + 1][col + 1] = special.unwrap_or('|'),
+                (1, 1) => canvas[row + 1][col + 3] = special.unwrap_or('\\'),
+                (-1, 1) => canvas[row + 1][col - 1] = special.unwrap_or('/'),
+                // Exits that only go one way are drawn from the end they leave
+                (-1, 0) => canvas[row][col - 1] = special.unwrap_or('-'),
+                (0, -1) => canvas[row - 1][col + 1] = special.unwrap_or('|'),
+                (-1, -1) => canvas[row - 1][col - 1] = special.unwrap_or('\\'),
+                (1, -1) => canvas[row - 1][col + 3] = special.unwrap_or('/'),
+                _ => {}
+            }
+        }
+    }
+
+    canvas.into_iter()
+        .map(|line| line.iter().collect::<String>().trim_end().to_string())
+        .collect()
+}
+
+/// Render the explored world as an ASCII map with a legend and annotations
+pub fn render_map(world: &WorldState) -> String {
+    let positions = layout(world);
+
+    let region = overworld::region_of(&world.current_location);
+
+    let mut output = format!("=== Map of {} ===\n\n", region.name());
+    for line in draw_grid(world, &positions) {
+        output.push_str(&line);
+        output.push('\n');
+    }
+
+    // Key to the doorways drawn, if any aren't plain
+    let passages: Vec<Passage> = positions.keys()
+        .filter_map(|id| world.locations.get(id))
+        .flat_map(|location| {
+            location.exits.iter()
+                .filter(|(_, destination)| positions.contains_key(*destination))
+                .map(|(direction, destination)| passage(world, &location.id, direction, destination))
+        })
+        .collect();
+    let mut key = Vec::new();
+    if passages.contains(&Passage::Locked) {
+        key.push(format!("{} locked", LOCKED_CONNECTOR));
+    }
+    if passages.contains(&Passage::Secret) {
+        key.push(format!("{} secret passage", SECRET_CONNECTOR));
+    }
+    if !key.is_empty() {
+        output.push_str(&format!("\nDoorways: {}\n", key.join(", ")));
+    }
+
+    // Legend, nearest locations first, grouped by region if the map crosses
+    // into another one
+    let mut placed: Vec<(&String, &(i32, i32))> = positions.iter().collect();
+    placed.sort_by_key(|(id, (x, y))| (x.abs() + y.abs(), (*id).clone()));
+    let mut regions: Vec<Region> = Vec::new();
+    for (id, _) in &placed {
+        let region = overworld::region_of(id);
+        if !regions.contains(&region) {
+            regions.push(region);
+        }
+    }
+
+    output.push_str("\nLegend:\n");
+    for legend_region in &regions {
+        if regions.len() > 1 {
+            output.push_str(&format!("  In {}:\n", legend_region.name()));
+        }
+        for (id, _) in placed.iter().filter(|(id, _)| overworld::region_of(id) == *legend_region) {
+            let Some(location) = world.locations.get(*id) else { continue };
+            let name = if location.visited {
+                location.name.as_str()
+            } else {
+                "Unexplored"
+            };
+            let suffix = if **id == world.current_location { " (you are here)" } else { "" };
+            output.push_str(&format!("  [{}] {}{}\n", marker_for(world, id), name, suffix));
+
+            for annotation in world.annotations_for(id) {
+                output.push_str(&format!("      {} {}\n", annotation.marker, annotation.note));
+            }
+        }
+    }
+
+    if let Some(location) = world.current_location() {
+        let others: Vec<String> = location.exits.iter()
+            .filter(|(direction, _)| grid_offset(direction).is_none())
+            .map(|(direction, destination)| match passage(world, &location.id, direction, destination) {
+                Passage::Open => direction.display_name().to_string(),
+                Passage::Locked => format!("{} (locked)", direction.display_name()),
+                Passage::Secret => format!("{} (secret)", direction.display_name()),
+            })
+            .collect();
+        if !others.is_empty() {
+            output.push_str(&format!("\nOther exits from here: {}\n", others.join(", ")));
+        }
+    }
+
+    // Regions explored before, reached by road rather than through exits
+    let elsewhere: Vec<String> = Region::ALL.iter()
+        .filter(|other| !regions.contains(other))
+        .filter_map(|other| {
+            let visited: Vec<&str> = other.locations().iter()
+                .filter_map(|id| world.locations.get(*id))
+                .filter(|location| location.visited)
+                .map(|location| location.name.as_str())
+                .collect();
+            (!visited.is_empty()).then(|| format!("  {}: {}", other.name(), visited.join(", ")))
+        })
+        .collect();
+    if !elsewhere.is_empty() {
+        output.push_str(&format!("\nElsewhere, by road:\n{}\n", elsewhere.join("\n")));
+    }
+
+    output.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::Location;
+
+    fn create_world() -> WorldState {
+        let mut world = WorldState::new();
+
+        let mut chamber = Location::new("tutorial_chamber".to_string(), "Tutorial Chamber".to_string(), "A chamber.".to_string());
+        chamber.add_exit(Direction::North, "practice_hall".to_string());
+        chamber.add_exit(Direction::Up, "tower".to_string());
+        chamber.visited = true;
+
+        let mut hall = Location::new("practice_hall".to_string(), "Practice Hall".to_string(), "A hall.".to_string());
+        hall.add_exit(Direction::South, "tutorial_chamber".to_string());
+        hall.add_exit(Direction::East, "garden".to_string());
+        hall.visited = true;
+
+        let garden = Location::new("garden".to_string(), "Crystal Garden".to_string(), "A garden.".to_string());
+        let tower = Location::new("tower".to_string(), "Tower".to_string(), "A tower.".to_string());
+
+        world.add_location(chamber);
+        world.add_location(hall);
+        world.add_location(garden);
+        world.add_location(tower);
+        world
+    }
+
+    #[test]
+    fn test_map_layout_and_connectors() {
+        let world = create_world();
+        let map = render_map(&world);
+
+        assert!(map.contains("[ ]-[?]"));
+        assert!(map.contains("[@]"));
+        assert!(map.contains(" |"));
+        assert!(map.contains("Tutorial Chamber (you are here)"));
+        assert!(!map.contains("Crystal Garden"));
+        assert!(map.contains("Other exits from here: up"));
+    }
+
+    #[test]
+    fn test_locked_and_secret_doorways_and_other_regions() {
+        let mut world = create_world();
+        world.locations.get_mut("practice_hall").unwrap().locks.insert(Direction::East, crate::systems::access::ExitLock {
+            kind: crate::systems::access::LockKind::Locked,
+            requirement: crate::systems::access::AccessRequirement::Key { item: "brass_key".to_string() },
+            description: "A brass-bound door.".to_string(),
+        });
+        let chamber = world.locations.get_mut("tutorial_chamber").unwrap();
+        chamber.exits.remove(&Direction::North);
+        chamber.hidden_exits.insert(Direction::North, crate::systems::secrets::HiddenExit {
+            to: "practice_hall".to_string(),
+            feature: "tapestry".to_string(),
+            clue: "Behind the tapestry is a passage.".to_string(),
+            known_to: Vec::new(),
+            documents: Vec::new(),
+        });
+        world.locations.get_mut("practice_hall").unwrap().exits.remove(&Direction::South);
+
+        // Undiscovered, the passage north isn't on the map at all
+        assert!(!render_map(&world).contains("Practice Hall"));
+
+        world.change_location("tutorial_chamber", LocationChange::Reveal { direction: Direction::North }).unwrap();
+        let mut observatory = Location::new(
+            "resonance_observatory".to_string(), "Resonance Observatory".to_string(), "A tower.".to_string(),
+        );
+        observatory.visited = true;
+        world.add_location(observatory);
+
+        let map = render_map(&world);
+        assert!(map.starts_with("=== Map of the old city ==="));
+        assert!(map.contains("[ ]#[?]"));
+        assert!(map.contains(" :\n[@]"));
+        assert!(map.contains("Doorways: # locked, : secret passage"));
+        assert!(map.contains("Elsewhere, by road:\n  the heights: Resonance Observatory"));
+    }
+
+    #[test]
+    fn test_annotations_appear_on_map() {
+        let mut world = create_world();
+        world.annotate("garden", '!', "good foraging".to_string()).unwrap();
+
+        let map = render_map(&world);
+        assert!(map.contains("[ ]-[!]"));
+        assert!(map.contains("! good foraging"));
+
+        assert_eq!(world.clear_annotations("garden"), 1);
+        assert!(world.annotations_for("garden").is_empty());
+        assert!(world.annotate("nowhere", '*', "lost".to_string()).is_err());
+    }
+}
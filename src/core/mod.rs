@@ -3,16 +3,26 @@
 //! This module contains the fundamental systems that drive the game:
 //! - Game engine and main loop
 //! - Player state and character management
+//! - Player health and injuries
 //! - World state and location tracking
+//! - Regions and the roads between them
+//! - Fast travel to places already visited
+//! - The calendar: days, weeks, seasons and faction meetings
+//! - World events, scheduled and triggered, and the bus that broadcasts them
+//! - Snapshots for taking back the last few actions
 
 pub mod game_engine;
 pub mod player;
+pub mod health;
 pub mod world_state;
-
-// EventBus module archived - can be restored from src/core/events.rs.bak if needed in future
-// pub mod events;
+pub mod map;
+pub mod overworld;
+pub mod fast_travel;
+pub mod calendar;
+pub mod events;
+pub mod undo;
 
 pub use game_engine::GameEngine;
 pub use player::Player;
 pub use world_state::WorldState;
-// pub use events::{Event, EventBus};
\ No newline at end of file
+pub use events::{Event, EventBus};
\ No newline at end of file
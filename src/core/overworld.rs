@@ -0,0 +1,328 @@
+//! Regions and the roads between them
+//!
+//! Above individual locations, the world is divided into regions. Locations
+//! within a region connect through their exits; regions connect through
+//! roads, which take real time to travel and can't be walked one exit at a
+//! time. Every road starts and ends at a region's gateway location, and a
+//! region may be reachable only by road, so the world can grow beyond the
+//! one connected building the player starts in.
+//!
+//! Roads carry risk. Each journey may turn up:
+//! - An encounter with something that lives at the far end of the road
+//! - A discovery along the way
+//! - A faction checkpoint, on roads a faction controls: friends are waved
+//!   through, strangers pay a toll or wait to be searched, and enemies are
+//!   turned back
+
+use crate::core::{Player, WorldState};
+use crate::systems::combat::{CombatSystem, DifficultyTier};
+use crate::systems::factions::FactionId;
+use crate::GameResult;
+use rand::Rng;
+
+/// Silver a checkpoint charges those it doesn't know
+pub const TOLL: i32 = 5;
+/// Standing at which a faction's checkpoint waves the player through
+pub const WAVED_THROUGH: i32 = 20;
+/// Standing at or below which a faction's checkpoint turns the player back
+pub const TURNED_BACK: i32 = -20;
+/// Minutes lost being searched when the player can't pay the toll
+const SEARCH_MINUTES: i32 = 30;
+/// Chance that a checkpoint is manned when the player passes
+const CHECKPOINT_CHANCE: f32 = 0.6;
+
+/// Parts of the world, each with its own locations and weather
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Region {
+    OldCity,
+    Heights,
+    Outskirts,
+    Quarries,
+}
+
+impl Region {
+    pub const ALL: [Region; 4] = [Region::OldCity, Region::Heights, Region::Outskirts, Region::Quarries];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Region::OldCity => "the old city",
+            Region::Heights => "the heights",
+            Region::Outskirts => "the outskirts",
+            Region::Quarries => "the Shardfall quarries",
+        }
+    }
+
+    /// Find a region by name, ignoring case and a leading "the"
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        let name = name.strip_prefix("the ").unwrap_or(&name);
+        Self::ALL.into_iter().find(|region| {
+            let full = region.name().to_lowercase();
+            let full = full.strip_prefix("the ").unwrap_or(&full).to_string();
+            full == name || full.split(' ').any(|word| word == name)
+        })
+    }
+
+    /// Where roads into the region arrive and roads out of it set off
+    pub fn gateway(&self) -> &'static str {
+        match self {
+            Region::OldCity => "faction_diplomacy_hall",
+            Region::Heights => "resonance_observatory",
+            Region::Outskirts => "unstable_resonance_site",
+            Region::Quarries => "shardfall_quarry",
+        }
+    }
+
+    /// Locations that lie in the region
+    pub fn locations(&self) -> &'static [&'static str] {
+        match self {
+            Region::OldCity => &[
+                "tutorial_chamber",
+                "practice_hall",
+                "personal_laboratory",
+                "crystal_garden_lab",
+                "whispering_cellar",
+                "harmonic_testing_chambers",
+                "crystalline_archives",
+                "archive_vault",
+                "faction_diplomacy_hall",
+            ],
+            Region::Heights => &["resonance_observatory"],
+            Region::Outskirts => &["unstable_resonance_site"],
+            Region::Quarries => &["shardfall_quarry"],
+        }
+    }
+
+    /// What the player might find on the road into the region, and its worth in silver
+    fn discoveries(&self) -> &'static [(&'static str, i32)] {
+        match self {
+            Region::OldCity => &[
+                ("A courier's satchel lies split open in the gutter; a few coins are still inside.", 6),
+                ("Someone has chalked a cache mark on a milestone. Behind it is a twist of coins.", 4),
+            ],
+            Region::Heights => &[
+                ("A cracked array crystal has rolled down the tower steps. A collector would pay for it.", 8),
+            ],
+            Region::Outskirts => &[
+                ("A fused lump of resonance glass glitters in the verge, still faintly warm.", 10),
+                ("An abandoned survey pack holds nothing but mouldy notes and a purse.", 7),
+            ],
+            Region::Quarries => &[
+                ("A seam of raw shard crystal has been exposed by a rockfall. You pry out what you can carry.", 15),
+                ("A quarry token lies in the dust, good for silver at any Consortium office.", 9),
+            ],
+        }
+    }
+}
+
+/// The region a location lies in; locations not placed in any region are in
+/// the old city
+pub fn region_of(location_id: &str) -> Region {
+    Region::ALL.into_iter()
+        .find(|region| region.locations().contains(&location_id))
+        .unwrap_or(Region::OldCity)
+}
+
+/// A road between two regions, travelled either way
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Route {
+    pub between: (Region, Region),
+    pub minutes: i32,
+    /// Chance of an encounter or discovery on the way
+    pub danger: f32,
+    /// The faction that keeps a checkpoint on the road, if any
+    pub checkpoint: Option<FactionId>,
+}
+
+impl Route {
+    /// The region at the other end of the road, if it touches this one
+    pub fn other_end(&self, region: Region) -> Option<Region> {
+        match self.between {
+            (a, b) if a == region => Some(b),
+            (a, b) if b == region => Some(a),
+            _ => None,
+        }
+    }
+}
+
+/// Every road between regions
+pub const ROUTES: &[Route] = &[
+    Route { between: (Region::OldCity, Region::Heights), minutes: 15, danger: 0.05, checkpoint: Some(FactionId::MagistersCouncil) },
+    Route { between: (Region::OldCity, Region::Outskirts), minutes: 40, danger: 0.35, checkpoint: None },
+    Route { between: (Region::Heights, Region::Outskirts), minutes: 50, danger: 0.3, checkpoint: None },
+    Route { between: (Region::Outskirts, Region::Quarries), minutes: 90, danger: 0.5, checkpoint: Some(FactionId::IndustrialConsortium) },
+];
+
+/// Roads leading out of a region
+pub fn routes_from(region: Region) -> Vec<(Region, &'static Route)> {
+    ROUTES.iter().filter_map(|route| route.other_end(region).map(|to| (to, route))).collect()
+}
+
+/// How a journey went
+#[derive(Debug, Clone, PartialEq)]
+pub struct Journey {
+    /// What happened on the road, in order
+    pub events: Vec<String>,
+    pub minutes: i32,
+    /// Whether the player reached the far end
+    pub arrived: bool,
+}
+
+/// Travel by road to another region, arriving at its gateway
+///
+/// Any fight met on the road starts once the player arrives.
+pub fn travel(
+    to: Region,
+    player: &mut Player,
+    world: &mut WorldState,
+    combat_system: &mut CombatSystem,
+    rng: &mut impl Rng,
+) -> GameResult<Journey> {
+    let from = region_of(&world.current_location);
+    if from == to {
+        return Err(crate::GameError::InvalidCommand(format!("You're already in {}.", to.name())).into());
+    }
+    let route = routes_from(from).into_iter()
+        .find(|(end, _)| *end == to)
+        .map(|(_, route)| route)
+        .ok_or_else(|| crate::GameError::InvalidCommand(format!("No road runs directly from {} to {}.", from.name(), to.name())))?;
+    let destination = to.gateway();
+    if !world.locations.contains_key(destination) {
+        return Err(crate::GameError::ContentNotFound(format!("Destination '{}' not found", destination)).into());
+    }
+
+    let mut journey = Journey {
+        events: vec![format!("You set out along the road to {}.", to.name())],
+        minutes: route.minutes,
+        arrived: true,
+    };
+
+    if let Some(faction) = route.checkpoint.filter(|_| rng.gen::<f32>() < CHECKPOINT_CHANCE) {
+        let standing = player.faction_reputation(faction);
+        let keepers = faction.display_name();
+        if standing <= TURNED_BACK {
+            journey.events.push(format!("A {} checkpoint bars the road. They know your face, and turn you back.", keepers));
+            journey.minutes = route.minutes / 2;
+            journey.arrived = false;
+        } else if standing >= WAVED_THROUGH {
+            journey.events.push(format!("The {} checkpoint waves you through with a nod.", keepers));
+        } else if player.inventory.silver >= TOLL {
+            player.inventory.silver -= TOLL;
+            journey.events.push(format!("The {} checkpoint takes a toll of {} silver before letting you pass.", keepers, TOLL));
+        } else {
+            journey.minutes += SEARCH_MINUTES;
+            journey.events.push(format!(
+                "You can't pay the {} checkpoint's toll, so they search your pack before letting you pass ({} minutes).",
+                keepers, SEARCH_MINUTES
+            ));
+        }
+    }
+
+    let mut encounter = None;
+    if journey.arrived && rng.gen::<f32>() < route.danger {
+        let candidates: Vec<_> = combat_system.enemies_at(destination)
+            .into_iter()
+            .filter(|enemy| enemy.difficulty_tier != DifficultyTier::Boss)
+            .collect();
+        if !candidates.is_empty() && rng.gen_bool(0.5) {
+            encounter = Some(candidates[rng.gen_range(0..candidates.len())].clone());
+        } else {
+            let discoveries = to.discoveries();
+            let (text, silver) = discoveries[rng.gen_range(0..discoveries.len())];
+            player.inventory.silver += silver;
+            journey.events.push(format!("{} (+{} silver)", text, silver));
+        }
+    }
+
+    world.advance_time(journey.minutes);
+    player.playtime_minutes += journey.minutes;
+    if !journey.arrived {
+        return Ok(journey);
+    }
+
+    // Nothing left behind goes missing while the player was around to see it
+    world.ground.watch(&world.current_location, world.game_time_minutes);
+    world.current_location = destination.to_string();
+    world.mark_current_visited();
+    world.refresh_weather();
+    player.current_location = destination.to_string();
+
+    if let Some(enemy) = encounter {
+        journey.events.push(format!("Something has followed you down the road.\n{}", combat_system.start_encounter(enemy)?));
+    }
+    Ok(journey)
+}
+
+/// The roads leading out of the player's region
+pub fn describe_routes(world: &WorldState) -> String {
+    let here = region_of(&world.current_location);
+    let mut output = format!("You are in {}. Roads lead to:\n", here.name());
+    for (to, route) in routes_from(here) {
+        let checkpoint = route.checkpoint
+            .map_or_else(String::new, |faction| format!(", {} checkpoint", faction.display_name()));
+        output.push_str(&format!("  • {} - {} minutes{}\n", to.name(), route.minutes, checkpoint));
+    }
+    output.push_str("Use 'travel to <region>' to set out.");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::Location;
+    use rand::rngs::mock::StepRng;
+
+    fn world_with_gateways() -> WorldState {
+        let mut world = WorldState::new();
+        for region in Region::ALL {
+            let id = region.gateway().to_string();
+            world.add_location(Location::new(id.clone(), id.replace('_', " "), "A place.".to_string()));
+        }
+        world.current_location = Region::OldCity.gateway().to_string();
+        world
+    }
+
+    #[test]
+    fn test_regions_and_routes() {
+        assert_eq!(region_of("crystalline_archives"), Region::OldCity);
+        assert_eq!(region_of("shardfall_quarry"), Region::Quarries);
+        assert_eq!(Region::from_name("quarries"), Some(Region::Quarries));
+        assert_eq!(Region::from_name("The Heights"), Some(Region::Heights));
+        assert_eq!(routes_from(Region::Quarries).len(), 1);
+        assert_eq!(ROUTES.iter().filter(|route| route.other_end(Region::OldCity).is_some()).count(), 2);
+    }
+
+    #[test]
+    fn test_travel_takes_time_and_checkpoints_judge_standing() {
+        let mut world = world_with_gateways();
+        let mut player = Player::new("Test".to_string());
+        let mut combat = CombatSystem::new();
+
+        // The quarries are only reachable from the outskirts
+        assert!(travel(Region::Quarries, &mut player, &mut world, &mut combat, &mut StepRng::new(0, 0)).is_err());
+
+        // A quiet road: no checkpoint, no events
+        let journey = travel(Region::Outskirts, &mut player, &mut world, &mut combat, &mut StepRng::new(u64::MAX, 0)).unwrap();
+        assert!(journey.arrived);
+        assert_eq!(journey.events.len(), 1);
+        assert_eq!(world.current_location, "unstable_resonance_site");
+        assert_eq!(world.game_time_minutes, 40);
+
+        // The Consortium turns back those it distrusts
+        player.modify_faction_reputation(FactionId::IndustrialConsortium, -50);
+        let journey = travel(Region::Quarries, &mut player, &mut world, &mut combat, &mut StepRng::new(0, 0)).unwrap();
+        assert!(!journey.arrived);
+        assert_eq!(world.current_location, "unstable_resonance_site");
+
+        // Strangers pay the toll, and the road turns up something
+        player.modify_faction_reputation(FactionId::IndustrialConsortium, 50);
+        let silver = player.inventory.silver;
+        let journey = travel(Region::Quarries, &mut player, &mut world, &mut combat, &mut StepRng::new(0, 0)).unwrap();
+        assert!(journey.arrived);
+        assert!(journey.events[1].contains("toll"));
+        assert!(journey.events[2].contains("silver"));
+        assert!(player.inventory.silver > silver - TOLL);
+        assert_eq!(player.current_location, "shardfall_quarry");
+        assert!(world.locations["shardfall_quarry"].visited);
+    }
+}
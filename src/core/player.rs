@@ -12,6 +12,12 @@ use crate::systems::factions::FactionId;
 use crate::systems::knowledge::{TheoryProgress, LearningActivity, LearningMethod};
 use crate::GameResult;
 
+/// Every capability `Player::has_magic_capability` can grant
+pub const MAGIC_CAPABILITIES: [&str; 7] = [
+    "advanced_light_spells", "healing_spells", "detection_spells", "long_distance_magic",
+    "power_amplification", "custom_spell_combinations", "council_certified",
+];
+
 /// Core player attributes that define magical capabilities
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerAttributes {
@@ -92,6 +98,15 @@ pub struct KnowledgeState {
     /// Current session tracking for learning efficiency calculations
     #[serde(default)]
     pub current_session: Option<LearningSession>,
+    /// Assessment currently in progress
+    #[serde(default)]
+    pub active_assessment: Option<crate::systems::assessments::ActiveAssessment>,
+    /// Theories the player holds a certification in
+    #[serde(default)]
+    pub certifications: Vec<String>,
+    /// Scientific concepts the player has encountered, by glossary ID
+    #[serde(default)]
+    pub encountered_concepts: Vec<String>,
 }
 
 /// Tracks current learning session for efficiency calculations
@@ -165,6 +180,30 @@ pub struct Player {
     pub current_location: String,
     /// Total playtime in minutes
     pub playtime_minutes: i32,
+    /// Display preferences such as text verbosity
+    #[serde(default)]
+    pub preferences: crate::ui::DisplayPreferences,
+    /// Physical health and lasting injuries
+    #[serde(default)]
+    pub health: crate::core::health::Health,
+    /// Faction casting styles learned from mentors
+    #[serde(default)]
+    pub styles: crate::systems::magic::styles::SpellStyles,
+    /// Getting-started checklist
+    #[serde(default)]
+    pub onboarding: crate::systems::onboarding::Onboarding,
+    /// Porter carrying part of the load, if one is hired
+    #[serde(default)]
+    pub porter: Option<crate::systems::encumbrance::Porter>,
+    /// Whether mastered analysis routines resolve instantly
+    #[serde(default)]
+    pub fast_analysis: bool,
+    /// NPCs travelling with the player outside combat
+    #[serde(default)]
+    pub followers: crate::systems::followers::Followers,
+    /// The player's own laboratory, once bought
+    #[serde(default)]
+    pub laboratory: crate::systems::laboratory::Laboratory,
 }
 
 impl Player {
@@ -197,6 +236,9 @@ impl Player {
                 learning_history: Vec::new(),
                 available_methods: HashMap::new(),
                 current_session: None,
+                active_assessment: None,
+                certifications: Vec::new(),
+            encountered_concepts: Vec::new(),
             },
             inventory: Inventory {
                 crystals: vec![
@@ -216,6 +258,14 @@ impl Player {
             },
             current_location: "tutorial_chamber".to_string(),
             playtime_minutes: 0,
+            preferences: crate::ui::DisplayPreferences::default(),
+            health: crate::core::health::Health::default(),
+            styles: crate::systems::magic::styles::SpellStyles::default(),
+            onboarding: crate::systems::onboarding::Onboarding::default(),
+            porter: None,
+            fast_analysis: false,
+            followers: crate::systems::followers::Followers::default(),
+            laboratory: crate::systems::laboratory::Laboratory::default(),
         }
     }
 
@@ -236,6 +286,8 @@ impl Player {
 
         self.mental_state.current_energy =
             (self.mental_state.current_energy - amount).max(0);
+        // A heavy pack makes every exertion more tiring
+        let fatigue_cost = (fatigue_cost as f32 * crate::systems::encumbrance::encumbrance(self).fatigue_multiplier()).round() as i32;
         self.mental_state.fatigue =
             (self.mental_state.fatigue + fatigue_cost).min(100);
 
@@ -244,6 +296,7 @@ impl Player {
 
     /// Recover mental energy through rest
     pub fn recover_energy(&mut self, amount: i32, fatigue_reduction: i32) {
+        let amount = (amount as f32 * self.health.energy_recovery_factor()) as i32;
         self.mental_state.current_energy =
             (self.mental_state.current_energy + amount).min(self.mental_state.max_energy);
         self.mental_state.fatigue =
@@ -280,6 +333,25 @@ impl Player {
         }
     }
 
+    /// Check if the player holds a certification for a theory
+    pub fn has_certification(&self, theory_id: &str) -> bool {
+        self.knowledge.certifications.iter().any(|t| t == theory_id)
+    }
+
+    /// Record a scientific concept as encountered, returning true if it is new
+    pub fn encounter_concept(&mut self, concept_id: &str) -> bool {
+        if self.has_encountered_concept(concept_id) {
+            return false;
+        }
+        self.knowledge.encountered_concepts.push(concept_id.to_string());
+        true
+    }
+
+    /// Check if the player has encountered a scientific concept
+    pub fn has_encountered_concept(&self, concept_id: &str) -> bool {
+        self.knowledge.encountered_concepts.iter().any(|c| c == concept_id)
+    }
+
     /// Add experience to an attribute
     pub fn add_experience(&mut self, attribute: AttributeType, amount: i32) {
         match attribute {
@@ -710,6 +782,7 @@ impl Player {
             "long_distance_magic" => self.theory_understanding("sympathetic_networks") >= 1.0,
             "power_amplification" => self.theory_understanding("resonance_amplification") >= 1.0,
             "custom_spell_combinations" => self.theory_understanding("theoretical_synthesis") >= 1.0,
+            "council_certified" => !self.knowledge.certifications.is_empty(),
             _ => false,
         }
     }
@@ -819,6 +892,36 @@ impl Player {
         }
     }
 
+    /// Wear down the equipped items a cause affects, returning anything worth mentioning
+    pub fn wear_equipment(&mut self, cause: crate::systems::items::WearCause) -> Vec<String> {
+        if let Some(mut item_system) = self.inventory.enhanced_items.take() {
+            let messages = item_system.wear_equipment(self, cause);
+            self.inventory.enhanced_items = Some(item_system);
+            messages
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Wear down a single item from the enhanced system
+    pub fn wear_enhanced_item(&mut self, item_id: &str, amount: i32) -> Option<String> {
+        let mut item_system = self.inventory.enhanced_items.take()?;
+        let message = item_system.wear_item(self, &item_id.to_string(), amount);
+        self.inventory.enhanced_items = Some(item_system);
+        message
+    }
+
+    /// Repair an item from the enhanced system, returning the durability restored
+    pub fn repair_enhanced_item(&mut self, item_id: &str, method: crate::systems::items::RepairMethod) -> GameResult<i32> {
+        if let Some(mut item_system) = self.inventory.enhanced_items.take() {
+            let result = item_system.repair_item(self, &item_id.to_string(), method);
+            self.inventory.enhanced_items = Some(item_system);
+            result
+        } else {
+            Err(crate::GameError::InvalidInput("Enhanced item system not available".to_string()).into())
+        }
+    }
+
     /// Get enhanced inventory summary
     pub fn enhanced_inventory_summary(&self) -> String {
         if let Some(ref item_system) = self.inventory.enhanced_items {
@@ -847,7 +950,7 @@ impl Player {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AttributeType {
     MentalAcuity,
     ResonanceSensitivity,
@@ -881,6 +984,9 @@ impl KnowledgeState {
             learning_history: Vec::new(),
             available_methods: HashMap::new(),
             current_session: None,
+            active_assessment: None,
+            certifications: Vec::new(),
+            encountered_concepts: Vec::new(),
         }
     }
 
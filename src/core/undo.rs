@@ -0,0 +1,203 @@
+//! Taking back the last few actions
+//!
+//! Before each command that changes the game, the engine keeps a snapshot of
+//! the player, the world and every stateful system. `undo` restores the
+//! snapshot taken before the earliest of the commands taken back, so a
+//! misparsed command that spent energy, time or an item costs nothing.
+//!
+//! Fights can't be rewound: the history is cleared whenever the player is in
+//! combat, and `undo` is refused until the fight is over. Settings such as
+//! verbosity and the undo limit itself survive an undo.
+
+use crate::core::{Player, WorldState};
+use crate::input::ParsedCommand;
+use crate::systems::{CombatSystem, DialogueSystem, FactionSystem, KnowledgeSystem, QuestSystem};
+use std::collections::VecDeque;
+
+/// Actions kept for undo unless the player chooses otherwise
+pub const DEFAULT_UNDO_LIMIT: usize = 5;
+/// Most actions the player can ask to keep
+pub const MAX_UNDO_LIMIT: usize = 20;
+
+/// Whether a command is an action worth taking back
+///
+/// Looking things up, changing settings and saving change nothing `undo`
+/// should reach, so `undo` after checking the inventory takes back whatever
+/// came before it.
+pub fn is_undoable(command: &ParsedCommand) -> bool {
+    !matches!(
+        command,
+        ParsedCommand::Look { .. }
+            | ParsedCommand::Relationships
+            | ParsedCommand::Actions
+            | ParsedCommand::Locate { .. }
+            | ParsedCommand::Inventory
+            | ParsedCommand::Status
+            | ParsedCommand::CrystalStatus
+            | ParsedCommand::Components { .. }
+            | ParsedCommand::FactionStatus
+            | ParsedCommand::Legacy
+            | ParsedCommand::Save { .. }
+            | ParsedCommand::Load { .. }
+            | ParsedCommand::Saves
+            | ParsedCommand::RunSummary { .. }
+            | ParsedCommand::ExportSave { .. }
+            | ParsedCommand::ImportSave { .. }
+            | ParsedCommand::ReloadContent { .. }
+            | ParsedCommand::Bestiary { .. }
+            | ParsedCommand::Codex { .. }
+            | ParsedCommand::CombatLog
+            | ParsedCommand::Stats { .. }
+            | ParsedCommand::Party
+            | ParsedCommand::Followers
+            | ParsedCommand::Explain { .. }
+            | ParsedCommand::Calculate { .. }
+            | ParsedCommand::ShowMap
+            | ParsedCommand::SetVerbosity { .. }
+            | ParsedCommand::SetTheme { .. }
+            | ParsedCommand::SetNavigation { .. }
+            | ParsedCommand::SetPrompt { .. }
+            | ParsedCommand::Shortcuts { .. }
+            | ParsedCommand::Alias { .. }
+            | ParsedCommand::Macro { .. }
+            | ParsedCommand::Undo { .. }
+            | ParsedCommand::UndoLimit { .. }
+            | ParsedCommand::Checklist { .. }
+            | ParsedCommand::Help { .. }
+            | ParsedCommand::Quit
+            | ParsedCommand::QuestList
+            | ParsedCommand::QuestActive
+            | ParsedCommand::QuestJournal
+            | ParsedCommand::QuestPage { .. }
+            | ParsedCommand::TrackQuest { .. }
+            | ParsedCommand::Hint { .. }
+            | ParsedCommand::Endings
+            | ParsedCommand::QuestInfo { .. }
+            | ParsedCommand::QuestStatus { .. }
+            | ParsedCommand::QuestRecommendations
+            | ParsedCommand::Caches
+            | ParsedCommand::Services
+            | ParsedCommand::Recipes
+            | ParsedCommand::Calendar
+            | ParsedCommand::Forecast
+            | ParsedCommand::ShowCredentials { .. }
+            | ParsedCommand::Wanted
+            | ParsedCommand::Unknown { .. }
+    )
+}
+
+/// Everything a command could change, as it was before the command ran
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// The command about to run
+    pub command: String,
+    pub player: Player,
+    pub world: WorldState,
+    pub quest_system: QuestSystem,
+    pub dialogue_system: DialogueSystem,
+    pub faction_system: FactionSystem,
+    pub combat_system: CombatSystem,
+    pub knowledge_system: KnowledgeSystem,
+}
+
+/// The snapshots taken before the most recent actions, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct UndoHistory {
+    snapshots: VecDeque<Snapshot>,
+}
+
+impl UndoHistory {
+    /// Keep a snapshot, dropping the oldest beyond the limit
+    pub fn record(&mut self, snapshot: Snapshot, limit: usize) {
+        self.snapshots.push_back(snapshot);
+        self.truncate(limit);
+    }
+
+    /// Drop the most recent snapshot, for a command that failed without changing anything
+    pub fn discard_last(&mut self) {
+        self.snapshots.pop_back();
+    }
+
+    /// Forget everything
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+
+    /// Keep no more than `limit` snapshots
+    pub fn truncate(&mut self, limit: usize) {
+        while self.snapshots.len() > limit {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// How many actions can be taken back
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Take back up to `steps` actions, returning the state before the
+    /// earliest of them and the commands taken back, earliest first
+    pub fn rewind(&mut self, steps: usize) -> Option<(Snapshot, Vec<String>)> {
+        let keep = self.snapshots.len().saturating_sub(steps.max(1));
+        let mut undone: Vec<Snapshot> = self.snapshots.drain(keep..).collect();
+        let commands = undone.iter().map(|snapshot| snapshot.command.clone()).collect();
+        if undone.is_empty() {
+            return None;
+        }
+        Some((undone.swap_remove(0), commands))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(command: &str, energy: i32) -> Snapshot {
+        let mut player = Player::new("Tester".to_string());
+        player.mental_state.current_energy = energy;
+        Snapshot {
+            command: command.to_string(),
+            player,
+            world: WorldState::new(),
+            quest_system: QuestSystem::new(),
+            dialogue_system: DialogueSystem::new(),
+            faction_system: FactionSystem::new(),
+            combat_system: CombatSystem::new(),
+            knowledge_system: KnowledgeSystem::new(),
+        }
+    }
+
+    #[test]
+    fn test_rewind_returns_state_before_earliest_action() {
+        let mut history = UndoHistory::default();
+        history.record(snapshot("go north", 100), 5);
+        history.record(snapshot("cast light", 90), 5);
+        history.record(snapshot("take crystal", 80), 5);
+
+        let (restored, commands) = history.rewind(2).unwrap();
+        assert_eq!(restored.player.mental_state.current_energy, 90);
+        assert_eq!(commands, vec!["cast light".to_string(), "take crystal".to_string()]);
+        assert_eq!(history.len(), 1);
+
+        let (restored, _) = history.rewind(10).unwrap();
+        assert_eq!(restored.command, "go north");
+        assert!(history.rewind(1).is_none());
+    }
+
+    #[test]
+    fn test_history_keeps_only_the_limit() {
+        let mut history = UndoHistory::default();
+        for step in 0..4 {
+            history.record(snapshot(&format!("step {}", step), 100), 3);
+        }
+        assert_eq!(history.len(), 3);
+        history.truncate(1);
+        let (restored, commands) = history.rewind(1).unwrap();
+        assert_eq!(restored.command, "step 3");
+        assert_eq!(commands.len(), 1);
+    }
+}
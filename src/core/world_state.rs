@@ -6,8 +6,10 @@
 //! - Environmental storytelling through magical signatures
 //! - Time tracking and world events
 
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use crate::GameResult;
 
 /// Complete world state including location, environment, and time
@@ -23,6 +25,119 @@ pub struct WorldState {
     pub environment: EnvironmentState,
     /// Active world events and their states
     pub events: HashMap<String, WorldEvent>,
+    /// World events starting and ending, waiting to be broadcast
+    #[serde(default)]
+    pub event_bus: crate::core::events::EventBus,
+    /// Bounties the factions hold against the player
+    #[serde(default)]
+    pub wanted: crate::systems::wanted::Wanted,
+    /// Run metadata used for challenge summaries
+    #[serde(default)]
+    pub run: RunInfo,
+    /// Player map annotations by location ID
+    #[serde(default)]
+    pub annotations: HashMap<String, Vec<MapAnnotation>>,
+    /// Defeat policy and history for this run
+    #[serde(default)]
+    pub defeat: crate::systems::defeat::DefeatState,
+    /// Hotseat co-op link with a second player's apprentice
+    #[serde(default)]
+    pub coop: crate::systems::coop::CoopState,
+    /// Wards prepared ahead of a fight
+    #[serde(default)]
+    pub wards: crate::systems::wards::WardState,
+    /// Items dropped or hidden around the world
+    #[serde(default)]
+    pub ground: crate::systems::ground_items::GroundItems,
+    /// Flux cycle of the Unstable Resonance Site
+    #[serde(default)]
+    pub site_flux: crate::systems::site_flux::SiteFlux,
+    /// Notebooks left by retired characters in worlds with this seed
+    #[serde(default)]
+    pub legacy: crate::systems::legacy_notes::LegacyState,
+    /// Placed items waiting to respawn
+    #[serde(default)]
+    pub placed_items: crate::systems::placed_items::PlacedItems,
+    /// Buffs and debuffs waiting to wear off
+    #[serde(default)]
+    pub timed_effects: crate::systems::timed_effects::TimedEffects,
+    /// Quest reward being negotiated, if any
+    #[serde(default)]
+    pub reward_offer: Option<crate::systems::reward_negotiation::RewardOffer>,
+    /// Demand and haggling for each service provider
+    #[serde(default)]
+    pub services: crate::systems::services::ServiceMarket,
+    /// Lasting consequences of critical crafting failures
+    #[serde(default)]
+    pub phenomena: crate::systems::phenomena::Phenomena,
+    /// What people are saying about the player
+    #[serde(default)]
+    pub rumors: crate::systems::rumors::Rumors,
+    /// Faction meetings the player has sat in on, as "meeting_id/week"
+    #[serde(default)]
+    pub attended_meetings: HashSet<String>,
+    /// Lasting changes to locations, replayed onto content when a save loads
+    #[serde(default)]
+    pub location_changes: crate::systems::location_changes::LocationOverrides,
+    /// Scenery interactions that have already given up what they yield
+    #[serde(default)]
+    pub scenery_used: HashSet<String>,
+    /// Times each archive text has taught the player something, by text ID
+    #[serde(default)]
+    pub archive_readings: HashMap<String, u32>,
+    /// Faction emissaries calling at the player's laboratory
+    #[serde(default)]
+    pub emissaries: crate::systems::emissaries::Emissaries,
+}
+
+/// A player-written marker and note attached to a location on the map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapAnnotation {
+    /// Single character drawn on the map for this location
+    pub marker: char,
+    /// Free-form note text
+    pub note: String,
+    /// Game time when the note was written
+    pub created_at: i32,
+}
+
+/// Identifying information about a single playthrough
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunInfo {
+    /// Seed shared between players attempting the same challenge
+    pub seed: u64,
+    /// Tells this playthrough apart from every other, even one with the same
+    /// seed; given when the run starts, and empty in saves from before runs
+    /// had one
+    #[serde(default)]
+    pub id: String,
+    /// Challenge mutators declared at the start of the run
+    pub mutators: Vec<String>,
+    /// Number of commands executed this run
+    pub turns: u32,
+    /// Random generators handed out so far, so a loaded save carries on
+    /// with the rolls it would have had
+    #[serde(default)]
+    pub draws: u64,
+}
+
+impl RunInfo {
+    /// A generator for the run's next rolls
+    ///
+    /// Every chance the game takes is rolled from one of these, each drawn in
+    /// turn from the run seed, so the same seed and the same commands always
+    /// play out the same way.
+    pub fn rng(&mut self) -> StdRng {
+        let stream = self.seed ^ self.draws.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        self.draws += 1;
+        StdRng::seed_from_u64(stream)
+    }
+
+    /// A generator for rolls made while only looking at the world, such as
+    /// flavour text; the same all turn, and drawn from the run seed
+    pub fn turn_rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.seed ^ u64::from(self.turns).wrapping_mul(0xD1B5_4A32_D192_ED03))
+    }
 }
 
 /// A single location in the game world
@@ -40,6 +155,20 @@ pub struct Location {
         deserialize_with = "crate::systems::serde_helpers::deserialize_direction_map"
     )]
     pub exits: HashMap<Direction, String>,
+    /// Locks on exits that keep the player out until opened
+    #[serde(
+        default,
+        serialize_with = "crate::systems::serde_helpers::serialize_direction_map",
+        deserialize_with = "crate::systems::serde_helpers::deserialize_direction_map"
+    )]
+    pub locks: HashMap<Direction, crate::systems::access::ExitLock>,
+    /// Exits that stay out of sight until discovered
+    #[serde(
+        default,
+        serialize_with = "crate::systems::serde_helpers::serialize_direction_map",
+        deserialize_with = "crate::systems::serde_helpers::deserialize_direction_map"
+    )]
+    pub hidden_exits: HashMap<Direction, crate::systems::secrets::HiddenExit>,
     /// NPCs currently in this location
     pub npcs: Vec<String>,
     /// Items available in this location
@@ -50,6 +179,15 @@ pub struct Location {
     pub faction_presence: HashMap<String, FactionPresence>,
     /// Whether this location has been visited by the player
     pub visited: bool,
+    /// Description added while a world flag condition holds
+    #[serde(default)]
+    pub flag_descriptions: Vec<crate::systems::world_flags::FlaggedText>,
+    /// Ambient lines this location adds to its description now and then
+    #[serde(default)]
+    pub ambience: crate::systems::ambience::AmbienceConfig,
+    /// The faction that keeps this location for those it trusts, if any
+    #[serde(default)]
+    pub restricted: Option<crate::systems::stealth::Restriction>,
 }
 
 /// Cardinal and special directions for movement
@@ -130,16 +268,38 @@ pub struct EnvironmentState {
     pub disturbances: Vec<GlobalDisturbance>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Weather {
     Clear,      // No weather effects
     Cloudy,     // Minor reduction in solar-based magic
     Rainy,      // Water magic enhanced, fire magic reduced
     Stormy,     // Electrical interference with all magic
     Foggy,      // Scrying and detection magic impaired
+    ResonanceStorm, // Wild resonance interferes with all magic
+    NullFog,    // Magic muffled almost to nothing
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Weather {
+    /// Lowercase name used in descriptive text
+    pub fn name(&self) -> &'static str {
+        match self {
+            Weather::Clear => "clear",
+            Weather::Cloudy => "cloudy",
+            Weather::Rainy => "rainy",
+            Weather::Stormy => "stormy",
+            Weather::Foggy => "foggy",
+            Weather::ResonanceStorm => "a resonance storm",
+            Weather::NullFog => "a null fog",
+        }
+    }
+
+    /// Whether this is magical rather than mundane weather
+    pub fn is_magical(&self) -> bool {
+        matches!(self, Weather::ResonanceStorm | Weather::NullFog)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TimeOfDay {
     Dawn,       // Transition magic enhanced
     Morning,    // Light magic enhanced
@@ -150,7 +310,35 @@ pub enum TimeOfDay {
     Midnight,   // Dark magic at peak
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl TimeOfDay {
+    /// Period of the day containing the given hour (0-23)
+    pub fn from_hour(hour: u8) -> Self {
+        match hour % 24 {
+            5..=6 => TimeOfDay::Dawn,
+            7..=11 => TimeOfDay::Morning,
+            12..=13 => TimeOfDay::Midday,
+            14..=17 => TimeOfDay::Afternoon,
+            18..=19 => TimeOfDay::Evening,
+            20..=23 => TimeOfDay::Night,
+            _ => TimeOfDay::Midnight,
+        }
+    }
+
+    /// Lowercase name used in descriptive text
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeOfDay::Dawn => "dawn",
+            TimeOfDay::Morning => "morning",
+            TimeOfDay::Midday => "midday",
+            TimeOfDay::Afternoon => "afternoon",
+            TimeOfDay::Evening => "evening",
+            TimeOfDay::Night => "night",
+            TimeOfDay::Midnight => "midnight",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Season {
     Spring,     // Growth magic enhanced
     Summer,     // Fire and light magic enhanced
@@ -158,6 +346,18 @@ pub enum Season {
     Winter,     // Ice and preservation magic enhanced
 }
 
+impl Season {
+    /// Lowercase name used in descriptive text
+    pub fn name(&self) -> &'static str {
+        match self {
+            Season::Spring => "spring",
+            Season::Summer => "summer",
+            Season::Autumn => "autumn",
+            Season::Winter => "winter",
+        }
+    }
+}
+
 /// Major world events that affect multiple locations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorldEvent {
@@ -173,6 +373,12 @@ pub struct WorldEvent {
     pub magical_effects: HashMap<String, f32>,
     /// Whether this event is currently active
     pub active: bool,
+    /// When the event began (game time in minutes)
+    #[serde(default)]
+    pub started_at: i32,
+    /// When the event is due to end (game time in minutes)
+    #[serde(default)]
+    pub ends_at: i32,
 }
 
 /// Global magical disturbance affecting wide areas
@@ -211,11 +417,33 @@ impl WorldState {
             locations: HashMap::new(),
             environment: EnvironmentState {
                 weather: Weather::Clear,
-                time_of_day: TimeOfDay::Morning,
+                // The clock starts at midnight on the first day
+                time_of_day: TimeOfDay::from_hour(0),
                 season: Season::Spring,
                 disturbances: Vec::new(),
             },
             events: HashMap::new(),
+            event_bus: crate::core::events::EventBus::default(),
+            wanted: crate::systems::wanted::Wanted::default(),
+            run: RunInfo::default(),
+            annotations: HashMap::new(),
+            defeat: crate::systems::defeat::DefeatState::default(),
+            coop: crate::systems::coop::CoopState::default(),
+            wards: crate::systems::wards::WardState::default(),
+            ground: crate::systems::ground_items::GroundItems::default(),
+            site_flux: crate::systems::site_flux::SiteFlux::default(),
+            legacy: crate::systems::legacy_notes::LegacyState::default(),
+            placed_items: crate::systems::placed_items::PlacedItems::default(),
+            timed_effects: crate::systems::timed_effects::TimedEffects::default(),
+            reward_offer: None,
+            services: crate::systems::services::ServiceMarket::default(),
+            phenomena: crate::systems::phenomena::Phenomena::default(),
+            rumors: crate::systems::rumors::Rumors::default(),
+            attended_meetings: HashSet::new(),
+            location_changes: crate::systems::location_changes::LocationOverrides::default(),
+            scenery_used: HashSet::new(),
+            archive_readings: HashMap::new(),
+            emissaries: crate::systems::emissaries::Emissaries::default(),
         }
     }
 
@@ -229,6 +457,22 @@ impl WorldState {
         self.locations.get_mut(&self.current_location)
     }
 
+    /// Change a location for good, remembering the change with the save
+    pub fn change_location(&mut self, location_id: &str, change: crate::systems::location_changes::LocationChange) -> GameResult<()> {
+        let location = self.locations.get_mut(location_id)
+            .ok_or_else(|| crate::GameError::ContentNotFound(format!("Location '{}' not found", location_id)))?;
+        change.apply(location);
+        self.location_changes.record(location_id, change);
+        Ok(())
+    }
+
+    /// Take locations afresh from content, keeping play state and replaying
+    /// every lasting change the player has made
+    pub fn merge_content(&mut self, content: HashMap<String, Location>) {
+        let saved = std::mem::take(&mut self.locations);
+        self.locations = crate::systems::location_changes::merge(content, saved, &self.location_changes);
+    }
+
     /// Move to a new location if possible
     pub fn move_to_location(&mut self, direction: Direction) -> GameResult<String> {
         // Get destination before any mutable operations
@@ -238,6 +482,10 @@ impl WorldState {
                     format!("Current location '{}' not found", self.current_location)
                 ))?;
 
+            if let Some(lock) = current_location.locks.get(&direction) {
+                return Err(crate::GameError::InvalidCommand(lock.refusal()).into());
+            }
+
             current_location.exits.get(&direction)
                 .ok_or_else(|| crate::GameError::InvalidCommand(
                     "You can't go that way".to_string()
@@ -245,19 +493,39 @@ impl WorldState {
                 .clone()
         };
 
-        if !self.locations.contains_key(&destination) {
+        self.enter_location(&destination)?;
+        Ok(destination)
+    }
+
+    /// Go straight to a location, however the player got past what stood between
+    pub fn enter_location(&mut self, destination: &str) -> GameResult<()> {
+        if !self.locations.contains_key(destination) {
             return Err(crate::GameError::ContentNotFound(
                 format!("Destination '{}' not found", destination)
             ).into());
         }
 
-        // Mark new location as visited
-        if let Some(location) = self.locations.get_mut(&destination) {
+        // Nothing left here goes missing while the player was around to see it
+        self.ground.watch(&self.current_location, self.game_time_minutes);
+        self.current_location = destination.to_string();
+        self.mark_current_visited();
+        self.refresh_weather();
+        Ok(())
+    }
+
+    /// Count the place the player is standing in as visited, wherever they came from
+    pub fn mark_current_visited(&mut self) {
+        if let Some(location) = self.locations.get_mut(&self.current_location) {
             location.visited = true;
         }
+    }
 
-        self.current_location = destination.clone();
-        Ok(destination)
+    /// Set the weather to whatever the current location's region has now
+    pub fn refresh_weather(&mut self) {
+        use crate::core::overworld::region_of;
+        use crate::systems::weather::weather_at;
+
+        self.environment.weather = weather_at(region_of(&self.current_location), self.game_time_minutes, self.run.seed);
     }
 
     /// Add a location to the world
@@ -265,21 +533,48 @@ impl WorldState {
         self.locations.insert(location.id.clone(), location);
     }
 
+    /// Current hour of the in-game day (0-23)
+    pub fn hour_of_day(&self) -> u8 {
+        ((self.game_time_minutes / 60) % 24) as u8
+    }
+
+    /// Today's date on the calendar
+    pub fn date(&self) -> crate::core::calendar::Date {
+        crate::core::calendar::Date::at(self.game_time_minutes)
+    }
+
+    /// Sit in on the faction meeting in session here, once per meeting;
+    /// returns the meeting if the player hasn't attended it yet
+    pub fn attend_meeting(&mut self) -> Option<&'static crate::core::calendar::Meeting> {
+        use crate::core::calendar::{meeting_at, DAYS_PER_WEEK};
+
+        let meeting = meeting_at(&self.current_location, self.game_time_minutes)?;
+        let week = self.date().day.div_euclid(DAYS_PER_WEEK);
+        self.attended_meetings.insert(format!("{}/{}", meeting.id, week)).then_some(meeting)
+    }
+
     /// Advance game time and update world state
     pub fn advance_time(&mut self, minutes: i32) {
+        let start = self.game_time_minutes;
         self.game_time_minutes += minutes;
 
-        // Update time of day
-        let hour_of_day = (self.game_time_minutes / 60) % 24;
-        self.environment.time_of_day = match hour_of_day {
-            5..=6 => TimeOfDay::Dawn,
-            7..=11 => TimeOfDay::Morning,
-            12..=13 => TimeOfDay::Midday,
-            14..=17 => TimeOfDay::Afternoon,
-            18..=19 => TimeOfDay::Evening,
-            20..=23 => TimeOfDay::Night,
-            _ => TimeOfDay::Midnight,
-        };
+        // Run the Unstable Resonance Site's flux cycle
+        let at_site = self.current_location == crate::systems::site_flux::SITE_ID;
+        self.site_flux.advance(start, self.game_time_minutes, at_site, self.run.seed);
+
+        // Restock placed items whose respawn time has come
+        for respawn in self.placed_items.due(self.game_time_minutes) {
+            if let Some(location) = self.locations.get_mut(&respawn.location) {
+                if !location.items.contains(&respawn.item_id) {
+                    location.items.push(respawn.item_id);
+                }
+            }
+        }
+
+        // Update time of day, season and weather
+        self.environment.time_of_day = TimeOfDay::from_hour(self.hour_of_day());
+        self.environment.season = self.date().season;
+        self.refresh_weather();
 
         // Age magical signatures
         for location in self.locations.values_mut() {
@@ -296,6 +591,9 @@ impl WorldState {
             let elapsed = self.game_time_minutes - disturbance.start_time;
             elapsed < disturbance.duration_minutes
         });
+
+        // Start and end world events
+        crate::core::events::update(self);
     }
 
     /// Add a magical signature to current location
@@ -332,6 +630,13 @@ impl WorldState {
 
             // Interference reduces effectiveness
             modifier *= 1.0 - location.magical_properties.interference;
+
+            if location.id == crate::systems::site_flux::SITE_ID {
+                modifier *= self.site_flux.phase.magic_modifier();
+            }
+
+            // World events such as surges and backlashes
+            modifier *= crate::core::events::ambient_multiplier(self, &location.id);
         }
 
         // Weather effects
@@ -341,6 +646,8 @@ impl WorldState {
             Weather::Rainy => modifier *= 0.9,
             Weather::Stormy => modifier *= 0.8,
             Weather::Foggy => modifier *= 0.9,
+            Weather::ResonanceStorm => modifier *= 0.75,
+            Weather::NullFog => modifier *= 0.6,
         }
 
         // Time of day effects
@@ -372,6 +679,33 @@ impl WorldState {
         modifier.max(0.1) // Minimum 10% effectiveness
     }
 
+    /// Attach a note to a location
+    pub fn annotate(&mut self, location_id: &str, marker: char, note: String) -> GameResult<()> {
+        if !self.locations.contains_key(location_id) {
+            return Err(crate::GameError::ContentNotFound(
+                format!("Location '{}' not found", location_id)
+            ).into());
+        }
+
+        let annotation = MapAnnotation {
+            marker,
+            note,
+            created_at: self.game_time_minutes,
+        };
+        self.annotations.entry(location_id.to_string()).or_default().push(annotation);
+        Ok(())
+    }
+
+    /// Notes the player has left at a location
+    pub fn annotations_for(&self, location_id: &str) -> &[MapAnnotation] {
+        self.annotations.get(location_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Remove all notes at a location, returning how many were removed
+    pub fn clear_annotations(&mut self, location_id: &str) -> usize {
+        self.annotations.remove(location_id).map(|notes| notes.len()).unwrap_or(0)
+    }
+
     /// Get available exits from current location
     pub fn available_exits(&self) -> Vec<(Direction, String)> {
         if let Some(location) = self.current_location() {
@@ -397,6 +731,8 @@ impl Location {
             name,
             description,
             exits: HashMap::new(),
+            locks: HashMap::new(),
+            hidden_exits: HashMap::new(),
             npcs: Vec::new(),
             items: Vec::new(),
             magical_properties: MagicalProperties {
@@ -408,6 +744,9 @@ impl Location {
             },
             faction_presence: HashMap::new(),
             visited: false,
+            flag_descriptions: Vec::new(),
+            ambience: crate::systems::ambience::AmbienceConfig::default(),
+            restricted: None,
         }
     }
 
@@ -473,6 +812,8 @@ mod tests {
         let world = WorldState::new();
         assert_eq!(world.game_time_minutes, 0);
         assert_eq!(world.current_location, "tutorial_chamber");
+        // The time of day agrees with the clock from the start
+        assert_eq!(world.environment.time_of_day, TimeOfDay::from_hour(world.hour_of_day()));
     }
 
     #[test]
@@ -3,14 +3,22 @@
 //! This module contains handlers that execute parsed commands
 
 use crate::input::command_parser::ParsedCommand;
-use crate::core::{Player, WorldState};
+use crate::input::quantities::Parameters;
+use crate::core::{calendar, Player, WorldState};
+use crate::core::health::{InjuryKind, HEALING_SPELL_HEALTH, MAX_SEVERITY, SICKNESS_FATIGUE_THRESHOLD};
 use crate::persistence::{DatabaseManager, SaveManager};
 use crate::systems::magic::MagicSystem;
 use crate::systems::dialogue::DialogueSystem;
-use crate::systems::factions::FactionSystem;
+use crate::systems::factions::{FactionId, FactionSystem};
 use crate::systems::knowledge::{KnowledgeSystem, LearningMethod};
-use crate::systems::quests::QuestSystem;
+use crate::systems::items::artifacts::ArtifactRegistry;
+use crate::systems::codex::Codex;
+use crate::systems::quests::{QuestId, QuestStatus, QuestSystem};
+use crate::systems::quest_generator;
+use crate::systems::world_flags::WorldFlags;
 use crate::systems::combat::{CombatSystem, DefenseType};
+use crate::systems::scenery::{SceneryAction, SceneryObject};
+use crate::ui::{Theme, Verbosity};
 use crate::GameResult;
 
 /// Trait for handling command execution
@@ -51,27 +59,88 @@ impl CommandHandler for DefaultCommandHandler {
     ) -> GameResult<String> {
         match command {
             ParsedCommand::Move { direction } => {
-                handle_movement(direction, player, world)
+                handle_movement(direction, player, world, dialogue_system, quest_system)
             }
 
             ParsedCommand::Look { target } => {
-                handle_look(target, player, world, database)
+                handle_look(target, player, world, database, dialogue_system, quest_system)
             }
 
             ParsedCommand::Examine { target } => {
                 handle_examine(target, player, world, database)
             }
 
-            ParsedCommand::CastMagic { spell_type, crystal, target } => {
-                handle_magic(spell_type, crystal, target, player, world, magic_system)
+            ParsedCommand::Laboratory => {
+                Ok(player.laboratory.report(player.inventory.silver))
+            }
+
+            ParsedCommand::BuyLaboratory => {
+                crate::systems::laboratory::buy(player, world)
+            }
+
+            ParsedCommand::UpgradeFixture { fixture } => {
+                handle_upgrade_fixture(fixture, player, world)
+            }
+
+            ParsedCommand::StoreItem { item } => {
+                handle_store_item(item, player, world)
+            }
+
+            ParsedCommand::RetrieveItem { item } => {
+                handle_retrieve_item(item, player, world)
+            }
+
+            ParsedCommand::Harvest => {
+                handle_harvest(player, world, database)
+            }
+
+            ParsedCommand::Emissary { choice } => {
+                let mut rng = world.run.rng();
+                crate::systems::emissaries::answer(choice, player, world, &mut rng)
+            }
+
+            ParsedCommand::Search { target } => {
+                handle_scenery(target, SceneryAction::Search, player, world, database)
+            }
+
+            ParsedCommand::Touch { target } => {
+                handle_scenery(target, SceneryAction::Touch, player, world, database)
+            }
+
+            ParsedCommand::CastMagic { spell_type, crystal, target, frequency, duration } => {
+                handle_magic(spell_type, crystal, target, Parameters { frequency, duration }, player, world, magic_system)
             }
 
             ParsedCommand::Talk { target } => {
-                handle_talk(target, player, world, database, dialogue_system, faction_system)
+                handle_talk(target, player, world, dialogue_system, faction_system, quest_system)
             }
 
             ParsedCommand::Ask { target, topic } => {
-                handle_ask(target, topic, player, world, database, dialogue_system, faction_system)
+                handle_ask(target, topic, player, world, dialogue_system, faction_system, quest_system)
+            }
+
+            ParsedCommand::Persuade { target, argument } => {
+                handle_persuade(target, argument, player, world, dialogue_system, faction_system, quest_system)
+            }
+
+            ParsedCommand::Reply { choice } => {
+                handle_reply(choice, player, world, database, dialogue_system, faction_system, quest_system)
+            }
+
+            ParsedCommand::Relationships => {
+                handle_relationships(dialogue_system)
+            }
+
+            ParsedCommand::Actions => {
+                handle_actions(player, world, dialogue_system, knowledge_system, quest_system, faction_system)
+            }
+
+            ParsedCommand::Locate { target } => {
+                handle_locate(target, player, world, dialogue_system, quest_system)
+            }
+
+            ParsedCommand::SpendTime { target, courting } => {
+                handle_spend_time(target, courting, player, world, dialogue_system, quest_system)
             }
 
             ParsedCommand::Inventory => {
@@ -79,48 +148,183 @@ impl CommandHandler for DefaultCommandHandler {
             }
 
             ParsedCommand::Status => {
-                handle_status(player)
+                handle_status(player, world, quest_system)
             }
 
             ParsedCommand::CrystalStatus => {
                 handle_crystal_status(player)
             }
 
+            ParsedCommand::Components { spell } => {
+                crate::systems::magic::components::report(&spell, player)
+            }
+
             ParsedCommand::FactionStatus => {
                 handle_faction_status(player)
             }
 
+            ParsedCommand::Legacy => {
+                Ok(faction_system.legacy_report())
+            }
+
             ParsedCommand::Rest => {
-                handle_rest(player, world)
+                handle_rest(player, world, combat_system)
+            }
+
+            ParsedCommand::RestUntilRecovered => {
+                handle_rest_until_recovered(player, world, dialogue_system, quest_system, combat_system)
+            }
+
+            ParsedCommand::Ward { kind } => {
+                handle_ward(kind, player, world, magic_system, combat_system)
             }
 
             ParsedCommand::Meditate => {
                 handle_meditate(player, world)
             }
 
-            ParsedCommand::Study { theory } => {
-                handle_study(theory, player, database, knowledge_system, world)
+            ParsedCommand::Study { theory, duration } => {
+                handle_study(theory, duration, player, database, knowledge_system, world)
             }
 
             ParsedCommand::Research { topic } => {
                 handle_research(topic, player, knowledge_system, world)
             }
 
+            ParsedCommand::ArchiveResearch { topic } => {
+                handle_archive_research(topic, player, world, database)
+            }
+
+            ParsedCommand::Assess { theory } => {
+                handle_assess(theory, player)
+            }
+
+            ParsedCommand::AssessmentAnswer { answer } => {
+                handle_assessment_answer(answer, player, world)
+            }
+
             ParsedCommand::Take { item } => {
-                handle_take(item, player, world)
+                handle_take(item, player, world, database)
             }
 
             ParsedCommand::Drop { item } => {
                 handle_drop(item, player, world)
             }
 
+            ParsedCommand::Hide { item } => {
+                handle_hide(item, player, world)
+            }
+
+            ParsedCommand::Caches => {
+                handle_caches(world)
+            }
+
+            ParsedCommand::ReadFlux => {
+                handle_read_flux(player, world)
+            }
+
+            ParsedCommand::ReadNotebook => {
+                handle_read_notebook(world, knowledge_system)
+            }
+
+            ParsedCommand::Retire { leave_notes } => {
+                handle_retire(leave_notes, player, world, save_manager)
+            }
+
+            ParsedCommand::Repair { item, npc } => {
+                handle_repair(item, npc, player, world, dialogue_system, quest_system, faction_system)
+            }
+
+            ParsedCommand::Appraise { item, with } => {
+                handle_appraise(item, with, player, world, dialogue_system, quest_system, faction_system)
+            }
+
+            ParsedCommand::FastAnalysis { enabled } => {
+                crate::systems::fast_analysis::set(player, enabled)
+            }
+
+            ParsedCommand::CleanUp { target } => {
+                crate::systems::phenomena::clean_up(world, player, target.as_deref())
+            }
+
+            ParsedCommand::Porter { action } => {
+                handle_porter(action, player, world)
+            }
+
+            ParsedCommand::Services => {
+                handle_services(player, world, dialogue_system, faction_system, quest_system)
+            }
+
+            ParsedCommand::BuyService { service, npc } => {
+                handle_buy_service(service, npc, player, world, dialogue_system, quest_system, faction_system)
+            }
+
+            ParsedCommand::Haggle { npc } => {
+                handle_haggle(npc, player, world, dialogue_system, quest_system, faction_system)
+            }
+
+            ParsedCommand::Calendar => {
+                Ok(handle_calendar(world))
+            }
+
+            ParsedCommand::Forecast => {
+                Ok(handle_forecast(player, world))
+            }
+
+            ParsedCommand::Travel { destination } => {
+                handle_travel(destination, player, world, dialogue_system, quest_system, combat_system)
+            }
+
+            ParsedCommand::Unlock { direction, with } => {
+                crate::systems::access::unlock(&direction, with.as_deref(), player, world)
+            }
+
+            ParsedCommand::PickLock { direction } => {
+                let mut rng = world.run.rng();
+                crate::systems::access::pick_lock(&direction, player, world, &mut rng)
+            }
+
+            ParsedCommand::ShowCredentials { direction } => {
+                crate::systems::access::show_credentials(direction.as_ref(), player, world)
+            }
+
+            ParsedCommand::Sneak { direction } => {
+                handle_sneak(direction, player, world, dialogue_system, quest_system)
+            }
+
+            ParsedCommand::Wanted => {
+                Ok(world.wanted.describe())
+            }
+
+            ParsedCommand::PayBounty { faction } => {
+                let faction = crate::systems::wanted::faction_named(&faction)
+                    .ok_or_else(|| crate::GameError::InvalidInput(format!("There's no faction called '{}'.", faction)))?;
+                world.wanted.pay(player, faction)
+            }
+
             // Quest commands
             ParsedCommand::QuestList => {
-                handle_quest_list(quest_system, player, faction_system)
+                handle_quest_list(quest_system, player, world, dialogue_system, faction_system)
             }
             ParsedCommand::QuestActive => {
                 handle_quest_active(quest_system)
             }
+            ParsedCommand::QuestJournal => {
+                Ok(crate::systems::quest_journal::journal(player, quest_system))
+            }
+            ParsedCommand::QuestPage { quest_id } => {
+                crate::systems::quest_journal::page(quest_system, &quest_id, world.game_time_minutes)
+            }
+            ParsedCommand::TrackQuest { quest } => {
+                handle_track_quest(quest, player, quest_system)
+            }
+            ParsedCommand::Hint { quest } => {
+                crate::systems::quest_hints::hint(quest_system, player, world, dialogue_system, quest.as_deref())
+            }
+            ParsedCommand::Endings => {
+                crate::systems::endings::EndingsGallery::load(save_manager.get_save_directory_path())
+                    .map(|gallery| gallery.describe())
+            }
             ParsedCommand::QuestInfo { quest_id } => {
                 handle_quest_info(quest_id, quest_system)
             }
@@ -140,6 +344,14 @@ impl CommandHandler for DefaultCommandHandler {
                 handle_quest_choice(quest_id, choice_id, option_id, quest_system, player, faction_system)
             }
 
+            ParsedCommand::QuestTurnIn { quest_id } => {
+                handle_quest_turn_in(quest_id, player, world, quest_system, dialogue_system, faction_system)
+            }
+
+            ParsedCommand::RewardChoice { option } => {
+                handle_reward_choice(option, player, world, quest_system, faction_system, database)
+            }
+
             ParsedCommand::Equip { crystal } => {
                 handle_equip_crystal(crystal, player)
             }
@@ -149,21 +361,191 @@ impl CommandHandler for DefaultCommandHandler {
             }
 
             ParsedCommand::Load { slot } => {
-                handle_load(slot, player, world, quest_system, combat_system, faction_system, knowledge_system, dialogue_system, magic_system, save_manager)
+                handle_load(slot, player, world, quest_system, combat_system, faction_system, knowledge_system, dialogue_system, magic_system, save_manager, database)
+            }
+
+            ParsedCommand::Saves => {
+                handle_saves(save_manager)
+            }
+
+            ParsedCommand::Recruit { target } => {
+                handle_recruit(target, player, world, dialogue_system, quest_system, combat_system)
+            }
+
+            ParsedCommand::LearnStyle { style, mentor } => {
+                handle_learn_style(style, mentor, player, world, dialogue_system, quest_system)
+            }
+
+            ParsedCommand::Style { style } => {
+                handle_style(style, player)
+            }
+
+            ParsedCommand::Dismiss { target } => {
+                if combat_system.is_in_combat() {
+                    return Ok("You can't part ways in the middle of a fight.".to_string());
+                }
+                if combat_system.party().find(&target).is_none() {
+                    if let Some(message) = player.followers.dismiss(&target) {
+                        return Ok(message);
+                    }
+                }
+                combat_system.party_mut().dismiss(&target)
+            }
+
+            ParsedCommand::Party => {
+                Ok(combat_system.party().report())
+            }
+
+            ParsedCommand::Invite { target } => {
+                handle_invite(target, player, world, dialogue_system, quest_system)
+            }
+
+            ParsedCommand::Followers => {
+                Ok(player.followers.report(|id| {
+                    world.locations.get(id).map_or_else(|| id.replace('_', " "), |location| location.name.clone())
+                }))
+            }
+
+            ParsedCommand::Coop { action } => {
+                handle_coop(action, player, world)
+            }
+
+            ParsedCommand::Bestiary { enemy } => {
+                let codex = Codex::visible(save_manager.get_save_directory_path(), player, world, combat_system);
+                combat_system.bestiary_report_with(enemy.as_deref(), &codex.enemies)
+            }
+
+            ParsedCommand::CombatLog => {
+                Ok(combat_system.combat_log_report())
+            }
+
+            ParsedCommand::DefeatPolicy { policy } => {
+                handle_defeat_policy(policy, world)
+            }
+
+            ParsedCommand::Difficulty { level } => {
+                handle_difficulty(level, combat_system)
+            }
+
+            ParsedCommand::Calculate { query } => {
+                match query {
+                    Some(query) => crate::systems::calculator::calculate(&query, player, world, magic_system, knowledge_system),
+                    None => Ok(crate::systems::calculator::usage()),
+                }
+            }
+
+            ParsedCommand::Explain { concept } => {
+                let codex = Codex::visible(save_manager.get_save_directory_path(), player, world, combat_system);
+                handle_explain(concept, player, &codex)
+            }
+
+            ParsedCommand::ShowMap => {
+                Ok(crate::core::map::render_map(world))
+            }
+
+            ParsedCommand::Annotate { marker, note } => {
+                handle_annotate(marker, note, world)
+            }
+
+            ParsedCommand::ClearAnnotations => {
+                let location_id = world.current_location.clone();
+                match world.clear_annotations(&location_id) {
+                    0 => Ok("You have no notes here.".to_string()),
+                    count => Ok(format!("Removed {} note(s) from this location.", count)),
+                }
+            }
+
+            ParsedCommand::SetVerbosity { level } => {
+                handle_set_verbosity(level, player)
+            }
+
+            ParsedCommand::SetTheme { theme } => {
+                handle_set_theme(theme, player)
+            }
+
+            ParsedCommand::SetNavigation { setting } => {
+                handle_set_navigation(setting, player)
+            }
+
+            ParsedCommand::UndoLimit { limit } => {
+                handle_undo_limit(limit, player)
+            }
+
+            ParsedCommand::SetPrompt { setting } => {
+                handle_set_prompt(setting, player, quest_system)
+            }
+
+            ParsedCommand::Shortcuts { setting } => {
+                handle_shortcuts(setting, player)
+            }
+
+            ParsedCommand::Checklist { action } => {
+                handle_checklist(action, player)
+            }
+
+            ParsedCommand::RunSummary { export } => {
+                handle_run_summary(export, player, world, quest_system, faction_system, save_manager)
+            }
+
+            ParsedCommand::ExportSave { file } => {
+                let game = crate::persistence::save_system::ArchiveContents {
+                    player, world, quest_system, combat_system, faction_system,
+                    knowledge_system, dialogue_system, magic_system,
+                };
+                let path = save_manager.export_archive(&game, &file)?;
+                Ok(format!(
+                    "Game exported to {}\n\nCopy it into the same folder on another machine and use 'import save {}' there, or attach it to a bug report.",
+                    path.display(), file
+                ))
+            }
+
+            ParsedCommand::ImportSave { file, slot } => {
+                handle_import_save(&file, slot, save_manager)
             }
 
             ParsedCommand::Help { topic: _ } => {
                 Ok("Help is handled by the parser.".to_string())
             }
 
+            ParsedCommand::Alias { .. } | ParsedCommand::Macro { .. } => {
+                Ok("Aliases and macros are handled by the game engine.".to_string())
+            }
+
+            ParsedCommand::Undo { .. } => {
+                Ok("Undo is handled by the game engine.".to_string())
+            }
+
+            ParsedCommand::ReloadContent { .. } => {
+                Ok("Reloading content is handled by the game engine.".to_string())
+            }
+
+            ParsedCommand::Codex { setting } => {
+                handle_codex(setting, player, world, combat_system, save_manager)
+            }
+
+            ParsedCommand::Stats { category } => {
+                handle_stats(category, player, world, save_manager)
+            }
+
             ParsedCommand::Quit => {
                 Ok("QUIT_GAME".to_string()) // Special return value for game loop
             }
 
             // Item system commands (basic implementations)
             ParsedCommand::UseItem { item, target } => {
+                // Timed boosts the item grants, to be undone when they run out
+                let used = player.enhanced_item_system()
+                    .and_then(|item_system| item_system.inventory_manager.get_item(&item))
+                    .map(|used| (used.properties.name.clone(), used.item_type.clone()));
                 match player.use_enhanced_item(&item, target.as_deref()) {
-                    Ok(result) => Ok(result),
+                    Ok(result) => {
+                        if let Some((name, crate::systems::items::ItemType::Consumable { effect, .. })) = used {
+                            for (attribute, amount, minutes) in crate::systems::timed_effects::attribute_boosts(&effect) {
+                                world.timed_effects.track(player, &name, &attribute, amount, minutes, world.game_time_minutes);
+                            }
+                        }
+                        Ok(result)
+                    }
                     Err(_) => Ok(format!("Could not use item: {}", item))
                 }
             }
@@ -173,10 +555,20 @@ impl CommandHandler for DefaultCommandHandler {
             }
 
             ParsedCommand::CraftItem { action, items, recipe } => {
-                let items_str = items.join(", ");
-                let recipe_str = recipe.as_deref().unwrap_or("none");
-                Ok(format!("Crafting: {} with items [{}] using recipe '{}' - not yet implemented.",
-                    action, items_str, recipe_str))
+                handle_craft(action, items, recipe, player, world, database)
+            }
+
+            ParsedCommand::Combine { first, second } => {
+                handle_combine(first, second, player, world, database)
+            }
+
+            ParsedCommand::Deconstruct { item } => {
+                handle_deconstruct(item, player, world)
+            }
+
+            ParsedCommand::Recipes => {
+                let crafting = crate::systems::items::CraftingSystem::load(database)?;
+                Ok(crafting.report(player, &world.current_location))
             }
 
             ParsedCommand::ExamineItem { item } => {
@@ -194,7 +586,7 @@ impl CommandHandler for DefaultCommandHandler {
             }
 
             ParsedCommand::GiveItem { item, target } => {
-                Ok(format!("Give {} to {} - not yet implemented.", item, target))
+                handle_give(item, target, player, world, dialogue_system, quest_system)
             }
 
             ParsedCommand::Attack { target, spell } => {
@@ -202,15 +594,19 @@ impl CommandHandler for DefaultCommandHandler {
             }
 
             ParsedCommand::Defend { defense_type } => {
-                handle_defend_command(defense_type, player, combat_system)
+                handle_defend_command(defense_type, player, world, combat_system)
             }
 
             ParsedCommand::Flee => {
                 handle_flee_command(player, combat_system)
             }
 
+            ParsedCommand::CombatTactic { tactic } => {
+                handle_combat_tactic(tactic, player, world, magic_system, combat_system)
+            }
+
             ParsedCommand::ExamineEnemy => {
-                handle_examine_enemy_command(combat_system)
+                handle_examine_enemy_command(combat_system, player)
             }
 
             ParsedCommand::Unknown { original, suggestions } => {
@@ -225,21 +621,66 @@ fn handle_movement(
     direction: crate::core::world_state::Direction,
     player: &mut Player,
     world: &mut WorldState,
+    dialogue_system: &DialogueSystem,
+    quest_system: &QuestSystem,
 ) -> GameResult<String> {
+    if let Some(lock) = crate::systems::access::lock_on(world, &direction) {
+        return Ok(lock.refusal());
+    }
+    if let Some(way) = crate::systems::stealth::restricted_way(world, player, &direction) {
+        let guards = watchers(way.faction, &way.destination, world, dialogue_system, quest_system);
+        if let Some(refusal) = crate::systems::stealth::turned_away(&way, &guards, &direction) {
+            return Ok(refusal);
+        }
+    }
+
+    let origin = world.current_location.clone();
     match world.move_to_location(direction.clone()) {
         Ok(destination) => {
             player.current_location = destination.clone();
 
-            // Advance time slightly for movement
-            world.advance_time(1);
-            player.playtime_minutes += 1;
+            // Travel takes longer, and can tire, under a heavy pack or in bad weather
+            let load = crate::systems::encumbrance::encumbrance(player);
+            let weather = world.environment.weather;
+            let delay = crate::systems::weather::travel_delay(weather);
+            let minutes = load.travel_minutes() + delay;
+            world.advance_time(minutes);
+            player.playtime_minutes += minutes;
+            player.mental_state.fatigue = (player.mental_state.fatigue + load.travel_fatigue()).min(100);
 
             let mut response = format!("You head {}.\n\n", direction.display_name());
+            if load.travel_minutes() > 1 {
+                response.push_str(&format!("Your {} pack slows you; the walk takes {} minutes.\n\n", load.name().to_lowercase(), minutes));
+            } else if delay > 0 {
+                response.push_str(&format!("The weather is {}, and the walk takes {} minutes.\n\n", weather.name(), minutes));
+            }
+
+            // Catch up on what scavengers took while the player was away
+            let traffic = world.current_location().map_or(0, |location| location.npcs.len());
+            let now = world.game_time_minutes;
+            let mut rng = world.run.rng();
+            for message in world.ground.settle(&destination, now, traffic, &mut rng) {
+                response.push_str(&format!("{}\n", message));
+            }
+            if !response.ends_with("\n\n") {
+                response.push('\n');
+            }
 
             let location = world.current_location()
                 .ok_or_else(|| crate::GameError::ContentNotFound("Current location not found".to_string()))?;
 
-            response.push_str(&generate_location_description(location, player));
+            let people = people_here(player, world, dialogue_system, quest_system);
+            response.push_str(&generate_location_description(location, player, world, &quest_system.global_state.flags, &people));
+
+            for message in ArtifactRegistry::default().on_enter_location(player, location) {
+                response.push_str(&format!("\n{}", message));
+            }
+
+            // Followers come along, or stay behind where they won't go
+            let location_name = |id: &str| world.locations.get(id).map_or_else(|| id.replace('_', " "), |location| location.name.clone());
+            for message in player.followers.arrive(&origin, &destination, location_name) {
+                response.push_str(&format!("\n\n{}", message));
+            }
 
             Ok(response)
         }
@@ -249,12 +690,71 @@ fn handle_movement(
     }
 }
 
+/// Handle travelling by road to another region, or walking to a visited place
+fn handle_travel(
+    destination: Option<String>,
+    player: &mut Player,
+    world: &mut WorldState,
+    dialogue_system: &DialogueSystem,
+    quest_system: &QuestSystem,
+    combat_system: &mut CombatSystem,
+) -> GameResult<String> {
+    use crate::core::overworld::{self, Region};
+
+    let Some(destination) = destination else {
+        return Ok(overworld::describe_routes(world));
+    };
+    let origin = world.current_location.clone();
+    let mut response = match Region::from_name(&destination) {
+        Some(to) => {
+            let mut rng = world.run.rng();
+            let journey = overworld::travel(to, player, world, combat_system, &mut rng)?;
+            let mut response = journey.events.join("\n\n");
+            if !journey.arrived {
+                response.push_str(&format!("\n\nYou lose {} minutes on the road and end up back where you started.", journey.minutes));
+                return Ok(response);
+            }
+            response.push_str(&format!("\n\nThe journey takes {} minutes.\n\n", journey.minutes));
+            response
+        }
+        None => {
+            use crate::core::fast_travel;
+
+            let to = fast_travel::resolve(world, &destination).ok_or_else(|| crate::GameError::InvalidInput(
+                format!("There's no region or place called '{}'.", destination)
+            ))?;
+            if player.preferences.manual_navigation {
+                return Ok("You've chosen to find your own way. ('navigation fast' to allow travelling to places you know)".to_string());
+            }
+            if combat_system.is_in_combat() {
+                return Ok("You can't slip away across the city in the middle of a fight!".to_string());
+            }
+            let trip = fast_travel::travel(&to, player, world)?;
+            format!("You make your way by {}. The walk takes {} minutes.\n\n", trip.path.join(", "), trip.minutes)
+        }
+    };
+
+    let location = world.current_location()
+        .ok_or_else(|| crate::GameError::ContentNotFound("Current location not found".to_string()))?;
+    let people = people_here(player, world, dialogue_system, quest_system);
+    response.push_str(&generate_location_description(location, player, world, &quest_system.global_state.flags, &people));
+
+    let destination = world.current_location.clone();
+    let location_name = |id: &str| world.locations.get(id).map_or_else(|| id.replace('_', " "), |location| location.name.clone());
+    for message in player.followers.arrive(&origin, &destination, location_name) {
+        response.push_str(&format!("\n\n{}", message));
+    }
+    Ok(response)
+}
+
 /// Handle look commands
 fn handle_look(
     target: Option<String>,
     player: &Player,
     world: &WorldState,
     _database: &DatabaseManager,
+    dialogue_system: &DialogueSystem,
+    quest_system: &QuestSystem,
 ) -> GameResult<String> {
     match target {
         Some(target_str) => {
@@ -266,27 +766,116 @@ fn handle_look(
             let location = world.current_location()
                 .ok_or_else(|| crate::GameError::ContentNotFound("Current location not found".to_string()))?;
 
-            Ok(generate_location_description(location, player))
+            let people = people_here(player, world, dialogue_system, quest_system);
+            Ok(generate_location_description(location, player, world, &quest_system.global_state.flags, &people))
+        }
+    }
+}
+
+/// Why an NPC can't talk right now, if they can't; followers travelling
+/// with the player are always on hand
+fn unavailability(player: &Player, dialogue_system: &DialogueSystem, npc_id: &str, world: &WorldState, quest_system: &QuestSystem) -> Option<String> {
+    if player.followers.with_player(npc_id) {
+        return None;
+    }
+    dialogue_system.unavailability_message(npc_id, world, quest_system)
+}
+
+/// Names of a faction's people keeping watch here and at a destination
+fn watchers(faction: FactionId, destination: &str, world: &WorldState, dialogue_system: &DialogueSystem, quest_system: &QuestSystem) -> Vec<String> {
+    [world.current_location.as_str(), destination].iter()
+        .flat_map(|location| dialogue_system.npcs_present(location, world, quest_system))
+        .filter(|npc| npc.faction_affiliation == Some(faction))
+        .map(|npc| npc.name.clone())
+        .collect()
+}
+
+/// Try to slip past whoever keeps watch over a way
+fn handle_sneak(
+    direction: crate::core::world_state::Direction,
+    player: &mut Player,
+    world: &mut WorldState,
+    dialogue_system: &DialogueSystem,
+    quest_system: &QuestSystem,
+) -> GameResult<String> {
+    let way = crate::systems::stealth::watched_way(world, player, &direction)?;
+    let guards = watchers(way.faction, &way.destination, world, dialogue_system, quest_system);
+    let mut rng = world.run.rng();
+    let mut response = crate::systems::stealth::sneak(&way, &guards, player, world, &mut rng)?;
+    if world.current_location == way.destination {
+        let location = world.current_location()
+            .ok_or_else(|| crate::GameError::ContentNotFound("Current location not found".to_string()))?;
+        let people = people_here(player, world, dialogue_system, quest_system);
+        response.push_str(&format!("\n\n{}", generate_location_description(location, player, world, &quest_system.global_state.flags, &people)));
+    }
+    Ok(response)
+}
+
+/// Who is at the current location right now, and what they're doing;
+/// followers are wherever the player is, or wherever they stayed behind
+fn people_here(player: &Player, world: &WorldState, dialogue_system: &DialogueSystem, quest_system: &QuestSystem) -> Vec<String> {
+    let mut people: Vec<String> = dialogue_system.npcs_present(&world.current_location, world, quest_system).into_iter()
+        .filter(|npc| player.followers.find(&npc.id).is_none())
+        .map(|npc| match npc.scheduled_activity(world.game_time_minutes, quest_system) {
+            Some(entry) => format!("{} ({})", npc.name, entry.activity),
+            None => npc.name.clone(),
+        })
+        .collect();
+    for follower in player.followers.members() {
+        match follower.waiting_at.as_deref() {
+            None => people.push(format!("{} (your {})", follower.name, follower.role.name())),
+            Some(location) if location == world.current_location => people.push(format!("{} (waiting for you)", follower.name)),
+            Some(_) => {}
         }
     }
+    people
 }
 
 /// Handle examine commands
 fn handle_examine(
     target: String,
-    player: &Player,
-    world: &WorldState,
-    _database: &DatabaseManager,
+    player: &mut Player,
+    world: &mut WorldState,
+    database: &DatabaseManager,
 ) -> GameResult<String> {
     // Check if examining own crystals
     if target.contains("crystal") && (target.contains("my") || target.contains("crystals")) {
         return handle_crystal_status(player);
     }
 
+    // A close look may turn up a hidden way out
+    if let Some(found) = crate::systems::secrets::examine(world, &target) {
+        return Ok(found);
+    }
+
     // Check if target is in current location
     let location = world.current_location()
         .ok_or_else(|| crate::GameError::ContentNotFound("Current location not found".to_string()))?;
 
+    // Items lying here, stocked or left by the player
+    let query = target.trim_start_matches("the ").to_lowercase();
+    if let Some(id) = location.items.iter()
+        .find(|id| crate::systems::placed_items::display_name(id).contains(&query))
+    {
+        return Ok(match database.load_item_definition(id)? {
+            Some(definition) => crate::systems::items::describe_item(&definition.instantiate()),
+            None => format!("A {} lies here.", crate::systems::placed_items::display_name(id)),
+        });
+    }
+    if let Some(entry) = world.ground.at(&location.id).iter()
+        .find(|entry| entry.item.properties.name.to_lowercase().contains(&query))
+    {
+        return Ok(crate::systems::items::describe_item(&entry.item));
+    }
+
+    // Fixed scenery with a description of its own
+    let objects = database.load_scenery(&location.id)?;
+    if let Some(object) = crate::systems::scenery::find(&objects, &target) {
+        return Ok(interact_with_scenery(object, SceneryAction::Examine, player, world));
+    }
+    let location = world.current_location()
+        .ok_or_else(|| crate::GameError::ContentNotFound("Current location not found".to_string()))?;
+
     // For now, provide basic examination
     let mut response = format!("You examine the {} carefully.\n\n", target);
 
@@ -312,24 +901,48 @@ fn handle_examine(
     Ok(response)
 }
 
-/// Handle magic casting
-fn handle_magic(
+/// Search or touch a piece of scenery here
+fn handle_scenery(
+    target: String,
+    action: SceneryAction,
+    player: &mut Player,
+    world: &mut WorldState,
+    database: &DatabaseManager,
+) -> GameResult<String> {
+    let objects = database.load_scenery(&world.current_location)?;
+    match crate::systems::scenery::find(&objects, &target) {
+        Some(object) => Ok(interact_with_scenery(object, action, player, world)),
+        None => Ok(format!("You don't see any {} here to {}.", target.trim_start_matches("the "), action.name())),
+    }
+}
+
+fn interact_with_scenery(object: &SceneryObject, action: SceneryAction, player: &mut Player, world: &mut WorldState) -> String {
+    let mut rng = world.run.rng();
+    crate::systems::scenery::interact(object, action, player, world, &mut rng)
+}
+
+/// Handle magic casting
+fn handle_magic(
     spell_type: String,
     _crystal: Option<String>,
     target: Option<String>,
+    parameters: Parameters,
     player: &mut Player,
     world: &mut WorldState,
     magic_system: &mut MagicSystem,
 ) -> GameResult<String> {
+    let fatigue_before = player.mental_state.fatigue;
+    let spell_name = player.styles.active.map_or(spell_type.clone(), |style| style.variant_name(&spell_type));
+
     // Use the MagicSystem for proper calculation and execution
-    match magic_system.attempt_magic(&spell_type, player, world, target.as_deref()) {
+    match magic_system.attempt_magic_with(&spell_type, player, world, target.as_deref(), parameters.frequency, parameters.duration) {
         Ok(result) => {
             let mut response = String::new();
 
             if result.success {
                 response.push_str(&format!(
                     "You successfully cast {}{}.\n\n",
-                    spell_type,
+                    spell_name,
                     target.as_ref().map(|t| format!(" on {}", t)).unwrap_or_default()
                 ));
 
@@ -340,14 +953,46 @@ fn handle_magic(
                     result.energy_cost,
                     result.time_cost
                 ));
+
+                if spell_type == "detection" {
+                    if let Some(message) = target.as_deref().and_then(|target| analyze_unidentified(target, player)) {
+                        response.push_str(&format!("\n\n{}", message));
+                    }
+                    for message in crate::systems::secrets::detect(world) {
+                        response.push_str(&format!("\n\n{}", message));
+                    }
+                }
+
+                let on_self = target.as_deref().is_none_or(|t| matches!(t, "self" | "me" | "myself"));
+                if spell_type == "healing" && on_self {
+                    let healed = player.health.heal((result.power_level * HEALING_SPELL_HEALTH) as i32);
+                    response.push_str(&format!("\n\nYou recover {} health ({}/{}).", healed, player.health.current, player.health.max));
+                    if let Some(message) = player.health.treat() {
+                        response.push_str(&format!("\n{}", message));
+                    }
+                }
             } else {
                 response.push_str(&format!(
                     "Your attempt to cast {} failed.\n\n",
-                    spell_type
+                    spell_name
                 ));
                 response.push_str(&result.explanation);
             }
 
+            for message in &result.wear {
+                response.push_str(&format!("\n{}", message));
+            }
+
+            for message in ArtifactRegistry::default().on_cast(player, &spell_type, result.success) {
+                response.push_str(&format!("\n{}", message));
+            }
+
+            if world.phenomena.has_pocket(&world.current_location) {
+                use crate::systems::phenomena::POCKET_FATIGUE;
+                player.mental_state.fatigue = (player.mental_state.fatigue + POCKET_FATIGUE).min(100);
+                response.push_str(&format!("\nThe wild resonance pocket drags at your spell. (Fatigue +{})", POCKET_FATIGUE));
+            }
+
             // Show current energy status
             response.push_str(&format!(
                 "\n\nMental Energy: {}/{} (Fatigue: {})",
@@ -356,6 +1001,8 @@ fn handle_magic(
                 player.mental_state.fatigue
             ));
 
+            response.push_str(&apply_casting_strain(player, world, fatigue_before, result.success));
+
             Ok(response)
         }
         Err(e) => {
@@ -364,57 +1011,107 @@ fn handle_magic(
     }
 }
 
+/// Injuries from pushing magic past the body's limits
+///
+/// A failed cast while badly fatigued backlashes into resonance sickness,
+/// and casting to the very limit of fatigue brings on an exhaustion collapse.
+/// Sickness that is already as bad as it gets brings the caster down.
+fn apply_casting_strain(player: &mut Player, world: &mut WorldState, fatigue_before: i32, success: bool) -> String {
+    use crate::systems::defeat::{resolve_defeat, DefeatCause};
+
+    let mut output = String::new();
+    if !success && fatigue_before >= SICKNESS_FATIGUE_THRESHOLD {
+        if player.health.severity(InjuryKind::ResonanceSickness) >= MAX_SEVERITY {
+            let cause = DefeatCause::Affliction { name: InjuryKind::ResonanceSickness.name().to_lowercase() };
+            output.push_str(&format!("\n\n{}", resolve_defeat(&cause, player, world)));
+            return output;
+        }
+        output.push_str(&format!("\n\nThe failed resonance backlashes through you. {}", player.health.injure(InjuryKind::ResonanceSickness)));
+    }
+    if fatigue_before < 100 && player.mental_state.fatigue >= 100 {
+        output.push_str(&format!("\n\nYour body gives out beneath you. {}", player.health.injure(InjuryKind::ExhaustionCollapse)));
+    }
+    output
+}
+
 /// Handle talking to NPCs with theory-aware responses
 fn handle_talk(
     target: String,
     player: &Player,
-    world: &WorldState,
-    _database: &DatabaseManager,
+    world: &mut WorldState,
     dialogue_system: &mut DialogueSystem,
     faction_system: &FactionSystem,
+    quest_system: &QuestSystem,
 ) -> GameResult<String> {
-    // For now, try to find an NPC in the current location
     let location = world.current_location()
         .ok_or_else(|| crate::GameError::ContentNotFound("Current location not found".to_string()))?;
 
-    // Check if the target is mentioned in the location description or NPCs
-    if location.description.to_lowercase().contains(&target.to_lowercase()) {
-        match dialogue_system.talk_to_npc(&target, player, faction_system) {
-            Ok(mut response) => {
-                // Add theory-aware topics
-                let theory_topics = dialogue_system.get_theory_topics(&target, player);
-                let theory_only_topics: Vec<String> = theory_topics.iter()
-                    .filter(|topic| {
-                        matches!(topic.as_str(),
-                            "resonance_theory" | "crystal_research" | "mental_techniques" |
-                            "light_experiments" | "healing_methods" | "detection_techniques" |
-                            "network_theory" | "advanced_amplification" | "theoretical_mastery" |
-                            "advanced_theory_discussion" | "research_collaboration" |
-                            "theoretical_breakthroughs" | "healing_applications" |
-                            "magical_detection" | "long_distance_communication" | "spell_innovation"
-                        )
-                    })
-                    .cloned()
-                    .collect();
-
-                if !theory_only_topics.is_empty() {
-                    response.push_str("\n\nTheory Discussion Topics: ");
-                    response.push_str(&theory_only_topics.join(", "));
-                }
+    let npc_id = match dialogue_system.find_npc(&target, &location.id) {
+        Some(npc) => npc.id.clone(),
+        None => return Ok(format!("You don't see {} here to talk to.", target)),
+    };
 
-                Ok(response)
-            },
-            Err(_) => {
-                // If specific NPC not found, create a basic interaction
-                Ok(format!(
-                    "You approach the {} to start a conversation.\n\nThe {} acknowledges you but seems to have little to say.\n\n[Full NPC dialogue system loading...]",
-                    target, target
-                ))
-            }
+    // Absent, busy, or away on quest business
+    if let Some(message) = unavailability(player, dialogue_system, &npc_id, world, quest_system) {
+        return Ok(message);
+    }
+
+    let mut response = dialogue_system.talk_to_npc(&npc_id, player, faction_system)?;
+    if let Some(npc) = dialogue_system.get_npc(&npc_id) {
+        for line in quest_system.global_state.flags.select(&npc.flag_lines) {
+            response.push_str(&format!("\n\n{}", line));
+        }
+    }
+    if player.preferences.verbosity == Verbosity::Rich {
+        if let Some(npc) = dialogue_system.get_npc(&npc_id) {
+            response = format!("{}\n\n{}", npc.description, response);
+        }
+    }
+
+    // Faction members pick up on how you cast
+    if let (Some(style), Some(npc)) = (player.styles.active, dialogue_system.get_npc(&npc_id)) {
+        if let Some(faction) = npc.faction_affiliation {
+            response.push_str(&format!("\n\n{}", style.recognition(npc.short_name(), faction)));
+        }
+    }
+
+    // Anyone nearby has something to say about a mess left here
+    if let Some((phenomenon, npc)) = world.phenomena.at(&location.id).next().zip(dialogue_system.get_npc(&npc_id)) {
+        response.push_str(&format!("\n\n{}", phenomenon.remark(npc.short_name())));
+    }
+
+    // Word of what the player has done reaches people eventually, a little wrong
+    if let Some(npc) = dialogue_system.get_npc(&npc_id) {
+        if let Some(rumor) = world.rumors.hear(&npc_id, &world.current_location, &world.locations, world.game_time_minutes) {
+            response.push_str(&format!("\n\n{} has heard something about you: \"{}\"", npc.short_name(), rumor));
+        }
+        for hint in crate::systems::secrets::hints(world, &npc_id, npc.short_name()) {
+            response.push_str(&format!("\n\n{}", hint));
         }
-    } else {
-        Ok(format!("You don't see {} here to talk to.", target))
     }
+
+    // Add theory-aware topics
+    let theory_topics = dialogue_system.get_theory_topics(&npc_id, player);
+    let theory_only_topics: Vec<String> = theory_topics.iter()
+        .filter(|topic| {
+            matches!(topic.as_str(),
+                "resonance_theory" | "crystal_research" | "mental_techniques" |
+                "light_experiments" | "healing_methods" | "detection_techniques" |
+                "network_theory" | "advanced_amplification" | "theoretical_mastery" |
+                "advanced_theory_discussion" | "research_collaboration" |
+                "theoretical_breakthroughs" | "healing_applications" |
+                "magical_detection" | "long_distance_communication" | "spell_innovation"
+            )
+        })
+        .cloned()
+        .collect();
+
+    if !theory_only_topics.is_empty() {
+        response.push_str("\n\nTheory Discussion Topics: ");
+        response.push_str(&theory_only_topics.join(", "));
+    }
+
+    Ok(response)
 }
 
 /// Handle asking NPCs about topics with theory-aware responses
@@ -423,35 +1120,247 @@ fn handle_ask(
     topic: String,
     player: &Player,
     world: &WorldState,
-    _database: &DatabaseManager,
     dialogue_system: &mut DialogueSystem,
     faction_system: &FactionSystem,
+    quest_system: &mut QuestSystem,
 ) -> GameResult<String> {
-    // For now, try to find an NPC in the current location
     let location = world.current_location()
         .ok_or_else(|| crate::GameError::ContentNotFound("Current location not found".to_string()))?;
 
-    // Check if the target is mentioned in the location description or NPCs
-    if location.description.to_lowercase().contains(&target.to_lowercase()) {
-        // First try theory-aware responses
-        if let Some(theory_response) = dialogue_system.get_theory_response(&target, &topic, player) {
-            return Ok(format!("You ask {} about {}.\n\n{}", target, topic, theory_response));
+    // Without a name, keep talking to whoever you were talking to, or the only person here
+    let (npc_id, target) = if target.is_empty() {
+        match dialogue_system.addressee(world, quest_system) {
+            Some(npc) => (npc.id.clone(), npc.short_name().to_string()),
+            None => return Err(crate::GameError::InvalidCommand(format!("Who do you want to ask about {}? Use: ask <person> about <topic>", topic)).into()),
+        }
+    } else {
+        match dialogue_system.find_npc(&target, &location.id) {
+            Some(npc) => (npc.id.clone(), target),
+            None => return Ok(format!("You don't see {} here to ask about {}.", target, topic)),
+        }
+    };
+
+    if let Some(message) = unavailability(player, dialogue_system, &npc_id, world, quest_system) {
+        return Ok(message);
+    }
+
+    // Anyone can be asked where someone else has gone
+    if let Some(answer) = dialogue_system.whereabouts_answer(&npc_id, &topic, world, quest_system) {
+        return Ok(format!("You ask {} about {}.\n\n{}", target, topic, answer));
+    }
+
+    // First try theory-aware responses
+    if let Some(theory_response) = dialogue_system.get_theory_response(&npc_id, &topic, player) {
+        return Ok(format!("You ask {} about {}.\n\n{}", target, topic, theory_response));
+    }
+
+    // Fall back to standard dialogue system
+    let response = dialogue_system.ask_about_topic(&npc_id, &topic, player, faction_system)?;
+
+    // Some conversations change what the world knows
+    if let Some(flag) = dialogue_system.topic_flag(&npc_id, &topic, player, faction_system) {
+        quest_system.global_state.flags.set(flag);
+    }
+    Ok(response)
+}
+
+/// Handle choosing a response to the topic last asked about
+fn handle_reply(
+    choice: usize,
+    player: &mut Player,
+    world: &WorldState,
+    database: &DatabaseManager,
+    dialogue_system: &mut DialogueSystem,
+    faction_system: &FactionSystem,
+    quest_system: &mut QuestSystem,
+) -> GameResult<String> {
+    use crate::systems::dialogue::DialogueEffect;
+
+    // The conversation ends when either of you walks away
+    if let Some(conversation) = dialogue_system.conversation() {
+        if conversation.location_id != world.current_location {
+            return Err(crate::GameError::InvalidCommand("You've left that conversation behind.".to_string()).into());
         }
+        if let Some(message) = unavailability(player, dialogue_system, &conversation.npc_id, world, quest_system) {
+            return Ok(message);
+        }
+    }
 
-        // Fall back to standard dialogue system
-        match dialogue_system.ask_about_topic(&target, &topic, player, faction_system) {
-            Ok(response) => Ok(response),
-            Err(_) => {
-                // If specific NPC not found, create a basic interaction
-                Ok(format!(
-                    "You ask the {} about {}.\n\nThe {} doesn't seem to know much about that topic.\n\n[Topic: {} - Full dialogue system loading...]",
-                    target, topic, target, topic
-                ))
+    let (mut response, effect) = dialogue_system.reply(choice, player, faction_system)?;
+    match effect {
+        DialogueEffect::None => {}
+        DialogueEffect::FactionStandingChange(faction, change) => {
+            player.modify_faction_reputation(faction, change);
+            response.push_str(&format!(
+                "\n\n{} faction standing with {}",
+                if change > 0 { format!("+{}", change) } else { change.to_string() },
+                faction.display_name()
+            ));
+        }
+        DialogueEffect::GiveInformation(information) | DialogueEffect::UnlockTheoryDiscussion(information)
+            | DialogueEffect::OfferMentorship(information) => {
+            response.push_str(&format!("\n\n{}", information));
+        }
+        DialogueEffect::ShareResearch(theory_id, research) => {
+            response.push_str(&format!("\n\nOn {}: {}", theory_id.replace('_', " "), research));
+        }
+        DialogueEffect::GiveItem(item_id) => {
+            let definition = database.load_item_definition(&item_id)?
+                .ok_or_else(|| crate::GameError::ContentNotFound(format!("Item '{}' not found", item_id)))?;
+            player.add_enhanced_item(definition.instantiate())?;
+            response.push_str(&format!("\n\nReceived: {}", definition.name));
+        }
+        DialogueEffect::QuestStart(quest_id) => {
+            match quest_system.start_quest(&quest_id, player, faction_system) {
+                Ok(message) => response.push_str(&format!("\n\n{}", message)),
+                Err(error) => response.push_str(&format!("\n\n{}", error)),
             }
         }
-    } else {
-        Ok(format!("You don't see {} here to ask about {}.", target, topic))
+        DialogueEffect::TheoryInsight(theory_id, bonus) => {
+            let understanding = player.knowledge.theories.entry(theory_id.clone()).or_insert(0.0);
+            *understanding = (*understanding + bonus).min(1.0);
+            response.push_str(&format!("\n\nYour understanding of {} deepens.", theory_id.replace('_', " ")));
+        }
+    }
+    Ok(response)
+}
+
+/// Handle listing how the people the player has met feel about them
+fn handle_relationships(dialogue_system: &DialogueSystem) -> GameResult<String> {
+    use crate::systems::bonds::MAX_DEPTH;
+
+    let mut known: Vec<_> = dialogue_system.relationships().iter()
+        .filter(|(_, relationship)| relationship.met)
+        .filter_map(|(npc_id, relationship)| dialogue_system.get_npc(npc_id).map(|npc| (npc_id, npc.name.clone(), relationship)))
+        .collect();
+    if known.is_empty() {
+        return Ok("You haven't got to know anyone yet. Talk to people to start.".to_string());
+    }
+    known.sort_by(|a, b| b.2.affinity.cmp(&a.2.affinity).then_with(|| a.1.cmp(&b.1)));
+
+    let mut response = "=== RELATIONSHIPS ===\n".to_string();
+    for (npc_id, name, relationship) in known {
+        response.push_str(&format!("\n{} - {}", name, relationship.stage().name()));
+        if let Some(bond) = dialogue_system.bonds().get(npc_id).filter(|bond| bond.depth > 0 || bond.strained) {
+            response.push_str(&format!("\n  Bond: {} {}/{}{}", bond.kind.name(), bond.depth, MAX_DEPTH, if bond.strained { " (strained)" } else { "" }));
+        }
+        if let Some(memory) = relationship.memories.last() {
+            response.push_str(&format!("\n  Remembers: {}", memory));
+        }
+    }
+    Ok(response)
+}
+
+/// Handle spending the day with someone on their bond track
+fn handle_spend_time(
+    target: String,
+    courting: bool,
+    player: &mut Player,
+    world: &mut WorldState,
+    dialogue_system: &mut DialogueSystem,
+    quest_system: &mut QuestSystem,
+) -> GameResult<String> {
+    use crate::systems::bonds::TIME_TOGETHER_MINUTES;
+
+    let npc_id = match dialogue_system.find_npc(&target, &world.current_location) {
+        Some(npc) => npc.id.clone(),
+        None => return Ok(format!("You don't see {} here.", target)),
+    };
+    if let Some(message) = unavailability(player, dialogue_system, &npc_id, world, quest_system) {
+        return Ok(message);
+    }
+
+    let (mut response, flag) = dialogue_system.spend_time(&npc_id, courting, world.game_time_minutes)?;
+    if let Some(flag) = flag {
+        quest_system.global_state.flags.set(&flag);
+        response.push_str("\n\nThey've asked something of you. Check 'quests' for what it is.");
+    }
+    world.advance_time(TIME_TOGETHER_MINUTES);
+    player.playtime_minutes += TIME_TOGETHER_MINUTES;
+    Ok(response)
+}
+
+/// Handle persuading an NPC to defect to another faction
+fn handle_persuade(
+    target: String,
+    argument: String,
+    player: &mut Player,
+    world: &mut WorldState,
+    dialogue_system: &mut DialogueSystem,
+    faction_system: &mut FactionSystem,
+    quest_system: &mut QuestSystem,
+) -> GameResult<String> {
+    use crate::systems::factions::{defection, PersuasionArgument};
+
+    let argument = match PersuasionArgument::from_string(&argument) {
+        Some(argument) => argument,
+        None => {
+            let options: Vec<&str> = PersuasionArgument::all().iter().map(|a| a.name()).collect();
+            return Ok(format!("'{}' isn't an argument anyone will listen to. Try: {}", argument, options.join(", ")));
+        }
+    };
+
+    // The same people 'talk' finds: by name, here, and free to listen
+    let (npc_id, npc_name, npc_faction, disposition) = match dialogue_system.find_npc(&target, &world.current_location) {
+        Some(npc) => (npc.id.clone(), npc.name.clone(), npc.faction_affiliation, npc.current_disposition),
+        None => return Ok(format!("You don't see {} here to persuade.", target)),
+    };
+    if let Some(message) = unavailability(player, dialogue_system, &npc_id, world, quest_system) {
+        return Ok(message);
+    }
+
+    let target_faction = argument.champion();
+    let result = faction_system.defection.persuade(
+        &npc_id,
+        npc_faction,
+        argument,
+        player.faction_reputation(target_faction),
+        disposition,
+        world.game_time_minutes,
+    )?;
+
+    world.advance_time(15);
+    player.playtime_minutes += 15;
+
+    let mut response = format!("{}: {}", npc_name, result.message);
+
+    if result.defected {
+        if let Some(npc) = dialogue_system.get_npc_mut(&npc_id) {
+            npc.faction_affiliation = Some(result.target_faction);
+        }
+
+        faction_system.apply_defection(result.original_faction, result.target_faction);
+
+        // The defector takes their post with them
+        if let Some(location) = world.current_location_mut() {
+            if let Some(from) = result.original_faction {
+                if let Some(presence) = location.faction_presence.get_mut(from.key()) {
+                    presence.member_count = (presence.member_count - 1).max(0);
+                }
+            }
+            if let Some(presence) = location.faction_presence.get_mut(result.target_faction.key()) {
+                presence.member_count += 1;
+            }
+        }
+
+        if let Some(from) = result.original_faction {
+            player.modify_faction_reputation(from, -10);
+        }
+        player.modify_faction_reputation(result.target_faction, 10);
+
+        let quest = defection::create_defector_quest(&npc_id, &npc_name, result.target_faction);
+        let quest_id = quest.id.clone();
+        if !quest_system.quest_definitions.contains_key(&quest_id) {
+            response.push_str(&format!("\n\nNew quest available: {} (quest info {})", quest.title, quest_id));
+            quest_system.add_quest_definition(quest);
+            quest_system.global_state.unlocked_quest_lines.push(quest_id);
+        }
+    } else if let Some(from) = npc_faction {
+        // Courting someone's members doesn't go unnoticed
+        player.modify_faction_reputation(from, -2);
     }
+
+    Ok(response)
 }
 
 /// Handle inventory display
@@ -488,15 +1397,21 @@ fn handle_inventory(player: &Player) -> GameResult<String> {
 
     // Currency
     response.push_str(&format!("\nSilver: {} pieces\n", player.inventory.silver));
+    response.push_str(&format!("Load: {}\n", crate::systems::encumbrance::describe_load(player)));
 
     Ok(response)
 }
 
 /// Handle status display with theory benefits
-fn handle_status(player: &Player) -> GameResult<String> {
+fn handle_status(player: &Player, world: &WorldState, quest_system: &QuestSystem) -> GameResult<String> {
     let mut response = String::new();
     response.push_str(&format!("=== {} ===\n\n", player.name));
 
+    // The tracked quest stays pinned above everything else
+    if let Some(tracker) = crate::systems::quest_journal::tracker_line(player, quest_system) {
+        response.push_str(&format!("{}\n\n", tracker));
+    }
+
     // Attributes
     response.push_str("Attributes:\n");
     response.push_str(&format!("  Mental Acuity: {}/100\n", player.attributes.mental_acuity));
@@ -508,6 +1423,16 @@ fn handle_status(player: &Player) -> GameResult<String> {
     response.push_str(&format!("  Fatigue: {}/100\n", player.mental_state.fatigue));
     response.push_str(&format!("  Effective Energy: {}\n", player.effective_mental_energy()));
 
+    response.push_str("\nHealth:\n");
+    response.push_str(&player.health.report());
+
+    response.push_str(&format!("\nLoad: {}\n", crate::systems::encumbrance::describe_load(player)));
+
+    if let Some(effects) = world.timed_effects.summary(player, world.game_time_minutes) {
+        response.push_str("\nActive Effects:\n");
+        response.push_str(&effects);
+    }
+
     // Active crystal
     response.push_str("\nActive Crystal:\n");
     if let Some(crystal) = player.active_crystal() {
@@ -621,8 +1546,6 @@ fn handle_faction_status(player: &Player) -> GameResult<String> {
     let mut response = String::new();
     response.push_str("=== FACTION STANDINGS ===\n\n");
 
-    use crate::systems::factions::FactionId;
-
     for faction_id in FactionId::all() {
         let reputation = player.faction_reputation(faction_id);
         let standing = match reputation {
@@ -644,110 +1567,309 @@ fn handle_faction_status(player: &Player) -> GameResult<String> {
 }
 
 /// Handle rest command
-fn handle_rest(player: &mut Player, world: &mut WorldState) -> GameResult<String> {
+fn handle_rest(player: &mut Player, world: &mut WorldState, combat_system: &mut CombatSystem) -> GameResult<String> {
     let rest_time = 60; // 1 hour
     let fatigue_reduction = 10;
 
     player.recover_energy(0, fatigue_reduction);
     world.advance_time(rest_time);
     player.playtime_minutes += rest_time;
+    let healing = player.health.rest(rest_time);
 
-    Ok(format!(
+    let mut output = format!(
         "You rest for an hour, feeling somewhat refreshed.\n\
-         Fatigue reduced by {}. Current fatigue: {}/100",
-        fatigue_reduction, player.mental_state.fatigue
-    ))
+         Fatigue reduced by {}. Current fatigue: {}/100. Health: {}/{}",
+        fatigue_reduction, player.mental_state.fatigue, player.health.current, player.health.max
+    );
+    for message in healing {
+        output.push_str(&format!("\n{}", message));
+    }
+    for message in combat_system.party_mut().rest() {
+        output.push_str(&format!("\n{}", message));
+    }
+
+    Ok(output)
 }
 
-/// Handle meditate command
-fn handle_meditate(player: &mut Player, world: &mut WorldState) -> GameResult<String> {
-    let meditation_time = 60; // 1 hour
-    let fatigue_reduction = 15;
+/// Handle resting until recovered
+fn handle_rest_until_recovered(
+    player: &mut Player,
+    world: &mut WorldState,
+    dialogue_system: &DialogueSystem,
+    quest_system: &QuestSystem,
+    combat_system: &mut CombatSystem,
+) -> GameResult<String> {
+    use crate::systems::resting;
 
-    player.recover_energy(0, fatigue_reduction);
-    world.advance_time(meditation_time);
-    player.playtime_minutes += meditation_time;
+    if combat_system.is_in_combat() {
+        return Ok("You can't rest in the middle of a fight!".to_string());
+    }
+    if resting::is_recovered(player) {
+        return Ok("You are already fully rested.".to_string());
+    }
 
-    Ok(format!(
-        "You enter a meditative state, focusing your mind and clearing mental fog.\n\
-         Fatigue reduced by {}. Current fatigue: {}/100",
-        fatigue_reduction, player.mental_state.fatigue
-    ))
+    let mut rng = world.run.rng();
+    let summary = resting::rest_until_recovered(player, world, dialogue_system, quest_system, combat_system, &mut rng);
+    Ok(summary.report(player))
 }
 
-/// Handle study command with enhanced knowledge system
-fn handle_study(
-    theory: String,
+/// Handle preparing a ward before a fight
+fn handle_ward(
+    kind: Option<String>,
     player: &mut Player,
-    _database: &DatabaseManager,
-    knowledge_system: &mut KnowledgeSystem,
-    world: &mut WorldState
+    world: &mut WorldState,
+    magic_system: &mut MagicSystem,
+    combat_system: &CombatSystem,
 ) -> GameResult<String> {
-    let study_time = 30; // 30 minutes
-
-    // Check if player can access this theory
-    let accessible_theories = knowledge_system.get_accessible_theories(player)?;
-    let theory_available = accessible_theories.iter().any(|t| t.id == theory);
+    use crate::systems::wards::WardKind;
 
-    if !theory_available {
-        // Fallback to simple study for backward compatibility
-        let current_understanding = player.knowledge.theories.get(&theory).copied().unwrap_or(0.0);
-        let progress = 0.1; // 10% progress per study session
-        let new_understanding = (current_understanding + progress).min(1.0);
-        player.knowledge.theories.insert(theory.clone(), new_understanding);
-        player.playtime_minutes += study_time;
+    let Some(kind) = kind else {
+        let (location, now) = (world.current_location.clone(), world.game_time_minutes);
+        return Ok(world.wards.report(&location, now));
+    };
+    if combat_system.is_in_combat() {
+        return Ok("Wards take too long to raise mid-fight. Prepare them before combat.".to_string());
+    }
+    let kind = WardKind::from_string(&kind).ok_or_else(|| {
+        crate::GameError::InvalidInput(format!("Unknown ward '{}'. Try 'ward shield' or 'ward dampener'.", kind))
+    })?;
 
+    let result = match magic_system.attempt_magic(kind.spell(), player, world, None) {
+        Ok(result) => result,
+        Err(e) => return Ok(format!("Unable to raise a {}: {}", kind.name(), e)),
+    };
+    if !result.success {
         return Ok(format!(
-            "You spend {} minutes studying {}.\n\
-             Understanding: {:.0}% -> {:.0}%\n\
-             (Enhanced theory system not available for this topic)",
-            study_time, theory,
-            current_understanding * 100.0,
-            new_understanding * 100.0
+            "The {} fails to take hold.\n\n{}",
+            kind.name(), result.explanation
         ));
     }
 
-    // Check if player can use study method
-    if !player.can_use_learning_method(&theory, &LearningMethod::Study) {
-        return Ok("You cannot use the study method for this theory right now.".to_string());
+    let (location, now) = (world.current_location.clone(), world.game_time_minutes);
+    let message = world.wards.raise(kind, result.power_level, &location, now);
+    Ok(format!(
+        "{}\n\nEnergy Cost: {}, Fatigue Cost: {}",
+        message, result.energy_cost, result.fatigue_cost
+    ))
+}
+
+/// Handle recruit command
+fn handle_recruit(
+    target: String,
+    player: &mut Player,
+    world: &WorldState,
+    dialogue_system: &DialogueSystem,
+    quest_system: &QuestSystem,
+    combat_system: &mut CombatSystem,
+) -> GameResult<String> {
+    if combat_system.is_in_combat() {
+        return Ok("This is no time for negotiations.".to_string());
     }
 
-    // Start learning session
-    player.start_learning_session(theory.clone(), LearningMethod::Study)?;
+    let location = world.current_location()
+        .ok_or_else(|| crate::GameError::ContentNotFound("Current location not found".to_string()))?;
+    let (npc_id, npc_name) = match dialogue_system.find_npc(&target, &location.id) {
+        Some(npc) => (npc.id.clone(), npc.name.clone()),
+        None => return Ok(format!("You don't see {} here.", target)),
+    };
 
-    // Attempt learning through the knowledge system
-    match knowledge_system.attempt_learning(&theory, LearningMethod::Study, study_time, player, world) {
-        Ok(activity) => {
-            // Update player progress
-            player.update_theory_progress(&activity)?;
-            player.playtime_minutes += study_time;
+    if let Some(message) = unavailability(player, dialogue_system, &npc_id, world, quest_system) {
+        return Ok(message);
+    }
+    if crate::systems::companions::companion_template(&npc_id).is_none() {
+        return Ok(format!("{} has no interest in joining you.", npc_name));
+    }
 
-            let mut response = format!(
-                "You spend {} minutes studying {}.\n\n",
-                study_time, theory
-            );
+    combat_system.party_mut().recruit(&npc_id, player)
+}
 
-            response.push_str(&format!(
-                "Session Results:\n\
-                 - Understanding gained: {:.1}%\n\
-                 - Experience gained: {} XP\n\
-                 - Success rate: {:.0}%\n",
-                activity.understanding_gained * 100.0,
-                activity.experience_gained,
-                activity.success_rate * 100.0
-            ));
+/// Handle inviting an NPC to travel with the player
+fn handle_invite(
+    target: String,
+    player: &mut Player,
+    world: &WorldState,
+    dialogue_system: &DialogueSystem,
+    quest_system: &QuestSystem,
+) -> GameResult<String> {
+    let (npc_id, npc_name) = match dialogue_system.find_npc(&target, &world.current_location) {
+        Some(npc) => (npc.id.clone(), npc.name.clone()),
+        None => return Ok(format!("You don't see {} here.", target)),
+    };
+    if let Some(message) = unavailability(player, dialogue_system, &npc_id, world, quest_system) {
+        return Ok(message);
+    }
 
-            let current_understanding = player.theory_understanding(&theory);
-            response.push_str(&format!(
-                "\nCurrent understanding: {:.0}%",
-                current_understanding * 100.0
-            ));
+    let stage = dialogue_system.relationships().stage(&npc_id);
+    player.followers.invite(&npc_id, &npc_name, stage, &world.current_location, &mut player.inventory.silver)
+}
+
+/// Handle learning a faction casting style from a mentor
+fn handle_learn_style(
+    style: String,
+    mentor: String,
+    player: &mut Player,
+    world: &mut WorldState,
+    dialogue_system: &DialogueSystem,
+    quest_system: &QuestSystem,
+) -> GameResult<String> {
+    use crate::systems::magic::styles::{SpellStyle, LESSON_MINUTES, MIN_MENTOR_REPUTATION};
+
+    let style = SpellStyle::from_string(&style).ok_or_else(|| {
+        crate::GameError::InvalidInput(format!("Unknown style '{}'. Styles: council, harmony, underground.", style))
+    })?;
+
+    let location = world.current_location()
+        .ok_or_else(|| crate::GameError::ContentNotFound("Current location not found".to_string()))?;
+    let (npc_id, npc_name, faction) = match dialogue_system.find_npc(&mentor, &location.id) {
+        Some(npc) => (npc.id.clone(), npc.name.clone(), npc.faction_affiliation),
+        None => return Ok(format!("You don't see {} here.", mentor)),
+    };
+    if let Some(message) = unavailability(player, dialogue_system, &npc_id, world, quest_system) {
+        return Ok(message);
+    }
+
+    if faction != Some(style.faction()) {
+        return Ok(format!(
+            "{} can't teach the {} style. Only mentors of the {} pass it on.",
+            npc_name, style.name(), style.faction().display_name()
+        ));
+    }
+    if player.styles.knows(style) {
+        return Ok(format!("You already know the {} style.", style.name()));
+    }
+    let standing = player.faction_reputation(style.faction());
+    if standing < MIN_MENTOR_REPUTATION {
+        return Ok(format!(
+            "{} won't share the {}'s techniques with an outsider. (Standing {}, needs {})",
+            npc_name, style.faction().short_name(), standing, MIN_MENTOR_REPUTATION
+        ));
+    }
+
+    world.advance_time(LESSON_MINUTES);
+    player.playtime_minutes += LESSON_MINUTES;
+    player.styles.learn(style);
+
+    Ok(format!(
+        "{} spends an hour drilling you in the {} style.\n{}\n{}\n\nStyle in use: {}.",
+        npc_name, style.name(), style.description(), style.explain(),
+        player.styles.active.map_or("plain", |active| active.name())
+    ))
+}
+
+/// Handle showing or switching casting styles
+fn handle_style(style: Option<String>, player: &mut Player) -> GameResult<String> {
+    use crate::systems::magic::styles::SpellStyle;
+
+    let Some(style) = style else {
+        return Ok(player.styles.report());
+    };
+    if matches!(style.as_str(), "plain" | "none" | "off") {
+        player.styles.active = None;
+        return Ok("You set aside faction techniques and cast plainly.".to_string());
+    }
+
+    let style = SpellStyle::from_string(&style).ok_or_else(|| {
+        crate::GameError::InvalidInput(format!("Unknown style '{}'. Styles: council, harmony, underground.", style))
+    })?;
+    if !player.styles.knows(style) {
+        return Ok(format!(
+            "You haven't learned the {} style. A mentor of the {} could teach you.",
+            style.name(), style.faction().display_name()
+        ));
+    }
+    player.styles.active = Some(style);
+    Ok(format!("You now cast in the {} style.\n{}", style.name(), style.explain()))
+}
+
+/// Handle meditate command
+fn handle_meditate(player: &mut Player, world: &mut WorldState) -> GameResult<String> {
+    let meditation_time = 60; // 1 hour
+    let fatigue_reduction = 15;
+
+    player.recover_energy(0, fatigue_reduction);
+    world.advance_time(meditation_time);
+    player.playtime_minutes += meditation_time;
+
+    Ok(format!(
+        "You enter a meditative state, focusing your mind and clearing mental fog.\n\
+         Fatigue reduced by {}. Current fatigue: {}/100",
+        fatigue_reduction, player.mental_state.fatigue
+    ))
+}
+
+/// Handle study command with enhanced knowledge system
+fn handle_study(
+    theory: String,
+    duration: Option<i32>,
+    player: &mut Player,
+    _database: &DatabaseManager,
+    knowledge_system: &mut KnowledgeSystem,
+    world: &mut WorldState
+) -> GameResult<String> {
+    use crate::systems::knowledge::{MAX_STUDY_MINUTES, MIN_STUDY_MINUTES, STUDY_SESSION_MINUTES};
+    let study_time = duration.unwrap_or(STUDY_SESSION_MINUTES);
+    if !(MIN_STUDY_MINUTES..=MAX_STUDY_MINUTES).contains(&study_time) {
+        return Err(crate::GameError::InvalidInput(format!(
+            "A study session runs from {} minutes to {} hours.", MIN_STUDY_MINUTES, MAX_STUDY_MINUTES / 60
+        )).into());
+    }
+
+    // Players name a theory as they read it, e.g. "harmonic fundamentals"
+    let Some(found) = knowledge_system.find_theory(&theory) else {
+        return Err(crate::GameError::ContentNotFound(format!(
+            "There is no theory called '{}'. Use 'actions' to see the theories you can study.", theory
+        )).into());
+    };
+    let (theory, theory_name) = (found.id.clone(), found.name.clone());
+
+    // Check if player can access this theory
+    let accessible_theories = knowledge_system.get_accessible_theories(player)?;
+    if !accessible_theories.iter().any(|t| t.id == theory) {
+        return Ok(format!(
+            "You aren't ready to study {} yet; it builds on theories you haven't learned.", theory_name
+        ));
+    }
+
+    // Check if player can use study method
+    if !player.can_use_learning_method(&theory, &LearningMethod::Study) {
+        return Ok("You cannot use the study method for this theory right now.".to_string());
+    }
+
+    // Start learning session
+    player.start_learning_session(theory.clone(), LearningMethod::Study)?;
+
+    // Attempt learning through the knowledge system
+    match knowledge_system.attempt_learning(&theory, LearningMethod::Study, study_time, player, world) {
+        Ok(activity) => {
+            player.playtime_minutes += study_time;
+            world.advance_time(study_time);
+
+            let mut response = format!(
+                "You spend {} minutes studying {}.\n\n",
+                study_time, theory_name
+            );
+
+            response.push_str(&format!(
+                "Session Results:\n\
+                 - Understanding gained: {:.1}%\n\
+                 - Experience gained: {} XP\n\
+                 - Success rate: {:.0}%\n",
+                activity.understanding_gained * 100.0,
+                activity.experience_gained,
+                activity.success_rate * 100.0
+            ));
+
+            let current_understanding = player.theory_understanding(&theory);
+            response.push_str(&format!(
+                "\nCurrent understanding: {:.0}%",
+                current_understanding * 100.0
+            ));
 
             // Add mastery message if reached 100%
             if current_understanding >= 1.0 {
                 response.push_str(&format!(
                     "\n\nCongratulations! You have mastered {}!",
-                    theory
+                    theory_name
                 ));
             }
 
@@ -803,8 +1925,6 @@ fn handle_research(
     // Attempt research through the knowledge system
     match knowledge_system.attempt_learning(&topic, LearningMethod::Research, research_time, player, world) {
         Ok(activity) => {
-            // Update player progress
-            player.update_theory_progress(&activity)?;
             player.playtime_minutes += research_time;
 
             let mut response = format!(
@@ -846,40 +1966,206 @@ fn handle_research(
     }
 }
 
+/// Handle researching a topic in the Crystalline Archives
+fn handle_archive_research(
+    topic: String,
+    player: &mut Player,
+    world: &mut WorldState,
+    database: &DatabaseManager,
+) -> GameResult<String> {
+    use crate::core::player::{Item, ItemType};
+    use crate::systems::knowledge::LearningActivity;
+    use rand::Rng;
+
+    let archive_time = 90;
+    // Each reading of a text teaches half what the last one did, up to this many
+    let archive_max_readings = 3;
+
+    if world.current_location != "crystalline_archives" {
+        return Ok("You need to be in the Crystalline Archives to search its catalog.".to_string());
+    }
+
+    let texts = database.search_archive_texts(&topic)?;
+    if texts.is_empty() {
+        return Ok(format!("The catalog crystals stay dark. Nothing in the archives concerns '{}'.", topic));
+    }
+
+    player.use_mental_energy(5, 5)?;
+    world.advance_time(archive_time);
+    player.playtime_minutes += archive_time;
+
+    let mut response = format!(
+        "You spend {} minutes attuning to the catalog crystals, searching for '{}'.\n\n",
+        archive_time, topic
+    );
+
+    let mut rng = world.run.rng();
+    let mut found_any = false;
+
+    for text in texts.iter().take(3) {
+        // Roll d100 plus half of mental acuity against the text's difficulty
+        let roll = rng.gen_range(1..=100) + player.attributes.mental_acuity / 2;
+        let target = 40 + text.difficulty * 10;
+
+        if roll < target {
+            response.push_str(&format!("• {} - the resonance patterns slip away from you.\n", text.title));
+            continue;
+        }
+
+        found_any = true;
+        response.push_str(&format!("• {} - you grasp its contents.\n", text.title));
+        for message in crate::systems::secrets::read(world, &text.id) {
+            response.push_str(&format!("  {}\n", message));
+        }
+
+        if let Some(theory_id) = &text.theory_id {
+            let readings = world.archive_readings.entry(text.id.clone()).or_default();
+            if *readings >= archive_max_readings {
+                response.push_str("  You know it by heart; it has nothing more to teach you.\n");
+            } else {
+                let share = 0.5_f32.powi(*readings as i32);
+                *readings += 1;
+                let activity = LearningActivity {
+                    theory_id: theory_id.clone(),
+                    method: LearningMethod::Study,
+                    duration: archive_time,
+                    success_rate: (roll as f32 / target as f32).min(1.0),
+                    experience_gained: (10.0 * text.difficulty as f32 * share).round() as i32,
+                    understanding_gained: 0.03 * text.difficulty as f32 * share,
+                    resources_used: std::collections::HashMap::new(),
+                    side_effects: vec![format!("Read '{}' in the archives", text.title)],
+                };
+                player.update_theory_progress(&activity)?;
+                response.push_str(&format!("  {} understanding: {:.0}%\n",
+                    theory_id, player.theory_understanding(theory_id) * 100.0));
+            }
+        }
+
+        if !player.inventory.items.iter().any(|item| item.name == text.title) {
+            player.inventory.items.push(Item {
+                name: text.title.clone(),
+                description: text.content.clone(),
+                item_type: ItemType::Note(text.content.clone()),
+            });
+            response.push_str("  You transcribe a copy for your notes.\n");
+        }
+    }
+
+    if !found_any {
+        response.push_str("\nThe texts resist your attempts at resonance matching. Perhaps a sharper mind would fare better.");
+    }
+
+    Ok(response)
+}
+
+/// Handle starting, reviewing or abandoning a theory assessment
+fn handle_assess(theory: Option<String>, player: &mut Player) -> GameResult<String> {
+    use crate::systems::assessments::AssessmentSystem;
+
+    let assessments = AssessmentSystem::new();
+
+    match theory.as_deref() {
+        Some("abandon") | Some("quit") => {
+            if player.knowledge.active_assessment.take().is_some() {
+                Ok("You set the assessment aside. You may retake it later.".to_string())
+            } else {
+                Ok("You are not taking an assessment.".to_string())
+            }
+        }
+        Some(theory_id) => {
+            if let Some(prompt) = assessments.current_prompt(player) {
+                return Ok(format!("Finish your current assessment first (or 'assess abandon').\n\n{}", prompt));
+            }
+            assessments.start(player, theory_id)
+        }
+        None => {
+            if let Some(prompt) = assessments.current_prompt(player) {
+                return Ok(prompt);
+            }
+
+            let available = assessments.available_assessments(player);
+            if available.is_empty() {
+                return Ok("No assessments are available. Reach 30% understanding in a theory to qualify.".to_string());
+            }
+
+            let mut response = String::from("=== AVAILABLE ASSESSMENTS ===\n\n");
+            for assessment in available {
+                response.push_str(&format!("• {} ({} steps) - assess {}\n",
+                    assessment.title, assessment.steps.len(), assessment.theory_id));
+            }
+            Ok(response)
+        }
+    }
+}
+
+/// Handle answering the current assessment step
+fn handle_assessment_answer(answer: String, player: &mut Player, world: &mut WorldState) -> GameResult<String> {
+    use crate::systems::assessments::AssessmentSystem;
+
+    let (_, message) = AssessmentSystem::new().answer(player, &answer)?;
+
+    world.advance_time(5);
+    player.playtime_minutes += 5;
+
+    Ok(message)
+}
+
 /// Handle take command
-fn handle_take(item_name: String, player: &mut Player, world: &mut WorldState) -> GameResult<String> {
+fn handle_take(item_name: String, player: &mut Player, world: &mut WorldState, database: &DatabaseManager) -> GameResult<String> {
     // Ensure player has enhanced item system
     player.ensure_enhanced_item_system();
 
+    // Things the player dropped or hid here keep all their properties
+    let location_id = world.current_location.clone();
+    if let Some(entry) = world.ground.take(&location_id, &item_name) {
+        let name = entry.item.properties.name.clone();
+        let from_cache = entry.concealment.is_some();
+        let glance = |player: &mut Player| crate::systems::fast_analysis::identify(player, &entry.item.id)
+            .map(|message| format!("\n{}", message))
+            .unwrap_or_default();
+        return match player.add_enhanced_item(entry.item.clone()) {
+            Ok(()) if from_cache => Ok(format!("You retrieve the {} from your cache.{}", name, glance(player))),
+            Ok(()) => Ok(format!("You take the {}.{}", name, glance(player))),
+            Err(e) => {
+                world.ground.by_location.entry(location_id).or_default().push(entry);
+                Err(e)
+            }
+        };
+    }
+
     // Get current location
     let location = world.current_location_mut()
         .ok_or_else(|| crate::GameError::InvalidCommand("You are not in a valid location".to_string()))?;
 
     // Search for item in location's items list (case-insensitive)
+    let name_of = |item: &String| crate::systems::placed_items::display_name(item).to_lowercase();
     let item_index = location.items.iter()
-        .position(|item| item.to_lowercase().contains(&item_name.to_lowercase()))
+        .position(|item| name_of(item) == item_name.to_lowercase())
+        .or_else(|| location.items.iter().position(|item| name_of(item).contains(&item_name.to_lowercase())))
         .ok_or_else(|| crate::GameError::InvalidInput(
             format!("There is no '{}' here to take", item_name)
         ))?;
 
     let item_id = location.items.remove(item_index);
 
-    // Create a basic item for the inventory
-    // In a full implementation, this would load from database or item definitions
-    let item = crate::systems::items::core::Item {
-        id: item_id.clone(),
-        properties: crate::systems::items::core::ItemProperties {
-            name: item_id.clone(),
-            description: format!("A {}", item_id),
-            weight: 1.0,
-            value: 10,
-            durability: 100,
-            max_durability: 100,
-            rarity: crate::systems::items::core::ItemRarity::Common,
-            custom_properties: std::collections::HashMap::new(),
+    // Stocked items come from their database definition
+    let item = match database.load_item_definition(&item_id) {
+        Ok(Some(definition)) => definition.instantiate(),
+        _ => crate::systems::items::core::Item {
+            id: item_id.clone(),
+            properties: crate::systems::items::core::ItemProperties {
+                name: item_id.clone(),
+                description: format!("A {}", item_id),
+                weight: 1.0,
+                value: 10,
+                durability: 100,
+                max_durability: 100,
+                rarity: crate::systems::items::core::ItemRarity::Common,
+                custom_properties: std::collections::HashMap::new(),
+            },
+            item_type: crate::systems::items::core::ItemType::Mundane,
+            magical_properties: None,
         },
-        item_type: crate::systems::items::core::ItemType::Mundane,
-        magical_properties: None,
     };
 
     // Try to add to inventory
@@ -889,12 +2175,14 @@ fn handle_take(item_name: String, player: &mut Player, world: &mut WorldState) -
     let item_system = player.inventory.enhanced_items.as_mut()
         .ok_or_else(|| crate::GameError::InvalidCommand("Item system not available".to_string()))?;
 
-    // Validate addition
-    item_system.inventory_manager.validate_addition(&item)?;
-
-    // Add to inventory manager
+    // Add to inventory manager, which validates the addition
     match item_system.inventory_manager.add_item(item.clone()) {
-        Ok(_) => {
+        Ok(()) => {
+            // Respawning items come back in time; one-time items are gone for good
+            if let Ok(Some(placement)) = database.load_item_placement(&world.current_location, &item_id) {
+                world.placed_items.taken(&placement, world.game_time_minutes);
+            }
+
             // Update player's legacy inventory for backward compatibility
             let legacy_item = crate::core::player::Item {
                 name: item.properties.name.clone(),
@@ -902,161 +2190,810 @@ fn handle_take(item_name: String, player: &mut Player, world: &mut WorldState) -
                 item_type: crate::core::player::ItemType::Mundane,
             };
             player.inventory.items.push(legacy_item);
-            Ok(format!("You take the {}.", item_name))
+            let mut response = format!("You take the {}.", item_name);
+            if let Some(message) = crate::systems::fast_analysis::identify(player, &item.id) {
+                response.push_str(&format!("\n{}", message));
+            }
+            Ok(response)
         }
         Err(e) => {
             // If adding fails, put the item back in the location
             if let Some(loc) = world.current_location_mut() {
                 loc.items.push(item_id);
             }
-            Err(e.into())
+            Err(e)
         }
     }
 }
 
-/// Handle drop command
-fn handle_drop(item_name: String, player: &mut Player, world: &mut WorldState) -> GameResult<String> {
+/// Take an unequipped item out of the inventory so it can be left here
+fn take_from_inventory(item_name: &str, verb: &str, player: &mut Player) -> GameResult<crate::systems::items::core::Item> {
     // Ensure player has enhanced item system
     player.ensure_enhanced_item_system();
 
-    // Find item in inventory (case-insensitive search by name)
     let item_system = player.inventory.enhanced_items.as_ref()
         .ok_or_else(|| crate::GameError::InvalidCommand("Item system not available".to_string()))?;
 
-    // Search for item by name
-    let item_id = item_system.inventory_manager.items.iter()
-        .find(|(_, item)| item.properties.name.to_lowercase().contains(&item_name.to_lowercase()))
+    // Search for item by name (case-insensitive)
+    let items = &item_system.inventory_manager.items;
+    let item_id = items.iter()
+        .find(|(_, item)| item.properties.name.to_lowercase() == item_name.to_lowercase())
+        .or_else(|| items.iter().find(|(_, item)| item.properties.name.to_lowercase().contains(&item_name.to_lowercase())))
         .map(|(id, _)| id.clone())
         .ok_or_else(|| crate::GameError::InvalidInput(
-            format!("You don't have a '{}' to drop", item_name)
+            format!("You don't have a '{}' to {}", item_name, verb)
         ))?;
 
     // Check if item is equipped
-    if let Some(equipment_manager) = player.inventory.enhanced_items.as_ref().map(|sys| &sys.equipment_manager) {
-        let equipped_items = equipment_manager.get_equipped_items();
-        if equipped_items.contains(&&item_id) {
-            return Err(crate::GameError::InvalidCommand(
-                format!("You must unequip the {} before dropping it", item_name)
-            ).into());
-        }
+    if item_system.equipment_manager.get_equipped_items().contains(&&item_id) {
+        return Err(crate::GameError::InvalidCommand(
+            format!("You must unequip the {} before {} it", item_name, match verb { "drop" => "dropping", "give" => "giving", "store" => "storing", _ => "hiding" })
+        ).into());
     }
 
-    // Get mutable reference to item system
-    let item_system = player.inventory.enhanced_items.as_mut()
-        .ok_or_else(|| crate::GameError::InvalidCommand("Item system not available".to_string()))?;
-
-    // Remove from inventory manager
-    match item_system.inventory_manager.remove_item(&item_id) {
-        Ok(Some(item)) => {
-            // Remove from player's legacy inventory
-            if let Some(pos) = player.inventory.items.iter().position(|i| i.name == item.properties.name) {
-                player.inventory.items.remove(pos);
-            }
+    player.remove_enhanced_item(&item_id)?
+        .ok_or_else(|| crate::GameError::InvalidInput("Item not found".to_string()).into())
+}
 
-            // Add to current location
-            if let Some(location) = world.current_location_mut() {
-                location.items.push(item.id.clone());
-                Ok(format!("You drop the {}.", item.properties.name))
-            } else {
-                // If we can't add to location, put it back in inventory
-                let _ = item_system.inventory_manager.add_item(item.clone());
-                let legacy_item = crate::core::player::Item {
-                    name: item.properties.name.clone(),
-                    description: item.properties.description.clone(),
-                    item_type: crate::core::player::ItemType::Mundane,
-                };
-                player.inventory.items.push(legacy_item);
-                Err(crate::GameError::InvalidCommand("Cannot drop item here".to_string()).into())
-            }
-        }
-        Ok(None) => Err(crate::GameError::InvalidInput("Item not found".to_string()).into()),
-        Err(e) => Err(e.into()),
+/// Handle drop command
+fn handle_drop(item_name: String, player: &mut Player, world: &mut WorldState) -> GameResult<String> {
+    if world.current_location().is_none() {
+        return Err(crate::GameError::InvalidCommand("Cannot drop item here".to_string()).into());
     }
-}
 
-/// Handle unequip command
-fn handle_unequip(slot_name: Option<String>, player: &mut Player) -> GameResult<String> {
-    // Ensure player has enhanced item system
-    player.ensure_enhanced_item_system();
+    let item = take_from_inventory(&item_name, "drop", player)?;
+    let message = format!("You drop the {}.", item.properties.name);
+    let location_id = world.current_location.clone();
+    world.ground.drop_item(&location_id, item, world.game_time_minutes);
+    Ok(message)
+}
 
-    let item_system = player.inventory.enhanced_items.as_mut()
-        .ok_or_else(|| crate::GameError::InvalidCommand("Item system not available".to_string()))?;
+/// Handle upgrading a fixture in the player's laboratory
+fn handle_upgrade_fixture(fixture: String, player: &mut Player, world: &WorldState) -> GameResult<String> {
+    use crate::systems::laboratory::Fixture;
 
-    // If no slot specified, show help
-    let slot_str = slot_name.ok_or_else(|| crate::GameError::InvalidInput(
-        "Please specify a slot to unequip (e.g., 'unequip head', 'unequip ring1')".to_string()
-    ))?;
+    let fixture = Fixture::from_name(&fixture).ok_or_else(|| crate::GameError::InvalidInput(format!(
+        "Your laboratory has no '{}'. You can upgrade its storage, bench, garden or library.", fixture
+    )))?;
+    crate::systems::laboratory::upgrade(fixture, player, world)
+}
 
-    // Parse slot name
-    let slot = parse_equipment_slot(&slot_str)?;
+/// Handle putting an item into laboratory storage
+fn handle_store_item(item_name: String, player: &mut Player, world: &WorldState) -> GameResult<String> {
+    use crate::systems::laboratory::LAB_LOCATION;
 
-    // Check if slot is occupied
-    if !item_system.equipment_manager.is_slot_occupied(slot.clone()) {
-        return Ok(format!("Nothing is equipped in the {} slot.", slot_display_name(&slot)));
+    if !player.laboratory.owned || world.current_location != LAB_LOCATION {
+        return Err(crate::GameError::InvalidCommand("You can only store things in your own laboratory.".to_string()).into());
     }
-
-    // Check if inventory has space
-    let current_slots = item_system.inventory_manager.current_slots();
-    let max_slots = item_system.inventory_manager.constraints.max_slots;
-    if current_slots >= max_slots {
-        return Err(crate::GameError::InvalidCommand(
-            "Your inventory is full. You need to drop something before unequipping.".to_string()
+    if !player.laboratory.has_room() {
+        return Err(crate::GameError::InsufficientResources(
+            "Your storage is full. Upgrading it would make more room.".to_string()
         ).into());
     }
+    let item = take_from_inventory(&item_name, "store", player)?;
+    crate::systems::laboratory::store(item, player)
+}
 
-    // Unequip the item
-    match item_system.equipment_manager.unequip_item(slot.clone()) {
-        Ok(Some((item_id, _equipment))) => {
-            // Get the item from inventory to add back
-            if let Some(item) = item_system.inventory_manager.get_item(&item_id) {
-                let item_name = item.properties.name.clone();
+/// Handle taking an item out of laboratory storage
+fn handle_retrieve_item(item_name: String, player: &mut Player, world: &WorldState) -> GameResult<String> {
+    use crate::systems::laboratory::LAB_LOCATION;
 
-                // Item is already in inventory_manager, just need to confirm
-                Ok(format!("You unequip the {} from your {} slot.", item_name, slot_display_name(&slot)))
-            } else {
-                // This shouldn't happen, but handle gracefully
-                Ok(format!("You unequip an item from your {} slot.", slot_display_name(&slot)))
-            }
-        }
-        Ok(None) => {
-            Ok(format!("Nothing is equipped in the {} slot.", slot_display_name(&slot)))
-        }
-        Err(e) => Err(e),
+    if !player.laboratory.owned || world.current_location != LAB_LOCATION {
+        return Err(crate::GameError::InvalidCommand("Your stored things are back in your laboratory.".to_string()).into());
     }
+    let item = crate::systems::laboratory::retrieve(&item_name, player)
+        .ok_or_else(|| crate::GameError::InvalidInput(format!("There's no '{}' in your storage.", item_name)))?;
+    let name = item.properties.name.clone();
+    player.add_enhanced_item(item)?;
+    Ok(format!("You take the {} out of storage.", name))
 }
 
-/// Parse equipment slot from string
-fn parse_equipment_slot(slot_str: &str) -> GameResult<crate::systems::items::equipment::EquipmentSlot> {
-    use crate::systems::items::equipment::EquipmentSlot;
+/// Handle gathering what the laboratory's garden plot has grown
+fn handle_harvest(player: &mut Player, world: &WorldState, database: &DatabaseManager) -> GameResult<String> {
+    use crate::systems::laboratory::{Fixture, LAB_LOCATION};
 
-    match slot_str.to_lowercase().as_str() {
-        "head" => Ok(EquipmentSlot::Head),
-        "neck" => Ok(EquipmentSlot::Neck),
-        "chest" => Ok(EquipmentSlot::Chest),
-        "hands" | "hand" | "gloves" => Ok(EquipmentSlot::Hands),
-        "ring1" | "ring 1" | "leftring" | "left ring" => Ok(EquipmentSlot::Ring1),
-        "ring2" | "ring 2" | "rightring" | "right ring" => Ok(EquipmentSlot::Ring2),
-        "waist" | "belt" => Ok(EquipmentSlot::Waist),
-        "legs" | "leg" | "pants" => Ok(EquipmentSlot::Legs),
-        "feet" | "foot" | "boots" | "shoes" => Ok(EquipmentSlot::Feet),
-        "mainhand" | "main hand" | "weapon" => Ok(EquipmentSlot::MainHand),
-        "offhand" | "off hand" | "shield" => Ok(EquipmentSlot::OffHand),
-        "back" | "cloak" | "cape" => Ok(EquipmentSlot::Back),
-        _ => Err(crate::GameError::InvalidInput(
-            format!("Unknown equipment slot '{}'. Valid slots: head, neck, chest, hands, ring1, ring2, waist, legs, feet, mainhand, offhand, back", slot_str)
-        ).into()),
+    if !player.laboratory.owned || world.current_location != LAB_LOCATION {
+        return Err(crate::GameError::InvalidCommand("There's nothing of yours to harvest here.".to_string()).into());
+    }
+    if player.laboratory.level(Fixture::GardenPlot) == 0 {
+        return Ok("Your laboratory has no garden plot yet ('upgrade garden').".to_string());
     }
-}
 
-/// Get display name for equipment slot
-fn slot_display_name(slot: &crate::systems::items::equipment::EquipmentSlot) -> &str {
-    use crate::systems::items::equipment::EquipmentSlot;
+    let harvest = player.laboratory.harvest(world.game_time_minutes);
+    if harvest.is_empty() {
+        return Ok("Nothing in the garden plot is ready yet. Give it a day.".to_string());
+    }
+    let mut names = Vec::new();
+    for id in harvest {
+        if let Some(definition) = database.load_item_definition(id)? {
+            let item = definition.instantiate();
+            names.push(item.properties.name.clone());
+            player.add_enhanced_item(item)?;
+        }
+    }
+    Ok(format!("You harvest the garden plot: {}.", names.join(", ")))
+}
 
-    match slot {
-        EquipmentSlot::Head => "head",
-        EquipmentSlot::Neck => "neck",
-        EquipmentSlot::Chest => "chest",
-        EquipmentSlot::Hands => "hands",
+/// Handle giving an item to an NPC as a gift
+fn handle_give(
+    item_name: String,
+    target: String,
+    player: &mut Player,
+    world: &WorldState,
+    dialogue_system: &mut DialogueSystem,
+    quest_system: &QuestSystem,
+) -> GameResult<String> {
+    let npc_id = match dialogue_system.find_npc(&target, &world.current_location) {
+        Some(npc) => npc.id.clone(),
+        None => return Ok(format!("You don't see {} here to give anything to.", target)),
+    };
+    if let Some(message) = unavailability(player, dialogue_system, &npc_id, world, quest_system) {
+        return Ok(message);
+    }
+
+    let item = take_from_inventory(&item_name, "give", player)?;
+    dialogue_system.give_gift(&npc_id, &item.properties.name, item.properties.value, world.game_time_minutes)
+}
+
+/// Handle hiding an item in a cache at the current location
+fn handle_hide(item_name: String, player: &mut Player, world: &mut WorldState) -> GameResult<String> {
+    use crate::systems::ground_items::{concealment, HIDE_MINUTES, WELL_CONCEALED};
+
+    let traffic = world.current_location()
+        .map(|location| location.npcs.len())
+        .ok_or_else(|| crate::GameError::InvalidCommand("There is nowhere to hide anything here".to_string()))?;
+
+    let item = take_from_inventory(&item_name, "hide", player)?;
+    let mut rng = world.run.rng();
+    let concealment = concealment(player, traffic, &mut rng);
+    let message = format!(
+        "You spend a while stashing the {} out of sight. {}",
+        item.properties.name,
+        if concealment >= WELL_CONCEALED {
+            "Nobody will stumble on this hiding place."
+        } else {
+            "The hiding place isn't perfect; a thorough scavenger might find it."
+        }
+    );
+
+    let location_id = world.current_location.clone();
+    world.ground.hide_item(&location_id, item, concealment, world.game_time_minutes);
+    world.advance_time(HIDE_MINUTES);
+    player.playtime_minutes += HIDE_MINUTES;
+    Ok(message)
+}
+
+/// Handle listing the player's hidden caches
+fn handle_caches(world: &WorldState) -> GameResult<String> {
+    Ok(world.ground.cache_report(|id| {
+        world.locations.get(id).map_or_else(|| id.to_string(), |location| location.name.clone())
+    }))
+}
+
+/// Handle finding someone with the Resonance Observatory's detection arrays
+fn handle_locate(
+    target: String,
+    player: &mut Player,
+    world: &mut WorldState,
+    dialogue_system: &mut DialogueSystem,
+    quest_system: &QuestSystem,
+) -> GameResult<String> {
+    use crate::systems::dialogue::{LOCATE_LOCATION, LOCATE_MINUTES, LOCATE_THEORY, LOCATE_UNDERSTANDING};
+
+    if world.current_location != LOCATE_LOCATION {
+        return Err(crate::GameError::InvalidCommand(
+            "Only the Resonance Observatory's detection arrays can pick someone out across the city.".to_string()
+        ).into());
+    }
+    if player.theory_understanding(LOCATE_THEORY) < LOCATE_UNDERSTANDING {
+        return Err(crate::GameError::InvalidCommand(format!(
+            "You don't understand {} well enough to tune the arrays to one person's signature.",
+            LOCATE_THEORY.replace('_', " ")
+        )).into());
+    }
+
+    let response = dialogue_system.locate(&target, world, quest_system)?;
+    world.advance_time(LOCATE_MINUTES);
+    player.playtime_minutes += LOCATE_MINUTES;
+    Ok(response)
+}
+
+/// Handle reading the Unstable Resonance Site's flux, on site or through the observatory's arrays
+fn handle_read_flux(player: &Player, world: &WorldState) -> GameResult<String> {
+    use crate::systems::site_flux::{READING_THEORY, SITE_ID};
+
+    let understanding = player.theory_understanding(READING_THEORY);
+    let reading = world.site_flux.reading(world.game_time_minutes, understanding);
+    match world.current_location.as_str() {
+        SITE_ID => Ok(reading),
+        "resonance_observatory" => Ok(format!("The observatory's arrays trace the distant Unstable Resonance Site.\n{}", reading)),
+        _ => Err(crate::GameError::InvalidCommand(
+            "There is no flux to read here. Visit the Unstable Resonance Site, or watch it from the Resonance Observatory.".to_string()
+        ).into()),
+    }
+}
+
+/// Reasons a quoted price differs from the list price, e.g. ` (+20% demand)`
+fn price_notes(quote: &crate::systems::services::Quote) -> String {
+    if quote.notes.is_empty() { String::new() } else { format!(" ({})", quote.notes.join(", ")) }
+}
+
+/// Health and injury points a healer would restore
+fn healing_needed(player: &Player) -> i32 {
+    let injuries: i32 = player.health.injuries.iter().map(|injury| injury.severity() as i32 * 10).sum();
+    (player.health.max - player.health.current).max(0) + injuries
+}
+
+/// Find an available service provider here by name
+fn find_provider<'a>(
+    npc: &str,
+    world: &WorldState,
+    dialogue_system: &'a DialogueSystem,
+    quest_system: &QuestSystem,
+) -> GameResult<Result<&'a crate::systems::dialogue::NPC, String>> {
+    let Some(found) = dialogue_system.find_npc(npc, &world.current_location) else {
+        return Ok(Err(format!("You don't see {} here.", npc)));
+    };
+    if let Some(message) = dialogue_system.unavailability_message(&found.id, world, quest_system) {
+        return Ok(Err(message));
+    }
+    if crate::systems::services::services_offered(&found.id).is_empty() {
+        return Err(crate::GameError::InvalidCommand(format!("{} doesn't offer any paid services.", found.name)).into());
+    }
+    let (opens, closes) = calendar::service_hours(&found.id);
+    if !calendar::hour_in(world.hour_of_day(), opens, closes) {
+        return Ok(Err(format!("{} isn't doing business at this hour. Come back between {:02}:00 and {:02}:00.", found.name, opens, closes)));
+    }
+    Ok(Ok(found))
+}
+
+/// List the services offered here with their current prices
+fn handle_services(
+    player: &Player,
+    world: &WorldState,
+    dialogue_system: &DialogueSystem,
+    faction_system: &FactionSystem,
+    quest_system: &QuestSystem,
+) -> GameResult<String> {
+    use crate::systems::services::{services_offered, ServiceKind};
+
+    let location = world.current_location()
+        .ok_or_else(|| crate::GameError::ContentNotFound("Current location not found".to_string()))?;
+    let now = world.game_time_minutes;
+    let mut lines = Vec::new();
+    for npc in dialogue_system.npcs_present(&location.id, world, quest_system) {
+        let npc_id = &npc.id;
+        let (opens, closes) = calendar::service_hours(npc_id);
+        if !services_offered(npc_id).is_empty() && !calendar::hour_in(world.hour_of_day(), opens, closes) {
+            lines.push(format!("  • {} - not doing business until {:02}:00", npc.name, opens));
+            continue;
+        }
+        for service in services_offered(npc_id) {
+            let line = match service.kind {
+                ServiceKind::Healing => {
+                    let needed = healing_needed(player);
+                    let quote = world.services.quote(npc_id, npc.faction_affiliation, service.list_price(needed), faction_system, now);
+                    if needed == 0 {
+                        format!("{} - healing (you need none right now){}", npc.name, price_notes(&quote))
+                    } else {
+                        format!("{} - healing: {} silver{}", npc.name, quote.price, price_notes(&quote))
+                    }
+                }
+                ServiceKind::Training => {
+                    let quote = world.services.quote(npc_id, npc.faction_affiliation, service.list_price(0), faction_system, now);
+                    format!("{} - training: {} silver an hour{}", npc.name, quote.price, price_notes(&quote))
+                }
+                ServiceKind::Repair => {
+                    // Priced per point, so quote a hundred points and scale back down
+                    let quote = world.services.quote(npc_id, npc.faction_affiliation, service.list_price(100), faction_system, now);
+                    format!("{} - repair: {:.2} silver per point of wear{}", npc.name, quote.price as f32 / 100.0, price_notes(&quote))
+                }
+                ServiceKind::Appraisal => {
+                    let quote = world.services.quote(npc_id, npc.faction_affiliation, service.list_price(0), faction_system, now);
+                    format!("{} - appraisal: from {} silver an item{}", npc.name, quote.price, price_notes(&quote))
+                }
+            };
+            lines.push(format!("  • {}", line));
+        }
+    }
+
+    if lines.is_empty() {
+        return Ok("No one here offers paid services.".to_string());
+    }
+    Ok(format!(
+        "=== SERVICES ===\n{}\n\nUse 'buy <healing|training> from <person>', 'repair <item> with <person>', 'appraise <item> with <person>', or 'haggle with <person>' first.",
+        lines.join("\n")
+    ))
+}
+
+/// Find an item in the player's pack by name, preferring unidentified ones
+fn find_appraisable(player: &Player, name: &str) -> Option<(String, bool)> {
+    use crate::systems::items::identification::is_identified;
+
+    let name = name.to_lowercase();
+    let items = &player.inventory.enhanced_items.as_ref()?.inventory_manager.items;
+    let mut matches: Vec<(&String, &crate::systems::items::Item)> = items.iter()
+        .filter(|(_, item)| item.properties.name.to_lowercase().contains(&name))
+        .collect();
+    matches.sort_by_key(|(_, item)| is_identified(item));
+    matches.first().map(|(id, item)| ((*id).clone(), is_identified(item)))
+}
+
+/// Identify an item in the player's pack, returning the revelation
+fn identify_enhanced_item(player: &mut Player, item_id: &String, method: crate::systems::items::identification::AppraisalMethod) -> Option<String> {
+    let item = player.inventory.enhanced_items.as_mut()?.inventory_manager.items.get_mut(item_id)?;
+    crate::systems::items::identification::identify(item, method)
+}
+
+/// Read an unidentified item's signature after a successful detection spell
+fn analyze_unidentified(target: &str, player: &mut Player) -> Option<String> {
+    use crate::systems::items::identification::{AppraisalMethod, ANALYSIS_THEORY, ANALYSIS_UNDERSTANDING};
+
+    let (item_id, identified) = find_appraisable(player, target)?;
+    if identified {
+        return None;
+    }
+    if player.theory_understanding(ANALYSIS_THEORY) < ANALYSIS_UNDERSTANDING {
+        return Some(format!(
+            "A signature shimmers around the {}, but you can't make sense of it yet. (Detection Arrays {:.0}% needed)",
+            target, ANALYSIS_UNDERSTANDING * 100.0
+        ));
+    }
+    identify_enhanced_item(player, &item_id, AppraisalMethod::Spell)
+}
+
+/// Appraise an unidentified item with a detection tool or a paid expert,
+/// or list the player's unidentified items when none is named
+fn handle_appraise(
+    item_name: Option<String>,
+    with: Option<String>,
+    player: &mut Player,
+    world: &mut WorldState,
+    dialogue_system: &DialogueSystem,
+    quest_system: &QuestSystem,
+    faction_system: &FactionSystem,
+) -> GameResult<String> {
+    use crate::systems::items::identification::{
+        is_identified, AppraisalMethod, ANALYSIS_THEORY, ANALYSIS_UNDERSTANDING, APPRAISAL_MINUTES, APPRAISAL_TOOL_FUNCTION,
+    };
+    use crate::systems::items::ItemType;
+    use crate::systems::services::{self, ServiceKind};
+
+    player.ensure_enhanced_item_system();
+    let item_system = player.inventory.enhanced_items.as_ref()
+        .ok_or_else(|| crate::GameError::InvalidCommand("Item system not available".to_string()))?;
+
+    let Some(item_name) = item_name else {
+        let mut unknown: Vec<String> = item_system.inventory_manager.items.values()
+            .filter(|item| !is_identified(item))
+            .map(|item| format!("  • {}", item.properties.name))
+            .collect();
+        if unknown.is_empty() {
+            return Ok("You know what everything you carry is.".to_string());
+        }
+        unknown.sort();
+        return Ok(format!(
+            "=== UNIDENTIFIED ===\n{}\n\nAppraise one with 'appraise <item>' using a detection tool, \
+             'appraise <item> with <person>', or 'cast detection on <item>'.",
+            unknown.join("\n")
+        ));
+    };
+
+    let (item_id, identified) = find_appraisable(player, &item_name)
+        .ok_or_else(|| crate::GameError::InvalidInput(format!("You don't have a '{}' to appraise", item_name)))?;
+    let item = item_system.inventory_manager.items[&item_id].clone();
+    if identified {
+        return Ok(format!("You already know what the {} is.", item.properties.name));
+    }
+    if with.is_none() {
+        if let Some(message) = crate::systems::fast_analysis::identify(player, &item_id) {
+            return Ok(message);
+        }
+    }
+    let item_system = player.inventory.enhanced_items.as_ref()
+        .ok_or_else(|| crate::GameError::InvalidCommand("Item system not available".to_string()))?;
+
+    let is_appraisal_tool = |tool: &crate::systems::items::Item| tool.is_usable()
+        && matches!(&tool.item_type, ItemType::Tool { tool_function } if tool_function == APPRAISAL_TOOL_FUNCTION);
+    let tool = item_system.inventory_manager.items.iter()
+        .filter(|(id, tool)| **id != item_id && is_appraisal_tool(tool))
+        .find(|(_, tool)| with.as_ref().is_none_or(|with| tool.properties.name.to_lowercase().contains(with.as_str())))
+        .map(|(id, tool)| (id.clone(), tool.properties.name.clone()));
+
+    let message = match (tool, with) {
+        (Some((tool_id, tool_name)), _) => {
+            let mut message = format!("You study the {} through your {}.\n", item.properties.name, tool_name);
+            message.push_str(&identify_enhanced_item(player, &item_id, AppraisalMethod::Tool).unwrap_or_default());
+            if let Some(wear) = player.wear_enhanced_item(&tool_id, crate::systems::items::WearCause::ToolUse.amount()) {
+                message.push_str(&format!("\n{}", wear));
+            }
+            message
+        }
+        (None, Some(npc)) => {
+            let provider = match find_provider(&npc, world, dialogue_system, quest_system) {
+                Ok(Ok(provider)) => provider,
+                Ok(Err(message)) => return Ok(message),
+                Err(_) => return Ok(format!("Neither your pack nor anyone here named '{}' can appraise the {}.", npc, item.properties.name)),
+            };
+            let service = services::service_offered(&provider.id, ServiceKind::Appraisal)
+                .ok_or_else(|| crate::GameError::InvalidCommand(format!("{} doesn't appraise items.", provider.name)))?;
+            let now = world.game_time_minutes;
+            let quote = world.services.quote(
+                &provider.id, provider.faction_affiliation, service.list_price(item.properties.value), faction_system, now,
+            );
+            if player.inventory.silver < quote.price {
+                return Err(crate::GameError::InsufficientResources(format!(
+                    "{} asks {} silver to appraise the {}. You have {}.",
+                    provider.name, quote.price, item.properties.name, player.inventory.silver
+                )).into());
+            }
+            player.inventory.silver -= quote.price;
+            world.services.record_purchase(&provider.id, now);
+            format!(
+                "You pay {} {} silver{}.\n{}",
+                provider.name, quote.price, price_notes(&quote),
+                identify_enhanced_item(player, &item_id, AppraisalMethod::Service).unwrap_or_default()
+            )
+        }
+        (None, None) => {
+            use crate::systems::fast_analysis::Routine;
+
+            let spell_hint = if player.theory_understanding(ANALYSIS_THEORY) >= ANALYSIS_UNDERSTANDING {
+                format!("'cast detection on {}' to read its signature", item_name)
+            } else {
+                format!("a detection spell, once you understand Detection Arrays to {:.0}%", ANALYSIS_UNDERSTANDING * 100.0)
+            };
+            let mut message = format!(
+                "You can't tell what the {} is by eye. Try a detection tool such as a resonance lens, \
+                 'appraise {} with <person>' for someone with a trained eye, or {}.",
+                item.properties.name, item_name, spell_hint
+            );
+            if Routine::Identification.mastered(player) && !player.fast_analysis {
+                message.push_str("\nYou know Detection Arrays well enough to skip all this: 'fast analysis on'.");
+            }
+            return Ok(message);
+        }
+    };
+
+    world.advance_time(APPRAISAL_MINUTES);
+    player.playtime_minutes += APPRAISAL_MINUTES;
+    Ok(message)
+}
+
+/// Pay an NPC for healing or training
+fn handle_buy_service(
+    service_name: String,
+    npc: String,
+    player: &mut Player,
+    world: &mut WorldState,
+    dialogue_system: &DialogueSystem,
+    quest_system: &QuestSystem,
+    faction_system: &FactionSystem,
+) -> GameResult<String> {
+    use crate::systems::services::{self, ServiceKind};
+
+    let kind = ServiceKind::from_string(&service_name)
+        .ok_or_else(|| crate::GameError::InvalidInput(format!("Unknown service '{}'. Try healing or training.", service_name)))?;
+    let provider = match find_provider(&npc, world, dialogue_system, quest_system)? {
+        Ok(provider) => provider,
+        Err(message) => return Ok(message),
+    };
+    let service = services::service_offered(&provider.id, kind)
+        .ok_or_else(|| crate::GameError::InvalidCommand(format!("{} doesn't offer {}.", provider.name, kind.name())))?;
+
+    let points = match kind {
+        ServiceKind::Healing => healing_needed(player),
+        ServiceKind::Training => 0,
+        ServiceKind::Repair => return Ok(format!("Name what you want mended: 'repair <item> with {}'.", provider.short_name())),
+        ServiceKind::Appraisal => return Ok(format!("Name what you want appraised: 'appraise <item> with {}'.", provider.short_name())),
+    };
+    if kind == ServiceKind::Healing && points == 0 {
+        return Ok(format!("{} looks you over. \"You're in good health. Save your silver.\"", provider.name));
+    }
+
+    let now = world.game_time_minutes;
+    let quote = world.services.quote(&provider.id, provider.faction_affiliation, service.list_price(points), faction_system, now);
+    if player.inventory.silver < quote.price {
+        return Err(crate::GameError::InsufficientResources(format!(
+            "{} asks {} silver for {}. You have {}.", provider.name, quote.price, kind.name(), player.inventory.silver
+        )).into());
+    }
+    player.inventory.silver -= quote.price;
+    world.services.record_purchase(&provider.id, now);
+
+    let (minutes, result) = match (kind, service.trains) {
+        (ServiceKind::Training, Some(attribute)) => (services::TRAINING_MINUTES, services::train(player, attribute)),
+        _ => {
+            let mut treated = Vec::new();
+            while let Some(message) = player.health.treat() {
+                treated.push(message);
+            }
+            let healed = player.health.heal(player.health.max);
+            treated.insert(0, format!("You recover {} health ({}/{}).", healed, player.health.current, player.health.max));
+            (services::HEALING_MINUTES, treated.join(" "))
+        }
+    };
+    world.advance_time(minutes);
+    player.playtime_minutes += minutes;
+
+    Ok(format!("You pay {} {} silver{}.\n{}", provider.name, quote.price, price_notes(&quote), result))
+}
+
+/// Handle calendar command
+fn handle_calendar(world: &WorldState) -> String {
+    use crate::core::world_state::TimeOfDay;
+
+    let now = world.game_time_minutes;
+    let mut lines = vec![
+        format!("It is {}.", world.date().describe()),
+        format!(
+            "The time is {:02}:{:02}, {}.",
+            world.hour_of_day(), now.rem_euclid(60), TimeOfDay::from_hour(world.hour_of_day()).name()
+        ),
+        String::new(),
+        "Faction meetings:".to_string(),
+    ];
+    for meeting in calendar::MEETINGS {
+        let place = world.locations.get(meeting.location).map_or(meeting.location, |location| location.name.as_str());
+        let when = if meeting.in_session(now) {
+            "in session now".to_string()
+        } else {
+            let start = meeting.next_start(now);
+            format!("next on {} at {:02}:00", calendar::Date::at(start).weekday.name(), meeting.start_hour)
+        };
+        lines.push(format!("  • The {} ({}, {}) - {}", meeting.name, meeting.faction.display_name(), place, when));
+    }
+
+    lines.push(String::new());
+    lines.push("World events:".to_string());
+    for def in crate::core::events::WORLD_EVENTS {
+        let place = match (world.events.get(def.id), def.location) {
+            (Some(event), _) if !event.affected_locations.is_empty() => event.affected_locations.iter()
+                .map(|id| world.locations.get(id).map_or(id.as_str(), |location| location.name.as_str()))
+                .collect::<Vec<_>>()
+                .join(", "),
+            (_, Some(location)) => world.locations.get(location).map_or(location, |location| location.name.as_str()).to_string(),
+            (_, None) => "across the city".to_string(),
+        };
+        let when = match (world.events.get(def.id), def.next_start(now)) {
+            (Some(event), _) => {
+                let end = event.ends_at.rem_euclid(calendar::MINUTES_PER_DAY);
+                format!("under way until {:02}:{:02}", end / 60, end % 60)
+            }
+            (None, Some(start)) => format!("next on {} at {:02}:00", calendar::Date::at(start).weekday.name(), start.rem_euclid(calendar::MINUTES_PER_DAY) / 60),
+            // Triggered events only show while they're happening
+            (None, None) => continue,
+        };
+        lines.push(format!("  • The {} ({}) - {}", def.name, place, when));
+    }
+    lines.join("\n")
+}
+
+/// Handle forecast command
+fn handle_forecast(player: &Player, world: &WorldState) -> String {
+    use crate::core::overworld::region_of;
+    use crate::systems::weather::{self, FORECAST_LOCATION, FORECAST_THEORY};
+
+    let now = world.game_time_minutes;
+    if world.current_location == FORECAST_LOCATION {
+        return weather::forecast(now, world.run.seed, player.theory_understanding(FORECAST_THEORY));
+    }
+    let here = format!(
+        "Over {} the weather is {}.",
+        region_of(&world.current_location).name(), world.environment.weather.name()
+    );
+    match weather::sign(world.environment.weather) {
+        Some(sign) => format!("{} {}\nOnly the Observatory's arrays can see what's coming.", here, sign),
+        None => format!("{}\nOnly the Observatory's arrays can see what's coming.", here),
+    }
+}
+
+/// Bargain with a service provider over their next price
+fn handle_haggle(
+    npc: String,
+    player: &Player,
+    world: &mut WorldState,
+    dialogue_system: &DialogueSystem,
+    quest_system: &QuestSystem,
+    faction_system: &FactionSystem,
+) -> GameResult<String> {
+    let provider = match find_provider(&npc, world, dialogue_system, quest_system)? {
+        Ok(provider) => provider,
+        Err(message) => return Ok(message),
+    };
+    let disposition = dialogue_system.calculate_disposition(provider, player, faction_system);
+    let chance = crate::systems::services::haggle_chance(player, disposition);
+
+    let mut rng = world.run.rng();
+    match world.services.haggle(&provider.id, chance, world.game_time_minutes, &mut rng) {
+        Ok(true) => Ok(format!("{} sighs and agrees to knock something off your next service.", provider.name)),
+        Ok(false) => Ok(format!("{} bristles at your haggling. Their next price to you will be higher.", provider.name)),
+        Err(refusal) => Ok(format!("{}: {}", provider.name, refusal)),
+    }
+}
+
+/// Handle repairing an item, or listing worn items when none is named
+///
+/// Craftspeople restore an item fully for silver. Otherwise the player mends
+/// it themselves, properly with a repair kit or roughly without one.
+fn handle_repair(
+    item_name: Option<String>,
+    npc: Option<String>,
+    player: &mut Player,
+    world: &mut WorldState,
+    dialogue_system: &DialogueSystem,
+    quest_system: &QuestSystem,
+    faction_system: &FactionSystem,
+) -> GameResult<String> {
+    use crate::systems::items::durability::{condition, repair_service, REPAIR_KIT_FUNCTION, REPAIR_MINUTES};
+    use crate::systems::items::{ItemType, RepairMethod};
+
+    player.ensure_enhanced_item_system();
+    let item_system = player.inventory.enhanced_items.as_ref()
+        .ok_or_else(|| crate::GameError::InvalidCommand("Item system not available".to_string()))?;
+
+    let Some(item_name) = item_name else {
+        let mut worn: Vec<String> = item_system.inventory_manager.items.values()
+            .filter(|item| item.properties.durability < item.properties.max_durability)
+            .map(|item| format!("  • {} - {} ({}/{})",
+                item.properties.name, condition(item), item.properties.durability, item.properties.max_durability))
+            .collect();
+        if worn.is_empty() {
+            return Ok("All your gear is in good repair.".to_string());
+        }
+        worn.sort();
+        return Ok(format!(
+            "=== WORN GEAR ===\n{}\n\nMend an item with 'repair <item>', or pay a craftsperson with 'repair <item> with <person>'.",
+            worn.join("\n")
+        ));
+    };
+
+    let (item_id, item) = item_system.inventory_manager.items.iter()
+        .find(|(_, item)| item.properties.name.to_lowercase().contains(&item_name.to_lowercase()))
+        .map(|(id, item)| (id.clone(), item.clone()))
+        .ok_or_else(|| crate::GameError::InvalidInput(format!("You don't have a '{}' to repair", item_name)))?;
+    let name = item.properties.name.clone();
+    if item.properties.durability >= item.properties.max_durability {
+        return Ok(format!("The {} is in good repair.", name));
+    }
+
+    let message = if let Some(npc) = npc {
+        let location = world.current_location()
+            .ok_or_else(|| crate::GameError::ContentNotFound("Current location not found".to_string()))?;
+        let (npc_id, npc_name, faction) = match dialogue_system.find_npc(&npc, &location.id) {
+            Some(found) => (found.id.clone(), found.name.clone(), found.faction_affiliation),
+            None => return Ok(format!("You don't see {} here.", npc)),
+        };
+        if let Some(message) = unavailability(player, dialogue_system, &npc_id, world, quest_system) {
+            return Ok(message);
+        }
+        let service = repair_service(&npc_id)
+            .ok_or_else(|| crate::GameError::InvalidCommand(format!("{} doesn't take on repairs.", npc_name)))?;
+        let quote = world.services.quote(&npc_id, faction, service.quote(&item), faction_system, world.game_time_minutes);
+        let fee = quote.price;
+        if player.inventory.silver < fee {
+            return Err(crate::GameError::InsufficientResources(format!(
+                "{} asks {} silver to restore the {}. You have {}.", npc_name, fee, name, player.inventory.silver
+            )).into());
+        }
+        player.inventory.silver -= fee;
+        player.repair_enhanced_item(&item_id, RepairMethod::Service)?;
+        world.services.record_purchase(&npc_id, world.game_time_minutes);
+        format!("{} restores your {} to full condition for {} silver{}.", npc_name, name, fee, price_notes(&quote))
+    } else {
+        let kit = item_system.inventory_manager.items.iter()
+            .find(|(id, kit)| **id != item_id && kit.is_usable()
+                && matches!(&kit.item_type, ItemType::Tool { tool_function } if tool_function == REPAIR_KIT_FUNCTION))
+            .map(|(id, kit)| (id.clone(), kit.properties.name.clone()));
+
+        match kit {
+            Some((kit_id, kit_name)) => {
+                let restored = player.repair_enhanced_item(&item_id, RepairMethod::Kit)?;
+                let mut message = format!("You work on the {} with your {}, restoring {} durability.", name, kit_name, restored);
+                if let Some(wear) = player.wear_enhanced_item(&kit_id, crate::systems::items::WearCause::ToolUse.amount()) {
+                    message.push_str(&format!("\n{}", wear));
+                }
+                message
+            }
+            None => {
+                let restored = player.repair_enhanced_item(&item_id, RepairMethod::Field)?;
+                format!(
+                    "Without a repair kit you patch the {} up as best you can, restoring {} durability. \
+                     The rough fix weakens it for good; a repair kit or a craftsperson would do better.",
+                    name, restored
+                )
+            }
+        }
+    };
+
+    world.advance_time(REPAIR_MINUTES);
+    player.playtime_minutes += REPAIR_MINUTES;
+    Ok(message)
+}
+
+/// Handle unequip command
+fn handle_unequip(slot_name: Option<String>, player: &mut Player) -> GameResult<String> {
+    // Ensure player has enhanced item system
+    player.ensure_enhanced_item_system();
+
+    let item_system = player.inventory.enhanced_items.as_mut()
+        .ok_or_else(|| crate::GameError::InvalidCommand("Item system not available".to_string()))?;
+
+    // If no slot specified, show help
+    let slot_str = slot_name.ok_or_else(|| crate::GameError::InvalidInput(
+        "Please specify a slot to unequip (e.g., 'unequip head', 'unequip ring1')".to_string()
+    ))?;
+
+    // Parse slot name
+    let slot = parse_equipment_slot(&slot_str)?;
+
+    // Check if slot is occupied
+    if !item_system.equipment_manager.is_slot_occupied(slot.clone()) {
+        return Ok(format!("Nothing is equipped in the {} slot.", slot_display_name(&slot)));
+    }
+
+    // Check if inventory has space
+    let current_slots = item_system.inventory_manager.current_slots();
+    let max_slots = item_system.inventory_manager.constraints.max_slots;
+    if current_slots >= max_slots {
+        return Err(crate::GameError::InvalidCommand(
+            "Your inventory is full. You need to drop something before unequipping.".to_string()
+        ).into());
+    }
+
+    // Unequip the item, taking back its bonuses
+    match player.unequip_enhanced_item(slot.clone()) {
+        Ok(Some(item_id)) => {
+            // Get the item from inventory to add back
+            if let Some(item) = player.enhanced_item_system().and_then(|item_system| item_system.inventory_manager.get_item(&item_id)) {
+                let item_name = item.properties.name.clone();
+
+                // Item is already in inventory_manager, just need to confirm
+                Ok(format!("You unequip the {} from your {} slot.", item_name, slot_display_name(&slot)))
+            } else {
+                // This shouldn't happen, but handle gracefully
+                Ok(format!("You unequip an item from your {} slot.", slot_display_name(&slot)))
+            }
+        }
+        Ok(None) => {
+            Ok(format!("Nothing is equipped in the {} slot.", slot_display_name(&slot)))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Parse equipment slot from string
+fn parse_equipment_slot(slot_str: &str) -> GameResult<crate::systems::items::equipment::EquipmentSlot> {
+    use crate::systems::items::equipment::EquipmentSlot;
+
+    match slot_str.to_lowercase().as_str() {
+        "head" => Ok(EquipmentSlot::Head),
+        "neck" => Ok(EquipmentSlot::Neck),
+        "chest" => Ok(EquipmentSlot::Chest),
+        "hands" | "hand" | "gloves" => Ok(EquipmentSlot::Hands),
+        "ring1" | "ring 1" | "leftring" | "left ring" => Ok(EquipmentSlot::Ring1),
+        "ring2" | "ring 2" | "rightring" | "right ring" => Ok(EquipmentSlot::Ring2),
+        "waist" | "belt" => Ok(EquipmentSlot::Waist),
+        "legs" | "leg" | "pants" => Ok(EquipmentSlot::Legs),
+        "feet" | "foot" | "boots" | "shoes" => Ok(EquipmentSlot::Feet),
+        "mainhand" | "main hand" | "weapon" => Ok(EquipmentSlot::MainHand),
+        "offhand" | "off hand" | "shield" => Ok(EquipmentSlot::OffHand),
+        "back" | "cloak" | "cape" => Ok(EquipmentSlot::Back),
+        _ => Err(crate::GameError::InvalidInput(
+            format!("Unknown equipment slot '{}'. Valid slots: head, neck, chest, hands, ring1, ring2, waist, legs, feet, mainhand, offhand, back", slot_str)
+        ).into()),
+    }
+}
+
+/// Get display name for equipment slot
+fn slot_display_name(slot: &crate::systems::items::equipment::EquipmentSlot) -> &str {
+    use crate::systems::items::equipment::EquipmentSlot;
+
+    match slot {
+        EquipmentSlot::Head => "head",
+        EquipmentSlot::Neck => "neck",
+        EquipmentSlot::Chest => "chest",
+        EquipmentSlot::Hands => "hands",
         EquipmentSlot::Ring1 => "ring1",
         EquipmentSlot::Ring2 => "ring2",
         EquipmentSlot::Waist => "waist",
@@ -1078,7 +3015,23 @@ fn handle_equip_crystal(crystal_name: String, player: &mut Player) -> GameResult
         }
     }
 
-    Ok(format!("You don't have a crystal matching '{}'.", crystal_name))
+    // Otherwise look for wearable equipment
+    let equipment = player.enhanced_item_system().and_then(|item_system| {
+        item_system.inventory_manager.items.iter()
+            .find(|(_, item)| matches!(item.item_type, crate::systems::items::ItemType::Equipment(_))
+                && item.properties.name.to_lowercase().contains(&crystal_name.to_lowercase()))
+            .map(|(id, item)| (id.clone(), item.properties.name.clone()))
+    });
+    if let Some((item_id, name)) = equipment {
+        player.equip_enhanced_item(&item_id)?;
+        let mut response = format!("You equip the {}.", name);
+        if let Some(message) = ArtifactRegistry::default().on_equip(player, &item_id) {
+            response.push_str(&format!("\n{}", message));
+        }
+        return Ok(response);
+    }
+
+    Ok(format!("You don't have a crystal or equipment matching '{}'.", crystal_name))
 }
 
 /// Handle unknown commands
@@ -1099,11 +3052,41 @@ fn handle_unknown_command(original: String, suggestions: Vec<String>) -> GameRes
 fn generate_location_description(
     location: &crate::core::world_state::Location,
     player: &Player,
+    world: &WorldState,
+    flags: &WorldFlags,
+    people: &[String],
 ) -> String {
     let mut description = format!("=== {} ===\n\n", location.name);
     description.push_str(&location.description);
+    for text in flags.select(&location.flag_descriptions) {
+        description.push_str(&format!(" {}", text));
+    }
+    // The clock decides the time of day, even in saves where the two fell out of step
+    let time_of_day = crate::core::world_state::TimeOfDay::from_hour(world.hour_of_day());
+    description.push_str(&format!(" {}", calendar::ambience(&time_of_day, &world.environment.season)));
+    if let Some(sign) = crate::systems::weather::sign(world.environment.weather) {
+        description.push_str(&format!(" {}", sign));
+    }
     description.push_str("\n\n");
 
+    // Now and then, a line of flavour from what's going on here
+    let mut rng = world.run.turn_rng();
+    if let Some(line) = crate::systems::ambience::line(location, world, &mut rng) {
+        description.push_str(&format!("{}\n\n", line));
+    }
+
+    if player.preferences.verbosity == Verbosity::Rich {
+        description.push_str(&format!(
+            "It is {} on {}, and the weather is {}.\n\n",
+            time_of_day.name(),
+            world.date().describe(),
+            world.environment.weather.name()
+        ));
+    }
+    if let Some(meeting) = calendar::meeting_at(&location.id, world.game_time_minutes) {
+        description.push_str(&format!("The {} is in session here.\n\n", meeting.name));
+    }
+
     // Add magical information if player has sensitivity
     if player.attributes.resonance_sensitivity > 5 {
         description.push_str(&format!(
@@ -1129,94 +3112,832 @@ fn generate_location_description(
         description.push_str("\n");
     }
 
+    // Signs of the Unstable Resonance Site's flux are plain to anyone
+    if location.id == crate::systems::site_flux::SITE_ID {
+        description.push_str(&world.site_flux.signs(world.game_time_minutes));
+        description.push_str("\n\n");
+    }
+
+    // Lasting damage from crafting gone wrong
+    if let Some(phenomena) = world.phenomena.describe(&location.id) {
+        description.push_str(&phenomena);
+        description.push_str("\n\n");
+    }
+
+    // Notebooks left by characters who retired here in earlier runs
+    if let Some(notebooks) = world.legacy.describe(&location.id) {
+        description.push_str(&notebooks);
+        description.push_str("\n\n");
+    }
+
+    // Items stocked here by content
+    if !location.items.is_empty() {
+        let names: Vec<String> = location.items.iter()
+            .map(|id| crate::systems::placed_items::display_name(id))
+            .collect();
+        description.push_str(&format!("You notice: {}\n", names.join(", ")));
+    }
+
+    // Anything the player left here
+    let left_here = world.ground.describe(&location.id);
+    if !left_here.is_empty() {
+        description.push_str(&left_here);
+        description.push('\n');
+    }
+
+    // Whoever's routine has brought them here at this hour
+    if !people.is_empty() {
+        description.push_str(&format!("People here: {}\n", people.join(", ")));
+    }
+
+    // Remind the player of their own map notes
+    let notes = world.annotations_for(&location.id);
+    if !notes.is_empty() {
+        description.push_str("Your notes:\n");
+        for annotation in notes {
+            description.push_str(&format!("• [{}] {}\n", annotation.marker, annotation.note));
+        }
+        description.push('\n');
+    }
+
     // Show exits
     if !location.exits.is_empty() {
         description.push_str("Exits: ");
-        let exit_list: Vec<String> = location.exits.keys()
-            .map(|dir| dir.display_name().to_string())
+        let mut directions: Vec<_> = location.exits.keys().collect();
+        directions.sort_by_key(|dir| dir.display_name().to_string());
+        let exit_list: Vec<String> = directions.into_iter()
+            .map(|dir| match location.locks.get(dir) {
+                Some(lock) => format!("{} ({})", dir.display_name(), lock.kind.name()),
+                None => dir.display_name().to_string(),
+            })
             .collect();
         description.push_str(&exit_list.join(", "));
         description.push_str("\n");
     }
 
-    description
+    description
+}
+
+/// Handle attack command to initiate or continue combat
+fn handle_attack_command(
+    target: String,
+    spell: Option<String>,
+    player: &mut Player,
+    world: &mut WorldState,
+    magic_system: &mut MagicSystem,
+    combat_system: &mut CombatSystem,
+) -> GameResult<String> {
+    if !combat_system.is_in_combat() {
+        use crate::systems::combat::{Enemy, DifficultyTier};
+
+        // Prefer the named enemy, then whatever lives here, then a stray shard
+        let enemy = combat_system.find_enemy(&target)
+            .or_else(|| combat_system.enemies_at(&world.current_location).into_iter().next())
+            .unwrap_or_else(|| Enemy::new(
+                "corrupted_shard".to_string(),
+                "Corrupted Crystal Shard".to_string(),
+                "A small crystalline entity crackling with unstable magical energy.".to_string(),
+                DifficultyTier::Beginner,
+            ));
+
+        combat_system.start_encounter(enemy)?;
+        let wards = world.wards.take_for_combat(&world.current_location, world.game_time_minutes);
+        let prepared = combat_system.apply_wards(wards);
+
+        let spell_type = spell.unwrap_or_else(|| "light".to_string());
+        let output = combat_system.player_attack(player, world, magic_system, &spell_type)?;
+        return Ok(if prepared.is_empty() { output } else { format!("{}\n{}", prepared, output) });
+    }
+
+    // Determine spell to use
+    let spell_type = spell.unwrap_or_else(|| "light".to_string());
+
+    // Execute attack (correct argument order: player, world, magic_system, spell_type)
+    combat_system.player_attack(player, world, magic_system, &spell_type)
+}
+
+/// Handle defend command during combat
+fn handle_defend_command(
+    defense_type: Option<String>,
+    player: &mut Player,
+    world: &mut WorldState,
+    combat_system: &mut CombatSystem,
+) -> GameResult<String> {
+    if !combat_system.is_in_combat() {
+        return Ok("You are not in combat.".to_string());
+    }
+
+    // Parse defense type
+    let def_type = match defense_type.as_deref() {
+        Some("shield") => DefenseType::Shield,
+        Some("evade") => DefenseType::Evade,
+        Some("counter") | Some("countermagic") => DefenseType::CounterMagic,
+        _ => DefenseType::Shield, // Default to shield
+    };
+
+    combat_system.player_defend(player, world, def_type)
+}
+
+/// Handle flee command during combat
+fn handle_flee_command(
+    player: &mut Player,
+    combat_system: &mut CombatSystem,
+) -> GameResult<String> {
+    if !combat_system.is_in_combat() {
+        return Ok("You are not in combat.".to_string());
+    }
+
+    combat_system.player_flee(player)
+}
+
+/// Handle a non-lethal tactic during combat
+fn handle_combat_tactic(
+    tactic: String,
+    player: &mut Player,
+    world: &mut WorldState,
+    magic_system: &mut MagicSystem,
+    combat_system: &mut CombatSystem,
+) -> GameResult<String> {
+    use crate::systems::negotiation::Tactic;
+
+    if !combat_system.is_in_combat() {
+        return Ok("You are not in combat.".to_string());
+    }
+
+    let tactic = Tactic::from_string(&tactic)
+        .ok_or_else(|| crate::GameError::InvalidCommand(format!("Unknown tactic: {}", tactic)))?;
+    combat_system.attempt_tactic(player, world, magic_system, tactic)
+}
+
+/// Handle examine enemy command
+fn handle_examine_enemy_command(
+    combat_system: &CombatSystem,
+    player: &Player,
+) -> GameResult<String> {
+    if !combat_system.is_in_combat() {
+        return Ok("You are not in combat.".to_string());
+    }
+
+    let mut status = combat_system.get_status()
+        .ok_or_else(|| crate::GameError::InvalidCommand("Not in combat.".to_string()))?;
+
+    if player.preferences.verbosity == Verbosity::Rich {
+        if let Some(enemy) = combat_system.current_enemy() {
+            status.push_str(&format!("\n\n{}", enemy.description));
+        }
+    }
+
+    Ok(status)
+}
+
+/// Handle viewing or changing the defeat policy
+fn handle_defeat_policy(policy: Option<String>, world: &mut WorldState) -> GameResult<String> {
+    use crate::systems::defeat::{DefeatPolicy, IRONMAN_MUTATOR};
+
+    let current = world.defeat.effective_policy(&world.run.mutators);
+
+    let Some(requested) = policy else {
+        let mut response = format!(
+            "Defeat policy: {} - {}\nTimes fallen this run: {}\n\nAvailable policies:\n",
+            current.name(), current.description(), world.defeat.defeats
+        );
+        for option in [DefeatPolicy::Reload, DefeatPolicy::Capture, DefeatPolicy::Rescue, DefeatPolicy::Permadeath] {
+            response.push_str(&format!("• {} - {}\n", option.name(), option.description()));
+        }
+        return Ok(response.trim_end().to_string());
+    };
+
+    let new_policy = DefeatPolicy::from_string(&requested).ok_or_else(|| {
+        crate::GameError::InvalidInput(format!(
+            "Unknown defeat policy '{}'. Choose reload, capture, rescue, or permadeath.",
+            requested
+        ))
+    })?;
+
+    if world.run.mutators.iter().any(|m| m == IRONMAN_MUTATOR) || current == DefeatPolicy::Permadeath {
+        return Err(crate::GameError::InvalidCommand(
+            "Permadeath cannot be changed once chosen.".to_string()
+        ).into());
+    }
+
+    world.defeat.policy = new_policy;
+    Ok(format!("Defeat policy set to {}: {}.", new_policy.name(), new_policy.description()))
+}
+
+/// Handle viewing or changing the combat difficulty
+fn handle_difficulty(level: Option<String>, combat_system: &mut CombatSystem) -> GameResult<String> {
+    use crate::systems::enemy_ai::Difficulty;
+
+    let current = combat_system.difficulty();
+
+    let Some(requested) = level else {
+        let mut response = format!("Difficulty: {} - {}\n\nAvailable difficulties:\n", current.name(), current.description());
+        for option in Difficulty::all() {
+            response.push_str(&format!("• {} - {}\n", option.name(), option.description()));
+        }
+        return Ok(response.trim_end().to_string());
+    };
+
+    let new_difficulty = Difficulty::from_string(&requested).ok_or_else(|| {
+        crate::GameError::InvalidInput(format!(
+            "Unknown difficulty '{}'. Choose story, normal, hard, or nightmare.",
+            requested
+        ))
+    })?;
+
+    if combat_system.is_in_combat() {
+        return Err(crate::GameError::InvalidCommand(
+            "You can't change the difficulty in the middle of a fight.".to_string()
+        ).into());
+    }
+
+    combat_system.set_difficulty(new_difficulty);
+    Ok(format!("Difficulty set to {}: {}.", new_difficulty.name(), new_difficulty.description()))
+}
+
+/// Handle crafting an item from a recipe
+fn handle_craft(
+    action: String,
+    items: Vec<String>,
+    recipe: Option<String>,
+    player: &mut Player,
+    world: &mut WorldState,
+    database: &DatabaseManager,
+) -> GameResult<String> {
+    use crate::systems::items::CraftingSystem;
+
+    if action == "repair" {
+        return Ok("To mend an item, use 'repair <item>'; 'repair' alone lists your worn gear.".to_string());
+    }
+    if action == "enhance" {
+        return Ok("You don't know how to enhance items yet. Type 'recipes' to see what you can craft.".to_string());
+    }
+
+    let crafting = CraftingSystem::load(database)?;
+    let query = recipe.unwrap_or_else(|| items.join(" "));
+    let found = if action == "combine" {
+        crafting.find_by_ingredients(&items).or_else(|| crafting.find(&query))
+    } else {
+        crafting.find(&query).or_else(|| crafting.find_by_ingredients(&items))
+    };
+    let recipe = found.ok_or_else(|| crate::GameError::ContentNotFound(format!(
+        "You don't know how to make '{}'. Type 'recipes' to see what you can craft.", query
+    )))?;
+
+    if let Some(station) = recipe.station.as_ref().filter(|station| world.phenomena.contaminated(&world.current_location, &station.name)) {
+        return Err(crate::GameError::InvalidCommand(format!(
+            "The {} is fouled with ruined reagent. Clean it up first ('clean up {}').", station.name, station.name
+        )).into());
+    }
+
+    player.ensure_enhanced_item_system();
+    let mut rng = world.run.rng();
+    let outcome = crafting.craft(recipe, player, &world.current_location, &mut rng)?;
+    world.advance_time(outcome.minutes);
+    player.playtime_minutes += outcome.minutes;
+
+    // A bad enough failure fouls the station, or leaves loose energy behind in the field
+    let aftermath = outcome.critical.then(|| {
+        use crate::systems::phenomena::{spawn, PhenomenonKind};
+        match &recipe.station {
+            Some(station) => spawn(world, PhenomenonKind::ContaminatedBench, Some(station.name.clone())),
+            None => spawn(world, PhenomenonKind::WildResonancePocket, None),
+        }
+    }).flatten().map(|message| format!("\n{}", message)).unwrap_or_default();
+
+    Ok(match outcome.item {
+        Some((item, quality)) => format!(
+            "After {} minutes of careful work you produce a {} ({} quality; {:.0}% chance of success).",
+            outcome.minutes, item.properties.name, quality.name(), outcome.chance * 100.0
+        ),
+        None => format!(
+            "After {} minutes the {} falls apart in your hands. The ingredients are spent ({:.0}% chance of success).{}",
+            outcome.minutes, outcome.recipe, outcome.chance * 100.0, aftermath
+        ),
+    })
+}
+
+/// Handle reading a retired character's notebook at the current location
+fn handle_read_notebook(world: &mut WorldState, knowledge_system: &KnowledgeSystem) -> GameResult<String> {
+    let location = world.current_location.clone();
+    let read = world.legacy.read_here(&mut world.annotations, &location);
+    if read.is_empty() {
+        return Err(crate::GameError::ContentNotFound("There is no notebook here to read.".to_string()).into());
+    }
+
+    let theory_name = |id: &str| knowledge_system.get_theory(id).map_or(id.replace('_', " "), |theory| theory.name.clone());
+    let mut output = read.iter().map(|note| note.pages(theory_name)).collect::<Vec<_>>().join("\n\n");
+    if read.iter().any(|note| !note.annotations.is_empty()) {
+        output.push_str("\n\nYou copy their map notes onto your own map.");
+    }
+    Ok(output)
+}
+
+/// Handle retiring the character
+fn handle_retire(leave_notes: Option<bool>, player: &Player, world: &mut WorldState, save_manager: &SaveManager) -> GameResult<String> {
+    use crate::systems::legacy_notes::{LegacyArchive, LegacyNote};
+
+    let Some(leave_notes) = leave_notes else {
+        return Ok(format!(
+            "Retiring ends {}'s journey for good.\n\
+             • retire with notes - Leave your lab notebook and map notes here, for a future character in a world\n\
+             \x20 with the same seed ({}) to find. They inherit what you knew to look for, not your skills.\n\
+             • retire quietly - Leave nothing behind.",
+            player.name, world.run.seed
+        ));
+    };
+
+    if world.coop.is_apprentice_turn() {
+        return Err(crate::GameError::InvalidCommand("Only the lead player can retire.".to_string()).into());
+    }
+
+    let mut output = format!("{} sets down their instruments and retires from the life of a resonance scholar.", player.name);
+    if leave_notes {
+        let note = LegacyNote::from_character(player, world);
+        let directory = save_manager.get_save_directory_path();
+        let mut archive = LegacyArchive::load(directory)?;
+        archive.notes.push(note);
+        archive.save(directory)?;
+        output.push_str(&format!(
+            "\nYour lab notebook stays behind at {}, waiting for whoever next walks a world with seed {}.",
+            world.current_location().map_or(world.current_location.as_str(), |location| location.name.as_str()),
+            world.run.seed
+        ));
+    }
+    world.legacy.retired = true;
+    output.push_str("\n\nUse 'summary' to review the run or 'quit' to exit.");
+    Ok(output)
+}
+
+/// Handle combining two carried items
+///
+/// Combination rules come first; failing those, a recipe whose ingredients
+/// are exactly the two items is crafted instead.
+fn handle_combine(
+    first: String,
+    second: String,
+    player: &mut Player,
+    world: &mut WorldState,
+    database: &DatabaseManager,
+) -> GameResult<String> {
+    use crate::systems::defeat::{resolve_defeat, DefeatCause};
+    use crate::systems::items::combination::{self, Combined};
+    use crate::systems::items::interactions::CombinationFailure;
+    use crate::systems::items::CraftingSystem;
+
+    player.ensure_enhanced_item_system();
+    let found = player.enhanced_item_system()
+        .and_then(|items| combination::find_rule(&items.interaction_rules, player, &first, &second))
+        .map(|(id, rule)| (id.clone(), rule.clone()));
+    let Some((rule_id, rule)) = found else {
+        let crafting = CraftingSystem::load(database)?;
+        if crafting.find_by_ingredients(&[first.clone(), second.clone()]).is_some() {
+            return handle_craft("combine".to_string(), vec![first, second], None, player, world, database);
+        }
+        for query in [&first, &second] {
+            if combination::carried_name(player, query).is_none() {
+                return Err(crate::GameError::ContentNotFound(format!("You aren't carrying any {}.", query)).into());
+            }
+        }
+        return Err(crate::GameError::InvalidInput(format!(
+            "Nothing comes of combining the {} with the {}.", first, second
+        )).into());
+    };
+
+    let mut rng = world.run.rng();
+    let outcome = combination::combine(&rule_id, &rule, player, &world.current_location, &mut rng)?;
+    world.advance_time(outcome.minutes);
+    player.playtime_minutes += outcome.minutes;
+
+    let (a, b) = (&rule.combinable_items[0], &rule.combinable_items[1]);
+    let mut output = match &outcome.result {
+        Combined::Made(name) => format!(
+            "After {} minutes of work the {} and the {} become a {} ({:.0}% chance of success).",
+            outcome.minutes, a, b, name, outcome.chance * 100.0
+        ),
+        Combined::Enhanced(name) => format!(
+            "After {} minutes of work the {} has improved your {} ({:.0}% chance of success).",
+            outcome.minutes, b, name, outcome.chance * 100.0
+        ),
+        Combined::Failed(CombinationFailure::Fizzle) => format!(
+            "After {} minutes nothing has taken. The {} and the {} are unharmed ({:.0}% chance of success).",
+            outcome.minutes, a, b, outcome.chance * 100.0
+        ),
+        Combined::Failed(CombinationFailure::Spoil) => format!(
+            "After {} minutes the mixture turns to sludge and the materials are ruined ({:.0}% chance of success).",
+            outcome.minutes, outcome.chance * 100.0
+        ),
+        Combined::Failed(CombinationFailure::Backlash { damage }) => format!(
+            "After {} minutes the resonance snaps back through your hands and the materials are ruined. \
+             (Health -{}, now {}/{}; {:.0}% chance of success)",
+            outcome.minutes, damage, player.health.current, player.health.max, outcome.chance * 100.0
+        ),
+    };
+    if let Combined::Failed(CombinationFailure::Backlash { .. }) = outcome.result {
+        use crate::systems::phenomena::{spawn, PhenomenonKind};
+        if let Some(message) = spawn(world, PhenomenonKind::WildResonancePocket, None) {
+            output.push_str(&format!("\n{}", message));
+        }
+    }
+    if player.health.is_down() {
+        let cause = DefeatCause::Hazard { source: format!("a combination of {} and {} gone wrong", a, b) };
+        output.push_str(&format!("\n\n{}", resolve_defeat(&cause, player, world)));
+    }
+    Ok(output)
+}
+
+/// Handle taking apart a combined item
+fn handle_deconstruct(item: String, player: &mut Player, world: &mut WorldState) -> GameResult<String> {
+    use crate::systems::items::combination::{self, DECONSTRUCT_MINUTES};
+
+    player.ensure_enhanced_item_system();
+    let rules = player.enhanced_item_system()
+        .map(|items| items.interaction_rules.clone())
+        .unwrap_or_default();
+    let mut rng = world.run.rng();
+    let outcome = combination::deconstruct(&rules, player, &item, &mut rng)?;
+    world.advance_time(DECONSTRUCT_MINUTES);
+    player.playtime_minutes += DECONSTRUCT_MINUTES;
+
+    let mut output = format!("You spend {} minutes taking apart the {}.", DECONSTRUCT_MINUTES, outcome.item);
+    if !outcome.recovered.is_empty() {
+        output.push_str(&format!("\nRecovered: {}.", outcome.recovered.join(", ")));
+    }
+    if !outcome.lost.is_empty() {
+        output.push_str(&format!("\nLost beyond saving: {}.", outcome.lost.join(", ")));
+    }
+    Ok(output)
+}
+
+/// Handle hiring and dismissing porters, or show the player's load
+fn handle_porter(action: Option<String>, player: &mut Player, world: &WorldState) -> GameResult<String> {
+    use crate::systems::encumbrance::{self, PORTER_DAILY_FEE};
+
+    let Some(action) = action else {
+        let mut output = format!("Load: {}", encumbrance::describe_load(player));
+        match &player.porter {
+            Some(porter) => output.push_str(&format!(
+                "\n{} is with you for {} more day(s).", porter.name, porter.days_left(world.game_time_minutes)
+            )),
+            None => output.push_str(&format!(
+                "\nPorters at the Practice Hall and the Faction Diplomacy Hall carry for {} silver a day ('hire porter for <days>').",
+                PORTER_DAILY_FEE
+            )),
+        }
+        return Ok(output);
+    };
+
+    match action.split_once(' ') {
+        Some(("hire", days)) => {
+            let days = days.parse::<i32>()
+                .map_err(|_| crate::GameError::InvalidInput(format!("'{}' isn't a number of days.", days)))?;
+            encumbrance::hire(player, days, &world.current_location, world.game_time_minutes)
+        }
+        _ if action == "dismiss" => encumbrance::dismiss(player),
+        _ => Err(crate::GameError::InvalidInput(format!("Unknown porter action '{}'.", action)).into()),
+    }
+}
+
+/// Handle hotseat co-op commands
+fn handle_coop(action: Option<String>, player: &Player, world: &mut WorldState) -> GameResult<String> {
+    use crate::systems::coop::SegmentKind;
+
+    if world.coop.is_apprentice_turn() {
+        return Err(crate::GameError::InvalidCommand("Only the lead player can manage the link.".to_string()).into());
+    }
+    let Some(action) = action else {
+        return Ok(world.coop.report(player));
+    };
+
+    let (verb, argument) = action.split_once(' ').unwrap_or((action.as_str(), ""));
+    match verb {
+        "link" | "join" => {
+            // Input arrives lowercased; names read better capitalized
+            let mut chars = argument.trim().chars();
+            let name: String = chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default();
+            let location = world.current_location.clone();
+            world.coop.link(&name, &location)
+        }
+        "unlink" | "leave" => world.coop.unlink(),
+        "end" | "stop" => world.coop.end(),
+        "status" => Ok(world.coop.report(player)),
+        other => match SegmentKind::from_string(other) {
+            Some(kind) => world.coop.begin(kind),
+            None => Err(crate::GameError::InvalidInput(format!(
+                "Unknown co-op action '{}'. Use link, ritual, expedition, end or unlink.",
+                other
+            )).into()),
+        },
+    }
+}
+
+/// Handle scientific concept explanations
+fn handle_explain(concept: Option<String>, player: &Player, codex: &Codex) -> GameResult<String> {
+    use crate::systems::concepts::ConceptGlossary;
+
+    let glossary = ConceptGlossary::new();
+
+    let Some(query) = concept else {
+        let encountered: Vec<String> = glossary.entries()
+            .iter()
+            .filter(|entry| codex.concepts.contains(&entry.id))
+            .map(|entry| if player.has_encountered_concept(&entry.id) {
+                entry.name.clone()
+            } else {
+                format!("{} (from your codex)", entry.name)
+            })
+            .collect();
+
+        if encountered.is_empty() {
+            return Ok("You haven't encountered any scientific concepts yet. Study a theory to begin.".to_string());
+        }
+
+        return Ok(format!(
+            "=== Scientific Concepts ({}/{}) ===\n\n{}\n\nUse 'explain <concept>' to learn more.",
+            encountered.len(),
+            glossary.entries().len(),
+            encountered.iter().map(|name| format!("• {}", name)).collect::<Vec<_>>().join("\n")
+        ));
+    };
+
+    let entry = glossary.lookup(&query).ok_or_else(|| {
+        crate::GameError::ContentNotFound(format!("No scientific concept called '{}'", query))
+    })?;
+
+    if !codex.concepts.contains(&entry.id) {
+        let source = if entry.theories.is_empty() {
+            "your theory studies".to_string()
+        } else {
+            entry.theories.join(" or ")
+        };
+        return Ok(format!(
+            "You haven't encountered {} yet. Studying {} will introduce it.",
+            entry.name, source
+        ));
+    }
+
+    Ok(glossary.explain(entry))
 }
 
-/// Handle attack command to initiate or continue combat
-fn handle_attack_command(
-    _target: String,
-    spell: Option<String>,
+/// Show what this character has done, in every category or just one
+fn handle_stats(category: Option<String>, player: &Player, world: &WorldState, save_manager: &SaveManager) -> GameResult<String> {
+    use crate::systems::statistics::{character_key, StatCategory, Statistics};
+
+    let only = match category {
+        Some(name) => Some(StatCategory::parse(&name).ok_or_else(|| crate::GameError::InvalidInput(format!(
+            "There are no '{}' statistics. Try one of: {}.",
+            name, StatCategory::ALL.iter().map(StatCategory::as_str).collect::<Vec<_>>().join(", ")
+        )))?),
+        None => None,
+    };
+    Statistics::open(save_manager.get_save_directory_path())?
+        .describe(&character_key(player, world), &player.name, only)
+}
+
+/// Show codex completion across saves, or hide or show spoilers in this save
+fn handle_codex(
+    setting: Option<String>,
     player: &mut Player,
-    world: &mut WorldState,
-    magic_system: &mut MagicSystem,
-    combat_system: &mut CombatSystem,
+    world: &WorldState,
+    combat_system: &CombatSystem,
+    save_manager: &SaveManager,
 ) -> GameResult<String> {
-    // For now, create a stub enemy - in the future this would look up enemies in the world
-    if !combat_system.is_in_combat() {
-        // Start new combat encounter
-        use crate::systems::combat::{Enemy, DifficultyTier};
+    use crate::systems::concepts::ConceptGlossary;
 
-        // Create a simple enemy for testing (this should come from world/database)
-        let enemy = Enemy::new(
-            "corrupted_shard".to_string(),
-            "Corrupted Crystal Shard".to_string(),
-            "A small crystalline entity crackling with unstable magical energy.".to_string(),
-            DifficultyTier::Beginner,
-        );
+    match setting.as_deref() {
+        Some("spoilers off") | Some("hide spoilers") => {
+            player.preferences.hide_codex_spoilers = true;
+            return Ok("This save's codex will show only what this character has discovered.".to_string());
+        }
+        Some("spoilers on") | Some("show spoilers") => {
+            player.preferences.hide_codex_spoilers = false;
+            return Ok("This save's codex will show everything your characters have discovered.".to_string());
+        }
+        Some(other) => {
+            return Err(crate::GameError::InvalidInput(format!(
+                "Unknown codex setting '{}'. Use 'codex spoilers on' or 'codex spoilers off'.", other
+            )).into());
+        }
+        None => {}
+    }
 
-        combat_system.start_encounter(enemy)?;
+    let codex = Codex::visible(save_manager.get_save_directory_path(), player, world, combat_system);
+    let mut places: Vec<(&str, i32)> = codex.frequencies.iter()
+        .map(|(id, frequency)| (world.locations.get(id).map_or(id.as_str(), |location| location.name.as_str()), *frequency))
+        .collect();
+    places.sort();
+    let tuned_places = world.locations.values()
+        .filter(|location| location.magical_properties.dominant_frequency.is_some())
+        .count();
+
+    let mut output = format!(
+        "=== CODEX ===\n\nLore codex: {}/{} concepts ('explain')\nBestiary: {}/{} enemies ('bestiary')\nFrequency compendium: {}/{} places\n",
+        codex.concepts.len(), ConceptGlossary::new().entries().len(),
+        codex.enemies.len(), combat_system.catalog_size(),
+        places.len(), tuned_places
+    );
+    for (name, frequency) in places {
+        output.push_str(&format!("  • {} - frequency {}\n", name, frequency));
     }
+    output.push_str(if player.preferences.hide_codex_spoilers {
+        "\nShowing only this character's discoveries. ('codex spoilers on' to include earlier characters')"
+    } else {
+        "\nIncludes what earlier characters discovered. ('codex spoilers off' to hide it in this save)"
+    });
+    Ok(output)
+}
 
-    // Determine spell to use
-    let spell_type = spell.unwrap_or_else(|| "light".to_string());
+/// Handle map annotation command
+fn handle_annotate(marker: Option<char>, note: String, world: &mut WorldState) -> GameResult<String> {
+    let location_id = world.current_location.clone();
+    let marker = marker.unwrap_or('*');
+    world.annotate(&location_id, marker, note.clone())?;
 
-    // Execute attack (correct argument order: player, world, magic_system, spell_type)
-    combat_system.player_attack(player, world, magic_system, &spell_type)
+    let location_name = world.current_location()
+        .map(|location| location.name.clone())
+        .unwrap_or(location_id);
+    Ok(format!("You mark [{}] on your map at {}: \"{}\"", marker, location_name, note))
 }
 
-/// Handle defend command during combat
-fn handle_defend_command(
-    defense_type: Option<String>,
-    player: &mut Player,
-    combat_system: &mut CombatSystem,
-) -> GameResult<String> {
-    if !combat_system.is_in_combat() {
-        return Ok("You are not in combat.".to_string());
-    }
+/// Handle verbosity profile command
+fn handle_set_verbosity(level: Option<String>, player: &mut Player) -> GameResult<String> {
+    let Some(level) = level else {
+        return Ok(format!(
+            "Text verbosity: {}\nAvailable profiles: terse, standard, rich",
+            player.preferences.verbosity.name()
+        ));
+    };
 
-    // Parse defense type
-    let def_type = match defense_type.as_deref() {
-        Some("shield") => DefenseType::Shield,
-        Some("evade") => DefenseType::Evade,
-        Some("counter") | Some("countermagic") => DefenseType::CounterMagic,
-        _ => DefenseType::Shield, // Default to shield
+    let verbosity = Verbosity::from_string(&level).ok_or_else(|| {
+        crate::GameError::InvalidInput(format!(
+            "Unknown verbosity profile '{}'. Choose terse, standard, or rich.",
+            level
+        ))
+    })?;
+
+    player.preferences.verbosity = verbosity;
+    Ok(format!("Text verbosity set to {}.", verbosity.name()))
+}
+
+/// Handle colour theme command
+fn handle_set_theme(theme: Option<String>, player: &mut Player) -> GameResult<String> {
+    let names = Theme::ALL.iter().map(|theme| theme.name()).collect::<Vec<_>>().join(", ");
+    let Some(requested) = theme else {
+        return Ok(format!("Colour theme: {}\nAvailable themes: {}", player.preferences.theme.name(), names));
     };
 
-    combat_system.player_defend(player, def_type)
+    let theme = Theme::from_string(&requested).ok_or_else(|| {
+        crate::GameError::InvalidInput(format!("Unknown theme '{}'. Choose {}.", requested, names))
+    })?;
+
+    player.preferences.theme = theme;
+    Ok(format!("Colour theme set to {}.", theme.name()))
 }
 
-/// Handle flee command during combat
-fn handle_flee_command(
-    player: &mut Player,
-    combat_system: &mut CombatSystem,
+/// Show whether fast travel is allowed, or switch between fast and manual navigation
+fn handle_set_navigation(setting: Option<String>, player: &mut Player) -> GameResult<String> {
+    match setting.as_deref() {
+        None => Ok(format!(
+            "Navigation: {}\nAvailable modes: fast, manual",
+            if player.preferences.manual_navigation { "manual" } else { "fast" }
+        )),
+        Some("fast" | "on") => {
+            player.preferences.manual_navigation = false;
+            Ok("Fast travel is on: 'travel to <place>' walks you anywhere you've been.".to_string())
+        }
+        Some("manual" | "off") => {
+            player.preferences.manual_navigation = true;
+            Ok("Fast travel is off. You'll find every way yourself.".to_string())
+        }
+        Some(other) => Err(crate::GameError::InvalidInput(format!(
+            "Unknown navigation mode '{}'. Choose fast or manual.", other
+        )).into()),
+    }
+}
+
+/// List what the player could do here, asking each system in turn
+fn handle_actions(
+    player: &Player,
+    world: &WorldState,
+    dialogue_system: &DialogueSystem,
+    knowledge_system: &KnowledgeSystem,
+    quest_system: &QuestSystem,
+    faction_system: &FactionSystem,
 ) -> GameResult<String> {
-    if !combat_system.is_in_combat() {
-        return Ok("You are not in combat.".to_string());
+    use crate::systems::suggestions::{self, Situation};
+    let situation = Situation { player, world, quest_system, faction_system };
+    // Quests go first so a step toward one explains a way out better than the map does
+    Ok(suggestions::suggest(&situation, &[quest_system, dialogue_system, world, knowledge_system, player]))
+}
+
+/// Show or change how many actions undo can take back
+fn handle_undo_limit(limit: Option<usize>, player: &mut Player) -> GameResult<String> {
+    use crate::core::undo::{DEFAULT_UNDO_LIMIT, MAX_UNDO_LIMIT};
+    match limit {
+        None => Ok(format!(
+            "Undo keeps your last {} actions (up to {}).",
+            player.preferences.undo_limit.unwrap_or(DEFAULT_UNDO_LIMIT), MAX_UNDO_LIMIT
+        )),
+        Some(limit) if limit > MAX_UNDO_LIMIT => Err(crate::GameError::InvalidInput(format!(
+            "Undo can keep at most {} actions.", MAX_UNDO_LIMIT
+        )).into()),
+        Some(0) => {
+            player.preferences.undo_limit = Some(0);
+            Ok("Undo is off. Your actions stand as they are.".to_string())
+        }
+        Some(limit) => {
+            player.preferences.undo_limit = Some(limit);
+            Ok(format!("Undo now keeps your last {} actions.", limit))
+        }
     }
+}
 
-    combat_system.player_flee(player)
+/// Handle the getting-started checklist command
+fn handle_checklist(action: Option<String>, player: &mut Player) -> GameResult<String> {
+    let onboarding = &mut player.onboarding;
+    match action.as_deref() {
+        None => Ok(onboarding.render()),
+        Some("dismiss" | "hide" | "off") => {
+            onboarding.dismissed = true;
+            Ok("Checklist reminders hidden. Use 'checklist show' to bring them back.".to_string())
+        }
+        Some("show" | "on" | "resume") => {
+            onboarding.dismissed = false;
+            onboarding.idle_commands = 0;
+            Ok(onboarding.render())
+        }
+        Some(other) => Err(crate::GameError::InvalidInput(format!(
+            "Unknown checklist option '{}'. Use 'checklist', 'checklist dismiss' or 'checklist show'.",
+            other
+        )).into()),
+    }
 }
 
-/// Handle examine enemy command
-fn handle_examine_enemy_command(
-    combat_system: &CombatSystem,
-) -> GameResult<String> {
-    if !combat_system.is_in_combat() {
-        return Ok("You are not in combat.".to_string());
+/// Handle prompt customization command
+fn handle_set_prompt(setting: Option<String>, player: &mut Player, quest_system: &QuestSystem) -> GameResult<String> {
+    use crate::ui::PromptToken;
+
+    let available = PromptToken::ALL.iter().map(|token| token.name()).collect::<Vec<_>>().join(", ");
+    let Some(setting) = setting else {
+        let current = if player.preferences.prompt.is_empty() {
+            "plain".to_string()
+        } else {
+            player.preferences.prompt.iter().map(|token| token.name()).collect::<Vec<_>>().join(" ")
+        };
+        return Ok(format!(
+            "Prompt: {}\nAvailable tokens: {}\nUse 'prompt <tokens>' to choose, 'prompt off' for a plain prompt, \
+             or 'prompt track <quest>' to pick the quest shown.",
+            current, available
+        ));
+    };
+
+    if let Some(query) = setting.strip_prefix("track ") {
+        let quest = crate::systems::quest_journal::find_active(quest_system, query)?;
+        let response = format!("The prompt now follows {}.", quest.title);
+        player.preferences.tracked_quest = Some(quest.id.clone());
+        if !player.preferences.prompt.contains(&PromptToken::Quest) {
+            player.preferences.prompt.push(PromptToken::Quest);
+        }
+        return Ok(response);
+    }
+
+    if matches!(setting.as_str(), "off" | "plain" | "default" | "none") {
+        player.preferences.prompt.clear();
+        return Ok("Prompt reset to plain.".to_string());
+    }
+
+    let mut tokens = Vec::new();
+    for word in setting.split(|c: char| c.is_whitespace() || c == ',').filter(|word| !word.is_empty()) {
+        let token = PromptToken::from_string(word).ok_or_else(|| {
+            crate::GameError::InvalidInput(format!("Unknown prompt token '{}'. Choose from: {}.", word, available))
+        })?;
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
     }
+    player.preferences.prompt = tokens;
+    Ok(format!(
+        "Prompt will show: {}.",
+        player.preferences.prompt.iter().map(|token| token.name()).collect::<Vec<_>>().join(", ")
+    ))
+}
 
-    combat_system.get_status()
-        .ok_or_else(|| crate::GameError::InvalidCommand("Not in combat.".to_string()).into())
+/// Handle listing and managing command shortcuts
+fn handle_shortcuts(setting: Option<String>, player: &mut Player) -> GameResult<String> {
+    use crate::input::shortcuts;
+
+    let custom = &mut player.preferences.shortcuts;
+    let Some(setting) = setting else {
+        return Ok(shortcuts::describe(custom));
+    };
+    let mut words = setting.splitn(3, char::is_whitespace);
+    match (words.next(), words.next(), words.next()) {
+        (Some("add" | "set"), Some(key), Some(command)) => shortcuts::define(custom, key, command),
+        (Some("remove" | "delete" | "clear"), Some(key), None) => shortcuts::remove(custom, key),
+        _ => Err(crate::GameError::InvalidInput(
+            "Use 'shortcut add <key> <command>' or 'shortcut remove <key>'.".to_string()
+        ).into()),
+    }
 }
 
 /// Main function to execute a command
@@ -1233,12 +3954,270 @@ pub fn execute_command(
     combat_system: &mut CombatSystem,
     save_manager: &SaveManager,
 ) -> GameResult<String> {
+    // A character lost to permadeath can only review or leave the run
+    if (world.defeat.fallen || world.legacy.retired)
+        && !matches!(command, ParsedCommand::Quit | ParsedCommand::RunSummary { .. } | ParsedCommand::Help { .. })
+    {
+        return Err(crate::GameError::InvalidCommand(
+            "Your journey has ended. Use 'summary' to review the run or 'quit' to exit.".to_string()
+        ).into());
+    }
+
+    // Look up notebooks left in worlds with this seed once per run
+    if world.legacy.needs_loading(world.run.seed) {
+        use crate::systems::legacy_notes::LegacyArchive;
+        // A missing or unreadable archive just means there is nothing to find
+        let notes = LegacyArchive::load(save_manager.get_save_directory_path())
+            .map(|archive| archive.for_seed(world.run.seed))
+            .unwrap_or_default();
+        world.legacy.load(world.run.seed, notes);
+    }
+
+    // What the command shows the player can do, for the onboarding checklist;
+    // spells and study only count once they've actually happened
+    use crate::systems::onboarding::Competency;
+    let mut competencies: Vec<Competency> = match &command {
+        ParsedCommand::Examine { .. } | ParsedCommand::ExamineItem { .. } | ParsedCommand::ExamineEnemy
+            | ParsedCommand::Look { target: Some(_) } => vec![Competency::Examined],
+        ParsedCommand::Save { .. } => vec![Competency::Saved],
+        _ => Vec::new(),
+    };
+    let study_before: i32 = player.knowledge.theory_progress.values().map(|progress| progress.time_invested).sum();
+    let records_codex = matches!(command, ParsedCommand::Save { .. } | ParsedCommand::Quit);
+    // Loading swaps in another save's history, which is nothing the player just did
+    let loads_save = matches!(command, ParsedCommand::Load { .. });
+    let casts = matches!(command, ParsedCommand::CastMagic { .. });
+    let resolved_before: std::collections::HashSet<QuestId> = quest_system.player_progress.values()
+        .filter(|progress| matches!(progress.status, QuestStatus::Completed | QuestStatus::Failed))
+        .map(|progress| progress.quest_id.clone())
+        .collect();
+    let location_before = world.current_location.clone();
+    let injuries_before: u32 = player.health.injuries.iter().map(|injury| injury.severity()).sum();
+    let standings_before = player.faction_standings.clone();
+    let bestiary_before = combat_system.bestiary().clone();
+    let talked_to = match &command {
+        ParsedCommand::Talk { target } => Some((target.clone(), None)),
+        ParsedCommand::Ask { target, topic } => Some((target.clone(), Some(topic.clone()))),
+        _ => None,
+    }
+    .and_then(|(target, topic)| {
+        let npc = if target.is_empty() {
+            dialogue_system.addressee(world, quest_system)?
+        } else {
+            dialogue_system.find_npc(&target, &world.current_location)?
+        };
+        unavailability(player, dialogue_system, &npc.id, world, quest_system).is_none().then(|| (npc.id.clone(), topic))
+    });
+
+    dialogue_system.set_variables(crate::systems::text_templates::TextVariables::gather(player, world, quest_system));
+
     let handler = DefaultCommandHandler;
-    handler.execute(command, player, world, database, magic_system, dialogue_system, faction_system, knowledge_system, quest_system, combat_system, save_manager)
+    let mut response = handler.execute(command, player, world, database, magic_system, dialogue_system, faction_system, knowledge_system, quest_system, combat_system, save_manager)?;
+
+    // Saving or quitting adds this run's discoveries to the codex shared by every save;
+    // a codex that can't be written shouldn't get in the way
+    if records_codex {
+        let _ = Codex::sync(save_manager.get_save_directory_path(), player, world, combat_system);
+    }
+
+    // Progress quest objectives met by what the command did
+    if response != "QUIT_GAME" {
+        let mut quest_updates = Vec::new();
+        if world.current_location != location_before {
+            quest_updates.extend(quest_system.handle_location_visit(&world.current_location)?);
+        }
+        if let Some((npc_id, topic)) = &talked_to {
+            quest_updates.extend(quest_system.handle_dialogue_trigger(npc_id, topic.as_deref(), player)?);
+        }
+        let theories: Vec<(String, f32)> = player.knowledge.theories.iter().map(|(id, level)| (id.clone(), *level)).collect();
+        for (theory_id, level) in theories {
+            quest_updates.extend(quest_system.handle_theory_progress(&theory_id, level, player)?);
+        }
+        quest_updates.extend(quest_system.handle_items_carried(&crate::systems::placed_items::carried_definitions(player))?);
+        if !quest_updates.is_empty() {
+            response.push_str("\n\n--- Quest Updates ---\n");
+            for update in quest_updates {
+                response.push_str(&format!("• {}\n", update));
+            }
+        }
+    }
+
+    // Timed quests warn as their deadlines near; quests that can no longer
+    // succeed fail and leave their fallout behind
+    for message in crate::systems::quest_fallout::resolve(world.game_time_minutes, quest_system, world, dialogue_system, faction_system) {
+        response.push_str(&format!("\n\n{}", message));
+    }
+
+    // The people involved remember how quests turned out, and word gets around
+    use crate::systems::rumors::{Deed, NOTABLE_STANDING_CHANGE};
+    let mut resolved: Vec<(QuestId, bool)> = quest_system.player_progress.values()
+        .filter(|progress| !loads_save && !resolved_before.contains(&progress.quest_id))
+        .filter_map(|progress| match progress.status {
+            QuestStatus::Completed => Some((progress.quest_id.clone(), true)),
+            QuestStatus::Failed => Some((progress.quest_id.clone(), false)),
+            _ => None,
+        })
+        .collect();
+    resolved.sort();
+    for (quest_id, completed) in resolved {
+        if let Some(quest) = quest_system.quest_definitions.get(&quest_id) {
+            for message in dialogue_system.quest_resolved(&quest.involved_npcs, &quest.title, completed) {
+                response.push_str(&format!("\n\n{}", message));
+            }
+            let deed = if completed { Deed::QuestCompleted(quest.title.clone()) } else { Deed::QuestFailed(quest.title.clone()) };
+            world.rumors.start(deed, &world.current_location, world.game_time_minutes);
+
+            // Completed work leaves lasting marks on the places it touched
+            let edits = if completed { quest.changes_locations.as_slice() } else { &[] };
+            let mut changed: Vec<String> = Vec::new();
+            for edit in edits {
+                if world.change_location(&edit.location_id, edit.change.clone()).is_ok() && !changed.contains(&edit.location_id) {
+                    changed.push(edit.location_id.clone());
+                }
+            }
+            for location_id in changed {
+                let name = world.locations.get(&location_id).map_or_else(|| location_id.replace('_', " "), |location| location.name.clone());
+                response.push_str(&format!("\n\nYour work on {} has left its mark on the {} for good.", quest.title, name));
+            }
+        }
+    }
+
+    // Being present while a faction meets counts as sitting in on it
+    if !loads_save && response != "QUIT_GAME" {
+        if let Some(meeting) = world.attend_meeting() {
+            player.modify_faction_reputation(meeting.faction, calendar::MEETING_STANDING);
+            response.push_str(&format!(
+                "\n\nYou sit in on the {}. (+{} standing with the {})",
+                meeting.name, calendar::MEETING_STANDING, meeting.faction.display_name()
+            ));
+        }
+    }
+
+    // World events starting or ending reach every system that listens for them;
+    // heavy casting in one place may set off a backlash first
+    if !loads_save && response != "QUIT_GAME" {
+        if casts {
+            crate::core::events::check_backlash(world);
+            let mut rng = world.run.rng();
+            if let Some(alarm) = crate::systems::stealth::check_alarm(player, world, &mut rng) {
+                response.push_str(&format!("\n\n{}", alarm));
+            }
+        }
+        let listeners: &mut [&mut dyn crate::core::events::EventListener] = &mut [&mut *player, &mut *quest_system, &mut world.services];
+        for message in world.event_bus.broadcast(listeners) {
+            response.push_str(&format!("\n\n{}", message));
+        }
+    }
+
+    // Factions send emissaries to the player's laboratory as their regard shifts
+    if !loads_save && response != "QUIT_GAME" {
+        if let Some(message) = crate::systems::emissaries::tick(player, world, faction_system) {
+            response.push_str(&format!("\n\n{}", message));
+        }
+    }
+
+    // Fights won or ended peacefully, and big swings in a faction's regard, get talked about
+    if !loads_save {
+        for (enemy_id, entry) in combat_system.bestiary() {
+            let before = bestiary_before.get(enemy_id).cloned().unwrap_or_default();
+            if entry.defeats == before.defeats && entry.spared == before.spared {
+                continue;
+            }
+            let name = combat_system.find_enemy(enemy_id).map_or_else(|| enemy_id.replace('_', " "), |enemy| enemy.name);
+            let deed = if entry.defeats > before.defeats { Deed::EnemyDefeated(name) } else { Deed::EnemySpared(name) };
+            world.rumors.start(deed, &world.current_location, world.game_time_minutes);
+        }
+        for faction in FactionId::all() {
+            let change = player.faction_reputation(faction) - standings_before.get(&faction).copied().unwrap_or(0);
+            if change >= NOTABLE_STANDING_CHANGE {
+                world.rumors.start(Deed::FactionFavoured(faction), &world.current_location, world.game_time_minutes);
+            } else if change <= -NOTABLE_STANDING_CHANGE {
+                world.rumors.start(Deed::FactionCrossed(faction), &world.current_location, world.game_time_minutes);
+            }
+        }
+
+        // Close friends notice when the player's loyalties pull against them
+        for message in dialogue_system.weigh_loyalties(&player.faction_standings) {
+            response.push_str(&format!("\n\n{}", message));
+        }
+    }
+
+    // Repeatable work returns to the board as its cooldown passes
+    for message in quest_system.refresh_repeatables(world.game_time_minutes) {
+        response.push_str(&format!("\n\n{}", message));
+    }
+
+    // Resolving a story milestone tells the epilogue the player's choices have earned
+    if response != "QUIT_GAME" {
+        if let Some(epilogue) = crate::systems::endings::at_milestone(quest_system, player, faction_system, save_manager.get_save_directory_path()) {
+            response.push_str(&format!("\n\n{}", epilogue));
+        }
+    }
+
+    // Fresh injuries mark a dangerous time, and healers charge more for a while
+    let injuries_after: u32 = player.health.injuries.iter().map(|injury| injury.severity()).sum();
+    if injuries_after > injuries_before {
+        use crate::systems::services::{ServiceKind, PROVIDERS};
+        world.services.crisis(ServiceKind::Healing, injuries_after - injuries_before, &PROVIDERS, world.game_time_minutes);
+    }
+
+    // Tick off the checklist, or nudge a player who seems stuck
+    if world.current_location != location_before {
+        competencies.push(Competency::Moved);
+    }
+    if casts && magic_system.last_cast().is_some_and(|(_, held)| held) {
+        competencies.push(Competency::CastSpell);
+    }
+    if player.knowledge.theory_progress.values().map(|progress| progress.time_invested).sum::<i32>() > study_before {
+        competencies.push(Competency::Studied);
+    }
+    let surroundings = crate::systems::onboarding::Surroundings {
+        has_exits: world.current_location().is_some_and(|location| !location.exits.is_empty()),
+        has_things: world.current_location().is_some_and(|location| !location.items.is_empty() || !location.npcs.is_empty())
+            || !world.ground.at(&world.current_location).is_empty(),
+        in_combat: combat_system.is_in_combat(),
+    };
+    let quitting = response == "QUIT_GAME";
+    if let Some(message) = player.onboarding.observe(&competencies, surroundings).filter(|_| !quitting) {
+        response.push_str(&format!("\n\n{}", message));
+    }
+
+    // Buffs and debuffs whose time has run out
+    for message in world.timed_effects.expire(player, world.game_time_minutes) {
+        response.push_str(&format!("\n\n{}", message));
+    }
+
+    // Porters leave once their contract runs out
+    if let Some(message) = crate::systems::encumbrance::expire(player, world.game_time_minutes) {
+        response.push_str(&format!("\n\n{}", message));
+    }
+
+    // Time spent in a surge storm at the Unstable Resonance Site
+    if let Some(exposure) = crate::systems::site_flux::apply_exposure(player, world) {
+        response.push_str(&format!("\n\n{}", exposure));
+    }
+
+    // Every render path goes through the player's verbosity profile
+    Ok(crate::ui::apply_verbosity(&response, player.preferences.verbosity))
 }
 
 /// Handle quest list command
-fn handle_quest_list(quest_system: &QuestSystem, player: &Player, faction_system: &FactionSystem) -> GameResult<String> {
+fn handle_quest_list(
+    quest_system: &mut QuestSystem,
+    player: &Player,
+    world: &WorldState,
+    dialogue_system: &DialogueSystem,
+    faction_system: &FactionSystem,
+) -> GameResult<String> {
+    // When the authored quests run dry, the people nearby find work for the
+    // player; repeatable work doesn't count, since it never runs dry
+    let authored_available = quest_system.get_available_quests(player, faction_system).iter()
+        .any(|quest| !quest_generator::is_job(&quest.id) && quest.repeat.is_none());
+    if !authored_available {
+        let mut rng = world.run.turn_rng();
+        quest_generator::offer_job(quest_system, player, world, dialogue_system, faction_system, &mut rng);
+    }
+
     let available_quests = quest_system.get_available_quests(player, faction_system);
 
     if available_quests.is_empty() {
@@ -1249,7 +4228,7 @@ fn handle_quest_list(quest_system: &QuestSystem, player: &Player, faction_system
 
     for quest in available_quests {
         response.push_str(&format!(
-            "• {} [{}]\n  {}\n  Difficulty: {:?} | Category: {:?}\n  Estimated time: {} minutes\n\n",
+            "• {} [{}]\n  {}\n  Difficulty: {:?} | Category: {:?}\n  Estimated time: {} minutes\n",
             quest.title,
             quest.id,
             quest.description,
@@ -1257,6 +4236,11 @@ fn handle_quest_list(quest_system: &QuestSystem, player: &Player, faction_system
             quest.category,
             quest.estimated_duration
         ));
+        if let Some(repeat) = &quest.repeat {
+            let completions = quest_system.global_state.repeat_completions.get(&quest.id).copied().unwrap_or(0);
+            response.push_str(&format!("  Repeatable {} | Completed {} time{}\n", repeat.describe(), completions, if completions == 1 { "" } else { "s" }));
+        }
+        response.push('\n');
     }
 
     response.push_str("Use 'quest info <id>' for detailed information about a quest.\n");
@@ -1337,6 +4321,17 @@ fn handle_quest_info(quest_id: String, quest_system: &QuestSystem) -> GameResult
     }
 }
 
+/// Handle pinning a quest to the status display
+fn handle_track_quest(quest: Option<String>, player: &mut Player, quest_system: &QuestSystem) -> GameResult<String> {
+    use crate::systems::quest_journal;
+
+    match quest {
+        Some(query) => quest_journal::track(player, quest_system, &query),
+        None => Ok(quest_journal::tracker_line(player, quest_system)
+            .unwrap_or_else(|| "You have no active quest to track.".to_string())),
+    }
+}
+
 /// Handle quest status command
 fn handle_quest_status(quest_id: String, quest_system: &QuestSystem) -> GameResult<String> {
     quest_system.get_quest_status(&quest_id)
@@ -1391,6 +4386,96 @@ fn handle_quest_choice(
     quest_system.make_quest_choice(&quest_id, &choice_id, &option_id, player, faction_system)
 }
 
+/// Hand a completed quest to its giver, opening the reward negotiation
+fn handle_quest_turn_in(
+    quest_id: String,
+    player: &Player,
+    world: &mut WorldState,
+    quest_system: &QuestSystem,
+    dialogue_system: &DialogueSystem,
+    faction_system: &FactionSystem,
+) -> GameResult<String> {
+    use crate::systems::reward_negotiation::{RewardOffer, CLAIMED_VARIABLE};
+
+    let quest = quest_system.quest_definitions.get(&quest_id)
+        .ok_or_else(|| crate::GameError::ContentNotFound(format!("Quest '{}' not found", quest_id)))?;
+    let progress = quest_system.player_progress.get(&quest_id)
+        .filter(|progress| progress.status == crate::systems::quests::QuestStatus::Completed)
+        .ok_or_else(|| crate::GameError::InvalidCommand(format!("You haven't finished \"{}\" yet.", quest.title)))?;
+    if progress.quest_variables.contains_key(CLAIMED_VARIABLE) {
+        return Err(crate::GameError::InvalidCommand(format!("You've already been rewarded for \"{}\".", quest.title)).into());
+    }
+
+    // The first involved NPC who is here takes the report
+    let here = world.current_location()
+        .ok_or_else(|| crate::GameError::ContentNotFound("Current location not found".to_string()))?;
+    let present = dialogue_system.npcs_present(&here.id, world, quest_system);
+    let giver = quest.involved_npcs.iter()
+        .find_map(|npc_id| present.iter().copied().find(|npc| &npc.id == npc_id));
+    if giver.is_none() && !quest.involved_npcs.is_empty() {
+        let names: Vec<String> = quest.involved_npcs.iter()
+            .map(|npc_id| dialogue_system.get_npc(npc_id).map_or(npc_id.clone(), |npc| npc.name.clone()))
+            .collect();
+        return Err(crate::GameError::InvalidCommand(format!(
+            "There's no one here to turn \"{}\" in to. Find {}.", quest.title, names.join(" or ")
+        )).into());
+    }
+
+    let disposition = giver.map_or(0, |npc| dialogue_system.calculate_disposition(npc, player, faction_system));
+    let offer = RewardOffer::new(quest, &world.current_location, giver, disposition, player);
+    let response = offer.render();
+    world.reward_offer = Some(offer);
+    Ok(response)
+}
+
+/// Settle on a reward mix for the quest being turned in
+fn handle_reward_choice(
+    option: Option<String>,
+    player: &mut Player,
+    world: &mut WorldState,
+    quest_system: &mut QuestSystem,
+    faction_system: &mut FactionSystem,
+    database: &DatabaseManager,
+) -> GameResult<String> {
+    use crate::systems::reward_negotiation::{RewardOption, CLAIMED_VARIABLE};
+
+    let offer = world.reward_offer.as_ref()
+        .filter(|offer| offer.location == world.current_location)
+        .ok_or_else(|| crate::GameError::InvalidCommand(
+            "No one is offering you a reward. Use 'turn in <quest>' once a quest is complete.".to_string()
+        ))?;
+    let Some(option) = option else {
+        return Ok(offer.render());
+    };
+
+    let choice = RewardOption::from_string(&option)
+        .ok_or_else(|| crate::GameError::InvalidInput(format!(
+            "Unknown reward '{}'. Choose standard, silver, standing, insight or item.", option
+        )))?;
+    if !offer.options.contains(&choice) {
+        return Err(crate::GameError::InvalidCommand(format!(
+            "{} won't agree to that. You could ask for: {}.",
+            offer.giver.as_deref().unwrap_or("They"),
+            offer.options.iter().map(|option| option.name()).collect::<Vec<_>>().join(", ")
+        )).into());
+    }
+
+    let quest_id = offer.quest_id.clone();
+    let mut terms = offer.terms(choice);
+    let listed = quest_system.quest_definitions.get(&quest_id).map_or(&[][..], |quest| &quest.rewards.items[..]);
+    for item_id in listed {
+        if let Some(definition) = database.load_item_definition(item_id)? {
+            terms.reward_items.push(definition.instantiate());
+        }
+    }
+    let rewards = quest_system.apply_quest_rewards_with(&quest_id, player, faction_system, &terms)?;
+    if let Some(progress) = quest_system.player_progress.get_mut(&quest_id) {
+        progress.quest_variables.insert(CLAIMED_VARIABLE.to_string(), choice.name().to_string());
+    }
+    world.reward_offer = None;
+    Ok(rewards)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1406,7 +4491,7 @@ mod tests {
     #[test]
     fn test_handle_status() {
         let player = Player::new("Test Player".to_string());
-        let result = handle_status(&player).unwrap();
+        let result = handle_status(&player, &WorldState::new(), &QuestSystem::new()).unwrap();
         assert!(result.contains("Test Player"));
         assert!(result.contains("Mental Acuity:"));
     }
@@ -1441,7 +4526,7 @@ fn handle_save(
         slot.clone(), save_name
     ) {
         Ok(message) => Ok(format!("{}\n\nGame progress saved successfully.", message)),
-        Err(e) => Ok(format!("Failed to save game: {}", e)),
+        Err(e) => Err(crate::GameError::SaveLoadError(format!("Failed to save game: {}", e)).into()),
     }
 }
 
@@ -1457,8 +4542,16 @@ fn handle_load(
     dialogue_system: &mut DialogueSystem,
     magic_system: &mut MagicSystem,
     save_manager: &SaveManager,
+    database: &DatabaseManager,
 ) -> GameResult<String> {
-    let slot_name = slot.unwrap_or_else(|| "autosave".to_string());
+    // With no slot named, the most recent save is the one to go back to
+    let slot_name = match slot {
+        Some(slot) => slot,
+        None => match save_manager.list_save_slots()?.into_iter().next() {
+            Some(latest) => latest.slot_name,
+            None => return Ok("There are no saves to load.".to_string()),
+        },
+    };
 
     match save_manager.load_game(&slot_name) {
         Ok((
@@ -1473,6 +4566,11 @@ fn handle_load(
         )) => {
             *player = loaded_player;
             *world = loaded_world;
+            // Locations follow the current content, with the player's changes on top
+            if let Ok(content) = database.load_locations() {
+                world.merge_content(content);
+            }
+            world.mark_current_visited();
             *quest_system = loaded_quest_system;
             *combat_system = loaded_combat_system;
             *faction_system = loaded_faction_system;
@@ -1484,4 +4582,77 @@ fn handle_load(
         }
         Err(e) => Ok(format!("Failed to load game: {}", e)),
     }
-}
\ No newline at end of file
+}
+
+/// List the saved games, newest first
+fn handle_saves(save_manager: &SaveManager) -> GameResult<String> {
+    let slots = save_manager.list_save_slots()?;
+    if slots.is_empty() {
+        return Ok("You have no saved games yet. Use 'save <name>' to make one.".to_string());
+    }
+
+    let mut response = "=== Saved Games ===".to_string();
+    for slot in &slots {
+        let Some(info) = &slot.info else {
+            response.push_str(&format!("\n\n{} - unreadable", slot.slot_name));
+            continue;
+        };
+        response.push_str(&format!(
+            "\n\n{} - {}\n  Saved {}, {}h {}m played\n  At {}",
+            slot.slot_name,
+            info.save_name,
+            info.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+            info.playtime_minutes / 60,
+            info.playtime_minutes % 60,
+            info.location_name
+        ));
+        if let Some(quest) = &info.active_quest {
+            response.push_str(&format!(", following '{}'", quest));
+        }
+        let backups = save_manager.list_backups(&slot.slot_name).len();
+        if backups > 0 {
+            response.push_str(&format!("\n  {} earlier version{} kept", backups, if backups == 1 { "" } else { "s" }));
+        }
+    }
+    Ok(response)
+}
+
+/// Unpack an archive into a save slot named after the file, unless one is given
+fn handle_import_save(file: &str, slot: Option<String>, save_manager: &SaveManager) -> GameResult<String> {
+    let (slot, info) = save_manager.import_archive(file, slot.as_deref())?;
+    Ok(format!(
+        "Imported {}'s game ({}h {}m played, at {}) into slot '{}'.\nUse 'load {}' to play it.",
+        info.character_name,
+        info.playtime_minutes / 60,
+        info.playtime_minutes % 60,
+        info.location_name,
+        slot,
+        slot
+    ))
+}
+
+/// Handle showing or exporting the run summary
+fn handle_run_summary(
+    export: bool,
+    player: &Player,
+    world: &WorldState,
+    quest_system: &QuestSystem,
+    faction_system: &FactionSystem,
+    save_manager: &SaveManager,
+) -> GameResult<String> {
+    use crate::persistence::RunSummary;
+
+    let summary = RunSummary::from_game_state(player, world, quest_system, faction_system);
+    let mut response = summary.format();
+
+    if export {
+        let path = save_manager.get_save_directory_path()
+            .join(format!("run_summary_{}.json", summary.seed));
+        match summary.export(&path) {
+            Ok(()) => response.push_str(&format!("\n\nSummary exported to {}", path.display())),
+            Err(e) => response.push_str(&format!("\n\nFailed to export summary: {}", e)),
+        }
+    }
+
+    Ok(response)
+}
@@ -1,12 +1,20 @@
 //! Command parser that converts recognized intents into executable commands
 
-use crate::input::natural_language::{InputTokenizer, CommandIntent};
+use crate::input::natural_language::{InputTokenizer, CommandIntent, FREE_TEXT_COMMANDS};
+use crate::input::shortcuts::CommandConfig;
+use crate::input::quantities::split_parameters;
+use crate::input::vocabulary::Vocabulary;
+use crate::GameResult;
 use crate::core::world_state::Direction;
 use serde::{Deserialize, Serialize};
 
 /// Main command parser that processes user input
 pub struct CommandParser {
     tokenizer: InputTokenizer,
+    /// The words the parser knows, with any verbs content packs added
+    vocabulary: Vocabulary,
+    /// The player's aliases and macros
+    commands: CommandConfig,
 }
 
 /// Result of command parsing
@@ -29,19 +37,67 @@ pub enum ParsedCommand {
     /// Examine something in detail
     Examine { target: String },
 
-    /// Cast magic with optional crystal and target
+    /// Search through a piece of scenery
+    Search { target: String },
+
+    /// Show the player's laboratory, or what it would cost
+    Laboratory,
+
+    /// Buy the laboratory off the Practice Hall
+    BuyLaboratory,
+
+    /// Upgrade a fixture in the laboratory
+    UpgradeFixture { fixture: String },
+
+    /// Put an item into laboratory storage
+    StoreItem { item: String },
+
+    /// Take an item out of laboratory storage
+    RetrieveItem { item: String },
+
+    /// Gather what the laboratory's garden plot has grown
+    Harvest,
+
+    /// Hear out or answer a faction emissary at the laboratory
+    Emissary { choice: Option<usize> },
+
+    /// Touch a piece of scenery
+    Touch { target: String },
+
+    /// Cast magic with optional crystal and target, driven at a chosen
+    /// frequency in Hz and sustained for a number of minutes
     CastMagic {
         spell_type: String,
         crystal: Option<String>,
-        target: Option<String>
+        target: Option<String>,
+        frequency: Option<i32>,
+        duration: Option<i32>,
     },
 
     /// Talk to an NPC
     Talk { target: String },
 
-    /// Ask NPC about a topic
+    /// Ask NPC about a topic; an empty target means whoever the player is talking to
     Ask { target: String, topic: String },
 
+    /// Persuade an NPC toward another faction with an argument
+    Persuade { target: String, argument: String },
+
+    /// Choose one of the responses offered by the topic or branch the conversation is on
+    Reply { choice: usize },
+
+    /// Show how the people you've met feel about you
+    Relationships,
+
+    /// List what the player could do here and now
+    Actions,
+
+    /// Find someone with the Resonance Observatory's detection arrays
+    Locate { target: String },
+
+    /// Spend the day with someone close to you, as a friend or courting them
+    SpendTime { target: String, courting: bool },
+
     /// Show inventory
     Inventory,
 
@@ -51,15 +107,129 @@ pub enum ParsedCommand {
     /// Show crystal status
     CrystalStatus,
 
+    /// Show the material components a spell can use
+    Components { spell: String },
+
+    /// Prepare a ward before a fight, or list prepared wards
+    Ward { kind: Option<String> },
+
+    /// Learn a faction casting style from one of its mentors
+    LearnStyle { style: String, mentor: String },
+
+    /// Show known casting styles, or switch to one
+    Style { style: Option<String> },
+
     /// Show faction standings
     FactionStatus,
 
+    /// Show faction influence meters and the projected epilogue
+    Legacy,
+
     /// Save the game
     Save { slot: Option<String> },
 
     /// Load a saved game
     Load { slot: Option<String> },
 
+    /// List the saved games, with when, where and how far along each was saved
+    Saves,
+
+    /// Show the run summary, optionally exporting it to a file
+    RunSummary { export: bool },
+
+    /// Pack the game into a portable, checksummed archive
+    ExportSave { file: String },
+
+    /// Unpack an archive into a save slot, named after the file unless given
+    ImportSave { file: String, slot: Option<String> },
+
+    /// Re-read world content into the running game, first importing the
+    /// JSON content files in a directory if given (debug mode only)
+    ReloadContent { from: Option<String> },
+
+    /// Show discovered enemies, or details of one
+    Bestiary { enemy: Option<String> },
+
+    /// Show codex completion across saves, or change whether this save shows spoilers
+    Codex { setting: Option<String> },
+
+    /// Show the full log of the current or most recent fight
+    CombatLog,
+
+    /// Show what this character has done, in every category or one
+    Stats { category: Option<String> },
+
+    /// Ask an NPC to join the party
+    Recruit { target: String },
+
+    /// Part ways with a companion or send a follower home
+    Dismiss { target: String },
+
+    /// Show the party roster
+    Party,
+
+    /// Ask an NPC to travel with you outside combat
+    Invite { target: String },
+
+    /// Show who is travelling with you and who is waiting behind
+    Followers,
+
+    /// Hotseat co-op: link an apprentice, start or end a shared segment
+    Coop { action: Option<String> },
+
+    /// Hire or dismiss a porter; with no action, show the load being carried
+    Porter { action: Option<String> },
+
+    /// Show or change what happens when the player is defeated
+    DefeatPolicy { policy: Option<String> },
+
+    /// Show or change how hard fights are
+    Difficulty { level: Option<String> },
+
+    /// Explain a scientific concept, or list encountered concepts
+    Explain { concept: Option<String> },
+
+    /// Run the game's formulas through the calculator
+    Calculate { query: Option<String> },
+
+    /// Show the ASCII map of explored locations
+    ShowMap,
+
+    /// Attach a note to the current location on the map
+    Annotate { marker: Option<char>, note: String },
+
+    /// Remove notes from the current location
+    ClearAnnotations,
+
+    /// Show or change the text verbosity profile
+    SetVerbosity { level: Option<String> },
+    /// Show or change the colour theme
+    SetTheme { theme: Option<String> },
+
+    /// Show or change whether fast travel is allowed, or movement is manual only
+    SetNavigation { setting: Option<String> },
+
+    /// Show or change what the input prompt displays
+    SetPrompt { setting: Option<String> },
+
+    /// List, add or remove command shortcuts
+    Shortcuts { setting: Option<String> },
+
+    /// List, add or remove aliases kept across characters
+    Alias { setting: Option<String> },
+
+    /// List, add or remove macros that run several commands in turn
+    Macro { setting: Option<String> },
+
+    /// Take back the last few actions
+    Undo { steps: usize },
+
+    /// Show or change how many actions undo can take back
+    UndoLimit { limit: Option<usize> },
+
+    /// Show, dismiss or restore the getting-started checklist
+    Checklist { action: Option<String> },
+
     /// Show help
     Help { topic: Option<String> },
 
@@ -69,15 +239,27 @@ pub enum ParsedCommand {
     /// Rest to recover energy
     Rest,
 
+    /// Rest in long stretches until recovered or interrupted
+    RestUntilRecovered,
+
     /// Meditate for faster recovery
     Meditate,
 
     /// Study a magic theory
-    Study { theory: String },
+    Study { theory: String, duration: Option<i32> },
 
     /// Research a new topic
     Research { topic: String },
 
+    /// Research a topic in the Crystalline Archives catalog
+    ArchiveResearch { topic: String },
+
+    /// Start, review or abandon a theory assessment
+    Assess { theory: Option<String> },
+
+    /// Answer the current assessment step
+    AssessmentAnswer { answer: String },
+
     /// Quest-related commands
     /// Show available quests
     QuestList,
@@ -85,6 +267,21 @@ pub enum ParsedCommand {
     /// Show active quests
     QuestActive,
 
+    /// Show the quest journal: active quests with their objectives
+    QuestJournal,
+
+    /// Show a single quest's journal page
+    QuestPage { quest_id: String },
+
+    /// Show the tracked quest, or pin a quest to the status display
+    TrackQuest { quest: Option<String> },
+
+    /// Ask for a hint on the next step of the tracked quest, or a named one
+    Hint { quest: Option<String> },
+
+    /// Show the endings reached across every save
+    Endings,
+
     /// Show quest details
     QuestInfo { quest_id: String },
 
@@ -103,12 +300,82 @@ pub enum ParsedCommand {
     /// Make a quest choice
     QuestChoice { quest_id: String, choice_id: String, option_id: String },
 
+    /// Turn in a completed quest to its giver
+    QuestTurnIn { quest_id: String },
+
+    /// Settle on a reward for the quest being turned in
+    RewardChoice { option: Option<String> },
+
     /// Take an item
     Take { item: String },
 
     /// Drop an item
     Drop { item: String },
 
+    /// Hide an item in a cache at the current location
+    Hide { item: String },
+
+    /// List hidden caches
+    Caches,
+
+    /// Read the Unstable Resonance Site's flux
+    ReadFlux,
+
+    /// Read a retired character's lab notebook lying here
+    ReadNotebook,
+
+    /// Retire the character, optionally leaving their notebook for later characters
+    Retire { leave_notes: Option<bool> },
+
+    /// Repair an item, optionally paying a craftsperson; with no item, list worn gear
+    Repair { item: Option<String>, npc: Option<String> },
+
+    /// Appraise an unidentified item with a tool or a person; with no item, list unidentified items
+    Appraise { item: Option<String>, with: Option<String> },
+
+    /// Switch fast analysis on or off; with no setting, show what it covers
+    FastAnalysis { enabled: Option<bool> },
+
+    /// Clean up a phenomenon left by a failed experiment
+    CleanUp { target: Option<String> },
+
+    /// List the paid services offered here and their current prices
+    Services,
+
+    /// Buy a service such as healing or training from an NPC
+    BuyService { service: String, npc: String },
+
+    /// Bargain with a service provider over their prices
+    Haggle { npc: String },
+
+    /// Show the date, time and upcoming faction meetings
+    Calendar,
+
+    /// Show the weather, and forecast it from the Observatory
+    Forecast,
+
+    /// Travel by road to another region, or walk to a place already visited;
+    /// with no destination, list the roads
+    Travel { destination: Option<String> },
+
+    /// Open a lock on an exit with a key, know-how or the answer to its puzzle
+    Unlock { direction: Direction, with: Option<String> },
+
+    /// Try to pick the lock on an exit
+    PickLock { direction: Direction },
+
+    /// Show the guards your standing, at one exit or whichever is guarded
+    ShowCredentials { direction: Option<Direction> },
+
+    /// Try to slip unseen past whoever keeps watch over a way
+    Sneak { direction: Direction },
+
+    /// Show the bounties the factions hold against you
+    Wanted,
+
+    /// Pay off a faction's bounty
+    PayBounty { faction: String },
+
     /// Equip a crystal
     Equip { crystal: String },
 
@@ -121,6 +388,15 @@ pub enum ParsedCommand {
     /// Combine/craft items
     CraftItem { action: String, items: Vec<String>, recipe: Option<String> },
 
+    /// Combine two carried items
+    Combine { first: String, second: String },
+
+    /// Take apart an item made by combination
+    Deconstruct { item: String },
+
+    /// List crafting recipes and what they need
+    Recipes,
+
     /// Examine an item in detail
     ExamineItem { item: String },
 
@@ -136,6 +412,9 @@ pub enum ParsedCommand {
     /// Flee from combat
     Flee,
 
+    /// End combat with a non-lethal tactic (parley, intimidate, demoralize, surrender)
+    CombatTactic { tactic: String },
+
     /// Examine enemy during combat
     ExamineEnemy,
 
@@ -149,9 +428,74 @@ pub enum ParsedCommand {
 impl CommandParser {
     /// Create a new command parser
     pub fn new() -> Self {
+        Self::with_commands(CommandConfig::default())
+    }
+
+    /// Create a parser that knows the player's aliases and macros
+    pub fn with_commands(commands: CommandConfig) -> Self {
+        let vocabulary = Vocabulary::built_in();
         Self {
-            tokenizer: InputTokenizer::new(),
+            tokenizer: InputTokenizer::with_vocabulary(&vocabulary),
+            vocabulary,
+            commands,
+        }
+    }
+
+    /// Teach the parser a verb standing for a command it already has
+    pub fn add_verb(&mut self, word: &str, meaning: &str) -> GameResult<()> {
+        self.vocabulary.add_verb(word, meaning)?;
+        self.tokenizer = InputTokenizer::with_vocabulary(&self.vocabulary);
+        Ok(())
+    }
+
+    /// The player's aliases and macros, to change them
+    pub fn commands_mut(&mut self) -> &mut CommandConfig {
+        &mut self.commands
+    }
+
+    /// Split compound input into the commands to run in turn: "go north then
+    /// examine the array and talk to lyra". Semicolons and "then" always
+    /// split; "and" only when a command verb follows, so names like "mortar
+    /// and pestle" stay whole.
+    pub fn split_chain(&self, input: &str) -> Vec<String> {
+        let lowered = input.trim().to_lowercase();
+        if FREE_TEXT_COMMANDS.iter().any(|command| lowered.starts_with(command)) {
+            return vec![input.trim().to_string()];
+        }
+
+        let mut commands = Vec::new();
+        let mut current: Vec<&str> = Vec::new();
+        let mut finish = |current: &mut Vec<&str>| {
+            if !current.is_empty() {
+                commands.push(std::mem::take(current).join(" "));
+            }
+        };
+        let mut words = input.split_whitespace().peekable();
+        while let Some(word) = words.next() {
+            let next = words.peek().map(|next| next.to_lowercase());
+            let next_is_verb = next.as_deref().is_some_and(|next| next == "then" || crate::input::completion::VERBS.contains(&next) || self.vocabulary.is_added_verb(next));
+            let (word, ends_clause) = match word.strip_suffix(';') {
+                Some(stripped) => (stripped, true),
+                None => (word.strip_suffix(',').filter(|_| next_is_verb || next.as_deref() == Some("and")).unwrap_or(word), false),
+            };
+            match word.to_lowercase().as_str() {
+                "then" => finish(&mut current),
+                "and" if next_is_verb => finish(&mut current),
+                "" => {}
+                _ => current.push(word),
+            }
+            if ends_clause {
+                finish(&mut current);
+            }
         }
+        finish(&mut current);
+        commands
+    }
+
+    /// The commands some input stands for, with shortcuts, aliases and macros
+    /// expanded, ready to be parsed in turn
+    pub fn expand(&self, input: &str, shortcuts: &std::collections::BTreeMap<String, String>) -> Vec<String> {
+        self.commands.expand(input, shortcuts)
     }
 
     /// Parse raw input into a command
@@ -172,12 +516,8 @@ impl CommandParser {
                 self.parse_examination(target)
             }
 
-            CommandIntent::Magic { spell_type, crystal, target } => {
-                CommandResult::Success(ParsedCommand::CastMagic {
-                    spell_type,
-                    crystal,
-                    target,
-                })
+            CommandIntent::Magic { .. } => {
+                self.parse_magic(input)
             }
 
             CommandIntent::Social { action, target } => {
@@ -214,6 +554,28 @@ impl CommandParser {
         }
     }
 
+    /// Parse a cast, taking any frequency and duration out before the
+    /// spell, crystal and target are read
+    fn parse_magic(&self, input: &str) -> CommandResult {
+        let (rest, parameters) = match split_parameters(&input.to_lowercase()) {
+            Ok(split) => split,
+            Err(message) => return CommandResult::Error(message),
+        };
+        let tokens = self.tokenizer.tokenize(&rest);
+        match self.tokenizer.recognize_intent(&tokens) {
+            CommandIntent::Magic { spell_type, crystal, target } => {
+                CommandResult::Success(ParsedCommand::CastMagic {
+                    spell_type,
+                    crystal,
+                    target,
+                    frequency: parameters.frequency,
+                    duration: parameters.duration,
+                })
+            }
+            _ => CommandResult::Error("What do you want to cast?".to_string()),
+        }
+    }
+
     /// Parse movement commands
     fn parse_movement(&self, direction_str: String) -> CommandResult {
         match Direction::from_string(&direction_str) {
@@ -282,7 +644,8 @@ impl CommandParser {
             ["quit"] | ["exit"] => CommandResult::Success(ParsedCommand::Quit),
 
             // Quest commands
-            ["quest", "list"] | ["quests"] => CommandResult::Success(ParsedCommand::QuestList),
+            ["quest", "list"] => CommandResult::Success(ParsedCommand::QuestList),
+            ["quests"] => CommandResult::Success(ParsedCommand::QuestJournal),
             ["quest", "active"] => CommandResult::Success(ParsedCommand::QuestActive),
             ["quest", "recommendations"] => CommandResult::Success(ParsedCommand::QuestRecommendations),
             ["quest", "info", quest_id] => CommandResult::Success(ParsedCommand::QuestInfo { quest_id: quest_id.to_string() }),
@@ -294,6 +657,7 @@ impl CommandParser {
                 choice_id: choice_id.to_string(),
                 option_id: option_id.to_string()
             }),
+            ["quest", quest_id] => CommandResult::Success(ParsedCommand::QuestPage { quest_id: quest_id.to_string() }),
 
             _ => CommandResult::Error(format!("Unknown system command: {}", command)),
         }
@@ -306,7 +670,18 @@ impl CommandParser {
                 "Movement Commands:\n\
                  • north, south, east, west (or n, s, e, w)\n\
                  • up, down, in, out\n\
-                 • go <direction>\n\n\
+                 • go <direction>\n\
+                 • map - Show a map of the places you have explored, with locked and secret doorways marked\n\
+                 • annotate <note> - Leave a note on the map at your location\n\
+                 • mark <symbol> <note> - Leave a note with a custom map symbol\n\
+                 • clear annotations - Remove your notes here\n\
+                 • roads - See the roads out of this region\n\
+                 • travel to <region> - Take the road to another region\n\
+                 • travel to <place> - Walk to somewhere you've been by the shortest open way\n\
+                 • unlock <direction> [with <answer>] - Open a lock with its key, your know-how, or the answer to its puzzle\n\
+                 • pick lock <direction> - Try to pick a locked door (takes time)\n\
+                 • show credentials - Show the guards your standing with their faction\n\
+                 • sneak <direction> - Try to slip past guards into a place a faction keeps to itself\n\n\
                  Examples:\n\
                  • north\n\
                  • go east\n\
@@ -316,33 +691,103 @@ impl CommandParser {
             Some("magic") => {
                 "Magic Commands:\n\
                  • cast <spell> using <crystal> on <target>\n\
+                 • cast <spell> at <N> hz for <N> minutes - Drive your crystal up to 2 Hz off its own frequency, or hold the spell longer\n\
                  • examine <crystal>\n\
-                 • study <theory>\n\
-                 • research <topic>\n\n\
+                 • study <theory> [for <N> hours] - Study for 10 minutes to 4 hours (30 minutes if not given)\n\
+                 • research <topic>\n\
+                 • research <topic> in archives - Search the Crystalline Archives\n\
+                 • assess [theory] - Take a certification assessment\n\
+                 • answer <response> - Answer the current assessment step\n\
+                 • assess abandon - Give up the current assessment\n\
+                 • explain [concept] - Explain a scientific concept you have encountered\n\
+                 • calculate <resonance|cost|learning> ... - Run the formulas behind the magic\n\
+                 • components for <spell> - Materials that strengthen a spell, and substitutes\n\n\
                  Examples:\n\
                  • cast healing using amethyst on guard\n\
                  • cast light using quartz\n\
+                 • cast light at 5 hz for 20 minutes\n\
                  • examine my crystals\n\
-                 • study harmonic fundamentals"
+                 • study harmonic fundamentals\n\
+                 • study harmonic_fundamentals for 2 hours\n\
+                 • assess harmonic_fundamentals"
             }
 
             Some("social") => {
                 "Social Commands:\n\
-                 • talk to <person>\n\
-                 • ask <person> about <topic>\n\
-                 • faction status\n\n\
+                 • talk to <person> - Use an ID or any part of their name\n\
+                 • ask <person> about <topic> - Or just 'ask about <topic>' to keep talking to the same person\n\
+                 • reply <number> - Answer with one of the responses a topic offers; some test persuasion, influence or theory, and some lead the conversation further\n\
+                 • give <item> to <person> - Offer a gift; people remember kindness\n\
+                 • relationships - See how the people you've met feel about you\n\
+                 • locate <person> - Find someone with the Resonance Observatory's detection arrays\n\
+                 • spend time with <person> - Get closer to a friend; some have a story to share\n\
+                 • court <person> - Spend time with someone as more than a friend\n\
+                 • persuade <person> with <argument> - Win someone over to another faction\n\
+                 • recruit <person> - Ask someone to fight alongside you\n\
+                 • learn <style> from <mentor> - Learn a faction's casting style (council, harmony, underground)\n\
+                 • style [name] - Show your casting styles or switch between them\n\
+                 • invite <person> - Ask a research partner, student or bodyguard to travel with you\n\
+                 • followers - See who is travelling with you, and who is waiting behind\n\
+                 • dismiss <companion> - Part ways with a companion or send a follower home\n\
+                 • services - See who offers healing, training or repairs here, and their prices\n\
+                 • buy <healing|training> from <person> - Pay for a service\n\
+                 • haggle with <person> - Bargain for a better price on your next service\n\
+                 • calendar - See the date, the time, when the factions next meet, and the city's events\n\
+                 • forecast - Check the weather; at the Observatory, read what's coming\n\
+                 • party - Show your companions\n\
+                 • faction status\n\
+                 • legacy - See how far you've advanced each faction's goals\n\
+                 • wanted - See the bounties the factions have put on you\n\
+                 • pay bounty <faction> - Clear your name with a faction in silver\n\n\
+                 Arguments: safety, harmony, progress, freedom, knowledge\n\n\
+                 People keep their own routines - if someone is away or busy,\n\
+                 you'll be told where they are and when they'll be free.\n\n\
+                 Guards can be hired for silver; students and faction agents only\n\
+                 join players in good standing. Companions rest when you do.\n\n\
                  Examples:\n\
-                 • talk to scholar\n\
+                 • talk to gareth\n\
                  • ask merchant about crystals\n\
+                 • persuade mage_kira with safety\n\
+                 • recruit vera\n\
                  • faction status"
             }
 
             Some("system") => {
                 "System Commands:\n\
-                 • save [slot] - Save your game\n\
-                 • load [slot] - Load a saved game\n\
+                 • save [slot] - Save your game to a named slot (quicksave if not given); the slot's last few versions are kept as backups\n\
+                 • load [slot] - Load a saved game, or the most recent save if no slot is given\n\
+                 • saves - List your saves with when, where and how far along each was made\n\
+                 • stats [spells|money|travel|conversations|combat] - What this character has done, kept across sessions\n\
                  • status - Show character information\n\
                  • inventory - Show your items\n\
+                 • rest - Rest for an hour\n\
+                 • rest until recovered - Rest until recovered, waking early if something happens\n\
+                 • summary - Show the run summary and its checksum\n\
+                 • export summary - Write the run summary to a file for sharing\n\
+                 • export save <name> - Pack your game into a compressed, checksummed archive in your exports folder to move it or attach it to a bug report\n\
+                 • import save <name> [as <slot>] - Unpack an archive from your exports folder into a save slot, then 'load' it\n\
+                 • reload content [from <dir>] - Debug: re-read locations, NPCs, theories and quests without restarting\n\
+                 • retire [with notes|quietly] - End this character's journey, optionally leaving your notebook\n\
+                 • read notebook - Read a notebook a retired character left here\n\
+                 • verbosity [terse|standard|rich] - Set how much descriptive text is shown\n\
+                 • theme [standard|high-contrast|monochrome|solarized] - Set the colour theme\n\
+                 • navigation [fast|manual] - Allow fast travel, or find every way yourself\n\
+                 • prompt [location time energy quest|off] - Choose what the input prompt shows\n\
+                 • prompt track <quest> - Choose the quest the prompt follows\n\
+                 • shortcuts - List command shortcuts such as 'x' for examine\n\
+                 • shortcut add <key> <command> | shortcut remove <key> - Manage your own shortcuts\n\
+                 • alias <key> <command> | alias remove <key> - Shortcuts kept for every character\n\
+                 • macro <name> <command>; <command> | macro remove <name> - Run several commands with one word\n\
+                 • Up and down arrows recall earlier commands; tab completes commands, items, people, exits and theories\n\
+                 • undo [n] - Take back your last action, or the last n (not during a fight)\n\
+                 • undo limit [n] - Show or change how many actions undo can take back\n\
+                 • Chain commands with 'then', 'and' or ';' (go north then examine the array); a chain stops at the first that fails\n\
+                 • checklist [dismiss|show] - Review the getting-started checklist, or hide its reminders\n\
+                 • defeat policy [reload|capture|rescue|permadeath] - Choose what happens when you fall\n\
+                 • difficulty [story|normal|hard|nightmare] - Choose how hard fights are\n\
+                 • coop link <name> - Let a second player join as your linked apprentice\n\
+                 • coop ritual | coop expedition - Take turns, one command each\n\
+                 • coop end | coop unlink | coop - Finish the segment, release the apprentice, or show the link\n\
                  • quit - Exit the game\n\n\
                  Examples:\n\
                  • save\n\
@@ -351,6 +796,11 @@ impl CommandParser {
             }
             Some("quests") | Some("quest") => {
                 "Quest Commands:\n\
+                 • quests - Open your journal: active quests and their objectives\n\
+                 • quest <id> - Read a quest's journal page\n\
+                 • track <quest> | track off - Pin a quest to your status display\n\
+                 • hint [quest] - Get a nudge toward the next step; ask again for a clearer one\n\
+                 • endings - See the endings you've reached and hints at the rest\n\
                  • quest list - Show all available quests\n\
                  • quest active - Show your active quests\n\
                  • quest info <id> - Show detailed quest information\n\
@@ -358,7 +808,11 @@ impl CommandParser {
                  • quest choose <quest_id> <choice_id> <option_id> - Make a quest choice\n\
                  • quest start <id> - Start a quest\n\
                  • quest abandon <id> - Abandon a quest\n\
+                 • turn in <id> - Hand a completed quest to its giver and hear their offer\n\
+                 • reward <standard|silver|standing|insight|item> - Settle on a reward mix\n\
                  • quest recommendations - Get quest suggestions\n\n\
+                 What a quest giver will agree to depends on how they feel about you and on\n\
+                 your understanding of Mental Resonance.\n\n\
                  Examples:\n\
                  • quest list\n\
                  • quest start resonance_foundation\n\
@@ -366,25 +820,68 @@ impl CommandParser {
                  • quest recommendations"
             }
 
+            Some("combat") | Some("fight") => {
+                "Combat Commands:\n\
+                 Not every fight has to end in a kill. Each tactic is a skill check\n\
+                 that costs your turn if it fails, and gets harder with every failure.\n\n\
+                 • parley - Negotiate a truce; easier with good standing in the enemy's faction\n\
+                 • intimidate - Frighten the enemy into fleeing; its faction will resent it\n\
+                 • demoralize - Convince a battered enemy to yield what it carries\n\
+                 • surrender - Always accepted, but costs a third of your silver\n\
+                 • combat log - Every roll, damage calculation and cost from your last fight\n\
+                 • ward shield - Raise shield charges on yourself before a fight\n\
+                 • ward dampener - Anchor a resonance dampener here to weaken enemies\n\
+                 • wards - List the wards you have prepared\n\
+                 • bestiary [enemy] - Review the enemies you have encountered\n\
+                 • read flux - Read the signs of the Unstable Resonance Site's flux cycle\n\n\
+                 Examples:\n\
+                 • parley\n\
+                 • demoralize"
+            }
+
             Some("examination") | Some("look") => {
                 "Examination Commands:\n\
                  • look - Look around current location\n\
+                 • actions - List what you could do here: ways out, people, items, quest steps and theories\n\
                  • examine <target> - Examine something closely\n\
-                 • analyze <target> - Magical analysis\n\n\
+                 • search <target> - Search through something here, such as rubble or a desk\n\
+                 • touch <target> - Touch something here, such as a crystal formation\n\
+                 • analyze <target> - Magical analysis\n\
+                 • bestiary [enemy] - Review the enemies you have encountered\n\
+                 • codex - Lore, bestiary and frequency compendium completion across all your saves\n\
+                 • codex spoilers off - Show only this character's discoveries in this save\n\n\
                  Examples:\n\
                  • look\n\
                  • examine crystal formation\n\
-                 • analyze magical signature"
+                 • analyze magical signature\n\n\
+                 Unidentified finds can also be read with 'cast detection on <item>'\n\
+                 once you understand Detection Arrays."
             }
 
             Some("items") | Some("item") => {
                 "Item Commands:\n\
                  • take <item> - Pick up an item\n\
-                 • drop <item> - Drop an item from inventory\n\
+                 • drop <item> - Drop an item from inventory; it stays here, but may be scavenged while you're away\n\
+                 • hide <item> - Stash an item in a cache here; well-hidden caches are never found\n\
+                 • caches - List your hidden caches\n\
+                 • laboratory - Your own laboratory: its fixtures, storage and upgrades\n\
+                 • buy laboratory - Take on the disused laboratory off the Practice Hall\n\
+                 • upgrade <storage|bench|garden|library> - Fit out your laboratory further\n\
+                 • store <item> / retrieve <item> - Keep items in your laboratory's storage\n\
+                 • harvest - Gather what your laboratory's garden plot has grown\n\
+                 • emissary [number] - Hear out a faction emissary at your laboratory, or answer them; with none waiting, past visits\n\
+                 • repair [item] [with <person>] - List worn gear, or mend an item yourself or for silver\n\
+                 • appraise [item] [with <tool|person>] - List unidentified finds, or learn what one really is\n\
+                 • fast analysis [on|off] - Once Detection Arrays is mastered, identify finds at a glance\n\
+                 • load - How heavy your pack is; a heavy pack slows travel and tires you faster\n\
+                 • hire porter [for <days>] - Hire someone to carry 25 kg more (Practice Hall, Diplomacy Hall)\n\
+                 • dismiss porter - Send your porter away\n\
                  • give <item> to <person> - Give an item to someone\n\
                  • use <item> - Use or consume an item\n\
                  • hold <item> - Hold an item (same as take)\n\
                  • get <item> - Get an item (same as take)\n\n\
+                 If a name fits several different things, you'll be asked which one you\n\
+                 meant: answer with its number or name, or 'cancel'.\n\n\
                  Examples:\n\
                  • take sword\n\
                  • drop healing potion\n\
@@ -398,7 +895,8 @@ impl CommandParser {
                  • unequip <item> - Remove equipped item\n\
                  • wear <item> - Wear equipment (same as equip)\n\
                  • remove <item> - Remove equipment (same as unequip)\n\
-                 • wield <item> - Wield a weapon (same as equip)\n\n\
+                 • wield <item> - Wield a weapon (same as equip)\n\
+                 • repair <item> - Mend worn equipment; broken equipment gives no bonuses\n\n\
                  Examples:\n\
                  • equip crystal sword\n\
                  • wear helmet\n\
@@ -408,36 +906,41 @@ impl CommandParser {
 
             Some("crafting") | Some("craft") => {
                 "Crafting Commands:\n\
-                 • combine <item1> with <item2> - Combine items together\n\
+                 • recipes - List recipes, what they need and your chance of success\n\
                  • craft <recipe> - Create item using recipe\n\
-                 • create <item> - Create item from components\n\
-                 • synthesize <items> - Synthesize magical items\n\
-                 • enhance <item> - Improve existing item\n\
-                 • repair <item> - Fix damaged item\n\n\
+                 • combine <item1> with <item2> - Join two items, or craft the recipe that uses exactly them\n\
+                 • deconstruct <item> - Take apart a combined item, salvaging some of its parts\n\
+                 • create <item> - Create item from components\n\n\
+                 Recipes consume their ingredients, need any listed tools in your pack, and some\n\
+                 can only be made at a station in a particular location. Understanding the\n\
+                 recipe's theory improves both the odds and the quality of the result.\n\n\
+                 A badly botched attempt can foul the station or leave a wild resonance pocket\n\
+                 behind. 'clean up [it]' deals with the mess: benches need resonant solvent,\n\
+                 pockets are grounded through your own focus.\n\n\
                  Examples:\n\
-                 • combine crystal dust with water\n\
-                 • craft healing potion\n\
-                 • synthesize resonance crystal\n\
-                 • enhance sword with crystal"
+                 • craft mortar and pestle\n\
+                 • craft healing salve\n\
+                 • combine crystal fragment with spring water"
             }
 
             None => {
                 "Available Commands:\n\n\
-                 Movement: north, south, east, west, up, down, go <direction>\n\
-                 Examination: look, examine <target>, analyze <target>\n\
-                 Items: take <item>, drop <item>, give <item> to <person>, use <item>\n\
+                 Movement: north, south, east, west, up, down, go <direction>, map, annotate <note>, travel to <region or place>, unlock <direction>, sneak <direction>\n\
+                 Examination: look, actions, examine <target>, search <target>, touch <target>, analyze <target>, bestiary, codex\n\
+                 Items: take <item>, drop <item>, give <item> to <person>, use <item>, appraise <item>, laboratory\n\
                  Equipment: equip <item>, unequip <item>, wear <item>, remove <item>\n\
-                 Crafting: combine <items>, craft <recipe>, create <item>, synthesize <items>\n\
-                 Magic: cast <spell> using <crystal>, study <theory>, research <topic>\n\
-                 Social: talk to <person>, ask <person> about <topic>, faction status\n\
-                 Quests: quest list, quest start <id>, quest status <id>, quest recommendations\n\
-                 System: save [slot], load [slot], status, inventory, quit\n\n\
+                 Crafting: combine <item> with <item>, deconstruct <item>, craft <recipe>, create <item>, synthesize <items>, clean up\n\
+                 Magic: cast <spell> using <crystal>, study <theory>, research <topic>, assess [theory], explain <concept>\n\
+                 Social: talk to <person>, ask <person> about <topic>, reply <number>, persuade <person> with <argument>, recruit <person>, party, services, haggle with <person>, faction status, legacy, wanted\n\
+                 Quests: quest list, quest start <id>, quest status <id>, turn in <id>, quest recommendations\n\
+                 Combat: parley, intimidate, demoralize, surrender\n\
+                 System: save [slot], load [slot], saves, stats, status, inventory, summary, verbosity, theme, navigation, prompt, alias, macro, checklist, quit\n\n\
                  For detailed help on a topic, type: help <topic>\n\
-                 Available topics: movement, magic, social, system, examination, quests, items, equipment, crafting"
+                 Available topics: movement, magic, social, system, examination, quests, combat, items, equipment, crafting"
             }
 
             Some(unknown) => {
-                &format!("No help available for '{}'. Available topics: movement, magic, social, system, examination, quests, items, equipment, crafting", unknown)
+                &format!("No help available for '{}'. Available topics: movement, magic, social, system, examination, quests, combat, items, equipment, crafting", unknown)
             }
         };
 
@@ -587,7 +1090,8 @@ impl CommandParser {
 
     /// Parse advanced commands with multiple parameters
     pub fn parse_advanced(&self, input: &str) -> CommandResult {
-        let trimmed = input.trim().to_lowercase();
+        // Verbs from content packs stand for commands the parser already knows
+        let trimmed = self.vocabulary.rewrite(&input.trim().to_lowercase());
 
         // Handle complex multi-word commands
         if trimmed.starts_with("save ") {
@@ -605,11 +1109,17 @@ impl CommandParser {
         }
 
         if trimmed.starts_with("study ") {
-            let theory = trimmed[6..].trim().to_string();
+            let (theory, parameters) = match split_parameters(&trimmed[6..]) {
+                Ok(split) => split,
+                Err(message) => return CommandResult::Error(message),
+            };
             if theory.is_empty() {
                 return CommandResult::Error("What theory do you want to study?".to_string());
             }
-            return CommandResult::Success(ParsedCommand::Study { theory });
+            if parameters.frequency.is_some() {
+                return CommandResult::Error("Studying doesn't take a frequency; try 'study <theory> for 2 hours'.".to_string());
+            }
+            return CommandResult::Success(ParsedCommand::Study { theory, duration: parameters.duration });
         }
 
         if trimmed.starts_with("research ") {
@@ -617,9 +1127,98 @@ impl CommandParser {
             if topic.is_empty() {
                 return CommandResult::Error("What do you want to research?".to_string());
             }
+            for suffix in [" in the archives", " in archives"] {
+                if let Some(archive_topic) = topic.strip_suffix(suffix) {
+                    return CommandResult::Success(ParsedCommand::ArchiveResearch {
+                        topic: archive_topic.trim().to_string()
+                    });
+                }
+            }
             return CommandResult::Success(ParsedCommand::Research { topic });
         }
 
+        if let Some(rest) = trimmed.strip_prefix("assess ") {
+            let theory = rest.trim().replace(' ', "_");
+            return CommandResult::Success(ParsedCommand::Assess {
+                theory: if theory.is_empty() { None } else { Some(theory) }
+            });
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("answer ") {
+            let answer = rest.trim().to_string();
+            if answer.is_empty() {
+                return CommandResult::Error("What is your answer?".to_string());
+            }
+            return CommandResult::Success(ParsedCommand::AssessmentAnswer { answer });
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("reply ").or_else(|| trimmed.strip_prefix("respond ")) {
+            // Options can be picked by number or by letter, so "reply b" is the second
+            let rest = rest.trim();
+            let letter = match rest.as_bytes() {
+                [letter @ b'a'..=b'z'] => Some((letter - b'a') as usize + 1),
+                _ => None,
+            };
+            return match rest.parse::<usize>().ok().or(letter) {
+                Some(choice) => CommandResult::Success(ParsedCommand::Reply { choice }),
+                None => CommandResult::Error("Use: reply <number>".to_string()),
+            };
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("ask about ") {
+            let topic = rest.trim().to_string();
+            if topic.is_empty() {
+                return CommandResult::Error("What do you want to ask about?".to_string());
+            }
+            return CommandResult::Success(ParsedCommand::Ask { target: String::new(), topic });
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("locate ") {
+            let target = rest.trim().to_string();
+            if target.is_empty() {
+                return CommandResult::Error("Who do you want to locate?".to_string());
+            }
+            return CommandResult::Success(ParsedCommand::Locate { target });
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("spend time with ").or_else(|| trimmed.strip_prefix("court ")) {
+            let target = rest.trim().to_string();
+            if target.is_empty() {
+                return CommandResult::Error("Who do you want to spend time with?".to_string());
+            }
+            let courting = trimmed.starts_with("court ");
+            return CommandResult::Success(ParsedCommand::SpendTime { target, courting });
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("persuade ") {
+            let rest = rest.trim();
+            let (target, argument) = if let Some(pos) = rest.find(" with ") {
+                (rest[..pos].trim(), rest[pos + 6..].trim())
+            } else if let Some(pos) = rest.rfind(' ') {
+                (rest[..pos].trim(), rest[pos + 1..].trim())
+            } else {
+                (rest, "")
+            };
+            if target.is_empty() || argument.is_empty() {
+                return CommandResult::Error("Use: persuade <person> with <argument>".to_string());
+            }
+            return CommandResult::Success(ParsedCommand::Persuade {
+                target: target.to_string(),
+                argument: argument.to_string(),
+            });
+        }
+
+        if let Some(item) = trimmed.strip_prefix("take apart ")
+            .or_else(|| trimmed.strip_prefix("deconstruct "))
+            .or_else(|| trimmed.strip_prefix("dismantle "))
+        {
+            let item = item.trim().to_string();
+            if item.is_empty() {
+                return CommandResult::Error("What do you want to take apart?".to_string());
+            }
+            return CommandResult::Success(ParsedCommand::Deconstruct { item });
+        }
+
         if trimmed.starts_with("take ") {
             let item = trimmed[5..].trim().to_string();
             if item.is_empty() {
@@ -636,125 +1235,565 @@ impl CommandParser {
             return CommandResult::Success(ParsedCommand::Drop { item });
         }
 
-        if trimmed.starts_with("equip ") {
-            let crystal = trimmed[6..].trim().to_string();
-            if crystal.is_empty() {
-                return CommandResult::Error("What crystal do you want to equip?".to_string());
+        if let Some(recipe) = trimmed.strip_prefix("craft ") {
+            let recipe = recipe.trim().to_string();
+            if recipe.is_empty() {
+                return CommandResult::Error("What do you want to craft?".to_string());
             }
-            return CommandResult::Success(ParsedCommand::Equip { crystal });
+            return CommandResult::Success(ParsedCommand::CraftItem {
+                action: "craft".to_string(),
+                items: Vec::new(),
+                recipe: Some(recipe),
+            });
         }
 
-        // Handle single-word advanced commands
-        match trimmed.as_str() {
-            "rest" => CommandResult::Success(ParsedCommand::Rest),
-            "meditate" => CommandResult::Success(ParsedCommand::Meditate),
-            "faction status" | "factions" => CommandResult::Success(ParsedCommand::FactionStatus),
-            "crystal status" | "crystals" => CommandResult::Success(ParsedCommand::CrystalStatus),
-            _ => self.parse(input), // Fall back to normal parsing
+        if let Some(rest) = trimmed.strip_prefix("combine ") {
+            return match rest.split_once(" with ").or_else(|| rest.split_once(" and ")) {
+                Some((first, second)) if !first.trim().is_empty() && !second.trim().is_empty() => {
+                    CommandResult::Success(ParsedCommand::Combine {
+                        first: first.trim().to_string(),
+                        second: second.trim().to_string(),
+                    })
+                }
+                _ => CommandResult::Error("Use: combine <item> with <item>".to_string()),
+            };
         }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_movement_parsing() {
-        let parser = CommandParser::new();
-        let result = parser.parse("go north");
 
-        match result {
-            CommandResult::Success(ParsedCommand::Move { direction }) => {
-                assert_eq!(direction, Direction::North);
+        if let Some(item) = trimmed.strip_prefix("hide ").or_else(|| trimmed.strip_prefix("stash ")) {
+            let item = item.trim().to_string();
+            if item.is_empty() {
+                return CommandResult::Error("What do you want to hide?".to_string());
             }
-            _ => panic!("Expected successful movement command"),
+            return CommandResult::Success(ParsedCommand::Hide { item });
         }
-    }
-
-    #[test]
-    fn test_magic_parsing() {
-        let parser = CommandParser::new();
-        let result = parser.parse("cast healing using amethyst on guard");
 
-        match result {
-            CommandResult::Success(ParsedCommand::CastMagic { spell_type, crystal, target }) => {
-                assert_eq!(spell_type, "healing");
-                assert_eq!(crystal, Some("amethyst".to_string()));
-                assert_eq!(target, Some("guard".to_string()));
+        if let Some(rest) = trimmed.strip_prefix("repair ") {
+            let (item, npc) = match rest.split_once(" with ").or_else(|| rest.split_once(" at ")) {
+                Some((item, npc)) => (item.trim(), Some(npc.trim().to_string())),
+                None => (rest.trim(), None),
+            };
+            if item.is_empty() {
+                return CommandResult::Error("What do you want to repair?".to_string());
             }
-            _ => panic!("Expected successful magic command"),
+            return CommandResult::Success(ParsedCommand::Repair { item: Some(item.to_string()), npc });
         }
-    }
-
-    #[test]
-    fn test_examination_parsing() {
-        let parser = CommandParser::new();
-        let result = parser.parse("examine crystal formation");
 
-        match result {
-            CommandResult::Success(ParsedCommand::Examine { target }) => {
-                assert_eq!(target, "crystal formation");
+        if let Some(rest) = trimmed.strip_prefix("appraise ").or_else(|| trimmed.strip_prefix("identify ")) {
+            let (item, with) = match rest.split_once(" with ").or_else(|| rest.split_once(" using ")) {
+                Some((item, with)) => (item.trim(), Some(with.trim().to_string())),
+                None => (rest.trim(), None),
+            };
+            if item.is_empty() {
+                return CommandResult::Error("What do you want to appraise?".to_string());
             }
-            _ => panic!("Expected successful examination command"),
+            return CommandResult::Success(ParsedCommand::Appraise { item: Some(item.to_string()), with });
         }
-    }
 
-    #[test]
-    fn test_help_parsing() {
-        let parser = CommandParser::new();
-        let result = parser.parse("help magic");
+        if let Some(target) = trimmed.strip_prefix("clean up ").or_else(|| trimmed.strip_prefix("clean ")).filter(|target| *target != "up") {
+            return CommandResult::Success(ParsedCommand::CleanUp { target: Some(target.trim().to_string()) });
+        }
 
-        match result {
-            CommandResult::Help(text) => {
-                assert!(text.contains("Magic Commands"));
-            }
-            _ => panic!("Expected help result"),
+        if let Some(choice) = trimmed.strip_prefix("emissary ") {
+            return match choice.trim().parse::<usize>() {
+                Ok(choice) => CommandResult::Success(ParsedCommand::Emissary { choice: Some(choice) }),
+                Err(_) => CommandResult::Error("Use: emissary <number>".to_string()),
+            };
         }
-    }
 
-    #[test]
-    fn test_advanced_parsing() {
-        let parser = CommandParser::new();
-        let result = parser.parse_advanced("save game1");
+        if matches!(trimmed.as_str(), "buy laboratory" | "buy lab" | "buy the laboratory") {
+            return CommandResult::Success(ParsedCommand::BuyLaboratory);
+        }
 
-        match result {
-            CommandResult::Success(ParsedCommand::Save { slot }) => {
-                assert_eq!(slot, Some("game1".to_string()));
-            }
-            _ => panic!("Expected successful save command"),
+        if let Some(fixture) = trimmed.strip_prefix("upgrade ") {
+            return CommandResult::Success(ParsedCommand::UpgradeFixture { fixture: fixture.trim().to_string() });
         }
-    }
 
-    #[test]
-    fn test_unknown_command_suggestions() {
-        let parser = CommandParser::new();
-        let result = parser.parse("blahblah");
+        if let Some(item) = trimmed.strip_prefix("store ") {
+            return CommandResult::Success(ParsedCommand::StoreItem { item: item.trim().to_string() });
+        }
 
-        match result {
-            CommandResult::Success(ParsedCommand::Unknown { original, suggestions }) => {
-                assert_eq!(original, "blahblah");
-                assert!(!suggestions.is_empty());
-            }
-            _ => panic!("Expected unknown command with suggestions"),
+        if let Some(item) = trimmed.strip_prefix("retrieve ") {
+            return CommandResult::Success(ParsedCommand::RetrieveItem { item: item.trim().to_string() });
         }
-    }
 
-    #[test]
-    fn test_social_parsing() {
-        let parser = CommandParser::new();
-        let result = parser.parse("ask merchant about crystals");
+        if let Some(rest) = trimmed.strip_prefix("buy ") {
+            return match rest.split_once(" from ") {
+                Some((service, npc)) if !service.trim().is_empty() && !npc.trim().is_empty() => {
+                    CommandResult::Success(ParsedCommand::BuyService { service: service.trim().to_string(), npc: npc.trim().to_string() })
+                }
+                _ => CommandResult::Error("Usage: buy <healing|training> from <person>".to_string()),
+            };
+        }
 
-        match result {
-            CommandResult::Success(ParsedCommand::Ask { target, topic }) => {
-                assert_eq!(target, "merchant");
-                assert_eq!(topic, "crystals");
-            }
-            _ => panic!("Expected successful ask command"),
+        if let Some(npc) = trimmed.strip_prefix("haggle with ").or_else(|| trimmed.strip_prefix("haggle ")) {
+            return CommandResult::Success(ParsedCommand::Haggle { npc: npc.trim().to_string() });
         }
-    }
 
-    #[test]
+        if let Some(destination) = trimmed.strip_prefix("travel to ").or_else(|| trimmed.strip_prefix("travel ")) {
+            return CommandResult::Success(ParsedCommand::Travel { destination: Some(destination.trim().to_string()) });
+        }
+
+        if let Some(target) = trimmed.strip_prefix("search ") {
+            return CommandResult::Success(ParsedCommand::Search { target: target.trim().to_string() });
+        }
+
+        if let Some(target) = trimmed.strip_prefix("touch ") {
+            return CommandResult::Success(ParsedCommand::Touch { target: target.trim().to_string() });
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("unlock ") {
+            let (way, with) = match rest.split_once(" with ") {
+                Some((way, with)) => (way, Some(with.trim().to_string())),
+                None => (rest, None),
+            };
+            return match Direction::from_string(way.trim()) {
+                Some(direction) => CommandResult::Success(ParsedCommand::Unlock { direction, with }),
+                None => CommandResult::Error("Usage: unlock <direction> [with <answer>]".to_string()),
+            };
+        }
+
+        if let Some(way) = trimmed.strip_prefix("pick the lock ").or_else(|| trimmed.strip_prefix("pick lock ")) {
+            return match Direction::from_string(way.trim()) {
+                Some(direction) => CommandResult::Success(ParsedCommand::PickLock { direction }),
+                None => CommandResult::Error("Usage: pick lock <direction>".to_string()),
+            };
+        }
+
+        if let Some(way) = trimmed.strip_prefix("sneak ") {
+            let way = way.trim().trim_start_matches("past ").trim_start_matches("into ").trim();
+            return match Direction::from_string(way) {
+                Some(direction) => CommandResult::Success(ParsedCommand::Sneak { direction }),
+                None => CommandResult::Error("Usage: sneak <direction>".to_string()),
+            };
+        }
+
+        if let Some(faction) = trimmed.strip_prefix("pay bounty ").or_else(|| trimmed.strip_prefix("pay off bounty ")) {
+            return CommandResult::Success(ParsedCommand::PayBounty { faction: faction.trim().to_string() });
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("show credentials") {
+            let way = rest.trim().trim_start_matches("to ").trim_start_matches("the ").trim_start_matches("guards").trim();
+            return CommandResult::Success(ParsedCommand::ShowCredentials { direction: Direction::from_string(way) });
+        }
+
+        if let Some(target) = trimmed.strip_prefix("invite ") {
+            let target = target.trim().to_string();
+            if target.is_empty() {
+                return CommandResult::Error("Who do you want to invite along?".to_string());
+            }
+            return CommandResult::Success(ParsedCommand::Invite { target });
+        }
+
+        if let Some(target) = trimmed.strip_prefix("recruit ") {
+            let target = target.trim().to_string();
+            if target.is_empty() {
+                return CommandResult::Error("Who do you want to recruit?".to_string());
+            }
+            return CommandResult::Success(ParsedCommand::Recruit { target });
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("learn ") {
+            return match rest.split_once(" from ") {
+                Some((style, mentor)) if !style.trim().is_empty() && !mentor.trim().is_empty() => {
+                    CommandResult::Success(ParsedCommand::LearnStyle {
+                        style: style.trim().to_string(),
+                        mentor: mentor.trim().to_string(),
+                    })
+                }
+                _ => CommandResult::Error("Learn from whom? Try 'learn <style> from <mentor>'.".to_string()),
+            };
+        }
+
+        if let Some(style) = trimmed.strip_prefix("style ") {
+            return CommandResult::Success(ParsedCommand::Style { style: Some(style.trim().to_string()) });
+        }
+
+        if trimmed == "dismiss porter" || trimmed == "dismiss the porter" {
+            return CommandResult::Success(ParsedCommand::Porter { action: Some("dismiss".to_string()) });
+        }
+
+        if let Some(target) = trimmed.strip_prefix("dismiss ") {
+            let target = target.trim().to_string();
+            if target.is_empty() {
+                return CommandResult::Error("Which companion or follower do you want to dismiss?".to_string());
+            }
+            return CommandResult::Success(ParsedCommand::Dismiss { target });
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("hire porter") {
+            let days = rest.trim().trim_start_matches("for ").trim_end_matches("days").trim_end_matches("day").trim();
+            let days = if days.is_empty() { "1" } else { days };
+            return CommandResult::Success(ParsedCommand::Porter { action: Some(format!("hire {}", days)) });
+        }
+
+        if let Some(action) = trimmed.strip_prefix("coop ") {
+            return CommandResult::Success(ParsedCommand::Coop { action: Some(action.trim().to_string()) });
+        }
+
+        if let Some(kind) = trimmed.strip_prefix("ward ") {
+            return CommandResult::Success(ParsedCommand::Ward { kind: Some(kind.trim().to_string()) });
+        }
+
+        if let Some(spell) = trimmed.strip_prefix("components for ") {
+            return CommandResult::Success(ParsedCommand::Components { spell: spell.trim().to_string() });
+        }
+
+        if let Some(setting) = trimmed.strip_prefix("codex ") {
+            return CommandResult::Success(ParsedCommand::Codex { setting: Some(setting.trim().to_string()) });
+        }
+
+        if let Some(category) = trimmed.strip_prefix("stats ").or_else(|| trimmed.strip_prefix("statistics ")) {
+            return CommandResult::Success(ParsedCommand::Stats { category: Some(category.trim().to_string()) });
+        }
+
+        if let Some(enemy) = trimmed.strip_prefix("bestiary ") {
+            return CommandResult::Success(ParsedCommand::Bestiary { enemy: Some(enemy.trim().to_string()) });
+        }
+
+        if let Some(policy) = trimmed.strip_prefix("defeat policy ") {
+            return CommandResult::Success(ParsedCommand::DefeatPolicy { policy: Some(policy.trim().to_string()) });
+        }
+
+        if let Some(level) = trimmed.strip_prefix("difficulty ") {
+            return CommandResult::Success(ParsedCommand::Difficulty { level: Some(level.trim().to_string()) });
+        }
+
+        if let Some(query) = trimmed.strip_prefix("calculate ").or_else(|| trimmed.strip_prefix("calc ")) {
+            let query = query.trim().to_string();
+            return CommandResult::Success(ParsedCommand::Calculate {
+                query: if query.is_empty() { None } else { Some(query) },
+            });
+        }
+
+        if let Some(concept) = trimmed.strip_prefix("explain ") {
+            let concept = concept.trim().to_string();
+            return CommandResult::Success(ParsedCommand::Explain {
+                concept: if concept.is_empty() { None } else { Some(concept) },
+            });
+        }
+
+        // File names keep the case they were typed in
+        if trimmed.starts_with("export save ") || trimmed.starts_with("import save ") {
+            let file = input.trim().get("export save ".len()..).unwrap_or_default().trim();
+            if file.is_empty() {
+                return CommandResult::Error("Which file? Try 'export save my_game'.".to_string());
+            }
+            if trimmed.starts_with("export ") {
+                return CommandResult::Success(ParsedCommand::ExportSave { file: file.to_string() });
+            }
+            let (file, slot) = match file.rsplit_once(" as ") {
+                Some((file, slot)) => (file.trim(), Some(slot.trim().to_string())),
+                None => (file, None),
+            };
+            return CommandResult::Success(ParsedCommand::ImportSave { file: file.to_string(), slot });
+        }
+        if trimmed == "reload content" || trimmed.starts_with("reload content from ") {
+            let dir = input.trim().get("reload content from ".len()..).unwrap_or_default().trim();
+            return CommandResult::Success(ParsedCommand::ReloadContent {
+                from: if dir.is_empty() { None } else { Some(dir.to_string()) },
+            });
+        }
+
+        if let Some(note) = trimmed.strip_prefix("annotate ") {
+            let note = note.trim().to_string();
+            if note.is_empty() {
+                return CommandResult::Error("What note do you want to leave here?".to_string());
+            }
+            return CommandResult::Success(ParsedCommand::Annotate { marker: None, note });
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("mark ") {
+            let mut chars = rest.trim().chars();
+            let marker = chars.next();
+            let note = chars.as_str().trim().to_string();
+            return match marker {
+                Some(marker) if !note.is_empty() && !marker.is_alphanumeric() => {
+                    CommandResult::Success(ParsedCommand::Annotate { marker: Some(marker), note })
+                }
+                _ => CommandResult::Error("Usage: mark <symbol> <note> (e.g. 'mark ! avoid patrols at night')".to_string()),
+            };
+        }
+
+        if let Some(level) = trimmed.strip_prefix("verbosity ") {
+            return CommandResult::Success(ParsedCommand::SetVerbosity { level: Some(level.trim().to_string()) });
+        }
+
+        if let Some(theme) = trimmed.strip_prefix("theme ") {
+            return CommandResult::Success(ParsedCommand::SetTheme { theme: Some(theme.trim().to_string()) });
+        }
+
+        if let Some(setting) = trimmed.strip_prefix("navigation ") {
+            return CommandResult::Success(ParsedCommand::SetNavigation { setting: Some(setting.trim().to_string()) });
+        }
+
+        if let Some(setting) = trimmed.strip_prefix("prompt ") {
+            return CommandResult::Success(ParsedCommand::SetPrompt { setting: Some(setting.trim().to_string()) });
+        }
+
+        if let Some(setting) = trimmed.strip_prefix("shortcut ").or_else(|| trimmed.strip_prefix("shortcuts ")) {
+            return CommandResult::Success(ParsedCommand::Shortcuts { setting: Some(setting.trim().to_string()) });
+        }
+
+        if let Some(setting) = trimmed.strip_prefix("alias ") {
+            return CommandResult::Success(ParsedCommand::Alias { setting: Some(setting.trim().to_string()) });
+        }
+
+        if let Some(setting) = trimmed.strip_prefix("macro ") {
+            return CommandResult::Success(ParsedCommand::Macro { setting: Some(setting.trim().to_string()) });
+        }
+
+        if let Some(limit) = trimmed.strip_prefix("undo limit") {
+            return match limit.trim() {
+                "" => CommandResult::Success(ParsedCommand::UndoLimit { limit: None }),
+                limit => match limit.parse::<usize>() {
+                    Ok(limit) => CommandResult::Success(ParsedCommand::UndoLimit { limit: Some(limit) }),
+                    Err(_) => CommandResult::Error("Usage: undo limit <number> (e.g. 'undo limit 10')".to_string()),
+                },
+            };
+        }
+
+        if let Some(steps) = trimmed.strip_prefix("undo ") {
+            return match steps.trim().parse::<usize>() {
+                Ok(steps) if steps > 0 => CommandResult::Success(ParsedCommand::Undo { steps }),
+                _ => CommandResult::Error("Usage: undo [number] (e.g. 'undo 3')".to_string()),
+            };
+        }
+
+        if let Some(quest) = trimmed.strip_prefix("hint ") {
+            return CommandResult::Success(ParsedCommand::Hint { quest: Some(quest.trim().to_string()) });
+        }
+
+        if let Some(quest) = trimmed.strip_prefix("track ") {
+            return CommandResult::Success(ParsedCommand::TrackQuest { quest: Some(quest.trim().to_string()) });
+        }
+
+        if let Some(quest_id) = trimmed.strip_prefix("quest turn in ").or_else(|| trimmed.strip_prefix("turn in ")) {
+            return CommandResult::Success(ParsedCommand::QuestTurnIn { quest_id: quest_id.trim().to_string() });
+        }
+
+        if let Some(option) = trimmed.strip_prefix("reward ") {
+            return CommandResult::Success(ParsedCommand::RewardChoice { option: Some(option.trim().to_string()) });
+        }
+
+        if let Some(setting) = trimmed.strip_prefix("fast analysis ") {
+            let enabled = match setting.trim() {
+                "on" => true,
+                "off" => false,
+                _ => return CommandResult::Error("Use 'fast analysis on' or 'fast analysis off'.".to_string()),
+            };
+            return CommandResult::Success(ParsedCommand::FastAnalysis { enabled: Some(enabled) });
+        }
+
+        if let Some(action) = trimmed.strip_prefix("checklist ") {
+            return CommandResult::Success(ParsedCommand::Checklist { action: Some(action.trim().to_string()) });
+        }
+
+        if trimmed.starts_with("equip ") {
+            let crystal = trimmed[6..].trim().to_string();
+            if crystal.is_empty() {
+                return CommandResult::Error("What crystal do you want to equip?".to_string());
+            }
+            return CommandResult::Success(ParsedCommand::Equip { crystal });
+        }
+
+        // Handle single-word advanced commands
+        match trimmed.as_str() {
+            "rest" => CommandResult::Success(ParsedCommand::Rest),
+            "rest until recovered" | "rest until rested" | "long rest" => CommandResult::Success(ParsedCommand::RestUntilRecovered),
+            "verbosity" => CommandResult::Success(ParsedCommand::SetVerbosity { level: None }),
+            "theme" => CommandResult::Success(ParsedCommand::SetTheme { theme: None }),
+            "navigation" => CommandResult::Success(ParsedCommand::SetNavigation { setting: None }),
+            "prompt" => CommandResult::Success(ParsedCommand::SetPrompt { setting: None }),
+            "journal" => CommandResult::Success(ParsedCommand::QuestJournal),
+            "track" => CommandResult::Success(ParsedCommand::TrackQuest { quest: None }),
+            "untrack" => CommandResult::Success(ParsedCommand::TrackQuest { quest: Some("off".to_string()) }),
+            "hint" | "hints" => CommandResult::Success(ParsedCommand::Hint { quest: None }),
+            "relationships" | "relations" => CommandResult::Success(ParsedCommand::Relationships),
+            "saves" | "list saves" => CommandResult::Success(ParsedCommand::Saves),
+            "actions" | "suggestions" | "what can i do" | "what can i do here" | "what now" => CommandResult::Success(ParsedCommand::Actions),
+            "endings" | "ending gallery" | "gallery" => CommandResult::Success(ParsedCommand::Endings),
+            "shortcuts" | "shortcut" => CommandResult::Success(ParsedCommand::Shortcuts { setting: None }),
+            "alias" | "aliases" => CommandResult::Success(ParsedCommand::Alias { setting: None }),
+            "macro" | "macros" => CommandResult::Success(ParsedCommand::Macro { setting: None }),
+            "undo" => CommandResult::Success(ParsedCommand::Undo { steps: 1 }),
+            "reward" | "rewards" => CommandResult::Success(ParsedCommand::RewardChoice { option: None }),
+            "checklist" | "getting started" => CommandResult::Success(ParsedCommand::Checklist { action: None }),
+            "map" => CommandResult::Success(ParsedCommand::ShowMap),
+            "defeat policy" => CommandResult::Success(ParsedCommand::DefeatPolicy { policy: None }),
+            "caches" | "stashes" => CommandResult::Success(ParsedCommand::Caches),
+            "laboratory" | "lab" | "my lab" | "my laboratory" => CommandResult::Success(ParsedCommand::Laboratory),
+            "emissary" | "emissaries" => CommandResult::Success(ParsedCommand::Emissary { choice: None }),
+            "harvest" | "harvest garden" => CommandResult::Success(ParsedCommand::Harvest),
+            "repair" | "repairs" => CommandResult::Success(ParsedCommand::Repair { item: None, npc: None }),
+            "services" | "prices" => CommandResult::Success(ParsedCommand::Services),
+            "time" | "date" | "calendar" | "events" => CommandResult::Success(ParsedCommand::Calendar),
+            "weather" | "forecast" | "weather forecast" => CommandResult::Success(ParsedCommand::Forecast),
+            "travel" | "roads" | "routes" | "regions" => CommandResult::Success(ParsedCommand::Travel { destination: None }),
+            "clean up" | "clean" | "cleanup" => CommandResult::Success(ParsedCommand::CleanUp { target: None }),
+            "appraise" | "identify" | "unidentified" => CommandResult::Success(ParsedCommand::Appraise { item: None, with: None }),
+            "fast analysis" => CommandResult::Success(ParsedCommand::FastAnalysis { enabled: None }),
+            "flux" | "read flux" | "read the flux" => CommandResult::Success(ParsedCommand::ReadFlux),
+            "read notebook" | "read the notebook" | "read lab notebook" | "read notes" => {
+                CommandResult::Success(ParsedCommand::ReadNotebook)
+            }
+            "retire" => CommandResult::Success(ParsedCommand::Retire { leave_notes: None }),
+            "retire with notes" | "retire and leave notes" => CommandResult::Success(ParsedCommand::Retire { leave_notes: Some(true) }),
+            "retire quietly" | "retire without notes" => CommandResult::Success(ParsedCommand::Retire { leave_notes: Some(false) }),
+            "recipes" | "recipe book" => CommandResult::Success(ParsedCommand::Recipes),
+            "difficulty" => CommandResult::Success(ParsedCommand::Difficulty { level: None }),
+            "bestiary" => CommandResult::Success(ParsedCommand::Bestiary { enemy: None }),
+            "codex" | "compendium" | "frequency compendium" => CommandResult::Success(ParsedCommand::Codex { setting: None }),
+            "combat log" | "battle log" => CommandResult::Success(ParsedCommand::CombatLog),
+            "stats" | "statistics" => CommandResult::Success(ParsedCommand::Stats { category: None }),
+            "ward" | "wards" => CommandResult::Success(ParsedCommand::Ward { kind: None }),
+            "style" | "styles" => CommandResult::Success(ParsedCommand::Style { style: None }),
+            "party" | "companions" => CommandResult::Success(ParsedCommand::Party),
+            "followers" => CommandResult::Success(ParsedCommand::Followers),
+            "coop" | "co-op" => CommandResult::Success(ParsedCommand::Coop { action: None }),
+            "porter" | "load" | "encumbrance" => CommandResult::Success(ParsedCommand::Porter { action: None }),
+            "parley" | "negotiate" | "intimidate" | "threaten" | "demoralize" | "demoralise" | "surrender" | "yield" => {
+                CommandResult::Success(ParsedCommand::CombatTactic { tactic: trimmed.clone() })
+            }
+            "calculate" | "calc" | "calculator" => CommandResult::Success(ParsedCommand::Calculate { query: None }),
+            "explain" | "concepts" | "glossary" => CommandResult::Success(ParsedCommand::Explain { concept: None }),
+            "clear annotations" | "unannotate" => CommandResult::Success(ParsedCommand::ClearAnnotations),
+            "assess" | "assessments" => CommandResult::Success(ParsedCommand::Assess { theory: None }),
+            "summary" | "run summary" => CommandResult::Success(ParsedCommand::RunSummary { export: false }),
+            "export summary" => CommandResult::Success(ParsedCommand::RunSummary { export: true }),
+            "meditate" => CommandResult::Success(ParsedCommand::Meditate),
+            "faction status" | "factions" => CommandResult::Success(ParsedCommand::FactionStatus),
+            "legacy" | "influence" => CommandResult::Success(ParsedCommand::Legacy),
+            "wanted" | "bounties" => CommandResult::Success(ParsedCommand::Wanted),
+            "crystal status" | "crystals" => CommandResult::Success(ParsedCommand::CrystalStatus),
+            _ => self.parse(input), // Fall back to normal parsing
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_movement_parsing() {
+        let parser = CommandParser::new();
+        let result = parser.parse("go north");
+
+        match result {
+            CommandResult::Success(ParsedCommand::Move { direction }) => {
+                assert_eq!(direction, Direction::North);
+            }
+            _ => panic!("Expected successful movement command"),
+        }
+    }
+
+    #[test]
+    fn test_magic_parsing() {
+        let parser = CommandParser::new();
+        let result = parser.parse("cast healing using amethyst on guard");
+
+        match result {
+            CommandResult::Success(ParsedCommand::CastMagic { spell_type, crystal, target, frequency, duration }) => {
+                assert_eq!((frequency, duration), (None, None));
+                assert_eq!(spell_type, "healing");
+                assert_eq!(crystal, Some("amethyst".to_string()));
+                assert_eq!(target, Some("guard".to_string()));
+            }
+            _ => panic!("Expected successful magic command"),
+        }
+    }
+
+    #[test]
+    fn test_magic_and_study_take_quantities() {
+        let parser = CommandParser::new();
+        match parser.parse_advanced("cast light at 7 hz for 10 minutes") {
+            CommandResult::Success(ParsedCommand::CastMagic { spell_type, frequency, duration, .. }) => {
+                assert_eq!(spell_type, "light");
+                assert_eq!(frequency, Some(7));
+                assert_eq!(duration, Some(10));
+            }
+            other => panic!("Expected a cast with parameters, got {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("cast light at 7.5 hz"), CommandResult::Error(_)));
+
+        match parser.parse_advanced("study harmonic_fundamentals for 2 hours") {
+            CommandResult::Success(ParsedCommand::Study { theory, duration }) => {
+                assert_eq!(theory, "harmonic_fundamentals");
+                assert_eq!(duration, Some(120));
+            }
+            other => panic!("Expected a timed study session, got {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("study harmonic_fundamentals at 4 hz"), CommandResult::Error(_)));
+    }
+
+    #[test]
+    fn test_examination_parsing() {
+        let parser = CommandParser::new();
+        let result = parser.parse("examine crystal formation");
+
+        match result {
+            CommandResult::Success(ParsedCommand::Examine { target }) => {
+                assert_eq!(target, "crystal formation");
+            }
+            _ => panic!("Expected successful examination command"),
+        }
+    }
+
+    #[test]
+    fn test_help_parsing() {
+        let parser = CommandParser::new();
+        let result = parser.parse("help magic");
+
+        match result {
+            CommandResult::Help(text) => {
+                assert!(text.contains("Magic Commands"));
+            }
+            _ => panic!("Expected help result"),
+        }
+    }
+
+    #[test]
+    fn test_advanced_parsing() {
+        let parser = CommandParser::new();
+        let result = parser.parse_advanced("save game1");
+
+        match result {
+            CommandResult::Success(ParsedCommand::Save { slot }) => {
+                assert_eq!(slot, Some("game1".to_string()));
+            }
+            _ => panic!("Expected successful save command"),
+        }
+    }
+
+    #[test]
+    fn test_unknown_command_suggestions() {
+        let parser = CommandParser::new();
+        let result = parser.parse("blahblah");
+
+        match result {
+            CommandResult::Success(ParsedCommand::Unknown { original, suggestions }) => {
+                assert_eq!(original, "blahblah");
+                assert!(!suggestions.is_empty());
+            }
+            _ => panic!("Expected unknown command with suggestions"),
+        }
+    }
+
+    #[test]
+    fn test_social_parsing() {
+        let parser = CommandParser::new();
+        let result = parser.parse("ask merchant about crystals");
+
+        match result {
+            CommandResult::Success(ParsedCommand::Ask { target, topic }) => {
+                assert_eq!(target, "merchant");
+                assert_eq!(topic, "crystals");
+            }
+            _ => panic!("Expected successful ask command"),
+        }
+    }
+
+    #[test]
     fn test_quest_list_parsing() {
         let parser = CommandParser::new();
         let result = parser.parse("quest list");
@@ -773,13 +1812,36 @@ mod tests {
         let result = parser.parse("quests");
 
         match result {
-            CommandResult::Success(ParsedCommand::QuestList) => {
+            CommandResult::Success(ParsedCommand::QuestJournal) => {
                 // Success
             }
             other => panic!("Expected successful quests command, got: {:?}", other),
         }
     }
 
+    #[test]
+    fn test_quest_journal_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("quest resonance_foundation") {
+            CommandResult::Success(ParsedCommand::QuestPage { quest_id }) => assert_eq!(quest_id, "resonance_foundation"),
+            other => panic!("Expected quest page command, got: {:?}", other),
+        }
+        match parser.parse_advanced("Track Understanding") {
+            CommandResult::Success(ParsedCommand::TrackQuest { quest }) => assert_eq!(quest.as_deref(), Some("understanding")),
+            other => panic!("Expected track command, got: {:?}", other),
+        }
+        assert!(matches!(
+            parser.parse_advanced("track"),
+            CommandResult::Success(ParsedCommand::TrackQuest { quest: None })
+        ));
+        match parser.parse_advanced("hint crystal") {
+            CommandResult::Success(ParsedCommand::Hint { quest }) => assert_eq!(quest.as_deref(), Some("crystal")),
+            other => panic!("Expected hint command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("endings"), CommandResult::Success(ParsedCommand::Endings)));
+    }
+
     #[test]
     fn test_quest_start_parsing() {
         let parser = CommandParser::new();
@@ -793,6 +1855,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_quest_turn_in_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("Turn in resonance_foundation") {
+            CommandResult::Success(ParsedCommand::QuestTurnIn { quest_id }) => assert_eq!(quest_id, "resonance_foundation"),
+            other => panic!("Expected quest turn in command, got: {:?}", other),
+        }
+        assert!(matches!(
+            parser.parse_advanced("quest turn in resonance_foundation"),
+            CommandResult::Success(ParsedCommand::QuestTurnIn { .. })
+        ));
+        match parser.parse_advanced("reward insight") {
+            CommandResult::Success(ParsedCommand::RewardChoice { option }) => assert_eq!(option.as_deref(), Some("insight")),
+            other => panic!("Expected reward choice command, got: {:?}", other),
+        }
+        assert!(matches!(
+            parser.parse_advanced("reward"),
+            CommandResult::Success(ParsedCommand::RewardChoice { option: None })
+        ));
+    }
+
     #[test]
     fn test_quest_parsing_via_parse_advanced() {
         let parser = CommandParser::new();
@@ -805,4 +1889,600 @@ mod tests {
             other => panic!("Expected successful quest list via parse_advanced, got: {:?}", other),
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_archive_research_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("research resonance in the archives") {
+            CommandResult::Success(ParsedCommand::ArchiveResearch { topic }) => {
+                assert_eq!(topic, "resonance");
+            }
+            other => panic!("Expected archive research command, got: {:?}", other),
+        }
+
+        match parser.parse_advanced("research harmonic_fundamentals") {
+            CommandResult::Success(ParsedCommand::Research { topic }) => {
+                assert_eq!(topic, "harmonic_fundamentals");
+            }
+            other => panic!("Expected research command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_assessment_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("assess harmonic fundamentals") {
+            CommandResult::Success(ParsedCommand::Assess { theory }) => {
+                assert_eq!(theory, Some("harmonic_fundamentals".to_string()));
+            }
+            other => panic!("Expected assess command, got: {:?}", other),
+        }
+
+        match parser.parse_advanced("answer an octave") {
+            CommandResult::Success(ParsedCommand::AssessmentAnswer { answer }) => {
+                assert_eq!(answer, "an octave");
+            }
+            other => panic!("Expected answer command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_persuade_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("persuade mage_kira with safety") {
+            CommandResult::Success(ParsedCommand::Persuade { target, argument }) => {
+                assert_eq!(target, "mage_kira");
+                assert_eq!(argument, "safety");
+            }
+            other => panic!("Expected persuade command, got: {:?}", other),
+        }
+
+        match parser.parse_advanced("persuade mage_kira") {
+            CommandResult::Error(_) => {}
+            other => panic!("Expected error for missing argument, got: {:?}", other),
+        }
+
+        assert!(matches!(parser.parse_advanced("reply 2"), CommandResult::Success(ParsedCommand::Reply { choice: 2 })));
+        assert!(matches!(parser.parse_advanced("reply sharply"), CommandResult::Error(_)));
+        assert!(matches!(parser.parse_advanced("reply b"), CommandResult::Success(ParsedCommand::Reply { choice: 2 })));
+        match parser.parse_advanced("ask about crystal_engineering") {
+            CommandResult::Success(ParsedCommand::Ask { target, topic }) => {
+                assert!(target.is_empty());
+                assert_eq!(topic, "crystal_engineering");
+            }
+            other => panic!("Expected ask command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("relationships"), CommandResult::Success(ParsedCommand::Relationships)));
+        assert!(matches!(parser.parse_advanced("list saves"), CommandResult::Success(ParsedCommand::Saves)));
+        match parser.parse_advanced("import save Backups/Run.srsave as Second") {
+            CommandResult::Success(ParsedCommand::ImportSave { file, slot }) => {
+                assert_eq!(file, "Backups/Run.srsave");
+                assert_eq!(slot.as_deref(), Some("Second"));
+            }
+            other => panic!("Expected an import, got {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("export save My_Game"), CommandResult::Success(ParsedCommand::ExportSave { file }) if file == "My_Game"));
+        assert!(matches!(parser.parse_advanced("reload content"), CommandResult::Success(ParsedCommand::ReloadContent { from: None })));
+        assert!(matches!(parser.parse_advanced("reload content from Drafts/content"), CommandResult::Success(ParsedCommand::ReloadContent { from: Some(dir) }) if dir == "Drafts/content"));
+        match parser.parse_advanced("locate Magistrate Cordelia") {
+            CommandResult::Success(ParsedCommand::Locate { target }) => assert_eq!(target, "magistrate cordelia"),
+            other => panic!("Expected locate command, got: {:?}", other),
+        }
+        match parser.parse_advanced("spend time with Echo") {
+            CommandResult::Success(ParsedCommand::SpendTime { target, courting }) => {
+                assert_eq!(target, "echo");
+                assert!(!courting);
+            }
+            other => panic!("Expected spend time command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("court elara"), CommandResult::Success(ParsedCommand::SpendTime { courting: true, .. })));
+    }
+
+    #[test]
+    fn test_verbosity_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("verbosity terse") {
+            CommandResult::Success(ParsedCommand::SetVerbosity { level }) => {
+                assert_eq!(level.as_deref(), Some("terse"));
+            }
+            other => panic!("Expected verbosity command, got: {:?}", other),
+        }
+
+        match parser.parse_advanced("verbosity") {
+            CommandResult::Success(ParsedCommand::SetVerbosity { level: None }) => {}
+            other => panic!("Expected verbosity query, got: {:?}", other),
+        }
+
+        match parser.parse_advanced("theme high-contrast") {
+            CommandResult::Success(ParsedCommand::SetTheme { theme }) => {
+                assert_eq!(theme, Some("high-contrast".to_string()));
+            }
+            other => panic!("Expected theme command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("theme"), CommandResult::Success(ParsedCommand::SetTheme { theme: None })));
+
+        match parser.parse_advanced("navigation manual") {
+            CommandResult::Success(ParsedCommand::SetNavigation { setting }) => assert_eq!(setting.as_deref(), Some("manual")),
+            other => panic!("Expected navigation command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_service_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("Buy healing from Seraphina") {
+            CommandResult::Success(ParsedCommand::BuyService { service, npc }) => {
+                assert_eq!(service, "healing");
+                assert_eq!(npc, "seraphina");
+            }
+            other => panic!("Expected buy service command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("buy healing"), CommandResult::Error(_)));
+        match parser.parse_advanced("haggle with felix") {
+            CommandResult::Success(ParsedCommand::Haggle { npc }) => assert_eq!(npc, "felix"),
+            other => panic!("Expected haggle command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("services"), CommandResult::Success(ParsedCommand::Services)));
+        assert!(matches!(parser.parse_advanced("calendar"), CommandResult::Success(ParsedCommand::Calendar)));
+        assert!(matches!(parser.parse_advanced("events"), CommandResult::Success(ParsedCommand::Calendar)));
+        assert!(matches!(parser.parse_advanced("weather"), CommandResult::Success(ParsedCommand::Forecast)));
+        match parser.parse_advanced("travel to the quarries") {
+            CommandResult::Success(ParsedCommand::Travel { destination }) => assert_eq!(destination.as_deref(), Some("the quarries")),
+            other => panic!("Expected travel command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("roads"), CommandResult::Success(ParsedCommand::Travel { destination: None })));
+        match parser.parse_advanced("search rubble") {
+            CommandResult::Success(ParsedCommand::Search { target }) => assert_eq!(target, "rubble"),
+            other => panic!("Expected search command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("touch crystal formation"), CommandResult::Success(ParsedCommand::Touch { .. })));
+        match parser.parse_advanced("unlock down with resonate") {
+            CommandResult::Success(ParsedCommand::Unlock { direction, with }) => {
+                assert_eq!(direction, Direction::Down);
+                assert_eq!(with.as_deref(), Some("resonate"));
+            }
+            other => panic!("Expected unlock command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("pick lock north"), CommandResult::Success(ParsedCommand::PickLock { direction: Direction::North })));
+        assert!(matches!(parser.parse_advanced("show credentials"), CommandResult::Success(ParsedCommand::ShowCredentials { direction: None })));
+        assert!(matches!(parser.parse_advanced("sneak down"), CommandResult::Success(ParsedCommand::Sneak { direction: Direction::Down })));
+        assert!(matches!(parser.parse_advanced("bounties"), CommandResult::Success(ParsedCommand::Wanted)));
+        match parser.parse_advanced("pay bounty council") {
+            CommandResult::Success(ParsedCommand::PayBounty { faction }) => assert_eq!(faction, "council"),
+            other => panic!("Expected PayBounty, got {:?}", other),
+        }
+
+        match parser.parse_advanced("appraise strange artifact with Lyra") {
+            CommandResult::Success(ParsedCommand::Appraise { item, with }) => {
+                assert_eq!(item.as_deref(), Some("strange artifact"));
+                assert_eq!(with.as_deref(), Some("lyra"));
+            }
+            other => panic!("Expected appraise command, got: {:?}", other),
+        }
+        assert!(matches!(
+            parser.parse_advanced("appraise"),
+            CommandResult::Success(ParsedCommand::Appraise { item: None, with: None })
+        ));
+        assert!(matches!(
+            parser.parse_advanced("fast analysis on"),
+            CommandResult::Success(ParsedCommand::FastAnalysis { enabled: Some(true) })
+        ));
+        assert!(matches!(
+            parser.parse_advanced("fast analysis"),
+            CommandResult::Success(ParsedCommand::FastAnalysis { enabled: None })
+        ));
+        assert!(matches!(parser.parse_advanced("fast analysis maybe"), CommandResult::Error(_)));
+    }
+
+    #[test]
+    fn test_checklist_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("Checklist Dismiss") {
+            CommandResult::Success(ParsedCommand::Checklist { action }) => {
+                assert_eq!(action.as_deref(), Some("dismiss"));
+            }
+            other => panic!("Expected checklist command, got: {:?}", other),
+        }
+        assert!(matches!(
+            parser.parse_advanced("checklist"),
+            CommandResult::Success(ParsedCommand::Checklist { action: None })
+        ));
+    }
+
+    #[test]
+    fn test_prompt_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("Prompt Location Time") {
+            CommandResult::Success(ParsedCommand::SetPrompt { setting }) => {
+                assert_eq!(setting.as_deref(), Some("location time"));
+            }
+            other => panic!("Expected prompt command, got: {:?}", other),
+        }
+        assert!(matches!(
+            parser.parse_advanced("prompt"),
+            CommandResult::Success(ParsedCommand::SetPrompt { setting: None })
+        ));
+    }
+
+    #[test]
+    fn test_shortcuts_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("shortcut add h cast healing") {
+            CommandResult::Success(ParsedCommand::Shortcuts { setting }) => {
+                assert_eq!(setting.as_deref(), Some("add h cast healing"));
+            }
+            other => panic!("Expected shortcuts command, got: {:?}", other),
+        }
+        assert!(matches!(
+            parser.parse_advanced("shortcuts"),
+            CommandResult::Success(ParsedCommand::Shortcuts { setting: None })
+        ));
+        match parser.parse_advanced("macro gq go north; examine crystals") {
+            CommandResult::Success(ParsedCommand::Macro { setting }) => {
+                assert_eq!(setting.as_deref(), Some("gq go north; examine crystals"));
+            }
+            other => panic!("Expected macro command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("aliases"), CommandResult::Success(ParsedCommand::Alias { setting: None })));
+        assert!(matches!(parser.parse_advanced("what can I do here"), CommandResult::Success(ParsedCommand::Actions)));
+        assert!(matches!(parser.parse_advanced("undo"), CommandResult::Success(ParsedCommand::Undo { steps: 1 })));
+        assert!(matches!(parser.parse_advanced("undo 3"), CommandResult::Success(ParsedCommand::Undo { steps: 3 })));
+        assert!(matches!(parser.parse_advanced("undo limit 10"), CommandResult::Success(ParsedCommand::UndoLimit { limit: Some(10) })));
+        assert!(matches!(parser.parse_advanced("undo limit"), CommandResult::Success(ParsedCommand::UndoLimit { limit: None })));
+        assert!(matches!(parser.parse_advanced("undo everything"), CommandResult::Error(_)));
+    }
+
+    #[test]
+    fn test_content_verbs_stand_for_known_commands() {
+        let mut parser = CommandParser::new();
+        parser.add_verb("transcribe", "research").unwrap();
+        parser.add_verb("calibrate", "examine").unwrap();
+        match parser.parse_advanced("Transcribe harmonic glyphs") {
+            CommandResult::Success(ParsedCommand::Research { topic }) => assert_eq!(topic, "harmonic glyphs"),
+            other => panic!("Expected research command, got: {:?}", other),
+        }
+        assert!(matches!(
+            parser.parse_advanced("calibrate crystal formation"),
+            CommandResult::Success(ParsedCommand::Examine { target }) if target == "crystal formation"
+        ));
+        assert_eq!(parser.split_chain("look and calibrate crystal"), vec!["look".to_string(), "calibrate crystal".to_string()]);
+        assert!(parser.add_verb("look", "take").is_err());
+    }
+
+    #[test]
+    fn test_chained_input_splits_into_commands() {
+        let parser = CommandParser::new();
+        assert_eq!(
+            parser.split_chain("go north then examine the array and talk to lyra"),
+            vec!["go north", "examine the array", "talk to lyra"]
+        );
+        assert_eq!(parser.split_chain("take lantern; go up, and then look"), vec!["take lantern", "go up", "look"]);
+        assert_eq!(parser.split_chain("craft mortar and pestle"), vec!["craft mortar and pestle"]);
+        assert_eq!(parser.split_chain("macro gq go north; examine crystals"), vec!["macro gq go north; examine crystals"]);
+    }
+
+    #[test]
+    fn test_map_annotation_parsing() {
+        let parser = CommandParser::new();
+
+        assert!(matches!(parser.parse_advanced("map"), CommandResult::Success(ParsedCommand::ShowMap)));
+
+        match parser.parse_advanced("annotate good foraging") {
+            CommandResult::Success(ParsedCommand::Annotate { marker, note }) => {
+                assert_eq!(marker, None);
+                assert_eq!(note, "good foraging");
+            }
+            other => panic!("Expected annotate command, got: {:?}", other),
+        }
+
+        match parser.parse_advanced("mark ! avoid patrols at night") {
+            CommandResult::Success(ParsedCommand::Annotate { marker, note }) => {
+                assert_eq!(marker, Some('!'));
+                assert_eq!(note, "avoid patrols at night");
+            }
+            other => panic!("Expected mark command, got: {:?}", other),
+        }
+
+        assert!(matches!(parser.parse_advanced("mark hello"), CommandResult::Error(_)));
+    }
+
+    #[test]
+    fn test_explain_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("explain Wave Physics") {
+            CommandResult::Success(ParsedCommand::Explain { concept }) => {
+                assert_eq!(concept.as_deref(), Some("wave physics"));
+            }
+            other => panic!("Expected explain command, got: {:?}", other),
+        }
+
+        assert!(matches!(
+            parser.parse_advanced("concepts"),
+            CommandResult::Success(ParsedCommand::Explain { concept: None })
+        ));
+    }
+
+    #[test]
+    fn test_calculate_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("calculate Resonance healing 6") {
+            CommandResult::Success(ParsedCommand::Calculate { query }) => {
+                assert_eq!(query.as_deref(), Some("resonance healing 6"));
+            }
+            other => panic!("Expected calculate command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("calc"), CommandResult::Success(ParsedCommand::Calculate { query: None })));
+    }
+
+    #[test]
+    fn test_defeat_policy_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("defeat policy capture") {
+            CommandResult::Success(ParsedCommand::DefeatPolicy { policy }) => {
+                assert_eq!(policy.as_deref(), Some("capture"));
+            }
+            other => panic!("Expected defeat policy command, got: {:?}", other),
+        }
+
+        assert!(matches!(
+            parser.parse_advanced("defeat policy"),
+            CommandResult::Success(ParsedCommand::DefeatPolicy { policy: None })
+        ));
+    }
+
+    #[test]
+    fn test_difficulty_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("difficulty Hard") {
+            CommandResult::Success(ParsedCommand::Difficulty { level }) => {
+                assert_eq!(level.as_deref(), Some("hard"));
+            }
+            other => panic!("Expected difficulty command, got: {:?}", other),
+        }
+
+        assert!(matches!(
+            parser.parse_advanced("difficulty"),
+            CommandResult::Success(ParsedCommand::Difficulty { level: None })
+        ));
+    }
+
+    #[test]
+    fn test_craft_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("craft Healing Salve") {
+            CommandResult::Success(ParsedCommand::CraftItem { action, items, recipe }) => {
+                assert_eq!(action, "craft");
+                assert!(items.is_empty());
+                assert_eq!(recipe.as_deref(), Some("healing salve"));
+            }
+            other => panic!("Expected craft command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("recipes"), CommandResult::Success(ParsedCommand::Recipes)));
+
+        match parser.parse_advanced("clean up Alchemy Bench") {
+            CommandResult::Success(ParsedCommand::CleanUp { target }) => assert_eq!(target.as_deref(), Some("alchemy bench")),
+            other => panic!("Expected clean up command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("clean up"), CommandResult::Success(ParsedCommand::CleanUp { target: None })));
+    }
+
+    #[test]
+    fn test_hide_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("stash Silver Locket") {
+            CommandResult::Success(ParsedCommand::Hide { item }) => assert_eq!(item, "silver locket"),
+            other => panic!("Expected hide command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("caches"), CommandResult::Success(ParsedCommand::Caches)));
+        assert!(matches!(parser.parse_advanced("buy laboratory"), CommandResult::Success(ParsedCommand::BuyLaboratory)));
+        assert!(matches!(parser.parse_advanced("emissaries"), CommandResult::Success(ParsedCommand::Emissary { choice: None })));
+        assert!(matches!(parser.parse_advanced("emissary 2"), CommandResult::Success(ParsedCommand::Emissary { choice: Some(2) })));
+        assert!(matches!(parser.parse_advanced("emissary yes"), CommandResult::Error(_)));
+        match parser.parse_advanced("upgrade garden") {
+            CommandResult::Success(ParsedCommand::UpgradeFixture { fixture }) => assert_eq!(fixture, "garden"),
+            other => panic!("Expected upgrade command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_repair_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("repair Scholar's Gloves with Marcus") {
+            CommandResult::Success(ParsedCommand::Repair { item, npc }) => {
+                assert_eq!(item.as_deref(), Some("scholar's gloves"));
+                assert_eq!(npc.as_deref(), Some("marcus"));
+            }
+            other => panic!("Expected repair command, got: {:?}", other),
+        }
+        assert!(matches!(
+            parser.parse_advanced("repair"),
+            CommandResult::Success(ParsedCommand::Repair { item: None, npc: None })
+        ));
+    }
+
+    #[test]
+    fn test_combine_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("combine Resonance Lens with brass frame") {
+            CommandResult::Success(ParsedCommand::Combine { first, second }) => {
+                assert_eq!(first, "resonance lens");
+                assert_eq!(second, "brass frame");
+            }
+            other => panic!("Expected combine command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("combine lens"), CommandResult::Error(_)));
+        match parser.parse_advanced("take apart crystal tincture") {
+            CommandResult::Success(ParsedCommand::Deconstruct { item }) => assert_eq!(item, "crystal tincture"),
+            other => panic!("Expected deconstruct command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_read_flux_parsing() {
+        let parser = CommandParser::new();
+        assert!(matches!(parser.parse_advanced("Read Flux"), CommandResult::Success(ParsedCommand::ReadFlux)));
+        assert!(matches!(parser.parse_advanced("flux"), CommandResult::Success(ParsedCommand::ReadFlux)));
+    }
+
+    #[test]
+    fn test_retire_parsing() {
+        let parser = CommandParser::new();
+        assert!(matches!(
+            parser.parse_advanced("Retire with notes"),
+            CommandResult::Success(ParsedCommand::Retire { leave_notes: Some(true) })
+        ));
+        assert!(matches!(parser.parse_advanced("retire"), CommandResult::Success(ParsedCommand::Retire { leave_notes: None })));
+        assert!(matches!(parser.parse_advanced("read notebook"), CommandResult::Success(ParsedCommand::ReadNotebook)));
+    }
+
+    #[test]
+    fn test_bestiary_parsing() {
+        let parser = CommandParser::new();
+
+        assert!(matches!(
+            parser.parse_advanced("bestiary"),
+            CommandResult::Success(ParsedCommand::Bestiary { enemy: None })
+        ));
+
+        match parser.parse_advanced("bestiary static wisp") {
+            CommandResult::Success(ParsedCommand::Bestiary { enemy }) => {
+                assert_eq!(enemy.as_deref(), Some("static wisp"));
+            }
+            other => panic!("Expected bestiary command, got: {:?}", other),
+        }
+
+        assert!(matches!(parser.parse_advanced("codex"), CommandResult::Success(ParsedCommand::Codex { setting: None })));
+        assert!(matches!(parser.parse_advanced("stats"), CommandResult::Success(ParsedCommand::Stats { category: None })));
+        match parser.parse_advanced("statistics spells") {
+            CommandResult::Success(ParsedCommand::Stats { category }) => assert_eq!(category.as_deref(), Some("spells")),
+            other => panic!("Expected stats command, got: {:?}", other),
+        }
+        match parser.parse_advanced("codex spoilers off") {
+            CommandResult::Success(ParsedCommand::Codex { setting }) => assert_eq!(setting.as_deref(), Some("spoilers off")),
+            other => panic!("Expected codex command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_components_parsing() {
+        let parser = CommandParser::new();
+        match parser.parse_advanced("components for Manipulation") {
+            CommandResult::Success(ParsedCommand::Components { spell }) => assert_eq!(spell, "manipulation"),
+            other => panic!("Expected components command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_ward_parsing() {
+        let parser = CommandParser::new();
+        assert!(matches!(parser.parse_advanced("wards"), CommandResult::Success(ParsedCommand::Ward { kind: None })));
+        match parser.parse_advanced("ward dampener") {
+            CommandResult::Success(ParsedCommand::Ward { kind }) => assert_eq!(kind.as_deref(), Some("dampener")),
+            other => panic!("Expected ward command, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_style_parsing() {
+        let parser = CommandParser::new();
+        match parser.parse_advanced("learn harmony-attuned from Elara") {
+            CommandResult::Success(ParsedCommand::LearnStyle { style, mentor }) => {
+                assert_eq!(style, "harmony-attuned");
+                assert_eq!(mentor, "elara");
+            }
+            other => panic!("Expected learn style command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("learn harmony"), CommandResult::Error(_)));
+        assert!(matches!(parser.parse_advanced("style"), CommandResult::Success(ParsedCommand::Style { style: None })));
+    }
+
+    #[test]
+    fn test_combat_log_parsing() {
+        let parser = CommandParser::new();
+        assert!(matches!(parser.parse_advanced("combat log"), CommandResult::Success(ParsedCommand::CombatLog)));
+        assert!(matches!(parser.parse_advanced("Battle Log"), CommandResult::Success(ParsedCommand::CombatLog)));
+    }
+
+    #[test]
+    fn test_party_commands_parsing() {
+        let parser = CommandParser::new();
+
+        match parser.parse_advanced("recruit Captain Vera") {
+            CommandResult::Success(ParsedCommand::Recruit { target }) => assert_eq!(target, "captain vera"),
+            other => panic!("Expected recruit command, got: {:?}", other),
+        }
+        match parser.parse_advanced("dismiss thomas") {
+            CommandResult::Success(ParsedCommand::Dismiss { target }) => assert_eq!(target, "thomas"),
+            other => panic!("Expected dismiss command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("companions"), CommandResult::Success(ParsedCommand::Party)));
+        match parser.parse_advanced("invite Dr. Felix") {
+            CommandResult::Success(ParsedCommand::Invite { target }) => assert_eq!(target, "dr. felix"),
+            other => panic!("Expected invite command, got: {:?}", other),
+        }
+        assert!(matches!(parser.parse_advanced("followers"), CommandResult::Success(ParsedCommand::Followers)));
+    }
+
+    #[test]
+    fn test_combat_tactic_parsing() {
+        let parser = CommandParser::new();
+
+        for word in ["parley", "Intimidate", "demoralize", "surrender"] {
+            match parser.parse_advanced(word) {
+                CommandResult::Success(ParsedCommand::CombatTactic { tactic }) => assert_eq!(tactic, word.to_lowercase()),
+                other => panic!("Expected combat tactic, got: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_legacy_parsing() {
+        let parser = CommandParser::new();
+        assert!(matches!(parser.parse_advanced("legacy"), CommandResult::Success(ParsedCommand::Legacy)));
+        assert!(matches!(parser.parse_advanced("influence"), CommandResult::Success(ParsedCommand::Legacy)));
+    }
+
+    #[test]
+    fn test_porter_parsing() {
+        let parser = CommandParser::new();
+
+        for (input, action) in [
+            ("hire porter", "hire 1"),
+            ("Hire porter for 3 days", "hire 3"),
+            ("dismiss porter", "dismiss"),
+        ] {
+            match parser.parse_advanced(input) {
+                CommandResult::Success(ParsedCommand::Porter { action: Some(parsed) }) => assert_eq!(parsed, action),
+                other => panic!("Expected porter command for '{}', got: {:?}", input, other),
+            }
+        }
+        assert!(matches!(parser.parse_advanced("load"), CommandResult::Success(ParsedCommand::Porter { action: None })));
+    }
+
+    #[test]
+    fn test_coop_parsing() {
+        let parser = CommandParser::new();
+        assert!(matches!(parser.parse_advanced("coop"), CommandResult::Success(ParsedCommand::Coop { action: None })));
+        assert!(matches!(parser.parse_advanced("rest until recovered"), CommandResult::Success(ParsedCommand::RestUntilRecovered)));
+        match parser.parse_advanced("coop link Mira") {
+            CommandResult::Success(ParsedCommand::Coop { action: Some(action) }) => assert_eq!(action, "link mira"),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
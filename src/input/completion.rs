@@ -0,0 +1,159 @@
+//! Tab completion at the command prompt
+//!
+//! Pressing tab completes the word being typed. The first word completes
+//! over command verbs; anything after it over what's at hand right now:
+//! - Items lying here and in the player's pack
+//! - The people present
+//! - The exits out of here
+//! - The theories the player can study
+//!
+//! Names can run to several words, so "talk to sage m" completes to the
+//! whole of "Sage Meridian". What's at hand is gathered fresh before each
+//! prompt.
+
+use crate::core::{Player, WorldState};
+use crate::systems::dialogue::DialogueSystem;
+use crate::systems::knowledge::KnowledgeSystem;
+use crate::systems::quests::QuestSystem;
+use rustyline::completion::Completer;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+
+/// Command verbs offered for the first word, and that can start a chained command
+pub const VERBS: &[&str] = &[
+    "actions", "analyze", "appraise", "ask", "assess", "bestiary", "buy", "calendar", "cast", "codex", "combine",
+    "craft", "deconstruct", "dismiss", "down", "drop", "east", "equip", "examine", "factions",
+    "followers", "forecast", "give", "go", "haggle", "help", "hide", "inventory", "invite", "journal",
+    "laboratory", "legacy", "load", "look", "map", "north", "pay", "persuade", "quest", "quit",
+    "recruit", "relationships", "remove", "repair", "research", "rest", "save", "saves", "search", "services",
+    "sneak", "south", "status", "study", "take", "talk", "touch", "travel", "undo", "unequip", "unlock", "up",
+    "use", "wanted", "wear", "west",
+];
+
+/// Everything tab can complete to at the moment
+#[derive(Debug, Clone, Default)]
+pub struct Completions {
+    names: Vec<String>,
+}
+
+impl Completions {
+    /// Gather what's at hand for the player right now
+    pub fn gather(
+        player: &Player,
+        world: &WorldState,
+        dialogue_system: &DialogueSystem,
+        quest_system: &QuestSystem,
+        knowledge_system: &KnowledgeSystem,
+    ) -> Self {
+        let mut names: Vec<String> = Vec::new();
+        if let Some(location) = world.current_location() {
+            names.extend(location.items.iter().map(|id| crate::systems::placed_items::display_name(id)));
+            names.extend(location.exits.keys().map(|direction| direction.display_name().to_string()));
+        }
+        names.extend(world.ground.at(&world.current_location).iter().map(|entry| entry.item.properties.name.clone()));
+        if let Some(items) = &player.inventory.enhanced_items {
+            names.extend(items.inventory_manager.items.values().map(|item| item.properties.name.clone()));
+        }
+        names.extend(dialogue_system.npcs_present(&world.current_location, world, quest_system).iter().map(|npc| npc.name.clone()));
+        if let Ok(theories) = knowledge_system.get_accessible_theories(player) {
+            names.extend(theories.iter().map(|theory| theory.id.clone()));
+        }
+        Self::from_names(names)
+    }
+
+    /// Completions over a given set of names
+    pub fn from_names(mut names: Vec<String>) -> Self {
+        names.sort_by_key(|name| name.to_lowercase());
+        names.dedup_by_key(|name| name.to_lowercase());
+        Self { names }
+    }
+
+    /// Where the completed text starts in the line, and what it could be
+    pub fn complete(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let line = &line[..pos];
+        let Some(first_word_end) = line.find(char::is_whitespace) else {
+            let typed = line.to_lowercase();
+            let verbs = VERBS.iter().filter(|verb| verb.starts_with(&typed)).map(|verb| verb.to_string()).collect();
+            return (0, verbs);
+        };
+
+        // Try the longest stretch of typed words first, so a name's later
+        // words complete along with its first
+        let starts = line.char_indices()
+            .filter(|(index, c)| *index > first_word_end && !c.is_whitespace() && line[..*index].ends_with(char::is_whitespace))
+            .map(|(index, _)| index)
+            .chain(line.ends_with(char::is_whitespace).then_some(pos));
+        for start in starts {
+            let typed = line[start..].to_lowercase();
+            let matches: Vec<String> = self.names.iter()
+                .filter(|name| name.to_lowercase().starts_with(&typed))
+                .cloned()
+                .collect();
+            if !matches.is_empty() {
+                return (start, matches);
+            }
+        }
+        (pos, Vec::new())
+    }
+}
+
+/// The line editor's helper, completing from what was last gathered
+#[derive(Debug, Default)]
+pub struct CommandHelper {
+    pub completions: Completions,
+}
+
+impl Completer for CommandHelper {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.completions.complete(line, pos))
+    }
+}
+
+impl Hinter for CommandHelper {
+    type Hint = String;
+}
+
+impl Highlighter for CommandHelper {}
+
+impl Validator for CommandHelper {}
+
+impl Helper for CommandHelper {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at_hand() -> Completions {
+        Completions::from_names(vec![
+            "Sage Meridian".to_string(),
+            "quartz crystal".to_string(),
+            "Quartz Crystal".to_string(),
+            "north".to_string(),
+            "resonance_foundation".to_string(),
+        ])
+    }
+
+    #[test]
+    fn test_first_word_completes_over_verbs() {
+        let completions = at_hand();
+        assert_eq!(completions.complete("exa", 3), (0, vec!["examine".to_string()]));
+        let (start, verbs) = completions.complete("s", 1);
+        assert_eq!(start, 0);
+        assert!(verbs.contains(&"sneak".to_string()) && verbs.contains(&"study".to_string()));
+    }
+
+    #[test]
+    fn test_later_words_complete_over_what_is_at_hand() {
+        let completions = at_hand();
+        assert_eq!(completions.complete("talk to sage m", 14), (8, vec!["Sage Meridian".to_string()]));
+        assert_eq!(completions.complete("take qu", 7), (5, vec!["quartz crystal".to_string()]));
+        assert_eq!(completions.complete("study reso", 10), (6, vec!["resonance_foundation".to_string()]));
+        assert_eq!(completions.complete("go no", 5), (3, vec!["north".to_string()]));
+        assert_eq!(completions.complete("take zz", 7), (7, Vec::new()));
+        assert_eq!(completions.complete("go ", 3).1.len(), 4);
+    }
+}
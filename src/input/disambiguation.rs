@@ -0,0 +1,167 @@
+//! Asking which thing the player meant
+//!
+//! When a command names something loosely enough to match several different
+//! things at hand ("take crystal" with three kinds of crystal here), the
+//! player is asked which one they meant, rather than being handed whichever
+//! matched first:
+//!
+//! ```text
+//! Which crystal? 1) cracked quartz 2) quartz crystal 3) raw amethyst crystal
+//! ```
+//!
+//! The command waits for an answer, by number or by name. Entering any other
+//! command sets it aside, and `cancel` drops it.
+
+use crate::core::{Player, WorldState};
+use crate::input::ParsedCommand;
+
+/// A command waiting for the player to say which thing they meant
+#[derive(Debug, Clone)]
+pub struct PendingChoice {
+    command: ParsedCommand,
+    /// What the player called it
+    noun: String,
+    options: Vec<String>,
+}
+
+/// The thing a command acts on, if it's one that can be ambiguous
+fn target(command: &ParsedCommand) -> Option<&str> {
+    match command {
+        ParsedCommand::Take { item }
+        | ParsedCommand::Drop { item }
+        | ParsedCommand::Hide { item }
+        | ParsedCommand::StoreItem { item }
+        | ParsedCommand::UseItem { item, .. }
+        | ParsedCommand::Deconstruct { item }
+        | ParsedCommand::ExamineItem { item }
+        | ParsedCommand::GiveItem { item, .. } => Some(item),
+        _ => None,
+    }
+}
+
+/// The same command acting on a named thing
+fn with_target(command: &ParsedCommand, name: &str) -> ParsedCommand {
+    let mut command = command.clone();
+    match &mut command {
+        ParsedCommand::Take { item }
+        | ParsedCommand::Drop { item }
+        | ParsedCommand::Hide { item }
+        | ParsedCommand::StoreItem { item }
+        | ParsedCommand::UseItem { item, .. }
+        | ParsedCommand::Deconstruct { item }
+        | ParsedCommand::ExamineItem { item }
+        | ParsedCommand::GiveItem { item, .. } => *item = name.to_string(),
+        _ => {}
+    }
+    command
+}
+
+/// Names of the things a command could be reaching for: what's lying here
+/// for `take`, and what the player carries for everything else
+fn things_at_hand(command: &ParsedCommand, player: &Player, world: &WorldState) -> Vec<String> {
+    match command {
+        ParsedCommand::Take { .. } => {
+            let dropped = world.ground.at(&world.current_location).iter().map(|entry| entry.item.properties.name.clone());
+            let stocked = world.current_location().into_iter()
+                .flat_map(|location| location.items.iter().map(|id| crate::systems::placed_items::display_name(id)));
+            dropped.chain(stocked).collect()
+        }
+        _ => player.inventory.enhanced_items.as_ref()
+            .map(|items| items.inventory_manager.items.values().map(|item| item.properties.name.clone()).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// Whether the player wants to drop a pending choice
+pub fn cancels(input: &str) -> bool {
+    matches!(input.trim().to_lowercase().as_str(), "cancel" | "never mind" | "nevermind")
+}
+
+/// A choice to put to the player, if a command could mean several different things
+pub fn check(command: &ParsedCommand, player: &Player, world: &WorldState) -> Option<PendingChoice> {
+    let noun = target(command)?.trim().to_lowercase();
+    let mut options: Vec<String> = things_at_hand(command, player, world).into_iter()
+        .filter(|name| name.to_lowercase().contains(&noun))
+        .collect();
+    options.sort_by_key(|name| name.to_lowercase());
+    options.dedup_by_key(|name| name.to_lowercase());
+
+    // Naming one of them exactly is no question at all
+    if options.len() < 2 || options.iter().any(|name| name.to_lowercase() == noun) {
+        return None;
+    }
+    Some(PendingChoice { command: command.clone(), noun, options })
+}
+
+impl PendingChoice {
+    /// The question put to the player
+    pub fn prompt(&self) -> String {
+        let options: Vec<String> = self.options.iter().enumerate()
+            .map(|(index, name)| format!("{}) {}", index + 1, name))
+            .collect();
+        format!("Which {}? {}", self.noun, options.join(" "))
+    }
+
+    /// The command the player meant, if their answer picks out one option
+    pub fn answer(&self, input: &str) -> Option<ParsedCommand> {
+        let input = input.trim().trim_end_matches(')').trim_start_matches("the ").to_lowercase();
+        if let Ok(number) = input.parse::<usize>() {
+            return self.options.get(number.checked_sub(1)?).map(|name| with_target(&self.command, name));
+        }
+        if input.is_empty() {
+            return None;
+        }
+
+        let exact = self.options.iter().find(|name| name.to_lowercase() == input);
+        let matching: Vec<&String> = self.options.iter().filter(|name| name.to_lowercase().contains(&input)).collect();
+        let chosen = exact.or(match matching.as_slice() {
+            [name] => Some(*name),
+            _ => None,
+        })?;
+        Some(with_target(&self.command, chosen))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::Location;
+
+    fn workshop() -> WorldState {
+        let mut world = WorldState::new();
+        let mut location = Location::new("workshop".to_string(), "Workshop".to_string(), "Benches.".to_string());
+        location.items = vec!["cracked_quartz".to_string(), "quartz_crystal".to_string(), "raw_amethyst_crystal".to_string(), "spring_water".to_string()];
+        world.add_location(location);
+        world.current_location = "workshop".to_string();
+        world
+    }
+
+    #[test]
+    fn test_loose_names_ask_which_one() {
+        let world = workshop();
+        let player = Player::new("Tester".to_string());
+        let take = |item: &str| ParsedCommand::Take { item: item.to_string() };
+
+        let choice = check(&take("crystal"), &player, &world).unwrap();
+        assert_eq!(choice.prompt(), "Which crystal? 1) quartz crystal 2) raw amethyst crystal");
+        assert!(check(&take("quartz crystal"), &player, &world).is_none());
+        assert!(check(&take("water"), &player, &world).is_none());
+        assert!(check(&ParsedCommand::Drop { item: "crystal".to_string() }, &player, &world).is_none());
+        assert!(check(&ParsedCommand::Look { target: None }, &player, &world).is_none());
+    }
+
+    #[test]
+    fn test_answers_by_number_or_name() {
+        let world = workshop();
+        let player = Player::new("Tester".to_string());
+        let choice = check(&ParsedCommand::Take { item: "quartz".to_string() }, &player, &world).unwrap();
+
+        assert!(matches!(choice.answer("2"), Some(ParsedCommand::Take { item }) if item == "quartz crystal"));
+        assert!(matches!(choice.answer("the cracked quartz"), Some(ParsedCommand::Take { item }) if item == "cracked quartz"));
+        assert!(matches!(choice.answer("cracked"), Some(ParsedCommand::Take { item }) if item == "cracked quartz"));
+        assert!(choice.answer("3").is_none());
+        assert!(choice.answer("0").is_none());
+        assert!(choice.answer("look").is_none());
+        assert!(cancels("Never mind"));
+    }
+}
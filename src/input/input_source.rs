@@ -0,0 +1,192 @@
+//! Where the game's commands come from
+//!
+//! The game loop reads from an [`InputSource`]: normally the interactive
+//! line editor, with history and tab completion, or a script of commands
+//! played back one per line. A script is echoed after the prompt as it's
+//! read, so the output of a playback reads as a transcript of the session:
+//!
+//! ```text
+//! # Reach the archive and study there
+//! go north
+//! take quartz crystal
+//! study resonance_foundation
+//! ```
+//!
+//! Blank lines and lines starting with `#` are skipped.
+
+use crate::input::completion::{CommandHelper, Completions};
+use crate::ui::tui::StatusPanel;
+use crate::GameResult;
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::Editor;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+/// What reading the next line produced
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputLine {
+    /// A command to run
+    Command(String),
+    /// The player pressed Ctrl+C
+    Interrupted,
+    /// No more input is coming
+    Closed,
+}
+
+/// A source of commands for the game loop
+pub trait InputSource {
+    /// Read the next line, showing the prompt first
+    fn read_line(&mut self, prompt: &str) -> GameResult<InputLine>;
+
+    /// Offer what's at hand for tab completion
+    fn offer_completions(&mut self, _completions: Completions) {}
+
+    /// Show the player output from the game
+    fn show(&mut self, text: &str) {
+        println!("{}", text);
+    }
+
+    /// Offer the player's state, for an interface that keeps it on screen
+    fn offer_status(&mut self, _status: StatusPanel) {}
+
+    /// The commands read so far, oldest first
+    fn history(&self) -> Vec<String>;
+
+    /// Tidy up once the session is over
+    fn finish(&mut self) {}
+}
+
+/// Where command history is kept between sessions
+pub fn history_path() -> GameResult<PathBuf> {
+    match dirs::data_dir() {
+        Some(data_dir) => {
+            let app_dir = data_dir.join("SympatheticResonance");
+            std::fs::create_dir_all(&app_dir)?;
+            Ok(app_dir.join("command_history.txt"))
+        }
+        None => Ok(PathBuf::from("command_history.txt")),
+    }
+}
+
+/// The interactive line editor, with command history kept between sessions
+pub struct ReadlineInput {
+    editor: Editor<CommandHelper, DefaultHistory>,
+    history_path: PathBuf,
+}
+
+impl ReadlineInput {
+    /// Open the line editor, loading history from the given file if it exists
+    pub fn new(history_path: PathBuf) -> GameResult<Self> {
+        let mut editor = Editor::new()
+            .map_err(|e| anyhow::anyhow!("Failed to create readline editor: {}", e))?;
+        editor.set_helper(Some(CommandHelper::default()));
+        // A missing history file just means a first session
+        let _ = editor.load_history(&history_path);
+        Ok(Self { editor, history_path })
+    }
+}
+
+impl InputSource for ReadlineInput {
+    fn read_line(&mut self, prompt: &str) -> GameResult<InputLine> {
+        match self.editor.readline(prompt) {
+            Ok(line) => {
+                let line = line.trim();
+                if !line.is_empty() {
+                    let _ = self.editor.add_history_entry(line);
+                }
+                Ok(InputLine::Command(line.to_string()))
+            }
+            Err(ReadlineError::Interrupted) => Ok(InputLine::Interrupted),
+            Err(ReadlineError::Eof) => Ok(InputLine::Closed),
+            Err(err) => Err(anyhow::anyhow!("Readline error: {}", err)),
+        }
+    }
+
+    fn history(&self) -> Vec<String> {
+        self.editor.history().iter().cloned().collect()
+    }
+
+    fn offer_completions(&mut self, completions: Completions) {
+        if let Some(helper) = self.editor.helper_mut() {
+            helper.completions = completions;
+        }
+    }
+
+    fn finish(&mut self) {
+        if let Err(e) = self.editor.save_history(&self.history_path) {
+            eprintln!("Failed to save command history: {}", e);
+        }
+    }
+}
+
+/// Commands played back from a script, echoed as they're read
+#[derive(Debug, Clone, Default)]
+pub struct ScriptInput {
+    lines: VecDeque<String>,
+    played: Vec<String>,
+}
+
+impl ScriptInput {
+    /// Read a script file
+    pub fn load(path: &Path) -> GameResult<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            crate::GameError::ContentNotFound(format!("Could not read script {}: {}", path.display(), e))
+        })?;
+        Ok(Self::parse(&text))
+    }
+
+    /// The commands in a script's text, skipping blank lines and comments
+    pub fn parse(text: &str) -> Self {
+        let lines = text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::to_string)
+            .collect();
+        Self { lines, played: Vec::new() }
+    }
+
+    /// Commands still to be played
+    pub fn remaining(&self) -> usize {
+        self.lines.len()
+    }
+}
+
+impl InputSource for ScriptInput {
+    fn read_line(&mut self, prompt: &str) -> GameResult<InputLine> {
+        match self.lines.pop_front() {
+            Some(line) => {
+                println!("{}{}", prompt, line);
+                self.played.push(line.clone());
+                Ok(InputLine::Command(line))
+            }
+            None => Ok(InputLine::Closed),
+        }
+    }
+
+    fn history(&self) -> Vec<String> {
+        self.played.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts_skip_comments_and_blank_lines() {
+        let script = ScriptInput::parse("# Opening moves\nlook\n\n  take quartz crystal  \n# done\n");
+        assert_eq!(script.remaining(), 2);
+        assert_eq!(script.lines, vec!["look".to_string(), "take quartz crystal".to_string()]);
+    }
+
+    #[test]
+    fn test_scripts_play_in_order_then_close() {
+        let mut script = ScriptInput::parse("look\ninventory");
+        assert_eq!(script.read_line("> ").unwrap(), InputLine::Command("look".to_string()));
+        assert_eq!(script.read_line("> ").unwrap(), InputLine::Command("inventory".to_string()));
+        assert_eq!(script.read_line("> ").unwrap(), InputLine::Closed);
+        assert_eq!(script.history(), vec!["look".to_string(), "inventory".to_string()]);
+        assert!(ScriptInput::load(Path::new("no/such/script.txt")).is_err());
+    }
+}
@@ -3,11 +3,23 @@
 //! This module handles:
 //! - Natural language command parsing
 //! - Command recognition and validation
+//! - The vocabulary of verbs and synonyms, loaded from data
 //! - Input tokenization and intent recognition
+//! - Frequencies and durations given with a command
+//! - Command shortcuts expanded before parsing
+//! - Asking which thing the player meant when a command is ambiguous
+//! - Tab completion over verbs and whatever is at hand
+//! - Reading commands from the line editor or a script
 
 pub mod command_parser;
 pub mod natural_language;
+pub mod vocabulary;
+pub mod quantities;
 pub mod command_handlers;
+pub mod shortcuts;
+pub mod disambiguation;
+pub mod completion;
+pub mod input_source;
 
 pub use command_parser::{CommandParser, CommandResult, ParsedCommand};
 pub use natural_language::{InputTokenizer, CommandIntent};
@@ -1,10 +1,55 @@
 //! Natural language processing for command input
 //!
-//! This module handles tokenization and intent recognition for player commands
+//! This module handles tokenization and intent recognition for player commands,
+//! resolving pronouns like "it" or "her" against what the player last
+//! mentioned, and guessing which command a free-form sentence such as "I want
+//! to learn more about crystals" is after when it doesn't parse
 
+use crate::input::ParsedCommand;
+use crate::input::vocabulary::Vocabulary;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Pronouns that point back at the last thing mentioned
+const THING_PRONOUNS: &[&str] = &["it"];
+/// Pronouns that point back at the last person mentioned
+const PERSON_PRONOUNS: &[&str] = &["him", "her", "them"];
+/// Pronouns that are also possessives, and only point back at someone when
+/// nothing they could belong to follows: "ask her about it", but not "ask
+/// elara about her research"
+const POSSESSIVE_PRONOUNS: &[&str] = &["her"];
+/// Words that can follow a pronoun standing for someone
+const AFTER_OBJECT_WORDS: &[&str] = &[
+    "about", "to", "with", "for", "from", "at", "on", "in", "into", "by", "over", "using",
+    "the", "a", "an", "some", "and", "then",
+];
+/// Commands whose words are the player's own: pronouns in them are never
+/// resolved, and they're never split into a chain
+pub(crate) const FREE_TEXT_COMMANDS: &[&str] = &["annotate ", "mark ", "shortcut ", "shortcuts ", "alias ", "macro "];
+/// Abbreviations that only stand for a command on their own; in a sentence,
+/// "i" is the player talking about themselves
+const STANDALONE_SYNONYMS: &[&str] = &["i"];
+/// Phrases that give away what a free-form sentence wants, and the command
+/// each points at; `{}` takes whatever the sentence goes on to name. Earlier
+/// entries win, so the more telling phrases come first.
+const INTENT_CUES: &[(&[&str], &str)] = &[
+    (&["learn", "understand", "know more", "read up", "find out"], "research {}"),
+    (&["talk", "chat", "speak", "converse"], "talk to {}"),
+    (&["go to", "head to", "walk to", "get to", "get back to"], "travel to {}"),
+    (&["pick up", "grab", "get"], "take {}"),
+    (&["can't see", "cannot see", "too dark"], "cast light"),
+    (&["heal", "hurt", "wounded", "injured"], "cast healing"),
+    (&["tired", "sleep", "rest", "exhausted"], "rest"),
+    (&["where am i", "around me", "surroundings", "look around"], "look"),
+    (&["carrying", "my bag", "what do i have", "belongings"], "inventory"),
+    (&["how am i", "my health", "my stats"], "status"),
+    (&["should i do", "can i do", "stuck"], "actions"),
+    (&["task", "job", "errand", "mission"], "quests"),
+];
+/// Words between a cue and the thing a sentence names
+const FILLER_WORDS: &[&str] = &["more", "about", "with", "to", "up", "the", "a", "an", "some", "on", "into", "of"];
+
 /// Tokenizes raw input into meaningful components
 pub struct InputTokenizer {
     /// Patterns for recognizing different token types
@@ -20,7 +65,8 @@ struct TokenPattern {
 }
 
 /// Types of tokens that can be extracted from input
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TokenType {
     Verb,
     Object,
@@ -72,57 +118,24 @@ pub enum CommandIntent {
 }
 
 impl InputTokenizer {
-    /// Create a new tokenizer with default patterns
+    /// Create a new tokenizer knowing the built-in vocabulary
     pub fn new() -> Self {
+        Self::with_vocabulary(&Vocabulary::built_in())
+    }
+
+    /// Create a tokenizer knowing the given vocabulary
+    pub fn with_vocabulary(vocabulary: &Vocabulary) -> Self {
         let mut tokenizer = Self {
             token_patterns: Vec::new(),
-            synonyms: HashMap::new(),
+            synonyms: vocabulary.synonyms.iter().map(|(word, meaning)| (word.clone(), meaning.clone())).collect(),
         };
-
-        tokenizer.initialize_patterns();
-        tokenizer.initialize_synonyms();
+        for group in &vocabulary.word_kinds {
+            let words: Vec<String> = group.words.iter().map(|word| regex::escape(word)).collect();
+            tokenizer.add_pattern(&format!(r"\b({})\b", words.join("|")), group.kind.clone());
+        }
         tokenizer
     }
 
-    /// Set up token recognition patterns
-    fn initialize_patterns(&mut self) {
-        // Movement verbs
-        self.add_pattern(r"\b(go|move|walk|travel|head|proceed)\b", TokenType::Verb);
-
-        // Examination verbs
-        self.add_pattern(r"\b(look|examine|inspect|study|observe|check|analyze)\b", TokenType::Verb);
-
-        // Magic verbs
-        self.add_pattern(r"\b(cast|channel|focus|resonate|attune|use)\b", TokenType::Verb);
-
-        // Social verbs
-        self.add_pattern(r"\b(talk|speak|ask|tell|say|greet|converse)\b", TokenType::Verb);
-
-        // System verbs
-        self.add_pattern(r"\b(save|load|quit|exit|help|status|inventory|quest|quests)\b", TokenType::Verb);
-
-        // Item interaction verbs
-        self.add_pattern(r"\b(get|take|pick|grab|drop|give|put|place|hold|carry)\b", TokenType::Verb);
-        self.add_pattern(r"\b(equip|wear|wield|remove|unequip|don|doff)\b", TokenType::Verb);
-        self.add_pattern(r"\b(combine|craft|create|make|synthesize|enhance|repair)\b", TokenType::Verb);
-        self.add_pattern(r"\b(drink|eat|consume|apply|activate|trigger)\b", TokenType::Verb);
-
-        // Directions
-        self.add_pattern(r"\b(north|south|east|west|northeast|northwest|southeast|southwest|up|down|in|out|n|s|e|w|ne|nw|se|sw|u|d)\b", TokenType::Direction);
-
-        // Magic keywords
-        self.add_pattern(r"\b(using|with|through|via|crystal|magic|spell|energy|resonance)\b", TokenType::MagicKeyword);
-
-        // Prepositions
-        self.add_pattern(r"\b(to|at|on|in|with|using|through|about|for|from)\b", TokenType::Preposition);
-
-        // Articles
-        self.add_pattern(r"\b(the|a|an)\b", TokenType::Article);
-
-        // Common adjectives
-        self.add_pattern(r"\b(small|large|bright|dark|magical|ancient|broken|crystal|quartz|amethyst|obsidian|garnet)\b", TokenType::Adjective);
-    }
-
     /// Add a pattern to the tokenizer
     fn add_pattern(&mut self, pattern: &str, token_type: TokenType) {
         if let Ok(regex) = Regex::new(&format!("(?i){}", pattern)) {
@@ -133,35 +146,6 @@ impl InputTokenizer {
         }
     }
 
-    /// Set up common synonyms and abbreviations
-    fn initialize_synonyms(&mut self) {
-        // Movement synonyms
-        self.synonyms.insert("n".to_string(), "north".to_string());
-        self.synonyms.insert("s".to_string(), "south".to_string());
-        self.synonyms.insert("e".to_string(), "east".to_string());
-        self.synonyms.insert("w".to_string(), "west".to_string());
-        self.synonyms.insert("ne".to_string(), "northeast".to_string());
-        self.synonyms.insert("nw".to_string(), "northwest".to_string());
-        self.synonyms.insert("se".to_string(), "southeast".to_string());
-        self.synonyms.insert("sw".to_string(), "southwest".to_string());
-        self.synonyms.insert("u".to_string(), "up".to_string());
-        self.synonyms.insert("d".to_string(), "down".to_string());
-
-        // Examination synonyms
-        self.synonyms.insert("l".to_string(), "look".to_string());
-        self.synonyms.insert("ex".to_string(), "examine".to_string());
-        self.synonyms.insert("x".to_string(), "examine".to_string());
-
-        // Inventory synonyms
-        self.synonyms.insert("inv".to_string(), "inventory".to_string());
-        self.synonyms.insert("i".to_string(), "inventory".to_string());
-
-        // System synonyms
-        self.synonyms.insert("q".to_string(), "quit".to_string());
-        self.synonyms.insert("h".to_string(), "help".to_string());
-        self.synonyms.insert("stats".to_string(), "status".to_string());
-    }
-
     /// Tokenize input string into meaningful components
     pub fn tokenize(&self, input: &str) -> Vec<Token> {
         let mut tokens = Vec::new();
@@ -170,7 +154,8 @@ impl InputTokenizer {
 
         for (position, word) in words.iter().enumerate() {
             // Expand synonyms first
-            let expanded_word = self.synonyms.get(*word).unwrap_or(&word.to_string()).clone();
+            let standalone = words.len() == 1 || !STANDALONE_SYNONYMS.contains(word);
+            let expanded_word = self.synonyms.get(*word).filter(|_| standalone).unwrap_or(&word.to_string()).clone();
 
             // Find matching token type
             let mut token_type = None;
@@ -608,6 +593,121 @@ impl InputTokenizer {
     }
 }
 
+/// What the player last mentioned, so a later command can point back at it:
+/// "examine the crystal" then "take it", or "talk to Elara" then "ask her
+/// about resonance"
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceTracker {
+    thing: Option<String>,
+    person: Option<String>,
+}
+
+impl ReferenceTracker {
+    /// Replace pronouns standing as the object of a command with what they
+    /// refer to, keeping the rest of the input as it was typed; any without a
+    /// referent yet, and any inside quotes, are left as they are
+    pub fn resolve(&self, input: &str) -> String {
+        let lowered = input.trim().to_lowercase();
+        if FREE_TEXT_COMMANDS.iter().any(|command| lowered.starts_with(command)) {
+            return input.to_string();
+        }
+
+        let words: Vec<(usize, &str)> = input.split_whitespace()
+            .map(|word| (word.as_ptr() as usize - input.as_ptr() as usize, word))
+            .collect();
+        let mut resolved = String::with_capacity(input.len());
+        let mut copied = 0;
+        for (index, &(start, word)) in words.iter().enumerate() {
+            let lowered = word.to_lowercase();
+            // The command's own verb comes first; quoted words are the player's
+            let quoted = input[..start].matches('"').count() % 2 == 1;
+            let possessive = POSSESSIVE_PRONOUNS.contains(&lowered.as_str())
+                && words.get(index + 1).is_some_and(|(_, next)| !AFTER_OBJECT_WORDS.contains(&next.to_lowercase().as_str()));
+            if index == 0 || quoted || possessive {
+                continue;
+            }
+
+            let referent = if THING_PRONOUNS.contains(&lowered.as_str()) {
+                self.thing.as_ref()
+            } else if PERSON_PRONOUNS.contains(&lowered.as_str()) {
+                // "them" can mean a thing when no one has been mentioned
+                self.person.as_ref().or(self.thing.as_ref().filter(|_| lowered == "them"))
+            } else {
+                None
+            };
+            if let Some(referent) = referent {
+                resolved.push_str(&input[copied..start]);
+                resolved.push_str(referent);
+                copied = start + word.len();
+            }
+        }
+        resolved.push_str(&input[copied..]);
+        resolved
+    }
+
+    /// Remember what a command referred to
+    pub fn note(&mut self, command: &ParsedCommand) {
+        let (thing, person) = match command {
+            ParsedCommand::Look { target: Some(target) }
+            | ParsedCommand::Examine { target }
+            | ParsedCommand::Search { target }
+            | ParsedCommand::Touch { target }
+            | ParsedCommand::Take { item: target }
+            | ParsedCommand::Drop { item: target }
+            | ParsedCommand::Hide { item: target }
+            | ParsedCommand::StoreItem { item: target }
+            | ParsedCommand::RetrieveItem { item: target }
+            | ParsedCommand::UseItem { item: target, .. }
+            | ParsedCommand::Deconstruct { item: target }
+            | ParsedCommand::ExamineItem { item: target } => (Some(target), None),
+            ParsedCommand::GiveItem { item, target } => (Some(item), Some(target)),
+            ParsedCommand::Talk { target }
+            | ParsedCommand::Ask { target, .. }
+            | ParsedCommand::Persuade { target, .. }
+            | ParsedCommand::Locate { target }
+            | ParsedCommand::SpendTime { target, .. }
+            | ParsedCommand::Recruit { target }
+            | ParsedCommand::Invite { target }
+            | ParsedCommand::Haggle { npc: target }
+            | ParsedCommand::BuyService { npc: target, .. } => (None, Some(target)),
+            _ => (None, None),
+        };
+        if let Some(thing) = thing.filter(|thing| !thing.trim().is_empty()) {
+            self.thing = Some(thing.trim().to_string());
+        }
+        // Asking with no one named keeps talking to the same person
+        if let Some(person) = person.filter(|person| !person.trim().is_empty()) {
+            self.person = Some(person.trim().to_string());
+        }
+    }
+}
+
+/// The command a free-form sentence is most likely after, for when it
+/// doesn't parse as one: "I want to learn more about crystals" is after
+/// `research crystals`
+pub fn guess_intent(sentence: &str) -> Option<String> {
+    let words: Vec<String> = sentence.to_lowercase()
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'').to_string())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    INTENT_CUES.iter().find_map(|(cues, command)| {
+        cues.iter().find_map(|cue| {
+            let cue: Vec<&str> = cue.split(' ').collect();
+            let start = words.windows(cue.len()).position(|window| window == cue.as_slice())?;
+            if !command.contains("{}") {
+                return Some(command.to_string());
+            }
+            let object: Vec<&str> = words[start + cue.len()..].iter()
+                .map(String::as_str)
+                .skip_while(|word| FILLER_WORDS.contains(word))
+                .collect();
+            (!object.is_empty()).then(|| command.replace("{}", &object.join(" ")))
+        })
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -824,4 +924,62 @@ mod tests {
             _ => panic!("Expected item intent for potion use"),
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_pronouns_point_back_at_what_was_mentioned() {
+        let parser = crate::input::CommandParser::new();
+        let mut references = ReferenceTracker::default();
+        assert_eq!(references.resolve("take it"), "take it");
+
+        references.note(&ParsedCommand::Examine { target: "the cracked crystal".to_string() });
+        assert_eq!(references.resolve("take it"), "take the cracked crystal");
+        assert_eq!(references.resolve("take them"), "take the cracked crystal");
+
+        let talk = parser.parse_advanced("talk to elara");
+        if let crate::input::CommandResult::Success(command) = talk {
+            references.note(&command);
+        }
+        assert_eq!(references.resolve("ask her about resonance"), "ask elara about resonance");
+        assert_eq!(references.resolve("give it to him"), "give the cracked crystal to elara");
+    }
+
+    #[test]
+    fn test_free_text_and_unnamed_asking_are_left_alone() {
+        let mut references = ReferenceTracker::default();
+        references.note(&ParsedCommand::Take { item: "lantern".to_string() });
+        references.note(&ParsedCommand::Talk { target: "Marcus".to_string() });
+        references.note(&ParsedCommand::Ask { target: String::new(), topic: "it".to_string() });
+
+        assert_eq!(references.resolve("annotate left it here"), "annotate left it here");
+        assert_eq!(references.resolve("ask him about it"), "ask Marcus about lantern");
+        assert_eq!(references.resolve("ask  him   about it"), "ask  Marcus   about lantern");
+        assert_eq!(references.resolve("say \"give  it back\" to her"), "say \"give  it back\" to Marcus");
+    }
+
+    #[test]
+    fn test_possessive_her_is_not_a_reference() {
+        let mut references = ReferenceTracker::default();
+        references.note(&ParsedCommand::Talk { target: "elara".to_string() });
+
+        assert_eq!(references.resolve("ask elara about her research"), "ask elara about her research");
+        assert_eq!(references.resolve("give her the crystal"), "give elara the crystal");
+        assert_eq!(references.resolve("talk to her"), "talk to elara");
+    }
+
+    #[test]
+    fn test_sentences_are_matched_to_commands() {
+        assert_eq!(guess_intent("I want to learn more about crystals"), Some("research crystals".to_string()));
+        assert_eq!(guess_intent("Could I speak with Elara?"), Some("talk to elara".to_string()));
+        assert_eq!(guess_intent("I'd like to go to the market"), Some("travel to market".to_string()));
+        assert_eq!(guess_intent("It's too dark in here"), Some("cast light".to_string()));
+        assert_eq!(guess_intent("what should I do now"), Some("actions".to_string()));
+    }
+
+    #[test]
+    fn test_sentences_without_cues_are_not_guessed() {
+        assert_eq!(guess_intent("purple monkey dishwasher"), None);
+        // A cue that needs something named isn't enough on its own
+        assert_eq!(guess_intent("I want to learn"), None);
+        assert_eq!(guess_intent("restless winds"), None);
+    }
+}
@@ -0,0 +1,160 @@
+//! Quantities in commands
+//!
+//! Some commands take a number with a unit after them:
+//!
+//! ```text
+//! cast light at 7 hz for 10 minutes
+//! study harmonic_fundamentals for 2 hours
+//! ```
+//!
+//! A frequency follows "at" and a duration follows "for". Amounts can be
+//! digits ("7", "1.5"), small number words ("ten"), "a"/"an" for one, or
+//! "half an hour". Units can be written out or run onto the number, as in
+//! "7hz" or "90min". Hours are turned into minutes.
+
+/// Number words a player might spell out
+const NUMBER_WORDS: [(&str, f32); 13] = [
+    ("a", 1.0), ("an", 1.0), ("one", 1.0), ("two", 2.0), ("three", 3.0),
+    ("four", 4.0), ("five", 5.0), ("six", 6.0), ("seven", 7.0), ("eight", 8.0),
+    ("nine", 9.0), ("ten", 10.0), ("twelve", 12.0),
+];
+
+/// Longest run of words one quantity can take, as in "half an hour"
+const MAX_QUANTITY_WORDS: usize = 3;
+
+/// What a quantity measures
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unit {
+    Hertz,
+    Minutes,
+}
+
+/// An amount with its unit, durations already in minutes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity {
+    pub amount: f32,
+    pub unit: Unit,
+}
+
+/// The frequency and duration given with a command
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Parameters {
+    /// Frequency in Hz, from "at N hz"
+    pub frequency: Option<i32>,
+    /// Duration in minutes, from "for N minutes" or "for N hours"
+    pub duration: Option<i32>,
+}
+
+/// Read a quantity such as "7 hz", "90min" or "half an hour"
+pub fn parse_quantity(text: &str) -> Option<Quantity> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let (amount, unit) = match words.as_slice() {
+        ["half", "an", unit] => (0.5, *unit),
+        [amount, unit] => (parse_amount(amount)?, *unit),
+        [joined] => {
+            let split = joined.find(|c: char| c.is_ascii_alphabetic())?;
+            let (amount, unit) = joined.split_at(split);
+            (amount.parse::<f32>().ok()?, unit)
+        }
+        _ => return None,
+    };
+    if amount <= 0.0 {
+        return None;
+    }
+
+    let (unit, scale) = match unit {
+        "hz" | "hertz" => (Unit::Hertz, 1.0),
+        "m" | "min" | "mins" | "minute" | "minutes" => (Unit::Minutes, 1.0),
+        "h" | "hr" | "hrs" | "hour" | "hours" => (Unit::Minutes, 60.0),
+        _ => return None,
+    };
+    Some(Quantity { amount: amount * scale, unit })
+}
+
+/// Take "at <frequency>" and "for <duration>" out of a command, returning
+/// what's left and the parameters found
+pub fn split_parameters(input: &str) -> Result<(String, Parameters), String> {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let mut parameters = Parameters::default();
+    let mut rest = Vec::new();
+
+    let mut index = 0;
+    while index < words.len() {
+        let word = words[index];
+        let found = matches!(word, "at" | "for").then(|| {
+            (1..=MAX_QUANTITY_WORDS).rev()
+                .filter(|length| index + length < words.len())
+                .find_map(|length| {
+                    let quantity = parse_quantity(&words[index + 1..=index + length].join(" "))?;
+                    Some((quantity, length))
+                })
+        }).flatten();
+
+        match found {
+            Some((quantity, length)) if quantity.unit == Unit::Hertz && word == "at" => {
+                if parameters.frequency.is_some() {
+                    return Err("Give only one frequency.".to_string());
+                }
+                if quantity.amount.fract() != 0.0 {
+                    return Err(format!("Crystals resonate at whole frequencies, not {} Hz.", quantity.amount));
+                }
+                parameters.frequency = Some(quantity.amount as i32);
+                index += length + 1;
+            }
+            Some((quantity, length)) if quantity.unit == Unit::Minutes && word == "for" => {
+                if parameters.duration.is_some() {
+                    return Err("Give only one duration.".to_string());
+                }
+                parameters.duration = Some((quantity.amount.round() as i32).max(1));
+                index += length + 1;
+            }
+            _ => {
+                rest.push(word);
+                index += 1;
+            }
+        }
+    }
+    Ok((rest.join(" "), parameters))
+}
+
+/// A number written as digits or as a word
+fn parse_amount(word: &str) -> Option<f32> {
+    word.parse::<f32>().ok().filter(|amount| amount.is_finite()).or_else(|| {
+        NUMBER_WORDS.iter().find(|(name, _)| *name == word).map(|(_, amount)| *amount)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantities_read_units_and_number_words() {
+        assert_eq!(parse_quantity("7 hz"), Some(Quantity { amount: 7.0, unit: Unit::Hertz }));
+        assert_eq!(parse_quantity("7hz"), Some(Quantity { amount: 7.0, unit: Unit::Hertz }));
+        assert_eq!(parse_quantity("ten minutes"), Some(Quantity { amount: 10.0, unit: Unit::Minutes }));
+        assert_eq!(parse_quantity("1.5 hours"), Some(Quantity { amount: 90.0, unit: Unit::Minutes }));
+        assert_eq!(parse_quantity("an hour"), Some(Quantity { amount: 60.0, unit: Unit::Minutes }));
+        assert_eq!(parse_quantity("half an hour"), Some(Quantity { amount: 30.0, unit: Unit::Minutes }));
+        assert_eq!(parse_quantity("the guard"), None);
+        assert_eq!(parse_quantity("0 minutes"), None);
+    }
+
+    #[test]
+    fn test_parameters_are_taken_out_of_commands() {
+        let (rest, parameters) = split_parameters("cast light at 7 hz for 10 minutes").unwrap();
+        assert_eq!(rest, "cast light");
+        assert_eq!(parameters, Parameters { frequency: Some(7), duration: Some(10) });
+
+        let (rest, parameters) = split_parameters("harmonic_fundamentals for 2 hours").unwrap();
+        assert_eq!(rest, "harmonic_fundamentals");
+        assert_eq!(parameters.duration, Some(120));
+
+        let (rest, parameters) = split_parameters("cast healing at guard").unwrap();
+        assert_eq!(rest, "cast healing at guard");
+        assert_eq!(parameters, Parameters::default());
+
+        assert!(split_parameters("cast light at 7.5 hz").is_err());
+        assert!(split_parameters("cast light for 5 minutes for an hour").is_err());
+    }
+}
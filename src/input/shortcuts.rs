@@ -0,0 +1,310 @@
+//! Command shortcuts
+//!
+//! Short strings stand in for full commands. The first word of the input is
+//! looked up before parsing and, if it is a shortcut, replaced by the
+//! command it stands for, keeping the rest of the input: with `x` for
+//! `examine`, `x crystal` becomes `examine crystal`.
+//!
+//! A few shortcuts are built in. Players add their own in their settings,
+//! saved with the character; their own take precedence over the built-ins.
+//!
+//! Aliases and macros are kept in a command config file next to the saves,
+//! so they follow the player from one character to the next:
+//! - An alias works like a shortcut: `alias gn go north`
+//! - A macro runs several commands in turn, separated by semicolons:
+//!   `macro gq go north; examine crystals`. It stops at the first command
+//!   that fails.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// File in the save directory holding the player's aliases and macros
+pub const CONFIG_FILE: &str = "command_config.json";
+/// Most commands one macro can run
+const MAX_MACRO_STEPS: usize = 10;
+
+/// Shortcuts every player has
+pub const BUILT_IN: [(&str, &str); 13] = [
+    ("n", "go north"),
+    ("s", "go south"),
+    ("e", "go east"),
+    ("w", "go west"),
+    ("ne", "go northeast"),
+    ("nw", "go northwest"),
+    ("se", "go southeast"),
+    ("sw", "go southwest"),
+    ("u", "go up"),
+    ("d", "go down"),
+    ("l", "look"),
+    ("x", "examine"),
+    ("i", "inventory"),
+];
+
+/// Built-in shortcuts that only apply on their own: "i want to learn" is
+/// the player talking, not a look at the inventory
+const STANDALONE: [&str; 1] = ["i"];
+
+/// Words that can't be made into shortcuts, aliases or macros, so the
+/// settings stay reachable
+const RESERVED: [&str; 6] = ["shortcut", "shortcuts", "alias", "aliases", "macro", "macros"];
+
+/// The command a shortcut stands for
+fn lookup<'a>(key: &str, custom: &'a BTreeMap<String, String>) -> Option<&'a str> {
+    custom.get(key).map(String::as_str)
+        .or_else(|| BUILT_IN.iter().find(|(built_in, _)| *built_in == key).map(|(_, command)| *command))
+}
+
+/// Replace a leading shortcut with the command it stands for
+pub fn expand(input: &str, custom: &BTreeMap<String, String>) -> String {
+    let trimmed = input.trim();
+    let (first, rest) = trimmed.split_once(char::is_whitespace).unwrap_or((trimmed, ""));
+    let first = first.to_lowercase();
+    match lookup(&first, custom) {
+        Some(command) if rest.trim().is_empty() => command.to_string(),
+        Some(_) if STANDALONE.contains(&first.as_str()) && !custom.contains_key(&first) => input.to_string(),
+        Some(command) => format!("{} {}", command, rest.trim()),
+        None => input.to_string(),
+    }
+}
+
+/// Add a shortcut to the player's own
+pub fn define(custom: &mut BTreeMap<String, String>, key: &str, command: &str) -> crate::GameResult<String> {
+    let key = key.trim().to_lowercase();
+    let command = command.trim();
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return Err(crate::GameError::InvalidInput("A shortcut must be a single word.".to_string()).into());
+    }
+    if RESERVED.contains(&key.as_str()) {
+        return Err(crate::GameError::InvalidInput(format!("'{}' can't be used as a shortcut.", key)).into());
+    }
+    if command.is_empty() {
+        return Err(crate::GameError::InvalidInput(format!("What should '{}' stand for?", key)).into());
+    }
+    let message = match lookup(&key, custom) {
+        Some(previous) => format!("'{}' now stands for '{}' instead of '{}'.", key, command, previous),
+        None => format!("'{}' now stands for '{}'.", key, command),
+    };
+    custom.insert(key, command.to_string());
+    Ok(message)
+}
+
+/// Remove one of the player's own shortcuts
+pub fn remove(custom: &mut BTreeMap<String, String>, key: &str) -> crate::GameResult<String> {
+    let key = key.trim().to_lowercase();
+    custom.remove(&key)
+        .map(|command| format!("'{}' no longer stands for '{}'.", key, command))
+        .ok_or_else(|| crate::GameError::ContentNotFound(format!("You have no shortcut '{}'.", key)).into())
+}
+
+/// Every shortcut in effect, the player's own first
+pub fn describe(custom: &BTreeMap<String, String>) -> String {
+    let mut response = "=== Shortcuts ===\n".to_string();
+    if !custom.is_empty() {
+        response.push_str("\nYour shortcuts:\n");
+        for (key, command) in custom {
+            response.push_str(&format!("  {:<4} → {}\n", key, command));
+        }
+    }
+    response.push_str("\nBuilt in:\n");
+    for (key, command) in BUILT_IN.iter().filter(|(key, _)| !custom.contains_key(*key)) {
+        response.push_str(&format!("  {:<4} → {}\n", key, command));
+    }
+    response.push_str("\nUse 'shortcut add <key> <command>' to add your own, or 'shortcut remove <key>' to drop one.");
+    response
+}
+
+/// The player's aliases and macros, kept across characters
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandConfig {
+    #[serde(default)]
+    aliases: BTreeMap<String, String>,
+    #[serde(default)]
+    macros: BTreeMap<String, Vec<String>>,
+}
+
+/// A name for an alias or macro, if it can be used as one
+fn valid_name(name: &str, what: &str) -> crate::GameResult<String> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err(crate::GameError::InvalidInput(format!("An {} must be a single word.", what)).into());
+    }
+    if RESERVED.contains(&name.as_str()) || name == "remove" {
+        return Err(crate::GameError::InvalidInput(format!("'{}' can't be used as an {}.", name, what)).into());
+    }
+    Ok(name)
+}
+
+impl CommandConfig {
+    /// Load the config from a directory, empty if none has been written yet
+    pub fn load(directory: &Path) -> crate::GameResult<Self> {
+        let path = directory.join(CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to read command config: {}", e)))?;
+        serde_json::from_str(&json)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to parse command config: {}", e)).into())
+    }
+
+    /// Write the config into a directory
+    pub fn save(&self, directory: &Path) -> crate::GameResult<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to serialize command config: {}", e)))?;
+        std::fs::write(directory.join(CONFIG_FILE), json)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to write command config: {}", e)).into())
+    }
+
+    /// The commands to run for some input: a macro's steps, or the input with
+    /// any leading shortcut or alias replaced. The character's own shortcuts
+    /// come before aliases, and aliases before the built-ins.
+    pub fn expand(&self, input: &str, shortcuts: &BTreeMap<String, String>) -> Vec<String> {
+        let expand_one = |input: &str| {
+            let trimmed = input.trim();
+            let first = trimmed.split_whitespace().next().unwrap_or("").to_lowercase();
+            if shortcuts.contains_key(&first) || !self.aliases.contains_key(&first) {
+                return expand(trimmed, shortcuts);
+            }
+            expand(trimmed, &self.aliases)
+        };
+        match self.macros.get(&input.trim().to_lowercase()) {
+            Some(steps) => steps.iter().map(|step| expand_one(step)).collect(),
+            None => vec![expand_one(input)],
+        }
+    }
+
+    /// List, add or remove aliases
+    pub fn configure_alias(&mut self, setting: Option<&str>) -> crate::GameResult<String> {
+        let Some(setting) = setting else {
+            if self.aliases.is_empty() {
+                return Ok("You have no aliases. Use 'alias <key> <command>' to add one.".to_string());
+            }
+            let mut response = "=== Aliases ===\n".to_string();
+            for (key, command) in &self.aliases {
+                response.push_str(&format!("  {:<4} → {}\n", key, command));
+            }
+            return Ok(response.trim_end().to_string());
+        };
+        if let Some(key) = setting.strip_prefix("remove ") {
+            let key = key.trim().to_lowercase();
+            return self.aliases.remove(&key)
+                .map(|command| format!("'{}' no longer stands for '{}'.", key, command))
+                .ok_or_else(|| crate::GameError::ContentNotFound(format!("You have no alias '{}'.", key)).into());
+        }
+
+        let (key, command) = setting.trim().split_once(char::is_whitespace).unwrap_or((setting.trim(), ""));
+        let key = valid_name(key, "alias")?;
+        if command.trim().is_empty() {
+            return Err(crate::GameError::InvalidInput(format!("What should '{}' stand for?", key)).into());
+        }
+        if self.macros.remove(&key).is_some() {
+            self.aliases.insert(key.clone(), command.trim().to_string());
+            return Ok(format!("'{}' now stands for '{}' instead of running a macro.", key, command.trim()));
+        }
+        let message = match self.aliases.insert(key.clone(), command.trim().to_string()) {
+            Some(previous) => format!("'{}' now stands for '{}' instead of '{}'.", key, command.trim(), previous),
+            None => format!("'{}' now stands for '{}'.", key, command.trim()),
+        };
+        Ok(message)
+    }
+
+    /// List, add or remove macros
+    pub fn configure_macro(&mut self, setting: Option<&str>) -> crate::GameResult<String> {
+        let Some(setting) = setting else {
+            if self.macros.is_empty() {
+                return Ok("You have no macros. Use 'macro <name> <command>; <command>' to add one.".to_string());
+            }
+            let mut response = "=== Macros ===\n".to_string();
+            for (name, steps) in &self.macros {
+                response.push_str(&format!("  {:<4} → {}\n", name, steps.join("; ")));
+            }
+            return Ok(response.trim_end().to_string());
+        };
+        if let Some(name) = setting.strip_prefix("remove ") {
+            let name = name.trim().to_lowercase();
+            return self.macros.remove(&name)
+                .map(|_| format!("The macro '{}' is gone.", name))
+                .ok_or_else(|| crate::GameError::ContentNotFound(format!("You have no macro '{}'.", name)).into());
+        }
+
+        let (name, commands) = setting.trim().split_once(char::is_whitespace).unwrap_or((setting.trim(), ""));
+        let name = valid_name(name, "macro")?;
+        let steps: Vec<String> = commands.split(';')
+            .map(|step| step.trim().to_string())
+            .filter(|step| !step.is_empty())
+            .collect();
+        if steps.is_empty() {
+            return Err(crate::GameError::InvalidInput(format!("What should '{}' do? Separate its commands with ';'.", name)).into());
+        }
+        if steps.len() > MAX_MACRO_STEPS {
+            return Err(crate::GameError::InvalidInput(format!("A macro can run at most {} commands.", MAX_MACRO_STEPS)).into());
+        }
+        self.aliases.remove(&name);
+        let message = format!("'{}' now runs: {}", name, steps.join("; "));
+        self.macros.insert(name, steps);
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortcuts_expand_before_parsing() {
+        let mut custom = BTreeMap::new();
+        assert_eq!(expand("x crystal formation", &custom), "examine crystal formation");
+        assert_eq!(expand("I", &custom), "inventory");
+        assert_eq!(expand("I want to rest", &custom), "I want to rest");
+        assert_eq!(expand("look around", &custom), "look around");
+
+        define(&mut custom, "h", "cast healing").unwrap();
+        assert!(define(&mut custom, "x", "examine enemy").unwrap().contains("instead of 'examine'"));
+        assert_eq!(expand("h on guard", &custom), "cast healing on guard");
+        assert_eq!(expand("x", &custom), "examine enemy");
+        assert!(define(&mut custom, "shortcuts", "look").is_err());
+        assert!(define(&mut custom, "two words", "look").is_err());
+
+        assert!(describe(&custom).contains("h    → cast healing"));
+        remove(&mut custom, "x").unwrap();
+        assert_eq!(expand("x", &custom), "examine");
+        assert!(remove(&mut custom, "x").is_err());
+    }
+
+    #[test]
+    fn test_aliases_and_macros_expand_before_parsing() {
+        let mut config = CommandConfig::default();
+        let mut shortcuts = BTreeMap::new();
+        config.configure_alias(Some("gn go north")).unwrap();
+        config.configure_macro(Some("gq go north; examine crystals")).unwrap();
+        assert!(config.configure_macro(Some("macro look")).is_err());
+        assert!(config.configure_macro(Some("empty ;")).is_err());
+
+        assert_eq!(config.expand("gn", &shortcuts), vec!["go north".to_string()]);
+        assert_eq!(config.expand("GQ", &shortcuts), vec!["go north".to_string(), "examine crystals".to_string()]);
+        assert_eq!(config.expand("x altar", &shortcuts), vec!["examine altar".to_string()]);
+
+        // A character's own shortcut wins over an alias
+        define(&mut shortcuts, "gn", "go northeast").unwrap();
+        assert_eq!(config.expand("gn", &shortcuts), vec!["go northeast".to_string()]);
+
+        assert!(config.configure_alias(Some("gq look")).unwrap().contains("instead of running a macro"));
+        assert_eq!(config.expand("gq", &shortcuts), vec!["look".to_string()]);
+        assert!(config.configure_macro(Some("remove gq")).is_err());
+        assert!(config.configure_alias(Some("remove gq")).is_ok());
+    }
+
+    #[test]
+    fn test_command_config_is_kept_in_the_save_directory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(CommandConfig::load(dir.path()).unwrap().aliases.is_empty());
+
+        let mut config = CommandConfig::default();
+        config.configure_macro(Some("scan look; forecast")).unwrap();
+        config.save(dir.path()).unwrap();
+        let loaded = CommandConfig::load(dir.path()).unwrap();
+        assert_eq!(loaded.expand("scan", &BTreeMap::new()).len(), 2);
+        assert!(loaded.clone().configure_macro(None).unwrap().contains("scan → look; forecast"));
+    }
+}
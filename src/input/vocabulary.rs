@@ -0,0 +1,121 @@
+//! The words the parser knows
+//!
+//! Which words are verbs, directions, prepositions and so on, and the
+//! abbreviations that stand for other words, are data rather than code. The
+//! built-in vocabulary ships in `content/vocabulary.json`. Content packs add
+//! verbs of their own through the `verb_synonyms` content table, each
+//! standing for a command the game already has:
+//!
+//! ```text
+//! calibrate crystal  →  attune crystal
+//! transcribe glyphs  →  research glyphs
+//! ```
+//!
+//! A verb can't be added if it's already a word the parser knows.
+
+use crate::input::natural_language::TokenType;
+use crate::GameResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The vocabulary built into the game
+const BUILT_IN: &str = include_str!("../../content/vocabulary.json");
+
+/// Words of one kind, matched in the order the groups are listed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WordGroup {
+    pub kind: TokenType,
+    /// What the words have in common, for whoever edits the file
+    #[serde(default)]
+    pub group: String,
+    pub words: Vec<String>,
+}
+
+/// Every word the parser knows, and what the shorter ones stand for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vocabulary {
+    format_version: u32,
+    /// Word groups; a word in several takes the kind of the first
+    pub word_kinds: Vec<WordGroup>,
+    /// Words that stand for others, such as "x" for "examine"
+    pub synonyms: BTreeMap<String, String>,
+    /// Verbs added by content packs, and the commands they stand for
+    #[serde(skip)]
+    added_verbs: BTreeMap<String, String>,
+}
+
+impl Vocabulary {
+    /// The vocabulary the game ships with
+    pub fn built_in() -> Self {
+        serde_json::from_str(BUILT_IN).expect("the built-in vocabulary is valid")
+    }
+
+    /// The kind of a word, if it's one the parser knows
+    pub fn kind_of(&self, word: &str) -> Option<&TokenType> {
+        self.word_kinds.iter()
+            .find(|group| group.words.iter().any(|known| known == word))
+            .map(|group| &group.kind)
+    }
+
+    /// Add a verb standing for a command the game already has
+    pub fn add_verb(&mut self, word: &str, meaning: &str) -> GameResult<()> {
+        let word = word.trim().to_lowercase();
+        let meaning = meaning.trim().to_lowercase();
+        if word.is_empty() || word.contains(char::is_whitespace) {
+            return Err(crate::GameError::InvalidInput(format!("'{}' isn't a single word.", word)).into());
+        }
+        if meaning.is_empty() {
+            return Err(crate::GameError::InvalidInput(format!("'{}' needs a command to stand for.", word)).into());
+        }
+        if self.kind_of(&word).is_some() || self.synonyms.contains_key(&word) {
+            return Err(crate::GameError::InvalidInput(format!("'{}' already means something to the parser.", word)).into());
+        }
+        self.synonyms.insert(word.clone(), meaning.clone());
+        self.added_verbs.insert(word, meaning);
+        Ok(())
+    }
+
+    /// Whether a word is a verb added by a content pack
+    pub fn is_added_verb(&self, word: &str) -> bool {
+        self.added_verbs.contains_key(word)
+    }
+
+    /// Input with an added verb at its start replaced by the command it stands for
+    pub fn rewrite(&self, input: &str) -> String {
+        let (first, rest) = input.split_once(' ').unwrap_or((input, ""));
+        match self.added_verbs.get(first) {
+            Some(meaning) if rest.is_empty() => meaning.clone(),
+            Some(meaning) => format!("{} {}", meaning, rest),
+            None => input.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_built_in_vocabulary_loads() {
+        let vocabulary = Vocabulary::built_in();
+        assert_eq!(vocabulary.kind_of("grab"), Some(&TokenType::Verb));
+        assert_eq!(vocabulary.kind_of("in"), Some(&TokenType::Direction));
+        assert_eq!(vocabulary.kind_of("crystal"), Some(&TokenType::MagicKeyword));
+        assert_eq!(vocabulary.synonyms.get("x").map(String::as_str), Some("examine"));
+        assert!(vocabulary.kind_of("calibrate").is_none());
+    }
+
+    #[test]
+    fn test_added_verbs_stand_for_commands() {
+        let mut vocabulary = Vocabulary::built_in();
+        vocabulary.add_verb("Calibrate", "attune").unwrap();
+        assert_eq!(vocabulary.rewrite("calibrate quartz crystal"), "attune quartz crystal");
+        assert_eq!(vocabulary.rewrite("calibrate"), "attune");
+        assert_eq!(vocabulary.rewrite("take calibrate"), "take calibrate");
+
+        assert!(vocabulary.add_verb("take", "drop").is_err());
+        assert!(vocabulary.add_verb("x", "look").is_err());
+        assert!(vocabulary.add_verb("write down", "annotate").is_err());
+        assert!(vocabulary.add_verb("transcribe", " ").is_err());
+    }
+}
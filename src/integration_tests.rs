@@ -57,6 +57,9 @@ fn test_faction_dialogue_integration() -> GameResult<()> {
         faction_affiliation: Some(FactionId::MagistersCouncil),
         personality: None,
         quest_dialogue: HashMap::new(),
+        availability: Default::default(),
+        flag_lines: Vec::new(),
+        topic_flags: HashMap::new(),
         dialogue_tree: DialogueTree {
             greeting: DialogueNode {
                 text_templates: vec![
@@ -72,9 +75,12 @@ fn test_faction_dialogue_integration() -> GameResult<()> {
                     theory_requirements: vec![],
                     min_theory_mastery: None,
                     required_capabilities: vec![],
+                    min_relationship: None,
                 },
             },
             time_based_greetings: HashMap::new(),
+            relationship_greetings: HashMap::new(),
+            branches: HashMap::new(),
             topics: {
                 let mut topics = HashMap::new();
                 topics.insert("council_business".to_string(), DialogueNode {
@@ -91,6 +97,7 @@ fn test_faction_dialogue_integration() -> GameResult<()> {
                         theory_requirements: vec![],
                         min_theory_mastery: None,
                         required_capabilities: vec![],
+                        min_relationship: None,
                     },
                 });
                 topics
@@ -168,6 +175,9 @@ fn test_performance_integration() -> GameResult<()> {
             faction_affiliation: Some(FactionId::MagistersCouncil),
             personality: None,
             quest_dialogue: HashMap::new(),
+            availability: Default::default(),
+            flag_lines: Vec::new(),
+            topic_flags: HashMap::new(),
             dialogue_tree: DialogueTree {
                 greeting: DialogueNode {
                     text_templates: vec!["Hello".to_string()],
@@ -179,9 +189,12 @@ fn test_performance_integration() -> GameResult<()> {
                         theory_requirements: vec![],
                         min_theory_mastery: None,
                         required_capabilities: vec![],
+                        min_relationship: None,
                     },
                 },
                 time_based_greetings: HashMap::new(),
+                relationship_greetings: HashMap::new(),
+                branches: HashMap::new(),
                 topics: HashMap::new(),
                 faction_specific: HashMap::new(),
             },
@@ -7,7 +7,7 @@
 //! - [`core`] - Core game engine and fundamental data structures
 //! - [`systems`] - Game systems (magic, factions, knowledge progression)
 //! - [`input`] - Command parsing and natural language processing
-//! - [`content`] - Content loading and narrative management
+//! - [`content`] - Content packs of locations, NPCs, theories, quests and items in JSON or TOML
 //! - [`persistence`] - Save/load system and database operations
 //! - [`ui`] - User interface and terminal display systems
 
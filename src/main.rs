@@ -1,11 +1,19 @@
 use clap::{Arg, Command};
 use log::info;
+use std::path::Path;
 use sympathetic_resonance::{GameEngine, DatabaseManager};
+use sympathetic_resonance::content::ContentLoader;
+use sympathetic_resonance::persistence::{content_io, content_validation};
+use sympathetic_resonance::input::input_source::{self, ScriptInput};
+use sympathetic_resonance::ui::tui::TuiInput;
 
 fn main() -> anyhow::Result<()> {
     // Initialize logging
     env_logger::init();
 
+    // Recover from unexpected panics with an emergency save and crash report
+    sympathetic_resonance::persistence::crash_recovery::install_panic_hook();
+
     // Parse command line arguments
     let matches = Command::new("Sympathetic Resonance")
         .version("0.1.0")
@@ -17,6 +25,37 @@ fn main() -> anyhow::Result<()> {
                 .help("Initialize the game database")
                 .action(clap::ArgAction::SetTrue)
         )
+        .arg(
+            Arg::new("export-content")
+                .long("export-content")
+                .value_name("DIR")
+                .help("Export world content to declarative JSON files in DIR")
+        )
+        .arg(
+            Arg::new("import-content")
+                .long("import-content")
+                .value_name("DIR")
+                .help("Replace world content with the JSON files in DIR")
+        )
+        .arg(
+            Arg::new("validate-content")
+                .long("validate-content")
+                .help("Check the content database for broken references and unreachable locations")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .arg(
+            Arg::new("load-pack")
+                .long("load-pack")
+                .value_name("DIR")
+                .action(clap::ArgAction::Append)
+                .help("Add the locations, NPCs, theories, quests and items in a content pack directory (repeatable)")
+        )
+        .arg(
+            Arg::new("watch-content")
+                .long("watch-content")
+                .value_name("DIR")
+                .help("Play with the content files in DIR, reloading them into the game whenever they change")
+        )
         .arg(
             Arg::new("save-file")
                 .short('s')
@@ -24,6 +63,59 @@ fn main() -> anyhow::Result<()> {
                 .value_name("FILE")
                 .help("Load a specific save file")
         )
+        .arg(
+            Arg::new("scenario")
+                .long("scenario")
+                .value_name("FILE")
+                .conflicts_with("save-file")
+                .help("Start a new game from a scenario file")
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .value_name("SEED")
+                .value_parser(clap::value_parser!(u64))
+                .conflicts_with("save-file")
+                .help("Start a new run with a specific seed")
+        )
+        .arg(
+            Arg::new("mutator")
+                .long("mutator")
+                .value_name("NAME")
+                .action(clap::ArgAction::Append)
+                .help("Declare a challenge mutator for this run (repeatable)")
+        )
+        .arg(
+            Arg::new("defeat-policy")
+                .long("defeat-policy")
+                .value_name("POLICY")
+                .help("What happens when you fall: reload, capture, rescue, or permadeath")
+        )
+        .arg(
+            Arg::new("difficulty")
+                .long("difficulty")
+                .value_name("LEVEL")
+                .help("How hard fights are: story, normal, hard, or nightmare")
+        )
+        .arg(
+            Arg::new("locale")
+                .long("locale")
+                .value_name("LOCALE")
+                .help("Language for dialogue, from the translations in the content database")
+        )
+        .arg(
+            Arg::new("script")
+                .long("script")
+                .value_name("FILE")
+                .help("Play back the commands in FILE, one per line, printing a transcript")
+        )
+        .arg(
+            Arg::new("tui")
+                .long("tui")
+                .conflicts_with("script")
+                .help("Play in a full-screen interface with status, map and log panes")
+                .action(clap::ArgAction::SetTrue)
+        )
         .arg(
             Arg::new("debug")
                 .long("debug")
@@ -43,23 +135,137 @@ fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    if let Some(dir) = matches.get_one::<String>("export-content") {
+        info!("Exporting content to {}", dir);
+        db_manager.initialize_schema()?;
+        let report = content_io::export_content(&db_manager, Path::new(dir))?;
+        println!("Content exported to {}:\n{}", dir, report.summary());
+        return Ok(());
+    }
+
+    if let Some(dir) = matches.get_one::<String>("import-content") {
+        info!("Importing content from {}", dir);
+        db_manager.initialize_schema()?;
+        let report = content_io::import_content(&db_manager, Path::new(dir))?;
+        println!("Content imported from {}:\n{}", dir, report.summary());
+        return Ok(());
+    }
+
+    if matches.get_flag("validate-content") {
+        info!("Validating content...");
+        db_manager.initialize_schema()?;
+        let issues = content_validation::validate_content(&db_manager)?;
+        if issues.is_empty() {
+            println!("Content is consistent: no problems found.");
+            return Ok(());
+        }
+        println!("Found {} content problem{}:", issues.len(), if issues.len() == 1 { "" } else { "s" });
+        for issue in &issues {
+            println!("  {}", issue);
+        }
+        std::process::exit(1);
+    }
+
+    if let Some(packs) = matches.get_many::<String>("load-pack") {
+        db_manager.initialize_schema()?;
+        for pack in packs {
+            info!("Loading content pack {}", pack);
+            let report = ContentLoader::new(pack).load_into(&db_manager)?;
+            println!("Content pack {} loaded:\n{}", pack, report.summary());
+        }
+        return Ok(());
+    }
+
+    // Bring an existing database up to the current schema before loading from it
+    db_manager.initialize_schema()?;
+
+    // Content being authored is imported before the game loads it
+    let watch_dir = matches.get_one::<String>("watch-content");
+    if let Some(dir) = watch_dir {
+        info!("Watching content in {}", dir);
+        content_io::import_content(&db_manager, Path::new(dir))?;
+    }
+
     // Initialize game engine
     let mut game_engine = GameEngine::new(db_manager)?;
+    if let Some(dir) = watch_dir {
+        game_engine.watch_content(dir)?;
+    }
+
+    // Journal the session, picking up where a crashed one left off if the
+    // player wants; a script is played again from the start instead
+    let recovered = if matches.contains_id("script") {
+        None
+    } else {
+        game_engine.start_journal()?
+    };
 
     // Load save file if specified
-    if let Some(save_file) = matches.get_one::<String>("save-file") {
+    if let Some(save_file) = matches.get_one::<String>("save-file").filter(|_| recovered.is_none()) {
         info!("Loading save file: {}", save_file);
         game_engine.load_save(save_file)?;
     }
 
+    // Start from custom conditions if a scenario was given
+    let scenario_intro = match matches.get_one::<String>("scenario").filter(|_| recovered.is_none()) {
+        Some(scenario_file) => {
+            info!("Loading scenario: {}", scenario_file);
+            Some(game_engine.load_scenario(scenario_file)?)
+        }
+        None => None,
+    };
+
+    if let Some(locale) = matches.get_one::<String>("locale") {
+        game_engine.set_locale(locale)?;
+    }
+
+    // A loaded or recovered game keeps the seed its run was started with
+    let new_game = recovered.is_none() && !matches.contains_id("save-file");
+
+    // A script plays back the same commands every time; a new game starts
+    // from a fixed seed, and the player's autosaves are left alone
+    if let Some(script_file) = matches.get_one::<String>("script") {
+        info!("Playing script: {}", script_file);
+        game_engine.set_input(Box::new(ScriptInput::load(Path::new(script_file))?));
+        if new_game {
+            game_engine.set_run_seed(0);
+        }
+        game_engine.configure_autosave(false, 5, 3);
+    }
+
+    // Apply challenge run settings
+    if let Some(seed) = matches.get_one::<u64>("seed").filter(|_| new_game) {
+        game_engine.set_run_seed(*seed);
+    }
+    if let Some(mutators) = matches.get_many::<String>("mutator") {
+        for mutator in mutators {
+            game_engine.add_run_mutator(mutator.clone());
+        }
+    }
+
+    if let Some(policy) = matches.get_one::<String>("defeat-policy") {
+        game_engine.set_defeat_policy(policy)?;
+    }
+
+    if let Some(difficulty) = matches.get_one::<String>("difficulty") {
+        game_engine.set_difficulty(difficulty)?;
+    }
+
     // Set debug mode
     if matches.get_flag("debug") {
         game_engine.set_debug_mode(true);
     }
 
-    println!("Welcome to Sympathetic Resonance!");
-    println!("Type 'help' for available commands or 'quit' to exit.");
-    println!();
+    // The full-screen interface takes over the terminal once nothing else
+    // needs to ask the player anything on the way in
+    if matches.get_flag("tui") {
+        game_engine.set_input(Box::new(TuiInput::open(input_source::history_path()?)?));
+    }
+
+    game_engine.show("Welcome to Sympathetic Resonance!\nType 'help' for available commands or 'quit' to exit.\n");
+    if let Some(intro) = scenario_intro.or(recovered) {
+        game_engine.show(&format!("{}\n", intro));
+    }
 
     // Start main game loop
     game_engine.run()?;
@@ -17,6 +17,9 @@ use tempfile::NamedTempFile;
 /// Target response time for all commands (100ms)
 const TARGET_RESPONSE_TIME: Duration = Duration::from_millis(100);
 
+/// Target time to write or read a save of a large world
+const SAVE_LOAD_TARGET: Duration = Duration::from_millis(250);
+
 /// Create a test environment
 fn create_test_env() -> (Player, WorldState, DatabaseManager, MagicSystem, DialogueSystem, FactionSystem, KnowledgeSystem) {
     let temp_file = NamedTempFile::new().unwrap();
@@ -269,4 +272,56 @@ mod tests {
                 "Stress test took {:.2}ms, exceeds stress target of {:.2}ms",
                 duration.as_secs_f64() * 1000.0, stress_target.as_secs_f64() * 1000.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_save_and_load_performance() {
+        let (player, mut world, db, magic_system, mut dialogue_system, faction_system, knowledge_system) = create_test_env();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut save_manager = crate::persistence::SaveManager::new().unwrap();
+        save_manager.set_save_directory_for_test(temp_dir.path().to_path_buf());
+
+        // A world grown well beyond the default content
+        let template = world.locations.values().next().unwrap().clone();
+        for index in 0..500 {
+            let mut location = template.clone();
+            location.id = format!("grown_location_{}", index);
+            world.locations.insert(location.id.clone(), location);
+        }
+        for npc in db.load_npcs().unwrap() {
+            dialogue_system.add_npc(npc);
+        }
+        let mut quest_system = crate::systems::quests::QuestSystem::new();
+        for quest in crate::systems::quest_examples::create_example_quests() {
+            quest_system.add_quest_definition(quest);
+        }
+        let combat_system = crate::systems::CombatSystem::new();
+
+        let save = |world: &WorldState| save_manager.save_game(
+            &player, world, &quest_system, &combat_system, &faction_system,
+            &knowledge_system, &dialogue_system, &magic_system, Some("large".to_string()), None,
+        ).unwrap();
+
+        let (first_save, _) = time_operation(|| save(&world));
+        world.game_time_minutes += 30;
+        let (second_save, _) = time_operation(|| save(&world));
+        let (load, loaded) = time_operation(|| save_manager.load_game("large").unwrap());
+
+        println!("Save (fresh): {:.2}ms, save (incremental): {:.2}ms, load: {:.2}ms",
+                 first_save.as_secs_f64() * 1000.0, second_save.as_secs_f64() * 1000.0, load.as_secs_f64() * 1000.0);
+        for (operation, duration) in [("Fresh save", first_save), ("Incremental save", second_save), ("Load", load)] {
+            assert!(duration <= SAVE_LOAD_TARGET,
+                    "{} took {:.2}ms, exceeds target of {:.2}ms",
+                    operation, duration.as_secs_f64() * 1000.0, SAVE_LOAD_TARGET.as_secs_f64() * 1000.0);
+        }
+
+        // Only the world, timestamp and metadata changed between the two saves
+        assert!(save_manager.sections_reused() >= 7, "Reused {} sections", save_manager.sections_reused());
+        assert_eq!(loaded.1.locations.len(), world.locations.len());
+
+        // Compression keeps the file to a fraction of its JSON size
+        let file_size = std::fs::metadata(save_manager.slot_path("large")).unwrap().len();
+        let json_size = serde_json::to_string_pretty(&loaded.1).unwrap().len() as u64;
+        println!("Save file: {} bytes for a {} byte world", file_size, json_size);
+        assert!(file_size * 4 < json_size);
+    }
+}
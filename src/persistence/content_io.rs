@@ -0,0 +1,355 @@
+//! Declarative content files for bulk import and export
+//!
+//! World content lives in SQLite for the game, but is easier to review,
+//! diff and share as text. Export writes one pretty-printed JSON file per
+//! content domain with rows in a stable order; import replaces the matching
+//! tables with the file contents. Player progress tables are never touched.
+//!
+//! Columns that hold JSON text are embedded as structured JSON so nested
+//! data diffs cleanly, and are turned back into text on import.
+//!
+//! While authoring, a running game can watch a content directory and pick
+//! up edits to its files without restarting.
+
+use super::DatabaseManager;
+use crate::GameResult;
+use rusqlite::types::{Value, ValueRef};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Version of the content file format
+const CONTENT_FORMAT_VERSION: u32 = 1;
+
+/// Content domains and the tables stored in each file
+const CONTENT_FILES: &[(&str, &[&str])] = &[
+    ("locations", &["locations", "location_exits", "hidden_exits", "scenery", "ambience", "faction_presence"]),
+    ("npcs", &["npcs", "npc_schedules"]),
+    ("theories", &["magic_theories"]),
+    ("quests", &["quest_definitions"]),
+    ("items", &["items"]),
+    ("archives", &["archive_texts"]),
+    ("enemies", &["enemies"]),
+    ("recipes", &["recipes"]),
+    ("localization", &["localized_text"]),
+    ("vocabulary", &["verb_synonyms"]),
+];
+
+type Row = BTreeMap<String, serde_json::Value>;
+
+/// On-disk layout of a content file
+#[derive(Debug, Serialize, Deserialize)]
+struct ContentFile {
+    format_version: u32,
+    tables: BTreeMap<String, Vec<Row>>,
+}
+
+/// Rows written or read per table
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContentReport {
+    pub tables: BTreeMap<String, usize>,
+}
+
+impl ContentReport {
+    /// Human-readable summary, one table per line
+    pub fn summary(&self) -> String {
+        self.tables.iter()
+            .map(|(table, rows)| format!("  {}: {} row{}", table, rows, if *rows == 1 { "" } else { "s" }))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Write all content tables to declarative files in `dir`
+pub fn export_content(db: &DatabaseManager, dir: &Path) -> GameResult<ContentReport> {
+    fs::create_dir_all(dir)
+        .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to create {}: {}", dir.display(), e)))?;
+
+    let mut report = ContentReport::default();
+    for (file_name, tables) in CONTENT_FILES {
+        let mut file = ContentFile {
+            format_version: CONTENT_FORMAT_VERSION,
+            tables: BTreeMap::new(),
+        };
+
+        for table in *tables {
+            let rows = export_table(db, table)?;
+            report.tables.insert(table.to_string(), rows.len());
+            file.tables.insert(table.to_string(), rows);
+        }
+
+        let json = serde_json::to_string_pretty(&file)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to serialize {}: {}", file_name, e)))?;
+        let path = dir.join(format!("{}.json", file_name));
+        fs::write(&path, json + "\n")
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to write {}: {}", path.display(), e)))?;
+    }
+
+    Ok(report)
+}
+
+/// Replace content tables with the files found in `dir`
+///
+/// Missing files are skipped, so a directory may carry a subset of the
+/// content. Everything is imported in one transaction.
+pub fn import_content(db: &DatabaseManager, dir: &Path) -> GameResult<ContentReport> {
+    if !dir.is_dir() {
+        return Err(crate::GameError::SaveLoadError(format!("Content directory {} not found", dir.display())).into());
+    }
+
+    let mut files = Vec::new();
+    for (file_name, tables) in CONTENT_FILES {
+        let path = dir.join(format!("{}.json", file_name));
+        if !path.exists() {
+            continue;
+        }
+
+        let text = fs::read_to_string(&path)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to read {}: {}", path.display(), e)))?;
+        let file: ContentFile = serde_json::from_str(&text)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Invalid content file {}: {}", path.display(), e)))?;
+
+        if file.format_version > CONTENT_FORMAT_VERSION {
+            return Err(crate::GameError::SaveLoadError(format!(
+                "{} uses content format {}, but this build supports up to {}",
+                path.display(), file.format_version, CONTENT_FORMAT_VERSION
+            )).into());
+        }
+        if let Some(unknown) = file.tables.keys().find(|table| !tables.contains(&table.as_str())) {
+            return Err(crate::GameError::SaveLoadError(format!(
+                "{} contains unknown table '{}'", path.display(), unknown
+            )).into());
+        }
+
+        files.push((*tables, file));
+    }
+
+    let transaction = db.connection().unchecked_transaction()?;
+    // Tables reference each other, so only check foreign keys once everything is in place
+    db.connection().execute_batch("PRAGMA defer_foreign_keys = ON")
+        .map_err(|e| crate::GameError::DatabaseError(format!("Failed to defer foreign keys: {}", e)))?;
+
+    let mut report = ContentReport::default();
+    for (tables, file) in &files {
+        for table in tables.iter() {
+            if let Some(rows) = file.tables.get(*table) {
+                import_table(db, table, rows)?;
+                report.tables.insert(table.to_string(), rows.len());
+            }
+        }
+    }
+    transaction.commit()?;
+
+    Ok(report)
+}
+
+/// Watches a content directory for edited files
+#[derive(Debug, Clone)]
+pub struct ContentWatcher {
+    dir: PathBuf,
+    /// Newest modification time seen among the content files
+    last_modified: Option<SystemTime>,
+}
+
+impl ContentWatcher {
+    /// Start watching `dir`, treating the files as they are now as already seen
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            last_modified: latest_modification(dir),
+        }
+    }
+
+    /// The directory being watched
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Whether any content file was written, added or removed since the last check
+    pub fn poll(&mut self) -> bool {
+        let latest = latest_modification(&self.dir);
+        let changed = latest != self.last_modified;
+        self.last_modified = latest;
+        changed
+    }
+}
+
+/// Newest modification time among the content files in `dir`
+fn latest_modification(dir: &Path) -> Option<SystemTime> {
+    CONTENT_FILES.iter()
+        .filter_map(|(file_name, _)| fs::metadata(dir.join(format!("{}.json", file_name))).ok())
+        .filter_map(|metadata| metadata.modified().ok())
+        .max()
+}
+
+/// Column names of a table, in declaration order
+fn table_columns(db: &DatabaseManager, table: &str) -> GameResult<Vec<String>> {
+    let mut stmt = db.connection().prepare(&format!("PRAGMA table_info({})", table))
+        .map_err(|e| crate::GameError::DatabaseError(format!("Failed to inspect {}: {}", table, e)))?;
+    let columns = stmt.query_map([], |row| row.get(1))
+        .map_err(|e| crate::GameError::DatabaseError(format!("Failed to inspect {}: {}", table, e)))?
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| crate::GameError::DatabaseError(format!("Failed to inspect {}: {}", table, e)))?;
+    Ok(columns)
+}
+
+/// Read every row of a table, ordered for stable diffs
+fn export_table(db: &DatabaseManager, table: &str) -> GameResult<Vec<Row>> {
+    let columns = table_columns(db, table)?;
+    let order = if columns.len() > 1 { "1, 2" } else { "1" };
+
+    let mut stmt = db.connection().prepare(&format!("SELECT * FROM {} ORDER BY {}", table, order))
+        .map_err(|e| crate::GameError::DatabaseError(format!("Failed to query {}: {}", table, e)))?;
+    let rows = stmt.query_map([], |row| {
+        let mut values = Row::new();
+        for (index, column) in columns.iter().enumerate() {
+            values.insert(column.clone(), to_json(row.get_ref(index)?));
+        }
+        Ok(values)
+    }).map_err(|e| crate::GameError::DatabaseError(format!("Failed to query {}: {}", table, e)))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| crate::GameError::DatabaseError(format!("Failed to read {}: {}", table, e)).into())
+}
+
+/// Replace a table's rows
+fn import_table(db: &DatabaseManager, table: &str, rows: &[Row]) -> GameResult<()> {
+    let columns = table_columns(db, table)?;
+
+    db.connection().execute(&format!("DELETE FROM {}", table), [])
+        .map_err(|e| crate::GameError::DatabaseError(format!("Failed to clear {}: {}", table, e)))?;
+
+    for row in rows {
+        // Column names come from the file, so only accept ones the table declares
+        if let Some(unknown) = row.keys().find(|key| !columns.contains(key)) {
+            return Err(crate::GameError::SaveLoadError(format!("Unknown column '{}' in {}", unknown, table)).into());
+        }
+
+        let names: Vec<&str> = row.keys().map(String::as_str).collect();
+        let placeholders: Vec<String> = (1..=names.len()).map(|i| format!("?{}", i)).collect();
+        let values: Vec<Value> = row.values().map(from_json).collect();
+
+        db.connection().execute(
+            &format!("INSERT INTO {} ({}) VALUES ({})", table, names.join(", "), placeholders.join(", ")),
+            rusqlite::params_from_iter(values),
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to import {} row: {}", table, e)))?;
+    }
+
+    Ok(())
+}
+
+/// Convert a column value to JSON, expanding embedded JSON text
+fn to_json(value: ValueRef) -> serde_json::Value {
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => serde_json::Value::from(i),
+        ValueRef::Real(f) => serde_json::Value::from(f),
+        ValueRef::Text(bytes) => {
+            let text = String::from_utf8_lossy(bytes).to_string();
+            if text.starts_with('[') || text.starts_with('{') {
+                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&text) {
+                    return json;
+                }
+            }
+            serde_json::Value::String(text)
+        }
+        ValueRef::Blob(bytes) => serde_json::Value::from(bytes.to_vec()),
+    }
+}
+
+/// Convert JSON back to a column value, re-encoding structured data as text
+fn from_json(value: &serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Integer(*b as i64),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => Value::Text(s.clone()),
+        structured => Value::Text(structured.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::{NamedTempFile, TempDir};
+
+    fn create_db(with_content: bool) -> (DatabaseManager, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path().to_str().unwrap()).unwrap();
+        db.initialize_schema().unwrap();
+        if with_content {
+            db.load_default_content().unwrap();
+        }
+        (db, temp_file)
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let (source, _source_file) = create_db(true);
+        let dir = TempDir::new().unwrap();
+
+        let exported = export_content(&source, dir.path()).unwrap();
+        assert!(exported.tables["locations"] > 0);
+        assert!(exported.tables["npcs"] > 0);
+        assert!(dir.path().join("theories.json").exists());
+
+        let (target, _target_file) = create_db(false);
+        let imported = import_content(&target, dir.path()).unwrap();
+        assert_eq!(imported, exported);
+
+        assert_eq!(target.load_locations().unwrap().len(), source.load_locations().unwrap().len());
+        assert_eq!(target.load_npcs().unwrap().len(), source.load_npcs().unwrap().len());
+        assert_eq!(target.load_theories().unwrap().len(), source.load_theories().unwrap().len());
+        assert_eq!(target.load_enemies().unwrap().len(), source.load_enemies().unwrap().len());
+        assert_eq!(target.load_recipes().unwrap().len(), source.load_recipes().unwrap().len());
+
+        // A second export of the imported content is byte-for-byte identical
+        let again = TempDir::new().unwrap();
+        export_content(&target, again.path()).unwrap();
+        for (file_name, _) in CONTENT_FILES {
+            let name = format!("{}.json", file_name);
+            assert_eq!(
+                fs::read_to_string(dir.path().join(&name)).unwrap(),
+                fs::read_to_string(again.path().join(&name)).unwrap(),
+                "{} differs after round trip", name
+            );
+        }
+    }
+
+    #[test]
+    fn test_import_rejects_unknown_columns() {
+        let (db, _file) = create_db(false);
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("items.json"),
+            r#"{"format_version": 1, "tables": {"items": [{"id": "x", "name": "X", "description": "x", "item_type": "misc", "bogus": 1}]}}"#,
+        ).unwrap();
+
+        assert!(import_content(&db, dir.path()).is_err());
+        assert!(import_content(&db, &dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_watcher_notices_edited_content_files() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("npcs.json");
+        fs::write(&path, "{}").unwrap();
+
+        let mut watcher = ContentWatcher::new(dir.path());
+        assert!(!watcher.poll());
+
+        let later = SystemTime::now() + std::time::Duration::from_secs(5);
+        fs::File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+        assert!(watcher.poll());
+        assert!(!watcher.poll());
+
+        // Files that aren't content don't count
+        fs::write(dir.path().join("notes.txt"), "draft").unwrap();
+        assert!(!watcher.poll());
+    }
+}
@@ -0,0 +1,317 @@
+//! Cross-checks of world content
+//!
+//! `--validate-content` reads the content database, together with the quests
+//! built into the game, and looks for references that lead nowhere: exits to
+//! missing locations, quests naming NPCs or theories that don't exist,
+//! dialogue gated on capabilities no theory or quest grants, and locations a new
+//! character could never walk or ride to. Everything found is reported at
+//! once as a list of issues, each saying what kind of problem it is, where,
+//! and what is wrong.
+
+use super::DatabaseManager;
+use crate::core::overworld::{self, Region};
+use crate::core::player::MAGIC_CAPABILITIES;
+use crate::core::world_state::Direction;
+use crate::core::WorldState;
+use crate::systems::dialogue::{DialogueEffect, DialogueNode, NPC};
+use crate::systems::laboratory::{LAB_ENTRANCE, LAB_LOCATION};
+use crate::systems::quests::{ObjectiveType, QuestDefinition};
+use crate::GameResult;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+/// What kind of problem an issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IssueKind {
+    /// An exit that can't be followed
+    BrokenExit,
+    MissingLocation,
+    MissingNpc,
+    MissingTheory,
+    MissingQuest,
+    /// A dialogue branch that isn't defined
+    MissingBranch,
+    UnknownCapability,
+    /// A location no route from the start reaches
+    Unreachable,
+}
+
+impl IssueKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            IssueKind::BrokenExit => "broken exit",
+            IssueKind::MissingLocation => "missing location",
+            IssueKind::MissingNpc => "missing NPC",
+            IssueKind::MissingTheory => "missing theory",
+            IssueKind::MissingQuest => "missing quest",
+            IssueKind::MissingBranch => "missing branch",
+            IssueKind::UnknownCapability => "unknown capability",
+            IssueKind::Unreachable => "unreachable location",
+        }
+    }
+}
+
+/// One problem found in the content
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ContentIssue {
+    pub kind: IssueKind,
+    /// What the problem was found in, such as "quest 'x'"
+    pub subject: String,
+    pub message: String,
+}
+
+impl fmt::Display for ContentIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {}", self.kind.label(), self.subject, self.message)
+    }
+}
+
+/// Known IDs each reference is checked against
+struct Known {
+    locations: HashSet<String>,
+    npcs: HashSet<String>,
+    theories: HashSet<String>,
+    quests: HashSet<String>,
+    /// Capabilities theories or quest rewards can grant
+    capabilities: HashSet<String>,
+}
+
+/// Everything wrong with the content, in a stable order
+pub fn validate_content(db: &DatabaseManager) -> GameResult<Vec<ContentIssue>> {
+    let locations = db.load_locations()?;
+    let npcs = db.load_npcs()?;
+    let theories = db.load_theories()?;
+    let quests = game_quests(db);
+
+    let known = Known {
+        locations: locations.keys().cloned().collect(),
+        npcs: npcs.iter().map(|npc| npc.id.clone()).collect(),
+        theories: theories.keys().cloned().collect(),
+        quests: quests.iter().map(|quest| quest.id.clone()).collect(),
+        capabilities: MAGIC_CAPABILITIES.iter().map(|c| c.to_string())
+            .chain(quests.iter().flat_map(|quest| quest.rewards.new_capabilities.iter().cloned()))
+            .collect(),
+    };
+
+    let mut issues = BTreeSet::new();
+    check_exits(db, &known, &mut issues)?;
+    for (id, theory) in &theories {
+        for prerequisite in theory.prerequisites.iter().filter(|p| !known.theories.contains(*p)) {
+            issues.insert(issue(IssueKind::MissingTheory, format!("theory '{}'", id), format!("requires '{}'", prerequisite)));
+        }
+    }
+    for npc in &npcs {
+        check_npc(npc, &known, &mut issues);
+    }
+    for quest in &quests {
+        check_quest(quest, &known, &mut issues);
+    }
+    check_reachability(db, &known, &mut issues)?;
+
+    Ok(issues.into_iter().collect())
+}
+
+/// The quests a game plays with: those built in, then any from the database
+fn game_quests(db: &DatabaseManager) -> Vec<QuestDefinition> {
+    use crate::systems::quest_examples::*;
+    create_example_quests().into_iter()
+        .chain(create_cleanup_quests())
+        .chain(create_repeatable_quests())
+        .chain(create_bond_quests())
+        .chain(db.load_quest_definitions().unwrap_or_default().into_values())
+        .collect()
+}
+
+fn issue(kind: IssueKind, subject: String, message: String) -> ContentIssue {
+    ContentIssue { kind, subject, message }
+}
+
+/// Exits and hidden exits as stored, since loading drops the ones it can't place
+fn exit_rows(db: &DatabaseManager) -> GameResult<Vec<(String, String, String)>> {
+    let mut stmt = db.connection().prepare(
+        "SELECT location_id, direction, destination_id FROM location_exits
+         UNION ALL SELECT location_id, direction, destination_id FROM hidden_exits"
+    ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare exits query: {}", e)))?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| crate::GameError::DatabaseError(format!("Failed to query exits: {}", e)))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| crate::GameError::DatabaseError(format!("Failed to read exits: {}", e)))?;
+    Ok(rows)
+}
+
+fn check_exits(db: &DatabaseManager, known: &Known, issues: &mut BTreeSet<ContentIssue>) -> GameResult<()> {
+    for (from, direction, to) in exit_rows(db)? {
+        let subject = format!("exit {} from '{}'", direction, from);
+        if !known.locations.contains(&from) {
+            issues.insert(issue(IssueKind::BrokenExit, subject.clone(), "starts at a location that doesn't exist".to_string()));
+        }
+        if Direction::from_string(&direction).is_none() {
+            issues.insert(issue(IssueKind::BrokenExit, subject.clone(), format!("'{}' isn't a direction", direction)));
+        }
+        if !known.locations.contains(&to) {
+            issues.insert(issue(IssueKind::MissingLocation, subject, format!("leads to '{}'", to)));
+        }
+    }
+    Ok(())
+}
+
+fn check_npc(npc: &NPC, known: &Known, issues: &mut BTreeSet<ContentIssue>) {
+    let subject = format!("NPC '{}'", npc.id);
+    if let Some(home) = npc.availability.home_location.as_ref().filter(|l| !known.locations.contains(*l)) {
+        issues.insert(issue(IssueKind::MissingLocation, subject.clone(), format!("lives at '{}'", home)));
+    }
+    for entry in &npc.availability.schedule {
+        if let Some(location) = entry.location.as_ref().filter(|l| !known.locations.contains(*l)) {
+            issues.insert(issue(IssueKind::MissingLocation, subject.clone(), format!("is scheduled at '{}'", location)));
+        }
+    }
+
+    let tree = &npc.dialogue_tree;
+    let nodes = std::iter::once(("greeting".to_string(), &tree.greeting))
+        .chain(tree.topics.iter().map(|(topic, node)| (format!("topic '{}'", topic), node)))
+        .chain(tree.faction_specific.iter().map(|(faction, node)| (format!("{:?} greeting", faction), node)))
+        .chain(tree.branches.iter().map(|(branch, node)| (format!("branch '{}'", branch), node)));
+    for (name, node) in nodes {
+        check_dialogue_node(&format!("NPC '{}' {}", npc.id, name), node, &tree.branches, known, issues);
+    }
+}
+
+fn check_dialogue_node(
+    subject: &str,
+    node: &DialogueNode,
+    branches: &HashMap<String, DialogueNode>,
+    known: &Known,
+    issues: &mut BTreeSet<ContentIssue>,
+) {
+    for (theory, _) in node.requirements.theory_requirements.iter().filter(|(t, _)| !known.theories.contains(t)) {
+        issues.insert(issue(IssueKind::MissingTheory, subject.to_string(), format!("requires '{}'", theory)));
+    }
+    for capability in node.requirements.required_capabilities.iter().filter(|c| !known.capabilities.contains(*c)) {
+        issues.insert(issue(IssueKind::UnknownCapability, subject.to_string(), format!("requires '{}'", capability)));
+    }
+    for response in &node.responses {
+        if let Some(next) = response.next.as_ref().filter(|next| !branches.contains_key(*next)) {
+            issues.insert(issue(IssueKind::MissingBranch, subject.to_string(), format!("response '{}' leads to '{}'", response.text, next)));
+        }
+        if let DialogueEffect::QuestStart(quest) = &response.effect {
+            if !known.quests.contains(quest) {
+                issues.insert(issue(IssueKind::MissingQuest, subject.to_string(), format!("response '{}' starts '{}'", response.text, quest)));
+            }
+        }
+    }
+}
+
+fn check_quest(quest: &QuestDefinition, known: &Known, issues: &mut BTreeSet<ContentIssue>) {
+    let subject = format!("quest '{}'", quest.id);
+    let requirements = &quest.requirements;
+
+    let mut npcs: Vec<&String> = quest.involved_npcs.iter().collect();
+    let mut locations: Vec<&String> = quest.locations.iter().chain(&requirements.location_requirements).collect();
+    let mut theories: Vec<&String> = requirements.theory_requirements.iter().map(|(theory, _)| theory).collect();
+    for objective in &quest.objectives {
+        match &objective.objective_type {
+            ObjectiveType::TalkToNPC { npc_id, .. } => npcs.push(npc_id),
+            ObjectiveType::VisitLocation { location_id } => locations.push(location_id),
+            ObjectiveType::TeachTheory { npc_id, theory_id } => {
+                npcs.push(npc_id);
+                theories.push(theory_id);
+            }
+            ObjectiveType::LearnTheory { theory_id, .. }
+            | ObjectiveType::MagicalDemonstration { theory_id, .. }
+            | ObjectiveType::Research { theory_id, .. }
+            | ObjectiveType::LearningActivity { theory_id, .. } => theories.push(theory_id),
+            _ => {}
+        }
+    }
+
+    for npc in npcs.into_iter().filter(|n| !known.npcs.contains(*n)) {
+        issues.insert(issue(IssueKind::MissingNpc, subject.clone(), format!("involves '{}'", npc)));
+    }
+    for location in locations.into_iter().filter(|l| !known.locations.contains(*l)) {
+        issues.insert(issue(IssueKind::MissingLocation, subject.clone(), format!("takes place at '{}'", location)));
+    }
+    for theory in theories.into_iter().filter(|t| !known.theories.contains(*t)) {
+        issues.insert(issue(IssueKind::MissingTheory, subject.clone(), format!("requires '{}'", theory)));
+    }
+    for prerequisite in requirements.prerequisite_quests.iter().filter(|q| !known.quests.contains(*q)) {
+        issues.insert(issue(IssueKind::MissingQuest, subject.clone(), format!("follows '{}'", prerequisite)));
+    }
+    for capability in requirements.capability_requirements.iter().filter(|c| !known.capabilities.contains(*c)) {
+        issues.insert(issue(IssueKind::UnknownCapability, subject.clone(), format!("requires '{}'", capability)));
+    }
+}
+
+/// Flag locations a new character can't get to by exits, hidden exits,
+/// roads or the laboratory door
+fn check_reachability(db: &DatabaseManager, known: &Known, issues: &mut BTreeSet<ContentIssue>) -> GameResult<()> {
+    let start = WorldState::new().current_location;
+    if !known.locations.contains(&start) {
+        issues.insert(issue(IssueKind::MissingLocation, "new games".to_string(), format!("start at '{}'", start)));
+        return Ok(());
+    }
+
+    let mut ways: HashMap<String, Vec<String>> = HashMap::new();
+    for (from, _, to) in exit_rows(db)? {
+        ways.entry(from).or_default().push(to);
+    }
+    // Every region's gateway is a road away from the gateways it connects to
+    for region in Region::ALL {
+        let gateways = overworld::routes_from(region).into_iter().map(|(to, _)| to.gateway().to_string());
+        ways.entry(region.gateway().to_string()).or_default().extend(gateways);
+    }
+    // The laboratory opens off its entrance once the player takes it on
+    ways.entry(LAB_ENTRANCE.to_string()).or_default().push(LAB_LOCATION.to_string());
+
+    let mut reached = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(location) = queue.pop_front() {
+        for next in ways.get(&location).into_iter().flatten() {
+            if known.locations.contains(next) && reached.insert(next.clone()) {
+                queue.push_back(next.clone());
+            }
+        }
+    }
+
+    for location in known.locations.iter().filter(|l| !reached.contains(*l)) {
+        issues.insert(issue(IssueKind::Unreachable, format!("location '{}'", location), "no exit or road from the start leads here".to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn create_db() -> (DatabaseManager, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path().to_str().unwrap()).unwrap();
+        db.initialize_schema().unwrap();
+        db.load_default_content().unwrap();
+        (db, temp_file)
+    }
+
+    #[test]
+    fn test_default_content_is_consistent() {
+        let (db, _file) = create_db();
+        let issues = validate_content(&db).unwrap();
+        assert!(issues.is_empty(), "{}", issues.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"));
+    }
+
+    #[test]
+    fn test_broken_references_are_listed() {
+        let (db, _file) = create_db();
+        // Hand-edited databases don't always enforce their foreign keys
+        db.connection().execute_batch("PRAGMA foreign_keys = OFF").unwrap();
+        db.insert_location("forgotten_attic", "Forgotten Attic", "Dust.", 1.0, None, 0.0, &[]).unwrap();
+        db.insert_exit("forgotten_attic", "down", "collapsed_stair").unwrap();
+        db.insert_npc("lost_clerk", "Lost Clerk", "x", None, r#"{"greeting": {"text_templates": ["Hm?"], "responses": [],
+            "requirements": {"required_capabilities": ["time_travel"]}}, "topics": {}, "faction_specific": []}"#, "tutorial_chamber").unwrap();
+
+        let issues = validate_content(&db).unwrap();
+        let kinds: Vec<IssueKind> = issues.iter().map(|issue| issue.kind).collect();
+        assert_eq!(kinds, vec![IssueKind::MissingLocation, IssueKind::UnknownCapability, IssueKind::Unreachable]);
+        assert_eq!(issues[0].to_string(), "[missing location] exit down from 'forgotten_attic': leads to 'collapsed_stair'");
+        assert_eq!(issues[2].subject, "location 'forgotten_attic'");
+    }
+}
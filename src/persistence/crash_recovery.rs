@@ -0,0 +1,177 @@
+//! Crash recovery with emergency saves
+//!
+//! A bug that panics partway through a command shouldn't silently cost the
+//! player their session. The panic hook records what went wrong, and the
+//! game loop runs each command guarded so a panic is caught. Then:
+//! - the game is saved to a quarantine slot, kept apart from ordinary saves
+//!   so a state the panic may have left half-updated is never loaded by
+//!   accident
+//! - a crash report bundle is written: the panic, where it happened, a
+//!   backtrace and the commands leading up to it
+//! - the player is told where both are and how to resume
+//!
+//! Panics outside a guarded command still reach the previous hook and are
+//! reported as usual.
+
+use crate::GameResult;
+use std::cell::{Cell, RefCell};
+use std::fmt::Write as _;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+// Catching a panic needs it to unwind; a build that aborts would lose the
+// player's session without a save or a report
+#[cfg(not(panic = "unwind"))]
+compile_error!("crash recovery needs panic = \"unwind\" in every profile the game is built with");
+
+/// Directory, inside the save directory, holding emergency saves
+pub const QUARANTINE_DIR: &str = "quarantine";
+/// Directory, inside the save directory, holding crash report bundles
+pub const CRASH_REPORT_DIR: &str = "crash_reports";
+
+thread_local! {
+    /// The panic on this thread most recently seen by the hook
+    static LAST_PANIC: RefCell<Option<PanicRecord>> = const { RefCell::new(None) };
+    /// Whether a panic on this thread will be caught and recovered from
+    static GUARDED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// What is known about a panic
+#[derive(Debug, Clone, PartialEq)]
+pub struct PanicRecord {
+    pub message: String,
+    /// Source file, line and column, when known
+    pub location: Option<String>,
+    pub backtrace: String,
+}
+
+/// The message carried by a panic payload
+fn payload_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload.downcast_ref::<&str>().map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Record every panic, passing those outside a guarded command to the
+/// previous hook
+pub fn install_panic_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let record = PanicRecord {
+            message: payload_message(info.payload()),
+            location: info.location().map(|location| location.to_string()),
+            backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        };
+        LAST_PANIC.with(|last| *last.borrow_mut() = Some(record));
+        if !GUARDED.with(Cell::get) {
+            previous(info);
+        }
+    }));
+}
+
+/// Run a command, catching any panic it raises
+pub fn guarded<T>(command: impl FnOnce() -> T) -> Result<T, PanicRecord> {
+    GUARDED.with(|guarded| guarded.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(command));
+    GUARDED.with(|guarded| guarded.set(false));
+
+    result.map_err(|payload| {
+        let recorded = LAST_PANIC.with(|last| last.borrow_mut().take());
+        // Without the hook installed only the payload is known
+        recorded.unwrap_or_else(|| PanicRecord {
+            message: payload_message(payload.as_ref()),
+            location: None,
+            backtrace: String::new(),
+        })
+    })
+}
+
+/// Write a crash report bundle into a new directory under `save_directory`,
+/// returning the bundle's path
+pub fn write_report(
+    save_directory: &Path,
+    record: &PanicRecord,
+    command: &str,
+    recent_commands: &[String],
+    emergency_save: Result<&Path, &str>,
+) -> GameResult<PathBuf> {
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let bundle = save_directory.join(CRASH_REPORT_DIR).join(format!("crash_{}", timestamp));
+    std::fs::create_dir_all(&bundle)
+        .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to create crash report directory: {}", e)))?;
+
+    let mut report = String::new();
+    let _ = writeln!(report, "Sympathetic Resonance {} crash report", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(report, "Time: {}", chrono::Local::now().to_rfc3339());
+    let _ = writeln!(report, "Command: {}", command);
+    let _ = writeln!(report, "Panic: {}", record.message);
+    let _ = writeln!(report, "Location: {}", record.location.as_deref().unwrap_or("unknown"));
+    let _ = match emergency_save {
+        Ok(path) => writeln!(report, "Emergency save: {}", path.display()),
+        Err(error) => writeln!(report, "Emergency save failed: {}", error),
+    };
+    let _ = writeln!(report, "\nBacktrace:\n{}", record.backtrace);
+
+    let write = |name: &str, contents: &str| std::fs::write(bundle.join(name), contents)
+        .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to write crash report: {}", e)));
+    write("report.txt", &report)?;
+    write("recent_commands.txt", &recent_commands.join("\n"))?;
+    Ok(bundle)
+}
+
+/// What to tell the player after a crash
+pub fn recovery_instructions(emergency_save: Result<&Path, &str>, bundle: Result<&Path, &str>, save_directory: &Path) -> String {
+    let mut message = "Something went wrong and the game had to stop.\n".to_string();
+    match emergency_save {
+        Ok(path) => {
+            let slot = path.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().to_string());
+            let _ = write!(
+                message,
+                "\nYour progress was saved to a quarantine slot:\n  {}\n\
+                 It holds the game exactly as the crash left it, which may be partway through your last command.\n\
+                 To resume from it, move it into {} and start the game with '--save {}'.\n\
+                 Your regular saves are untouched; loading the most recent one is the safest choice.\n",
+                path.display(), save_directory.display(), slot
+            );
+        }
+        Err(error) => {
+            let _ = write!(message, "\nAn emergency save couldn't be made ({}). Load your most recent save to continue.\n", error);
+        }
+    }
+    match bundle {
+        Ok(path) => {
+            let _ = write!(message, "\nA crash report was written to:\n  {}\nPlease include it if you report the problem.", path.display());
+        }
+        Err(error) => {
+            let _ = write!(message, "\nThe crash report couldn't be written ({}).", error);
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_guarded_commands_catch_panics_and_report_them() {
+        install_panic_hook();
+        assert_eq!(guarded(|| 7), Ok(7));
+        let record = guarded(|| -> i32 { panic!("resonance overflow") }).unwrap_err();
+        assert_eq!(record.message, "resonance overflow");
+        assert!(record.location.as_deref().is_some_and(|location| location.contains("crash_recovery.rs")));
+
+        let directory = TempDir::new().unwrap();
+        let save = directory.path().join(QUARANTINE_DIR).join("emergency.save");
+        let bundle = write_report(directory.path(), &record, "cast light", &["look".to_string(), "cast light".to_string()], Ok(&save)).unwrap();
+        let report = std::fs::read_to_string(bundle.join("report.txt")).unwrap();
+        assert!(report.contains("Panic: resonance overflow"));
+        assert!(report.contains("Command: cast light"));
+        assert_eq!(std::fs::read_to_string(bundle.join("recent_commands.txt")).unwrap(), "look\ncast light");
+
+        let message = recovery_instructions(Ok(&save), Ok(&bundle), directory.path());
+        assert!(message.contains("'--save emergency'"));
+        assert!(recovery_instructions(Err("disk full"), Ok(&bundle), directory.path()).contains("couldn't be made (disk full)"));
+    }
+}
@@ -3,20 +3,24 @@
 //! This module handles:
 //! - SQLite database schema creation and management
 //! - Content loading from database
-//! - Database migration and versioning
+//! - Database migration and versioning, through the ordered steps in
+//!   [`migrations`](crate::persistence::migrations)
 
 use rusqlite::{Connection, params, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use crate::core::world_state::{Location, Direction, MagicalProperties, FactionPresence, PresenceVisibility};
+use crate::systems::access::{AccessRequirement, ExitLock, LockKind};
+use crate::systems::scenery::SceneryObject;
+use crate::systems::secrets::HiddenExit;
+use crate::systems::factions::FactionId;
 use crate::GameResult;
 
-/// Database schema version for migration management
-const SCHEMA_VERSION: i32 = 3;
-
 /// Manager for all database operations
 pub struct DatabaseManager {
     connection: Connection,
+    /// Where the database was opened from
+    path: String,
 }
 
 /// NPC definition from database
@@ -42,6 +46,17 @@ pub struct TheoryData {
     pub applications: Vec<String>, // What this theory enables
 }
 
+/// Text stored in the Crystalline Archives catalog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveTextData {
+    pub id: String,
+    pub title: String,
+    pub topics: Vec<String>, // Search keywords
+    pub theory_id: Option<String>, // Theory this text advances, if any
+    pub difficulty: i32, // 1-5, used for research rolls
+    pub content: String,
+}
+
 /// Item definition from database
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ItemData {
@@ -58,293 +73,40 @@ impl DatabaseManager {
         let connection = Connection::open(database_path)
             .map_err(|e| crate::GameError::DatabaseError(format!("Failed to open database: {}", e)))?;
 
-        Ok(Self { connection })
+        Ok(Self { connection, path: database_path.to_string() })
     }
 
-    /// Initialize database schema
-    pub fn initialize_schema(&self) -> GameResult<()> {
-        // Create version table first
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS schema_version (
-                version INTEGER PRIMARY KEY
-            )",
-            [],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create version table: {}", e)))?;
-
-        // Check current version
-        let current_version: Option<i32> = self.connection
-            .query_row(
-                "SELECT version FROM schema_version ORDER BY version DESC LIMIT 1",
-                [],
-                |row| row.get(0),
-            )
-            .optional()
-            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to check version: {}", e)))?;
-
-        if current_version.is_none() || current_version.unwrap() < SCHEMA_VERSION {
-            self.create_tables()?;
-            self.update_schema_version()?;
-        }
-
-        Ok(())
+    /// Where the database was opened from
+    pub fn path(&self) -> &str {
+        &self.path
     }
 
-    /// Create all database tables
-    fn create_tables(&self) -> GameResult<()> {
-        // Locations table
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS locations (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                description TEXT NOT NULL,
-                ambient_energy REAL DEFAULT 1.0,
-                dominant_frequency INTEGER,
-                interference REAL DEFAULT 0.0,
-                phenomena TEXT, -- JSON array
-                visited BOOLEAN DEFAULT FALSE
-            )",
-            [],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create locations table: {}", e)))?;
-
-        // Location exits (separate table for flexibility)
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS location_exits (
-                location_id TEXT NOT NULL,
-                direction TEXT NOT NULL,
-                destination_id TEXT NOT NULL,
-                FOREIGN KEY(location_id) REFERENCES locations(id),
-                FOREIGN KEY(destination_id) REFERENCES locations(id),
-                PRIMARY KEY(location_id, direction)
-            )",
-            [],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create exits table: {}", e)))?;
-
-        // NPCs table
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS npcs (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                description TEXT NOT NULL,
-                faction_id TEXT,
-                dialogue_tree TEXT, -- JSON
-                current_location TEXT,
-                FOREIGN KEY(current_location) REFERENCES locations(id)
-            )",
-            [],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create npcs table: {}", e)))?;
-
-        // Magic theories table (enhanced for comprehensive learning system)
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS magic_theories (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                description TEXT NOT NULL,
-                tier INTEGER NOT NULL, -- 1=Foundation, 2=Application, 3=Advanced
-                category TEXT NOT NULL, -- Theory category for organization
-                prerequisites TEXT, -- JSON array of theory IDs
-                complexity_level INTEGER NOT NULL,
-                learning_time_base INTEGER NOT NULL,
-                scientific_concepts TEXT, -- JSON array of scientific concepts
-                applications TEXT, -- JSON array of applications
-                available_methods TEXT, -- JSON array of available learning methods
-                method_multipliers TEXT -- JSON object of method efficiency multipliers
-            )",
-            [],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create theories table: {}", e)))?;
-
-        // Player theory progress tracking
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS player_theory_progress (
-                player_id TEXT NOT NULL,
-                theory_id TEXT NOT NULL,
-                understanding_level REAL NOT NULL DEFAULT 0.0,
-                experience_points INTEGER NOT NULL DEFAULT 0,
-                learning_history TEXT, -- JSON object of method contributions
-                time_invested INTEGER NOT NULL DEFAULT 0,
-                discovered_at INTEGER NOT NULL,
-                mastered_at INTEGER,
-                is_active_research BOOLEAN DEFAULT FALSE,
-                research_progress REAL DEFAULT 0.0,
-                PRIMARY KEY(player_id, theory_id),
-                FOREIGN KEY(theory_id) REFERENCES magic_theories(id)
-            )",
-            [],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create theory progress table: {}", e)))?;
-
-        // Learning activity log for detailed tracking
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS learning_activities (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                player_id TEXT NOT NULL,
-                theory_id TEXT NOT NULL,
-                method TEXT NOT NULL,
-                duration INTEGER NOT NULL,
-                success_rate REAL NOT NULL,
-                experience_gained INTEGER NOT NULL,
-                understanding_gained REAL NOT NULL,
-                resources_used TEXT, -- JSON object of resources consumed
-                side_effects TEXT, -- JSON array of side effects
-                timestamp INTEGER NOT NULL,
-                FOREIGN KEY(theory_id) REFERENCES magic_theories(id)
-            )",
-            [],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create learning activities table: {}", e)))?;
-
-        // Items table
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS items (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                description TEXT NOT NULL,
-                item_type TEXT NOT NULL,
-                properties TEXT -- JSON for type-specific properties
-            )",
-            [],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create items table: {}", e)))?;
-
-        // Faction presence in locations
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS faction_presence (
-                location_id TEXT NOT NULL,
-                faction_id TEXT NOT NULL,
-                influence INTEGER NOT NULL,
-                visibility TEXT NOT NULL,
-                member_count INTEGER DEFAULT 0,
-                FOREIGN KEY(location_id) REFERENCES locations(id),
-                PRIMARY KEY(location_id, faction_id)
-            )",
-            [],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create faction presence table: {}", e)))?;
-
-        // Quest definitions table
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS quest_definitions (
-                id TEXT PRIMARY KEY,
-                title TEXT NOT NULL,
-                description TEXT NOT NULL,
-                category TEXT NOT NULL,
-                difficulty TEXT NOT NULL,
-                requirements TEXT NOT NULL, -- JSON
-                objectives TEXT NOT NULL, -- JSON array
-                rewards TEXT NOT NULL, -- JSON
-                faction_effects TEXT, -- JSON
-                educational_focus TEXT, -- JSON
-                branching_paths TEXT, -- JSON
-                involved_npcs TEXT, -- JSON array
-                locations TEXT, -- JSON array
-                estimated_duration INTEGER NOT NULL,
-                created_at INTEGER NOT NULL,
-                updated_at INTEGER NOT NULL
-            )",
-            [],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create quest definitions table: {}", e)))?;
-
-        // Player quest progress tracking
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS player_quest_progress (
-                player_id TEXT NOT NULL,
-                quest_id TEXT NOT NULL,
-                status TEXT NOT NULL,
-                started_at INTEGER NOT NULL,
-                completed_at INTEGER,
-                objective_progress TEXT NOT NULL, -- JSON
-                chosen_branch TEXT,
-                player_choices TEXT, -- JSON
-                time_invested INTEGER NOT NULL DEFAULT 0,
-                quest_variables TEXT, -- JSON
-                learning_progress TEXT, -- JSON
-                PRIMARY KEY(player_id, quest_id),
-                FOREIGN KEY(quest_id) REFERENCES quest_definitions(id)
-            )",
-            [],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create player quest progress table: {}", e)))?;
-
-        // Quest objective completion log for detailed tracking
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS quest_objective_log (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                player_id TEXT NOT NULL,
-                quest_id TEXT NOT NULL,
-                objective_id TEXT NOT NULL,
-                completed_at INTEGER NOT NULL,
-                progress_value REAL NOT NULL,
-                completion_method TEXT,
-                learning_data TEXT, -- JSON
-                FOREIGN KEY(quest_id) REFERENCES quest_definitions(id)
-            )",
-            [],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create quest objective log table: {}", e)))?;
-
-        // Quest rewards awarded to players
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS quest_rewards_awarded (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                player_id TEXT NOT NULL,
-                quest_id TEXT NOT NULL,
-                reward_type TEXT NOT NULL,
-                reward_data TEXT NOT NULL, -- JSON
-                awarded_at INTEGER NOT NULL,
-                FOREIGN KEY(quest_id) REFERENCES quest_definitions(id)
-            )",
-            [],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create quest rewards table: {}", e)))?;
-
-        // Global quest state and unlocks
-        self.connection.execute(
-            "CREATE TABLE IF NOT EXISTS quest_global_state (
-                key TEXT PRIMARY KEY,
-                value TEXT NOT NULL, -- JSON
-                updated_at INTEGER NOT NULL
-            )",
-            [],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create quest global state table: {}", e)))?;
-
-        // Create indexes for performance
-        self.create_indexes()?;
-
-        Ok(())
+    /// Open a pool of further connections to the same database, for queries
+    /// run in the background
+    pub fn query_pool(&self, workers: usize) -> GameResult<crate::persistence::query_pool::QueryPool> {
+        crate::persistence::query_pool::QueryPool::open(&self.path, workers)
     }
 
-    /// Create database indexes for performance
-    fn create_indexes(&self) -> GameResult<()> {
-        let indexes = vec![
-            "CREATE INDEX IF NOT EXISTS idx_location_exits_location ON location_exits(location_id)",
-            "CREATE INDEX IF NOT EXISTS idx_npcs_location ON npcs(current_location)",
-            "CREATE INDEX IF NOT EXISTS idx_npcs_faction ON npcs(faction_id)",
-            "CREATE INDEX IF NOT EXISTS idx_faction_presence_location ON faction_presence(location_id)",
-            "CREATE INDEX IF NOT EXISTS idx_theory_progress_player ON player_theory_progress(player_id)",
-            "CREATE INDEX IF NOT EXISTS idx_theory_progress_theory ON player_theory_progress(theory_id)",
-            "CREATE INDEX IF NOT EXISTS idx_learning_activities_player ON learning_activities(player_id)",
-            "CREATE INDEX IF NOT EXISTS idx_learning_activities_theory ON learning_activities(theory_id)",
-            "CREATE INDEX IF NOT EXISTS idx_learning_activities_timestamp ON learning_activities(timestamp)",
-            "CREATE INDEX IF NOT EXISTS idx_theories_tier ON magic_theories(tier)",
-            "CREATE INDEX IF NOT EXISTS idx_theories_category ON magic_theories(category)",
-            // Quest system indexes
-            "CREATE INDEX IF NOT EXISTS idx_quest_definitions_category ON quest_definitions(category)",
-            "CREATE INDEX IF NOT EXISTS idx_quest_definitions_difficulty ON quest_definitions(difficulty)",
-            "CREATE INDEX IF NOT EXISTS idx_player_quest_progress_player ON player_quest_progress(player_id)",
-            "CREATE INDEX IF NOT EXISTS idx_player_quest_progress_status ON player_quest_progress(status)",
-            "CREATE INDEX IF NOT EXISTS idx_quest_objective_log_player ON quest_objective_log(player_id)",
-            "CREATE INDEX IF NOT EXISTS idx_quest_objective_log_quest ON quest_objective_log(quest_id)",
-            "CREATE INDEX IF NOT EXISTS idx_quest_objective_log_completed ON quest_objective_log(completed_at)",
-            "CREATE INDEX IF NOT EXISTS idx_quest_rewards_player ON quest_rewards_awarded(player_id)",
-            "CREATE INDEX IF NOT EXISTS idx_quest_rewards_quest ON quest_rewards_awarded(quest_id)",
-        ];
-
-        for index_sql in indexes {
-            self.connection.execute(index_sql, [])
-                .map_err(|e| crate::GameError::DatabaseError(format!("Failed to create index: {}", e)))?;
-        }
-
+    /// Initialize database schema, running any migrations the database
+    /// hasn't had
+    pub fn initialize_schema(&self) -> GameResult<()> {
+        crate::persistence::migrations::migrate(self)?;
         Ok(())
     }
 
-    /// Update schema version
-    fn update_schema_version(&self) -> GameResult<()> {
-        self.connection.execute(
-            "INSERT OR REPLACE INTO schema_version (version) VALUES (?1)",
-            params![SCHEMA_VERSION],
-        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to update schema version: {}", e)))?;
+    /// Add a column to a table created by an older schema version
+    pub(crate) fn add_column_if_missing(&self, table: &str, column: &str, definition: &str) -> GameResult<()> {
+        let mut stmt = self.connection.prepare(&format!("PRAGMA table_info({})", table))
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to inspect {} table: {}", table, e)))?;
+        let columns: Vec<String> = stmt.query_map([], |row| row.get(1))
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to inspect {} table: {}", table, e)))?
+            .collect::<Result<_, _>>()
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to inspect {} table: {}", table, e)))?;
+
+        if !columns.iter().any(|c| c == column) {
+            self.connection.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])
+                .map_err(|e| crate::GameError::DatabaseError(format!("Failed to add {}.{}: {}", table, column, e)))?;
+        }
 
         Ok(())
     }
@@ -440,6 +202,49 @@ impl DatabaseManager {
             &["reality_distortion".to_string(), "temporal_fluctuation".to_string(), "dimensional_instability".to_string(), "magical_overflow".to_string()],
         )?;
 
+        // Beyond the city, reached only by road
+        self.insert_location(
+            "shardfall_quarry",
+            "Shardfall Quarry",
+            "A stepped pit cut deep into the hills, where the Consortium mines raw shard crystal for the city's workshops. Cranes creak over terraces glittering with spoil, and every blast of the quarrymen's charges sets the exposed seams singing. The Consortium's surveyors keep careful records of which seams have been tapped, and by whom.",
+            1.3, // Raw crystal seams lend strength
+            Some(5), // Shard crystal frequency
+            0.25, // Blasting and seam resonance interfere
+            &["exposed_crystal_seams".to_string(), "seam_resonance".to_string()],
+        )?;
+
+        self.insert_location(
+            "archive_vault",
+            "Archive Vault",
+            "A low vault beneath the Crystalline Archives where the oldest and least stable crystal records are kept. The shelves here hum at frequencies the archivists prefer not to let loose upstairs, and the dust on the reading plinths has not been disturbed in years.",
+            1.2, // Old records still hold their charge
+            Some(9), // Lapis Lazuli frequency, like the archives above
+            0.2, // Unstable records bleed into one another
+            &["memory_enhancement".to_string(), "archival_echoes".to_string()],
+        )?;
+
+        self.insert_location(
+            "whispering_cellar",
+            "Whispering Cellar",
+            "A forgotten cistern beneath the Crystal Garden, dry now but for a trickle from the drains above. Someone has made it a meeting place: crates serve as seats, shielded lanterns hang from the pipes, and scratched into the brick are the marks the Underground Network uses to pass word along. Sound carries strangely here, so that every whisper seems to come from somewhere else.",
+            0.9, // Damp stone dulls the ambient field
+            None, // No dominant frequency
+            0.3, // The Network's shielding muffles scrying
+            &["scrying_shielding".to_string()],
+        )?;
+
+        // Opens onto the Practice Hall once the player buys it
+        self.insert_location(
+            "personal_laboratory",
+            "Disused Laboratory",
+            "A narrow workroom behind the Practice Hall, long left to dust. There is room along the walls for shelving and \
+             a bench, a glazed alcove where a garden plot could catch the light, and hooks where someone once hung their notes.",
+            1.1, // Quiet, with a little residual charge
+            None, // Free to tune as the owner likes
+            0.05, // Well away from other workings
+            &[],
+        )?;
+
         // Now insert exits after all locations exist
         // Tutorial progression path
         self.insert_exit("tutorial_chamber", "north", "practice_hall")?;
@@ -464,6 +269,40 @@ impl DatabaseManager {
         self.insert_exit("unstable_resonance_site", "south", "harmonic_testing_chambers")?;
         self.insert_exit("faction_diplomacy_hall", "northeast", "unstable_resonance_site")?;
         self.insert_exit("unstable_resonance_site", "southwest", "faction_diplomacy_hall")?;
+        self.insert_exit_lock("faction_diplomacy_hall", "northeast", &ExitLock {
+            kind: LockKind::Guarded,
+            requirement: AccessRequirement::Standing { faction: FactionId::MagistersCouncil, min: 10 },
+            description: "Council wardens hold the road to the Unstable Resonance Site.".to_string(),
+        })?;
+
+        // A secret way under the garden
+        self.insert_hidden_exit("crystal_garden_lab", "down", &HiddenExit {
+            to: "whispering_cellar".to_string(),
+            feature: "streams".to_string(),
+            clue: "Where the garden's streams drain away beneath the lowest terrace, a grate lifts free, and a ladder leads down into the dark.".to_string(),
+            known_to: vec!["healer_seraphina".to_string()],
+            documents: vec!["garden_waterworks_plans".to_string()],
+        })?;
+        self.insert_exit("whispering_cellar", "up", "crystal_garden_lab")?;
+
+        // Sealed records beneath the archives
+        self.insert_exit("crystalline_archives", "down", "archive_vault")?;
+        self.insert_exit("archive_vault", "up", "crystalline_archives")?;
+        self.insert_exit_lock("crystalline_archives", "down", &ExitLock {
+            kind: LockKind::Sealed,
+            requirement: AccessRequirement::Puzzle {
+                prompt: "Two crystals cut to one frequency: strike the first, and what does the second do?".to_string(),
+                answer: "resonate".to_string(),
+            },
+            description: "A crystal seal covers the stair down to the vault.".to_string(),
+        })?;
+        // The Scholars keep the vault for their own, and its wards listen for casting
+        self.insert_location_restriction("archive_vault", &crate::systems::stealth::Restriction {
+            faction: FactionId::NeutralScholars,
+            min_standing: 20,
+            wardens: 0,
+            alarm: true,
+        })?;
 
         // Load comprehensive magic theory hierarchy
         self.load_foundational_theories()?;
@@ -473,6 +312,24 @@ impl DatabaseManager {
         // Load NPCs for all locations
         self.load_default_npcs()?;
 
+        // Stock the Crystalline Archives
+        self.load_default_archive_texts()?;
+
+        // Scenery to examine, search and touch
+        self.load_default_scenery()?;
+
+        // Ambient lines for location descriptions
+        self.load_default_ambience()?;
+
+        // Populate the bestiary
+        self.load_default_enemies()?;
+
+        // Crafting recipes
+        self.load_default_recipes()?;
+
+        // Items to be found around the world
+        self.load_default_items()?;
+
         transaction.commit()?;
         Ok(())
     }
@@ -702,7 +559,7 @@ impl DatabaseManager {
             for (topic_id, topic_texts, theory_req) in topics {
                 let mut requirements = HashMap::new();
                 if let Some(theory) = theory_req {
-                    requirements.insert("theory_requirements".to_string(), serde_json::json!([theory]));
+                    requirements.insert("theory_requirements".to_string(), serde_json::json!([[theory, 0.3]]));
                 }
 
                 topic_map.insert(topic_id.to_string(), serde_json::json!({
@@ -719,24 +576,99 @@ impl DatabaseManager {
                     "requirements": {}
                 },
                 "topics": topic_map,
-                "faction_specific": {}
+                "faction_specific": []
             });
 
             dialogue_tree.to_string()
         };
 
+        // Topics that need a closer relationship, and greetings for how close it's grown
+        let with_relationship = |tree: String, gated: &[(&str, &str)], greetings: serde_json::Value| -> String {
+            let mut tree: serde_json::Value = serde_json::from_str(&tree).expect("dialogue tree is valid JSON");
+            for (topic_id, stage) in gated {
+                tree["topics"][*topic_id]["requirements"]["min_relationship"] = serde_json::json!(stage);
+            }
+            tree["relationship_greetings"] = greetings;
+            tree.to_string()
+        };
+
+        // Responses offered after a topic, some of them behind skill checks
+        let with_responses = |tree: String, topic_id: &str, responses: serde_json::Value| -> String {
+            let mut tree: serde_json::Value = serde_json::from_str(&tree).expect("dialogue tree is valid JSON");
+            tree["topics"][topic_id]["responses"] = responses;
+            tree.to_string()
+        };
+
+        // Follow-up nodes that responses lead into, by branch ID
+        let with_branches = |tree: String, branches: serde_json::Value| -> String {
+            let mut tree: serde_json::Value = serde_json::from_str(&tree).expect("dialogue tree is valid JSON");
+            tree["branches"] = branches;
+            tree.to_string()
+        };
+
+        // Greetings for parts of the day: "morning", "afternoon" or "evening"
+        let with_time_greetings = |tree: String, greetings: serde_json::Value| -> String {
+            let mut tree: serde_json::Value = serde_json::from_str(&tree).expect("dialogue tree is valid JSON");
+            tree["time_based_greetings"] = greetings;
+            tree.to_string()
+        };
+
         // 1. Resonance Observatory NPCs
         self.insert_npc(
             "observer_lyra",
             "Observer Lyra Nightwatch",
             "A keen-eyed detection specialist manning the observatory's surveillance arrays. Her expression carries the weight of moral conflicts about the balance between security and privacy.",
             Some("magisters_council"),
-            &create_dialogue_tree(
-                vec!["Welcome to the Observatory. I monitor the city's magical pulse.", "The detection arrays show interesting patterns today."],
-                vec![
-                    ("detection_theory", vec!["Detection magic relies on resonance pattern recognition.", "Each magical signature has unique characteristics."], Some("detection_arrays")),
-                    ("surveillance_ethics", vec!["Sometimes I wonder if we observe too much.", "Knowledge is power, but at what cost?"], None),
-                ]
+            &with_branches(
+                with_responses(
+                    create_dialogue_tree(
+                        vec!["Welcome to the Observatory. I monitor the city's magical pulse.", "The detection arrays show interesting patterns today."],
+                        vec![
+                            ("detection_theory", vec!["Detection magic relies on resonance pattern recognition.", "Each magical signature has unique characteristics."], Some("detection_arrays")),
+                            ("surveillance_ethics", vec!["Sometimes I wonder if we observe too much.", "Knowledge is power, but at what cost?"], None),
+                        ]
+                    ),
+                    "surveillance_ethics",
+                    serde_json::json!([
+                        {
+                            "text": "What makes you doubt it?",
+                            "effect": "None",
+                            "reply": "Lyra glances at the arrays before she answers.",
+                            "next": "lyra_doubts"
+                        },
+                        {
+                            "text": "The city is safer for it.",
+                            "effect": "None",
+                            "reply": "\"That's what the Council tells me too.\""
+                        }
+                    ]),
+                ),
+                serde_json::json!({
+                    "lyra_doubts": {
+                        "text_templates": ["Lyra lowers her voice. \"The arrays log every casting in the city, licensed or not. Some of those logs never reach the Council.\""],
+                        "responses": [
+                            {
+                                "text": "Then who does read them?",
+                                "effect": { "GiveInformation": "Someone in the Industrial Consortium collects the unlicensed array readings every evening." },
+                                "reply": "Lyra hesitates, then decides she trusts you.",
+                                "check": { "Persuade": { "min_acuity": 50 } },
+                                "failure": { "text": "Lyra shakes her head. \"I've said too much already.\"", "effect": "None" },
+                                "next": "lyra_unlogged"
+                            },
+                            {
+                                "text": "You should report it.",
+                                "effect": "None",
+                                "reply": "\"To whom? The people I'd report it to are the ones reading them.\""
+                            }
+                        ],
+                        "requirements": {}
+                    },
+                    "lyra_unlogged": {
+                        "text_templates": ["\"A courier from the Consortium collects the unlicensed readings every evening. Marcus swears he doesn't know who sends her. I'm not sure I believe him.\""],
+                        "responses": [],
+                        "requirements": {}
+                    }
+                }),
             ),
             "resonance_observatory"
         )?;
@@ -746,12 +678,48 @@ impl DatabaseManager {
             "Technician Marcus Clearview",
             "An equipment engineer focused on the commercial applications of detection technology. His workshop tools are always immaculately organized.",
             Some("industrial_consortium"),
-            &create_dialogue_tree(
-                vec!["These detection arrays represent cutting-edge magical engineering.", "I'm working on efficiency improvements for commercial deployment."],
-                vec![
-                    ("crystal_engineering", vec!["Sapphire crystals provide excellent detection clarity.", "Proper crystal tuning is essential for accurate readings."], Some("crystal_structures")),
-                    ("commercial_applications", vec!["Detection magic has enormous market potential.", "Businesses need magical security solutions."], None),
-                ]
+            &with_responses(
+                with_relationship(
+                    create_dialogue_tree(
+                        vec!["These detection arrays represent cutting-edge magical engineering.", "I'm working on efficiency improvements for commercial deployment."],
+                        vec![
+                            ("crystal_engineering", vec!["Sapphire crystals provide excellent detection clarity.", "Proper crystal tuning is essential for accurate readings."], Some("crystal_structures")),
+                            ("commercial_applications", vec!["Detection magic has enormous market potential.", "Businesses need magical security solutions."], None),
+                            ("consortium_contracts", vec!["Between us, the Consortium's selling these arrays to both the Council and the Underground.", "Keep it quiet, but half my orders come from people who shouldn't be buying detection arrays."], None),
+                        ]
+                    ),
+                    &[("consortium_contracts", "Friend")],
+                    serde_json::json!({
+                        "Hostile": "Marcus keeps his back to you. \"I've nothing to say to you. Touch nothing on your way out.\"",
+                        "Wary": "Marcus glances up and keeps one hand on his tools. \"What is it this time?\"",
+                        "Friend": "Marcus waves you over. \"Come and look at this, you'll appreciate it. The new lattice is holding.\"",
+                        "Confidant": "Marcus grins and clears a stool for you. \"Just the person I wanted. I've got something I'd only show you.\""
+                    }),
+                ),
+                "crystal_engineering",
+                serde_json::json!([
+                    {
+                        "text": "Your sapphires are cut against the lattice grain. Tune along it and you'd lose half the noise.",
+                        "effect": { "GiveItem": "detection_array_schematics" },
+                        "reply": "Marcus stares, then laughs. \"Nobody outside the workshop has spotted that. Here, take a copy of the array schematics and tell me what else I've got wrong.\"",
+                        "check": { "CiteTheory": { "theory_id": "crystal_structures", "min_understanding": 0.6 } },
+                        "failure": { "text": "Marcus frowns. \"That's not how lattices work. Read up before you lecture me on my own crystals.\"", "effect": "None" }
+                    },
+                    {
+                        "text": "Share the schematics and I'll tell you how the arrays hold up in the field.",
+                        "effect": { "GiveInformation": "Marcus walks you through the array's tuning sequence: each sapphire is matched to its neighbours before the array is powered." },
+                        "reply": "Marcus considers it. \"Field reports would be worth something. I can't hand over the plans, but I'll show you the tuning sequence.\"",
+                        "check": { "Persuade": { "min_acuity": 55 } },
+                        "failure": { "text": "\"Everyone wants to field-test for me. Nobody ever reports back.\"", "effect": "None" }
+                    },
+                    {
+                        "text": "The Council would be very interested in unlicensed detection arrays.",
+                        "effect": { "GiveInformation": "Marcus lowers his voice and admits the arrays can read casters through two walls, further than their licence allows." },
+                        "reply": "Marcus pales. \"There's no need for that. Fine, here's what the arrays really do.\"",
+                        "check": { "Intimidate": { "faction": "MagistersCouncil", "min_standing": 40 } },
+                        "failure": { "text": "\"The Council? They don't know your name. Get out of my workshop.\"", "effect": { "FactionStandingChange": ["IndustrialConsortium", -5] } }
+                    }
+                ]),
             ),
             "resonance_observatory"
         )?;
@@ -793,12 +761,18 @@ impl DatabaseManager {
             "Safety Warden Gareth Ironshield",
             "A safety officer haunted by past magical disasters. His vigilance has prevented countless accidents, but the weight of responsibility shows in his weathered face.",
             Some("magisters_council"),
-            &create_dialogue_tree(
-                vec!["Safety first in the testing chambers. I've seen what happens when protocols are ignored.", "These containment systems have saved more lives than most realize."],
-                vec![
-                    ("safety_protocols", vec!["Every safety rule is written in someone's pain.", "Proper containment prevents magical cascade failures."], None),
-                    ("magical_disasters", vec!["The Unstable Site reminds us of magic's dangers.", "Prevention is always better than cleanup."], Some("sympathetic_networks")),
-                ]
+            &with_time_greetings(
+                create_dialogue_tree(
+                    vec!["Safety first in the testing chambers. I've seen what happens when protocols are ignored.", "These containment systems have saved more lives than most realize."],
+                    vec![
+                        ("safety_protocols", vec!["Every safety rule is written in someone's pain.", "Proper containment prevents magical cascade failures."], None),
+                        ("magical_disasters", vec!["The Unstable Site reminds us of magic's dangers.", "Prevention is always better than cleanup."], Some("sympathetic_networks")),
+                    ]
+                ),
+                serde_json::json!({
+                    "morning": "Drills first, questions after. The wards don't check themselves.",
+                    "evening": "Late to be in the chambers, {player}. Make it quick, and touch nothing that hums.",
+                }),
             ),
             "harmonic_testing_chambers"
         )?;
@@ -858,7 +832,7 @@ impl DatabaseManager {
             &create_dialogue_tree(
                 vec!["Welcome to the Archives. Knowledge illuminates, but it must be shared responsibly.", "These crystals contain the accumulated wisdom of generations."],
                 vec![
-                    ("knowledge_theory", vec!["Information stored in crystal matrices never degrades.", "Proper resonance allows direct knowledge transfer."], Some("crystalline_archives")),
+                    ("knowledge_theory", vec!["Information stored in crystal matrices never degrades.", "Proper resonance allows direct knowledge transfer."], Some("crystal_structures")),
                     ("forbidden_knowledge", vec!["Some knowledge requires wisdom to handle safely.", "I decide what information students are ready to access."], Some("sympathetic_networks")),
                 ]
             ),
@@ -905,7 +879,7 @@ impl DatabaseManager {
                 vec!["This place whispers secrets the Council wants buried.", "Reality bends here in ways that reveal magic's true nature."],
                 vec![
                     ("unstable_magic", vec!["Chaos teaches lessons that order cannot.", "The boundaries between dimensions grow thin here."], Some("sympathetic_networks")),
-                    ("forbidden_research", vec!["True understanding requires embracing danger.", "They fear what we might discover in the chaos."], Some("energy_manipulation")),
+                    ("forbidden_research", vec!["True understanding requires embracing danger.", "They fear what we might discover in the chaos."], Some("resonance_amplification")),
                 ]
             ),
             "unstable_resonance_site"
@@ -952,6 +926,713 @@ impl DatabaseManager {
             "tutorial_chamber"
         )?;
 
+        self.load_default_npc_schedules()?;
+        self.load_default_flag_content()?;
+
+        Ok(())
+    }
+
+    /// Load the default text and topics tied to world flags
+    fn load_default_flag_content(&self) -> GameResult<()> {
+        use crate::systems::world_flags::FlaggedText;
+
+        let texts = [
+            ("faction_diplomacy_hall", FlaggedText::new("accord_signed", "A copy of the accord between the Council and the Underground hangs framed beside the door, both seals pressed side by side.")),
+            ("faction_diplomacy_hall", FlaggedText::new("talks_collapsed", "The negotiating table has been pushed against the wall, and the envoys' chairs stand empty.")),
+            ("ambassador_cordelia", FlaggedText::new("accord_signed", "\"The accord is holding,\" Cordelia says, with the first real smile you've seen from her. \"Thanks in no small part to you.\"")),
+            ("secretary_malik", FlaggedText::new("accord_signed", "Malik taps a freshly bound volume. \"The accord is in the record now. People will be reading about that mediation for years.\"")),
+            ("secretary_malik", FlaggedText::new("talks_collapsed", "Malik closes his ledger on the failed negotiation with a sigh. \"I record what happened, not what should have.\"")),
+            ("secretary_malik", FlaggedText::new("negotiation_records_read", "\"Since you've read the records, you know how close the last round came to breaking down.\"")),
+        ];
+        let topics = [
+            ("secretary_malik", "record_keeping", "negotiation_records_read"),
+        ];
+
+        // Replace rather than duplicate content when it is reloaded
+        for (subject_id, _) in &texts {
+            self.connection.execute("DELETE FROM flag_texts WHERE subject_id = ?1", params![subject_id])
+                .map_err(|e| crate::GameError::DatabaseError(format!("Failed to clear flag text: {}", e)))?;
+        }
+
+        for (subject_id, text) in &texts {
+            self.insert_flag_text(subject_id, text)?;
+        }
+        for (npc_id, topic, flag) in &topics {
+            self.connection.execute(
+                "INSERT OR REPLACE INTO npc_topic_flags (npc_id, topic, flag) VALUES (?1, ?2, ?3)",
+                params![npc_id, topic, flag],
+            ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert topic flag: {}", e)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Load the default NPC routines
+    fn load_default_npc_schedules(&self) -> GameResult<()> {
+        use crate::systems::dialogue::ScheduleEntry;
+
+        let entry = |start_hour: u8, end_hour: u8, activity: &str, location: Option<&str>, available: bool, quest_id: Option<&str>| ScheduleEntry {
+            start_hour,
+            end_hour,
+            activity: activity.to_string(),
+            location: location.map(str::to_string),
+            available,
+            quest_id: quest_id.map(str::to_string),
+            flag: None,
+            weekday: None,
+        };
+        // Travel set off by events in the world, while a world flag condition holds
+        let after = |flag: &str, entry: ScheduleEntry| ScheduleEntry { flag: Some(flag.to_string()), ..entry };
+
+        let mut schedules = vec![
+            ("warden_gareth", entry(14, 18, "inspecting the containment wards at the Unstable Site", Some("unstable_resonance_site"), false, None)),
+            ("warden_gareth", entry(8, 10, "running the morning safety drills", None, false, None)),
+            ("dr_felix", entry(9, 12, "cataloguing crystal samples in the Archives", Some("crystalline_archives"), false, None)),
+            ("dr_felix", entry(14, 18, "running growth trials with Seraphina's plants", None, true, Some("healing_research"))),
+            ("healer_seraphina", entry(6, 8, "tending the garden beds before the day's research", None, false, None)),
+            ("observer_lyra", entry(18, 22, "calibrating the arrays for the night watch", None, false, None)),
+            ("ambassador_cordelia", entry(10, 13, "meeting privately with faction envoys", None, false, None)),
+            ("ambassador_cordelia", entry(14, 17, "consulting the watchers at the Observatory", Some("resonance_observatory"), true, Some("diplomatic_balance"))),
+            ("captain_vera", entry(16, 20, "patrolling the Testing Chambers perimeter", Some("harmonic_testing_chambers"), true, Some("unstable_site_investigation"))),
+            ("ambassador_cordelia", after("accord_signed", entry(9, 12, "chairing the accord's joint council at the Observatory", Some("resonance_observatory"), true, None))),
+            ("secretary_malik", after("talks_collapsed", entry(13, 17, "searching the Archives for precedents to restart the talks", Some("crystalline_archives"), true, None))),
+            ("mage_kira", after("talks_collapsed", entry(19, 23, "lying low in the Archives stacks while tempers cool", Some("crystalline_archives"), false, None))),
+        ];
+
+        // Members go to their faction's weekly meetings
+        for meeting in crate::core::calendar::MEETINGS {
+            for (npc_id, activity) in meeting.attendees {
+                schedules.push((npc_id, ScheduleEntry {
+                    weekday: Some(meeting.weekday),
+                    ..entry(meeting.start_hour, meeting.end_hour, activity, Some(meeting.location), true, None)
+                }));
+            }
+        }
+
+        // Replace rather than duplicate routines when content is reloaded
+        for (npc_id, _) in &schedules {
+            self.connection.execute("DELETE FROM npc_schedules WHERE npc_id = ?1", params![npc_id])
+                .map_err(|e| crate::GameError::DatabaseError(format!("Failed to clear NPC schedule: {}", e)))?;
+        }
+
+        for (npc_id, schedule_entry) in &schedules {
+            self.insert_npc_schedule(npc_id, schedule_entry)?;
+        }
+
+        Ok(())
+    }
+
+    /// Load the default enemy definitions
+    fn load_default_enemies(&self) -> GameResult<()> {
+        use crate::systems::combat::{create_example_enemies, AiProfile, DifficultyTier, Enemy, EnemyPhase};
+        use crate::systems::status_effects::StatusKind;
+
+        for enemy in create_example_enemies() {
+            self.insert_enemy(&enemy)?;
+        }
+
+        self.insert_enemy(
+            &Enemy::new(
+                "static_wisp".to_string(),
+                "Static Wisp".to_string(),
+                "A flickering knot of stray charge drawn to the observatory's detection arrays, and to any mind it can feed on.".to_string(),
+                DifficultyTier::Beginner,
+            )
+            .with_resistance("light", 0.6)
+            .with_vulnerable_frequency(3)
+            .with_loot("crystal_fragment", 0.4, (1, 1))
+            .with_ai_profile(AiProfile::SpellThief)
+            .with_status_ability(StatusKind::Stun, 0.2, 1)
+            .with_habitat("resonance_observatory"),
+        )?;
+
+        self.insert_enemy(
+            &Enemy::new(
+                "archive_sentinel".to_string(),
+                "Archive Sentinel".to_string(),
+                "A crystal construct built to guard the restricted stacks, humming at a steady warning pitch.".to_string(),
+                DifficultyTier::Intermediate,
+            )
+            .with_resistance("detection", 0.5)
+            .with_resistance("light", 0.2)
+            .with_vulnerable_frequency(6)
+            .with_faction(FactionId::MagistersCouncil)
+            .with_loot("research_notes", 0.7, (1, 2))
+            .with_ai_profile(AiProfile::Relentless)
+            .with_status_ability(StatusKind::Disorient, 0.3, 2)
+            .with_habitat("crystalline_archives"),
+        )?;
+
+        // Capstone fight at the unstable site; phases are declared as data
+        let rift_phases: Vec<EnemyPhase> = serde_json::from_value(serde_json::json!([
+            {
+                "health_threshold": 0.66,
+                "name": "Fracturing",
+                "announcement": "The heart splits along its faults, and each shard sings a different note.",
+                "resistances": { "light": 0.6, "manipulation": 0.2 },
+                "vulnerable_frequency": 4,
+                "ai_profile": "Aggressive",
+                "status_abilities": [{ "kind": "ResonanceBurn", "chance": 0.4, "duration": 3 }],
+                "damage_multiplier": 1.2
+            },
+            {
+                "health_threshold": 0.33,
+                "name": "Collapse",
+                "announcement": "The shards fall inward. The air itself hums on the edge of breaking.",
+                "resistances": { "light": 0.0, "healing": 0.0, "manipulation": 0.5 },
+                "vulnerable_frequency": 7,
+                "ai_profile": "Relentless",
+                "status_abilities": [{ "kind": "Stun", "chance": 0.3, "duration": 1 }],
+                "damage_multiplier": 1.5,
+                "cleanse": true
+            }
+        ])).map_err(|e| crate::GameError::DatabaseError(format!("Invalid phase data: {}", e)))?;
+
+        let mut rift_heart = Enemy::new(
+            "rift_heart".to_string(),
+            "Rift Heart".to_string(),
+            "The crystallised core of the original disaster, pulsing with resonance that bends the air around it.".to_string(),
+            DifficultyTier::Boss,
+        )
+        .with_resistance("light", 0.3)
+        .with_resistance("healing", 0.5)
+        .with_vulnerable_frequency(2)
+        .with_loot("rare_crystal", 1.0, (1, 2))
+        .with_ai_profile(AiProfile::Balanced)
+        .with_status_ability(StatusKind::Disorient, 0.25, 2)
+        .with_habitat("unstable_resonance_site");
+        for phase in rift_phases {
+            rift_heart = rift_heart.with_phase(phase);
+        }
+        self.insert_enemy(&rift_heart)?;
+
+        Ok(())
+    }
+
+    /// Load the default crafting recipes
+    fn load_default_recipes(&self) -> GameResult<()> {
+        use crate::systems::items::core::ItemEffect;
+        use crate::systems::items::crafting::{Ingredient, Recipe, RecipeOutput, Station};
+
+        let ingredient = |item: &str, quantity: i32| Ingredient { item: item.to_string(), quantity };
+        let station = |name: &str, location: &str| Some(Station { name: name.to_string(), locations: vec![location.to_string()] });
+
+        let recipes = [
+            Recipe {
+                id: "mortar_and_pestle".to_string(),
+                name: "mortar and pestle".to_string(),
+                description: "Two river stones, one hollowed, for grinding reagents by hand.".to_string(),
+                output: RecipeOutput::Tool { function: "grinding".to_string() },
+                ingredients: vec![ingredient("river stone", 2)],
+                tools: Vec::new(),
+                station: None,
+                theory: None,
+                min_understanding: 0.0,
+                base_chance: 0.8,
+                minutes: 20,
+            },
+            Recipe {
+                id: "healing_salve".to_string(),
+                name: "healing salve".to_string(),
+                description: "Silverleaf ground into beeswax; its resonance coaxes torn tissue back together.".to_string(),
+                output: RecipeOutput::Consumable { effect: ItemEffect::HealDamage(20), uses: 1 },
+                ingredients: vec![ingredient("silverleaf", 2), ingredient("beeswax", 1)],
+                tools: vec!["grinding".to_string()],
+                station: None,
+                theory: Some("bio_resonance".to_string()),
+                min_understanding: 0.2,
+                base_chance: 0.55,
+                minutes: 30,
+            },
+            Recipe {
+                id: "focus_tonic".to_string(),
+                name: "focus tonic".to_string(),
+                description: "Spring water steeped over crystal fragments until it rings faintly when poured.".to_string(),
+                output: RecipeOutput::Consumable {
+                    effect: ItemEffect::Multiple(vec![ItemEffect::RestoreEnergy(20), ItemEffect::ReduceFatigue(10)]),
+                    uses: 1,
+                },
+                ingredients: vec![ingredient("crystal fragment", 1), ingredient("spring water", 1)],
+                tools: Vec::new(),
+                station: station("alchemy bench", "crystal_garden_lab"),
+                theory: Some("mental_resonance".to_string()),
+                min_understanding: 0.2,
+                base_chance: 0.5,
+                minutes: 45,
+            },
+            Recipe {
+                id: "tuning_fork".to_string(),
+                name: "tuning fork".to_string(),
+                description: "A brass fork tipped with crystal, filed until it sings a clean reference tone.".to_string(),
+                output: RecipeOutput::Tool { function: "tuning".to_string() },
+                ingredients: vec![ingredient("brass rod", 1), ingredient("crystal fragment", 1)],
+                tools: Vec::new(),
+                station: station("tuning anvil", "harmonic_testing_chambers"),
+                theory: Some("harmonic_fundamentals".to_string()),
+                min_understanding: 0.2,
+                base_chance: 0.5,
+                minutes: 60,
+            },
+            Recipe {
+                id: "resonance_lens".to_string(),
+                name: "resonance lens".to_string(),
+                description: "A disc of ground glass that gathers faint magical signatures into a visible shimmer.".to_string(),
+                output: RecipeOutput::Tool { function: "detection".to_string() },
+                ingredients: vec![ingredient("glass blank", 1), ingredient("powdered quartz", 1)],
+                tools: vec!["grinding".to_string()],
+                station: station("optics bench", "resonance_observatory"),
+                theory: Some("light_manipulation".to_string()),
+                min_understanding: 0.3,
+                base_chance: 0.45,
+                minutes: 90,
+            },
+            Recipe {
+                id: "repair_kit".to_string(),
+                name: "repair kit".to_string(),
+                description: "A roll of brass shims, wax and fine files for keeping equipment in working order.".to_string(),
+                output: RecipeOutput::Tool { function: "repair".to_string() },
+                ingredients: vec![ingredient("brass rod", 1), ingredient("beeswax", 1)],
+                tools: Vec::new(),
+                station: None,
+                theory: None,
+                min_understanding: 0.0,
+                base_chance: 0.7,
+                minutes: 30,
+            },
+        ];
+
+        for recipe in &recipes {
+            self.insert_recipe(recipe)?;
+        }
+
+        Ok(())
+    }
+
+    /// Load the default Crystalline Archives catalog
+    fn load_default_items(&self) -> GameResult<()> {
+        use crate::systems::items::core::ItemType;
+        use crate::systems::placed_items::{display_name, ItemDefinition, Placement};
+
+        let material = |id: &str, kind: &str, description: &str| ItemDefinition {
+            id: id.to_string(),
+            name: display_name(id),
+            description: description.to_string(),
+            item_type: ItemType::Material { material_type: kind.to_string(), quality: 0.6 },
+            custom_properties: std::collections::HashMap::new(),
+        };
+
+        // Unidentified finds go by a vague name until appraised
+        let unidentified = |id: &str, description: &str, item_type: ItemType, true_name: &str, true_description: &str| {
+            use crate::systems::items::identification::{TRUE_DESCRIPTION_PROPERTY, TRUE_NAME_PROPERTY};
+            ItemDefinition {
+                id: id.to_string(),
+                name: display_name(id),
+                description: description.to_string(),
+                item_type,
+                custom_properties: std::collections::HashMap::from([
+                    (TRUE_NAME_PROPERTY.to_string(), true_name.to_string()),
+                    (TRUE_DESCRIPTION_PROPERTY.to_string(), true_description.to_string()),
+                ]),
+            }
+        };
+
+        // Artifacts act through a named behavior, tuned by `artifact_` parameters
+        let with_behavior = |mut definition: ItemDefinition, behavior: &str, params: &[(&str, &str)]| {
+            use crate::systems::items::artifacts::BEHAVIOR_PROPERTY;
+            definition.custom_properties.insert(BEHAVIOR_PROPERTY.to_string(), behavior.to_string());
+            for (param, value) in params {
+                definition.custom_properties.insert(format!("artifact_{}", param), value.to_string());
+            }
+            definition
+        };
+        let named = |id: &str, name: &str, description: &str, item_type: ItemType| ItemDefinition {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            item_type,
+            custom_properties: std::collections::HashMap::new(),
+        };
+
+        let items = [
+            material("river_stone", "stone", "A smooth, palm-sized stone, dense enough to grind against."),
+            material("silverleaf", "herb", "A pale herb whose leaves shimmer faintly when bruised."),
+            material("beeswax", "wax", "A block of golden wax, soft enough to work by hand."),
+            material("crystal_fragment", "crystal", "A shard of raw crystal that hums when held near others."),
+            material("spring_water", "water", "Cold, clear water drawn from the garden's spring."),
+            material("brass_rod", "metal", "A short length of brass rod, offcut from an apparatus."),
+            material("glass_blank", "glass", "An unground disc of optical glass."),
+            material("powdered_quartz", "crystal", "Quartz ground to a fine, glittering powder."),
+            material("resonant_solvent", "solvent", "A sharp-smelling solvent used to clean and dissolve crystal."),
+            material("copper_wire", "metal", "A coil of fine copper wire."),
+            material("brass_frame", "metal", "A small brass frame, made to hold a lens."),
+            with_behavior(unidentified(
+                "strange_artifact",
+                "A palm-sized disc of dark stone ringed with brass, faintly warm to the touch.",
+                ItemType::Artifact { properties: "Hums in sympathy with nearby crystals, sounding their resonant frequency.".to_string() },
+                "Harmonic Keystone",
+                "A tuning keystone from an early resonance engine. Held near a crystal, it sings the crystal's frequency aloud.",
+            ), "frequency_sense", &[]),
+            unidentified(
+                "unknown_crystal",
+                "A cloudy crystal with a reddish core that flickers when you look away.",
+                ItemType::Material { material_type: "crystal".to_string(), quality: 0.9 },
+                "Flux-Touched Garnet",
+                "A garnet steeped in the site's unstable flux; its lattice holds resonance unusually well.",
+            ),
+            unidentified(
+                "murky_vial",
+                "A stoppered vial of something cloudy that smells of crushed leaves.",
+                ItemType::Consumable {
+                    effect: crate::systems::items::core::ItemEffect::TemporaryAttributeBoost {
+                        attribute: "mental_acuity".to_string(),
+                        amount: 4,
+                        duration: 90,
+                    },
+                    uses_remaining: 1,
+                },
+                "Draught of Clarity",
+                "A herbal draught that sharpens thought for an hour and a half.",
+            ),
+            // Quest rewards
+            with_behavior(named(
+                "optimized_crystal_prototype",
+                "Optimized Crystal Prototype",
+                "Marcus's reworked lattice, cut to hand back part of the energy a spell draws through it.",
+                ItemType::Artifact { properties: "Returns some mental energy after each successful spell.".to_string() },
+            ), "energy_echo", &[("energy", "4")]),
+            with_behavior(named(
+                "advanced_analysis_tools",
+                "Advanced Analysis Tools",
+                "A fitted case of calibrated lenses and tuning forks that read the resonance of anything nearby.",
+                ItemType::Tool { tool_function: "detection".to_string() },
+            ), "frequency_sense", &[]),
+            with_behavior(named(
+                "diplomatic_resonance_crystal",
+                "Diplomatic Resonance Crystal",
+                "A pendant crystal tuned to the rhythms of speech, worn by envoys between the factions.",
+                ItemType::Equipment(crate::systems::items::equipment::Equipment {
+                    slot: crate::systems::items::equipment::EquipmentSlot::Neck,
+                    bonuses: Vec::new(),
+                    requirements: crate::systems::items::equipment::EquipmentRequirements {
+                        min_attributes: std::collections::HashMap::new(),
+                        required_theories: Vec::new(),
+                        faction_requirements: std::collections::HashMap::new(),
+                        min_level: None,
+                    },
+                    special_abilities: Vec::new(),
+                }),
+            ), "attunement", &[("theory", "mental_resonance"), ("floor", "0.25")]),
+            named(
+                "detection_array_schematics",
+                "Detection Array Schematics",
+                "Marcus's working drawings for the Observatory's detection arrays, crystal tunings pencilled in the margins.",
+                ItemType::Book { theory_id: "detection_arrays".to_string() },
+            ),
+            ItemDefinition {
+                id: "worn_primer".to_string(),
+                name: display_name("worn_primer"),
+                description: "A dog-eared primer on harmonic fundamentals, margins full of a student's questions.".to_string(),
+                item_type: ItemType::Book { theory_id: "harmonic_fundamentals".to_string() },
+                custom_properties: std::collections::HashMap::new(),
+            },
+        ];
+        for item in &items {
+            self.insert_item_definition(item)?;
+        }
+
+        // (location, item, respawn minutes; None for one-time finds)
+        let placements = [
+            ("tutorial_chamber", "river_stone", Some(240)),
+            ("tutorial_chamber", "worn_primer", None),
+            ("practice_hall", "brass_rod", Some(720)),
+            ("practice_hall", "beeswax", Some(480)),
+            ("resonance_observatory", "glass_blank", None),
+            ("resonance_observatory", "brass_frame", None),
+            ("crystal_garden_lab", "silverleaf", Some(360)),
+            ("crystal_garden_lab", "spring_water", Some(120)),
+            ("harmonic_testing_chambers", "copper_wire", Some(720)),
+            ("crystalline_archives", "resonant_solvent", Some(1440)),
+            ("crystalline_archives", "strange_artifact", None),
+            ("crystal_garden_lab", "murky_vial", None),
+            ("unstable_resonance_site", "unknown_crystal", Some(1440)),
+            ("unstable_resonance_site", "crystal_fragment", Some(180)),
+            ("unstable_resonance_site", "powdered_quartz", Some(360)),
+        ];
+        for (location, item_id, respawn_minutes) in placements {
+            self.insert_item_placement(&Placement {
+                location: location.to_string(),
+                item_id: item_id.to_string(),
+                respawn_minutes,
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn load_default_archive_texts(&self) -> GameResult<()> {
+        self.insert_archive_text(
+            "standing_waves_treatise",
+            "A Treatise on Standing Waves",
+            &["resonance", "harmonics", "waves", "frequency"],
+            Some("harmonic_fundamentals"),
+            1,
+            "When two waves of equal frequency travel in opposite directions, they form a standing wave \
+             whose nodes never move. The earliest resonance scholars learned to place crystals at the \
+             antinodes, where energy concentrates, doubling the yield of their first experiments.",
+        )?;
+
+        self.insert_archive_text(
+            "lattice_defects_survey",
+            "Survey of Lattice Defects",
+            &["crystal", "crystals", "lattice", "purity", "defects"],
+            Some("crystal_structures"),
+            2,
+            "Every crystal carries flaws: vacancies, dislocations, and foreign atoms trapped during growth. \
+             This survey catalogs how each defect scatters resonant energy, and why Council assayers \
+             grade crystals by purity before licensing them for practical use.",
+        )?;
+
+        self.insert_archive_text(
+            "mind_field_journal",
+            "Journal of the Mind-Field Experiments",
+            &["mental", "mind", "fatigue", "consciousness"],
+            Some("mental_resonance"),
+            2,
+            "Volunteers who cast repeatedly showed measurable drops in reaction time long before they \
+             reported feeling tired. The journal concludes that fatigue is a physical strain on the \
+             mind's own resonance, and recommends structured rest between workings.",
+        )?;
+
+        self.insert_archive_text(
+            "prism_notebooks",
+            "The Prism Notebooks",
+            &["light", "spectrum", "optics", "color"],
+            Some("light_manipulation"),
+            3,
+            "A white beam passed through a tuned quartz prism splits into its component colors, each \
+             bending by a different amount. The notebooks record the first attempt to reverse the \
+             process with resonance, recombining the spectrum into a single coherent beam.",
+        )?;
+
+        self.insert_archive_text(
+            "sympathetic_network_charter",
+            "Charter of the First Sympathetic Network",
+            &["network", "networks", "communication", "distance"],
+            Some("sympathetic_networks"),
+            4,
+            "Twelve paired crystals, cut from the same parent stone, once linked the city's watchtowers. \
+             The charter describes how the pairs drifted out of tune over months and how relay stations \
+             were added to keep the network coherent across the river.",
+        )?;
+
+        self.insert_archive_text(
+            "garden_waterworks_plans",
+            "Waterworks of the Crystal Garden",
+            &["garden", "water", "waterworks", "drainage", "plans"],
+            None,
+            1,
+            "The surveyor's plans show how the garden's streams are fed from the old city cistern and drained \
+             back into it through a grate beneath the lowest terrace. A later hand has noted that the cistern \
+             has since run dry, and that the ladder down is still sound.",
+        )?;
+
+        self.insert_archive_text(
+            "founding_of_the_council",
+            "On the Founding of the Magisters' Council",
+            &["council", "history", "factions", "regulation", "politics"],
+            None,
+            1,
+            "After the disaster that created the Unstable Resonance Site, the city's surviving scholars \
+             agreed that no single workshop should again attempt uncontained amplification. Their \
+             agreement became the first charter of the Magisters' Council.",
+        )?;
+
+        Ok(())
+    }
+
+    fn load_default_scenery(&self) -> GameResult<()> {
+        use crate::systems::scenery::{CheckAttribute, Interaction, SceneryAction, SceneryYield, SkillCheck};
+
+        let says = |action: SceneryAction, text: &str| Interaction { action, text: text.to_string(), check: None, yields: None };
+        let object = |id: &str, location_id: &str, name: &str, aliases: &[&str], interactions: Vec<Interaction>| SceneryObject {
+            id: id.to_string(),
+            location_id: location_id.to_string(),
+            name: name.to_string(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            interactions,
+        };
+
+        let objects = [
+            object("tutorial_formations", "tutorial_chamber", "crystal formation", &["formations", "crystal formations", "walls"], vec![
+                says(SceneryAction::Examine,
+                    "Clusters of pale quartz grow straight out of the stone, each point facing the centre of the room. \
+                     Someone long ago arranged the chamber around them rather than the other way round."),
+                Interaction {
+                    action: SceneryAction::Touch,
+                    text: "Under your palm the formation hums, and the hum steadies as your breathing does. For a moment \
+                           you can feel exactly how far your own resonance reaches.".to_string(),
+                    check: Some(SkillCheck {
+                        attribute: CheckAttribute::ResonanceSensitivity,
+                        difficulty: 1,
+                        failure: "The crystal is cool and faintly buzzing, but whatever it's doing, you can't quite feel it yet.".to_string(),
+                    }),
+                    yields: Some(SceneryYield::Lore {
+                        title: "Notes on the Tutorial Formations".to_string(),
+                        text: "The formations answer whoever touches them, and the answer settles as the student settles. \
+                               A calm mind makes for a clean resonance.".to_string(),
+                    }),
+                },
+            ]),
+            object("practice_scorch_marks", "practice_hall", "scorch marks", &["scorch", "marks", "floor"], vec![
+                says(SceneryAction::Examine,
+                    "Generations of scorch marks overlap on the flagstones. The darkest form a ring around the room's \
+                     centre, exactly where a careless caster would stand."),
+                says(SceneryAction::Touch, "The stone is cold now, but gritty with soot that comes away on your fingers."),
+            ]),
+            object("practice_debris", "practice_hall", "crystal debris", &["debris", "rubble", "fragments", "shards"], vec![
+                says(SceneryAction::Examine, "Shattered crystal and chipped stone, swept into the corners and forgotten."),
+                Interaction {
+                    action: SceneryAction::Search,
+                    text: "You sift through the debris, feeling for anything that still rings true.".to_string(),
+                    check: Some(SkillCheck {
+                        attribute: CheckAttribute::ResonanceSensitivity,
+                        difficulty: 2,
+                        failure: "You sift through the debris, but every shard you pick up is dead glass.".to_string(),
+                    }),
+                    yields: Some(SceneryYield::Item { item_id: "crystal_fragment".to_string() }),
+                },
+            ]),
+            object("garden_beds", "crystal_garden_lab", "growing beds", &["beds", "terraces", "plants"], vec![
+                says(SceneryAction::Examine,
+                    "Each terrace is planted to a different crystal's frequency. The silverleaf nearest the emerald \
+                     seedlings stands nearly twice the height of the rest."),
+                Interaction {
+                    action: SceneryAction::Search,
+                    text: "You part the leaves carefully, looking along the rows.".to_string(),
+                    check: None,
+                    yields: Some(SceneryYield::Item { item_id: "silverleaf".to_string() }),
+                },
+            ]),
+            object("archive_alcove", "crystalline_archives", "reading alcove", &["alcove", "alcoves", "reading alcoves"], vec![
+                says(SceneryAction::Examine, "A curved niche with a crystal plinth at its heart, worn smooth by scholars' hands."),
+                Interaction {
+                    action: SceneryAction::Touch,
+                    text: "You match your resonance to the plinth, and a fragment of an old lecture surfaces in your mind.".to_string(),
+                    check: Some(SkillCheck {
+                        attribute: CheckAttribute::MentalAcuity,
+                        difficulty: 2,
+                        failure: "Voices murmur at the edge of hearing, too many at once to follow.".to_string(),
+                    }),
+                    yields: Some(SceneryYield::Lore {
+                        title: "A Lecture on Sympathetic Distance".to_string(),
+                        text: "Resonance weakens with distance not because the link frays, but because the world in between \
+                               keeps interrupting. Shield the path, and two crystals may speak across a city.".to_string(),
+                    }),
+                },
+            ]),
+            object("diplomacy_treaties", "faction_diplomacy_hall", "peace treaties", &["treaties", "treaty", "etchings"], vec![
+                says(SceneryAction::Examine,
+                    "Line after line of crystal-etched clauses, each signed in five inks. The newest are cut more \
+                     shallowly than the old, as if no one expected them to last."),
+                Interaction {
+                    action: SceneryAction::Search,
+                    text: "You run your eye down the oldest treaty, clause by clause.".to_string(),
+                    check: Some(SkillCheck {
+                        attribute: CheckAttribute::MentalAcuity,
+                        difficulty: 1,
+                        failure: "The legal phrasing defeats you; the clauses blur together.".to_string(),
+                    }),
+                    yields: Some(SceneryYield::Lore {
+                        title: "The Hall Accord".to_string(),
+                        text: "No faction may work resonance against another within these walls, and any who do forfeit \
+                               their seat until all five agree to restore it.".to_string(),
+                    }),
+                },
+            ]),
+            object("site_rubble", "unstable_resonance_site", "rubble", &["ruins", "collapsed wall", "debris"], vec![
+                says(SceneryAction::Examine,
+                    "What's left of the old workshop: stone blocks half-melted into glass, flickering in and out of \
+                     their own outlines."),
+                Interaction {
+                    action: SceneryAction::Search,
+                    text: "You pick through the rubble, keeping well clear of the shimmering edges.".to_string(),
+                    check: Some(SkillCheck {
+                        attribute: CheckAttribute::MentalAcuity,
+                        difficulty: 3,
+                        failure: "You find nothing but glassy stone, and you're not sure all of it was there a moment ago.".to_string(),
+                    }),
+                    yields: Some(SceneryYield::Silver { amount: 12 }),
+                },
+                says(SceneryAction::Touch, "The stone is warm, and for an instant your hand seems to pass a finger's width into it."),
+            ]),
+        ];
+
+        for object in &objects {
+            self.insert_scenery(object)?;
+        }
+
+        Ok(())
+    }
+
+    fn load_default_ambience(&self) -> GameResult<()> {
+        use crate::core::world_state::{TimeOfDay, Weather};
+        use crate::systems::ambience::{AmbienceConfig, AmbientCondition, AmbientLine, DEFAULT_CHANCE};
+
+        let always = |text: &str| AmbientLine::new(AmbientCondition::Always, text);
+        let at = |time: TimeOfDay, text: &str| AmbientLine::new(AmbientCondition::TimeOfDay { time }, text);
+        let during = |weather: Weather, text: &str| AmbientLine::new(AmbientCondition::Weather { weather }, text);
+        let config = |chance: f32, lines: Vec<AmbientLine>| AmbienceConfig { chance, lines, general: true };
+
+        let ambience = [
+            ("tutorial_chamber", config(DEFAULT_CHANCE, vec![
+                always("A practice crystal on the shelf chimes softly as you pass, then falls quiet."),
+                at(TimeOfDay::Night, "The chamber's single lamp throws your shadow huge across the diagrams on the wall."),
+            ])),
+            ("practice_hall", config(0.5, vec![
+                always("Somewhere down the hall, a student's spell fizzles with a sharp pop and a muttered curse."),
+                always("Scorch marks on the floor show where the last set of exercises went wrong."),
+                at(TimeOfDay::Morning, "Apprentices file in with their notebooks, still yawning."),
+                AmbientLine::new(AmbientCondition::RecentMagic { min_signatures: 3 }, "The practice targets are still warm from the morning's drills."),
+            ])),
+            ("resonance_observatory", config(DEFAULT_CHANCE, vec![
+                always("The great arrays turn a fraction of a degree with a low grinding sigh."),
+                at(TimeOfDay::Midnight, "At this hour the arrays sing faintly, tuned to something far above the city."),
+                during(Weather::Cloudy, "An observer frowns up at the clouds and taps a gauge that refuses to move."),
+                during(Weather::ResonanceStorm, "Every needle on the instrument wall swings hard over and stays there."),
+            ])),
+            ("crystal_garden_lab", config(DEFAULT_CHANCE, vec![
+                always("A growing crystal ticks as a new facet settles into place."),
+                at(TimeOfDay::Dawn, "Dew beads on the crystal leaves and splits the first light into colours."),
+                during(Weather::Rainy, "Rain streams down the glass roof, and the garden smells of wet stone and mint."),
+            ])),
+            ("harmonic_testing_chambers", config(DEFAULT_CHANCE, vec![
+                always("A test rig thrums through the floor, steady as a heartbeat."),
+                always("A technician chalks a result on the board, then scrubs it out again."),
+                AmbientLine::new(AmbientCondition::Faction { faction: "industrial_consortium".to_string(), min_influence: 30 }, "Consortium inspectors drift between the rigs, clipboards held close."),
+            ])),
+            ("faction_diplomacy_hall", config(DEFAULT_CHANCE, vec![
+                always("Footsteps echo from the gallery, then stop, as if someone is listening."),
+                at(TimeOfDay::Evening, "Servants clear the day's cups from the long table, careful not to disturb the papers."),
+            ])),
+            ("crystalline_archives", config(DEFAULT_CHANCE, vec![
+                always("A page turns somewhere in the stacks, loud in the hush."),
+                always("Dust motes hang in the lamplight over the reading desks."),
+                during(Weather::Stormy, "Thunder rolls outside, and an archivist glances anxiously at the skylights."),
+            ])),
+            ("unstable_resonance_site", config(0.5, vec![
+                always("Loose shards skitter across the ground, pushed by nothing you can see."),
+                always("For a heartbeat the air smells of lightning, then of nothing at all."),
+                during(Weather::NullFog, "In the null fog even the Site seems to hold its breath."),
+            ])),
+            ("whispering_cellar", config(DEFAULT_CHANCE, vec![
+                always("Water drips somewhere in the dark, each drop answered by a faint whisper from the stone."),
+            ])),
+        ];
+
+        for (location_id, config) in &ambience {
+            self.insert_ambience(location_id, config)?;
+        }
+
         Ok(())
     }
 
@@ -979,6 +1660,19 @@ impl DatabaseManager {
         Ok(())
     }
 
+    /// Keep a location for those in good standing with a faction
+    pub fn insert_location_restriction(&self, location_id: &str, restriction: &crate::systems::stealth::Restriction) -> GameResult<()> {
+        let restriction_json = serde_json::to_string(restriction)
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to serialize restriction: {}", e)))?;
+
+        self.connection.execute(
+            "UPDATE locations SET restriction = ?1 WHERE id = ?2",
+            params![restriction_json, location_id],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert restriction: {}", e)))?;
+
+        Ok(())
+    }
+
     /// Insert an exit between locations
     pub fn insert_exit(&self, from_location: &str, direction: &str, to_location: &str) -> GameResult<()> {
         self.connection.execute(
@@ -989,6 +1683,37 @@ impl DatabaseManager {
         Ok(())
     }
 
+    /// Insert an exit that stays hidden until discovered
+    pub fn insert_hidden_exit(&self, from_location: &str, direction: &str, hidden: &HiddenExit) -> GameResult<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO hidden_exits (location_id, direction, destination_id, feature, clue, known_to, documents)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                from_location,
+                direction,
+                hidden.to,
+                hidden.feature,
+                hidden.clue,
+                serde_json::to_string(&hidden.known_to).unwrap_or_else(|_| "[]".to_string()),
+                serde_json::to_string(&hidden.documents).unwrap_or_else(|_| "[]".to_string()),
+            ],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert hidden exit: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Put a lock on an existing exit
+    pub fn insert_exit_lock(&self, from_location: &str, direction: &str, lock: &ExitLock) -> GameResult<()> {
+        let access_json = serde_json::to_string(lock)
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to serialize exit lock: {}", e)))?;
+        self.connection.execute(
+            "UPDATE location_exits SET access = ?3 WHERE location_id = ?1 AND direction = ?2",
+            params![from_location, direction, access_json],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert exit lock: {}", e)))?;
+
+        Ok(())
+    }
+
     /// Insert a comprehensive magic theory with all learning metadata
     pub fn insert_comprehensive_theory(
         &self,
@@ -1132,7 +1857,7 @@ impl DatabaseManager {
 
         // Load basic location data
         let mut stmt = self.connection.prepare(
-            "SELECT id, name, description, ambient_energy, dominant_frequency, interference, phenomena, visited
+            "SELECT id, name, description, ambient_energy, dominant_frequency, interference, phenomena, visited, restriction
              FROM locations"
         ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare location query: {}", e)))?;
 
@@ -1145,6 +1870,7 @@ impl DatabaseManager {
             let interference: f32 = row.get(5)?;
             let phenomena_json: String = row.get(6)?;
             let visited: bool = row.get(7)?;
+            let restriction: Option<String> = row.get(8)?;
 
             let phenomena: Vec<String> = serde_json::from_str(&phenomena_json)
                 .unwrap_or_else(|_| Vec::new());
@@ -1154,6 +1880,8 @@ impl DatabaseManager {
                 name,
                 description,
                 exits: HashMap::new(), // Will be populated below
+                locks: HashMap::new(), // Will be populated below
+                hidden_exits: HashMap::new(), // Will be populated below
                 npcs: Vec::new(), // Will be populated below
                 items: Vec::new(), // Will be populated below
                 magical_properties: MagicalProperties {
@@ -1165,6 +1893,9 @@ impl DatabaseManager {
                 },
                 faction_presence: HashMap::new(), // Will be populated below
                 visited,
+                flag_descriptions: Vec::new(), // Will be populated below
+                ambience: Default::default(), // Will be populated below
+                restricted: restriction.and_then(|json| serde_json::from_str(&json).ok()),
             }))
         }).map_err(|e| crate::GameError::DatabaseError(format!("Failed to query locations: {}", e)))?;
 
@@ -1177,31 +1908,50 @@ impl DatabaseManager {
         // Load exits
         self.load_exits(&mut locations)?;
 
+        self.load_hidden_exits(&mut locations)?;
+
         // Load faction presence
         self.load_faction_presence(&mut locations)?;
 
+        // Ambient lines for descriptions
+        self.load_ambience(&mut locations)?;
+
+        // Stock placed items
+        self.load_location_items(&mut locations)?;
+
+        // Descriptions that change with world flags
+        for (id, texts) in self.load_flag_texts()? {
+            if let Some(location) = locations.get_mut(&id) {
+                location.flag_descriptions = texts;
+            }
+        }
+
         Ok(locations)
     }
 
     /// Load exits for all locations
     fn load_exits(&self, locations: &mut HashMap<String, Location>) -> GameResult<()> {
         let mut stmt = self.connection.prepare(
-            "SELECT location_id, direction, destination_id FROM location_exits"
+            "SELECT location_id, direction, destination_id, access FROM location_exits"
         ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare exits query: {}", e)))?;
 
         let exit_rows = stmt.query_map([], |row| {
             let location_id: String = row.get(0)?;
             let direction: String = row.get(1)?;
             let destination_id: String = row.get(2)?;
-            Ok((location_id, direction, destination_id))
+            let access_json: Option<String> = row.get(3)?;
+            Ok((location_id, direction, destination_id, access_json))
         }).map_err(|e| crate::GameError::DatabaseError(format!("Failed to query exits: {}", e)))?;
 
         for exit_result in exit_rows {
-            let (location_id, direction_str, destination_id) = exit_result
+            let (location_id, direction_str, destination_id, access_json) = exit_result
                 .map_err(|e| crate::GameError::DatabaseError(format!("Failed to parse exit: {}", e)))?;
 
             if let Some(location) = locations.get_mut(&location_id) {
                 if let Some(direction) = Direction::from_string(&direction_str) {
+                    if let Some(lock) = access_json.and_then(|json| serde_json::from_str(&json).ok()) {
+                        location.locks.insert(direction.clone(), lock);
+                    }
                     location.exits.insert(direction, destination_id);
                 }
             }
@@ -1210,6 +1960,40 @@ impl DatabaseManager {
         Ok(())
     }
 
+    /// Load hidden exits for all locations
+    fn load_hidden_exits(&self, locations: &mut HashMap<String, Location>) -> GameResult<()> {
+        let mut stmt = self.connection.prepare(
+            "SELECT location_id, direction, destination_id, feature, clue, known_to, documents FROM hidden_exits"
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare hidden exits query: {}", e)))?;
+
+        let rows = stmt.query_map([], |row| {
+            let location_id: String = row.get(0)?;
+            let direction: String = row.get(1)?;
+            let known_to_json: String = row.get(5)?;
+            let documents_json: String = row.get(6)?;
+            Ok((location_id, direction, HiddenExit {
+                to: row.get(2)?,
+                feature: row.get(3)?,
+                clue: row.get(4)?,
+                known_to: serde_json::from_str(&known_to_json).unwrap_or_default(),
+                documents: serde_json::from_str(&documents_json).unwrap_or_default(),
+            }))
+        }).map_err(|e| crate::GameError::DatabaseError(format!("Failed to query hidden exits: {}", e)))?;
+
+        for row in rows {
+            let (location_id, direction_str, hidden) = row
+                .map_err(|e| crate::GameError::DatabaseError(format!("Failed to parse hidden exit: {}", e)))?;
+
+            if let Some(location) = locations.get_mut(&location_id) {
+                if let Some(direction) = Direction::from_string(&direction_str) {
+                    location.hidden_exits.insert(direction, hidden);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Load faction presence for all locations
     fn load_faction_presence(&self, locations: &mut HashMap<String, Location>) -> GameResult<()> {
         let mut stmt = self.connection.prepare(
@@ -1428,6 +2212,379 @@ impl DatabaseManager {
         Ok(activities)
     }
 
+    /// Insert a text into the Crystalline Archives catalog
+    pub fn insert_archive_text(
+        &self,
+        id: &str,
+        title: &str,
+        topics: &[&str],
+        theory_id: Option<&str>,
+        difficulty: i32,
+        content: &str,
+    ) -> GameResult<()> {
+        let topics_json = serde_json::to_string(topics)
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to serialize topics: {}", e)))?;
+
+        self.connection.execute(
+            "INSERT OR REPLACE INTO archive_texts
+             (id, title, topics, theory_id, difficulty, content)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![id, title, topics_json, theory_id, difficulty, content],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert archive text: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Insert or replace a scenery object
+    pub fn insert_scenery(&self, object: &SceneryObject) -> GameResult<()> {
+        let aliases_json = serde_json::to_string(&object.aliases)
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to serialize scenery aliases: {}", e)))?;
+        let interactions_json = serde_json::to_string(&object.interactions)
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to serialize scenery interactions: {}", e)))?;
+
+        self.connection.execute(
+            "INSERT OR REPLACE INTO scenery (id, location_id, name, aliases, interactions) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![object.id, object.location_id, object.name, aliases_json, interactions_json],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert scenery: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Insert or replace how a location adds ambient lines
+    pub fn insert_ambience(&self, location_id: &str, config: &crate::systems::ambience::AmbienceConfig) -> GameResult<()> {
+        let lines_json = serde_json::to_string(&config.lines)
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to serialize ambient lines: {}", e)))?;
+
+        self.connection.execute(
+            "INSERT OR REPLACE INTO ambience (location_id, chance, lines, general) VALUES (?1, ?2, ?3, ?4)",
+            params![location_id, config.chance, lines_json, config.general],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert ambience: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Give each location its ambient lines
+    fn load_ambience(&self, locations: &mut HashMap<String, Location>) -> GameResult<()> {
+        let mut stmt = self.connection.prepare(
+            "SELECT location_id, chance, lines, general FROM ambience"
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare ambience query: {}", e)))?;
+
+        let rows = stmt.query_map([], |row| {
+            let location_id: String = row.get(0)?;
+            let lines_json: String = row.get(2)?;
+            Ok((location_id, crate::systems::ambience::AmbienceConfig {
+                chance: row.get(1)?,
+                lines: serde_json::from_str(&lines_json).unwrap_or_default(),
+                general: row.get(3)?,
+            }))
+        }).map_err(|e| crate::GameError::DatabaseError(format!("Failed to query ambience: {}", e)))?;
+
+        for row in rows {
+            let (location_id, config) = row
+                .map_err(|e| crate::GameError::DatabaseError(format!("Failed to parse ambience: {}", e)))?;
+            if let Some(location) = locations.get_mut(&location_id) {
+                location.ambience = config;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Load the scenery at a location
+    pub fn load_scenery(&self, location_id: &str) -> GameResult<Vec<SceneryObject>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, location_id, name, aliases, interactions FROM scenery WHERE location_id = ?1 ORDER BY id"
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare scenery query: {}", e)))?;
+
+        let rows = stmt.query_map(params![location_id], |row| {
+            let aliases_json: String = row.get(3)?;
+            let interactions_json: String = row.get(4)?;
+            Ok(SceneryObject {
+                id: row.get(0)?,
+                location_id: row.get(1)?,
+                name: row.get(2)?,
+                aliases: serde_json::from_str(&aliases_json).unwrap_or_default(),
+                interactions: serde_json::from_str(&interactions_json).unwrap_or_default(),
+            })
+        }).map_err(|e| crate::GameError::DatabaseError(format!("Failed to query scenery: {}", e)))?;
+
+        let mut objects = Vec::new();
+        for row in rows {
+            objects.push(row.map_err(|e| crate::GameError::DatabaseError(format!("Failed to parse scenery: {}", e)))?);
+        }
+
+        Ok(objects)
+    }
+
+    /// Search the archives catalog by topic keyword, title or theory
+    pub fn search_archive_texts(&self, topic: &str) -> GameResult<Vec<ArchiveTextData>> {
+        let pattern = format!("%{}%", topic.to_lowercase().replace(' ', "_"));
+        let keyword_pattern = format!("%\"{}\"%", topic.to_lowercase());
+        let title_pattern = format!("%{}%", topic.to_lowercase());
+
+        let mut stmt = self.connection.prepare(
+            "SELECT id, title, topics, theory_id, difficulty, content FROM archive_texts
+             WHERE LOWER(topics) LIKE ?1 OR LOWER(title) LIKE ?2 OR theory_id LIKE ?3
+             ORDER BY difficulty, id"
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare archive query: {}", e)))?;
+
+        let rows = stmt.query_map(params![keyword_pattern, title_pattern, pattern], |row| {
+            let topics_json: String = row.get(2)?;
+            Ok(ArchiveTextData {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                topics: serde_json::from_str(&topics_json).unwrap_or_default(),
+                theory_id: row.get(3)?,
+                difficulty: row.get(4)?,
+                content: row.get(5)?,
+            })
+        }).map_err(|e| crate::GameError::DatabaseError(format!("Failed to query archive texts: {}", e)))?;
+
+        let mut texts = Vec::new();
+        for row in rows {
+            texts.push(row.map_err(|e| crate::GameError::DatabaseError(format!("Failed to parse archive text: {}", e)))?);
+        }
+
+        Ok(texts)
+    }
+
+    /// Insert or replace an enemy definition
+    pub fn insert_enemy(&self, enemy: &crate::systems::combat::Enemy) -> GameResult<()> {
+        let resistances_json = serde_json::to_string(&enemy.magical_resistance)
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to serialize resistances: {}", e)))?;
+        let loot_json = serde_json::to_string(&enemy.loot_table)
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to serialize loot table: {}", e)))?;
+        let habitats_json = serde_json::to_string(&enemy.habitats)
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to serialize habitats: {}", e)))?;
+        let abilities_json = serde_json::to_string(&enemy.status_abilities)
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to serialize status abilities: {}", e)))?;
+        let phases_json = serde_json::to_string(&enemy.phases)
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to serialize phases: {}", e)))?;
+        let tier = format!("{:?}", enemy.difficulty_tier).to_lowercase();
+        let faction = enemy.faction_affiliation.map(|f| f.key().to_string());
+
+        self.connection.execute(
+            "INSERT OR REPLACE INTO enemies
+             (id, name, description, difficulty_tier, resistances, vulnerable_frequency,
+              faction_id, ai_profile, loot_table, habitats, status_abilities, phases)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                enemy.id, enemy.name, enemy.description, tier, resistances_json,
+                enemy.vulnerable_frequency, faction, enemy.ai_profile.name(), loot_json, habitats_json,
+                abilities_json, phases_json
+            ],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert enemy: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Load all enemy definitions
+    pub fn load_enemies(&self) -> GameResult<Vec<crate::systems::combat::Enemy>> {
+        use crate::systems::combat::{AiProfile, DifficultyTier, Enemy};
+
+        let mut stmt = self.connection.prepare(
+            "SELECT id, name, description, difficulty_tier, resistances, vulnerable_frequency,
+                    faction_id, ai_profile, loot_table, habitats, status_abilities, phases
+             FROM enemies ORDER BY id"
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare enemy query: {}", e)))?;
+
+        let rows = stmt.query_map([], |row| {
+            let tier: String = row.get(3)?;
+            let resistances_json: String = row.get(4)?;
+            let faction_key: Option<String> = row.get(6)?;
+            let ai_profile: String = row.get(7)?;
+            let loot_json: String = row.get(8)?;
+            let habitats_json: String = row.get(9)?;
+            let abilities_json: String = row.get(10)?;
+            let phases_json: String = row.get(11)?;
+
+            let mut enemy = Enemy::new(
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                DifficultyTier::from_string(&tier).unwrap_or(DifficultyTier::Beginner),
+            );
+            enemy.magical_resistance = serde_json::from_str(&resistances_json).unwrap_or_default();
+            enemy.vulnerable_frequency = row.get(5)?;
+            enemy.faction_affiliation = faction_key.and_then(|key| {
+                FactionId::all().into_iter().find(|faction| faction.key() == key)
+            });
+            enemy.ai_profile = AiProfile::from_string(&ai_profile).unwrap_or_default();
+            enemy.loot_table = serde_json::from_str(&loot_json).unwrap_or_default();
+            enemy.habitats = serde_json::from_str(&habitats_json).unwrap_or_default();
+            enemy.status_abilities = serde_json::from_str(&abilities_json).unwrap_or_default();
+            let phases: Vec<crate::systems::combat::EnemyPhase> = serde_json::from_str(&phases_json).unwrap_or_default();
+            for phase in phases {
+                enemy = enemy.with_phase(phase);
+            }
+            Ok(enemy)
+        }).map_err(|e| crate::GameError::DatabaseError(format!("Failed to query enemies: {}", e)))?;
+
+        let mut enemies = Vec::new();
+        for row in rows {
+            enemies.push(row.map_err(|e| crate::GameError::DatabaseError(format!("Failed to parse enemy: {}", e)))?);
+        }
+
+        Ok(enemies)
+    }
+
+    /// Insert or replace a crafting recipe
+    pub fn insert_recipe(&self, recipe: &crate::systems::items::crafting::Recipe) -> GameResult<()> {
+        let to_json = |value: serde_json::Result<String>, what: &str| {
+            value.map_err(|e| crate::GameError::DatabaseError(format!("Failed to serialize recipe {}: {}", what, e)))
+        };
+        let output_json = to_json(serde_json::to_string(&recipe.output), "output")?;
+        let ingredients_json = to_json(serde_json::to_string(&recipe.ingredients), "ingredients")?;
+        let tools_json = to_json(serde_json::to_string(&recipe.tools), "tools")?;
+        let station_json = recipe.station.as_ref()
+            .map(|station| to_json(serde_json::to_string(station), "station"))
+            .transpose()?;
+
+        self.connection.execute(
+            "INSERT OR REPLACE INTO recipes
+             (id, name, description, output, ingredients, tools, station, theory_id,
+              min_understanding, base_chance, minutes)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                recipe.id, recipe.name, recipe.description, output_json, ingredients_json, tools_json,
+                station_json, recipe.theory, recipe.min_understanding, recipe.base_chance, recipe.minutes
+            ],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert recipe: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Load all crafting recipes
+    pub fn load_recipes(&self) -> GameResult<Vec<crate::systems::items::crafting::Recipe>> {
+        use crate::systems::items::crafting::Recipe;
+
+        let mut stmt = self.connection.prepare(
+            "SELECT id, name, description, output, ingredients, tools, station, theory_id,
+                    min_understanding, base_chance, minutes
+             FROM recipes ORDER BY name"
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare recipe query: {}", e)))?;
+
+        let rows = stmt.query_map([], |row| {
+            let output_json: String = row.get(3)?;
+            let ingredients_json: String = row.get(4)?;
+            let tools_json: String = row.get(5)?;
+            let station_json: Option<String> = row.get(6)?;
+            Ok((
+                row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?,
+                output_json, ingredients_json, tools_json, station_json,
+                row.get::<_, Option<String>>(7)?, row.get::<_, f32>(8)?, row.get::<_, f32>(9)?, row.get::<_, i32>(10)?,
+            ))
+        }).map_err(|e| crate::GameError::DatabaseError(format!("Failed to query recipes: {}", e)))?;
+
+        let mut recipes = Vec::new();
+        for row in rows {
+            let (id, name, description, output_json, ingredients_json, tools_json, station_json, theory, min_understanding, base_chance, minutes) =
+                row.map_err(|e| crate::GameError::DatabaseError(format!("Failed to parse recipe: {}", e)))?;
+            let output = serde_json::from_str(&output_json)
+                .map_err(|e| crate::GameError::DatabaseError(format!("Invalid output for recipe {}: {}", id, e)))?;
+            recipes.push(Recipe {
+                output,
+                ingredients: serde_json::from_str(&ingredients_json).unwrap_or_default(),
+                tools: serde_json::from_str(&tools_json).unwrap_or_default(),
+                station: station_json.and_then(|json| serde_json::from_str(&json).ok()),
+                id,
+                name,
+                description,
+                theory,
+                min_understanding,
+                base_chance,
+                minutes,
+            });
+        }
+
+        Ok(recipes)
+    }
+
+    /// Insert or replace an item definition
+    pub fn insert_item_definition(&self, item: &crate::systems::placed_items::ItemDefinition) -> GameResult<()> {
+        let item_type_json = serde_json::to_string(&item.item_type)
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to serialize item type: {}", e)))?;
+        let properties_json = serde_json::to_string(&item.custom_properties)
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to serialize item properties: {}", e)))?;
+
+        self.connection.execute(
+            "INSERT OR REPLACE INTO items (id, name, description, item_type, properties) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![item.id, item.name, item.description, item_type_json, properties_json],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert item: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Load an item definition by ID
+    pub fn load_item_definition(&self, id: &str) -> GameResult<Option<crate::systems::placed_items::ItemDefinition>> {
+        use crate::systems::placed_items::ItemDefinition;
+
+        let row = self.connection.query_row(
+            "SELECT id, name, description, item_type, properties FROM items WHERE id = ?1",
+            params![id],
+            |row| Ok((
+                row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?, row.get::<_, Option<String>>(4)?,
+            )),
+        ).optional().map_err(|e| crate::GameError::DatabaseError(format!("Failed to query item: {}", e)))?;
+
+        let Some((id, name, description, item_type_json, properties_json)) = row else {
+            return Ok(None);
+        };
+        let item_type = serde_json::from_str(&item_type_json)
+            .map_err(|e| crate::GameError::DatabaseError(format!("Invalid type for item {}: {}", id, e)))?;
+        Ok(Some(ItemDefinition {
+            custom_properties: properties_json.and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default(),
+            id,
+            name,
+            description,
+            item_type,
+        }))
+    }
+
+    /// Stock an item at a location
+    pub fn insert_item_placement(&self, placement: &crate::systems::placed_items::Placement) -> GameResult<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO location_items (location_id, item_id, respawn_minutes) VALUES (?1, ?2, ?3)",
+            params![placement.location, placement.item_id, placement.respawn_minutes],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert item placement: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Load how an item is stocked at a location
+    pub fn load_item_placement(&self, location: &str, item_id: &str) -> GameResult<Option<crate::systems::placed_items::Placement>> {
+        self.connection.query_row(
+            "SELECT respawn_minutes FROM location_items WHERE location_id = ?1 AND item_id = ?2",
+            params![location, item_id],
+            |row| Ok(crate::systems::placed_items::Placement {
+                location: location.to_string(),
+                item_id: item_id.to_string(),
+                respawn_minutes: row.get(0)?,
+            }),
+        ).optional().map_err(|e| crate::GameError::DatabaseError(format!("Failed to query item placement: {}", e)).into())
+    }
+
+    /// Stock every location with its placed items
+    fn load_location_items(&self, locations: &mut HashMap<String, Location>) -> GameResult<()> {
+        let mut stmt = self.connection.prepare(
+            "SELECT location_id, item_id FROM location_items ORDER BY location_id, item_id"
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare location items query: {}", e)))?;
+
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to query location items: {}", e)))?;
+
+        for row in rows {
+            let (location_id, item_id) = row
+                .map_err(|e| crate::GameError::DatabaseError(format!("Failed to parse location item: {}", e)))?;
+            if let Some(location) = locations.get_mut(&location_id) {
+                location.items.push(item_id);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Insert an NPC into the database
     pub fn insert_npc(
         &self,
@@ -1448,10 +2605,156 @@ impl DatabaseManager {
         Ok(())
     }
 
+    /// Insert a schedule entry for an NPC
+    pub fn insert_npc_schedule(&self, npc_id: &str, entry: &crate::systems::dialogue::ScheduleEntry) -> GameResult<()> {
+        self.connection.execute(
+            "INSERT INTO npc_schedules
+             (npc_id, start_hour, end_hour, activity, location_id, available, quest_id, flag_condition, weekday)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![npc_id, entry.start_hour, entry.end_hour, entry.activity, entry.location, entry.available, entry.quest_id, entry.flag,
+                entry.weekday.map(|weekday| weekday.name())],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert NPC schedule: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Insert a text shown for a location or NPC while a world flag condition holds
+    pub fn insert_flag_text(&self, subject_id: &str, text: &crate::systems::world_flags::FlaggedText) -> GameResult<()> {
+        self.connection.execute(
+            "INSERT INTO flag_texts (subject_id, flag_condition, text) VALUES (?1, ?2, ?3)",
+            params![subject_id, text.condition, text.text],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert flag text: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Load all flag texts, grouped by location or NPC ID
+    fn load_flag_texts(&self) -> GameResult<HashMap<String, Vec<crate::systems::world_flags::FlaggedText>>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT subject_id, flag_condition, text FROM flag_texts ORDER BY rowid"
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare flag text query: {}", e)))?;
+
+        let rows = stmt.query_map([], |row| {
+            let subject_id: String = row.get(0)?;
+            Ok((subject_id, crate::systems::world_flags::FlaggedText {
+                condition: row.get(1)?,
+                text: row.get(2)?,
+            }))
+        }).map_err(|e| crate::GameError::DatabaseError(format!("Failed to query flag texts: {}", e)))?;
+
+        let mut texts: HashMap<String, Vec<_>> = HashMap::new();
+        for row in rows {
+            let (subject_id, text) = row
+                .map_err(|e| crate::GameError::DatabaseError(format!("Failed to parse flag text: {}", e)))?;
+            texts.entry(subject_id).or_default().push(text);
+        }
+
+        Ok(texts)
+    }
+
+    /// Insert or replace the translation of a dialogue line for a locale
+    pub fn insert_localized_text(&self, locale: &str, key: &str, text: &str) -> GameResult<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO localized_text (locale, key, text) VALUES (?1, ?2, ?3)",
+            params![locale, key, text],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert localized text: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Load the translated dialogue lines for a locale, by localization key
+    pub fn load_localization(&self, locale: &str) -> GameResult<crate::systems::text_templates::Localization> {
+        let mut stmt = self.connection.prepare("SELECT key, text FROM localized_text WHERE locale = ?1")
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare localized text query: {}", e)))?;
+
+        let strings = stmt.query_map(params![locale], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to query localized text: {}", e)))?
+            .collect::<Result<HashMap<_, _>, _>>()
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to parse localized text: {}", e)))?;
+
+        Ok(crate::systems::text_templates::Localization::new(locale, strings))
+    }
+
+    /// Insert or replace a verb standing for a command the parser knows
+    pub fn insert_verb_synonym(&self, word: &str, meaning: &str) -> GameResult<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO verb_synonyms (word, meaning) VALUES (?1, ?2)",
+            params![word, meaning],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to insert verb synonym: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Load the verbs content packs added, and the commands they stand for
+    pub fn load_verb_synonyms(&self) -> GameResult<Vec<(String, String)>> {
+        let mut stmt = self.connection.prepare("SELECT word, meaning FROM verb_synonyms ORDER BY word")
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare verb synonym query: {}", e)))?;
+
+        let verbs = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to query verb synonyms: {}", e)))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to parse verb synonyms: {}", e)))?;
+
+        Ok(verbs)
+    }
+
+    /// Load the world flags each NPC's topics set, grouped by NPC ID
+    fn load_topic_flags(&self) -> GameResult<HashMap<String, HashMap<String, String>>> {
+        let mut stmt = self.connection.prepare("SELECT npc_id, topic, flag FROM npc_topic_flags")
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare topic flag query: {}", e)))?;
+
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to query topic flags: {}", e)))?;
+
+        let mut flags: HashMap<String, HashMap<String, String>> = HashMap::new();
+        for row in rows {
+            let (npc_id, topic, flag) = row
+                .map_err(|e| crate::GameError::DatabaseError(format!("Failed to parse topic flag: {}", e)))?;
+            flags.entry(npc_id).or_default().insert(topic, flag);
+        }
+
+        Ok(flags)
+    }
+
+    /// Load all NPC schedules, grouped by NPC ID
+    fn load_npc_schedules(&self) -> GameResult<HashMap<String, Vec<crate::systems::dialogue::ScheduleEntry>>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT npc_id, start_hour, end_hour, activity, location_id, available, quest_id, flag_condition, weekday
+             FROM npc_schedules ORDER BY npc_id, start_hour"
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare NPC schedule query: {}", e)))?;
+
+        let rows = stmt.query_map([], |row| {
+            let npc_id: String = row.get(0)?;
+            Ok((npc_id, crate::systems::dialogue::ScheduleEntry {
+                start_hour: row.get(1)?,
+                end_hour: row.get(2)?,
+                activity: row.get(3)?,
+                location: row.get(4)?,
+                available: row.get(5)?,
+                quest_id: row.get(6)?,
+                flag: row.get(7)?,
+                weekday: row.get::<_, Option<String>>(8)?.and_then(|name| crate::core::calendar::Weekday::from_name(&name)),
+            }))
+        }).map_err(|e| crate::GameError::DatabaseError(format!("Failed to query NPC schedules: {}", e)))?;
+
+        let mut schedules: HashMap<String, Vec<_>> = HashMap::new();
+        for row in rows {
+            let (npc_id, entry) = row
+                .map_err(|e| crate::GameError::DatabaseError(format!("Failed to parse NPC schedule: {}", e)))?;
+            schedules.entry(npc_id).or_default().push(entry);
+        }
+
+        Ok(schedules)
+    }
+
     /// Load all NPCs from the database
     pub fn load_npcs(&self) -> GameResult<Vec<crate::systems::dialogue::NPC>> {
+        let mut schedules = self.load_npc_schedules()?;
+        let mut flag_texts = self.load_flag_texts()?;
+        let mut topic_flags = self.load_topic_flags()?;
+
         let mut stmt = self.connection.prepare(
-            "SELECT id, name, description, faction_id, dialogue_tree FROM npcs"
+            "SELECT id, name, description, faction_id, dialogue_tree, current_location FROM npcs"
         ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare NPC query: {}", e)))?;
 
         let npc_rows = stmt.query_map([], |row| {
@@ -1470,8 +2773,18 @@ impl DatabaseManager {
                 serde_json::from_str(&dialogue_tree_json)
                     .map_err(|_| rusqlite::Error::InvalidColumnType(4, "Invalid JSON".to_string(), rusqlite::types::Type::Text))?;
 
+            let id: String = row.get(0)?;
+            let availability = crate::systems::dialogue::NPCAvailability {
+                home_location: row.get(5)?,
+                schedule: schedules.remove(&id).unwrap_or_default(),
+                busy: None,
+                departed: None,
+            };
+            let flag_lines = flag_texts.remove(&id).unwrap_or_default();
+            let topic_flags = topic_flags.remove(&id).unwrap_or_default();
+
             Ok(crate::systems::dialogue::NPC {
-                id: row.get(0)?,
+                id,
                 name: row.get(1)?,
                 description: row.get(2)?,
                 faction_affiliation: faction_id,
@@ -1479,6 +2792,9 @@ impl DatabaseManager {
                 current_disposition: 0, // Default neutral disposition
                 personality: None, // Will be populated from quest content
                 quest_dialogue: std::collections::HashMap::new(), // Will be populated from quest content
+                availability,
+                flag_lines,
+                topic_flags,
             })
         }).map_err(|e| crate::GameError::DatabaseError(format!("Failed to query NPCs: {}", e)))?;
 
@@ -1627,6 +2943,13 @@ impl DatabaseManager {
                 involved_npcs,
                 locations,
                 estimated_duration,
+                deadline: None, // Not stored in database yet
+                failure_triggers: vec![],
+                fallout: None,
+                cleans_up: None,
+                repeat: None,
+                sets_flags: vec![],
+                changes_locations: vec![],
             }))
         }).map_err(|e| crate::GameError::DatabaseError(format!("Failed to query quest definitions: {}", e)))?;
 
@@ -1864,4 +3187,155 @@ mod tests {
         assert!(room1.exits.contains_key(&Direction::North));
         assert_eq!(room1.exits[&Direction::North], "room2");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_archive_search() {
+        let (db, _temp_file) = create_test_db();
+
+        db.insert_archive_text("waves", "On Waves", &["resonance", "waves"], None, 1, "Text").unwrap();
+        db.insert_archive_text("crystals", "Crystal Growth", &["crystal"], None, 2, "Text").unwrap();
+
+        let results = db.search_archive_texts("resonance").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "waves");
+
+        assert_eq!(db.search_archive_texts("growth").unwrap().len(), 1);
+        assert!(db.search_archive_texts("politics").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_default_recipes_roundtrip() {
+        use crate::systems::items::crafting::RecipeOutput;
+
+        let (db, _temp_file) = create_test_db();
+        db.load_default_recipes().unwrap();
+
+        let recipes = db.load_recipes().unwrap();
+        assert_eq!(recipes.len(), 6);
+        let tonic = recipes.iter().find(|recipe| recipe.id == "focus_tonic").unwrap();
+        assert!(matches!(tonic.output, RecipeOutput::Consumable { uses: 1, .. }));
+        assert_eq!(tonic.station.as_ref().unwrap().locations, vec!["crystal_garden_lab".to_string()]);
+        assert_eq!(tonic.theory.as_deref(), Some("mental_resonance"));
+        let mortar = recipes.iter().find(|recipe| recipe.id == "mortar_and_pestle").unwrap();
+        assert!(mortar.station.is_none() && mortar.tools.is_empty());
+    }
+
+    #[test]
+    fn test_placed_items_roundtrip() {
+        let (db, _temp_file) = create_test_db();
+        db.load_default_content().unwrap();
+
+        let locations = db.load_locations().unwrap();
+        let garden = &locations["crystal_garden_lab"];
+        assert_eq!(garden.items, vec!["murky_vial".to_string(), "silverleaf".to_string(), "spring_water".to_string()]);
+
+        let water = db.load_item_placement("crystal_garden_lab", "spring_water").unwrap().unwrap();
+        assert_eq!(water.respawn_minutes, Some(120));
+        assert_eq!(db.load_item_placement("tutorial_chamber", "worn_primer").unwrap().unwrap().respawn_minutes, None);
+        assert!(db.load_item_placement("tutorial_chamber", "spring_water").unwrap().is_none());
+
+        let primer = db.load_item_definition("worn_primer").unwrap().unwrap().instantiate();
+        assert_eq!(primer.properties.name, "worn primer");
+        assert!(matches!(primer.item_type, crate::systems::items::ItemType::Book { .. }));
+        assert!(db.load_item_definition("unobtainium").unwrap().is_none());
+
+        let mut artifact = db.load_item_definition("strange_artifact").unwrap().unwrap().instantiate();
+        assert_eq!(artifact.properties.name, "strange artifact");
+        assert!(!crate::systems::items::identification::is_identified(&artifact));
+        crate::systems::items::identification::identify(
+            &mut artifact, crate::systems::items::identification::AppraisalMethod::Tool,
+        );
+        assert_eq!(artifact.properties.name, "Harmonic Keystone");
+    }
+
+    #[test]
+    fn test_enemy_roundtrip() {
+        use crate::systems::combat::{AiProfile, DifficultyTier, Enemy};
+        use crate::systems::status_effects::StatusKind;
+
+        let (db, _temp_file) = create_test_db();
+        let enemy = Enemy::new("hound".to_string(), "Resonance Hound".to_string(), "Snarling.".to_string(), DifficultyTier::Advanced)
+            .with_resistance("light", 0.4)
+            .with_vulnerable_frequency(2)
+            .with_faction(FactionId::IndustrialConsortium)
+            .with_loot("rare_crystal", 0.25, (1, 1))
+            .with_ai_profile(AiProfile::Aggressive)
+            .with_status_ability(StatusKind::ResonanceBurn, 0.5, 2)
+            .with_habitat("practice_hall");
+        db.insert_enemy(&enemy).unwrap();
+
+        let loaded = db.load_enemies().unwrap();
+        assert_eq!(loaded.len(), 1);
+        let hound = &loaded[0];
+        assert_eq!(hound.difficulty_tier, DifficultyTier::Advanced);
+        assert_eq!(hound.magical_resistance.get("light"), Some(&0.4));
+        assert_eq!(hound.vulnerable_frequency, Some(2));
+        assert_eq!(hound.faction_affiliation, Some(FactionId::IndustrialConsortium));
+        assert_eq!(hound.ai_profile, AiProfile::Aggressive);
+        assert_eq!(hound.loot_table.len(), 1);
+        assert_eq!(hound.habitats, vec!["practice_hall".to_string()]);
+        assert_eq!(hound.status_abilities.len(), 1);
+        assert_eq!(hound.status_abilities[0].kind, StatusKind::ResonanceBurn);
+    }
+
+    #[test]
+    fn test_boss_phases_loaded_from_data() {
+        use crate::systems::status_effects::StatusKind;
+
+        let (db, _temp_file) = create_test_db();
+        db.load_default_content().unwrap();
+
+        let enemies = db.load_enemies().unwrap();
+        let boss = enemies.iter().find(|enemy| enemy.id == "rift_heart").unwrap();
+        assert_eq!(boss.habitats, vec!["unstable_resonance_site".to_string()]);
+        let names: Vec<&str> = boss.phases.iter().map(|phase| phase.name.as_str()).collect();
+        assert_eq!(names, vec!["Fracturing", "Collapse"]);
+        assert!(boss.phases[1].cleanse);
+        assert_eq!(boss.phases[1].status_abilities.as_ref().unwrap()[0].kind, StatusKind::Stun);
+    }
+
+    #[test]
+    fn test_npc_schedules_loaded_with_npcs() {
+        let (db, _temp_file) = create_test_db();
+        db.load_default_content().unwrap();
+        // Reloading content must not duplicate routines
+        db.load_default_content().unwrap();
+
+        let npcs = db.load_npcs().unwrap();
+        let gareth = npcs.iter().find(|npc| npc.id == "warden_gareth").unwrap();
+        assert_eq!(gareth.availability.home_location.as_deref(), Some("harmonic_testing_chambers"));
+        assert_eq!(gareth.availability.schedule.iter().filter(|entry| entry.weekday.is_none()).count(), 2);
+        assert_eq!(gareth.availability.schedule[0].start_hour, 8);
+        // Faction meetings are kept on their weekday
+        assert!(gareth.availability.schedule.iter()
+            .any(|entry| entry.weekday == Some(crate::core::calendar::Weekday::Monday) && entry.location.as_deref() == Some("faction_diplomacy_hall")));
+
+        let felix = npcs.iter().find(|npc| npc.id == "dr_felix").unwrap();
+        assert!(felix.availability.schedule.iter()
+            .any(|entry| entry.quest_id.as_deref() == Some("healing_research") && entry.available));
+        let cordelia = npcs.iter().find(|npc| npc.id == "ambassador_cordelia").unwrap();
+        assert!(cordelia.availability.schedule.iter()
+            .any(|entry| entry.flag.as_deref() == Some("accord_signed")
+                && entry.location.as_deref() == Some("resonance_observatory")));
+
+        // Marcus's schematics sit behind a theory check
+        let marcus = npcs.iter().find(|npc| npc.id == "technician_marcus").unwrap();
+        let responses = &marcus.dialogue_tree.topics["crystal_engineering"].responses;
+        assert_eq!(responses.len(), 3);
+        assert!(matches!(&responses[0].check,
+            Some(crate::systems::dialogue::DialogueCheck::CiteTheory { theory_id, min_understanding })
+                if theory_id == "crystal_structures" && *min_understanding == 0.6));
+        assert!(db.load_item_definition("detection_array_schematics").unwrap().is_some());
+        assert_eq!(
+            marcus.dialogue_tree.topics["consortium_contracts"].requirements.min_relationship,
+            Some(crate::systems::relationships::RelationshipStage::Friend)
+        );
+        assert_eq!(marcus.dialogue_tree.relationship_greetings.len(), 4);
+
+        // Lyra's doubts about the arrays open up over two branches
+        let lyra = npcs.iter().find(|npc| npc.id == "observer_lyra").unwrap();
+        assert_eq!(lyra.dialogue_tree.topics["surveillance_ethics"].responses[0].next.as_deref(), Some("lyra_doubts"));
+        assert_eq!(lyra.dialogue_tree.branches["lyra_doubts"].responses[0].next.as_deref(), Some("lyra_unlogged"));
+        assert!(lyra.dialogue_tree.branches.contains_key("lyra_unlogged"));
+    }
+}
@@ -0,0 +1,330 @@
+//! Write-ahead journal of the session's commands
+//!
+//! Progress made since the last save would be lost if the game stopped
+//! unexpectedly, so every command is written to a journal in the save
+//! directory before it runs, followed by what it changed once it has run.
+//! Saving or loading starts the journal afresh from that slot, and a session
+//! that ends normally removes it. A journal still holding commands at the
+//! next start belongs to a session that didn't end normally: loading its base
+//! save and replaying the commands brings the game back to where it stopped,
+//! and what each replayed command changed is checked against the journal. A
+//! journal that can't be read is kept beside it rather than written over.
+//!
+//! The journal is a file of JSON lines, appended to and flushed as it goes:
+//!
+//! ```text
+//! {"record":"base","slot":"quicksave","started":"2026-03-02T19:04:11Z"}
+//! {"record":"command","input":"go north"}
+//! {"record":"done","delta":{"moved_to":"crystal_gardens","minutes":5}}
+//! ```
+//!
+//! A command with no `done` after it was running when the game stopped, and
+//! is left out of a recovery in case it was what brought the game down.
+
+use crate::core::{Player, WorldState};
+use crate::GameResult;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// File, inside the save directory, holding the journal
+pub const JOURNAL_FILE: &str = "session.journal";
+/// Where a journal that couldn't be read is kept, in case it's wanted
+pub const BAD_JOURNAL_FILE: &str = "session.journal.bad";
+
+/// What a command changed, recorded so a replay can be checked against it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StateDelta {
+    /// Where the player went, if they moved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moved_to: Option<String>,
+    /// Game minutes that passed
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub minutes: i32,
+    /// Change in mental energy
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub energy: i32,
+    /// Change in silver carried
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub silver: i32,
+    /// Change in the number of items carried
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub items: i32,
+}
+
+impl StateDelta {
+    /// How this delta differs from the one recorded for the same command,
+    /// one description per difference
+    pub fn differences(&self, recorded: &StateDelta) -> Vec<String> {
+        let mut differences = Vec::new();
+        if self.moved_to != recorded.moved_to {
+            let place = |moved_to: &Option<String>| moved_to.clone().unwrap_or_else(|| "nowhere".to_string());
+            differences.push(format!("moved to {} instead of {}", place(&self.moved_to), place(&recorded.moved_to)));
+        }
+        for (name, now, then) in [
+            ("minutes", self.minutes, recorded.minutes),
+            ("energy", self.energy, recorded.energy),
+            ("silver", self.silver, recorded.silver),
+            ("items", self.items, recorded.items),
+        ] {
+            if now != then {
+                differences.push(format!("{} {:+} instead of {:+}", name, now, then));
+            }
+        }
+        differences
+    }
+}
+
+fn is_zero(value: &i32) -> bool {
+    *value == 0
+}
+
+/// The parts of the game a delta is measured against
+#[derive(Debug, Clone)]
+struct StateMark {
+    location: String,
+    minutes: i32,
+    energy: i32,
+    silver: i32,
+    items: i32,
+}
+
+impl StateMark {
+    fn of(player: &Player, world: &WorldState) -> Self {
+        Self {
+            location: world.current_location.clone(),
+            minutes: world.game_time_minutes,
+            energy: player.mental_state.current_energy,
+            silver: player.inventory.silver,
+            items: player.inventory.items.len() as i32,
+        }
+    }
+
+    fn delta(&self, player: &Player, world: &WorldState) -> StateDelta {
+        let now = Self::of(player, world);
+        StateDelta {
+            moved_to: (now.location != self.location).then_some(now.location),
+            minutes: now.minutes - self.minutes,
+            energy: now.energy - self.energy,
+            silver: now.silver - self.silver,
+            items: now.items - self.items,
+        }
+    }
+}
+
+/// One line of the journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+enum Record {
+    /// The save the commands that follow start from; none for a new game
+    Base { slot: Option<String>, started: DateTime<Utc> },
+    /// A command about to run
+    Command { input: String },
+    /// What the command before it changed
+    Done { delta: StateDelta },
+}
+
+/// A command that ran to completion, and what it changed
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    pub command: String,
+    pub delta: StateDelta,
+}
+
+/// What a journal left behind by an earlier session holds
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recovery {
+    /// The save to start from, or none to start from a new game
+    pub base: Option<String>,
+    /// When the journal was started from the base
+    pub started: DateTime<Utc>,
+    /// The commands run since, oldest first
+    pub entries: Vec<JournalEntry>,
+    /// The command that was running when the session stopped, if any
+    pub interrupted: Option<String>,
+}
+
+impl Recovery {
+    /// Read the journal in `save_directory`, if there is one
+    pub fn read(save_directory: &Path) -> GameResult<Option<Self>> {
+        let path = save_directory.join(JOURNAL_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to read journal: {}", e)))?;
+
+        let mut lines = contents.lines();
+        let Some(Ok(Record::Base { slot, started })) = lines.next().map(serde_json::from_str::<Record>) else {
+            return Err(crate::GameError::SaveLoadError("Journal doesn't say which save it starts from".to_string()).into());
+        };
+        let mut recovery = Self { base: slot, started, entries: Vec::new(), interrupted: None };
+        // A line the session stopped partway through writing ends the journal
+        for record in lines.map_while(|line| serde_json::from_str::<Record>(line).ok()) {
+            match record {
+                Record::Command { input } => {
+                    recovery.interrupted = Some(input);
+                }
+                Record::Done { delta } => {
+                    if let Some(command) = recovery.interrupted.take() {
+                        recovery.entries.push(JournalEntry { command, delta });
+                    }
+                }
+                Record::Base { .. } => break,
+            }
+        }
+        Ok(Some(recovery))
+    }
+
+    /// Move an unreadable journal in `save_directory` out of the way,
+    /// replacing any set aside before it
+    pub fn set_aside(save_directory: &Path) -> GameResult<PathBuf> {
+        let path = save_directory.join(BAD_JOURNAL_FILE);
+        fs::rename(save_directory.join(JOURNAL_FILE), &path)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to set the journal aside: {}", e)))?;
+        Ok(path)
+    }
+
+    /// Remove the journal in `save_directory`
+    pub fn discard(save_directory: &Path) -> GameResult<()> {
+        match fs::remove_file(save_directory.join(JOURNAL_FILE)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(crate::GameError::SaveLoadError(format!("Failed to remove journal: {}", e)).into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The journal of the session being played
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    file: File,
+    /// The game as it was before the command being run
+    pending: Option<StateMark>,
+}
+
+impl Journal {
+    /// Start a journal in `save_directory` from a save, or from a new game,
+    /// replacing any journal already there
+    pub fn start(save_directory: &Path, base: Option<String>) -> GameResult<Self> {
+        let path = save_directory.join(JOURNAL_FILE);
+        let file = OpenOptions::new().create(true).write(true).truncate(true).open(&path)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to start journal: {}", e)))?;
+        let mut journal = Self { path, file, pending: None };
+        journal.append(&Record::Base { slot: base, started: Utc::now() })?;
+        Ok(journal)
+    }
+
+    /// Record a command before it runs
+    pub fn begin(&mut self, input: &str, player: &Player, world: &WorldState) -> GameResult<()> {
+        self.pending = Some(StateMark::of(player, world));
+        self.append(&Record::Command { input: input.to_string() })
+    }
+
+    /// Record what the command begun last changed, returning it
+    pub fn finish(&mut self, player: &Player, world: &WorldState) -> GameResult<StateDelta> {
+        let Some(mark) = self.pending.take() else {
+            return Err(crate::GameError::SaveLoadError("No command is being journaled".to_string()).into());
+        };
+        let delta = mark.delta(player, world);
+        self.append(&Record::Done { delta: delta.clone() })?;
+        Ok(delta)
+    }
+
+    /// Remove the journal once the session has ended normally
+    pub fn close(self) -> GameResult<()> {
+        let directory = self.path.parent().map(Path::to_path_buf).unwrap_or_default();
+        drop(self.file);
+        Recovery::discard(&directory)
+    }
+
+    /// Write a record and make sure it reaches the disk before going on
+    fn append(&mut self, record: &Record) -> GameResult<()> {
+        let line = serde_json::to_string(record)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to write journal: {}", e)))?;
+        writeln!(self.file, "{}", line)
+            .and_then(|_| self.file.sync_data())
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to write journal: {}", e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_completed_commands_are_recovered_and_the_interrupted_one_left_out() {
+        let directory = TempDir::new().unwrap();
+        let mut player = Player::new("Tester".to_string());
+        let mut world = WorldState::new();
+
+        let mut journal = Journal::start(directory.path(), Some("quicksave".to_string())).unwrap();
+        journal.begin("go north", &player, &world).unwrap();
+        world.current_location = "crystal_gardens".to_string();
+        world.game_time_minutes += 5;
+        player.inventory.silver += 3;
+        let delta = journal.finish(&player, &world).unwrap();
+        assert_eq!(delta.moved_to.as_deref(), Some("crystal_gardens"));
+        assert_eq!((delta.minutes, delta.silver, delta.items), (5, 3, 0));
+
+        journal.begin("cast light", &player, &world).unwrap();
+        drop(journal);
+        // The session stopped partway through writing its last line
+        let path = directory.path().join(JOURNAL_FILE);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "{{\"record\":\"do").unwrap();
+
+        let recovery = Recovery::read(directory.path()).unwrap().unwrap();
+        assert_eq!(recovery.base.as_deref(), Some("quicksave"));
+        assert_eq!(recovery.entries, vec![JournalEntry { command: "go north".to_string(), delta }]);
+        assert_eq!(recovery.interrupted.as_deref(), Some("cast light"));
+    }
+
+    #[test]
+    fn test_closing_the_journal_leaves_nothing_to_recover() {
+        let directory = TempDir::new().unwrap();
+        assert_eq!(Recovery::read(directory.path()).unwrap(), None);
+
+        let player = Player::new("Tester".to_string());
+        let world = WorldState::new();
+        let mut journal = Journal::start(directory.path(), None).unwrap();
+        journal.begin("look", &player, &world).unwrap();
+        assert_eq!(journal.finish(&player, &world).unwrap(), StateDelta::default());
+        assert!(journal.finish(&player, &world).is_err());
+
+        let recovery = Recovery::read(directory.path()).unwrap().unwrap();
+        assert_eq!((recovery.base, recovery.entries.len()), (None, 1));
+
+        journal.close().unwrap();
+        assert_eq!(Recovery::read(directory.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_an_unreadable_journal_is_set_aside_intact() {
+        let directory = TempDir::new().unwrap();
+        fs::write(directory.path().join(JOURNAL_FILE), "not a journal").unwrap();
+        assert!(Recovery::read(directory.path()).is_err());
+
+        let path = Recovery::set_aside(directory.path()).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "not a journal");
+        assert_eq!(Recovery::read(directory.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_replayed_deltas_describe_how_they_differ() {
+        let recorded = StateDelta { moved_to: Some("crystal_gardens".to_string()), minutes: 5, ..StateDelta::default() };
+        assert!(recorded.differences(&recorded).is_empty());
+
+        let replayed = StateDelta { minutes: 5, energy: -3, ..StateDelta::default() };
+        assert_eq!(replayed.differences(&recorded), vec![
+            "moved to nowhere instead of crystal_gardens".to_string(),
+            "energy -3 instead of +0".to_string(),
+        ]);
+    }
+}
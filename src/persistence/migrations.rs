@@ -0,0 +1,519 @@
+//! Ordered migrations of the database schema
+//!
+//! Each [`Migration`] takes a database from the version before it to its own
+//! version, and may transform the data already there as well as the tables.
+//! Opening a database runs whichever migrations it hasn't had yet, oldest
+//! first, each in its own transaction recorded in `schema_version`, so a
+//! player's database carries its progress through schema changes and one that
+//! fails partway is left at the last version it reached.
+//!
+//! Databases from before migrations were kept share one baseline step up to
+//! version 17, whose tables are written out here as they stood then. A schema
+//! change adds a migration to the end of [`MIGRATIONS`] with its own SQL;
+//! migrations that have shipped are never edited.
+
+use crate::persistence::database::DatabaseManager;
+use crate::GameResult;
+use rusqlite::{params, OptionalExtension};
+
+/// A step in the schema's history
+pub struct Migration {
+    /// The version a database is at once this has run
+    pub version: i32,
+    /// What changes
+    pub description: &'static str,
+    apply: fn(&DatabaseManager) -> GameResult<()>,
+}
+
+/// Every migration, oldest first
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 17,
+        description: "Baseline: every table, and the columns added to older tables since version 6",
+        apply: baseline,
+    },
+    Migration {
+        version: 18,
+        description: "Dialogue topics require theories that exist",
+        apply: fix_dialogue_theory_requirements,
+    },
+];
+
+/// The version a database is at once every migration has run
+pub fn latest_version() -> i32 {
+    MIGRATIONS.last().map_or(0, |migration| migration.version)
+}
+
+/// The version a database is at, or none for one that's never been set up
+pub fn current_version(db: &DatabaseManager) -> GameResult<Option<i32>> {
+    db.connection().execute(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY
+        )",
+        [],
+    ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create version table: {}", e)))?;
+
+    let version = db.connection()
+        .query_row("SELECT MAX(version) FROM schema_version", [], |row| row.get(0))
+        .optional()
+        .map_err(|e| crate::GameError::DatabaseError(format!("Failed to check version: {}", e)))?;
+    Ok(version.flatten())
+}
+
+/// Run the migrations a database hasn't had, returning the versions reached
+pub fn migrate(db: &DatabaseManager) -> GameResult<Vec<i32>> {
+    let current = current_version(db)?;
+    if current.is_some_and(|version| version > latest_version()) {
+        return Err(crate::GameError::DatabaseError(format!(
+            "The database is at schema version {}, newer than this game knows ({})",
+            current.unwrap_or_default(), latest_version()
+        )).into());
+    }
+
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|migration| current.is_none_or(|version| migration.version > version)) {
+        let transaction = db.connection().unchecked_transaction()?;
+        (migration.apply)(db).map_err(|e| crate::GameError::DatabaseError(format!(
+            "Migration to version {} ({}) failed: {}", migration.version, migration.description, e
+        )))?;
+        transaction.execute("INSERT OR REPLACE INTO schema_version (version) VALUES (?1)", params![migration.version])
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to update schema version: {}", e)))?;
+        transaction.commit()?;
+        applied.push(migration.version);
+    }
+    Ok(applied)
+}
+
+/// The schema as it stood at version 17
+const BASELINE_SCHEMA: &str = "
+    -- Locations table
+    CREATE TABLE IF NOT EXISTS locations (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        ambient_energy REAL DEFAULT 1.0,
+        dominant_frequency INTEGER,
+        interference REAL DEFAULT 0.0,
+        phenomena TEXT, -- JSON array
+        visited BOOLEAN DEFAULT FALSE,
+        restriction TEXT -- JSON, for places a faction keeps to itself
+    );
+
+    -- Location exits (separate table for flexibility)
+    CREATE TABLE IF NOT EXISTS location_exits (
+        location_id TEXT NOT NULL,
+        direction TEXT NOT NULL,
+        destination_id TEXT NOT NULL,
+        access TEXT,
+        FOREIGN KEY(location_id) REFERENCES locations(id),
+        FOREIGN KEY(destination_id) REFERENCES locations(id),
+        PRIMARY KEY(location_id, direction)
+    );
+
+    -- NPCs table
+    CREATE TABLE IF NOT EXISTS npcs (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        faction_id TEXT,
+        dialogue_tree TEXT, -- JSON
+        current_location TEXT,
+        FOREIGN KEY(current_location) REFERENCES locations(id)
+    );
+
+    -- NPC daily routines and quest-driven activities
+    CREATE TABLE IF NOT EXISTS npc_schedules (
+        npc_id TEXT NOT NULL,
+        start_hour INTEGER NOT NULL,
+        end_hour INTEGER NOT NULL,
+        activity TEXT NOT NULL,
+        location_id TEXT, -- NULL means the NPC's home location
+        available BOOLEAN DEFAULT FALSE,
+        quest_id TEXT, -- Entry only applies while this quest is in progress
+        flag_condition TEXT, -- Entry only applies while this world flag condition holds
+        weekday TEXT, -- Entry only applies on this day of the week
+        FOREIGN KEY(npc_id) REFERENCES npcs(id)
+    );
+
+    -- Descriptions and NPC lines that change with world flags
+    CREATE TABLE IF NOT EXISTS flag_texts (
+        subject_id TEXT NOT NULL, -- Location or NPC ID
+        flag_condition TEXT NOT NULL, -- Flag, or !flag for one that must not be set
+        text TEXT NOT NULL
+    );
+
+    -- Translated dialogue lines, looked up by localization key
+    CREATE TABLE IF NOT EXISTS localized_text (
+        locale TEXT NOT NULL,
+        key TEXT NOT NULL,
+        text TEXT NOT NULL,
+        PRIMARY KEY(locale, key)
+    );
+
+    -- Verbs added by content packs, each standing for a command the parser knows
+    CREATE TABLE IF NOT EXISTS verb_synonyms (
+        word TEXT PRIMARY KEY,
+        meaning TEXT NOT NULL
+    );
+
+    -- World flags set by discussing a topic with an NPC
+    CREATE TABLE IF NOT EXISTS npc_topic_flags (
+        npc_id TEXT NOT NULL,
+        topic TEXT NOT NULL,
+        flag TEXT NOT NULL,
+        PRIMARY KEY(npc_id, topic),
+        FOREIGN KEY(npc_id) REFERENCES npcs(id)
+    );
+
+    -- Magic theories table (enhanced for comprehensive learning system)
+    CREATE TABLE IF NOT EXISTS magic_theories (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        tier INTEGER NOT NULL, -- 1=Foundation, 2=Application, 3=Advanced
+        category TEXT NOT NULL, -- Theory category for organization
+        prerequisites TEXT, -- JSON array of theory IDs
+        complexity_level INTEGER NOT NULL,
+        learning_time_base INTEGER NOT NULL,
+        scientific_concepts TEXT, -- JSON array of scientific concepts
+        applications TEXT, -- JSON array of applications
+        available_methods TEXT, -- JSON array of available learning methods
+        method_multipliers TEXT -- JSON object of method efficiency multipliers
+    );
+
+    -- Player theory progress tracking
+    CREATE TABLE IF NOT EXISTS player_theory_progress (
+        player_id TEXT NOT NULL,
+        theory_id TEXT NOT NULL,
+        understanding_level REAL NOT NULL DEFAULT 0.0,
+        experience_points INTEGER NOT NULL DEFAULT 0,
+        learning_history TEXT, -- JSON object of method contributions
+        time_invested INTEGER NOT NULL DEFAULT 0,
+        discovered_at INTEGER NOT NULL,
+        mastered_at INTEGER,
+        is_active_research BOOLEAN DEFAULT FALSE,
+        research_progress REAL DEFAULT 0.0,
+        PRIMARY KEY(player_id, theory_id),
+        FOREIGN KEY(theory_id) REFERENCES magic_theories(id)
+    );
+
+    -- Learning activity log for detailed tracking
+    CREATE TABLE IF NOT EXISTS learning_activities (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        player_id TEXT NOT NULL,
+        theory_id TEXT NOT NULL,
+        method TEXT NOT NULL,
+        duration INTEGER NOT NULL,
+        success_rate REAL NOT NULL,
+        experience_gained INTEGER NOT NULL,
+        understanding_gained REAL NOT NULL,
+        resources_used TEXT, -- JSON object of resources consumed
+        side_effects TEXT, -- JSON array of side effects
+        timestamp INTEGER NOT NULL,
+        FOREIGN KEY(theory_id) REFERENCES magic_theories(id)
+    );
+
+    -- Items table
+    CREATE TABLE IF NOT EXISTS items (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        item_type TEXT NOT NULL,
+        properties TEXT -- JSON for type-specific properties
+    );
+
+    -- Items stocked at locations
+    CREATE TABLE IF NOT EXISTS location_items (
+        location_id TEXT NOT NULL,
+        item_id TEXT NOT NULL,
+        respawn_minutes INTEGER, -- NULL for items that can only be taken once
+        PRIMARY KEY (location_id, item_id),
+        FOREIGN KEY (location_id) REFERENCES locations(id),
+        FOREIGN KEY (item_id) REFERENCES items(id)
+    );
+
+    -- Exits that stay hidden until discovered
+    CREATE TABLE IF NOT EXISTS hidden_exits (
+        location_id TEXT NOT NULL,
+        direction TEXT NOT NULL,
+        destination_id TEXT NOT NULL,
+        feature TEXT NOT NULL, -- What to examine to find it
+        clue TEXT NOT NULL,
+        known_to TEXT NOT NULL DEFAULT '[]', -- JSON array of NPC IDs
+        documents TEXT NOT NULL DEFAULT '[]', -- JSON array of archive text IDs
+        PRIMARY KEY (location_id, direction),
+        FOREIGN KEY (location_id) REFERENCES locations(id),
+        FOREIGN KEY (destination_id) REFERENCES locations(id)
+    );
+
+    -- Fixed objects the player can examine, search or touch
+    CREATE TABLE IF NOT EXISTS scenery (
+        id TEXT PRIMARY KEY,
+        location_id TEXT NOT NULL,
+        name TEXT NOT NULL,
+        aliases TEXT NOT NULL DEFAULT '[]', -- JSON array of other names
+        interactions TEXT NOT NULL, -- JSON array of interactions
+        FOREIGN KEY (location_id) REFERENCES locations(id)
+    );
+
+    -- Ambient lines locations add to their descriptions now and then
+    CREATE TABLE IF NOT EXISTS ambience (
+        location_id TEXT PRIMARY KEY,
+        chance REAL NOT NULL, -- Chance a description gets a line
+        lines TEXT NOT NULL DEFAULT '[]', -- JSON array of conditional lines
+        general INTEGER NOT NULL DEFAULT 1, -- Whether the general lines apply too
+        FOREIGN KEY (location_id) REFERENCES locations(id)
+    );
+
+    -- Faction presence in locations
+    CREATE TABLE IF NOT EXISTS faction_presence (
+        location_id TEXT NOT NULL,
+        faction_id TEXT NOT NULL,
+        influence INTEGER NOT NULL,
+        visibility TEXT NOT NULL,
+        member_count INTEGER DEFAULT 0,
+        FOREIGN KEY(location_id) REFERENCES locations(id),
+        PRIMARY KEY(location_id, faction_id)
+    );
+
+    -- Quest definitions table
+    CREATE TABLE IF NOT EXISTS quest_definitions (
+        id TEXT PRIMARY KEY,
+        title TEXT NOT NULL,
+        description TEXT NOT NULL,
+        category TEXT NOT NULL,
+        difficulty TEXT NOT NULL,
+        requirements TEXT NOT NULL, -- JSON
+        objectives TEXT NOT NULL, -- JSON array
+        rewards TEXT NOT NULL, -- JSON
+        faction_effects TEXT, -- JSON
+        educational_focus TEXT, -- JSON
+        branching_paths TEXT, -- JSON
+        involved_npcs TEXT, -- JSON array
+        locations TEXT, -- JSON array
+        estimated_duration INTEGER NOT NULL,
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+
+    -- Player quest progress tracking
+    CREATE TABLE IF NOT EXISTS player_quest_progress (
+        player_id TEXT NOT NULL,
+        quest_id TEXT NOT NULL,
+        status TEXT NOT NULL,
+        started_at INTEGER NOT NULL,
+        completed_at INTEGER,
+        objective_progress TEXT NOT NULL, -- JSON
+        chosen_branch TEXT,
+        player_choices TEXT, -- JSON
+        time_invested INTEGER NOT NULL DEFAULT 0,
+        quest_variables TEXT, -- JSON
+        learning_progress TEXT, -- JSON
+        PRIMARY KEY(player_id, quest_id),
+        FOREIGN KEY(quest_id) REFERENCES quest_definitions(id)
+    );
+
+    -- Quest objective completion log for detailed tracking
+    CREATE TABLE IF NOT EXISTS quest_objective_log (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        player_id TEXT NOT NULL,
+        quest_id TEXT NOT NULL,
+        objective_id TEXT NOT NULL,
+        completed_at INTEGER NOT NULL,
+        progress_value REAL NOT NULL,
+        completion_method TEXT,
+        learning_data TEXT, -- JSON
+        FOREIGN KEY(quest_id) REFERENCES quest_definitions(id)
+    );
+
+    -- Quest rewards awarded to players
+    CREATE TABLE IF NOT EXISTS quest_rewards_awarded (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        player_id TEXT NOT NULL,
+        quest_id TEXT NOT NULL,
+        reward_type TEXT NOT NULL,
+        reward_data TEXT NOT NULL, -- JSON
+        awarded_at INTEGER NOT NULL,
+        FOREIGN KEY(quest_id) REFERENCES quest_definitions(id)
+    );
+
+    -- Global quest state and unlocks
+    CREATE TABLE IF NOT EXISTS quest_global_state (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL, -- JSON
+        updated_at INTEGER NOT NULL
+    );
+
+    -- Crystalline Archives catalog of in-game texts
+    CREATE TABLE IF NOT EXISTS archive_texts (
+        id TEXT PRIMARY KEY,
+        title TEXT NOT NULL,
+        topics TEXT NOT NULL, -- JSON array of search keywords
+        theory_id TEXT,
+        difficulty INTEGER NOT NULL DEFAULT 1,
+        content TEXT NOT NULL,
+        FOREIGN KEY(theory_id) REFERENCES magic_theories(id)
+    );
+
+    -- Enemy bestiary definitions
+    CREATE TABLE IF NOT EXISTS enemies (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        difficulty_tier TEXT NOT NULL,
+        resistances TEXT NOT NULL, -- JSON object of spell type to resistance
+        vulnerable_frequency INTEGER,
+        faction_id TEXT,
+        ai_profile TEXT NOT NULL DEFAULT 'balanced',
+        loot_table TEXT NOT NULL, -- JSON array of loot drops
+        habitats TEXT NOT NULL, -- JSON array of location IDs
+        status_abilities TEXT NOT NULL DEFAULT '[]', -- JSON array of inflicted status effects
+        phases TEXT NOT NULL DEFAULT '[]' -- JSON array of scripted boss phases
+    );
+
+    -- Crafting recipes
+    CREATE TABLE IF NOT EXISTS recipes (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        output TEXT NOT NULL, -- JSON description of the item produced
+        ingredients TEXT NOT NULL, -- JSON array of consumed ingredients
+        tools TEXT NOT NULL DEFAULT '[]', -- JSON array of required tool functions
+        station TEXT, -- JSON station and the locations that have one
+        theory_id TEXT,
+        min_understanding REAL NOT NULL DEFAULT 0.0,
+        base_chance REAL NOT NULL,
+        minutes INTEGER NOT NULL
+    );
+
+    -- Indexes for performance
+    CREATE INDEX IF NOT EXISTS idx_location_exits_location ON location_exits(location_id);
+    CREATE INDEX IF NOT EXISTS idx_npcs_location ON npcs(current_location);
+    CREATE INDEX IF NOT EXISTS idx_npcs_faction ON npcs(faction_id);
+    CREATE INDEX IF NOT EXISTS idx_npc_schedules_npc ON npc_schedules(npc_id);
+    CREATE INDEX IF NOT EXISTS idx_flag_texts_subject ON flag_texts(subject_id);
+    CREATE INDEX IF NOT EXISTS idx_faction_presence_location ON faction_presence(location_id);
+    CREATE INDEX IF NOT EXISTS idx_location_items_location ON location_items(location_id);
+    CREATE INDEX IF NOT EXISTS idx_theory_progress_player ON player_theory_progress(player_id);
+    CREATE INDEX IF NOT EXISTS idx_theory_progress_theory ON player_theory_progress(theory_id);
+    CREATE INDEX IF NOT EXISTS idx_learning_activities_player ON learning_activities(player_id);
+    CREATE INDEX IF NOT EXISTS idx_learning_activities_theory ON learning_activities(theory_id);
+    CREATE INDEX IF NOT EXISTS idx_learning_activities_timestamp ON learning_activities(timestamp);
+    CREATE INDEX IF NOT EXISTS idx_theories_tier ON magic_theories(tier);
+    CREATE INDEX IF NOT EXISTS idx_theories_category ON magic_theories(category);
+    CREATE INDEX IF NOT EXISTS idx_quest_definitions_category ON quest_definitions(category);
+    CREATE INDEX IF NOT EXISTS idx_quest_definitions_difficulty ON quest_definitions(difficulty);
+    CREATE INDEX IF NOT EXISTS idx_player_quest_progress_player ON player_quest_progress(player_id);
+    CREATE INDEX IF NOT EXISTS idx_player_quest_progress_status ON player_quest_progress(status);
+    CREATE INDEX IF NOT EXISTS idx_quest_objective_log_player ON quest_objective_log(player_id);
+    CREATE INDEX IF NOT EXISTS idx_quest_objective_log_quest ON quest_objective_log(quest_id);
+    CREATE INDEX IF NOT EXISTS idx_quest_objective_log_completed ON quest_objective_log(completed_at);
+    CREATE INDEX IF NOT EXISTS idx_quest_rewards_player ON quest_rewards_awarded(player_id);
+    CREATE INDEX IF NOT EXISTS idx_quest_rewards_quest ON quest_rewards_awarded(quest_id);
+    CREATE INDEX IF NOT EXISTS idx_archive_texts_theory ON archive_texts(theory_id);
+";
+
+/// Version 17: create any table a database doesn't have yet, and add the
+/// columns later versions gave tables it does
+fn baseline(db: &DatabaseManager) -> GameResult<()> {
+    db.connection().execute_batch(BASELINE_SCHEMA)
+        .map_err(|e| crate::GameError::DatabaseError(format!("Failed to create tables: {}", e)))?;
+    db.add_column_if_missing("enemies", "status_abilities", "TEXT NOT NULL DEFAULT '[]'")?;
+    db.add_column_if_missing("enemies", "phases", "TEXT NOT NULL DEFAULT '[]'")?;
+    db.add_column_if_missing("npc_schedules", "flag_condition", "TEXT")?;
+    db.add_column_if_missing("npc_schedules", "weekday", "TEXT")?;
+    db.add_column_if_missing("location_exits", "access", "TEXT")?;
+    db.add_column_if_missing("locations", "restriction", "TEXT")?;
+    Ok(())
+}
+
+/// Version 18: two topics in the default content named theories that don't
+/// exist, so they could never be discussed
+fn fix_dialogue_theory_requirements(db: &DatabaseManager) -> GameResult<()> {
+    const RENAMED: [(&str, &str, &str, &str); 2] = [
+        ("sage_meridian", "knowledge_theory", "crystalline_archives", "crystal_structures"),
+        ("echo_voidwalker", "forbidden_research", "energy_manipulation", "resonance_amplification"),
+    ];
+
+    for (npc_id, topic, old, new) in RENAMED {
+        let tree: Option<String> = db.connection()
+            .query_row("SELECT dialogue_tree FROM npcs WHERE id = ?1", [npc_id], |row| row.get(0))
+            .optional()
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to read dialogue for {}: {}", npc_id, e)))?
+            .flatten();
+        // An NPC that isn't there, or whose dialogue isn't JSON, is left alone
+        let Some(mut tree) = tree.and_then(|tree| serde_json::from_str::<serde_json::Value>(&tree).ok()) else {
+            continue;
+        };
+
+        let mut changed = false;
+        if let Some(requirements) = tree["topics"][topic]["requirements"]["theory_requirements"].as_array_mut() {
+            for requirement in requirements {
+                if requirement[0] == old {
+                    requirement[0] = serde_json::json!(new);
+                    changed = true;
+                }
+            }
+        }
+        if changed {
+            db.connection().execute("UPDATE npcs SET dialogue_tree = ?1 WHERE id = ?2", params![tree.to_string(), npc_id])
+                .map_err(|e| crate::GameError::DatabaseError(format!("Failed to update dialogue for {}: {}", npc_id, e)))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_migrations_run_in_order_once() {
+        assert!(MIGRATIONS.windows(2).all(|pair| pair[0].version < pair[1].version));
+
+        let file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(file.path().to_str().unwrap()).unwrap();
+        assert_eq!(current_version(&db).unwrap(), None);
+        assert_eq!(migrate(&db).unwrap(), vec![17, 18]);
+        assert_eq!(current_version(&db).unwrap(), Some(latest_version()));
+        assert!(migrate(&db).unwrap().is_empty());
+
+        db.connection().execute("INSERT INTO schema_version (version) VALUES (?1)", [latest_version() + 1]).unwrap();
+        assert!(migrate(&db).is_err());
+    }
+
+    #[test]
+    fn test_an_older_database_keeps_its_data_through_migration() {
+        let file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(file.path().to_str().unwrap()).unwrap();
+        // A version 16 database: no recipes table, enemies without phases, and
+        // dialogue naming a theory that doesn't exist
+        db.connection().execute_batch(
+            "CREATE TABLE schema_version (version INTEGER PRIMARY KEY);
+             INSERT INTO schema_version (version) VALUES (16);
+             CREATE TABLE enemies (
+                 id TEXT PRIMARY KEY, name TEXT NOT NULL, description TEXT NOT NULL,
+                 difficulty_tier TEXT NOT NULL, resistances TEXT NOT NULL, vulnerable_frequency INTEGER,
+                 faction_id TEXT, ai_profile TEXT NOT NULL DEFAULT 'balanced',
+                 loot_table TEXT NOT NULL, habitats TEXT NOT NULL,
+                 status_abilities TEXT NOT NULL DEFAULT '[]'
+             );
+             INSERT INTO enemies (id, name, description, difficulty_tier, resistances, loot_table, habitats)
+                 VALUES ('shard', 'Shard', 'A shard', 'minor', '{}', '[]', '[]');
+             CREATE TABLE npcs (
+                 id TEXT PRIMARY KEY, name TEXT NOT NULL, description TEXT NOT NULL,
+                 faction_id TEXT, dialogue_tree TEXT, current_location TEXT
+             );
+             INSERT INTO npcs (id, name, description, dialogue_tree) VALUES ('sage_meridian', 'Sage', 'A sage',
+                 '{\"topics\":{\"knowledge_theory\":{\"requirements\":{\"theory_requirements\":[[\"crystalline_archives\",0.3]]}}}}');",
+        ).unwrap();
+
+        assert_eq!(migrate(&db).unwrap(), vec![17, 18]);
+        let phases: String = db.connection().query_row("SELECT phases FROM enemies WHERE id = 'shard'", [], |row| row.get(0)).unwrap();
+        assert_eq!(phases, "[]");
+        assert!(db.load_recipes().unwrap().is_empty());
+        let tree: String = db.connection().query_row("SELECT dialogue_tree FROM npcs WHERE id = 'sage_meridian'", [], |row| row.get(0)).unwrap();
+        assert!(tree.contains("crystal_structures") && !tree.contains("crystalline_archives"));
+    }
+}
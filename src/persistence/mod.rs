@@ -2,13 +2,31 @@
 //!
 //! This module provides:
 //! - Database schema and content management
+//! - A pool of connections for queries run in the background
 //! - Save/load system for game state
-//! - Data serialization and migration
+//! - Portable save archives for moving progress between machines
+//! - Data serialization, and ordered migrations of the database schema
+//! - Run summaries for sharing challenge results
+//! - Declarative content files for bulk import/export
+//! - Cross-checks of content for references that lead nowhere
+//! - Scenario files with custom starting conditions
+//! - Crash recovery with emergency saves and crash reports
+//! - A journal of each session's commands, replayed after a crash
 
 pub mod database;
+pub mod query_pool;
+pub mod migrations;
 pub mod save_system;
+pub mod save_archive;
 pub mod serialization;
+pub mod run_summary;
+pub mod content_io;
+pub mod content_validation;
+pub mod scenario;
+pub mod crash_recovery;
+pub mod journal;
 
 pub use database::DatabaseManager;
 pub use save_system::SaveManager;
+pub use run_summary::RunSummary;
 pub use serialization::{GameStateData, serialize_game_state, deserialize_game_state};
\ No newline at end of file
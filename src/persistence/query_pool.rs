@@ -0,0 +1,265 @@
+//! Background queries on a pool of database connections
+//!
+//! [`DatabaseManager`] holds a single blocking connection, used by the game
+//! loop between reading commands. Loads that scan whole tables can run on a
+//! [`QueryPool`] instead: each worker thread owns its own connection to the
+//! same database file, and a query handed to the pool comes back as a
+//! [`PendingQuery`]. That can be awaited from async code, checked without
+//! blocking from the game loop, or waited on when the result is needed.
+//!
+//! The engine starts its content loads as [`Deferred`] ones when it starts up,
+//! loads a save or reloads content, so the tables load side by side while
+//! the main connection (or the save file) is read.
+//!
+//! ```no_run
+//! # use sympathetic_resonance::persistence::DatabaseManager;
+//! # fn main() -> sympathetic_resonance::GameResult<()> {
+//! let database = DatabaseManager::new("content/database.db")?;
+//! let pool = database.query_pool(2)?;
+//! let theories = pool.theories();
+//! // ... other work on the main connection ...
+//! println!("{} theories", theories.wait()?.len());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Pooled connections only read what the main connection has committed, so a
+//! query sees content written before it was handed over.
+
+use crate::persistence::database::{DatabaseManager, TheoryData};
+use crate::systems::quests::QuestDefinition;
+use crate::GameResult;
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tokio::sync::oneshot::{self, error::TryRecvError};
+
+/// How long a pooled connection waits for the main one to finish writing
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Work for a pooled connection
+type Job = Box<dyn FnOnce(&DatabaseManager) + Send>;
+
+/// A learning activity as recorded: theory, method, duration, success rate,
+/// experience, understanding, resources used, side effects and timestamp
+pub type LearningActivityRow = (String, String, i32, f32, i32, f32, HashMap<String, i32>, Vec<String>, i64);
+
+/// Worker threads, each with its own connection, running queries in turn
+pub struct QueryPool {
+    jobs: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl QueryPool {
+    /// Open `workers` connections to the database at `path`
+    pub fn open(path: &str, workers: usize) -> GameResult<Self> {
+        if path.is_empty() || path == ":memory:" {
+            return Err(crate::GameError::DatabaseError(
+                "An in-memory database can't be shared with a query pool".to_string()
+            ).into());
+        }
+
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let mut handles = Vec::new();
+        for _ in 0..workers.max(1) {
+            let database = DatabaseManager::new(path)?;
+            database.connection().busy_timeout(BUSY_TIMEOUT)
+                .map_err(|e| crate::GameError::DatabaseError(format!("Failed to configure pooled connection: {}", e)))?;
+            let receiver = Arc::clone(&receiver);
+            handles.push(std::thread::spawn(move || loop {
+                // The lock is held only while taking the next job
+                let job = receiver.lock().map_err(|_| ()).and_then(|jobs| jobs.recv().map_err(|_| ()));
+                let Ok(job) = job else { break };
+                // A query that panics abandons its result, not the worker
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| job(&database)));
+            }));
+        }
+
+        Ok(Self { jobs: Some(sender), workers: handles })
+    }
+
+    /// Run a query on the next free connection
+    pub fn query<T, F>(&self, query: F) -> PendingQuery<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&DatabaseManager) -> GameResult<T> + Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        if let Some(jobs) = &self.jobs {
+            // If the workers have gone the result is simply never sent
+            let _ = jobs.send(Box::new(move |database: &DatabaseManager| {
+                let _ = sender.send(query(database));
+            }));
+        }
+        PendingQuery { receiver }
+    }
+
+    /// Load every theory
+    pub fn theories(&self) -> PendingQuery<HashMap<String, TheoryData>> {
+        self.query(|database| database.load_theories())
+    }
+
+    /// Load the quests defined in the content database
+    pub fn quest_definitions(&self) -> PendingQuery<HashMap<String, QuestDefinition>> {
+        self.query(|database| database.load_quest_definitions())
+    }
+
+    /// Load a player's learning activities, newest first, for one theory or all
+    pub fn learning_activities(&self, player_id: &str, theory_id: Option<&str>, limit: Option<i32>) -> PendingQuery<Vec<LearningActivityRow>> {
+        let player_id = player_id.to_string();
+        let theory_id = theory_id.map(str::to_string);
+        self.query(move |database| database.load_learning_activities(&player_id, theory_id.as_deref(), limit))
+    }
+
+    /// How many connections the pool has
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+}
+
+impl Drop for QueryPool {
+    /// Let queued queries finish, then close the connections
+    fn drop(&mut self) {
+        self.jobs.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// The result of a query still running on the pool
+pub struct PendingQuery<T> {
+    receiver: oneshot::Receiver<GameResult<T>>,
+}
+
+fn abandoned() -> anyhow::Error {
+    crate::GameError::DatabaseError("The query was abandoned before it finished".to_string()).into()
+}
+
+impl<T> PendingQuery<T> {
+    /// The result, if the query has finished; never blocks
+    pub fn try_take(&mut self) -> Option<GameResult<T>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Closed) => Some(Err(abandoned())),
+        }
+    }
+
+    /// Block until the query finishes. Async code should await it instead.
+    pub fn wait(self) -> GameResult<T> {
+        self.receiver.blocking_recv().unwrap_or_else(|_| Err(abandoned()))
+    }
+}
+
+/// A load started on the pool if there is one, or run on the main
+/// connection when it's finished if not
+pub struct Deferred<T> {
+    pending: Option<PendingQuery<T>>,
+    load: fn(&DatabaseManager) -> GameResult<T>,
+}
+
+impl<T: Send + 'static> Deferred<T> {
+    /// Hand `load` to the pool, if there is one
+    pub fn start(pool: Option<&QueryPool>, load: fn(&DatabaseManager) -> GameResult<T>) -> Self {
+        Self { pending: pool.map(|pool| pool.query(load)), load }
+    }
+
+    /// The loaded result, waiting for the pool or loading it on `database`
+    pub fn finish(self, database: &DatabaseManager) -> GameResult<T> {
+        match self.pending {
+            Some(pending) => pending.wait(),
+            None => (self.load)(database),
+        }
+    }
+}
+
+impl<T> Future for PendingQuery<T> {
+    type Output = GameResult<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.receiver).poll(cx).map(|result| result.unwrap_or_else(|_| Err(abandoned())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn content_database() -> (DatabaseManager, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        let database = DatabaseManager::new(temp_file.path().to_str().unwrap()).unwrap();
+        database.initialize_schema().unwrap();
+        database.load_default_content().unwrap();
+        (database, temp_file)
+    }
+
+    #[test]
+    fn test_pooled_queries_match_the_main_connection() {
+        let (database, _file) = content_database();
+        let pool = database.query_pool(2).unwrap();
+        assert_eq!(pool.size(), 2);
+
+        let theories = pool.theories();
+        let quests = pool.quest_definitions();
+        assert_eq!(theories.wait().unwrap().len(), database.load_theories().unwrap().len());
+        assert_eq!(quests.wait().unwrap().len(), database.load_quest_definitions().unwrap().len());
+
+        // The same query can be awaited from async code
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let activities = runtime.block_on(pool.learning_activities("player", None, Some(10))).unwrap();
+        assert!(activities.is_empty());
+
+        // Errors and panics come back as errors, and the pool carries on
+        let failed = pool.query(|database| database.connection().execute("SELECT * FROM missing_table", [])
+            .map_err(|e| crate::GameError::DatabaseError(e.to_string()).into()));
+        assert!(failed.wait().is_err());
+        let panicked = pool.query(|_| -> GameResult<()> { panic!("query bug") });
+        assert!(panicked.wait().is_err());
+        assert!(pool.theories().wait().is_ok());
+    }
+
+    #[test]
+    fn test_pending_queries_can_be_checked_without_blocking() {
+        let (database, _file) = content_database();
+        assert!(QueryPool::open(":memory:", 1).is_err());
+
+        let pool = database.query_pool(1).unwrap();
+        let (release, gate) = std::sync::mpsc::channel::<()>();
+        let mut pending = pool.query(move |database| {
+            gate.recv().unwrap();
+            database.load_theories()
+        });
+        assert!(pending.try_take().is_none());
+
+        release.send(()).unwrap();
+        let result = loop {
+            if let Some(result) = pending.try_take() {
+                break result;
+            }
+            std::thread::yield_now();
+        };
+        assert!(!result.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_deferred_loads_match_with_or_without_a_pool() {
+        let (database, _file) = content_database();
+        let pool = database.query_pool(1).unwrap();
+
+        let pooled = Deferred::start(Some(&pool), DatabaseManager::load_npcs);
+        let here = Deferred::start(None, DatabaseManager::load_npcs);
+        let expected = database.load_npcs().unwrap().len();
+        assert!(expected > 0);
+        assert_eq!(pooled.finish(&database).unwrap().len(), expected);
+        assert_eq!(here.finish(&database).unwrap().len(), expected);
+    }
+}
@@ -0,0 +1,239 @@
+//! Exportable run summaries for community challenges
+//!
+//! A run summary captures the seed, mutators, turn count, milestones and
+//! final statistics of a playthrough together with a checksum, so results
+//! can be shared and compared outside the game. The checksum catches a
+//! summary damaged in transit or edited by hand without care; it is not a
+//! signature, and anyone can recompute it after changing a field.
+
+use crate::core::{Player, WorldState};
+use crate::systems::factions::{FactionId, FactionSystem};
+use crate::systems::quests::{QuestStatus, QuestSystem};
+use crate::GameResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Final character statistics recorded in a run summary
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FinalStats {
+    pub mental_acuity: i32,
+    pub resonance_sensitivity: i32,
+    pub theories_discovered: usize,
+    pub theories_mastered: usize,
+    pub quests_completed: usize,
+    pub silver: i32,
+    /// Reputation by faction key, sorted for a stable checksum
+    pub faction_standings: BTreeMap<String, i32>,
+}
+
+/// Shareable summary of a playthrough
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub game_version: String,
+    pub player_name: String,
+    pub seed: u64,
+    pub mutators: Vec<String>,
+    pub turns: u32,
+    pub playtime_minutes: i32,
+    pub game_time_minutes: i32,
+    /// Notable achievements in a stable order
+    pub milestones: Vec<String>,
+    pub final_stats: FinalStats,
+    /// Checksum over all other fields, in hex
+    #[serde(alias = "hash")]
+    pub checksum: String,
+}
+
+impl RunSummary {
+    /// Build a summary from the current game state
+    pub fn from_game_state(
+        player: &Player,
+        world: &WorldState,
+        quest_system: &QuestSystem,
+        faction_system: &FactionSystem,
+    ) -> Self {
+        let mut completed_quests: Vec<String> = quest_system.player_progress
+            .iter()
+            .filter(|(_, progress)| progress.status == QuestStatus::Completed)
+            .map(|(id, _)| id.clone())
+            .collect();
+        completed_quests.sort();
+
+        let mut mastered = player.get_mastered_theories();
+        mastered.sort();
+
+        let mut certifications = player.knowledge.certifications.clone();
+        certifications.sort();
+
+        let mut defectors: Vec<String> = faction_system.defection
+            .defectors()
+            .iter()
+            .map(|arc| arc.npc_id.clone())
+            .collect();
+        defectors.sort();
+
+        let mut milestones = Vec::new();
+        milestones.extend(completed_quests.iter().map(|id| format!("quest_completed:{}", id)));
+        milestones.extend(mastered.iter().map(|id| format!("theory_mastered:{}", id)));
+        milestones.extend(certifications.iter().map(|id| format!("certified:{}", id)));
+        milestones.extend(defectors.iter().map(|id| format!("recruited:{}", id)));
+
+        let faction_standings = FactionId::all()
+            .into_iter()
+            .map(|faction| (faction.key().to_string(), player.faction_reputation(faction)))
+            .collect();
+
+        let mut summary = Self {
+            game_version: crate::VERSION.to_string(),
+            player_name: player.name.clone(),
+            seed: world.run.seed,
+            mutators: world.run.mutators.clone(),
+            turns: world.run.turns,
+            playtime_minutes: player.playtime_minutes,
+            game_time_minutes: world.game_time_minutes,
+            milestones,
+            final_stats: FinalStats {
+                mental_acuity: player.attributes.mental_acuity,
+                resonance_sensitivity: player.attributes.resonance_sensitivity,
+                theories_discovered: player.knowledge.theories_discovered(),
+                theories_mastered: player.knowledge.theories_mastered(),
+                quests_completed: completed_quests.len(),
+                silver: player.inventory.silver,
+                faction_standings,
+            },
+            checksum: String::new(),
+        };
+        summary.checksum = summary.compute_checksum();
+        summary
+    }
+
+    /// Compute the checksum over every field except the checksum itself
+    pub fn compute_checksum(&self) -> String {
+        let mut unsummed = self.clone();
+        unsummed.checksum = String::new();
+        let canonical = serde_json::to_string(&unsummed).unwrap_or_default();
+        checksum(canonical.as_bytes())
+    }
+
+    /// Check that the fields still match the checksum
+    pub fn verify(&self) -> bool {
+        self.checksum == self.compute_checksum()
+    }
+
+    /// Human-readable summary for display
+    pub fn format(&self) -> String {
+        let mut response = String::from("=== RUN SUMMARY ===\n\n");
+        response.push_str(&format!("Player: {}\n", self.player_name));
+        response.push_str(&format!("Seed: {}\n", self.seed));
+        response.push_str(&format!("Mutators: {}\n",
+            if self.mutators.is_empty() { "none".to_string() } else { self.mutators.join(", ") }));
+        response.push_str(&format!("Turns: {}\n", self.turns));
+        response.push_str(&format!("Playtime: {}h {}m\n", self.playtime_minutes / 60, self.playtime_minutes % 60));
+        response.push_str(&format!("Theories mastered: {}\n", self.final_stats.theories_mastered));
+        response.push_str(&format!("Quests completed: {}\n", self.final_stats.quests_completed));
+
+        if !self.milestones.is_empty() {
+            response.push_str("\nMilestones:\n");
+            for milestone in &self.milestones {
+                response.push_str(&format!("  • {}\n", milestone));
+            }
+        }
+
+        response.push_str(&format!("\nChecksum: {}", self.checksum));
+        response
+    }
+
+    /// Write the summary to a JSON file
+    pub fn export(&self, path: &Path) -> GameResult<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to serialize run summary: {}", e)))?;
+        std::fs::write(path, json)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to write run summary: {}", e)))?;
+        Ok(())
+    }
+
+    /// Read a summary from a JSON file
+    pub fn import(path: &Path) -> GameResult<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to read run summary: {}", e)))?;
+        serde_json::from_str(&json)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Invalid run summary: {}", e)).into())
+    }
+}
+
+/// A stable 128-bit checksum of some data, as 32 hex digits
+///
+/// This catches accidental damage, not deliberate edits: it is unkeyed and
+/// the salt is public.
+pub(crate) fn checksum(data: &[u8]) -> String {
+    // FNV-1a with two offsets gives a stable 128-bit checksum across platforms
+    let digest_a = fnv1a_64(crate::GAME_NAME.as_bytes(), data, 0xcbf29ce484222325);
+    let digest_b = fnv1a_64(crate::GAME_NAME.as_bytes(), data, 0x84222325cbf29ce4);
+    format!("{:016x}{:016x}", digest_a, digest_b)
+}
+
+fn fnv1a_64(salt: &[u8], data: &[u8], offset: u64) -> u64 {
+    let mut hash = offset;
+    for byte in salt.iter().chain(data.iter()) {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_summary() -> RunSummary {
+        let player = Player::new("Challenger".to_string());
+        let mut world = WorldState::new();
+        world.run.seed = 42;
+        world.run.mutators.push("no_rest".to_string());
+        world.run.turns = 17;
+        RunSummary::from_game_state(&player, &world, &QuestSystem::new(), &FactionSystem::new())
+    }
+
+    #[test]
+    fn test_summary_checksum_is_stable() {
+        let first = create_summary();
+        let second = create_summary();
+        assert_eq!(first.checksum, second.checksum);
+        assert_eq!(first.checksum.len(), 32);
+        assert!(first.verify());
+    }
+
+    #[test]
+    fn test_edit_without_new_checksum_fails_verification() {
+        let mut summary = create_summary();
+        summary.turns = 3;
+        assert!(!summary.verify());
+
+        // Not a signature: whoever edits a field can recompute the checksum
+        summary.checksum = summary.compute_checksum();
+        assert!(summary.verify());
+    }
+
+    #[test]
+    fn test_summaries_with_a_hash_field_still_load() {
+        let summary = create_summary();
+        let json = serde_json::to_string(&summary).unwrap().replace("\"checksum\"", "\"hash\"");
+        let loaded: RunSummary = serde_json::from_str(&json).unwrap();
+        assert!(loaded.verify());
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("summary.json");
+        let summary = create_summary();
+
+        summary.export(&path).unwrap();
+        let loaded = RunSummary::import(&path).unwrap();
+
+        assert_eq!(loaded, summary);
+        assert!(loaded.verify());
+    }
+}
@@ -0,0 +1,170 @@
+//! Portable save archives
+//!
+//! `export save <file>` writes the game to a single archive that can be
+//! carried to another machine, or attached to a bug report, and read back
+//! with `import save <file>`. An archive is gzip-compressed JSON holding the
+//! save itself and a checksum over it:
+//!
+//! ```text
+//! {
+//!   "format": "sympathetic-resonance-save",
+//!   "format_version": 1,
+//!   "game_version": "0.1.0",
+//!   "exported_at": "...",
+//!   "checksum": "<32 hex digits>",
+//!   "save": "<the save file's JSON>"
+//! }
+//! ```
+//!
+//! An archive whose save doesn't match its checksum, or isn't a valid game,
+//! is refused rather than imported.
+
+use crate::persistence::run_summary::checksum;
+use crate::persistence::serialization::{validate_game_state, GameStateData};
+use crate::GameResult;
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Extension given to archive files named without one
+pub const ARCHIVE_EXTENSION: &str = "srsave";
+/// Marks a file as a save archive
+const ARCHIVE_FORMAT: &str = "sympathetic-resonance-save";
+/// Current archive format version
+const ARCHIVE_VERSION: u32 = 1;
+
+/// A save packed for moving between machines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveArchive {
+    format: String,
+    format_version: u32,
+    /// Game version that exported it
+    pub game_version: String,
+    pub exported_at: DateTime<Utc>,
+    /// Checksum over the save
+    pub checksum: String,
+    /// The save file's JSON
+    save: String,
+}
+
+impl SaveArchive {
+    /// Pack a serialized save
+    pub fn new(save: String) -> Self {
+        Self {
+            format: ARCHIVE_FORMAT.to_string(),
+            format_version: ARCHIVE_VERSION,
+            game_version: crate::VERSION.to_string(),
+            exported_at: Utc::now(),
+            checksum: checksum(save.as_bytes()),
+            save,
+        }
+    }
+
+    /// Whether the save is exactly as it was when packed
+    pub fn verify(&self) -> bool {
+        self.checksum == checksum(self.save.as_bytes())
+    }
+
+    /// The serialized save, as the save directory stores it
+    pub fn save_data(&self) -> &str {
+        &self.save
+    }
+
+    /// The game the archive holds, checked for integrity
+    pub fn game_state(&self) -> GameResult<GameStateData> {
+        let state: GameStateData = serde_json::from_str(&self.save)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("The archived save is unreadable: {}", e)))?;
+        validate_game_state(&state)?;
+        Ok(state)
+    }
+
+    /// Write the archive, compressed, to a file
+    pub fn write(&self, path: &Path) -> GameResult<()> {
+        let json = serde_json::to_vec(self)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to pack save: {}", e)))?;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&json)
+            .and_then(|_| encoder.finish())
+            .and_then(|compressed| std::fs::write(path, compressed))
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to write {}: {}", path.display(), e)))?;
+        Ok(())
+    }
+
+    /// Read an archive from a file, refusing one that has been altered or damaged
+    pub fn read(path: &Path) -> GameResult<Self> {
+        let compressed = std::fs::read(path)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to read {}: {}", path.display(), e)))?;
+        let mut json = Vec::new();
+        GzDecoder::new(compressed.as_slice()).read_to_end(&mut json)
+            .map_err(|_| crate::GameError::SaveLoadError(format!("{} isn't a save archive.", path.display())))?;
+        let archive: Self = serde_json::from_slice(&json)
+            .map_err(|_| crate::GameError::SaveLoadError(format!("{} isn't a save archive.", path.display())))?;
+
+        if archive.format != ARCHIVE_FORMAT {
+            return Err(crate::GameError::SaveLoadError(format!("{} isn't a save archive.", path.display())).into());
+        }
+        if archive.format_version > ARCHIVE_VERSION {
+            return Err(crate::GameError::SaveLoadError(format!(
+                "{} was exported by a newer version of the game ({}).", path.display(), archive.game_version
+            )).into());
+        }
+        if !archive.verify() {
+            return Err(crate::GameError::SaveLoadError(format!(
+                "{} has been altered or damaged; its checksum doesn't match.", path.display()
+            )).into());
+        }
+        Ok(archive)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Player, WorldState};
+    use crate::persistence::serialize_game_state;
+    use crate::systems::quests::QuestSystem;
+    use crate::systems::{CombatSystem, DialogueSystem, FactionSystem, KnowledgeSystem, MagicSystem};
+    use tempfile::TempDir;
+
+    fn serialized_game() -> String {
+        let mut world = WorldState::new();
+        world.current_location = "tutorial_chamber".to_string();
+        serialize_game_state(
+            &Player::new("Archivist".to_string()), &world, &QuestSystem::new(),
+            &CombatSystem::new(), &FactionSystem::new(), &KnowledgeSystem::new(),
+            &DialogueSystem::new(), &MagicSystem::new(), None,
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_archives_round_trip_compressed() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("travel.srsave");
+        let save = serialized_game();
+
+        SaveArchive::new(save.clone()).write(&path).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() < save.len() as u64);
+
+        let archive = SaveArchive::read(&path).unwrap();
+        assert_eq!(archive.save_data(), save);
+        assert_eq!(archive.game_state().unwrap().player.name, "Archivist");
+    }
+
+    #[test]
+    fn test_altered_or_foreign_files_are_refused() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("tampered.srsave");
+        let mut archive = SaveArchive::new(serialized_game());
+        archive.save = archive.save.replace("Archivist", "Impostor");
+        archive.write(&path).unwrap();
+        assert!(SaveArchive::read(&path).unwrap_err().to_string().contains("checksum"));
+
+        let plain = dir.path().join("plain.json");
+        std::fs::write(&plain, "{}").unwrap();
+        assert!(SaveArchive::read(&plain).is_err());
+    }
+}
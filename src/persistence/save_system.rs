@@ -1,25 +1,48 @@
 //! Save and load system for game state persistence
 //!
 //! This module provides file-based save/load functionality with
-//! multiple save slots and backup management
+//! multiple save slots and backup management. Each slot keeps its own
+//! rolling backups: overwriting a slot sets its old contents aside, and only
+//! the most recent few are kept.
+//!
+//! Saves are compressed, and the manager keeps the compressed form of each
+//! section it has written so that saving again only compresses what changed.
 
 use crate::core::{Player, WorldState};
 use crate::systems::quests::QuestSystem;
 use crate::systems::{CombatSystem, FactionSystem, KnowledgeSystem, DialogueSystem, MagicSystem};
 use crate::persistence::serialization::{
     SaveFileInfo, serialize_game_state, deserialize_game_state,
-    validate_game_state, compress_save_data, decompress_save_data
+    validate_game_state, compress_save_data, compress_save_sections, decompress_save_data, SectionCache
 };
+use crate::persistence::save_archive::{SaveArchive, ARCHIVE_EXTENSION};
 use crate::GameResult;
+use std::cell::RefCell;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// The game as it stands, borrowed to be packed into an archive
+pub struct ArchiveContents<'a> {
+    pub player: &'a Player,
+    pub world: &'a WorldState,
+    pub quest_system: &'a QuestSystem,
+    pub combat_system: &'a CombatSystem,
+    pub faction_system: &'a FactionSystem,
+    pub knowledge_system: &'a KnowledgeSystem,
+    pub dialogue_system: &'a DialogueSystem,
+    pub magic_system: &'a MagicSystem,
+}
+
 /// Manages save file operations
 pub struct SaveManager {
     /// Directory for save files
     save_directory: PathBuf,
-    /// Maximum number of auto-save backups to keep
+    /// Maximum number of backups to keep for each slot
     max_backups: usize,
+    /// Compressed sections of recent saves, reused while unchanged
+    section_cache: RefCell<SectionCache>,
+    /// The slot most recently saved to or loaded from, until it's taken
+    latest_slot: RefCell<Option<String>>,
 }
 
 /// Information about an available save slot
@@ -45,6 +68,8 @@ impl SaveManager {
         Ok(Self {
             save_directory,
             max_backups: 5,
+            section_cache: RefCell::default(),
+            latest_slot: RefCell::default(),
         })
     }
 
@@ -118,12 +143,13 @@ impl SaveManager {
             save_name
         )?;
 
-        // Compress data
-        let compressed_data = compress_save_data(&serialized_data)?;
+        // Compress data, reusing sections unchanged since the last save
+        let compressed_data = compress_save_sections(&serialized_data, &mut self.section_cache.borrow_mut())?;
 
         // Write to file
         fs::write(&file_path, compressed_data)
             .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to write save file: {}", e)))?;
+        *self.latest_slot.borrow_mut() = Some(slot.clone());
 
         Ok(format!("Game saved to slot '{}'", slot))
     }
@@ -147,6 +173,7 @@ impl SaveManager {
 
         // Deserialize game state
         let (player, world, quest_system, combat_system, faction_system, knowledge_system, dialogue_system, magic_system) = deserialize_game_state(&serialized_data)?;
+        *self.latest_slot.borrow_mut() = Some(slot_name.to_string());
 
         Ok((player, world, quest_system, combat_system, faction_system, knowledge_system, dialogue_system, magic_system))
     }
@@ -227,43 +254,53 @@ impl SaveManager {
                 .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to create backup directory: {}", e)))?;
         }
 
-        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-        let backup_name = format!("{}_{}.backup",
-                                 original_path.file_stem().unwrap().to_str().unwrap(),
-                                 timestamp);
+        let slot_name = original_path.file_stem().and_then(|s| s.to_str()).unwrap_or("default");
+        let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S%3f");
+        let backup_name = format!("{}_{}.backup", slot_name, timestamp);
         let backup_path = backup_dir.join(backup_name);
 
         fs::copy(original_path, &backup_path)
             .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to create backup: {}", e)))?;
 
         // Clean up old backups
-        self.cleanup_old_backups(&backup_dir)?;
+        self.cleanup_old_backups(slot_name)?;
 
         Ok(())
     }
 
-    /// Remove old backup files to save space
-    fn cleanup_old_backups(&self, backup_dir: &Path) -> GameResult<()> {
+    /// Backups of a slot, newest first
+    pub fn list_backups(&self, slot_name: &str) -> Vec<PathBuf> {
+        let slot_name = self.sanitize_slot_name(slot_name);
         let mut backups = Vec::new();
 
-        if let Ok(entries) = fs::read_dir(backup_dir) {
+        if let Ok(entries) = fs::read_dir(self.save_directory.join("backups")) {
             for entry in entries.flatten() {
                 let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) == Some("backup") {
-                    if let Ok(metadata) = fs::metadata(&path) {
-                        if let Ok(modified) = metadata.modified() {
-                            backups.push((path, modified));
-                        }
-                    }
+                if path.extension().and_then(|s| s.to_str()) == Some("backup")
+                    && Self::backup_slot_name(&path).as_deref() == Some(slot_name.as_str())
+                {
+                    backups.push(path);
                 }
             }
         }
 
-        // Sort by modification time (newest first)
-        backups.sort_by(|a, b| b.1.cmp(&a.1));
+        // Backup names end in their timestamp, so the newest sorts last
+        backups.sort();
+        backups.reverse();
+        backups
+    }
+
+    /// The slot a backup file was taken from
+    fn backup_slot_name(path: &Path) -> Option<String> {
+        let stem = path.file_stem()?.to_str()?;
+        let mut parts = stem.rsplitn(3, '_');
+        let (_time, _date) = (parts.next()?, parts.next()?);
+        parts.next().map(str::to_string)
+    }
 
-        // Remove excess backups
-        for (path, _) in backups.into_iter().skip(self.max_backups) {
+    /// Remove a slot's backups beyond the most recent few
+    fn cleanup_old_backups(&self, slot_name: &str) -> GameResult<()> {
+        for path in self.list_backups(slot_name).into_iter().skip(self.max_backups) {
             let _ = fs::remove_file(path); // Ignore errors for backup cleanup
         }
 
@@ -374,6 +411,98 @@ impl SaveManager {
         Ok(())
     }
 
+    /// Where an archive named by the player lives: archives are kept in the
+    /// save directory's exports folder, named by the rules for slot names,
+    /// with or without the archive extension
+    pub fn archive_path(&self, file: &str) -> GameResult<PathBuf> {
+        let path = Path::new(file);
+        let name = match path.extension() {
+            Some(extension) if extension == ARCHIVE_EXTENSION => path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default(),
+            _ => file,
+        };
+        let name = self.checked_name(name)?;
+        Ok(self.save_directory.join("exports").join(format!("{}.{}", name, ARCHIVE_EXTENSION)))
+    }
+
+    /// Pack the game into a portable archive
+    pub fn export_archive(&self, game: &ArchiveContents, file: &str) -> GameResult<PathBuf> {
+        let path = self.archive_path(file)?;
+        let serialized = serialize_game_state(
+            game.player, game.world, game.quest_system,
+            game.combat_system, game.faction_system, game.knowledge_system,
+            game.dialogue_system, game.magic_system,
+            Some(format!("{}'s Adventure", game.player.name))
+        )?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to create {}: {}", parent.display(), e)))?;
+        }
+        SaveArchive::new(serialized).write(&path)?;
+        Ok(path)
+    }
+
+    /// Unpack a portable archive from the exports folder into a save slot,
+    /// after checking it; the slot is named after the archive unless one is
+    /// given, and is returned with what the save holds
+    pub fn import_archive(&self, file: &str, slot_name: Option<&str>) -> GameResult<(String, SaveFileInfo)> {
+        let path = self.archive_path(file)?;
+        // Slots are named in lower case, the way 'load' reads them
+        let slot = match slot_name {
+            Some(slot) => self.checked_name(slot)?,
+            None => path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default().to_string(),
+        }.to_lowercase();
+        if !path.exists() {
+            return Err(crate::GameError::SaveLoadError(format!(
+                "There is no archive at {}; copy archives into that folder to import them.", path.display()
+            )).into());
+        }
+        let archive = SaveArchive::read(&path)?;
+        let state = archive.game_state()?;
+
+        let target_path = self.get_save_file_path(&slot);
+        if target_path.exists() {
+            self.create_backup(&target_path)?;
+        }
+        fs::write(&target_path, compress_save_data(archive.save_data())?)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to write save file: {}", e)))?;
+        Ok((slot, SaveFileInfo::from(&state)))
+    }
+
+    /// A name for a slot or archive, refused unless it already follows the
+    /// rules saving holds slot names to
+    fn checked_name(&self, name: &str) -> GameResult<String> {
+        if self.sanitize_slot_name(name) != name {
+            return Err(crate::GameError::InvalidInput(format!(
+                "'{}' can't be used as a name; use up to 50 letters, numbers, '-' and '_'.", name
+            )).into());
+        }
+        Ok(name.to_string())
+    }
+
+    /// A manager for the quarantine directory, where emergency saves are kept
+    /// apart from ordinary ones
+    pub fn quarantine(&self) -> GameResult<Self> {
+        let save_directory = self.save_directory.join(crate::persistence::crash_recovery::QUARANTINE_DIR);
+        fs::create_dir_all(&save_directory)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to create quarantine directory: {}", e)))?;
+        Ok(Self { save_directory, max_backups: self.max_backups, section_cache: RefCell::default(), latest_slot: RefCell::default() })
+    }
+
+    /// Get file path for a save slot
+    pub fn slot_path(&self, slot_name: &str) -> PathBuf {
+        self.get_save_file_path(slot_name)
+    }
+
+    /// How many sections the last save reused rather than compressing again
+    pub fn sections_reused(&self) -> usize {
+        self.section_cache.borrow().reused()
+    }
+
+    /// The slot saved to or loaded from since this was last asked, if any
+    pub fn take_latest_slot(&self) -> Option<String> {
+        self.latest_slot.borrow_mut().take()
+    }
+
     /// Get save directory path for user reference
     pub fn get_save_directory_path(&self) -> &Path {
         &self.save_directory
@@ -441,6 +570,40 @@ mod tests {
         assert_eq!(loaded_player.name, "Test Player");
     }
 
+    #[test]
+    fn test_archives_stay_in_the_exports_folder() {
+        let (manager, temp_dir) = create_test_save_manager();
+        let player = Player::new("Archivist".to_string());
+        let world = WorldState::new();
+        let quest_system = QuestSystem::new();
+        let (combat_system, faction_system, knowledge_system, dialogue_system, magic_system) = create_test_systems();
+        let game = ArchiveContents {
+            player: &player,
+            world: &world,
+            quest_system: &quest_system,
+            combat_system: &combat_system,
+            faction_system: &faction_system,
+            knowledge_system: &knowledge_system,
+            dialogue_system: &dialogue_system,
+            magic_system: &magic_system,
+        };
+        let export = |file: &str| manager.export_archive(&game, file);
+
+        let path = export("My_Run").unwrap();
+        assert_eq!(path, temp_dir.path().join("exports").join("My_Run.srsave"));
+        assert_eq!(export("My_Run.srsave").unwrap(), path);
+        for file in ["../escaped", "/tmp/escaped", "nested/run", "run.zip", ""] {
+            assert!(export(file).is_err(), "{}", file);
+        }
+
+        let (slot, info) = manager.import_archive("My_Run", None).unwrap();
+        assert_eq!((slot.as_str(), info.character_name.as_str()), ("my_run", "Archivist"));
+        assert!(manager.load_game("my_run").is_ok());
+        assert!(manager.import_archive("My_Run", Some("../../escaped")).is_err());
+        assert!(manager.import_archive("../My_Run", None).is_err());
+        assert!(manager.import_archive("missing", None).is_err());
+    }
+
     #[test]
     fn test_save_info() {
         let (manager, _temp_dir) = create_test_save_manager();
@@ -532,6 +695,35 @@ mod tests {
         assert!(load_result.is_ok());
     }
 
+    #[test]
+    fn test_each_slot_keeps_its_own_rolling_backups() {
+        let (manager, _temp_dir) = create_test_save_manager();
+        let player = Player::new("Backup Test".to_string());
+        let world = WorldState::new();
+        let quest_system = QuestSystem::new();
+        let (combat_system, faction_system, knowledge_system, dialogue_system, magic_system) = create_test_systems();
+        let save = |slot: &str| {
+            manager.save_game(
+                &player, &world, &quest_system,
+                &combat_system, &faction_system, &knowledge_system,
+                &dialogue_system, &magic_system,
+                Some(slot.to_string()), None
+            ).unwrap();
+            // Backup names carry the time to the millisecond
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        };
+
+        save("other_slot");
+        save("other_slot");
+        for _ in 0..manager.max_backups + 3 {
+            save("main");
+        }
+
+        assert_eq!(manager.list_backups("main").len(), manager.max_backups);
+        assert_eq!(manager.list_backups("other_slot").len(), 1);
+        assert!(manager.list_backups("missing").is_empty());
+    }
+
     #[test]
     fn test_path_traversal_protection() {
         let (manager, temp_dir) = create_test_save_manager();
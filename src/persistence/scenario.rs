@@ -0,0 +1,296 @@
+//! Scenario files for starting a game from custom conditions
+//!
+//! A scenario is a JSON file describing where a new game begins: player
+//! attributes, known theories, inventory, faction standings, starting
+//! location and quests already under way. Scenarios are used for testing,
+//! teaching demos and community challenges. Every field is optional, and
+//! anything left out keeps the normal new-game value.
+//!
+//! ```json
+//! {
+//!   "format_version": 1,
+//!   "name": "Archive Heist",
+//!   "player": { "mental_acuity": 40 },
+//!   "known_theories": { "harmonic_fundamentals": 0.8 },
+//!   "faction_standings": { "underground_network": 35 },
+//!   "location": "practice_hall",
+//!   "active_quests": ["resonance_foundation"]
+//! }
+//! ```
+
+use crate::core::player::{Crystal, CrystalSize, CrystalType, Item, KnowledgeState};
+use crate::core::{Player, WorldState};
+use crate::systems::factions::{FactionId, FactionSystem};
+use crate::systems::knowledge::KnowledgeSystem;
+use crate::systems::quests::QuestSystem;
+use crate::GameResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Version of the scenario file format
+const SCENARIO_FORMAT_VERSION: u32 = 1;
+
+fn default_format_version() -> u32 {
+    SCENARIO_FORMAT_VERSION
+}
+
+/// Custom starting conditions for a new game
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Scenario {
+    #[serde(default = "default_format_version")]
+    pub format_version: u32,
+    pub name: String,
+    /// Shown to the player when the scenario starts
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub player: ScenarioPlayer,
+    /// Understanding level (0.0-1.0) by theory ID
+    #[serde(default)]
+    pub known_theories: BTreeMap<String, f32>,
+    #[serde(default)]
+    pub inventory: ScenarioInventory,
+    /// Reputation by faction key, e.g. "magisters_council"
+    #[serde(default)]
+    pub faction_standings: BTreeMap<String, i32>,
+    /// Starting location ID
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Quest IDs to start, in order
+    #[serde(default)]
+    pub active_quests: Vec<String>,
+}
+
+/// Player attribute overrides
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScenarioPlayer {
+    pub name: Option<String>,
+    pub mental_acuity: Option<i32>,
+    pub resonance_sensitivity: Option<i32>,
+    pub fatigue: Option<i32>,
+}
+
+/// Inventory overrides; crystals replace the starting crystal when given
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScenarioInventory {
+    pub silver: Option<i32>,
+    #[serde(default)]
+    pub crystals: Vec<ScenarioCrystal>,
+    #[serde(default)]
+    pub items: Vec<Item>,
+}
+
+/// A crystal to start with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioCrystal {
+    pub crystal_type: CrystalType,
+    pub size: CrystalSize,
+    #[serde(default = "default_integrity")]
+    pub integrity: f32,
+    #[serde(default = "default_purity")]
+    pub purity: f32,
+}
+
+fn default_integrity() -> f32 {
+    100.0
+}
+
+fn default_purity() -> f32 {
+    0.6
+}
+
+impl Scenario {
+    /// Read a scenario file
+    pub fn load(path: &Path) -> GameResult<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to read scenario {}: {}", path.display(), e)))?;
+        let scenario: Scenario = serde_json::from_str(&text)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Invalid scenario {}: {}", path.display(), e)))?;
+
+        if scenario.format_version > SCENARIO_FORMAT_VERSION {
+            return Err(crate::GameError::SaveLoadError(format!(
+                "{} uses scenario format {}, but this build supports up to {}",
+                path.display(), scenario.format_version, SCENARIO_FORMAT_VERSION
+            )).into());
+        }
+        Ok(scenario)
+    }
+
+    /// Check every referenced ID against the loaded content
+    pub fn validate(&self, world: &WorldState, knowledge_system: &KnowledgeSystem, quest_system: &QuestSystem) -> GameResult<()> {
+        let invalid = |msg: String| -> GameResult<()> {
+            Err(crate::GameError::InvalidInput(format!("Scenario '{}': {}", self.name, msg)).into())
+        };
+
+        if let Some(location) = &self.location {
+            if !world.locations.contains_key(location) {
+                return invalid(format!("unknown location '{}'", location));
+            }
+        }
+        for (theory, understanding) in &self.known_theories {
+            if knowledge_system.get_theory(theory).is_none() {
+                return invalid(format!("unknown theory '{}'", theory));
+            }
+            if !(0.0..=1.0).contains(understanding) {
+                return invalid(format!("understanding of '{}' must be between 0.0 and 1.0", theory));
+            }
+        }
+        for faction in self.faction_standings.keys() {
+            if faction_from_key(faction).is_none() {
+                return invalid(format!("unknown faction '{}'", faction));
+            }
+        }
+        for quest in &self.active_quests {
+            if !quest_system.quest_definitions.contains_key(quest) {
+                return invalid(format!("unknown quest '{}'", quest));
+            }
+        }
+        Ok(())
+    }
+
+    /// Set up a new game from this scenario, returning the introduction text
+    pub fn apply(
+        &self,
+        player: &mut Player,
+        world: &mut WorldState,
+        knowledge_system: &KnowledgeSystem,
+        quest_system: &mut QuestSystem,
+        faction_system: &FactionSystem,
+    ) -> GameResult<String> {
+        self.validate(world, knowledge_system, quest_system)?;
+
+        if let Some(name) = &self.player.name {
+            player.name = name.clone();
+        }
+        if let Some(acuity) = self.player.mental_acuity {
+            player.attributes.mental_acuity = acuity.clamp(1, 100);
+            player.mental_state.max_energy = (player.attributes.mental_acuity as f32 * 1.5) as i32;
+            player.mental_state.current_energy = player.mental_state.max_energy;
+        }
+        if let Some(sensitivity) = self.player.resonance_sensitivity {
+            player.attributes.resonance_sensitivity = sensitivity.clamp(0, 100);
+        }
+        if let Some(fatigue) = self.player.fatigue {
+            player.mental_state.fatigue = fatigue.clamp(0, 100);
+        }
+
+        if !self.known_theories.is_empty() {
+            let theories = self.known_theories.iter().map(|(id, level)| (id.clone(), *level)).collect();
+            let concepts = std::mem::take(&mut player.knowledge.encountered_concepts);
+            player.knowledge = KnowledgeState::migrate_from_legacy(theories, None, 0.0);
+            player.knowledge.encountered_concepts = concepts;
+        }
+
+        if let Some(silver) = self.inventory.silver {
+            player.inventory.silver = silver.max(0);
+        }
+        if !self.inventory.crystals.is_empty() {
+            player.inventory.crystals = self.inventory.crystals.iter()
+                .map(|crystal| Crystal::new(crystal.crystal_type.clone(), crystal.integrity, crystal.purity, crystal.size.clone()))
+                .collect();
+            player.inventory.active_crystal = Some(0);
+        }
+        player.inventory.items.extend(self.inventory.items.iter().cloned());
+
+        for (key, standing) in &self.faction_standings {
+            if let Some(faction) = faction_from_key(key) {
+                player.faction_standings.insert(faction, (*standing).clamp(-100, 100));
+            }
+        }
+
+        if let Some(location) = &self.location {
+            world.current_location = location.clone();
+            player.current_location = location.clone();
+            if let Some(location) = world.current_location_mut() {
+                location.visited = true;
+            }
+        }
+
+        // Quests start last so their requirements see the scenario's player
+        for quest in &self.active_quests {
+            quest_system.start_quest(quest, player, faction_system).map_err(|e| {
+                crate::GameError::InvalidInput(format!("Scenario '{}': cannot start quest '{}': {}", self.name, quest, e))
+            })?;
+        }
+
+        let mut intro = format!("=== Scenario: {} ===", self.name);
+        if !self.description.is_empty() {
+            intro.push_str(&format!("\n{}", self.description));
+        }
+        Ok(intro)
+    }
+}
+
+/// Parse a faction key as used in content data
+fn faction_from_key(key: &str) -> Option<FactionId> {
+    FactionId::all().into_iter().find(|faction| faction.key() == key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::DatabaseManager;
+    use tempfile::NamedTempFile;
+
+    fn setup() -> (Player, WorldState, KnowledgeSystem, QuestSystem, NamedTempFile) {
+        let temp_file = NamedTempFile::new().unwrap();
+        let db = DatabaseManager::new(temp_file.path().to_str().unwrap()).unwrap();
+        db.initialize_schema().unwrap();
+        db.load_default_content().unwrap();
+
+        let mut world = WorldState::new();
+        world.locations = db.load_locations().unwrap();
+        let mut knowledge = KnowledgeSystem::new();
+        knowledge.initialize(&db).unwrap();
+        let mut quests = QuestSystem::new();
+        for quest in crate::systems::quest_examples::create_example_quests() {
+            quests.add_quest_definition(quest);
+        }
+        (Player::new("Test".to_string()), world, knowledge, quests, temp_file)
+    }
+
+    #[test]
+    fn test_apply_scenario() {
+        let (mut player, mut world, knowledge, mut quests, _db) = setup();
+        let scenario: Scenario = serde_json::from_str(r#"{
+            "name": "Demo",
+            "description": "A prepared lesson.",
+            "player": { "name": "Ada", "mental_acuity": 40 },
+            "known_theories": { "harmonic_fundamentals": 0.8 },
+            "inventory": { "silver": 120, "crystals": [{ "crystal_type": "Amethyst", "size": "Large" }] },
+            "faction_standings": { "neutral_scholars": 25 },
+            "location": "practice_hall",
+            "active_quests": ["resonance_foundation"]
+        }"#).unwrap();
+
+        let intro = scenario.apply(&mut player, &mut world, &knowledge, &mut quests, &FactionSystem::new()).unwrap();
+        assert!(intro.contains("Scenario: Demo"));
+        assert_eq!(player.name, "Ada");
+        assert_eq!(player.mental_state.max_energy, 60);
+        assert_eq!(player.theory_understanding("harmonic_fundamentals"), 0.8);
+        assert_eq!(player.inventory.silver, 120);
+        assert!(matches!(player.inventory.crystals[0].crystal_type, CrystalType::Amethyst));
+        assert_eq!(player.faction_reputation(FactionId::NeutralScholars), 25);
+        assert_eq!(world.current_location, "practice_hall");
+        assert!(quests.player_progress.contains_key("resonance_foundation"));
+    }
+
+    #[test]
+    fn test_invalid_references_are_rejected() {
+        let (mut player, mut world, knowledge, mut quests, _db) = setup();
+        let factions = FactionSystem::new();
+
+        for bad in [
+            r#"{"name": "x", "location": "nowhere"}"#,
+            r#"{"name": "x", "known_theories": {"made_up": 0.5}}"#,
+            r#"{"name": "x", "faction_standings": {"pirates": 10}}"#,
+            r#"{"name": "x", "active_quests": ["no_such_quest"]}"#,
+        ] {
+            let scenario: Scenario = serde_json::from_str(bad).unwrap();
+            assert!(scenario.apply(&mut player, &mut world, &knowledge, &mut quests, &factions).is_err(), "{}", bad);
+        }
+        assert_eq!(player.name, "Test");
+    }
+}
@@ -1,13 +1,29 @@
 //! Game state serialization and deserialization
 //!
-//! This module handles converting game state to/from storage format
+//! This module handles converting game state to/from storage format.
+//!
+//! Save files are gzip-compressed, one top-level section (player, world,
+//! quest system and so on) per gzip member. Members concatenate into a single
+//! valid gzip stream, so a save decompresses like any other, but a section
+//! that hasn't changed since it was last saved can be written from the
+//! compressed bytes kept for it instead of being compressed again. Most of a
+//! save, such as quest and NPC definitions, rarely changes between saves.
 
 use crate::core::{Player, WorldState};
 use crate::systems::quests::QuestSystem;
 use crate::systems::{CombatSystem, FactionSystem, KnowledgeSystem, DialogueSystem, MagicSystem};
 use crate::GameResult;
 use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
 use chrono::{DateTime, Utc};
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+
+/// First bytes of a gzip stream; saves without them predate compression
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
 
 /// Complete serializable game state
 #[derive(Debug, Serialize, Deserialize)]
@@ -47,6 +63,9 @@ pub struct SaveMetadata {
     pub location_name: String,
     /// Game version when saved
     pub game_version: String,
+    /// Title of the quest being followed when saved
+    #[serde(default)]
+    pub active_quest: Option<String>,
 }
 
 /// Current save format version
@@ -68,6 +87,10 @@ pub fn serialize_game_state(
         .map(|loc| loc.name.clone())
         .unwrap_or_else(|| "Unknown Location".to_string());
 
+    let active_quest = crate::systems::quest_journal::tracked(player, quest_system)
+        .and_then(|progress| quest_system.quest_definitions.get(&progress.quest_id))
+        .map(|quest| quest.title.clone());
+
     let save_name = save_name.unwrap_or_else(|| {
         format!("Save {}", chrono::Utc::now().format("%Y-%m-%d %H:%M"))
     });
@@ -88,6 +111,7 @@ pub fn serialize_game_state(
             playtime_minutes: player.playtime_minutes,
             location_name,
             game_version: crate::VERSION.to_string(),
+            active_quest,
         },
     };
 
@@ -218,6 +242,7 @@ pub struct SaveFileInfo {
     pub playtime_minutes: i32,
     pub timestamp: DateTime<Utc>,
     pub game_version: String,
+    pub active_quest: Option<String>,
 }
 
 impl From<&GameStateData> for SaveFileInfo {
@@ -229,20 +254,80 @@ impl From<&GameStateData> for SaveFileInfo {
             playtime_minutes: state.metadata.playtime_minutes,
             timestamp: state.timestamp,
             game_version: state.metadata.game_version.clone(),
+            active_quest: state.metadata.active_quest.clone(),
         }
     }
 }
 
+/// Compressed save sections kept from recent saves, by section name
+#[derive(Debug, Default)]
+pub struct SectionCache {
+    /// Each section's text, with its compressed bytes
+    sections: HashMap<String, (String, Vec<u8>)>,
+    /// How many sections the last save reused
+    reused: usize,
+}
+
+impl SectionCache {
+    /// How many sections the last save took from the cache
+    pub fn reused(&self) -> usize {
+        self.reused
+    }
+}
+
 /// Compress save data for storage efficiency
 pub fn compress_save_data(data: &str) -> GameResult<Vec<u8>> {
-    // For now, just convert to bytes. In the future, could add compression
-    Ok(data.as_bytes().to_vec())
+    gzip(data.as_bytes())
+}
+
+/// Compress save data a section at a time, reusing the compressed form of
+/// every section unchanged since it was last compressed
+pub fn compress_save_sections(data: &str, cache: &mut SectionCache) -> GameResult<Vec<u8>> {
+    let sections: BTreeMap<String, &RawValue> = serde_json::from_str(data)
+        .map_err(|e| crate::GameError::SaveLoadError(format!("Save data isn't a JSON object: {}", e)))?;
+
+    cache.reused = 0;
+    let mut compressed = gzip(b"{")?;
+    for (index, (name, value)) in sections.iter().enumerate() {
+        if index > 0 {
+            compressed.extend(gzip(b",")?);
+        }
+        let text = format!("{}:{}", serde_json::Value::from(name.as_str()), value.get());
+        match cache.sections.get(name) {
+            Some((cached, bytes)) if *cached == text => {
+                compressed.extend_from_slice(bytes);
+                cache.reused += 1;
+            }
+            _ => {
+                let bytes = gzip(text.as_bytes())?;
+                compressed.extend_from_slice(&bytes);
+                cache.sections.insert(name.clone(), (text, bytes));
+            }
+        }
+    }
+    compressed.extend(gzip(b"}")?);
+    Ok(compressed)
 }
 
 /// Decompress save data from storage
 pub fn decompress_save_data(data: &[u8]) -> GameResult<String> {
-    String::from_utf8(data.to_vec())
-        .map_err(|e| crate::GameError::SaveLoadError(format!("Invalid UTF-8 in save data: {}", e)).into())
+    if !data.starts_with(&GZIP_MAGIC) {
+        return String::from_utf8(data.to_vec())
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Invalid UTF-8 in save data: {}", e)).into());
+    }
+
+    let mut text = String::new();
+    MultiGzDecoder::new(data).read_to_string(&mut text)
+        .map_err(|e| crate::GameError::SaveLoadError(format!("Corrupt save data: {}", e)))?;
+    Ok(text)
+}
+
+/// One gzip member holding `data`
+fn gzip(data: &[u8]) -> GameResult<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)
+        .and_then(|_| encoder.finish())
+        .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to compress save data: {}", e)).into())
 }
 
 #[cfg(test)]
@@ -351,4 +436,22 @@ mod tests {
 
         assert_eq!(test_data, decompressed);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_unchanged_sections_are_reused_when_compressing() {
+        let mut cache = SectionCache::default();
+        let first = r#"{"player": {"name": "Hero"}, "world": {"time": 1}, "quests": [1, 2, 3]}"#;
+        let compressed = compress_save_sections(first, &mut cache).unwrap();
+        assert_eq!(cache.reused(), 0);
+        let value: serde_json::Value = serde_json::from_str(&decompress_save_data(&compressed).unwrap()).unwrap();
+        assert_eq!(value, serde_json::from_str::<serde_json::Value>(first).unwrap());
+
+        let second = r#"{"player": {"name": "Hero"}, "world": {"time": 2}, "quests": [1, 2, 3]}"#;
+        let compressed = compress_save_sections(second, &mut cache).unwrap();
+        assert_eq!(cache.reused(), 2);
+        assert_eq!(decompress_save_data(&compressed).unwrap(), r#"{"player":{"name": "Hero"},"quests":[1, 2, 3],"world":{"time": 2}}"#);
+
+        // Saves written before compression still read
+        assert_eq!(decompress_save_data(first.as_bytes()).unwrap(), first);
+    }
+}
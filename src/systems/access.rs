@@ -0,0 +1,287 @@
+//! Locked, barred, guarded and sealed exits
+//!
+//! Not every way out of a location is open to everyone. An exit can carry a
+//! lock, defined alongside the exit in the database, that keeps the player
+//! out until its requirement is met:
+//! - A key, which must be carried (locked doors can also be picked)
+//! - Standing with a faction, shown to the guards as credentials
+//! - Understanding of a theory, needed to work a resonance ward open
+//! - The answer to a puzzle set into the seal
+//!
+//! Opening a lock is a lasting change to the location, kept as an override
+//! like any other, so it stays open for the rest of the game. Where the far
+//! side of the same doorway carries a lock back, that opens too.
+
+use crate::core::world_state::{Direction, WorldState};
+use crate::core::Player;
+use crate::systems::factions::FactionId;
+use crate::systems::location_changes::LocationChange;
+use crate::GameResult;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Minutes spent working at a lock with picks
+pub const PICK_MINUTES: i32 = 10;
+/// Chance of picking a lock before the player's acuity counts
+const PICK_BASE_CHANCE: f32 = 0.2;
+/// Best chance of picking a lock, however sharp the player
+const PICK_MAX_CHANCE: f32 = 0.75;
+
+/// What kind of obstacle stands in the way
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LockKind {
+    /// A door with a lock, opened with its key or picked
+    Locked,
+    /// A way barred shut, often by a ward
+    Barred,
+    /// A way watched over by guards
+    Guarded,
+    /// A way sealed by a puzzle
+    Sealed,
+}
+
+impl LockKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LockKind::Locked => "locked",
+            LockKind::Barred => "barred",
+            LockKind::Guarded => "guarded",
+            LockKind::Sealed => "sealed",
+        }
+    }
+}
+
+/// What it takes to get through
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AccessRequirement {
+    /// A carried item that opens the lock
+    Key { item: String },
+    /// Enough standing with a faction
+    Standing { faction: FactionId, min: i32 },
+    /// Enough understanding of a theory
+    Capability { theory: String, understanding: f32 },
+    /// The answer to a riddle or question
+    Puzzle { prompt: String, answer: String },
+}
+
+/// A lock on one exit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExitLock {
+    pub kind: LockKind,
+    pub requirement: AccessRequirement,
+    /// What the player sees in the way
+    pub description: String,
+}
+
+impl ExitLock {
+    /// Why the player can't go through, and what might get them through
+    pub fn refusal(&self) -> String {
+        let hint = match &self.requirement {
+            AccessRequirement::Key { item } => format!("You'd need the {} to 'unlock' it, or you could try to 'pick lock'.", item),
+            AccessRequirement::Standing { faction, .. } => format!(
+                "The guards let through only those who can 'show credentials' with the {}.", faction.display_name()
+            ),
+            AccessRequirement::Capability { theory, .. } => format!(
+                "Someone who understood {} might 'unlock' it.", theory.replace('_', " ")
+            ),
+            AccessRequirement::Puzzle { prompt, .. } => format!("Words are set into it: \"{}\"", prompt),
+        };
+        format!("{} {}", self.description, hint)
+    }
+}
+
+/// The lock on an exit from the player's location, if any
+pub fn lock_on<'a>(world: &'a WorldState, direction: &Direction) -> Option<&'a ExitLock> {
+    world.current_location()?.locks.get(direction)
+}
+
+/// Open an exit for good, along with the far side of the same doorway
+fn open(world: &mut WorldState, direction: &Direction) -> GameResult<()> {
+    let here = world.current_location.clone();
+    let far_side = world.current_location()
+        .and_then(|location| location.exits.get(direction))
+        .and_then(|destination| world.locations.get(destination))
+        .and_then(|destination| {
+            destination.exits.iter()
+                .find(|(back, to)| **to == here && destination.locks.contains_key(*back))
+                .map(|(back, _)| (destination.id.clone(), back.clone()))
+        });
+
+    world.change_location(&here, LocationChange::Unlock { direction: direction.clone() })?;
+    if let Some((destination, back)) = far_side {
+        world.change_location(&destination, LocationChange::Unlock { direction: back })?;
+    }
+    Ok(())
+}
+
+fn find_lock(world: &WorldState, direction: &Direction) -> GameResult<ExitLock> {
+    lock_on(world, direction).cloned().ok_or_else(|| {
+        crate::GameError::InvalidCommand(format!("Nothing bars the way {}.", direction.display_name())).into()
+    })
+}
+
+/// Try to open a lock with a key, know-how or an answer
+pub fn unlock(direction: &Direction, attempt: Option<&str>, player: &Player, world: &mut WorldState) -> GameResult<String> {
+    let lock = find_lock(world, direction)?;
+    let way = direction.display_name();
+    let response = match &lock.requirement {
+        AccessRequirement::Key { item } => {
+            match crate::systems::items::combination::find_carried(player, item) {
+                Some((_, name)) => format!("You turn the {} in the lock, and the way {} opens.", name, way),
+                None => return Ok(format!("You don't have the {}. {}", item, lock.refusal())),
+            }
+        }
+        AccessRequirement::Capability { theory, understanding } => {
+            if player.theory_understanding(theory) < *understanding {
+                return Ok(format!(
+                    "You can feel the ward's pattern, but not how to undo it. A deeper understanding of {} would let you open it.",
+                    theory.replace('_', " ")
+                ));
+            }
+            format!("You find the frequency the ward is tuned to and ease it apart. The way {} is clear.", way)
+        }
+        AccessRequirement::Puzzle { prompt, answer } => match attempt {
+            None => return Ok(format!("\"{}\"\n\nAnswer with 'unlock {} with <answer>'.", prompt, way)),
+            Some(guess) if guess.trim().eq_ignore_ascii_case(answer) => {
+                format!("As you answer \"{}\", the seal glows, then fades. The way {} is open.", answer, way)
+            }
+            Some(_) => return Ok("Nothing happens. That isn't the answer the seal is waiting for.".to_string()),
+        },
+        AccessRequirement::Standing { .. } => return Ok(lock.refusal()),
+    };
+
+    open(world, direction)?;
+    Ok(response)
+}
+
+/// Chance the player picks a lock
+pub fn pick_chance(player: &Player) -> f32 {
+    (PICK_BASE_CHANCE + player.attributes.mental_acuity as f32 / 200.0).min(PICK_MAX_CHANCE)
+}
+
+/// Try to pick a locked door; it takes time whether or not it works
+pub fn pick_lock(direction: &Direction, player: &mut Player, world: &mut WorldState, rng: &mut impl Rng) -> GameResult<String> {
+    let lock = find_lock(world, direction)?;
+    if lock.kind != LockKind::Locked {
+        return Ok(format!("There's no lock to pick: the way {} is {}. {}", direction.display_name(), lock.kind.name(), lock.refusal()));
+    }
+
+    world.advance_time(PICK_MINUTES);
+    player.playtime_minutes += PICK_MINUTES;
+    if rng.gen::<f32>() >= pick_chance(player) {
+        return Ok(format!("You work at the lock for {} minutes, but it holds.", PICK_MINUTES));
+    }
+
+    open(world, direction)?;
+    Ok(format!("After {} minutes of careful work, the lock gives with a click. The way {} is open.", PICK_MINUTES, direction.display_name()))
+}
+
+/// Show the guards the player's standing, at one exit or whichever is guarded
+pub fn show_credentials(direction: Option<&Direction>, player: &Player, world: &mut WorldState) -> GameResult<String> {
+    let guarded = world.current_location()
+        .into_iter()
+        .flat_map(|location| location.locks.iter())
+        .find(|(way, lock)| lock.kind == LockKind::Guarded && direction.is_none_or(|direction| direction == *way))
+        .map(|(way, lock)| (way.clone(), lock.clone()));
+    let Some((way, lock)) = guarded else {
+        return Ok("There's no one here to show your credentials to.".to_string());
+    };
+    let AccessRequirement::Standing { faction, min } = lock.requirement else {
+        return Ok(lock.refusal());
+    };
+
+    let standing = player.faction_reputation(faction);
+    if standing < min {
+        return Ok(format!(
+            "The guards look over your credentials and shake their heads. Only those in good standing with the {} may pass ({} needed, you have {}).",
+            faction.display_name(), min, standing
+        ));
+    }
+
+    open(world, &way)?;
+    Ok(format!(
+        "The guards look over your credentials, note your standing with the {}, and wave you through. The way {} is open to you.",
+        faction.display_name(), way.display_name()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::Location;
+    use rand::rngs::mock::StepRng;
+
+    fn world_with(lock: ExitLock) -> WorldState {
+        let mut world = WorldState::new();
+        let mut hall = Location::new("hall".to_string(), "Hall".to_string(), "A hall.".to_string());
+        hall.exits.insert(Direction::North, "vault".to_string());
+        hall.locks.insert(Direction::North, lock.clone());
+        let mut vault = Location::new("vault".to_string(), "Vault".to_string(), "A vault.".to_string());
+        vault.exits.insert(Direction::South, "hall".to_string());
+        vault.locks.insert(Direction::South, lock);
+        world.add_location(hall);
+        world.add_location(vault);
+        world.current_location = "hall".to_string();
+        world
+    }
+
+    #[test]
+    fn test_puzzles_and_picks_open_both_sides_for_good() {
+        let riddle = ExitLock {
+            kind: LockKind::Sealed,
+            requirement: AccessRequirement::Puzzle { prompt: "What do matched crystals do?".to_string(), answer: "resonate".to_string() },
+            description: "A seal covers the door.".to_string(),
+        };
+        let player = Player::new("Tester".to_string());
+        let mut world = world_with(riddle);
+        assert!(world.move_to_location(Direction::North).is_err());
+        assert!(unlock(&Direction::North, None, &player, &mut world).unwrap().contains("matched crystals"));
+        assert!(unlock(&Direction::North, Some("shatter"), &player, &mut world).unwrap().contains("Nothing happens"));
+        unlock(&Direction::North, Some(" Resonate "), &player, &mut world).unwrap();
+        assert!(world.locations["vault"].locks.is_empty());
+        assert!(world.move_to_location(Direction::North).is_ok());
+        assert_eq!(world.location_changes.changes("vault"), &[LocationChange::Unlock { direction: Direction::South }]);
+
+        let door = ExitLock {
+            kind: LockKind::Locked,
+            requirement: AccessRequirement::Key { item: "iron key".to_string() },
+            description: "An iron door is locked.".to_string(),
+        };
+        let mut player = Player::new("Tester".to_string());
+        let mut world = world_with(door);
+        assert!(unlock(&Direction::North, None, &player, &mut world).unwrap().contains("don't have the iron key"));
+        let start = world.game_time_minutes;
+        assert!(pick_lock(&Direction::North, &mut player, &mut world, &mut StepRng::new(u64::MAX, 0)).unwrap().contains("holds"));
+        assert_eq!(world.game_time_minutes, start + PICK_MINUTES);
+        pick_lock(&Direction::North, &mut player, &mut world, &mut StepRng::new(0, 0)).unwrap();
+        assert!(lock_on(&world, &Direction::North).is_none());
+    }
+
+    #[test]
+    fn test_guards_and_wards_want_standing_and_understanding() {
+        let guards = ExitLock {
+            kind: LockKind::Guarded,
+            requirement: AccessRequirement::Standing { faction: FactionId::MagistersCouncil, min: 10 },
+            description: "Wardens stand at the gate.".to_string(),
+        };
+        let mut player = Player::new("Tester".to_string());
+        let mut world = world_with(guards);
+        let mut rng = StepRng::new(0, 0);
+        assert!(pick_lock(&Direction::North, &mut player, &mut world, &mut rng).unwrap().contains("no lock to pick"));
+        assert!(show_credentials(None, &player, &mut world).unwrap().contains("shake their heads"));
+        player.modify_faction_reputation(FactionId::MagistersCouncil, 15);
+        assert!(show_credentials(Some(&Direction::North), &player, &mut world).unwrap().contains("wave you through"));
+        assert!(lock_on(&world, &Direction::North).is_none());
+
+        let ward = ExitLock {
+            kind: LockKind::Barred,
+            requirement: AccessRequirement::Capability { theory: "harmonic_fundamentals".to_string(), understanding: 0.5 },
+            description: "A ward bars the way.".to_string(),
+        };
+        let mut world = world_with(ward);
+        assert!(unlock(&Direction::North, None, &player, &mut world).unwrap().contains("deeper understanding"));
+        player.knowledge.theories.insert("harmonic_fundamentals".to_string(), 0.6);
+        unlock(&Direction::North, None, &player, &mut world).unwrap();
+        assert!(lock_on(&world, &Direction::North).is_none());
+    }
+}
@@ -0,0 +1,206 @@
+//! Ambient lines added to location descriptions
+//!
+//! Now and then, describing a location adds a line of flavour drawn from
+//! what's going on around the player: the time of day, the weather, which
+//! faction holds sway there, and how much magic has been cast there lately.
+//! Lines come from two places:
+//! - The location's own lines from content, each shown only while its
+//!   condition holds
+//! - General lines any location can use, for a faction's open presence and
+//!   for lingering traces of recent casting
+//!
+//! Content also sets how often a location adds a line, and whether it uses
+//! the general lines at all.
+
+use crate::core::world_state::{Location, PresenceVisibility, TimeOfDay, Weather, WorldState};
+use crate::systems::factions::FactionId;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Chance a description gets an ambient line, unless content says otherwise
+pub const DEFAULT_CHANCE: f32 = 0.35;
+/// Magical signatures that leave a noticeable trace
+const TRACE_SIGNATURES: usize = 2;
+/// Magical signatures that leave the air thick with casting
+const HEAVY_SIGNATURES: usize = 4;
+/// Influence at which an openly present faction colours a place
+const PRESENCE_INFLUENCE: i32 = 40;
+
+/// When an ambient line can be used
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AmbientCondition {
+    Always,
+    TimeOfDay { time: TimeOfDay },
+    Weather { weather: Weather },
+    /// A faction, by content key, has at least this much influence here
+    Faction { faction: String, min_influence: i32 },
+    /// At least this many magical signatures linger here
+    RecentMagic { min_signatures: usize },
+}
+
+impl AmbientCondition {
+    pub fn holds(&self, location: &Location, world: &WorldState) -> bool {
+        match self {
+            AmbientCondition::Always => true,
+            AmbientCondition::TimeOfDay { time } => world.environment.time_of_day == *time,
+            AmbientCondition::Weather { weather } => world.environment.weather == *weather,
+            AmbientCondition::Faction { faction, min_influence } => location.faction_presence.get(faction)
+                .is_some_and(|presence| presence.influence >= *min_influence),
+            AmbientCondition::RecentMagic { min_signatures } => location.magical_properties.recent_activity.len() >= *min_signatures,
+        }
+    }
+}
+
+/// A line of flavour and when it applies
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmbientLine {
+    pub condition: AmbientCondition,
+    pub text: String,
+}
+
+impl AmbientLine {
+    pub fn new(condition: AmbientCondition, text: &str) -> Self {
+        Self { condition, text: text.to_string() }
+    }
+}
+
+/// How a location adds ambient lines
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmbienceConfig {
+    /// Chance a description gets a line (0.0 for never)
+    #[serde(default = "default_chance")]
+    pub chance: f32,
+    /// Lines only this location uses
+    #[serde(default)]
+    pub lines: Vec<AmbientLine>,
+    /// Whether the general lines are used here too
+    #[serde(default = "default_general")]
+    pub general: bool,
+}
+
+fn default_chance() -> f32 {
+    DEFAULT_CHANCE
+}
+
+fn default_general() -> bool {
+    true
+}
+
+impl Default for AmbienceConfig {
+    fn default() -> Self {
+        Self { chance: DEFAULT_CHANCE, lines: Vec::new(), general: true }
+    }
+}
+
+/// General lines that apply at a location right now
+fn general_lines(location: &Location) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    let mut present: Vec<(FactionId, i32)> = FactionId::all().into_iter()
+        .filter_map(|faction| {
+            let presence = location.faction_presence.get(faction.key())?;
+            let open = matches!(presence.visibility, PresenceVisibility::Open | PresenceVisibility::Dominant);
+            (open && presence.influence >= PRESENCE_INFLUENCE).then_some((faction, presence.influence))
+        })
+        .collect();
+    present.sort_by_key(|(_, influence)| -influence);
+    if let Some((faction, _)) = present.first() {
+        lines.push(match faction {
+            FactionId::MagistersCouncil => "A Council clerk hurries past with a satchel of sealed writs.",
+            FactionId::OrderOfHarmony => "Someone nearby is humming one of the Order's tuning chants under their breath.",
+            FactionId::IndustrialConsortium => "A Consortium runner checks a crate's stamp against a ledger and moves on.",
+            FactionId::UndergroundNetwork => "A chalk mark by the door, easy to miss, tells those who know that the Underground passes this way.",
+            FactionId::NeutralScholars => "Two scholars argue in low voices over a marked-up page.",
+        }.to_string());
+    }
+
+    let signatures = location.magical_properties.recent_activity.len();
+    if signatures >= HEAVY_SIGNATURES {
+        lines.push("The air is thick with spent resonance, and your teeth ache faintly with it.".to_string());
+    } else if signatures >= TRACE_SIGNATURES {
+        lines.push("A faint prickle in the air tells of casting here not long ago.".to_string());
+    }
+
+    lines
+}
+
+/// Every ambient line that could be used at a location right now
+pub fn candidates(location: &Location, world: &WorldState) -> Vec<String> {
+    let mut lines: Vec<String> = location.ambience.lines.iter()
+        .filter(|line| line.condition.holds(location, world))
+        .map(|line| line.text.clone())
+        .collect();
+    if location.ambience.general {
+        lines.extend(general_lines(location));
+    }
+    lines
+}
+
+/// Perhaps pick an ambient line for a location's description
+pub fn line(location: &Location, world: &WorldState, rng: &mut impl Rng) -> Option<String> {
+    if rng.gen::<f32>() >= location.ambience.chance {
+        return None;
+    }
+    let lines = candidates(location, world);
+    if lines.is_empty() {
+        return None;
+    }
+    Some(lines[rng.gen_range(0..lines.len())].clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::{FactionPresence, MagicalSignature};
+    use rand::rngs::mock::StepRng;
+
+    fn observatory() -> Location {
+        let mut location = Location::new("observatory".to_string(), "Observatory".to_string(), "Arrays.".to_string());
+        location.ambience.lines = vec![
+            AmbientLine::new(AmbientCondition::Weather { weather: Weather::Rainy }, "Rain ticks against the dome."),
+            AmbientLine::new(AmbientCondition::Faction { faction: "neutral_scholars".to_string(), min_influence: 30 }, "A scholar adjusts an array."),
+        ];
+        location
+    }
+
+    #[test]
+    fn test_lines_follow_weather_factions_and_magic() {
+        let mut world = WorldState::new();
+        let mut location = observatory();
+        assert!(candidates(&location, &world).is_empty());
+
+        world.environment.weather = Weather::Rainy;
+        assert_eq!(candidates(&location, &world), vec!["Rain ticks against the dome.".to_string()]);
+
+        location.faction_presence.insert("neutral_scholars".to_string(), FactionPresence {
+            influence: 60,
+            visibility: PresenceVisibility::Open,
+            member_count: 3,
+        });
+        location.magical_properties.recent_activity = vec![
+            MagicalSignature { magic_type: "light".to_string(), strength: 0.5, age_minutes: 0, frequency: 4 };
+            TRACE_SIGNATURES
+        ];
+        let lines = candidates(&location, &world);
+        assert_eq!(lines.len(), 4);
+        assert!(lines[2].contains("scholars argue"));
+        assert!(lines[3].contains("casting here"));
+
+        location.ambience.general = false;
+        assert_eq!(candidates(&location, &world).len(), 2);
+    }
+
+    #[test]
+    fn test_lines_come_up_only_as_often_as_content_says() {
+        let mut world = WorldState::new();
+        world.environment.weather = Weather::Rainy;
+        let mut location = observatory();
+
+        // A roll of 0 always falls under the chance
+        assert_eq!(line(&location, &world, &mut StepRng::new(0, 0)).as_deref(), Some("Rain ticks against the dome."));
+        assert!(line(&location, &world, &mut StepRng::new(u64::MAX, 0)).is_none());
+
+        location.ambience.chance = 0.0;
+        assert!(line(&location, &world, &mut StepRng::new(0, 0)).is_none());
+    }
+}
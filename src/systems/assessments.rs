@@ -0,0 +1,509 @@
+//! Skill challenge assessments for theory mastery
+//!
+//! Assessments are optional, multi-step puzzles that test the scientific
+//! concepts behind a theory. Passing one grants bonus understanding and a
+//! certification recognized by the Magisters' Council.
+
+use crate::core::player::{Item, ItemType, Player};
+use crate::systems::factions::FactionId;
+use crate::systems::knowledge::{LearningActivity, LearningMethod};
+use crate::GameResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Minimum understanding before a theory can be assessed
+pub const MIN_ASSESSMENT_UNDERSTANDING: f32 = 0.3;
+/// Wrong answers allowed before an assessment is failed
+pub const MAX_ASSESSMENT_MISTAKES: i32 = 2;
+/// Reputation granted by the Magisters' Council for a new certification
+const COUNCIL_CERTIFICATION_REPUTATION: i32 = 5;
+
+/// One step of a multi-step assessment puzzle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssessmentStep {
+    /// Question or scenario presented to the player
+    pub prompt: String,
+    /// Words or phrases that count as a correct answer
+    pub accepted_answers: Vec<String>,
+    /// Wrong options the question offers; an answer naming one isn't accepted
+    #[serde(default)]
+    pub rejected_answers: Vec<String>,
+    /// Explanation shown after the step is answered
+    pub explanation: String,
+}
+
+/// Assessment definition for a single theory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Assessment {
+    pub theory_id: String,
+    pub title: String,
+    pub steps: Vec<AssessmentStep>,
+    /// Understanding awarded on passing
+    pub bonus_understanding: f32,
+    /// Name of the certification item awarded on passing
+    pub certification_name: String,
+}
+
+/// Assessment currently being taken by the player
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveAssessment {
+    pub theory_id: String,
+    /// Index of the step awaiting an answer
+    pub current_step: usize,
+    /// Wrong answers given so far
+    pub mistakes: i32,
+}
+
+/// Result of answering an assessment step
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssessmentOutcome {
+    /// Answer accepted, another step follows
+    Correct,
+    /// Answer rejected, the step may be retried
+    Incorrect,
+    /// All steps answered, certification awarded
+    Passed,
+    /// Too many mistakes, assessment ended
+    Failed,
+}
+
+/// Catalog of available assessments
+#[derive(Debug, Clone)]
+pub struct AssessmentSystem {
+    assessments: HashMap<String, Assessment>,
+}
+
+impl AssessmentSystem {
+    /// Create the assessment catalog with built-in puzzles
+    pub fn new() -> Self {
+        let mut assessments = HashMap::new();
+        for assessment in default_assessments() {
+            assessments.insert(assessment.theory_id.clone(), assessment);
+        }
+        Self { assessments }
+    }
+
+    /// Get the assessment for a theory
+    pub fn get_assessment(&self, theory_id: &str) -> Option<&Assessment> {
+        self.assessments.get(theory_id)
+    }
+
+    /// List assessments the player is eligible to attempt
+    pub fn available_assessments(&self, player: &Player) -> Vec<&Assessment> {
+        let mut available: Vec<&Assessment> = self.assessments
+            .values()
+            .filter(|a| player.theory_understanding(&a.theory_id) >= MIN_ASSESSMENT_UNDERSTANDING)
+            .filter(|a| !player.has_certification(&a.theory_id))
+            .collect();
+        available.sort_by(|a, b| a.theory_id.cmp(&b.theory_id));
+        available
+    }
+
+    /// Begin an assessment, returning the first prompt
+    pub fn start(&self, player: &mut Player, theory_id: &str) -> GameResult<String> {
+        let assessment = self.get_assessment(theory_id)
+            .ok_or_else(|| crate::GameError::ContentNotFound(format!("No assessment exists for '{}'", theory_id)))?;
+
+        if player.has_certification(theory_id) {
+            return Err(crate::GameError::InvalidCommand(format!("You are already certified in {}.", assessment.title)).into());
+        }
+
+        if player.theory_understanding(theory_id) < MIN_ASSESSMENT_UNDERSTANDING {
+            return Err(crate::GameError::InsufficientResources(format!(
+                "You need at least {:.0}% understanding of this theory before being assessed.",
+                MIN_ASSESSMENT_UNDERSTANDING * 100.0
+            )).into());
+        }
+
+        player.knowledge.active_assessment = Some(ActiveAssessment {
+            theory_id: theory_id.to_string(),
+            current_step: 0,
+            mistakes: 0,
+        });
+
+        Ok(format!(
+            "=== ASSESSMENT: {} ===\n{} steps. You may make {} mistakes.\n\nStep 1: {}",
+            assessment.title,
+            assessment.steps.len(),
+            MAX_ASSESSMENT_MISTAKES,
+            assessment.steps[0].prompt
+        ))
+    }
+
+    /// Show the prompt for the assessment in progress
+    pub fn current_prompt(&self, player: &Player) -> Option<String> {
+        let active = player.knowledge.active_assessment.as_ref()?;
+        let assessment = self.get_assessment(&active.theory_id)?;
+        let step = assessment.steps.get(active.current_step)?;
+        Some(format!("{} - Step {}: {}", assessment.title, active.current_step + 1, step.prompt))
+    }
+
+    /// Answer the current step of the active assessment
+    pub fn answer(&self, player: &mut Player, answer: &str) -> GameResult<(AssessmentOutcome, String)> {
+        let mut active = player.knowledge.active_assessment.clone()
+            .ok_or_else(|| crate::GameError::InvalidCommand("You are not taking an assessment.".to_string()))?;
+        let assessment = self.get_assessment(&active.theory_id)
+            .ok_or_else(|| crate::GameError::ContentNotFound(format!("No assessment exists for '{}'", active.theory_id)))?;
+        let step = &assessment.steps[active.current_step];
+
+        let correct = answer_is_correct(answer, step);
+
+        if !correct {
+            active.mistakes += 1;
+            if active.mistakes > MAX_ASSESSMENT_MISTAKES {
+                player.knowledge.active_assessment = None;
+                return Ok((AssessmentOutcome::Failed, format!(
+                    "Incorrect. {}\n\nThe assessment has ended. Study further and try again.",
+                    step.explanation
+                )));
+            }
+            let remaining = MAX_ASSESSMENT_MISTAKES - active.mistakes;
+            player.knowledge.active_assessment = Some(active);
+            return Ok((AssessmentOutcome::Incorrect, format!(
+                "That isn't right. {} mistake(s) remaining.\n\nStep: {}",
+                remaining, step.prompt
+            )));
+        }
+
+        active.current_step += 1;
+        if let Some(next) = assessment.steps.get(active.current_step) {
+            let message = format!(
+                "Correct. {}\n\nStep {}: {}",
+                step.explanation, active.current_step + 1, next.prompt
+            );
+            player.knowledge.active_assessment = Some(active);
+            return Ok((AssessmentOutcome::Correct, message));
+        }
+
+        player.knowledge.active_assessment = None;
+        self.award_certification(player, assessment)?;
+
+        Ok((AssessmentOutcome::Passed, format!(
+            "Correct. {}\n\nAssessment passed! Your understanding of the theory deepens (+{:.0}%).\n\
+             You receive: {}. The Magisters' Council takes note of your certification.",
+            step.explanation,
+            assessment.bonus_understanding * 100.0,
+            assessment.certification_name
+        )))
+    }
+
+    /// Grant understanding, certification item and Council recognition
+    fn award_certification(&self, player: &mut Player, assessment: &Assessment) -> GameResult<()> {
+        let activity = LearningActivity {
+            theory_id: assessment.theory_id.clone(),
+            method: LearningMethod::Study,
+            duration: 30,
+            success_rate: 1.0,
+            experience_gained: 50,
+            understanding_gained: assessment.bonus_understanding,
+            resources_used: HashMap::new(),
+            side_effects: vec!["Passed theory assessment".to_string()],
+        };
+        player.update_theory_progress(&activity)?;
+
+        player.inventory.items.push(Item {
+            name: assessment.certification_name.clone(),
+            description: format!("An official certificate attesting mastery of {}.", assessment.title),
+            item_type: ItemType::Artifact(format!("certification:{}", assessment.theory_id)),
+        });
+        player.knowledge.certifications.push(assessment.theory_id.clone());
+        player.modify_faction_reputation(FactionId::MagistersCouncil, COUNCIL_CERTIFICATION_REPUTATION);
+
+        Ok(())
+    }
+}
+
+impl Default for AssessmentSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether an answer settles on an accepted answer: it has to name one as
+/// whole words, and names no rejected option or other number alongside it
+fn answer_is_correct(answer: &str, step: &AssessmentStep) -> bool {
+    let words = words(answer);
+    if !step.accepted_answers.iter().any(|accepted| contains_phrase(&words, accepted)) {
+        return false;
+    }
+    if step.rejected_answers.iter().any(|rejected| contains_phrase(&words, rejected)) {
+        return false;
+    }
+    // Listing numbers until one is right isn't an answer
+    !words.iter().any(|word| word.parse::<f64>().is_ok() && !step.accepted_answers.contains(word))
+}
+
+/// Whether the answer's words include a keyword or phrase as whole words
+fn contains_phrase(words: &[String], phrase: &str) -> bool {
+    let phrase = self::words(phrase);
+    !phrase.is_empty() && words.windows(phrase.len()).any(|window| window == phrase.as_slice())
+}
+
+/// The lowercase words of an answer
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn step(prompt: &str, accepted: &[&str], rejected: &[&str], explanation: &str) -> AssessmentStep {
+    AssessmentStep {
+        prompt: prompt.to_string(),
+        accepted_answers: accepted.iter().map(|s| s.to_string()).collect(),
+        rejected_answers: rejected.iter().map(|s| s.to_string()).collect(),
+        explanation: explanation.to_string(),
+    }
+}
+
+/// Built-in assessments for foundation and application theories
+fn default_assessments() -> Vec<Assessment> {
+    vec![
+        Assessment {
+            theory_id: "harmonic_fundamentals".to_string(),
+            title: "Harmonic Fundamentals".to_string(),
+            steps: vec![
+                step(
+                    "A quartz crystal rings at frequency 4. Which frequency should a second crystal have to resonate with it most strongly?",
+                    &["4", "four"],
+                    &["two", "eight"],
+                    "Sympathetic resonance is strongest when frequencies match exactly.",
+                ),
+                step(
+                    "Energy flows from one crystal into another. Can the second crystal end up with more energy than was supplied?",
+                    &["no", "cannot", "conserved", "conserve", "conserves", "conservation"],
+                    &["yes"],
+                    "Energy is conserved; resonance transfers energy but never creates it.",
+                ),
+                step(
+                    "Doubling a frequency produces a note one ____ higher. (One word)",
+                    &["octave"],
+                    &[],
+                    "A 2:1 frequency ratio is an octave, the simplest harmonic relationship.",
+                ),
+            ],
+            bonus_understanding: 0.1,
+            certification_name: "Certificate of Harmonic Fundamentals".to_string(),
+        },
+        Assessment {
+            theory_id: "crystal_structures".to_string(),
+            title: "Crystal Lattice Theory".to_string(),
+            steps: vec![
+                step(
+                    "What is the repeating three-dimensional arrangement of atoms in a crystal called?",
+                    &["lattice"],
+                    &[],
+                    "A crystal lattice is the periodic arrangement of atoms that gives crystals their properties.",
+                ),
+                step(
+                    "Two crystals are identical except one has more impurities. Which conducts resonance more efficiently: the pure or impure one?",
+                    &["pure"],
+                    &["impure"],
+                    "Impurities are defects that scatter energy, so higher purity means better conductivity.",
+                ),
+                step(
+                    "Repeated heavy use slowly damages a crystal's structure. What property of your crystals tracks this damage?",
+                    &["integrity"],
+                    &[],
+                    "Integrity measures accumulated structural damage; degraded crystals channel less power.",
+                ),
+            ],
+            bonus_understanding: 0.1,
+            certification_name: "Certificate of Crystal Lattice Theory".to_string(),
+        },
+        Assessment {
+            theory_id: "mental_resonance".to_string(),
+            title: "Mental Resonance Theory".to_string(),
+            steps: vec![
+                step(
+                    "Casting drains a practitioner's mental energy. What accumulates alongside it and reduces effective energy?",
+                    &["fatigue"],
+                    &[],
+                    "Fatigue builds with exertion and reduces the energy you can effectively use.",
+                ),
+                step(
+                    "Which attribute determines a practitioner's maximum mental energy?",
+                    &["mental acuity", "acuity"],
+                    &["sensitivity", "strength"],
+                    "Maximum energy scales with mental acuity.",
+                ),
+                step(
+                    "Name one activity that reduces fatigue without consuming resources.",
+                    &["rest", "resting", "meditate", "meditating", "meditation", "sleep", "sleeping"],
+                    &[],
+                    "Rest and meditation allow the mind to recover from resonance strain.",
+                ),
+            ],
+            bonus_understanding: 0.1,
+            certification_name: "Certificate of Mental Resonance".to_string(),
+        },
+        Assessment {
+            theory_id: "light_manipulation".to_string(),
+            title: "Electromagnetic Spectrum Control".to_string(),
+            steps: vec![
+                step(
+                    "Light is a wave. If its wavelength gets shorter, does its frequency go up or down?",
+                    &["up", "higher", "increase", "increases", "rises"],
+                    &["down", "lower", "decrease", "decreases", "falls"],
+                    "For waves of constant speed, frequency and wavelength are inversely proportional.",
+                ),
+                step(
+                    "Which end of the visible spectrum has the higher frequency: red or violet?",
+                    &["violet"],
+                    &["red"],
+                    "Violet light has the shortest visible wavelength and therefore the highest frequency.",
+                ),
+                step(
+                    "Light in which every wave is in phase with the others is called ____ light. (One word)",
+                    &["coherent"],
+                    &["incoherent"],
+                    "Coherent light, like a laser, keeps a fixed phase relationship between waves.",
+                ),
+            ],
+            bonus_understanding: 0.1,
+            certification_name: "Certificate of Spectrum Control".to_string(),
+        },
+        Assessment {
+            theory_id: "bio_resonance".to_string(),
+            title: "Biological Sympathetic Healing".to_string(),
+            steps: vec![
+                step(
+                    "Nerve signals in living tissue are carried by what kind of energy?",
+                    &["electric", "electrical", "electricity", "bioelectric"],
+                    &["chemical", "magnetic"],
+                    "Nerves transmit bioelectric impulses, which healing resonance can couple with.",
+                ),
+                step(
+                    "Healing works best when the spell frequency matches or mismatches the tissue's natural frequency?",
+                    &["match", "matches", "matching", "matched"],
+                    &["mismatch", "mismatches", "mismatching", "mismatched"],
+                    "Matching frequencies maximizes energy transfer into the target tissue.",
+                ),
+                step(
+                    "What is the smallest living unit that healing magic ultimately acts on?",
+                    &["cell", "cells"],
+                    &["atom", "atoms"],
+                    "Tissue regeneration happens one cell at a time.",
+                ),
+            ],
+            bonus_understanding: 0.1,
+            certification_name: "Certificate of Sympathetic Healing".to_string(),
+        },
+        Assessment {
+            theory_id: "detection_arrays".to_string(),
+            title: "Magical Signature Analysis".to_string(),
+            steps: vec![
+                step(
+                    "Magical signatures fade over time. Is an older signature stronger or weaker?",
+                    &["weak", "weaker"],
+                    &["strong", "stronger"],
+                    "Signatures decay as they age, like any dissipating wave.",
+                ),
+                step(
+                    "Unwanted background energy that obscures a signal is called what?",
+                    &["interference", "noise"],
+                    &[],
+                    "Interference (noise) lowers the signal-to-noise ratio of a detection array.",
+                ),
+                step(
+                    "How many detection points do you need, at minimum, to triangulate a source on a flat map?",
+                    &["3", "three"],
+                    &["two", "four"],
+                    "Three non-collinear measurements fix a position in two dimensions.",
+                ),
+            ],
+            bonus_understanding: 0.1,
+            certification_name: "Certificate of Signature Analysis".to_string(),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ready_player() -> Player {
+        let mut player = Player::new("Test".to_string());
+        player.knowledge.theories.insert("harmonic_fundamentals".to_string(), 0.5);
+        player
+    }
+
+    fn step_of(theory_id: &str, index: usize) -> AssessmentStep {
+        AssessmentSystem::new().get_assessment(theory_id).unwrap().steps[index].clone()
+    }
+
+    #[test]
+    fn test_answer_matching() {
+        let conserved = step_of("harmonic_fundamentals", 1);
+        assert!(answer_is_correct("Energy is conserved", &conserved));
+        assert!(answer_is_correct("No.", &conserved));
+        assert!(!answer_is_correct("I don't know", &conserved));
+        assert!(answer_is_correct("Mental acuity", &step_of("mental_resonance", 1)));
+    }
+
+    #[test]
+    fn test_answers_that_hedge_or_list_options_are_rejected() {
+        // Keywords have to be whole words
+        let purity = step_of("crystal_structures", 1);
+        assert!(answer_is_correct("the pure one", &purity));
+        assert!(!answer_is_correct("impure", &purity));
+        assert!(!answer_is_correct("pure or impure", &purity));
+        assert!(!answer_is_correct("purely", &purity));
+
+        // Naming both options given in the question
+        assert!(answer_is_correct("violet", &step_of("light_manipulation", 1)));
+        assert!(!answer_is_correct("red or violet", &step_of("light_manipulation", 1)));
+        assert!(!answer_is_correct("up or down", &step_of("light_manipulation", 0)));
+        assert!(!answer_is_correct("mismatch", &step_of("bio_resonance", 1)));
+
+        // Listing numbers until one is right
+        let frequency = step_of("harmonic_fundamentals", 0);
+        assert!(answer_is_correct("frequency 4", &frequency));
+        assert!(!answer_is_correct("1 2 3 4 5 6 7 8", &frequency));
+        assert!(!answer_is_correct("4 or 8", &frequency));
+        assert!(!answer_is_correct("44", &frequency));
+    }
+
+    #[test]
+    fn test_start_requires_understanding() {
+        let system = AssessmentSystem::new();
+        let mut player = Player::new("Test".to_string());
+        assert!(system.start(&mut player, "harmonic_fundamentals").is_err());
+        assert!(system.start(&mut ready_player(), "harmonic_fundamentals").is_ok());
+    }
+
+    #[test]
+    fn test_passing_grants_certification() {
+        let system = AssessmentSystem::new();
+        let mut player = ready_player();
+        let council_before = player.faction_reputation(FactionId::MagistersCouncil);
+
+        system.start(&mut player, "harmonic_fundamentals").unwrap();
+        assert_eq!(system.answer(&mut player, "4").unwrap().0, AssessmentOutcome::Correct);
+        assert_eq!(system.answer(&mut player, "maybe").unwrap().0, AssessmentOutcome::Incorrect);
+        assert_eq!(system.answer(&mut player, "No, energy is conserved").unwrap().0, AssessmentOutcome::Correct);
+        assert_eq!(system.answer(&mut player, "an octave").unwrap().0, AssessmentOutcome::Passed);
+
+        assert!(player.has_certification("harmonic_fundamentals"));
+        assert!(player.knowledge.active_assessment.is_none());
+        assert!(player.theory_understanding("harmonic_fundamentals") > 0.5);
+        assert!(player.inventory.items.iter().any(|i| i.name == "Certificate of Harmonic Fundamentals"));
+        assert!(player.faction_reputation(FactionId::MagistersCouncil) > council_before);
+        assert!(system.start(&mut player, "harmonic_fundamentals").is_err());
+    }
+
+    #[test]
+    fn test_too_many_mistakes_fails() {
+        let system = AssessmentSystem::new();
+        let mut player = ready_player();
+
+        system.start(&mut player, "harmonic_fundamentals").unwrap();
+        for _ in 0..MAX_ASSESSMENT_MISTAKES {
+            assert_eq!(system.answer(&mut player, "wrong").unwrap().0, AssessmentOutcome::Incorrect);
+        }
+        assert_eq!(system.answer(&mut player, "wrong").unwrap().0, AssessmentOutcome::Failed);
+        assert!(player.knowledge.active_assessment.is_none());
+        assert!(!player.has_certification("harmonic_fundamentals"));
+    }
+}
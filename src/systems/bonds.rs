@@ -0,0 +1,245 @@
+//! Deeper bonds with a few key NPCs
+//!
+//! Elara, Dr. Felix and Echo have more to them than an ordinary relationship
+//! stage. Once one of them counts the player as a friend, the player can
+//! choose to spend time with them, at most once a day, and each time the
+//! bond deepens and a milestone scene plays. Those open to it can be courted
+//! instead, which changes how the last scene goes. The middle milestone opens
+//! a personal quest for that NPC, and the last waits until they trust the
+//! player completely.
+//!
+//! Each of them distrusts one faction. Growing too close to it strains the
+//! bond: it loses a milestone's depth, they think less of the player, and it
+//! can't deepen again until the player's standing with that faction falls back.
+//!
+//! Bonds are kept with the dialogue system, so they are saved with the game.
+
+use crate::systems::factions::FactionId;
+use crate::systems::relationships::RelationshipStage;
+use crate::GameResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Milestones on each bond track
+pub const MAX_DEPTH: usize = 3;
+/// Depth at which the NPC's personal quest opens
+pub const QUEST_DEPTH: usize = 2;
+/// Standing with the faction an NPC distrusts at which the bond is strained
+pub const RIVAL_STANDING: i32 = 40;
+/// Affinity for an afternoon spent together, and the minutes it takes
+pub const TIME_TOGETHER: i32 = 3;
+pub const TIME_TOGETHER_MINUTES: i32 = 180;
+/// Affinity lost when a bond is strained by the player's loyalties
+pub const STRAINED: i32 = -10;
+
+/// Whether a bond is a friendship or a romance
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BondKind {
+    #[default]
+    Friendship,
+    Romance,
+}
+
+impl BondKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BondKind::Friendship => "friendship",
+            BondKind::Romance => "romance",
+        }
+    }
+}
+
+/// The scenes on an NPC's bond track and what pulls against it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BondTrack {
+    /// Whether they can be courted rather than only befriended
+    pub romance: bool,
+    /// The faction they distrust, and what they say when the player grows close to it
+    pub rival: FactionId,
+    pub strained: &'static str,
+    pub mended: &'static str,
+    /// One scene per milestone, the last as a friendship
+    pub scenes: [&'static str; MAX_DEPTH],
+    /// The last scene as a romance
+    pub romance_scene: &'static str,
+}
+
+/// NPCs with a bond track
+pub fn bond_track(npc_id: &str) -> Option<BondTrack> {
+    match npc_id {
+        "tutorial_assistant" => Some(BondTrack {
+            romance: true,
+            rival: FactionId::UndergroundNetwork,
+            strained: "Elara has heard how close you've grown to the Underground Network. \"They burn students out. I've watched it happen, and I won't watch it happen to you.\"",
+            mended: "Elara seems to have made her peace with the company you keep.",
+            scenes: [
+                "Elara walks you through the practice circles after the last students have gone, and for once she talks about herself: she failed her first resonance exam three times.",
+                "Elara shows you the notebook she kept as a student, its margins crowded with corrections in her own hand. \"There's a lesson in here I never learned how to teach. Maybe you can help me find it.\"",
+                "Elara laughs until she has to sit down on the chamber steps. \"Whatever you end up doing, come back and tell me about it. I mean that.\"",
+            ],
+            romance_scene: "Elara takes your hand under the chamber's humming crystals and doesn't let go. \"I keep telling myself students move on. I'd rather you didn't.\"",
+        }),
+        "dr_felix" => Some(BondTrack {
+            romance: false,
+            rival: FactionId::IndustrialConsortium,
+            strained: "Felix has heard you've been working with the Consortium. \"They patent living things and call it progress. I thought you understood that.\"",
+            mended: "Felix has stopped glancing at your hands for Consortium ink.",
+            scenes: [
+                "Felix spends an afternoon teaching you to sketch lichen under the garden lamps, and talks the whole time about the mentor who taught him.",
+                "Felix admits he lost a season of field notes years ago, the best work he ever did, somewhere in the Archives' stacks. \"I stopped looking. I'd like to start again.\"",
+                "Felix gives you a pressed crystal-fern from his first expedition. \"For my research partner. The only one I've ever had who asked the right questions.\"",
+            ],
+            romance_scene: "",
+        }),
+        "echo_voidwalker" => Some(BondTrack {
+            romance: true,
+            rival: FactionId::MagistersCouncil,
+            strained: "Echo has heard the Council speaks well of you now. \"Funny. They spoke well of me too, right up until they wrote me out of the records.\"",
+            mended: "Echo seems willing to believe you're not the Council's creature after all.",
+            scenes: [
+                "Echo lets you sit with them at the edge of the Site while the air folds and unfolds, saying nothing, which from Echo is a kind of trust.",
+                "Echo tells you they had another name once, before the Council struck it from the Observatory's registers. \"I want to know if anyone kept a copy.\"",
+                "Echo's outline steadies for the first time since you've known them. \"Stay a while. You're the only thing out here that doesn't shift.\"",
+            ],
+            romance_scene: "Echo's outline steadies, and they lean into you until the distortion settles around you both. \"Stay. You're the only thing out here that doesn't shift.\"",
+        }),
+        _ => None,
+    }
+}
+
+/// World flag set when an NPC's personal quest opens
+pub fn quest_flag(npc_id: &str) -> String {
+    format!("bond_{}", npc_id)
+}
+
+/// How far the player has come with one NPC on their track
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bond {
+    pub kind: BondKind,
+    /// Milestones reached, up to MAX_DEPTH
+    pub depth: usize,
+    /// Game day the player last spent time with them
+    pub last_day: Option<i32>,
+    /// Whether the player's loyalties are pulling against the bond
+    pub strained: bool,
+}
+
+/// A milestone reached by spending time with someone
+#[derive(Debug, Clone, PartialEq)]
+pub struct Milestone {
+    pub scene: String,
+    /// World flag to set, when the milestone opens their personal quest
+    pub flag: Option<String>,
+}
+
+/// Every bond the player has started, by NPC ID
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bonds {
+    bonds: HashMap<String, Bond>,
+}
+
+impl Bonds {
+    pub fn get(&self, npc_id: &str) -> Option<&Bond> {
+        self.bonds.get(npc_id)
+    }
+
+    /// Spend the day's time with an NPC as a friend or, if courting, a
+    /// romance, deepening the bond to its next milestone
+    pub fn spend_time(&mut self, npc_id: &str, name: &str, stage: RelationshipStage, courting: bool, day: i32) -> GameResult<Milestone> {
+        let track = bond_track(npc_id)
+            .ok_or_else(|| crate::GameError::InvalidCommand(format!("{} is pleasant enough, but keeps their distance.", name)))?;
+        if courting && !track.romance {
+            return Err(crate::GameError::InvalidCommand(format!("{} doesn't seem to see you that way.", name)).into());
+        }
+        let bond = self.bonds.get(npc_id);
+        let depth = bond.map_or(0, |bond| bond.depth);
+        let needed = if depth + 1 == MAX_DEPTH { RelationshipStage::Confidant } else { RelationshipStage::Friend };
+        if depth >= MAX_DEPTH {
+            return Err(crate::GameError::InvalidCommand(format!("You and {} already know each other as well as two people can.", name)).into());
+        }
+        if bond.is_some_and(|bond| bond.strained) {
+            return Err(crate::GameError::InvalidCommand(format!("{} makes an excuse. Your loyalties are still between you.", name)).into());
+        }
+        if bond.is_some_and(|bond| bond.last_day == Some(day)) {
+            return Err(crate::GameError::InvalidCommand(format!("You've already spent today with {}.", name)).into());
+        }
+        if stage < needed {
+            return Err(crate::GameError::InvalidCommand(format!(
+                "{} would need to count you as a {} before spending that kind of time with you.", name, needed.name()
+            )).into());
+        }
+
+        let bond = self.bonds.entry(npc_id.to_string()).or_default();
+        if courting {
+            bond.kind = BondKind::Romance;
+        }
+        bond.depth += 1;
+        bond.last_day = Some(day);
+        let scene = match bond.kind {
+            BondKind::Romance if bond.depth == MAX_DEPTH => track.romance_scene,
+            _ => track.scenes[bond.depth - 1],
+        };
+        Ok(Milestone {
+            scene: scene.to_string(),
+            flag: (bond.depth == QUEST_DEPTH).then(|| quest_flag(npc_id)),
+        })
+    }
+
+    /// Strain bonds with NPCs whose distrusted faction the player has grown
+    /// close to, and mend them once that standing falls back; returns each
+    /// NPC whose bond changed, whether it is now strained, and what they say
+    pub fn weigh_loyalties(&mut self, standings: &HashMap<FactionId, i32>) -> Vec<(String, bool, &'static str)> {
+        let mut changes = Vec::new();
+        for (npc_id, bond) in &mut self.bonds {
+            let Some(track) = bond_track(npc_id) else { continue };
+            let conflicted = standings.get(&track.rival).is_some_and(|&standing| standing >= RIVAL_STANDING);
+            if conflicted && !bond.strained {
+                bond.strained = true;
+                bond.depth = bond.depth.saturating_sub(1);
+                changes.push((npc_id.clone(), true, track.strained));
+            } else if !conflicted && bond.strained {
+                bond.strained = false;
+                changes.push((npc_id.clone(), false, track.mended));
+            }
+        }
+        changes.sort();
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bonds_deepen_through_milestones_and_strain_with_loyalties() {
+        let mut bonds = Bonds::default();
+        assert!(bonds.spend_time("warden_gareth", "Gareth", RelationshipStage::Confidant, false, 0).is_err());
+        assert!(bonds.spend_time("dr_felix", "Felix", RelationshipStage::Friend, true, 0).is_err());
+        assert!(bonds.spend_time("echo_voidwalker", "Echo", RelationshipStage::Acquaintance, false, 0).is_err());
+
+        // One milestone a day; the second opens their quest, the last needs complete trust
+        let first = bonds.spend_time("echo_voidwalker", "Echo", RelationshipStage::Friend, false, 0).unwrap();
+        assert_eq!(first.flag, None);
+        assert!(bonds.spend_time("echo_voidwalker", "Echo", RelationshipStage::Friend, false, 0).is_err());
+        let second = bonds.spend_time("echo_voidwalker", "Echo", RelationshipStage::Friend, true, 1).unwrap();
+        assert_eq!(second.flag.as_deref(), Some("bond_echo_voidwalker"));
+        assert!(bonds.spend_time("echo_voidwalker", "Echo", RelationshipStage::Friend, false, 2).is_err());
+
+        // Growing close to the Council strains the bond until that standing falls back
+        let council = HashMap::from([(FactionId::MagistersCouncil, RIVAL_STANDING)]);
+        let strained = bonds.weigh_loyalties(&council);
+        assert_eq!(strained.len(), 1);
+        assert!(strained[0].1);
+        assert_eq!(bonds.get("echo_voidwalker").unwrap().depth, 1);
+        assert!(bonds.weigh_loyalties(&council).is_empty());
+        assert!(bonds.spend_time("echo_voidwalker", "Echo", RelationshipStage::Confidant, false, 3).is_err());
+
+        assert!(!bonds.weigh_loyalties(&HashMap::new())[0].1);
+        bonds.spend_time("echo_voidwalker", "Echo", RelationshipStage::Confidant, false, 4).unwrap();
+        let last = bonds.spend_time("echo_voidwalker", "Echo", RelationshipStage::Confidant, false, 5).unwrap();
+        assert_eq!(bonds.get("echo_voidwalker").unwrap().kind, BondKind::Romance);
+        assert!(last.scene.contains("lean into you"));
+        assert!(bonds.spend_time("echo_voidwalker", "Echo", RelationshipStage::Confidant, false, 6).is_err());
+    }
+}
@@ -0,0 +1,211 @@
+//! In-game calculator for the mathematics behind the magic
+//!
+//! The `calculate` command runs the game's own formulas on the player's
+//! current situation and shows the working, so the numbers behind a cast or a
+//! study plan can be checked rather than guessed:
+//! - resonance: how well a crystal frequency matches a spell
+//! - cost: odds and expected costs of casting a spell right now
+//! - learning: study time needed to reach a level of understanding
+
+use crate::core::{Player, WorldState};
+use crate::systems::knowledge::{KnowledgeSystem, STUDY_SESSION_MINUTES};
+use crate::systems::magic::calculation_engine::{frequency_modifier, resonance_match};
+use crate::systems::magic::MagicSystem;
+use crate::GameResult;
+
+/// Spells the calculator knows about
+const SPELLS: [&str; 5] = ["light", "healing", "detection", "manipulation", "communication"];
+/// Share of the costs a failed cast still takes
+const FAILURE_COST_SHARE: f32 = 0.5;
+
+/// A calculation the player asked for
+#[derive(Debug, Clone, PartialEq)]
+pub enum Calculation {
+    /// Resonance match between a crystal frequency and a spell
+    Resonance { spell: String, frequency: Option<i32> },
+    /// Odds and costs of casting a spell
+    SpellCost { spell: String },
+    /// Study needed to reach a target understanding of a theory
+    Learning { theory: String, target: f32 },
+}
+
+impl Calculation {
+    /// Parse the words after `calculate`
+    pub fn parse(query: &str) -> GameResult<Self> {
+        let query = query.trim().to_lowercase();
+        let (kind, rest) = query.split_once(' ').unwrap_or((query.as_str(), ""));
+        let rest = rest.trim();
+        if rest.is_empty() {
+            return Err(crate::GameError::InvalidInput(usage()).into());
+        }
+
+        match kind {
+            "resonance" | "match" => {
+                let (spell, frequency) = match rest.rsplit_once(' ') {
+                    Some((spell, number)) => match number.parse::<i32>() {
+                        Ok(frequency) if (1..=10).contains(&frequency) => (spell, Some(frequency)),
+                        Ok(_) => return Err(crate::GameError::InvalidInput("Crystal frequencies run from 1 to 10.".to_string()).into()),
+                        Err(_) => (rest, None),
+                    },
+                    None => (rest, None),
+                };
+                Ok(Calculation::Resonance { spell: spell.trim().to_string(), frequency })
+            }
+            "cost" | "spell" | "cast" => Ok(Calculation::SpellCost { spell: rest.to_string() }),
+            "learning" | "study" => {
+                let (theory, target) = match rest.rsplit_once(" to ") {
+                    Some((theory, percent)) => {
+                        let percent = percent.trim().trim_end_matches('%').parse::<f32>().map_err(|_| {
+                            crate::GameError::InvalidInput(format!("'{}' is not a percentage.", percent.trim()))
+                        })?;
+                        (theory, (percent / 100.0).clamp(0.0, 1.0))
+                    }
+                    None => (rest, 1.0),
+                };
+                Ok(Calculation::Learning { theory: theory.trim().to_string(), target })
+            }
+            _ => Err(crate::GameError::InvalidInput(usage()).into()),
+        }
+    }
+}
+
+/// What the calculator can work out
+pub fn usage() -> String {
+    "The calculator runs the game's own formulas on your situation:\n\
+     • calculate resonance <spell> [frequency] - Match between a crystal frequency and a spell\n\
+     • calculate cost <spell> - Odds and expected costs of casting a spell now\n\
+     • calculate learning <theory> [to <percent>] - Study time to reach an understanding\n\n\
+     Examples:\n\
+     • calculate resonance healing 6\n\
+     • calculate cost light\n\
+     • calculate learning harmonic fundamentals to 50%".to_string()
+}
+
+/// Run a calculation
+pub fn calculate(
+    query: &str,
+    player: &Player,
+    world: &WorldState,
+    magic_system: &MagicSystem,
+    knowledge_system: &KnowledgeSystem,
+) -> GameResult<String> {
+    match Calculation::parse(query)? {
+        Calculation::Resonance { spell, frequency } => resonance(&spell, frequency, player, magic_system),
+        Calculation::SpellCost { spell } => spell_cost(&spell, player, world, magic_system),
+        Calculation::Learning { theory, target } => learning(&theory, target, player, knowledge_system),
+    }
+}
+
+fn optimal_frequency(spell: &str, magic_system: &MagicSystem) -> GameResult<i32> {
+    magic_system.optimal_frequency(spell).ok_or_else(|| {
+        crate::GameError::ContentNotFound(format!("Unknown spell '{}'. Try one of: {}.", spell, SPELLS.join(", "))).into()
+    })
+}
+
+/// Resonance match, defaulting to the equipped crystal's frequency
+fn resonance(spell: &str, frequency: Option<i32>, player: &Player, magic_system: &MagicSystem) -> GameResult<String> {
+    let optimal = optimal_frequency(spell, magic_system)?;
+    let frequency = match frequency.or_else(|| player.active_crystal().map(|crystal| crystal.frequency)) {
+        Some(frequency) => frequency,
+        None => return Err(crate::GameError::InvalidInput(
+            "No crystal equipped. Give a frequency, e.g. 'calculate resonance light 4'.".to_string()
+        ).into()),
+    };
+
+    let distance = (frequency - optimal).abs();
+    let mut output = format!("=== RESONANCE: {} ===\n", spell.to_uppercase());
+    output.push_str(&format!(
+        "Crystal frequency {} vs optimal {}: {} off, {:.0}% match, {:+.0}% success\n\n",
+        frequency, optimal, distance, resonance_match(frequency, optimal), frequency_modifier(distance)
+    ));
+    output.push_str("Formula: success modifier by distance from the optimal frequency\n");
+    for step in 0..=5 {
+        let marker = if step == distance.min(5) { " <" } else { "" };
+        let label = if step == 5 { "5+".to_string() } else { step.to_string() };
+        output.push_str(&format!("  {:>2} off: {:+.0}%{}\n", label, frequency_modifier(step), marker));
+    }
+    output.push_str("Match = (modifier + 25) x 2, so a perfect match is 100% and 5 or more off is 0%.");
+    Ok(output)
+}
+
+/// Odds and expected costs of casting a spell right now
+fn spell_cost(spell: &str, player: &Player, world: &WorldState, magic_system: &MagicSystem) -> GameResult<String> {
+    optimal_frequency(spell, magic_system)?;
+    let estimate = magic_system.estimate_magic(spell, player, world)?;
+
+    let p = estimate.success_probability;
+    let expected = |cost: f32| cost * (p + (1.0 - p) * FAILURE_COST_SHARE);
+    let mut output = format!("=== CASTING COST: {} ===\n", spell.to_uppercase());
+    output.push_str(&estimate.explanation);
+    output.push_str(&format!(
+        "\n\nOn success: {} energy, {} fatigue, {:.2} crystal wear, power {:.2}\n\
+         On failure: half the energy, fatigue and crystal wear\n\
+         Expected cost: {:.1} energy, {:.1} fatigue, {:.2} crystal wear\n\
+         Expected = cost x (p + (1 - p) x 0.5), with p = {:.0}%\n\
+         Energy available: {}",
+        estimate.energy_cost, estimate.fatigue_cost, estimate.crystal_degradation, estimate.power_level,
+        expected(estimate.energy_cost as f32), expected(estimate.fatigue_cost as f32), expected(estimate.crystal_degradation),
+        p * 100.0, player.effective_mental_energy()
+    ));
+    Ok(output)
+}
+
+/// Study time to reach a target understanding of a theory
+fn learning(theory: &str, target: f32, player: &Player, knowledge_system: &KnowledgeSystem) -> GameResult<String> {
+    let theory = knowledge_system.find_theory(theory)
+        .ok_or_else(|| crate::GameError::ContentNotFound(format!("No theory called '{}'.", theory)))?;
+    let estimate = knowledge_system.estimate_study(&theory.id, player, target, STUDY_SESSION_MINUTES)?;
+    let current = player.theory_understanding(&theory.id);
+
+    let mut output = format!("=== LEARNING: {} ===\n", theory.name.to_uppercase());
+    output.push_str(&format!(
+        "Understanding {:.0}% -> {:.0}% (complexity {}, Mental Acuity {})\n",
+        current * 100.0, target * 100.0, theory.complexity_level, player.attributes.mental_acuity
+    ));
+    if current >= target {
+        output.push_str("You have already reached that understanding.\n");
+    } else if !estimate.reachable {
+        output.push_str("Study alone will not get you there; try experiments, observation or a mentor.\n");
+    } else {
+        output.push_str(&format!(
+            "Study: {} sessions of {} minutes ({:.1} hours), costing {} mental energy\n\
+             Next session gains about {:.1}%\n",
+            estimate.sessions, STUDY_SESSION_MINUTES, estimate.minutes as f32 / 60.0,
+            estimate.energy, estimate.first_session_gain * 100.0
+        ));
+    }
+    output.push_str(
+        "\nFormula per session:\n\
+         \x20 success = Mental Acuity / 100 x (1 - understanding / 2)\n\
+         \x20 experience = minutes x success x 10 x study multiplier\n\
+         \x20 understanding gained = experience / (complexity x 100), at most 20%\n\
+         Mixing learning methods earns breakthroughs; repeating one loses effectiveness."
+    );
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculation_parsing_and_resonance() {
+        assert_eq!(
+            Calculation::parse("resonance healing 6").unwrap(),
+            Calculation::Resonance { spell: "healing".to_string(), frequency: Some(6) }
+        );
+        assert_eq!(
+            Calculation::parse("Learning Harmonic Fundamentals to 50%").unwrap(),
+            Calculation::Learning { theory: "harmonic fundamentals".to_string(), target: 0.5 }
+        );
+        assert_eq!(Calculation::parse("cost light").unwrap(), Calculation::SpellCost { spell: "light".to_string() });
+        assert!(Calculation::parse("resonance light 12").is_err());
+        assert!(Calculation::parse("cost").is_err());
+
+        let magic_system = MagicSystem::new();
+        let player = Player::new("Test".to_string());
+        let output = resonance("healing", Some(6), &player, &magic_system).unwrap();
+        assert!(output.contains("Crystal frequency 6 vs optimal 7: 1 off, 80% match, +15% success"));
+        assert!(resonance("fireball", Some(6), &player, &magic_system).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! Discovery codex shared across saves
+//!
+//! Encyclopedic discoveries belong to the player, not to one character. The
+//! codex records three collections in a profile file beside the save files:
+//! - the lore codex: scientific concepts encountered
+//! - the bestiary: enemies met, with the most any character learned of them
+//! - the frequency compendium: the dominant frequency of each place visited
+//!
+//! Each save's discoveries are merged in when the game is saved or quit, so a
+//! new character starts with every page earlier characters filled in. Only
+//! knowledge carries over; nothing in the codex grants understanding or
+//! stats. A save can hide spoilers, limiting what it shows to the current
+//! character's own discoveries.
+
+use crate::core::{Player, WorldState};
+use crate::systems::combat::{BestiaryEntry, CombatSystem};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Codex file kept in the save directory
+pub const CODEX_FILE: &str = "codex.json";
+
+/// Every discovery made across saves
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Codex {
+    /// IDs of scientific concepts encountered
+    pub concepts: BTreeSet<String>,
+    /// Enemies met, by ID
+    pub enemies: BTreeMap<String, BestiaryEntry>,
+    /// Dominant frequencies heard, by location ID
+    pub frequencies: BTreeMap<String, i32>,
+}
+
+impl Codex {
+    /// Load the codex from a directory, empty if none has been written yet
+    pub fn load(directory: &Path) -> crate::GameResult<Self> {
+        let path = directory.join(CODEX_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to read codex: {}", e)))?;
+        serde_json::from_str(&json)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to parse codex: {}", e)).into())
+    }
+
+    /// Write the codex into a directory
+    pub fn save(&self, directory: &Path) -> crate::GameResult<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to serialize codex: {}", e)))?;
+        std::fs::write(directory.join(CODEX_FILE), json)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to write codex: {}", e)).into())
+    }
+
+    /// The current save's own discoveries
+    pub fn from_save(player: &Player, world: &WorldState, combat: &CombatSystem) -> Self {
+        Self {
+            concepts: player.knowledge.encountered_concepts.iter().cloned().collect(),
+            enemies: combat.bestiary().iter().map(|(id, entry)| (id.clone(), entry.clone())).collect(),
+            frequencies: world.locations.values()
+                .filter(|location| location.visited)
+                .filter_map(|location| location.magical_properties.dominant_frequency.map(|frequency| (location.id.clone(), frequency)))
+                .collect(),
+        }
+    }
+
+    /// Merge in another codex, keeping the most learned about each enemy;
+    /// returns whether anything was new
+    pub fn merge(&mut self, other: &Codex) -> bool {
+        let before = self.clone();
+        self.concepts.extend(other.concepts.iter().cloned());
+        for (id, entry) in &other.enemies {
+            let known = self.enemies.entry(id.clone()).or_default();
+            known.encounters = known.encounters.max(entry.encounters);
+            known.defeats = known.defeats.max(entry.defeats);
+            known.spared = known.spared.max(entry.spared);
+        }
+        self.frequencies.extend(other.frequencies.iter().map(|(id, frequency)| (id.clone(), *frequency)));
+        *self != before
+    }
+
+    /// Record the current save's discoveries in the codex kept in a directory
+    pub fn sync(directory: &Path, player: &Player, world: &WorldState, combat: &CombatSystem) -> crate::GameResult<()> {
+        let mut codex = Self::load(directory)?;
+        if codex.merge(&Self::from_save(player, world, combat)) {
+            codex.save(directory)?;
+        }
+        Ok(())
+    }
+
+    /// What a save may show: everything known, or only its own discoveries
+    /// when it hides spoilers
+    pub fn visible(directory: &Path, player: &Player, world: &WorldState, combat: &CombatSystem) -> Self {
+        let mut codex = Self::from_save(player, world, combat);
+        if !player.preferences.hide_codex_spoilers {
+            // A missing or unreadable codex just means nothing else is known
+            codex.merge(&Self::load(directory).unwrap_or_default());
+        }
+        codex
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_codex_carries_discoveries_to_new_characters() {
+        let directory = TempDir::new().unwrap();
+        let combat = CombatSystem::new();
+
+        let mut first = Player::new("Ada".to_string());
+        first.knowledge.encountered_concepts.push("wave_interference".to_string());
+        let mut world = WorldState::new();
+        let mut hall = crate::core::world_state::Location::new("hall".to_string(), "Hall".to_string(), "Echoing.".to_string());
+        hall.magical_properties.dominant_frequency = Some(4);
+        hall.visited = true;
+        world.add_location(hall);
+        Codex::sync(directory.path(), &first, &world, &combat).unwrap();
+
+        let mut second = Player::new("Bram".to_string());
+        let fresh = WorldState::new();
+        let shared = Codex::visible(directory.path(), &second, &fresh, &combat);
+        assert!(shared.concepts.contains("wave_interference"));
+        assert_eq!(shared.frequencies.get("hall"), Some(&4));
+
+        second.preferences.hide_codex_spoilers = true;
+        assert_eq!(Codex::visible(directory.path(), &second, &fresh, &combat), Codex::default());
+
+        // Syncing again with nothing new leaves the codex as it was
+        let mut codex = Codex::load(directory.path()).unwrap();
+        assert!(!codex.merge(&Codex::from_save(&first, &world, &combat)));
+    }
+}
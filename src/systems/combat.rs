@@ -5,15 +5,25 @@
 //! - Turn-based combat loop
 //! - Damage calculations using magic system
 //! - Enemy AI and decision making
+//! - Multi-phase boss fights scripted by health thresholds
 //! - Combat rewards and consequences
 
 use crate::core::{Player, WorldState};
 use crate::systems::magic::{MagicSystem, MagicResult};
 use crate::systems::factions::FactionId;
+use crate::core::health::InjuryKind;
+use crate::systems::combat_log::{CombatLog, DamageComponent, LogEvent};
+use crate::systems::wards::CombatWards;
+use crate::systems::enemy_ai::{ai_for, AiContext, Difficulty, EnemyAction};
+use crate::systems::defeat::{resolve_defeat, DefeatCause};
+use crate::systems::status_effects::{spell_status_ability, StatusAbility, StatusEffects, StatusKind};
+use crate::systems::companions::{enemy_initiative, Party, COMPANION_TARGET_CHANCE};
+use crate::systems::negotiation::{self, Tactic};
 use crate::GameResult;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 /// Difficulty tier for enemies
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -44,6 +54,78 @@ impl DifficultyTier {
     }
 }
 
+/// Combat temperament shaping how an enemy fights
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AiProfile {
+    /// Fights steadily and retreats when badly hurt
+    #[default]
+    Balanced,
+    /// Hits harder and rarely retreats
+    Aggressive,
+    /// Retreats early when the fight turns against it
+    Cautious,
+    /// Never retreats
+    Relentless,
+    /// Never retreats and hits harder the more it is hurt
+    Berserk,
+    /// Waits out defences and punishes a drained caster
+    Tactician,
+    /// Siphons the player's mental energy to mend itself
+    SpellThief,
+}
+
+impl AiProfile {
+    /// Parse an AI profile name
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "balanced" => Some(AiProfile::Balanced),
+            "aggressive" => Some(AiProfile::Aggressive),
+            "cautious" => Some(AiProfile::Cautious),
+            "relentless" => Some(AiProfile::Relentless),
+            "berserk" => Some(AiProfile::Berserk),
+            "tactician" => Some(AiProfile::Tactician),
+            "spell-thief" | "spell thief" | "spellthief" => Some(AiProfile::SpellThief),
+            _ => None,
+        }
+    }
+
+    /// Display name of the profile
+    pub fn name(&self) -> &'static str {
+        match self {
+            AiProfile::Balanced => "balanced",
+            AiProfile::Aggressive => "aggressive",
+            AiProfile::Cautious => "cautious",
+            AiProfile::Relentless => "relentless",
+            AiProfile::Berserk => "berserk",
+            AiProfile::Tactician => "tactician",
+            AiProfile::SpellThief => "spell-thief",
+        }
+    }
+
+    /// Multiplier applied to the enemy's attack damage
+    fn damage_multiplier(&self) -> f32 {
+        match self {
+            AiProfile::Aggressive => 1.2,
+            AiProfile::Berserk => 1.1,
+            AiProfile::Cautious => 0.9,
+            _ => 1.0,
+        }
+    }
+}
+
+impl DifficultyTier {
+    /// Parse a difficulty tier name
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "beginner" => Some(DifficultyTier::Beginner),
+            "intermediate" => Some(DifficultyTier::Intermediate),
+            "advanced" => Some(DifficultyTier::Advanced),
+            "boss" => Some(DifficultyTier::Boss),
+            _ => None,
+        }
+    }
+}
+
 /// Loot drop definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LootDrop {
@@ -52,6 +134,39 @@ pub struct LootDrop {
     pub quantity_range: (i32, i32),
 }
 
+fn default_phase_multiplier() -> f32 {
+    1.0
+}
+
+/// A stage of a multi-phase fight, entered when health falls to a threshold
+///
+/// Anything a phase leaves unset keeps its value from the previous phase.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnemyPhase {
+    /// Health fraction (0.0-1.0) at or below which the phase begins
+    pub health_threshold: f32,
+    pub name: String,
+    /// Narration when the phase begins
+    #[serde(default)]
+    pub announcement: String,
+    /// Resistances changed by this phase, by spell type
+    #[serde(default)]
+    pub resistances: HashMap<String, f32>,
+    #[serde(default)]
+    pub vulnerable_frequency: Option<u8>,
+    #[serde(default)]
+    pub ai_profile: Option<AiProfile>,
+    /// Replaces the enemy's status abilities when set
+    #[serde(default)]
+    pub status_abilities: Option<Vec<StatusAbility>>,
+    /// Multiplier on attack damage while the phase lasts
+    #[serde(default = "default_phase_multiplier")]
+    pub damage_multiplier: f32,
+    /// Whether the enemy throws off its status effects on entering the phase
+    #[serde(default)]
+    pub cleanse: bool,
+}
+
 /// Enemy definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Enemy {
@@ -68,6 +183,21 @@ pub struct Enemy {
     pub faction_affiliation: Option<FactionId>,
     /// Vulnerable frequency (takes extra damage from this crystal frequency)
     pub vulnerable_frequency: Option<u8>,
+    /// Combat temperament
+    #[serde(default)]
+    pub ai_profile: AiProfile,
+    /// Location IDs where this enemy is encountered
+    #[serde(default)]
+    pub habitats: Vec<String>,
+    /// Status effects this enemy's attacks can inflict
+    #[serde(default)]
+    pub status_abilities: Vec<StatusAbility>,
+    /// Scripted phases, ordered from highest health threshold to lowest
+    #[serde(default)]
+    pub phases: Vec<EnemyPhase>,
+    /// Number of phases entered so far this fight
+    #[serde(default)]
+    pub phases_entered: usize,
 }
 
 impl Enemy {
@@ -88,6 +218,11 @@ impl Enemy {
             experience_reward,
             faction_affiliation: None,
             vulnerable_frequency: None,
+            ai_profile: AiProfile::default(),
+            habitats: Vec::new(),
+            status_abilities: Vec::new(),
+            phases: Vec::new(),
+            phases_entered: 0,
         }
     }
 
@@ -119,6 +254,72 @@ impl Enemy {
         self
     }
 
+    /// Set combat temperament
+    pub fn with_ai_profile(mut self, profile: AiProfile) -> Self {
+        self.ai_profile = profile;
+        self
+    }
+
+    /// Give this enemy's attacks a chance to inflict a status effect
+    pub fn with_status_ability(mut self, kind: StatusKind, chance: f32, duration: u32) -> Self {
+        self.status_abilities.push(StatusAbility { kind, chance: chance.clamp(0.0, 1.0), duration });
+        self
+    }
+
+    /// Add a location where this enemy is found
+    pub fn with_habitat(mut self, location_id: &str) -> Self {
+        self.habitats.push(location_id.to_string());
+        self
+    }
+
+    /// Add a scripted phase, keeping phases ordered by threshold
+    pub fn with_phase(mut self, phase: EnemyPhase) -> Self {
+        self.phases.push(phase);
+        self.phases.sort_by(|a, b| b.health_threshold.total_cmp(&a.health_threshold));
+        self
+    }
+
+    /// The phase currently in effect, if any
+    pub fn current_phase(&self) -> Option<&EnemyPhase> {
+        self.phases_entered.checked_sub(1).and_then(|index| self.phases.get(index))
+    }
+
+    /// Damage multiplier from the current phase
+    pub fn phase_damage_multiplier(&self) -> f32 {
+        self.current_phase().map(|phase| phase.damage_multiplier).unwrap_or(1.0)
+    }
+
+    /// Enter every phase whose threshold health has fallen to
+    ///
+    /// Returns the phases entered, in order. A defeated enemy enters no phases.
+    pub fn advance_phases(&mut self) -> Vec<EnemyPhase> {
+        let mut entered = Vec::new();
+        while self.is_alive() {
+            let Some(phase) = self.phases.get(self.phases_entered).cloned() else {
+                break;
+            };
+            if self.health_percentage() > phase.health_threshold {
+                break;
+            }
+
+            for (spell_type, resistance) in &phase.resistances {
+                self.magical_resistance.insert(spell_type.clone(), resistance.clamp(0.0, 1.0));
+            }
+            if phase.vulnerable_frequency.is_some() {
+                self.vulnerable_frequency = phase.vulnerable_frequency;
+            }
+            if let Some(profile) = phase.ai_profile {
+                self.ai_profile = profile;
+            }
+            if let Some(abilities) = &phase.status_abilities {
+                self.status_abilities = abilities.clone();
+            }
+            self.phases_entered += 1;
+            entered.push(phase);
+        }
+        entered
+    }
+
     /// Take damage
     pub fn take_damage(&mut self, amount: i32) {
         self.health = (self.health - amount).max(0);
@@ -152,6 +353,13 @@ pub enum CombatAction {
     Flee,
 }
 
+/// Which side of an encounter an effect targets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combatant {
+    Player,
+    Enemy,
+}
+
 /// Combat outcome
 #[derive(Debug, Clone)]
 pub enum CombatOutcome {
@@ -161,8 +369,7 @@ pub enum CombatOutcome {
         faction_change: Option<(FactionId, i32)>,
     },
     Defeat {
-        energy_drain_percent: i32,
-        fatigue_increase: i32,
+        aftermath: String,
         faction_penalty: Option<(FactionId, i32)>,
     },
     Fled {
@@ -170,6 +377,15 @@ pub enum CombatOutcome {
         fatigue_cost: i32,
         faction_penalty: Option<(FactionId, i32)>,
     },
+    /// Ended without a kill through a non-lethal tactic
+    Resolved {
+        tactic: Tactic,
+        narration: String,
+        experience: i32,
+        loot: Vec<String>,
+        faction_change: Option<(FactionId, i32)>,
+        silver_lost: i32,
+    },
 }
 
 /// Active combat encounter
@@ -179,16 +395,55 @@ pub struct CombatEncounter {
     pub turn_count: i32,
     pub player_defending: bool,
     pub last_defense_type: Option<DefenseType>,
+    /// Status effects on the player
+    #[serde(default)]
+    pub player_effects: StatusEffects,
+    /// Status effects on the enemy
+    #[serde(default)]
+    pub enemy_effects: StatusEffects,
+    /// Non-lethal tactics that have failed this encounter
+    #[serde(default)]
+    pub failed_tactics: u32,
+    /// Everything that has happened in this fight
+    #[serde(default)]
+    pub log: CombatLog,
+    /// Wards prepared before the fight
+    #[serde(default)]
+    pub wards: CombatWards,
 }
 
 impl CombatEncounter {
+    /// Move the enemy into any phases its health has reached, returning the narration
+    fn check_phases(&mut self) -> String {
+        let mut output = String::new();
+        for phase in self.enemy.advance_phases() {
+            self.log.note(format!("{} enters its {} phase", self.enemy.name, phase.name));
+            output.push_str(&format!("\n>>> {} enters its {} phase! <<<\n", self.enemy.name, phase.name));
+            if !phase.announcement.is_empty() {
+                output.push_str(&format!("{}\n", phase.announcement));
+            }
+            if phase.cleanse && self.enemy_effects.summary().is_some() {
+                self.enemy_effects = StatusEffects::new();
+                output.push_str(&format!("{} shrugs off every lingering effect.\n", self.enemy.name));
+            }
+        }
+        output
+    }
+
     /// Create new combat encounter
     pub fn new(enemy: Enemy) -> Self {
+        let mut log = CombatLog::new(&enemy.name);
+        log.note(format!("{} engages you. (HP {}/{})", enemy.name, enemy.health, enemy.max_health));
         Self {
+            log,
             enemy,
             turn_count: 0,
             player_defending: false,
             last_defense_type: None,
+            player_effects: StatusEffects::new(),
+            enemy_effects: StatusEffects::new(),
+            failed_tactics: 0,
+            wards: CombatWards::default(),
         }
     }
 }
@@ -197,6 +452,42 @@ impl CombatEncounter {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CombatSystem {
     active_encounter: Option<CombatEncounter>,
+    /// Enemy definitions by ID
+    #[serde(default)]
+    catalog: HashMap<String, Enemy>,
+    /// Enemies the player has met, by ID
+    #[serde(default)]
+    bestiary: HashMap<String, BestiaryEntry>,
+    /// Companions fighting alongside the player
+    #[serde(default)]
+    party: Party,
+    /// Log of the most recently finished encounter
+    #[serde(default)]
+    last_log: Option<CombatLog>,
+    /// How hard fights are this run
+    #[serde(default)]
+    difficulty: Difficulty,
+    /// Rolls for the round being fought, drawn from the run at the start of
+    /// each player turn
+    #[serde(skip, default = "idle_rng")]
+    rng: StdRng,
+}
+
+/// A generator for a combat system between fights
+fn idle_rng() -> StdRng {
+    StdRng::seed_from_u64(0)
+}
+
+/// What the player has learned about an enemy
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BestiaryEntry {
+    /// Times the player has fought this enemy
+    pub encounters: u32,
+    /// Times the player has defeated this enemy
+    pub defeats: u32,
+    /// Times the encounter ended without a kill
+    #[serde(default)]
+    pub spared: u32,
 }
 
 impl CombatSystem {
@@ -204,27 +495,129 @@ impl CombatSystem {
     pub fn new() -> Self {
         Self {
             active_encounter: None,
+            catalog: HashMap::new(),
+            bestiary: HashMap::new(),
+            party: Party::new(),
+            last_log: None,
+            difficulty: Difficulty::default(),
+            rng: idle_rng(),
         }
     }
 
+    /// How hard fights are this run
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// Change the difficulty; fights already under way keep their scaling
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+    }
+
+    /// The player's companions
+    pub fn party(&self) -> &Party {
+        &self.party
+    }
+
+    /// Mutable access to the player's companions
+    pub fn party_mut(&mut self) -> &mut Party {
+        &mut self.party
+    }
+
+    /// Replace the enemy catalog with definitions loaded from content
+    pub fn load_catalog(&mut self, enemies: Vec<Enemy>) {
+        self.catalog = enemies.into_iter()
+            .map(|enemy| (enemy.id.clone(), enemy))
+            .collect();
+    }
+
+    /// Enemy definitions, falling back to the built-in examples
+    fn enemy_definitions(&self) -> Vec<Enemy> {
+        if self.catalog.is_empty() {
+            create_example_enemies()
+        } else {
+            self.catalog.values().cloned().collect()
+        }
+    }
+
+    /// Find an enemy definition by ID or name
+    pub fn find_enemy(&self, query: &str) -> Option<Enemy> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return None;
+        }
+
+        let mut definitions = self.enemy_definitions();
+        definitions.sort_by(|a, b| a.id.cmp(&b.id));
+        definitions.iter()
+            .find(|enemy| enemy.id == query.replace(' ', "_") || enemy.name.to_lowercase() == query)
+            .or_else(|| definitions.iter().find(|enemy| enemy.name.to_lowercase().contains(&query)))
+            .cloned()
+    }
+
+    /// Enemies that live at a location
+    pub fn enemies_at(&self, location_id: &str) -> Vec<Enemy> {
+        let mut enemies: Vec<Enemy> = self.enemy_definitions()
+            .into_iter()
+            .filter(|enemy| enemy.habitats.iter().any(|h| h == location_id))
+            .collect();
+        enemies.sort_by(|a, b| a.id.cmp(&b.id));
+        enemies
+    }
+
     /// Start a combat encounter
-    pub fn start_encounter(&mut self, enemy: Enemy) -> GameResult<String> {
+    pub fn start_encounter(&mut self, mut enemy: Enemy) -> GameResult<String> {
         if self.active_encounter.is_some() {
             return Err(crate::GameError::InvalidCommand(
                 "Already in combat!".to_string()
             ).into());
         }
 
+        self.bestiary.entry(enemy.id.clone()).or_default().encounters += 1;
+
         let enemy_name = enemy.name.clone();
-        self.active_encounter = Some(CombatEncounter::new(enemy));
+        self.difficulty.scale(&mut enemy);
+        let mut encounter = CombatEncounter::new(enemy);
+        if self.difficulty != Difficulty::Normal {
+            encounter.log.note(self.difficulty.explain());
+        }
+        self.active_encounter = Some(encounter);
 
         Ok(format!(
             "Combat initiated with {}! Prepare for battle.\n\
-             Use 'cast <spell>' to attack, 'defend' for protection, or 'flee' to escape.",
+             Use 'cast <spell>' to attack, 'defend' for protection, or 'flee' to escape.\n\
+             You can also try to parley, intimidate, demoralize or surrender.",
             enemy_name
         ))
     }
 
+    /// Bring prepared wards into the fight that just started
+    pub fn apply_wards(&mut self, wards: CombatWards) -> String {
+        let Some(encounter) = self.active_encounter.as_mut() else {
+            return String::new();
+        };
+        let Some(summary) = wards.summary() else {
+            return String::new();
+        };
+        encounter.log.note(format!("Prepared wards: {}", summary));
+        encounter.wards = wards;
+        format!("Your preparations hold: {}.", summary)
+    }
+
+    /// End the active encounter, keeping its log for review
+    fn end_encounter(&mut self) -> Option<CombatEncounter> {
+        let encounter = self.active_encounter.take()?;
+        self.last_log = Some(encounter.log.clone());
+        Some(encounter)
+    }
+
+    /// Log of the current fight, or of the last one if not in combat
+    pub fn combat_log(&self) -> Option<&CombatLog> {
+        self.active_encounter.as_ref()
+            .map(|encounter| &encounter.log)
+            .or(self.last_log.as_ref())
+    }
+
     /// Check if currently in combat
     pub fn is_in_combat(&self) -> bool {
         self.active_encounter.is_some()
@@ -243,18 +636,38 @@ impl CombatSystem {
         magic_system: &mut MagicSystem,
         spell_type: &str,
     ) -> GameResult<String> {
-        let encounter = self.active_encounter.as_mut()
-            .ok_or_else(|| crate::GameError::InvalidCommand("Not in combat".to_string()))?;
+        let (mut output, stunned) = self.begin_player_turn(player, world)?;
+        let Some(encounter) = self.active_encounter.as_mut() else {
+            return Ok(output); // Burned out before acting
+        };
+
+        // A stunned player forfeits the attack
+        if stunned {
+            encounter.turn_count += 1;
+            encounter.player_defending = false;
+            output.push_str(&self.finish_round(player, magic_system, world)?);
+            return Ok(output);
+        }
 
         // Cast spell using magic system
+        let (energy, fatigue) = (player.mental_state.current_energy, player.mental_state.fatigue);
         let magic_result = magic_system.attempt_magic(
             spell_type,
             player,
             world,
             Some(&encounter.enemy.name),
         )?;
-
-        let mut output = String::new();
+        encounter.log.record(LogEvent::Roll {
+            label: format!("Cast {}", spell_type),
+            roll: None,
+            chance: magic_result.success_probability,
+            success: magic_result.success,
+        });
+        encounter.log.resource("You", "energy", energy, player.mental_state.current_energy);
+        encounter.log.resource("You", "fatigue", fatigue, player.mental_state.fatigue);
+        for message in &magic_result.wear {
+            output.push_str(&format!("{}\n", message));
+        }
 
         // Calculate damage if spell succeeded
         if magic_result.success {
@@ -265,15 +678,28 @@ impl CombatSystem {
             let enemy_vuln_freq = encounter.enemy.vulnerable_frequency;
 
             // Calculate damage (avoiding borrowing conflicts)
-            let damage = Self::calculate_damage_static(
+            let (mut components, damage) = Self::damage_breakdown(
                 &magic_result,
                 player,
                 &enemy_resistances,
                 enemy_vuln_freq,
                 spell_type
             );
+            let condition_multiplier = encounter.player_effects.damage_multiplier();
+            let damage = ((damage as f32 * condition_multiplier) as i32).max(1);
+            if condition_multiplier != 1.0 {
+                components.push(DamageComponent::scale("conditions", condition_multiplier));
+            }
 
+            let enemy_health = encounter.enemy.health;
             encounter.enemy.take_damage(damage);
+            encounter.log.record(LogEvent::Damage {
+                source: "You".to_string(),
+                target: enemy_name.clone(),
+                components,
+                total: damage,
+            });
+            encounter.log.resource(&enemy_name, "health", enemy_health, encounter.enemy.health);
 
             output.push_str(&format!(
                 "Your {} spell strikes {}! (Damage: {}, Enemy HP: {}/{})\n",
@@ -286,9 +712,23 @@ impl CombatSystem {
 
             // Check if enemy defeated
             if !encounter.enemy.is_alive() {
-                let outcome = self.resolve_victory(player);
-                self.active_encounter = None;
-                return Ok(format!("{}\n{}", output, self.format_outcome(&outcome)));
+                return Ok(format!("{}\n{}", output, self.finish_victory(player)));
+            }
+            output.push_str(&encounter.check_phases());
+
+            // Some spell types leave a lingering effect
+            if let Some(ability) = spell_status_ability(spell_type) {
+                let roll = self.rng.gen::<f32>();
+                encounter.log.record(LogEvent::Roll {
+                    label: format!("{} on {}", ability.kind.name(), enemy_name),
+                    roll: Some(roll),
+                    chance: ability.chance,
+                    success: roll < ability.chance,
+                });
+                if roll < ability.chance {
+                    let message = encounter.enemy_effects.apply(ability.kind, ability.duration, &enemy_name);
+                    output.push_str(&format!("{}\n", message));
+                }
             }
         } else {
             output.push_str(&format!(
@@ -300,20 +740,211 @@ impl CombatSystem {
         // Enemy turn
         encounter.turn_count += 1;
         encounter.player_defending = false;
-        let enemy_action_result = self.enemy_turn(player, magic_system, world)?;
-        output.push_str(&enemy_action_result);
+        output.push_str(&self.finish_round(player, magic_system, world)?);
+
+        Ok(output)
+    }
+
+    /// Companions and the enemy act after the player, in initiative order
+    fn finish_round(
+        &mut self,
+        player: &mut Player,
+        magic_system: &mut MagicSystem,
+        world: &mut WorldState,
+    ) -> GameResult<String> {
+        let mut output = self.companion_turns(player, true);
+        if self.active_encounter.is_none() {
+            return Ok(output);
+        }
+
+        output.push_str(&self.enemy_turn(player, magic_system, world)?);
+        if self.active_encounter.is_some() {
+            output.push_str(&self.companion_turns(player, false));
+        }
 
         Ok(output)
     }
 
-    /// Calculate damage from magic attack (static version to avoid borrowing conflicts)
-    fn calculate_damage_static(
+    /// Active companions faster (or slower) than the enemy take their turns
+    fn companion_turns(&mut self, player: &mut Player, before_enemy: bool) -> String {
+        let Some(encounter) = self.active_encounter.as_mut() else {
+            return String::new();
+        };
+
+        let enemy_speed = enemy_initiative(encounter.enemy.difficulty_tier);
+        let mut order: Vec<&crate::systems::companions::Companion> = self.party.members().iter()
+            .filter(|member| member.is_active() && (member.initiative >= enemy_speed) == before_enemy)
+            .collect();
+        order.sort_by_key(|member| -member.initiative);
+
+        let mut output = String::new();
+        for member in order {
+            if !encounter.enemy.is_alive() {
+                break;
+            }
+            let enemy_health = encounter.enemy.health;
+            let line = member.act(&mut encounter.enemy, &mut encounter.enemy_effects, player, &mut self.rng);
+            output.push_str(&format!("{}\n", line));
+            encounter.log.note(line);
+            let enemy_name = encounter.enemy.name.clone();
+            encounter.log.resource(&enemy_name, "health", enemy_health, encounter.enemy.health);
+            output.push_str(&encounter.check_phases());
+        }
+
+        if !encounter.enemy.is_alive() {
+            output.push_str(&self.finish_victory(player));
+        }
+        output
+    }
+
+    /// Record a defeated enemy and end the encounter
+    fn finish_victory(&mut self, player: &mut Player) -> String {
+        if let Some(encounter) = &self.active_encounter {
+            self.bestiary.entry(encounter.enemy.id.clone()).or_default().defeats += 1;
+        }
+        let outcome = self.resolve_victory(player);
+        self.end_encounter();
+        self.format_outcome(&outcome)
+    }
+
+    /// Apply a status effect to one side of the current encounter
+    pub fn apply_status(&mut self, target: Combatant, kind: StatusKind, duration: u32) -> GameResult<String> {
+        let encounter = self.active_encounter.as_mut()
+            .ok_or_else(|| crate::GameError::InvalidCommand("Not in combat".to_string()))?;
+
+        Ok(match target {
+            Combatant::Player => encounter.player_effects.apply(kind, duration, "You"),
+            Combatant::Enemy => encounter.enemy_effects.apply(kind, duration, &encounter.enemy.name),
+        })
+    }
+
+    /// Try to end the encounter without a kill
+    ///
+    /// An unworkable tactic is refused without using the turn; a failed
+    /// attempt gives the enemy (and companions) their turn as usual.
+    pub fn attempt_tactic(
+        &mut self,
+        player: &mut Player,
+        world: &mut WorldState,
+        magic_system: &mut MagicSystem,
+        tactic: Tactic,
+    ) -> GameResult<String> {
+        let encounter = self.active_encounter.as_ref()
+            .ok_or_else(|| crate::GameError::InvalidCommand("Not in combat".to_string()))?;
+        if let Err(message) = negotiation::success_chance(
+            tactic, &encounter.enemy, &encounter.enemy_effects, player, encounter.failed_tactics,
+        ) {
+            return Ok(message);
+        }
+
+        let (mut output, stunned) = self.begin_player_turn(player, world)?;
+        let Some(encounter) = self.active_encounter.as_mut() else {
+            return Ok(output); // Burned out before acting
+        };
+        encounter.turn_count += 1;
+        encounter.player_defending = false;
+
+        if !stunned {
+            let chance = negotiation::success_chance(
+                tactic, &encounter.enemy, &encounter.enemy_effects, player, encounter.failed_tactics,
+            ).unwrap_or(0.0);
+            let roll = self.rng.gen::<f32>();
+            encounter.log.record(LogEvent::Roll {
+                label: format!("{} {}", tactic.name(), encounter.enemy.name),
+                roll: Some(roll),
+                chance,
+                success: roll < chance,
+            });
+            if roll < chance {
+                let outcome = self.resolve_tactic(player, tactic);
+                self.end_encounter();
+                output.push_str(&self.format_outcome(&outcome));
+                return Ok(output);
+            }
+
+            encounter.failed_tactics += 1;
+            output.push_str(&format!("Your attempt to {} {} fails.\n", tactic.name(), encounter.enemy.name));
+        }
+
+        output.push_str(&self.finish_round(player, magic_system, world)?);
+        Ok(output)
+    }
+
+    /// Apply the consequences of a successful tactic
+    fn resolve_tactic(&mut self, player: &mut Player, tactic: Tactic) -> CombatOutcome {
+        let encounter = self.active_encounter.as_ref().unwrap();
+        let consequences = negotiation::consequences(tactic, &encounter.enemy, player);
+        let faction_change = negotiation::faction_change(&encounter.enemy, &consequences);
+
+        if let Some((faction, change)) = faction_change {
+            player.modify_faction_reputation(faction, change);
+        }
+        player.inventory.silver -= consequences.silver_lost;
+        player.mental_state.fatigue = (player.mental_state.fatigue + consequences.fatigue).min(100);
+
+        let experience = (encounter.enemy.experience_reward as f32 * consequences.experience_fraction) as i32;
+        let loot = if consequences.loot { Self::roll_loot(&encounter.enemy, &mut self.rng) } else { Vec::new() };
+        if tactic != Tactic::Surrender {
+            self.bestiary.entry(encounter.enemy.id.clone()).or_default().spared += 1;
+        }
+
+        CombatOutcome::Resolved {
+            tactic,
+            narration: consequences.narration,
+            experience,
+            loot,
+            faction_change,
+            silver_lost: consequences.silver_lost,
+        }
+    }
+
+    /// Tick the player's status effects before they act
+    ///
+    /// Returns the narration and whether the player is stunned this turn.
+    /// Ends the encounter in defeat if a burn takes the last of their health.
+    fn begin_player_turn(&mut self, player: &mut Player, world: &mut WorldState) -> GameResult<(String, bool)> {
+        self.rng = world.run.rng();
+        let encounter = self.active_encounter.as_mut()
+            .ok_or_else(|| crate::GameError::InvalidCommand("Not in combat".to_string()))?;
+
+        encounter.log.begin_round();
+        let tick = encounter.player_effects.tick("you");
+        let mut output: String = tick.messages.iter().map(|m| format!("{}\n", m)).collect();
+        for message in &tick.messages {
+            encounter.log.note(message.clone());
+        }
+
+        if tick.damage > 0 {
+            // Resonance burns leave scars that outlast the fight
+            let health = player.health.current;
+            let lost = player.health.take_damage(tick.damage);
+            encounter.log.record(LogEvent::Damage {
+                source: "Resonance burn".to_string(),
+                target: "You".to_string(),
+                components: vec![DamageComponent::flat("burn", tick.damage as f32)],
+                total: lost,
+            });
+            encounter.log.resource("You", "health", health, player.health.current);
+            output.push_str(&format!("{}\n", player.health.injure(InjuryKind::Burn)));
+            if player.health.is_down() {
+                let outcome = self.resolve_defeat(player, world);
+                self.end_encounter();
+                output.push_str(&self.format_outcome(&outcome));
+                return Ok((output, false));
+            }
+        }
+
+        Ok((output, tick.skip_action))
+    }
+
+    /// Damage from a magic attack, with each factor that went into it
+    fn damage_breakdown(
         magic_result: &MagicResult,
         player: &Player,
         enemy_resistances: &HashMap<String, f32>,
         enemy_vuln_freq: Option<u8>,
         spell_type: &str,
-    ) -> i32 {
+    ) -> (Vec<DamageComponent>, i32) {
         // Base damage from magic power level
         let base_damage = (magic_result.power_level * 10.0) as i32;
 
@@ -341,7 +972,13 @@ impl CombatSystem {
         // Calculate final damage
         let final_damage = (base_damage as f32 * damage_multiplier * resistance_multiplier * vulnerability_bonus) as i32;
 
-        final_damage.max(1) // Minimum 1 damage
+        let components = vec![
+            DamageComponent::flat("base (power x10)", base_damage as f32),
+            DamageComponent::scale("theory bonus", damage_multiplier),
+            DamageComponent::scale("resistance", resistance_multiplier),
+            DamageComponent::scale("frequency match", vulnerability_bonus),
+        ];
+        (components, final_damage.max(1)) // Minimum 1 damage
     }
 
     /// Calculate damage from magic attack (convenience wrapper)
@@ -352,23 +989,29 @@ impl CombatSystem {
         enemy: &Enemy,
         spell_type: &str,
     ) -> i32 {
-        Self::calculate_damage_static(
+        Self::damage_breakdown(
             magic_result,
             player,
             &enemy.magical_resistance,
             enemy.vulnerable_frequency,
             spell_type
-        )
+        ).1
     }
 
     /// Execute player defense action
     pub fn player_defend(
         &mut self,
         player: &mut Player,
+        world: &mut WorldState,
         defense_type: DefenseType,
     ) -> GameResult<String> {
-        let encounter = self.active_encounter.as_mut()
-            .ok_or_else(|| crate::GameError::InvalidCommand("Not in combat".to_string()))?;
+        let (output, stunned) = self.begin_player_turn(player, world)?;
+        let Some(encounter) = self.active_encounter.as_mut() else {
+            return Ok(output);
+        };
+        if stunned {
+            return Ok(format!("{}You can't raise a defense while stunned.", output));
+        }
 
         // Apply defense costs
         let (energy_cost, fatigue_cost) = match defense_type {
@@ -385,7 +1028,10 @@ impl CombatSystem {
             }
         };
 
+        let (energy, fatigue) = (player.mental_state.current_energy, player.mental_state.fatigue);
         player.use_mental_energy(energy_cost, fatigue_cost)?;
+        encounter.log.resource("You", "energy", energy, player.mental_state.current_energy);
+        encounter.log.resource("You", "fatigue", fatigue, player.mental_state.fatigue);
 
         encounter.player_defending = true;
         encounter.last_defense_type = Some(defense_type);
@@ -396,7 +1042,8 @@ impl CombatSystem {
             DefenseType::CounterMagic => "counter-magic ward",
         };
 
-        Ok(format!("You adopt a defensive {} position.", defense_name))
+        encounter.log.note(format!("You raise a {}", defense_name));
+        Ok(format!("{}You adopt a defensive {} position.", output, defense_name))
     }
 
     /// Player attempts to flee
@@ -404,12 +1051,20 @@ impl CombatSystem {
         &mut self,
         player: &mut Player,
     ) -> GameResult<String> {
-        let encounter = self.active_encounter.take()
+        let encounter = self.end_encounter()
             .ok_or_else(|| crate::GameError::InvalidCommand("Not in combat".to_string()))?;
 
         // Apply flee costs
+        let (energy, fatigue) = (player.mental_state.current_energy, player.mental_state.fatigue);
         player.use_mental_energy(20, 20)?;
 
+        let summary = self.last_log.as_mut().map(|log| {
+            log.note("You flee");
+            log.resource("You", "energy", energy, player.mental_state.current_energy);
+            log.resource("You", "fatigue", fatigue, player.mental_state.fatigue);
+            log.summary()
+        }).unwrap_or_default();
+
         // Faction penalty if enemy has affiliation
         let faction_penalty = encounter.enemy.faction_affiliation.map(|faction| (faction, -5));
 
@@ -419,17 +1074,19 @@ impl CombatSystem {
             return Ok(format!(
                 "You flee from combat with {}!\n\
                  Energy Cost: 20, Fatigue Cost: 20\n\
-                 Faction Penalty: {} {}",
+                 Faction Penalty: {} {}\n{}",
                 encounter.enemy.name,
                 faction_name,
-                penalty
+                penalty,
+                summary
             ));
         }
 
         Ok(format!(
             "You flee from combat with {}!\n\
-             Energy Cost: 20, Fatigue Cost: 20",
-            encounter.enemy.name
+             Energy Cost: 20, Fatigue Cost: 20\n{}",
+            encounter.enemy.name,
+            summary
         ))
     }
 
@@ -438,23 +1095,91 @@ impl CombatSystem {
         &mut self,
         player: &mut Player,
         _magic_system: &mut MagicSystem,
-        _world: &mut WorldState,
+        world: &mut WorldState,
     ) -> GameResult<String> {
+        let difficulty = self.difficulty;
         let encounter = self.active_encounter.as_mut()
             .ok_or_else(|| crate::GameError::InvalidCommand("Not in combat".to_string()))?;
 
-        // Simple AI: attack aggressively when player is low on energy
-        let _action = if player.mental_state.current_energy < 30 {
-            "aggressive_attack"
-        } else if encounter.enemy.health_percentage() < 0.3 {
-            // Flee if low health
-            if rand::thread_rng().gen_bool(0.5) {
-                return self.enemy_flees();
+        // Lingering effects resolve before the enemy acts
+        let enemy_name = encounter.enemy.name.clone();
+        let tick = encounter.enemy_effects.tick(&enemy_name);
+        let mut output: String = tick.messages.iter().map(|m| format!("\n{}", m)).collect();
+        for message in &tick.messages {
+            encounter.log.note(message.clone());
+        }
+        if tick.damage > 0 {
+            let enemy_health = encounter.enemy.health;
+            encounter.enemy.take_damage(tick.damage);
+            encounter.log.record(LogEvent::Damage {
+                source: "Lingering effects".to_string(),
+                target: enemy_name.clone(),
+                components: vec![DamageComponent::flat("effects", tick.damage as f32)],
+                total: enemy_health - encounter.enemy.health,
+            });
+            encounter.log.resource(&enemy_name, "health", enemy_health, encounter.enemy.health);
+            if !encounter.enemy.is_alive() {
+                output.push_str(&format!("\n{}", self.finish_victory(player)));
+                return Ok(output);
             }
-            "desperate_attack"
-        } else {
-            "normal_attack"
+            output.push_str(&encounter.check_phases());
+        }
+        if tick.skip_action {
+            output.push('\n');
+            return Ok(output);
+        }
+
+        // The enemy's AI profile picks what it does this turn
+        let context = AiContext {
+            enemy: &encounter.enemy,
+            player,
+            player_defending: encounter.player_defending,
+            turn: encounter.turn_count,
+            difficulty,
         };
+        let action = ai_for(encounter.enemy.ai_profile).decide(&context, &mut self.rng);
+
+        let (tactic_multiplier, intent) = match action {
+            EnemyAction::Attack { multiplier, intent } => (multiplier, intent),
+            EnemyAction::Flee { chance } => {
+                let roll = self.rng.gen::<f32>();
+                let flees = roll < chance;
+                encounter.log.record(LogEvent::Roll {
+                    label: format!("{} tries to flee", enemy_name),
+                    roll: Some(roll),
+                    chance,
+                    success: flees,
+                });
+                if flees {
+                    return Ok(format!("{}{}", output, self.enemy_flees()?));
+                }
+                (1.0, Some("lunges desperately, cornered".to_string()))
+            }
+            EnemyAction::Wait(text) => {
+                encounter.log.note(text.clone());
+                output.push_str(&format!("\n{}\n", text));
+                return Ok(output);
+            }
+            EnemyAction::Siphon { amount } => {
+                let energy = player.mental_state.current_energy;
+                let taken = amount.min(energy);
+                player.mental_state.current_energy -= taken;
+                let enemy_health = encounter.enemy.health;
+                encounter.enemy.health = (enemy_health + taken).min(encounter.enemy.max_health);
+                encounter.log.note(format!("{} siphons your mental energy", enemy_name));
+                encounter.log.resource("You", "energy", energy, player.mental_state.current_energy);
+                encounter.log.resource(&enemy_name, "health", enemy_health, encounter.enemy.health);
+                output.push_str(&format!(
+                    "\n{} siphons {} mental energy from you and mends {} of its wounds.\n",
+                    enemy_name, taken, encounter.enemy.health - enemy_health
+                ));
+                return Ok(output);
+            }
+        };
+        if let Some(intent) = &intent {
+            encounter.log.note(format!("{} {}", enemy_name, intent));
+            output.push_str(&format!("\n{} {}!", enemy_name, intent));
+        }
 
         // Enemy attacks with a basic spell
         // Get difficulty tier to avoid borrowing issues
@@ -463,18 +1188,48 @@ impl CombatSystem {
 
         // Simplified enemy attack (doesn't use full magic system to avoid player cost application)
         let base_damage = match encounter.enemy.difficulty_tier {
-            DifficultyTier::Beginner => rand::thread_rng().gen_range(10..=20),
-            DifficultyTier::Intermediate => rand::thread_rng().gen_range(25..=40),
-            DifficultyTier::Advanced => rand::thread_rng().gen_range(40..=60),
-            DifficultyTier::Boss => rand::thread_rng().gen_range(60..=90),
+            DifficultyTier::Beginner => self.rng.gen_range(10..=20),
+            DifficultyTier::Intermediate => self.rng.gen_range(25..=40),
+            DifficultyTier::Advanced => self.rng.gen_range(40..=60),
+            DifficultyTier::Boss => self.rng.gen_range(60..=90),
         };
 
+        let mut components = vec![
+            DamageComponent::flat("base", base_damage as f32),
+            DamageComponent::scale("temperament", encounter.enemy.ai_profile.damage_multiplier()),
+            DamageComponent::scale("phase", encounter.enemy.phase_damage_multiplier()),
+            DamageComponent::scale("conditions", encounter.enemy_effects.damage_multiplier()),
+            DamageComponent::scale("tactic", tactic_multiplier),
+            DamageComponent::scale("difficulty", difficulty.damage_multiplier()),
+        ];
+        components.push(DamageComponent::scale("dampener", encounter.wards.damage_multiplier()));
+        components.retain(|component| !component.multiplier || component.value != 1.0);
+
+        let base_damage = (base_damage as f32
+            * encounter.enemy.ai_profile.damage_multiplier()
+            * encounter.enemy.phase_damage_multiplier()
+            * encounter.enemy_effects.damage_multiplier()
+            * tactic_multiplier
+            * difficulty.damage_multiplier()
+            * encounter.wards.damage_multiplier()) as i32;
+
         // Apply defense reductions
         let final_damage = if encounter.player_defending {
             match encounter.last_defense_type {
-                Some(DefenseType::Shield) => base_damage / 2, // 50% reduction
+                Some(DefenseType::Shield) => {
+                    components.push(DamageComponent::scale("shield", 0.5));
+                    base_damage / 2 // 50% reduction
+                }
                 Some(DefenseType::Evade) => {
-                    if rand::thread_rng().gen_bool(0.7) {
+                    let dodged = self.rng.gen_bool(0.7);
+                    encounter.log.record(LogEvent::Roll {
+                        label: "Evade".to_string(),
+                        roll: None,
+                        chance: 0.7,
+                        success: dodged,
+                    });
+                    if dodged {
+                        components.push(DamageComponent::scale("dodged", 0.0));
                         0 // 70% chance to dodge completely
                     } else {
                         base_damage
@@ -483,7 +1238,18 @@ impl CombatSystem {
                 Some(DefenseType::CounterMagic) => {
                     // Reflect 30% damage back to enemy
                     let reflected = (base_damage as f32 * 0.3) as i32;
+                    let enemy_health = encounter.enemy.health;
                     encounter.enemy.take_damage(reflected);
+                    encounter.log.record(LogEvent::Damage {
+                        source: "Counter-magic".to_string(),
+                        target: enemy_name.clone(),
+                        components: vec![
+                            DamageComponent::flat("incoming", base_damage as f32),
+                            DamageComponent::scale("reflected", 0.3),
+                        ],
+                        total: enemy_health - encounter.enemy.health,
+                    });
+                    components.push(DamageComponent::flat("reflected", -(reflected as f32)));
                     base_damage - reflected
                 }
                 None => base_damage,
@@ -492,21 +1258,94 @@ impl CombatSystem {
             base_damage
         };
 
-        // Apply damage to player by reducing energy
-        let actual_damage = final_damage.min(player.mental_state.current_energy);
-        player.mental_state.current_energy = (player.mental_state.current_energy - actual_damage).max(0);
+        // Companions draw some attacks, and guards share the blows aimed at the player
+        let mut final_damage = final_damage;
+        let targets: Vec<usize> = (0..self.party.members().len())
+            .filter(|&i| self.party.members()[i].is_active())
+            .collect();
+        if !targets.is_empty() && self.rng.gen_bool(COMPANION_TARGET_CHANCE) {
+            let companion = &mut self.party.members_mut()[targets[self.rng.gen_range(0..targets.len())]];
+            encounter.log.record(LogEvent::Damage {
+                source: enemy_name.clone(),
+                target: companion.name.clone(),
+                components,
+                total: final_damage,
+            });
+            output.push_str(&format!(
+                "\n{} attacks {} with {}! (Damage: {})\n",
+                encounter.enemy.name, companion.name, spell_type, final_damage
+            ));
+            if let Some(message) = companion.take_damage(final_damage) {
+                output.push_str(&format!("{}\n", message));
+            }
+            return Ok(output);
+        }
+        if let Some(guard) = self.party.members_mut().iter_mut().find(|member| member.guards()) {
+            let absorbed = final_damage / 2;
+            if absorbed > 0 {
+                final_damage -= absorbed;
+                components.push(DamageComponent::flat(&format!("{} intercepts", guard.name), -(absorbed as f32)));
+                output.push_str(&format!("\n{} intercepts part of the blow. (Absorbed: {})", guard.name, absorbed));
+                if let Some(message) = guard.take_damage(absorbed) {
+                    output.push_str(&format!("\n{}", message));
+                }
+            }
+        }
 
-        let mut output = format!(
+        let shielded = encounter.wards.absorb(final_damage);
+        if shielded > 0 {
+            final_damage -= shielded;
+            components.push(DamageComponent::flat("shield ward", -(shielded as f32)));
+            output.push_str(&format!(
+                "\nYour shield ward flares and absorbs {} damage. ({} charge{} left)",
+                shielded, encounter.wards.shield_charges, if encounter.wards.shield_charges == 1 { "" } else { "s" }
+            ));
+        }
+
+        let health = player.health.current;
+        let actual_damage = player.health.take_damage(final_damage);
+        encounter.log.record(LogEvent::Damage {
+            source: enemy_name.clone(),
+            target: "You".to_string(),
+            components,
+            total: actual_damage,
+        });
+        encounter.log.resource("You", "health", health, player.health.current);
+
+        output.push_str(&format!(
             "\n{} attacks with {}! (Damage: {})\n",
             encounter.enemy.name,
             spell_type,
             actual_damage
-        );
+        ));
+
+        // Landed hits wear down armour
+        if actual_damage > 0 {
+            for message in player.wear_equipment(crate::systems::items::WearCause::Hit) {
+                output.push_str(&format!("{}\n", message));
+            }
+        }
+
+        // Landed hits may carry the enemy's signature effects
+        if actual_damage > 0 {
+            for ability in &encounter.enemy.status_abilities {
+                let roll = self.rng.gen::<f32>();
+                encounter.log.record(LogEvent::Roll {
+                    label: format!("{} on you", ability.kind.name()),
+                    roll: Some(roll),
+                    chance: ability.chance,
+                    success: roll < ability.chance,
+                });
+                if roll < ability.chance {
+                    let message = encounter.player_effects.apply(ability.kind, ability.duration, "You");
+                    output.push_str(&format!("{}\n", message));
+                }
+            }
+        }
 
-        // Check if player is defeated (energy depleted)
-        if player.mental_state.current_energy == 0 {
-            let outcome = self.resolve_defeat(player);
-            self.active_encounter = None;
+        if player.health.is_down() {
+            let outcome = self.resolve_defeat(player, world);
+            self.end_encounter();
             output.push_str(&format!("\n{}", self.format_outcome(&outcome)));
         }
 
@@ -525,17 +1364,21 @@ impl CombatSystem {
 
     /// Enemy flees from combat
     fn enemy_flees(&mut self) -> GameResult<String> {
-        let encounter = self.active_encounter.take()
+        let encounter = self.active_encounter.as_mut()
             .ok_or_else(|| crate::GameError::InvalidCommand("Not in combat".to_string()))?;
+        encounter.log.note(format!("{} flees", encounter.enemy.name));
+        let name = encounter.enemy.name.clone();
+        self.end_encounter();
 
         Ok(format!(
-            "\n{} flees from combat!",
-            encounter.enemy.name
+            "\n{} flees from combat!\n{}",
+            name,
+            self.last_log.as_ref().map(CombatLog::summary).unwrap_or_default()
         ))
     }
 
     /// Resolve combat victory
-    fn resolve_victory(&self, _player: &mut Player) -> CombatOutcome {
+    fn resolve_victory(&mut self, _player: &mut Player) -> CombatOutcome {
         let encounter = self.active_encounter.as_ref().unwrap();
 
         // Calculate experience
@@ -543,17 +1386,7 @@ impl CombatSystem {
         let efficiency_bonus = if encounter.turn_count < 5 { 1.1 } else { 1.0 };
         let total_exp = (base_exp as f32 * efficiency_bonus) as i32;
 
-        // Roll for loot
-        let mut loot = Vec::new();
-        let mut rng = rand::thread_rng();
-        for drop in &encounter.enemy.loot_table {
-            if rng.gen::<f32>() < drop.drop_chance {
-                let quantity = rng.gen_range(drop.quantity_range.0..=drop.quantity_range.1);
-                for _ in 0..quantity {
-                    loot.push(drop.item_id.clone());
-                }
-            }
-        }
+        let loot = Self::roll_loot(&encounter.enemy, &mut self.rng);
 
         // Faction consequences (defeating enemy gives penalty with their faction)
         let faction_change = encounter.enemy.faction_affiliation.map(|faction| (faction, -10));
@@ -565,26 +1398,41 @@ impl CombatSystem {
         }
     }
 
-    /// Resolve combat defeat
-    fn resolve_defeat(&self, player: &mut Player) -> CombatOutcome {
+    /// Roll an enemy's loot table
+    fn roll_loot(enemy: &Enemy, rng: &mut impl Rng) -> Vec<String> {
+        let mut loot = Vec::new();
+        for drop in &enemy.loot_table {
+            if rng.gen::<f32>() < drop.drop_chance {
+                let quantity = rng.gen_range(drop.quantity_range.0..=drop.quantity_range.1);
+                for _ in 0..quantity {
+                    loot.push(drop.item_id.clone());
+                }
+            }
+        }
+        loot
+    }
+
+    /// Resolve combat defeat through the run's defeat policy
+    fn resolve_defeat(&self, player: &mut Player, world: &mut WorldState) -> CombatOutcome {
         let encounter = self.active_encounter.as_ref().unwrap();
 
-        // Drain energy to 10% and add fatigue
-        player.mental_state.current_energy = (player.mental_state.max_energy as f32 * 0.1) as i32;
-        player.mental_state.fatigue = (player.mental_state.fatigue + 40).min(100);
+        let cause = DefeatCause::Combat {
+            enemy: encounter.enemy.name.clone(),
+            faction: encounter.enemy.faction_affiliation,
+        };
+        let aftermath = resolve_defeat(&cause, player, world);
 
         let faction_penalty = encounter.enemy.faction_affiliation.map(|faction| (faction, -10));
 
         CombatOutcome::Defeat {
-            energy_drain_percent: 90,
-            fatigue_increase: 40,
+            aftermath,
             faction_penalty,
         }
     }
 
     /// Format combat outcome for display
     fn format_outcome(&self, outcome: &CombatOutcome) -> String {
-        match outcome {
+        let mut output = match outcome {
             CombatOutcome::Victory { experience, loot, faction_change } => {
                 let mut output = format!("\n=== VICTORY ===\nYou have defeated the enemy!\n");
                 output.push_str(&format!("Experience Gained: {}\n", experience));
@@ -599,12 +1447,8 @@ impl CombatSystem {
 
                 output
             }
-            CombatOutcome::Defeat { energy_drain_percent, fatigue_increase, faction_penalty } => {
-                let mut output = format!(
-                    "\n=== DEFEAT ===\nYou have been overwhelmed!\n\
-                     Energy Drained: {}%, Fatigue Increased: {}\n",
-                    energy_drain_percent, fatigue_increase
-                );
+            CombatOutcome::Defeat { aftermath, faction_penalty } => {
+                let mut output = format!("\n=== DEFEAT ===\n{}\n", aftermath);
 
                 if let Some((faction, penalty)) = faction_penalty {
                     output.push_str(&format!("Faction Penalty: {:?} {}\n", faction, penalty));
@@ -624,13 +1468,44 @@ impl CombatSystem {
 
                 output
             }
+            CombatOutcome::Resolved { tactic, narration, experience, loot, faction_change, silver_lost } => {
+                let mut output = format!("\n=== ENCOUNTER RESOLVED ({}) ===\n{}\n", tactic.name(), narration);
+
+                if *experience > 0 {
+                    output.push_str(&format!("Experience Gained: {}\n", experience));
+                }
+                if !loot.is_empty() {
+                    output.push_str(&format!("Loot Acquired: {}\n", loot.join(", ")));
+                }
+                if let Some((faction, change)) = faction_change {
+                    output.push_str(&format!("Faction Change: {} {:+}\n", faction.display_name(), change));
+                }
+                if *silver_lost > 0 {
+                    output.push_str(&format!("Silver Lost: {}\n", silver_lost));
+                }
+
+                output
+            }
+        };
+
+        if let Some(log) = &self.last_log {
+            output.push_str(&format!("\n{}\n", log.summary()));
+        }
+        output
+    }
+
+    /// Expanded log of the current or most recent fight
+    pub fn combat_log_report(&self) -> String {
+        match self.combat_log() {
+            Some(log) => log.render(),
+            None => "You haven't fought anything yet.".to_string(),
         }
     }
 
     /// Get current combat status
     pub fn get_status(&self) -> Option<String> {
         self.active_encounter.as_ref().map(|encounter| {
-            format!(
+            let mut status = format!(
                 "=== COMBAT STATUS ===\n\
                  Enemy: {} (HP: {}/{})\n\
                  Turn: {}\n\
@@ -640,10 +1515,134 @@ impl CombatSystem {
                 encounter.enemy.max_health,
                 encounter.turn_count,
                 if encounter.player_defending { "Active" } else { "None" }
-            )
+            );
+
+            if let Some(phase) = encounter.enemy.current_phase() {
+                status.push_str(&format!("\nEnemy Phase: {} ({} of {})",
+                    phase.name, encounter.enemy.phases_entered, encounter.enemy.phases.len()));
+            }
+            if let Some(effects) = encounter.enemy_effects.summary() {
+                status.push_str(&format!("\nEnemy Conditions: {}", effects));
+            }
+            if let Some(effects) = encounter.player_effects.summary() {
+                status.push_str(&format!("\nYour Conditions: {}", effects));
+            }
+            if let Some(wards) = encounter.wards.summary() {
+                status.push_str(&format!("\nYour Wards: {}", wards));
+            }
+
+            status
         })
     }
 
+    /// Enemies the player has met this run, by ID
+    pub fn bestiary(&self) -> &HashMap<String, BestiaryEntry> {
+        &self.bestiary
+    }
+
+    /// Number of enemies that can be discovered
+    pub fn catalog_size(&self) -> usize {
+        self.catalog.len()
+    }
+
+    /// Describe discovered enemies, or one enemy in detail
+    pub fn bestiary_report(&self, query: Option<&str>) -> GameResult<String> {
+        self.bestiary_report_with(query, &std::collections::BTreeMap::new())
+    }
+
+    /// Describe enemies discovered this run or recorded by earlier characters
+    pub fn bestiary_report_with(
+        &self,
+        query: Option<&str>,
+        recorded: &std::collections::BTreeMap<String, BestiaryEntry>,
+    ) -> GameResult<String> {
+        let known = |id: &str| self.bestiary.get(id).or_else(|| recorded.get(id));
+
+        if let Some(query) = query {
+            let enemy = self.find_enemy(query)
+                .filter(|enemy| known(&enemy.id).is_some())
+                .ok_or_else(|| crate::GameError::ContentNotFound(
+                    format!("You haven't encountered anything called '{}'", query)
+                ))?;
+            let mut output = self.describe_enemy(&enemy, known(&enemy.id).unwrap_or(&BestiaryEntry::default()));
+            if !self.bestiary.contains_key(&enemy.id) {
+                output.push_str("\nRecorded in your codex by an earlier character.");
+            }
+            return Ok(output);
+        }
+
+        let mut ids: Vec<&String> = self.bestiary.keys().chain(recorded.keys()).collect();
+        ids.sort();
+        ids.dedup();
+        let mut entries: Vec<(Enemy, &BestiaryEntry)> = ids.into_iter()
+            .filter_map(|id| Some((self.find_enemy(id)?, known(id)?)))
+            .collect();
+        if entries.is_empty() {
+            return Ok("Your bestiary is empty. Enemies are recorded as you encounter them.".to_string());
+        }
+        entries.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+
+        let mut output = format!("=== BESTIARY ({} discovered) ===\n\n", entries.len());
+        for (enemy, entry) in entries {
+            if self.bestiary.contains_key(&enemy.id) {
+                output.push_str(&format!(
+                    "• {} [{:?}] - encountered {}, defeated {}\n",
+                    enemy.name, enemy.difficulty_tier, entry.encounters, entry.defeats
+                ));
+            } else {
+                output.push_str(&format!("• {} [{:?}] - recorded by an earlier character\n", enemy.name, enemy.difficulty_tier));
+            }
+        }
+        output.push_str("\nUse 'bestiary <enemy>' for details.");
+        Ok(output)
+    }
+
+    /// Detailed bestiary page; loot is only revealed after a victory
+    fn describe_enemy(&self, enemy: &Enemy, entry: &BestiaryEntry) -> String {
+        let mut output = format!("=== {} ===\n{}\n\n", enemy.name, enemy.description);
+        output.push_str(&format!("Tier: {:?} | HP: {} | Temperament: {}\n",
+            enemy.difficulty_tier, enemy.max_health, enemy.ai_profile.name()));
+
+        if let Some(faction) = enemy.faction_affiliation {
+            output.push_str(&format!("Allegiance: {}\n", faction.display_name()));
+        }
+        if let Some(frequency) = enemy.vulnerable_frequency {
+            output.push_str(&format!("Vulnerable frequency: {}\n", frequency));
+        }
+        if !enemy.phases.is_empty() {
+            if entry.defeats > 0 {
+                let names: Vec<&str> = enemy.phases.iter().map(|phase| phase.name.as_str()).collect();
+                output.push_str(&format!("Phases: {}\n", names.join(" -> ")));
+            } else {
+                output.push_str(&format!("Fights in {} further phase(s) as it weakens\n", enemy.phases.len()));
+            }
+        }
+
+        if !enemy.magical_resistance.is_empty() {
+            let mut resistances: Vec<_> = enemy.magical_resistance.iter().collect();
+            resistances.sort_by(|a, b| a.0.cmp(b.0));
+            let list: Vec<String> = resistances.iter()
+                .map(|(spell, value)| format!("{} {:.0}%", spell, *value * 100.0))
+                .collect();
+            output.push_str(&format!("Resistances: {}\n", list.join(", ")));
+        }
+
+        if entry.defeats > 0 && !enemy.loot_table.is_empty() {
+            let loot: Vec<String> = enemy.loot_table.iter()
+                .map(|drop| format!("{} ({:.0}%)", drop.item_id, drop.drop_chance * 100.0))
+                .collect();
+            output.push_str(&format!("Known drops: {}\n", loot.join(", ")));
+        } else if !enemy.loot_table.is_empty() {
+            output.push_str("Known drops: defeat one to learn what it carries\n");
+        }
+
+        output.push_str(&format!("\nEncountered {} time(s), defeated {} time(s).", entry.encounters, entry.defeats));
+        if entry.spared > 0 {
+            output.push_str(&format!(" Resolved peacefully {} time(s).", entry.spared));
+        }
+        output
+    }
+
     /// Legacy method for compatibility
     pub fn handle_combat(
         &self,
@@ -673,7 +1672,8 @@ pub fn create_example_enemies() -> Vec<Enemy> {
         )
         .with_resistance("shield", 0.2)
         .with_loot("damaged_crystal", 0.6, (1, 2))
-        .with_vulnerable_frequency(5),
+        .with_vulnerable_frequency(5)
+        .with_habitat("practice_hall"),
 
         // Tier 2: Intermediate
         Enemy::new(
@@ -686,7 +1686,9 @@ pub fn create_example_enemies() -> Vec<Enemy> {
         .with_resistance("healing", 0.5)
         .with_faction(FactionId::UndergroundNetwork)
         .with_loot("research_notes", 0.4, (1, 1))
-        .with_loot("crystal_fragment", 0.5, (1, 3)),
+        .with_loot("crystal_fragment", 0.5, (1, 3))
+        .with_ai_profile(AiProfile::Cautious)
+        .with_status_ability(StatusKind::Disorient, 0.25, 2),
 
         // Tier 3: Advanced
         Enemy::new(
@@ -698,7 +1700,10 @@ pub fn create_example_enemies() -> Vec<Enemy> {
         .with_resistance("detection", 0.6)
         .with_resistance("manipulation", 0.4)
         .with_loot("rare_crystal", 0.3, (1, 1))
-        .with_vulnerable_frequency(7),
+        .with_vulnerable_frequency(7)
+        .with_ai_profile(AiProfile::Relentless)
+        .with_status_ability(StatusKind::ResonanceBurn, 0.35, 3)
+        .with_habitat("unstable_resonance_site"),
     ]
 }
 
@@ -804,4 +1809,264 @@ mod tests {
         assert_eq!(enemies[2].difficulty_tier, DifficultyTier::Advanced);
         assert_eq!(enemies[2].health, 150);
     }
+
+    #[test]
+    fn test_catalog_lookup_and_habitats() {
+        let mut combat = CombatSystem::new();
+        combat.load_catalog(create_example_enemies());
+
+        assert_eq!(combat.find_enemy("rogue practitioner").unwrap().id, "rogue_practitioner");
+        assert_eq!(combat.find_enemy("anomaly").unwrap().id, "resonance_anomaly");
+        assert!(combat.find_enemy("dragon").is_none());
+
+        let at_site = combat.enemies_at("unstable_resonance_site");
+        assert_eq!(at_site.len(), 1);
+        assert_eq!(at_site[0].ai_profile, AiProfile::Relentless);
+    }
+
+    #[test]
+    fn test_bestiary_records_encounters() {
+        let mut combat = CombatSystem::new();
+        assert!(combat.bestiary_report(None).unwrap().contains("empty"));
+
+        let enemy = combat.find_enemy("corrupted_shard").unwrap();
+        combat.start_encounter(enemy).unwrap();
+
+        let report = combat.bestiary_report(None).unwrap();
+        assert!(report.contains("Corrupted Crystal Shard"));
+        assert!(report.contains("encountered 1, defeated 0"));
+
+        let details = combat.bestiary_report(Some("shard")).unwrap();
+        assert!(details.contains("Vulnerable frequency: 5"));
+        assert!(details.contains("defeat one to learn"));
+
+        assert!(combat.bestiary_report(Some("anomaly")).is_err());
+    }
+
+    #[test]
+    fn test_status_effects_tick_on_enemy_turn() {
+        let mut combat = CombatSystem::new();
+        let mut player = Player::new("Test".to_string());
+        let mut world = WorldState::new();
+        let mut magic = MagicSystem::new();
+
+        let enemy = combat.find_enemy("corrupted_shard").unwrap();
+        combat.start_encounter(enemy).unwrap();
+
+        // A stunned enemy loses its attack
+        combat.apply_status(Combatant::Enemy, StatusKind::Stun, 1).unwrap();
+        let energy = player.mental_state.current_energy;
+        let output = combat.enemy_turn(&mut player, &mut magic, &mut world).unwrap();
+        assert!(output.contains("stunned and cannot act"));
+        assert_eq!(player.mental_state.current_energy, energy);
+
+        // A burn can finish a weakened enemy before it acts
+        combat.apply_status(Combatant::Enemy, StatusKind::ResonanceBurn, 2).unwrap();
+        combat.active_encounter.as_mut().unwrap().enemy.health = 3;
+        let output = combat.enemy_turn(&mut player, &mut magic, &mut world).unwrap();
+        assert!(output.contains("Resonance burn sears"));
+        assert!(output.contains("VICTORY"));
+        assert!(!combat.is_in_combat());
+        assert_eq!(combat.bestiary["corrupted_shard"].defeats, 1);
+    }
+
+    #[test]
+    fn test_player_status_effects_persist_in_combat_state() {
+        let mut combat = CombatSystem::new();
+        let mut player = Player::new("Test".to_string());
+        let mut world = WorldState::new();
+
+        let enemy = combat.find_enemy("resonance_anomaly").unwrap();
+        assert_eq!(enemy.status_abilities[0].kind, StatusKind::ResonanceBurn);
+        combat.start_encounter(enemy).unwrap();
+        assert!(combat.apply_status(Combatant::Player, StatusKind::Disorient, 2).unwrap().contains("disoriented"));
+        combat.apply_status(Combatant::Player, StatusKind::Stun, 1).unwrap();
+
+        let json = serde_json::to_string(&combat).unwrap();
+        let mut restored: CombatSystem = serde_json::from_str(&json).unwrap();
+        assert!(restored.get_status().unwrap().contains("Your Conditions: Disorient (2 turns), Stun (1 turn)"));
+
+        // Stunned players can't defend, and the stun is spent
+        let output = restored.player_defend(&mut player, &mut world, DefenseType::Shield).unwrap();
+        assert!(output.contains("can't raise a defense"));
+        assert!(restored.get_status().unwrap().contains("Your Conditions: Disorient (1 turn)"));
+    }
+
+    #[test]
+    fn test_companions_fight_and_persist() {
+        let mut combat = CombatSystem::new();
+        let mut player = Player::new("Test".to_string());
+        let mut world = WorldState::new();
+        let mut magic = MagicSystem::new();
+        combat.party_mut().recruit("captain_vera", &mut player).unwrap();
+
+        let enemy = combat.find_enemy("corrupted_shard").unwrap();
+        combat.start_encounter(enemy).unwrap();
+        combat.active_encounter.as_mut().unwrap().enemy.health = 1;
+
+        // Even while the player is stunned, a faster companion acts before the enemy
+        combat.apply_status(Combatant::Player, StatusKind::Stun, 1).unwrap();
+        let output = combat.player_attack(&mut player, &mut world, &mut magic, "light").unwrap();
+        assert!(output.contains("Captain Vera strikes"));
+        assert!(output.contains("VICTORY"));
+        assert!(!combat.is_in_combat());
+        assert_eq!(combat.bestiary["corrupted_shard"].defeats, 1);
+
+        combat.party_mut().members_mut()[0].take_damage(25);
+        let json = serde_json::to_string(&combat).unwrap();
+        let restored: CombatSystem = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.party().members()[0].health, 35);
+    }
+
+    #[test]
+    fn test_non_lethal_resolution() {
+        let mut combat = CombatSystem::new();
+        let mut player = Player::new("Test".to_string());
+        let mut world = WorldState::new();
+        let mut magic = MagicSystem::new();
+
+        // Creatures without a faction can't be reasoned with, and refusing costs no turn
+        let shard = combat.find_enemy("corrupted_shard").unwrap();
+        combat.start_encounter(shard).unwrap();
+        let output = combat.attempt_tactic(&mut player, &mut world, &mut magic, Tactic::Parley).unwrap();
+        assert!(output.contains("cannot be reasoned with"));
+        assert_eq!(combat.active_encounter.as_ref().unwrap().turn_count, 0);
+
+        // Surrender always ends the fight, at a price
+        let output = combat.attempt_tactic(&mut player, &mut world, &mut magic, Tactic::Surrender).unwrap();
+        assert!(output.contains("ENCOUNTER RESOLVED (surrender)"));
+        assert!(!combat.is_in_combat());
+        assert_eq!(player.inventory.silver, 50 - 16);
+        assert_eq!(player.mental_state.fatigue, 15);
+        assert_eq!(combat.bestiary["corrupted_shard"].spared, 0);
+
+        // A parley either wins standing with the enemy's faction or costs the turn
+        let rogue = combat.find_enemy("rogue_practitioner").unwrap();
+        let faction = rogue.faction_affiliation.unwrap();
+        combat.start_encounter(rogue).unwrap();
+        let output = combat.attempt_tactic(&mut player, &mut world, &mut magic, Tactic::Parley).unwrap();
+        if output.contains("ENCOUNTER RESOLVED") {
+            assert_eq!(player.faction_reputation(faction), 5);
+            assert_eq!(combat.bestiary["rogue_practitioner"].spared, 1);
+        } else {
+            assert!(output.contains("fails"));
+            if combat.is_in_combat() {
+                assert_eq!(combat.active_encounter.as_ref().unwrap().failed_tactics, 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_boss_phases_change_behaviour() {
+        let phase = |threshold: f32, name: &str| EnemyPhase {
+            health_threshold: threshold,
+            name: name.to_string(),
+            announcement: String::new(),
+            resistances: HashMap::new(),
+            vulnerable_frequency: None,
+            ai_profile: None,
+            status_abilities: None,
+            damage_multiplier: 1.0,
+            cleanse: false,
+        };
+        let boss = Enemy::new("boss".to_string(), "Boss".to_string(), "Big.".to_string(), DifficultyTier::Boss)
+            .with_resistance("light", 0.2)
+            .with_phase(EnemyPhase {
+                resistances: [("light".to_string(), 0.0)].into_iter().collect(),
+                ai_profile: Some(AiProfile::Relentless),
+                damage_multiplier: 1.5,
+                cleanse: true,
+                ..phase(0.3, "Collapse")
+            })
+            .with_phase(EnemyPhase {
+                resistances: [("light".to_string(), 0.7)].into_iter().collect(),
+                ..phase(0.6, "Fracture")
+            });
+        assert_eq!(boss.phases[0].name, "Fracture");
+
+        let mut combat = CombatSystem::new();
+        combat.start_encounter(boss).unwrap();
+        let encounter = combat.active_encounter.as_mut().unwrap();
+        encounter.enemy_effects.apply(StatusKind::Disorient, 2, "Boss");
+
+        // A single heavy blow can skip straight through both phases
+        encounter.enemy.take_damage(200);
+        let output = encounter.check_phases();
+        assert!(output.contains("Fracture phase"));
+        assert!(output.contains("Collapse phase"));
+        assert!(output.contains("shrugs off"));
+        assert_eq!(encounter.enemy.magical_resistance["light"], 0.0);
+        assert_eq!(encounter.enemy.ai_profile, AiProfile::Relentless);
+        assert_eq!(encounter.enemy.phase_damage_multiplier(), 1.5);
+        assert!(encounter.enemy_effects.summary().is_none());
+        assert!(encounter.check_phases().is_empty());
+        assert!(combat.get_status().unwrap().contains("Enemy Phase: Collapse (2 of 2)"));
+    }
+
+    #[test]
+    fn test_enemy_attacks_wound_health_and_burns_scar() {
+        let mut combat = CombatSystem::new();
+        let mut player = Player::new("Test".to_string());
+        let mut world = WorldState::new();
+        let mut magic = MagicSystem::new();
+
+        let enemy = combat.find_enemy("corrupted_shard").unwrap();
+        combat.start_encounter(enemy).unwrap();
+        let energy = player.mental_state.current_energy;
+        combat.enemy_turn(&mut player, &mut magic, &mut world).unwrap();
+        assert!(player.health.current < player.health.max);
+        assert_eq!(player.mental_state.current_energy, energy);
+
+        combat.apply_status(Combatant::Player, StatusKind::ResonanceBurn, 2).unwrap();
+        let (output, _) = combat.begin_player_turn(&mut player, &mut world).unwrap();
+        assert!(output.contains("New injury - Burn"));
+        assert_eq!(player.health.severity(InjuryKind::Burn), 1);
+    }
+
+    #[test]
+    fn test_combat_log_survives_the_fight() {
+        let mut combat = CombatSystem::new();
+        let mut player = Player::new("Test".to_string());
+        let mut world = WorldState::new();
+        let mut magic = MagicSystem::new();
+        assert_eq!(combat.combat_log_report(), "You haven't fought anything yet.");
+
+        let enemy = combat.find_enemy("corrupted_shard").unwrap();
+        combat.start_encounter(enemy).unwrap();
+        combat.begin_player_turn(&mut player, &mut world).unwrap();
+        combat.enemy_turn(&mut player, &mut magic, &mut world).unwrap();
+        let output = combat.player_flee(&mut player).unwrap();
+        assert!(output.contains("--- Battle summary: 1 round vs"));
+        assert!(output.contains("Damage dealt: 0"));
+
+        let log = combat.combat_log().unwrap();
+        assert!(log.entries.iter().any(|entry| matches!(
+            &entry.event, LogEvent::Damage { target, total, .. } if target == "You" && *total > 0
+        )));
+        let report = combat.combat_log_report();
+        assert!(report.contains("Opening:"));
+        assert!(report.contains("Round 1:"));
+        assert!(report.contains("You health: 100 ->"));
+        assert!(report.contains("You flee"));
+    }
+
+    #[test]
+    fn test_prepared_wards_soften_enemy_attacks() {
+        let mut combat = CombatSystem::new();
+        let mut player = Player::new("Test".to_string());
+        let mut world = WorldState::new();
+        let mut magic = MagicSystem::new();
+        assert_eq!(combat.apply_wards(CombatWards { shield_charges: 1, dampening: 0.4 }), "");
+
+        let enemy = combat.find_enemy("corrupted_shard").unwrap();
+        combat.start_encounter(enemy).unwrap();
+        let message = combat.apply_wards(CombatWards { shield_charges: 1, dampening: 0.4 });
+        assert!(message.contains("shield ward (1 charge)"));
+
+        let output = combat.enemy_turn(&mut player, &mut magic, &mut world).unwrap();
+        assert!(output.contains("Your shield ward flares"));
+        assert_eq!(combat.active_encounter.as_ref().unwrap().wards.shield_charges, 0);
+        assert!(combat.get_status().unwrap().contains("Your Wards: resonance dampener (-40% enemy damage)"));
+        assert!(combat.combat_log_report().contains("x0.60 dampener"));
+    }
 }
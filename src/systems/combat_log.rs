@@ -0,0 +1,220 @@
+//! Round-by-round record of a fight
+//!
+//! Every roll, each component of a damage calculation and every change to
+//! health, energy or fatigue is recorded as it happens. When a fight ends
+//! the player sees a short summary; `combat log` expands the whole record
+//! for anyone who wants to check the math.
+
+use serde::{Deserialize, Serialize};
+
+/// One factor in a damage calculation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DamageComponent {
+    pub label: String,
+    pub value: f32,
+    /// Whether the value scales the running total rather than adding to it
+    pub multiplier: bool,
+}
+
+impl DamageComponent {
+    /// Starting amount or flat adjustment
+    pub fn flat(label: &str, value: f32) -> Self {
+        Self { label: label.to_string(), value, multiplier: false }
+    }
+
+    /// Scaling factor
+    pub fn scale(label: &str, value: f32) -> Self {
+        Self { label: label.to_string(), value, multiplier: true }
+    }
+
+    fn render(&self, first: bool) -> String {
+        if self.multiplier {
+            format!("x{:.2} {}", self.value, self.label)
+        } else if first {
+            format!("{} {}", self.value, self.label)
+        } else {
+            format!("{:+} {}", self.value, self.label)
+        }
+    }
+}
+
+/// Something that happened during a fight
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LogEvent {
+    /// A chance-based check; `roll` is absent when another system rolled
+    Roll { label: String, roll: Option<f32>, chance: f32, success: bool },
+    /// Damage and how it was calculated
+    Damage { source: String, target: String, components: Vec<DamageComponent>, total: i32 },
+    /// A tracked value changed
+    Resource { owner: String, resource: String, before: i32, after: i32 },
+    /// Anything else worth recording
+    Note(String),
+}
+
+/// A logged event and the round it happened in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// Round number; 0 is before anyone acts
+    pub round: u32,
+    pub event: LogEvent,
+}
+
+/// Record of one encounter
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CombatLog {
+    pub enemy: String,
+    pub round: u32,
+    pub entries: Vec<LogEntry>,
+}
+
+impl CombatLog {
+    pub fn new(enemy: &str) -> Self {
+        Self { enemy: enemy.to_string(), round: 0, entries: Vec::new() }
+    }
+
+    /// Start the next round
+    pub fn begin_round(&mut self) {
+        self.round += 1;
+    }
+
+    /// Record an event in the current round
+    pub fn record(&mut self, event: LogEvent) {
+        self.entries.push(LogEntry { round: self.round, event });
+    }
+
+    /// Record a change to a tracked value, skipping ones that didn't change
+    pub fn resource(&mut self, owner: &str, resource: &str, before: i32, after: i32) {
+        if before != after {
+            self.record(LogEvent::Resource {
+                owner: owner.to_string(),
+                resource: resource.to_string(),
+                before,
+                after,
+            });
+        }
+    }
+
+    /// Record a note
+    pub fn note(&mut self, text: impl Into<String>) {
+        self.record(LogEvent::Note(text.into()));
+    }
+
+    /// Total damage dealt by or to the player's side
+    fn damage_totals(&self) -> (i32, i32, i32) {
+        let mut dealt = 0;
+        let mut taken = 0;
+        let mut largest = 0;
+        for entry in &self.entries {
+            if let LogEvent::Damage { target, total, .. } = &entry.event {
+                if *target == self.enemy {
+                    dealt += total;
+                    largest = largest.max(*total);
+                } else {
+                    taken += total;
+                }
+            }
+        }
+        (dealt, taken, largest)
+    }
+
+    /// Short post-battle summary
+    pub fn summary(&self) -> String {
+        let (dealt, taken, largest) = self.damage_totals();
+        let rolls: Vec<bool> = self.entries.iter()
+            .filter_map(|entry| match &entry.event {
+                LogEvent::Roll { success, .. } => Some(*success),
+                _ => None,
+            })
+            .collect();
+
+        format!(
+            "--- Battle summary: {} round{} vs {} ---\n\
+             Damage dealt: {} (largest hit {}) | Damage taken: {} | Checks passed: {}/{}\n\
+             Type 'combat log' for the full breakdown.",
+            self.round, if self.round == 1 { "" } else { "s" }, self.enemy,
+            dealt, largest, taken,
+            rolls.iter().filter(|success| **success).count(), rolls.len()
+        )
+    }
+
+    /// Every entry, grouped by round
+    pub fn render(&self) -> String {
+        let mut output = format!("=== COMBAT LOG: {} ===", self.enemy);
+        let mut current_round = None;
+
+        for entry in &self.entries {
+            if current_round != Some(entry.round) {
+                current_round = Some(entry.round);
+                if entry.round == 0 {
+                    output.push_str("\nOpening:");
+                } else {
+                    output.push_str(&format!("\nRound {}:", entry.round));
+                }
+            }
+            output.push_str(&format!("\n  {}", render_event(&entry.event)));
+        }
+
+        if self.entries.is_empty() {
+            output.push_str("\nNothing has happened yet.");
+        }
+        output
+    }
+}
+
+fn render_event(event: &LogEvent) -> String {
+    match event {
+        LogEvent::Roll { label, roll, chance, success } => {
+            let outcome = if *success { "success" } else { "failure" };
+            match roll {
+                Some(roll) => format!("{}: rolled {:.0} vs {:.0}% -> {}", label, roll * 100.0, chance * 100.0, outcome),
+                None => format!("{}: {:.0}% chance -> {}", label, chance * 100.0, outcome),
+            }
+        }
+        LogEvent::Damage { source, target, components, total } => {
+            let breakdown: Vec<String> = components.iter()
+                .enumerate()
+                .map(|(index, component)| component.render(index == 0))
+                .collect();
+            format!("{} -> {}: {} damage = {}", source, target, total, breakdown.join(" "))
+        }
+        LogEvent::Resource { owner, resource, before, after } => {
+            format!("{} {}: {} -> {} ({:+})", owner, resource, before, after, after - before)
+        }
+        LogEvent::Note(text) => text.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_renders_rounds_and_summary() {
+        let mut log = CombatLog::new("Shard");
+        log.note("The fight begins.");
+        log.begin_round();
+        log.record(LogEvent::Roll { label: "Cast energy_blast".to_string(), roll: None, chance: 0.72, success: true });
+        log.record(LogEvent::Damage {
+            source: "You".to_string(),
+            target: "Shard".to_string(),
+            components: vec![DamageComponent::flat("base", 10.0), DamageComponent::scale("vulnerability", 1.5)],
+            total: 15,
+        });
+        log.resource("Shard", "health", 50, 35);
+        log.resource("You", "energy", 30, 30);
+        log.begin_round();
+        log.record(LogEvent::Roll { label: "Status: Stun".to_string(), roll: Some(0.9), chance: 0.25, success: false });
+        log.record(LogEvent::Damage { source: "Shard".to_string(), target: "You".to_string(), components: Vec::new(), total: 12 });
+
+        let text = log.render();
+        assert!(text.contains("Opening:\n  The fight begins."));
+        assert!(text.contains("You -> Shard: 15 damage = 10 base x1.50 vulnerability"));
+        assert!(text.contains("Shard health: 50 -> 35 (-15)"));
+        assert!(!text.contains("You energy"));
+        assert!(text.contains("Status: Stun: rolled 90 vs 25% -> failure"));
+
+        let summary = log.summary();
+        assert!(summary.contains("2 rounds vs Shard"));
+        assert!(summary.contains("Damage dealt: 15 (largest hit 15) | Damage taken: 12 | Checks passed: 1/2"));
+    }
+}
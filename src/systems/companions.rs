@@ -0,0 +1,361 @@
+//! Companion NPCs who fight alongside the player
+//!
+//! Some NPCs can be recruited into the player's party: guards hired for
+//! silver, students who volunteer once the player has standing with the
+//! scholars, and agents lent by a faction that trusts the player. In combat
+//! each companion acts on their own initiative with their own abilities.
+//! Companion health persists between fights (and in saves); a companion
+//! knocked out stays down until the party rests.
+
+use crate::core::Player;
+use crate::systems::combat::{DifficultyTier, Enemy};
+use crate::systems::factions::FactionId;
+use crate::systems::status_effects::{StatusEffects, StatusKind};
+use crate::GameResult;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Most companions the player can lead at once
+pub const MAX_PARTY_SIZE: usize = 2;
+/// Chance an enemy attacks a companion instead of the player
+pub const COMPANION_TARGET_CHANCE: f64 = 0.3;
+/// Fraction of their maximum health companions regain per rest
+const REST_RECOVERY: f32 = 0.25;
+/// Player energy below which a healer tends to them instead of attacking
+const MEND_THRESHOLD: f32 = 0.4;
+
+/// How a companion came to join the party
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompanionRole {
+    HiredGuard,
+    StudentAlly,
+    FactionAgent,
+}
+
+impl CompanionRole {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompanionRole::HiredGuard => "Hired Guard",
+            CompanionRole::StudentAlly => "Student Ally",
+            CompanionRole::FactionAgent => "Faction Agent",
+        }
+    }
+}
+
+/// Something a companion can do on their turn
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CompanionAbility {
+    /// Attack the enemy directly
+    Strike { damage: i32 },
+    /// Restore some of the player's mental energy
+    Mend { energy: i32 },
+    /// Inflict a status effect on the enemy
+    Hex { kind: StatusKind, duration: u32 },
+    /// Take half of each blow aimed at the player
+    Guard,
+}
+
+/// A recruited ally
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Companion {
+    pub npc_id: String,
+    pub name: String,
+    pub role: CompanionRole,
+    pub health: i32,
+    pub max_health: i32,
+    /// Turn order; companions faster than the enemy act before it
+    pub initiative: i32,
+    pub abilities: Vec<CompanionAbility>,
+    #[serde(default)]
+    pub knocked_out: bool,
+}
+
+impl Companion {
+    /// Whether the companion can act
+    pub fn is_active(&self) -> bool {
+        !self.knocked_out && self.health > 0
+    }
+
+    /// Whether the companion shields the player
+    pub fn guards(&self) -> bool {
+        self.is_active() && self.abilities.contains(&CompanionAbility::Guard)
+    }
+
+    /// Take damage, returning a message if it knocks them out
+    pub fn take_damage(&mut self, amount: i32) -> Option<String> {
+        self.health = (self.health - amount).max(0);
+        if self.health == 0 && !self.knocked_out {
+            self.knocked_out = true;
+            return Some(format!("{} is knocked out!", self.name));
+        }
+        None
+    }
+
+    /// Choose and perform this turn's action
+    pub fn act(&self, enemy: &mut Enemy, enemy_effects: &mut StatusEffects, player: &mut Player, rng: &mut impl Rng) -> String {
+        let energy_ratio = player.mental_state.current_energy as f32 / player.mental_state.max_energy.max(1) as f32;
+
+        // Tend to a flagging player first
+        for ability in &self.abilities {
+            if let CompanionAbility::Mend { energy } = ability {
+                if energy_ratio < MEND_THRESHOLD {
+                    player.recover_energy(*energy, 0);
+                    return format!("{} steadies your focus. (+{} energy)", self.name, energy);
+                }
+            }
+        }
+
+        // Then keep the enemy hampered
+        for ability in &self.abilities {
+            if let CompanionAbility::Hex { kind, duration } = ability {
+                if !enemy_effects.has(*kind) {
+                    return format!("{} works a hex. {}", self.name, enemy_effects.apply(*kind, *duration, &enemy.name));
+                }
+            }
+        }
+
+        let strike = self.abilities.iter().find_map(|ability| match ability {
+            CompanionAbility::Strike { damage } => Some(*damage),
+            _ => None,
+        });
+        match strike {
+            Some(damage) => {
+                let damage = rng.gen_range(damage * 3 / 4..=damage);
+                enemy.take_damage(damage);
+                format!("{} strikes {}! (Damage: {}, Enemy HP: {}/{})", self.name, enemy.name, damage, enemy.health, enemy.max_health)
+            }
+            None => format!("{} holds position beside you.", self.name),
+        }
+    }
+}
+
+/// What a player must offer for an NPC to join
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecruitTerms {
+    /// Silver paid on recruitment
+    pub silver: i32,
+    /// Minimum reputation with a faction
+    pub min_standing: Option<(FactionId, i32)>,
+}
+
+/// Recruitable NPCs with their companion stats and terms
+pub fn companion_template(npc_id: &str) -> Option<(Companion, RecruitTerms)> {
+    let companion = |name: &str, role, max_health, initiative, abilities: Vec<CompanionAbility>| Companion {
+        npc_id: npc_id.to_string(),
+        name: name.to_string(),
+        role,
+        health: max_health,
+        max_health,
+        initiative,
+        abilities,
+        knocked_out: false,
+    };
+
+    match npc_id {
+        "captain_vera" => Some((
+            companion("Captain Vera", CompanionRole::HiredGuard, 60, 14, vec![
+                CompanionAbility::Guard,
+                CompanionAbility::Strike { damage: 12 },
+            ]),
+            RecruitTerms { silver: 40, min_standing: None },
+        )),
+        "assistant_thomas" => Some((
+            companion("Thomas", CompanionRole::StudentAlly, 35, 8, vec![
+                CompanionAbility::Mend { energy: 12 },
+                CompanionAbility::Strike { damage: 6 },
+            ]),
+            RecruitTerms { silver: 0, min_standing: Some((FactionId::NeutralScholars, 10)) },
+        )),
+        "echo_voidwalker" => Some((
+            companion("Echo", CompanionRole::FactionAgent, 45, 16, vec![
+                CompanionAbility::Hex { kind: StatusKind::Disorient, duration: 2 },
+                CompanionAbility::Strike { damage: 10 },
+            ]),
+            RecruitTerms { silver: 0, min_standing: Some((FactionId::UndergroundNetwork, 30)) },
+        )),
+        "warden_gareth" => Some((
+            companion("Gareth", CompanionRole::FactionAgent, 70, 9, vec![
+                CompanionAbility::Guard,
+                CompanionAbility::Hex { kind: StatusKind::Stun, duration: 1 },
+                CompanionAbility::Strike { damage: 8 },
+            ]),
+            RecruitTerms { silver: 0, min_standing: Some((FactionId::MagistersCouncil, 30)) },
+        )),
+        _ => None,
+    }
+}
+
+/// Initiative of an enemy, used to order companion turns around it
+pub fn enemy_initiative(tier: DifficultyTier) -> i32 {
+    match tier {
+        DifficultyTier::Beginner => 5,
+        DifficultyTier::Intermediate => 10,
+        DifficultyTier::Advanced => 15,
+        DifficultyTier::Boss => 20,
+    }
+}
+
+/// The player's companions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Party {
+    members: Vec<Companion>,
+}
+
+impl Party {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn members(&self) -> &[Companion] {
+        &self.members
+    }
+
+    pub fn members_mut(&mut self) -> &mut [Companion] {
+        &mut self.members
+    }
+
+    /// Find a member by NPC ID or name
+    pub fn find(&self, query: &str) -> Option<&Companion> {
+        let query = query.trim().to_lowercase();
+        self.members.iter().find(|member| {
+            member.npc_id == query || member.name.to_lowercase().split_whitespace().any(|word| word == query)
+                || member.name.to_lowercase() == query
+        })
+    }
+
+    /// Recruit an NPC, paying their terms
+    pub fn recruit(&mut self, npc_id: &str, player: &mut Player) -> GameResult<String> {
+        let (companion, terms) = companion_template(npc_id)
+            .ok_or_else(|| crate::GameError::InvalidCommand("They aren't interested in joining you.".to_string()))?;
+
+        if self.members.iter().any(|member| member.npc_id == npc_id) {
+            return Err(crate::GameError::InvalidCommand(format!("{} is already with you.", companion.name)).into());
+        }
+        if self.members.len() >= MAX_PARTY_SIZE {
+            return Err(crate::GameError::InvalidCommand(format!(
+                "You can lead at most {} companions. Dismiss someone first.", MAX_PARTY_SIZE
+            )).into());
+        }
+        if let Some((faction, min)) = terms.min_standing {
+            if player.faction_reputation(faction) < min {
+                return Err(crate::GameError::InsufficientResources(format!(
+                    "{} will only join someone with {} reputation of at least {} (yours: {}).",
+                    companion.name, faction.display_name(), min, player.faction_reputation(faction)
+                )).into());
+            }
+        }
+        if player.inventory.silver < terms.silver {
+            return Err(crate::GameError::InsufficientResources(format!(
+                "{} asks {} silver for their service. You have {}.", companion.name, terms.silver, player.inventory.silver
+            )).into());
+        }
+
+        player.inventory.silver -= terms.silver;
+        let fee = if terms.silver > 0 { format!(" (paid {} silver)", terms.silver) } else { String::new() };
+        let message = format!("{} joins you as a {}{}.", companion.name, companion.role.name().to_lowercase(), fee);
+        self.members.push(companion);
+        Ok(message)
+    }
+
+    /// Dismiss a companion
+    pub fn dismiss(&mut self, query: &str) -> GameResult<String> {
+        let npc_id = self.find(query)
+            .map(|member| member.npc_id.clone())
+            .ok_or_else(|| crate::GameError::InvalidCommand(format!("No companion called '{}'.", query)))?;
+        let index = self.members.iter().position(|member| member.npc_id == npc_id).unwrap_or_default();
+        let member = self.members.remove(index);
+        Ok(format!("{} parts ways with you.", member.name))
+    }
+
+    /// Recover health during a rest; knocked-out companions get back up
+    pub fn rest(&mut self) -> Vec<String> {
+        let mut messages = Vec::new();
+        for member in &mut self.members {
+            let recovered = ((member.max_health as f32 * REST_RECOVERY) as i32).max(1);
+            member.health = (member.health + recovered).min(member.max_health);
+            if member.knocked_out {
+                member.knocked_out = false;
+                messages.push(format!("{} is back on their feet ({}/{}).", member.name, member.health, member.max_health));
+            }
+        }
+        messages
+    }
+
+    /// Party roster
+    pub fn report(&self) -> String {
+        if self.members.is_empty() {
+            return "You travel alone. Some people can be recruited with 'recruit <name>'.".to_string();
+        }
+
+        let mut output = String::from("=== Companions ===\n");
+        for member in &self.members {
+            let state = if member.knocked_out { " - knocked out".to_string() } else { String::new() };
+            output.push_str(&format!(
+                "{} ({}) HP {}/{}, initiative {}{}\n",
+                member.name, member.role.name(), member.health, member.max_health, member.initiative, state
+            ));
+        }
+        output.trim_end().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recruit_terms_and_party_limit() {
+        let mut party = Party::new();
+        let mut player = Player::new("Test".to_string());
+        player.inventory.silver = 30;
+
+        // Not enough silver for the guard, not enough standing for the agent
+        assert!(party.recruit("captain_vera", &mut player).is_err());
+        assert!(party.recruit("echo_voidwalker", &mut player).is_err());
+        assert!(party.recruit("dr_felix", &mut player).is_err());
+
+        player.inventory.silver = 100;
+        player.modify_faction_reputation(FactionId::NeutralScholars, 20);
+        player.modify_faction_reputation(FactionId::MagistersCouncil, 40);
+        assert!(party.recruit("captain_vera", &mut player).unwrap().contains("paid 40 silver"));
+        assert_eq!(player.inventory.silver, 60);
+        assert!(party.recruit("captain_vera", &mut player).is_err());
+        party.recruit("assistant_thomas", &mut player).unwrap();
+        assert!(party.recruit("warden_gareth", &mut player).is_err());
+
+        assert!(party.dismiss("vera").unwrap().contains("Captain Vera"));
+        party.recruit("warden_gareth", &mut player).unwrap();
+        assert_eq!(party.members().len(), MAX_PARTY_SIZE);
+    }
+
+    #[test]
+    fn test_knockout_and_rest_recovery() {
+        let (mut companion, _) = companion_template("assistant_thomas").unwrap();
+        assert!(companion.take_damage(50).unwrap().contains("knocked out"));
+        assert!(!companion.is_active());
+
+        let mut party = Party { members: vec![companion] };
+        let messages = party.rest();
+        assert!(messages[0].contains("back on their feet"));
+        assert!(party.members()[0].is_active());
+        assert_eq!(party.members()[0].health, 8);
+    }
+
+    #[test]
+    fn test_companion_actions() {
+        let mut player = Player::new("Test".to_string());
+        let mut enemy = Enemy::new("e".to_string(), "Shard".to_string(), "x".to_string(), DifficultyTier::Beginner);
+        let mut effects = StatusEffects::new();
+
+        let (thomas, _) = companion_template("assistant_thomas").unwrap();
+        player.mental_state.current_energy = 5;
+        assert!(thomas.act(&mut enemy, &mut effects, &mut player, &mut rand::thread_rng()).contains("steadies your focus"));
+        assert_eq!(player.mental_state.current_energy, 17);
+
+        let (gareth, _) = companion_template("warden_gareth").unwrap();
+        assert!(gareth.act(&mut enemy, &mut effects, &mut player, &mut rand::thread_rng()).contains("stunned"));
+        let health = enemy.health;
+        assert!(gareth.act(&mut enemy, &mut effects, &mut player, &mut rand::thread_rng()).contains("strikes"));
+        assert!(enemy.health < health);
+    }
+}
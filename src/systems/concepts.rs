@@ -0,0 +1,266 @@
+//! Scientific concept glossary
+//!
+//! Every theory is grounded in real science. The glossary explains each
+//! scientific concept twice: once as the Resonance scholars understand it,
+//! and once in real-world terms, so players can connect the game's magic
+//! to the physics and biology it is modelled on.
+
+use crate::core::Player;
+use crate::systems::knowledge::Theory;
+use serde::{Deserialize, Serialize};
+
+/// Glossary entry for a single scientific concept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConceptEntry {
+    /// Normalized identifier (e.g. "wave_physics")
+    pub id: String,
+    /// Display name matching the theory data (e.g. "Wave Physics")
+    pub name: String,
+    /// Theories whose study introduces this concept
+    pub theories: Vec<String>,
+    /// How the concept is understood within the game world
+    pub in_game: String,
+    /// The real-world science behind it
+    pub real_world: String,
+}
+
+/// Lookup table of all scientific concepts referenced by theories
+#[derive(Debug, Clone)]
+pub struct ConceptGlossary {
+    entries: Vec<ConceptEntry>,
+}
+
+/// (name, theories, in-game explanation, real-world framing)
+const BUILT_IN_CONCEPTS: &[(&str, &[&str], &str, &str)] = &[
+    ("Wave Physics", &["harmonic_fundamentals"],
+     "Magical energy travels as waves through the resonant medium. A mage who knows a spell's wavelength can predict where it will reinforce and where it will cancel.",
+     "Waves carry energy without carrying matter. Their behaviour is described by wavelength, frequency and amplitude, and two waves can interfere constructively or destructively."),
+    ("Harmonic Oscillation", &["harmonic_fundamentals"],
+     "Every crystal and mind has natural frequencies. Driving them at those frequencies builds resonance with very little effort.",
+     "A system displaced from equilibrium oscillates at a natural frequency; driving it at that frequency produces resonance, as with a child's swing or a tuning fork."),
+    ("Energy Conservation", &["harmonic_fundamentals"],
+     "No spell creates energy. Every effect is paid for by the caster's mental reserves or a crystal's stored lattice energy.",
+     "The first law of thermodynamics: energy in a closed system is neither created nor destroyed, only converted between forms."),
+    ("Thermodynamics", &[],
+     "Wasted resonance becomes heat, which is why careless casting leaves crystals warm and mages drained.",
+     "The study of heat, work and energy transfer. Every real energy conversion loses some energy as heat, increasing entropy."),
+    ("Crystallography", &["crystal_structures"],
+     "The lattice of a crystal decides which frequencies it can hold. Quartz, amethyst and obsidian each favour different bands.",
+     "The science of how atoms are arranged in crystalline solids, usually studied by diffracting X-rays through the lattice."),
+    ("Solid State Physics", &["crystal_structures"],
+     "Flaws in a lattice trap and leak resonance. This is why crystals degrade with use and why purity matters.",
+     "The physics of rigid matter, explaining how lattice structure and defects determine electrical, optical and mechanical properties."),
+    ("Materials Science", &["crystal_structures"],
+     "Choosing the right focus crystal for a task is an engineering decision: strength, purity and tolerance all trade off.",
+     "An interdisciplinary field linking a material's structure and processing to its properties and performance."),
+    ("Neuroscience", &["mental_resonance"],
+     "A mage's mind is itself an oscillator. Focus is the skill of holding that rhythm steady while the spell draws on it.",
+     "The study of the nervous system. Neurons fire in rhythmic patterns, measured as brain waves, that correlate with attention and rest."),
+    ("Psychology", &["mental_resonance"],
+     "Fatigue, confidence and distraction all alter mental resonance, which is why exhausted mages miscast.",
+     "The scientific study of mind and behaviour, including how fatigue and stress degrade attention and performance."),
+    ("Biophysics", &["mental_resonance"],
+     "Living tissue conducts resonance, but imperfectly. The body shapes the signal before it ever reaches a crystal.",
+     "The application of physics to biological systems, such as the electrical signalling of nerves and the mechanics of cells."),
+    ("Electromagnetic Theory", &["light_manipulation"],
+     "Light is resonance at very high frequency, so a mage who can tune that high can bend and shape it.",
+     "Maxwell's equations describe electricity, magnetism and light as aspects of a single electromagnetic field."),
+    ("Optics", &["light_manipulation"],
+     "Lenses of shaped crystal focus illumination spells, and a prism splits them into their component frequencies.",
+     "The study of how light is reflected, refracted and focused by mirrors, lenses and prisms."),
+    ("Photonics", &["light_manipulation"],
+     "Advanced light-workers send messages as pulses of coloured light through crystal fibres.",
+     "The science of generating, guiding and detecting light, used in lasers and fibre-optic communication."),
+    ("Biology", &["bio_resonance"],
+     "Every living thing has its own resonant signature. Healers read that signature before they try to mend it.",
+     "The study of living organisms, their structure, function, growth and evolution."),
+    ("Physiology", &["bio_resonance"],
+     "Healing resonance must match the body's own rhythms of heartbeat and breath, or it does more harm than good.",
+     "The study of how the organs and systems of living things function, from heart rhythm to nerve conduction."),
+    ("Biochemistry", &["bio_resonance"],
+     "Resonance can nudge the body's chemistry along, but it cannot supply materials the body does not have.",
+     "The chemistry of living processes, including the enzymes and reactions that build and repair tissue."),
+    ("Medical Physics", &["bio_resonance"],
+     "Diagnostic resonance reflects off injured tissue differently from healthy tissue, so a trained healer can see wounds hidden beneath the skin.",
+     "The application of physics to medicine, including ultrasound, which images the body using reflected sound waves."),
+    ("Signal Processing", &["detection_arrays"],
+     "Detection arrays separate a faint magical signature from the background hum of the city.",
+     "Techniques for filtering, transforming and analysing signals to extract information from noise."),
+    ("Pattern Recognition", &["detection_arrays"],
+     "Every mage leaves a characteristic signature. Trained observers can identify who cast a spell, and when.",
+     "Identifying regularities in data, the basis of fingerprint matching, speech recognition and machine learning."),
+    ("Sensor Networks", &["detection_arrays"],
+     "Many small crystals spread across a district report together to locate disturbances precisely.",
+     "Distributed sensors that share measurements, such as seismometer networks that triangulate earthquakes."),
+    ("Quantum Mechanics", &["sympathetic_networks"],
+     "Crystals cut from the same seed stay linked at any distance. What resonates in one is felt in its twin.",
+     "The physics of the very small. Entangled particles show correlations that cannot be explained by local causes, though they cannot transmit messages faster than light."),
+    ("Network Theory", &["sympathetic_networks"],
+     "A web of linked crystals is only as strong as its connections. Well-placed relay nodes carry messages across the realm.",
+     "The mathematics of graphs of nodes and links, used to study the internet, power grids and social networks."),
+    ("Information Theory", &["sympathetic_networks"],
+     "A sympathetic link can carry only so much meaning before the message blurs into noise.",
+     "Shannon's theory quantifying information and the maximum rate at which it can be sent reliably over a noisy channel."),
+    ("Electrical Engineering", &["resonance_amplification"],
+     "Amplifier circuits of linked crystals step resonance up to power city lights and great workings.",
+     "The design of systems that generate, transmit and use electricity, from circuits to transformers."),
+    ("Power Systems", &["resonance_amplification"],
+     "Stored resonance must be distributed carefully. Overload one node and the whole grid can fail in a cascade.",
+     "The generation, storage and distribution of electrical power, including the balancing of supply against demand."),
+    ("Control Theory", &["resonance_amplification"],
+     "Stable amplification needs feedback. Without a damping crystal, resonance runs away and shatters the focus.",
+     "The study of feedback in dynamic systems, used to keep aircraft, thermostats and power plants stable."),
+    ("Systems Theory", &["theoretical_synthesis"],
+     "Great innovations come from seeing how separate schools of resonance interact as a single system.",
+     "An interdisciplinary study of complex systems, focusing on how interactions among parts produce the behaviour of the whole."),
+    ("Mathematical Modeling", &["theoretical_synthesis"],
+     "Before casting a novel spell, a careful mage models it on paper, because an untested spell is a dangerous one.",
+     "Describing real systems with equations so their behaviour can be predicted and tested before building them."),
+    ("Research Methodology", &["theoretical_synthesis"],
+     "Hypothesis, experiment and peer review are how the Magisters' Council separates real discoveries from wishful thinking.",
+     "The scientific method: forming testable hypotheses, controlling variables, and having others scrutinise and reproduce results."),
+    ("Innovation Theory", &["theoretical_synthesis"],
+     "New spells arise from recombining known principles in ways no one has tried before.",
+     "The study of how new ideas and technologies emerge, spread and displace older ones."),
+];
+
+impl Default for ConceptGlossary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConceptGlossary {
+    /// Create the glossary with all built-in concepts
+    pub fn new() -> Self {
+        let entries = BUILT_IN_CONCEPTS
+            .iter()
+            .map(|(name, theories, in_game, real_world)| ConceptEntry {
+                id: normalize_concept(name),
+                name: name.to_string(),
+                theories: theories.iter().map(|t| t.to_string()).collect(),
+                in_game: in_game.to_string(),
+                real_world: real_world.to_string(),
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    /// Find a concept by name or id, accepting unambiguous prefixes
+    pub fn lookup(&self, query: &str) -> Option<&ConceptEntry> {
+        let query = normalize_concept(query);
+        if query.is_empty() {
+            return None;
+        }
+
+        if let Some(entry) = self.entries.iter().find(|entry| entry.id == query) {
+            return Some(entry);
+        }
+
+        let mut matches = self.entries.iter().filter(|entry| entry.id.starts_with(&query));
+        match (matches.next(), matches.next()) {
+            (Some(entry), None) => Some(entry),
+            _ => None,
+        }
+    }
+
+    /// All concepts in glossary order
+    pub fn entries(&self) -> &[ConceptEntry] {
+        &self.entries
+    }
+
+    /// Concepts introduced by studying a theory
+    pub fn concepts_for_theory(&self, theory: &Theory) -> Vec<&ConceptEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                entry.theories.contains(&theory.id)
+                    || theory.scientific_concepts.iter().any(|name| normalize_concept(name) == entry.id)
+            })
+            .collect()
+    }
+
+    /// Record the concepts behind a theory as encountered, returning newly met ones
+    pub fn encounter_theory(&self, theory: &Theory, player: &mut Player) -> Vec<String> {
+        self.concepts_for_theory(theory)
+            .into_iter()
+            .filter(|entry| player.encounter_concept(&entry.id))
+            .map(|entry| entry.name.clone())
+            .collect()
+    }
+
+    /// Full explanation text for a concept
+    pub fn explain(&self, entry: &ConceptEntry) -> String {
+        let mut text = format!("=== {} ===\n\n", entry.name);
+        text.push_str(&format!("In the world of Resonance:\n{}\n\n", entry.in_game));
+        text.push_str(&format!("In the real world:\n{}", entry.real_world));
+        if !entry.theories.is_empty() {
+            text.push_str(&format!("\n\nRelated theories: {}", entry.theories.join(", ")));
+        }
+        text
+    }
+}
+
+/// Normalize a concept name to its identifier form
+pub fn normalize_concept(name: &str) -> String {
+    name.trim()
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_by_name_and_prefix() {
+        let glossary = ConceptGlossary::new();
+
+        assert_eq!(glossary.lookup("Wave Physics").unwrap().id, "wave_physics");
+        assert_eq!(glossary.lookup("crystallography").unwrap().name, "Crystallography");
+        assert_eq!(glossary.lookup("optic").unwrap().name, "Optics");
+        // "p" matches several concepts and is ambiguous
+        assert!(glossary.lookup("p").is_none());
+        assert!(glossary.lookup("alchemy").is_none());
+    }
+
+    #[test]
+    fn test_every_concept_has_both_framings() {
+        let glossary = ConceptGlossary::new();
+        for entry in glossary.entries() {
+            assert!(!entry.in_game.is_empty(), "{} missing in-game text", entry.name);
+            assert!(!entry.real_world.is_empty(), "{} missing real-world text", entry.name);
+        }
+    }
+
+    #[test]
+    fn test_encountering_concepts_is_tracked_once() {
+        let glossary = ConceptGlossary::new();
+        let mut player = Player::new("Student".to_string());
+        let theory = Theory {
+            id: "light_manipulation".to_string(),
+            name: "Light Manipulation".to_string(),
+            description: String::new(),
+            tier: crate::systems::knowledge::TheoryTier::Application,
+            category: crate::systems::knowledge::TheoryCategory::LightManipulation,
+            prerequisites: vec![],
+            complexity_level: 4,
+            base_learning_time: 60,
+            scientific_concepts: vec!["Optics".to_string()],
+            applications: vec![],
+            available_learning_methods: Default::default(),
+            method_multipliers: Default::default(),
+        };
+
+        let first = glossary.encounter_theory(&theory, &mut player);
+        assert_eq!(first.len(), 3);
+        assert!(player.has_encountered_concept("photonics"));
+
+        let second = glossary.encounter_theory(&theory, &mut player);
+        assert!(second.is_empty());
+    }
+}
@@ -0,0 +1,243 @@
+//! Hotseat co-op with a resonance-linked apprentice
+//!
+//! A second player at the same keyboard can take control of an apprentice
+//! whose resonance is linked to the lead character. The link keeps the two
+//! together, so the world, the current location and any fight are shared,
+//! while each character keeps their own inventory, knowledge and energy.
+//!
+//! Outside of co-op segments the lead plays alone. During a ritual or an
+//! expedition segment the two seats alternate, one command each.
+
+use crate::core::Player;
+use crate::GameResult;
+use serde::{Deserialize, Serialize};
+
+/// Who is at the keyboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Seat {
+    #[default]
+    Lead,
+    Apprentice,
+}
+
+/// The kind of shared activity the seats alternate through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SegmentKind {
+    Ritual,
+    Expedition,
+}
+
+impl SegmentKind {
+    /// Parse a segment name
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "ritual" => Some(SegmentKind::Ritual),
+            "expedition" => Some(SegmentKind::Expedition),
+            _ => None,
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            SegmentKind::Ritual => "ritual",
+            SegmentKind::Expedition => "expedition",
+        }
+    }
+}
+
+/// A ritual or expedition in progress
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoopSegment {
+    pub kind: SegmentKind,
+    /// Commands taken by the lead in this segment
+    pub lead_turns: u32,
+    /// Commands taken by the apprentice in this segment
+    pub apprentice_turns: u32,
+}
+
+/// Co-op state, saved with the world
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoopState {
+    /// The linked apprentice, if a second player has joined
+    ///
+    /// Taken out while the apprentice's command runs.
+    pub apprentice: Option<Player>,
+    pub segment: Option<CoopSegment>,
+    pub active_seat: Seat,
+}
+
+impl CoopState {
+    /// Link a new apprentice character at the lead's location
+    pub fn link(&mut self, name: &str, location: &str) -> GameResult<String> {
+        if let Some(apprentice) = &self.apprentice {
+            return Err(crate::GameError::InvalidCommand(format!(
+                "{} is already linked to you. Use 'coop unlink' first.",
+                apprentice.name
+            )).into());
+        }
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(crate::GameError::InvalidInput("Name the apprentice: 'coop link <name>'.".to_string()).into());
+        }
+
+        let mut apprentice = Player::new(name.to_string());
+        apprentice.current_location = location.to_string();
+        self.apprentice = Some(apprentice);
+        Ok(format!(
+            "{} attunes to your resonance. The link holds: where you go, they go.\n\
+             Start a shared segment with 'coop ritual' or 'coop expedition'.",
+            name
+        ))
+    }
+
+    /// Release the apprentice; their character is lost
+    pub fn unlink(&mut self) -> GameResult<String> {
+        if self.segment.is_some() {
+            return Err(crate::GameError::InvalidCommand("Finish the current segment with 'coop end' first.".to_string()).into());
+        }
+        let apprentice = self.apprentice.take().ok_or_else(|| {
+            crate::GameError::InvalidCommand("No apprentice is linked to you.".to_string())
+        })?;
+        Ok(format!("The link fades. {} goes their own way.", apprentice.name))
+    }
+
+    /// Start alternating turns; the lead acts first
+    pub fn begin(&mut self, kind: SegmentKind) -> GameResult<String> {
+        let Some(apprentice) = &self.apprentice else {
+            return Err(crate::GameError::InvalidCommand(
+                "Link an apprentice first with 'coop link <name>'.".to_string()
+            ).into());
+        };
+        if let Some(segment) = &self.segment {
+            return Err(crate::GameError::InvalidCommand(format!(
+                "A {} is already under way. Use 'coop end' to finish it.",
+                segment.kind.name()
+            )).into());
+        }
+
+        let name = apprentice.name.clone();
+        self.segment = Some(CoopSegment { kind, lead_turns: 0, apprentice_turns: 0 });
+        self.active_seat = Seat::Lead;
+        let opening = match kind {
+            SegmentKind::Ritual => format!("You and {} join your resonance for a shared ritual.", name),
+            SegmentKind::Expedition => format!("You and {} set out together on an expedition.", name),
+        };
+        Ok(format!("{} Turns now alternate, one command each. The lead acts first.", opening))
+    }
+
+    /// Stop alternating and hand the keyboard back to the lead
+    pub fn end(&mut self) -> GameResult<String> {
+        let segment = self.segment.take().ok_or_else(|| {
+            crate::GameError::InvalidCommand("No co-op segment is under way.".to_string())
+        })?;
+        self.active_seat = Seat::Lead;
+        Ok(format!(
+            "The {} ends. Lead: {} command{}, apprentice: {} command{}.",
+            segment.kind.name(),
+            segment.lead_turns, if segment.lead_turns == 1 { "" } else { "s" },
+            segment.apprentice_turns, if segment.apprentice_turns == 1 { "" } else { "s" }
+        ))
+    }
+
+    /// Whether the next command belongs to the apprentice
+    pub fn is_apprentice_turn(&self) -> bool {
+        self.segment.is_some() && self.active_seat == Seat::Apprentice
+    }
+
+    /// Record a command for the seat that acted and pass to the other seat
+    pub fn pass_turn(&mut self) {
+        let Some(segment) = &mut self.segment else {
+            return;
+        };
+        self.active_seat = match self.active_seat {
+            Seat::Lead => {
+                segment.lead_turns += 1;
+                Seat::Apprentice
+            }
+            Seat::Apprentice => {
+                segment.apprentice_turns += 1;
+                Seat::Lead
+            }
+        };
+    }
+
+    /// Name of the character whose seat is active
+    pub fn active_name<'a>(&'a self, lead_name: &'a str) -> &'a str {
+        match (&self.active_seat, &self.apprentice) {
+            (Seat::Apprentice, Some(apprentice)) => &apprentice.name,
+            _ => lead_name,
+        }
+    }
+
+    /// Input prompt naming whose turn it is
+    pub fn prompt(&self, lead_name: &str) -> String {
+        if self.segment.is_none() {
+            return "> ".to_string();
+        }
+        format!("[{}] > ", self.active_name(lead_name))
+    }
+
+    /// Summary of the link and the current segment
+    pub fn report(&self, lead: &Player) -> String {
+        let Some(apprentice) = &self.apprentice else {
+            return "No apprentice is linked. A second player can join with 'coop link <name>'.".to_string();
+        };
+
+        let mut output = String::from("=== RESONANCE LINK ===\n");
+        for (label, character) in [("Lead", lead), ("Apprentice", apprentice)] {
+            output.push_str(&format!(
+                "{}: {} - energy {}/{}, fatigue {}, {} silver, {} theories\n",
+                label,
+                character.name,
+                character.mental_state.current_energy,
+                character.mental_state.max_energy,
+                character.mental_state.fatigue,
+                character.inventory.silver,
+                character.knowledge.theories.len()
+            ));
+        }
+
+        match &self.segment {
+            Some(segment) => {
+                output.push_str(&format!(
+                    "\nShared {} under way. {} acts next.",
+                    segment.kind.name(), self.active_name(&lead.name)
+                ));
+            }
+            None => output.push_str("\nNo shared segment. Use 'coop ritual' or 'coop expedition' to start one."),
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segments_alternate_seats() {
+        let mut coop = CoopState::default();
+        assert!(coop.begin(SegmentKind::Ritual).is_err());
+
+        coop.link("Mira", "practice_hall").unwrap();
+        assert!(coop.link("Again", "practice_hall").is_err());
+        assert_eq!(coop.apprentice.as_ref().unwrap().current_location, "practice_hall");
+        assert!(!coop.is_apprentice_turn());
+
+        coop.begin(SegmentKind::Expedition).unwrap();
+        assert_eq!(coop.prompt("Ada"), "[Ada] > ");
+        coop.pass_turn();
+        assert!(coop.is_apprentice_turn());
+        assert_eq!(coop.prompt("Ada"), "[Mira] > ");
+        coop.pass_turn();
+        coop.pass_turn();
+
+        assert!(coop.unlink().is_err());
+        let summary = coop.end().unwrap();
+        assert!(summary.contains("Lead: 2 commands, apprentice: 1 command."));
+        assert_eq!(coop.active_seat, Seat::Lead);
+        assert_eq!(coop.prompt("Ada"), "> ");
+        assert!(coop.unlink().is_ok());
+    }
+}
@@ -0,0 +1,330 @@
+//! Defeat and respawn policy
+//!
+//! Every system that can leave the player unable to continue (combat,
+//! environmental hazards, afflictions) reports a [`DefeatCause`] here
+//! instead of deciding the consequences itself. The run's [`DefeatPolicy`]
+//! then determines what happens: reloading the last save, being captured,
+//! being rescued by faction allies at a cost, or permanent death in ironman
+//! runs.
+
+use crate::core::{Player, WorldState};
+use crate::systems::factions::FactionId;
+use serde::{Deserialize, Serialize};
+
+/// Mutator name that forces permadeath for a run
+pub const IRONMAN_MUTATOR: &str = "ironman";
+/// Reputation needed before a faction will send a rescue party
+const RESCUE_REPUTATION_THRESHOLD: i32 = 25;
+/// Reputation spent with the rescuing faction
+const RESCUE_REPUTATION_COST: i32 = 5;
+/// Base silver paid to rescuers, increased by each previous defeat
+const RESCUE_BASE_SILVER: i32 = 20;
+/// Fraction of carried silver confiscated by captors
+const CAPTURE_CONFISCATION: f32 = 0.25;
+/// Location used when no faction has a stronghold to take the player to
+const FALLBACK_SAFE_LOCATION: &str = "tutorial_chamber";
+
+/// What happens to the player when they fall
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DefeatPolicy {
+    /// Return to the most recent save
+    Reload,
+    /// Hostile factions take the player prisoner
+    Capture,
+    /// Allied factions carry the player to safety for a price
+    #[default]
+    Rescue,
+    /// The character's journey ends (ironman)
+    Permadeath,
+}
+
+impl DefeatPolicy {
+    /// Parse a policy name
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "reload" => Some(DefeatPolicy::Reload),
+            "capture" => Some(DefeatPolicy::Capture),
+            "rescue" => Some(DefeatPolicy::Rescue),
+            "permadeath" | "ironman" => Some(DefeatPolicy::Permadeath),
+            _ => None,
+        }
+    }
+
+    /// Display name of the policy
+    pub fn name(&self) -> &'static str {
+        match self {
+            DefeatPolicy::Reload => "reload",
+            DefeatPolicy::Capture => "capture",
+            DefeatPolicy::Rescue => "rescue",
+            DefeatPolicy::Permadeath => "permadeath",
+        }
+    }
+
+    /// Short description for players choosing a policy
+    pub fn description(&self) -> &'static str {
+        match self {
+            DefeatPolicy::Reload => "Return to your most recent save",
+            DefeatPolicy::Capture => "Hostile factions take you prisoner and confiscate silver",
+            DefeatPolicy::Rescue => "Faction allies carry you to safety for silver and favour",
+            DefeatPolicy::Permadeath => "Your character's journey ends for good",
+        }
+    }
+}
+
+/// What caused the player to fall
+#[derive(Debug, Clone, PartialEq)]
+pub enum DefeatCause {
+    /// Overwhelmed in combat
+    Combat { enemy: String, faction: Option<FactionId> },
+    /// Overcome by an environmental hazard
+    Hazard { source: String },
+    /// Collapsed from an illness, injury or magical affliction
+    Affliction { name: String },
+}
+
+impl DefeatCause {
+    /// Narrative description of the cause
+    pub fn describe(&self) -> String {
+        match self {
+            DefeatCause::Combat { enemy, .. } => format!("You have been overwhelmed by {}!", enemy),
+            DefeatCause::Hazard { source } => format!("You are overcome by {}!", source),
+            DefeatCause::Affliction { name } => format!("You collapse, stricken by {}!", name),
+        }
+    }
+
+    /// Faction responsible for the defeat, if any
+    pub fn hostile_faction(&self) -> Option<FactionId> {
+        match self {
+            DefeatCause::Combat { faction, .. } => *faction,
+            _ => None,
+        }
+    }
+}
+
+/// Per-run defeat settings and history
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DefeatState {
+    /// Policy applied when the player falls
+    pub policy: DefeatPolicy,
+    /// Number of times the player has fallen this run
+    pub defeats: u32,
+    /// Set when a reload has been requested and not yet performed
+    pub reload_pending: bool,
+    /// Set when the character has died permanently
+    pub fallen: bool,
+}
+
+impl DefeatState {
+    /// Policy in effect, with the ironman mutator always forcing permadeath
+    pub fn effective_policy(&self, mutators: &[String]) -> DefeatPolicy {
+        if mutators.iter().any(|m| m == IRONMAN_MUTATOR) {
+            DefeatPolicy::Permadeath
+        } else {
+            self.policy
+        }
+    }
+}
+
+/// Apply the run's defeat policy and describe the aftermath
+pub fn resolve_defeat(cause: &DefeatCause, player: &mut Player, world: &mut WorldState) -> String {
+    let policy = world.defeat.effective_policy(&world.run.mutators);
+    world.defeat.defeats += 1;
+
+    let mut output = format!("{}\n", cause.describe());
+
+    match policy {
+        DefeatPolicy::Reload => {
+            world.defeat.reload_pending = true;
+            recover_in_place(player);
+            output.push_str("Your vision fades... and the world folds back to your last save.");
+        }
+        DefeatPolicy::Capture => match cause.hostile_faction() {
+            Some(captor) => output.push_str(&capture(captor, player, world)),
+            None => output.push_str(&rescue(cause, player, world)),
+        },
+        DefeatPolicy::Rescue => output.push_str(&rescue(cause, player, world)),
+        DefeatPolicy::Permadeath => {
+            world.defeat.fallen = true;
+            player.mental_state.current_energy = 0;
+            player.health.current = 0;
+            output.push_str(&format!(
+                "=== YOUR JOURNEY ENDS ===\n{} will not rise again. Only 'summary' and 'quit' remain.",
+                player.name
+            ));
+        }
+    }
+
+    output
+}
+
+/// Legacy outcome: wake where you fell, drained and exhausted
+fn recover_in_place(player: &mut Player) {
+    player.mental_state.current_energy = (player.mental_state.max_energy as f32 * 0.1) as i32;
+    player.mental_state.fatigue = (player.mental_state.fatigue + 40).min(100);
+    player.health.restore_fraction(0.1);
+}
+
+/// Carried away by the faction holding the enemy's allegiance
+fn capture(captor: FactionId, player: &mut Player, world: &mut WorldState) -> String {
+    let confiscated = (player.inventory.silver as f32 * CAPTURE_CONFISCATION) as i32;
+    player.inventory.silver -= confiscated;
+    player.mental_state.current_energy = (player.mental_state.max_energy as f32 * 0.25) as i32;
+    player.mental_state.fatigue = (player.mental_state.fatigue + 30).min(100);
+    player.health.restore_fraction(0.25);
+    player.modify_faction_reputation(captor, -5);
+
+    let prison = faction_stronghold(captor, world);
+    let prison_name = relocate(player, world, &prison);
+
+    format!(
+        "Agents of the {} drag you away. You wake under guard at {}.\n\
+         Confiscated: {} silver. {} reputation -5.",
+        captor.display_name(), prison_name, confiscated, captor.display_name()
+    )
+}
+
+/// Carried to safety by the friendliest faction, for a price
+fn rescue(cause: &DefeatCause, player: &mut Player, world: &mut WorldState) -> String {
+    let hostile = cause.hostile_faction();
+    let rescuer = FactionId::all()
+        .into_iter()
+        .filter(|faction| Some(*faction) != hostile)
+        .map(|faction| (faction, player.faction_reputation(faction)))
+        .filter(|(_, reputation)| *reputation >= RESCUE_REPUTATION_THRESHOLD)
+        .max_by_key(|(_, reputation)| *reputation)
+        .map(|(faction, _)| faction);
+
+    let Some(rescuer) = rescuer else {
+        recover_in_place(player);
+        return "No one comes for you. Eventually you come to where you fell, drained and exhausted.".to_string();
+    };
+
+    let fee = (RESCUE_BASE_SILVER + 10 * (world.defeat.defeats as i32 - 1)).min(player.inventory.silver);
+    player.inventory.silver -= fee;
+    player.modify_faction_reputation(rescuer, -RESCUE_REPUTATION_COST);
+    player.mental_state.current_energy = player.mental_state.max_energy / 2;
+    player.mental_state.fatigue = (player.mental_state.fatigue + 20).min(100);
+    player.health.restore_fraction(0.5);
+
+    let haven = faction_stronghold(rescuer, world);
+    let haven_name = relocate(player, world, &haven);
+
+    format!(
+        "Allies from the {} find you and carry you to {}.\n\
+         Their help costs {} silver and {} favour ({} reputation -{}).",
+        rescuer.display_name(), haven_name, fee, rescuer.display_name(),
+        rescuer.display_name(), RESCUE_REPUTATION_COST
+    )
+}
+
+/// Location where a faction holds the most influence
+fn faction_stronghold(faction: FactionId, world: &WorldState) -> String {
+    world.locations
+        .values()
+        .filter_map(|location| {
+            location.faction_presence
+                .get(faction.key())
+                .map(|presence| (presence.influence, location.id.clone()))
+        })
+        .max()
+        .map(|(_, id)| id)
+        .unwrap_or_else(|| FALLBACK_SAFE_LOCATION.to_string())
+}
+
+/// Move the player to a location if it exists, returning where they ended up
+fn relocate(player: &mut Player, world: &mut WorldState, location_id: &str) -> String {
+    if world.locations.contains_key(location_id) {
+        world.current_location = location_id.to_string();
+        player.current_location = location_id.to_string();
+    }
+    world.current_location()
+        .map(|location| location.name.clone())
+        .unwrap_or_else(|| world.current_location.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::{FactionPresence, Location, PresenceVisibility};
+
+    fn create_world() -> WorldState {
+        let mut world = WorldState::new();
+        world.add_location(Location::new("tutorial_chamber".to_string(), "Tutorial Chamber".to_string(), String::new()));
+        let mut hall = Location::new("council_hall".to_string(), "Council Hall".to_string(), String::new());
+        hall.faction_presence.insert("magisters_council".to_string(), FactionPresence {
+            influence: 80,
+            visibility: PresenceVisibility::Open,
+            member_count: 5,
+        });
+        world.add_location(hall);
+        world.current_location = "tutorial_chamber".to_string();
+        world
+    }
+
+    fn combat_cause(faction: Option<FactionId>) -> DefeatCause {
+        DefeatCause::Combat { enemy: "Rogue Mage".to_string(), faction }
+    }
+
+    #[test]
+    fn test_policy_parsing() {
+        assert_eq!(DefeatPolicy::from_string("Capture"), Some(DefeatPolicy::Capture));
+        assert_eq!(DefeatPolicy::from_string("ironman"), Some(DefeatPolicy::Permadeath));
+        assert_eq!(DefeatPolicy::from_string("nap"), None);
+        assert_eq!(DefeatPolicy::default(), DefeatPolicy::Rescue);
+    }
+
+    #[test]
+    fn test_rescue_by_allied_faction() {
+        let mut world = create_world();
+        let mut player = Player::new("Fallen".to_string());
+        player.inventory.silver = 100;
+        player.modify_faction_reputation(FactionId::MagistersCouncil, 40);
+
+        let output = resolve_defeat(&combat_cause(None), &mut player, &mut world);
+
+        assert!(output.contains("Council Hall"));
+        assert_eq!(world.current_location, "council_hall");
+        assert_eq!(player.inventory.silver, 80);
+        assert_eq!(player.faction_reputation(FactionId::MagistersCouncil), 35);
+        assert_eq!(player.health.current, player.health.max / 2);
+        assert_eq!(world.defeat.defeats, 1);
+    }
+
+    #[test]
+    fn test_rescue_without_allies_recovers_in_place() {
+        let mut world = create_world();
+        let mut player = Player::new("Alone".to_string());
+
+        let output = resolve_defeat(&DefeatCause::Hazard { source: "a resonance storm".to_string() }, &mut player, &mut world);
+
+        assert!(output.contains("No one comes for you"));
+        assert_eq!(world.current_location, "tutorial_chamber");
+        assert_eq!(player.mental_state.current_energy, player.mental_state.max_energy / 10);
+    }
+
+    #[test]
+    fn test_capture_confiscates_silver() {
+        let mut world = create_world();
+        world.defeat.policy = DefeatPolicy::Capture;
+        let mut player = Player::new("Prisoner".to_string());
+        player.inventory.silver = 100;
+
+        resolve_defeat(&combat_cause(Some(FactionId::MagistersCouncil)), &mut player, &mut world);
+
+        assert_eq!(player.inventory.silver, 75);
+        assert_eq!(world.current_location, "council_hall");
+    }
+
+    #[test]
+    fn test_ironman_forces_permadeath() {
+        let mut world = create_world();
+        world.defeat.policy = DefeatPolicy::Reload;
+        world.run.mutators.push(IRONMAN_MUTATOR.to_string());
+        let mut player = Player::new("Ironman".to_string());
+
+        resolve_defeat(&DefeatCause::Affliction { name: "resonance fever".to_string() }, &mut player, &mut world);
+
+        assert!(world.defeat.fallen);
+        assert!(!world.defeat.reload_pending);
+    }
+}
@@ -1,8 +1,24 @@
-use crate::core::Player;
+use crate::core::calendar::{self, Weekday};
+use crate::core::world_state::TimeOfDay;
+use crate::systems::bonds::{self, Bonds};
+use crate::core::{Player, WorldState};
 use crate::systems::factions::{FactionId, FactionSystem};
+use crate::systems::quests::{QuestStatus, QuestSystem};
+use crate::systems::relationships::{self, RelationshipStage, Relationships};
+use crate::systems::suggestions::{ActionKind, ActionSource, Situation, SuggestedAction};
+use crate::systems::text_templates::{self, Localization, TextVariables};
+use crate::systems::world_flags::FlaggedText;
 use crate::GameResult;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// Location whose detection arrays can pick out an NPC's resonance signature
+pub const LOCATE_LOCATION: &str = "resonance_observatory";
+/// Theory the arrays are tuned with, and the understanding it takes
+pub const LOCATE_THEORY: &str = "detection_arrays";
+pub const LOCATE_UNDERSTANDING: f32 = 0.3;
+/// Minutes spent tuning the arrays to a signature
+pub const LOCATE_MINUTES: i32 = 10;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NPCPersonality {
@@ -28,6 +44,111 @@ pub struct NPC {
     /// Quest-specific dialogue contexts (quest_id -> dialogue content)
     #[serde(default)]
     pub quest_dialogue: std::collections::HashMap<String, QuestDialogue>,
+    /// Where the NPC can be found and when they are free to talk
+    #[serde(default)]
+    pub availability: NPCAvailability,
+    /// Lines added to the NPC's greeting while a world flag condition holds
+    #[serde(default)]
+    pub flag_lines: Vec<FlaggedText>,
+    /// World flags set when the player asks about a topic (topic -> flag)
+    #[serde(default)]
+    pub topic_flags: HashMap<String, String>,
+}
+
+impl NPC {
+    /// Given name used in conversational messages ("Gareth" for "Safety Warden Gareth Ironshield")
+    pub fn short_name(&self) -> &str {
+        let words: Vec<&str> = self.name.split_whitespace().collect();
+        match words.len() {
+            0 => &self.id,
+            1 | 2 => words[0],
+            n => words[n - 2],
+        }
+    }
+
+    /// Schedule entry in effect at the given game time, honouring quest and world flag conditions
+    pub fn scheduled_activity(&self, now: i32, quest_system: &QuestSystem) -> Option<&ScheduleEntry> {
+        // Quest-, event- and weekday-driven entries take precedence over the daily routine
+        let applicable = |entry: &&ScheduleEntry| entry.covers(now) && match &entry.quest_id {
+            Some(quest_id) => quest_system.player_progress.get(quest_id)
+                .is_some_and(|progress| progress.status == QuestStatus::InProgress),
+            None => true,
+        } && entry.flag.as_deref().is_none_or(|condition| quest_system.global_state.flags.holds(condition));
+
+        self.availability.schedule.iter()
+            .filter(applicable)
+            .find(|entry| entry.quest_id.is_some() || entry.flag.is_some() || entry.weekday.is_some())
+            .or_else(|| self.availability.schedule.iter().find(applicable))
+    }
+
+    /// Where the NPC is at the given game time: wherever their schedule takes
+    /// them, otherwise home; None once they've left for good
+    pub fn whereabouts(&self, now: i32, quest_system: &QuestSystem) -> Option<&str> {
+        if self.availability.departed.is_some() {
+            return None;
+        }
+        self.scheduled_activity(now, quest_system)
+            .and_then(|entry| entry.location.as_deref())
+            .or(self.availability.home_location.as_deref())
+    }
+}
+
+/// Schedule and cooldown data controlling when an NPC will talk
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NPCAvailability {
+    /// Location the NPC occupies when not scheduled elsewhere
+    #[serde(default)]
+    pub home_location: Option<String>,
+    /// Daily routine and quest-driven activities
+    #[serde(default)]
+    pub schedule: Vec<ScheduleEntry>,
+    /// Temporary cooldown after which the NPC is free again
+    #[serde(default)]
+    pub busy: Option<BusyState>,
+    /// Why the NPC has left for good, phrased to follow their name
+    #[serde(default)]
+    pub departed: Option<String>,
+}
+
+/// A block of the day an NPC spends on a particular activity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleEntry {
+    /// First hour of the activity (0-23)
+    pub start_hour: u8,
+    /// Hour the activity ends (exclusive); wraps past midnight if before start
+    pub end_hour: u8,
+    /// What the NPC is doing, phrased to follow their name ("inspecting the chambers")
+    pub activity: String,
+    /// Where the activity takes place; None means their home location
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Whether the NPC will still talk while doing this
+    #[serde(default)]
+    pub available: bool,
+    /// Only applies while this quest is in progress
+    #[serde(default)]
+    pub quest_id: Option<String>,
+    /// Only applies while this world flag condition holds
+    #[serde(default)]
+    pub flag: Option<String>,
+    /// Only applies on this day of the week; None means every day
+    #[serde(default)]
+    pub weekday: Option<Weekday>,
+}
+
+impl ScheduleEntry {
+    /// Whether the entry covers the given game time
+    pub fn covers(&self, now: i32) -> bool {
+        calendar::in_span(now, self.weekday, self.start_hour, self.end_hour)
+    }
+}
+
+/// Short-term activity that keeps an NPC from talking
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusyState {
+    pub activity: String,
+    /// Game time (minutes) when the NPC becomes free
+    pub until: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +179,12 @@ pub struct DialogueTree {
     /// Time-of-day variations for greeting (optional)
     #[serde(default)]
     pub time_based_greetings: HashMap<String, String>, // "morning", "afternoon", "evening"
+    /// Greetings that replace the usual one once a relationship reaches a stage
+    #[serde(default)]
+    pub relationship_greetings: HashMap<RelationshipStage, String>,
+    /// Follow-up nodes reached by choosing a response rather than by asking, by ID
+    #[serde(default)]
+    pub branches: HashMap<String, DialogueNode>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,16 +194,86 @@ pub struct DialogueNode {
     pub requirements: DialogueRequirements,
 }
 
+/// Something the player can say after asking about a topic
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DialogueResponse {
+    pub text: String,
+    /// Applied when the response is chosen and any check passes
+    pub effect: DialogueEffect,
+    /// What the NPC says back when any check passes
+    #[serde(default)]
+    pub reply: Option<String>,
+    /// A check the response must pass to succeed
+    #[serde(default)]
+    pub check: Option<DialogueCheck>,
+    /// What happens instead when the check fails
+    #[serde(default)]
+    pub failure: Option<DialogueOutcome>,
+    /// Branch the conversation moves on to when the response succeeds
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+/// A skill check on a dialogue response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DialogueCheck {
+    /// Talk them round, resolved against mental acuity
+    Persuade { min_acuity: i32 },
+    /// Lean on them with a faction's weight behind you, resolved against standing with it
+    Intimidate { faction: FactionId, min_standing: i32 },
+    /// Cite a theory, resolved against understanding of it
+    CiteTheory { theory_id: String, min_understanding: f32 },
+}
+
+impl DialogueCheck {
+    /// How the check is shown beside a response
+    pub fn label(&self) -> String {
+        match self {
+            DialogueCheck::Persuade { .. } => "[Persuade]".to_string(),
+            DialogueCheck::Intimidate { faction, .. } => format!("[Intimidate: {}]", faction.short_name()),
+            DialogueCheck::CiteTheory { theory_id, .. } => format!("[Cite {}]", theory_id.replace('_', " ")),
+        }
+    }
+
+    pub fn passes(&self, player: &Player) -> bool {
+        match self {
+            DialogueCheck::Persuade { min_acuity } => player.attributes.mental_acuity >= *min_acuity,
+            DialogueCheck::Intimidate { faction, min_standing } => player.faction_reputation(*faction) >= *min_standing,
+            DialogueCheck::CiteTheory { theory_id, min_understanding } => player.theory_understanding(theory_id) >= *min_understanding,
+        }
+    }
+}
+
+/// What the NPC says and does when a check fails
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DialogueOutcome {
     pub text: String,
     pub effect: DialogueEffect,
 }
 
+/// The topic whose responses the player can currently choose from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Conversation {
+    pub npc_id: String,
+    pub topic: String,
+    pub location_id: String,
+    /// Branch earlier replies led to; None while still on the topic itself
+    #[serde(default)]
+    pub branch: Option<String>,
+}
+
+impl Conversation {
+    /// ID of the node whose responses are on offer, the branch or else the topic
+    pub fn node_id(&self) -> &str {
+        self.branch.as_deref().unwrap_or(&self.topic)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DialogueRequirements {
     pub min_faction_standing: Option<(FactionId, i32)>,
     pub max_faction_standing: Option<(FactionId, i32)>,
+    #[serde(default)]
     pub knowledge_requirements: Vec<String>,
     /// Theory understanding requirements (theory_id, min_understanding 0.0-1.0)
     #[serde(default)]
@@ -87,6 +284,9 @@ pub struct DialogueRequirements {
     /// Specific theory capabilities required
     #[serde(default)]
     pub required_capabilities: Vec<String>,
+    /// How close the player must be to the NPC
+    #[serde(default)]
+    pub min_relationship: Option<RelationshipStage>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -109,19 +309,106 @@ pub enum DialogueEffect {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DialogueSystem {
     npcs: HashMap<String, NPC>,
+    /// The last topic asked about that offered responses
+    #[serde(default)]
+    conversation: Option<Conversation>,
+    /// Checked responses already tried, as "npc_id/node/index"; each gets one attempt
+    #[serde(default)]
+    attempted_checks: HashSet<String>,
+    /// Topics and branches the player has heard, as "npc_id/node"
+    #[serde(default)]
+    visited_nodes: HashSet<String>,
+    /// Who the player last spoke to, and where, as (NPC ID, location ID)
+    #[serde(default)]
+    partner: Option<(String, String)>,
+    /// What each NPC thinks of the player
+    #[serde(default)]
+    relationships: Relationships,
+    /// Where the player last learned each NPC had gone, by NPC ID
+    #[serde(default)]
+    known_whereabouts: HashMap<String, String>,
+    /// Deeper bonds with the few NPCs who have a bond track
+    #[serde(default)]
+    bonds: Bonds,
+    /// Translated lines for the chosen locale
+    #[serde(default)]
+    localization: Localization,
+    /// Values for the variables in dialogue lines, refreshed before each command
+    #[serde(skip)]
+    variables: TextVariables,
 }
 
 impl DialogueSystem {
     pub fn new() -> Self {
         Self {
             npcs: HashMap::new(),
+            conversation: None,
+            attempted_checks: HashSet::new(),
+            visited_nodes: HashSet::new(),
+            partner: None,
+            relationships: Relationships::default(),
+            known_whereabouts: HashMap::new(),
+            bonds: Bonds::default(),
+            localization: Localization::default(),
+            variables: TextVariables::default(),
         }
     }
 
+    pub fn relationships(&self) -> &Relationships {
+        &self.relationships
+    }
+
+    /// Shift an NPC's affinity and remember why; returns a message if the
+    /// relationship reached a new stage
+    pub fn shift_relationship(&mut self, npc_id: &str, change: i32, memory: &str) -> Option<String> {
+        let name = self.npcs.get(npc_id)?.short_name().to_string();
+        let relationship = self.relationships.get_mut(npc_id);
+        relationship.met = true;
+        relationship.shift(change, memory).map(|stage| relationships::stage_message(&name, stage))
+    }
+
+    pub fn localization(&self) -> &Localization {
+        &self.localization
+    }
+
+    /// Speak in another locale from now on
+    pub fn set_localization(&mut self, localization: Localization) {
+        self.localization = localization;
+    }
+
+    /// Update the values dialogue lines fill their variables from
+    pub fn set_variables(&mut self, variables: TextVariables) {
+        self.variables = variables;
+    }
+
+    /// A dialogue line as it is spoken, translated and with its variables filled in
+    fn render(&self, line: &str) -> String {
+        text_templates::render(line, &self.localization, &self.variables)
+    }
+
     pub fn add_npc(&mut self, npc: NPC) {
         self.npcs.insert(npc.id.clone(), npc);
     }
 
+    /// Replace an NPC's definition with freshly loaded content, keeping how
+    /// they currently feel about the player
+    pub fn refresh_npc(&mut self, mut npc: NPC) {
+        if let Some(existing) = self.npcs.get(&npc.id) {
+            npc.current_disposition = existing.current_disposition;
+        }
+        self.add_npc(npc);
+    }
+
+    /// Get an NPC by ID
+    pub fn get_npc(&self, npc_id: &str) -> Option<&NPC> {
+        self.npcs.get(npc_id)
+    }
+
+    /// Get a mutable NPC by ID
+    pub fn get_npc_mut(&mut self, npc_id: &str) -> Option<&mut NPC> {
+        self.npcs.get_mut(npc_id)
+    }
+
     /// Get quest-specific dialogue for an NPC
     pub fn get_quest_dialogue(
         &self,
@@ -173,6 +460,221 @@ impl DialogueSystem {
         None
     }
 
+    /// Find an NPC by ID, full name, or any single word of their name
+    ///
+    /// NPCs based at `location_id` are preferred when several match.
+    pub fn find_npc(&self, query: &str, location_id: &str) -> Option<&NPC> {
+        let query = query.trim().to_lowercase();
+        if let Some(npc) = self.npcs.get(&query) {
+            return Some(npc);
+        }
+
+        let mut matches: Vec<&NPC> = self.npcs.values()
+            .filter(|npc| {
+                let name = npc.name.to_lowercase();
+                name == query
+                    || name.split_whitespace().any(|word| word.trim_end_matches('.') == query)
+                    || npc.id.split('_').any(|part| part == query)
+            })
+            .collect();
+        matches.sort_by_key(|npc| (npc.availability.home_location.as_deref() != Some(location_id), npc.id.clone()));
+        matches.into_iter().next()
+    }
+
+    /// Make an NPC unavailable for the given number of minutes
+    pub fn set_busy(&mut self, npc_id: &str, activity: &str, now: i32, minutes: i32) -> GameResult<()> {
+        let npc = self.npcs.get_mut(npc_id)
+            .ok_or_else(|| crate::GameError::ContentNotFound(format!("NPC '{}' not found", npc_id)))?;
+        npc.availability.busy = Some(BusyState {
+            activity: activity.to_string(),
+            until: now + minutes,
+        });
+        Ok(())
+    }
+
+    /// Send an NPC away for good
+    pub fn depart(&mut self, npc_id: &str, reason: &str) {
+        if let Some(npc) = self.npcs.get_mut(npc_id) {
+            npc.availability.departed = Some(reason.to_string());
+        }
+    }
+
+    /// The name of an NPC who has left for good
+    pub fn departed_name(&self, npc_id: &str) -> Option<String> {
+        self.npcs.get(npc_id).filter(|npc| npc.availability.departed.is_some()).map(|npc| npc.name.clone())
+    }
+
+    /// Explain why an NPC can't talk right now, or None if they can
+    ///
+    /// Checks, in order: whether they have left for good, a temporary cooldown, the NPC's schedule (which may
+    /// depend on quest state), and whether they are at their home location.
+    pub fn unavailability_message(&self, npc_id: &str, world: &WorldState, quest_system: &QuestSystem) -> Option<String> {
+        let npc = self.npcs.get(npc_id)?;
+        let name = npc.short_name();
+        let here = world.current_location.as_str();
+
+        if let Some(reason) = &npc.availability.departed {
+            return Some(format!("{} {}.", npc.name, reason));
+        }
+
+        if let Some(busy) = &npc.availability.busy {
+            let remaining = busy.until - world.game_time_minutes;
+            if remaining > 0 {
+                return Some(format!(
+                    "{} is busy {}. Try again in about {} minute{}.",
+                    name, busy.activity, remaining, if remaining == 1 { "" } else { "s" }
+                ));
+            }
+        }
+
+        let location_name = |id: &str| world.locations.get(id)
+            .map(|location| location.name.clone())
+            .unwrap_or_else(|| id.replace('_', " "));
+
+        let mut message = if let Some(entry) = npc.scheduled_activity(world.game_time_minutes, quest_system) {
+            let until = TimeOfDay::from_hour(entry.end_hour).name();
+            let location = entry.location.as_deref().or(npc.availability.home_location.as_deref());
+            // Everyone knows where someone works; anywhere else has to be found out
+            let known = |location: &str| npc.availability.home_location.as_deref() == Some(location)
+                || self.known_whereabouts.get(npc_id).is_some_and(|known| known == location);
+            match location {
+                Some(location) if location != here && known(location) => format!(
+                    "{} is {} until {}. You'll find them at the {} in the meantime.",
+                    name, entry.activity, until, location_name(location)
+                ),
+                Some(location) if location != here => format!(
+                    "{} is {} until {}, somewhere other than here. Someone who knows them might say where, \
+                     or the Observatory's arrays could pick them out.",
+                    name, entry.activity, until
+                ),
+                _ if !entry.available => format!(
+                    "{} is {} until {} and can't talk right now. Come back then.",
+                    name, entry.activity, until
+                ),
+                _ => return None,
+            }
+        } else {
+            match npc.availability.home_location.as_deref() {
+                Some(home) if home != here => format!(
+                    "{} isn't here. They can usually be found at the {}.",
+                    name, location_name(home)
+                ),
+                _ => return None,
+            }
+        };
+
+        // Point players with related quest work toward something useful
+        let active_quest = npc.quest_dialogue.keys()
+            .filter(|quest_id| quest_system.player_progress.get(*quest_id)
+                .is_some_and(|progress| progress.status == QuestStatus::InProgress))
+            .min();
+        if let Some(hint) = active_quest.and_then(|quest_id| self.get_progress_hint(npc_id, quest_id)) {
+            message.push_str(&format!("\n\nWhile you wait, remember {}'s advice: {}", name, hint));
+        }
+
+        Some(message)
+    }
+
+    /// NPCs at a location right now, ordered by name
+    pub fn npcs_present(&self, location_id: &str, world: &WorldState, quest_system: &QuestSystem) -> Vec<&NPC> {
+        let mut present: Vec<&NPC> = self.npcs.values()
+            .filter(|npc| npc.whereabouts(world.game_time_minutes, quest_system) == Some(location_id))
+            .collect();
+        present.sort_by(|a, b| a.name.cmp(&b.name));
+        present
+    }
+
+    /// Remember where the player found out an NPC had gone
+    pub fn learn_whereabouts(&mut self, npc_id: &str, location_id: &str) {
+        self.known_whereabouts.insert(npc_id.to_string(), location_id.to_string());
+    }
+
+    /// What one NPC can tell the player about where another is, or None if
+    /// the query is one of their own topics or isn't someone else they could
+    /// know. Colleagues, who share a faction or a workplace, know where each
+    /// other have gone; anyone else only knows where they usually work.
+    pub fn whereabouts_answer(&mut self, asker_id: &str, query: &str, world: &WorldState, quest_system: &QuestSystem) -> Option<String> {
+        let asker = self.npcs.get(asker_id).filter(|asker| !asker.dialogue_tree.topics.contains_key(query))?;
+        let target = self.find_npc(query, &world.current_location).filter(|target| target.id != asker_id)?;
+        let (asker_name, target_name, target_id) = (asker.short_name().to_string(), target.short_name().to_string(), target.id.clone());
+        let location_name = |id: &str| world.locations.get(id).map_or_else(|| id.replace('_', " "), |location| location.name.clone());
+
+        if self.relationships.stage(asker_id) <= RelationshipStage::Wary {
+            return Some(format!("{} won't tell you anything about {}.", asker_name, target_name));
+        }
+        if let Some(reason) = &target.availability.departed {
+            return Some(format!("{}: \"{}? They {}.\"", asker_name, target_name, reason));
+        }
+
+        let colleagues = (asker.faction_affiliation.is_some() && asker.faction_affiliation == target.faction_affiliation)
+            || (asker.availability.home_location.is_some() && asker.availability.home_location == target.availability.home_location);
+        let home = target.availability.home_location.clone();
+        let answer = match (target.whereabouts(world.game_time_minutes, quest_system), colleagues) {
+            (Some(location), _) if location == world.current_location => {
+                format!("{}: \"{}? They're right here.\"", asker_name, target_name)
+            }
+            (Some(location), true) => {
+                let location = location.to_string();
+                let answer = match target.scheduled_activity(world.game_time_minutes, quest_system) {
+                    Some(entry) => format!("{}: \"{} is {}. Try the {}.\"", asker_name, target_name, entry.activity, location_name(&location)),
+                    None => format!("{}: \"{} should be at the {}.\"", asker_name, target_name, location_name(&location)),
+                };
+                self.learn_whereabouts(&target_id, &location);
+                answer
+            }
+            _ => match home {
+                Some(home) => format!(
+                    "{}: \"I don't keep track of {}. They usually work at the {}.\"",
+                    asker_name, target_name, location_name(&home)
+                ),
+                None => format!("{}: \"I couldn't tell you where {} is.\"", asker_name, target_name),
+            },
+        };
+        Some(answer)
+    }
+
+    /// Pick out an NPC's resonance signature with the Observatory's detection
+    /// arrays, learning where they are
+    pub fn locate(&mut self, query: &str, world: &WorldState, quest_system: &QuestSystem) -> GameResult<String> {
+        let npc = self.find_npc(query, &world.current_location)
+            .ok_or_else(|| crate::GameError::ContentNotFound(format!("The arrays have no signature on file for '{}'.", query)))?;
+        let (name, npc_id) = (npc.name.clone(), npc.id.clone());
+        let location_name = |id: &str| world.locations.get(id).map_or_else(|| id.replace('_', " "), |location| location.name.clone());
+
+        match npc.whereabouts(world.game_time_minutes, quest_system).map(str::to_string) {
+            Some(location) => {
+                let activity = npc.scheduled_activity(world.game_time_minutes, quest_system)
+                    .map_or_else(String::new, |entry| format!(" They seem to be {}.", entry.activity));
+                self.learn_whereabouts(&npc_id, &location);
+                Ok(format!("The arrays pick out {}'s resonance signature at the {}.{}", name, location_name(&location), activity))
+            }
+            None => Ok(format!("The arrays find no trace of {} anywhere in the city.", name)),
+        }
+    }
+
+    /// Short names of NPCs at the current location who are free to talk
+    pub fn npcs_available_here(&self, world: &WorldState, quest_system: &QuestSystem) -> Vec<String> {
+        let here = world.current_location.as_str();
+        let mut names: Vec<String> = self.npcs.values()
+            .filter(|npc| npc.whereabouts(world.game_time_minutes, quest_system) == Some(here))
+            .filter(|npc| self.unavailability_message(&npc.id, world, quest_system).is_none())
+            .map(|npc| npc.short_name().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// NPCs whose busy cooldown ends after `from` and no later than `to`, with their activity
+    pub fn cooldowns_ending(&self, from: i32, to: i32) -> Vec<(String, String)> {
+        let mut ending: Vec<(String, String)> = self.npcs.values()
+            .filter_map(|npc| npc.availability.busy.as_ref()
+                .filter(|busy| busy.until > from && busy.until <= to)
+                .map(|busy| (npc.short_name().to_string(), busy.activity.clone())))
+            .collect();
+        ending.sort();
+        ending
+    }
+
     pub fn talk_to_npc(
         &mut self,
         npc_id: &str,
@@ -180,16 +682,38 @@ impl DialogueSystem {
         faction_system: &FactionSystem,
     ) -> GameResult<String> {
         // Get all data we need first without mutable borrowing
-        let (disposition, npc_name, topics, greeting_text) = {
+        let (npc_name, short_name, faction, topics) = {
             let npc = self.npcs.get(npc_id)
                 .ok_or_else(|| crate::GameError::ContentNotFound(format!("NPC '{}' not found", npc_id)))?;
-
-            let disposition = self.calculate_disposition(npc, player, faction_system);
-            let npc_name = npc.name.clone();
             let topics: Vec<String> = npc.dialogue_tree.topics.keys().cloned().collect();
-            let greeting_text = self.select_greeting_text(npc, player)?;
+            (npc.name.clone(), npc.short_name().to_string(), npc.faction_affiliation, topics)
+        };
 
-            (disposition, npc_name, topics, greeting_text)
+        // They notice how your standing with their faction has moved since you last spoke
+        let mut remarks = Vec::new();
+        self.partner = Some((npc_id.to_string(), player.current_location.clone()));
+        self.relationships.get_mut(npc_id).met = true;
+        if let Some(faction) = faction {
+            let standing = player.faction_reputation(faction);
+            let relationship = self.relationships.get_mut(npc_id);
+            let change = relationship.standing_seen.map_or(0, |seen| standing - seen);
+            relationship.standing_seen = Some(standing);
+            if change.abs() >= relationships::NOTICED_STANDING_CHANGE {
+                let (remark, memory) = if change > 0 {
+                    (format!("{} has heard how you've helped the {}.", short_name, faction.display_name()), format!("You helped the {}", faction.display_name()))
+                } else {
+                    (format!("{} has heard you've been working against the {}.", short_name, faction.display_name()), format!("You worked against the {}", faction.display_name()))
+                };
+                remarks.push(remark);
+                remarks.extend(self.shift_relationship(npc_id, change / 5, &memory));
+            }
+        }
+
+        let (disposition, greeting_text) = {
+            let npc = self.npcs.get(npc_id)
+                .ok_or_else(|| crate::GameError::ContentNotFound(format!("NPC '{}' not found", npc_id)))?;
+            let disposition = self.calculate_disposition(npc, player, faction_system);
+            (disposition, self.select_greeting_text(npc, player, disposition)?)
         };
 
         // Now get mutable reference and update disposition
@@ -197,13 +721,18 @@ impl DialogueSystem {
             .ok_or_else(|| crate::GameError::ContentNotFound(format!("NPC '{}' not found", npc_id)))?;
         npc.current_disposition = disposition;
 
-        Ok(format!(
-            "{}\n\n[Disposition: {}] You can ask {} about: {}",
-            greeting_text,
+        let mut response = greeting_text;
+        for remark in remarks {
+            response.push_str(&format!("\n\n{}", remark));
+        }
+        response.push_str(&format!(
+            "\n\n[Disposition: {} | Relationship: {}] You can ask {} about: {}",
             self.disposition_description(disposition),
+            self.relationships.stage(npc_id).name(),
             npc_name,
             topics.join(", ")
-        ))
+        ));
+        Ok(response)
     }
 
     pub fn ask_about_topic(
@@ -229,13 +758,234 @@ impl DialogueSystem {
         if !self.check_requirements(&dialogue_node.requirements, player, faction_system) {
             return Ok(format!("{} doesn't seem willing to discuss {} with you.", npc_name, topic));
         }
+        if !self.relationship_allows(npc_id, &dialogue_node.requirements) {
+            return Ok(format!("{} doesn't know you well enough to discuss {}.", npc_name, topic));
+        }
 
         // Select response based on disposition
-        let response_text = self.select_response_text(&dialogue_node, current_disposition)?;
+        let mut response_text = self.select_response_text(&dialogue_node, current_disposition)?;
+        self.partner = Some((npc_id.to_string(), player.current_location.clone()));
+        self.visited_nodes.insert(format!("{}/{}", npc_id, topic));
+
+        // Offer the topic's responses until another topic is raised
+        self.conversation = None;
+        if !dialogue_node.responses.is_empty() {
+            response_text.push_str(&self.offer_responses(npc_id, topic, &dialogue_node));
+            self.conversation = Some(Conversation {
+                npc_id: npc_id.to_string(),
+                topic: topic.to_string(),
+                location_id: player.current_location.clone(),
+                branch: None,
+            });
+        }
 
         Ok(response_text)
     }
 
+    /// The numbered responses a node offers, marking checks already tried
+    /// and branches already heard
+    fn offer_responses(&self, npc_id: &str, node_id: &str, node: &DialogueNode) -> String {
+        let mut text = "\n\nYou could reply:".to_string();
+        for (index, response) in node.responses.iter().enumerate() {
+            let label = response.check.as_ref().map_or_else(String::new, |check| format!("{} ", check.label()));
+            let note = if self.attempted_checks.contains(&format!("{}/{}/{}", npc_id, node_id, index)) {
+                " (already tried)"
+            } else if response.next.as_ref().is_some_and(|next| self.visited_nodes.contains(&format!("{}/{}", npc_id, next))) {
+                " (heard)"
+            } else {
+                ""
+            };
+            text.push_str(&format!("\n  {}. {}\"{}\"{}", index + 1, label, self.render(&response.text), note));
+        }
+        text.push_str("\n(Use 'reply <number>'.)");
+        text
+    }
+
+    /// Move the conversation on to one of the NPC's branches, returning what
+    /// they say there; the conversation ends at a branch with no responses
+    fn enter_branch(&mut self, conversation: &Conversation, branch: &str, player: &Player, faction_system: &FactionSystem) -> Option<String> {
+        let npc = self.npcs.get(&conversation.npc_id)?;
+        let node = npc.dialogue_tree.branches.get(branch)?.clone();
+        if !self.check_requirements(&node.requirements, player, faction_system) || !self.relationship_allows(&npc.id, &node.requirements) {
+            return Some(format!("\n\n{} won't say more about that with you yet.", npc.short_name()));
+        }
+
+        let mut text = format!("\n\n{}", self.format_dialogue_text(&node.text_templates, npc.current_disposition));
+        self.visited_nodes.insert(format!("{}/{}", conversation.npc_id, branch));
+        if node.responses.is_empty() {
+            self.conversation = None;
+        } else {
+            text.push_str(&self.offer_responses(&conversation.npc_id, branch, &node));
+            self.conversation = Some(Conversation { branch: Some(branch.to_string()), ..conversation.clone() });
+        }
+        Some(text)
+    }
+
+    /// Who the player is talking to when they don't say: whoever they last
+    /// spoke to if that was here and they're still here, otherwise the only
+    /// person present
+    pub fn addressee(&self, world: &WorldState, quest_system: &QuestSystem) -> Option<&NPC> {
+        let present = self.npcs_present(&world.current_location, world, quest_system);
+        match &self.partner {
+            Some((npc_id, location)) if *location == world.current_location => {
+                present.into_iter().find(|npc| &npc.id == npc_id)
+            }
+            _ if present.len() == 1 => present.into_iter().next(),
+            _ => None,
+        }
+    }
+
+    /// Hand an NPC a gift worth `value` silver; returns how they take it
+    pub fn give_gift(&mut self, npc_id: &str, item_name: &str, value: i32, now: i32) -> GameResult<String> {
+        let name = self.npcs.get(npc_id)
+            .ok_or_else(|| crate::GameError::ContentNotFound(format!("NPC '{}' not found", npc_id)))?
+            .short_name().to_string();
+        let day = now.div_euclid(relationships::MINUTES_PER_DAY);
+        let change = relationships::gift_affinity(self.relationships.get_mut(npc_id), value, day);
+        self.relationships.get_mut(npc_id).last_gift_day = Some(day);
+
+        let mut response = format!("You give the {} to {}. {}", item_name, name, match change {
+            c if c >= 5 => "They're clearly touched.",
+            c if c > 1 => "They accept it with thanks.",
+            _ => "They accept it, a little bemused at another gift so soon.",
+        });
+        if let Some(message) = self.shift_relationship(npc_id, change, &format!("You gave them a {}", item_name)) {
+            response.push_str(&format!("\n\n{}", message));
+        }
+        Ok(response)
+    }
+
+    pub fn bonds(&self) -> &Bonds {
+        &self.bonds
+    }
+
+    /// Spend the day with an NPC on their bond track, as a friend or courting
+    /// them; returns the milestone scene and any world flag it sets
+    pub fn spend_time(&mut self, npc_id: &str, courting: bool, now: i32) -> GameResult<(String, Option<String>)> {
+        let name = self.npcs.get(npc_id)
+            .ok_or_else(|| crate::GameError::ContentNotFound(format!("NPC '{}' not found", npc_id)))?
+            .short_name().to_string();
+        let stage = self.relationships.stage(npc_id);
+        let milestone = self.bonds.spend_time(npc_id, &name, stage, courting, now.div_euclid(relationships::MINUTES_PER_DAY))?;
+
+        let mut response = self.render(&milestone.scene);
+        if let Some(message) = self.shift_relationship(npc_id, bonds::TIME_TOGETHER, "You spent time together") {
+            response.push_str(&format!("\n\n{}", message));
+        }
+        Ok((response, milestone.flag))
+    }
+
+    /// Bonds strained or mended by the player's faction standings; returns
+    /// what the NPCs have to say about it
+    pub fn weigh_loyalties(&mut self, standings: &HashMap<FactionId, i32>) -> Vec<String> {
+        let mut messages = Vec::new();
+        for (npc_id, strained, line) in self.bonds.weigh_loyalties(standings) {
+            messages.push(line.to_string());
+            if let Some(track) = bonds::bond_track(&npc_id).filter(|_| strained) {
+                let memory = format!("You grew close to the {}", track.rival.display_name());
+                messages.extend(self.shift_relationship(&npc_id, bonds::STRAINED, &memory));
+            }
+        }
+        messages
+    }
+
+    /// NPCs involved in a quest remember how it turned out; returns what they
+    /// make of it
+    pub fn quest_resolved(&mut self, involved_npcs: &[String], title: &str, completed: bool) -> Vec<String> {
+        let (change, memory) = if completed {
+            (relationships::QUEST_COMPLETED, format!("You saw {} through", title))
+        } else {
+            (relationships::QUEST_FAILED, format!("You let {} fail", title))
+        };
+        let mut messages = Vec::new();
+        for npc_id in involved_npcs {
+            let Some(name) = self.npcs.get(npc_id).map(|npc| npc.short_name().to_string()) else { continue };
+            messages.push(if completed {
+                format!("{} is grateful you saw {} through.", name, title)
+            } else {
+                format!("{} won't soon forget that {} failed.", name, title)
+            });
+            messages.extend(self.shift_relationship(npc_id, change, &memory));
+        }
+        messages
+    }
+
+    /// The topic whose responses the player can currently choose from
+    pub fn conversation(&self) -> Option<&Conversation> {
+        self.conversation.as_ref()
+    }
+
+    /// Choose a response to the topic or branch the conversation is on,
+    /// resolving its check and following it to any branch it leads to;
+    /// returns what is said and the effect to apply
+    pub fn reply(&mut self, choice: usize, player: &Player, faction_system: &FactionSystem) -> GameResult<(String, DialogueEffect)> {
+        let conversation = self.conversation.clone()
+            .ok_or_else(|| crate::GameError::InvalidCommand("Nobody is waiting on a reply from you. Ask someone about a topic first.".to_string()))?;
+        let npc = self.npcs.get(&conversation.npc_id)
+            .ok_or_else(|| crate::GameError::ContentNotFound(format!("NPC '{}' not found", conversation.npc_id)))?;
+        let node = match &conversation.branch {
+            Some(branch) => npc.dialogue_tree.branches.get(branch),
+            None => npc.dialogue_tree.topics.get(&conversation.topic),
+        };
+        let responses = node.map_or(&[][..], |node| &node.responses[..]);
+        let response = choice.checked_sub(1).and_then(|index| responses.get(index))
+            .ok_or_else(|| crate::GameError::InvalidInput(format!("Choose a reply from 1 to {}.", responses.len())))?
+            .clone();
+        let name = npc.short_name().to_string();
+
+        let Some(check) = &response.check else {
+            let reply = response.reply.as_deref().map_or_else(|| format!("{} nods.", name), |reply| self.render(reply));
+            let mut text = format!("You say, \"{}\"\n\n{}", self.render(&response.text), reply);
+            if let Some(next) = &response.next {
+                text.extend(self.enter_branch(&conversation, next, player, faction_system));
+            }
+            return Ok((text, response.effect.clone()));
+        };
+        let key = format!("{}/{}/{}", conversation.npc_id, conversation.node_id(), choice - 1);
+        if self.attempted_checks.contains(&key) {
+            return Ok((format!("You've already tried that with {}. It won't work a second time.", name), DialogueEffect::None));
+        }
+
+        let said = format!("You say, \"{}\"\n\n", self.render(&response.text));
+        let passed = check.passes(player);
+        let (mut text, effect) = if passed {
+            (format!("{} Success. {}", check.label(), response.reply.as_deref().map_or_else(|| format!("{} comes round.", name), |reply| self.render(reply))), response.effect.clone())
+        } else {
+            match &response.failure {
+                Some(failure) => (format!("{} Failure. {}", check.label(), self.render(&failure.text)), failure.effect.clone()),
+                None => (format!("{} Failure. {} isn't convinced.", check.label(), name), DialogueEffect::None),
+            }
+        };
+        self.attempted_checks.insert(key);
+
+        // They remember how they were talked to, and threats most of all
+        let (change, memory) = match (check, passed) {
+            (DialogueCheck::Intimidate { faction, .. }, true) => (relationships::INTIMIDATED, format!("You threatened them with the {}", faction.display_name())),
+            (DialogueCheck::CiteTheory { theory_id, .. }, true) => (relationships::CHECK_PASSED, format!("You impressed them with your grasp of {}", theory_id.replace('_', " "))),
+            (DialogueCheck::Persuade { .. }, true) => (relationships::CHECK_PASSED, "You talked them round".to_string()),
+            (DialogueCheck::Intimidate { .. }, false) => (relationships::CHECK_FAILED, "You tried to threaten them".to_string()),
+            (DialogueCheck::CiteTheory { theory_id, .. }, false) => (relationships::CHECK_FAILED, format!("You lectured them on {} and got it wrong", theory_id.replace('_', " "))),
+            (DialogueCheck::Persuade { .. }, false) => (relationships::CHECK_FAILED, "You failed to talk them round".to_string()),
+        };
+        if let Some(message) = self.shift_relationship(&conversation.npc_id, change, &memory) {
+            text.push_str(&format!("\n\n{}", message));
+        }
+        if let Some(next) = response.next.as_ref().filter(|_| passed) {
+            text.extend(self.enter_branch(&conversation, next, player, faction_system));
+        }
+        Ok((format!("{}{}", said, text), effect))
+    }
+
+    /// The world flag set by discussing a topic, if the NPC is willing to
+    /// discuss it
+    pub fn topic_flag(&self, npc_id: &str, topic: &str, player: &Player, faction_system: &FactionSystem) -> Option<&str> {
+        let npc = self.npcs.get(npc_id)?;
+        let node = npc.dialogue_tree.topics.get(topic)?;
+        (self.check_requirements(&node.requirements, player, faction_system) && self.relationship_allows(npc_id, &node.requirements))
+            .then(|| npc.topic_flags.get(topic).map(String::as_str))
+            .flatten()
+    }
+
     /// Generate theory-aware topics based on player's knowledge
     pub fn get_theory_topics(&self, npc_id: &str, player: &Player) -> Vec<String> {
         let mut topics = Vec::new();
@@ -383,7 +1133,8 @@ impl DialogueSystem {
         }
     }
 
-    fn calculate_disposition(&self, npc: &NPC, player: &Player, faction_system: &FactionSystem) -> i32 {
+    /// How an NPC feels about the player, from -100 to 100
+    pub fn calculate_disposition(&self, npc: &NPC, player: &Player, faction_system: &FactionSystem) -> i32 {
         let mut disposition = 0;
 
         // Base disposition from faction affiliation
@@ -405,24 +1156,44 @@ impl DialogueSystem {
             }
         }
 
+        // What they think of you personally
+        disposition += self.relationships.affinity(&npc.id);
+
         // Clamp disposition to valid range
         disposition.clamp(-100, 100)
     }
 
-    fn select_greeting_text(&self, npc: &NPC, player: &Player) -> GameResult<String> {
-        // Check for faction-specific greetings first
+    fn select_greeting_text(&self, npc: &NPC, player: &Player, disposition: i32) -> GameResult<String> {
+        // How far you've come with them colours the greeting most
+        if let Some(greeting) = npc.dialogue_tree.relationship_greetings.get(&self.relationships.stage(&npc.id)) {
+            return Ok(self.render(greeting));
+        }
+
+        // Check for faction-specific greetings next
         if let Some(faction_id) = npc.faction_affiliation {
             if let Some(faction_dialogue) = npc.dialogue_tree.faction_specific.get(&faction_id) {
                 if let Some(&player_standing) = player.faction_standings.get(&faction_id) {
                     if player_standing >= 50 {
-                        return Ok(self.format_dialogue_text(&faction_dialogue.text_templates, npc.current_disposition));
+                        return Ok(self.format_dialogue_text(&faction_dialogue.text_templates, disposition));
                     }
                 }
             }
         }
 
+        // Then how they greet people at this time of day, by its name or the
+        // broader part of the day it falls in
+        let period = match self.variables.time.as_str() {
+            "dawn" | "morning" => "morning",
+            "midday" | "afternoon" => "afternoon",
+            _ => "evening",
+        };
+        let greetings = &npc.dialogue_tree.time_based_greetings;
+        if let Some(greeting) = greetings.get(&self.variables.time).or_else(|| greetings.get(period)).filter(|_| !self.variables.time.is_empty()) {
+            return Ok(self.render(greeting));
+        }
+
         // Use default greeting
-        Ok(self.format_dialogue_text(&npc.dialogue_tree.greeting.text_templates, npc.current_disposition))
+        Ok(self.format_dialogue_text(&npc.dialogue_tree.greeting.text_templates, disposition))
     }
 
     fn select_response_text(&self, node: &DialogueNode, disposition: i32) -> GameResult<String> {
@@ -438,10 +1209,15 @@ impl DialogueSystem {
         };
 
         templates.get(template_index)
-            .cloned()
+            .map(|template| self.render(template))
             .unwrap_or_else(|| "...".to_string())
     }
 
+    /// Whether the player is close enough to an NPC for a node's requirements
+    fn relationship_allows(&self, npc_id: &str, requirements: &DialogueRequirements) -> bool {
+        requirements.min_relationship.is_none_or(|min| self.relationships.stage(npc_id) >= min)
+    }
+
     fn check_requirements(
         &self,
         requirements: &DialogueRequirements,
@@ -522,6 +1298,18 @@ impl DialogueSystem {
     }
 }
 
+impl ActionSource for DialogueSystem {
+    fn available_actions(&self, situation: &Situation) -> Vec<SuggestedAction> {
+        self.npcs_present(&situation.world.current_location, situation.world, situation.quest_system).iter()
+            .map(|npc| SuggestedAction::new(
+                ActionKind::People,
+                format!("talk to {}", npc.name),
+                npc.faction_affiliation.map_or("here now".to_string(), |faction| format!("of the {}", faction.display_name())),
+            ))
+            .collect()
+    }
+}
+
 impl Default for DialogueSystem {
     fn default() -> Self {
         Self::new()
@@ -569,6 +1357,9 @@ mod tests {
                 quirks: vec!["Often mentions profit margins".to_string()],
             }),
             quest_dialogue: HashMap::new(),
+            availability: NPCAvailability::default(),
+            flag_lines: Vec::new(),
+            topic_flags: HashMap::new(),
             dialogue_tree: DialogueTree {
                 greeting: DialogueNode {
                     text_templates: vec![
@@ -584,9 +1375,12 @@ mod tests {
                         theory_requirements: vec![],
                         min_theory_mastery: None,
                         required_capabilities: vec![],
+                        min_relationship: None,
                     },
                 },
                 time_based_greetings: HashMap::new(),
+                relationship_greetings: HashMap::new(),
+                branches: HashMap::new(),
                 topics: {
                     let mut topics = HashMap::new();
                     topics.insert("trade".to_string(), DialogueNode {
@@ -603,6 +1397,7 @@ mod tests {
                             theory_requirements: vec![],
                             min_theory_mastery: None,
                             required_capabilities: vec![],
+                            min_relationship: None,
                         },
                     });
                     topics.insert("secrets".to_string(), DialogueNode {
@@ -619,6 +1414,7 @@ mod tests {
                             theory_requirements: vec![],
                             min_theory_mastery: None,
                             required_capabilities: vec![],
+                            min_relationship: None,
                         },
                     });
                     topics
@@ -639,6 +1435,7 @@ mod tests {
                             theory_requirements: vec![],
                             min_theory_mastery: None,
                             required_capabilities: vec![],
+                            min_relationship: None,
                         },
                     });
                     faction_specific
@@ -656,6 +1453,9 @@ mod tests {
             faction_affiliation: None,
             personality: None,
             quest_dialogue: HashMap::new(),
+            availability: NPCAvailability::default(),
+            flag_lines: Vec::new(),
+            topic_flags: HashMap::new(),
             dialogue_tree: DialogueTree {
                 greeting: DialogueNode {
                     text_templates: vec![
@@ -671,9 +1471,12 @@ mod tests {
                         theory_requirements: vec![],
                         min_theory_mastery: None,
                         required_capabilities: vec![],
+                        min_relationship: None,
                     },
                 },
                 time_based_greetings: HashMap::new(),
+                relationship_greetings: HashMap::new(),
+                branches: HashMap::new(),
                 topics: {
                     let mut topics = HashMap::new();
                     topics.insert("research".to_string(), DialogueNode {
@@ -690,6 +1493,7 @@ mod tests {
                             theory_requirements: vec![],
                             min_theory_mastery: None,
                             required_capabilities: vec![],
+                            min_relationship: None,
                         },
                     });
                     topics
@@ -708,6 +1512,9 @@ mod tests {
             faction_affiliation: Some(FactionId::UndergroundNetwork),
             personality: None,
             quest_dialogue: HashMap::new(),
+            availability: NPCAvailability::default(),
+            flag_lines: Vec::new(),
+            topic_flags: HashMap::new(),
             dialogue_tree: DialogueTree {
                 greeting: DialogueNode {
                     text_templates: vec![
@@ -723,9 +1530,12 @@ mod tests {
                         theory_requirements: vec![],
                         min_theory_mastery: None,
                         required_capabilities: vec![],
+                        min_relationship: None,
                     },
                 },
                 time_based_greetings: HashMap::new(),
+                relationship_greetings: HashMap::new(),
+                branches: HashMap::new(),
                 topics: {
                     let mut topics = HashMap::new();
                     topics.insert("operations".to_string(), DialogueNode {
@@ -742,6 +1552,7 @@ mod tests {
                             theory_requirements: vec![],
                             min_theory_mastery: None,
                             required_capabilities: vec![],
+                            min_relationship: None,
                         },
                     });
                     topics
@@ -903,6 +1714,7 @@ mod tests {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         };
         assert!(dialogue_system.check_requirements(&req_met, &player, &faction_system));
 
@@ -914,6 +1726,7 @@ mod tests {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         };
         assert!(!dialogue_system.check_requirements(&req_not_met, &player, &faction_system));
 
@@ -925,6 +1738,7 @@ mod tests {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         };
         assert!(dialogue_system.check_requirements(&req_max_met, &player, &faction_system));
 
@@ -936,6 +1750,7 @@ mod tests {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         };
         assert!(!dialogue_system.check_requirements(&req_max_not_met, &player, &faction_system));
     }
@@ -954,6 +1769,7 @@ mod tests {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         };
         assert!(dialogue_system.check_requirements(&req_met, &player, &faction_system));
 
@@ -965,6 +1781,7 @@ mod tests {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         };
         assert!(!dialogue_system.check_requirements(&req_not_met, &player, &faction_system));
 
@@ -976,6 +1793,7 @@ mod tests {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         };
         assert!(dialogue_system.check_requirements(&req_multiple, &player, &faction_system));
 
@@ -987,6 +1805,7 @@ mod tests {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         };
         assert!(!dialogue_system.check_requirements(&req_multiple_missing, &player, &faction_system));
     }
@@ -1005,6 +1824,7 @@ mod tests {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         };
         assert!(dialogue_system.check_requirements(&req_all_met, &player, &faction_system));
 
@@ -1016,6 +1836,7 @@ mod tests {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         };
         assert!(!dialogue_system.check_requirements(&req_partial, &player, &faction_system));
     }
@@ -1026,7 +1847,7 @@ mod tests {
         let player = create_test_player();
         let npc = create_neutral_npc(); // No faction affiliation
 
-        let greeting = dialogue_system.select_greeting_text(&npc, &player).unwrap();
+        let greeting = dialogue_system.select_greeting_text(&npc, &player, npc.current_disposition).unwrap();
 
         // Should use default greeting (neutral disposition -> middle template)
         assert_eq!(greeting, "Hello.");
@@ -1044,7 +1865,7 @@ mod tests {
         let faction_system = create_test_faction_system();
         npc.current_disposition = dialogue_system.calculate_disposition(&npc, &player, &faction_system);
 
-        let greeting = dialogue_system.select_greeting_text(&npc, &player).unwrap();
+        let greeting = dialogue_system.select_greeting_text(&npc, &player, npc.current_disposition).unwrap();
 
         // Should use faction-specific greeting when disposition is friendly
         // Since greeting selection depends on disposition, check for any of the possible faction-specific greetings
@@ -1060,7 +1881,7 @@ mod tests {
 
         let npc = create_basic_npc(); // Has Consortium affiliation but standing too low
 
-        let greeting = dialogue_system.select_greeting_text(&npc, &player).unwrap();
+        let greeting = dialogue_system.select_greeting_text(&npc, &player, npc.current_disposition).unwrap();
 
         // Should use default greeting since standing < 50
         assert_eq!(greeting, "Hello there, how can I help?");
@@ -1086,6 +1907,46 @@ mod tests {
         assert!(response.contains("Disposition:"));
     }
 
+    #[test]
+    fn test_greetings_are_translated_and_filled_in() {
+        let mut dialogue_system = DialogueSystem::new();
+        let player = create_test_player();
+        let faction_system = create_test_faction_system();
+        let mut npc = create_basic_npc();
+        npc.dialogue_tree.greeting.text_templates = vec!["@merchant.greeting|Good {time}, {player}.".to_string()];
+        dialogue_system.add_npc(npc);
+        dialogue_system.set_variables(TextVariables {
+            player: "Elara".to_string(),
+            time: "evening".to_string(),
+            ..TextVariables::default()
+        });
+
+        let response = dialogue_system.talk_to_npc("test_merchant", &player, &faction_system).unwrap();
+        assert!(response.starts_with("Good evening, Elara."));
+
+        dialogue_system.set_localization(Localization::new("fr", HashMap::from([
+            ("merchant.greeting".to_string(), "Bonsoir, {player}.".to_string()),
+        ])));
+        let response = dialogue_system.talk_to_npc("test_merchant", &player, &faction_system).unwrap();
+        assert!(response.starts_with("Bonsoir, Elara."));
+        assert_eq!(dialogue_system.localization().locale(), "fr");
+
+        // Time-of-day greetings take over at their part of the day
+        let mut npc = create_basic_npc();
+        npc.dialogue_tree.time_based_greetings = HashMap::from([
+            ("morning".to_string(), "Early start, {player}.".to_string()),
+            ("evening".to_string(), "Late one, {player}.".to_string()),
+        ]);
+        dialogue_system.add_npc(npc);
+        dialogue_system.set_variables(TextVariables {
+            player: "Elara".to_string(),
+            time: "night".to_string(),
+            ..TextVariables::default()
+        });
+        let response = dialogue_system.talk_to_npc("test_merchant", &player, &faction_system).unwrap();
+        assert!(response.starts_with("Late one, Elara."));
+    }
+
     #[test]
     fn test_talk_to_npc_not_found() {
         let mut dialogue_system = DialogueSystem::new();
@@ -1341,6 +2202,7 @@ mod tests {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         };
 
         // Should fail because player has no standing (treated as 0, which is < 10)
@@ -1364,9 +2226,379 @@ mod tests {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         };
 
         // Should pass because player has no standing (treated as 0, which is <= 10)
         assert!(dialogue_system.check_requirements(&req, &player, &faction_system));
     }
-}
\ No newline at end of file
+
+    fn create_scheduled_world() -> (DialogueSystem, WorldState) {
+        use crate::core::world_state::Location;
+
+        let mut world = WorldState::new();
+        world.add_location(Location::new("chambers".to_string(), "Testing Chambers".to_string(), "Wards hum.".to_string()));
+        world.add_location(Location::new("unstable_site".to_string(), "Unstable Site".to_string(), "Reality flickers.".to_string()));
+        world.current_location = "chambers".to_string();
+
+        let mut npc = create_basic_npc();
+        npc.id = "warden_gareth".to_string();
+        npc.name = "Safety Warden Gareth Ironshield".to_string();
+        npc.availability.home_location = Some("chambers".to_string());
+        npc.availability.schedule = vec![
+            ScheduleEntry {
+                start_hour: 14,
+                end_hour: 18,
+                activity: "inspecting the wards".to_string(),
+                location: Some("unstable_site".to_string()),
+                available: false,
+                quest_id: None,
+                flag: None,
+                weekday: None,
+            },
+            ScheduleEntry {
+                start_hour: 8,
+                end_hour: 10,
+                activity: "running safety drills".to_string(),
+                location: None,
+                available: false,
+                quest_id: None,
+                flag: None,
+                weekday: None,
+            },
+        ];
+
+        let mut dialogue_system = DialogueSystem::new();
+        dialogue_system.add_npc(npc);
+        (dialogue_system, world)
+    }
+
+    #[test]
+    fn test_find_npc_by_name_fragment() {
+        let (dialogue_system, _world) = create_scheduled_world();
+
+        assert_eq!(dialogue_system.find_npc("gareth", "chambers").unwrap().id, "warden_gareth");
+        assert_eq!(dialogue_system.find_npc("Warden_Gareth", "elsewhere").unwrap().id, "warden_gareth");
+        assert!(dialogue_system.find_npc("lyra", "chambers").is_none());
+        assert_eq!(dialogue_system.get_npc("warden_gareth").unwrap().short_name(), "Gareth");
+    }
+
+    #[test]
+    fn test_schedule_absence_and_busy_messages() {
+        let (mut dialogue_system, mut world) = create_scheduled_world();
+        let quest_system = QuestSystem::new();
+
+        // Midnight: at home and free
+        assert!(dialogue_system.unavailability_message("warden_gareth", &world, &quest_system).is_none());
+
+        world.advance_time(15 * 60);
+        let absent = dialogue_system.unavailability_message("warden_gareth", &world, &quest_system).unwrap();
+        assert!(absent.contains("Gareth is inspecting the wards until evening, somewhere other than here"));
+        assert!(!absent.contains("Unstable Site"));
+        dialogue_system.learn_whereabouts("warden_gareth", "unstable_site");
+        let absent = dialogue_system.unavailability_message("warden_gareth", &world, &quest_system).unwrap();
+        assert!(absent.contains("Gareth is inspecting the wards until evening"));
+        assert!(absent.contains("Unstable Site"));
+
+        world.advance_time(-6 * 60);
+        let busy = dialogue_system.unavailability_message("warden_gareth", &world, &quest_system).unwrap();
+        assert!(busy.contains("running safety drills until morning"));
+        assert!(busy.contains("Come back then"));
+
+        world.current_location = "unstable_site".to_string();
+        world.advance_time(3 * 60);
+        let elsewhere = dialogue_system.unavailability_message("warden_gareth", &world, &quest_system).unwrap();
+        assert!(elsewhere.contains("can usually be found at the Testing Chambers"));
+
+        // A weekly routine only applies on its day, and overrides the daily one
+        dialogue_system.get_npc_mut("warden_gareth").unwrap().availability.schedule.push(ScheduleEntry {
+            start_hour: 14,
+            end_hour: 18,
+            activity: "briefing the Council".to_string(),
+            location: None,
+            available: true,
+            quest_id: None,
+            flag: None,
+            weekday: Some(Weekday::Tuesday),
+        });
+        world.current_location = "chambers".to_string();
+        world.advance_time(3 * 60);
+        assert!(dialogue_system.unavailability_message("warden_gareth", &world, &quest_system).is_some());
+        world.advance_time(24 * 60);
+        assert!(dialogue_system.unavailability_message("warden_gareth", &world, &quest_system).is_none());
+    }
+
+    #[test]
+    fn test_quest_schedule_and_cooldown() {
+        use crate::systems::quests::{LearningMetrics, QuestLearningProgress, QuestProgress};
+
+        let (mut dialogue_system, mut world) = create_scheduled_world();
+        let mut quest_system = QuestSystem::new();
+
+        dialogue_system.get_npc_mut("warden_gareth").unwrap().availability.schedule.push(ScheduleEntry {
+            start_hour: 14,
+            end_hour: 18,
+            activity: "briefing your investigation team".to_string(),
+            location: None,
+            available: true,
+            quest_id: Some("unstable_site_investigation".to_string()),
+            flag: None,
+            weekday: None,
+        });
+
+        world.advance_time(15 * 60);
+        assert!(dialogue_system.unavailability_message("warden_gareth", &world, &quest_system).is_some());
+
+        // With the quest under way, the quest entry overrides the routine
+        let progress = QuestProgress {
+            quest_id: "unstable_site_investigation".to_string(),
+            status: QuestStatus::InProgress,
+            started_at: chrono::Utc::now(),
+            completed_at: None,
+            objective_progress: HashMap::new(),
+            chosen_branch: None,
+            player_choices: HashMap::new(),
+            time_invested: 0,
+            quest_variables: HashMap::new(),
+            learning_progress: QuestLearningProgress {
+                mastered_concepts: Vec::new(),
+                demonstrated_methods: Vec::new(),
+                assessment_scores: HashMap::new(),
+                learning_metrics: LearningMetrics {
+                    completion_efficiency: 1.0,
+                    first_attempt_success_rate: 1.0,
+                    help_requests: 0,
+                    application_accuracy: 1.0,
+                },
+            },
+        };
+        quest_system.player_progress.insert("unstable_site_investigation".to_string(), progress);
+        assert!(dialogue_system.unavailability_message("warden_gareth", &world, &quest_system).is_none());
+
+        dialogue_system.set_busy("warden_gareth", "filing an incident report", world.game_time_minutes, 30).unwrap();
+        let cooldown = dialogue_system.unavailability_message("warden_gareth", &world, &quest_system).unwrap();
+        assert!(cooldown.contains("busy filing an incident report"));
+        assert!(cooldown.contains("30 minutes"));
+
+        world.advance_time(30);
+        assert!(dialogue_system.unavailability_message("warden_gareth", &world, &quest_system).is_none());
+    }
+
+    #[test]
+    fn test_event_schedules_and_tracking_npcs_down() {
+        let (mut dialogue_system, mut world) = create_scheduled_world();
+        let mut quest_system = QuestSystem::new();
+
+        let mut colleague = create_basic_npc();
+        colleague.id = "warden_mira".to_string();
+        colleague.name = "Warden Mira".to_string();
+        colleague.availability.home_location = Some("chambers".to_string());
+        let mut stranger = create_basic_npc();
+        stranger.id = "scholar_edwin".to_string();
+        stranger.name = "Scholar Edwin".to_string();
+        stranger.faction_affiliation = Some(FactionId::NeutralScholars);
+        stranger.availability.home_location = Some("archives".to_string());
+        dialogue_system.add_npc(colleague);
+        dialogue_system.add_npc(stranger);
+        dialogue_system.get_npc_mut("warden_gareth").unwrap().availability.schedule.push(ScheduleEntry {
+            start_hour: 14,
+            end_hour: 18,
+            activity: "guarding the accord".to_string(),
+            location: None,
+            available: true,
+            quest_id: None,
+            flag: Some("accord_signed".to_string()),
+            weekday: None,
+        });
+
+        world.advance_time(15 * 60);
+        let names = |present: Vec<&NPC>| present.iter().map(|npc| npc.id.clone()).collect::<Vec<_>>();
+        assert_eq!(names(dialogue_system.npcs_present("unstable_site", &world, &quest_system)), vec!["warden_gareth"]);
+        assert_eq!(names(dialogue_system.npcs_present("chambers", &world, &quest_system)), vec!["warden_mira"]);
+
+        // Only colleagues know where someone has gone, and their own topics come first
+        assert!(dialogue_system.whereabouts_answer("warden_mira", "trade", &world, &quest_system).is_none());
+        assert!(dialogue_system.whereabouts_answer("warden_mira", "nobody", &world, &quest_system).is_none());
+        let vague = dialogue_system.whereabouts_answer("scholar_edwin", "gareth", &world, &quest_system).unwrap();
+        assert!(vague.contains("usually work at the Testing Chambers"));
+        assert!(!dialogue_system.unavailability_message("warden_gareth", &world, &quest_system).unwrap().contains("Unstable Site"));
+        let exact = dialogue_system.whereabouts_answer("warden_mira", "gareth", &world, &quest_system).unwrap();
+        assert!(exact.contains("inspecting the wards. Try the Unstable Site"));
+        assert!(dialogue_system.unavailability_message("warden_gareth", &world, &quest_system).unwrap().contains("Unstable Site"));
+
+        let found = dialogue_system.locate("edwin", &world, &quest_system).unwrap();
+        assert!(found.contains("Scholar Edwin's resonance signature at the archives"));
+        assert!(dialogue_system.locate("nobody", &world, &quest_system).is_err());
+
+        // Once the accord is signed, the event keeps Gareth at home instead
+        quest_system.global_state.flags.set("accord_signed");
+        assert_eq!(dialogue_system.get_npc("warden_gareth").unwrap().whereabouts(15 * 60, &quest_system), Some("chambers"));
+        assert_eq!(
+            names(dialogue_system.npcs_present("chambers", &world, &quest_system)),
+            vec!["warden_gareth", "warden_mira"]
+        );
+        assert!(dialogue_system.unavailability_message("warden_gareth", &world, &quest_system).is_none());
+    }
+
+    #[test]
+    fn test_presence_and_cooldowns_for_resting() {
+        let (mut dialogue_system, mut world) = create_scheduled_world();
+        let quest_system = QuestSystem::new();
+
+        assert_eq!(dialogue_system.npcs_available_here(&world, &quest_system), vec!["Gareth".to_string()]);
+        world.advance_time(15 * 60);
+        assert!(dialogue_system.npcs_available_here(&world, &quest_system).is_empty());
+
+        dialogue_system.set_busy("warden_gareth", "filing an incident report", world.game_time_minutes, 90).unwrap();
+        let now = world.game_time_minutes;
+        assert!(dialogue_system.cooldowns_ending(now, now + 60).is_empty());
+        assert_eq!(
+            dialogue_system.cooldowns_ending(now + 60, now + 120),
+            vec![("Gareth".to_string(), "filing an incident report".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_checked_responses_branch_and_are_tried_once() {
+        let mut dialogue_system = DialogueSystem::new();
+        let mut npc = create_basic_npc();
+        npc.dialogue_tree.topics.get_mut("trade").unwrap().responses = vec![
+            DialogueResponse {
+                text: "Your prices ignore how the crystals are cut.".to_string(),
+                effect: DialogueEffect::GiveInformation("Ledger shared".to_string()),
+                reply: Some("Fair point.".to_string()),
+                check: Some(DialogueCheck::CiteTheory { theory_id: "crystal_structures".to_string(), min_understanding: 0.6 }),
+                failure: Some(DialogueOutcome { text: "Nonsense.".to_string(), effect: DialogueEffect::FactionStandingChange(FactionId::IndustrialConsortium, -5) }),
+                next: None,
+            },
+            DialogueResponse {
+                text: "Thanks anyway.".to_string(),
+                effect: DialogueEffect::None,
+                reply: None,
+                check: None,
+                failure: None,
+                next: None,
+            },
+        ];
+        dialogue_system.add_npc(npc);
+        let mut player = create_test_player();
+        let faction_system = create_test_faction_system();
+
+        assert!(dialogue_system.reply(1, &player, &faction_system).is_err());
+        let asked = dialogue_system.ask_about_topic("test_merchant", "trade", &player, &faction_system).unwrap();
+        assert!(asked.contains("1. [Cite crystal structures] \"Your prices ignore how the crystals are cut.\""));
+        assert!(dialogue_system.reply(3, &player, &faction_system).is_err());
+
+        player.knowledge.theories.insert("crystal_structures".to_string(), 0.4);
+        let (text, effect) = dialogue_system.reply(1, &player, &faction_system).unwrap();
+        assert!(text.ends_with("[Cite crystal structures] Failure. Nonsense."));
+        assert!(matches!(effect, DialogueEffect::FactionStandingChange(FactionId::IndustrialConsortium, -5)));
+
+        // Learning more doesn't buy a second attempt
+        player.knowledge.theories.insert("crystal_structures".to_string(), 0.7);
+        let (text, effect) = dialogue_system.reply(1, &player, &faction_system).unwrap();
+        assert!(text.contains("already tried"));
+        assert!(matches!(effect, DialogueEffect::None));
+        assert!(dialogue_system.ask_about_topic("test_merchant", "trade", &player, &faction_system).unwrap().contains("(already tried)"));
+        assert!(dialogue_system.reply(2, &player, &faction_system).unwrap().0.ends_with("Test nods."));
+
+        let check = DialogueCheck::CiteTheory { theory_id: "crystal_structures".to_string(), min_understanding: 0.6 };
+        assert!(check.passes(&player));
+        assert!(DialogueCheck::Intimidate { faction: FactionId::MagistersCouncil, min_standing: 40 }.passes(&player));
+        assert!(!DialogueCheck::Persuade { min_acuity: 101 }.passes(&player));
+    }
+
+    #[test]
+    fn test_responses_lead_into_nested_branches() {
+        let mut dialogue_system = DialogueSystem::new();
+        let mut npc = create_basic_npc();
+        let response = |text: &str, next: Option<&str>| DialogueResponse {
+            text: text.to_string(),
+            effect: DialogueEffect::None,
+            reply: None,
+            check: None,
+            failure: None,
+            next: next.map(str::to_string),
+        };
+        let node = |text: &str, responses: Vec<DialogueResponse>| DialogueNode {
+            text_templates: vec![text.to_string()],
+            responses,
+            ..npc.dialogue_tree.greeting.clone()
+        };
+        let suppliers = node("My suppliers are a secret.", vec![response("Not even a hint?", Some("hint")), response("Fair enough.", None)]);
+        let hint = node("Try the eastern quarry.", Vec::new());
+        npc.dialogue_tree.branches.insert("suppliers".to_string(), suppliers);
+        npc.dialogue_tree.branches.insert("hint".to_string(), hint);
+        npc.dialogue_tree.topics.get_mut("trade").unwrap().responses = vec![response("Where do you buy?", Some("suppliers"))];
+        dialogue_system.add_npc(npc);
+        let player = create_test_player();
+        let faction_system = create_test_faction_system();
+
+        dialogue_system.ask_about_topic("test_merchant", "trade", &player, &faction_system).unwrap();
+        let (text, _) = dialogue_system.reply(1, &player, &faction_system).unwrap();
+        assert!(text.contains("My suppliers are a secret."));
+        assert!(text.contains("1. \"Not even a hint?\""));
+        assert_eq!(dialogue_system.conversation().unwrap().node_id(), "suppliers");
+
+        // Fair enough leaves the conversation where it is; the hint ends it
+        assert!(dialogue_system.reply(2, &player, &faction_system).unwrap().0.ends_with("Test nods."));
+        let (text, _) = dialogue_system.reply(1, &player, &faction_system).unwrap();
+        assert!(text.ends_with("Try the eastern quarry."));
+        assert!(dialogue_system.conversation().is_none());
+
+        let again = dialogue_system.ask_about_topic("test_merchant", "trade", &player, &faction_system).unwrap();
+        assert!(again.contains("\"Where do you buy?\" (heard)"));
+    }
+
+    #[test]
+    fn test_addressee_is_last_partner_or_only_person_here() {
+        let (mut dialogue_system, world) = create_scheduled_world();
+        let quest_system = QuestSystem::new();
+        let mut player = create_test_player();
+        let faction_system = create_test_faction_system();
+        player.current_location = "chambers".to_string();
+
+        assert_eq!(dialogue_system.addressee(&world, &quest_system).unwrap().id, "warden_gareth");
+
+        let mut other = create_basic_npc();
+        other.availability.home_location = Some("chambers".to_string());
+        dialogue_system.add_npc(other);
+        assert!(dialogue_system.addressee(&world, &quest_system).is_none());
+        dialogue_system.talk_to_npc("test_merchant", &player, &faction_system).unwrap();
+        assert_eq!(dialogue_system.addressee(&world, &quest_system).unwrap().id, "test_merchant");
+    }
+
+    #[test]
+    fn test_relationships_shift_disposition_and_gate_topics() {
+        use crate::systems::relationships::RelationshipStage;
+
+        let mut dialogue_system = DialogueSystem::new();
+        let mut npc = create_basic_npc();
+        npc.dialogue_tree.topics.get_mut("trade").unwrap().requirements.min_relationship = Some(RelationshipStage::Friend);
+        npc.dialogue_tree.relationship_greetings.insert(RelationshipStage::Friend, "Good to see you, friend.".to_string());
+        dialogue_system.add_npc(npc);
+        let mut player = create_test_player();
+        let faction_system = create_test_faction_system();
+
+        let first = dialogue_system.talk_to_npc("test_merchant", &player, &faction_system).unwrap();
+        assert!(first.contains("Relationship: acquaintance"));
+        let before = dialogue_system.get_npc("test_merchant").unwrap().current_disposition;
+        assert!(dialogue_system.ask_about_topic("test_merchant", "trade", &player, &faction_system).unwrap()
+            .contains("doesn't know you well enough"));
+
+        // A finished quest and a gift make a friend
+        let messages = dialogue_system.quest_resolved(&["test_merchant".to_string(), "nobody".to_string()], "The Ledger", true);
+        assert_eq!(messages, vec!["Test is grateful you saw The Ledger through.".to_string()]);
+        let gift = dialogue_system.give_gift("test_merchant", "crystal fragment", 60, 0).unwrap();
+        assert!(gift.ends_with("Test counts you as a friend now."));
+        assert_eq!(dialogue_system.relationships().get("test_merchant").unwrap().memories.last().unwrap(), "You gave them a crystal fragment");
+
+        // The Consortium hears of the player's work for it
+        player.faction_standings.insert(FactionId::IndustrialConsortium, 40);
+        let greeting = dialogue_system.talk_to_npc("test_merchant", &player, &faction_system).unwrap();
+        assert!(greeting.starts_with("Good to see you, friend."));
+        assert!(greeting.contains("Test has heard how you've helped the Industrial Consortium."));
+        assert!(dialogue_system.get_npc("test_merchant").unwrap().current_disposition > before + 20);
+        assert!(dialogue_system.ask_about_topic("test_merchant", "trade", &player, &faction_system).is_ok_and(|text| !text.contains("well enough")));
+    }
+}
@@ -0,0 +1,464 @@
+//! Faction emissaries calling at the player's laboratory
+//!
+//! Once the player owns a laboratory the factions know where to find them.
+//! Every few days the faction whose regard for the player has swung furthest
+//! since the last visit sends someone round:
+//! - a faction the player has pleased offers a deal: silver for the use of
+//!   the bench, which their rivals won't like
+//! - a faction the player has slighted sends a warning, to be heeded or defied
+//! - a hostile faction, or one whose warning was defied, sends a saboteur
+//!   after the laboratory's stock and equipment
+//!
+//! An emissary waits at the laboratory for a day. The player answers with
+//! `emissary <number>` once they're there; one left waiting goes away, though
+//! a saboteur does their work first. Visits are kept with the world, so the
+//! schedule and history are saved with the game.
+
+use crate::core::calendar::{Date, MINUTES_PER_DAY};
+use crate::core::{Player, WorldState};
+use crate::systems::factions::{FactionId, FactionSystem};
+use crate::systems::laboratory::{Fixture, Laboratory, LAB_LOCATION};
+use crate::GameResult;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Minutes between one emissary and the next
+pub const VISIT_INTERVAL: i32 = 2 * MINUTES_PER_DAY;
+/// Minutes an emissary waits at the laboratory for an answer
+pub const PATIENCE_MINUTES: i32 = MINUTES_PER_DAY;
+/// Standing at or below which a faction sends saboteurs rather than warnings
+pub const HOSTILE_STANDING: i32 = -30;
+/// Silver a faction pays for the use of the bench
+const DEAL_SILVER: i32 = 40;
+/// Standing gained with a faction by taking their deal, and half of it lost
+/// with their rival
+const DEAL_STANDING: i32 = 6;
+/// Standing a heeded warning wins back; a defied one costs twice as much
+const WARNING_STANDING: i32 = 4;
+/// Silver a saboteur takes to leave the laboratory alone
+const PAYOFF_SILVER: i32 = 30;
+/// Standing lost with a faction whose saboteur is driven off
+const DRIVEN_OFF_STANDING: i32 = 5;
+/// Visits remembered for the history
+const HISTORY_LENGTH: usize = 10;
+
+/// Why an emissary has come
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VisitKind {
+    Deal,
+    Warning,
+    Sabotage,
+}
+
+/// How a visit ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    Accepted,
+    Declined,
+    Heeded,
+    Defied,
+    DrivenOff,
+    PaidOff,
+    Sabotaged,
+    /// The emissary gave up waiting
+    Lapsed,
+}
+
+impl Outcome {
+    fn describe(&self) -> &'static str {
+        match self {
+            Outcome::Accepted => "deal accepted",
+            Outcome::Declined => "deal declined",
+            Outcome::Heeded => "warning heeded",
+            Outcome::Defied => "warning defied",
+            Outcome::DrivenOff => "saboteur driven off",
+            Outcome::PaidOff => "saboteur paid off",
+            Outcome::Sabotaged => "laboratory sabotaged",
+            Outcome::Lapsed => "left waiting",
+        }
+    }
+}
+
+/// An emissary at the laboratory, waiting for an answer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Visit {
+    pub faction: FactionId,
+    pub kind: VisitKind,
+    /// The faction a deal would cost standing with
+    pub rival: Option<FactionId>,
+    /// Game time the emissary arrived, in minutes
+    pub arrived_at: i32,
+    /// Whether the player has been to the laboratory to hear them out
+    pub met: bool,
+}
+
+/// A visit that has ended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VisitRecord {
+    pub faction: FactionId,
+    pub kind: VisitKind,
+    pub outcome: Outcome,
+    /// Game time it ended, in minutes
+    pub at: i32,
+}
+
+/// Emissary schedule and history, saved with the world
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Emissaries {
+    /// When the next emissary is due, once the player has a laboratory
+    #[serde(default)]
+    next_visit: Option<i32>,
+    #[serde(default)]
+    waiting: Option<Visit>,
+    /// The player's standing with each faction when it last sent someone,
+    /// to measure the swing since
+    #[serde(
+        default,
+        serialize_with = "crate::systems::serde_helpers::serialize_faction_map",
+        deserialize_with = "crate::systems::serde_helpers::deserialize_faction_map"
+    )]
+    standings_seen: HashMap<FactionId, i32>,
+    #[serde(default)]
+    history: Vec<VisitRecord>,
+}
+
+impl Emissaries {
+    /// The emissary waiting at the laboratory, if any
+    pub fn waiting(&self) -> Option<&Visit> {
+        self.waiting.as_ref()
+    }
+
+    /// Visits that have ended, oldest first
+    pub fn history(&self) -> &[VisitRecord] {
+        &self.history
+    }
+
+    /// Who to send, and why, given how the player's standings have moved
+    fn choose(&self, player: &Player, faction_system: &FactionSystem, now: i32) -> Option<Visit> {
+        let (faction, swing) = FactionId::all().into_iter()
+            .map(|faction| (faction, player.faction_reputation(faction) - self.standings_seen.get(&faction).copied().unwrap_or(0)))
+            .filter(|(_, swing)| *swing != 0)
+            .fold(None, |best: Option<(FactionId, i32)>, (faction, swing)| match best {
+                Some((_, best_swing)) if best_swing.abs() >= swing.abs() => best,
+                _ => Some((faction, swing)),
+            })?;
+
+        let defied = self.history.iter().rev()
+            .find(|record| record.faction == faction)
+            .is_some_and(|record| record.outcome == Outcome::Defied);
+        let kind = if swing > 0 {
+            VisitKind::Deal
+        } else if defied || player.faction_reputation(faction) <= HOSTILE_STANDING {
+            VisitKind::Sabotage
+        } else {
+            VisitKind::Warning
+        };
+        let rival = (kind == VisitKind::Deal).then(|| {
+            FactionId::all().into_iter()
+                .filter(|other| *other != faction)
+                .min_by(|a, b| faction_system.get_relationship_strength(faction, *a)
+                    .total_cmp(&faction_system.get_relationship_strength(faction, *b)))
+        }).flatten();
+
+        Some(Visit { faction, kind, rival, arrived_at: now, met: false })
+    }
+
+    /// End the visit waiting, measuring the faction's next swing from here
+    fn finish(&mut self, visit: &Visit, outcome: Outcome, player: &Player, now: i32) {
+        self.waiting = None;
+        self.standings_seen.insert(visit.faction, player.faction_reputation(visit.faction));
+        self.next_visit = Some(now + VISIT_INTERVAL);
+        self.history.push(VisitRecord { faction: visit.faction, kind: visit.kind, outcome, at: now });
+        if self.history.len() > HISTORY_LENGTH {
+            self.history.remove(0);
+        }
+    }
+}
+
+/// What the emissary says, and the choices the player has
+fn scene(visit: &Visit, player: &Player) -> String {
+    let faction = visit.faction.display_name();
+    let mut text = match visit.kind {
+        VisitKind::Deal => format!(
+            "An emissary of the {} is at your laboratory door. Your recent work has been noticed, and they offer\n\
+             {} silver for the use of your bench on their own projects.{}\n\
+             1) Accept the deal\n\
+             2) Decline politely",
+            faction, DEAL_SILVER,
+            visit.rival.map(|rival| format!(" The {} won't like it.", rival.display_name())).unwrap_or_default()
+        ),
+        VisitKind::Warning => format!(
+            "An emissary of the {} is waiting in your laboratory, unsmiling. The {} have not forgotten how you\n\
+             have treated them of late, and advise you to mend your ways.\n\
+             1) Promise to do better\n\
+             2) Show them the door",
+            faction, visit.faction.short_name()
+        ),
+        VisitKind::Sabotage => format!(
+            "Someone is in your laboratory: an agent of the {}, tools in hand and eyeing your equipment.\n\
+             1) Confront them\n\
+             2) Pay them {} silver to leave (you have {})\n\
+             3) Let them be",
+            faction, PAYOFF_SILVER, player.inventory.silver
+        ),
+    };
+    text.push_str("\n(Answer with 'emissary <number>'.)");
+    text
+}
+
+/// Break or take something from the laboratory, returning what was lost
+fn sabotage(lab: &mut Laboratory) -> String {
+    if let Some(item) = lab.storage.pop() {
+        return format!("The {} is gone from your storage.", item.properties.name);
+    }
+    let target = [Fixture::Bench, Fixture::GardenPlot, Fixture::LibraryShelf].into_iter()
+        .filter(|fixture| lab.level(*fixture) > 0)
+        .max_by_key(|fixture| lab.level(*fixture));
+    match target {
+        Some(fixture) => {
+            let level = lab.fixtures.entry(fixture).or_insert(0);
+            *level -= 1;
+            format!("Your {} is wrecked back to level {}.", fixture.name(), level)
+        }
+        None => "They find nothing worth breaking.".to_string(),
+    }
+}
+
+/// Send emissaries as they fall due, show one to a player who has come to
+/// hear them out, and send away those left waiting too long
+pub fn tick(player: &mut Player, world: &mut WorldState, faction_system: &FactionSystem) -> Option<String> {
+    if !player.laboratory.owned {
+        return None;
+    }
+    let now = world.game_time_minutes;
+    let at_lab = world.current_location == LAB_LOCATION;
+    let emissaries = &mut world.emissaries;
+
+    // Nobody waits forever; a saboteur doesn't need the player there at all
+    if let Some(visit) = emissaries.waiting.take_if(|visit| now >= visit.arrived_at + PATIENCE_MINUTES) {
+        let faction = visit.faction.display_name();
+        let (outcome, message) = match visit.kind {
+            VisitKind::Deal => (Outcome::Lapsed, format!("The emissary of the {} has given up waiting at your laboratory.", faction)),
+            VisitKind::Warning => {
+                player.modify_faction_reputation(visit.faction, -WARNING_STANDING * 2);
+                (Outcome::Defied, format!(
+                    "The emissary of the {} has left your laboratory, their warning unanswered. (-{} standing)",
+                    faction, WARNING_STANDING * 2
+                ))
+            }
+            VisitKind::Sabotage => (Outcome::Sabotaged, format!(
+                "An agent of the {} has been at your laboratory while you were away. {}",
+                faction, sabotage(&mut player.laboratory)
+            )),
+        };
+        emissaries.finish(&visit, outcome, player, now);
+        return Some(message);
+    }
+
+    let next_visit = *emissaries.next_visit.get_or_insert_with(|| {
+        emissaries.standings_seen = player.faction_standings.clone();
+        now + VISIT_INTERVAL
+    });
+    if emissaries.waiting.is_none() && now >= next_visit {
+        emissaries.next_visit = Some(now + VISIT_INTERVAL);
+        if let Some(visit) = emissaries.choose(player, faction_system, now) {
+            emissaries.waiting = Some(visit);
+            if !at_lab {
+                let visit = emissaries.waiting.as_ref()?;
+                return Some(format!(
+                    "Word reaches you that an emissary of the {} is waiting at your laboratory. They won't wait past tomorrow.",
+                    visit.faction.display_name()
+                ));
+            }
+        }
+    }
+
+    let visit = emissaries.waiting.as_mut().filter(|visit| at_lab && !visit.met)?;
+    visit.met = true;
+    Some(scene(visit, player))
+}
+
+/// Answer the emissary at the laboratory, or review past visits
+pub fn answer(choice: Option<usize>, player: &mut Player, world: &mut WorldState, rng: &mut impl Rng) -> GameResult<String> {
+    let now = world.game_time_minutes;
+    let at_lab = world.current_location == LAB_LOCATION;
+    let emissaries = &mut world.emissaries;
+
+    let Some(choice) = choice else {
+        if let Some(visit) = emissaries.waiting.as_mut() {
+            if at_lab {
+                visit.met = true;
+                return Ok(scene(visit, player));
+            }
+            return Ok(format!("An emissary of the {} is waiting at your laboratory.", visit.faction.display_name()));
+        }
+        if emissaries.history.is_empty() {
+            return Ok("No faction has sent anyone to your laboratory yet.".to_string());
+        }
+        let mut output = String::from("=== EMISSARIES ===\n");
+        for record in emissaries.history.iter().rev() {
+            output.push_str(&format!("• {}: the {} - {}\n", Date::at(record.at).describe(), record.faction.display_name(), record.outcome.describe()));
+        }
+        return Ok(output.trim_end().to_string());
+    };
+
+    let visit = match emissaries.waiting.as_ref() {
+        Some(visit) if at_lab => visit.clone(),
+        Some(_) => return Err(crate::GameError::InvalidCommand("The emissary is waiting at your laboratory; answer them there.".to_string()).into()),
+        None => return Err(crate::GameError::InvalidCommand("No emissary is waiting on you.".to_string()).into()),
+    };
+    let faction = visit.faction.display_name();
+    let (outcome, message) = match (visit.kind, choice) {
+        (VisitKind::Deal, 1) => {
+            player.inventory.silver += DEAL_SILVER;
+            player.modify_faction_reputation(visit.faction, DEAL_STANDING);
+            let mut message = format!(
+                "You shake on it. The {} pay {} silver for time at your bench. (+{} standing)",
+                faction, DEAL_SILVER, DEAL_STANDING
+            );
+            if let Some(rival) = visit.rival {
+                player.modify_faction_reputation(rival, -DEAL_STANDING / 2);
+                message.push_str(&format!(" Word soon reaches the {}. (-{} standing)", rival.display_name(), DEAL_STANDING / 2));
+            }
+            (Outcome::Accepted, message)
+        }
+        (VisitKind::Deal, 2) => (Outcome::Declined, format!("You turn the {} down politely, and their emissary takes their leave.", faction)),
+        (VisitKind::Warning, 1) => {
+            player.modify_faction_reputation(visit.faction, WARNING_STANDING);
+            (Outcome::Heeded, format!("The emissary seems satisfied, for now. (+{} standing with the {})", WARNING_STANDING, faction))
+        }
+        (VisitKind::Warning, 2) => {
+            player.modify_faction_reputation(visit.faction, -WARNING_STANDING * 2);
+            (Outcome::Defied, format!(
+                "You show the emissary out. The {} will remember it, and next time they won't just send words. (-{} standing)",
+                faction, WARNING_STANDING * 2
+            ))
+        }
+        (VisitKind::Sabotage, 1) => {
+            let chance = 0.25 + player.attributes.mental_acuity as f32 / 200.0;
+            if rng.gen::<f32>() < chance {
+                player.modify_faction_reputation(visit.faction, -DRIVEN_OFF_STANDING);
+                (Outcome::DrivenOff, format!(
+                    "You face the agent down and they flee empty-handed. The {} won't thank you for it. (-{} standing)",
+                    faction, DRIVEN_OFF_STANDING
+                ))
+            } else {
+                (Outcome::Sabotaged, format!("The agent shoves past you and gets to work before fleeing. {}", sabotage(&mut player.laboratory)))
+            }
+        }
+        (VisitKind::Sabotage, 2) => {
+            if player.inventory.silver < PAYOFF_SILVER {
+                return Err(crate::GameError::InsufficientResources(format!(
+                    "The agent wants {} silver; you have {}.", PAYOFF_SILVER, player.inventory.silver
+                )).into());
+            }
+            player.inventory.silver -= PAYOFF_SILVER;
+            (Outcome::PaidOff, format!("The agent pockets {} silver and leaves your laboratory untouched.", PAYOFF_SILVER))
+        }
+        (VisitKind::Sabotage, 3) => (Outcome::Sabotaged, format!("You stand back and let the agent work. {}", sabotage(&mut player.laboratory))),
+        _ => return Err(crate::GameError::InvalidInput("That isn't one of the choices; use 'emissary' to hear them again.".to_string()).into()),
+    };
+    emissaries.finish(&visit, outcome, player, now);
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::items::core::{Item, ItemType};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn owner() -> (Player, WorldState, FactionSystem) {
+        let mut player = Player::new("Ada".to_string());
+        player.laboratory.owned = true;
+        player.laboratory.fixtures.insert(Fixture::Storage, 1);
+        player.laboratory.fixtures.insert(Fixture::Bench, 1);
+        let mut world = WorldState::new();
+        world.current_location = LAB_LOCATION.to_string();
+        (player, world, FactionSystem::new())
+    }
+
+    /// Let the next emissary fall due and arrive
+    fn next_visit(player: &mut Player, world: &mut WorldState, factions: &FactionSystem) -> Option<String> {
+        world.game_time_minutes += VISIT_INTERVAL;
+        tick(player, world, factions)
+    }
+
+    #[test]
+    fn test_emissaries_follow_the_player_standings() {
+        let (mut player, mut world, factions) = owner();
+        assert!(tick(&mut player, &mut world, &factions).is_none());
+        // Nothing has changed, so nobody comes
+        assert!(next_visit(&mut player, &mut world, &factions).is_none());
+
+        player.modify_faction_reputation(FactionId::IndustrialConsortium, 20);
+        player.modify_faction_reputation(FactionId::OrderOfHarmony, -5);
+        let scene = next_visit(&mut player, &mut world, &factions).unwrap();
+        assert!(scene.contains("Industrial Consortium") && scene.contains("1) Accept"), "{}", scene);
+        let visit = world.emissaries.waiting().unwrap();
+        assert_eq!((visit.kind, visit.met), (VisitKind::Deal, true));
+
+        let silver = player.inventory.silver;
+        let mut rng = StdRng::seed_from_u64(1);
+        answer(Some(1), &mut player, &mut world, &mut rng).unwrap();
+        assert_eq!(player.inventory.silver, silver + DEAL_SILVER);
+        assert_eq!(player.faction_reputation(FactionId::IndustrialConsortium), 20 + DEAL_STANDING);
+        assert!(world.emissaries.waiting().is_none());
+
+        // The smaller slight to the Order is still remembered
+        let scene = next_visit(&mut player, &mut world, &factions).unwrap();
+        assert!(scene.contains("Order of Natural Harmony") && scene.contains("mend your ways"), "{}", scene);
+    }
+
+    #[test]
+    fn test_a_defied_warning_brings_a_saboteur() {
+        let (mut player, mut world, factions) = owner();
+        let mut rng = StdRng::seed_from_u64(1);
+        tick(&mut player, &mut world, &factions);
+        player.modify_faction_reputation(FactionId::MagistersCouncil, -10);
+        next_visit(&mut player, &mut world, &factions).unwrap();
+        assert!(answer(Some(3), &mut player, &mut world, &mut rng).is_err());
+        answer(Some(2), &mut player, &mut world, &mut rng).unwrap();
+        assert_eq!(world.emissaries.history()[0].outcome, Outcome::Defied);
+
+        player.modify_faction_reputation(FactionId::MagistersCouncil, -1);
+        let scene = next_visit(&mut player, &mut world, &factions).unwrap();
+        assert!(scene.contains("agent of the Magisters' Council"), "{}", scene);
+
+        let stone = Item::new_basic("River Stone".to_string(), "A stone.".to_string(), ItemType::Mundane);
+        player.laboratory.storage.push(stone);
+        let message = answer(Some(3), &mut player, &mut world, &mut rng).unwrap();
+        assert!(message.contains("River Stone is gone"), "{}", message);
+        assert!(player.laboratory.storage.is_empty());
+    }
+
+    #[test]
+    fn test_emissaries_left_waiting_go_away() {
+        let (mut player, mut world, factions) = owner();
+        world.current_location = "practice_hall".to_string();
+        tick(&mut player, &mut world, &factions);
+        player.modify_faction_reputation(FactionId::UndergroundNetwork, -40);
+
+        let word = next_visit(&mut player, &mut world, &factions).unwrap();
+        assert!(word.contains("waiting at your laboratory"), "{}", word);
+        let mut rng = StdRng::seed_from_u64(1);
+        assert!(answer(Some(1), &mut player, &mut world, &mut rng).is_err());
+
+        world.game_time_minutes += PATIENCE_MINUTES;
+        let message = tick(&mut player, &mut world, &factions).unwrap();
+        assert!(message.contains("while you were away") && message.contains("experiment bench"), "{}", message);
+        assert_eq!(player.laboratory.level(Fixture::Bench), 0);
+        assert_eq!(world.emissaries.history()[0].outcome, Outcome::Sabotaged);
+    }
+
+    #[test]
+    fn test_no_emissaries_without_a_laboratory() {
+        let (mut player, mut world, factions) = owner();
+        player.laboratory.owned = false;
+        player.modify_faction_reputation(FactionId::NeutralScholars, 30);
+        assert!(next_visit(&mut player, &mut world, &factions).is_none());
+        assert!(next_visit(&mut player, &mut world, &factions).is_none());
+    }
+}
@@ -0,0 +1,249 @@
+//! Encumbrance and hired porters
+//!
+//! The inventory's weight limit is a hard cap, but a pack gets harder to
+//! carry well before it is full. The share of capacity in use sets a tier:
+//! - Unburdened, up to half capacity: no penalty
+//! - Burdened, up to three quarters: travel takes longer, magic tires a little more
+//! - Heavy, up to nine tenths: slower still, and travel itself is tiring
+//! - Overloaded, above that: every step is a struggle
+//!
+//! Porters wait for work at busy halls. A hired porter carries extra weight,
+//! raising the player's capacity for as many days as were paid for, and
+//! leaves when the contract runs out, handing everything back.
+
+use crate::core::Player;
+use serde::{Deserialize, Serialize};
+
+/// Weight a porter carries, in kilograms
+pub const PORTER_CAPACITY: f32 = 25.0;
+/// Silver per day of a porter's time
+pub const PORTER_DAILY_FEE: i32 = 15;
+/// Longest contract a porter will take, in days
+pub const MAX_PORTER_DAYS: i32 = 7;
+/// Locations where porters can be hired
+pub const PORTER_LOCATIONS: [&str; 2] = ["practice_hall", "faction_diplomacy_hall"];
+const MINUTES_PER_DAY: i32 = 1440;
+
+/// How weighed down the player is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Encumbrance {
+    Unburdened,
+    Burdened,
+    Heavy,
+    Overloaded,
+}
+
+impl Encumbrance {
+    /// Tier for a load as a share of capacity
+    pub fn from_load(share: f32) -> Self {
+        match share {
+            share if share <= 0.5 => Encumbrance::Unburdened,
+            share if share <= 0.75 => Encumbrance::Burdened,
+            share if share <= 0.9 => Encumbrance::Heavy,
+            _ => Encumbrance::Overloaded,
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encumbrance::Unburdened => "Unburdened",
+            Encumbrance::Burdened => "Burdened",
+            Encumbrance::Heavy => "Heavy",
+            Encumbrance::Overloaded => "Overloaded",
+        }
+    }
+
+    /// Multiplier on fatigue gained from magic and exertion
+    pub fn fatigue_multiplier(&self) -> f32 {
+        match self {
+            Encumbrance::Unburdened => 1.0,
+            Encumbrance::Burdened => 1.1,
+            Encumbrance::Heavy => 1.25,
+            Encumbrance::Overloaded => 1.5,
+        }
+    }
+
+    /// Minutes a move between locations takes
+    pub fn travel_minutes(&self) -> i32 {
+        match self {
+            Encumbrance::Unburdened => 1,
+            Encumbrance::Burdened => 2,
+            Encumbrance::Heavy => 3,
+            Encumbrance::Overloaded => 5,
+        }
+    }
+
+    /// Fatigue gained from a move between locations
+    pub fn travel_fatigue(&self) -> i32 {
+        match self {
+            Encumbrance::Unburdened | Encumbrance::Burdened => 0,
+            Encumbrance::Heavy => 1,
+            Encumbrance::Overloaded => 3,
+        }
+    }
+}
+
+/// Carried weight and capacity in kilograms
+pub fn load(player: &Player) -> (f32, f32) {
+    let empty = (0.0, crate::systems::items::InventoryConstraints::default().max_weight);
+    player.inventory.enhanced_items.as_ref().map_or(empty, |items| {
+        let manager = &items.inventory_manager;
+        // Summing no weights at all gives -0.0, which shows as "-0.0 kg"
+        let weight = manager.current_weight();
+        (if weight > 0.0 { weight } else { 0.0 }, manager.constraints.max_weight)
+    })
+}
+
+/// The player's encumbrance tier
+pub fn encumbrance(player: &Player) -> Encumbrance {
+    match load(player) {
+        (_, capacity) if capacity <= 0.0 => Encumbrance::Unburdened,
+        (weight, capacity) => Encumbrance::from_load(weight / capacity),
+    }
+}
+
+/// Status line describing the player's load
+pub fn describe_load(player: &Player) -> String {
+    let (weight, capacity) = load(player);
+    let mut line = format!("{:.1}/{:.1} kg ({})", weight, capacity, encumbrance(player).name());
+    if let Some(porter) = &player.porter {
+        line.push_str(&format!(", {} carrying {:.0} kg", porter.name, porter.capacity));
+    }
+    line
+}
+
+/// A porter under contract
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Porter {
+    pub name: String,
+    /// Weight they carry, added to the player's capacity
+    pub capacity: f32,
+    /// Game time (minutes) the contract ends
+    pub until: i32,
+}
+
+impl Porter {
+    /// Days, rounded up, left on the contract
+    pub fn days_left(&self, now: i32) -> i32 {
+        ((self.until - now).max(0) + MINUTES_PER_DAY - 1) / MINUTES_PER_DAY
+    }
+}
+
+/// Change the player's carrying capacity
+fn adjust_capacity(player: &mut Player, amount: f32) {
+    player.ensure_enhanced_item_system();
+    if let Some(items) = player.inventory.enhanced_items.as_mut() {
+        items.inventory_manager.constraints.max_weight += amount;
+    }
+}
+
+/// Hire a porter, or extend the current one's contract, returning a description
+pub fn hire(player: &mut Player, days: i32, location: &str, now: i32) -> crate::GameResult<String> {
+    if !PORTER_LOCATIONS.contains(&location) {
+        return Err(crate::GameError::InvalidCommand(
+            "There are no porters looking for work here. Try the Practice Hall or the Faction Diplomacy Hall.".to_string()
+        ).into());
+    }
+    if !(1..=MAX_PORTER_DAYS).contains(&days) {
+        return Err(crate::GameError::InvalidInput(format!("Porters take contracts of 1 to {} days.", MAX_PORTER_DAYS)).into());
+    }
+    let fee = days * PORTER_DAILY_FEE;
+    if player.inventory.silver < fee {
+        return Err(crate::GameError::InsufficientResources(format!(
+            "A porter asks {} silver for {} day{}. You have {}.", fee, days, if days == 1 { "" } else { "s" }, player.inventory.silver
+        )).into());
+    }
+    player.inventory.silver -= fee;
+
+    let extension = days * MINUTES_PER_DAY;
+    Ok(match player.porter.as_mut() {
+        Some(porter) => {
+            porter.until = porter.until.max(now) + extension;
+            format!("You pay {} {} silver to stay on. {} days left on the contract.", porter.name, fee, porter.days_left(now))
+        }
+        None => {
+            let names = ["Bram", "Tilde", "Osric", "Wenna"];
+            let name = names[(now / MINUTES_PER_DAY).unsigned_abs() as usize % names.len()].to_string();
+            let message = format!(
+                "You pay {} silver and {} shoulders a frame pack, ready to carry {:.0} kg for {} day{}.",
+                fee, name, PORTER_CAPACITY, days, if days == 1 { "" } else { "s" }
+            );
+            player.porter = Some(Porter { name, capacity: PORTER_CAPACITY, until: now + extension });
+            adjust_capacity(player, PORTER_CAPACITY);
+            message
+        }
+    })
+}
+
+/// Send the porter away, returning what happened
+fn release(player: &mut Player, reason: &str) -> Option<String> {
+    let porter = player.porter.take()?;
+    adjust_capacity(player, -porter.capacity);
+    let mut message = format!("{} {} and hands back everything they were carrying.", porter.name, reason);
+    if encumbrance(player) == Encumbrance::Overloaded {
+        message.push_str(" Your pack is overloaded.");
+    }
+    Some(message)
+}
+
+/// Dismiss the porter before their contract ends; paid days are not refunded
+pub fn dismiss(player: &mut Player) -> crate::GameResult<String> {
+    release(player, "takes their leave")
+        .ok_or_else(|| crate::GameError::InvalidCommand("You haven't hired a porter.".to_string()).into())
+}
+
+/// Let the porter go once their contract runs out
+pub fn expire(player: &mut Player, now: i32) -> Option<String> {
+    if player.porter.as_ref().is_some_and(|porter| porter.until <= now) {
+        release(player, "has worked out their contract")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::items::{Item, ItemType};
+
+    #[test]
+    fn test_load_sets_encumbrance_and_porters_carry_more() {
+        let mut player = Player::new("Ada".to_string());
+        player.ensure_enhanced_item_system();
+        let (_, capacity) = load(&player);
+        assert_eq!(encumbrance(&player), Encumbrance::Unburdened);
+
+        let mut anvil = Item::new_basic("Tuning Anvil".to_string(), "Heavy.".to_string(), ItemType::Mundane);
+        anvil.properties.weight = capacity * 0.8;
+        player.add_enhanced_item(anvil).unwrap();
+        assert_eq!(encumbrance(&player), Encumbrance::Heavy);
+        assert!(Encumbrance::Heavy.travel_minutes() > Encumbrance::Unburdened.travel_minutes());
+
+        player.inventory.silver = 0;
+        assert!(hire(&mut player, 2, "crystal_garden_lab", 0).is_err());
+        assert!(hire(&mut player, 2, "practice_hall", 0).is_err()); // no silver
+        player.inventory.silver = 100;
+        hire(&mut player, 2, "practice_hall", 0).unwrap();
+        assert_eq!(player.inventory.silver, 70);
+        assert_eq!(load(&player).1, capacity + PORTER_CAPACITY);
+        assert_eq!(encumbrance(&player), Encumbrance::Burdened);
+        assert!(describe_load(&player).contains("carrying 25 kg"));
+
+        hire(&mut player, 1, "practice_hall", 100).unwrap();
+        assert_eq!(player.porter.as_ref().unwrap().days_left(100), 3);
+        assert!(expire(&mut player, 3 * 1440 - 1).is_none());
+        assert!(expire(&mut player, 3 * 1440).is_some());
+        assert!(player.porter.is_none());
+        assert_eq!(load(&player).1, capacity);
+        assert!(dismiss(&mut player).is_err());
+    }
+
+    #[test]
+    fn test_an_empty_pack_weighs_nothing() {
+        let mut player = Player::new("Ada".to_string());
+        assert!(describe_load(&player).starts_with("0.0/"), "{}", describe_load(&player));
+        player.ensure_enhanced_item_system();
+        assert!(describe_load(&player).starts_with("0.0/"), "{}", describe_load(&player));
+    }
+}
@@ -0,0 +1,290 @@
+//! Epilogues and the endings gallery
+//!
+//! Some quests are story milestones. When one is resolved, completed or
+//! failed, the player's accumulated choices are weighed: their standing with
+//! each faction, how the major quests turned out, and how many theories they
+//! have mastered. The ending those choices earn is told as an epilogue,
+//! followed by what became of the threads the player pulled on. Each
+//! milestone is told once per save, and play carries on afterwards.
+//!
+//! Every ending reached is unlocked in a gallery kept beside the save files,
+//! so it is shared by every character. Locked endings show only a hint at
+//! what earns them.
+
+use crate::core::Player;
+use crate::systems::factions::{FactionId, FactionSystem};
+use crate::systems::quests::{QuestStatus, QuestSystem};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Gallery file kept in the save directory
+pub const GALLERY_FILE: &str = "endings.json";
+/// Quests whose resolution is a story milestone
+pub const MILESTONES: [&str; 2] = ["diplomatic_balance", "unstable_site_investigation"];
+/// Prefix of the world flag recording that a milestone's epilogue was told
+const TOLD_FLAG_PREFIX: &str = "epilogue_told:";
+/// Standing with a faction that ties the player's story to it
+const ALLEGIANCE_STANDING: i32 = 50;
+/// Standing below which a faction counts as alienated
+const ALIENATED_STANDING: i32 = -20;
+/// Mastered theories that make the player's story one of scholarship
+const MASTERY_THEORIES: usize = 5;
+
+/// An ending the player's choices can earn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ending {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub narrative: &'static str,
+    /// What the gallery says of the ending while it is locked
+    pub hint: &'static str,
+}
+
+/// Every ending, in the order the gallery lists them
+pub const ENDINGS: [Ending; 8] = [
+    Ending {
+        id: "mediator",
+        title: "The Mediator",
+        narrative: "The accord you brokered outlives the arguments that nearly sank it. Council wardens and \
+                    Underground runners still don't like each other, but they sit at the same table now, and \
+                    when they can't agree they send for you.",
+        hint: "Bring the factions to terms without turning any of them against you.",
+    },
+    Ending {
+        id: "council_archmage",
+        title: "Archmage of the Council",
+        narrative: "The Magisters' Council takes you into its inner circle. Your name goes on the regulations \
+                    that govern every licensed practitioner in the city, and the arrays in the Observatory \
+                    answer to your signature.",
+        hint: "Rise high in the Magisters' Council.",
+    },
+    Ending {
+        id: "underground_voice",
+        title: "Voice of the Underground",
+        narrative: "The Underground Network stops whispering your name and starts repeating it. Forbidden \
+                    techniques pass through your hands into the open, and the Council's licences matter a \
+                    little less every season.",
+        hint: "Earn the Underground Network's trust.",
+    },
+    Ending {
+        id: "harmony_keeper",
+        title: "Keeper of the Garden",
+        narrative: "The Order of Natural Harmony entrusts you with the Crystal Garden. Healing frequencies \
+                    you helped refine spread to every infirmary in the city, and the plants still lean toward \
+                    you when you pass.",
+        hint: "Earn the Order of Natural Harmony's devotion.",
+    },
+    Ending {
+        id: "consortium_magnate",
+        title: "Consortium Magnate",
+        narrative: "The Industrial Consortium builds a workshop in your name. Resonance engines built to your \
+                    tolerances light whole districts, and every one of them pays you a share.",
+        hint: "Become indispensable to the Industrial Consortium.",
+    },
+    Ending {
+        id: "archivist",
+        title: "Heir to the Archives",
+        narrative: "The Neutral Scholars give you a key to the deepest vaults of the Crystalline Archives. \
+                    What you record there will be read long after the factions you served are forgotten.",
+        hint: "Win the Neutral Scholars' confidence.",
+    },
+    Ending {
+        id: "theorist",
+        title: "The Theorist",
+        narrative: "You never belonged to any faction, only to the work. Students argue over your proofs, \
+                    and somewhere in the city a lecture hall is already being named for you.",
+        hint: "Master a wide range of theories.",
+    },
+    Ending {
+        id: "wanderer",
+        title: "The Wanderer",
+        narrative: "No faction claims you and no theory defines you. The city goes on much as it was, and you \
+                    go on through it, still listening for the resonance that first caught your ear.",
+        hint: "Walk your own path.",
+    },
+];
+
+/// The ending the player's choices earn so far
+pub fn earned(quest_system: &QuestSystem, player: &Player, faction_system: &FactionSystem) -> &'static Ending {
+    let ending = |id: &str| ENDINGS.iter().find(|ending| ending.id == id).expect("ending is defined");
+    let flags = &quest_system.global_state.flags;
+
+    if flags.is_set("accord_signed") && FactionId::all().iter().all(|faction| faction_system.get_reputation(*faction) > ALIENATED_STANDING) {
+        return ending("mediator");
+    }
+
+    let allegiance = FactionId::all().into_iter()
+        .map(|faction| (faction, faction_system.get_reputation(faction)))
+        .filter(|(_, standing)| *standing >= ALLEGIANCE_STANDING)
+        .max_by_key(|(_, standing)| *standing);
+    if let Some((faction, _)) = allegiance {
+        return ending(match faction {
+            FactionId::MagistersCouncil => "council_archmage",
+            FactionId::UndergroundNetwork => "underground_voice",
+            FactionId::OrderOfHarmony => "harmony_keeper",
+            FactionId::IndustrialConsortium => "consortium_magnate",
+            FactionId::NeutralScholars => "archivist",
+        });
+    }
+
+    if player.get_mastered_theories().len() >= MASTERY_THEORIES {
+        return ending("theorist");
+    }
+    ending("wanderer")
+}
+
+/// What became of the threads the player pulled on
+fn threads(quest_system: &QuestSystem, player: &Player) -> Vec<String> {
+    let flags = &quest_system.global_state.flags;
+    let status = |quest_id: &str| quest_system.player_progress.get(quest_id).map(|progress| progress.status.clone());
+    let mut threads = Vec::new();
+
+    if flags.is_set("accord_signed") {
+        threads.push("The accord between the Council and the Underground holds, framed beside the door of the Diplomacy Hall.".to_string());
+    } else if flags.is_set("talks_collapsed") {
+        threads.push("The failed negotiation is still spoken of bitterly in both camps.".to_string());
+    }
+    match status("healing_research") {
+        Some(QuestStatus::Completed) => threads.push("The healing research you carried out in the Garden is taught to every new infirmary apprentice.".to_string()),
+        Some(QuestStatus::Failed) => threads.push("The Garden's healing research stalled without you, and Seraphina still hopes to take it up again.".to_string()),
+        _ => {}
+    }
+    match status("unstable_site_investigation") {
+        Some(QuestStatus::Completed) => threads.push("The Unstable Site is quiet now, its resonance understood rather than feared.".to_string()),
+        Some(QuestStatus::Failed) => threads.push("The Unstable Site still hums behind its wards, a question nobody has answered.".to_string()),
+        _ => {}
+    }
+    let mastered = player.get_mastered_theories().len();
+    if mastered > 0 {
+        threads.push(format!("You mastered {} theor{} along the way.", mastered, if mastered == 1 { "y" } else { "ies" }));
+    }
+    threads
+}
+
+/// Tell the epilogue for a milestone resolved since the last command, once
+/// per save, unlocking its ending in the gallery kept in `directory`
+pub fn at_milestone(quest_system: &mut QuestSystem, player: &Player, faction_system: &FactionSystem, directory: &Path) -> Option<String> {
+    let milestone = MILESTONES.iter().find(|quest_id| {
+        quest_system.player_progress.get(**quest_id)
+            .is_some_and(|progress| matches!(progress.status, QuestStatus::Completed | QuestStatus::Failed))
+            && !quest_system.global_state.flags.is_set(&format!("{}{}", TOLD_FLAG_PREFIX, quest_id))
+    })?;
+    quest_system.global_state.flags.set(&format!("{}{}", TOLD_FLAG_PREFIX, milestone));
+
+    let ending = earned(quest_system, player, faction_system);
+    let mut epilogue = format!("=== EPILOGUE: {} ===\n\n{}\n", ending.title, ending.narrative);
+    for thread in threads(quest_system, player) {
+        epilogue.push_str(&format!("\n• {}", thread));
+    }
+
+    // A gallery that can't be written shouldn't spoil the moment
+    let mut gallery = EndingsGallery::load(directory).unwrap_or_default();
+    if gallery.unlock(ending.id, &player.name) {
+        let _ = gallery.save(directory);
+        epilogue.push_str(&format!(
+            "\n\nEnding unlocked: {} ({}/{} in the gallery; see 'endings').",
+            ending.title, gallery.unlocked.len(), ENDINGS.len()
+        ));
+    }
+    epilogue.push_str("\n\nThe story isn't over. You can play on.");
+    Some(epilogue)
+}
+
+/// The endings reached across saves, by ending ID, with the character who
+/// first reached each
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EndingsGallery {
+    pub unlocked: BTreeMap<String, String>,
+}
+
+impl EndingsGallery {
+    /// Load the gallery from a directory, empty if none has been written yet
+    pub fn load(directory: &Path) -> crate::GameResult<Self> {
+        let path = directory.join(GALLERY_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to read endings gallery: {}", e)))?;
+        serde_json::from_str(&json)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to parse endings gallery: {}", e)).into())
+    }
+
+    /// Write the gallery into a directory
+    pub fn save(&self, directory: &Path) -> crate::GameResult<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to serialize endings gallery: {}", e)))?;
+        std::fs::write(directory.join(GALLERY_FILE), json)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to write endings gallery: {}", e)).into())
+    }
+
+    /// Unlock an ending; returns whether it was locked before
+    pub fn unlock(&mut self, ending_id: &str, character: &str) -> bool {
+        if self.unlocked.contains_key(ending_id) {
+            return false;
+        }
+        self.unlocked.insert(ending_id.to_string(), character.to_string());
+        true
+    }
+
+    /// Every ending, unlocked ones by title and locked ones by hint
+    pub fn describe(&self) -> String {
+        let mut response = format!("=== ENDINGS GALLERY ({}/{}) ===\n\n", self.unlocked.len(), ENDINGS.len());
+        for ending in &ENDINGS {
+            match self.unlocked.get(ending.id) {
+                Some(character) => response.push_str(&format!("✓ {} (first reached by {})\n", ending.title, character)),
+                None => response.push_str(&format!("? ??? - {}\n", ending.hint)),
+            }
+        }
+        response.push_str("\nEndings are told when you reach a turning point in the story.");
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::quest_examples::create_example_quests;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_milestone_tells_the_earned_ending_once_and_unlocks_it() {
+        let directory = TempDir::new().unwrap();
+        let mut quest_system = QuestSystem::new();
+        let quests = create_example_quests();
+        let open_requirements = quests.iter().find(|quest| quest.id == "resonance_foundation").unwrap().requirements.clone();
+        for mut quest in quests {
+            if quest.id == "diplomatic_balance" {
+                quest.requirements = open_requirements.clone();
+            }
+            quest_system.add_quest_definition(quest);
+        }
+        let mut player = Player::new("Ada".to_string());
+        player.current_location = "practice_hall".to_string();
+        let mut faction_system = FactionSystem::new();
+        assert_eq!(earned(&quest_system, &player, &faction_system).id, "wanderer");
+
+        faction_system.modify_reputation(FactionId::UndergroundNetwork, 60);
+        assert_eq!(earned(&quest_system, &player, &faction_system).id, "underground_voice");
+        quest_system.global_state.flags.set("accord_signed");
+        assert_eq!(earned(&quest_system, &player, &faction_system).id, "mediator");
+
+        assert!(at_milestone(&mut quest_system, &player, &faction_system, directory.path()).is_none());
+        quest_system.start_quest("diplomatic_balance", &player, &faction_system).unwrap();
+        assert!(at_milestone(&mut quest_system, &player, &faction_system, directory.path()).is_none());
+        quest_system.fail_quest("diplomatic_balance").unwrap();
+
+        let epilogue = at_milestone(&mut quest_system, &player, &faction_system, directory.path()).unwrap();
+        assert!(epilogue.starts_with("=== EPILOGUE: The Mediator ==="));
+        assert!(epilogue.contains("The accord between the Council and the Underground holds"));
+        assert!(epilogue.contains("Ending unlocked: The Mediator (1/8"));
+        assert!(at_milestone(&mut quest_system, &player, &faction_system, directory.path()).is_none());
+
+        let gallery = EndingsGallery::load(directory.path()).unwrap();
+        assert_eq!(gallery.unlocked.get("mediator").map(String::as_str), Some("Ada"));
+        let listing = gallery.describe();
+        assert!(listing.contains("✓ The Mediator (first reached by Ada)"));
+        assert!(listing.contains("? ??? - Master a wide range of theories."));
+    }
+}
@@ -0,0 +1,361 @@
+//! Enemy decision making and difficulty scaling
+//!
+//! Each AI profile is its own `EnemyAi` implementation that looks at the
+//! state of the fight and picks one action for the enemy's turn. Profiles
+//! range from plain temperaments (balanced, aggressive, cautious, relentless)
+//! to personalities with tricks of their own: berserkers grow more dangerous
+//! as they bleed, tacticians wait out defences and punish a drained caster,
+//! and spell-thieves siphon the player's mental energy to mend themselves.
+//!
+//! The run's difficulty scales enemy health, damage and rewards when a fight
+//! starts, and sets how often an enemy thinks to use its tricks at all.
+
+use crate::core::Player;
+use crate::systems::combat::{AiProfile, DifficultyTier, Enemy};
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+/// Energy below which the player counts as drained
+const DRAINED_ENERGY: i32 = 30;
+/// Chance that an enemy trying to flee gets away
+const FLEE_CHANCE: f32 = 0.5;
+
+/// How hard the run's fights are
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// Weaker, simpler enemies for players here for the story
+    Story,
+    #[default]
+    Normal,
+    /// Tougher enemies that use their tricks most of the time
+    Hard,
+    /// Enemies at their strongest, never missing an opening
+    Nightmare,
+}
+
+impl Difficulty {
+    pub fn all() -> [Difficulty; 4] {
+        [Difficulty::Story, Difficulty::Normal, Difficulty::Hard, Difficulty::Nightmare]
+    }
+
+    /// Parse a difficulty name
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "story" | "easy" => Some(Difficulty::Story),
+            "normal" | "standard" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            "nightmare" => Some(Difficulty::Nightmare),
+            _ => None,
+        }
+    }
+
+    /// Display name of the difficulty
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Story => "story",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Nightmare => "nightmare",
+        }
+    }
+
+    /// Short description for players choosing a difficulty
+    pub fn description(&self) -> &'static str {
+        match self {
+            Difficulty::Story => "Fragile, predictable enemies; fights rarely get in the way",
+            Difficulty::Normal => "Enemies as designed, using their tricks some of the time",
+            Difficulty::Hard => "Tougher enemies that exploit most openings",
+            Difficulty::Nightmare => "Enemies at full strength that never miss an opening",
+        }
+    }
+
+    /// Multiplier on enemy health and experience
+    pub fn health_multiplier(&self) -> f32 {
+        match self {
+            Difficulty::Story => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.25,
+            Difficulty::Nightmare => 1.5,
+        }
+    }
+
+    /// Multiplier on enemy attack damage
+    pub fn damage_multiplier(&self) -> f32 {
+        match self {
+            Difficulty::Story => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.15,
+            Difficulty::Nightmare => 1.3,
+        }
+    }
+
+    /// Chance that an enemy uses its profile's tricks on a given turn
+    pub fn sophistication(&self) -> f32 {
+        match self {
+            Difficulty::Story => 0.25,
+            Difficulty::Normal => 0.6,
+            Difficulty::Hard => 0.85,
+            Difficulty::Nightmare => 1.0,
+        }
+    }
+
+    /// Scale a freshly spawned enemy's stats
+    pub fn scale(&self, enemy: &mut Enemy) {
+        let multiplier = self.health_multiplier();
+        if multiplier == 1.0 {
+            return;
+        }
+        let scale = |value: i32| ((value as f32 * multiplier).round() as i32).max(1);
+        enemy.max_health = scale(enemy.max_health);
+        enemy.health = scale(enemy.health).min(enemy.max_health);
+        enemy.experience_reward = scale(enemy.experience_reward);
+    }
+
+    /// Explanation line for the combat log
+    pub fn explain(&self) -> String {
+        format!(
+            "Difficulty {}: enemy health x{:.2}, damage x{:.2}, tricks {:.0}% of turns",
+            self.name(), self.health_multiplier(), self.damage_multiplier(), self.sophistication() * 100.0
+        )
+    }
+}
+
+/// What an enemy does on its turn
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnemyAction {
+    /// Attack, scaling the damage; `intent` narrates anything unusual
+    Attack { multiplier: f32, intent: Option<String> },
+    /// Try to escape the fight
+    Flee { chance: f32 },
+    /// Hold back this turn
+    Wait(String),
+    /// Drain the player's mental energy, healing by the amount taken
+    Siphon { amount: i32 },
+}
+
+impl EnemyAction {
+    fn attack() -> Self {
+        EnemyAction::Attack { multiplier: 1.0, intent: None }
+    }
+
+    fn attack_with(multiplier: f32, intent: &str) -> Self {
+        EnemyAction::Attack { multiplier, intent: Some(intent.to_string()) }
+    }
+}
+
+/// What an enemy can see when deciding
+pub struct AiContext<'a> {
+    pub enemy: &'a Enemy,
+    pub player: &'a Player,
+    pub player_defending: bool,
+    pub turn: i32,
+    pub difficulty: Difficulty,
+}
+
+impl AiContext<'_> {
+    fn player_drained(&self) -> bool {
+        self.player.mental_state.current_energy < DRAINED_ENERGY
+    }
+
+    fn wounded_below(&self, threshold: f32) -> bool {
+        self.enemy.health_percentage() < threshold
+    }
+
+    /// Whether the enemy thinks to use a trick this turn
+    fn clever(&self, rng: &mut dyn RngCore) -> bool {
+        rng.gen::<f32>() < self.difficulty.sophistication()
+    }
+}
+
+/// A way of choosing enemy actions
+pub trait EnemyAi {
+    fn decide(&self, context: &AiContext, rng: &mut dyn RngCore) -> EnemyAction;
+}
+
+/// Fights steadily, presses a drained caster and retreats when badly hurt
+struct Balanced;
+
+impl EnemyAi for Balanced {
+    fn decide(&self, context: &AiContext, rng: &mut dyn RngCore) -> EnemyAction {
+        if context.wounded_below(0.3) {
+            return EnemyAction::Flee { chance: FLEE_CHANCE };
+        }
+        if context.player_drained() && context.clever(rng) {
+            return EnemyAction::attack_with(1.15, "presses the advantage while your focus is spent");
+        }
+        EnemyAction::attack()
+    }
+}
+
+/// Hits hard and only breaks off when nearly beaten
+struct Aggressive;
+
+impl EnemyAi for Aggressive {
+    fn decide(&self, context: &AiContext, _rng: &mut dyn RngCore) -> EnemyAction {
+        if context.wounded_below(0.15) {
+            return EnemyAction::Flee { chance: FLEE_CHANCE };
+        }
+        EnemyAction::attack()
+    }
+}
+
+/// Keeps its distance from a raised guard and leaves early
+struct Cautious;
+
+impl EnemyAi for Cautious {
+    fn decide(&self, context: &AiContext, rng: &mut dyn RngCore) -> EnemyAction {
+        if context.wounded_below(0.5) {
+            return EnemyAction::Flee { chance: FLEE_CHANCE };
+        }
+        if context.player_defending && context.clever(rng) {
+            return EnemyAction::Wait(format!("{} keeps its distance, unwilling to strike into your guard.", context.enemy.name));
+        }
+        EnemyAction::attack()
+    }
+}
+
+/// Never retreats
+struct Relentless;
+
+impl EnemyAi for Relentless {
+    fn decide(&self, _context: &AiContext, _rng: &mut dyn RngCore) -> EnemyAction {
+        EnemyAction::attack()
+    }
+}
+
+/// Never retreats and grows wilder the more it is hurt
+struct Berserk;
+
+impl EnemyAi for Berserk {
+    fn decide(&self, context: &AiContext, _rng: &mut dyn RngCore) -> EnemyAction {
+        let wounds = 1.0 - context.enemy.health_percentage();
+        if wounds >= 0.5 {
+            EnemyAction::attack_with(1.0 + 0.6 * wounds, "lashes out in a blood-mad frenzy")
+        } else {
+            EnemyAction::attack()
+        }
+    }
+}
+
+/// Waits out defences and strikes when the player is weakest
+struct Tactician;
+
+impl EnemyAi for Tactician {
+    fn decide(&self, context: &AiContext, rng: &mut dyn RngCore) -> EnemyAction {
+        if context.wounded_below(0.3) {
+            return EnemyAction::Flee { chance: FLEE_CHANCE };
+        }
+        if !context.clever(rng) {
+            return EnemyAction::attack();
+        }
+        if context.player_defending {
+            return EnemyAction::Wait(format!("{} circles, studying your defence instead of striking into it.", context.enemy.name));
+        }
+        if context.player_drained() {
+            return EnemyAction::attack_with(1.3, "strikes precisely where your exhausted focus is thinnest");
+        }
+        if context.turn % 3 == 0 {
+            return EnemyAction::attack_with(1.2, "feints and finds an opening");
+        }
+        EnemyAction::attack()
+    }
+}
+
+/// Steals the player's mental energy to mend its own wounds
+struct SpellThief;
+
+impl EnemyAi for SpellThief {
+    fn decide(&self, context: &AiContext, rng: &mut dyn RngCore) -> EnemyAction {
+        if context.wounded_below(0.25) {
+            return EnemyAction::Flee { chance: FLEE_CHANCE };
+        }
+        if context.player.mental_state.current_energy > 0 && context.clever(rng) {
+            let amount = match context.enemy.difficulty_tier {
+                DifficultyTier::Beginner => 8,
+                DifficultyTier::Intermediate => 12,
+                DifficultyTier::Advanced => 16,
+                DifficultyTier::Boss => 20,
+            };
+            return EnemyAction::Siphon { amount };
+        }
+        EnemyAction::Attack { multiplier: 0.85, intent: None }
+    }
+}
+
+/// The decision maker for an AI profile
+pub fn ai_for(profile: AiProfile) -> &'static dyn EnemyAi {
+    match profile {
+        AiProfile::Balanced => &Balanced,
+        AiProfile::Aggressive => &Aggressive,
+        AiProfile::Cautious => &Cautious,
+        AiProfile::Relentless => &Relentless,
+        AiProfile::Berserk => &Berserk,
+        AiProfile::Tactician => &Tactician,
+        AiProfile::SpellThief => &SpellThief,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    fn enemy(profile: AiProfile) -> Enemy {
+        Enemy::new("foe".to_string(), "Foe".to_string(), "A test foe".to_string(), DifficultyTier::Intermediate)
+            .with_ai_profile(profile)
+    }
+
+    fn decide(enemy: &Enemy, player: &Player, defending: bool, difficulty: Difficulty) -> EnemyAction {
+        let context = AiContext { enemy, player, player_defending: defending, turn: 1, difficulty };
+        // A zero roll always counts as a clever turn
+        ai_for(enemy.ai_profile).decide(&context, &mut StepRng::new(0, 0))
+    }
+
+    #[test]
+    fn test_profiles_choose_their_own_actions() {
+        let mut player = Player::new("Test".to_string());
+        player.mental_state.current_energy = 80;
+
+        assert_eq!(decide(&enemy(AiProfile::Relentless), &player, true, Difficulty::Hard), EnemyAction::attack());
+        assert!(matches!(decide(&enemy(AiProfile::Tactician), &player, true, Difficulty::Hard), EnemyAction::Wait(_)));
+        assert_eq!(
+            decide(&enemy(AiProfile::SpellThief), &player, false, Difficulty::Normal),
+            EnemyAction::Siphon { amount: 12 }
+        );
+
+        let mut berserker = enemy(AiProfile::Berserk);
+        berserker.health = berserker.max_health / 5;
+        match decide(&berserker, &player, false, Difficulty::Story) {
+            EnemyAction::Attack { multiplier, .. } => assert!((multiplier - 1.48).abs() < 0.001),
+            other => panic!("Expected a frenzied attack, got {:?}", other),
+        }
+
+        let mut cautious = enemy(AiProfile::Cautious);
+        cautious.health = cautious.max_health * 2 / 5;
+        assert_eq!(decide(&cautious, &player, false, Difficulty::Normal), EnemyAction::Flee { chance: FLEE_CHANCE });
+
+        // With no sophistication left to spend, a tactician just attacks
+        player.mental_state.current_energy = 10;
+        let context = AiContext { enemy: &enemy(AiProfile::Tactician), player: &player, player_defending: false, turn: 1, difficulty: Difficulty::Story };
+        assert_eq!(Tactician.decide(&context, &mut StepRng::new(u64::MAX, 0)), EnemyAction::attack());
+        assert!(matches!(decide(&enemy(AiProfile::Tactician), &player, false, Difficulty::Story), EnemyAction::Attack { multiplier, .. } if multiplier > 1.0));
+    }
+
+    #[test]
+    fn test_difficulty_scales_enemies() {
+        assert_eq!(Difficulty::from_string(" Nightmare"), Some(Difficulty::Nightmare));
+        assert_eq!(Difficulty::from_string("impossible"), None);
+
+        let mut foe = enemy(AiProfile::Balanced);
+        Difficulty::Normal.scale(&mut foe);
+        assert_eq!(foe.max_health, 100);
+
+        Difficulty::Hard.scale(&mut foe);
+        assert_eq!((foe.health, foe.max_health, foe.experience_reward), (125, 125, 125));
+
+        let mut weak = enemy(AiProfile::Balanced);
+        Difficulty::Story.scale(&mut weak);
+        assert_eq!(weak.max_health, 75);
+        assert!(Difficulty::Story.explain().contains("damage x0.75"));
+    }
+}
@@ -0,0 +1,414 @@
+//! NPC defection and recruitment arcs
+//!
+//! Players can slowly win NPCs over to a different faction by building
+//! rapport and presenting arguments that appeal to that faction's philosophy.
+//! Conviction accumulates across many conversations; once an NPC is both
+//! convinced and trusts the player, they defect.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use super::FactionId;
+use crate::systems::quests::*;
+use crate::GameResult;
+
+/// Conviction required before an NPC will switch factions
+pub const DEFECTION_CONVICTION_THRESHOLD: i32 = 100;
+/// Minimum rapport before an NPC trusts the player enough to defect
+pub const DEFECTION_RAPPORT_THRESHOLD: i32 = 40;
+/// Game minutes an NPC needs to reflect between persuasion attempts
+pub const PERSUASION_COOLDOWN_MINUTES: i32 = 480;
+
+/// Arguments the player can make, each championing one faction's worldview
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PersuasionArgument {
+    /// Regulation keeps practitioners safe (Magisters' Council)
+    Safety,
+    /// Magic should respect natural cycles (Order of Harmony)
+    Harmony,
+    /// Magic drives prosperity and innovation (Industrial Consortium)
+    Progress,
+    /// Knowledge should be free for everyone (Underground Network)
+    Freedom,
+    /// Truth matters more than politics (Neutral Scholars)
+    Knowledge,
+}
+
+impl PersuasionArgument {
+    /// Parse an argument from player input
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "safety" | "regulation" | "order" => Some(Self::Safety),
+            "harmony" | "nature" | "tradition" => Some(Self::Harmony),
+            "progress" | "profit" | "innovation" => Some(Self::Progress),
+            "freedom" | "liberty" => Some(Self::Freedom),
+            "knowledge" | "truth" | "research" => Some(Self::Knowledge),
+            _ => None,
+        }
+    }
+
+    /// Faction whose philosophy this argument promotes
+    pub fn champion(&self) -> FactionId {
+        match self {
+            Self::Safety => FactionId::MagistersCouncil,
+            Self::Harmony => FactionId::OrderOfHarmony,
+            Self::Progress => FactionId::IndustrialConsortium,
+            Self::Freedom => FactionId::UndergroundNetwork,
+            Self::Knowledge => FactionId::NeutralScholars,
+        }
+    }
+
+    /// Short name for display
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Safety => "safety",
+            Self::Harmony => "harmony",
+            Self::Progress => "progress",
+            Self::Freedom => "freedom",
+            Self::Knowledge => "knowledge",
+        }
+    }
+
+    /// Get all persuasion arguments
+    pub fn all() -> Vec<Self> {
+        vec![Self::Safety, Self::Harmony, Self::Progress, Self::Freedom, Self::Knowledge]
+    }
+}
+
+/// Where an NPC currently stands in their defection arc
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DefectionStatus {
+    /// Still loyal but listening to the player
+    Considering,
+    /// Has switched to the target faction
+    Defected,
+}
+
+/// Long-term persuasion progress with a single NPC
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefectionArc {
+    pub npc_id: String,
+    /// Faction the NPC belonged to when the arc began (None for independents)
+    pub original_faction: Option<FactionId>,
+    /// Faction the player is trying to bring them into
+    pub target_faction: FactionId,
+    /// Personal trust built with the player (0-100)
+    pub rapport: i32,
+    /// How convinced the NPC is by the player's case (0-100)
+    pub conviction: i32,
+    /// Arguments already presented to this NPC
+    pub arguments_used: Vec<PersuasionArgument>,
+    /// Game time of the last persuasion attempt
+    pub last_attempt_minutes: Option<i32>,
+    pub status: DefectionStatus,
+}
+
+/// Result of a single persuasion attempt
+#[derive(Debug, Clone)]
+pub struct PersuasionResult {
+    pub message: String,
+    /// Set when this attempt caused the NPC to defect
+    pub defected: bool,
+    pub original_faction: Option<FactionId>,
+    pub target_faction: FactionId,
+}
+
+/// Tracks all ongoing and completed defection arcs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DefectionSystem {
+    arcs: HashMap<String, DefectionArc>,
+}
+
+impl DefectionSystem {
+    /// Create an empty defection tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the arc for a specific NPC
+    pub fn get_arc(&self, npc_id: &str) -> Option<&DefectionArc> {
+        self.arcs.get(npc_id)
+    }
+
+    /// Get all NPCs that have defected
+    pub fn defectors(&self) -> Vec<&DefectionArc> {
+        self.arcs
+            .values()
+            .filter(|arc| arc.status == DefectionStatus::Defected)
+            .collect()
+    }
+
+    /// Present an argument to an NPC, advancing their defection arc
+    ///
+    /// `player_standing` is the player's reputation with the faction the
+    /// argument champions; `npc_disposition` is the NPC's current attitude.
+    pub fn persuade(
+        &mut self,
+        npc_id: &str,
+        npc_faction: Option<FactionId>,
+        argument: PersuasionArgument,
+        player_standing: i32,
+        npc_disposition: i32,
+        game_time_minutes: i32,
+    ) -> GameResult<PersuasionResult> {
+        let target_faction = argument.champion();
+
+        if npc_faction == Some(target_faction) {
+            return Err(crate::GameError::InvalidCommand(format!(
+                "They already serve the {}.", target_faction.display_name()
+            )).into());
+        }
+
+        if player_standing < -20 {
+            return Err(crate::GameError::InsufficientResources(format!(
+                "Your poor standing with the {} undermines any case you make for them.",
+                target_faction.display_name()
+            )).into());
+        }
+
+        let arc = self.arcs.entry(npc_id.to_string()).or_insert_with(|| DefectionArc {
+            npc_id: npc_id.to_string(),
+            original_faction: npc_faction,
+            target_faction,
+            rapport: (npc_disposition / 2).clamp(0, 100),
+            conviction: 0,
+            arguments_used: Vec::new(),
+            last_attempt_minutes: None,
+            status: DefectionStatus::Considering,
+        });
+
+        if arc.status == DefectionStatus::Defected {
+            // A defector can be courted again from their new allegiance
+            arc.original_faction = npc_faction;
+            arc.conviction = 0;
+            arc.arguments_used.clear();
+            arc.status = DefectionStatus::Considering;
+        }
+
+        if let Some(last) = arc.last_attempt_minutes {
+            let elapsed = game_time_minutes - last;
+            if elapsed < PERSUASION_COOLDOWN_MINUTES {
+                let hours_left = (PERSUASION_COOLDOWN_MINUTES - elapsed + 59) / 60;
+                return Err(crate::GameError::InvalidCommand(format!(
+                    "They are still weighing your last conversation. Give them about {} more hour(s).",
+                    hours_left
+                )).into());
+            }
+        }
+
+        // Changing the pitch mid-arc costs credibility
+        if arc.target_faction != target_faction {
+            arc.target_faction = target_faction;
+            arc.conviction /= 2;
+            arc.arguments_used.clear();
+        }
+
+        let mut gain = 15 + player_standing.clamp(0, 100) / 5;
+        if arc.arguments_used.contains(&argument) {
+            gain /= 2;
+        } else {
+            arc.arguments_used.push(argument);
+        }
+        if arc.rapport < DEFECTION_RAPPORT_THRESHOLD / 2 {
+            gain /= 2;
+        }
+
+        arc.conviction = (arc.conviction + gain).min(100);
+        arc.rapport = (arc.rapport + 8).min(100);
+        arc.last_attempt_minutes = Some(game_time_minutes);
+
+        let defected = arc.conviction >= DEFECTION_CONVICTION_THRESHOLD
+            && arc.rapport >= DEFECTION_RAPPORT_THRESHOLD;
+
+        let message = if defected {
+            arc.status = DefectionStatus::Defected;
+            format!(
+                "Your {} argument finally tips the balance. They agree to join the {}.",
+                argument.name(), target_faction.display_name()
+            )
+        } else {
+            let stance = match arc.conviction {
+                75..=100 => "nearly persuaded",
+                50..=74 => "seriously considering your words",
+                25..=49 => "intrigued but unconvinced",
+                _ => "politely skeptical",
+            };
+            format!(
+                "You make the case for {}. They seem {}. (Conviction {}/{}, Rapport {}/{})",
+                argument.name(), stance,
+                arc.conviction, DEFECTION_CONVICTION_THRESHOLD,
+                arc.rapport, DEFECTION_RAPPORT_THRESHOLD
+            )
+        };
+
+        Ok(PersuasionResult {
+            message,
+            defected,
+            original_faction: arc.original_faction,
+            target_faction,
+        })
+    }
+}
+
+/// Build the follow-up quest opened when an NPC defects
+pub fn create_defector_quest(npc_id: &str, npc_name: &str, target_faction: FactionId) -> QuestDefinition {
+    let mut faction_effects = HashMap::new();
+    faction_effects.insert(target_faction, 10);
+
+    let mut faction_changes = HashMap::new();
+    faction_changes.insert(target_faction, 5);
+
+    QuestDefinition {
+        id: format!("defector_{}", npc_id),
+        title: format!("Safe Passage for {}", npc_name),
+        description: format!(
+            "{} has broken with their former colleagues to join the {}. Old allies will not \
+             take the betrayal lightly. Help them settle into their new allegiance and present \
+             their credentials at the Faction Diplomacy Hall.",
+            npc_name, target_faction.display_name()
+        ),
+        category: QuestCategory::Political,
+        difficulty: QuestDifficulty::Intermediate,
+        requirements: QuestRequirements {
+            theory_requirements: vec![],
+            faction_requirements: vec![],
+            faction_restrictions: vec![],
+            prerequisite_quests: vec![],
+            attribute_requirements: AttributeRequirements {
+                min_mental_acuity: None,
+                min_resonance_sensitivity: None,
+                min_total_playtime: None,
+            },
+            capability_requirements: vec![],
+            location_requirements: vec![],
+            world_flags: vec![],
+        },
+        objectives: vec![
+            QuestObjective {
+                id: "reassure_defector".to_string(),
+                description: format!("Speak with {} about their decision.", npc_name),
+                objective_type: ObjectiveType::TalkToNPC {
+                    npc_id: npc_id.to_string(),
+                    topic: None,
+                },
+                optional: false,
+                visible: true,
+                completion_reward: ObjectiveReward {
+                    experience: 20,
+                    theory_insights: HashMap::new(),
+                    faction_changes: HashMap::new(),
+                    items: vec![],
+                },
+            },
+            QuestObjective {
+                id: "present_credentials".to_string(),
+                description: "Accompany the defector to the Faction Diplomacy Hall.".to_string(),
+                objective_type: ObjectiveType::VisitLocation {
+                    location_id: "faction_diplomacy_hall".to_string(),
+                },
+                optional: false,
+                visible: true,
+                completion_reward: ObjectiveReward {
+                    experience: 30,
+                    theory_insights: HashMap::new(),
+                    faction_changes,
+                    items: vec![],
+                },
+            },
+        ],
+        rewards: QuestRewards {
+            experience: 75,
+            attribute_bonuses: AttributeBonuses {
+                mental_acuity: None,
+                resonance_sensitivity: None,
+            },
+            theory_bonuses: HashMap::new(),
+            faction_changes: HashMap::new(),
+            items: vec![],
+            new_capabilities: vec![],
+            unlocked_quests: vec![],
+        },
+        faction_effects,
+        educational_focus: EducationalObjectives {
+            primary_concepts: vec!["Persuasion and trust".to_string()],
+            secondary_concepts: vec![],
+            applications: vec!["Political negotiation".to_string()],
+            problem_solving_methods: vec![],
+            assessment_criteria: vec![],
+        },
+        branching_paths: HashMap::new(),
+        choices: vec![],
+        involved_npcs: vec![npc_id.to_string()],
+        locations: vec!["faction_diplomacy_hall".to_string()],
+        estimated_duration: 30,
+        deadline: None,
+        failure_triggers: vec![],
+        fallout: None,
+        cleans_up: None,
+        repeat: None,
+        sets_flags: vec![],
+        changes_locations: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_argument_parsing() {
+        assert_eq!(PersuasionArgument::from_string("Freedom"), Some(PersuasionArgument::Freedom));
+        assert_eq!(PersuasionArgument::from_string("profit"), Some(PersuasionArgument::Progress));
+        assert_eq!(PersuasionArgument::from_string("bribery"), None);
+    }
+
+    #[test]
+    fn test_cannot_persuade_to_current_faction() {
+        let mut system = DefectionSystem::new();
+        let result = system.persuade(
+            "warden_gareth", Some(FactionId::MagistersCouncil),
+            PersuasionArgument::Safety, 50, 50, 0,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_persuasion_respects_cooldown() {
+        let mut system = DefectionSystem::new();
+        let first = system.persuade(
+            "mage_kira", Some(FactionId::UndergroundNetwork),
+            PersuasionArgument::Knowledge, 30, 40, 0,
+        ).unwrap();
+        let conviction = system.get_arc("mage_kira").unwrap().conviction;
+        assert!(!first.defected);
+
+        let second = system.persuade(
+            "mage_kira", Some(FactionId::UndergroundNetwork),
+            PersuasionArgument::Knowledge, 30, 40, 60,
+        );
+        assert!(second.is_err());
+        assert_eq!(system.get_arc("mage_kira").unwrap().conviction, conviction);
+    }
+
+    #[test]
+    fn test_defection_after_long_term_persuasion() {
+        let mut system = DefectionSystem::new();
+        let mut time = 0;
+        let mut defected = false;
+
+        for _ in 0..20 {
+            let result = system.persuade(
+                "mage_kira", Some(FactionId::UndergroundNetwork),
+                PersuasionArgument::Safety, 60, 40, time,
+            ).unwrap();
+            time += PERSUASION_COOLDOWN_MINUTES;
+            if result.defected {
+                assert_eq!(result.original_faction, Some(FactionId::UndergroundNetwork));
+                defected = true;
+                break;
+            }
+        }
+
+        assert!(defected);
+        assert_eq!(system.defectors().len(), 1);
+    }
+}
@@ -0,0 +1,185 @@
+//! Long-term faction influence and the epilogue it shapes
+//!
+//! Reputation measures how a faction feels about the player; influence
+//! measures how far the player's actions have carried that faction's goals.
+//! Completing quests for a faction, siding with it in quest choices and
+//! winning its members converts all advance its influence. At the end of the
+//! story the factions with the most influence shape how the world turns out.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use super::FactionId;
+
+/// Ceiling for a single faction's influence meter
+pub const MAX_INFLUENCE: i32 = 100;
+/// Share of total weight a faction needs to be mentioned beside the leader
+const SECONDARY_SHARE: f32 = 0.25;
+/// Recent contributions kept for the legacy report
+const HISTORY_LIMIT: usize = 10;
+
+/// One action that moved a faction's influence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InfluenceEvent {
+    pub faction: FactionId,
+    pub change: i32,
+    pub reason: String,
+}
+
+/// Influence meters for every faction
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LegacyTracker {
+    #[serde(
+        serialize_with = "crate::systems::serde_helpers::serialize_faction_map",
+        deserialize_with = "crate::systems::serde_helpers::deserialize_faction_map"
+    )]
+    influence: HashMap<FactionId, i32>,
+    /// Most recent contributions, oldest first
+    history: Vec<InfluenceEvent>,
+}
+
+impl LegacyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current influence of a faction (0-100)
+    pub fn influence(&self, faction: FactionId) -> i32 {
+        self.influence.get(&faction).copied().unwrap_or(0)
+    }
+
+    /// Move a faction's influence and record why
+    pub fn advance(&mut self, faction: FactionId, change: i32, reason: &str) {
+        if change == 0 {
+            return;
+        }
+
+        let value = (self.influence(faction) + change).clamp(0, MAX_INFLUENCE);
+        self.influence.insert(faction, value);
+
+        self.history.push(InfluenceEvent { faction, change, reason: reason.to_string() });
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+    }
+
+    /// Recent contributions, oldest first
+    pub fn history(&self) -> &[InfluenceEvent] {
+        &self.history
+    }
+
+    /// Each faction's share of the epilogue, strongest first
+    ///
+    /// Influence carries the most weight; positive reputation adds a little
+    /// so that goodwill breaks ties between equally advanced factions.
+    pub fn epilogue_weights(&self, reputation: impl Fn(FactionId) -> i32) -> Vec<(FactionId, f32)> {
+        let scores: Vec<(FactionId, f32)> = FactionId::all()
+            .into_iter()
+            .map(|faction| (faction, self.influence(faction) as f32 + reputation(faction).max(0) as f32 / 4.0))
+            .collect();
+        let total: f32 = scores.iter().map(|(_, score)| score).sum();
+        if total <= 0.0 {
+            return Vec::new();
+        }
+
+        let mut weights: Vec<(FactionId, f32)> = scores.into_iter()
+            .filter(|(_, score)| *score > 0.0)
+            .map(|(faction, score)| (faction, score / total))
+            .collect();
+        weights.sort_by(|a, b| b.1.total_cmp(&a.1));
+        weights
+    }
+
+    /// How the world turns out, given the current weights
+    pub fn epilogue(&self, reputation: impl Fn(FactionId) -> i32) -> String {
+        let weights = self.epilogue_weights(reputation);
+        let Some((leader, _)) = weights.first() else {
+            return "Your deeds left the balance of power untouched. The city carries on much as it did before you arrived.".to_string();
+        };
+
+        let mut text = epilogue_text(*leader).to_string();
+        let supporters: Vec<&str> = weights.iter()
+            .skip(1)
+            .filter(|(_, share)| *share >= SECONDARY_SHARE)
+            .map(|(faction, _)| faction.display_name())
+            .collect();
+        if !supporters.is_empty() {
+            text.push_str(&format!(" The {} keep a strong voice in the new order.", supporters.join(" and the ")));
+        }
+        text
+    }
+
+    /// Influence meters, recent contributions and the projected epilogue
+    pub fn report(&self, reputation: impl Fn(FactionId) -> i32 + Copy) -> String {
+        let mut output = String::from("=== LEGACY ===\nHow far your actions have advanced each faction's goals:\n\n");
+        for faction in FactionId::all() {
+            let value = self.influence(faction);
+            let filled = (value / 10) as usize;
+            output.push_str(&format!(
+                "{:<26} [{}{}] {:>3}\n",
+                faction.display_name(), "#".repeat(filled), "-".repeat(10 - filled), value
+            ));
+        }
+
+        if !self.history.is_empty() {
+            output.push_str("\nRecent contributions:\n");
+            for event in self.history.iter().rev() {
+                output.push_str(&format!("  {:+} {} - {}\n", event.change, event.faction.short_name(), event.reason));
+            }
+        }
+
+        output.push_str(&format!("\nIf your story ended today:\n{}", self.epilogue(reputation)));
+        output
+    }
+}
+
+/// Closing narration when a faction shapes the world
+fn epilogue_text(faction: FactionId) -> &'static str {
+    match faction {
+        FactionId::MagistersCouncil => "The Magisters' Council tightens its hold on practice. Licensed, regulated resonance becomes the law of the land, and accidents grow rare - as do unsanctioned discoveries.",
+        FactionId::OrderOfHarmony => "The Order of Natural Harmony's caution carries the day. Great works are set aside in favour of balance, and the crystal groves are left to grow undisturbed.",
+        FactionId::IndustrialConsortium => "The Industrial Consortium's workshops spread across the city. Resonance lights every street and powers every mill, and those who own the crystals grow rich.",
+        FactionId::UndergroundNetwork => "The Underground Network's pamphlets become textbooks. Knowledge of resonance passes freely from hand to hand, and the old gatekeepers struggle to keep up.",
+        FactionId::NeutralScholars => "The Neutral Scholars become the arbiters of truth. Every faction must now argue from evidence, and the Archives grow into the heart of the city.",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_influence_clamps_and_history_is_bounded() {
+        let mut legacy = LegacyTracker::new();
+        legacy.advance(FactionId::OrderOfHarmony, 80, "Restored the grove");
+        legacy.advance(FactionId::OrderOfHarmony, 50, "Sealed the quarry");
+        legacy.advance(FactionId::IndustrialConsortium, -10, "Sabotaged a mill");
+        assert_eq!(legacy.influence(FactionId::OrderOfHarmony), MAX_INFLUENCE);
+        assert_eq!(legacy.influence(FactionId::IndustrialConsortium), 0);
+
+        for i in 0..20 {
+            legacy.advance(FactionId::NeutralScholars, 1, &format!("Lecture {}", i));
+        }
+        assert_eq!(legacy.history().len(), HISTORY_LIMIT);
+        assert_eq!(legacy.history().last().unwrap().reason, "Lecture 19");
+    }
+
+    #[test]
+    fn test_epilogue_weighted_by_influence() {
+        let mut legacy = LegacyTracker::new();
+        assert!(legacy.epilogue(|_| 0).contains("untouched"));
+
+        legacy.advance(FactionId::UndergroundNetwork, 40, "Freed the archive");
+        legacy.advance(FactionId::NeutralScholars, 30, "Published findings");
+        // Reputation alone can't outweigh real influence
+        let reputation = |faction| if faction == FactionId::MagistersCouncil { 80 } else { 0 };
+
+        let weights = legacy.epilogue_weights(reputation);
+        assert_eq!(weights[0].0, FactionId::UndergroundNetwork);
+        assert!((weights.iter().map(|(_, share)| share).sum::<f32>() - 1.0).abs() < 0.001);
+
+        let epilogue = legacy.epilogue(reputation);
+        assert!(epilogue.starts_with("The Underground Network"));
+        assert!(epilogue.contains("Neutral Scholars keep a strong voice"));
+        assert!(legacy.report(reputation).contains("If your story ended today"));
+    }
+}
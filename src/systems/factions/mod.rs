@@ -4,15 +4,20 @@
 //! - Faction identification and properties
 //! - Reputation tracking and modification
 //! - Inter-faction relationship modeling
+//! - Long-term influence over how the story ends
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 pub mod reputation;
 pub mod politics;
+pub mod defection;
+pub mod legacy;
 
 pub use reputation::ReputationSystem;
 pub use politics::PoliticalSystem;
+pub use defection::{DefectionSystem, PersuasionArgument};
+pub use legacy::LegacyTracker;
 
 /// Unique identifiers for the five major factions
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
@@ -86,6 +91,12 @@ pub struct FactionSystem {
     pub reputation: ReputationSystem,
     /// Political relationships
     pub politics: PoliticalSystem,
+    /// NPC defection and recruitment arcs
+    #[serde(default)]
+    pub defection: DefectionSystem,
+    /// How far the player has advanced each faction's goals
+    #[serde(default)]
+    pub legacy: LegacyTracker,
 }
 
 impl FactionSystem {
@@ -104,6 +115,8 @@ impl FactionSystem {
             factions,
             reputation: ReputationSystem::new(),
             politics: PoliticalSystem::new(),
+            defection: DefectionSystem::new(),
+            legacy: LegacyTracker::new(),
         }
     }
 
@@ -197,6 +210,28 @@ impl FactionSystem {
         standings
     }
 
+    /// Shift resources when a member defects from one faction to another
+    pub fn apply_defection(&mut self, from: Option<FactionId>, to: FactionId) {
+        if let Some(from_id) = from {
+            if let Some(faction) = self.factions.get_mut(&from_id) {
+                faction.resources.information = (faction.resources.information - 3).clamp(0, 100);
+                faction.resources.magical_assets = (faction.resources.magical_assets - 2).clamp(0, 100);
+            }
+            self.legacy.advance(from_id, -5, &format!("Lost a member to the {}", to.display_name()));
+        }
+        self.legacy.advance(to, 10, "Won over a new member");
+
+        if let Some(faction) = self.factions.get_mut(&to) {
+            faction.resources.information = (faction.resources.information + 3).clamp(0, 100);
+            faction.resources.magical_assets = (faction.resources.magical_assets + 2).clamp(0, 100);
+        }
+    }
+
+    /// Influence meters and the epilogue they currently point to
+    pub fn legacy_report(&self) -> String {
+        self.legacy.report(|faction| self.get_reputation(faction))
+    }
+
     /// Get relationship strength between two factions (-1.0 to 1.0)
     pub fn get_relationship_strength(&self, faction1: FactionId, faction2: FactionId) -> f32 {
         self.politics.get_relationship(faction1, faction2).to_strength()
@@ -378,6 +413,17 @@ impl FactionId {
         }
     }
 
+    /// Get the snake_case key used in content data
+    pub fn key(&self) -> &str {
+        match self {
+            FactionId::MagistersCouncil => "magisters_council",
+            FactionId::OrderOfHarmony => "order_of_harmony",
+            FactionId::IndustrialConsortium => "industrial_consortium",
+            FactionId::UndergroundNetwork => "underground_network",
+            FactionId::NeutralScholars => "neutral_scholars",
+        }
+    }
+
     /// Get short name for display
     pub fn short_name(&self) -> &str {
         match self {
@@ -442,4 +488,16 @@ mod tests {
         let modifier = faction_system.get_price_modifier(FactionId::IndustrialConsortium);
         assert!(modifier > 1.0);
     }
+
+    #[test]
+    fn test_defection_shifts_resources() {
+        let mut faction_system = FactionSystem::new();
+        let council_info = faction_system.get_faction(FactionId::MagistersCouncil).unwrap().resources.information;
+        let underground_info = faction_system.get_faction(FactionId::UndergroundNetwork).unwrap().resources.information;
+
+        faction_system.apply_defection(Some(FactionId::MagistersCouncil), FactionId::UndergroundNetwork);
+
+        assert!(faction_system.get_faction(FactionId::MagistersCouncil).unwrap().resources.information < council_info);
+        assert!(faction_system.get_faction(FactionId::UndergroundNetwork).unwrap().resources.information > underground_info);
+    }
 }
\ No newline at end of file
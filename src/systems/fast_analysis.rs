@@ -0,0 +1,132 @@
+//! Fast analysis for experts
+//!
+//! Novices work through routine analysis step by step: finding a detection
+//! tool, paying someone with a trained eye, or casting a detection spell and
+//! reading the signature it reveals. Once the player has mastered the theory
+//! behind a routine they can switch fast analysis on, and the routine
+//! resolves on the spot for a little mental energy:
+//! - Identification (Detection Arrays): unidentified finds are identified as
+//!   they are picked up, and `appraise` needs no tool, person or spell
+//!
+//! The full flow stays available. Fast analysis only skips it when the player
+//! has both the mastery and the mode switched on, and has energy to spare.
+
+use crate::core::Player;
+use crate::systems::items::identification::{self, AppraisalMethod};
+
+/// Understanding at which a theory counts as mastered for routine analysis
+pub const MASTERY_UNDERSTANDING: f32 = 0.8;
+/// Mental energy spent on each fast analysis
+pub const FAST_ANALYSIS_ENERGY: i32 = 3;
+
+/// Analysis routines fast analysis can resolve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Routine {
+    Identification,
+}
+
+impl Routine {
+    /// Every routine
+    pub fn all() -> [Routine; 1] {
+        [Routine::Identification]
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Routine::Identification => "Identification",
+        }
+    }
+
+    /// Theory that must be mastered
+    pub fn theory(&self) -> &'static str {
+        match self {
+            Routine::Identification => identification::ANALYSIS_THEORY,
+        }
+    }
+
+    /// Whether the player has mastered this routine's theory
+    pub fn mastered(&self, player: &Player) -> bool {
+        player.theory_understanding(self.theory()) >= MASTERY_UNDERSTANDING
+    }
+}
+
+/// Whether a routine resolves instantly for the player
+pub fn resolves(player: &Player, routine: Routine) -> bool {
+    player.fast_analysis && routine.mastered(player)
+}
+
+/// Identify an item in the pack at a glance, returning the revelation
+///
+/// Returns None, spending nothing, when fast analysis doesn't apply: the
+/// mode is off, identification isn't mastered, the player is too drained or
+/// the item is already known.
+pub fn identify(player: &mut Player, item_id: &String) -> Option<String> {
+    if !resolves(player, Routine::Identification) || player.effective_mental_energy() < FAST_ANALYSIS_ENERGY {
+        return None;
+    }
+    let item = player.inventory.enhanced_items.as_mut()?.inventory_manager.items.get_mut(item_id)?;
+    let revelation = identification::identify(item, AppraisalMethod::Expertise)?;
+    player.use_mental_energy(FAST_ANALYSIS_ENERGY, 0).ok()?;
+    Some(format!("{} (Energy -{})", revelation, FAST_ANALYSIS_ENERGY))
+}
+
+/// Switch fast analysis on or off, or describe it when no setting is given
+pub fn set(player: &mut Player, enabled: Option<bool>) -> crate::GameResult<String> {
+    let routines: Vec<String> = Routine::all().iter()
+        .map(|routine| format!(
+            "  • {} - {} {:.0}%/{:.0}%{}",
+            routine.name(),
+            routine.theory().replace('_', " "),
+            player.theory_understanding(routine.theory()) * 100.0,
+            MASTERY_UNDERSTANDING * 100.0,
+            if routine.mastered(player) { " (mastered)" } else { "" }
+        ))
+        .collect();
+
+    match enabled {
+        None => Ok(format!(
+            "Fast analysis is {}. Mastered routines resolve instantly for {} energy.\n{}\n\nUse 'fast analysis on' or 'fast analysis off'.",
+            if player.fast_analysis { "on" } else { "off" }, FAST_ANALYSIS_ENERGY, routines.join("\n")
+        )),
+        Some(true) if !Routine::all().iter().any(|routine| routine.mastered(player)) => {
+            Err(crate::GameError::InvalidCommand(format!(
+                "You haven't mastered any routine well enough to rush it.\n{}", routines.join("\n")
+            )).into())
+        }
+        Some(true) => {
+            player.fast_analysis = true;
+            Ok("Fast analysis on. Routine work you've mastered will resolve at a glance.".to_string())
+        }
+        Some(false) => {
+            player.fast_analysis = false;
+            Ok("Fast analysis off. You'll work through each analysis step by step.".to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::items::{Item, ItemType};
+
+    #[test]
+    fn test_fast_analysis_needs_mastery_and_the_mode_on() {
+        let mut player = Player::new("Ada".to_string());
+        let mut item = Item::new_basic("Harmonic Keystone".to_string(), "Sings.".to_string(), ItemType::Artifact { properties: String::new() });
+        identification::conceal(&mut item, "strange artifact", "A disc of dark stone.");
+        let item_id = item.id.clone();
+        player.add_enhanced_item(item).unwrap();
+
+        assert!(set(&mut player, Some(true)).is_err());
+        player.knowledge.theories.insert(identification::ANALYSIS_THEORY.to_string(), 0.9);
+        assert!(identify(&mut player, &item_id).is_none()); // mode off
+
+        set(&mut player, Some(true)).unwrap();
+        let energy = player.mental_state.current_energy;
+        assert!(identify(&mut player, &item_id).unwrap().contains("reveals itself: Harmonic Keystone"));
+        assert_eq!(player.mental_state.current_energy, energy - FAST_ANALYSIS_ENERGY);
+        assert!(identify(&mut player, &item_id).is_none()); // already known
+        assert!(set(&mut player, None).unwrap().contains("(mastered)"));
+    }
+}
@@ -0,0 +1,317 @@
+//! Followers who travel with the player outside combat
+//!
+//! A few NPCs will come along when invited: a research partner who works
+//! through study, research and experiments with the player, a student who
+//! gives the player someone to teach, and a bodyguard who keeps watch while
+//! the player observes. Followers go wherever the player goes and have a word
+//! to say about some of the places they arrive, except where they refuse to
+//! set foot; there they wait behind until the player comes back for them.
+//!
+//! Followers are kept on the player, so they are saved with the game. They
+//! are separate from the combat party, which fights rather than travels.
+
+use crate::systems::knowledge::{LearningActivity, LearningMethod};
+use crate::systems::relationships::RelationshipStage;
+use crate::GameResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Most followers the player can travel with at once
+pub const MAX_FOLLOWERS: usize = 2;
+/// Extra understanding and experience from working alongside a research partner
+const PARTNER_BONUS: f32 = 0.2;
+/// Extra understanding and experience from teaching an actual student
+const STUDENT_BONUS: f32 = 0.3;
+/// Extra understanding and experience from observing with someone keeping watch
+const BODYGUARD_BONUS: f32 = 0.1;
+
+/// What a follower does for the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FollowerRole {
+    ResearchPartner,
+    Student,
+    Bodyguard,
+}
+
+impl FollowerRole {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FollowerRole::ResearchPartner => "research partner",
+            FollowerRole::Student => "student",
+            FollowerRole::Bodyguard => "bodyguard",
+        }
+    }
+
+    /// The bonus the role gives a learning method, if any
+    pub fn learning_bonus(&self, method: &LearningMethod) -> Option<f32> {
+        match (self, method) {
+            (FollowerRole::ResearchPartner, LearningMethod::Study | LearningMethod::Research | LearningMethod::Experimentation) => Some(PARTNER_BONUS),
+            (FollowerRole::Student, LearningMethod::Teaching) => Some(STUDENT_BONUS),
+            (FollowerRole::Bodyguard, LearningMethod::Observation) => Some(BODYGUARD_BONUS),
+            _ => None,
+        }
+    }
+}
+
+/// Who will follow the player, on what terms, and where they won't go
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FollowerTerms {
+    pub role: FollowerRole,
+    /// How well the NPC must know the player to come along
+    pub min_stage: RelationshipStage,
+    /// Silver paid when they join
+    pub silver: i32,
+    /// Locations they refuse to enter, with why
+    pub refuses: &'static [(&'static str, &'static str)],
+    /// What they say on first arriving at a location with the player
+    pub remarks: &'static [(&'static str, &'static str)],
+}
+
+/// NPCs who can be invited along
+pub fn follower_terms(npc_id: &str) -> Option<FollowerTerms> {
+    match npc_id {
+        "dr_felix" => Some(FollowerTerms {
+            role: FollowerRole::ResearchPartner,
+            min_stage: RelationshipStage::Acquaintance,
+            silver: 0,
+            refuses: &[("faction_diplomacy_hall", "I study living things, not politicians")],
+            remarks: &[
+                ("crystalline_archives", "Felix makes straight for the bio-resonance shelves, muttering citations."),
+                ("resonance_observatory", "Felix squints up at the arrays. \"All that power, and they point it at people.\""),
+                ("unstable_resonance_site", "Felix kneels by a cracked stone. \"Look, lichen. Even here, something grows.\""),
+            ],
+        }),
+        "assistant_thomas" => Some(FollowerTerms {
+            role: FollowerRole::Student,
+            min_stage: RelationshipStage::Acquaintance,
+            silver: 0,
+            refuses: &[("unstable_resonance_site", "Sage Meridian would never forgive me if I went near the Site")],
+            remarks: &[
+                ("resonance_observatory", "Thomas stares at the detection arrays with his mouth slightly open."),
+                ("crystal_garden_lab", "Thomas asks, very quietly, whether the plants can hear him."),
+                ("practice_hall", "Thomas copies your stance at the practice circle, a beat behind."),
+            ],
+        }),
+        "captain_vera" => Some(FollowerTerms {
+            role: FollowerRole::Bodyguard,
+            min_stage: RelationshipStage::Stranger,
+            silver: 25,
+            refuses: &[("faction_diplomacy_hall", "Armed escorts aren't allowed in the Diplomacy Hall")],
+            remarks: &[
+                ("unstable_resonance_site", "Vera checks the barrier wards out of habit. \"Stay inside the markers.\""),
+                ("crystalline_archives", "Vera takes a post by the door where she can see every aisle."),
+                ("harmonic_testing_chambers", "Vera nods to the wardens on duty like old colleagues."),
+            ],
+        }),
+        _ => None,
+    }
+}
+
+/// Someone travelling with the player
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Follower {
+    pub npc_id: String,
+    pub name: String,
+    pub role: FollowerRole,
+    /// Where they stayed behind, if they refused to go on
+    pub waiting_at: Option<String>,
+    /// Locations they've already remarked on
+    pub remarked: HashSet<String>,
+}
+
+/// Everyone travelling with the player
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Followers {
+    members: Vec<Follower>,
+}
+
+impl Followers {
+    pub fn members(&self) -> &[Follower] {
+        &self.members
+    }
+
+    /// Whether someone is travelling with the player rather than waiting behind
+    pub fn with_player(&self, npc_id: &str) -> bool {
+        self.members.iter().any(|member| member.npc_id == npc_id && member.waiting_at.is_none())
+    }
+
+    /// Find a follower by NPC ID or any part of their name
+    pub fn find(&self, query: &str) -> Option<&Follower> {
+        let query = query.trim().to_lowercase();
+        self.members.iter().find(|member| {
+            member.npc_id == query || member.name.to_lowercase() == query
+                || member.name.to_lowercase().split_whitespace().any(|word| word.trim_end_matches('.') == query)
+        })
+    }
+
+    /// Ask an NPC to come along, given how well they know the player and
+    /// where the player is now
+    pub fn invite(&mut self, npc_id: &str, name: &str, stage: RelationshipStage, location_id: &str, player_silver: &mut i32) -> GameResult<String> {
+        let terms = follower_terms(npc_id)
+            .ok_or_else(|| crate::GameError::InvalidCommand(format!("{} has no reason to follow you around.", name)))?;
+        if self.members.iter().any(|member| member.npc_id == npc_id) {
+            return Err(crate::GameError::InvalidCommand(format!("{} is already travelling with you.", name)).into());
+        }
+        if self.members.len() >= MAX_FOLLOWERS {
+            return Err(crate::GameError::InvalidCommand(format!(
+                "You can travel with at most {} followers. Send someone home first.", MAX_FOLLOWERS
+            )).into());
+        }
+        if stage < terms.min_stage {
+            return Err(crate::GameError::InvalidCommand(format!(
+                "{} doesn't know you well enough to go anywhere with you.", name
+            )).into());
+        }
+        if *player_silver < terms.silver {
+            return Err(crate::GameError::InsufficientResources(format!(
+                "{} asks {} silver to escort you. You have {}.", name, terms.silver, player_silver
+            )).into());
+        }
+
+        *player_silver -= terms.silver;
+        let fee = if terms.silver > 0 { format!(" (paid {} silver)", terms.silver) } else { String::new() };
+        self.members.push(Follower {
+            npc_id: npc_id.to_string(),
+            name: name.to_string(),
+            role: terms.role,
+            waiting_at: None,
+            remarked: HashSet::from([location_id.to_string()]),
+        });
+        Ok(format!("{} comes along as your {}{}.", name, terms.role.name(), fee))
+    }
+
+    /// Send a follower home
+    pub fn dismiss(&mut self, query: &str) -> Option<String> {
+        let npc_id = self.find(query)?.npc_id.clone();
+        let index = self.members.iter().position(|member| member.npc_id == npc_id)?;
+        let member = self.members.remove(index);
+        Some(format!("{} heads back to their own work.", member.name))
+    }
+
+    /// Followers arrive with the player, stay behind where they won't go, or
+    /// rejoin the player where they waited; returns what they say
+    pub fn arrive(&mut self, from: &str, to: &str, location_name: impl Fn(&str) -> String) -> Vec<String> {
+        let mut messages = Vec::new();
+        for member in &mut self.members {
+            let Some(terms) = follower_terms(&member.npc_id) else { continue };
+            match &member.waiting_at {
+                Some(waiting_at) if waiting_at == to => {
+                    member.waiting_at = None;
+                    messages.push(format!("{} rejoins you.", member.name));
+                }
+                Some(_) => continue,
+                None => {
+                    if let Some((_, reason)) = terms.refuses.iter().find(|(location, _)| *location == to) {
+                        member.waiting_at = Some(from.to_string());
+                        messages.push(format!("{} stops short. \"{}.\" They'll wait for you at the {}.", member.name, reason, location_name(from)));
+                        continue;
+                    }
+                }
+            }
+            if member.remarked.insert(to.to_string()) {
+                if let Some((_, remark)) = terms.remarks.iter().find(|(location, _)| *location == to) {
+                    messages.push(remark.to_string());
+                }
+            }
+        }
+        messages
+    }
+
+    /// Work alongside whichever follower with the player helps most with a
+    /// learning activity
+    pub fn assist(&self, activity: &mut LearningActivity) {
+        let helper = self.members.iter()
+            .filter(|member| member.waiting_at.is_none())
+            .filter_map(|member| member.role.learning_bonus(&activity.method).map(|bonus| (member, bonus)))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((member, bonus)) = helper {
+            activity.understanding_gained *= 1.0 + bonus;
+            activity.experience_gained = (activity.experience_gained as f32 * (1.0 + bonus)) as i32;
+            activity.side_effects.push(format!(
+                "{} works through it with you as your {} (+{:.0}%)", member.name, member.role.name(), bonus * 100.0
+            ));
+        }
+    }
+
+    /// Who is travelling with the player, and who is waiting where
+    pub fn report(&self, location_name: impl Fn(&str) -> String) -> String {
+        if self.members.is_empty() {
+            return "Nobody is travelling with you. Some people will come along if you 'invite' them.".to_string();
+        }
+        let mut output = String::from("=== Followers ===\n");
+        for member in &self.members {
+            let state = member.waiting_at.as_deref()
+                .map_or_else(String::new, |location| format!(" - waiting at the {}", location_name(location)));
+            output.push_str(&format!("{} ({}){}\n", member.name, member.role.name(), state));
+        }
+        output.trim_end().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn activity(method: LearningMethod) -> LearningActivity {
+        LearningActivity {
+            theory_id: "bio_resonance".to_string(),
+            method,
+            duration: 60,
+            success_rate: 1.0,
+            experience_gained: 100,
+            understanding_gained: 0.1,
+            resources_used: HashMap::new(),
+            side_effects: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_invite_limits_and_terms() {
+        let mut followers = Followers::default();
+        let mut silver = 10;
+
+        assert!(followers.invite("dr_felix", "Dr. Felix", RelationshipStage::Stranger, "crystal_garden_lab", &mut silver).is_err());
+        assert!(followers.invite("captain_vera", "Captain Vera", RelationshipStage::Stranger, "crystal_garden_lab", &mut silver).is_err());
+        assert!(followers.invite("sage_meridian", "Sage Meridian", RelationshipStage::Confidant, "crystal_garden_lab", &mut silver).is_err());
+
+        silver = 30;
+        assert!(followers.invite("captain_vera", "Captain Vera", RelationshipStage::Stranger, "crystal_garden_lab", &mut silver).unwrap().contains("paid 25 silver"));
+        assert_eq!(silver, 5);
+        followers.invite("dr_felix", "Dr. Felix", RelationshipStage::Acquaintance, "crystal_garden_lab", &mut silver).unwrap();
+        assert!(followers.invite("assistant_thomas", "Thomas", RelationshipStage::Friend, "crystal_garden_lab", &mut silver).is_err());
+
+        assert!(followers.dismiss("felix").unwrap().contains("Dr. Felix"));
+        assert!(followers.dismiss("felix").is_none());
+        assert_eq!(followers.members().len(), 1);
+    }
+
+    #[test]
+    fn test_followers_wait_where_they_refuse_and_remark_once() {
+        let mut followers = Followers::default();
+        let mut silver = 0;
+        followers.invite("assistant_thomas", "Thomas", RelationshipStage::Acquaintance, "crystalline_archives", &mut silver).unwrap();
+        let name = |id: &str| id.replace('_', " ");
+
+        let messages = followers.arrive("crystalline_archives", "resonance_observatory", name);
+        assert!(messages[0].contains("detection arrays"));
+        assert!(followers.arrive("resonance_observatory", "crystalline_archives", name).is_empty());
+        assert!(followers.arrive("crystalline_archives", "resonance_observatory", name).is_empty());
+
+        let messages = followers.arrive("resonance_observatory", "unstable_resonance_site", name);
+        assert!(messages[0].contains("wait for you at the resonance observatory"));
+        assert!(followers.arrive("unstable_resonance_site", "practice_hall", name).is_empty());
+        assert!(followers.report(name).contains("waiting at the resonance observatory"));
+
+        // Nobody to teach while he waits, then a student's bonus once he's back
+        let mut teaching = activity(LearningMethod::Teaching);
+        followers.assist(&mut teaching);
+        assert_eq!(teaching.experience_gained, 100);
+        assert_eq!(followers.arrive("practice_hall", "resonance_observatory", name), vec!["Thomas rejoins you.".to_string()]);
+        followers.assist(&mut teaching);
+        assert_eq!(teaching.experience_gained, 130);
+        let mut study = activity(LearningMethod::Study);
+        followers.assist(&mut study);
+        assert!(study.side_effects.is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+//! Items left behind in the world
+//!
+//! Dropped items stay where they were left, with all their properties, and
+//! are saved with the world. They are not safe forever: once the player has
+//! been away for a while, passers-by start picking over anything lying in
+//! the open, more quickly where more people come and go. Items hidden in a
+//! cache are only found if the hiding place was poor; a well-concealed cache
+//! is never discovered.
+//!
+//! Scavenging is settled lazily when the player returns to a location, using
+//! the time they were away.
+
+use crate::core::Player;
+use crate::systems::items::core::Item;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Minutes the player can be away before anyone starts taking what was left
+const GRACE_MINUTES: i32 = 240;
+/// Chance per day that an item in the open is taken from a quiet location
+const DAILY_SCAVENGE_CHANCE: f32 = 0.3;
+/// Extra scavenging per NPC who frequents the location
+const TRAFFIC_PER_NPC: f32 = 0.5;
+/// Concealment at which a cache is never found
+pub const WELL_CONCEALED: f32 = 0.7;
+/// Minutes it takes to hide an item
+pub const HIDE_MINUTES: i32 = 10;
+
+/// An item lying in a location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroundItem {
+    pub item: Item,
+    /// Game time (minutes) the item was left
+    pub dropped_at: i32,
+    /// How well it is hidden, if it was stashed in a cache
+    #[serde(default)]
+    pub concealment: Option<f32>,
+    /// Game time up to which scavenging has been settled
+    #[serde(default)]
+    pub settled_at: i32,
+}
+
+impl GroundItem {
+    fn name(&self) -> &str {
+        &self.item.properties.name
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.concealment.is_some()
+    }
+
+    /// Chance per day that someone makes off with the item
+    fn daily_chance(&self, traffic: usize) -> f32 {
+        let exposure = match self.concealment {
+            Some(concealment) if concealment >= WELL_CONCEALED => return 0.0,
+            Some(concealment) => 1.0 - concealment / WELL_CONCEALED,
+            None => 1.0,
+        };
+        (DAILY_SCAVENGE_CHANCE * (1.0 + TRAFFIC_PER_NPC * traffic as f32) * exposure).min(1.0)
+    }
+}
+
+/// Everything left lying around the world, saved with it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroundItems {
+    /// Items by location id
+    pub by_location: HashMap<String, Vec<GroundItem>>,
+}
+
+/// How well the player manages to hide something here
+pub fn concealment(player: &Player, traffic: usize, rng: &mut impl Rng) -> f32 {
+    let skill = (player.attributes.mental_acuity - 25) as f32 / 150.0;
+    (0.45 + skill + rng.gen_range(0.0..0.3) - 0.1 * traffic as f32).clamp(0.0, 1.0)
+}
+
+impl GroundItems {
+    /// Items at a location
+    pub fn at(&self, location: &str) -> &[GroundItem] {
+        self.by_location.get(location).map_or(&[], Vec::as_slice)
+    }
+
+    /// Leave an item in the open
+    pub fn drop_item(&mut self, location: &str, item: Item, now: i32) {
+        self.leave(location, item, None, now);
+    }
+
+    /// Stash an item in a cache
+    pub fn hide_item(&mut self, location: &str, item: Item, concealment: f32, now: i32) {
+        self.leave(location, item, Some(concealment), now);
+    }
+
+    fn leave(&mut self, location: &str, item: Item, concealment: Option<f32>, now: i32) {
+        self.by_location.entry(location.to_string()).or_default().push(GroundItem {
+            item,
+            dropped_at: now,
+            concealment,
+            settled_at: now,
+        });
+    }
+
+    /// Pick up the first item here whose name matches, open or cached
+    pub fn take(&mut self, location: &str, query: &str) -> Option<GroundItem> {
+        let query = query.trim().to_lowercase();
+        let items = self.by_location.get_mut(location)?;
+        let index = items.iter().position(|entry| entry.name().to_lowercase() == query)
+            .or_else(|| items.iter().position(|entry| entry.name().to_lowercase().contains(&query)))?;
+        let taken = items.remove(index);
+        if items.is_empty() {
+            self.by_location.remove(location);
+        }
+        Some(taken)
+    }
+
+    /// Note that the player was present until now, so nothing went missing meanwhile
+    pub fn watch(&mut self, location: &str, now: i32) {
+        for entry in self.by_location.get_mut(location).into_iter().flatten() {
+            entry.settled_at = entry.settled_at.max(now);
+        }
+    }
+
+    /// Decide what scavengers took while the player was away
+    ///
+    /// `traffic` is the number of NPCs who frequent the location. Returns a
+    /// line for each discovery the player makes on their return.
+    pub fn settle(&mut self, location: &str, now: i32, traffic: usize, rng: &mut impl Rng) -> Vec<String> {
+        let Some(items) = self.by_location.get_mut(location) else {
+            return Vec::new();
+        };
+
+        let mut messages = Vec::new();
+        items.retain_mut(|entry| {
+            let unwatched_from = entry.settled_at + GRACE_MINUTES;
+            entry.settled_at = entry.settled_at.max(now);
+            if now <= unwatched_from {
+                return true;
+            }
+
+            let days = (now - unwatched_from) as f32 / 1440.0;
+            let chance = 1.0 - (1.0 - entry.daily_chance(traffic)).powf(days);
+            if rng.gen::<f32>() >= chance {
+                return true;
+            }
+            messages.push(if entry.is_hidden() {
+                format!("Your cache has been disturbed: the {} is gone.", entry.name())
+            } else {
+                format!("The {} you left here is gone; someone must have picked it up.", entry.name())
+            });
+            false
+        });
+
+        if items.is_empty() {
+            self.by_location.remove(location);
+        }
+        messages
+    }
+
+    /// Lines for the location description
+    pub fn describe(&self, location: &str) -> String {
+        let (hidden, open): (Vec<&GroundItem>, Vec<&GroundItem>) = self.at(location).iter().partition(|entry| entry.is_hidden());
+        let names = |entries: &[&GroundItem]| entries.iter().map(|entry| entry.name().to_string()).collect::<Vec<_>>().join(", ");
+
+        let mut description = String::new();
+        if !open.is_empty() {
+            description.push_str(&format!("On the ground: {}\n", names(&open)));
+        }
+        if !hidden.is_empty() {
+            description.push_str(&format!("Hidden in your cache: {}\n", names(&hidden)));
+        }
+        description
+    }
+
+    /// Every cache the player has made, for the `caches` command
+    pub fn cache_report(&self, location_name: impl Fn(&str) -> String) -> String {
+        let mut caches: Vec<(String, Vec<&GroundItem>)> = self.by_location.iter()
+            .map(|(location, items)| (location_name(location), items.iter().filter(|entry| entry.is_hidden()).collect::<Vec<_>>()))
+            .filter(|(_, items)| !items.is_empty())
+            .collect();
+        if caches.is_empty() {
+            return "You have no hidden caches. Stash something with 'hide <item>'.".to_string();
+        }
+        caches.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut output = String::from("=== CACHES ===");
+        for (location, items) in caches {
+            output.push_str(&format!("\n{}:", location));
+            for entry in items {
+                let quality = if entry.concealment.unwrap_or(0.0) >= WELL_CONCEALED { "well hidden" } else { "poorly hidden" };
+                output.push_str(&format!("\n  • {} ({})", entry.name(), quality));
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::items::core::ItemType;
+    use rand::rngs::mock::StepRng;
+
+    fn item(name: &str) -> Item {
+        Item::new_basic(name.to_string(), format!("A {}", name), ItemType::Mundane)
+    }
+
+    #[test]
+    fn test_items_stay_until_scavenged() {
+        let mut ground = GroundItems::default();
+        ground.drop_item("market", item("lantern"), 0);
+        ground.hide_item("market", item("silver locket"), 0.9, 0);
+        ground.hide_item("market", item("old map"), 0.2, 0);
+        assert_eq!(ground.describe("market"), "On the ground: lantern\nHidden in your cache: silver locket, old map\n");
+
+        // A short absence, or time spent watching over them, costs nothing
+        let mut always = StepRng::new(0, 0);
+        assert!(ground.settle("market", GRACE_MINUTES, 3, &mut always).is_empty());
+        ground.watch("market", 5000);
+        assert!(ground.settle("market", 5000 + GRACE_MINUTES, 3, &mut always).is_empty());
+
+        // After a long absence only the well-hidden cache survives
+        let messages = ground.settle("market", 20000, 3, &mut always);
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].contains("lantern"));
+        assert!(messages[1].starts_with("Your cache has been disturbed"));
+        assert!(ground.cache_report(str::to_string).contains("market:\n  • silver locket (well hidden)"));
+
+        let taken = ground.take("market", "Locket").unwrap();
+        assert_eq!(taken.item.properties.name, "silver locket");
+        assert!(ground.at("market").is_empty());
+        assert!(ground.cache_report(str::to_string).starts_with("You have no hidden caches"));
+    }
+}
@@ -0,0 +1,217 @@
+//! Artifacts with scripted behaviors
+//!
+//! Unique items can react to what the player does. Content gives an item a
+//! behavior by name in its `artifact_behavior` custom property, with the
+//! behavior's parameters in further `artifact_<param>` properties, so a
+//! quest-reward artifact is defined entirely in the database. The registry
+//! maps behavior names to the code that builds them.
+//!
+//! Behaviors hook into three events:
+//! - `on_equip`, when the item is equipped
+//! - `on_cast`, after every spell while the item is carried or worn
+//! - `on_enter_location`, on arriving somewhere new
+//!
+//! Equipment only acts while equipped; other items act while carried.
+
+use super::core::{Item, ItemType};
+use crate::core::world_state::Location;
+use crate::core::Player;
+use std::collections::HashMap;
+
+/// Custom property naming an item's behavior
+pub const BEHAVIOR_PROPERTY: &str = "artifact_behavior";
+/// Prefix of custom properties holding behavior parameters
+const PARAM_PREFIX: &str = "artifact_";
+
+/// A behavior parameter from an item's custom properties
+fn param<T: std::str::FromStr>(item: &Item, name: &str) -> Option<T> {
+    item.get_custom_property(&format!("{}{}", PARAM_PREFIX, name))?.parse().ok()
+}
+
+/// Bespoke effects of an artifact; every hook does nothing unless overridden
+pub trait ArtifactBehavior {
+    fn on_equip(&self, _item: &Item, _player: &mut Player) -> Option<String> {
+        None
+    }
+
+    fn on_cast(&self, _item: &Item, _player: &mut Player, _spell_type: &str, _success: bool) -> Option<String> {
+        None
+    }
+
+    fn on_enter_location(&self, _item: &Item, _player: &mut Player, _location: &Location) -> Option<String> {
+        None
+    }
+}
+
+/// Returns some of the energy spent on a successful spell
+///
+/// Parameters: `energy` returned (default 3), and optionally the `spell`
+/// type it answers to.
+struct EnergyEcho {
+    energy: i32,
+    spell: Option<String>,
+}
+
+impl ArtifactBehavior for EnergyEcho {
+    fn on_cast(&self, item: &Item, player: &mut Player, spell_type: &str, success: bool) -> Option<String> {
+        if !success || self.spell.as_deref().is_some_and(|spell| spell != spell_type) {
+            return None;
+        }
+        let before = player.mental_state.current_energy;
+        player.mental_state.current_energy = (before + self.energy).min(player.mental_state.max_energy);
+        let restored = player.mental_state.current_energy - before;
+        (restored > 0).then(|| format!("The {} echoes your spell back to you. (Energy +{})", item.properties.name, restored))
+    }
+}
+
+/// Sounds the dominant frequency of each place the player enters
+struct FrequencySense;
+
+impl ArtifactBehavior for FrequencySense {
+    fn on_enter_location(&self, item: &Item, _player: &mut Player, location: &Location) -> Option<String> {
+        let properties = &location.magical_properties;
+        let mut message = match properties.dominant_frequency {
+            Some(frequency) => format!("The {} sings a clear note: this place resonates at frequency {}.", item.properties.name, frequency),
+            None => format!("The {} stays silent; nothing here dominates the resonance.", item.properties.name),
+        };
+        if properties.interference > 0.25 {
+            message.push_str(" The note wavers under heavy interference.");
+        }
+        Some(message)
+    }
+}
+
+/// Deepens understanding of a theory to at least a floor when first worn
+///
+/// Parameters: `theory` (default harmonic fundamentals) and `floor`
+/// (default 0.1).
+struct Attunement {
+    theory: String,
+    floor: f32,
+}
+
+impl ArtifactBehavior for Attunement {
+    fn on_equip(&self, item: &Item, player: &mut Player) -> Option<String> {
+        if player.theory_understanding(&self.theory) >= self.floor {
+            return None;
+        }
+        player.knowledge.theories.insert(self.theory.clone(), self.floor);
+        Some(format!(
+            "As the {} settles against you, {} comes into focus. ({} understanding now {:.0}%)",
+            item.properties.name, self.theory.replace('_', " "), self.theory.replace('_', " "), self.floor * 100.0
+        ))
+    }
+}
+
+/// Builds a behavior from an item's parameters
+pub type BehaviorFactory = fn(&Item) -> Box<dyn ArtifactBehavior>;
+
+/// Every behavior content can give an artifact
+pub struct ArtifactRegistry {
+    factories: HashMap<String, BehaviorFactory>,
+}
+
+impl Default for ArtifactRegistry {
+    fn default() -> Self {
+        let mut registry = Self { factories: HashMap::new() };
+        registry.register("energy_echo", |item| Box::new(EnergyEcho {
+            energy: param(item, "energy").unwrap_or(3),
+            spell: param(item, "spell"),
+        }));
+        registry.register("frequency_sense", |_| Box::new(FrequencySense));
+        registry.register("attunement", |item| Box::new(Attunement {
+            theory: param(item, "theory").unwrap_or_else(|| "harmonic_fundamentals".to_string()),
+            floor: param(item, "floor").unwrap_or(0.1),
+        }));
+        registry
+    }
+}
+
+impl ArtifactRegistry {
+    /// Add a behavior under a name
+    pub fn register(&mut self, name: &str, factory: BehaviorFactory) {
+        self.factories.insert(name.to_string(), factory);
+    }
+
+    /// The behavior of an item, if it has one the registry knows
+    pub fn behavior(&self, item: &Item) -> Option<Box<dyn ArtifactBehavior>> {
+        let name = item.get_custom_property(BEHAVIOR_PROPERTY)?;
+        self.factories.get(name).map(|factory| factory(item))
+    }
+
+    /// Artifacts currently able to act, with their behaviors
+    fn active(&self, player: &Player) -> Vec<(Item, Box<dyn ArtifactBehavior>)> {
+        let Some(items) = player.enhanced_item_system() else {
+            return Vec::new();
+        };
+        let equipped = items.equipment_manager.get_equipped_items();
+        let mut active: Vec<(Item, Box<dyn ArtifactBehavior>)> = items.inventory_manager.items.iter()
+            .filter(|(id, item)| !matches!(item.item_type, ItemType::Equipment(_)) || equipped.contains(id))
+            .filter(|(_, item)| item.is_usable())
+            .filter_map(|(_, item)| self.behavior(item).map(|behavior| (item.clone(), behavior)))
+            .collect();
+        active.sort_by(|(a, _), (b, _)| a.properties.name.cmp(&b.properties.name));
+        active
+    }
+
+    /// Run an item's equip hook
+    pub fn on_equip(&self, player: &mut Player, item_id: &str) -> Option<String> {
+        let item = player.enhanced_item_system()?.inventory_manager.get_item(&item_id.to_string())?.clone();
+        self.behavior(&item)?.on_equip(&item, player)
+    }
+
+    /// Run every active artifact's cast hook
+    pub fn on_cast(&self, player: &mut Player, spell_type: &str, success: bool) -> Vec<String> {
+        self.active(player).into_iter()
+            .filter_map(|(item, behavior)| behavior.on_cast(&item, player, spell_type, success))
+            .collect()
+    }
+
+    /// Run every active artifact's arrival hook
+    pub fn on_enter_location(&self, player: &mut Player, location: &Location) -> Vec<String> {
+        self.active(player).into_iter()
+            .filter_map(|(item, behavior)| behavior.on_enter_location(&item, player, location))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn artifact(name: &str, behavior: &str, params: &[(&str, &str)]) -> Item {
+        let mut item = Item::new_basic(name.to_string(), "Odd.".to_string(), ItemType::Artifact { properties: String::new() });
+        item.set_custom_property(BEHAVIOR_PROPERTY.to_string(), behavior.to_string());
+        for (key, value) in params {
+            item.set_custom_property(format!("{}{}", PARAM_PREFIX, key), value.to_string());
+        }
+        item
+    }
+
+    #[test]
+    fn test_artifacts_run_their_content_defined_behaviors() {
+        let registry = ArtifactRegistry::default();
+        let mut player = Player::new("Ada".to_string());
+        player.add_enhanced_item(artifact("Echo Prism", "energy_echo", &[("energy", "5"), ("spell", "light")])).unwrap();
+        player.add_enhanced_item(artifact("Harmonic Keystone", "frequency_sense", &[])).unwrap();
+        player.add_enhanced_item(artifact("Plain Rock", "no_such_behavior", &[])).unwrap();
+
+        player.mental_state.current_energy = 10;
+        assert!(registry.on_cast(&mut player, "healing", true).is_empty());
+        assert!(registry.on_cast(&mut player, "light", false).is_empty());
+        assert_eq!(registry.on_cast(&mut player, "light", true), vec!["The Echo Prism echoes your spell back to you. (Energy +5)"]);
+        assert_eq!(player.mental_state.current_energy, 15);
+
+        let mut hall = Location::new("hall".to_string(), "Hall".to_string(), "Echoing.".to_string());
+        hall.magical_properties.dominant_frequency = Some(4);
+        let messages = registry.on_enter_location(&mut player, &hall);
+        assert_eq!(messages, vec!["The Harmonic Keystone sings a clear note: this place resonates at frequency 4."]);
+
+        let pendant = artifact("Attuned Pendant", "attunement", &[("theory", "mental_resonance"), ("floor", "0.2")]);
+        let pendant_id = pendant.id.clone();
+        player.add_enhanced_item(pendant).unwrap();
+        assert!(registry.on_equip(&mut player, &pendant_id).unwrap().contains("mental resonance"));
+        assert_eq!(player.theory_understanding("mental_resonance"), 0.2);
+        assert!(registry.on_equip(&mut player, &pendant_id).is_none());
+    }
+}
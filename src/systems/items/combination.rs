@@ -0,0 +1,561 @@
+//! Combining two carried items, and taking combined items apart again
+//!
+//! Combination rules are fixed content held by the item system. Each rule
+//! names the two items it joins and what comes of them:
+//! - a new item, or one of several possible items
+//! - an enhancement of the first item, which is kept while the second is used up
+//!
+//! Rules may need a theory, an attribute, a tool, a place, mental energy and
+//! time. An attempt that fails goes wrong in the way its rule says: it fizzles
+//! and nothing is lost, it spoils the items, or it lashes back at the player.
+//!
+//! Items made by combination remember their rule, so they can be deconstructed
+//! to salvage some of what went into them.
+
+use super::core::{Item, ItemEffect, ItemType};
+use super::crafting::{carried, has_tool, normalize};
+use super::interactions::{CombinationFailure, CombinationResult, CombinationRule, InteractionConditions, ItemEnhancement};
+use crate::core::Player;
+use crate::GameResult;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Custom property naming the rule an item was combined by
+pub const COMBINATION_PROPERTY: &str = "combination";
+/// Minutes a combination takes when its rule gives no time
+const DEFAULT_MINUTES: i32 = 10;
+/// Minutes spent taking an item apart
+pub const DECONSTRUCT_MINUTES: i32 = 15;
+/// Chance of recovering each component when taking an item apart
+const SALVAGE_CHANCE: f32 = 0.5;
+/// Lowest and highest chance an attempt can have
+const MIN_CHANCE: f32 = 0.05;
+const MAX_CHANCE: f32 = 0.95;
+
+/// ID and name of the carried item best matching a name: exact first, then partial
+pub fn find_carried(player: &Player, query: &str) -> Option<(String, String)> {
+    let query = normalize(query);
+    if let Some((id, _)) = carried(player, &query) {
+        let name = player.enhanced_item_system()?.inventory_manager.get_item(&id)?.properties.name.clone();
+        return Some((id, name));
+    }
+    let items = player.enhanced_item_system()?;
+    let mut matches: Vec<&Item> = items.inventory_manager.get_all_items()
+        .into_iter()
+        .filter(|item| normalize(&item.properties.name).contains(&query))
+        .collect();
+    matches.sort_by(|a, b| a.properties.name.cmp(&b.properties.name));
+    matches.first().map(|item| (item.id.clone(), item.properties.name.clone()))
+}
+
+/// Name of the carried item matching a query, if any
+pub fn carried_name(player: &Player, query: &str) -> Option<String> {
+    find_carried(player, query).map(|(_, name)| name)
+}
+
+/// The rule that joins two carried items, in either order
+pub fn find_rule<'a>(
+    rules: &'a HashMap<String, CombinationRule>,
+    player: &Player,
+    first: &str,
+    second: &str,
+) -> Option<(&'a String, &'a CombinationRule)> {
+    let first = normalize(&carried_name(player, first)?);
+    let second = normalize(&carried_name(player, second)?);
+    let mut found: Vec<(&String, &CombinationRule)> = rules.iter()
+        .filter(|(_, rule)| match rule.combinable_items.as_slice() {
+            [a, b] => {
+                let (a, b) = (normalize(a), normalize(b));
+                (a == first && b == second) || (a == second && b == first)
+            }
+            _ => false,
+        })
+        .collect();
+    found.sort_by(|a, b| a.0.cmp(b.0));
+    found.into_iter().next()
+}
+
+fn attribute(player: &Player, name: &str) -> i32 {
+    match name {
+        "mental_acuity" => player.attributes.mental_acuity,
+        "resonance_sensitivity" => player.attributes.resonance_sensitivity,
+        _ => 0,
+    }
+}
+
+/// Reasons the player can't attempt a rule right now
+pub fn missing(rule: &CombinationRule, player: &Player, location: &str) -> Vec<String> {
+    let mut missing = Vec::new();
+    let mut needed: HashMap<String, i32> = HashMap::new();
+    for item in &rule.combinable_items {
+        *needed.entry(normalize(item)).or_insert(0) += 1;
+    }
+    let mut needed: Vec<(String, i32)> = needed.into_iter().collect();
+    needed.sort();
+    for (item, quantity) in needed {
+        let held = carried(player, &item).map_or(0, |(_, count)| count);
+        if held < quantity {
+            missing.push(format!("{} x{} (carrying {})", item, quantity, held));
+        }
+    }
+
+    let conditions = &rule.requirements;
+    for theory in &conditions.required_theories {
+        if player.theory_understanding(theory) <= 0.0 {
+            missing.push(format!("some understanding of {}", theory.replace('_', " ")));
+        }
+    }
+    let mut attributes: Vec<(&String, &i32)> = conditions.min_attributes.iter().collect();
+    attributes.sort();
+    for (name, required) in attributes {
+        if attribute(player, name) < *required {
+            missing.push(format!("{} {}", name.replace('_', " "), required));
+        }
+    }
+    for tool in &conditions.required_tools {
+        if !has_tool(player, tool) {
+            missing.push(format!("a {} tool", tool));
+        }
+    }
+    if let Some(environment) = &conditions.required_environment {
+        if environment != location {
+            missing.push(format!("to be at the {}", environment.replace('_', " ")));
+        }
+    }
+    if player.effective_mental_energy() < conditions.energy_cost {
+        missing.push(format!("{} mental energy", conditions.energy_cost));
+    }
+    missing
+}
+
+/// Chance an attempt at a rule succeeds
+pub fn success_chance(rule: &CombinationRule, player: &Player) -> f32 {
+    let theories = &rule.requirements.required_theories;
+    let skill = if theories.is_empty() {
+        0.0
+    } else {
+        theories.iter().map(|theory| player.theory_understanding(theory)).sum::<f32>() / theories.len() as f32 * 0.3
+    };
+    let focus = (player.attributes.mental_acuity - 25) as f32 / 250.0;
+    (rule.base_success_rate + skill + focus).clamp(MIN_CHANCE, MAX_CHANCE)
+}
+
+/// Minutes an attempt at a rule takes
+pub fn minutes(rule: &CombinationRule) -> i32 {
+    if rule.requirements.time_cost > 0 {
+        rule.requirements.time_cost
+    } else {
+        DEFAULT_MINUTES
+    }
+}
+
+/// What came of a combination attempt
+#[derive(Debug, Clone, PartialEq)]
+pub enum Combined {
+    /// A new item, by name
+    Made(String),
+    /// The first item was improved, by name
+    Enhanced(String),
+    /// The attempt failed
+    Failed(CombinationFailure),
+}
+
+/// Outcome of a combination attempt
+#[derive(Debug, Clone)]
+pub struct CombineOutcome {
+    pub chance: f32,
+    pub result: Combined,
+    pub minutes: i32,
+}
+
+fn remove_one(player: &mut Player, name: &str) -> GameResult<()> {
+    let (item_id, _) = carried(player, name)
+        .ok_or_else(|| crate::GameError::InsufficientResources(format!("Ran out of {}", name)))?;
+    player.remove_enhanced_item(&item_id)?;
+    Ok(())
+}
+
+fn enhance(item: &mut Item, enhancement: &ItemEnhancement) {
+    if let Some(change) = enhancement.durability_change {
+        item.properties.max_durability = (item.properties.max_durability + change.max(0)).max(1);
+        item.properties.durability = (item.properties.durability + change).clamp(0, item.properties.max_durability);
+    }
+    if let Some(multiplier) = enhancement.value_multiplier {
+        item.properties.value = (item.properties.value as f32 * multiplier).round() as i32;
+    }
+    for (key, value) in &enhancement.property_changes {
+        item.set_custom_property(key.clone(), value.clone());
+    }
+}
+
+/// Attempt a combination rule with the player's items
+pub fn combine(
+    rule_id: &str,
+    rule: &CombinationRule,
+    player: &mut Player,
+    location: &str,
+    rng: &mut impl Rng,
+) -> GameResult<CombineOutcome> {
+    let missing = missing(rule, player, location);
+    if !missing.is_empty() {
+        return Err(crate::GameError::InsufficientResources(format!(
+            "You can't combine those yet. Missing: {}.", missing.join(", ")
+        )).into());
+    }
+
+    let energy = rule.requirements.energy_cost;
+    if energy > 0 {
+        player.use_mental_energy(energy, energy / 2)?;
+    }
+
+    let chance = success_chance(rule, player);
+    let minutes = minutes(rule);
+    // An enhancement keeps its target; everything else is used up
+    let kept = match &rule.result {
+        CombinationResult::Enhancement { target_item, .. } => Some(*target_item),
+        _ => None,
+    };
+
+    if rng.gen::<f32>() >= chance {
+        match rule.failure {
+            CombinationFailure::Fizzle => {}
+            CombinationFailure::Spoil | CombinationFailure::Backlash { .. } => {
+                for (index, item) in rule.combinable_items.iter().enumerate() {
+                    if kept != Some(index) {
+                        remove_one(player, item)?;
+                    }
+                }
+            }
+        }
+        if let CombinationFailure::Backlash { damage } = rule.failure {
+            player.health.take_damage(damage);
+        }
+        return Ok(CombineOutcome { chance, result: Combined::Failed(rule.failure), minutes });
+    }
+
+    for (index, item) in rule.combinable_items.iter().enumerate() {
+        if kept != Some(index) {
+            remove_one(player, item)?;
+        }
+    }
+
+    let result = match &rule.result {
+        CombinationResult::SingleItem(item) => produce(item, rule_id, player)?,
+        CombinationResult::MultipleOutcomes(outcomes) => {
+            let total: f32 = outcomes.iter().map(|(_, weight)| weight).sum();
+            let mut roll = rng.gen::<f32>() * total;
+            let item = outcomes.iter()
+                .find(|(_, weight)| {
+                    roll -= weight;
+                    roll < 0.0
+                })
+                .or(outcomes.last())
+                .map(|(item, _)| item)
+                .ok_or_else(|| crate::GameError::InvalidInput(format!("Combination {} has no outcomes", rule_id)))?;
+            produce(item, rule_id, player)?
+        }
+        CombinationResult::Enhancement { target_item, enhancement } => {
+            let target = rule.combinable_items.get(*target_item)
+                .ok_or_else(|| crate::GameError::InvalidInput(format!("Combination {} has no target", rule_id)))?;
+            let (item_id, _) = carried(player, target)
+                .ok_or_else(|| crate::GameError::InsufficientResources(format!("Ran out of {}", target)))?;
+            let item = player.enhanced_item_system_mut()
+                .and_then(|items| items.inventory_manager.items.get_mut(&item_id))
+                .ok_or_else(|| crate::GameError::InvalidInput("Item not found".to_string()))?;
+            enhance(item, enhancement);
+            Combined::Enhanced(item.properties.name.clone())
+        }
+    };
+
+    Ok(CombineOutcome { chance, result, minutes })
+}
+
+fn produce(template: &Item, rule_id: &str, player: &mut Player) -> GameResult<Combined> {
+    let mut item = template.clone();
+    item.set_custom_property(COMBINATION_PROPERTY.to_string(), rule_id.to_string());
+    let name = item.properties.name.clone();
+    player.add_enhanced_item(item)?;
+    Ok(Combined::Made(name))
+}
+
+/// A plain material standing in for a salvaged component
+fn component(name: &str) -> Item {
+    Item::new_basic(name.to_string(), format!("Salvaged {}.", name), ItemType::Material {
+        material_type: name.replace(' ', "_"),
+        quality: 0.5,
+    })
+}
+
+/// Outcome of taking an item apart
+#[derive(Debug, Clone)]
+pub struct Deconstruction {
+    pub item: String,
+    pub recovered: Vec<String>,
+    pub lost: Vec<String>,
+}
+
+/// Take apart an item made by combination, salvaging some of its components
+pub fn deconstruct(
+    rules: &HashMap<String, CombinationRule>,
+    player: &mut Player,
+    query: &str,
+    rng: &mut impl Rng,
+) -> GameResult<Deconstruction> {
+    let (item_id, name) = find_carried(player, query)
+        .ok_or_else(|| crate::GameError::ContentNotFound(format!("You aren't carrying any {}.", query)))?;
+    let items = player.enhanced_item_system()
+        .ok_or_else(|| crate::GameError::ContentNotFound(format!("You aren't carrying any {}.", query)))?;
+    if items.is_equipped(&item_id) {
+        return Err(crate::GameError::InvalidCommand(format!("Unequip the {} first.", name)).into());
+    }
+    let rule = items.inventory_manager.get_item(&item_id)
+        .and_then(|item| item.get_custom_property(COMBINATION_PROPERTY))
+        .and_then(|rule_id| rules.get(rule_id))
+        .ok_or_else(|| crate::GameError::InvalidInput(format!("The {} can't be taken apart.", name)))?;
+    let components = rule.combinable_items.clone();
+
+    player.remove_enhanced_item(&item_id)?;
+    let mut outcome = Deconstruction { item: name, recovered: Vec::new(), lost: Vec::new() };
+    for component_name in components {
+        if rng.gen::<f32>() < SALVAGE_CHANCE {
+            player.add_enhanced_item(component(&component_name))?;
+            outcome.recovered.push(component_name);
+        } else {
+            outcome.lost.push(component_name);
+        }
+    }
+    Ok(outcome)
+}
+
+fn starter_rule(a: &str, b: &str, result: CombinationResult, base_success_rate: f32, failure: CombinationFailure) -> CombinationRule {
+    CombinationRule {
+        combinable_items: vec![a.to_string(), b.to_string()],
+        result,
+        base_success_rate,
+        requirements: InteractionConditions::default(),
+        failure,
+    }
+}
+
+/// The combinations every player can discover
+pub fn starter_rules() -> HashMap<String, CombinationRule> {
+    use CombinationFailure::{Backlash, Fizzle, Spoil};
+    use CombinationResult::{Enhancement, MultipleOutcomes, SingleItem};
+
+    let material = |name: &str, description: &str| Item::new_basic(name.to_string(), description.to_string(), ItemType::Material {
+        material_type: name.replace(' ', "_"),
+        quality: 0.6,
+    });
+    let consumable = |name: &str, description: &str, effect: ItemEffect| {
+        Item::new_consumable(name.to_string(), description.to_string(), effect, 1)
+    };
+    let tool = |name: &str, description: &str, function: &str| {
+        Item::new_tool(name.to_string(), description.to_string(), function.to_string())
+    };
+    let enhancement = |durability: Option<i32>, value: Option<f32>, property: (&str, &str)| ItemEnhancement {
+        durability_change: durability,
+        value_multiplier: value,
+        added_effects: Vec::new(),
+        property_changes: HashMap::from([(property.0.to_string(), property.1.to_string())]),
+    };
+
+    let mut rules = HashMap::new();
+    let mut add = |id: &str, rule: CombinationRule| {
+        rules.insert(id.to_string(), rule);
+    };
+
+    let mut rule = starter_rule("crystal fragment", "resonant solvent", SingleItem(consumable(
+        "crystal tincture", "Crystal dissolved into solvent; a sip sharpens a tired mind.", ItemEffect::RestoreEnergy(15),
+    )), 0.6, Spoil);
+    rule.requirements.required_theories.push("crystal_structures".to_string());
+    add("crystal_tincture", rule);
+
+    let mut lens = tool("framed resonance lens", "A resonance lens set in brass, steady enough to hold to the eye.", "detection");
+    lens.properties.max_durability = 250;
+    lens.properties.durability = 250;
+    let mut rule = starter_rule("resonance lens", "brass frame", SingleItem(lens), 0.8, Fizzle);
+    rule.requirements.time_cost = 30;
+    add("framed_lens", rule);
+
+    let mut rule = starter_rule("powdered quartz", "resonant solvent", SingleItem(material(
+        "quartz slurry", "A glittering paste for coating optics.",
+    )), 0.85, Spoil);
+    rule.requirements.required_tools.push("grinding".to_string());
+    add("quartz_slurry", rule);
+
+    add("silverleaf_tea", starter_rule("silverleaf", "spring water", SingleItem(consumable(
+        "silverleaf tea", "A pale, bitter tea that eases aching limbs.", ItemEffect::ReduceFatigue(10),
+    )), 0.9, Fizzle));
+
+    let mut rule = starter_rule("brass rod", "copper wire", SingleItem(tool(
+        "resonance probe", "A wire-wound rod that picks up the hum of nearby resonance.", "probing",
+    )), 0.75, Fizzle);
+    rule.requirements.required_tools.push("tuning".to_string());
+    rule.requirements.time_cost = 25;
+    add("resonance_probe", rule);
+
+    add("etched_glass", starter_rule("glass blank", "resonant solvent", SingleItem(material(
+        "etched glass", "Glass frosted by solvent into fine, light-scattering grooves.",
+    )), 0.7, Spoil));
+
+    let mut rule = starter_rule("tuning fork", "crystal fragment", Enhancement {
+        target_item: 0,
+        enhancement: enhancement(Some(30), Some(1.5), ("attuned", "true")),
+    }, 0.55, Spoil);
+    rule.requirements.required_theories.push("harmonic_fundamentals".to_string());
+    rule.requirements.energy_cost = 10;
+    add("attuned_fork", rule);
+
+    let mut rule = starter_rule("crystal fragment", "crystal fragment", MultipleOutcomes(vec![
+        (material("fused crystal shard", "Two fragments grown into one clear shard."), 0.7),
+        (material("cracked crystal shard", "A fused shard with a fault running through it."), 0.3),
+    ]), 0.5, Backlash { damage: 5 });
+    rule.requirements.required_theories.push("crystal_structures".to_string());
+    rule.requirements.energy_cost = 15;
+    rule.requirements.time_cost = 30;
+    add("fused_shard", rule);
+
+    add("quartz_water", starter_rule("spring water", "powdered quartz", SingleItem(consumable(
+        "quartz water", "Spring water clouded with quartz; it tingles on the tongue.", ItemEffect::RestoreEnergy(10),
+    )), 0.85, Fizzle));
+
+    let mut rule = starter_rule("focus tonic", "silverleaf", SingleItem(consumable(
+        "calming tonic", "Focus tonic softened with silverleaf, for long nights of study.",
+        ItemEffect::Multiple(vec![ItemEffect::RestoreEnergy(20), ItemEffect::ReduceFatigue(20)]),
+    )), 0.6, Spoil);
+    rule.requirements.required_theories.push("mental_resonance".to_string());
+    add("calming_tonic", rule);
+
+    let mut rule = starter_rule("healing salve", "spring water", SingleItem(consumable(
+        "healing draught", "Salve thinned into a draught that works from the inside.", ItemEffect::HealDamage(25),
+    )), 0.7, Spoil);
+    rule.requirements.required_theories.push("bio_resonance".to_string());
+    add("healing_draught", rule);
+
+    add("polishing_stone", starter_rule("river stone", "powdered quartz", SingleItem(tool(
+        "polishing stone", "A river stone dressed with quartz grit, for finishing crystal faces.", "polishing",
+    )), 0.9, Fizzle));
+
+    let mut rule = starter_rule("copper wire", "crystal fragment", MultipleOutcomes(vec![
+        (tool("signal crystal", "A wired crystal that carries a voice to its twin.", "communication"), 0.6),
+        (material("humming wire tangle", "Wire that took the resonance but not the shape."), 0.4),
+    ]), 0.5, Backlash { damage: 3 });
+    rule.requirements.required_theories.push("sympathetic_networks".to_string());
+    rule.requirements.energy_cost = 10;
+    add("signal_crystal", rule);
+
+    let mut rule = starter_rule("resonance lens", "quartz slurry", Enhancement {
+        target_item: 0,
+        enhancement: enhancement(None, Some(1.3), ("coated", "true")),
+    }, 0.65, Spoil);
+    rule.requirements.required_theories.push("light_manipulation".to_string());
+    add("coated_lens", rule);
+
+    add("cleaned_fork", starter_rule("tuning fork", "resonant solvent", Enhancement {
+        target_item: 0,
+        enhancement: enhancement(Some(50), None, ("cleaned", "true")),
+    }, 0.9, Fizzle));
+
+    let mut rule = starter_rule("glass blank", "crystal fragment", MultipleOutcomes(vec![
+        (tool("crystal prism", "A prism that splits light into its resonant bands.", "light"), 0.6),
+        (material("cracked glass", "Glass that could not hold the crystal's tension."), 0.4),
+    ]), 0.55, Spoil);
+    rule.requirements.required_theories.push("light_manipulation".to_string());
+    rule.requirements.time_cost = 20;
+    add("crystal_prism", rule);
+
+    let mut rule = starter_rule("crystal fragment", "river stone", SingleItem(material(
+        "crystal dust", "Crystal ground fine on a river stone.",
+    )), 0.8, Fizzle);
+    rule.requirements.required_tools.push("grinding".to_string());
+    add("crystal_dust", rule);
+
+    add("insulated_wire", starter_rule("beeswax", "copper wire", SingleItem(material(
+        "insulated wire", "Copper wire sheathed in wax so it carries resonance without bleeding it.",
+    )), 0.9, Fizzle));
+
+    let mut rule = starter_rule("insulated wire", "brass rod", SingleItem(tool(
+        "resonance coil", "A tight coil that gathers and amplifies ambient resonance.", "amplification",
+    )), 0.5, Backlash { damage: 4 });
+    rule.requirements.required_theories.push("resonance_amplification".to_string());
+    rule.requirements.required_environment = Some("harmonic_testing_chambers".to_string());
+    rule.requirements.min_attributes.insert("mental_acuity".to_string(), 30);
+    rule.requirements.energy_cost = 20;
+    rule.requirements.time_cost = 45;
+    add("resonance_coil", rule);
+
+    add("restocked_repair_kit", starter_rule("repair kit", "brass rod", Enhancement {
+        target_item: 0,
+        enhancement: enhancement(Some(40), None, ("restocked", "true")),
+    }, 0.95, Fizzle));
+
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    fn material(name: &str) -> Item {
+        Item::new_basic(name.to_string(), format!("Some {}", name), ItemType::Material {
+            material_type: "test".to_string(),
+            quality: 0.5,
+        })
+    }
+
+    #[test]
+    fn test_combine_and_deconstruct() {
+        let rules = starter_rules();
+        assert_eq!(rules.len(), 20);
+        let mut player = Player::new("Test".to_string());
+        player.add_enhanced_item(material("silverleaf")).unwrap();
+        player.add_enhanced_item(material("spring water")).unwrap();
+
+        // Rules match in either order and by partial names
+        let (rule_id, rule) = find_rule(&rules, &player, "water", "silverleaf").unwrap();
+        assert_eq!(rule_id, "silverleaf_tea");
+        assert!(find_rule(&rules, &player, "silverleaf", "beeswax").is_none());
+
+        // A fizzle leaves the items alone
+        let rule = rule.clone();
+        let outcome = combine(rule_id, &rule, &mut player, "hall", &mut StepRng::new(u64::MAX, 0)).unwrap();
+        assert_eq!(outcome.result, Combined::Failed(CombinationFailure::Fizzle));
+        assert!(carried(&player, "silverleaf").is_some());
+
+        let outcome = combine("silverleaf_tea", &rule, &mut player, "hall", &mut StepRng::new(0, 0)).unwrap();
+        assert_eq!(outcome.result, Combined::Made("silverleaf tea".to_string()));
+        assert!(carried(&player, "silverleaf").is_none());
+
+        // Made items come apart again; a zero roll salvages everything
+        let taken = deconstruct(&rules, &mut player, "tea", &mut StepRng::new(0, 0)).unwrap();
+        assert_eq!(taken.recovered, vec!["silverleaf", "spring water"]);
+        assert!(carried(&player, "silverleaf tea").is_none());
+        assert!(deconstruct(&rules, &mut player, "silverleaf", &mut StepRng::new(0, 0)).is_err());
+
+        // Backlash spoils the items and hurts
+        let shard = rules["fused_shard"].clone();
+        player.add_enhanced_item(material("crystal fragment")).unwrap();
+        assert!(missing(&shard, &player, "hall").iter().any(|m| m.contains("crystal fragment x2 (carrying 1)")));
+        player.add_enhanced_item(material("crystal fragment")).unwrap();
+        player.knowledge.theories.insert("crystal_structures".to_string(), 0.1);
+        let health = player.health.current;
+        let outcome = combine("fused_shard", &shard, &mut player, "hall", &mut StepRng::new(u64::MAX, 0)).unwrap();
+        assert_eq!(outcome.result, Combined::Failed(CombinationFailure::Backlash { damage: 5 }));
+        assert_eq!(player.health.current, health - 5);
+        assert!(carried(&player, "crystal fragment").is_none());
+
+        // Enhancements keep the target and improve it
+        let fork = rules["cleaned_fork"].clone();
+        let mut tuning_fork = Item::new_tool("tuning fork".to_string(), "A fork".to_string(), "tuning".to_string());
+        tuning_fork.properties.durability = 10;
+        player.add_enhanced_item(tuning_fork).unwrap();
+        player.add_enhanced_item(material("resonant solvent")).unwrap();
+        let outcome = combine("cleaned_fork", &fork, &mut player, "hall", &mut StepRng::new(0, 0)).unwrap();
+        assert_eq!(outcome.result, Combined::Enhanced("tuning fork".to_string()));
+        let (fork_id, _) = carried(&player, "tuning fork").unwrap();
+        let fork_item = player.enhanced_item_system().unwrap().inventory_manager.get_item(&fork_id).unwrap();
+        assert_eq!(fork_item.properties.durability, 60);
+        assert!(carried(&player, "resonant solvent").is_none());
+    }
+}
@@ -147,7 +147,7 @@ pub enum ItemEffect {
         understanding_boost: f32,
     },
 
-    /// Heal physical damage (for future health system)
+    /// Heal physical damage
     HealDamage(i32),
 
     /// Grant temporary magical ability
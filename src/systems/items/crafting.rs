@@ -0,0 +1,411 @@
+//! Crafting consumables and tools from recipes
+//!
+//! Recipes are content stored in the database. Each one lists:
+//! - Ingredients consumed by the attempt, successful or not
+//! - Tools that must be carried but are not used up
+//! - An optional station found only at certain locations
+//! - An optional theory the crafter must understand, which also drives the
+//!   skill check
+//!
+//! A successful attempt produces an item in one of four quality tiers.
+//! Quality scales the strength of a consumable's effect, or a tool's
+//! durability, and always its value.
+
+use super::core::{Item, ItemEffect, ItemRarity, ItemType};
+use crate::core::Player;
+use crate::GameResult;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Lowest and highest chance a skill check can have
+const MIN_CHANCE: f32 = 0.05;
+const MAX_CHANCE: f32 = 0.95;
+
+/// A material consumed by a recipe
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ingredient {
+    pub item: String,
+    pub quantity: i32,
+}
+
+/// A workstation that only exists at certain locations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Station {
+    pub name: String,
+    /// Location IDs where the station can be used
+    pub locations: Vec<String>,
+}
+
+/// What a recipe makes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RecipeOutput {
+    Consumable { effect: ItemEffect, uses: i32 },
+    Tool { function: String },
+}
+
+/// How to make one item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recipe {
+    pub id: String,
+    /// Name of the item produced
+    pub name: String,
+    pub description: String,
+    pub output: RecipeOutput,
+    pub ingredients: Vec<Ingredient>,
+    /// Tool functions or names that must be carried
+    pub tools: Vec<String>,
+    pub station: Option<Station>,
+    /// Theory the skill check draws on
+    pub theory: Option<String>,
+    /// Understanding of the theory needed to attempt the recipe at all
+    pub min_understanding: f32,
+    /// Base chance of success before skill
+    pub base_chance: f32,
+    /// Minutes an attempt takes
+    pub minutes: i32,
+}
+
+/// Quality of a crafted item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Quality {
+    Crude,
+    Standard,
+    Fine,
+    Masterwork,
+}
+
+impl Quality {
+    /// Quality earned by beating the skill check by `margin`
+    fn from_margin(margin: f32) -> Self {
+        if margin >= 0.5 {
+            Quality::Masterwork
+        } else if margin >= 0.3 {
+            Quality::Fine
+        } else if margin >= 0.1 {
+            Quality::Standard
+        } else {
+            Quality::Crude
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Quality::Crude => "crude",
+            Quality::Standard => "standard",
+            Quality::Fine => "fine",
+            Quality::Masterwork => "masterwork",
+        }
+    }
+
+    /// Multiplier on effect strength, durability and value
+    pub fn multiplier(&self) -> f32 {
+        match self {
+            Quality::Crude => 0.7,
+            Quality::Standard => 1.0,
+            Quality::Fine => 1.25,
+            Quality::Masterwork => 1.5,
+        }
+    }
+
+    fn rarity(&self) -> ItemRarity {
+        match self {
+            Quality::Crude | Quality::Standard => ItemRarity::Common,
+            Quality::Fine => ItemRarity::Uncommon,
+            Quality::Masterwork => ItemRarity::Rare,
+        }
+    }
+}
+
+/// Inventory names and ids are compared without case or underscores
+pub(super) fn normalize(name: &str) -> String {
+    name.trim().to_lowercase().replace('_', " ")
+}
+
+/// How many units of an item the player carries, and under which item id
+pub(super) fn carried(player: &Player, name: &str) -> Option<(String, i32)> {
+    let items = player.enhanced_item_system()?;
+    let manager = &items.inventory_manager;
+    let name = normalize(name);
+    manager.get_all_items()
+        .into_iter()
+        .find(|item| normalize(&item.properties.name) == name || normalize(&item.id) == name)
+        .map(|item| (item.id.clone(), manager.get_stack_size(&item.id)))
+}
+
+/// Whether the player carries a tool with this function or name
+pub(super) fn has_tool(player: &Player, tool: &str) -> bool {
+    let tool = normalize(tool);
+    player.enhanced_item_system().is_some_and(|items| {
+        items.inventory_manager.get_all_items().into_iter().any(|item| match &item.item_type {
+            ItemType::Tool { tool_function } => normalize(tool_function) == tool || normalize(&item.properties.name) == tool,
+            _ => false,
+        })
+    })
+}
+
+/// Scale an effect's strength by a quality multiplier
+fn scale_effect(effect: &ItemEffect, multiplier: f32) -> ItemEffect {
+    let scale = |amount: i32| ((amount as f32 * multiplier).round() as i32).max(1);
+    match effect {
+        ItemEffect::RestoreEnergy(amount) => ItemEffect::RestoreEnergy(scale(*amount)),
+        ItemEffect::ReduceFatigue(amount) => ItemEffect::ReduceFatigue(scale(*amount)),
+        ItemEffect::HealDamage(amount) => ItemEffect::HealDamage(scale(*amount)),
+        ItemEffect::TemporaryAttributeBoost { attribute, amount, duration } => ItemEffect::TemporaryAttributeBoost {
+            attribute: attribute.clone(),
+            amount: scale(*amount),
+            duration: *duration,
+        },
+        ItemEffect::Multiple(effects) => ItemEffect::Multiple(effects.iter().map(|e| scale_effect(e, multiplier)).collect()),
+        other => other.clone(),
+    }
+}
+
+impl Recipe {
+    /// Reasons the player can't attempt this recipe right now
+    pub fn missing(&self, player: &Player, location: &str) -> Vec<String> {
+        let mut missing = Vec::new();
+        for ingredient in &self.ingredients {
+            let held = carried(player, &ingredient.item).map_or(0, |(_, count)| count);
+            if held < ingredient.quantity {
+                missing.push(format!("{} x{} (carrying {})", ingredient.item, ingredient.quantity, held));
+            }
+        }
+        for tool in &self.tools {
+            if !has_tool(player, tool) {
+                missing.push(format!("a {} tool", tool));
+            }
+        }
+        if let Some(station) = &self.station {
+            if !station.locations.iter().any(|id| id == location) && !player.laboratory.has_bench_at(location) {
+                missing.push(format!("a {}", station.name));
+            }
+        }
+        if let Some(theory) = &self.theory {
+            if player.theory_understanding(theory) < self.min_understanding {
+                missing.push(format!("{:.0}% understanding of {}", self.min_understanding * 100.0, theory));
+            }
+        }
+        missing
+    }
+
+    /// Chance the attempt succeeds
+    pub fn success_chance(&self, player: &Player) -> f32 {
+        let skill = match &self.theory {
+            Some(theory) => (player.theory_understanding(theory) - self.min_understanding) * 0.8,
+            None => 0.0,
+        };
+        let focus = (player.attributes.mental_acuity - 25) as f32 / 250.0;
+        (self.base_chance + skill + focus).clamp(MIN_CHANCE, MAX_CHANCE)
+    }
+
+    /// Chance of success at a location, counting the player's own bench
+    pub fn success_chance_at(&self, player: &Player, location: &str) -> f32 {
+        (self.success_chance(player) + player.laboratory.crafting_bonus(location)).min(MAX_CHANCE)
+    }
+
+    /// The item this recipe makes at a given quality
+    pub fn produce(&self, quality: Quality) -> Item {
+        let name = match quality {
+            Quality::Standard => self.name.clone(),
+            other => format!("{} {}", other.name(), self.name),
+        };
+        let mut item = match &self.output {
+            RecipeOutput::Consumable { effect, uses } => {
+                Item::new_consumable(name, self.description.clone(), scale_effect(effect, quality.multiplier()), *uses)
+            }
+            RecipeOutput::Tool { function } => {
+                let mut tool = Item::new_tool(name, self.description.clone(), function.clone());
+                tool.properties.max_durability = (tool.properties.max_durability as f32 * quality.multiplier()) as i32;
+                tool.properties.durability = tool.properties.max_durability;
+                tool
+            }
+        };
+        item.properties.value = (item.properties.value as f32 * quality.multiplier()).round() as i32;
+        item.properties.rarity = quality.rarity();
+        item.set_custom_property("quality".to_string(), quality.name().to_string());
+        item.set_custom_property("recipe".to_string(), self.id.clone());
+        item
+    }
+
+    fn requirements(&self) -> String {
+        let mut parts: Vec<String> = self.ingredients.iter()
+            .map(|ingredient| format!("{} x{}", ingredient.item, ingredient.quantity))
+            .collect();
+        parts.extend(self.tools.iter().map(|tool| format!("{} tool", tool)));
+        if let Some(station) = &self.station {
+            parts.push(station.name.clone());
+        }
+        if let Some(theory) = &self.theory {
+            parts.push(format!("{} {:.0}%", theory, self.min_understanding * 100.0));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Outcome of a crafting attempt
+#[derive(Debug, Clone)]
+pub struct CraftOutcome {
+    pub recipe: String,
+    pub chance: f32,
+    /// The item made, if the attempt succeeded
+    pub item: Option<(Item, Quality)>,
+    pub minutes: i32,
+    /// Whether a failure went badly enough to leave its mark on the location
+    pub critical: bool,
+}
+
+/// Recipes the player can work from
+#[derive(Debug, Clone, Default)]
+pub struct CraftingSystem {
+    recipes: Vec<Recipe>,
+}
+
+impl CraftingSystem {
+    pub fn new(recipes: Vec<Recipe>) -> Self {
+        Self { recipes }
+    }
+
+    /// Load every recipe from the database
+    pub fn load(database: &crate::persistence::DatabaseManager) -> GameResult<Self> {
+        Ok(Self::new(database.load_recipes()?))
+    }
+
+    pub fn recipes(&self) -> &[Recipe] {
+        &self.recipes
+    }
+
+    /// Find a recipe by id, name or ingredient list
+    pub fn find(&self, query: &str) -> Option<&Recipe> {
+        let query = normalize(query);
+        self.recipes.iter()
+            .find(|recipe| normalize(&recipe.id) == query || normalize(&recipe.name) == query)
+            .or_else(|| self.recipes.iter().find(|recipe| normalize(&recipe.name).contains(&query)))
+    }
+
+    /// Find the recipe whose ingredients are exactly the named items
+    pub fn find_by_ingredients(&self, items: &[String]) -> Option<&Recipe> {
+        let mut wanted: Vec<String> = items.iter().map(|item| normalize(item)).collect();
+        wanted.sort();
+        self.recipes.iter().find(|recipe| {
+            let mut names: Vec<String> = recipe.ingredients.iter().map(|ingredient| normalize(&ingredient.item)).collect();
+            names.sort();
+            names.len() == wanted.len() && names.iter().zip(&wanted).all(|(name, item)| name.contains(item.as_str()))
+        })
+    }
+
+    /// Attempt a recipe, consuming its ingredients and adding any result to the inventory
+    pub fn craft(&self, recipe: &Recipe, player: &mut Player, location: &str, rng: &mut impl Rng) -> GameResult<CraftOutcome> {
+        let missing = recipe.missing(player, location);
+        if !missing.is_empty() {
+            return Err(crate::GameError::InsufficientResources(format!(
+                "You can't make {} yet. Missing: {}.", recipe.name, missing.join(", ")
+            )).into());
+        }
+
+        for ingredient in &recipe.ingredients {
+            for _ in 0..ingredient.quantity {
+                let (item_id, _) = carried(player, &ingredient.item)
+                    .ok_or_else(|| crate::GameError::InsufficientResources(format!("Ran out of {}", ingredient.item)))?;
+                player.remove_enhanced_item(&item_id)?;
+            }
+        }
+
+        let chance = recipe.success_chance_at(player, location);
+        let roll = rng.gen::<f32>();
+        let item = if roll < chance {
+            let quality = Quality::from_margin(chance - roll);
+            let item = recipe.produce(quality);
+            player.add_enhanced_item(item.clone())?;
+            Some((item, quality))
+        } else {
+            None
+        };
+
+        let critical = item.is_none() && roll - chance >= crate::systems::phenomena::CRITICAL_FAILURE_MARGIN;
+        Ok(CraftOutcome { recipe: recipe.name.clone(), chance, item, minutes: recipe.minutes, critical })
+    }
+
+    /// Every recipe and whether the player can make it here, for the `recipes` command
+    pub fn report(&self, player: &Player, location: &str) -> String {
+        if self.recipes.is_empty() {
+            return "You don't know any recipes.".to_string();
+        }
+
+        let mut output = String::from("=== RECIPES ===");
+        for recipe in &self.recipes {
+            let missing = recipe.missing(player, location);
+            let status = if missing.is_empty() {
+                format!("ready, {:.0}% chance", recipe.success_chance_at(player, location) * 100.0)
+            } else {
+                format!("missing {}", missing.join(", "))
+            };
+            output.push_str(&format!(
+                "\n\n{} ({} min)\n  {}\n  Needs: {}\n  {}",
+                recipe.name, recipe.minutes, recipe.description, recipe.requirements(), status
+            ));
+        }
+        output.push_str("\n\nCraft with 'craft <recipe>'. Ingredients are used up even if the attempt fails.");
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    fn salve() -> Recipe {
+        Recipe {
+            id: "healing_salve".to_string(),
+            name: "healing salve".to_string(),
+            description: "A soothing paste.".to_string(),
+            output: RecipeOutput::Consumable { effect: ItemEffect::HealDamage(20), uses: 1 },
+            ingredients: vec![Ingredient { item: "silverleaf".to_string(), quantity: 2 }],
+            tools: vec!["grinding".to_string()],
+            station: Some(Station { name: "alchemy bench".to_string(), locations: vec!["lab".to_string()] }),
+            theory: Some("bio_resonance".to_string()),
+            min_understanding: 0.2,
+            base_chance: 0.6,
+            minutes: 30,
+        }
+    }
+
+    fn material(name: &str) -> Item {
+        Item::new_basic(name.to_string(), format!("Some {}", name), ItemType::Material {
+            material_type: "herb".to_string(),
+            quality: 0.5,
+        })
+    }
+
+    #[test]
+    fn test_craft_checks_requirements_and_grades_quality() {
+        let crafting = CraftingSystem::new(vec![salve()]);
+        let recipe = crafting.find("Healing Salve").unwrap();
+        let mut player = Player::new("Test".to_string());
+
+        let missing = recipe.missing(&player, "hall");
+        assert_eq!(missing.len(), 4);
+        assert!(crafting.craft(recipe, &mut player, "hall", &mut StepRng::new(0, 0)).is_err());
+
+        player.add_enhanced_item(material("silverleaf")).unwrap();
+        player.add_enhanced_item(material("silverleaf")).unwrap();
+        player.add_enhanced_item(Item::new_tool("mortar".to_string(), "Stone.".to_string(), "grinding".to_string())).unwrap();
+        player.knowledge.theories.insert("bio_resonance".to_string(), 0.5);
+        assert_eq!(crafting.find_by_ingredients(&["silverleaf".to_string()]).unwrap().id, "healing_salve");
+        assert!(recipe.missing(&player, "lab").is_empty());
+        assert!(crafting.report(&player, "lab").contains("ready, 84% chance"));
+
+        // A zero roll beats the 84% check by a masterwork margin
+        let outcome = crafting.craft(recipe, &mut player, "lab", &mut StepRng::new(0, 0)).unwrap();
+        let (item, quality) = outcome.item.unwrap();
+        assert_eq!(quality, Quality::Masterwork);
+        assert!(!outcome.critical);
+        assert_eq!(item.properties.name, "masterwork healing salve");
+        assert!(matches!(item.item_type, ItemType::Consumable { effect: ItemEffect::HealDamage(30), .. }));
+        assert!(carried(&player, "silverleaf").is_none());
+        assert!(carried(&player, "masterwork healing salve").is_some());
+
+        assert_eq!(Quality::from_margin(0.15), Quality::Standard);
+        assert_eq!(recipe.produce(Quality::Crude).properties.value, 7);
+    }
+}
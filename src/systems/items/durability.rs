@@ -0,0 +1,196 @@
+//! Wear, breakage and repair
+//!
+//! Items wear down as they are used:
+//! - casting wears the focus gear worn on the hands, rings and neck
+//! - taking a hit in combat wears worn armour
+//! - every use of a tool wears the tool
+//!
+//! An item at zero durability is broken. Broken equipment gives none of its
+//! bonuses until it is repaired. Repairs come three ways: a repair kit
+//! restores a good share of durability, a field repair without one patches
+//! less and permanently weakens the item, and craftspeople restore an item
+//! fully for silver.
+
+use super::core::Item;
+use super::equipment::EquipmentSlot;
+use serde::{Deserialize, Serialize};
+
+/// Tool function of a repair kit
+pub const REPAIR_KIT_FUNCTION: &str = "repair";
+/// Minutes a repair takes
+pub const REPAIR_MINUTES: i32 = 20;
+/// Share of maximum durability a repair kit restores
+const KIT_REPAIR_SHARE: f32 = 0.4;
+/// Share of maximum durability a field repair restores
+const FIELD_REPAIR_SHARE: f32 = 0.15;
+/// Share of maximum durability a field repair takes away for good
+const FIELD_REPAIR_LOSS: f32 = 0.1;
+/// Durability share below which an item is reported as badly worn
+const WORN_SHARE: f32 = 0.2;
+
+/// What wears an item down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WearCause {
+    /// Casting a spell
+    Cast,
+    /// Being hit in combat
+    Hit,
+    /// Using a tool
+    ToolUse,
+}
+
+impl WearCause {
+    /// Durability lost each time
+    pub fn amount(&self) -> i32 {
+        match self {
+            WearCause::Cast => 1,
+            WearCause::Hit => 2,
+            WearCause::ToolUse => 3,
+        }
+    }
+
+    /// Whether equipment in a slot wears from this cause
+    pub fn wears_slot(&self, slot: EquipmentSlot) -> bool {
+        use EquipmentSlot::*;
+        match self {
+            WearCause::Cast => matches!(slot, Hands | MainHand | OffHand | Ring1 | Ring2 | Neck),
+            WearCause::Hit => matches!(slot, Head | Chest | Legs | Feet | Back | Waist | OffHand),
+            WearCause::ToolUse => false,
+        }
+    }
+}
+
+/// Word for an item's state of repair
+pub fn condition(item: &Item) -> &'static str {
+    let (durability, max) = (item.properties.durability, item.properties.max_durability.max(1));
+    match durability as f32 / max as f32 {
+        _ if durability <= 0 => "Broken",
+        share if share > 0.8 => "Good",
+        share if share > 0.5 => "Fair",
+        share if share > WORN_SHARE => "Poor",
+        _ => "Badly worn",
+    }
+}
+
+/// What happened when an item wore down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wear {
+    /// Lost some durability, nothing worth mentioning
+    Scuffed,
+    /// Just dropped below the badly worn threshold
+    BadlyWorn,
+    /// Just broke
+    Broke,
+}
+
+/// Wear an item down
+pub fn wear(item: &mut Item, amount: i32) -> Wear {
+    let max = item.properties.max_durability.max(1) as f32;
+    let before = item.properties.durability;
+    item.damage(amount);
+    let after = item.properties.durability;
+
+    if before > 0 && after == 0 {
+        Wear::Broke
+    } else if before as f32 / max >= WORN_SHARE && (after as f32 / max) < WORN_SHARE {
+        Wear::BadlyWorn
+    } else {
+        Wear::Scuffed
+    }
+}
+
+/// Player-facing line for noteworthy wear
+pub fn wear_message(name: &str, wear: Wear, equipped: bool) -> Option<String> {
+    match wear {
+        Wear::Scuffed => None,
+        Wear::BadlyWorn => Some(format!("Your {} is badly worn and will break soon. ('repair {}')", name, name.to_lowercase())),
+        Wear::Broke if equipped => Some(format!("Your {} breaks! Its bonuses are lost until it is repaired.", name)),
+        Wear::Broke => Some(format!("Your {} breaks!", name)),
+    }
+}
+
+/// How a repair is done
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RepairMethod {
+    /// With a repair kit
+    Kit,
+    /// Patched up without the proper tools
+    Field,
+    /// Restored by a craftsperson
+    Service,
+}
+
+/// Repair an item, returning the durability restored
+pub fn repair(item: &mut Item, method: RepairMethod) -> i32 {
+    let max = item.properties.max_durability;
+    let before = item.properties.durability;
+    match method {
+        RepairMethod::Kit => item.repair(((max as f32 * KIT_REPAIR_SHARE).ceil() as i32).max(1)),
+        RepairMethod::Field => {
+            let loss = ((max as f32 * FIELD_REPAIR_LOSS).ceil() as i32).max(1);
+            item.properties.max_durability = (max - loss).max(1);
+            item.repair(((max as f32 * FIELD_REPAIR_SHARE).ceil() as i32).max(1));
+        }
+        RepairMethod::Service => item.repair(max),
+    }
+    item.properties.durability - before
+}
+
+/// A craftsperson who repairs items for silver
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RepairService {
+    /// Silver charged per point of durability restored
+    pub silver_per_point: f32,
+}
+
+impl RepairService {
+    /// Price of restoring an item fully
+    pub fn quote(&self, item: &Item) -> i32 {
+        let missing = (item.properties.max_durability - item.properties.durability).max(0);
+        (missing as f32 * self.silver_per_point).ceil() as i32
+    }
+}
+
+/// Repair service an NPC offers, if any
+pub fn repair_service(npc_id: &str) -> Option<RepairService> {
+    match npc_id {
+        "technician_marcus" => Some(RepairService { silver_per_point: 0.3 }),
+        "warden_gareth" => Some(RepairService { silver_per_point: 0.5 }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::items::core::ItemType;
+
+    #[test]
+    fn test_wear_breaks_and_repairs() {
+        let mut tool = Item::new_basic("Tuning Fork".to_string(), "A fork".to_string(), ItemType::Tool {
+            tool_function: "tuning".to_string(),
+        });
+        let max = tool.properties.max_durability;
+        assert_eq!(condition(&tool), "Good");
+
+        tool.properties.durability = (max as f32 * WORN_SHARE) as i32 + 1;
+        assert_eq!(wear(&mut tool, 2), Wear::BadlyWorn);
+        assert_eq!(wear(&mut tool, max), Wear::Broke);
+        assert_eq!(condition(&tool), "Broken");
+        assert!(!tool.is_usable());
+        assert!(wear_message("Tuning Fork", Wear::Broke, true).unwrap().contains("bonuses are lost"));
+
+        // A field repair gets it working again at a permanent cost
+        let service = repair_service("technician_marcus").unwrap();
+        assert_eq!(service.quote(&tool), (max as f32 * 0.3).ceil() as i32);
+        assert!(repair(&mut tool, RepairMethod::Field) > 0);
+        assert!(tool.is_usable());
+        assert!(tool.properties.max_durability < max);
+
+        repair(&mut tool, RepairMethod::Service);
+        assert_eq!(tool.properties.durability, tool.properties.max_durability);
+        assert!(repair_service("sage_meridian").is_none());
+        assert!(WearCause::Hit.wears_slot(EquipmentSlot::Chest));
+        assert!(!WearCause::Cast.wears_slot(EquipmentSlot::Chest));
+    }
+}
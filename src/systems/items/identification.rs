@@ -0,0 +1,109 @@
+//! Unidentified items and appraisal
+//!
+//! Some finds arrive unidentified: an unknown crystal, a strange artifact.
+//! Until appraised, the item goes by a vague name and shows nothing of its
+//! type, effects or value. Its true name and description are kept in custom
+//! properties, so content can define unidentified items directly in the
+//! database. Appraisal comes three ways:
+//! - a detection tool such as a resonance lens, which always works and wears
+//!   the tool
+//! - a paid appraisal from someone with a trained eye
+//! - a detection spell cast on the item, which needs some understanding of
+//!   Detection Arrays to interpret the signature it reveals
+
+use super::core::Item;
+
+/// Custom property holding an unidentified item's true name
+pub const TRUE_NAME_PROPERTY: &str = "true_name";
+/// Custom property holding an unidentified item's true description
+pub const TRUE_DESCRIPTION_PROPERTY: &str = "true_description";
+/// Tool function that can appraise items
+pub const APPRAISAL_TOOL_FUNCTION: &str = "detection";
+/// Theory needed to read an item's signature from a detection spell
+pub const ANALYSIS_THEORY: &str = "detection_arrays";
+/// Understanding of the analysis theory needed to identify an item by spell
+pub const ANALYSIS_UNDERSTANDING: f32 = 0.3;
+/// Minutes an appraisal takes
+pub const APPRAISAL_MINUTES: i32 = 15;
+
+/// How an item came to be identified
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppraisalMethod {
+    Tool,
+    Service,
+    Spell,
+    /// A practiced glance, under fast analysis
+    Expertise,
+}
+
+/// Whether an item's properties are known
+pub fn is_identified(item: &Item) -> bool {
+    item.get_custom_property(TRUE_NAME_PROPERTY).is_none()
+}
+
+/// Hide an item's identity behind a vague name and description
+pub fn conceal(item: &mut Item, vague_name: &str, vague_description: &str) {
+    if !is_identified(item) {
+        return;
+    }
+    let true_name = std::mem::replace(&mut item.properties.name, vague_name.to_string());
+    let true_description = std::mem::replace(&mut item.properties.description, vague_description.to_string());
+    item.set_custom_property(TRUE_NAME_PROPERTY.to_string(), true_name);
+    item.set_custom_property(TRUE_DESCRIPTION_PROPERTY.to_string(), true_description);
+}
+
+/// Reveal an unidentified item, returning what it turned out to be
+pub fn identify(item: &mut Item, method: AppraisalMethod) -> Option<String> {
+    let true_name = item.properties.custom_properties.remove(TRUE_NAME_PROPERTY)?;
+    let vague_name = std::mem::replace(&mut item.properties.name, true_name);
+    if let Some(description) = item.properties.custom_properties.remove(TRUE_DESCRIPTION_PROPERTY) {
+        item.properties.description = description;
+    }
+
+    let how = match method {
+        AppraisalMethod::Tool => "Under close inspection",
+        AppraisalMethod::Service => "After a careful appraisal",
+        AppraisalMethod::Spell => "As its resonance signature resolves",
+        AppraisalMethod::Expertise => "At a practiced glance",
+    };
+    Some(format!("{}, the {} reveals itself: {}.\n{}", how, vague_name, item.properties.name, item.properties.description))
+}
+
+/// Description of an item whose properties are unknown
+pub fn describe_unidentified(item: &Item) -> String {
+    format!(
+        "{}\n{}\nIts nature is unknown. Appraise it with a detection tool, a knowledgeable \
+         person, or a detection spell to learn more.\nWeight: {:.1} kg\n",
+        item.properties.name, item.properties.description, item.properties.weight
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::items::core::{ItemEffect, ItemType};
+    use crate::systems::items::describe_item;
+
+    #[test]
+    fn test_unidentified_items_hide_their_nature_until_appraised() {
+        let mut item = Item::new_basic(
+            "Focus Tonic".to_string(),
+            "A bitter draught that sharpens the mind.".to_string(),
+            ItemType::Consumable { effect: ItemEffect::RestoreEnergy(20), uses_remaining: 1 },
+        );
+        conceal(&mut item, "murky vial", "A stoppered vial of something cloudy.");
+        assert!(!is_identified(&item));
+        assert_eq!(item.properties.name, "murky vial");
+
+        let description = describe_item(&item);
+        assert!(description.contains("Its nature is unknown"));
+        assert!(!description.contains("RestoreEnergy"));
+        assert!(!description.contains("Value"));
+
+        let revealed = identify(&mut item, AppraisalMethod::Spell).unwrap();
+        assert!(revealed.contains("the murky vial reveals itself: Focus Tonic"));
+        assert!(is_identified(&item));
+        assert!(describe_item(&item).contains("RestoreEnergy"));
+        assert!(identify(&mut item, AppraisalMethod::Tool).is_none());
+    }
+}
@@ -10,6 +10,7 @@ use super::core::{Item, ItemId, ItemEffect, ItemType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use crate::GameResult;
+use rand::Rng;
 
 /// Defines how items can interact with each other
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -148,6 +149,21 @@ pub struct CombinationRule {
     pub base_success_rate: f32,
     /// Requirements to attempt
     pub requirements: InteractionConditions,
+    /// What happens when the attempt fails
+    #[serde(default)]
+    pub failure: CombinationFailure,
+}
+
+/// Ways a combination attempt can go wrong
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum CombinationFailure {
+    /// Nothing takes; the items are left as they were
+    #[default]
+    Fizzle,
+    /// The items are ruined
+    Spoil,
+    /// The items are ruined and the release of energy hurts
+    Backlash { damage: i32 },
 }
 
 /// Result of a combination
@@ -298,6 +314,7 @@ impl ItemInteraction {
         available_items: &mut HashMap<ItemId, (Item, i32)>,
         player_attributes: &HashMap<String, i32>,
         player_theories: &[String],
+        rng: &mut impl Rng,
     ) -> GameResult<InteractionResult> {
         if !self.can_perform(available_items, player_attributes, player_theories) {
             return Err(crate::GameError::InvalidInput(
@@ -316,7 +333,7 @@ impl ItemInteraction {
 
         // Calculate success probability
         let base_success = self.calculate_success_probability(player_attributes, player_theories);
-        let random_roll = rand::random::<f32>();
+        let random_roll = rng.gen::<f32>();
 
         result.success = random_roll <= base_success;
 
@@ -330,7 +347,7 @@ impl ItemInteraction {
 
             // Produce output items
             for output in &self.outputs {
-                if rand::random::<f32>() <= output.success_chance {
+                if rng.gen::<f32>() <= output.success_chance {
                     for _ in 0..output.quantity {
                         result.outputs.push(output.item.clone());
                     }
@@ -346,7 +363,7 @@ impl ItemInteraction {
             // Partial consumption on failure for some interaction types
             if matches!(self.interaction_type, InteractionType::Synthesis | InteractionType::Ritual) {
                 for input in &self.inputs {
-                    if input.consumed && rand::random::<f32>() < 0.3 {
+                    if input.consumed && rng.gen::<f32>() < 0.3 {
                         // 30% chance to consume materials even on failure
                         self.consume_input(input, available_items, &mut result)?;
                     }
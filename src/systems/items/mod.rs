@@ -6,6 +6,10 @@
 //! - Equipment system with stat modifications
 //! - Inventory management with weight and space limits
 //! - Item interactions and combinations
+//! - Crafting consumables and tools from recipes
+//! - Wear, breakage and repair
+//! - Unidentified items and appraisal
+//! - Artifacts with scripted behaviors
 //! - Integration with existing magic and knowledge systems
 
 pub mod core;
@@ -14,6 +18,11 @@ pub mod educational;
 pub mod inventory;
 pub mod interactions;
 pub mod unlock_system;
+pub mod crafting;
+pub mod combination;
+pub mod durability;
+pub mod identification;
+pub mod artifacts;
 
 pub use core::{Item, ItemId, ItemType, ItemRarity, ItemProperties, ItemEffect};
 pub use equipment::{Equipment, EquipmentSlot, EquipmentManager, EquipmentBonus};
@@ -21,6 +30,8 @@ pub use educational::{EducationalItem, LearningBonus, ResearchTool, Collaborativ
 pub use inventory::{InventoryManager, InventoryConstraints, InventoryError};
 pub use interactions::{ItemInteraction, InteractionResult, CombinationRule};
 pub use unlock_system::{ItemUnlockSystem, UnlockRequirement, UnlockCategory, UnlockEvent};
+pub use crafting::{CraftingSystem, Recipe, Quality};
+pub use durability::{RepairMethod, WearCause};
 
 use crate::core::Player;
 use crate::systems::knowledge::LearningMethod;
@@ -35,7 +46,8 @@ pub struct ItemSystem {
     pub inventory_manager: InventoryManager,
     /// Equipment system for wearable items
     pub equipment_manager: EquipmentManager,
-    /// Item interaction and combination rules
+    /// Item interaction and combination rules, fixed content rather than save state
+    #[serde(skip, default = "ItemSystem::default_interaction_rules")]
     pub interaction_rules: HashMap<String, CombinationRule>,
     /// Educational item database
     pub educational_items: HashMap<ItemId, EducationalItem>,
@@ -89,6 +101,12 @@ impl ItemSystem {
         let item = self.inventory_manager.get_item(item_id)
             .ok_or_else(|| crate::GameError::InvalidInput("Item not found".to_string()))?;
 
+        if !item.is_usable() {
+            return Err(crate::GameError::InvalidCommand(
+                format!("The {} is broken. Repair it before equipping it.", item.properties.name)
+            ).into());
+        }
+
         if let ItemType::Equipment(equipment) = &item.item_type {
             self.equipment_manager.equip_item(item_id.clone(), equipment.clone())?;
 
@@ -106,9 +124,12 @@ impl ItemSystem {
     /// Unequip an item
     pub fn unequip_item(&mut self, player: &mut Player, slot: EquipmentSlot) -> GameResult<Option<ItemId>> {
         if let Some((item_id, equipment)) = self.equipment_manager.unequip_item(slot)? {
-            // Remove stat bonuses from player
-            for bonus in &equipment.bonuses {
-                self.remove_equipment_bonus(player, bonus);
+            // Remove stat bonuses from player; broken items already lost theirs
+            let broken = self.inventory_manager.get_item(&item_id).is_some_and(|item| !item.is_usable());
+            if !broken {
+                for bonus in &equipment.bonuses {
+                    self.remove_equipment_bonus(player, bonus);
+                }
             }
             Ok(Some(item_id))
         } else {
@@ -136,7 +157,16 @@ impl ItemSystem {
                 Ok(result)
             }
             ItemType::Tool { tool_function } => {
-                self.use_tool(player, tool_function, target)
+                if !item.is_usable() {
+                    return Err(crate::GameError::InvalidCommand(
+                        format!("The {} is broken and needs repair.", item.properties.name)
+                    ).into());
+                }
+                let mut result = self.use_tool(player, tool_function, target)?;
+                if let Some(message) = self.wear_item(player, item_id, WearCause::ToolUse.amount()) {
+                    result.push_str(&format!("\n{}", message));
+                }
+                Ok(result)
             }
             ItemType::Educational(educational) => {
                 self.use_educational_item(player, educational, target)
@@ -150,52 +180,7 @@ impl ItemSystem {
         let item = self.inventory_manager.get_item(item_id)
             .ok_or_else(|| crate::GameError::InvalidInput("Item not found".to_string()))?;
 
-        let mut description = format!("{}\n{}\n", item.properties.name, item.properties.description);
-
-        // Add type-specific information
-        match &item.item_type {
-            ItemType::Equipment(equipment) => {
-                description.push_str(&format!("Equipment Slot: {:?}\n", equipment.slot));
-                if !equipment.bonuses.is_empty() {
-                    description.push_str("Bonuses:\n");
-                    for bonus in &equipment.bonuses {
-                        description.push_str(&format!("  - {:?}\n", bonus));
-                    }
-                }
-            }
-            ItemType::Consumable { effect, uses_remaining } => {
-                description.push_str(&format!("Uses Remaining: {}\n", uses_remaining));
-                description.push_str(&format!("Effect: {:?}\n", effect));
-            }
-            ItemType::Educational(educational) => {
-                description.push_str("Educational Benefits:\n");
-                for bonus in &educational.learning_bonuses {
-                    description.push_str(&format!("  - {:?}\n", bonus));
-                }
-            }
-            _ => {}
-        }
-
-        // Add physical properties
-        description.push_str(&format!("Weight: {:.1} kg\n", item.properties.weight));
-        description.push_str(&format!("Value: {} silver\n", item.properties.value));
-        description.push_str(&format!("Rarity: {:?}\n", item.properties.rarity));
-
-        if item.properties.durability < item.properties.max_durability {
-            let condition = if item.properties.durability as f32 > item.properties.max_durability as f32 * 0.8 {
-                "Good"
-            } else if item.properties.durability as f32 > item.properties.max_durability as f32 * 0.5 {
-                "Fair"
-            } else if item.properties.durability as f32 > item.properties.max_durability as f32 * 0.2 {
-                "Poor"
-            } else {
-                "Broken"
-            };
-            description.push_str(&format!("Condition: {} ({}/{})\n",
-                condition, item.properties.durability, item.properties.max_durability));
-        }
-
-        Ok(description)
+        Ok(describe_item(item))
     }
 
     /// Get inventory summary
@@ -214,7 +199,7 @@ impl ItemSystem {
 
         // Check equipped educational items
         for equipment_id in self.equipment_manager.get_equipped_items() {
-            if let Some(item) = self.inventory_manager.get_item(equipment_id) {
+            if let Some(item) = self.inventory_manager.get_item(equipment_id).filter(|item| item.is_usable()) {
                 if let ItemType::Educational(educational) = &item.item_type {
                     for bonus in &educational.learning_bonuses {
                         if bonus.applies_to_theory(theory_id) && bonus.applies_to_method(method) {
@@ -226,7 +211,7 @@ impl ItemSystem {
         }
 
         // Check inventory for applicable educational items
-        for item in self.inventory_manager.get_all_items() {
+        for item in self.inventory_manager.get_all_items().into_iter().filter(|item| item.is_usable()) {
             if let ItemType::Educational(educational) = &item.item_type {
                 for bonus in &educational.learning_bonuses {
                     if bonus.applies_to_theory(theory_id) && bonus.applies_to_method(method) {
@@ -240,6 +225,60 @@ impl ItemSystem {
         total_bonus
     }
 
+    /// Whether an item is equipped
+    pub fn is_equipped(&self, item_id: &ItemId) -> bool {
+        self.equipment_manager.equipped_items.values().any(|(id, _)| id == item_id)
+    }
+
+    /// Wear an item down, dropping an equipped item's bonuses if it breaks
+    ///
+    /// Returns a message when the wear is worth telling the player about.
+    pub fn wear_item(&mut self, player: &mut Player, item_id: &ItemId, amount: i32) -> Option<String> {
+        let equipped = self.is_equipped(item_id);
+        let item = self.inventory_manager.items.get_mut(item_id).filter(|item| item.is_usable())?;
+        let wear = durability::wear(item, amount);
+        let message = durability::wear_message(&item.properties.name, wear, equipped);
+
+        if wear == durability::Wear::Broke && equipped {
+            if let ItemType::Equipment(equipment) = item.item_type.clone() {
+                for bonus in &equipment.bonuses {
+                    self.remove_equipment_bonus(player, bonus);
+                }
+            }
+        }
+        message
+    }
+
+    /// Wear down every equipped item a cause affects
+    pub fn wear_equipment(&mut self, player: &mut Player, cause: WearCause) -> Vec<String> {
+        let mut worn: Vec<ItemId> = self.equipment_manager.equipped_items.values()
+            .filter(|(_, equipment)| cause.wears_slot(equipment.slot))
+            .map(|(id, _)| id.clone())
+            .collect();
+        worn.sort();
+        worn.iter().filter_map(|id| self.wear_item(player, id, cause.amount())).collect()
+    }
+
+    /// Repair an item, restoring an equipped item's bonuses if it was broken
+    ///
+    /// Returns the durability restored.
+    pub fn repair_item(&mut self, player: &mut Player, item_id: &ItemId, method: RepairMethod) -> GameResult<i32> {
+        let equipped = self.is_equipped(item_id);
+        let item = self.inventory_manager.items.get_mut(item_id)
+            .ok_or_else(|| crate::GameError::InvalidInput("Item not found".to_string()))?;
+        let was_broken = !item.is_usable();
+        let restored = durability::repair(item, method);
+
+        if was_broken && equipped && item.is_usable() {
+            if let ItemType::Equipment(equipment) = item.item_type.clone() {
+                for bonus in &equipment.bonuses {
+                    self.apply_equipment_bonus(player, bonus);
+                }
+            }
+        }
+        Ok(restored)
+    }
+
     /// Check if player has required items for an action
     pub fn has_required_items(&self, requirements: &[ItemId]) -> bool {
         requirements.iter().all(|req| self.inventory_manager.has_item(req))
@@ -256,16 +295,13 @@ impl ItemSystem {
                 player.recover_energy(0, *amount);
                 Ok(format!("Reduced fatigue by {}", amount))
             }
-            ItemEffect::TemporaryAttributeBoost { attribute, amount, duration: _ } => {
-                // For now, apply permanent boost (temporary effects would need game time tracking)
-                match attribute.as_str() {
-                    "mental_acuity" => {
-                        player.attributes.mental_acuity += amount;
-                        Ok(format!("Mental acuity increased by {}", amount))
-                    }
-                    "resonance_sensitivity" => {
-                        player.attributes.resonance_sensitivity += amount;
-                        Ok(format!("Resonance sensitivity increased by {}", amount))
+            ItemEffect::TemporaryAttributeBoost { attribute, amount, duration } => {
+                // The world's timed effects undo the change once the duration runs out
+                use crate::systems::timed_effects;
+                match timed_effects::attribute_name(attribute) {
+                    Some(name) if timed_effects::adjust_attribute(player, attribute, *amount) => {
+                        Ok(format!("{} {} by {} for {} minutes", name,
+                            if *amount >= 0 { "increased" } else { "decreased" }, amount.abs(), duration))
                     }
                     _ => Ok("Unknown attribute boost".to_string())
                 }
@@ -277,8 +313,8 @@ impl ItemSystem {
                 Ok(format!("Gained understanding of {}", theory_id))
             }
             ItemEffect::HealDamage(amount) => {
-                // For future health system - for now just report it
-                Ok(format!("Would heal {} damage (health system not implemented)", amount))
+                let healed = player.health.heal(*amount);
+                Ok(format!("Healed {} damage", healed))
             }
             ItemEffect::TemporarySpell { spell_type, duration: _ } => {
                 // For future temporary spell system
@@ -430,8 +466,7 @@ impl ItemSystem {
     }
 
     fn default_interaction_rules() -> HashMap<String, CombinationRule> {
-        // TODO: Implement default item combination rules
-        HashMap::new()
+        combination::starter_rules()
     }
 
     fn default_educational_items() -> HashMap<ItemId, EducationalItem> {
@@ -544,6 +579,51 @@ impl ItemSystem {
     }
 }
 
+/// Full description of an item, wherever it is
+pub fn describe_item(item: &Item) -> String {
+    if !identification::is_identified(item) {
+        return identification::describe_unidentified(item);
+    }
+
+    let mut description = format!("{}\n{}\n", item.properties.name, item.properties.description);
+
+    // Add type-specific information
+    match &item.item_type {
+        ItemType::Equipment(equipment) => {
+            description.push_str(&format!("Equipment Slot: {:?}\n", equipment.slot));
+            if !equipment.bonuses.is_empty() {
+                description.push_str("Bonuses:\n");
+                for bonus in &equipment.bonuses {
+                    description.push_str(&format!("  - {:?}\n", bonus));
+                }
+            }
+        }
+        ItemType::Consumable { effect, uses_remaining } => {
+            description.push_str(&format!("Uses Remaining: {}\n", uses_remaining));
+            description.push_str(&format!("Effect: {:?}\n", effect));
+        }
+        ItemType::Educational(educational) => {
+            description.push_str("Educational Benefits:\n");
+            for bonus in &educational.learning_bonuses {
+                description.push_str(&format!("  - {:?}\n", bonus));
+            }
+        }
+        _ => {}
+    }
+
+    // Add physical properties
+    description.push_str(&format!("Weight: {:.1} kg\n", item.properties.weight));
+    description.push_str(&format!("Value: {} silver\n", item.properties.value));
+    description.push_str(&format!("Rarity: {:?}\n", item.properties.rarity));
+
+    if item.properties.durability < item.properties.max_durability {
+        description.push_str(&format!("Condition: {} ({}/{})\n",
+            durability::condition(item), item.properties.durability, item.properties.max_durability));
+    }
+
+    description
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -578,4 +658,34 @@ mod tests {
         assert!(removed.is_some());
         assert!(!item_system.inventory_manager.has_item(&item_id));
     }
+
+    #[test]
+    fn test_broken_equipment_loses_bonuses() {
+        let mut item_system = ItemSystem::new();
+        let mut player = Player::new("Test".to_string());
+        let acuity = player.attributes.mental_acuity;
+
+        let mut gloves = Equipment::new_basic(EquipmentSlot::Hands);
+        gloves.bonuses.push(EquipmentBonus::AttributeBoost { attribute: "mental_acuity".to_string(), amount: 5 });
+        let item = Item::new_basic("Scholar's Gloves".to_string(), "Fine gloves".to_string(), ItemType::Equipment(gloves));
+        let item_id = item.id.clone();
+        item_system.add_item(&mut player, item).unwrap();
+        item_system.equip_item(&mut player, &item_id).unwrap();
+        assert_eq!(player.attributes.mental_acuity, acuity + 5);
+
+        // Casting wears the gloves; armour-only wear leaves them alone
+        assert!(item_system.wear_equipment(&mut player, WearCause::Hit).is_empty());
+        item_system.wear_equipment(&mut player, WearCause::Cast);
+        assert_eq!(item_system.inventory_manager.get_item(&item_id).unwrap().properties.durability, 199);
+
+        let message = item_system.wear_item(&mut player, &item_id, 500).unwrap();
+        assert!(message.contains("breaks"));
+        assert_eq!(player.attributes.mental_acuity, acuity);
+        assert!(item_system.wear_item(&mut player, &item_id, 5).is_none());
+
+        item_system.repair_item(&mut player, &item_id, RepairMethod::Kit).unwrap();
+        assert_eq!(player.attributes.mental_acuity, acuity + 5);
+        item_system.unequip_item(&mut player, EquipmentSlot::Hands).unwrap();
+        assert_eq!(player.attributes.mental_acuity, acuity);
+    }
 }
\ No newline at end of file
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use crate::core::{Player, world_state::WorldState};
 use crate::persistence::database::{DatabaseManager, TheoryData};
+use crate::systems::suggestions::{ActionKind, ActionSource, Situation, SuggestedAction};
 use crate::GameResult;
 
 /// Complete knowledge progression system
@@ -25,6 +26,30 @@ pub struct KnowledgeSystem {
     benefit_calculator: BenefitCalculator,
 }
 
+/// Length of a study session started with the `study` command
+pub const STUDY_SESSION_MINUTES: i32 = 30;
+/// Shortest study session a player can ask for
+pub const MIN_STUDY_MINUTES: i32 = 10;
+/// Longest study session a player can ask for
+pub const MAX_STUDY_MINUTES: i32 = 240;
+/// Most study sessions an estimate will project before giving up
+const MAX_ESTIMATED_SESSIONS: i32 = 500;
+
+/// Projected study needed to reach a level of understanding
+#[derive(Debug, Clone, PartialEq)]
+pub struct StudyEstimate {
+    /// Study sessions needed
+    pub sessions: i32,
+    /// Total minutes of study
+    pub minutes: i32,
+    /// Total mental energy spent
+    pub energy: i32,
+    /// Understanding gained by the next session
+    pub first_session_gain: f32,
+    /// Whether the target can be reached by study at all
+    pub reachable: bool,
+}
+
 /// Comprehensive theory definition with all learning metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Theory {
@@ -181,8 +206,29 @@ pub struct LearningMechanics {
     teaching_mechanics: TeachingMechanics,
     /// Research mechanics for advanced discovery
     research_mechanics: ResearchMechanics,
+    /// Rules rewarding varied learning methods
+    #[serde(default)]
+    variety_rules: VarietyRules,
+}
+
+/// Diminishing returns for repeated methods and breakthroughs for mixing them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VarietyRules {
+    /// Gain multiplier applied per consecutive repeat of the same method
+    repetition_decay: f32,
+    /// Lowest multiplier repetition can reduce gains to
+    repetition_floor: f32,
+    /// Number of recent activities on a theory considered for variety
+    recent_window: usize,
+    /// Distinct methods within the window needed for a breakthrough
+    breakthrough_methods: usize,
+    /// Bonus understanding fraction granted by a breakthrough
+    breakthrough_bonus: f32,
 }
 
+/// Side-effect prefix marking a method-mixing breakthrough
+pub const BREAKTHROUGH_MARKER: &str = "Breakthrough!";
+
 /// Study mechanics implementation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StudyMechanics {
@@ -262,8 +308,11 @@ impl KnowledgeSystem {
 
     /// Initialize the system with theories from database
     pub fn initialize(&mut self, database: &DatabaseManager) -> GameResult<()> {
-        let theory_data = database.load_theories()?;
+        self.initialize_with(database.load_theories()?)
+    }
 
+    /// Initialize from theories already loaded from the database
+    pub fn initialize_with(&mut self, theory_data: HashMap<String, TheoryData>) -> GameResult<()> {
         for (id, data) in theory_data {
             let theory = self.convert_theory_data(data)?;
             self.theories.insert(id.clone(), theory);
@@ -371,6 +420,26 @@ impl KnowledgeSystem {
         multipliers.insert(LearningMethod::Research, 2.0);
         multipliers.insert(LearningMethod::Mentorship, 1.3);
 
+        // Each field of study rewards some approaches more than others
+        let category = self.determine_theory_category(data).unwrap_or(TheoryCategory::HarmonicFundamentals);
+        let (favoured, disfavoured) = match category {
+            TheoryCategory::HarmonicFundamentals => (LearningMethod::Study, LearningMethod::Research),
+            TheoryCategory::CrystalStructures => (LearningMethod::Experimentation, LearningMethod::Observation),
+            TheoryCategory::MentalResonance => (LearningMethod::Mentorship, LearningMethod::Experimentation),
+            TheoryCategory::LightManipulation => (LearningMethod::Experimentation, LearningMethod::Study),
+            TheoryCategory::BioResonance => (LearningMethod::Observation, LearningMethod::Experimentation),
+            TheoryCategory::DetectionArrays => (LearningMethod::Observation, LearningMethod::Study),
+            TheoryCategory::SympatheticNetworks => (LearningMethod::Teaching, LearningMethod::Observation),
+            TheoryCategory::ResonanceAmplification => (LearningMethod::Experimentation, LearningMethod::Teaching),
+            TheoryCategory::TheoreticalSynthesis => (LearningMethod::Research, LearningMethod::Experimentation),
+        };
+        if let Some(multiplier) = multipliers.get_mut(&favoured) {
+            *multiplier *= 1.4;
+        }
+        if let Some(multiplier) = multipliers.get_mut(&disfavoured) {
+            *multiplier *= 0.7;
+        }
+
         // Adjust based on theory complexity
         let complexity_factor = data.complexity_level as f32 / 10.0;
         for (_, multiplier) in multipliers.iter_mut() {
@@ -437,7 +506,7 @@ impl KnowledgeSystem {
         }
 
         // Delegate to appropriate learning mechanic
-        let activity = match method {
+        let mut activity = match method {
             LearningMethod::Study => {
                 self.learning_mechanics.study_mechanics.attempt_study(&theory, duration, player, world)?
             },
@@ -461,6 +530,27 @@ impl KnowledgeSystem {
             },
         };
 
+        // Reward varied approaches and penalise grinding a single method
+        self.learning_mechanics.variety_rules.apply(&mut activity, &theory, player);
+
+        // A partner, student or guard alongside makes the work go further
+        player.followers.assist(&mut activity);
+
+        // Magical weather gives observers something rare to watch
+        crate::systems::weather::assist(&mut activity, world.environment.weather);
+
+        // The player's own laboratory is fitted out for the work
+        player.laboratory.assist(&mut activity, &world.current_location);
+
+        // Studying a theory introduces the science behind it
+        let glossary = crate::systems::concepts::ConceptGlossary::new();
+        for concept in glossary.encounter_theory(&theory, player) {
+            activity.side_effects.push(format!(
+                "New scientific concept: {} (use 'explain {}')",
+                concept, concept.to_lowercase()
+            ));
+        }
+
         // Apply learning results to player
         self.apply_learning_results(&activity, player)?;
 
@@ -471,10 +561,10 @@ impl KnowledgeSystem {
     fn apply_learning_results(&self, activity: &LearningActivity, player: &mut Player) -> GameResult<()> {
         // Get or create theory progress
         let current_understanding = player.theory_understanding(&activity.theory_id);
-        let new_understanding = (current_understanding + activity.understanding_gained).min(1.0);
 
-        // Update player's knowledge state
-        player.knowledge.theories.insert(activity.theory_id.clone(), new_understanding);
+        // Update player's knowledge state and learning history
+        player.update_theory_progress(activity)?;
+        let new_understanding = player.theory_understanding(&activity.theory_id);
 
         // If theory is now mastered, apply benefits
         if new_understanding >= 1.0 && current_understanding < 1.0 {
@@ -520,6 +610,52 @@ impl KnowledgeSystem {
         Ok(accessible)
     }
 
+    /// Look up a theory by ID
+    pub fn get_theory(&self, theory_id: &str) -> Option<&Theory> {
+        self.theories.get(theory_id)
+    }
+
+    /// Look up a theory by ID or name, ignoring case and treating spaces and
+    /// underscores alike
+    pub fn find_theory(&self, query: &str) -> Option<&Theory> {
+        let query = query.trim().to_lowercase().replace('_', " ");
+        self.theories.get(&query.replace(' ', "_")).or_else(|| {
+            self.theories.values().find(|theory| theory.name.to_lowercase() == query)
+        })
+    }
+
+    /// Estimate the study needed to bring a theory up to a target understanding
+    ///
+    /// Uses the same formula as study sessions, ignoring the variety bonuses and
+    /// repetition penalties that mixing or repeating methods brings.
+    pub fn estimate_study(&self, theory_id: &str, player: &Player, target: f32, session_minutes: i32) -> GameResult<StudyEstimate> {
+        let theory = self.theories.get(theory_id)
+            .ok_or_else(|| crate::GameError::ContentNotFound(format!("Theory not found: {}", theory_id)))?;
+        let study = &self.learning_mechanics.study_mechanics;
+        let target = target.clamp(0.0, 1.0);
+
+        let mut understanding = player.theory_understanding(theory_id);
+        let mut estimate = StudyEstimate {
+            sessions: 0,
+            minutes: 0,
+            energy: 0,
+            first_session_gain: study.session_outcome(theory, session_minutes, player.attributes.mental_acuity, understanding).2,
+            reachable: true,
+        };
+        while understanding < target {
+            let (_, _, gained) = study.session_outcome(theory, session_minutes, player.attributes.mental_acuity, understanding);
+            if gained <= 0.0 || estimate.sessions >= MAX_ESTIMATED_SESSIONS {
+                estimate.reachable = false;
+                break;
+            }
+            understanding = (understanding + gained).min(1.0);
+            estimate.sessions += 1;
+            estimate.minutes += session_minutes;
+            estimate.energy += study.energy_cost(session_minutes);
+        }
+        Ok(estimate)
+    }
+
     /// Get theories by category
     pub fn get_theories_by_category(&self, category: TheoryCategory) -> Vec<&Theory> {
         self.theories.values()
@@ -698,6 +834,70 @@ impl LearningMechanics {
             observation_mechanics: ObservationMechanics::new(),
             teaching_mechanics: TeachingMechanics::new(),
             research_mechanics: ResearchMechanics::new(),
+            variety_rules: VarietyRules::default(),
+        }
+    }
+}
+
+impl Default for VarietyRules {
+    fn default() -> Self {
+        Self {
+            repetition_decay: 0.75,
+            repetition_floor: 0.3,
+            recent_window: 4,
+            breakthrough_methods: 3,
+            breakthrough_bonus: 0.5,
+        }
+    }
+}
+
+impl VarietyRules {
+    /// Scale an activity's gains by how varied the player's recent learning has been
+    fn apply(&self, activity: &mut LearningActivity, theory: &Theory, player: &Player) {
+        let recent: Vec<&LearningActivity> = player.knowledge.learning_history
+            .iter()
+            .rev()
+            .filter(|past| past.theory_id == activity.theory_id)
+            .take(self.recent_window)
+            .collect();
+
+        // Consecutive repeats of the same method lose effectiveness
+        let repeats = recent.iter()
+            .take_while(|past| past.method == activity.method)
+            .count();
+        if repeats > 0 {
+            let factor = self.repetition_decay.powi(repeats as i32).max(self.repetition_floor);
+            activity.experience_gained = (activity.experience_gained as f32 * factor) as i32;
+            activity.understanding_gained *= factor;
+            activity.side_effects.push(format!(
+                "Diminishing returns from repeated {:?} ({:.0}% effectiveness)",
+                activity.method, factor * 100.0
+            ));
+            return;
+        }
+
+        // Switching approach after mixing enough methods yields a breakthrough
+        let mut methods: HashSet<&LearningMethod> = recent.iter().map(|past| &past.method).collect();
+        methods.insert(&activity.method);
+        let recent_breakthrough = recent.iter()
+            .any(|past| past.side_effects.iter().any(|effect| effect.starts_with(BREAKTHROUGH_MARKER)));
+
+        if methods.len() >= self.breakthrough_methods && !recent_breakthrough && activity.success_rate > 0.0 {
+            // Methods well suited to the theory produce larger breakthroughs
+            let multiplier = theory.method_multipliers.get(&activity.method).copied().unwrap_or(1.0);
+            let average = if theory.method_multipliers.is_empty() {
+                1.0
+            } else {
+                theory.method_multipliers.values().sum::<f32>() / theory.method_multipliers.len() as f32
+            };
+            let bonus = self.breakthrough_bonus * (multiplier / average);
+
+            activity.understanding_gained *= 1.0 + bonus;
+            activity.experience_gained = (activity.experience_gained as f32 * (1.0 + bonus)) as i32;
+            activity.side_effects.push(format!(
+                "{} Combining {} approaches deepened your understanding (+{:.0}%)",
+                BREAKTHROUGH_MARKER, methods.len(), bonus * 100.0
+            ));
         }
     }
 }
@@ -711,6 +911,40 @@ impl StudyMechanics {
         }
     }
 
+    /// Mental energy a study session costs
+    ///
+    /// The first half hour is the hardest; a longer session settles into a
+    /// pace that tires the student far less.
+    fn energy_cost(&self, duration: i32) -> i32 {
+        let settled = (duration - STUDY_SESSION_MINUTES).max(0);
+        (duration.min(STUDY_SESSION_MINUTES) as f32 * 0.5 + settled as f32 * 0.15) as i32
+    }
+
+    /// Success rate, experience and understanding from one study session
+    fn session_outcome(&self, theory: &Theory, duration: i32, mental_acuity: i32, current_understanding: f32) -> (f32, i32, f32) {
+        // Calculate success rate based on mental acuity and current understanding
+        let mental_acuity_factor = mental_acuity as f32 / 100.0;
+        let understanding_factor = 1.0 - (current_understanding * 0.5); // Harder to learn as understanding increases
+
+        let success_rate = (self.base_efficiency * mental_acuity_factor * understanding_factor).min(1.0);
+
+        // Calculate effective duration (diminishing returns after max_effective_duration)
+        let effective_duration = if duration <= self.max_effective_duration {
+            duration as f32
+        } else {
+            self.max_effective_duration as f32 +
+            ((duration - self.max_effective_duration) as f32 * 0.3) // 30% efficiency after limit
+        };
+
+        // Calculate learning outcomes
+        let base_experience = (effective_duration * success_rate * 10.0) as i32;
+        let experience_gained = (base_experience as f32 * theory.method_multipliers.get(&LearningMethod::Study).unwrap_or(&1.0)) as i32;
+
+        let understanding_gained = (experience_gained as f32 / (theory.complexity_level as f32 * 100.0)).min(0.2); // Max 20% per session
+
+        (success_rate, experience_gained, understanding_gained)
+    }
+
     fn attempt_study(
         &self,
         theory: &Theory,
@@ -719,7 +953,7 @@ impl StudyMechanics {
         _world: &mut WorldState,
     ) -> GameResult<LearningActivity> {
         // Calculate mental energy cost
-        let energy_cost = (duration as f32 * 0.5) as i32;
+        let energy_cost = self.energy_cost(duration);
         let fatigue_cost = (duration as f32 * self.fatigue_rate) as i32;
 
         // Check if player has enough energy
@@ -732,26 +966,9 @@ impl StudyMechanics {
         // Use mental energy
         player.use_mental_energy(energy_cost, fatigue_cost)?;
 
-        // Calculate success rate based on mental acuity and current understanding
-        let current_understanding = player.theory_understanding(&theory.id);
-        let mental_acuity_factor = player.attributes.mental_acuity as f32 / 100.0;
-        let understanding_factor = 1.0 - (current_understanding * 0.5); // Harder to learn as understanding increases
-
-        let success_rate = (self.base_efficiency * mental_acuity_factor * understanding_factor).min(1.0);
-
-        // Calculate effective duration (diminishing returns after max_effective_duration)
-        let effective_duration = if duration <= self.max_effective_duration {
-            duration as f32
-        } else {
-            self.max_effective_duration as f32 +
-            ((duration - self.max_effective_duration) as f32 * 0.3) // 30% efficiency after limit
-        };
-
-        // Calculate learning outcomes
-        let base_experience = (effective_duration * success_rate * 10.0) as i32;
-        let experience_gained = (base_experience as f32 * theory.method_multipliers.get(&LearningMethod::Study).unwrap_or(&1.0)) as i32;
-
-        let understanding_gained = (experience_gained as f32 / (theory.complexity_level as f32 * 100.0)).min(0.2); // Max 20% per session
+        let (success_rate, experience_gained, understanding_gained) = self.session_outcome(
+            theory, duration, player.attributes.mental_acuity, player.theory_understanding(&theory.id)
+        );
 
         // Create learning activity record
         let mut resources_used = HashMap::new();
@@ -1075,6 +1292,20 @@ impl ResearchMechanics {
     }
 }
 
+impl ActionSource for KnowledgeSystem {
+    fn available_actions(&self, situation: &Situation) -> Vec<SuggestedAction> {
+        let player = situation.player;
+        self.get_accessible_theories(player).unwrap_or_default().into_iter()
+            .filter(|theory| player.theory_understanding(&theory.id) < 1.0)
+            .map(|theory| SuggestedAction::new(
+                ActionKind::Study,
+                format!("study {}", theory.id),
+                format!("{}, {:.0}% understood", theory.name, player.theory_understanding(&theory.id) * 100.0),
+            ))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1258,6 +1489,23 @@ mod tests {
         assert!(player.theory_understanding("harmonic_fundamentals") > 0.0);
     }
 
+    #[test]
+    fn test_study_estimate_matches_sessions() {
+        let (mut system, _db, _temp_file) = create_test_system();
+        let mut player = create_test_player();
+        let mut world = create_test_world();
+
+        let theory = system.find_theory("Harmonic Fundamentals").unwrap().id.clone();
+        let estimate = system.estimate_study(&theory, &player, 1.0, STUDY_SESSION_MINUTES).unwrap();
+        assert!(estimate.reachable);
+        assert_eq!(estimate.minutes, estimate.sessions * STUDY_SESSION_MINUTES);
+
+        // The projected first session is what a real session gains
+        let activity = system.attempt_learning(&theory, LearningMethod::Study, STUDY_SESSION_MINUTES, &mut player, &mut world).unwrap();
+        assert!((activity.understanding_gained - estimate.first_session_gain).abs() < 1e-6);
+        assert_eq!(system.estimate_study(&theory, &player, 0.0, STUDY_SESSION_MINUTES).unwrap().sessions, 0);
+    }
+
     #[test]
     fn test_experimentation_learning_attempt() {
         let (mut system, _db, _temp_file) = create_test_system();
@@ -1527,4 +1775,65 @@ mod tests {
         assert!(concepts.contains(&"Wave Physics".to_string()));
         assert!(concepts.contains(&"Energy Conservation".to_string()));
     }
-}
\ No newline at end of file
+
+    fn past_activity(theory_id: &str, method: LearningMethod) -> LearningActivity {
+        LearningActivity {
+            theory_id: theory_id.to_string(),
+            method,
+            duration: 30,
+            success_rate: 0.8,
+            experience_gained: 100,
+            understanding_gained: 0.1,
+            resources_used: HashMap::new(),
+            side_effects: vec![],
+        }
+    }
+
+    #[test]
+    fn test_repeated_method_has_diminishing_returns() {
+        let (mut system, _db, _temp_file) = create_test_system();
+        let mut player = create_test_player();
+        let mut world = create_test_world();
+
+        let first = system.attempt_learning("harmonic_fundamentals", LearningMethod::Study, 30, &mut player, &mut world).unwrap();
+        player.mental_state.current_energy = 150;
+        player.mental_state.fatigue = 0;
+        let second = system.attempt_learning("harmonic_fundamentals", LearningMethod::Study, 30, &mut player, &mut world).unwrap();
+
+        assert!(second.understanding_gained < first.understanding_gained);
+        assert!(second.side_effects.iter().any(|effect| effect.contains("Diminishing returns")));
+        assert_eq!(player.knowledge.learning_history.len(), 2);
+    }
+
+    #[test]
+    fn test_mixing_methods_triggers_breakthrough() {
+        let (system, _db, _temp_file) = create_test_system();
+        let mut player = create_test_player();
+        let theory = system.theories.get("crystal_structures").unwrap().clone();
+        let rules = VarietyRules::default();
+
+        player.knowledge.learning_history.push(past_activity(&theory.id, LearningMethod::Study));
+        player.knowledge.learning_history.push(past_activity(&theory.id, LearningMethod::Observation));
+
+        let mut activity = past_activity(&theory.id, LearningMethod::Experimentation);
+        rules.apply(&mut activity, &theory, &player);
+        assert!(activity.understanding_gained > 0.1);
+        assert!(activity.side_effects.iter().any(|effect| effect.starts_with(BREAKTHROUGH_MARKER)));
+
+        // A second breakthrough needs fresh variety
+        player.knowledge.learning_history.push(activity);
+        let mut follow_up = past_activity(&theory.id, LearningMethod::Study);
+        rules.apply(&mut follow_up, &theory, &player);
+        assert!(!follow_up.side_effects.iter().any(|effect| effect.starts_with(BREAKTHROUGH_MARKER)));
+    }
+
+    #[test]
+    fn test_method_multipliers_favour_suited_methods() {
+        let (system, _db, _temp_file) = create_test_system();
+        let theory = system.theories.get("crystal_structures").unwrap();
+
+        let experiment = theory.method_multipliers[&LearningMethod::Experimentation];
+        let observe = theory.method_multipliers[&LearningMethod::Observation];
+        assert!(experiment > observe * 2.0);
+    }
+}
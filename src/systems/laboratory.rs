@@ -0,0 +1,343 @@
+//! The player's own laboratory
+//!
+//! Once the player has the silver, they can take on a disused laboratory
+//! off the Practice Hall and fit it out over time. Each fixture is upgraded
+//! separately, up to `MAX_LEVEL`:
+//! - Storage: shelves and chests that hold items outside the player's pack
+//! - Experiment bench: experiments go further here, and it stands in for any
+//!   crafting station
+//! - Crystal garden plot: grows crystal and herbs, ready to harvest each day
+//! - Library shelf: study and research go further here
+//!
+//! The laboratory is an ordinary location, opened onto the Practice Hall as a
+//! lasting change when the player takes it on, so it stays part of the world
+//! for the rest of the game.
+
+use crate::core::world_state::{Direction, WorldState};
+use crate::core::Player;
+use crate::systems::items::core::Item;
+use crate::systems::knowledge::{LearningActivity, LearningMethod};
+use crate::systems::location_changes::LocationChange;
+use crate::GameResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Location of the laboratory
+pub const LAB_LOCATION: &str = "personal_laboratory";
+/// Location the laboratory opens onto, where it is bought
+pub const LAB_ENTRANCE: &str = "practice_hall";
+/// Silver it costs to take on the laboratory
+pub const LAB_PRICE: i32 = 120;
+/// Highest level a fixture can reach
+pub const MAX_LEVEL: u8 = 3;
+/// Silver per level to upgrade a fixture
+const UPGRADE_COST: i32 = 40;
+/// Items the storage holds per level
+const STORAGE_PER_LEVEL: usize = 8;
+/// Extra learning or crafting per level of the right fixture
+const BONUS_PER_LEVEL: f32 = 0.08;
+/// What the garden plot grows, in turn
+const GARDEN_HARVEST: [&str; 2] = ["crystal_fragment", "silverleaf"];
+/// Days of growth the garden plot holds before it stops growing
+const GARDEN_MAX_DAYS: i32 = 3;
+
+/// Fixtures the laboratory can be fitted with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Fixture {
+    Storage,
+    Bench,
+    GardenPlot,
+    LibraryShelf,
+}
+
+impl Fixture {
+    pub const ALL: [Fixture; 4] = [Fixture::Storage, Fixture::Bench, Fixture::GardenPlot, Fixture::LibraryShelf];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Fixture::Storage => "storage",
+            Fixture::Bench => "experiment bench",
+            Fixture::GardenPlot => "crystal garden plot",
+            Fixture::LibraryShelf => "library shelf",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().trim_start_matches("the ").to_lowercase();
+        match name.as_str() {
+            "storage" | "chests" | "shelves" => Some(Fixture::Storage),
+            "bench" | "experiment bench" | "workbench" => Some(Fixture::Bench),
+            "garden" | "plot" | "garden plot" | "crystal garden plot" => Some(Fixture::GardenPlot),
+            "library" | "shelf" | "library shelf" | "bookshelf" => Some(Fixture::LibraryShelf),
+            _ => None,
+        }
+    }
+
+    /// What each level of the fixture does
+    fn benefit(&self) -> String {
+        match self {
+            Fixture::Storage => format!("holds {} items per level", STORAGE_PER_LEVEL),
+            Fixture::Bench => format!("+{:.0}% to experiments and crafting here per level, and serves as any crafting station", BONUS_PER_LEVEL * 100.0),
+            Fixture::GardenPlot => "grows crystal and herbs each day, more with each level".to_string(),
+            Fixture::LibraryShelf => format!("+{:.0}% to study and research here per level", BONUS_PER_LEVEL * 100.0),
+        }
+    }
+}
+
+/// The player's laboratory, once they have one
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Laboratory {
+    pub owned: bool,
+    /// Level of each fixture fitted
+    pub fixtures: BTreeMap<Fixture, u8>,
+    /// Items kept in storage
+    pub storage: Vec<Item>,
+    /// When the garden plot was last harvested, or planted
+    pub harvested_at: i32,
+}
+
+impl Laboratory {
+    pub fn level(&self, fixture: Fixture) -> u8 {
+        self.fixtures.get(&fixture).copied().unwrap_or(0)
+    }
+
+    /// How many items storage holds
+    pub fn capacity(&self) -> usize {
+        self.level(Fixture::Storage) as usize * STORAGE_PER_LEVEL
+    }
+
+    /// Whether storage has room for another item
+    pub fn has_room(&self) -> bool {
+        self.storage.len() < self.capacity()
+    }
+
+    /// Silver to take a fixture to its next level, if it can go further
+    pub fn upgrade_cost(&self, fixture: Fixture) -> Option<i32> {
+        let level = self.level(fixture);
+        (level < MAX_LEVEL).then(|| UPGRADE_COST * (level as i32 + 1))
+    }
+
+    /// Whether the bench stands in for crafting stations at a location
+    pub fn has_bench_at(&self, location: &str) -> bool {
+        self.owned && location == LAB_LOCATION && self.level(Fixture::Bench) > 0
+    }
+
+    /// Extra crafting success at a location
+    pub fn crafting_bonus(&self, location: &str) -> f32 {
+        if self.has_bench_at(location) {
+            self.level(Fixture::Bench) as f32 * BONUS_PER_LEVEL
+        } else {
+            0.0
+        }
+    }
+
+    /// Make the most of the laboratory's fixtures in a learning session
+    pub fn assist(&self, activity: &mut LearningActivity, location: &str) {
+        if !self.owned || location != LAB_LOCATION {
+            return;
+        }
+        let fixture = match activity.method {
+            LearningMethod::Experimentation => Fixture::Bench,
+            LearningMethod::Study | LearningMethod::Research => Fixture::LibraryShelf,
+            _ => return,
+        };
+        let level = self.level(fixture);
+        if level == 0 {
+            return;
+        }
+        let bonus = level as f32 * BONUS_PER_LEVEL;
+        activity.understanding_gained *= 1.0 + bonus;
+        activity.side_effects.push(format!("Your own {} makes the work go further (+{:.0}%)", fixture.name(), bonus * 100.0));
+    }
+
+    /// Take what the garden plot has grown since it was last harvested
+    pub fn harvest(&mut self, now: i32) -> Vec<&'static str> {
+        use crate::core::calendar::MINUTES_PER_DAY;
+
+        let level = self.level(Fixture::GardenPlot) as i32;
+        let days = ((now - self.harvested_at) / MINUTES_PER_DAY).min(GARDEN_MAX_DAYS);
+        if level == 0 || days <= 0 {
+            return Vec::new();
+        }
+        self.harvested_at = now;
+        (0..days * level).map(|n| GARDEN_HARVEST[n as usize % GARDEN_HARVEST.len()]).collect()
+    }
+
+    /// The laboratory's fixtures, storage and what's left to upgrade
+    pub fn report(&self, player_silver: i32) -> String {
+        if !self.owned {
+            return format!(
+                "You don't have a laboratory of your own. A disused one off the Practice Hall is yours for {} silver\n\
+                 ('buy laboratory' in the Practice Hall). You have {} silver.",
+                LAB_PRICE, player_silver
+            );
+        }
+
+        let mut output = String::from("=== YOUR LABORATORY ===\n");
+        for fixture in Fixture::ALL {
+            let level = self.level(fixture);
+            let upgrade = match self.upgrade_cost(fixture) {
+                Some(cost) => format!("upgrade for {} silver", cost),
+                None => "fully upgraded".to_string(),
+            };
+            output.push_str(&format!("{} - level {}/{} ({}); {}\n", fixture.name(), level, MAX_LEVEL, upgrade, fixture.benefit()));
+        }
+        output.push_str(&format!("\nStorage: {}/{} items", self.storage.len(), self.capacity()));
+        for item in &self.storage {
+            output.push_str(&format!("\n• {}", item.properties.name));
+        }
+        output.push_str("\n\nUse 'upgrade <fixture>', 'store <item>', 'retrieve <item>' and 'harvest' in the laboratory.");
+        output
+    }
+}
+
+/// Take on the laboratory, opening it onto the Practice Hall
+pub fn buy(player: &mut Player, world: &mut WorldState) -> GameResult<String> {
+    let lab = &mut player.laboratory;
+    if lab.owned {
+        return Err(crate::GameError::InvalidCommand("The laboratory is already yours.".to_string()).into());
+    }
+    if world.current_location != LAB_ENTRANCE {
+        return Err(crate::GameError::InvalidCommand("The laboratory for sale is off the Practice Hall; buy it there.".to_string()).into());
+    }
+    if player.inventory.silver < LAB_PRICE {
+        return Err(crate::GameError::InsufficientResources(format!(
+            "The laboratory costs {} silver; you have {}.", LAB_PRICE, player.inventory.silver
+        )).into());
+    }
+
+    player.inventory.silver -= LAB_PRICE;
+    lab.owned = true;
+    lab.fixtures.insert(Fixture::Storage, 1);
+    lab.fixtures.insert(Fixture::Bench, 1);
+    lab.harvested_at = world.game_time_minutes;
+    world.change_location(LAB_ENTRANCE, LocationChange::OpenExit { direction: Direction::West, to: LAB_LOCATION.to_string() })?;
+    world.change_location(LAB_LOCATION, LocationChange::OpenExit { direction: Direction::East, to: LAB_ENTRANCE.to_string() })?;
+    world.change_location(LAB_LOCATION, LocationChange::Rename { name: format!("{}'s Laboratory", player.name) })?;
+
+    Ok(format!(
+        "You hand over {} silver and receive a heavy brass key. The disused laboratory west of the Practice Hall\n\
+         is yours, with a little storage and a serviceable bench. Type 'laboratory' to see what it could become.",
+        LAB_PRICE
+    ))
+}
+
+/// Upgrade a fixture in the laboratory
+pub fn upgrade(fixture: Fixture, player: &mut Player, world: &WorldState) -> GameResult<String> {
+    let lab = &mut player.laboratory;
+    if !lab.owned || world.current_location != LAB_LOCATION {
+        return Err(crate::GameError::InvalidCommand("You can only fit out your laboratory from inside it.".to_string()).into());
+    }
+    let cost = lab.upgrade_cost(fixture).ok_or_else(|| {
+        crate::GameError::InvalidCommand(format!("Your {} is as good as it gets.", fixture.name()))
+    })?;
+    if player.inventory.silver < cost {
+        return Err(crate::GameError::InsufficientResources(format!(
+            "Upgrading the {} costs {} silver; you have {}.", fixture.name(), cost, player.inventory.silver
+        )).into());
+    }
+
+    player.inventory.silver -= cost;
+    let level = lab.fixtures.entry(fixture).or_insert(0);
+    *level += 1;
+    if fixture == Fixture::GardenPlot && *level == 1 {
+        lab.harvested_at = world.game_time_minutes;
+    }
+    Ok(format!("For {} silver, your {} is now level {}: it {}.", cost, fixture.name(), level, fixture.benefit()))
+}
+
+/// Put an item into storage, if there's room
+pub fn store(item: Item, player: &mut Player) -> GameResult<String> {
+    let lab = &mut player.laboratory;
+    if !lab.has_room() {
+        return Err(crate::GameError::InsufficientResources(format!(
+            "Your storage is full; there's no room for the {}. Upgrading it would make more.", item.properties.name
+        )).into());
+    }
+    let message = format!("You put the {} away in storage ({}/{}).", item.properties.name, lab.storage.len() + 1, lab.capacity());
+    lab.storage.push(item);
+    Ok(message)
+}
+
+/// Take an item out of storage
+pub fn retrieve(query: &str, player: &mut Player) -> Option<Item> {
+    let query = query.trim().to_lowercase();
+    let lab = &mut player.laboratory;
+    let index = lab.storage.iter().position(|item| item.properties.name.to_lowercase().contains(&query))?;
+    Some(lab.storage.remove(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::calendar::MINUTES_PER_DAY;
+    use crate::core::world_state::Location;
+    use std::collections::HashMap;
+
+    fn world() -> WorldState {
+        let mut world = WorldState::new();
+        world.add_location(Location::new(LAB_ENTRANCE.to_string(), "Practice Hall".to_string(), "A hall.".to_string()));
+        world.add_location(Location::new(LAB_LOCATION.to_string(), "Laboratory".to_string(), "A lab.".to_string()));
+        world.current_location = LAB_ENTRANCE.to_string();
+        world
+    }
+
+    #[test]
+    fn test_buying_and_upgrading_the_laboratory() {
+        let mut player = Player::new("Ada".to_string());
+        let mut world = world();
+        player.inventory.silver = LAB_PRICE - 1;
+        assert!(buy(&mut player, &mut world).is_err());
+
+        player.inventory.silver = LAB_PRICE + UPGRADE_COST * 2;
+        buy(&mut player, &mut world).unwrap();
+        assert_eq!(player.inventory.silver, UPGRADE_COST * 2);
+        assert_eq!(world.move_to_location(Direction::West).unwrap(), LAB_LOCATION);
+        assert_eq!(world.current_location().unwrap().name, "Ada's Laboratory");
+
+        assert_eq!(player.laboratory.upgrade_cost(Fixture::Bench), Some(UPGRADE_COST * 2));
+        upgrade(Fixture::Bench, &mut player, &world).unwrap();
+        assert_eq!(player.laboratory.level(Fixture::Bench), 2);
+        assert!(upgrade(Fixture::Bench, &mut player, &world).is_err());
+        assert!(player.laboratory.has_bench_at(LAB_LOCATION));
+        assert!((player.laboratory.crafting_bonus(LAB_LOCATION) - 2.0 * BONUS_PER_LEVEL).abs() < 1e-6);
+        assert_eq!(player.laboratory.crafting_bonus(LAB_ENTRANCE), 0.0);
+    }
+
+    #[test]
+    fn test_fixtures_help_learning_grow_crops_and_hold_items() {
+        let mut lab = Laboratory { owned: true, ..Laboratory::default() };
+        lab.fixtures.insert(Fixture::LibraryShelf, 2);
+        let mut activity = LearningActivity {
+            theory_id: "harmonic_fundamentals".to_string(),
+            method: LearningMethod::Study,
+            duration: 60,
+            success_rate: 1.0,
+            experience_gained: 10,
+            understanding_gained: 0.1,
+            resources_used: HashMap::new(),
+            side_effects: Vec::new(),
+        };
+        lab.assist(&mut activity, LAB_ENTRANCE);
+        assert!(activity.side_effects.is_empty());
+        lab.assist(&mut activity, LAB_LOCATION);
+        assert!((activity.understanding_gained - 0.116).abs() < 1e-6);
+
+        assert!(lab.harvest(MINUTES_PER_DAY * 10).is_empty());
+        lab.fixtures.insert(Fixture::GardenPlot, 2);
+        assert_eq!(lab.harvest(MINUTES_PER_DAY * 10).len(), (GARDEN_MAX_DAYS * 2) as usize);
+        assert!(lab.harvest(MINUTES_PER_DAY * 10 + 60).is_empty());
+
+        let mut player = Player::new("Ada".to_string());
+        player.laboratory.owned = true;
+        player.laboratory.fixtures.insert(Fixture::Storage, 1);
+        let stone = Item::new_basic("River Stone".to_string(), "A stone.".to_string(), crate::systems::items::core::ItemType::Mundane);
+        for _ in 0..STORAGE_PER_LEVEL {
+            store(stone.clone(), &mut player).unwrap();
+        }
+        assert!(!player.laboratory.has_room());
+        assert!(store(stone, &mut player).is_err());
+        assert!(retrieve("river", &mut player).is_some());
+        assert_eq!(player.laboratory.storage.len(), STORAGE_PER_LEVEL - 1);
+    }
+}
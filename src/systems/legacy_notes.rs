@@ -0,0 +1,235 @@
+//! Legacy notes left by retired characters
+//!
+//! A player who retires a character may choose to leave their lab notebook
+//! behind. The notebook records which theories the character understood and
+//! how well, along with every note they wrote on their map. It is kept in an
+//! archive beside the save files, keyed by the run seed.
+//!
+//! A new character in a world with the same seed can come across the
+//! notebook where its author retired. Reading it copies the author's map
+//! notes onto the reader's map and points them toward what the author
+//! learned, but grants no understanding, attributes or items: continuity
+//! between runs comes from knowing where to look, not from carried stats.
+
+use crate::core::world_state::MapAnnotation;
+use crate::core::{Player, WorldState};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Archive file kept in the save directory
+pub const ARCHIVE_FILE: &str = "legacy_notes.json";
+/// Least understanding of a theory worth writing down
+const MIN_RECORDED_UNDERSTANDING: f32 = 0.1;
+
+/// A retired character's lab notebook
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LegacyNote {
+    /// Seed of the world the character lived in
+    pub seed: u64,
+    pub author: String,
+    /// Location where the notebook was left
+    pub location: String,
+    /// Turn of the run on which the character retired, telling notes apart
+    pub turn: u32,
+    /// Theories the author studied, with their understanding
+    pub notebook: Vec<(String, f32)>,
+    /// The author's map notes by location ID
+    pub annotations: HashMap<String, Vec<MapAnnotation>>,
+}
+
+impl LegacyNote {
+    /// Write up a retiring character's notebook
+    pub fn from_character(player: &Player, world: &WorldState) -> Self {
+        let mut notebook: Vec<(String, f32)> = player.knowledge.theories.iter()
+            .filter(|(_, understanding)| **understanding >= MIN_RECORDED_UNDERSTANDING)
+            .map(|(theory, understanding)| (theory.clone(), *understanding))
+            .collect();
+        notebook.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        Self {
+            seed: world.run.seed,
+            author: player.name.clone(),
+            location: world.current_location.clone(),
+            turn: world.run.turns,
+            notebook,
+            annotations: world.annotations.clone(),
+        }
+    }
+
+    /// Identifier telling notes from the same seed apart
+    pub fn id(&self) -> String {
+        format!("{}@{}", self.author, self.turn)
+    }
+
+    /// The notebook's pages, naming theories with a lookup for display names
+    pub fn pages(&self, theory_name: impl Fn(&str) -> String) -> String {
+        let mut output = format!("=== LAB NOTEBOOK OF {} ===\n", self.author.to_uppercase());
+        if self.notebook.is_empty() {
+            output.push_str("The pages are mostly sketches; whatever they studied, they kept it in their head.\n");
+        } else {
+            output.push_str("Their study notes, in a cramped hand:\n");
+            for (theory, understanding) in &self.notebook {
+                let depth = match understanding {
+                    u if *u >= 0.8 => "mastered",
+                    u if *u >= 0.5 => "worked through in depth",
+                    u if *u >= 0.25 => "studied with care",
+                    _ => "began to study",
+                };
+                output.push_str(&format!("• {} - {} ({:.0}%)\n", theory_name(theory), depth, understanding * 100.0));
+            }
+        }
+
+        let marked = self.annotations.values().map(Vec::len).sum::<usize>();
+        if marked > 0 {
+            output.push_str(&format!(
+                "\nA folded map is tucked in the back, marked with {} note{}.",
+                marked, if marked == 1 { "" } else { "s" }
+            ));
+        }
+        output
+    }
+}
+
+/// Every notebook left behind, kept beside the save files
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LegacyArchive {
+    pub notes: Vec<LegacyNote>,
+}
+
+impl LegacyArchive {
+    /// Load the archive from a directory, empty if none has been written yet
+    pub fn load(directory: &Path) -> crate::GameResult<Self> {
+        let path = directory.join(ARCHIVE_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = std::fs::read_to_string(&path)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to read legacy notes: {}", e)))?;
+        serde_json::from_str(&json)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to parse legacy notes: {}", e)).into())
+    }
+
+    /// Write the archive into a directory
+    pub fn save(&self, directory: &Path) -> crate::GameResult<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to serialize legacy notes: {}", e)))?;
+        std::fs::write(directory.join(ARCHIVE_FILE), json)
+            .map_err(|e| crate::GameError::SaveLoadError(format!("Failed to write legacy notes: {}", e)).into())
+    }
+
+    /// Notes left in worlds with a given seed
+    pub fn for_seed(&self, seed: u64) -> Vec<LegacyNote> {
+        self.notes.iter().filter(|note| note.seed == seed).cloned().collect()
+    }
+}
+
+/// Legacy notes known to the current run, saved with the world
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LegacyState {
+    /// Seed the notes were looked up for
+    pub loaded_seed: Option<u64>,
+    /// Notebooks waiting somewhere in this world
+    pub notes: Vec<LegacyNote>,
+    /// IDs of the notebooks the player has read
+    pub read: Vec<String>,
+    /// Whether the current character has retired
+    pub retired: bool,
+}
+
+impl LegacyState {
+    /// Whether notes for the run's seed still need looking up
+    pub fn needs_loading(&self, seed: u64) -> bool {
+        self.loaded_seed != Some(seed)
+    }
+
+    /// Replace the known notes with those left for a seed
+    pub fn load(&mut self, seed: u64, notes: Vec<LegacyNote>) {
+        self.loaded_seed = Some(seed);
+        self.notes = notes;
+    }
+
+    /// Notebooks lying at a location
+    pub fn notes_at(&self, location_id: &str) -> Vec<&LegacyNote> {
+        self.notes.iter().filter(|note| note.location == location_id).collect()
+    }
+
+    /// Line describing notebooks at a location, if any
+    pub fn describe(&self, location_id: &str) -> Option<String> {
+        let here = self.notes_at(location_id);
+        let authors: Vec<&str> = here.iter().map(|note| note.author.as_str()).collect();
+        match authors.as_slice() {
+            [] => None,
+            [author] => Some(format!("A weathered lab notebook signed by {} lies here. ('read notebook')", author)),
+            _ => Some(format!("Weathered lab notebooks signed by {} lie here. ('read notebook')", authors.join(" and "))),
+        }
+    }
+
+    /// Read the notebooks at the current location, copying their map notes
+    pub fn read_here(&mut self, world_annotations: &mut HashMap<String, Vec<MapAnnotation>>, location_id: &str) -> Vec<LegacyNote> {
+        let here: Vec<LegacyNote> = self.notes_at(location_id).into_iter().cloned().collect();
+        for note in &here {
+            if self.read.contains(&note.id()) {
+                continue;
+            }
+            for (location, annotations) in &note.annotations {
+                let notes = world_annotations.entry(location.clone()).or_default();
+                notes.extend(annotations.iter().map(|annotation| MapAnnotation {
+                    marker: annotation.marker,
+                    note: format!("{} (from {}'s map)", annotation.note, note.author),
+                    created_at: annotation.created_at,
+                }));
+            }
+            self.read.push(note.id());
+        }
+        here
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_notes_carry_over_to_a_new_character_with_the_same_seed() {
+        let mut player = Player::new("Ada".to_string());
+        player.knowledge.theories.insert("harmonic_fundamentals".to_string(), 0.6);
+        player.knowledge.theories.insert("light_manipulation".to_string(), 0.05);
+        let mut world = WorldState::new();
+        world.run.seed = 42;
+        let location = world.current_location.clone();
+        world.annotations.insert(location.clone(), vec![MapAnnotation {
+            marker: '!',
+            note: "Loose floor stone".to_string(),
+            created_at: 0,
+        }]);
+
+        let note = LegacyNote::from_character(&player, &world);
+        assert_eq!(note.notebook, vec![("harmonic_fundamentals".to_string(), 0.6)]);
+
+        let dir = TempDir::new().unwrap();
+        let mut archive = LegacyArchive::load(dir.path()).unwrap();
+        archive.notes.push(note);
+        archive.save(dir.path()).unwrap();
+        let archive = LegacyArchive::load(dir.path()).unwrap();
+        assert!(archive.for_seed(7).is_empty());
+
+        // A new character in the same world finds and reads the notebook
+        let mut next = WorldState::new();
+        next.run.seed = 42;
+        assert!(next.legacy.needs_loading(42));
+        next.legacy.load(42, archive.for_seed(42));
+        assert!(next.legacy.describe(&location).unwrap().contains("signed by Ada"));
+
+        let read = next.legacy.read_here(&mut next.annotations, &location);
+        assert_eq!(read.len(), 1);
+        assert!(read[0].pages(|id| id.to_string()).contains("harmonic_fundamentals - worked through in depth (60%)"));
+        assert_eq!(next.annotations_for(&location).len(), 1);
+        assert!(next.annotations_for(&location)[0].note.contains("from Ada's map"));
+
+        // Reading again copies nothing twice
+        next.legacy.read_here(&mut next.annotations, &location);
+        assert_eq!(next.annotations_for(&location).len(), 1);
+    }
+}
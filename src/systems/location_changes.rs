@@ -0,0 +1,252 @@
+//! Lasting changes to locations
+//!
+//! Quests and the player's own actions can change a location for good: a
+//! barrier repaired, a lab wrecked, a passage opened. Locations come from
+//! the database, so rather than editing them directly, each change is kept
+//! as an override in the world state and saved with the game. When a save is
+//! loaded, the database's version of every location is taken afresh and the
+//! overrides are replayed onto it, so updated content still shows through
+//! wherever the player hasn't changed things.
+//!
+//! Overrides are kept compact: a later change to the same thing replaces an
+//! earlier one, and adjustments that cancel out are dropped.
+
+use crate::core::world_state::{Direction, Location};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// One lasting change to a location
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LocationChange {
+    /// The location takes a new name
+    Rename { name: String },
+    /// The description is rewritten
+    Describe { text: String },
+    /// A sentence is added to the description
+    AddDetail { text: String },
+    /// A new way out opens
+    OpenExit { direction: Direction, to: String },
+    /// A way out is blocked off
+    CloseExit { direction: Direction },
+    /// The lock on a way out is opened
+    Unlock { direction: Direction },
+    /// A hidden way out is found
+    Reveal { direction: Direction },
+    /// Ambient magical energy rises or falls
+    AdjustEnergy { by: f32 },
+    /// Interference rises or falls
+    AdjustInterference { by: f32 },
+    /// A phenomenon appears
+    AddPhenomenon { name: String },
+    /// A phenomenon is gone
+    RemovePhenomenon { name: String },
+}
+
+impl LocationChange {
+    /// Make the change to a location
+    pub fn apply(&self, location: &mut Location) {
+        let properties = &mut location.magical_properties;
+        match self {
+            LocationChange::Rename { name } => location.name = name.clone(),
+            LocationChange::Describe { text } => location.description = text.clone(),
+            LocationChange::AddDetail { text } => {
+                if !location.description.ends_with(text.as_str()) {
+                    location.description = format!("{} {}", location.description.trim_end(), text);
+                }
+            }
+            LocationChange::OpenExit { direction, to } => {
+                location.exits.insert(direction.clone(), to.clone());
+            }
+            LocationChange::CloseExit { direction } => {
+                location.exits.remove(direction);
+            }
+            LocationChange::Unlock { direction } => {
+                location.locks.remove(direction);
+            }
+            LocationChange::Reveal { direction } => {
+                if let Some(hidden) = location.hidden_exits.remove(direction) {
+                    location.exits.insert(direction.clone(), hidden.to);
+                }
+            }
+            LocationChange::AdjustEnergy { by } => properties.ambient_energy = (properties.ambient_energy + by).max(0.0),
+            LocationChange::AdjustInterference { by } => properties.interference = (properties.interference + by).clamp(0.0, 1.0),
+            LocationChange::AddPhenomenon { name } => {
+                if !properties.phenomena.contains(name) {
+                    properties.phenomena.push(name.clone());
+                }
+            }
+            LocationChange::RemovePhenomenon { name } => properties.phenomena.retain(|phenomenon| phenomenon != name),
+        }
+    }
+
+    /// Fold a later change into this one, if they concern the same thing;
+    /// returns whether it was folded in
+    fn absorb(&mut self, later: &LocationChange) -> bool {
+        use LocationChange::*;
+        match (&mut *self, later) {
+            (Rename { .. }, Rename { .. }) | (Describe { .. }, Describe { .. }) => {
+                *self = later.clone();
+                true
+            }
+            (AddDetail { text }, AddDetail { text: later_text }) => text == later_text,
+            (Unlock { direction }, Unlock { direction: later_direction })
+            | (Reveal { direction }, Reveal { direction: later_direction }) => direction == later_direction,
+            (OpenExit { direction, .. } | CloseExit { direction }, OpenExit { direction: later_direction, .. } | CloseExit { direction: later_direction })
+                if direction == later_direction =>
+            {
+                *self = later.clone();
+                true
+            }
+            (AdjustEnergy { by }, AdjustEnergy { by: more }) | (AdjustInterference { by }, AdjustInterference { by: more }) => {
+                *by += more;
+                true
+            }
+            (AddPhenomenon { name } | RemovePhenomenon { name }, AddPhenomenon { name: later_name } | RemovePhenomenon { name: later_name })
+                if name == later_name =>
+            {
+                *self = later.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the change no longer does anything
+    fn is_void(&self) -> bool {
+        match self {
+            LocationChange::AdjustEnergy { by } | LocationChange::AdjustInterference { by } => by.abs() < 1e-6,
+            _ => false,
+        }
+    }
+}
+
+/// A change to a named location, as quests describe them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocationEdit {
+    pub location_id: String,
+    pub change: LocationChange,
+}
+
+impl LocationEdit {
+    pub fn new(location_id: &str, change: LocationChange) -> Self {
+        Self { location_id: location_id.to_string(), change }
+    }
+}
+
+/// Every lasting change made to locations, by location ID
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LocationOverrides {
+    changes: BTreeMap<String, Vec<LocationChange>>,
+}
+
+impl LocationOverrides {
+    /// Remember a change to a location
+    pub fn record(&mut self, location_id: &str, change: LocationChange) {
+        let changes = self.changes.entry(location_id.to_string()).or_default();
+        match changes.iter().position(|earlier| earlier.clone().absorb(&change)) {
+            Some(index) => {
+                changes[index].absorb(&change);
+                if changes[index].is_void() {
+                    changes.remove(index);
+                }
+            }
+            None => changes.push(change),
+        }
+        if changes.is_empty() {
+            self.changes.remove(location_id);
+        }
+    }
+
+    /// The changes made to a location, in the order they are replayed
+    pub fn changes(&self, location_id: &str) -> &[LocationChange] {
+        self.changes.get(location_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Replay every change onto a set of locations
+    pub fn apply(&self, locations: &mut HashMap<String, Location>) {
+        for (location_id, changes) in &self.changes {
+            if let Some(location) = locations.get_mut(location_id) {
+                for change in changes {
+                    change.apply(location);
+                }
+            }
+        }
+    }
+}
+
+/// Rebuild saved locations from fresh content
+///
+/// Each location is taken from the content, keeping what play has changed
+/// about it (whether it was visited, what lies there, recent magic), and then
+/// the overrides are replayed on top. Locations only the save knows about are
+/// kept as saved.
+pub fn merge(
+    mut content: HashMap<String, Location>,
+    saved: HashMap<String, Location>,
+    overrides: &LocationOverrides,
+) -> HashMap<String, Location> {
+    for (id, saved) in saved {
+        match content.get_mut(&id) {
+            Some(location) => {
+                location.visited = saved.visited;
+                location.items = saved.items;
+                location.magical_properties.recent_activity = saved.magical_properties.recent_activity;
+            }
+            None => {
+                content.insert(id, saved);
+            }
+        }
+    }
+    overrides.apply(&mut content);
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(id: &str, description: &str) -> Location {
+        Location::new(id.to_string(), id.replace('_', " "), description.to_string())
+    }
+
+    #[test]
+    fn test_overrides_stay_compact() {
+        let mut overrides = LocationOverrides::default();
+        overrides.record("lab", LocationChange::AdjustInterference { by: 0.3 });
+        overrides.record("lab", LocationChange::AddPhenomenon { name: "pocket".to_string() });
+        overrides.record("lab", LocationChange::AdjustInterference { by: -0.3 });
+        overrides.record("lab", LocationChange::RemovePhenomenon { name: "pocket".to_string() });
+        assert_eq!(overrides.changes("lab"), &[LocationChange::RemovePhenomenon { name: "pocket".to_string() }]);
+
+        overrides.record("lab", LocationChange::OpenExit { direction: Direction::Down, to: "cellar".to_string() });
+        overrides.record("lab", LocationChange::CloseExit { direction: Direction::Down });
+        overrides.record("lab", LocationChange::AddDetail { text: "Soot.".to_string() });
+        overrides.record("lab", LocationChange::AddDetail { text: "Soot.".to_string() });
+        assert_eq!(overrides.changes("lab").len(), 3);
+        assert!(overrides.changes("hall").is_empty());
+    }
+
+    #[test]
+    fn test_merge_replays_changes_onto_fresh_content() {
+        let mut overrides = LocationOverrides::default();
+        overrides.record("lab", LocationChange::AddDetail { text: "The west wall is a heap of rubble.".to_string() });
+        overrides.record("lab", LocationChange::OpenExit { direction: Direction::West, to: "vault".to_string() });
+
+        let mut saved_lab = location("lab", "An old lab.");
+        saved_lab.visited = true;
+        saved_lab.items.push("crystal".to_string());
+        let saved = HashMap::from([
+            ("lab".to_string(), saved_lab),
+            ("camp".to_string(), location("camp", "Only the save knows this.")),
+        ]);
+        let content = HashMap::from([("lab".to_string(), location("lab", "A lab, newly rewritten."))]);
+
+        let merged = merge(content, saved, &overrides);
+        let lab = &merged["lab"];
+        assert_eq!(lab.description, "A lab, newly rewritten. The west wall is a heap of rubble.");
+        assert_eq!(lab.exits.get(&Direction::West).map(String::as_str), Some("vault"));
+        assert!(lab.visited);
+        assert_eq!(lab.items, vec!["crystal".to_string()]);
+        assert!(merged.contains_key("camp"));
+    }
+}
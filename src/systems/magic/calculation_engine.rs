@@ -7,6 +7,7 @@
 use crate::core::{Player, player::Crystal};
 use crate::core::world_state::WorldState;
 use crate::GameResult;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -29,6 +30,21 @@ pub struct MagicAttempt {
     pub target: Option<String>,
     /// Difficulty modifier (1.0 = normal)
     pub difficulty_modifier: f32,
+    /// Success chance added by a material focus
+    #[serde(default)]
+    pub focus_success_bonus: f32,
+    /// Power multiplier bonus from a material focus
+    #[serde(default)]
+    pub focus_power_bonus: f32,
+    /// Faction style the spell is worked in
+    #[serde(default)]
+    pub style: Option<super::styles::SpellStyle>,
+    /// Frequency in Hz the caster drives the crystal at, if not its own
+    #[serde(default)]
+    pub driven_frequency: Option<i32>,
+    /// Minutes the spell is held for, if longer than a moment
+    #[serde(default)]
+    pub sustain_minutes: Option<i32>,
 }
 
 /// Result of a magic attempt calculation
@@ -52,6 +68,53 @@ pub struct MagicResult {
     pub explanation: String,
     /// Success probability that was calculated
     pub success_probability: f32,
+    /// Noteworthy wear on the caster's equipment
+    pub wear: Vec<String>,
+}
+
+/// Odds and costs of a magic attempt, worked out without casting it
+#[derive(Debug, Clone)]
+pub struct MagicEstimate {
+    /// Chance the attempt succeeds
+    pub success_probability: f32,
+    /// Power level on success
+    pub power_level: f32,
+    /// Mental energy cost on success
+    pub energy_cost: i32,
+    /// Fatigue accumulation on success
+    pub fatigue_cost: i32,
+    /// Crystal degradation on success
+    pub crystal_degradation: f32,
+    /// Time taken in minutes
+    pub time_cost: i32,
+    /// Breakdown of the calculation
+    pub explanation: String,
+}
+
+/// Furthest, in Hz, a crystal can be driven from its natural frequency
+pub const MAX_DETUNE: i32 = 2;
+/// Extra crystal wear for each Hz a crystal is driven off its natural frequency
+const DETUNE_WEAR: f32 = 0.5;
+/// Minutes of sustained casting that cost as much as one ordinary cast
+const SUSTAIN_STEP_MINUTES: i32 = 10;
+/// Longest a spell can be sustained, in minutes
+pub const MAX_SUSTAIN_MINUTES: i32 = 120;
+
+/// Success modifier, in percentage points, for a crystal this far off a spell's optimal frequency
+pub fn frequency_modifier(frequency_diff: i32) -> f32 {
+    match frequency_diff.abs() {
+        0 => 25.0,      // Perfect match
+        1 => 15.0,      // Very good
+        2 => 5.0,       // Good
+        3 => -5.0,      // Poor
+        4 => -15.0,     // Bad
+        _ => -25.0,     // Terrible
+    }
+}
+
+/// How well a crystal resonates with a spell, from 0% (no resonance) to 100% (perfect)
+pub fn resonance_match(crystal_frequency: i32, optimal_frequency: i32) -> f32 {
+    (frequency_modifier(crystal_frequency - optimal_frequency) + 25.0) * 2.0
 }
 
 /// Magic formulas and constants from balance framework
@@ -118,7 +181,45 @@ impl MagicCalculationEngine {
         attempt: &MagicAttempt,
         caster: &Player,
         world: &WorldState,
+        rng: &mut impl Rng,
     ) -> GameResult<MagicResult> {
+        let calc_result = self.prepare(attempt, caster, world)?;
+
+        // Apply base modifiers and roll for success
+        Ok(self.finalize_result(calc_result, rng))
+    }
+
+    /// Work out a magic attempt's odds and costs without rolling for it
+    pub fn estimate_attempt(
+        &self,
+        attempt: &MagicAttempt,
+        caster: &Player,
+        world: &WorldState,
+    ) -> GameResult<MagicEstimate> {
+        let calc_result = self.prepare(attempt, caster, world)?;
+        Ok(MagicEstimate {
+            success_probability: calc_result.success_probability,
+            power_level: calc_result.power_level,
+            energy_cost: calc_result.energy_cost,
+            fatigue_cost: calc_result.fatigue_cost,
+            crystal_degradation: calc_result.crystal_degradation,
+            time_cost: calc_result.time_cost,
+            explanation: calc_result.explanation_parts.join("\n"),
+        })
+    }
+
+    /// Optimal crystal frequency for a magic type, if the type is known
+    pub fn optimal_frequency(&self, spell_type: &str) -> Option<i32> {
+        self.calculators.contains_key(spell_type).then(|| self.formulas.get_optimal_frequency(spell_type))
+    }
+
+    /// Calculate an attempt's odds and costs with every modifier applied
+    fn prepare(
+        &self,
+        attempt: &MagicAttempt,
+        caster: &Player,
+        world: &WorldState,
+    ) -> GameResult<MagicCalculationResult> {
         // Get active crystal
         let crystal = caster.active_crystal()
             .ok_or_else(|| crate::GameError::InsufficientResources("No crystal equipped".to_string()))?;
@@ -135,18 +236,72 @@ impl MagicCalculationEngine {
             .ok_or_else(|| crate::GameError::InvalidCommand(format!("Unknown magic type: {}", attempt.spell_type)))?;
 
         // Perform calculation
-        let calc_result = calculator.calculate(attempt, &context, &self.formulas);
+        let mut calc_result = calculator.calculate(attempt, &context, &self.formulas);
+
+        if let Some(frequency) = attempt.driven_frequency.filter(|frequency| *frequency != crystal.frequency) {
+            let detune = (frequency - crystal.frequency).abs();
+            if detune > MAX_DETUNE {
+                return Err(crate::GameError::InvalidInput(format!(
+                    "Your {} crystal resonates at {} Hz and can only be driven between {} and {} Hz.",
+                    crystal.display_name(), crystal.frequency,
+                    (crystal.frequency - MAX_DETUNE).max(1), crystal.frequency + MAX_DETUNE
+                )).into());
+            }
+            let optimal = self.formulas.get_optimal_frequency(&attempt.spell_type);
+            let shift = frequency_modifier(frequency - optimal) - frequency_modifier(crystal.frequency - optimal);
+            let wear = 1.0 + DETUNE_WEAR * detune as f32;
+            calc_result.success_probability = (calc_result.success_probability + shift / 100.0).clamp(0.05, 0.95);
+            calc_result.crystal_degradation *= wear;
+            calc_result.explanation_parts.push(format!(
+                "Driven at {} Hz against its natural {} Hz: {:+.1}% success, x{:.1} crystal wear",
+                frequency, crystal.frequency, shift, wear
+            ));
+        }
 
-        // Apply base modifiers and roll for success
-        let final_result = self.finalize_result(calc_result, &context);
+        if let Some(minutes) = attempt.sustain_minutes {
+            if !(1..=MAX_SUSTAIN_MINUTES).contains(&minutes) {
+                return Err(crate::GameError::InvalidInput(format!(
+                    "A spell can be held for at most {} minutes.", MAX_SUSTAIN_MINUTES
+                )).into());
+            }
+            // Holding a spell costs like casting it again every few minutes
+            let scale = (minutes as f32 / SUSTAIN_STEP_MINUTES as f32).max(1.0);
+            calc_result.energy_cost = (calc_result.energy_cost as f32 * scale).round() as i32;
+            calc_result.fatigue_cost = (calc_result.fatigue_cost as f32 * scale).round() as i32;
+            calc_result.crystal_degradation *= scale;
+            calc_result.time_cost = calc_result.time_cost.max(minutes);
+            calc_result.explanation_parts.push(format!(
+                "Sustained for {} minutes: x{:.1} energy, fatigue and crystal wear", minutes, scale
+            ));
+        }
 
-        Ok(final_result)
+        if let Some(style) = attempt.style {
+            let modifiers = style.modifiers();
+            calc_result.success_probability = (calc_result.success_probability + modifiers.success).clamp(0.05, 0.95);
+            calc_result.power_level *= modifiers.power;
+            calc_result.energy_cost = (calc_result.energy_cost as f32 * modifiers.energy).round() as i32;
+            calc_result.fatigue_cost = (calc_result.fatigue_cost as f32 * modifiers.fatigue).round() as i32;
+            calc_result.crystal_degradation *= modifiers.crystal_wear;
+            calc_result.explanation_parts.push(style.explain());
+        }
+
+        // A material focus still can't push past the usual 95% ceiling
+        if attempt.focus_success_bonus > 0.0 || attempt.focus_power_bonus > 0.0 {
+            calc_result.success_probability = (calc_result.success_probability + attempt.focus_success_bonus).min(0.95);
+            calc_result.power_level *= 1.0 + attempt.focus_power_bonus;
+            calc_result.explanation_parts.push(format!(
+                "Material focus: {:+.0}% success, x{:.2} power",
+                attempt.focus_success_bonus * 100.0, 1.0 + attempt.focus_power_bonus
+            ));
+        }
+
+        Ok(calc_result)
     }
 
     /// Apply final modifiers and determine success
-    fn finalize_result(&self, calc_result: MagicCalculationResult, _context: &MagicContext<'_>) -> MagicResult {
+    fn finalize_result(&self, calc_result: MagicCalculationResult, rng: &mut impl Rng) -> MagicResult {
         // Roll for success using calculated probability
-        let roll = rand::random::<f32>();
+        let roll = rng.gen::<f32>();
         let success = roll < calc_result.success_probability;
 
         // Calculate experience gained (always get some, more on success)
@@ -176,6 +331,7 @@ impl MagicCalculationEngine {
             experience_gained,
             explanation,
             success_probability: calc_result.success_probability,
+            wear: Vec::new(),
         }
     }
 }
@@ -247,14 +403,7 @@ fn calculate_base_success(context: &MagicContext<'_>, formulas: &MagicFormulas,
     // Crystal frequency matching
     let optimal_freq = formulas.get_optimal_frequency(magic_type);
     let frequency_diff = (context.crystal.frequency - optimal_freq).abs();
-    let frequency_modifier = match frequency_diff {
-        0 => 25.0,      // Perfect match
-        1 => 15.0,      // Very good
-        2 => 5.0,       // Good
-        3 => -5.0,      // Poor
-        4 => -15.0,     // Bad
-        _ => -25.0,     // Terrible
-    };
+    let frequency_modifier = frequency_modifier(frequency_diff);
     explanation.push(format!("Frequency matching (crystal {} vs optimal {}): {:+.1}%",
                             context.crystal.frequency, optimal_freq, frequency_modifier));
 
@@ -326,6 +475,11 @@ impl MagicAttempt {
             crystal_frequency,
             target: target.map(|s| s.to_string()),
             difficulty_modifier: 1.0,
+            focus_success_bonus: 0.0,
+            focus_power_bonus: 0.0,
+            style: None,
+            driven_frequency: None,
+            sustain_minutes: None,
         }
     }
 
@@ -333,6 +487,22 @@ impl MagicAttempt {
         self.difficulty_modifier = modifier;
         self
     }
+
+    pub fn with_focus(mut self, success_bonus: f32, power_bonus: f32) -> Self {
+        self.focus_success_bonus = success_bonus;
+        self.focus_power_bonus = power_bonus;
+        self
+    }
+
+    pub fn with_frequency(mut self, frequency: i32) -> Self {
+        self.driven_frequency = Some(frequency);
+        self
+    }
+
+    pub fn with_duration(mut self, minutes: i32) -> Self {
+        self.sustain_minutes = Some(minutes);
+        self
+    }
 }
 
 // Magic type calculators
@@ -583,7 +753,7 @@ mod tests {
         player.inventory.active_crystal = Some(0);
 
         let attempt = MagicAttempt::new("light", 4, None);
-        let result = engine.calculate_attempt(&attempt, &player, &world).unwrap();
+        let result = engine.calculate_attempt(&attempt, &player, &world, &mut rand::thread_rng()).unwrap();
 
         assert!(result.success_probability > 0.0);
         assert!(result.energy_cost > 0);
@@ -600,7 +770,7 @@ mod tests {
         player.inventory.active_crystal = Some(0);
 
         let attempt = MagicAttempt::new("healing", 7, Some("guard"));
-        let result = engine.calculate_attempt(&attempt, &player, &world).unwrap();
+        let result = engine.calculate_attempt(&attempt, &player, &world, &mut rand::thread_rng()).unwrap();
 
         assert!(result.energy_cost > 0);
         assert!(result.explanation.contains("Target healing"));
@@ -645,4 +815,46 @@ mod tests {
         assert!(explanation.iter().any(|line| line.contains("Very low energy") || line.contains("-20")));
         assert!(success_prob < 0.5); // Should be quite low due to energy penalty
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_faction_style_changes_costs_and_odds() {
+        use crate::systems::magic::styles::SpellStyle;
+
+        let engine = MagicCalculationEngine::new();
+        let (mut player, world, crystal) = create_test_context();
+        player.inventory.crystals = vec![crystal];
+        player.inventory.active_crystal = Some(0);
+
+        let plain = engine.calculate_attempt(&MagicAttempt::new("manipulation", 4, None), &player, &world, &mut rand::thread_rng()).unwrap();
+        let mut attempt = MagicAttempt::new("manipulation", 4, None);
+        attempt.style = Some(SpellStyle::HarmonyAttuned);
+        let attuned = engine.calculate_attempt(&attempt, &player, &world, &mut rand::thread_rng()).unwrap();
+        assert!(attuned.energy_cost < plain.energy_cost);
+        assert!(attuned.crystal_degradation < plain.crystal_degradation);
+        assert!(attuned.explanation.contains("Harmony-attuned technique"));
+
+        attempt.style = Some(SpellStyle::CouncilStandard);
+        let standard = engine.calculate_attempt(&attempt, &player, &world, &mut rand::thread_rng()).unwrap();
+        assert!(standard.success_probability > plain.success_probability || plain.success_probability >= 0.95);
+    }
+
+    #[test]
+    fn test_driven_frequency_and_sustain_change_costs() {
+        let engine = MagicCalculationEngine::new();
+        let (mut player, world, crystal) = create_test_context();
+        let natural = crystal.frequency;
+        player.inventory.crystals = vec![crystal];
+        player.inventory.active_crystal = Some(0);
+
+        let plain = engine.estimate_attempt(&MagicAttempt::new("light", natural, None), &player, &world).unwrap();
+        let driven = engine.estimate_attempt(&MagicAttempt::new("light", natural, None).with_frequency(natural + 1), &player, &world).unwrap();
+        assert!(driven.crystal_degradation > plain.crystal_degradation);
+        assert!(driven.explanation.contains(&format!("Driven at {} Hz", natural + 1)));
+        assert!(engine.estimate_attempt(&MagicAttempt::new("light", natural, None).with_frequency(natural + MAX_DETUNE + 1), &player, &world).is_err());
+
+        let held = engine.estimate_attempt(&MagicAttempt::new("light", natural, None).with_duration(30), &player, &world).unwrap();
+        assert_eq!(held.time_cost, 30);
+        assert!(held.energy_cost > plain.energy_cost);
+        assert!(engine.estimate_attempt(&MagicAttempt::new("light", natural, None).with_duration(MAX_SUSTAIN_MINUTES + 1), &player, &world).is_err());
+    }
+}
@@ -0,0 +1,229 @@
+//! Material components for higher-tier spells
+//!
+//! Manipulation and healing can draw on a material focus carried in the
+//! inventory. A focus is optional: the spell still works without one, but a
+//! cast that consumes its component is steadier and stronger. When the
+//! preferred component is missing, an accepted substitute is burned instead,
+//! usually in larger amounts and always for a smaller benefit.
+
+use crate::core::Player;
+use crate::GameResult;
+
+/// A material a spell can consume
+#[derive(Debug, Clone, PartialEq)]
+pub struct Component {
+    /// Item name as it appears in the inventory
+    pub item: &'static str,
+    /// Units consumed per cast
+    pub quantity: i32,
+    /// Fraction of the full benefit this material provides
+    pub effectiveness: f32,
+}
+
+/// Components a spell can use, best first
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpellComponents {
+    pub spell: &'static str,
+    pub preferred: Component,
+    /// Accepted replacements, tried in order when the preferred one is missing
+    pub substitutes: &'static [Component],
+    /// Success chance added by the preferred component
+    pub success_bonus: f32,
+    /// Power multiplier bonus granted by the preferred component
+    pub power_bonus: f32,
+    /// Why the material helps
+    pub rationale: &'static str,
+}
+
+/// The component chosen for one cast
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentUse {
+    pub item: String,
+    /// Inventory id of the stack it comes from
+    pub item_id: String,
+    pub quantity: i32,
+    pub substitute: bool,
+    pub success_bonus: f32,
+    pub power_bonus: f32,
+}
+
+impl ComponentUse {
+    /// Line for the spell explanation
+    pub fn describe(&self) -> String {
+        format!(
+            "Material focus: {} x{}{} ({:+.0}% success, {:+.0}% power)",
+            self.item, self.quantity,
+            if self.substitute { " as a substitute" } else { "" },
+            self.success_bonus * 100.0, self.power_bonus * 100.0
+        )
+    }
+}
+
+static SPELL_COMPONENTS: &[SpellComponents] = &[
+    SpellComponents {
+        spell: "manipulation",
+        preferred: Component { item: "powdered quartz", quantity: 1, effectiveness: 1.0 },
+        substitutes: &[
+            Component { item: "crystal fragment", quantity: 2, effectiveness: 0.6 },
+            Component { item: "damaged crystal", quantity: 1, effectiveness: 0.4 },
+        ],
+        success_bonus: 0.10,
+        power_bonus: 0.25,
+        rationale: "Scattered quartz dust gives the force a lattice to push against.",
+    },
+    SpellComponents {
+        spell: "healing",
+        preferred: Component { item: "tuned chime", quantity: 1, effectiveness: 1.0 },
+        substitutes: &[
+            Component { item: "glass bell", quantity: 1, effectiveness: 0.5 },
+        ],
+        success_bonus: 0.08,
+        power_bonus: 0.20,
+        rationale: "A chime struck at the body's own rhythm steadies the bio-resonance until it cracks.",
+    },
+];
+
+/// Component rules for a spell, if it uses any
+pub fn components_for(spell: &str) -> Option<&'static SpellComponents> {
+    SPELL_COMPONENTS.iter().find(|entry| entry.spell == spell)
+}
+
+/// Inventory names and ids are compared without case or underscores
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase().replace('_', " ")
+}
+
+/// How many units of a material the player carries, and under which item id
+fn carried(player: &Player, material: &str) -> Option<(String, i32)> {
+    let items = player.enhanced_item_system()?;
+    let manager = &items.inventory_manager;
+    manager.get_all_items()
+        .into_iter()
+        .find(|item| normalize(&item.properties.name) == material || normalize(&item.id) == material)
+        .map(|item| (item.id.clone(), manager.get_stack_size(&item.id)))
+}
+
+/// The best component the player can pay for, with its item id
+fn best_available(rules: &'static SpellComponents, player: &Player) -> Option<(&'static Component, String, bool)> {
+    std::iter::once((&rules.preferred, false))
+        .chain(rules.substitutes.iter().map(|component| (component, true)))
+        .find_map(|(component, substitute)| {
+            carried(player, component.item)
+                .filter(|(_, count)| *count >= component.quantity)
+                .map(|(id, _)| (component, id, substitute))
+        })
+}
+
+/// Pick the best component the player can pay for
+///
+/// Returns `None` when the spell has no components or the player carries
+/// none of them; the cast then goes ahead without a focus.
+pub fn choose(spell: &str, player: &Player) -> Option<ComponentUse> {
+    let rules = components_for(spell)?;
+    let (component, item_id, substitute) = best_available(rules, player)?;
+    Some(ComponentUse {
+        item: component.item.to_string(),
+        item_id,
+        quantity: component.quantity,
+        substitute,
+        success_bonus: rules.success_bonus * component.effectiveness,
+        power_bonus: rules.power_bonus * component.effectiveness,
+    })
+}
+
+/// Remove a chosen component from the inventory
+pub fn consume(component: &ComponentUse, player: &mut Player) -> GameResult<()> {
+    for _ in 0..component.quantity {
+        player.remove_enhanced_item(&component.item_id)?;
+    }
+    Ok(())
+}
+
+/// Answer to `components for <spell>`
+pub fn report(spell: &str, player: &Player) -> GameResult<String> {
+    let spell = normalize(spell);
+    let Some(rules) = components_for(&spell) else {
+        let spells: Vec<&str> = SPELL_COMPONENTS.iter().map(|entry| entry.spell).collect();
+        return Ok(format!(
+            "{} magic needs no material components. Spells that can use them: {}.",
+            capitalize(&spell), spells.join(", ")
+        ));
+    };
+
+    let line = |component: &Component| {
+        let held = carried(player, component.item).map_or(0, |(_, count)| count);
+        format!(
+            "{} x{} - {:.0}% of the benefit (carrying {})",
+            component.item, component.quantity, component.effectiveness * 100.0, held
+        )
+    };
+
+    let mut output = format!(
+        "=== COMPONENTS: {} ===\n{}\nA focus is optional; one is consumed on every cast, successful or not.\n\
+         Full benefit: {:+.0}% success, {:+.0}% power.\n\nPreferred:\n  {}\n",
+        spell.to_uppercase(), rules.rationale,
+        rules.success_bonus * 100.0, rules.power_bonus * 100.0, line(&rules.preferred)
+    );
+    if !rules.substitutes.is_empty() {
+        output.push_str("Substitutes, tried in order:\n");
+        for substitute in rules.substitutes {
+            output.push_str(&format!("  {}\n", line(substitute)));
+        }
+    }
+
+    output.push_str(&match best_available(rules, player) {
+        Some((component, _, _)) => format!("\nYour next {} cast will use {}.", spell, component.item),
+        None => format!("\nYou carry nothing usable; {} will be cast without a focus.", spell),
+    });
+    Ok(output)
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::items::core::{Item, ItemType};
+
+    fn material(name: &str) -> Item {
+        Item::new_basic(name.to_string(), format!("Some {}", name), ItemType::Material {
+            material_type: "reagent".to_string(),
+            quality: 0.5,
+        })
+    }
+
+    #[test]
+    fn test_preferred_component_then_substitutes() {
+        let mut player = Player::new("Test".to_string());
+        assert_eq!(choose("manipulation", &player), None);
+
+        // One fragment isn't enough for the two-fragment substitute
+        player.add_enhanced_item(material("crystal_fragment")).unwrap();
+        assert_eq!(choose("manipulation", &player), None);
+        player.add_enhanced_item(material("crystal_fragment")).unwrap();
+        player.add_enhanced_item(material("Powdered Quartz")).unwrap();
+        assert!(report("manipulation", &player).unwrap().contains("next manipulation cast will use powdered quartz"));
+
+        let used = choose("manipulation", &player).unwrap();
+        assert!(!used.substitute);
+        assert_eq!(used.success_bonus, 0.10);
+        consume(&used, &mut player).unwrap();
+
+        let used = choose("manipulation", &player).unwrap();
+        assert!(used.substitute);
+        assert_eq!(used.quantity, 2);
+        assert!((used.power_bonus - 0.15).abs() < 0.001);
+        consume(&used, &mut player).unwrap();
+        assert!(player.inventory.items.is_empty());
+        assert_eq!(choose("manipulation", &player), None);
+
+        assert_eq!(choose("light", &player), None);
+        assert!(report("light", &player).unwrap().contains("Light magic needs no material components"));
+    }
+}
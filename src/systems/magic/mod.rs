@@ -5,12 +5,16 @@
 //! - Crystal resonance and degradation mechanics
 //! - Mental energy and fatigue management
 //! - Theory-based magical applications
+//! - Material components for higher-tier spells
+//! - Faction casting styles taught by mentors
 
 pub mod calculation_engine;
 pub mod resonance_system;
 pub mod crystal_management;
+pub mod components;
+pub mod styles;
 
-pub use calculation_engine::{MagicCalculationEngine, MagicAttempt, MagicResult};
+pub use calculation_engine::{MagicCalculationEngine, MagicAttempt, MagicResult, MagicEstimate};
 pub use resonance_system::{ResonanceAnalyzer, ResonanceContext};
 pub use crystal_management::{CrystalManager, CrystalEfficiency};
 
@@ -29,6 +33,8 @@ pub struct MagicSystem {
     /// Crystal management system
     #[allow(dead_code)]
     crystal_manager: CrystalManager,
+    /// The spell cast most recently and whether it held, until taken
+    last_cast: Option<(String, bool)>,
 }
 
 // Custom serialization - MagicSystem has no state, just recreate on deserialize
@@ -83,6 +89,7 @@ impl MagicSystem {
             calculation_engine: MagicCalculationEngine::new(),
             resonance_analyzer: ResonanceAnalyzer::new(),
             crystal_manager: CrystalManager::new(),
+            last_cast: None,
         }
     }
 
@@ -94,19 +101,36 @@ impl MagicSystem {
         world: &mut WorldState,
         target: Option<&str>,
     ) -> GameResult<MagicResult> {
-        // Get active crystal info before any mutable operations
-        let crystal_frequency = caster.active_crystal()
-            .map(|c| c.frequency)
-            .ok_or_else(|| crate::GameError::InsufficientResources("No crystal equipped".to_string()))?;
+        self.attempt_magic_with(spell_type, caster, world, target, None, None)
+    }
 
-        // Create magic attempt
-        let attempt = MagicAttempt::new(spell_type, crystal_frequency, target);
+    /// Attempt to cast magic, driving the crystal at a chosen frequency in Hz
+    /// and holding the spell for a number of minutes
+    pub fn attempt_magic_with(
+        &mut self,
+        spell_type: &str,
+        caster: &mut Player,
+        world: &mut WorldState,
+        target: Option<&str>,
+        frequency: Option<i32>,
+        duration: Option<i32>,
+    ) -> GameResult<MagicResult> {
+        let (mut attempt, component) = Self::prepare_attempt(spell_type, caster, target)?;
+        if let Some(frequency) = frequency {
+            attempt = attempt.with_frequency(frequency);
+        }
+        if let Some(minutes) = duration {
+            attempt = attempt.with_duration(minutes);
+        }
+        let crystal_frequency = attempt.driven_frequency.unwrap_or(attempt.crystal_frequency);
 
         // Calculate result
-        let result = self.calculation_engine.calculate_attempt(
+        let mut rng = world.run.rng();
+        let mut result = self.calculation_engine.calculate_attempt(
             &attempt,
             caster,
             world,
+            &mut rng,
         )?;
 
         // Apply costs regardless of success to prevent zero-cost exploitation
@@ -115,15 +139,25 @@ impl MagicSystem {
 
         // Use mental energy (always applied, scaled for failures)
         let actual_energy_cost = (result.energy_cost as f32 * cost_multiplier) as i32;
-        let actual_fatigue_cost = (result.fatigue_cost as f32 * cost_multiplier) as i32;
+        // Resonance sickness makes every cast more draining
+        let actual_fatigue_cost = (result.fatigue_cost as f32 * cost_multiplier) as i32 + caster.health.extra_cast_fatigue();
         caster.use_mental_energy(actual_energy_cost, actual_fatigue_cost)?;
 
+        // The component is spent whether or not the resonance holds
+        if let Some(component) = &component {
+            components::consume(component, caster)?;
+            result.explanation.push_str(&format!("\n{}", component.describe()));
+        }
+
         // Degrade crystal (always applied, scaled for failures)
         if let Some(crystal) = caster.active_crystal_mut() {
             let actual_degradation = result.crystal_degradation * cost_multiplier;
             crystal.degrade(actual_degradation);
         }
 
+        // Channelling resonance wears the focus gear the caster wears
+        result.wear = caster.wear_equipment(crate::systems::items::WearCause::Cast);
+
         // Apply time cost (always applied, full cost regardless of success)
         world.advance_time(result.time_cost);
         caster.playtime_minutes += result.time_cost;
@@ -131,9 +165,11 @@ impl MagicSystem {
         // Only successful spells leave magical signatures and grant full experience
         if result.success {
             // Add magical signature to location
+            // Some styles linger in the air far longer than others
+            let signature = attempt.style.map_or(1.0, |style| style.modifiers().signature);
             world.add_magical_signature(
                 spell_type.to_string(),
-                result.power_level,
+                result.power_level * signature,
                 crystal_frequency,
             );
 
@@ -145,9 +181,62 @@ impl MagicSystem {
             caster.add_experience(crate::core::player::AttributeType::ResonanceSensitivity, reduced_experience);
         }
 
+        self.last_cast = Some((spell_type.to_string(), result.success));
         Ok(result)
     }
 
+    /// The spell cast since this was last asked, and whether it held
+    pub fn take_last_cast(&mut self) -> Option<(String, bool)> {
+        self.last_cast.take()
+    }
+
+    /// The spell cast since the last was taken, and whether it held, leaving it in place
+    pub fn last_cast(&self) -> Option<(&str, bool)> {
+        self.last_cast.as_ref().map(|(spell_type, held)| (spell_type.as_str(), *held))
+    }
+
+    /// Work out the odds and costs of casting a spell right now, without casting it
+    pub fn estimate_magic(
+        &self,
+        spell_type: &str,
+        caster: &Player,
+        world: &WorldState,
+    ) -> GameResult<MagicEstimate> {
+        let (attempt, _) = Self::prepare_attempt(spell_type, caster, None)?;
+        let mut estimate = self.calculation_engine.estimate_attempt(&attempt, caster, world)?;
+        estimate.fatigue_cost += caster.health.extra_cast_fatigue();
+        Ok(estimate)
+    }
+
+    /// Optimal crystal frequency for a spell, if the spell is known
+    pub fn optimal_frequency(&self, spell_type: &str) -> Option<i32> {
+        self.calculation_engine.optimal_frequency(spell_type)
+    }
+
+    /// Build the attempt the caster would make with their crystal, focus and style
+    fn prepare_attempt(
+        spell_type: &str,
+        caster: &Player,
+        target: Option<&str>,
+    ) -> GameResult<(MagicAttempt, Option<components::ComponentUse>)> {
+        // Get active crystal info before any mutable operations
+        let crystal_frequency = caster.active_crystal()
+            .map(|c| c.frequency)
+            .ok_or_else(|| crate::GameError::InsufficientResources("No crystal equipped".to_string()))?;
+
+        // A carried material focus steadies the higher-tier spells
+        let component = components::choose(spell_type, caster);
+
+        // Create magic attempt
+        let mut attempt = MagicAttempt::new(spell_type, crystal_frequency, target);
+        if let Some(component) = &component {
+            attempt = attempt.with_focus(component.success_bonus, component.power_bonus);
+        }
+        attempt.style = caster.styles.active;
+
+        Ok((attempt, component))
+    }
+
     /// Get magic system status for debugging
     pub fn get_status(&self) -> String {
         format!(
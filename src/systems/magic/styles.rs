@@ -0,0 +1,207 @@
+//! Faction casting styles
+//!
+//! Three factions teach their own way of working the core spells, and only
+//! their own mentors will pass it on:
+//! - Council-standard: the licensed method; dependable but formal and loud
+//! - Harmony-attuned: gentle on the caster and the crystal, softer in effect
+//! - Underground-improvised: hard-hitting and hard to trace, but erratic
+//!
+//! A known style can be adopted for every cast. Styles also leave a mark on
+//! how strongly a spell's signature lingers, and faction members recognise
+//! the style of a caster they talk to.
+
+use crate::systems::factions::FactionId;
+use serde::{Deserialize, Serialize};
+
+/// Reputation a faction's mentors require before teaching their style
+pub const MIN_MENTOR_REPUTATION: i32 = 25;
+/// Minutes a lesson with a mentor takes
+pub const LESSON_MINUTES: i32 = 60;
+
+/// A faction's way of casting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpellStyle {
+    CouncilStandard,
+    HarmonyAttuned,
+    UndergroundImprovised,
+}
+
+/// How a style changes a cast, as multipliers unless noted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StyleModifiers {
+    pub energy: f32,
+    pub fatigue: f32,
+    pub power: f32,
+    /// Added to the success probability
+    pub success: f32,
+    /// Strength of the magical signature left behind
+    pub signature: f32,
+    pub crystal_wear: f32,
+}
+
+impl SpellStyle {
+    pub fn all() -> [SpellStyle; 3] {
+        [SpellStyle::CouncilStandard, SpellStyle::HarmonyAttuned, SpellStyle::UndergroundImprovised]
+    }
+
+    /// Parse a style name
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().replace([' ', '_'], "-").as_str() {
+            "council" | "council-standard" | "standard" => Some(SpellStyle::CouncilStandard),
+            "harmony" | "harmony-attuned" | "attuned" => Some(SpellStyle::HarmonyAttuned),
+            "underground" | "underground-improvised" | "improvised" => Some(SpellStyle::UndergroundImprovised),
+            _ => None,
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpellStyle::CouncilStandard => "Council-standard",
+            SpellStyle::HarmonyAttuned => "Harmony-attuned",
+            SpellStyle::UndergroundImprovised => "Underground-improvised",
+        }
+    }
+
+    /// Faction whose mentors teach the style
+    pub fn faction(&self) -> FactionId {
+        match self {
+            SpellStyle::CouncilStandard => FactionId::MagistersCouncil,
+            SpellStyle::HarmonyAttuned => FactionId::OrderOfHarmony,
+            SpellStyle::UndergroundImprovised => FactionId::UndergroundNetwork,
+        }
+    }
+
+    /// Style a faction's mentors teach, if any
+    pub fn taught_by(faction: FactionId) -> Option<Self> {
+        Self::all().into_iter().find(|style| style.faction() == faction)
+    }
+
+    pub fn modifiers(&self) -> StyleModifiers {
+        match self {
+            SpellStyle::CouncilStandard => StyleModifiers {
+                energy: 1.1, fatigue: 0.9, power: 1.0, success: 0.05, signature: 1.3, crystal_wear: 1.0,
+            },
+            SpellStyle::HarmonyAttuned => StyleModifiers {
+                energy: 0.85, fatigue: 0.85, power: 0.9, success: 0.0, signature: 0.7, crystal_wear: 0.7,
+            },
+            SpellStyle::UndergroundImprovised => StyleModifiers {
+                energy: 0.9, fatigue: 1.0, power: 1.2, success: -0.05, signature: 0.4, crystal_wear: 1.3,
+            },
+        }
+    }
+
+    /// What the technique feels like in practice
+    pub fn description(&self) -> &'static str {
+        match self {
+            SpellStyle::CouncilStandard => "Textbook stances and registered frequencies. Reliable and easy on the mind, but the formal cadence costs energy and leaves a loud, traceable signature.",
+            SpellStyle::HarmonyAttuned => "You breathe with the crystal instead of driving it. Cheaper on body and crystal and nearly silent, at the price of some force.",
+            SpellStyle::UndergroundImprovised => "Clipped, off-book shortcuts learned in cellars. Hits harder and barely leaves a trace, but misfires more often and grinds the crystal down.",
+        }
+    }
+
+    /// Name of a core spell worked in this style, e.g. "Harmony-attuned healing"
+    pub fn variant_name(&self, spell_type: &str) -> String {
+        format!("{} {}", self.name(), spell_type)
+    }
+
+    /// Explanation line listing the tradeoffs
+    pub fn explain(&self) -> String {
+        let modifiers = self.modifiers();
+        let percent = |value: f32| format!("{:+.0}%", (value - 1.0) * 100.0);
+        format!(
+            "{} technique: energy {}, fatigue {}, power {}, success {:+.0}%, signature {}, crystal wear {}",
+            self.name(),
+            percent(modifiers.energy), percent(modifiers.fatigue), percent(modifiers.power),
+            modifiers.success * 100.0, percent(modifiers.signature), percent(modifiers.crystal_wear)
+        )
+    }
+
+    /// How a faction member reacts on recognising the style
+    pub fn recognition(&self, speaker: &str, faction: FactionId) -> String {
+        use FactionId::*;
+        let reaction = match (faction, self) {
+            (faction, style) if style.faction() == faction => "recognises your technique as one of their own and nods approvingly",
+            (MagistersCouncil, SpellStyle::UndergroundImprovised) => "notices the unlicensed shortcuts in your casting and frowns",
+            (MagistersCouncil, SpellStyle::HarmonyAttuned) => "notes your Harmony-attuned breathing with polite scepticism",
+            (OrderOfHarmony, SpellStyle::UndergroundImprovised) => "winces at how roughly you handle your crystal",
+            (OrderOfHarmony, SpellStyle::CouncilStandard) => "remarks that Council drills leave little room to listen to the crystal",
+            (UndergroundNetwork, SpellStyle::CouncilStandard) => "eyes your by-the-book stance warily, as if you might be reporting back",
+            (UndergroundNetwork, SpellStyle::HarmonyAttuned) => "seems amused by your unhurried, attuned casting",
+            (IndustrialConsortium, _) => "sizes up your technique for its commercial potential",
+            _ => "takes a scholarly interest in your style of casting",
+        };
+        format!("{} {}.", speaker, reaction)
+    }
+}
+
+/// Styles a character has learned and the one they use
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpellStyles {
+    pub known: Vec<SpellStyle>,
+    pub active: Option<SpellStyle>,
+}
+
+impl SpellStyles {
+    pub fn knows(&self, style: SpellStyle) -> bool {
+        self.known.contains(&style)
+    }
+
+    /// Learn a style, adopting it if no other style is in use
+    pub fn learn(&mut self, style: SpellStyle) {
+        if !self.knows(style) {
+            self.known.push(style);
+        }
+        self.active.get_or_insert(style);
+    }
+
+    /// Known styles and the one in use, for the `style` command
+    pub fn report(&self) -> String {
+        let mut output = String::from("=== CASTING STYLES ===\n");
+        output.push_str(&format!("In use: {}\n", self.active.map_or("plain (no faction style)", |style| style.name())));
+
+        if self.known.is_empty() {
+            output.push_str(&format!(
+                "\nYou know no faction styles. Mentors of the Council, the Order and the Underground\n\
+                 teach their own once your standing with them reaches {}: 'learn <style> from <mentor>'.",
+                MIN_MENTOR_REPUTATION
+            ));
+            return output;
+        }
+
+        for style in &self.known {
+            output.push_str(&format!("\n{}\n  {}\n  {}\n", style.name(), style.description(), style.explain()));
+        }
+        output.push_str("\nSwitch with 'style <name>', or 'style plain' to cast without one.");
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_styles_parse_and_trade_off() {
+        assert_eq!(SpellStyle::from_string("Harmony attuned"), Some(SpellStyle::HarmonyAttuned));
+        assert_eq!(SpellStyle::taught_by(FactionId::UndergroundNetwork), Some(SpellStyle::UndergroundImprovised));
+        assert_eq!(SpellStyle::taught_by(FactionId::NeutralScholars), None);
+
+        // Every style buys something and pays for it
+        for style in SpellStyle::all() {
+            let modifiers = style.modifiers();
+            assert!(modifiers.power > 1.0 || modifiers.energy < 1.0 || modifiers.success > 0.0);
+            assert!(modifiers.power < 1.0 || modifiers.energy > 1.0 || modifiers.success < 0.0);
+        }
+
+        let mut styles = SpellStyles::default();
+        styles.learn(SpellStyle::UndergroundImprovised);
+        styles.learn(SpellStyle::CouncilStandard);
+        assert_eq!(styles.active, Some(SpellStyle::UndergroundImprovised));
+        assert!(styles.report().contains("power +20%"));
+
+        let line = SpellStyle::UndergroundImprovised.recognition("Marcus", FactionId::MagistersCouncil);
+        assert_eq!(line, "Marcus notices the unlicensed shortcuts in your casting and frowns.");
+        assert!(SpellStyle::CouncilStandard.recognition("Ada", FactionId::MagistersCouncil).contains("one of their own"));
+    }
+}
@@ -6,15 +6,63 @@
 //! - Knowledge progression and theory development
 //! - Combat system with magical focus
 //! - Comprehensive item system with educational integration
+//! - Suggestions of what the player could do, gathered from each system
 
 pub mod magic;
 pub mod factions;
 pub mod knowledge;
 pub mod combat;
+pub mod combat_log;
+pub mod enemy_ai;
 pub mod dialogue;
 pub mod quests;
 pub mod quest_examples;
 pub mod items;
+pub mod assessments;
+pub mod concepts;
+pub mod defeat;
+pub mod status_effects;
+pub mod companions;
+pub mod negotiation;
+pub mod coop;
+pub mod resting;
+pub mod wards;
+pub mod ground_items;
+pub mod placed_items;
+pub mod site_flux;
+pub mod weather;
+pub mod calculator;
+pub mod legacy_notes;
+pub mod onboarding;
+pub mod timed_effects;
+pub mod reward_negotiation;
+pub mod services;
+pub mod phenomena;
+pub mod encumbrance;
+pub mod fast_analysis;
+pub mod codex;
+pub mod statistics;
+pub mod quest_generator;
+pub mod quest_journal;
+pub mod quest_hints;
+pub mod quest_fallout;
+pub mod world_flags;
+pub mod location_changes;
+pub mod access;
+pub mod secrets;
+pub mod scenery;
+pub mod laboratory;
+pub mod ambience;
+pub mod wanted;
+pub mod stealth;
+pub mod emissaries;
+pub mod endings;
+pub mod relationships;
+pub mod bonds;
+pub mod rumors;
+pub mod followers;
+pub mod text_templates;
+pub mod suggestions;
 pub mod serde_helpers;
 
 
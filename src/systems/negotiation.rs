@@ -0,0 +1,215 @@
+//! Non-lethal ways to end a combat encounter
+//!
+//! Instead of wearing an enemy down to nothing, the player can try to talk
+//! or frighten their way out of a fight. Each tactic is a skill check built
+//! from the player's attributes, their standing with the enemy's faction and
+//! the state of the fight, and each success ends the encounter differently:
+//! - Parley: a truce that improves standing with the enemy's faction
+//! - Intimidate: the enemy flees, but its faction resents the threat
+//! - Demoralize: a beaten enemy yields and hands over what it carries
+//! - Surrender: always accepted, at the cost of silver and pride
+//!
+//! A failed attempt costs the player's action, and every failure makes the
+//! next attempt in the same fight less likely to work.
+
+use crate::core::Player;
+use crate::systems::combat::{AiProfile, DifficultyTier, Enemy};
+use crate::systems::factions::FactionId;
+use crate::systems::status_effects::StatusEffects;
+use serde::{Deserialize, Serialize};
+
+/// Chance lost for each failed attempt in the same encounter
+const FAILED_ATTEMPT_PENALTY: f32 = 0.1;
+/// Fraction of the player's silver handed over on surrender
+const SURRENDER_SILVER_FRACTION: f32 = 0.33;
+/// Fatigue from the humiliation of surrender
+const SURRENDER_FATIGUE: i32 = 15;
+
+/// A non-lethal tactic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tactic {
+    Parley,
+    Intimidate,
+    Demoralize,
+    Surrender,
+}
+
+impl Tactic {
+    /// Parse a tactic name
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "parley" | "negotiate" => Some(Tactic::Parley),
+            "intimidate" | "threaten" => Some(Tactic::Intimidate),
+            "demoralize" | "demoralise" => Some(Tactic::Demoralize),
+            "surrender" | "yield" => Some(Tactic::Surrender),
+            _ => None,
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tactic::Parley => "parley",
+            Tactic::Intimidate => "intimidate",
+            Tactic::Demoralize => "demoralize",
+            Tactic::Surrender => "surrender",
+        }
+    }
+}
+
+/// What a successful tactic changes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Consequences {
+    /// Fraction of the enemy's experience reward granted
+    pub experience_fraction: f32,
+    /// Whether the enemy's loot is handed over
+    pub loot: bool,
+    /// Reputation change with the enemy's faction
+    pub reputation: i32,
+    /// Silver the player loses
+    pub silver_lost: i32,
+    /// Fatigue the player gains
+    pub fatigue: i32,
+    /// How the encounter ends
+    pub narration: String,
+}
+
+/// Probability that a tactic works against this enemy
+///
+/// Returns an error message when the tactic cannot work at all.
+pub fn success_chance(
+    tactic: Tactic,
+    enemy: &Enemy,
+    enemy_effects: &StatusEffects,
+    player: &Player,
+    failed_attempts: u32,
+) -> Result<f32, String> {
+    let wounds = 1.0 - enemy.health_percentage();
+    let energy_ratio = player.mental_state.current_energy as f32 / player.mental_state.max_energy.max(1) as f32;
+
+    let base = match tactic {
+        Tactic::Surrender => return Ok(1.0),
+        Tactic::Parley => {
+            let Some(faction) = enemy.faction_affiliation else {
+                return Err(format!("{} cannot be reasoned with.", enemy.name));
+            };
+            0.35 + player.faction_reputation(faction) as f32 / 200.0
+                + (player.attributes.mental_acuity - 25) as f32 / 200.0
+        }
+        Tactic::Intimidate => {
+            if enemy.ai_profile == AiProfile::Relentless {
+                return Err(format!("{} is beyond fear.", enemy.name));
+            }
+            let temperament = match enemy.ai_profile {
+                AiProfile::Cautious => 0.2,
+                AiProfile::Aggressive | AiProfile::Berserk => -0.15,
+                _ => 0.0,
+            };
+            0.25 + wounds * 0.3 + energy_ratio * 0.2 + temperament
+        }
+        Tactic::Demoralize => {
+            if enemy.ai_profile == AiProfile::Relentless {
+                return Err(format!("{} will never yield.", enemy.name));
+            }
+            0.1 + wounds * 0.5
+                + enemy_effects.effects().len() as f32 * 0.1
+                + player.attributes.resonance_sensitivity as f32 / 200.0
+        }
+    };
+
+    let tier_penalty = match enemy.difficulty_tier {
+        DifficultyTier::Beginner => 0.0,
+        DifficultyTier::Intermediate => 0.05,
+        DifficultyTier::Advanced => 0.15,
+        DifficultyTier::Boss => 0.3,
+    };
+
+    Ok((base - tier_penalty - failed_attempts as f32 * FAILED_ATTEMPT_PENALTY).clamp(0.05, 0.95))
+}
+
+/// Outcome of a successful tactic
+pub fn consequences(tactic: Tactic, enemy: &Enemy, player: &Player) -> Consequences {
+    match tactic {
+        Tactic::Parley => Consequences {
+            experience_fraction: 0.5,
+            loot: false,
+            reputation: 5,
+            silver_lost: 0,
+            fatigue: 0,
+            narration: format!("You talk {} down. Both of you walk away.", enemy.name),
+        },
+        Tactic::Intimidate => Consequences {
+            experience_fraction: 0.5,
+            loot: false,
+            reputation: -3,
+            silver_lost: 0,
+            fatigue: 0,
+            narration: format!("{} breaks and flees before your resonance.", enemy.name),
+        },
+        Tactic::Demoralize => Consequences {
+            experience_fraction: 0.75,
+            loot: true,
+            reputation: 0,
+            silver_lost: 0,
+            fatigue: 0,
+            narration: format!("{} lowers its guard and yields what it carries.", enemy.name),
+        },
+        Tactic::Surrender => Consequences {
+            experience_fraction: 0.0,
+            loot: false,
+            reputation: 0,
+            silver_lost: (player.inventory.silver as f32 * SURRENDER_SILVER_FRACTION) as i32,
+            fatigue: SURRENDER_FATIGUE,
+            narration: format!("You yield to {}, and are let go after handing over some silver.", enemy.name),
+        },
+    }
+}
+
+/// Reputation change applied to the enemy's faction, if it has one
+pub fn faction_change(enemy: &Enemy, consequences: &Consequences) -> Option<(FactionId, i32)> {
+    enemy.faction_affiliation
+        .filter(|_| consequences.reputation != 0)
+        .map(|faction| (faction, consequences.reputation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::status_effects::StatusKind;
+
+    fn enemy(profile: AiProfile) -> Enemy {
+        Enemy::new("e".to_string(), "Rogue".to_string(), "x".to_string(), DifficultyTier::Intermediate)
+            .with_faction(FactionId::UndergroundNetwork)
+            .with_ai_profile(profile)
+    }
+
+    #[test]
+    fn test_chances_respond_to_standing_and_wounds() {
+        let mut player = Player::new("Test".to_string());
+        let mut effects = StatusEffects::new();
+        let mut rogue = enemy(AiProfile::Balanced);
+
+        let cold = success_chance(Tactic::Parley, &rogue, &effects, &player, 0).unwrap();
+        player.modify_faction_reputation(FactionId::UndergroundNetwork, 60);
+        let warm = success_chance(Tactic::Parley, &rogue, &effects, &player, 0).unwrap();
+        assert!(warm > cold);
+        assert!(success_chance(Tactic::Parley, &rogue, &effects, &player, 2).unwrap() < warm);
+
+        let fresh = success_chance(Tactic::Demoralize, &rogue, &effects, &player, 0).unwrap();
+        rogue.take_damage(rogue.max_health / 2);
+        effects.apply(StatusKind::Disorient, 2, "Rogue");
+        assert!(success_chance(Tactic::Demoralize, &rogue, &effects, &player, 0).unwrap() > fresh);
+    }
+
+    #[test]
+    fn test_unworkable_tactics() {
+        let player = Player::new("Test".to_string());
+        let effects = StatusEffects::new();
+        let beast = Enemy::new("b".to_string(), "Anomaly".to_string(), "x".to_string(), DifficultyTier::Beginner);
+
+        assert!(success_chance(Tactic::Parley, &beast, &effects, &player, 0).is_err());
+        assert!(success_chance(Tactic::Intimidate, &enemy(AiProfile::Relentless), &effects, &player, 0).is_err());
+        assert_eq!(success_chance(Tactic::Surrender, &beast, &effects, &player, 5), Ok(1.0));
+        assert_eq!(Tactic::from_string("Threaten"), Some(Tactic::Intimidate));
+    }
+}
@@ -0,0 +1,181 @@
+//! Onboarding checklist for new players
+//!
+//! A short list of core competencies every player should pick up early:
+//! moving between rooms, examining something, casting a spell, studying a
+//! theory and saving the game. The checklist is not a quest; it listens for
+//! engine events after each command, ticks items off as they happen, and
+//! after a stretch without progress offers a nudge suited to where the player
+//! is. Players who already know the game can dismiss it at any time.
+
+use serde::{Deserialize, Serialize};
+
+/// Commands without progress before a nudge is offered
+const NUDGE_INTERVAL: u32 = 6;
+
+/// A core competency tracked by the checklist
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Competency {
+    Moved,
+    Examined,
+    CastSpell,
+    Studied,
+    Saved,
+}
+
+impl Competency {
+    /// Every competency, in the order they are suggested
+    pub const ALL: [Competency; 5] = [
+        Competency::Moved,
+        Competency::Examined,
+        Competency::CastSpell,
+        Competency::Studied,
+        Competency::Saved,
+    ];
+
+    /// Checklist entry
+    pub fn label(&self) -> &'static str {
+        match self {
+            Competency::Moved => "Move between rooms",
+            Competency::Examined => "Examine something up close",
+            Competency::CastSpell => "Cast a spell",
+            Competency::Studied => "Study a theory",
+            Competency::Saved => "Save the game",
+        }
+    }
+
+    /// Suggestion for how to do it
+    fn nudge(&self) -> &'static str {
+        match self {
+            Competency::Moved => "There are exits from here - try 'north' or 'go east' to explore.",
+            Competency::Examined => "Something here is worth a closer look - try 'examine <thing>'.",
+            Competency::CastSpell => "Your crystal can focus a spell - try 'cast light using quartz'.",
+            Competency::Studied => "Magic rests on theory - try 'study harmonic fundamentals'.",
+            Competency::Saved => "Keep your progress safe - type 'save' now and then.",
+        }
+    }
+}
+
+/// What the player's surroundings offer, for choosing a fitting nudge
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Surroundings {
+    pub has_exits: bool,
+    pub has_things: bool,
+    pub in_combat: bool,
+}
+
+/// Progress through the checklist, saved with the character
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Onboarding {
+    pub completed: Vec<Competency>,
+    /// Hidden by the player; progress is still tracked quietly
+    pub dismissed: bool,
+    /// Commands since the last progress or nudge
+    #[serde(default)]
+    pub idle_commands: u32,
+}
+
+impl Onboarding {
+    /// Whether every competency has been shown
+    pub fn is_complete(&self) -> bool {
+        Competency::ALL.iter().all(|competency| self.completed.contains(competency))
+    }
+
+    /// Record the events of a command, returning what to tell the player
+    pub fn observe(&mut self, events: &[Competency], surroundings: Surroundings) -> Option<String> {
+        if self.is_complete() {
+            return None;
+        }
+
+        let newly: Vec<Competency> = events.iter()
+            .copied()
+            .filter(|competency| !self.completed.contains(competency))
+            .collect();
+        if !newly.is_empty() {
+            self.completed.extend(newly.iter().copied());
+            self.idle_commands = 0;
+            if self.dismissed {
+                return None;
+            }
+            let done = self.completed.len();
+            let mut message = newly.iter()
+                .map(|competency| format!("[Checklist] ✓ {} ({}/{})", competency.label(), done, Competency::ALL.len()))
+                .collect::<Vec<_>>()
+                .join("\n");
+            if self.is_complete() {
+                message.push_str("\n[Checklist] You have the basics. Type 'help' whenever you want more.");
+            }
+            return Some(message);
+        }
+
+        self.idle_commands += 1;
+        if self.dismissed || surroundings.in_combat || self.idle_commands < NUDGE_INTERVAL {
+            return None;
+        }
+        self.idle_commands = 0;
+        self.next_step(surroundings).map(|competency| format!("[Checklist] {}", competency.nudge()))
+    }
+
+    /// The outstanding competency that best fits the surroundings
+    fn next_step(&self, surroundings: Surroundings) -> Option<Competency> {
+        Competency::ALL.iter()
+            .copied()
+            .filter(|competency| !self.completed.contains(competency))
+            .find(|competency| match competency {
+                Competency::Moved => surroundings.has_exits,
+                Competency::Examined => surroundings.has_things,
+                _ => true,
+            })
+    }
+
+    /// The checklist as shown by the `checklist` command
+    pub fn render(&self) -> String {
+        let mut output = "=== Getting Started ===\n".to_string();
+        for competency in Competency::ALL {
+            let mark = if self.completed.contains(&competency) { "✓" } else { " " };
+            output.push_str(&format!("[{}] {}\n", mark, competency.label()));
+        }
+        if self.is_complete() {
+            output.push_str("\nAll done - you have the basics.");
+        } else if self.dismissed {
+            output.push_str("\nThe checklist is hidden. Use 'checklist show' to bring back its reminders.");
+        } else {
+            output.push_str("\nUse 'checklist dismiss' to hide these reminders.");
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checklist_ticks_off_competencies_and_nudges_when_idle() {
+        let mut onboarding = Onboarding::default();
+        let indoors = Surroundings { has_exits: false, has_things: true, in_combat: false };
+
+        let message = onboarding.observe(&[Competency::Moved], indoors).unwrap();
+        assert!(message.contains("✓ Move between rooms (1/5)"));
+        assert!(onboarding.observe(&[Competency::Moved], indoors).is_none());
+
+        // Idle commands lead to a nudge fitting the surroundings
+        for _ in 0..NUDGE_INTERVAL - 2 {
+            assert!(onboarding.observe(&[], indoors).is_none());
+        }
+        assert!(onboarding.observe(&[], indoors).unwrap().contains("examine"));
+        let bare = Surroundings { has_things: false, ..indoors };
+        assert_eq!(onboarding.next_step(bare), Some(Competency::CastSpell));
+
+        // Dismissing silences the checklist without losing progress
+        onboarding.dismissed = true;
+        assert!(onboarding.observe(&[Competency::Saved], indoors).is_none());
+        assert!(onboarding.completed.contains(&Competency::Saved));
+
+        onboarding.dismissed = false;
+        onboarding.observe(&[Competency::Examined, Competency::CastSpell], indoors);
+        let message = onboarding.observe(&[Competency::Studied], indoors).unwrap();
+        assert!(message.contains("You have the basics"));
+        assert!(onboarding.is_complete());
+        assert!(onboarding.observe(&[], indoors).is_none());
+    }
+}
@@ -0,0 +1,236 @@
+//! Lasting consequences of crafting and experiments gone badly wrong
+//!
+//! A critical failure leaves its mark on the location where it happened:
+//! - A contaminated bench: a crafting station fouled by a ruined recipe.
+//!   Nothing can be made there until it is scrubbed down with resonant
+//!   solvent.
+//! - A wild resonance pocket: loose energy from a failed field recipe or a
+//!   combination that snapped back. It raises the location's interference,
+//!   weakening magic and study there, and drags at every spell cast nearby
+//!   until someone grounds it through their own focus.
+//!
+//! Phenomena persist with the world until cleaned up. People nearby remark
+//! on them, and the location description mentions them to anyone passing.
+
+use crate::core::{Player, WorldState};
+use crate::systems::location_changes::LocationChange;
+use serde::{Deserialize, Serialize};
+
+/// Interference a wild resonance pocket adds to its location
+const POCKET_INTERFERENCE: f32 = 0.3;
+/// Extra fatigue from casting beside a wild resonance pocket
+pub const POCKET_FATIGUE: i32 = 5;
+/// How far a roll must miss its chance for a crafting failure to be critical
+pub const CRITICAL_FAILURE_MARGIN: f32 = 0.3;
+/// Item used up scrubbing a contaminated bench
+pub const CLEANING_AGENT: &str = "resonant solvent";
+/// Minutes spent scrubbing a bench
+const SCRUB_MINUTES: i32 = 30;
+/// Mental energy and fatigue spent grounding a pocket
+const GROUNDING_ENERGY: i32 = 15;
+const GROUNDING_FATIGUE: i32 = 10;
+/// Minutes spent grounding a pocket
+const GROUNDING_MINUTES: i32 = 20;
+
+/// Kinds of lasting phenomenon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PhenomenonKind {
+    ContaminatedBench,
+    WildResonancePocket,
+}
+
+/// A phenomenon left at a location
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Phenomenon {
+    pub kind: PhenomenonKind,
+    pub location: String,
+    /// Crafting station fouled, for a contaminated bench
+    pub station: Option<String>,
+    /// Game time (minutes) it appeared
+    pub since: i32,
+}
+
+impl Phenomenon {
+    /// Name used in descriptions and to pick it out for cleaning
+    pub fn name(&self) -> String {
+        match self.kind {
+            PhenomenonKind::ContaminatedBench => format!("contaminated {}", self.station.as_deref().unwrap_or("bench")),
+            PhenomenonKind::WildResonancePocket => "wild resonance pocket".to_string(),
+        }
+    }
+
+    /// Line for the location description
+    pub fn describe(&self) -> String {
+        match self.kind {
+            PhenomenonKind::ContaminatedBench => format!(
+                "The {} is crusted with ruined reagent that fizzes faintly. Nothing can be made there until it's \
+                 scrubbed down with {}. ('clean up {}')",
+                self.station.as_deref().unwrap_or("bench"), CLEANING_AGENT, self.station.as_deref().unwrap_or("bench")
+            ),
+            PhenomenonKind::WildResonancePocket => "A wild resonance pocket crackles in the air, setting your teeth on edge \
+                 and dragging at any spell cast nearby. ('clean up pocket' to ground it)".to_string(),
+        }
+    }
+
+    /// What someone at the location says about it
+    pub fn remark(&self, speaker: &str) -> String {
+        match self.kind {
+            PhenomenonKind::ContaminatedBench => format!(
+                "{} wrinkles their nose at the {}. \"Whoever fouled that had better clean it up.\"",
+                speaker, self.station.as_deref().unwrap_or("bench")
+            ),
+            PhenomenonKind::WildResonancePocket => format!(
+                "{} keeps glancing at the crackling air. \"That pocket's been loose since someone's experiment went wrong. \
+                 It needs grounding before someone gets hurt.\"",
+                speaker
+            ),
+        }
+    }
+}
+
+/// Every phenomenon in the world, saved with it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Phenomena {
+    pub active: Vec<Phenomenon>,
+}
+
+impl Phenomena {
+    /// Phenomena at a location
+    pub fn at<'a>(&'a self, location: &'a str) -> impl Iterator<Item = &'a Phenomenon> + 'a {
+        self.active.iter().filter(move |phenomenon| phenomenon.location == location)
+    }
+
+    /// Whether a station at a location is contaminated
+    pub fn contaminated(&self, location: &str, station: &str) -> bool {
+        self.at(location).any(|phenomenon| {
+            phenomenon.kind == PhenomenonKind::ContaminatedBench && phenomenon.station.as_deref() == Some(station)
+        })
+    }
+
+    /// Whether a wild resonance pocket is loose at a location
+    pub fn has_pocket(&self, location: &str) -> bool {
+        self.at(location).any(|phenomenon| phenomenon.kind == PhenomenonKind::WildResonancePocket)
+    }
+
+    /// Lines for the location description
+    pub fn describe(&self, location: &str) -> Option<String> {
+        let lines: Vec<String> = self.at(location).map(Phenomenon::describe).collect();
+        if lines.is_empty() { None } else { Some(lines.join("\n")) }
+    }
+}
+
+/// Leave a phenomenon at the player's location, returning a line for the player
+///
+/// A phenomenon already present is not doubled up.
+pub fn spawn(world: &mut WorldState, kind: PhenomenonKind, station: Option<String>) -> Option<String> {
+    let phenomenon = Phenomenon { kind, location: world.current_location.clone(), station, since: world.game_time_minutes };
+    if world.phenomena.active.iter().any(|existing| existing.kind == kind && existing.location == phenomenon.location
+        && existing.station == phenomenon.station)
+    {
+        return None;
+    }
+
+    if kind == PhenomenonKind::WildResonancePocket {
+        let location = phenomenon.location.clone();
+        let _ = world.change_location(&location, LocationChange::AdjustInterference { by: POCKET_INTERFERENCE });
+        let _ = world.change_location(&location, LocationChange::AddPhenomenon { name: phenomenon.name() });
+    }
+    let message = match kind {
+        PhenomenonKind::ContaminatedBench => format!(
+            "The ruined mixture splashes across the {} and sets there, fizzing. It will need cleaning before it's usable again.",
+            phenomenon.station.as_deref().unwrap_or("bench")
+        ),
+        PhenomenonKind::WildResonancePocket => "The released energy doesn't dissipate. It knots into a wild resonance pocket \
+             that crackles in the air around you.".to_string(),
+    };
+    world.phenomena.active.push(phenomenon);
+    Some(message)
+}
+
+/// Clean up a phenomenon at the player's location, returning a description
+pub fn clean_up(world: &mut WorldState, player: &mut Player, query: Option<&str>) -> crate::GameResult<String> {
+    let here: Vec<usize> = world.phenomena.active.iter()
+        .enumerate()
+        .filter(|(_, phenomenon)| phenomenon.location == world.current_location)
+        .filter(|(_, phenomenon)| query.is_none_or(|query| phenomenon.name().contains(query.trim())))
+        .map(|(index, _)| index)
+        .collect();
+    let index = match (here.as_slice(), query) {
+        ([], None) => return Ok("There's nothing here that needs cleaning up.".to_string()),
+        ([], Some(query)) => {
+            return Err(crate::GameError::ContentNotFound(format!("There's no {} here to clean up.", query)).into());
+        }
+        ([index], _) => *index,
+        (several, _) => {
+            let names: Vec<String> = several.iter().map(|index| world.phenomena.active[*index].name()).collect();
+            return Ok(format!("Clean up which? {}.", names.join(", ")));
+        }
+    };
+
+    let phenomenon = world.phenomena.active[index].clone();
+    let (minutes, message) = match phenomenon.kind {
+        PhenomenonKind::ContaminatedBench => {
+            let (solvent, _) = crate::systems::items::combination::find_carried(player, CLEANING_AGENT)
+                .ok_or_else(|| crate::GameError::InsufficientResources(format!(
+                    "Scrubbing the {} clean takes {}, and you have none.", phenomenon.name(), CLEANING_AGENT
+                )))?;
+            player.remove_enhanced_item(&solvent)?;
+            (SCRUB_MINUTES, format!(
+                "You work the {} into the fouled {} until the residue lifts away. It's fit for use again.",
+                CLEANING_AGENT, phenomenon.station.as_deref().unwrap_or("bench")
+            ))
+        }
+        PhenomenonKind::WildResonancePocket => {
+            player.use_mental_energy(GROUNDING_ENERGY, GROUNDING_FATIGUE)?;
+            let location = phenomenon.location.clone();
+            let _ = world.change_location(&location, LocationChange::AdjustInterference { by: -POCKET_INTERFERENCE });
+            let _ = world.change_location(&location, LocationChange::RemovePhenomenon { name: phenomenon.name() });
+            (GROUNDING_MINUTES, format!(
+                "You draw the pocket's loose energy into your focus and let it bleed away. The air goes still. \
+                 (Energy -{}, Fatigue +{})",
+                GROUNDING_ENERGY, GROUNDING_FATIGUE
+            ))
+        }
+    };
+
+    world.phenomena.active.remove(index);
+    world.advance_time(minutes);
+    player.playtime_minutes += minutes;
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phenomena_persist_until_cleaned_up() {
+        let mut world = WorldState::new();
+        let mut player = Player::new("Ada".to_string());
+        world.current_location = "crystal_garden_lab".to_string();
+        world.add_location(crate::core::world_state::Location::new(
+            "crystal_garden_lab".to_string(), "Garden Lab".to_string(), "Green.".to_string(),
+        ));
+
+        assert!(spawn(&mut world, PhenomenonKind::ContaminatedBench, Some("alchemy bench".to_string())).is_some());
+        assert!(spawn(&mut world, PhenomenonKind::WildResonancePocket, None).is_some());
+        assert!(spawn(&mut world, PhenomenonKind::WildResonancePocket, None).is_none());
+        assert!(world.phenomena.contaminated("crystal_garden_lab", "alchemy bench"));
+        assert!(!world.phenomena.contaminated("crystal_garden_lab", "optics bench"));
+        assert!((world.current_location().unwrap().magical_properties.interference - POCKET_INTERFERENCE).abs() < 1e-6);
+        assert!(world.phenomena.describe("crystal_garden_lab").unwrap().contains("wild resonance pocket"));
+
+        // Several here, so the player must say which
+        assert!(clean_up(&mut world, &mut player, None).unwrap().starts_with("Clean up which?"));
+        // No solvent, no scrubbing
+        assert!(clean_up(&mut world, &mut player, Some("bench")).is_err());
+
+        clean_up(&mut world, &mut player, Some("pocket")).unwrap();
+        assert!(!world.phenomena.has_pocket("crystal_garden_lab"));
+        assert_eq!(world.current_location().unwrap().magical_properties.interference, 0.0);
+        assert!(world.current_location().unwrap().magical_properties.phenomena.is_empty());
+        assert!(world.location_changes.changes("crystal_garden_lab").iter()
+            .all(|change| !matches!(change, LocationChange::AdjustInterference { .. })));
+        assert_eq!(world.phenomena.active.len(), 1);
+    }
+}
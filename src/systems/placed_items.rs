@@ -0,0 +1,139 @@
+//! Items placed in the world by content
+//!
+//! Each location can be stocked with items defined in the database. A
+//! placement is either one-time, gone for good once taken, or respawning,
+//! coming back a set number of minutes after it was taken (spring water that
+//! refills, stones that can always be gathered again). Placed item IDs are
+//! their names with underscores, so `Location.items` can be shown without a
+//! database lookup.
+//!
+//! What the player takes is removed from the location, which is saved with
+//! the world; respawns still waiting are saved here. Items the player drops
+//! are handled by [`crate::systems::ground_items`] instead.
+
+use crate::systems::items::core::{Item, ItemType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Custom property recording which definition an item was made from
+pub const DEFINITION_PROPERTY: &str = "definition";
+
+/// An item as defined in the content database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemDefinition {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub item_type: ItemType,
+    /// Extra properties copied onto every instance
+    #[serde(default)]
+    pub custom_properties: HashMap<String, String>,
+}
+
+impl ItemDefinition {
+    /// Make a fresh instance of the item
+    pub fn instantiate(&self) -> Item {
+        let mut item = Item::new_basic(self.name.clone(), self.description.clone(), self.item_type.clone());
+        for (key, value) in &self.custom_properties {
+            item.set_custom_property(key.clone(), value.clone());
+        }
+        item.set_custom_property(DEFINITION_PROPERTY.to_string(), self.id.clone());
+        item
+    }
+}
+
+/// How many of each defined item the player carries, by definition ID
+pub fn carried_definitions(player: &crate::core::Player) -> HashMap<String, i32> {
+    let mut carried = HashMap::new();
+    if let Some(items) = player.enhanced_item_system() {
+        for item in items.inventory_manager.items.values() {
+            if let Some(definition) = item.get_custom_property(DEFINITION_PROPERTY) {
+                *carried.entry(definition.clone()).or_insert(0) += 1;
+            }
+        }
+    }
+    carried
+}
+
+/// An item stocked at a location
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Placement {
+    pub location: String,
+    pub item_id: String,
+    /// Minutes after being taken that the item comes back; `None` for one-time items
+    pub respawn_minutes: Option<i32>,
+}
+
+/// Display name of a placed item ID
+pub fn display_name(item_id: &str) -> String {
+    item_id.replace('_', " ")
+}
+
+/// A taken item waiting to come back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Respawn {
+    pub location: String,
+    pub item_id: String,
+    /// Game time (minutes) at which it returns
+    pub at: i32,
+}
+
+/// Respawns still waiting, saved with the world
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlacedItems {
+    pub pending: Vec<Respawn>,
+}
+
+impl PlacedItems {
+    /// Note that a placed item was taken, scheduling its return if it respawns
+    pub fn taken(&mut self, placement: &Placement, now: i32) {
+        if let Some(minutes) = placement.respawn_minutes {
+            self.pending.push(Respawn {
+                location: placement.location.clone(),
+                item_id: placement.item_id.clone(),
+                at: now + minutes,
+            });
+        }
+    }
+
+    /// Remove and return every respawn due by now
+    pub fn due(&mut self, now: i32) -> Vec<Respawn> {
+        let (due, waiting) = std::mem::take(&mut self.pending).into_iter().partition(|respawn| respawn.at <= now);
+        self.pending = waiting;
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_respawns_come_back_when_due() {
+        let mut placed = PlacedItems::default();
+        let water = Placement {
+            location: "crystal_garden_lab".to_string(),
+            item_id: "spring_water".to_string(),
+            respawn_minutes: Some(120),
+        };
+        let lens = Placement { item_id: "cracked_lens".to_string(), respawn_minutes: None, ..water.clone() };
+
+        placed.taken(&water, 100);
+        placed.taken(&lens, 100);
+        assert_eq!(placed.pending.len(), 1);
+        assert!(placed.due(219).is_empty());
+        assert_eq!(placed.due(220)[0].item_id, "spring_water");
+        assert!(placed.pending.is_empty());
+
+        let definition = ItemDefinition {
+            id: "spring_water".to_string(),
+            name: display_name("spring_water"),
+            description: "Cold, clear water.".to_string(),
+            item_type: ItemType::Material { material_type: "water".to_string(), quality: 0.6 },
+            custom_properties: HashMap::new(),
+        };
+        let item = definition.instantiate();
+        assert_eq!(item.properties.name, "spring water");
+        assert_eq!(item.get_custom_property(DEFINITION_PROPERTY).map(String::as_str), Some("spring_water"));
+    }
+}
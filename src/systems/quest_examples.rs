@@ -9,7 +9,8 @@
 
 use crate::systems::quests::*;
 use crate::systems::factions::FactionId;
-use crate::systems::dialogue::{NPC, NPCPersonality, QuestDialogue, DialogueTree, DialogueNode, DialogueRequirements};
+use crate::systems::location_changes::{LocationChange, LocationEdit};
+use crate::systems::dialogue::{NPC, NPCAvailability, NPCPersonality, QuestDialogue, DialogueTree, DialogueNode, DialogueRequirements};
 use std::collections::HashMap;
 
 /// Create the complete set of example quests for the game
@@ -23,6 +24,323 @@ pub fn create_example_quests() -> Vec<QuestDefinition> {
     ]
 }
 
+/// Quests offered only after another quest fails, to clean up what it left behind
+pub fn create_cleanup_quests() -> Vec<QuestDefinition> {
+    vec![create_mending_the_rift_quest()]
+}
+
+/// Cleanup quest: "Mending the Rift" - after the Diplomatic Balance fails
+/// Rebuild trust between the Council and the Underground one conversation at a time
+fn create_mending_the_rift_quest() -> QuestDefinition {
+    let mut faction_changes = HashMap::new();
+    faction_changes.insert(FactionId::MagistersCouncil, 5);
+    faction_changes.insert(FactionId::UndergroundNetwork, 5);
+
+    let talk_to = |id: &str, npc_id: &str, description: &str| QuestObjective {
+        id: id.to_string(),
+        description: description.to_string(),
+        objective_type: ObjectiveType::TalkToNPC { npc_id: npc_id.to_string(), topic: None },
+        optional: false,
+        visible: true,
+        completion_reward: ObjectiveReward {
+            experience: 30,
+            theory_insights: HashMap::new(),
+            faction_changes: HashMap::new(),
+            items: vec![],
+        },
+    };
+
+    QuestDefinition {
+        id: "mending_the_rift".to_string(),
+        title: "Mending the Rift".to_string(),
+        description: "The failed negotiation left the Council and the Underground further apart than \
+                     ever, and Ambassador Cordelia is gone. No treaty will come of it now, but the people \
+                     who were in the room still talk to you. Carrying words between them may keep the \
+                     rift from widening into something worse.".to_string(),
+        category: QuestCategory::Political,
+        difficulty: QuestDifficulty::Intermediate,
+
+        requirements: QuestRequirements {
+            theory_requirements: vec![],
+            faction_requirements: vec![],
+            faction_restrictions: vec![],
+            prerequisite_quests: vec![],
+            attribute_requirements: AttributeRequirements {
+                min_mental_acuity: None,
+                min_resonance_sensitivity: None,
+                min_total_playtime: None,
+            },
+            capability_requirements: vec![],
+            location_requirements: vec![],
+            world_flags: vec![],
+        },
+
+        objectives: vec![
+            talk_to("hear_the_council", "observer_lyra", "Hear what the Council's observer thinks went wrong"),
+            talk_to("hear_the_underground", "echo_voidwalker", "Carry the Council's account to the Underground"),
+        ],
+
+        rewards: QuestRewards {
+            experience: 120,
+            attribute_bonuses: AttributeBonuses {
+                mental_acuity: Some(1),
+                resonance_sensitivity: None,
+            },
+            theory_bonuses: HashMap::new(),
+            faction_changes,
+            items: vec![],
+            new_capabilities: vec![],
+            unlocked_quests: vec![],
+        },
+
+        faction_effects: HashMap::new(),
+        educational_focus: EducationalObjectives {
+            primary_concepts: vec!["Conflict Resolution".to_string()],
+            secondary_concepts: vec![],
+            applications: vec!["Rebuilding Trust".to_string()],
+            problem_solving_methods: vec!["Perspective Taking".to_string()],
+            assessment_criteria: vec![],
+        },
+
+        branching_paths: HashMap::new(),
+        choices: vec![],
+        involved_npcs: vec!["observer_lyra".to_string(), "echo_voidwalker".to_string()],
+        locations: vec!["faction_diplomacy_hall".to_string()],
+        estimated_duration: 60,
+        deadline: None,
+        failure_triggers: vec![],
+        fallout: None,
+        cleans_up: Some("diplomatic_balance".to_string()),
+        repeat: None,
+        sets_flags: vec![],
+        changes_locations: vec![],
+    }
+}
+
+/// Repeatable work offering a steady income of silver and standing
+///
+/// Payment in silver is settled when the quest is turned in, like any other.
+pub fn create_repeatable_quests() -> Vec<QuestDefinition> {
+    let visit = |id: &str, location_id: &str, description: &str| (
+        id.to_string(), description.to_string(), ObjectiveType::VisitLocation { location_id: location_id.to_string() },
+    );
+    let talk_to = |id: &str, npc_id: &str, description: &str| (
+        id.to_string(), description.to_string(), ObjectiveType::TalkToNPC { npc_id: npc_id.to_string(), topic: None },
+    );
+
+    let mut tutoring = create_repeatable_quest(
+        "tutoring_gig",
+        "Tutoring Gig",
+        "Assistant Thomas is struggling with harmonic theory and has offered to pay for an \
+         afternoon of tutoring. Explaining it well takes a sound grasp of it yourself.",
+        QuestCategory::Social,
+        vec![talk_to("tutor_thomas", "assistant_thomas", "Tutor Assistant Thomas in harmonic theory")],
+        (FactionId::NeutralScholars, 2),
+        2,
+    );
+    tutoring.requirements.theory_requirements = vec![("harmonic_fundamentals".to_string(), 0.5)];
+
+    vec![
+        create_repeatable_quest(
+            "research_commission",
+            "Research Commission",
+            "The Archives pay for fresh readings from the Crystal Garden, where living growth \
+             keeps shifting the local frequencies. Take the measurements and bring them to \
+             Sage Meridian.",
+            QuestCategory::Research,
+            vec![
+                visit("take_readings", "crystal_garden_lab", "Take frequency readings in the Crystal Garden"),
+                talk_to("deliver_readings", "sage_meridian", "Deliver the readings to Sage Meridian"),
+            ],
+            (FactionId::NeutralScholars, 3),
+            1,
+        ),
+        create_repeatable_quest(
+            "patrol_duty",
+            "Patrol Duty",
+            "Warden Gareth needs someone to walk the circuit between the Observatory and the \
+             Crystal Garden, checking that nothing has slipped its containment, and report back.",
+            QuestCategory::Practical,
+            vec![
+                visit("patrol_observatory", "resonance_observatory", "Check in at the Resonance Observatory"),
+                visit("patrol_garden", "crystal_garden_lab", "Walk the Crystal Garden"),
+                talk_to("report_to_warden", "warden_gareth", "Report to Warden Gareth"),
+            ],
+            (FactionId::MagistersCouncil, 3),
+            1,
+        ),
+        tutoring,
+    ]
+}
+
+/// Personal quests opened by growing close to Elara, Dr. Felix and Echo
+pub fn create_bond_quests() -> Vec<QuestDefinition> {
+    use crate::systems::bonds::quest_flag;
+
+    let visit = |id: &str, location_id: &str, description: &str| (
+        id.to_string(), description.to_string(), ObjectiveType::VisitLocation { location_id: location_id.to_string() },
+    );
+    let talk_to = |id: &str, npc_id: &str, description: &str| (
+        id.to_string(), description.to_string(), ObjectiveType::TalkToNPC { npc_id: npc_id.to_string(), topic: None },
+    );
+
+    [
+        (
+            "tutorial_assistant",
+            create_repeatable_quest(
+                "the_lesson_unlearned",
+                "The Lesson Unlearned",
+                "Elara failed her first resonance exam three times, and the notebook she kept \
+                 back then holds a lesson she never learned how to teach. Her old exam papers \
+                 are still filed in the Archives.",
+                QuestCategory::Social,
+                vec![
+                    visit("find_exam_papers", "crystalline_archives", "Find Elara's old exam papers in the Archives"),
+                    talk_to("work_through_lesson", "tutorial_assistant", "Work through the lesson with Elara"),
+                ],
+                (FactionId::NeutralScholars, 3),
+                0,
+            ),
+        ),
+        (
+            "dr_felix",
+            create_repeatable_quest(
+                "lost_field_notes",
+                "Lost Field Notes",
+                "Dr. Felix lost a season of field notes years ago, the best work he ever did, \
+                 somewhere in the Archives' stacks. He stopped looking. You haven't started.",
+                QuestCategory::Research,
+                vec![
+                    visit("search_stacks", "crystalline_archives", "Search the Archives' stacks for Felix's field notes"),
+                    talk_to("return_notes", "dr_felix", "Return the field notes to Felix"),
+                ],
+                (FactionId::NeutralScholars, 3),
+                0,
+            ),
+        ),
+        (
+            "echo_voidwalker",
+            create_repeatable_quest(
+                "a_name_struck_out",
+                "A Name Struck Out",
+                "Echo had another name once, before the Council struck it from the Observatory's \
+                 registers. Someone may have kept a copy.",
+                QuestCategory::Social,
+                vec![
+                    visit("search_registers", "resonance_observatory", "Search the Observatory's registers for Echo's old name"),
+                    talk_to("tell_echo", "echo_voidwalker", "Tell Echo what you found"),
+                ],
+                (FactionId::UndergroundNetwork, 3),
+                0,
+            ),
+        ),
+    ]
+    .into_iter()
+    .map(|(npc_id, mut quest)| {
+        quest.requirements.world_flags = vec![quest_flag(npc_id)];
+        quest.rewards.experience = 60;
+        quest.repeat = None;
+        quest
+    })
+    .collect()
+}
+
+/// A repeatable quest with one faction paying standing for the work
+fn create_repeatable_quest(
+    id: &str,
+    title: &str,
+    description: &str,
+    category: QuestCategory,
+    objectives: Vec<(String, String, ObjectiveType)>,
+    standing: (FactionId, i32),
+    cooldown_days: i32,
+) -> QuestDefinition {
+    let mut faction_changes = HashMap::new();
+    faction_changes.insert(standing.0, standing.1);
+
+    let mut involved_npcs = Vec::new();
+    let mut locations = Vec::new();
+    for (_, _, objective_type) in &objectives {
+        match objective_type {
+            ObjectiveType::TalkToNPC { npc_id, .. } => involved_npcs.push(npc_id.clone()),
+            ObjectiveType::VisitLocation { location_id } => locations.push(location_id.clone()),
+            _ => {}
+        }
+    }
+
+    QuestDefinition {
+        id: id.to_string(),
+        title: title.to_string(),
+        description: description.to_string(),
+        category,
+        difficulty: QuestDifficulty::Beginner,
+
+        requirements: QuestRequirements {
+            theory_requirements: vec![],
+            faction_requirements: vec![],
+            faction_restrictions: vec![],
+            prerequisite_quests: vec![],
+            attribute_requirements: AttributeRequirements {
+                min_mental_acuity: None,
+                min_resonance_sensitivity: None,
+                min_total_playtime: None,
+            },
+            capability_requirements: vec![],
+            location_requirements: vec![],
+            world_flags: vec![],
+        },
+
+        objectives: objectives.into_iter().map(|(id, description, objective_type)| QuestObjective {
+            id,
+            description,
+            objective_type,
+            optional: false,
+            visible: true,
+            completion_reward: ObjectiveReward {
+                experience: 5,
+                theory_insights: HashMap::new(),
+                faction_changes: HashMap::new(),
+                items: vec![],
+            },
+        }).collect(),
+
+        rewards: QuestRewards {
+            experience: 25,
+            attribute_bonuses: AttributeBonuses {
+                mental_acuity: None,
+                resonance_sensitivity: None,
+            },
+            theory_bonuses: HashMap::new(),
+            faction_changes,
+            items: vec![],
+            new_capabilities: vec![],
+            unlocked_quests: vec![],
+        },
+
+        faction_effects: HashMap::new(),
+        educational_focus: EducationalObjectives {
+            primary_concepts: vec![],
+            secondary_concepts: vec![],
+            applications: vec![],
+            problem_solving_methods: vec![],
+            assessment_criteria: vec![],
+        },
+
+        branching_paths: HashMap::new(),
+        choices: vec![],
+        involved_npcs,
+        locations,
+        estimated_duration: 30,
+        deadline: None,
+        failure_triggers: vec![],
+        fallout: None,
+        cleans_up: None,
+        repeat: Some(QuestRepeat { cooldown_days }),
+        sets_flags: vec![],
+        changes_locations: vec![],
+    }
+}
+
 /// Quest 1: "Understanding Resonance" - Tutorial Level
 /// Teaches fundamental harmonic principles through safe experimentation
 fn create_resonance_foundation_quest() -> QuestDefinition {
@@ -66,6 +384,7 @@ fn create_resonance_foundation_quest() -> QuestDefinition {
             },
             capability_requirements: vec![],
             location_requirements: vec!["practice_hall".to_string()],
+            world_flags: vec![],
         },
 
         objectives: vec![
@@ -189,6 +508,13 @@ fn create_resonance_foundation_quest() -> QuestDefinition {
         involved_npcs: vec!["tutorial_assistant".to_string()],
         locations: vec!["practice_hall".to_string(), "tutorial_chamber".to_string()],
         estimated_duration: 45,
+        deadline: None,
+        failure_triggers: vec![],
+        fallout: None,
+        cleans_up: None,
+        repeat: None,
+        sets_flags: vec![],
+        changes_locations: vec![],
     }
 }
 
@@ -360,6 +686,7 @@ fn create_crystal_analysis_quest() -> QuestDefinition {
         },
         capability_requirements: vec![],
         location_requirements: vec![],
+        world_flags: vec![],
     };
 
     let mut academic_faction_effects = HashMap::new();
@@ -436,6 +763,7 @@ fn create_crystal_analysis_quest() -> QuestDefinition {
         },
         capability_requirements: vec![],
         location_requirements: vec![],
+        world_flags: vec![],
     };
 
     let mut commercial_faction_effects = HashMap::new();
@@ -528,6 +856,7 @@ fn create_crystal_analysis_quest() -> QuestDefinition {
             },
             capability_requirements: vec!["basic_frequency_matching".to_string()],
             location_requirements: vec![],
+            world_flags: vec![],
         },
 
         objectives: vec![
@@ -630,6 +959,13 @@ fn create_crystal_analysis_quest() -> QuestDefinition {
         involved_npcs: vec!["dr_felix".to_string(), "technician_marcus".to_string()],
         locations: vec!["crystal_garden_lab".to_string(), "resonance_observatory".to_string()],
         estimated_duration: 90,
+        deadline: None,
+        failure_triggers: vec![],
+        fallout: None,
+        cleans_up: None,
+        repeat: None,
+        sets_flags: vec![],
+        changes_locations: vec![],
     }
 }
 
@@ -672,6 +1008,7 @@ fn create_diplomatic_balance_quest() -> QuestDefinition {
             },
             capability_requirements: vec!["crystal_quality_assessment".to_string()],
             location_requirements: vec![],
+            world_flags: vec![],
         },
 
         objectives: vec![
@@ -819,6 +1156,43 @@ fn create_diplomatic_balance_quest() -> QuestDefinition {
             "unstable_resonance_site".to_string()
         ],
         estimated_duration: 120,
+        // The negotiation won't wait forever, and needs a mediator both sides trust
+        deadline: Some(QuestDeadline {
+            minutes: 3 * 24 * 60,
+            warning_minutes: 12 * 60,
+        }),
+        failure_triggers: vec![
+            FailureTrigger::NpcLost { npc_id: "ambassador_cordelia".to_string() },
+            FailureTrigger::ReputationBelow { faction: FactionId::MagistersCouncil, reputation: -40 },
+            FailureTrigger::ReputationBelow { faction: FactionId::UndergroundNetwork, reputation: -40 },
+        ],
+        fallout: Some(QuestFallout {
+            aftermath: "The negotiation collapsed without a mediator, and the Council and the Underground \
+                        walked away angrier than they came.".to_string(),
+            effects: vec![
+                FalloutEffect::FactionRelations {
+                    factions: (FactionId::MagistersCouncil, FactionId::UndergroundNetwork),
+                    shift: -1,
+                },
+                FalloutEffect::Reputation { faction: FactionId::NeutralScholars, change: -5 },
+                FalloutEffect::NpcDeparts {
+                    npc_id: "ambassador_cordelia".to_string(),
+                    reason: "has left the city, recalled after the failed negotiation".to_string(),
+                },
+                FalloutEffect::SetFlag { flag: "talks_collapsed".to_string() },
+                FalloutEffect::ChangeLocation(LocationEdit::new("faction_diplomacy_hall", LocationChange::AddDetail {
+                    text: "A crack now runs through the central harmonizing crystal, where raised voices shook it out of true.".to_string(),
+                })),
+            ],
+        }),
+        cleans_up: None,
+        repeat: None,
+        sets_flags: vec!["accord_signed".to_string()],
+        changes_locations: vec![
+            LocationEdit::new("faction_diplomacy_hall", LocationChange::AddDetail {
+                text: "The new accord has been etched into a fresh crystal panel beside the ancient treaties.".to_string(),
+            }),
+        ],
     }
 }
 
@@ -863,6 +1237,7 @@ fn create_healing_research_quest() -> QuestDefinition {
             },
             capability_requirements: vec!["crystal_quality_assessment".to_string()],
             location_requirements: vec![],
+            world_flags: vec![],
         },
 
         objectives: vec![
@@ -1028,6 +1403,13 @@ fn create_healing_research_quest() -> QuestDefinition {
         involved_npcs: vec!["healer_seraphina".to_string(), "dr_felix".to_string()],
         locations: vec!["crystal_garden_lab".to_string()],
         estimated_duration: 150,
+        deadline: None,
+        failure_triggers: vec![],
+        fallout: None,
+        cleans_up: None,
+        repeat: None,
+        sets_flags: vec![],
+        changes_locations: vec![],
     }
 }
 
@@ -1075,6 +1457,7 @@ fn create_unstable_site_investigation_quest() -> QuestDefinition {
                 "bio_resonance_diagnosis".to_string(),
             ],
             location_requirements: vec![],
+            world_flags: vec![],
         },
 
         objectives: vec![
@@ -1296,6 +1679,18 @@ fn create_unstable_site_investigation_quest() -> QuestDefinition {
             "crystalline_archives".to_string(),
         ],
         estimated_duration: 240,
+        deadline: None,
+        failure_triggers: vec![],
+        fallout: None,
+        cleans_up: None,
+        repeat: None,
+        sets_flags: vec![],
+        changes_locations: vec![
+            LocationEdit::new("unstable_resonance_site", LocationChange::AddDetail {
+                text: "New containment barriers, raised on the strength of your findings, hold the worst of the flux back from the paths.".to_string(),
+            }),
+            LocationEdit::new("unstable_resonance_site", LocationChange::AdjustInterference { by: -0.15 }),
+        ],
     }
 }
 
@@ -1431,6 +1826,7 @@ fn create_tutorial_assistant() -> NPC {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         },
     });
 
@@ -1449,6 +1845,7 @@ fn create_tutorial_assistant() -> NPC {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         },
     });
 
@@ -1469,6 +1866,7 @@ fn create_tutorial_assistant() -> NPC {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         },
     });
 
@@ -1489,6 +1887,7 @@ fn create_tutorial_assistant() -> NPC {
             theory_requirements: vec![("harmonic_fundamentals".to_string(), 0.3)],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         },
     });
 
@@ -1502,6 +1901,9 @@ fn create_tutorial_assistant() -> NPC {
         faction_affiliation: Some(FactionId::MagistersCouncil),
         personality: Some(personality),
         quest_dialogue: quest_dialogue_map,
+        availability: NPCAvailability::default(),
+        flag_lines: Vec::new(),
+        topic_flags: HashMap::new(),
         dialogue_tree: DialogueTree {
             greeting: DialogueNode {
                 text_templates: vec![
@@ -1517,6 +1919,7 @@ fn create_tutorial_assistant() -> NPC {
                     theory_requirements: vec![],
                     min_theory_mastery: None,
                     required_capabilities: vec![],
+                    min_relationship: None,
                 },
             },
             time_based_greetings: {
@@ -1529,6 +1932,8 @@ fn create_tutorial_assistant() -> NPC {
                     "Good evening! Don't study too late - rest is as important as practice for true understanding.".to_string());
                 time_greetings
             },
+            relationship_greetings: HashMap::new(),
+            branches: HashMap::new(),
             topics,
             faction_specific: {
                 let mut faction_specific = HashMap::new();
@@ -1551,6 +1956,7 @@ fn create_tutorial_assistant() -> NPC {
                         theory_requirements: vec![],
                         min_theory_mastery: None,
                         required_capabilities: vec![],
+                        min_relationship: None,
                     },
                 });
 
@@ -1572,6 +1978,7 @@ fn create_tutorial_assistant() -> NPC {
                         theory_requirements: vec![],
                         min_theory_mastery: None,
                         required_capabilities: vec![],
+                        min_relationship: None,
                     },
                 });
 
@@ -1593,6 +2000,7 @@ fn create_tutorial_assistant() -> NPC {
                         theory_requirements: vec![],
                         min_theory_mastery: None,
                         required_capabilities: vec![],
+                        min_relationship: None,
                     },
                 });
 
@@ -1692,6 +2100,7 @@ fn create_dr_felix() -> NPC {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         },
     });
 
@@ -1710,6 +2119,7 @@ fn create_dr_felix() -> NPC {
             theory_requirements: vec![],
             min_theory_mastery: None,
             required_capabilities: vec![],
+            min_relationship: None,
         },
     });
 
@@ -1723,6 +2133,9 @@ fn create_dr_felix() -> NPC {
         faction_affiliation: Some(FactionId::NeutralScholars),
         personality: Some(personality),
         quest_dialogue: quest_dialogue_map,
+        availability: NPCAvailability::default(),
+        flag_lines: Vec::new(),
+        topic_flags: HashMap::new(),
         dialogue_tree: DialogueTree {
             greeting: DialogueNode {
                 text_templates: vec![
@@ -1738,9 +2151,12 @@ fn create_dr_felix() -> NPC {
                     theory_requirements: vec![],
                     min_theory_mastery: None,
                     required_capabilities: vec![],
+                    min_relationship: None,
                 },
             },
             time_based_greetings: HashMap::new(),
+            relationship_greetings: HashMap::new(),
+            branches: HashMap::new(),
             topics,
             faction_specific: {
                 let mut faction_specific = HashMap::new();
@@ -1759,6 +2175,7 @@ fn create_dr_felix() -> NPC {
                         theory_requirements: vec![],
                         min_theory_mastery: None,
                         required_capabilities: vec![],
+                        min_relationship: None,
                     },
                 });
 
@@ -1776,6 +2193,7 @@ fn create_dr_felix() -> NPC {
                         theory_requirements: vec![],
                         min_theory_mastery: None,
                         required_capabilities: vec![],
+                        min_relationship: None,
                     },
                 });
 
@@ -1800,6 +2218,9 @@ fn create_ambassador_cordelia() -> NPC {
         faction_affiliation: Some(FactionId::NeutralScholars),
         personality: Some(personality),
         quest_dialogue: HashMap::new(),
+        availability: NPCAvailability::default(),
+        flag_lines: Vec::new(),
+        topic_flags: HashMap::new(),
         dialogue_tree: DialogueTree {
             greeting: DialogueNode {
                 text_templates: vec!["Welcome. I hope we can find common ground.".to_string()],
@@ -1811,9 +2232,12 @@ fn create_ambassador_cordelia() -> NPC {
                     theory_requirements: vec![],
                     min_theory_mastery: None,
                     required_capabilities: vec![],
+                    min_relationship: None,
                 },
             },
             time_based_greetings: HashMap::new(),
+            relationship_greetings: HashMap::new(),
+            branches: HashMap::new(),
             topics: HashMap::new(),
             faction_specific: HashMap::new(),
         },
@@ -1836,6 +2260,9 @@ fn create_observer_lyra() -> NPC {
         faction_affiliation: Some(FactionId::MagistersCouncil),
         personality: Some(personality),
         quest_dialogue: HashMap::new(),
+        availability: NPCAvailability::default(),
+        flag_lines: Vec::new(),
+        topic_flags: HashMap::new(),
         dialogue_tree: DialogueTree {
             greeting: DialogueNode {
                 text_templates: vec!["The Council values order and proper procedure.".to_string()],
@@ -1847,9 +2274,12 @@ fn create_observer_lyra() -> NPC {
                     theory_requirements: vec![],
                     min_theory_mastery: None,
                     required_capabilities: vec![],
+                    min_relationship: None,
                 },
             },
             time_based_greetings: HashMap::new(),
+            relationship_greetings: HashMap::new(),
+            branches: HashMap::new(),
             topics: HashMap::new(),
             faction_specific: HashMap::new(),
         },
@@ -1872,6 +2302,9 @@ fn create_echo_voidwalker() -> NPC {
         faction_affiliation: Some(FactionId::UndergroundNetwork),
         personality: Some(personality),
         quest_dialogue: HashMap::new(),
+        availability: NPCAvailability::default(),
+        flag_lines: Vec::new(),
+        topic_flags: HashMap::new(),
         dialogue_tree: DialogueTree {
             greeting: DialogueNode {
                 text_templates: vec!["The shadows hold more truth than the Council's light.".to_string()],
@@ -1883,9 +2316,12 @@ fn create_echo_voidwalker() -> NPC {
                     theory_requirements: vec![],
                     min_theory_mastery: None,
                     required_capabilities: vec![],
+                    min_relationship: None,
                 },
             },
             time_based_greetings: HashMap::new(),
+            relationship_greetings: HashMap::new(),
+            branches: HashMap::new(),
             topics: HashMap::new(),
             faction_specific: HashMap::new(),
         },
@@ -0,0 +1,147 @@
+//! Quest failure and fallout
+//!
+//! An active quest fails when its clock runs out, when one of its failure
+//! triggers fires (an NPC it depends on leaves for good, or the player's
+//! standing with a faction crosses a threshold), or when a required
+//! objective can no longer be done. A failed quest's fallout then changes
+//! the world: reputations shift, faction relations sour, NPCs leave. Quests
+//! that clean up after the failure become available.
+//!
+//! Fallout can itself fail other quests, so failures are resolved until the
+//! world settles.
+
+use crate::core::WorldState;
+use crate::systems::dialogue::DialogueSystem;
+use crate::systems::factions::politics::{PoliticalEvent, RelationshipEffect};
+use crate::systems::factions::FactionSystem;
+use crate::systems::quests::{FalloutEffect, QuestId, QuestSystem};
+use std::collections::HashMap;
+
+/// Warn of nearing deadlines, then fail every quest that can no longer
+/// succeed and apply its fallout; returns the messages for the player
+pub fn resolve(
+    now: i32,
+    quest_system: &mut QuestSystem,
+    world: &mut WorldState,
+    dialogue_system: &mut DialogueSystem,
+    faction_system: &mut FactionSystem,
+) -> Vec<String> {
+    let mut messages = quest_system.check_deadlines(now);
+
+    loop {
+        let mut active: Vec<QuestId> = quest_system.get_active_quests().iter().map(|progress| progress.quest_id.clone()).collect();
+        active.sort();
+        let failure = active.into_iter().find_map(|quest_id| {
+            quest_system.failure_cause(
+                &quest_id,
+                now,
+                faction_system,
+                |npc_id| dialogue_system.departed_name(npc_id),
+                |location_id| world.locations.contains_key(location_id),
+            ).map(|cause| (quest_id, cause))
+        });
+        let Some((quest_id, cause)) = failure else { break };
+
+        if quest_system.fail_quest(&quest_id).is_err() {
+            break;
+        }
+        let quest = quest_system.quest_definitions[&quest_id].clone();
+        let mut message = format!("{} has failed: {}.", quest.title, cause);
+
+        if let Some(fallout) = &quest.fallout {
+            message.push_str(&format!(" {}", fallout.aftermath));
+            for effect in &fallout.effects {
+                match effect {
+                    FalloutEffect::SetFlag { flag } => {
+                        quest_system.global_state.flags.set(flag);
+                    }
+                    FalloutEffect::ChangeLocation(edit) => {
+                        let _ = world.change_location(&edit.location_id, edit.change.clone());
+                    }
+                    _ => {}
+                }
+                apply(effect, &quest.title, now, dialogue_system, faction_system);
+            }
+        }
+
+        let mut cleanups: Vec<_> = quest_system.quest_definitions.values()
+            .filter(|cleanup| cleanup.cleans_up.as_deref() == Some(quest_id.as_str()))
+            .map(|cleanup| (cleanup.id.clone(), cleanup.title.clone()))
+            .collect();
+        cleanups.sort();
+        for (id, title) in cleanups {
+            message.push_str(&format!("\nNew quest available: {} (quest info {})", title, id));
+        }
+        messages.push(message);
+    }
+
+    messages
+}
+
+/// Apply one fallout effect of a failed quest
+fn apply(effect: &FalloutEffect, title: &str, now: i32, dialogue_system: &mut DialogueSystem, faction_system: &mut FactionSystem) {
+    match effect {
+        FalloutEffect::Reputation { faction, change } => faction_system.modify_reputation(*faction, *change),
+        FalloutEffect::FactionRelations { factions, shift } => {
+            let mut relationship_effects = HashMap::new();
+            relationship_effects.insert(*factions, RelationshipEffect { shift: *shift, temporary: false });
+            faction_system.politics.add_event(PoliticalEvent {
+                id: format!("quest_failed_{}", now),
+                description: format!("Fallout from the failure of {}", title),
+                participants: vec![factions.0, factions.1],
+                relationship_effects,
+                start_time: now,
+                duration: None,
+                active: true,
+            }, now);
+        }
+        FalloutEffect::NpcDeparts { npc_id, reason } => dialogue_system.depart(npc_id, reason),
+        // Flags and locations live elsewhere and are changed by the caller
+        FalloutEffect::SetFlag { .. } | FalloutEffect::ChangeLocation(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Player;
+    use crate::systems::factions::FactionId;
+    use crate::systems::quest_examples::{create_cleanup_quests, create_example_quests};
+
+    #[test]
+    fn test_failed_quest_applies_fallout_and_unlocks_cleanup() {
+        let mut quest_system = QuestSystem::new();
+        let cleanups = create_cleanup_quests();
+        for mut quest in create_example_quests().into_iter().chain(cleanups.clone()) {
+            if quest.id == "diplomatic_balance" {
+                quest.requirements = cleanups[0].requirements.clone();
+            }
+            quest_system.add_quest_definition(quest);
+        }
+        let player = Player::new("Ada".to_string());
+        let mut faction_system = FactionSystem::new();
+        let mut dialogue_system = DialogueSystem::new();
+        let mut world = WorldState::new();
+        world.add_location(crate::core::world_state::Location::new(
+            "faction_diplomacy_hall".to_string(), "Faction Diplomacy Hall".to_string(), "A neutral chamber.".to_string(),
+        ));
+
+        assert!(!quest_system.is_quest_available(&cleanups[0], &player, &faction_system));
+        quest_system.start_quest("diplomatic_balance", &player, &faction_system).unwrap();
+        assert!(resolve(0, &mut quest_system, &mut world, &mut dialogue_system, &mut faction_system).is_empty());
+
+        // Losing the Council's trust ends the negotiation
+        faction_system.modify_reputation(FactionId::MagistersCouncil, -60);
+        let messages = resolve(10, &mut quest_system, &mut world, &mut dialogue_system, &mut faction_system);
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].starts_with("The Diplomatic Balance has failed: the Magisters' Council no longer trust you."));
+        assert!(messages[0].contains("New quest available: Mending the Rift (quest info mending_the_rift)"));
+        assert_eq!(quest_system.player_progress["diplomatic_balance"].status, crate::systems::quests::QuestStatus::Failed);
+        assert!(faction_system.get_reputation(FactionId::NeutralScholars) < 0);
+        assert!(quest_system.global_state.flags.is_set("talks_collapsed"));
+        // The hall bears the marks of it for good
+        assert!(world.locations["faction_diplomacy_hall"].description.contains("crack now runs"));
+        assert_eq!(world.location_changes.changes("faction_diplomacy_hall").len(), 1);
+        assert!(quest_system.is_quest_available(&cleanups[0], &player, &faction_system));
+    }
+}
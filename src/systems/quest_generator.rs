@@ -0,0 +1,422 @@
+//! Procedural jobs composed from the state of the world
+//!
+//! When the player runs out of authored quests, the people around them
+//! still have work. A job is built from what the world looks like now:
+//! - Fetch: bring back something lying at another location
+//! - Investigate signature: look into the strongest magical signature
+//!   elsewhere, such as a surge of ambient energy or a loose phenomenon
+//! - Mediate dispute: carry words between two factions at odds
+//! - Deliver: take a message to someone at another location
+//!
+//! Every job also nudges the player toward the theory they understand least,
+//! as an optional objective with a small insight reward. Jobs are ordinary
+//! quest definitions registered with the quest system, so they are started,
+//! tracked and turned in like any other quest.
+
+use crate::core::{Player, WorldState};
+use crate::systems::dialogue::DialogueSystem;
+use crate::systems::factions::{FactionId, FactionSystem};
+use crate::systems::quests::*;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Prefix of generated quest IDs
+pub const JOB_PREFIX: &str = "job_";
+/// Most jobs the player can have open, offered or underway, at once
+pub const MAX_OPEN_JOBS: usize = 2;
+/// Relationship strength below which two factions are in dispute
+const DISPUTE_THRESHOLD: f32 = -0.1;
+/// Understanding a job's study objective asks the player to gain
+const STUDY_STEP: f32 = 0.1;
+/// Insight awarded for the study objective
+const STUDY_INSIGHT: f32 = 0.05;
+
+/// Kinds of job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobKind {
+    Fetch,
+    InvestigateSignature,
+    MediateDispute,
+    Deliver,
+}
+
+/// Whether a quest was generated
+pub fn is_job(quest_id: &str) -> bool {
+    quest_id.starts_with(JOB_PREFIX)
+}
+
+/// Jobs offered or underway and not yet completed
+pub fn open_jobs(quest_system: &QuestSystem) -> usize {
+    quest_system.quest_definitions.keys()
+        .filter(|id| is_job(id))
+        .filter(|id| quest_system.player_progress.get(*id).is_none_or(|progress| {
+            matches!(progress.status, QuestStatus::Available | QuestStatus::InProgress)
+        }))
+        .count()
+}
+
+/// The theory the player has started but understands least, with the level a job should ask for
+fn theory_gap(player: &Player) -> Option<(String, f32)> {
+    player.knowledge.theories.iter()
+        .filter(|(_, understanding)| **understanding < 1.0)
+        .min_by(|a, b| a.1.total_cmp(b.1).then_with(|| a.0.cmp(b.0)))
+        .map(|(theory, understanding)| (theory.clone(), ((understanding + STUDY_STEP) * 100.0).round() / 100.0))
+        .map(|(theory, target)| (theory, target.min(1.0)))
+}
+
+fn objective(id: &str, description: String, objective_type: ObjectiveType, optional: bool) -> QuestObjective {
+    QuestObjective {
+        id: id.to_string(),
+        description,
+        objective_type,
+        optional,
+        visible: true,
+        completion_reward: ObjectiveReward {
+            experience: 10,
+            theory_insights: HashMap::new(),
+            faction_changes: HashMap::new(),
+            items: vec![],
+        },
+    }
+}
+
+/// The parts of a job that differ by kind
+struct Draft {
+    title: String,
+    description: String,
+    category: QuestCategory,
+    objectives: Vec<QuestObjective>,
+    involved_npcs: Vec<String>,
+    locations: Vec<String>,
+    faction_changes: HashMap<FactionId, i32>,
+}
+
+/// NPCs at a location, in a stable order
+fn npcs_at<'a>(world: &WorldState, dialogue_system: &'a DialogueSystem, location_id: &str) -> Vec<&'a crate::systems::dialogue::NPC> {
+    let mut npcs: Vec<_> = world.locations.get(location_id)
+        .map(|location| location.npcs.iter().filter_map(|id| dialogue_system.get_npc(id)).collect())
+        .unwrap_or_default();
+    npcs.sort_by(|a, b| a.id.cmp(&b.id));
+    npcs
+}
+
+/// Other locations, in a stable order
+fn elsewhere(world: &WorldState) -> Vec<&crate::core::world_state::Location> {
+    let mut locations: Vec<_> = world.locations.values().filter(|location| location.id != world.current_location).collect();
+    locations.sort_by(|a, b| a.id.cmp(&b.id));
+    locations
+}
+
+fn fetch(world: &WorldState, giver: &crate::systems::dialogue::NPC) -> Option<Draft> {
+    let (location, item_id) = elsewhere(world).into_iter()
+        .find_map(|location| location.items.first().map(|item_id| (location, item_id.clone())))?;
+    let item = crate::systems::placed_items::display_name(&item_id);
+    Some(Draft {
+        title: format!("A {} for {}", item, giver.short_name()),
+        description: format!("{} needs a {} and hasn't the time to fetch it. One was last seen at {}.", giver.name, item, location.name),
+        category: QuestCategory::Practical,
+        objectives: vec![objective(
+            "fetch_item",
+            format!("Bring back a {} from {}.", item, location.name),
+            ObjectiveType::CollectItems { item_ids: vec![item_id], quantities: vec![1] },
+            false,
+        )],
+        involved_npcs: vec![giver.id.clone()],
+        locations: vec![location.id.clone()],
+        faction_changes: giver.faction_affiliation.map(|faction| HashMap::from([(faction, 3)])).unwrap_or_default(),
+    })
+}
+
+fn investigate(world: &WorldState, giver: &crate::systems::dialogue::NPC) -> Option<Draft> {
+    let strength = |location: &crate::core::world_state::Location| {
+        let properties = &location.magical_properties;
+        properties.ambient_energy + properties.interference + properties.phenomena.len() as f32
+    };
+    let location = elsewhere(world).into_iter()
+        .filter(|location| strength(location) > 0.0)
+        .max_by(|a, b| strength(a).total_cmp(&strength(b)))?;
+    let sign = location.magical_properties.phenomena.first()
+        .map_or("an unusual resonance signature".to_string(), |phenomenon| format!("a {}", phenomenon));
+    Some(Draft {
+        title: format!("Strange Readings at {}", location.name),
+        description: format!(
+            "{} has picked up {} at {}. Go and take a reading, then report back.", giver.name, sign, location.name
+        ),
+        category: QuestCategory::Research,
+        objectives: vec![objective(
+            "investigate_signature",
+            format!("Investigate the signature at {}.", location.name),
+            ObjectiveType::VisitLocation { location_id: location.id.clone() },
+            false,
+        )],
+        involved_npcs: vec![giver.id.clone()],
+        locations: vec![location.id.clone()],
+        faction_changes: HashMap::from([(FactionId::NeutralScholars, 3)]),
+    })
+}
+
+fn mediate(world: &WorldState, dialogue_system: &DialogueSystem, faction_system: &FactionSystem, giver: &crate::systems::dialogue::NPC) -> Option<Draft> {
+    let mut representatives: Vec<(FactionId, &crate::systems::dialogue::NPC)> = Vec::new();
+    let mut location_ids: Vec<&String> = world.locations.keys().collect();
+    location_ids.sort();
+    for location_id in location_ids {
+        for npc in npcs_at(world, dialogue_system, location_id) {
+            if let Some(faction) = npc.faction_affiliation.filter(|faction| representatives.iter().all(|(known, _)| known != faction)) {
+                representatives.push((faction, npc));
+            }
+        }
+    }
+
+    let mut disputes: Vec<(f32, usize, usize)> = Vec::new();
+    for (i, (first, _)) in representatives.iter().enumerate() {
+        for (j, (second, _)) in representatives.iter().enumerate().skip(i + 1) {
+            let strength = faction_system.get_relationship_strength(*first, *second);
+            if strength < DISPUTE_THRESHOLD {
+                disputes.push((strength, i, j));
+            }
+        }
+    }
+    let (_, i, j) = disputes.into_iter().min_by(|a, b| a.0.total_cmp(&b.0))?;
+    let ((first, a), (second, b)) = (representatives[i], representatives[j]);
+
+    Some(Draft {
+        title: format!("Bridging the {} and the {}", first.short_name(), second.short_name()),
+        description: format!(
+            "Tempers are running high between the {} and the {}. {} asks you to hear out {} and {} and help them find common ground.",
+            first.display_name(), second.display_name(), giver.name, a.name, b.name
+        ),
+        category: QuestCategory::Political,
+        objectives: vec![
+            objective("hear_first_side", format!("Hear out {}.", a.name), ObjectiveType::TalkToNPC { npc_id: a.id.clone(), topic: None }, false),
+            objective("hear_second_side", format!("Hear out {}.", b.name), ObjectiveType::TalkToNPC { npc_id: b.id.clone(), topic: None }, false),
+        ],
+        involved_npcs: vec![giver.id.clone(), a.id.clone(), b.id.clone()],
+        locations: Vec::new(),
+        faction_changes: HashMap::from([(first, 4), (second, 4)]),
+    })
+}
+
+fn deliver(world: &WorldState, dialogue_system: &DialogueSystem, giver: &crate::systems::dialogue::NPC) -> Option<Draft> {
+    let (location, recipient) = elsewhere(world).into_iter()
+        .find_map(|location| npcs_at(world, dialogue_system, &location.id).into_iter()
+            .find(|npc| npc.id != giver.id)
+            .map(|npc| (location, npc)))?;
+    Some(Draft {
+        title: format!("A Message for {}", recipient.short_name()),
+        description: format!(
+            "{} has a sealed note for {} at {}. Deliver it and see it answered.", giver.name, recipient.name, location.name
+        ),
+        category: QuestCategory::Social,
+        objectives: vec![objective(
+            "deliver_message",
+            format!("Deliver {}'s message to {}.", giver.short_name(), recipient.name),
+            ObjectiveType::TalkToNPC { npc_id: recipient.id.clone(), topic: None },
+            false,
+        )],
+        involved_npcs: vec![recipient.id.clone(), giver.id.clone()],
+        locations: vec![location.id.clone()],
+        faction_changes: recipient.faction_affiliation.map(|faction| HashMap::from([(faction, 3)])).unwrap_or_default(),
+    })
+}
+
+/// Compose a job of a given kind from the world as it stands, offered by an NPC at the player's location
+pub fn compose(
+    kind: JobKind,
+    number: usize,
+    player: &Player,
+    world: &WorldState,
+    dialogue_system: &DialogueSystem,
+    faction_system: &FactionSystem,
+) -> Option<QuestDefinition> {
+    let giver = *npcs_at(world, dialogue_system, &world.current_location).first()?;
+    let mut draft = match kind {
+        JobKind::Fetch => fetch(world, giver),
+        JobKind::InvestigateSignature => investigate(world, giver),
+        JobKind::MediateDispute => mediate(world, dialogue_system, faction_system, giver),
+        JobKind::Deliver => deliver(world, dialogue_system, giver),
+    }?;
+
+    let mut theory_bonuses = HashMap::new();
+    let mut primary_concepts = Vec::new();
+    if let Some((theory, target)) = theory_gap(player) {
+        draft.objectives.push(objective(
+            "close_the_gap",
+            format!("Optional: while you're at it, study {} to {:.0}%.", theory.replace('_', " "), target * 100.0),
+            ObjectiveType::LearnTheory { theory_id: theory.clone(), min_level: target },
+            true,
+        ));
+        theory_bonuses.insert(theory.clone(), STUDY_INSIGHT);
+        primary_concepts.push(theory.replace('_', " "));
+    }
+
+    Some(QuestDefinition {
+        id: format!("{}{}", JOB_PREFIX, number),
+        title: draft.title,
+        description: draft.description,
+        category: draft.category,
+        difficulty: QuestDifficulty::Beginner,
+        requirements: QuestRequirements {
+            theory_requirements: vec![],
+            faction_requirements: vec![],
+            faction_restrictions: vec![],
+            prerequisite_quests: vec![],
+            attribute_requirements: AttributeRequirements {
+                min_mental_acuity: None,
+                min_resonance_sensitivity: None,
+                min_total_playtime: None,
+            },
+            capability_requirements: vec![],
+            location_requirements: vec![],
+            world_flags: vec![],
+        },
+        objectives: draft.objectives,
+        rewards: QuestRewards {
+            experience: 40,
+            attribute_bonuses: AttributeBonuses {
+                mental_acuity: None,
+                resonance_sensitivity: None,
+            },
+            theory_bonuses,
+            faction_changes: draft.faction_changes,
+            items: vec![],
+            new_capabilities: vec![],
+            unlocked_quests: vec![],
+        },
+        faction_effects: HashMap::new(),
+        educational_focus: EducationalObjectives {
+            primary_concepts,
+            secondary_concepts: vec![],
+            applications: vec![],
+            problem_solving_methods: vec![],
+            assessment_criteria: vec![],
+        },
+        branching_paths: HashMap::new(),
+        choices: vec![],
+        involved_npcs: draft.involved_npcs,
+        locations: draft.locations,
+        estimated_duration: 30,
+        deadline: None,
+        failure_triggers: vec![],
+        fallout: None,
+        cleans_up: None,
+        repeat: None,
+        sets_flags: vec![],
+        changes_locations: vec![],
+    })
+}
+
+/// Offer a new job if the player has room for one, returning its title
+///
+/// Kinds are tried in a random order, so a job the world can't support
+/// gives way to one it can.
+pub fn offer_job(
+    quest_system: &mut QuestSystem,
+    player: &Player,
+    world: &WorldState,
+    dialogue_system: &DialogueSystem,
+    faction_system: &FactionSystem,
+    rng: &mut impl Rng,
+) -> Option<String> {
+    if open_jobs(quest_system) >= MAX_OPEN_JOBS {
+        return None;
+    }
+    let number = quest_system.quest_definitions.keys().filter(|id| is_job(id)).count() + 1;
+    let kinds = [JobKind::Fetch, JobKind::InvestigateSignature, JobKind::MediateDispute, JobKind::Deliver];
+    let start = rng.gen_range(0..kinds.len());
+    let quest = (0..kinds.len())
+        .find_map(|offset| compose(kinds[(start + offset) % kinds.len()], number, player, world, dialogue_system, faction_system))?;
+    let title = quest.title.clone();
+    quest_system.add_quest_definition(quest);
+    Some(title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::Location;
+    use crate::systems::dialogue::{DialogueNode, DialogueRequirements, DialogueTree, NPC};
+
+    fn npc(id: &str, name: &str, faction: Option<FactionId>) -> NPC {
+        NPC {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            faction_affiliation: faction,
+            dialogue_tree: DialogueTree {
+                greeting: DialogueNode {
+                    text_templates: vec!["Hello.".to_string()],
+                    responses: vec![],
+                    requirements: DialogueRequirements {
+                        min_faction_standing: None,
+                        max_faction_standing: None,
+                        knowledge_requirements: vec![],
+                        theory_requirements: vec![],
+                        min_theory_mastery: None,
+                        required_capabilities: vec![],
+                        min_relationship: None,
+                    },
+                },
+                topics: HashMap::new(),
+                faction_specific: HashMap::new(),
+                time_based_greetings: HashMap::new(),
+                relationship_greetings: HashMap::new(),
+                branches: HashMap::new(),
+            },
+            current_disposition: 0,
+            personality: None,
+            quest_dialogue: HashMap::new(),
+            availability: Default::default(),
+            flag_lines: Vec::new(),
+            topic_flags: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_jobs_are_composed_from_the_world_and_theory_gaps() {
+        let mut world = WorldState::new();
+        let mut hall = Location::new("hall".to_string(), "Hall".to_string(), "Busy.".to_string());
+        hall.npcs.push("clerk".to_string());
+        let mut garden = Location::new("garden".to_string(), "Garden".to_string(), "Green.".to_string());
+        garden.items.push("silverleaf".to_string());
+        garden.npcs.push("gardener".to_string());
+        garden.magical_properties.ambient_energy = 0.8;
+        world.add_location(hall);
+        world.add_location(garden);
+        world.current_location = "hall".to_string();
+
+        let mut dialogue = DialogueSystem::new();
+        dialogue.add_npc(npc("clerk", "Clerk Ada Venn", Some(FactionId::NeutralScholars)));
+        dialogue.add_npc(npc("gardener", "Gardener Tom Reed", Some(FactionId::OrderOfHarmony)));
+        let factions = FactionSystem::new();
+        let mut player = Player::new("Bram".to_string());
+        player.knowledge.theories.insert("light_manipulation".to_string(), 0.2);
+        player.knowledge.theories.insert("harmonic_fundamentals".to_string(), 0.6);
+
+        let fetch = compose(JobKind::Fetch, 1, &player, &world, &dialogue, &factions).unwrap();
+        assert_eq!(fetch.id, "job_1");
+        assert!(matches!(&fetch.objectives[0].objective_type, ObjectiveType::CollectItems { item_ids, .. } if item_ids[0] == "silverleaf"));
+        assert_eq!(fetch.involved_npcs, vec!["clerk"]);
+        assert!(matches!(
+            &fetch.objectives[1].objective_type,
+            ObjectiveType::LearnTheory { theory_id, min_level } if theory_id == "light_manipulation" && (*min_level - 0.3).abs() < 1e-6
+        ));
+
+        let investigate = compose(JobKind::InvestigateSignature, 2, &player, &world, &dialogue, &factions).unwrap();
+        assert!(matches!(&investigate.objectives[0].objective_type, ObjectiveType::VisitLocation { location_id } if location_id == "garden"));
+        let deliver = compose(JobKind::Deliver, 3, &player, &world, &dialogue, &factions).unwrap();
+        assert_eq!(deliver.involved_npcs[0], "gardener");
+
+        // Nobody here to offer work
+        world.current_location = "garden".to_string();
+        world.locations.get_mut("garden").unwrap().npcs.clear();
+        assert!(compose(JobKind::Fetch, 4, &player, &world, &dialogue, &factions).is_none());
+
+        world.current_location = "hall".to_string();
+        let mut quests = QuestSystem::new();
+        let mut rng = rand::rngs::mock::StepRng::new(0, 0);
+        assert!(offer_job(&mut quests, &player, &world, &dialogue, &factions, &mut rng).is_some());
+        assert!(offer_job(&mut quests, &player, &world, &dialogue, &factions, &mut rng).is_some());
+        assert_eq!(open_jobs(&quests), MAX_OPEN_JOBS);
+        assert!(offer_job(&mut quests, &player, &world, &dialogue, &factions, &mut rng).is_none());
+    }
+}
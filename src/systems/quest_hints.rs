@@ -0,0 +1,243 @@
+//! Graduated quest hints
+//!
+//! The `hint` command looks at the next unfinished objective of the tracked
+//! quest, or of one the player names, and at what stands in the way: a
+//! theory not yet understood well enough, being in the wrong place, someone
+//! who can't be found. Each time the player asks about the same objective
+//! the hint gets more specific, up to a last nudge that still stops short of
+//! spelling out the steps. Hints are only given when asked for.
+
+use crate::core::{Player, WorldState};
+use crate::systems::dialogue::DialogueSystem;
+use crate::systems::quest_journal;
+use crate::systems::quests::{ObjectiveType, QuestObjective, QuestSystem};
+
+/// Most specific hint level
+pub const MAX_HINT_LEVEL: u32 = 3;
+/// Prefix of the quest variable holding the hint level reached for an objective
+const HINT_LEVEL_PREFIX: &str = "hint_level:";
+
+/// A hint for the next step of a quest, one level more specific than the
+/// last hint given for it
+pub fn hint(
+    quest_system: &mut QuestSystem,
+    player: &Player,
+    world: &WorldState,
+    dialogue_system: &DialogueSystem,
+    query: Option<&str>,
+) -> crate::GameResult<String> {
+    let quest_id = match query {
+        Some(query) => quest_journal::find_active(quest_system, query)?.id.clone(),
+        None => quest_journal::tracked(player, quest_system)
+            .map(|progress| progress.quest_id.clone())
+            .ok_or_else(|| crate::GameError::InvalidCommand("You have no active quest to get a hint for.".to_string()))?,
+    };
+    let quest = quest_system.quest_definitions.get(&quest_id)
+        .ok_or_else(|| crate::GameError::ContentNotFound(format!("Quest '{}' not found.", quest_id)))?;
+    let progress = quest_system.player_progress.get(&quest_id);
+
+    let next = quest.objectives.iter().enumerate()
+        .filter(|(index, objective)| !objective.optional && quest_journal::revealed(quest, *index, progress))
+        .map(|(_, objective)| objective)
+        .find(|objective| !progress.and_then(|progress| progress.objective_progress.get(&objective.id)).is_some_and(|state| state.completed));
+    let Some(objective) = next else {
+        return Ok(format!("Everything on {} is done. All that's left is to turn it in.", quest.title));
+    };
+
+    let key = format!("{}{}", HINT_LEVEL_PREFIX, objective.id);
+    let level = progress.and_then(|progress| progress.quest_variables.get(&key))
+        .and_then(|level| level.parse::<u32>().ok())
+        .map_or(1, |level| (level + 1).min(MAX_HINT_LEVEL));
+    let text = objective_hint(objective, level, player, world, dialogue_system, quest_system);
+    let title = quest.title.clone();
+
+    if let Some(progress) = quest_system.player_progress.get_mut(&quest_id) {
+        progress.quest_variables.insert(key, level.to_string());
+    }
+
+    Ok(format!(
+        "Hint {}/{} for {}: {}\n{}",
+        level, MAX_HINT_LEVEL, title, text,
+        if level < MAX_HINT_LEVEL { "Ask again for a clearer hint." } else { "That's as plain as the hint gets." }
+    ))
+}
+
+/// What stands between the player and an objective, told at a level of detail
+fn objective_hint(
+    objective: &QuestObjective,
+    level: u32,
+    player: &Player,
+    world: &WorldState,
+    dialogue_system: &DialogueSystem,
+    quest_system: &QuestSystem,
+) -> String {
+    let theory_name = |theory_id: &str| theory_id.replace('_', " ");
+    let percent = |understanding: f32| (understanding * 100.0).round() as i32;
+    let location_name = |location_id: &str| world.locations.get(location_id)
+        .map_or_else(|| location_id.replace('_', " "), |location| location.name.clone());
+    let pick = |hints: [String; 3]| hints[(level.clamp(1, MAX_HINT_LEVEL) - 1) as usize].clone();
+
+    match &objective.objective_type {
+        ObjectiveType::TalkToNPC { npc_id, topic } => {
+            let Some(npc) = dialogue_system.get_npc(npc_id) else {
+                return "Someone you need to speak with can't be found.".to_string();
+            };
+            let name = npc.short_name();
+            if let Some(reason) = &npc.availability.departed {
+                return format!("{} {}, so this can't be done any more.", npc.name, reason);
+            }
+            match npc.whereabouts(world.game_time_minutes, quest_system) {
+                Some(location) if location != player.current_location => pick([
+                    "Someone you need to speak with isn't here.".to_string(),
+                    format!("It's {} you need, and they aren't here.", name),
+                    format!("{} can be found at the {} at this hour.", name, location_name(location)),
+                ]),
+                _ if dialogue_system.unavailability_message(npc_id, world, quest_system).is_some() => {
+                    format!("{} is nearby but not free to talk. Give it some time.", name)
+                }
+                _ => pick([
+                    "The person you need is close by.".to_string(),
+                    format!("Have a word with {}.", name),
+                    match topic {
+                        Some(topic) => format!("{} has something to tell you about {}.", name, topic.replace('_', " ")),
+                        None => format!("{} is waiting to hear from you.", name),
+                    },
+                ]),
+            }
+        }
+        ObjectiveType::LearnTheory { theory_id, min_level } => pick([
+            "Your understanding isn't deep enough yet.".to_string(),
+            format!("You need a firmer grasp of {}.", theory_name(theory_id)),
+            format!(
+                "Your understanding of {} stands at {}% and needs to reach {}%. Study and experiment will get you there.",
+                theory_name(theory_id), percent(player.theory_understanding(theory_id)), percent(*min_level)
+            ),
+        ]),
+        ObjectiveType::VisitLocation { location_id } => {
+            let mut neighbours: Vec<&str> = world.locations.values()
+                .filter(|location| location.exits.values().any(|destination| destination == location_id))
+                .map(|location| location.name.as_str())
+                .collect();
+            neighbours.sort();
+            pick([
+                "There's somewhere you still need to go.".to_string(),
+                format!("Your path should lead to the {}.", location_name(location_id)),
+                match neighbours.first() {
+                    Some(neighbour) => format!("The {} can be reached from the {}.", location_name(location_id), neighbour),
+                    None => format!("Your path should lead to the {}.", location_name(location_id)),
+                },
+            ])
+        }
+        ObjectiveType::MagicalDemonstration { theory_id, success_threshold } => pick([
+            "This calls for showing what you can do.".to_string(),
+            format!("A demonstration of {} is expected.", theory_name(theory_id)),
+            format!(
+                "Cast something that draws on {}. A convincing demonstration takes about {}% understanding; you have {}%.",
+                theory_name(theory_id), percent(*success_threshold), percent(player.theory_understanding(theory_id))
+            ),
+        ]),
+        ObjectiveType::Research { theory_id, .. } => pick([
+            "There's research left to do.".to_string(),
+            format!("Your research into {} isn't finished.", theory_name(theory_id)),
+            format!("Keep working on {}; study and experiments both count toward it.", theory_name(theory_id)),
+        ]),
+        ObjectiveType::LearningActivity { theory_id, method, duration } => pick([
+            "There's more learning to be done.".to_string(),
+            format!("Spend some time on {}.", theory_name(theory_id)),
+            format!("Spend about {} minutes on {} through {}.", duration, theory_name(theory_id), method.replace('_', " ")),
+        ]),
+        ObjectiveType::WitnessEvent { event_id } => {
+            let event = crate::core::events::definition(event_id);
+            let name = event.map_or_else(|| event_id.replace('_', " "), |event| event.name.to_string());
+            let place = event.and_then(|event| event.location).map_or_else(|| "the city".to_string(), |location| format!("the {}", location_name(location)));
+            pick([
+                "Something is going to happen that you should see.".to_string(),
+                format!("You need to be there for the {}.", name),
+                format!("Be in {} when the {} begins. The calendar shows when that is.", place, name),
+            ])
+        }
+        ObjectiveType::TeachTheory { npc_id, theory_id } => {
+            let name = dialogue_system.get_npc(npc_id).map_or_else(|| npc_id.replace('_', " "), |npc| npc.short_name().to_string());
+            pick([
+                "Someone could use what you know.".to_string(),
+                format!("{} wants to learn from you.", name),
+                format!("{} wants to understand {}.", name, theory_name(theory_id)),
+            ])
+        }
+        ObjectiveType::CollectItems { item_ids, quantities } => {
+            let carried = crate::systems::placed_items::carried_definitions(player);
+            let missing: Vec<String> = item_ids.iter().zip(quantities)
+                .filter_map(|(item_id, needed)| {
+                    let short = needed - carried.get(item_id).copied().unwrap_or(0);
+                    (short > 0).then(|| format!("{} {}", short, crate::systems::placed_items::display_name(item_id)))
+                })
+                .collect();
+            pick([
+                "You're missing something you need to carry.".to_string(),
+                "Some things still need gathering. Keep an eye out where you've been.".to_string(),
+                format!("You still need {}.", if missing.is_empty() { "to hold on to what you've gathered".to_string() } else { missing.join(" and ") }),
+            ])
+        }
+        ObjectiveType::FactionStanding { faction_id, target_standing } => pick([
+            "Someone needs to trust you more.".to_string(),
+            format!("Your standing with the {} must improve.", faction_id.display_name()),
+            format!(
+                "Your standing with the {} is {} and needs to reach {}. Work done on their behalf raises it.",
+                faction_id.display_name(), player.faction_standings.get(faction_id).copied().unwrap_or(0), target_standing
+            ),
+        ]),
+        ObjectiveType::MasterTheories { count, .. } => pick([
+            "Your studies need to go further.".to_string(),
+            "You need to master more theories.".to_string(),
+            format!("You have mastered {} of the {} theories needed.", player.get_mastered_theories().len(), count),
+        ]),
+        ObjectiveType::DiplomaticChoice { factions, .. } => pick([
+            "A decision is waiting on you.".to_string(),
+            format!(
+                "The {} are waiting for you to take a position.",
+                factions.iter().map(|faction| faction.display_name()).collect::<Vec<_>>().join(" and the ")
+            ),
+            "The quest's details list the positions you could take.".to_string(),
+        ]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::{Direction, Location};
+    use crate::systems::factions::FactionSystem;
+    use crate::systems::quest_examples::create_example_quests;
+
+    #[test]
+    fn test_hints_grow_more_specific_per_objective() {
+        let mut quest_system = QuestSystem::new();
+        for quest in create_example_quests() {
+            quest_system.add_quest_definition(quest);
+        }
+        let mut world = WorldState::new();
+        let mut courtyard = Location::new("courtyard".to_string(), "Courtyard".to_string(), "Open.".to_string());
+        courtyard.add_exit(Direction::North, "practice_hall".to_string());
+        world.add_location(courtyard);
+        world.add_location(Location::new("practice_hall".to_string(), "Practice Hall".to_string(), "Quiet.".to_string()));
+        let mut player = Player::new("Ada".to_string());
+        player.current_location = "practice_hall".to_string();
+        let dialogue_system = DialogueSystem::new();
+
+        assert!(hint(&mut quest_system, &player, &world, &dialogue_system, None).is_err());
+        quest_system.start_quest("resonance_foundation", &player, &FactionSystem::new()).unwrap();
+        player.current_location = "courtyard".to_string();
+
+        let first = hint(&mut quest_system, &player, &world, &dialogue_system, None).unwrap();
+        assert!(first.starts_with("Hint 1/3 for Understanding Resonance: There's somewhere you still need to go."));
+        assert!(hint(&mut quest_system, &player, &world, &dialogue_system, None).unwrap().contains("lead to the Practice Hall"));
+        let last = hint(&mut quest_system, &player, &world, &dialogue_system, Some("understanding")).unwrap();
+        assert!(last.contains("can be reached from the Courtyard"));
+        assert!(last.ends_with("That's as plain as the hint gets."));
+
+        // The next objective starts over with a vague hint
+        quest_system.update_objective_progress("resonance_foundation", "visit_practice_hall", 1.0, true).unwrap();
+        let theory = hint(&mut quest_system, &player, &world, &dialogue_system, None).unwrap();
+        assert!(theory.starts_with("Hint 1/3 for Understanding Resonance: Your understanding isn't deep enough yet."));
+    }
+}
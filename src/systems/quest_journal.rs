@@ -0,0 +1,212 @@
+//! Quest journal
+//!
+//! Renders the player's quests for reading rather than bookkeeping:
+//! - the journal, every active quest with its objectives and progress bars
+//! - a single quest's page
+//! - the tracker line pinned to the status display, naming the next step of
+//!   the tracked quest
+//!
+//! Some objectives start hidden so a quest can surprise the player. A hidden
+//! objective is revealed once every required objective before it is done,
+//! or as soon as it makes progress of its own; until then the journal shows
+//! only that something more remains.
+
+use crate::core::Player;
+use crate::systems::quests::{QuestDefinition, QuestProgress, QuestSystem};
+
+/// Width of an objective's progress bar in characters
+const BAR_WIDTH: usize = 10;
+
+/// A progress bar for a fraction between 0 and 1, e.g. `[####------]`
+pub fn progress_bar(fraction: f32) -> String {
+    let filled = ((fraction.clamp(0.0, 1.0) * BAR_WIDTH as f32).round() as usize).min(BAR_WIDTH);
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(BAR_WIDTH - filled))
+}
+
+/// Whether the objective at an index is shown to the player
+pub fn revealed(quest: &QuestDefinition, index: usize, progress: Option<&QuestProgress>) -> bool {
+    let objective = &quest.objectives[index];
+    let done = |id: &str| progress
+        .and_then(|progress| progress.objective_progress.get(id))
+        .is_some_and(|objective| objective.completed);
+    let started = progress
+        .and_then(|progress| progress.objective_progress.get(&objective.id))
+        .is_some_and(|objective| objective.completed || objective.progress_value > 0.0);
+
+    objective.visible
+        || started
+        || (progress.is_some() && quest.objectives[..index].iter().filter(|earlier| !earlier.optional).all(|earlier| done(&earlier.id)))
+}
+
+/// The quest the player follows: the one they chose to track while it stays
+/// active, otherwise their first active quest
+pub fn tracked<'a>(player: &Player, quest_system: &'a QuestSystem) -> Option<&'a QuestProgress> {
+    let active = quest_system.get_active_quests();
+    player.preferences.tracked_quest.as_ref()
+        .and_then(|tracked| active.iter().find(|progress| &progress.quest_id == tracked))
+        .or_else(|| active.iter().min_by(|a, b| a.quest_id.cmp(&b.quest_id)))
+        .copied()
+}
+
+/// Find an active quest by ID or part of its title
+pub fn find_active<'a>(quest_system: &'a QuestSystem, query: &str) -> crate::GameResult<&'a QuestDefinition> {
+    let query = query.trim().to_lowercase();
+    let mut active = quest_system.get_active_quests();
+    active.sort_by(|a, b| a.quest_id.cmp(&b.quest_id));
+    active.iter()
+        .filter_map(|progress| quest_system.quest_definitions.get(&progress.quest_id))
+        .find(|quest| quest.id == query || quest.title.to_lowercase().contains(&query))
+        .ok_or_else(|| crate::GameError::ContentNotFound(format!("You have no active quest matching '{}'.", query)).into())
+}
+
+/// Objective lines of a quest, hidden ones masked
+fn objective_lines(quest: &QuestDefinition, progress: Option<&QuestProgress>) -> String {
+    let mut lines = String::new();
+    let mut hidden = 0;
+    for (index, objective) in quest.objectives.iter().enumerate() {
+        if !revealed(quest, index, progress) {
+            hidden += 1;
+            continue;
+        }
+        let state = progress.and_then(|progress| progress.objective_progress.get(&objective.id));
+        let completed = state.is_some_and(|state| state.completed);
+        let fraction = state.map_or(0.0, |state| if state.completed { 1.0 } else { state.progress_value });
+        lines.push_str(&format!(
+            "  {} {} {:>3.0}% {}{}\n",
+            if completed { "✓" } else { "○" },
+            progress_bar(fraction),
+            fraction * 100.0,
+            objective.description,
+            if objective.optional { " (Optional)" } else { "" }
+        ));
+    }
+    if hidden > 0 {
+        lines.push_str(&format!("  ? {} objective{} not yet revealed\n", hidden, if hidden == 1 { "" } else { "s" }));
+    }
+    lines
+}
+
+/// Completed and total objectives of a quest the player has taken
+fn tally(quest: &QuestDefinition, progress: &QuestProgress) -> (usize, usize) {
+    let done = quest.objectives.iter()
+        .filter(|objective| progress.objective_progress.get(&objective.id).is_some_and(|state| state.completed))
+        .count();
+    (done, quest.objectives.len())
+}
+
+/// Every active quest with its objectives
+pub fn journal(player: &Player, quest_system: &QuestSystem) -> String {
+    let mut active = quest_system.get_active_quests();
+    if active.is_empty() {
+        return "Your journal is empty. Use 'quest list' to find work.".to_string();
+    }
+    active.sort_by(|a, b| a.quest_id.cmp(&b.quest_id));
+    let tracked_id = tracked(player, quest_system).map(|progress| progress.quest_id.clone());
+
+    let mut response = "=== Quest Journal ===\n".to_string();
+    for progress in active {
+        let Some(quest) = quest_system.quest_definitions.get(&progress.quest_id) else { continue };
+        let (done, total) = tally(quest, progress);
+        response.push_str(&format!(
+            "\n{}{} [{}] {}/{}\n",
+            if tracked_id.as_deref() == Some(quest.id.as_str()) { "» " } else { "" },
+            quest.title, quest.id, done, total
+        ));
+        response.push_str(&objective_lines(quest, Some(progress)));
+    }
+    response.push_str("\nUse 'quest <id>' to read a quest in full, or 'track <quest>' to pin one to your status.");
+    response
+}
+
+/// A single quest's page
+pub fn page(quest_system: &QuestSystem, quest_id: &str, now: i32) -> crate::GameResult<String> {
+    let quest = quest_system.quest_definitions.get(quest_id)
+        .or_else(|| find_active(quest_system, quest_id).ok())
+        .ok_or_else(|| crate::GameError::ContentNotFound(format!("Quest '{}' not found.", quest_id)))?;
+    let progress = quest_system.player_progress.get(&quest.id);
+
+    let mut response = format!("=== {} ===\n", quest.title);
+    response.push_str(&format!(
+        "Status: {}\n\n{}\n\nObjectives:\n",
+        progress.map_or("Not Started".to_string(), |progress| format!("{:?}", progress.status)),
+        quest.description
+    ));
+    response.push_str(&objective_lines(quest, progress));
+    if let Some(progress) = progress {
+        let (done, total) = tally(quest, progress);
+        response.push_str(&format!("\nOverall: {} {}/{}\n", progress_bar(done as f32 / total.max(1) as f32), done, total));
+    }
+    if let Some(minutes) = quest_system.time_left(&quest.id, now) {
+        response.push_str(&format!("Time left: {}\n", crate::systems::quests::describe_minutes(minutes)));
+    }
+    if let Some(repeat) = &quest.repeat {
+        response.push_str(&format!("Repeatable {}\n", repeat.describe()));
+        if let Some(minutes) = quest_system.repeat_ready_in(&quest.id, now) {
+            response.push_str(&format!("On offer again in: {}\n", crate::systems::quests::describe_minutes(minutes)));
+        }
+    }
+    Ok(response)
+}
+
+/// The tracker line pinned to the status display
+pub fn tracker_line(player: &Player, quest_system: &QuestSystem) -> Option<String> {
+    let progress = tracked(player, quest_system)?;
+    let quest = quest_system.quest_definitions.get(&progress.quest_id)?;
+    let (done, total) = tally(quest, progress);
+    let next = quest.objectives.iter().enumerate()
+        .filter(|(index, _)| revealed(quest, *index, Some(progress)))
+        .map(|(_, objective)| objective)
+        .find(|objective| !objective.optional && !progress.objective_progress.get(&objective.id).is_some_and(|state| state.completed));
+    Some(match next {
+        Some(objective) => format!("Tracking: {} {} {}/{} - next: {}", quest.title, progress_bar(done as f32 / total.max(1) as f32), done, total, objective.description),
+        None => format!("Tracking: {} {} {}/{} - ready to turn in", quest.title, progress_bar(done as f32 / total.max(1) as f32), done, total),
+    })
+}
+
+/// Pin a quest to the status display, or unpin when the query is `off`
+pub fn track(player: &mut Player, quest_system: &QuestSystem, query: &str) -> crate::GameResult<String> {
+    if matches!(query.trim(), "off" | "none" | "clear") {
+        player.preferences.tracked_quest = None;
+        return Ok("You stop tracking a particular quest; your first active quest is shown instead.".to_string());
+    }
+    let quest = find_active(quest_system, query)?;
+    player.preferences.tracked_quest = Some(quest.id.clone());
+    Ok(format!("Now tracking {}.", quest.title))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::factions::FactionSystem;
+    use crate::systems::quest_examples::create_example_quests;
+
+    #[test]
+    fn test_journal_shows_progress_and_reveals_hidden_objectives() {
+        let mut quest_system = QuestSystem::new();
+        for quest in create_example_quests() {
+            quest_system.add_quest_definition(quest);
+        }
+        let mut player = Player::new("Ada".to_string());
+        player.current_location = "practice_hall".to_string();
+        assert!(tracker_line(&player, &quest_system).is_none());
+        quest_system.start_quest("resonance_foundation", &player, &FactionSystem::new()).unwrap();
+
+        // The demonstration stays hidden until the study before it is done
+        let journal_text = journal(&player, &quest_system);
+        assert!(journal_text.contains("» Understanding Resonance [resonance_foundation] 0/4"));
+        assert!(journal_text.contains("1 objective not yet revealed"));
+        assert_eq!(progress_bar(0.5), "[#####-----]");
+
+        quest_system.update_objective_progress("resonance_foundation", "visit_practice_hall", 1.0, true).unwrap();
+        quest_system.update_objective_progress("resonance_foundation", "learn_harmonic_fundamentals", 1.0, true).unwrap();
+        let quest = &quest_system.quest_definitions["resonance_foundation"];
+        assert!(revealed(quest, 2, quest_system.player_progress.get("resonance_foundation")));
+        assert!(!journal(&player, &quest_system).contains("not yet revealed"));
+
+        let tracker = tracker_line(&player, &quest_system).unwrap();
+        assert!(tracker.starts_with("Tracking: Understanding Resonance [#####-----] 2/4 - next:"));
+        assert!(track(&mut player, &quest_system, "crystal").is_err());
+        assert_eq!(track(&mut player, &quest_system, "understanding").unwrap(), "Now tracking Understanding Resonance.");
+        assert!(page(&quest_system, "resonance_foundation", 0).unwrap().contains("Overall: [#####-----] 2/4"));
+    }
+}
@@ -7,8 +7,13 @@
 //! - Multi-path quest progression based on player choices
 //! - Scientific learning integration with practical applications
 
+use crate::core::events::{Event, EventListener, EventType};
 use crate::core::Player;
 use crate::systems::factions::{FactionId, FactionSystem};
+use crate::systems::location_changes::LocationEdit;
+use crate::systems::quest_journal;
+use crate::systems::suggestions::{ActionKind, ActionSource, Situation, SuggestedAction};
+use crate::systems::world_flags::WorldFlags;
 use crate::GameResult;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -64,6 +69,113 @@ pub struct QuestDefinition {
     pub locations: Vec<String>,
     /// Estimated completion time in minutes
     pub estimated_duration: i32,
+    /// Game time allowed to finish the quest once started
+    #[serde(default)]
+    pub deadline: Option<QuestDeadline>,
+    /// Events that make the quest fail while it is underway
+    #[serde(default)]
+    pub failure_triggers: Vec<FailureTrigger>,
+    /// What the quest leaves behind if it fails
+    #[serde(default)]
+    pub fallout: Option<QuestFallout>,
+    /// Quest whose failure this one cleans up after; offered only once that
+    /// quest has failed
+    #[serde(default)]
+    pub cleans_up: Option<QuestId>,
+    /// How often the quest can be taken again, for repeatable work
+    #[serde(default)]
+    pub repeat: Option<QuestRepeat>,
+    /// World flags set when the quest is completed
+    #[serde(default)]
+    pub sets_flags: Vec<String>,
+    /// Lasting changes to locations when the quest is completed
+    #[serde(default)]
+    pub changes_locations: Vec<LocationEdit>,
+}
+
+/// A time limit on a quest, measured in game time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestDeadline {
+    /// Game minutes from starting the quest until it fails
+    pub minutes: i32,
+    /// How long before the deadline the player is warned, in game minutes
+    pub warning_minutes: i32,
+}
+
+/// A repeatable quest's cooldown, measured in days of the game calendar
+///
+/// Once the player has been paid, or the quest has failed, it returns to
+/// the board at the start of the day `cooldown_days` after.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestRepeat {
+    pub cooldown_days: i32,
+}
+
+impl QuestRepeat {
+    /// How often the quest comes round, e.g. `every 2 days`
+    pub fn describe(&self) -> String {
+        match self.cooldown_days {
+            days if days <= 1 => "every day".to_string(),
+            days => format!("every {} days", days),
+        }
+    }
+}
+
+/// Something that makes a quest fail while it is underway
+///
+/// Quests also fail without a trigger when time runs out or a required
+/// objective can no longer be done.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FailureTrigger {
+    /// An NPC the quest depends on is gone for good
+    NpcLost { npc_id: String },
+    /// The player's reputation with a faction falls below a threshold
+    ReputationBelow { faction: FactionId, reputation: i32 },
+    /// The player's reputation with a faction rises above a threshold
+    ReputationAbove { faction: FactionId, reputation: i32 },
+}
+
+/// What a failed quest leaves behind: an aftermath the player hears and a
+/// script of changes to the world
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestFallout {
+    pub aftermath: String,
+    #[serde(default)]
+    pub effects: Vec<FalloutEffect>,
+}
+
+/// A change to the world when a quest fails
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FalloutEffect {
+    /// The player's reputation with a faction changes
+    Reputation { faction: FactionId, change: i32 },
+    /// Relations between two factions shift; negative shifts sour them
+    FactionRelations { factions: (FactionId, FactionId), shift: i32 },
+    /// An NPC leaves for good; the reason follows their name
+    NpcDeparts { npc_id: String, reason: String },
+    /// A world flag is set
+    SetFlag { flag: String },
+    /// A location changes for good
+    ChangeLocation(LocationEdit),
+}
+
+/// Quest variable holding the game time a timed quest is due
+const DEADLINE_DUE: &str = "deadline_due";
+/// Quest variable set once the player has been warned of a deadline
+const DEADLINE_WARNED: &str = "deadline_warned";
+/// Quest variable holding the game time a repeatable quest returns to the board
+const REPEAT_READY: &str = "repeat_ready";
+/// Game minutes in a day of the calendar
+const MINUTES_PER_DAY: i32 = 24 * 60;
+
+/// A span of game minutes as hours and minutes, e.g. `2h 05m`
+pub fn describe_minutes(minutes: i32) -> String {
+    let minutes = minutes.max(0);
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
 }
 
 /// Categories of quests for organization
@@ -112,6 +224,9 @@ pub struct QuestRequirements {
     pub capability_requirements: Vec<String>,
     /// Player must be at specific locations
     pub location_requirements: Vec<String>,
+    /// World flag conditions that must hold
+    #[serde(default)]
+    pub world_flags: Vec<String>,
 }
 
 /// Player attribute requirements for quests
@@ -161,6 +276,8 @@ pub enum ObjectiveType {
     CollectItems { item_ids: Vec<String>, quantities: Vec<i32> },
     /// Complete learning activity with specific method
     LearningActivity { theory_id: String, method: String, duration: i32 },
+    /// Be there when a world event begins
+    WitnessEvent { event_id: String },
 }
 
 /// Rewards for completing individual objectives
@@ -203,6 +320,29 @@ pub struct QuestRewards {
     pub unlocked_quests: Vec<QuestId>,
 }
 
+/// Adjustments to a quest's rewards agreed when turning it in
+#[derive(Debug, Clone)]
+pub struct RewardTerms {
+    /// Silver paid on top of the listed rewards
+    pub silver: i32,
+    /// Multiplier on faction standing gains
+    pub standing_multiplier: f32,
+    /// Standing gained beyond the listed changes
+    pub extra_standing: Option<(FactionId, i32)>,
+    /// Understanding gained in a theory
+    pub insight: Option<(String, f32)>,
+    /// Item handed over
+    pub item: Option<crate::systems::items::Item>,
+    /// The quest's listed reward items, made from their content definitions
+    pub reward_items: Vec<crate::systems::items::Item>,
+}
+
+impl Default for RewardTerms {
+    fn default() -> Self {
+        Self { silver: 0, standing_multiplier: 1.0, extra_standing: None, insight: None, item: None, reward_items: Vec::new() }
+    }
+}
+
 /// Attribute bonuses from quest completion
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AttributeBonuses {
@@ -404,6 +544,12 @@ pub struct QuestGlobalState {
         deserialize_with = "crate::systems::serde_helpers::deserialize_faction_pair_map"
     )]
     pub faction_relationship_modifiers: HashMap<(FactionId, FactionId), f32>,
+    /// Times each repeatable quest has been completed
+    #[serde(default)]
+    pub repeat_completions: HashMap<QuestId, u32>,
+    /// Facts about the world shared with dialogue and locations
+    #[serde(default)]
+    pub flags: WorldFlags,
 }
 
 impl QuestSystem {
@@ -416,6 +562,8 @@ impl QuestSystem {
                 unlocked_quest_lines: vec!["tutorial".to_string()],
                 global_events: HashMap::new(),
                 faction_relationship_modifiers: HashMap::new(),
+                repeat_completions: HashMap::new(),
+                flags: WorldFlags::default(),
             },
         }
     }
@@ -440,6 +588,13 @@ impl QuestSystem {
             return progress.status == QuestStatus::Available;
         }
 
+        // Cleanup quests wait for the quest they clean up after to fail
+        if let Some(failed) = &quest.cleans_up {
+            if !self.player_progress.get(failed).is_some_and(|progress| progress.status == QuestStatus::Failed) {
+                return false;
+            }
+        }
+
         // Check all requirements
         self.check_quest_requirements(&quest.requirements, player, faction_system)
     }
@@ -517,6 +672,11 @@ impl QuestSystem {
             }
         }
 
+        // Check world flags
+        if !self.global_state.flags.all_hold(&requirements.world_flags) {
+            return false;
+        }
+
         true
     }
 
@@ -563,9 +723,13 @@ impl QuestSystem {
             },
         };
 
+        let mut message = format!("Started quest: {}\n{}", quest.title, quest.description);
+        if let Some(deadline) = &quest.deadline {
+            message.push_str(&format!("\n\nThis can't wait: you have {} to see it through.", describe_minutes(deadline.minutes)));
+        }
         self.player_progress.insert(quest_id.to_string(), progress);
 
-        Ok(format!("Started quest: {}\n{}", quest.title, quest.description))
+        Ok(message)
     }
 
     /// Update objective progress
@@ -619,6 +783,9 @@ impl QuestSystem {
         if completed_required {
             quest_progress.status = QuestStatus::Completed;
             quest_progress.completed_at = Some(Utc::now());
+            for flag in &quest_def.sets_flags {
+                self.global_state.flags.set(flag);
+            }
             return Ok(true);
         }
 
@@ -748,7 +915,7 @@ impl QuestSystem {
             if let Some(quest_def) = self.quest_definitions.get(&quest_id) {
                 for objective in &quest_def.objectives {
                     if let ObjectiveType::TalkToNPC { npc_id: required_npc, topic: required_topic } = &objective.objective_type {
-                        if required_npc == npc_id {
+                        if required_npc == npc_id && !self.objective_completed(&quest_id, &objective.id) {
                             let topic_matches = match (&required_topic, topic) {
                                 (Some(req_topic), Some(actual_topic)) => req_topic == actual_topic,
                                 (None, _) => true, // Any topic accepted
@@ -795,7 +962,9 @@ impl QuestSystem {
                 for objective in &quest_def.objectives {
                     match &objective.objective_type {
                         ObjectiveType::LearnTheory { theory_id: req_theory, min_level } => {
-                            if req_theory == theory_id && new_understanding_level >= *min_level {
+                            if req_theory == theory_id && new_understanding_level >= *min_level
+                                && !self.objective_completed(quest_id, &objective.id)
+                            {
                                 updates_to_apply.push((quest_id.clone(), objective.id.clone(), objective.description.clone()));
                             }
                         },
@@ -808,7 +977,7 @@ impl QuestSystem {
                                 mastered_theories.len()
                             };
 
-                            if relevant_theories >= *count as usize {
+                            if relevant_theories >= *count as usize && !self.objective_completed(quest_id, &objective.id) {
                                 updates_to_apply.push((quest_id.clone(), objective.id.clone(), objective.description.clone()));
                             }
                         },
@@ -843,7 +1012,7 @@ impl QuestSystem {
             if let Some(quest_def) = self.quest_definitions.get(quest_id) {
                 for objective in &quest_def.objectives {
                     if let ObjectiveType::VisitLocation { location_id: req_location } = &objective.objective_type {
-                        if req_location == location_id {
+                        if req_location == location_id && !self.objective_completed(quest_id, &objective.id) {
                             updates_to_apply.push((quest_id.clone(), objective.id.clone(), objective.description.clone()));
                         }
                     }
@@ -860,12 +1029,236 @@ impl QuestSystem {
         Ok(quest_updates)
     }
 
+    /// Handle the items the player carries for collection objectives, given
+    /// counts by content definition ID
+    pub fn handle_items_carried(&mut self, carried: &HashMap<String, i32>) -> GameResult<Vec<String>> {
+        let mut updates_to_apply = Vec::new();
+        for progress in self.get_active_quests() {
+            let Some(quest_def) = self.quest_definitions.get(&progress.quest_id) else { continue };
+            for objective in &quest_def.objectives {
+                if let ObjectiveType::CollectItems { item_ids, quantities } = &objective.objective_type {
+                    let gathered = item_ids.iter().enumerate()
+                        .all(|(i, item_id)| carried.get(item_id).copied().unwrap_or(0) >= quantities.get(i).copied().unwrap_or(1));
+                    if gathered && !self.objective_completed(&progress.quest_id, &objective.id) {
+                        updates_to_apply.push((progress.quest_id.clone(), objective.id.clone(), objective.description.clone()));
+                    }
+                }
+            }
+        }
+
+        let mut quest_updates = Vec::new();
+        for (quest_id, objective_id, description) in updates_to_apply {
+            self.update_objective_progress(&quest_id, &objective_id, 1.0, true)?;
+            quest_updates.push(format!("Quest objective completed: {}", description));
+        }
+
+        Ok(quest_updates)
+    }
+
+    /// Handle a world event: note the ones under way, and count those the
+    /// player saw begin toward witnessing objectives
+    pub fn handle_world_event(&mut self, event: &Event) -> GameResult<Vec<String>> {
+        let Some(def) = event.definition() else { return Ok(Vec::new()) };
+        match event.event_type {
+            EventType::Started => self.global_state.global_events.insert(def.id.to_string(), def.name.to_string()),
+            EventType::Ended => self.global_state.global_events.remove(def.id),
+        };
+        if event.event_type != EventType::Started || !event.witnessed {
+            return Ok(Vec::new());
+        }
+
+        let mut updates_to_apply = Vec::new();
+        for progress in self.get_active_quests() {
+            let Some(quest_def) = self.quest_definitions.get(&progress.quest_id) else { continue };
+            for objective in &quest_def.objectives {
+                if let ObjectiveType::WitnessEvent { event_id } = &objective.objective_type {
+                    if *event_id == event.event_id && !self.objective_completed(&progress.quest_id, &objective.id) {
+                        updates_to_apply.push((progress.quest_id.clone(), objective.id.clone(), objective.description.clone()));
+                    }
+                }
+            }
+        }
+
+        let mut quest_updates = Vec::new();
+        for (quest_id, objective_id, description) in updates_to_apply {
+            self.update_objective_progress(&quest_id, &objective_id, 1.0, true)?;
+            quest_updates.push(format!("Quest objective completed: {}", description));
+        }
+
+        Ok(quest_updates)
+    }
+
+    /// Run the clocks on timed quests, warning as deadlines near
+    ///
+    /// A quest's clock starts the first time it is checked after starting.
+    pub fn check_deadlines(&mut self, now: i32) -> Vec<String> {
+        let mut messages = Vec::new();
+        let mut ids: Vec<QuestId> = self.player_progress.keys().cloned().collect();
+        ids.sort();
+
+        for quest_id in ids {
+            let Some(quest) = self.quest_definitions.get(&quest_id) else { continue };
+            let Some(deadline) = &quest.deadline else { continue };
+            let Some(progress) = self.player_progress.get_mut(&quest_id) else { continue };
+            if progress.status != QuestStatus::InProgress {
+                continue;
+            }
+
+            let due = progress.quest_variables.get(DEADLINE_DUE).and_then(|due| due.parse::<i32>().ok())
+                .unwrap_or(now + deadline.minutes);
+            progress.quest_variables.insert(DEADLINE_DUE.to_string(), due.to_string());
+
+            if now < due && due - now <= deadline.warning_minutes && !progress.quest_variables.contains_key(DEADLINE_WARNED) {
+                progress.quest_variables.insert(DEADLINE_WARNED.to_string(), "true".to_string());
+                messages.push(format!("Time is running short on {}: {} left.", quest.title, describe_minutes(due - now)));
+            }
+        }
+
+        messages
+    }
+
+    /// Why an active quest has failed, if it has
+    ///
+    /// `departed` gives the name of an NPC who is gone for good, and
+    /// `location_exists` says whether a location can still be reached.
+    pub fn failure_cause(
+        &self,
+        quest_id: &str,
+        now: i32,
+        faction_system: &FactionSystem,
+        departed: impl Fn(&str) -> Option<String>,
+        location_exists: impl Fn(&str) -> bool,
+    ) -> Option<String> {
+        let quest = self.quest_definitions.get(quest_id)?;
+        let progress = self.player_progress.get(quest_id).filter(|progress| progress.status == QuestStatus::InProgress)?;
+
+        if self.time_left(quest_id, now) == Some(0) {
+            return Some("time ran out".to_string());
+        }
+
+        for trigger in &quest.failure_triggers {
+            match trigger {
+                FailureTrigger::NpcLost { npc_id } => {
+                    if let Some(name) = departed(npc_id) {
+                        return Some(format!("{} is gone", name));
+                    }
+                }
+                FailureTrigger::ReputationBelow { faction, reputation } if faction_system.get_reputation(*faction) < *reputation => {
+                    return Some(format!("the {} no longer trust you", faction.display_name()));
+                }
+                FailureTrigger::ReputationAbove { faction, reputation } if faction_system.get_reputation(*faction) > *reputation => {
+                    return Some(format!("you have grown too close to the {}", faction.display_name()));
+                }
+                _ => {}
+            }
+        }
+
+        quest.objectives.iter()
+            .filter(|objective| !objective.optional)
+            .filter(|objective| !progress.objective_progress.get(&objective.id).is_some_and(|state| state.completed))
+            .find(|objective| match &objective.objective_type {
+                ObjectiveType::TalkToNPC { npc_id, .. } | ObjectiveType::TeachTheory { npc_id, .. } => departed(npc_id).is_some(),
+                ObjectiveType::VisitLocation { location_id } => !location_exists(location_id),
+                _ => false,
+            })
+            .map(|objective| format!("\"{}\" can no longer be done", objective.description))
+    }
+
+    /// Mark an active quest failed
+    pub fn fail_quest(&mut self, quest_id: &str) -> GameResult<()> {
+        let progress = self.player_progress.get_mut(quest_id)
+            .filter(|progress| progress.status == QuestStatus::InProgress)
+            .ok_or_else(|| crate::GameError::InvalidCommand(format!("Quest '{}' is not active", quest_id)))?;
+        progress.status = QuestStatus::Failed;
+        progress.completed_at = Some(Utc::now());
+        Ok(())
+    }
+
+    /// Put repeatable quests whose cooldown has passed back on the board;
+    /// returns messages announcing them
+    ///
+    /// A completed quest starts its cooldown once its rewards are claimed, so
+    /// finishing without turning in never forfeits the pay.
+    pub fn refresh_repeatables(&mut self, now: i32) -> Vec<String> {
+        let mut messages = Vec::new();
+        let mut ids: Vec<QuestId> = self.player_progress.keys().cloned().collect();
+        ids.sort();
+
+        for quest_id in ids {
+            let Some(quest) = self.quest_definitions.get(&quest_id) else { continue };
+            let Some(repeat) = &quest.repeat else { continue };
+            let Some(progress) = self.player_progress.get_mut(&quest_id) else { continue };
+            let finished = match progress.status {
+                QuestStatus::Completed => progress.quest_variables.contains_key(crate::systems::reward_negotiation::CLAIMED_VARIABLE),
+                QuestStatus::Failed | QuestStatus::Abandoned => true,
+                _ => false,
+            };
+            if !finished {
+                continue;
+            }
+
+            let ready = match progress.quest_variables.get(REPEAT_READY).and_then(|ready| ready.parse::<i32>().ok()) {
+                Some(ready) => ready,
+                None => {
+                    let ready = (now.div_euclid(MINUTES_PER_DAY) + repeat.cooldown_days.max(1)) * MINUTES_PER_DAY;
+                    progress.quest_variables.insert(REPEAT_READY.to_string(), ready.to_string());
+                    if progress.status == QuestStatus::Completed {
+                        *self.global_state.repeat_completions.entry(quest_id.clone()).or_insert(0) += 1;
+                    }
+                    ready
+                }
+            };
+            if now >= ready {
+                messages.push(format!("{} is on offer again.", quest.title));
+                self.player_progress.remove(&quest_id);
+            }
+        }
+
+        messages
+    }
+
+    /// Game minutes until a repeatable quest returns to the board, once its
+    /// cooldown has started
+    pub fn repeat_ready_in(&self, quest_id: &str, now: i32) -> Option<i32> {
+        self.player_progress.get(quest_id)?
+            .quest_variables.get(REPEAT_READY)?
+            .parse::<i32>().ok()
+            .map(|ready| (ready - now).max(0))
+    }
+
+    /// Game minutes left on a timed quest, once its clock has started
+    pub fn time_left(&self, quest_id: &str, now: i32) -> Option<i32> {
+        self.player_progress.get(quest_id)
+            .filter(|progress| progress.status == QuestStatus::InProgress)?
+            .quest_variables.get(DEADLINE_DUE)?
+            .parse::<i32>().ok()
+            .map(|due| (due - now).max(0))
+    }
+
+    /// Whether an objective of a quest the player has taken is done
+    fn objective_completed(&self, quest_id: &str, objective_id: &str) -> bool {
+        self.player_progress.get(quest_id)
+            .and_then(|progress| progress.objective_progress.get(objective_id))
+            .is_some_and(|progress| progress.completed)
+    }
+
     /// Apply quest rewards to player
     pub fn apply_quest_rewards(
         &self,
         quest_id: &str,
         player: &mut Player,
         faction_system: &mut FactionSystem,
+    ) -> GameResult<String> {
+        self.apply_quest_rewards_with(quest_id, player, faction_system, &RewardTerms::default())
+    }
+
+    /// Apply quest rewards to player on negotiated terms
+    pub fn apply_quest_rewards_with(
+        &self,
+        quest_id: &str,
+        player: &mut Player,
+        faction_system: &mut FactionSystem,
+        terms: &RewardTerms,
     ) -> GameResult<String> {
         let quest_def = self.quest_definitions.get(quest_id)
             .ok_or_else(|| crate::GameError::ContentNotFound(format!("Quest '{}' not found", quest_id)))?;
@@ -903,9 +1296,40 @@ impl QuestSystem {
             }
         }
 
-        // Apply faction changes
-        for (faction_id, change) in &quest_def.rewards.faction_changes {
+        // Apply negotiated extras
+        if terms.silver > 0 {
+            player.inventory.silver += terms.silver;
+            reward_summary.push_str(&format!("• {} silver\n", terms.silver));
+        }
+
+        if let Some((theory_id, insight)) = &terms.insight {
+            let current_level = player.knowledge.theories.entry(theory_id.clone()).or_insert(0.0);
+            *current_level = (*current_level + insight).min(1.0);
+            reward_summary.push_str(&format!("• Insight: +{:.1}% understanding in {}\n", insight * 100.0, theory_id));
+        }
+
+        for item in terms.item.iter().chain(&terms.reward_items) {
+            let name = item.properties.name.clone();
+            match player.add_enhanced_item(item.clone()) {
+                Ok(()) => reward_summary.push_str(&format!("• Item received: {}\n", name)),
+                Err(_) => reward_summary.push_str(&format!("• The {} does not fit in your pack and is left behind\n", name)),
+            }
+        }
+
+        // Apply faction changes, scaled by the terms for gains
+        let mut faction_changes: Vec<(FactionId, i32)> = quest_def.rewards.faction_changes.iter()
+            .map(|(faction_id, change)| {
+                let change = if *change > 0 { (*change as f32 * terms.standing_multiplier).round() as i32 } else { *change };
+                (*faction_id, change)
+            })
+            .collect();
+        faction_changes.extend(terms.extra_standing);
+        for (faction_id, change) in &faction_changes {
+            if *change == 0 {
+                continue;
+            }
             faction_system.modify_reputation(*faction_id, *change);
+            faction_system.legacy.advance(*faction_id, *change, &format!("Completed {}", quest_def.title));
             reward_summary.push_str(&format!("• {} faction standing with {}\n",
                 if *change > 0 { format!("+{}", change) } else { change.to_string() },
                 faction_id.display_name()
@@ -917,8 +1341,8 @@ impl QuestSystem {
             reward_summary.push_str(&format!("• New capability unlocked: {}\n", capability));
         }
 
-        // Items would be added to inventory (not implemented in this snippet)
-        if !quest_def.rewards.items.is_empty() {
+        // Listed items without content definitions are only named
+        if terms.reward_items.is_empty() && !quest_def.rewards.items.is_empty() {
             reward_summary.push_str(&format!("• Items received: {}\n", quest_def.rewards.items.join(", ")));
         }
 
@@ -1077,9 +1501,10 @@ impl QuestSystem {
         // Apply the outcome
         let outcome = &option.outcome;
 
-        // Apply faction changes
+        // Apply faction changes; choices nudge long-term influence by half as much
         for (faction_id, change) in &outcome.faction_changes {
             faction_system.modify_reputation(*faction_id, *change);
+            faction_system.legacy.advance(*faction_id, *change / 2, &format!("Chose '{}'", option.text));
         }
 
         // Apply theory insights
@@ -1145,6 +1570,64 @@ impl QuestSystem {
     }
 }
 
+/// Quests keep track of world events and count the ones the player saw
+impl EventListener for QuestSystem {
+    fn handle_event(&mut self, event: &Event) -> Vec<String> {
+        self.handle_world_event(event).unwrap_or_default()
+    }
+}
+
+impl ActionSource for QuestSystem {
+    fn available_actions(&self, situation: &Situation) -> Vec<SuggestedAction> {
+        let world = situation.world;
+        let mut actions = Vec::new();
+        for progress in self.get_active_quests() {
+            let Some(quest) = self.quest_definitions.get(&progress.quest_id) else {
+                continue;
+            };
+            let next = quest.objectives.iter().enumerate()
+                .filter(|(index, objective)| !objective.optional && quest_journal::revealed(quest, *index, Some(progress)))
+                .map(|(_, objective)| objective)
+                .find(|objective| !progress.objective_progress.get(&objective.id).is_some_and(|state| state.completed));
+            match next.map(|objective| &objective.objective_type) {
+                None => actions.push(SuggestedAction::new(
+                    ActionKind::Quests,
+                    format!("turn in {}", quest.id),
+                    format!("everything on {} is done", quest.title),
+                )),
+                Some(ObjectiveType::VisitLocation { location_id }) => {
+                    let exit = world.current_location()
+                        .and_then(|location| location.exits.iter().find(|(_, destination)| *destination == location_id));
+                    if let Some((direction, _)) = exit {
+                        actions.push(SuggestedAction::new(
+                            ActionKind::Movement,
+                            format!("go {}", direction.display_name()),
+                            format!("where {} leads", quest.title),
+                        ));
+                    }
+                }
+                Some(ObjectiveType::LearnTheory { theory_id, .. }) => actions.push(SuggestedAction::new(
+                    ActionKind::Study,
+                    format!("study {}", theory_id),
+                    format!("needed for {}", quest.title),
+                )),
+                _ => {}
+            }
+        }
+
+        for quest in self.get_available_quests(situation.player, situation.faction_system) {
+            if quest.locations.contains(&world.current_location) {
+                actions.push(SuggestedAction::new(
+                    ActionKind::Quests,
+                    format!("quest start {}", quest.id),
+                    format!("{} is on offer here", quest.title),
+                ));
+            }
+        }
+        actions
+    }
+}
+
 impl Default for QuestSystem {
     fn default() -> Self {
         Self::new()
@@ -1185,6 +1668,7 @@ mod tests {
                 },
                 capability_requirements: vec![],
                 location_requirements: vec![],
+                world_flags: vec![],
             },
             objectives: vec![
                 QuestObjective {
@@ -1229,6 +1713,13 @@ mod tests {
             involved_npcs: vec!["test_npc".to_string()],
             locations: vec!["test_location".to_string()],
             estimated_duration: 30,
+            deadline: None,
+            failure_triggers: vec![],
+            fallout: None,
+            cleans_up: None,
+            repeat: None,
+            sets_flags: vec![],
+            changes_locations: vec![],
         }
     }
 
@@ -1311,6 +1802,117 @@ mod tests {
         assert_eq!(progress.status, QuestStatus::Completed);
     }
 
+    #[test]
+    fn test_deadlines_warn_then_fail() {
+        let mut quest_system = QuestSystem::new();
+        let mut quest = create_test_quest();
+        quest.deadline = Some(QuestDeadline { minutes: 600, warning_minutes: 120 });
+        quest.failure_triggers = vec![FailureTrigger::NpcLost { npc_id: "ambassador_cordelia".to_string() }];
+        let player = create_test_player();
+        let faction_system = FactionSystem::new();
+
+        quest_system.add_quest_definition(quest);
+        assert!(quest_system.start_quest("test_quest", &player, &faction_system).unwrap().contains("you have 10h 00m"));
+
+        assert!(quest_system.check_deadlines(100).is_empty());
+        assert_eq!(quest_system.time_left("test_quest", 400), Some(300));
+        assert_eq!(quest_system.check_deadlines(600), vec!["Time is running short on Test Quest: 1h 40m left."]);
+        assert!(quest_system.check_deadlines(650).is_empty());
+
+        let present = |_: &str| None;
+        let gone = |npc_id: &str| (npc_id == "ambassador_cordelia").then(|| "Ambassador Cordelia".to_string());
+        assert_eq!(quest_system.failure_cause("test_quest", 650, &faction_system, present, |_| true), None);
+        assert_eq!(quest_system.failure_cause("test_quest", 650, &faction_system, gone, |_| true).as_deref(), Some("Ambassador Cordelia is gone"));
+        assert_eq!(quest_system.failure_cause("test_quest", 700, &faction_system, present, |_| true).as_deref(), Some("time ran out"));
+
+        quest_system.fail_quest("test_quest").unwrap();
+        assert_eq!(quest_system.player_progress["test_quest"].status, QuestStatus::Failed);
+        assert_eq!(quest_system.failure_cause("test_quest", 800, &faction_system, present, |_| true), None);
+        assert!(quest_system.fail_quest("test_quest").is_err());
+    }
+
+    #[test]
+    fn test_repeatable_quests_return_after_cooldown() {
+        let mut quest_system = QuestSystem::new();
+        let mut quest = create_test_quest();
+        quest.repeat = Some(QuestRepeat { cooldown_days: 1 });
+        let player = create_test_player();
+        let faction_system = FactionSystem::new();
+
+        quest_system.add_quest_definition(quest.clone());
+        quest_system.start_quest("test_quest", &player, &faction_system).unwrap();
+        quest_system.update_objective_progress("test_quest", "obj1", 1.0, true).unwrap();
+
+        // The cooldown waits until the rewards are claimed
+        assert!(quest_system.refresh_repeatables(600).is_empty());
+        assert_eq!(quest_system.repeat_ready_in("test_quest", 600), None);
+        quest_system.player_progress.get_mut("test_quest").unwrap()
+            .quest_variables.insert(crate::systems::reward_negotiation::CLAIMED_VARIABLE.to_string(), "standard".to_string());
+
+        assert!(quest_system.refresh_repeatables(700).is_empty());
+        assert_eq!(quest_system.repeat_ready_in("test_quest", 700), Some(740));
+        assert!(!quest_system.is_quest_available(&quest, &player, &faction_system));
+
+        assert_eq!(quest_system.refresh_repeatables(1440), vec!["Test Quest is on offer again."]);
+        assert!(quest_system.is_quest_available(&quest, &player, &faction_system));
+        assert_eq!(quest_system.global_state.repeat_completions["test_quest"], 1);
+        assert_eq!(QuestRepeat { cooldown_days: 3 }.describe(), "every 3 days");
+    }
+
+    #[test]
+    fn test_world_flags_set_by_completion_gate_other_quests() {
+        let mut quest_system = QuestSystem::new();
+        let mut quest = create_test_quest();
+        quest.sets_flags = vec!["accord_signed".to_string()];
+        let mut sequel = create_test_quest();
+        sequel.id = "sequel".to_string();
+        sequel.requirements.world_flags = vec!["accord_signed".to_string()];
+        let mut rival = create_test_quest();
+        rival.id = "rival".to_string();
+        rival.requirements.world_flags = vec!["!accord_signed".to_string()];
+        let player = create_test_player();
+        let faction_system = FactionSystem::new();
+
+        quest_system.add_quest_definition(quest);
+        assert!(!quest_system.is_quest_available(&sequel, &player, &faction_system));
+        assert!(quest_system.is_quest_available(&rival, &player, &faction_system));
+
+        quest_system.start_quest("test_quest", &player, &faction_system).unwrap();
+        quest_system.update_objective_progress("test_quest", "obj1", 1.0, true).unwrap();
+        assert!(quest_system.global_state.flags.is_set("accord_signed"));
+        assert!(quest_system.is_quest_available(&sequel, &player, &faction_system));
+        assert!(!quest_system.is_quest_available(&rival, &player, &faction_system));
+    }
+
+    #[test]
+    fn test_negotiated_rewards_apply_terms() {
+        let mut quest_system = QuestSystem::new();
+        let mut quest = create_test_quest();
+        quest.rewards.faction_changes.insert(FactionId::MagistersCouncil, 10);
+        let mut player = create_test_player();
+        let mut faction_system = FactionSystem::new();
+
+        quest_system.add_quest_definition(quest);
+        quest_system.start_quest("test_quest", &player, &faction_system).unwrap();
+        assert!(quest_system.apply_quest_rewards("test_quest", &mut player, &mut faction_system).is_err());
+        quest_system.update_objective_progress("test_quest", "obj1", 1.0, true).unwrap();
+
+        let silver = player.inventory.silver;
+        let before = faction_system.get_reputation(FactionId::MagistersCouncil);
+        let terms = RewardTerms {
+            silver: 45,
+            standing_multiplier: 0.5,
+            insight: Some(("mental_resonance".to_string(), 0.1)),
+            ..RewardTerms::default()
+        };
+        let summary = quest_system.apply_quest_rewards_with("test_quest", &mut player, &mut faction_system, &terms).unwrap();
+
+        assert!(summary.contains("45 silver"));
+        assert_eq!(player.inventory.silver, silver + 45);
+        assert_eq!(faction_system.get_reputation(FactionId::MagistersCouncil), before + 5);
+        assert!((player.theory_understanding("mental_resonance") - 0.1).abs() < 1e-6);
+    }
+
     #[test]
     fn test_dialogue_trigger() {
         let mut quest_system = QuestSystem::new();
@@ -0,0 +1,173 @@
+//! NPC relationships
+//!
+//! Every NPC keeps track of how the player has treated them. Affinity rises
+//! and falls with what the player says in conversation, the gifts they give,
+//! how the quests the NPC is involved in turn out, and how the player's
+//! standing with the NPC's faction has moved since they last spoke. Affinity
+//! adds to the disposition faction standing gives, and the stage it reaches
+//! can open topics and change how the NPC greets the player. Each NPC also
+//! remembers the last few things that moved them.
+//!
+//! Relationships are kept with the dialogue system, so they are saved with
+//! the game.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Most recent memories an NPC keeps of the player
+pub const MEMORY_LIMIT: usize = 5;
+/// Affinity for winning an NPC over with a persuasive or learned reply
+pub const CHECK_PASSED: i32 = 5;
+/// Affinity for a reply that fell flat
+pub const CHECK_FAILED: i32 = -3;
+/// Affinity for an intimidation that worked; fear isn't fondness
+pub const INTIMIDATED: i32 = -8;
+/// Affinity for seeing a quest they're involved in through
+pub const QUEST_COMPLETED: i32 = 15;
+/// Affinity for letting a quest they're involved in fail
+pub const QUEST_FAILED: i32 = -15;
+/// Change in faction standing an NPC notices between conversations
+pub const NOTICED_STANDING_CHANGE: i32 = 10;
+/// Gifts given within one game day count as one occasion
+pub const MINUTES_PER_DAY: i32 = 1440;
+/// Most affinity a single gift can earn
+const GIFT_LIMIT: i32 = 10;
+
+/// How far a relationship has come
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RelationshipStage {
+    Hostile,
+    Wary,
+    Stranger,
+    Acquaintance,
+    Friend,
+    Confidant,
+}
+
+impl RelationshipStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RelationshipStage::Hostile => "hostile",
+            RelationshipStage::Wary => "wary",
+            RelationshipStage::Stranger => "stranger",
+            RelationshipStage::Acquaintance => "acquaintance",
+            RelationshipStage::Friend => "friend",
+            RelationshipStage::Confidant => "confidant",
+        }
+    }
+}
+
+/// What one NPC thinks of the player
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Relationship {
+    /// -100 to 100, added to the disposition faction standing gives
+    pub affinity: i32,
+    pub met: bool,
+    /// What moved them most recently, oldest first
+    pub memories: Vec<String>,
+    /// The player's standing with their faction when they last spoke
+    pub standing_seen: Option<i32>,
+    /// Game day of the last gift, after which further gifts that day count for little
+    pub last_gift_day: Option<i32>,
+}
+
+impl Relationship {
+    pub fn stage(&self) -> RelationshipStage {
+        match self.affinity {
+            a if a <= -40 => RelationshipStage::Hostile,
+            a if a < -10 => RelationshipStage::Wary,
+            _ if !self.met => RelationshipStage::Stranger,
+            a if a < 20 => RelationshipStage::Acquaintance,
+            a if a < 50 => RelationshipStage::Friend,
+            _ => RelationshipStage::Confidant,
+        }
+    }
+
+    /// Shift affinity and remember why; returns the new stage if it changed
+    pub fn shift(&mut self, change: i32, memory: &str) -> Option<RelationshipStage> {
+        let before = self.stage();
+        self.affinity = (self.affinity + change).clamp(-100, 100);
+        self.memories.push(memory.to_string());
+        if self.memories.len() > MEMORY_LIMIT {
+            self.memories.remove(0);
+        }
+        let after = self.stage();
+        (after != before).then_some(after)
+    }
+}
+
+/// Affinity a gift earns, by its value in silver, on a given game day
+pub fn gift_affinity(relationship: &Relationship, value: i32, day: i32) -> i32 {
+    if relationship.last_gift_day == Some(day) {
+        return 1;
+    }
+    (2 + value.max(0) / 20).min(GIFT_LIMIT)
+}
+
+/// Every NPC's relationship with the player, by NPC ID
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Relationships {
+    npcs: HashMap<String, Relationship>,
+}
+
+impl Relationships {
+    pub fn get(&self, npc_id: &str) -> Option<&Relationship> {
+        self.npcs.get(npc_id)
+    }
+
+    pub fn get_mut(&mut self, npc_id: &str) -> &mut Relationship {
+        self.npcs.entry(npc_id.to_string()).or_default()
+    }
+
+    pub fn affinity(&self, npc_id: &str) -> i32 {
+        self.get(npc_id).map_or(0, |relationship| relationship.affinity)
+    }
+
+    pub fn stage(&self, npc_id: &str) -> RelationshipStage {
+        self.get(npc_id).map_or(RelationshipStage::Stranger, Relationship::stage)
+    }
+
+    /// Every NPC the player has a relationship with, by ID
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Relationship)> {
+        self.npcs.iter()
+    }
+}
+
+/// What the player sees when a relationship reaches a new stage
+pub fn stage_message(name: &str, stage: RelationshipStage) -> String {
+    match stage {
+        RelationshipStage::Hostile => format!("{} has turned against you.", name),
+        RelationshipStage::Wary => format!("{} has grown wary of you.", name),
+        RelationshipStage::Stranger => format!("{} no longer seems to know what to make of you.", name),
+        RelationshipStage::Acquaintance => format!("{} regards you as an acquaintance now.", name),
+        RelationshipStage::Friend => format!("{} counts you as a friend now.", name),
+        RelationshipStage::Confidant => format!("{} trusts you completely now.", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_affinity_moves_through_stages_and_memories_are_capped() {
+        let mut relationship = Relationship::default();
+        assert_eq!(relationship.stage(), RelationshipStage::Stranger);
+        relationship.met = true;
+        assert_eq!(relationship.stage(), RelationshipStage::Acquaintance);
+
+        assert_eq!(relationship.shift(QUEST_COMPLETED, "You saw the research through"), None);
+        assert_eq!(relationship.shift(CHECK_PASSED, "You impressed them"), Some(RelationshipStage::Friend));
+        assert_eq!(relationship.shift(INTIMIDATED * 5, "You threatened them"), Some(RelationshipStage::Wary));
+        for _ in 0..MEMORY_LIMIT {
+            relationship.shift(0, "Small talk");
+        }
+        assert_eq!(relationship.memories.len(), MEMORY_LIMIT);
+        assert!(relationship.memories.iter().all(|memory| memory == "Small talk"));
+
+        assert_eq!(gift_affinity(&relationship, 100, 3), 7);
+        assert_eq!(gift_affinity(&relationship, 1000, 3), GIFT_LIMIT);
+        relationship.last_gift_day = Some(3);
+        assert_eq!(gift_affinity(&relationship, 1000, 3), 1);
+    }
+}
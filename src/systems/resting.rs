@@ -0,0 +1,220 @@
+//! Long rests that skip ahead until the player has recovered
+//!
+//! Rather than typing `rest` hour after hour, the player can rest until
+//! recovered. Time passes in hour-long steps, and the rest stops early when
+//! something needs attention: someone arrives or becomes free to talk, a
+//! busy NPC's wait runs out, or a creature living nearby finds the camp.
+//! Everything that happened along the way is collected into one summary.
+
+use crate::core::{Player, WorldState};
+use crate::systems::combat::{CombatSystem, DifficultyTier};
+use crate::systems::dialogue::DialogueSystem;
+use crate::systems::quests::QuestSystem;
+use rand::Rng;
+
+/// Minutes simulated per step
+const STEP_MINUTES: i32 = 60;
+/// Longest rest before the player gets up regardless
+const MAX_REST_HOURS: u32 = 12;
+/// Fatigue shed per hour, matching a single `rest`
+const FATIGUE_PER_HOUR: i32 = 10;
+/// Fraction of maximum energy restored per hour of deep rest
+const ENERGY_PER_HOUR: f32 = 0.1;
+/// Chance per hour that a local creature disturbs the rest
+const ENCOUNTER_CHANCE_PER_HOUR: f32 = 0.08;
+
+/// Why a rest ended before the player recovered
+#[derive(Debug, Clone, PartialEq)]
+pub enum Interruption {
+    /// Someone arrived or became free to talk
+    Scheduled(String),
+    /// A timed wait ran out
+    Deadline(String),
+    /// A creature found the player; combat has started
+    Encounter(String),
+}
+
+/// Everything that happened during a long rest
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestSummary {
+    pub hours: u32,
+    pub fatigue_recovered: i32,
+    pub energy_recovered: i32,
+    /// Notable events, each tagged with the hour it happened
+    pub events: Vec<String>,
+    pub interruption: Option<Interruption>,
+}
+
+impl RestSummary {
+    /// Compact report of the rest
+    pub fn report(&self, player: &Player) -> String {
+        let mut output = format!(
+            "You rest for {} hour{}. Fatigue -{}, energy +{} (now {}/{}, fatigue {}/100, health {}/{}).",
+            self.hours, if self.hours == 1 { "" } else { "s" },
+            self.fatigue_recovered, self.energy_recovered,
+            player.mental_state.current_energy, player.mental_state.max_energy,
+            player.mental_state.fatigue, player.health.current, player.health.max
+        );
+
+        for event in &self.events {
+            output.push_str(&format!("\n  {}", event));
+        }
+
+        match &self.interruption {
+            Some(Interruption::Scheduled(reason)) | Some(Interruption::Deadline(reason)) => {
+                output.push_str(&format!("\nYour rest is interrupted: {}", reason));
+            }
+            Some(Interruption::Encounter(text)) => {
+                output.push_str(&format!("\nYou wake to danger!\n{}", text));
+            }
+            None if is_recovered(player) => output.push_str("\nYou are fully recovered."),
+            None => output.push_str("\nYou get up before you are fully recovered."),
+        }
+        output
+    }
+}
+
+/// Whether resting has anything left to give
+pub fn is_recovered(player: &Player) -> bool {
+    player.mental_state.fatigue == 0
+        && player.mental_state.current_energy >= player.mental_state.max_energy
+        && player.health.current >= player.health.ceiling()
+}
+
+/// Rest in hour-long steps until recovered or interrupted
+pub fn rest_until_recovered(
+    player: &mut Player,
+    world: &mut WorldState,
+    dialogue_system: &DialogueSystem,
+    quest_system: &QuestSystem,
+    combat_system: &mut CombatSystem,
+    rng: &mut impl Rng,
+) -> RestSummary {
+    let start_fatigue = player.mental_state.fatigue;
+    let start_energy = player.mental_state.current_energy;
+    let energy_per_hour = ((player.mental_state.max_energy as f32 * ENERGY_PER_HOUR) as i32).max(1);
+    let mut summary = RestSummary {
+        hours: 0,
+        fatigue_recovered: 0,
+        energy_recovered: 0,
+        events: Vec::new(),
+        interruption: None,
+    };
+
+    while !is_recovered(player) && summary.hours < MAX_REST_HOURS && summary.interruption.is_none() {
+        let before_time = world.game_time_minutes;
+        let before_period = world.environment.time_of_day.name();
+        let before_disturbances = world.environment.disturbances.len();
+        let before_present = dialogue_system.npcs_available_here(world, quest_system);
+
+        player.recover_energy(energy_per_hour, FATIGUE_PER_HOUR);
+        world.advance_time(STEP_MINUTES);
+        player.playtime_minutes += STEP_MINUTES;
+        summary.hours += 1;
+        let hour = summary.hours;
+
+        for message in player.health.rest(STEP_MINUTES) {
+            summary.events.push(format!("[+{}h] {}", hour, message));
+        }
+
+        for message in combat_system.party_mut().rest() {
+            summary.events.push(format!("[+{}h] {}", hour, message));
+        }
+        if world.environment.time_of_day.name() != before_period {
+            summary.events.push(format!("[+{}h] It is now {}.", hour, world.environment.time_of_day.name()));
+        }
+        if world.environment.disturbances.len() < before_disturbances {
+            summary.events.push(format!("[+{}h] The magical disturbance subsides.", hour));
+        }
+
+        let ending = dialogue_system.cooldowns_ending(before_time, world.game_time_minutes);
+        if let Some((name, activity)) = ending.first() {
+            summary.interruption = Some(Interruption::Deadline(format!("{} has finished {}.", name, activity)));
+            break;
+        }
+
+        let arrivals: Vec<String> = dialogue_system.npcs_available_here(world, quest_system)
+            .into_iter()
+            .filter(|name| !before_present.contains(name))
+            .collect();
+        if !arrivals.is_empty() {
+            summary.interruption = Some(Interruption::Scheduled(format!(
+                "{} {} here and free to talk.",
+                arrivals.join(" and "), if arrivals.len() == 1 { "is" } else { "are" }
+            )));
+            break;
+        }
+
+        if let Some(text) = roll_encounter(world, combat_system, rng) {
+            summary.interruption = Some(Interruption::Encounter(text));
+        }
+    }
+
+    summary.fatigue_recovered = start_fatigue - player.mental_state.fatigue;
+    summary.energy_recovered = player.mental_state.current_energy - start_energy;
+    summary
+}
+
+/// Maybe start a fight with a creature that lives here
+fn roll_encounter(world: &mut WorldState, combat_system: &mut CombatSystem, rng: &mut impl Rng) -> Option<String> {
+    // Bosses guard their lairs; they don't wander into camps
+    let candidates: Vec<_> = combat_system.enemies_at(&world.current_location)
+        .into_iter()
+        .filter(|enemy| enemy.difficulty_tier != DifficultyTier::Boss)
+        .collect();
+    if candidates.is_empty() || rng.gen::<f32>() >= ENCOUNTER_CHANCE_PER_HOUR {
+        return None;
+    }
+
+    let enemy = candidates[rng.gen_range(0..candidates.len())].clone();
+    let mut text = combat_system.start_encounter(enemy).ok()?;
+    let wards = world.wards.take_for_combat(&world.current_location, world.game_time_minutes);
+    let prepared = combat_system.apply_wards(wards);
+    if !prepared.is_empty() {
+        text.push_str(&format!("\n{}", prepared));
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn test_rest_until_recovered() {
+        let mut player = Player::new("Test".to_string());
+        player.mental_state.fatigue = 35;
+        player.mental_state.current_energy = 10;
+        let mut world = WorldState::new();
+        world.current_location = "quiet_cell".to_string();
+        let mut combat = CombatSystem::new();
+
+        let summary = rest_until_recovered(
+            &mut player, &mut world, &DialogueSystem::new(), &QuestSystem::new(), &mut combat, &mut StepRng::new(0, 0),
+        );
+        assert!(is_recovered(&player));
+        assert_eq!(summary.interruption, None);
+        assert_eq!(summary.fatigue_recovered, 35);
+        assert_eq!(world.game_time_minutes, summary.hours as i32 * STEP_MINUTES);
+        assert!(summary.report(&player).contains("fully recovered"));
+    }
+
+    #[test]
+    fn test_rest_interrupted_by_local_creature() {
+        let mut player = Player::new("Test".to_string());
+        player.mental_state.fatigue = 80;
+        let mut world = WorldState::new();
+        let mut combat = CombatSystem::new();
+        world.current_location = "unstable_resonance_site".to_string();
+
+        // A zero roll always beats the encounter chance
+        let summary = rest_until_recovered(
+            &mut player, &mut world, &DialogueSystem::new(), &QuestSystem::new(), &mut combat, &mut StepRng::new(0, 0),
+        );
+        assert_eq!(summary.hours, 1);
+        assert!(matches!(summary.interruption, Some(Interruption::Encounter(_))));
+        assert!(combat.is_in_combat());
+        assert_ne!(combat.current_enemy().unwrap().difficulty_tier, DifficultyTier::Boss);
+    }
+}
@@ -0,0 +1,252 @@
+//! Negotiating the rewards for a finished quest
+//!
+//! Turning in a completed quest opens a short exchange with the quest giver.
+//! Besides accepting the rewards as offered, the player can press for a
+//! different mix: more silver at the cost of standing, more standing instead
+//! of silver, a theory insight, or an item. Which requests the giver will
+//! entertain depends on how they feel about the player and on the player's
+//! understanding of Mental Resonance, which lets them read what the giver
+//! actually values; better understanding also sweetens each deal.
+
+use crate::core::Player;
+use crate::systems::dialogue::NPC;
+use crate::systems::factions::FactionId;
+use crate::systems::items::{Item, ItemEffect, ItemType};
+use crate::systems::quests::{QuestDefinition, QuestDifficulty, RewardTerms};
+use serde::{Deserialize, Serialize};
+
+/// Quest variable recording which reward the player settled on
+pub const CLAIMED_VARIABLE: &str = "rewards_claimed";
+/// Theory that lets the player read what a quest giver values
+const MENTAL_RESONANCE: &str = "mental_resonance";
+/// Extra standing with the giver's faction when asking for standing
+const STANDING_FAVOR: i32 = 5;
+
+/// A reward mix the player can ask for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RewardOption {
+    /// The rewards as listed, with the usual payment
+    Standard,
+    /// More silver, less standing
+    Silver,
+    /// More standing, no silver
+    Standing,
+    /// Understanding of a theory the quest touched on
+    Insight,
+    /// An item from the giver's stores
+    Item,
+}
+
+impl RewardOption {
+    /// Every option, in the order they are offered
+    pub const ALL: [RewardOption; 5] = [
+        RewardOption::Standard,
+        RewardOption::Silver,
+        RewardOption::Standing,
+        RewardOption::Insight,
+        RewardOption::Item,
+    ];
+
+    /// Parse an option name
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "standard" | "accept" | "as offered" => Some(RewardOption::Standard),
+            "silver" | "coin" | "money" | "more silver" => Some(RewardOption::Silver),
+            "standing" | "reputation" | "favor" | "favour" => Some(RewardOption::Standing),
+            "insight" | "theory" | "knowledge" => Some(RewardOption::Insight),
+            "item" | "gift" => Some(RewardOption::Item),
+            _ => None,
+        }
+    }
+
+    /// Option name used in commands
+    pub fn name(&self) -> &'static str {
+        match self {
+            RewardOption::Standard => "standard",
+            RewardOption::Silver => "silver",
+            RewardOption::Standing => "standing",
+            RewardOption::Insight => "insight",
+            RewardOption::Item => "item",
+        }
+    }
+
+    /// Whether the giver will entertain this request
+    pub fn available(&self, disposition: i32, mental_resonance: f32) -> bool {
+        match self {
+            RewardOption::Standard => true,
+            RewardOption::Silver => disposition >= 0 || mental_resonance >= 0.2,
+            RewardOption::Standing => disposition >= 20 || mental_resonance >= 0.5,
+            RewardOption::Insight => mental_resonance >= 0.3,
+            RewardOption::Item => disposition >= 40 || (disposition >= 10 && mental_resonance >= 0.4),
+        }
+    }
+}
+
+/// Usual payment for a quest of a given difficulty
+pub fn base_silver(difficulty: &QuestDifficulty) -> i32 {
+    match difficulty {
+        QuestDifficulty::Beginner => 20,
+        QuestDifficulty::Intermediate => 40,
+        QuestDifficulty::Advanced => 70,
+        QuestDifficulty::Expert => 100,
+        QuestDifficulty::Master => 150,
+    }
+}
+
+/// Tonic handed over when the player asks for an item
+fn reward_item() -> Item {
+    Item::new_basic(
+        "Focus Tonic".to_string(),
+        "A bitter draught that sharpens the mind for a couple of hours.".to_string(),
+        ItemType::Consumable {
+            effect: ItemEffect::TemporaryAttributeBoost {
+                attribute: "mental_acuity".to_string(),
+                amount: 5,
+                duration: 120,
+            },
+            uses_remaining: 1,
+        },
+    )
+}
+
+/// A quest giver's offer, open until the player settles on a reward
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RewardOffer {
+    pub quest_id: String,
+    pub quest_title: String,
+    /// Location where the exchange is taking place
+    pub location: String,
+    /// Name of the quest giver, if someone is present to pay
+    pub giver: Option<String>,
+    pub giver_faction: Option<FactionId>,
+    pub disposition: i32,
+    pub mental_resonance: f32,
+    pub base_silver: i32,
+    /// Theory an insight would deepen
+    pub insight_theory: String,
+    pub options: Vec<RewardOption>,
+}
+
+impl RewardOffer {
+    /// Open the exchange for a completed quest
+    pub fn new(quest: &QuestDefinition, location: &str, giver: Option<&NPC>, disposition: i32, player: &Player) -> Self {
+        let mental_resonance = player.theory_understanding(MENTAL_RESONANCE);
+        let mut bonus_theories: Vec<&String> = quest.rewards.theory_bonuses.keys().collect();
+        bonus_theories.sort();
+        let insight_theory = bonus_theories.first().map(|theory| theory.to_string())
+            .or_else(|| quest.requirements.theory_requirements.first().map(|(theory, _)| theory.clone()))
+            .unwrap_or_else(|| MENTAL_RESONANCE.to_string());
+
+        Self {
+            quest_id: quest.id.clone(),
+            quest_title: quest.title.clone(),
+            location: location.to_string(),
+            giver: giver.map(|npc| npc.name.clone()),
+            giver_faction: giver.and_then(|npc| npc.faction_affiliation),
+            disposition,
+            mental_resonance,
+            base_silver: base_silver(&quest.difficulty),
+            insight_theory,
+            // With no one to haggle with, there is only what was promised
+            options: RewardOption::ALL.into_iter()
+                .filter(|option| giver.is_some() || *option == RewardOption::Standard)
+                .filter(|option| option.available(disposition, mental_resonance))
+                .collect(),
+        }
+    }
+
+    /// Adjustments to the rewards for an option
+    pub fn terms(&self, option: RewardOption) -> RewardTerms {
+        let read = self.mental_resonance;
+        let half = self.base_silver / 2;
+        match option {
+            RewardOption::Standard => RewardTerms { silver: self.base_silver, ..RewardTerms::default() },
+            RewardOption::Silver => RewardTerms {
+                silver: (self.base_silver as f32 * (2.0 + read * 0.5)).round() as i32,
+                standing_multiplier: 0.5,
+                ..RewardTerms::default()
+            },
+            RewardOption::Standing => RewardTerms {
+                silver: 0,
+                standing_multiplier: 1.5 + read * 0.5,
+                extra_standing: self.giver_faction.map(|faction| (faction, STANDING_FAVOR)),
+                ..RewardTerms::default()
+            },
+            RewardOption::Insight => RewardTerms {
+                silver: half,
+                insight: Some((self.insight_theory.clone(), 0.05 + read * 0.1)),
+                ..RewardTerms::default()
+            },
+            RewardOption::Item => RewardTerms { silver: half, item: Some(reward_item()), ..RewardTerms::default() },
+        }
+    }
+
+    /// The exchange as shown to the player
+    pub fn render(&self) -> String {
+        let mut output = match &self.giver {
+            Some(giver) => format!(
+                "{} looks over your account of \"{}\". \"Well done. Let's settle what you're owed.\"\n\n",
+                giver, self.quest_title
+            ),
+            None => format!("With \"{}\" behind you, you tally what you're owed.\n\n", self.quest_title),
+        };
+
+        output.push_str("You could ask for:\n");
+        for option in &self.options {
+            let terms = self.terms(*option);
+            let detail = match option {
+                RewardOption::Standard => format!("the rewards as offered, plus {} silver", terms.silver),
+                RewardOption::Silver => format!("{} silver, but only half the standing", terms.silver),
+                RewardOption::Standing => format!("{:.0}% standing gains and no silver", terms.standing_multiplier * 100.0),
+                RewardOption::Insight => format!(
+                    "{} silver and +{:.0}% understanding in {}",
+                    terms.silver, terms.insight.as_ref().map_or(0.0, |(_, amount)| amount * 100.0), self.insight_theory
+                ),
+                RewardOption::Item => format!("{} silver and a Focus Tonic", terms.silver),
+            };
+            output.push_str(&format!("  • {} - {}\n", option.name(), detail));
+        }
+
+        if self.options.len() < RewardOption::ALL.len() {
+            output.push_str(if self.mental_resonance < 0.3 {
+                "\nA better grasp of Mental Resonance might show you what else they'd part with.\n"
+            } else {
+                "\nThey don't warm to you enough to offer more.\n"
+            });
+        }
+        output.push_str("Choose with 'reward <option>'.");
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_depend_on_disposition_and_mental_resonance() {
+        let quest = crate::systems::quest_examples::create_example_quests().remove(0);
+        let giver = crate::systems::quest_examples::create_quest_npcs().remove(0);
+        let mut player = Player::new("Ada".to_string());
+
+        let cold = RewardOffer::new(&quest, "practice_hall", Some(&giver), -30, &player);
+        assert_eq!(cold.options, vec![RewardOption::Standard]);
+        assert!(cold.render().contains("Mental Resonance"));
+
+        player.knowledge.theories.insert(MENTAL_RESONANCE.to_string(), 0.6);
+        let warm = RewardOffer::new(&quest, "practice_hall", Some(&giver), 45, &player);
+        assert_eq!(warm.options.len(), RewardOption::ALL.len());
+        let alone = RewardOffer::new(&quest, "practice_hall", None, 45, &player);
+        assert_eq!(alone.options, vec![RewardOption::Standard]);
+
+        let standard = warm.terms(RewardOption::Standard);
+        let silver = warm.terms(RewardOption::Silver);
+        assert!(silver.silver > standard.silver * 2);
+        assert_eq!(silver.standing_multiplier, 0.5);
+        assert_eq!(warm.terms(RewardOption::Standing).silver, 0);
+        let (theory, insight) = warm.terms(RewardOption::Insight).insight.unwrap();
+        assert_eq!(theory, warm.insight_theory);
+        assert!((insight - 0.11).abs() < 1e-6);
+        assert!(warm.terms(RewardOption::Item).item.is_some());
+    }
+}
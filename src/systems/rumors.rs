@@ -0,0 +1,171 @@
+//! Rumors about the player
+//!
+//! Word of what the player does gets around. Notable deeds, such as quests
+//! seen through or let fail, enemies defeated or spared, and big swings in a
+//! faction's regard, start a rumor where they happen. The rumor travels one
+//! exit further every few hours, and each retelling drifts further from the
+//! truth. Anyone at a location the rumor has reached brings it up the next
+//! time the player talks to them, once each, so people across the city
+//! comment on things the player did elsewhere, late and a little wrong.
+//!
+//! Rumors are kept with the world, so they are saved with the game.
+
+use crate::core::world_state::Location;
+use crate::systems::factions::FactionId;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Minutes a rumor takes to travel one exit further
+pub const SPREAD_MINUTES: i32 = 180;
+/// Minutes after which a rumor is forgotten
+pub const RUMOR_LIFETIME: i32 = 7 * 1440;
+/// Change in faction standing from one command that gets talked about
+pub const NOTABLE_STANDING_CHANGE: i32 = 15;
+/// Most rumors going around at once; the oldest are forgotten first
+const RUMOR_LIMIT: usize = 20;
+
+/// Something the player did that people talk about
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Deed {
+    QuestCompleted(String),
+    QuestFailed(String),
+    EnemyDefeated(String),
+    EnemySpared(String),
+    FactionFavoured(FactionId),
+    FactionCrossed(FactionId),
+}
+
+impl Deed {
+    /// How the deed is told by someone the given number of exits from where
+    /// it happened: accurate nearby, embellished further out, garbled beyond
+    pub fn telling(&self, hops: usize) -> String {
+        match (self, hops) {
+            (Deed::QuestCompleted(title), 0) => format!("They say you saw \"{}\" through.", title),
+            (Deed::QuestCompleted(title), 1) => format!("I heard you finished \"{}\" single-handed, in half the time anyone expected.", title),
+            (Deed::QuestCompleted(title), _) => format!("Someone told me you saved the whole city over something called \"{}\". Is that true?", title),
+            (Deed::QuestFailed(title), 0) => format!("Word is \"{}\" fell apart on you.", title),
+            (Deed::QuestFailed(title), 1) => format!("I heard \"{}\" went badly wrong, and that it was your fault.", title),
+            (Deed::QuestFailed(title), _) => format!("People are saying you wrecked \"{}\" on purpose. I don't believe half of it.", title),
+            (Deed::EnemyDefeated(name), 0) => format!("Word is you put down a {}.", name),
+            (Deed::EnemyDefeated(name), 1) => format!("I heard you took down a {} with a single spell.", name),
+            (Deed::EnemyDefeated(name), _) => format!("They're saying you killed three of those {} things bare-handed.", name),
+            (Deed::EnemySpared(name), 0) => format!("I heard you let a {} go.", name),
+            (Deed::EnemySpared(name), 1) => format!("Someone said you talked a {} out of a fight.", name),
+            (Deed::EnemySpared(name), _) => format!("They say wild things go tame around you now. Something about a {}.", name),
+            (Deed::FactionFavoured(faction), 0) => format!("Word is the {} owe you a favour.", faction.display_name()),
+            (Deed::FactionFavoured(faction), 1) => format!("I heard the {} can't stop singing your praises.", faction.display_name()),
+            (Deed::FactionFavoured(faction), _) => format!("People say you're practically running the {} these days.", faction.display_name()),
+            (Deed::FactionCrossed(faction), 0) => format!("I heard you crossed the {}.", faction.display_name()),
+            (Deed::FactionCrossed(faction), 1) => format!("Word is the {} want you gone.", faction.display_name()),
+            (Deed::FactionCrossed(faction), _) => format!("Someone told me the {} have put a price on your head.", faction.display_name()),
+        }
+    }
+}
+
+/// A deed being talked about
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rumor {
+    pub deed: Deed,
+    /// Location where it happened
+    pub origin: String,
+    /// Game time it happened, in minutes
+    pub started_at: i32,
+    /// NPCs who have already brought it up with the player
+    pub told_by: HashSet<String>,
+}
+
+impl Rumor {
+    /// Whether the rumor has travelled the given number of exits by now
+    fn arrived(&self, hops: usize, now: i32) -> bool {
+        now >= self.started_at + hops as i32 * SPREAD_MINUTES
+    }
+}
+
+/// Rumors going around about the player, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Rumors {
+    rumors: Vec<Rumor>,
+}
+
+impl Rumors {
+    /// Start a rumor about a deed done at a location, forgetting stale ones
+    pub fn start(&mut self, deed: Deed, origin: &str, now: i32) {
+        self.rumors.retain(|rumor| now - rumor.started_at < RUMOR_LIFETIME);
+        if self.rumors.len() >= RUMOR_LIMIT {
+            self.rumors.remove(0);
+        }
+        self.rumors.push(Rumor { deed, origin: origin.to_string(), started_at: now, told_by: HashSet::new() });
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Rumor> {
+        self.rumors.iter()
+    }
+
+    /// The freshest rumor that has reached an NPC at a location and that they
+    /// haven't brought up yet, told as they heard it; they won't bring it up again
+    pub fn hear(&mut self, npc_id: &str, location_id: &str, locations: &HashMap<String, Location>, now: i32) -> Option<String> {
+        let (rumor, hops) = self.rumors.iter_mut().rev()
+            .filter(|rumor| now - rumor.started_at < RUMOR_LIFETIME && !rumor.told_by.contains(npc_id))
+            .find_map(|rumor| {
+                let hops = *exits_from(&rumor.origin, locations).get(location_id)?;
+                rumor.arrived(hops, now).then_some((rumor, hops))
+            })?;
+        rumor.told_by.insert(npc_id.to_string());
+        Some(rumor.deed.telling(hops))
+    }
+}
+
+/// How many exits each reachable location is from the given one
+fn exits_from<'a>(location_id: &'a str, locations: &'a HashMap<String, Location>) -> HashMap<&'a str, usize> {
+    let mut distances = HashMap::from([(location_id, 0)]);
+    let mut queue = VecDeque::from([location_id]);
+    while let Some(current) = queue.pop_front() {
+        let hops = distances[current];
+        for next in locations.get(current).into_iter().flat_map(|location| location.exits.values()) {
+            if !distances.contains_key(next.as_str()) {
+                distances.insert(next.as_str(), hops + 1);
+                queue.push_back(next.as_str());
+            }
+        }
+    }
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::Direction;
+
+    fn chain() -> HashMap<String, Location> {
+        let mut locations = HashMap::new();
+        for (id, next) in [("market", Some("archives")), ("archives", Some("garden")), ("garden", None)] {
+            let mut location = Location::new(id.to_string(), id.to_string(), String::new());
+            if let Some(next) = next {
+                location.exits.insert(Direction::East, next.to_string());
+            }
+            locations.insert(id.to_string(), location);
+        }
+        locations
+    }
+
+    #[test]
+    fn test_rumors_spread_late_and_distorted_and_are_told_once() {
+        let locations = chain();
+        let mut rumors = Rumors::default();
+        rumors.start(Deed::EnemyDefeated("Crystal Wraith".to_string()), "market", 0);
+
+        // Witnesses know at once; the garden, two exits away, hears later and wrongly
+        assert_eq!(rumors.hear("vendor", "market", &locations, 0).as_deref(), Some("Word is you put down a Crystal Wraith."));
+        assert!(rumors.hear("vendor", "market", &locations, 0).is_none());
+        assert!(rumors.hear("gardener", "garden", &locations, SPREAD_MINUTES).is_none());
+        let garbled = rumors.hear("gardener", "garden", &locations, 2 * SPREAD_MINUTES).unwrap();
+        assert!(garbled.contains("three of those Crystal Wraith things"));
+        assert!(rumors.hear("scholar", "archives", &locations, SPREAD_MINUTES).unwrap().contains("single spell"));
+
+        // Nothing flows back up a one-way exit, and old news is forgotten
+        rumors.start(Deed::QuestFailed("Garden Survey".to_string()), "garden", 0);
+        assert!(rumors.hear("clerk", "market", &locations, 10 * SPREAD_MINUTES).unwrap().contains("Crystal Wraith"));
+        rumors.start(Deed::FactionFavoured(FactionId::NeutralScholars), "market", RUMOR_LIFETIME);
+        assert_eq!(rumors.iter().count(), 1);
+    }
+}
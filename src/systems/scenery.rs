@@ -0,0 +1,242 @@
+//! Scenery the player can interact with
+//!
+//! Besides items and NPCs, each location has fixed objects the player can
+//! examine, search or touch: a desk, a heap of rubble, a crystal formation.
+//! Every object answers each kind of interaction in its own words. Some
+//! interactions ask for a skill check against one of the player's attributes
+//! before they give anything up, and a few yield something the first time:
+//! - An item, left where the player can take it
+//! - Lore, copied into the player's notes
+//! - A little silver
+//!
+//! Scenery is defined per location in the database; which yields the player
+//! has already had is kept in the world state.
+
+use crate::core::world_state::WorldState;
+use crate::core::Player;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Minutes spent searching through something
+pub const SEARCH_MINUTES: i32 = 5;
+
+/// Ways of interacting with scenery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SceneryAction {
+    Examine,
+    Search,
+    Touch,
+}
+
+impl SceneryAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SceneryAction::Examine => "examine",
+            SceneryAction::Search => "search",
+            SceneryAction::Touch => "touch",
+        }
+    }
+}
+
+/// Attribute a skill check is made against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheckAttribute {
+    MentalAcuity,
+    ResonanceSensitivity,
+}
+
+/// A check the player must pass to get anything from an interaction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillCheck {
+    pub attribute: CheckAttribute,
+    /// 1-5, as for archive research
+    pub difficulty: i32,
+    /// What the player gets instead on a failed check
+    pub failure: String,
+}
+
+impl SkillCheck {
+    /// Roll d100 plus half the attribute against the difficulty
+    pub fn roll(&self, player: &Player, rng: &mut impl Rng) -> bool {
+        let attribute = match self.attribute {
+            CheckAttribute::MentalAcuity => player.attributes.mental_acuity,
+            CheckAttribute::ResonanceSensitivity => player.attributes.resonance_sensitivity,
+        };
+        rng.gen_range(1..=100) + attribute / 2 >= 40 + self.difficulty * 10
+    }
+}
+
+/// Something an interaction gives up the first time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SceneryYield {
+    /// An item, left at the location
+    Item { item_id: String },
+    /// A note for the player's inventory
+    Lore { title: String, text: String },
+    /// Silver
+    Silver { amount: i32 },
+}
+
+/// How an object answers one kind of interaction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub action: SceneryAction,
+    pub text: String,
+    #[serde(default)]
+    pub check: Option<SkillCheck>,
+    #[serde(default)]
+    pub yields: Option<SceneryYield>,
+}
+
+/// A fixed object at a location
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SceneryObject {
+    pub id: String,
+    pub location_id: String,
+    pub name: String,
+    /// Other words the player might use for it
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    pub interactions: Vec<Interaction>,
+}
+
+impl SceneryObject {
+    /// Whether the player's words pick out this object
+    pub fn matches(&self, target: &str) -> bool {
+        let target = target.trim().trim_start_matches("the ").to_lowercase();
+        !target.is_empty()
+            && std::iter::once(&self.name).chain(&self.aliases)
+                .any(|name| name.to_lowercase() == target || target.contains(&name.to_lowercase()))
+    }
+}
+
+/// Find the object the player means among those here
+pub fn find<'a>(objects: &'a [SceneryObject], target: &str) -> Option<&'a SceneryObject> {
+    objects.iter().find(|object| object.matches(target))
+}
+
+/// Interact with an object
+pub fn interact(
+    object: &SceneryObject,
+    action: SceneryAction,
+    player: &mut Player,
+    world: &mut WorldState,
+    rng: &mut impl Rng,
+) -> String {
+    if action == SceneryAction::Search {
+        world.advance_time(SEARCH_MINUTES);
+        player.playtime_minutes += SEARCH_MINUTES;
+    }
+
+    let Some(interaction) = object.interactions.iter().find(|interaction| interaction.action == action) else {
+        return match action {
+            SceneryAction::Examine => format!("You look the {} over. Nothing about it stands out.", object.name),
+            SceneryAction::Search => format!("You search the {}, but find nothing of note.", object.name),
+            SceneryAction::Touch => format!("You touch the {}. It feels just as it looks.", object.name),
+        };
+    };
+
+    if let Some(check) = &interaction.check {
+        if !check.roll(player, rng) {
+            return check.failure.clone();
+        }
+    }
+
+    let mut response = interaction.text.clone();
+    let key = format!("{}:{}", object.id, action.name());
+    if let Some(found) = interaction.yields.as_ref().filter(|_| !world.scenery_used.contains(&key)) {
+        world.scenery_used.insert(key);
+        response.push_str("\n\n");
+        response.push_str(&match found {
+            SceneryYield::Item { item_id } => {
+                if let Some(location) = world.locations.get_mut(&object.location_id) {
+                    location.items.push(item_id.clone());
+                }
+                format!("You turn up a {}, and set it down where you can take it.", crate::systems::placed_items::display_name(item_id))
+            }
+            SceneryYield::Lore { title, text } => {
+                use crate::core::player::{Item, ItemType};
+                if !player.inventory.items.iter().any(|item| &item.name == title) {
+                    player.inventory.items.push(Item {
+                        name: title.clone(),
+                        description: text.clone(),
+                        item_type: ItemType::Note(text.clone()),
+                    });
+                }
+                format!("You copy what you've learned into your notes as '{}'.", title)
+            }
+            SceneryYield::Silver { amount } => {
+                player.inventory.silver += amount;
+                format!("You find {} silver.", amount)
+            }
+        });
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::Location;
+    use rand::rngs::mock::StepRng;
+
+    fn rubble() -> SceneryObject {
+        SceneryObject {
+            id: "hall_rubble".to_string(),
+            location_id: "hall".to_string(),
+            name: "rubble".to_string(),
+            aliases: vec!["debris".to_string()],
+            interactions: vec![
+                Interaction {
+                    action: SceneryAction::Examine,
+                    text: "Broken stone from the old wall.".to_string(),
+                    check: None,
+                    yields: None,
+                },
+                Interaction {
+                    action: SceneryAction::Search,
+                    text: "Under the stones, something glints.".to_string(),
+                    check: Some(SkillCheck {
+                        attribute: CheckAttribute::MentalAcuity,
+                        difficulty: 5,
+                        failure: "You shift a few stones and find only dust.".to_string(),
+                    }),
+                    yields: Some(SceneryYield::Item { item_id: "crystal_fragment".to_string() }),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_scenery_answers_each_interaction() {
+        let objects = vec![rubble()];
+        assert!(find(&objects, "the debris").is_some());
+        assert!(find(&objects, "desk").is_none());
+
+        let mut player = Player::new("Tester".to_string());
+        let mut world = WorldState::new();
+        let object = &objects[0];
+        let mut rng = StepRng::new(0, 0);
+        assert_eq!(interact(object, SceneryAction::Examine, &mut player, &mut world, &mut rng), "Broken stone from the old wall.");
+        assert!(interact(object, SceneryAction::Touch, &mut player, &mut world, &mut rng).contains("just as it looks"));
+    }
+
+    #[test]
+    fn test_checks_gate_yields_given_only_once() {
+        let object = rubble();
+        let mut player = Player::new("Tester".to_string());
+        let mut world = WorldState::new();
+        world.add_location(Location::new("hall".to_string(), "Hall".to_string(), "A hall.".to_string()));
+
+        let start = world.game_time_minutes;
+        let failed = interact(&object, SceneryAction::Search, &mut player, &mut world, &mut StepRng::new(0, 0));
+        assert!(failed.contains("only dust"));
+        assert_eq!(world.game_time_minutes, start + SEARCH_MINUTES);
+
+        // A roll of 94 (an all-ones rng is rejected by range sampling forever)
+        let mut lucky = StepRng::new(0xF000_0000, 0);
+        assert!(interact(&object, SceneryAction::Search, &mut player, &mut world, &mut lucky).contains("crystal fragment"));
+        assert!(!interact(&object, SceneryAction::Search, &mut player, &mut world, &mut lucky).contains("crystal fragment"));
+        assert_eq!(world.locations["hall"].items, vec!["crystal_fragment".to_string()]);
+    }
+}
@@ -0,0 +1,156 @@
+//! Hidden exits and how they are found
+//!
+//! Some ways out of a location aren't there until the player finds them: a
+//! grate under the garden beds, a stair behind a shelf. Hidden exits are kept
+//! apart from a location's ordinary exits, so movement, the map and the exit
+//! listing know nothing of them until they're discovered. They can be found:
+//! - By examining the feature that hides them
+//! - With a detection spell cast where they are
+//! - From a hint by someone who knows about them
+//! - By reading a document that tells of them
+//!
+//! A discovery is a lasting change to the location, kept as an override like
+//! any other, so the exit stays found for the rest of the game.
+
+use crate::core::world_state::{Direction, WorldState};
+use crate::systems::location_changes::LocationChange;
+use serde::{Deserialize, Serialize};
+
+/// An exit nobody has found yet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HiddenExit {
+    /// Where the exit leads
+    pub to: String,
+    /// What hides it, found by examining it
+    pub feature: String,
+    /// What the player learns on finding it
+    pub clue: String,
+    /// NPCs who can tell the player about it
+    #[serde(default)]
+    pub known_to: Vec<String>,
+    /// Archive texts that tell of it
+    #[serde(default)]
+    pub documents: Vec<String>,
+}
+
+/// Turn a hidden exit into an ordinary one for good
+fn reveal(world: &mut WorldState, location_id: &str, direction: &Direction) -> Option<String> {
+    let hidden = world.locations.get(location_id)?.hidden_exits.get(direction)?.clone();
+    world.change_location(location_id, LocationChange::Reveal { direction: direction.clone() }).ok()?;
+    Some(hidden.clue)
+}
+
+/// Reveal every hidden exit anywhere that matches, with the location it's in
+fn reveal_where(world: &mut WorldState, matches: impl Fn(&HiddenExit) -> bool) -> Vec<(String, String)> {
+    let mut found: Vec<(String, Direction)> = world.locations.values()
+        .flat_map(|location| {
+            location.hidden_exits.iter()
+                .filter(|(_, hidden)| matches(hidden))
+                .map(|(direction, _)| (location.id.clone(), direction.clone()))
+        })
+        .collect();
+    found.sort_by(|a, b| (&a.0, a.1.display_name()).cmp(&(&b.0, b.1.display_name())));
+
+    found.into_iter()
+        .filter_map(|(location_id, direction)| {
+            let clue = reveal(world, &location_id, &direction)?;
+            let name = world.locations.get(&location_id).map_or_else(|| location_id.replace('_', " "), |location| location.name.clone());
+            Some((name, clue))
+        })
+        .collect()
+}
+
+/// Look closely at something here, and perhaps find what it hides
+pub fn examine(world: &mut WorldState, target: &str) -> Option<String> {
+    let target = target.trim_start_matches("the ").to_lowercase();
+    if target.is_empty() {
+        return None;
+    }
+    let direction = world.current_location()?.hidden_exits.iter()
+        .find(|(_, hidden)| target.contains(&hidden.feature) || hidden.feature.contains(&target))
+        .map(|(direction, _)| direction.clone())?;
+    let here = world.current_location.clone();
+    let clue = reveal(world, &here, &direction)?;
+    Some(format!("{} You've found a hidden way {}.", clue, direction.display_name()))
+}
+
+/// A detection spell shows up every hidden exit here
+pub fn detect(world: &mut WorldState) -> Vec<String> {
+    let here = world.current_location.clone();
+    let directions: Vec<Direction> = world.current_location()
+        .map(|location| location.hidden_exits.keys().cloned().collect())
+        .unwrap_or_default();
+
+    directions.into_iter()
+        .filter_map(|direction| {
+            let clue = reveal(world, &here, &direction)?;
+            Some(format!("Your detection traces a draught of resonance {}: {}", direction.display_name(), clue))
+        })
+        .collect()
+}
+
+/// What an NPC lets slip about hidden ways they know of
+pub fn hints(world: &mut WorldState, npc_id: &str, npc_name: &str) -> Vec<String> {
+    reveal_where(world, |hidden| hidden.known_to.iter().any(|id| id == npc_id))
+        .into_iter()
+        .map(|(location, clue)| format!("{} lowers their voice: there's more to the {} than most people know. {}", npc_name, location, clue))
+        .collect()
+}
+
+/// What a document tells of hidden ways
+pub fn read(world: &mut WorldState, document_id: &str) -> Vec<String> {
+    reveal_where(world, |hidden| hidden.documents.iter().any(|id| id == document_id))
+        .into_iter()
+        .map(|(location, clue)| format!("The text tells of a hidden way in the {}. {}", location, clue))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::Location;
+
+    fn garden_world() -> WorldState {
+        let mut world = WorldState::new();
+        let mut garden = Location::new("garden".to_string(), "Garden".to_string(), "A garden with a grate.".to_string());
+        garden.hidden_exits.insert(Direction::Down, HiddenExit {
+            to: "cellar".to_string(),
+            feature: "grate".to_string(),
+            clue: "The grate lifts away.".to_string(),
+            known_to: vec!["healer".to_string()],
+            documents: vec!["plans".to_string()],
+        });
+        world.add_location(garden);
+        world.add_location(Location::new("cellar".to_string(), "Cellar".to_string(), "A cellar.".to_string()));
+        world.current_location = "garden".to_string();
+        world
+    }
+
+    #[test]
+    fn test_hidden_exits_stay_out_of_the_way_until_examined() {
+        let mut world = garden_world();
+        assert!(world.move_to_location(Direction::Down).is_err());
+        assert!(examine(&mut world, "the beds").is_none());
+        assert!(examine(&mut world, "the drainage grate").unwrap().contains("hidden way down"));
+        assert!(world.locations["garden"].hidden_exits.is_empty());
+        assert_eq!(world.location_changes.changes("garden"), &[LocationChange::Reveal { direction: Direction::Down }]);
+        assert!(world.move_to_location(Direction::Down).is_ok());
+        assert!(detect(&mut world).is_empty());
+    }
+
+    #[test]
+    fn test_spells_people_and_documents_reveal_hidden_exits() {
+        let mut world = garden_world();
+        assert_eq!(detect(&mut world).len(), 1);
+        assert!(world.locations["garden"].exits.contains_key(&Direction::Down));
+
+        let mut world = garden_world();
+        world.current_location = "cellar".to_string();
+        assert!(hints(&mut world, "guard", "The guard").is_empty());
+        assert!(hints(&mut world, "healer", "The healer")[0].contains("more to the Garden"));
+        assert!(read(&mut world, "plans").is_empty());
+
+        let mut world = garden_world();
+        assert_eq!(read(&mut world, "plans").len(), 1);
+    }
+}
@@ -0,0 +1,371 @@
+//! Paid services offered by NPCs: healing, training, repair and appraisal
+//!
+//! A service's price starts from the provider's base rate and is shaped by:
+//! - Faction standing, through the same price modifier used for trade
+//! - Demand, which rises when the provider is busy and drifts back down over
+//!   time; every injury the player suffers marks a dangerous time, and
+//!   healers everywhere charge more for a while after
+//! - Bundles: members of the provider's faction get a discount on each
+//!   further service bought from them within the hour
+//! - Haggling, which can win a discount on the next service or, if it goes
+//!   badly, sour the provider into charging more
+//! - Market day, when every provider knocks a share off their prices
+//!
+//! Each provider's demand and haggling are kept in their own ledger, saved
+//! with the world.
+
+use crate::core::events::{Event, EventListener, EventType, WorldEffect, MARKET_DAY_DISCOUNT};
+use crate::core::player::AttributeType;
+use crate::core::Player;
+use crate::systems::factions::{FactionId, FactionSystem};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Minutes for demand above normal to halve
+const DEMAND_HALF_LIFE_MINUTES: f32 = 720.0;
+/// Demand added by each purchase from a provider
+const PURCHASE_DEMAND: f32 = 0.05;
+/// Demand added at every healer by each new injury
+const INJURY_DEMAND: f32 = 0.2;
+/// Highest demand above normal; prices at most double from demand
+const MAX_DEMAND: f32 = 1.0;
+/// Standing with the provider's faction that counts as membership
+pub const MEMBER_STANDING: i32 = 50;
+/// Minutes after a purchase in which a member's next service is bundled
+const BUNDLE_WINDOW_MINUTES: i32 = 60;
+/// Discount on bundled services
+const BUNDLE_DISCOUNT: f32 = 0.15;
+/// Discount won by successful haggling
+const HAGGLE_DISCOUNT: f32 = 0.2;
+/// Markup after haggling goes badly
+const HAGGLE_MARKUP: f32 = 0.1;
+/// Minutes before a provider will haggle again
+const HAGGLE_COOLDOWN_MINUTES: i32 = 1440;
+/// Minutes a training session takes
+pub const TRAINING_MINUTES: i32 = 60;
+/// Attribute experience gained from a training session
+const TRAINING_EXPERIENCE: i32 = 60;
+/// Minutes a healer spends on the player
+pub const HEALING_MINUTES: i32 = 30;
+
+/// Kinds of paid service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ServiceKind {
+    Healing,
+    Training,
+    Repair,
+    Appraisal,
+}
+
+impl ServiceKind {
+    /// Parse a service name
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "healing" | "heal" | "treatment" => Some(ServiceKind::Healing),
+            "training" | "train" | "lesson" | "lessons" => Some(ServiceKind::Training),
+            "repair" | "repairs" => Some(ServiceKind::Repair),
+            "appraisal" | "appraise" | "identification" => Some(ServiceKind::Appraisal),
+            _ => None,
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            ServiceKind::Healing => "healing",
+            ServiceKind::Training => "training",
+            ServiceKind::Repair => "repair",
+            ServiceKind::Appraisal => "appraisal",
+        }
+    }
+}
+
+/// A service an NPC provides
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Service {
+    pub kind: ServiceKind,
+    /// Flat fee before any per-unit charge
+    pub base_fee: i32,
+    /// Charge per point of health or durability restored, or per silver of an appraised item's worth
+    pub per_point: f32,
+    /// Attribute a trainer teaches
+    pub trains: Option<AttributeType>,
+}
+
+/// Services an NPC offers
+pub fn services_offered(npc_id: &str) -> Vec<Service> {
+    let service = |kind, base_fee, per_point| Service { kind, base_fee, per_point, trains: None };
+    let training = |base_fee, attribute| Service { kind: ServiceKind::Training, base_fee, per_point: 0.0, trains: Some(attribute) };
+    match npc_id {
+        "healer_seraphina" => vec![service(ServiceKind::Healing, 10, 0.5)],
+        "dr_felix" => vec![service(ServiceKind::Healing, 15, 0.4)],
+        "sage_meridian" => vec![training(40, AttributeType::MentalAcuity)],
+        "tutorial_assistant" => vec![training(25, AttributeType::ResonanceSensitivity)],
+        "observer_lyra" => vec![service(ServiceKind::Appraisal, 20, 0.1)],
+        "echo_voidwalker" => vec![service(ServiceKind::Appraisal, 12, 0.15)],
+        _ => crate::systems::items::durability::repair_service(npc_id)
+            .map(|repair| service(ServiceKind::Repair, 0, repair.silver_per_point))
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// A provider's service of one kind, if they offer it
+pub fn service_offered(npc_id: &str, kind: ServiceKind) -> Option<Service> {
+    services_offered(npc_id).into_iter().find(|service| service.kind == kind)
+}
+
+impl Service {
+    /// Price before market adjustments for a number of points restored
+    pub fn list_price(&self, points: i32) -> i32 {
+        self.base_fee + (points.max(0) as f32 * self.per_point).ceil() as i32
+    }
+}
+
+/// What a provider last made of the player's haggling
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Haggle {
+    /// Price adjustment on the next service; negative for a discount
+    pub adjustment: f32,
+    pub attempted_at: i32,
+    /// Whether the adjustment has been spent on a purchase
+    pub spent: bool,
+}
+
+/// One provider's market state
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProviderLedger {
+    /// Demand above normal, as a fraction of the price
+    pub demand: f32,
+    /// Game time the demand was last brought up to date
+    pub updated_at: i32,
+    pub last_purchase_at: Option<i32>,
+    pub haggle: Option<Haggle>,
+}
+
+impl ProviderLedger {
+    /// Let demand drift back toward normal up to now
+    fn settle(&mut self, now: i32) {
+        let elapsed = (now - self.updated_at).max(0) as f32;
+        self.demand *= 0.5f32.powf(elapsed / DEMAND_HALF_LIFE_MINUTES);
+        self.updated_at = now;
+    }
+
+    fn add_demand(&mut self, amount: f32, now: i32) {
+        self.settle(now);
+        self.demand = (self.demand + amount).min(MAX_DEMAND);
+    }
+}
+
+/// A price broken down for the player
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    pub price: i32,
+    /// Reasons the price differs from the list price
+    pub notes: Vec<String>,
+}
+
+/// Every provider's ledger, saved with the world
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServiceMarket {
+    pub providers: HashMap<String, ProviderLedger>,
+    /// Whether it's market day
+    #[serde(default)]
+    pub market_day: bool,
+}
+
+impl ServiceMarket {
+    /// Price a service from a provider
+    pub fn quote(
+        &self,
+        npc_id: &str,
+        faction: Option<FactionId>,
+        list_price: i32,
+        faction_system: &FactionSystem,
+        now: i32,
+    ) -> Quote {
+        let mut ledger = self.providers.get(npc_id).cloned().unwrap_or_default();
+        ledger.settle(now);
+        let mut notes = Vec::new();
+        let mut multiplier = 1.0;
+
+        if let Some(faction) = faction {
+            let modifier = faction_system.get_price_modifier(faction);
+            if modifier != 1.0 {
+                notes.push(format!("{:+.0}% {} standing", (modifier - 1.0) * 100.0, faction.display_name()));
+            }
+            multiplier *= modifier;
+
+            let bundled = ledger.last_purchase_at.is_some_and(|at| now - at <= BUNDLE_WINDOW_MINUTES);
+            if bundled && faction_system.get_reputation(faction) >= MEMBER_STANDING {
+                notes.push(format!("-{:.0}% member bundle", BUNDLE_DISCOUNT * 100.0));
+                multiplier *= 1.0 - BUNDLE_DISCOUNT;
+            }
+        }
+
+        if ledger.demand >= 0.05 {
+            notes.push(format!("+{:.0}% demand", ledger.demand * 100.0));
+        }
+        multiplier *= 1.0 + ledger.demand;
+
+        if let Some(haggle) = ledger.haggle.filter(|haggle| !haggle.spent) {
+            notes.push(if haggle.adjustment < 0.0 {
+                format!("{:.0}% haggled", haggle.adjustment * 100.0)
+            } else {
+                format!("+{:.0}% soured by haggling", haggle.adjustment * 100.0)
+            });
+            multiplier *= 1.0 + haggle.adjustment;
+        }
+
+        if self.market_day {
+            notes.push(format!("-{:.0}% market day", MARKET_DAY_DISCOUNT * 100.0));
+            multiplier *= 1.0 - MARKET_DAY_DISCOUNT;
+        }
+
+        let price = if list_price > 0 { ((list_price as f32 * multiplier).round() as i32).max(1) } else { 0 };
+        Quote { price, notes }
+    }
+
+    /// Record a purchase, spending any haggled adjustment and raising demand
+    pub fn record_purchase(&mut self, npc_id: &str, now: i32) {
+        let ledger = self.providers.entry(npc_id.to_string()).or_default();
+        ledger.add_demand(PURCHASE_DEMAND, now);
+        ledger.last_purchase_at = Some(now);
+        if let Some(haggle) = &mut ledger.haggle {
+            haggle.spent = true;
+        }
+    }
+
+    /// A time of crisis: every provider of a kind becomes busier
+    pub fn crisis(&mut self, kind: ServiceKind, severity: u32, providers: &[&str], now: i32) {
+        for npc_id in providers {
+            if service_offered(npc_id, kind).is_some() {
+                self.providers.entry(npc_id.to_string()).or_default()
+                    .add_demand(INJURY_DEMAND * severity as f32, now);
+            }
+        }
+    }
+
+    /// Try to talk a provider down, returning whether it worked
+    pub fn haggle(&mut self, npc_id: &str, chance: f32, now: i32, rng: &mut impl Rng) -> Result<bool, String> {
+        let ledger = self.providers.entry(npc_id.to_string()).or_default();
+        if let Some(haggle) = ledger.haggle {
+            if now - haggle.attempted_at < HAGGLE_COOLDOWN_MINUTES {
+                return Err(if haggle.spent {
+                    "They've already bargained with you today.".to_string()
+                } else {
+                    "You've already struck a bargain with them; use it first.".to_string()
+                });
+            }
+        }
+
+        let success = rng.gen::<f32>() < chance;
+        ledger.haggle = Some(Haggle {
+            adjustment: if success { -HAGGLE_DISCOUNT } else { HAGGLE_MARKUP },
+            attempted_at: now,
+            spent: false,
+        });
+        Ok(success)
+    }
+}
+
+/// Providers lower their prices while market day lasts
+impl EventListener for ServiceMarket {
+    fn handle_event(&mut self, event: &Event) -> Vec<String> {
+        if event.definition().is_some_and(|def| def.effect == WorldEffect::MarketDay) {
+            self.market_day = event.event_type == EventType::Started;
+        }
+        Vec::new()
+    }
+}
+
+/// Chance of talking a provider down
+pub fn haggle_chance(player: &Player, disposition: i32) -> f32 {
+    (0.3 + disposition as f32 / 200.0 + (player.attributes.mental_acuity - 25) as f32 / 200.0).clamp(0.05, 0.9)
+}
+
+/// Every provider of any service, for spreading a crisis
+pub const PROVIDERS: [&str; 8] = [
+    "healer_seraphina",
+    "dr_felix",
+    "sage_meridian",
+    "tutorial_assistant",
+    "technician_marcus",
+    "warden_gareth",
+    "observer_lyra",
+    "echo_voidwalker",
+];
+
+/// Train an attribute, returning a description of the session
+pub fn train(player: &mut Player, attribute: AttributeType) -> String {
+    let name = match attribute {
+        AttributeType::MentalAcuity => "Mental Acuity",
+        AttributeType::ResonanceSensitivity => "Resonance Sensitivity",
+    };
+    player.add_experience(attribute, TRAINING_EXPERIENCE);
+    format!("An hour of drills sharpens your {} (+{} experience).", name, TRAINING_EXPERIENCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn test_prices_follow_standing_demand_bundles_and_haggling() {
+        let mut faction_system = FactionSystem::new();
+        let mut market = ServiceMarket::default();
+        let harmony = Some(FactionId::OrderOfHarmony);
+        let healer = service_offered("healer_seraphina", ServiceKind::Healing).unwrap();
+        let list = healer.list_price(40);
+        assert_eq!(list, 30);
+        assert_eq!(market.quote("healer_seraphina", harmony, list, &faction_system, 0).price, 30);
+
+        // A crisis raises every healer's prices, then fades
+        market.crisis(ServiceKind::Healing, 2, &PROVIDERS, 0);
+        assert!(market.providers.get("sage_meridian").is_none());
+        let busy = market.quote("healer_seraphina", harmony, list, &faction_system, 0);
+        assert_eq!(busy.price, 42);
+        assert!(busy.notes.iter().any(|note| note.contains("demand")));
+        assert_eq!(market.quote("healer_seraphina", harmony, list, &faction_system, 720).price, 36);
+
+        // Members get a bundle discount on further services
+        faction_system.modify_reputation(FactionId::OrderOfHarmony, 60);
+        market.record_purchase("healer_seraphina", 2000);
+        let bundled = market.quote("healer_seraphina", harmony, list, &faction_system, 2030);
+        assert!(bundled.notes.iter().any(|note| note.contains("member bundle")));
+        assert!(bundled.price < 30);
+
+        // Haggling once a day, spent on the next purchase
+        let mut lucky = StepRng::new(0, 0);
+        assert_eq!(market.haggle("sage_meridian", 0.5, 0, &mut lucky), Ok(true));
+        assert!(market.haggle("sage_meridian", 0.5, 10, &mut lucky).is_err());
+        assert_eq!(market.quote("sage_meridian", None, 50, &faction_system, 10).price, 40);
+        market.record_purchase("sage_meridian", 10);
+        assert!(market.haggle("sage_meridian", 0.5, 100, &mut lucky).is_err());
+        let mut unlucky = StepRng::new(u64::MAX, 0);
+        assert_eq!(market.haggle("dr_felix", 0.5, 1500, &mut unlucky), Ok(false));
+    }
+
+    #[test]
+    fn test_market_day_lowers_prices_while_it_lasts() {
+        let faction_system = FactionSystem::new();
+        let mut market = ServiceMarket::default();
+        let mut event = Event {
+            event_type: EventType::Started,
+            event_id: "market_day".to_string(),
+            location: None,
+            witnessed: true,
+            timestamp: 0,
+        };
+        market.handle_event(&event);
+        let quote = market.quote("sage_meridian", None, 50, &faction_system, 0);
+        assert_eq!(quote.price, 40);
+        assert!(quote.notes.iter().any(|note| note.contains("market day")));
+
+        event.event_type = EventType::Ended;
+        market.handle_event(&event);
+        assert_eq!(market.quote("sage_meridian", None, 50, &faction_system, 0).price, 50);
+    }
+}
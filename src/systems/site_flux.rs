@@ -0,0 +1,307 @@
+//! Resonance flux at the Unstable Resonance Site
+//!
+//! The site never settles. It cycles through four phases on its own clock:
+//! - Calm: a window in which the site can be explored safely
+//! - Rising: flux builds and magic there runs hot and erratic
+//! - Surge: a resonance storm that batters anyone caught at the site
+//! - Ebbing: the storm spends itself and magic runs weak
+//!
+//! Phase lengths vary from cycle to cycle, seeded by the run. Every phase
+//! shows signs anyone can see, and each phase gives warning shortly before it
+//! ends. Reading those signs precisely takes training in Detection Arrays
+//! (magical signature analysis): novices see only the signs, students learn
+//! which phase is coming, and experts can time the next calm window.
+
+use crate::core::health::InjuryKind;
+use crate::core::{Player, WorldState};
+use crate::systems::defeat::{resolve_defeat, DefeatCause};
+use serde::{Deserialize, Serialize};
+
+/// Location whose flux this module models
+pub const SITE_ID: &str = "unstable_resonance_site";
+/// Theory used to read the signs
+pub const READING_THEORY: &str = "detection_arrays";
+/// Understanding needed to name the phases
+const STUDENT_UNDERSTANDING: f32 = 0.2;
+/// Understanding needed to time them
+const EXPERT_UNDERSTANDING: f32 = 0.5;
+/// Minutes before a phase ends that its warning signs appear
+const WARNING_MINUTES: i32 = 10;
+/// Most a phase's length varies from its base
+const JITTER_MINUTES: u64 = 10;
+/// Health lost per minute spent in a surge storm
+const STORM_DAMAGE_PER_MINUTE: f32 = 0.6;
+/// Minutes of storm in one stretch that leave a burn
+const STORM_BURN_MINUTES: i32 = 20;
+
+/// Phases of the site's flux cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FluxPhase {
+    #[default]
+    Calm,
+    Rising,
+    Surge,
+    Ebbing,
+}
+
+impl FluxPhase {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FluxPhase::Calm => "calm window",
+            FluxPhase::Rising => "rising flux",
+            FluxPhase::Surge => "surge storm",
+            FluxPhase::Ebbing => "ebbing flux",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            FluxPhase::Calm => FluxPhase::Rising,
+            FluxPhase::Rising => FluxPhase::Surge,
+            FluxPhase::Surge => FluxPhase::Ebbing,
+            FluxPhase::Ebbing => FluxPhase::Calm,
+        }
+    }
+
+    fn base_minutes(&self) -> i32 {
+        match self {
+            FluxPhase::Calm => 60,
+            FluxPhase::Rising => 40,
+            FluxPhase::Surge => 30,
+            FluxPhase::Ebbing => 40,
+        }
+    }
+
+    /// Multiplier on magic cast at the site
+    pub fn magic_modifier(&self) -> f32 {
+        match self {
+            FluxPhase::Calm => 1.0,
+            FluxPhase::Rising => 1.15,
+            FluxPhase::Surge => 0.6,
+            FluxPhase::Ebbing => 0.9,
+        }
+    }
+
+    /// Whether exploring the site is safe
+    pub fn is_safe(&self) -> bool {
+        *self != FluxPhase::Surge
+    }
+
+    /// What anyone at the site can see during the phase
+    fn sign(&self) -> &'static str {
+        match self {
+            FluxPhase::Calm => "The crystal spires glow a steady, even amber and the air is still.",
+            FluxPhase::Rising => "The spires flicker out of step with one another, and a low hum climbs in pitch.",
+            FluxPhase::Surge => "Arcs of raw resonance whip between the spires; the light strobes violently.",
+            FluxPhase::Ebbing => "The spires gutter and dim, and stray sparks fall like slow snow.",
+        }
+    }
+
+    /// What anyone can see shortly before the phase gives way to the next
+    fn warning(&self) -> &'static str {
+        match self {
+            FluxPhase::Calm => "Loose crystal dust begins to lift from the ground and hang in the air.",
+            FluxPhase::Rising => "The hum cuts out all at once, and your hair stands on end.",
+            FluxPhase::Surge => "The arcs shorten and pull back toward the spires.",
+            FluxPhase::Ebbing => "The falling sparks thin out, and the spires warm back toward amber.",
+        }
+    }
+}
+
+/// The site's flux state, saved with the world
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiteFlux {
+    pub phase: FluxPhase,
+    /// Game time (minutes) at which the phase ends
+    pub ends_at: i32,
+    /// Full cycles completed, varying each cycle's phase lengths
+    pub cycle: u32,
+    /// Minutes the player has spent in a storm that have not yet been felt
+    #[serde(default)]
+    pub storm_minutes: i32,
+}
+
+impl Default for SiteFlux {
+    fn default() -> Self {
+        Self {
+            phase: FluxPhase::Calm,
+            ends_at: FluxPhase::Calm.base_minutes(),
+            cycle: 0,
+            storm_minutes: 0,
+        }
+    }
+}
+
+impl SiteFlux {
+    /// Length of a phase in a given cycle, varied by the run seed
+    fn duration(phase: FluxPhase, cycle: u32, seed: u64) -> i32 {
+        let mix = (seed ^ (cycle as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ phase as u64)
+            .wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        let jitter = (mix >> 33) % (2 * JITTER_MINUTES + 1);
+        phase.base_minutes() + jitter as i32 - JITTER_MINUTES as i32
+    }
+
+    /// Run the flux forward from one game time to another
+    ///
+    /// Storm minutes accumulate while the player is at the site.
+    pub fn advance(&mut self, from: i32, to: i32, at_site: bool, seed: u64) {
+        let mut now = from;
+        while now < to {
+            let step_end = to.min(self.ends_at.max(now));
+            if self.phase == FluxPhase::Surge && at_site {
+                self.storm_minutes += step_end - now;
+            }
+            now = step_end;
+            if now >= self.ends_at {
+                if self.phase == FluxPhase::Ebbing {
+                    self.cycle += 1;
+                }
+                self.phase = self.phase.next();
+                self.ends_at = now + Self::duration(self.phase, self.cycle, seed);
+            }
+        }
+    }
+
+    pub fn minutes_left(&self, now: i32) -> i32 {
+        (self.ends_at - now).max(0)
+    }
+
+    /// Signs visible at the site right now
+    pub fn signs(&self, now: i32) -> String {
+        if self.minutes_left(now) <= WARNING_MINUTES {
+            format!("{} {}", self.phase.sign(), self.phase.warning())
+        } else {
+            self.phase.sign().to_string()
+        }
+    }
+
+    /// Minutes until the next calm window opens, assuming average phase lengths
+    fn minutes_to_calm(&self, now: i32) -> i32 {
+        let mut minutes = self.minutes_left(now);
+        let mut phase = self.phase.next();
+        while phase != FluxPhase::Calm {
+            minutes += phase.base_minutes();
+            phase = phase.next();
+        }
+        minutes
+    }
+
+    /// Interpret the signs with a given understanding of Detection Arrays
+    pub fn reading(&self, now: i32, understanding: f32) -> String {
+        let mut output = format!("=== RESONANCE FLUX ===\n{}\n", self.signs(now));
+
+        if understanding < STUDENT_UNDERSTANDING {
+            output.push_str(
+                "\nThe signs clearly mean something, but you can't read them. Studying magical signature\n\
+                 analysis (Detection Arrays) would teach you what they foretell."
+            );
+            return output;
+        }
+
+        let imminent = self.minutes_left(now) <= WARNING_MINUTES;
+        output.push_str(&format!(
+            "\nThe site is in a {}{}. {}",
+            self.phase.name(),
+            if imminent { ", about to give way" } else { "" },
+            if self.phase.is_safe() { "It is safe to explore for now." } else { "Anyone caught in the open will be hurt." }
+        ));
+        output.push_str(&format!("\nNext comes the {}.", self.phase.next().name()));
+        output.push_str(&format!("\nMagic here runs at x{:.2} strength.", self.phase.magic_modifier()));
+
+        if understanding < EXPERT_UNDERSTANDING {
+            output.push_str("\nWith deeper study of Detection Arrays you could time the phases.");
+            return output;
+        }
+
+        output.push_str(&format!("\nThe current phase should last about {} more minutes.", self.minutes_left(now)));
+        if self.phase != FluxPhase::Calm {
+            output.push_str(&format!("\nThe next calm window should open in roughly {} minutes.", self.minutes_to_calm(now)));
+        }
+        output
+    }
+
+    /// Take the storm minutes the player has yet to feel
+    fn take_exposure(&mut self) -> i32 {
+        std::mem::take(&mut self.storm_minutes)
+    }
+}
+
+/// Hurt the player for time spent in a surge storm, returning what happened
+pub fn apply_exposure(player: &mut Player, world: &mut WorldState) -> Option<String> {
+    let minutes = world.site_flux.take_exposure();
+    if minutes <= 0 {
+        return None;
+    }
+
+    let damage = (minutes as f32 * STORM_DAMAGE_PER_MINUTE).ceil() as i32;
+    let lost = player.health.take_damage(damage);
+    let mut output = format!(
+        "The surge storm lashes you with raw resonance. (Health -{}, now {}/{})",
+        lost, player.health.current, player.health.max
+    );
+    if minutes >= STORM_BURN_MINUTES {
+        output.push_str(&format!("\n{}", player.health.injure(InjuryKind::Burn)));
+    }
+    if player.health.is_down() {
+        let cause = DefeatCause::Hazard { source: "a surge storm at the Unstable Resonance Site".to_string() };
+        output.push_str(&format!("\n{}", resolve_defeat(&cause, player, world)));
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flux_cycles_and_storms_hurt() {
+        let mut flux = SiteFlux::default();
+        assert!(flux.reading(0, 0.0).contains("can't read them"));
+        assert!(flux.signs(55).contains("crystal dust begins to lift"));
+
+        // Phase lengths stay within their jitter and the cycle wraps round
+        let mut now = 0;
+        let mut seen = Vec::new();
+        while flux.cycle < 3 {
+            let next = flux.ends_at;
+            flux.advance(now, next, false, 42);
+            seen.push(flux.phase);
+            now = next;
+        }
+        assert_eq!(&seen[..4], &[FluxPhase::Rising, FluxPhase::Surge, FluxPhase::Ebbing, FluxPhase::Calm]);
+        assert_eq!(flux.storm_minutes, 0);
+        for cycle in 0..20 {
+            let length = SiteFlux::duration(FluxPhase::Surge, cycle, 7);
+            assert!((20..=40).contains(&length));
+        }
+
+        // Skip ahead to a storm while standing in it
+        while flux.phase != FluxPhase::Surge {
+            let next = flux.ends_at;
+            flux.advance(now, next, true, 42);
+            now = next;
+        }
+        assert_eq!(flux.storm_minutes, 0);
+        flux.advance(now, now + 15, true, 42);
+        assert_eq!(flux.storm_minutes, 15);
+
+        let reading = flux.reading(now, 0.6);
+        assert!(reading.contains("surge storm"));
+        assert!(reading.contains("Anyone caught in the open will be hurt"));
+        assert!(reading.contains("next calm window should open in roughly"));
+        assert!(!flux.reading(now, 0.3).contains("roughly"));
+
+        let mut world = WorldState::new();
+        world.site_flux = flux;
+        let mut player = Player::new("Test".to_string());
+        let report = apply_exposure(&mut player, &mut world).unwrap();
+        assert!(report.contains("Health -9"));
+        assert_eq!(player.health.severity(InjuryKind::Burn), 0);
+        assert!(apply_exposure(&mut player, &mut world).is_none());
+
+        // A long stretch in the storm also burns
+        world.site_flux.storm_minutes = STORM_BURN_MINUTES;
+        apply_exposure(&mut player, &mut world).unwrap();
+        assert_eq!(player.health.severity(InjuryKind::Burn), 1);
+    }
+}
@@ -0,0 +1,321 @@
+//! Player statistics kept across sessions
+//!
+//! What each character has done is tallied in a small SQLite database beside
+//! the save files: spells cast and how many held, silver earned and spent,
+//! places moved through, words spoken to each person, and enemies defeated or
+//! spared. A character is told apart by name and the id their run was given
+//! when it started, so every save of the same playthrough adds to the same
+//! tallies and a new game starts fresh, even one replaying a fixed seed.
+//!
+//! Tallies only ever grow. Taking an action back with undo or loading an
+//! older save doesn't take back what was counted.
+
+use crate::core::{Player, WorldState};
+use crate::systems::combat::CombatSystem;
+use crate::GameResult;
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Statistics database kept in the save directory
+pub const STATISTICS_FILE: &str = "statistics.db";
+
+/// What a tally counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StatCategory {
+    /// By spell type: casts, and how many held
+    Spells,
+    /// "earned" and "spent": times, and silver
+    Money,
+    /// "moves": places moved through, and minutes on the move
+    Travel,
+    /// By person: conversations, and words spoken to them
+    Conversations,
+    /// By enemy: defeated, and spared
+    Combat,
+}
+
+impl StatCategory {
+    /// Every category, in the order they're shown
+    pub const ALL: [StatCategory; 5] = [
+        StatCategory::Spells,
+        StatCategory::Money,
+        StatCategory::Travel,
+        StatCategory::Conversations,
+        StatCategory::Combat,
+    ];
+
+    /// Name stored in the database and used to ask for the category
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatCategory::Spells => "spells",
+            StatCategory::Money => "money",
+            StatCategory::Travel => "travel",
+            StatCategory::Conversations => "conversations",
+            StatCategory::Combat => "combat",
+        }
+    }
+
+    /// The category a player asked for, accepting a few other words for each
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "spells" | "spell" | "magic" | "casting" => Some(StatCategory::Spells),
+            "money" | "silver" | "coin" => Some(StatCategory::Money),
+            "travel" | "distance" | "movement" => Some(StatCategory::Travel),
+            "conversations" | "conversation" | "talk" | "people" => Some(StatCategory::Conversations),
+            "combat" | "fights" | "enemies" => Some(StatCategory::Combat),
+            _ => None,
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            StatCategory::Spells => "Spells",
+            StatCategory::Money => "Money",
+            StatCategory::Travel => "Travel",
+            StatCategory::Conversations => "Conversations",
+            StatCategory::Combat => "Combat",
+        }
+    }
+}
+
+/// Something to add to a tally
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatEvent {
+    pub category: StatCategory,
+    pub subject: String,
+    /// How many times
+    pub count: i64,
+    /// A second measure, whose meaning depends on the category
+    pub total: i64,
+}
+
+impl StatEvent {
+    /// A spell cast, and whether it held
+    pub fn spell(spell_type: &str, success: bool) -> Self {
+        Self { category: StatCategory::Spells, subject: spell_type.to_string(), count: 1, total: success as i64 }
+    }
+
+    /// Something said to a person
+    pub fn conversation(name: &str, words: usize) -> Self {
+        Self { category: StatCategory::Conversations, subject: name.to_string(), count: 1, total: words as i64 }
+    }
+}
+
+/// How a character stood before a command, to tally what it changed
+#[derive(Debug, Clone)]
+pub struct StatsMark {
+    location: String,
+    minutes: i32,
+    silver: i32,
+    /// Defeated and spared, by enemy ID
+    enemies: HashMap<String, (u32, u32)>,
+}
+
+impl StatsMark {
+    pub fn of(player: &Player, world: &WorldState, combat: &CombatSystem) -> Self {
+        Self {
+            location: world.current_location.clone(),
+            minutes: world.game_time_minutes,
+            silver: player.inventory.silver,
+            enemies: combat.bestiary().iter().map(|(id, entry)| (id.clone(), (entry.defeats, entry.spared))).collect(),
+        }
+    }
+
+    /// What changed since the mark was made: silver gained or spent, a move
+    /// and the time it took, and enemies defeated or spared
+    pub fn events(&self, player: &Player, world: &WorldState, combat: &CombatSystem) -> Vec<StatEvent> {
+        let mut events = Vec::new();
+        let silver = player.inventory.silver - self.silver;
+        if silver != 0 {
+            let subject = if silver > 0 { "earned" } else { "spent" };
+            events.push(StatEvent { category: StatCategory::Money, subject: subject.to_string(), count: 1, total: silver.abs() as i64 });
+        }
+        if world.current_location != self.location {
+            let minutes = (world.game_time_minutes - self.minutes).max(0);
+            events.push(StatEvent { category: StatCategory::Travel, subject: "moves".to_string(), count: 1, total: minutes as i64 });
+        }
+        for (id, entry) in combat.bestiary() {
+            let (defeats, spared) = self.enemies.get(id).copied().unwrap_or_default();
+            if entry.defeats > defeats || entry.spared > spared {
+                let name = combat.find_enemy(id).map_or_else(|| id.replace('_', " "), |enemy| enemy.name);
+                events.push(StatEvent {
+                    category: StatCategory::Combat,
+                    subject: name,
+                    count: entry.defeats.saturating_sub(defeats) as i64,
+                    total: entry.spared.saturating_sub(spared) as i64,
+                });
+            }
+        }
+        events
+    }
+}
+
+/// The character a save belongs to; saves from before runs had an id go by
+/// the run seed instead
+pub fn character_key(player: &Player, world: &WorldState) -> String {
+    if world.run.id.is_empty() {
+        format!("{}#{}", player.name, world.run.seed)
+    } else {
+        format!("{}#{}", player.name, world.run.id)
+    }
+}
+
+/// The statistics database
+pub struct Statistics {
+    connection: Connection,
+}
+
+impl Statistics {
+    /// Open the statistics in a directory, creating them if there are none yet
+    pub fn open(directory: &Path) -> GameResult<Self> {
+        let connection = Connection::open(directory.join(STATISTICS_FILE))
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to open statistics: {}", e)))?;
+        connection.execute(
+            "CREATE TABLE IF NOT EXISTS statistics (
+                character TEXT NOT NULL, -- Player name and run id
+                category TEXT NOT NULL,
+                subject TEXT NOT NULL,
+                count INTEGER NOT NULL DEFAULT 0,
+                total INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (character, category, subject)
+            )",
+            [],
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to create statistics table: {}", e)))?;
+        Ok(Self { connection })
+    }
+
+    /// Add events to a character's tallies
+    pub fn record(&self, character: &str, events: &[StatEvent]) -> GameResult<()> {
+        let transaction = self.connection.unchecked_transaction()?;
+        for event in events {
+            transaction.execute(
+                "INSERT INTO statistics (character, category, subject, count, total) VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (character, category, subject)
+                 DO UPDATE SET count = count + excluded.count, total = total + excluded.total",
+                params![character, event.category.as_str(), event.subject, event.count, event.total],
+            ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to record statistics: {}", e)))?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// A character's tallies in a category: subject, count and total, by subject
+    pub fn tallies(&self, character: &str, category: StatCategory) -> GameResult<Vec<(String, i64, i64)>> {
+        let mut stmt = self.connection.prepare(
+            "SELECT subject, count, total FROM statistics WHERE character = ?1 AND category = ?2 ORDER BY subject"
+        ).map_err(|e| crate::GameError::DatabaseError(format!("Failed to prepare statistics query: {}", e)))?;
+        let rows = stmt.query_map(params![character, category.as_str()], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to query statistics: {}", e)))?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|e| crate::GameError::DatabaseError(format!("Failed to read statistics: {}", e)).into())
+    }
+
+    /// Describe a character's statistics, in every category or just one
+    pub fn describe(&self, character: &str, name: &str, only: Option<StatCategory>) -> GameResult<String> {
+        let mut response = format!("=== Statistics for {} ===", name);
+        let mut anything = false;
+        for category in StatCategory::ALL.into_iter().filter(|category| only.is_none_or(|only| only == *category)) {
+            let tallies = self.tallies(character, category)?;
+            if tallies.is_empty() {
+                if only.is_some() {
+                    response.push_str(&format!("\n\n{}\n  Nothing yet.", category.title()));
+                }
+                continue;
+            }
+            anything = true;
+            response.push_str(&format!("\n\n{}", category.title()));
+            for line in describe_category(category, &tallies) {
+                response.push_str(&format!("\n  {}", line));
+            }
+        }
+        if !anything && only.is_none() {
+            response.push_str("\n\nNothing has been counted yet. Cast, trade, travel, talk and fight, and it will show here.");
+        }
+        Ok(response)
+    }
+}
+
+/// The lines for one category's tallies
+fn describe_category(category: StatCategory, tallies: &[(String, i64, i64)]) -> Vec<String> {
+    let percent = |part: i64, whole: i64| if whole == 0 { 0 } else { part * 100 / whole };
+    let plural = |count: i64, word: &str| format!("{} {}{}", count, word, if count == 1 { "" } else { "s" });
+    match category {
+        StatCategory::Spells => {
+            let mut lines: Vec<String> = tallies.iter()
+                .map(|(spell, casts, held)| format!("{}: {} cast, {} held ({}%)", spell, casts, held, percent(*held, *casts)))
+                .collect();
+            let (casts, held) = tallies.iter().fold((0, 0), |(casts, held), (_, c, h)| (casts + c, held + h));
+            if tallies.len() > 1 {
+                lines.push(format!("Overall: {} cast, {} held ({}%)", casts, held, percent(held, casts)));
+            }
+            lines
+        }
+        StatCategory::Money => tallies.iter()
+            .map(|(subject, times, silver)| {
+                let label = if subject == "earned" { "Earned" } else { "Spent" };
+                format!("{}: {} silver over {}", label, silver, plural(*times, "transaction"))
+            })
+            .collect(),
+        StatCategory::Travel => tallies.iter()
+            .flat_map(|(_, moves, minutes)| [
+                format!("Places moved through: {}", moves),
+                format!("Time on the move: {}", crate::systems::quests::describe_minutes(*minutes as i32)),
+            ])
+            .collect(),
+        StatCategory::Conversations => tallies.iter()
+            .map(|(person, times, words)| format!("{}: {}, {} spoken", person, plural(*times, "exchange"), plural(*words, "word")))
+            .collect(),
+        StatCategory::Combat => tallies.iter()
+            .map(|(enemy, defeated, spared)| format!("{}: {} defeated, {} spared", enemy, defeated, spared))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_tallies_add_up_across_sessions() {
+        let directory = TempDir::new().unwrap();
+        let statistics = Statistics::open(directory.path()).unwrap();
+        statistics.record("Ada#7", &[StatEvent::spell("light", true), StatEvent::spell("light", false)]).unwrap();
+        statistics.record("Ada#7", &[StatEvent::conversation("Sage Meridian", 4)]).unwrap();
+        statistics.record("Bex#9", &[StatEvent::spell("healing", true)]).unwrap();
+        drop(statistics);
+
+        let statistics = Statistics::open(directory.path()).unwrap();
+        statistics.record("Ada#7", &[StatEvent::spell("light", true)]).unwrap();
+        assert_eq!(statistics.tallies("Ada#7", StatCategory::Spells).unwrap(), vec![("light".to_string(), 3, 2)]);
+
+        let all = statistics.describe("Ada#7", "Ada", None).unwrap();
+        assert!(all.contains("light: 3 cast, 2 held (66%)"));
+        assert!(all.contains("Sage Meridian: 1 exchange, 4 words spoken"));
+        assert!(!all.contains("healing"));
+        let money = statistics.describe("Ada#7", "Ada", StatCategory::parse("silver")).unwrap();
+        assert!(money.contains("Money\n  Nothing yet.") && !money.contains("Spells"));
+    }
+
+    #[test]
+    fn test_a_command_is_tallied_by_what_it_changed() {
+        let mut player = Player::new("Ada".to_string());
+        let mut world = WorldState::new();
+        let combat = CombatSystem::new();
+        let mark = StatsMark::of(&player, &world, &combat);
+        assert!(mark.events(&player, &world, &combat).is_empty());
+
+        player.inventory.silver -= 5;
+        world.current_location = "crystal_gardens".to_string();
+        world.game_time_minutes += 12;
+        let events = mark.events(&player, &world, &combat);
+        assert_eq!(events, vec![
+            StatEvent { category: StatCategory::Money, subject: "spent".to_string(), count: 1, total: 5 },
+            StatEvent { category: StatCategory::Travel, subject: "moves".to_string(), count: 1, total: 12 },
+        ]);
+        assert_eq!(character_key(&player, &world), format!("Ada#{}", world.run.seed));
+        world.run.id = "first-run".to_string();
+        assert_eq!(character_key(&player, &world), "Ada#first-run");
+    }
+}
@@ -0,0 +1,264 @@
+//! Lingering combat conditions applied by spells and enemy abilities
+//!
+//! Effects last a number of turns and tick at the start of the affected
+//! combatant's turn. Each kind has its own stacking rule:
+//! - Stun: cannot stack; reapplying keeps the longer duration
+//! - Resonance Burn: intensity stacks up to a cap, duration refreshes
+//! - Disorient: duration accumulates up to a cap
+
+use serde::{Deserialize, Serialize};
+
+/// Maximum intensity a resonance burn can build to
+const MAX_BURN_STACKS: u32 = 3;
+/// Damage dealt per burn stack each turn
+const BURN_DAMAGE_PER_STACK: i32 = 4;
+/// Longest a disorient can be extended to
+const MAX_DISORIENT_TURNS: u32 = 4;
+/// Damage multiplier for a disoriented attacker
+const DISORIENT_DAMAGE_MULTIPLIER: f32 = 0.6;
+
+/// Kinds of status effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StatusKind {
+    /// Loses their next action
+    Stun,
+    /// Sympathetic feedback deals damage every turn
+    ResonanceBurn,
+    /// Attacks land with reduced force
+    Disorient,
+}
+
+impl StatusKind {
+    /// Parse a status kind from a string
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.to_lowercase().replace([' ', '-'], "_").as_str() {
+            "stun" | "stunned" => Some(StatusKind::Stun),
+            "resonance_burn" | "burn" => Some(StatusKind::ResonanceBurn),
+            "disorient" | "disoriented" => Some(StatusKind::Disorient),
+            _ => None,
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusKind::Stun => "Stun",
+            StatusKind::ResonanceBurn => "Resonance Burn",
+            StatusKind::Disorient => "Disorient",
+        }
+    }
+}
+
+/// An active effect on a combatant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    /// Turns left before the effect wears off
+    pub remaining_turns: u32,
+    /// Intensity; only resonance burn stacks above 1
+    pub stacks: u32,
+}
+
+/// Chance for an attack to inflict a status effect
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StatusAbility {
+    pub kind: StatusKind,
+    /// Probability of applying on a hit (0.0-1.0)
+    pub chance: f32,
+    pub duration: u32,
+}
+
+/// Result of ticking a combatant's effects at the start of their turn
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatusTick {
+    /// Damage taken from effects this turn
+    pub damage: i32,
+    /// Whether the combatant loses this turn's action
+    pub skip_action: bool,
+    /// Narration for the tick
+    pub messages: Vec<String>,
+}
+
+/// All effects currently on one combatant
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply an effect following its stacking rule, returning a description
+    pub fn apply(&mut self, kind: StatusKind, duration: u32, target: &str) -> String {
+        if duration == 0 {
+            return String::new();
+        }
+
+        if let Some(existing) = self.effects.iter_mut().find(|effect| effect.kind == kind) {
+            match kind {
+                StatusKind::Stun => {
+                    existing.remaining_turns = existing.remaining_turns.max(duration);
+                    format!("{} remains stunned.", target)
+                }
+                StatusKind::ResonanceBurn => {
+                    existing.stacks = (existing.stacks + 1).min(MAX_BURN_STACKS);
+                    existing.remaining_turns = existing.remaining_turns.max(duration);
+                    format!("The resonance burn on {} intensifies (x{}).", target, existing.stacks)
+                }
+                StatusKind::Disorient => {
+                    existing.remaining_turns = (existing.remaining_turns + duration).min(MAX_DISORIENT_TURNS);
+                    format!("{} is further disoriented ({} turns).", target, existing.remaining_turns)
+                }
+            }
+        } else {
+            self.effects.push(StatusEffect { kind, remaining_turns: duration, stacks: 1 });
+            match kind {
+                StatusKind::Stun => format!("{} is stunned!", target),
+                StatusKind::ResonanceBurn => format!("{} is seared by resonance burn!", target),
+                StatusKind::Disorient => format!("{} is disoriented!", target),
+            }
+        }
+    }
+
+    /// Resolve effects at the start of the combatant's turn
+    ///
+    /// Burns deal damage, a stun costs the action, and every effect loses a
+    /// turn of duration. Expired effects are removed.
+    pub fn tick(&mut self, target: &str) -> StatusTick {
+        let mut tick = StatusTick::default();
+
+        for effect in &mut self.effects {
+            match effect.kind {
+                StatusKind::Stun => {
+                    tick.skip_action = true;
+                    tick.messages.push(format!("{} is stunned and cannot act!", target));
+                }
+                StatusKind::ResonanceBurn => {
+                    let damage = BURN_DAMAGE_PER_STACK * effect.stacks as i32;
+                    tick.damage += damage;
+                    tick.messages.push(format!("Resonance burn sears {} for {} damage.", target, damage));
+                }
+                StatusKind::Disorient => {}
+            }
+            effect.remaining_turns = effect.remaining_turns.saturating_sub(1);
+        }
+
+        self.effects.retain(|effect| {
+            if effect.remaining_turns == 0 {
+                tick.messages.push(format!("{} wears off {}.", effect.kind.name(), target));
+            }
+            effect.remaining_turns > 0
+        });
+
+        tick
+    }
+
+    /// Whether an effect of this kind is active
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+
+    /// Active effects
+    pub fn effects(&self) -> &[StatusEffect] {
+        &self.effects
+    }
+
+    /// Multiplier applied to this combatant's outgoing damage
+    pub fn damage_multiplier(&self) -> f32 {
+        if self.has(StatusKind::Disorient) {
+            DISORIENT_DAMAGE_MULTIPLIER
+        } else {
+            1.0
+        }
+    }
+
+    /// One-line summary, e.g. "Resonance Burn x2 (2 turns), Disorient (1 turn)"
+    pub fn summary(&self) -> Option<String> {
+        if self.effects.is_empty() {
+            return None;
+        }
+
+        Some(self.effects.iter()
+            .map(|effect| {
+                let stacks = if effect.stacks > 1 { format!(" x{}", effect.stacks) } else { String::new() };
+                let plural = if effect.remaining_turns == 1 { "" } else { "s" };
+                format!("{}{} ({} turn{})", effect.kind.name(), stacks, effect.remaining_turns, plural)
+            })
+            .collect::<Vec<_>>()
+            .join(", "))
+    }
+}
+
+/// Status effect a player spell type can inflict on hit
+pub fn spell_status_ability(spell_type: &str) -> Option<StatusAbility> {
+    match spell_type {
+        "light" => Some(StatusAbility { kind: StatusKind::Disorient, chance: 0.4, duration: 2 }),
+        "manipulation" => Some(StatusAbility { kind: StatusKind::Stun, chance: 0.3, duration: 1 }),
+        "communication" => Some(StatusAbility { kind: StatusKind::ResonanceBurn, chance: 0.35, duration: 3 }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stacking_rules() {
+        let mut effects = StatusEffects::new();
+
+        effects.apply(StatusKind::Stun, 1, "Enemy");
+        effects.apply(StatusKind::Stun, 2, "Enemy");
+        effects.apply(StatusKind::ResonanceBurn, 2, "Enemy");
+        effects.apply(StatusKind::ResonanceBurn, 3, "Enemy");
+        for _ in 0..3 {
+            effects.apply(StatusKind::ResonanceBurn, 1, "Enemy");
+        }
+        effects.apply(StatusKind::Disorient, 3, "Enemy");
+        effects.apply(StatusKind::Disorient, 3, "Enemy");
+
+        let stun = &effects.effects()[0];
+        assert_eq!((stun.remaining_turns, stun.stacks), (2, 1));
+        let burn = &effects.effects()[1];
+        assert_eq!((burn.remaining_turns, burn.stacks), (3, MAX_BURN_STACKS));
+        let disorient = &effects.effects()[2];
+        assert_eq!(disorient.remaining_turns, MAX_DISORIENT_TURNS);
+    }
+
+    #[test]
+    fn test_tick_damage_skip_and_expiry() {
+        let mut effects = StatusEffects::new();
+        effects.apply(StatusKind::Stun, 1, "You");
+        effects.apply(StatusKind::ResonanceBurn, 2, "You");
+        effects.apply(StatusKind::ResonanceBurn, 2, "You");
+        effects.apply(StatusKind::Disorient, 2, "You");
+        assert_eq!(effects.damage_multiplier(), DISORIENT_DAMAGE_MULTIPLIER);
+
+        let first = effects.tick("you");
+        assert!(first.skip_action);
+        assert_eq!(first.damage, 2 * BURN_DAMAGE_PER_STACK);
+        assert!(!effects.has(StatusKind::Stun));
+        assert!(first.messages.iter().any(|m| m.contains("Stun wears off")));
+
+        let second = effects.tick("you");
+        assert!(!second.skip_action);
+        assert_eq!(second.damage, 2 * BURN_DAMAGE_PER_STACK);
+        assert!(effects.summary().is_none());
+        assert_eq!(effects.damage_multiplier(), 1.0);
+    }
+
+    #[test]
+    fn test_serialization_roundtrip() {
+        let mut effects = StatusEffects::new();
+        effects.apply(StatusKind::ResonanceBurn, 3, "Enemy");
+        effects.apply(StatusKind::Disorient, 1, "Enemy");
+
+        let json = serde_json::to_string(&effects).unwrap();
+        let restored: StatusEffects = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, effects);
+        assert_eq!(restored.summary().unwrap(), "Resonance Burn (3 turns), Disorient (1 turn)");
+        assert_eq!(StatusKind::from_string("resonance burn"), Some(StatusKind::ResonanceBurn));
+    }
+}
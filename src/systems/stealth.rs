@@ -0,0 +1,234 @@
+//! Sneaking into places the factions keep to themselves
+//!
+//! Some ways are watched: a guarded exit, or a restricted location a faction
+//! keeps for those it trusts. Walking openly into a restricted place while
+//! anyone is keeping watch gets the player turned away, but they can try to
+//! `sneak` past instead. Each watcher, whether a posted warden or one of the
+//! faction's people who happens to be about, gets one roll to spot them. How
+//! likely that is depends on:
+//! - Visibility: darkness, fog and rain all help
+//! - Noise: a heavy pack gives the player away
+//! - The player's own wits
+//! - Any bounty the faction already holds against them
+//!
+//! Some restricted places are warded as well, and casting there without the
+//! faction's leave may set off the alarm; the stronger the spell's signature,
+//! the likelier it is. Being caught either way is an offence charged to the
+//! player through the faction's bounty.
+
+use crate::core::world_state::{Direction, TimeOfDay, Weather, WorldState};
+use crate::core::Player;
+use crate::systems::access::{AccessRequirement, LockKind};
+use crate::systems::encumbrance::{encumbrance, Encumbrance};
+use crate::systems::factions::FactionId;
+use crate::GameResult;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Minutes spent creeping past watchers
+pub const SNEAK_MINUTES: i32 = 10;
+/// Bounty for being caught sneaking
+pub const CAUGHT_BOUNTY: i32 = 25;
+/// Standing lost for being caught sneaking
+pub const CAUGHT_STANDING: i32 = 5;
+/// Bounty for setting off a ward
+pub const ALARM_BOUNTY: i32 = 15;
+/// Standing lost for setting off a ward
+pub const ALARM_STANDING: i32 = 3;
+/// Wardens posted at a guarded exit
+const POSTED_WARDENS: u32 = 2;
+/// Chance one watcher spots the player in ordinary conditions
+const BASE_DETECTION: f32 = 0.35;
+
+/// A place a faction keeps for those in good standing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Restriction {
+    pub faction: FactionId,
+    pub min_standing: i32,
+    /// Wardens always on watch, besides any of the faction's people about
+    #[serde(default)]
+    pub wardens: u32,
+    /// Whether wards here react to spellcasting by outsiders
+    #[serde(default)]
+    pub alarm: bool,
+}
+
+impl Restriction {
+    /// Whether the faction lets the player in
+    pub fn admits(&self, player: &Player) -> bool {
+        player.faction_reputation(self.faction) >= self.min_standing
+    }
+}
+
+/// A watched way the player might sneak through
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchedWay {
+    pub faction: FactionId,
+    pub destination: String,
+    /// Wardens posted there, besides the faction's people
+    pub wardens: u32,
+}
+
+/// The restriction keeping the player out of a location, if any
+pub fn barred_from<'a>(world: &'a WorldState, player: &Player, location_id: &str) -> Option<&'a Restriction> {
+    world.locations.get(location_id)?.restricted.as_ref().filter(|restriction| !restriction.admits(player))
+}
+
+/// The restricted place a direction leads, if the player isn't admitted there
+pub fn restricted_way(world: &WorldState, player: &Player, direction: &Direction) -> Option<WatchedWay> {
+    let destination = world.current_location()?.exits.get(direction)?;
+    let restriction = barred_from(world, player, destination)?;
+    Some(WatchedWay { faction: restriction.faction, destination: destination.clone(), wardens: restriction.wardens })
+}
+
+/// The watched way the player could sneak through in a direction
+pub fn watched_way(world: &WorldState, player: &Player, direction: &Direction) -> GameResult<WatchedWay> {
+    let here = world.current_location()
+        .ok_or_else(|| crate::GameError::ContentNotFound(format!("Current location '{}' not found", world.current_location)))?;
+    let destination = here.exits.get(direction)
+        .ok_or_else(|| crate::GameError::InvalidCommand("You can't go that way.".to_string()))?;
+
+    if let Some(lock) = here.locks.get(direction) {
+        return match (&lock.kind, &lock.requirement) {
+            (LockKind::Guarded, AccessRequirement::Standing { faction, .. }) => Ok(WatchedWay {
+                faction: *faction,
+                destination: destination.clone(),
+                wardens: POSTED_WARDENS,
+            }),
+            _ => Err(crate::GameError::InvalidCommand(format!(
+                "No amount of stealth gets you through: the way {} is {}. {}", direction.display_name(), lock.kind.name(), lock.refusal()
+            )).into()),
+        };
+    }
+
+    restricted_way(world, player, direction).ok_or_else(|| crate::GameError::InvalidCommand(format!(
+        "Nobody is keeping you from going {}; you can simply walk.", direction.display_name()
+    )).into())
+}
+
+/// What turns the player away from walking openly into a restricted place,
+/// if anyone is watching
+pub fn turned_away(way: &WatchedWay, guards: &[String], direction: &Direction) -> Option<String> {
+    let watcher = guards.first().cloned()
+        .or_else(|| (way.wardens > 0).then(|| format!("{} wardens", way.faction.short_name())))?;
+    Some(format!(
+        "{} stop you: the way {} is kept for those the {} trust. ('sneak {}' to try to slip past)",
+        watcher, direction.display_name(), way.faction.display_name(), direction.display_name()
+    ))
+}
+
+/// Chance one of a faction's watchers spots the player now
+pub fn detection_chance(player: &Player, world: &WorldState, faction: FactionId) -> f32 {
+    let visibility = match world.environment.time_of_day {
+        TimeOfDay::Night | TimeOfDay::Midnight => -0.15,
+        TimeOfDay::Dawn | TimeOfDay::Evening => -0.05,
+        _ => 0.0,
+    } + match world.environment.weather {
+        Weather::Foggy | Weather::NullFog => -0.15,
+        Weather::Rainy | Weather::Stormy | Weather::ResonanceStorm => -0.1,
+        _ => 0.0,
+    };
+    let noise = match encumbrance(player) {
+        Encumbrance::Unburdened => 0.0,
+        Encumbrance::Burdened => 0.05,
+        Encumbrance::Heavy => 0.15,
+        Encumbrance::Overloaded => 0.3,
+    };
+    let wits = (player.attributes.mental_acuity - 25) as f32 / 200.0;
+    (BASE_DETECTION + visibility + noise - wits + world.wanted.watchfulness(faction)).clamp(0.05, 0.95)
+}
+
+/// Try to slip through a watched way past everyone keeping watch; `guards`
+/// names the faction's people about, besides its posted wardens
+pub fn sneak(way: &WatchedWay, guards: &[String], player: &mut Player, world: &mut WorldState, rng: &mut impl Rng) -> GameResult<String> {
+    world.advance_time(SNEAK_MINUTES);
+    player.playtime_minutes += SNEAK_MINUTES;
+
+    let chance = detection_chance(player, world, way.faction);
+    let wardens = (0..way.wardens).map(|_| format!("One of the {} wardens", way.faction.short_name()));
+    for watcher in guards.iter().cloned().chain(wardens) {
+        if rng.gen::<f32>() < chance {
+            let place = world.locations.get(&way.destination).map_or_else(|| way.destination.replace('_', " "), |location| location.name.clone());
+            let charge = world.wanted.charge(player, way.faction, CAUGHT_BOUNTY, CAUGHT_STANDING, &format!("sneaking into the {}", place), world.game_time_minutes);
+            return Ok(format!("{} spots you and hauls you back.\n{}", watcher, charge));
+        }
+    }
+
+    world.enter_location(&way.destination)?;
+    player.current_location = way.destination.clone();
+    let name = world.current_location().map_or_else(|| way.destination.clone(), |location| location.name.clone());
+    Ok(format!("You slip past unseen into the {}.", name))
+}
+
+/// A spell cast where wards watch for outsiders may set them off
+pub fn check_alarm(player: &mut Player, world: &mut WorldState, rng: &mut impl Rng) -> Option<String> {
+    let restriction = barred_from(world, player, &world.current_location).filter(|restriction| restriction.alarm)?.clone();
+    let strength = world.current_location()?.magical_properties.recent_activity.last()?.strength;
+    if rng.gen::<f32>() >= strength.clamp(0.1, 1.0) {
+        return None;
+    }
+    let place = world.current_location()?.name.clone();
+    let charge = world.wanted.charge(player, restriction.faction, ALARM_BOUNTY, ALARM_STANDING, &format!("setting off the wards in the {}", place), world.game_time_minutes);
+    Some(format!("Your casting sets the wards ringing; somewhere, the {} know you're here.\n{}", restriction.faction.display_name(), charge))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::{Location, MagicalSignature};
+    use rand::rngs::mock::StepRng;
+
+    fn vault_world() -> WorldState {
+        let mut world = WorldState::new();
+        let mut stacks = Location::new("stacks".to_string(), "Stacks".to_string(), "Shelves.".to_string());
+        stacks.add_exit(Direction::Down, "vault".to_string());
+        let mut vault = Location::new("vault".to_string(), "Vault".to_string(), "Sealed records.".to_string());
+        vault.restricted = Some(Restriction { faction: FactionId::NeutralScholars, min_standing: 20, wardens: 1, alarm: true });
+        world.add_location(stacks);
+        world.add_location(vault);
+        world.current_location = "stacks".to_string();
+        world
+    }
+
+    #[test]
+    fn test_sneaking_past_watchers_or_getting_caught() {
+        let mut world = vault_world();
+        let mut player = Player::new("Tester".to_string());
+        let way = watched_way(&world, &player, &Direction::Down).unwrap();
+        assert_eq!(way.wardens, 1);
+        assert!(turned_away(&way, &[], &Direction::Down).unwrap().contains("Scholars wardens stop you"));
+
+        // A roll of 0 is under any chance, so the first watcher spots the player
+        let caught = sneak(&way, &["Sage Meridian".to_string()], &mut player, &mut world, &mut StepRng::new(0, 0)).unwrap();
+        assert!(caught.starts_with("Sage Meridian spots you"));
+        assert_eq!(world.current_location, "stacks");
+        assert_eq!(world.wanted.bounty(FactionId::NeutralScholars), CAUGHT_BOUNTY);
+
+        let slipped = sneak(&way, &[], &mut player, &mut world, &mut StepRng::new(u64::MAX, 0)).unwrap();
+        assert!(slipped.contains("slip past unseen"));
+        assert_eq!(world.current_location, "vault");
+
+        // Those the Scholars trust may simply walk in
+        player.modify_faction_reputation(FactionId::NeutralScholars, 50);
+        world.current_location = "stacks".to_string();
+        assert!(watched_way(&world, &player, &Direction::Down).is_err());
+    }
+
+    #[test]
+    fn test_darkness_and_wards() {
+        let mut world = vault_world();
+        let mut player = Player::new("Tester".to_string());
+        let by_day = detection_chance(&player, &world, FactionId::NeutralScholars);
+        world.environment.time_of_day = TimeOfDay::Night;
+        world.environment.weather = Weather::Foggy;
+        assert!(detection_chance(&player, &world, FactionId::NeutralScholars) < by_day);
+
+        world.current_location = "vault".to_string();
+        assert!(check_alarm(&mut player, &mut world, &mut StepRng::new(0, 0)).is_none());
+        world.locations.get_mut("vault").unwrap().magical_properties.recent_activity.push(MagicalSignature {
+            magic_type: "light".to_string(), strength: 0.8, age_minutes: 0, frequency: 4,
+        });
+        assert!(check_alarm(&mut player, &mut world, &mut StepRng::new(0, 0)).unwrap().contains("wards ringing"));
+        assert_eq!(world.wanted.bounty(FactionId::NeutralScholars), ALARM_BOUNTY);
+    }
+}
@@ -0,0 +1,200 @@
+//! What the player could do right now
+//!
+//! The `actions` command asks each system what it has to offer where the
+//! player stands, and lists the answers as commands ready to type:
+//! - The world: the ways out and what's lying about
+//! - The player: casting with the crystal in hand, or resting when spent
+//! - Dialogue: the people here to talk to
+//! - Quests: steps that can be taken here, and quests to turn in or start
+//! - Knowledge: theories within reach to study
+//!
+//! Each system answers through [`ActionSource`], so a new system only has to
+//! implement it to be asked.
+
+use crate::core::{Player, WorldState};
+use crate::systems::factions::FactionSystem;
+use crate::systems::quests::QuestSystem;
+use std::collections::BTreeMap;
+
+/// Most actions listed under one heading
+const ACTIONS_PER_KIND: usize = 6;
+/// Energy below which resting is suggested, as a fraction of the maximum
+const TIRED_ENERGY: f32 = 0.25;
+/// Fatigue above which resting is suggested
+const TIRED_FATIGUE: i32 = 50;
+
+/// What sort of thing an action is, in the order they're listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ActionKind {
+    Quests,
+    People,
+    Items,
+    Movement,
+    Study,
+    Magic,
+}
+
+impl ActionKind {
+    /// Heading the actions are listed under
+    pub fn heading(&self) -> &'static str {
+        match self {
+            ActionKind::Quests => "Quests",
+            ActionKind::People => "People",
+            ActionKind::Items => "Items",
+            ActionKind::Movement => "Ways out",
+            ActionKind::Study => "Study",
+            ActionKind::Magic => "Magic and rest",
+        }
+    }
+}
+
+/// Something the player could do, and the command that does it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuggestedAction {
+    pub kind: ActionKind,
+    pub command: String,
+    /// Why it's worth doing, or what it leads to
+    pub reason: String,
+}
+
+impl SuggestedAction {
+    pub fn new(kind: ActionKind, command: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self { kind, command: command.into(), reason: reason.into() }
+    }
+}
+
+/// Where the player stands, for systems to look at
+pub struct Situation<'a> {
+    pub player: &'a Player,
+    pub world: &'a WorldState,
+    pub quest_system: &'a QuestSystem,
+    pub faction_system: &'a FactionSystem,
+}
+
+/// A system that can say what the player could do with it right now
+pub trait ActionSource {
+    /// Actions open to the player in this situation
+    fn available_actions(&self, situation: &Situation) -> Vec<SuggestedAction>;
+}
+
+impl ActionSource for WorldState {
+    fn available_actions(&self, _situation: &Situation) -> Vec<SuggestedAction> {
+        let mut actions = Vec::new();
+        let Some(location) = self.current_location() else {
+            return actions;
+        };
+
+        for (direction, destination) in &location.exits {
+            let reason = match self.locations.get(destination) {
+                Some(place) if place.visited => format!("back to {}", place.name),
+                _ => "somewhere you haven't been".to_string(),
+            };
+            actions.push(SuggestedAction::new(ActionKind::Movement, format!("go {}", direction.display_name()), reason));
+        }
+        for name in location.items.iter().map(|id| crate::systems::placed_items::display_name(id)) {
+            actions.push(SuggestedAction::new(ActionKind::Items, format!("take {}", name), "lying here"));
+        }
+        for entry in self.ground.at(&self.current_location) {
+            actions.push(SuggestedAction::new(ActionKind::Items, format!("take {}", entry.item.properties.name), "left here"));
+        }
+        actions
+    }
+}
+
+impl ActionSource for Player {
+    fn available_actions(&self, _situation: &Situation) -> Vec<SuggestedAction> {
+        let mut actions = Vec::new();
+        let state = &self.mental_state;
+        if let Some(crystal) = self.active_crystal().filter(|crystal| crystal.is_usable()) {
+            if state.current_energy > 0 {
+                actions.push(SuggestedAction::new(
+                    ActionKind::Magic,
+                    "cast light",
+                    format!("your {} crystal is ready, {} energy left", crystal.display_name(), state.current_energy),
+                ));
+            }
+        }
+        if (state.current_energy as f32) < state.max_energy as f32 * TIRED_ENERGY || state.fatigue > TIRED_FATIGUE {
+            actions.push(SuggestedAction::new(ActionKind::Magic, "rest", format!("fatigue {}/100, energy {}/{}", state.fatigue, state.current_energy, state.max_energy)));
+        }
+        actions
+    }
+}
+
+/// Everything the given systems offer, listed under headings
+pub fn suggest(situation: &Situation, sources: &[&dyn ActionSource]) -> String {
+    let mut by_kind: BTreeMap<ActionKind, Vec<SuggestedAction>> = BTreeMap::new();
+    for action in sources.iter().flat_map(|source| source.available_actions(situation)) {
+        let actions = by_kind.entry(action.kind).or_default();
+        if !actions.iter().any(|known| known.command == action.command) {
+            actions.push(action);
+        }
+    }
+    if by_kind.is_empty() {
+        return "Nothing in particular comes to mind here. Try 'look' or 'help'.".to_string();
+    }
+
+    let mut output = "=== What You Could Do ===".to_string();
+    for (kind, mut actions) in by_kind {
+        actions.sort_by(|a, b| a.command.cmp(&b.command));
+        output.push_str(&format!("\n\n{}:", kind.heading()));
+        for action in actions.iter().take(ACTIONS_PER_KIND) {
+            output.push_str(&format!("\n  • {} - {}", action.command, action.reason));
+        }
+        if actions.len() > ACTIONS_PER_KIND {
+            output.push_str(&format!("\n  ...and {} more", actions.len() - ACTIONS_PER_KIND));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::world_state::{Direction, Location};
+
+    fn workshop() -> WorldState {
+        let mut world = WorldState::new();
+        let mut location = Location::new("workshop".to_string(), "Workshop".to_string(), "Benches.".to_string());
+        location.items = vec!["quartz_crystal".to_string()];
+        location.exits.insert(Direction::North, "yard".to_string());
+        let mut yard = Location::new("yard".to_string(), "Yard".to_string(), "Cobbles.".to_string());
+        yard.visited = true;
+        world.add_location(location);
+        world.add_location(yard);
+        world.current_location = "workshop".to_string();
+        world
+    }
+
+    #[test]
+    fn test_world_and_player_offer_what_is_at_hand() {
+        let world = workshop();
+        let mut player = Player::new("Tester".to_string());
+        let quest_system = QuestSystem::new();
+        let faction_system = FactionSystem::new();
+        let situation = Situation { player: &player, world: &world, quest_system: &quest_system, faction_system: &faction_system };
+
+        let actions = world.available_actions(&situation);
+        assert!(actions.contains(&SuggestedAction::new(ActionKind::Movement, "go north", "back to Yard")));
+        assert!(actions.contains(&SuggestedAction::new(ActionKind::Items, "take quartz crystal", "lying here")));
+        assert!(player.available_actions(&situation).iter().any(|action| action.command == "cast light"));
+
+        player.mental_state.fatigue = 80;
+        let situation = Situation { player: &player, world: &world, quest_system: &quest_system, faction_system: &faction_system };
+        assert!(player.available_actions(&situation).iter().any(|action| action.command == "rest"));
+    }
+
+    #[test]
+    fn test_suggestions_are_listed_under_headings() {
+        let world = workshop();
+        let player = Player::new("Tester".to_string());
+        let quest_system = QuestSystem::new();
+        let faction_system = FactionSystem::new();
+        let situation = Situation { player: &player, world: &world, quest_system: &quest_system, faction_system: &faction_system };
+
+        let output = suggest(&situation, &[&world, &world]);
+        assert!(output.starts_with("=== What You Could Do ===\n\nItems:\n  • take quartz crystal - lying here"));
+        assert_eq!(output.matches("go north").count(), 1);
+        assert_eq!(suggest(&situation, &[]), "Nothing in particular comes to mind here. Try 'look' or 'help'.");
+    }
+}
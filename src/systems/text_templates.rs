@@ -0,0 +1,167 @@
+//! Dialogue text templates
+//!
+//! Dialogue lines may refer to the player and their game with variables in
+//! braces, filled in when the line is spoken:
+//! - `{player}`: the player's name
+//! - `{quest}`: the quest the player most recently took on
+//! - `{time}`: the time of day, such as "evening"
+//! - `{theory:<id>}`: the player's understanding of a theory, as a percentage
+//!
+//! A line starting with `@` is a localization key rather than text, such as
+//! `@lyra.greeting`, optionally followed by `|` and the text to fall back on
+//! when the current locale has no entry for it. Content packs ship their
+//! translations in the `localized_text` table, so dialogue can be translated
+//! without code changes, and translated text may use the same variables.
+
+use crate::core::world_state::{TimeOfDay, WorldState};
+use crate::core::Player;
+use crate::systems::quests::{QuestStatus, QuestSystem};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Locale used when none is chosen; content is written in it
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// What `{quest}` reads as when the player has no quest in progress
+const NO_QUEST: &str = "your studies";
+
+/// Translated lines for one locale, by localization key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Localization {
+    locale: String,
+    strings: HashMap<String, String>,
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOCALE, HashMap::new())
+    }
+}
+
+impl Localization {
+    pub fn new(locale: &str, strings: HashMap<String, String>) -> Self {
+        Self { locale: locale.to_string(), strings }
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// The text of a line: a localization key is looked up in this locale,
+    /// falling back on the text after `|`, or the key itself if there is none
+    pub fn resolve<'a>(&'a self, line: &'a str) -> &'a str {
+        let Some(key) = line.strip_prefix('@') else {
+            return line;
+        };
+        let (key, fallback) = match key.split_once('|') {
+            Some((key, fallback)) => (key.trim(), Some(fallback.trim())),
+            None => (key.trim(), None),
+        };
+        self.strings.get(key).map(String::as_str).or(fallback).unwrap_or(key)
+    }
+}
+
+/// Values for the variables dialogue lines may use
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextVariables {
+    pub player: String,
+    /// Title of the quest most recently taken on that is still in progress
+    pub quest: Option<String>,
+    pub time: String,
+    /// Understanding of each theory the player has studied (0.0-1.0)
+    pub theories: HashMap<String, f32>,
+}
+
+impl TextVariables {
+    /// Gather the variables from the current state of the game
+    pub fn gather(player: &Player, world: &WorldState, quest_system: &QuestSystem) -> Self {
+        let quest = quest_system.player_progress.values()
+            .filter(|progress| progress.status == QuestStatus::InProgress)
+            .max_by_key(|progress| progress.started_at)
+            .and_then(|progress| quest_system.quest_definitions.get(&progress.quest_id))
+            .map(|definition| definition.title.clone());
+        let theories = player.knowledge.theories.keys()
+            .chain(player.knowledge.theory_progress.keys())
+            .map(|theory| (theory.clone(), player.theory_understanding(theory)))
+            .collect();
+
+        Self {
+            player: player.name.clone(),
+            quest,
+            time: TimeOfDay::from_hour(world.hour_of_day()).name().to_string(),
+            theories,
+        }
+    }
+
+    /// Fill in the variables a line uses; anything in braces that isn't a
+    /// known variable, or has no value yet, is left as written
+    pub fn fill(&self, text: &str) -> String {
+        let mut filled = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(open) = rest.find('{') {
+            filled.push_str(&rest[..open]);
+            let after = &rest[open + 1..];
+            let Some(close) = after.find('}') else {
+                rest = &rest[open..];
+                break;
+            };
+            let name = &after[..close];
+            match self.value(name) {
+                Some(value) => filled.push_str(&value),
+                None => filled.push_str(&rest[open..open + close + 2]),
+            }
+            rest = &after[close + 1..];
+        }
+        filled.push_str(rest);
+        filled
+    }
+
+    fn value(&self, name: &str) -> Option<String> {
+        match name {
+            "player" if !self.player.is_empty() => Some(self.player.clone()),
+            "quest" => Some(self.quest.clone().unwrap_or_else(|| NO_QUEST.to_string())),
+            "time" if !self.time.is_empty() => Some(self.time.clone()),
+            _ => {
+                let theory = name.strip_prefix("theory:")?;
+                Some(format!("{:.0}%", self.theories.get(theory).copied().unwrap_or(0.0) * 100.0))
+            }
+        }
+    }
+}
+
+/// Render a dialogue line: resolve it in the locale, then fill in its variables
+pub fn render(line: &str, localization: &Localization, variables: &TextVariables) -> String {
+    variables.fill(localization.resolve(line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_are_localized_and_filled_in() {
+        let variables = TextVariables {
+            player: "Elara".to_string(),
+            quest: Some("Resonance Foundation".to_string()),
+            time: "evening".to_string(),
+            theories: HashMap::from([("harmonic_fundamentals".to_string(), 0.42)]),
+        };
+        let localization = Localization::new("fr", HashMap::from([
+            ("lyra.greeting".to_string(), "Bonsoir, {player}. Toujours sur {quest}?".to_string()),
+        ]));
+
+        assert_eq!(render("@lyra.greeting", &localization, &variables), "Bonsoir, Elara. Toujours sur Resonance Foundation?");
+        assert_eq!(render("@lyra.farewell|Good {time}, {player}.", &localization, &variables), "Good evening, Elara.");
+        assert_eq!(render("@lyra.farewell", &localization, &variables), "lyra.farewell");
+        assert_eq!(
+            render("You grasp {theory:harmonic_fundamentals} of it, {theory:light_manipulation} of the rest.", &Localization::default(), &variables),
+            "You grasp 42% of it, 0% of the rest."
+        );
+
+        // Unknown or unset variables and stray braces stay as written
+        assert_eq!(variables.fill("{mood} {unclosed"), "{mood} {unclosed");
+        assert_eq!(TextVariables::default().fill("Hello, {player}."), "Hello, {player}.");
+        let idle = TextVariables { quest: None, ..variables };
+        assert_eq!(idle.fill("Still busy with {quest}?"), "Still busy with your studies?");
+    }
+}
@@ -0,0 +1,161 @@
+//! Buffs and debuffs that last for a stretch of game time
+//!
+//! Consumables such as tonics raise or lower an attribute for a number of
+//! minutes. The change is applied as soon as the item is used and an entry is
+//! kept in the world; once game time passes its expiry the change is undone.
+//! Effects belong to the character who used the item, so a co-op apprentice's
+//! tonic wears off on the apprentice rather than the lead.
+
+use crate::core::Player;
+use crate::systems::items::ItemEffect;
+use serde::{Deserialize, Serialize};
+
+/// Attributes a timed effect can change, with their display names
+const ATTRIBUTES: [(&str, &str); 2] = [
+    ("mental_acuity", "Mental Acuity"),
+    ("resonance_sensitivity", "Resonance Sensitivity"),
+];
+
+/// Display name of an attribute, if effects can change it
+pub fn attribute_name(attribute: &str) -> Option<&'static str> {
+    ATTRIBUTES.iter().find(|(id, _)| *id == attribute).map(|(_, name)| *name)
+}
+
+/// Change one of the player's attributes, returning false for unknown attributes
+pub fn adjust_attribute(player: &mut Player, attribute: &str, amount: i32) -> bool {
+    match attribute {
+        "mental_acuity" => player.attributes.mental_acuity += amount,
+        "resonance_sensitivity" => player.attributes.resonance_sensitivity += amount,
+        _ => return false,
+    }
+    true
+}
+
+/// Timed attribute changes within an item effect, as (attribute, amount, minutes)
+pub fn attribute_boosts(effect: &ItemEffect) -> Vec<(String, i32, i32)> {
+    match effect {
+        ItemEffect::TemporaryAttributeBoost { attribute, amount, duration } if attribute_name(attribute).is_some() => {
+            vec![(attribute.clone(), *amount, *duration)]
+        }
+        ItemEffect::Multiple(effects) => effects.iter().flat_map(attribute_boosts).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// An attribute change waiting to wear off
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedEffect {
+    /// Name of the character affected
+    pub owner: String,
+    /// What caused it, e.g. the item used
+    pub source: String,
+    pub attribute: String,
+    /// Change applied; negative for a debuff
+    pub amount: i32,
+    /// Game time (minutes) at which it wears off
+    pub expires_at: i32,
+}
+
+impl TimedEffect {
+    fn remaining(&self, now: i32) -> i32 {
+        (self.expires_at - now).max(0)
+    }
+}
+
+/// Every timed effect in play, saved with the world
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimedEffects {
+    pub active: Vec<TimedEffect>,
+}
+
+impl TimedEffects {
+    /// Track an attribute change the player already received, undoing it after `minutes`
+    pub fn track(&mut self, player: &Player, source: &str, attribute: &str, amount: i32, minutes: i32, now: i32) {
+        self.active.push(TimedEffect {
+            owner: player.name.clone(),
+            source: source.to_string(),
+            attribute: attribute.to_string(),
+            amount,
+            expires_at: now + minutes.max(1),
+        });
+    }
+
+    /// Apply an attribute change to the player for a number of minutes
+    pub fn apply(&mut self, player: &mut Player, source: &str, attribute: &str, amount: i32, minutes: i32, now: i32) -> bool {
+        if !adjust_attribute(player, attribute, amount) {
+            return false;
+        }
+        self.track(player, source, attribute, amount, minutes, now);
+        true
+    }
+
+    /// Undo the player's effects that have run out, describing each
+    pub fn expire(&mut self, player: &mut Player, now: i32) -> Vec<String> {
+        let (expired, active): (Vec<TimedEffect>, Vec<TimedEffect>) = std::mem::take(&mut self.active)
+            .into_iter()
+            .partition(|effect| effect.owner == player.name && effect.expires_at <= now);
+        self.active = active;
+
+        expired.into_iter()
+            .filter(|effect| adjust_attribute(player, &effect.attribute, -effect.amount))
+            .map(|effect| format!(
+                "The {} wears off ({} {:+}).",
+                effect.source,
+                attribute_name(&effect.attribute).unwrap_or(&effect.attribute),
+                -effect.amount
+            ))
+            .collect()
+    }
+
+    /// Lines for the status screen listing the player's effects and their remaining time
+    pub fn summary(&self, player: &Player, now: i32) -> Option<String> {
+        let lines: Vec<String> = self.active.iter()
+            .filter(|effect| effect.owner == player.name)
+            .map(|effect| format!(
+                "  {} {:+} ({}) - {} min left\n",
+                attribute_name(&effect.attribute).unwrap_or(&effect.attribute),
+                effect.amount,
+                effect.source,
+                effect.remaining(now)
+            ))
+            .collect();
+        if lines.is_empty() { None } else { Some(lines.concat()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boosts_wear_off_when_their_time_runs_out() {
+        let mut player = Player::new("Ada".to_string());
+        let base = player.attributes.mental_acuity;
+        let mut effects = TimedEffects::default();
+
+        assert!(effects.apply(&mut player, "focus tonic", "mental_acuity", 5, 30, 100));
+        assert!(effects.apply(&mut player, "dulling draught", "resonance_sensitivity", -3, 60, 100));
+        assert!(!effects.apply(&mut player, "odd brew", "luck", 5, 30, 100));
+        assert_eq!(player.attributes.mental_acuity, base + 5);
+
+        let summary = effects.summary(&player, 110).unwrap();
+        assert!(summary.contains("Mental Acuity +5 (focus tonic) - 20 min left"));
+        assert!(summary.contains("Resonance Sensitivity -3"));
+
+        // Another character's turn doesn't expire Ada's effects
+        let mut apprentice = Player::new("Bo".to_string());
+        assert!(effects.expire(&mut apprentice, 500).is_empty());
+
+        assert!(effects.expire(&mut player, 129).is_empty());
+        let messages = effects.expire(&mut player, 130);
+        assert_eq!(messages, vec!["The focus tonic wears off (Mental Acuity -5).".to_string()]);
+        assert_eq!(player.attributes.mental_acuity, base);
+        assert_eq!(effects.active.len(), 1);
+
+        let boosts = attribute_boosts(&ItemEffect::Multiple(vec![
+            ItemEffect::RestoreEnergy(5),
+            ItemEffect::TemporaryAttributeBoost { attribute: "mental_acuity".to_string(), amount: 2, duration: 15 },
+        ]));
+        assert_eq!(boosts, vec![("mental_acuity".to_string(), 2, 15)]);
+    }
+}
@@ -0,0 +1,157 @@
+//! Bounties the factions put on the player
+//!
+//! Being caught where the player shouldn't be, or setting off a faction's
+//! wards, is an offence against that faction. Each offence adds to the bounty
+//! the faction holds against the player, and costs standing with them. While
+//! a bounty stands, the faction's people watch for the player: their guards
+//! are quicker to spot them sneaking about. Paying a bounty off in silver
+//! clears it, though the standing stays lost.
+
+use crate::core::Player;
+use crate::systems::factions::FactionId;
+use crate::GameResult;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Added to a guard's chance of spotting the player for each silver of bounty
+/// their faction holds
+pub const WATCHFULNESS_PER_SILVER: f32 = 0.005;
+/// Most a bounty can add to a guard's chance of spotting the player
+const MAX_WATCHFULNESS: f32 = 0.3;
+/// Offences remembered for the wanted listing
+const OFFENCE_LOG_LENGTH: usize = 10;
+
+/// Something the player was caught doing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Offence {
+    pub faction: FactionId,
+    pub description: String,
+    pub bounty: i32,
+    /// Game time in minutes
+    pub at: i32,
+}
+
+/// Bounties by faction, saved with the world
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Wanted {
+    #[serde(
+        default,
+        serialize_with = "crate::systems::serde_helpers::serialize_faction_map",
+        deserialize_with = "crate::systems::serde_helpers::deserialize_faction_map"
+    )]
+    bounties: HashMap<FactionId, i32>,
+    #[serde(default)]
+    offences: Vec<Offence>,
+}
+
+impl Wanted {
+    /// The bounty a faction holds against the player
+    pub fn bounty(&self, faction: FactionId) -> i32 {
+        self.bounties.get(&faction).copied().unwrap_or(0)
+    }
+
+    /// How much more watchful a faction's guards are for the player
+    pub fn watchfulness(&self, faction: FactionId) -> f32 {
+        (self.bounty(faction) as f32 * WATCHFULNESS_PER_SILVER).min(MAX_WATCHFULNESS)
+    }
+
+    /// Charge the player with an offence: raise the faction's bounty and cost
+    /// standing with them, returning what the player is told
+    pub fn charge(&mut self, player: &mut Player, faction: FactionId, bounty: i32, standing: i32, description: &str, now: i32) -> String {
+        *self.bounties.entry(faction).or_insert(0) += bounty;
+        player.modify_faction_reputation(faction, -standing);
+        self.offences.push(Offence { faction, description: description.to_string(), bounty, at: now });
+        let excess = self.offences.len().saturating_sub(OFFENCE_LOG_LENGTH);
+        self.offences.drain(..excess);
+        format!(
+            "The {} put a bounty of {} silver on you for {}. (-{} standing; bounty now {} silver)",
+            faction.display_name(), bounty, description, standing, self.bounty(faction)
+        )
+    }
+
+    /// Pay off a faction's bounty in silver
+    pub fn pay(&mut self, player: &mut Player, faction: FactionId) -> GameResult<String> {
+        let bounty = self.bounty(faction);
+        if bounty == 0 {
+            return Err(crate::GameError::InvalidCommand(format!("The {} hold no bounty against you.", faction.display_name())).into());
+        }
+        if player.inventory.silver < bounty {
+            return Err(crate::GameError::InsufficientResources(format!(
+                "The {} want {} silver to clear your name; you have {}.", faction.display_name(), bounty, player.inventory.silver
+            )).into());
+        }
+        player.inventory.silver -= bounty;
+        self.bounties.remove(&faction);
+        Ok(format!("You pay the {} {} silver, and the bounty on you is lifted.", faction.display_name(), bounty))
+    }
+
+    /// List standing bounties and recent offences
+    pub fn describe(&self) -> String {
+        let mut factions: Vec<(FactionId, i32)> = self.bounties.iter()
+            .filter(|(_, bounty)| **bounty > 0)
+            .map(|(faction, bounty)| (*faction, *bounty))
+            .collect();
+        if factions.is_empty() {
+            return "No faction has a bounty on you.".to_string();
+        }
+        factions.sort_by_key(|(faction, _)| faction.display_name().to_string());
+
+        let mut lines = vec!["=== WANTED ===".to_string(), String::new()];
+        for (faction, bounty) in factions {
+            lines.push(format!("• {}: {} silver ('pay bounty {}')", faction.display_name(), bounty, faction.short_name().to_lowercase()));
+        }
+        lines.push(String::new());
+        lines.push("Recent offences:".to_string());
+        for offence in self.offences.iter().rev() {
+            lines.push(format!("  • {} ({} silver, {})", offence.description, offence.bounty, offence.faction.display_name()));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Find a faction by any of its names
+pub fn faction_named(name: &str) -> Option<FactionId> {
+    let name = name.trim().trim_start_matches("the ").to_lowercase();
+    FactionId::all().into_iter().find(|faction| {
+        [faction.display_name(), faction.short_name(), faction.key()].iter()
+            .any(|candidate| candidate.to_lowercase() == name.replace('_', " ") || candidate.to_lowercase() == name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offences_raise_bounties_and_watchfulness() {
+        let mut wanted = Wanted::default();
+        let mut player = Player::new("Tester".to_string());
+        let council = FactionId::MagistersCouncil;
+        assert_eq!(wanted.watchfulness(council), 0.0);
+
+        let message = wanted.charge(&mut player, council, 20, 5, "trespassing", 0);
+        assert!(message.contains("bounty of 20 silver"));
+        wanted.charge(&mut player, council, 100, 5, "setting off a ward", 10);
+        assert_eq!(wanted.bounty(council), 120);
+        assert_eq!(wanted.watchfulness(council), MAX_WATCHFULNESS);
+        assert_eq!(player.faction_reputation(council), -10);
+        assert!(wanted.describe().contains("setting off a ward"));
+    }
+
+    #[test]
+    fn test_paying_off_a_bounty() {
+        let mut wanted = Wanted::default();
+        let mut player = Player::new("Tester".to_string());
+        let scholars = faction_named("the neutral scholars").unwrap();
+        assert_eq!(faction_named("council"), Some(FactionId::MagistersCouncil));
+        assert!(wanted.pay(&mut player, scholars).is_err());
+
+        wanted.charge(&mut player, scholars, 30, 2, "trespassing", 0);
+        player.inventory.silver = 10;
+        assert!(wanted.pay(&mut player, scholars).is_err());
+        player.inventory.silver = 40;
+        assert!(wanted.pay(&mut player, scholars).is_ok());
+        assert_eq!((wanted.bounty(scholars), player.inventory.silver), (0, 10));
+        assert_eq!(wanted.describe(), "No faction has a bounty on you.");
+    }
+}
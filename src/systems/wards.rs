@@ -0,0 +1,225 @@
+//! Preparatory wards raised before an expected fight
+//!
+//! Wards are sustained spells that take too long to raise once blows are
+//! being traded. A shield ward is worn by the player and holds a number of
+//! charges, each soaking part of one hit; the whole ward is spent in the next
+//! fight. A resonance dampener is anchored to a location and weakens every
+//! enemy fought there until it fades. Both are cast through the magic
+//! system, so their strength follows the power of the cast.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Damage a single shield charge can absorb
+const SHIELD_ABSORB_PER_CHARGE: i32 = 10;
+/// Most charges a shield ward can hold
+const MAX_SHIELD_CHARGES: u32 = 4;
+/// Minutes a shield ward lasts before unravelling
+const SHIELD_DURATION_MINUTES: i32 = 120;
+/// Minutes a dampener lasts at its location
+const DAMPENER_DURATION_MINUTES: i32 = 240;
+/// Largest share of enemy damage a dampener can cancel
+const MAX_DAMPENING: f32 = 0.4;
+
+/// Kinds of ward
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WardKind {
+    /// Charges worn by the player that soak incoming hits
+    Shield,
+    /// Counter-resonance anchored to a location that weakens enemy attacks
+    Dampener,
+}
+
+impl WardKind {
+    /// Parse a ward name
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "shield" | "shield charges" | "charges" | "self" | "me" => Some(WardKind::Shield),
+            "dampener" | "resonance dampener" | "dampen" | "here" | "location" => Some(WardKind::Dampener),
+            _ => None,
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            WardKind::Shield => "shield ward",
+            WardKind::Dampener => "resonance dampener",
+        }
+    }
+
+    /// Spell type the ward is cast with
+    pub fn spell(&self) -> &'static str {
+        match self {
+            WardKind::Shield => "manipulation",
+            WardKind::Dampener => "detection",
+        }
+    }
+}
+
+/// A ward waiting for a fight
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ward {
+    pub kind: WardKind,
+    /// Power level of the cast that raised it
+    pub power: f32,
+    /// Game time (minutes) at which the ward fades
+    pub expires_at: i32,
+}
+
+impl Ward {
+    /// Shield charges held by a shield ward of this power
+    pub fn charges(&self) -> u32 {
+        (1 + (self.power * 3.0).round() as u32).min(MAX_SHIELD_CHARGES)
+    }
+
+    /// Share of enemy damage a dampener of this power cancels
+    pub fn dampening(&self) -> f32 {
+        (0.15 + 0.2 * self.power).min(MAX_DAMPENING)
+    }
+
+    fn describe(&self, now: i32) -> String {
+        let effect = match self.kind {
+            WardKind::Shield => format!("{} charges of up to {} damage each", self.charges(), SHIELD_ABSORB_PER_CHARGE),
+            WardKind::Dampener => format!("enemy damage -{:.0}%", self.dampening() * 100.0),
+        };
+        format!("{} ({}), fades in {} minutes", self.kind.name(), effect, self.expires_at - now)
+    }
+}
+
+/// Wards carried into a fight
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CombatWards {
+    pub shield_charges: u32,
+    /// Share of enemy damage cancelled
+    pub dampening: f32,
+}
+
+impl CombatWards {
+    /// Whether any ward is in effect
+    pub fn is_active(&self) -> bool {
+        self.shield_charges > 0 || self.dampening > 0.0
+    }
+
+    /// Multiplier on the enemy's outgoing damage
+    pub fn damage_multiplier(&self) -> f32 {
+        1.0 - self.dampening
+    }
+
+    /// Spend a shield charge against a hit, returning the damage absorbed
+    pub fn absorb(&mut self, damage: i32) -> i32 {
+        if self.shield_charges == 0 || damage <= 0 {
+            return 0;
+        }
+        self.shield_charges -= 1;
+        damage.min(SHIELD_ABSORB_PER_CHARGE)
+    }
+
+    /// Short description for combat status lines
+    pub fn summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if self.shield_charges > 0 {
+            parts.push(format!("shield ward ({} charge{})", self.shield_charges, if self.shield_charges == 1 { "" } else { "s" }));
+        }
+        if self.dampening > 0.0 {
+            parts.push(format!("resonance dampener (-{:.0}% enemy damage)", self.dampening * 100.0));
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+/// Wards the player has prepared, saved with the world
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WardState {
+    /// Shield ward worn by the player
+    pub personal: Option<Ward>,
+    /// Dampeners by location id
+    pub placed: HashMap<String, Ward>,
+}
+
+impl WardState {
+    /// Raise a ward from a successful cast, replacing any of the same kind
+    pub fn raise(&mut self, kind: WardKind, power: f32, location: &str, now: i32) -> String {
+        let duration = match kind {
+            WardKind::Shield => SHIELD_DURATION_MINUTES,
+            WardKind::Dampener => DAMPENER_DURATION_MINUTES,
+        };
+        let ward = Ward { kind, power, expires_at: now + duration };
+        let description = ward.describe(now);
+        let replaced = match kind {
+            WardKind::Shield => self.personal.replace(ward).is_some(),
+            WardKind::Dampener => self.placed.insert(location.to_string(), ward).is_some(),
+        };
+
+        format!(
+            "You {} a {}.",
+            if replaced { "renew your" } else { "raise" },
+            description
+        )
+    }
+
+    /// Drop wards that have faded
+    pub fn prune(&mut self, now: i32) {
+        if self.personal.as_ref().is_some_and(|ward| ward.expires_at <= now) {
+            self.personal = None;
+        }
+        self.placed.retain(|_, ward| ward.expires_at > now);
+    }
+
+    /// Wards that apply to a fight starting here
+    ///
+    /// The shield ward is spent on the fight; a dampener stays anchored for
+    /// later fights until it fades.
+    pub fn take_for_combat(&mut self, location: &str, now: i32) -> CombatWards {
+        self.prune(now);
+        CombatWards {
+            shield_charges: self.personal.take().map_or(0, |ward| ward.charges()),
+            dampening: self.placed.get(location).map_or(0.0, Ward::dampening),
+        }
+    }
+
+    /// Wards currently held, for the `wards` command
+    pub fn report(&mut self, location: &str, now: i32) -> String {
+        self.prune(now);
+        let mut output = String::from("=== WARDS ===\n");
+        match &self.personal {
+            Some(ward) => output.push_str(&format!("On you: {}\n", ward.describe(now))),
+            None => output.push_str("On you: none\n"),
+        }
+        match self.placed.get(location) {
+            Some(ward) => output.push_str(&format!("Here: {}\n", ward.describe(now))),
+            None => output.push_str("Here: none\n"),
+        }
+        let elsewhere = self.placed.keys().filter(|id| id.as_str() != location).count();
+        if elsewhere > 0 {
+            output.push_str(&format!("Dampeners elsewhere: {}\n", elsewhere));
+        }
+        output.push_str("\nPrepare with 'ward shield' (on yourself) or 'ward dampener' (on this location).");
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wards_carry_into_combat() {
+        let mut wards = WardState::default();
+        assert!(wards.raise(WardKind::Shield, 0.6, "grove", 0).contains("3 charges"));
+        assert!(wards.raise(WardKind::Dampener, 0.5, "grove", 0).starts_with("You raise a resonance dampener"));
+
+        let mut combat = wards.take_for_combat("grove", 60);
+        assert_eq!(combat.shield_charges, 3);
+        assert!((combat.damage_multiplier() - 0.75).abs() < 0.001);
+        assert_eq!(combat.absorb(25), SHIELD_ABSORB_PER_CHARGE);
+        assert_eq!(combat.absorb(4), 4);
+        assert_eq!(combat.summary().unwrap(), "shield ward (1 charge), resonance dampener (-25% enemy damage)");
+
+        // The shield is spent; the dampener stays until it fades
+        let next = wards.take_for_combat("grove", 120);
+        assert_eq!(next.shield_charges, 0);
+        assert!(next.dampening > 0.0);
+        assert!(!wards.take_for_combat("grove", DAMPENER_DURATION_MINUTES).is_active());
+    }
+}
@@ -0,0 +1,226 @@
+//! Weather, mundane and magical
+//!
+//! Each region keeps its own weather, which turns every
+//! `WEATHER_HOURS` hours. What comes next is rolled from the run seed with
+//! odds that follow the season and the region: the heights catch the storms,
+//! and the outskirts, downwind of the Unstable Resonance Site, see far more
+//! magical weather than anywhere else. Magical weather comes in two kinds:
+//! - Resonance storms: wild resonance that throws all magic off
+//! - Null fogs: a grey stillness in which magic is muffled almost to nothing
+//!
+//! Bad weather slows travel and gets in the way of magic, but magical weather
+//! is also a rare chance to watch resonance behave oddly, so observation
+//! learning goes further while it lasts. The Observatory's arrays can see
+//! weather coming; reading them takes training in Detection Arrays, and the
+//! better the player's understanding, the further and more precisely they
+//! can forecast.
+
+use crate::core::calendar::{Date, MINUTES_PER_DAY};
+use crate::core::overworld::Region;
+use crate::core::world_state::{Season, Weather};
+use crate::systems::knowledge::{LearningActivity, LearningMethod};
+
+/// Hours each spell of weather lasts
+pub const WEATHER_HOURS: i32 = 6;
+/// Location with the arrays that can forecast the weather
+pub const FORECAST_LOCATION: &str = "resonance_observatory";
+/// Theory used to read the forecast
+pub const FORECAST_THEORY: &str = "detection_arrays";
+/// Understanding needed to see the next spell of weather coming
+const STUDENT_UNDERSTANDING: f32 = 0.2;
+/// Understanding needed to tell magical weather apart and forecast a full day
+const EXPERT_UNDERSTANDING: f32 = 0.5;
+/// Spells of weather an expert can forecast
+const FORECAST_SPELLS: i32 = MINUTES_PER_DAY / (WEATHER_HOURS * 60);
+
+/// How much more likely a kind of weather is in a region than the season's odds
+fn bias(region: Region, weather: Weather) -> u32 {
+    match (region, weather) {
+        (Region::Heights, Weather::Stormy | Weather::ResonanceStorm) => 2,
+        (Region::Outskirts, Weather::ResonanceStorm | Weather::NullFog) => 4,
+        (Region::Quarries, Weather::Foggy) => 2,
+        _ => 1,
+    }
+}
+
+/// The season's odds of each kind of weather
+fn season_odds(season: Season) -> [(Weather, u32); 7] {
+    let [clear, cloudy, rainy, stormy, foggy, storm, fog] = match season {
+        Season::Spring => [30, 25, 25, 5, 10, 3, 2],
+        Season::Summer => [45, 20, 10, 12, 3, 6, 4],
+        Season::Autumn => [25, 25, 20, 8, 15, 4, 3],
+        Season::Winter => [30, 30, 10, 5, 15, 3, 7],
+    };
+    [
+        (Weather::Clear, clear),
+        (Weather::Cloudy, cloudy),
+        (Weather::Rainy, rainy),
+        (Weather::Stormy, stormy),
+        (Weather::Foggy, foggy),
+        (Weather::ResonanceStorm, storm),
+        (Weather::NullFog, fog),
+    ]
+}
+
+/// The weather in a region at a game time
+///
+/// The run always opens on a clear morning.
+pub fn weather_at(region: Region, minutes: i32, seed: u64) -> Weather {
+    let spell = minutes.div_euclid(WEATHER_HOURS * 60);
+    if spell <= 0 {
+        return Weather::Clear;
+    }
+
+    let odds = season_odds(Date::at(minutes).season).map(|(weather, odds)| (weather, odds * bias(region, weather)));
+    let total: u32 = odds.iter().map(|(_, odds)| odds).sum();
+    let mix = (seed ^ (spell as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (region as u64).wrapping_mul(0x94D0_49BB_1331_11EB))
+        .wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    let mut roll = ((mix >> 33) % total as u64) as u32;
+    for (weather, odds) in odds {
+        if roll < odds {
+            return weather;
+        }
+        roll -= odds;
+    }
+    Weather::Clear
+}
+
+/// Extra minutes the weather adds to a walk between locations
+pub fn travel_delay(weather: Weather) -> i32 {
+    match weather {
+        Weather::Clear | Weather::Cloudy => 0,
+        Weather::Rainy | Weather::Foggy => 1,
+        Weather::Stormy | Weather::NullFog => 2,
+        Weather::ResonanceStorm => 3,
+    }
+}
+
+/// Bonus to observation learning while the weather lasts
+pub fn observation_bonus(weather: Weather) -> Option<f32> {
+    match weather {
+        Weather::ResonanceStorm => Some(0.3),
+        Weather::NullFog => Some(0.15),
+        _ => None,
+    }
+}
+
+/// Make the most of magical weather in an observation session
+pub fn assist(activity: &mut LearningActivity, weather: Weather) {
+    if activity.method != LearningMethod::Observation {
+        return;
+    }
+    if let Some(bonus) = observation_bonus(weather) {
+        activity.understanding_gained *= 1.0 + bonus;
+        activity.side_effects.push(format!(
+            "With {} overhead, resonance is doing things you rarely get to watch (+{:.0}%)", weather.name(), bonus * 100.0
+        ));
+    }
+}
+
+/// What the weather adds to a location's description, if anything
+pub fn sign(weather: Weather) -> Option<&'static str> {
+    match weather {
+        Weather::Clear => None,
+        Weather::Cloudy => Some("Clouds sit low over the city."),
+        Weather::Rainy => Some("Rain drums steadily on every surface."),
+        Weather::Stormy => Some("Thunder rolls overhead, and the wind snatches at anything loose."),
+        Weather::Foggy => Some("Fog has crept in and softened every edge."),
+        Weather::ResonanceStorm => Some("A resonance storm is passing: the air crackles, and every crystal in sight rings out of tune."),
+        Weather::NullFog => Some("A null fog has settled in, a grey stillness in which magic feels muffled and far away."),
+    }
+}
+
+/// Read the arrays' forecast with a given understanding of Detection Arrays
+pub fn forecast(now: i32, seed: u64, understanding: f32) -> String {
+    let mut output = String::from("=== WEATHER FORECAST ===\n");
+    for region in Region::ALL {
+        output.push_str(&format!("Over {} it is {}.\n", region.name(), weather_at(region, now, seed).name()));
+    }
+
+    if understanding < STUDENT_UNDERSTANDING {
+        output.push_str(
+            "\nThe arrays chime with whatever is coming, but you can't read them. Studying magical\n\
+             signature analysis (Detection Arrays) would teach you to forecast the weather."
+        );
+        return output;
+    }
+
+    let spells = if understanding < EXPERT_UNDERSTANDING { 1 } else { FORECAST_SPELLS };
+    let current = now.div_euclid(WEATHER_HOURS * 60);
+    output.push('\n');
+    for ahead in 1..=spells {
+        let start = (current + ahead) * WEATHER_HOURS * 60;
+        let outlook = Region::ALL.iter()
+            .map(|&region| {
+                let weather = weather_at(region, start, seed);
+                let reading = if weather.is_magical() && understanding < EXPERT_UNDERSTANDING {
+                    "unsettled resonance"
+                } else {
+                    weather.name()
+                };
+                format!("{} {}", region.name(), reading)
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        output.push_str(&format!("From {:02}:00: {}\n", start.rem_euclid(MINUTES_PER_DAY) / 60, outlook));
+    }
+
+    if understanding < EXPERT_UNDERSTANDING {
+        output.push_str("With deeper study of Detection Arrays you could forecast further, and tell storm from fog.");
+    } else {
+        output.truncate(output.trim_end().len());
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::overworld::region_of;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_weather_follows_region_and_season() {
+        assert_eq!(weather_at(Region::Outskirts, 0, 7), Weather::Clear);
+
+        // The same seed always brings the same weather
+        let spell = WEATHER_HOURS * 60;
+        for n in 1..50 {
+            assert_eq!(weather_at(Region::Heights, n * spell, 7), weather_at(Region::Heights, n * spell + 30, 7));
+        }
+
+        // Downwind of the site, magical weather is far more common
+        let magical = |region: Region| (1..2000).filter(|&n| weather_at(region, n * spell, 11).is_magical()).count();
+        assert!(magical(Region::Outskirts) > 2 * magical(Region::OldCity));
+        assert_eq!(region_of("unstable_resonance_site"), Region::Outskirts);
+        assert_eq!(region_of("practice_hall"), Region::OldCity);
+    }
+
+    #[test]
+    fn test_magical_weather_helps_observation_and_forecasts_need_study() {
+        let mut activity = LearningActivity {
+            theory_id: "detection_arrays".to_string(),
+            method: LearningMethod::Observation,
+            duration: 60,
+            success_rate: 1.0,
+            experience_gained: 10,
+            understanding_gained: 0.1,
+            resources_used: HashMap::new(),
+            side_effects: Vec::new(),
+        };
+        assist(&mut activity, Weather::Rainy);
+        assert!(activity.side_effects.is_empty());
+        assist(&mut activity, Weather::ResonanceStorm);
+        assert!((activity.understanding_gained - 0.13).abs() < 1e-6);
+        assert!(travel_delay(Weather::ResonanceStorm) > travel_delay(Weather::Rainy));
+
+        let now = WEATHER_HOURS * 60 * 10;
+        assert!(forecast(now, 3, 0.0).contains("can't read them"));
+        let student = forecast(now, 3, 0.3);
+        assert_eq!(student.matches("From ").count(), 1);
+        assert!(student.contains("tell storm from fog"));
+        let expert = forecast(now, 3, 0.6);
+        assert_eq!(expert.matches("From ").count(), FORECAST_SPELLS as usize);
+        assert!(!expert.contains("unsettled"));
+    }
+}
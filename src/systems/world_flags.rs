@@ -0,0 +1,95 @@
+//! World flags shared between quests, dialogue and locations
+//!
+//! A world flag is a named fact about the world, such as `accord_signed`,
+//! kept with the quest system's global state so it is saved with the game.
+//! Quests set flags when they are completed or fail and can require them;
+//! NPCs set flags when asked about a topic and add lines while a flag holds;
+//! locations add to their descriptions while a flag holds. Finishing one
+//! quest can so change what the player sees and hears elsewhere.
+//!
+//! Conditions name a flag that must be set, or with a leading `!`, one that
+//! must not be.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// The flags currently set
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorldFlags {
+    flags: BTreeSet<String>,
+}
+
+/// Text shown only while a condition holds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlaggedText {
+    pub condition: String,
+    pub text: String,
+}
+
+impl FlaggedText {
+    pub fn new(condition: &str, text: &str) -> Self {
+        Self { condition: condition.to_string(), text: text.to_string() }
+    }
+}
+
+impl WorldFlags {
+    /// Set a flag; returns whether it was newly set
+    pub fn set(&mut self, flag: &str) -> bool {
+        self.flags.insert(flag.to_string())
+    }
+
+    /// Clear a flag; returns whether it was set
+    pub fn clear(&mut self, flag: &str) -> bool {
+        self.flags.remove(flag)
+    }
+
+    pub fn is_set(&self, flag: &str) -> bool {
+        self.flags.contains(flag)
+    }
+
+    /// Whether a condition holds
+    pub fn holds(&self, condition: &str) -> bool {
+        match condition.strip_prefix('!') {
+            Some(flag) => !self.is_set(flag),
+            None => self.is_set(condition),
+        }
+    }
+
+    /// Whether every condition holds
+    pub fn all_hold(&self, conditions: &[String]) -> bool {
+        conditions.iter().all(|condition| self.holds(condition))
+    }
+
+    /// The texts whose conditions hold, in order
+    pub fn select<'a>(&'a self, texts: &'a [FlaggedText]) -> impl Iterator<Item = &'a str> + 'a {
+        texts.iter().filter(|text| self.holds(&text.condition)).map(|text| text.text.as_str())
+    }
+
+    /// Every flag set, in order
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.flags.iter().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conditions_and_flagged_text() {
+        let mut flags = WorldFlags::default();
+        assert!(flags.set("accord_signed"));
+        assert!(!flags.set("accord_signed"));
+        assert!(flags.holds("accord_signed"));
+        assert!(flags.holds("!talks_collapsed"));
+        assert!(!flags.all_hold(&["accord_signed".to_string(), "talks_collapsed".to_string()]));
+
+        let texts = [
+            FlaggedText::new("accord_signed", "The accord hangs framed by the door."),
+            FlaggedText::new("!accord_signed", "Draft treaties litter the table."),
+        ];
+        assert_eq!(flags.select(&texts).collect::<Vec<_>>(), vec!["The accord hangs framed by the door."]);
+        assert!(flags.clear("accord_signed"));
+        assert_eq!(flags.select(&texts).collect::<Vec<_>>(), vec!["Draft treaties litter the table."]);
+    }
+}
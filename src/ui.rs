@@ -1,7 +1,410 @@
 use crate::core::{Player, WorldState};
+use crate::systems::quests::QuestSystem;
 use crate::GameResult;
+use serde::{Deserialize, Serialize};
 use std::io::{self, Write};
 
+pub mod tui;
+
+/// How much descriptive prose command output includes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Verbosity {
+    /// Mechanical summaries only; prose trimmed to its first sentence
+    Terse,
+    /// Default balance of description and mechanics
+    #[default]
+    Standard,
+    /// Extra atmospheric and background detail
+    Rich,
+}
+
+impl Verbosity {
+    /// Parse a verbosity profile name
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "terse" | "brief" | "short" => Some(Verbosity::Terse),
+            "standard" | "normal" | "default" => Some(Verbosity::Standard),
+            "rich" | "verbose" | "full" => Some(Verbosity::Rich),
+            _ => None,
+        }
+    }
+
+    /// Display name of the profile
+    pub fn name(&self) -> &'static str {
+        match self {
+            Verbosity::Terse => "terse",
+            Verbosity::Standard => "standard",
+            Verbosity::Rich => "rich",
+        }
+    }
+}
+
+/// Piece of information that can be shown in the input prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PromptToken {
+    /// Short name of the current location
+    Location,
+    /// In-game time of day
+    Time,
+    /// Mental energy
+    Energy,
+    /// Progress on the tracked quest
+    Quest,
+}
+
+impl PromptToken {
+    pub const ALL: [PromptToken; 4] = [PromptToken::Location, PromptToken::Time, PromptToken::Energy, PromptToken::Quest];
+
+    /// Parse a prompt token name
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "location" | "loc" | "place" => Some(PromptToken::Location),
+            "time" | "clock" => Some(PromptToken::Time),
+            "energy" | "mana" => Some(PromptToken::Energy),
+            "quest" | "objective" => Some(PromptToken::Quest),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PromptToken::Location => "location",
+            PromptToken::Time => "time",
+            PromptToken::Energy => "energy",
+            PromptToken::Quest => "quest",
+        }
+    }
+}
+
+/// Colour scheme for styled output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Theme {
+    /// Coloured headers and labels, with flavor text dimmed
+    #[default]
+    Standard,
+    /// Bright colours and no dimming, for low-vision play
+    HighContrast,
+    /// Bold, dim and underline only
+    Monochrome,
+    /// The Solarized palette
+    Solarized,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 4] = [Theme::Standard, Theme::HighContrast, Theme::Monochrome, Theme::Solarized];
+
+    /// Parse a theme name
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().replace(['-', '_'], " ").as_str() {
+            "standard" | "default" | "normal" => Some(Theme::Standard),
+            "high contrast" | "highcontrast" | "contrast" => Some(Theme::HighContrast),
+            "monochrome" | "mono" | "no color" | "no colour" => Some(Theme::Monochrome),
+            "solarized" | "solarised" => Some(Theme::Solarized),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Standard => "standard",
+            Theme::HighContrast => "high-contrast",
+            Theme::Monochrome => "monochrome",
+            Theme::Solarized => "solarized",
+        }
+    }
+
+    /// How the theme shows each part of the output
+    fn style(&self, role: Role) -> Style {
+        let plain = Style::default();
+        match (self, role) {
+            (Theme::Standard, Role::Header) => Style { bold: true, color: Some(Color::Basic(36)), ..plain },
+            (Theme::Standard, Role::Label) => Style { color: Some(Color::Basic(33)), ..plain },
+            (Theme::Standard, Role::Flavor) => Style { dim: true, ..plain },
+            (Theme::Standard, Role::Error) => Style { bold: true, color: Some(Color::Basic(31)), ..plain },
+            (Theme::HighContrast, Role::Header) => Style { bold: true, color: Some(Color::Basic(93)), ..plain },
+            (Theme::HighContrast, Role::Label) => Style { bold: true, color: Some(Color::Basic(96)), ..plain },
+            (Theme::HighContrast, Role::Flavor) => Style { color: Some(Color::Basic(97)), ..plain },
+            (Theme::HighContrast, Role::Error) => Style { bold: true, color: Some(Color::Basic(91)), ..plain },
+            (Theme::Monochrome, Role::Header) => Style { bold: true, ..plain },
+            (Theme::Monochrome, Role::Label) => Style { underline: true, ..plain },
+            (Theme::Monochrome, Role::Flavor) => Style { dim: true, ..plain },
+            (Theme::Monochrome, Role::Error) => Style { bold: true, underline: true, ..plain },
+            (Theme::Solarized, Role::Header) => Style { bold: true, color: Some(Color::Indexed(33, 34)), ..plain },
+            (Theme::Solarized, Role::Label) => Style { color: Some(Color::Indexed(136, 33)), ..plain },
+            (Theme::Solarized, Role::Flavor) => Style { color: Some(Color::Indexed(245, 90)), ..plain },
+            (Theme::Solarized, Role::Error) => Style { bold: true, color: Some(Color::Indexed(160, 31)), ..plain },
+        }
+    }
+}
+
+/// What the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSupport {
+    /// No styling at all: output is piped, the terminal is dumb, or NO_COLOR is set
+    None,
+    /// The sixteen standard colours
+    Basic,
+    /// The 256-colour palette
+    Extended,
+}
+
+impl ColorSupport {
+    /// What standard output can show
+    pub fn detect() -> Self {
+        use std::io::IsTerminal;
+        Self::from_environment(
+            std::env::var("TERM").ok().as_deref(),
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()),
+            io::stdout().is_terminal(),
+        )
+    }
+
+    /// What a terminal described by these settings can show
+    pub fn from_environment(term: Option<&str>, colorterm: Option<&str>, no_color: bool, is_terminal: bool) -> Self {
+        let term = term.unwrap_or_default();
+        if no_color || !is_terminal || term.is_empty() || term == "dumb" {
+            ColorSupport::None
+        } else if term.contains("256color") || matches!(colorterm, Some("truecolor" | "24bit")) {
+            ColorSupport::Extended
+        } else {
+            ColorSupport::Basic
+        }
+    }
+}
+
+/// Part of the output a theme styles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    /// `=== Title ===` and `--- Section ---` lines
+    Header,
+    /// The label before a colon in a line of figures, such as `Exits:`
+    Label,
+    /// Descriptive prose
+    Flavor,
+    /// Error lines
+    Error,
+}
+
+/// A foreground colour: one of the sixteen standard SGR codes, or a
+/// 256-colour palette index with the standard code to fall back on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+    Basic(u8),
+    Indexed(u8, u8),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Style {
+    bold: bool,
+    dim: bool,
+    underline: bool,
+    color: Option<Color>,
+}
+
+impl Style {
+    /// Wrap text in the escape codes for this style
+    fn paint(&self, text: &str, support: ColorSupport) -> String {
+        let mut codes: Vec<String> = Vec::new();
+        if self.bold {
+            codes.push("1".to_string());
+        }
+        if self.dim {
+            codes.push("2".to_string());
+        }
+        if self.underline {
+            codes.push("4".to_string());
+        }
+        match (self.color, support) {
+            (_, ColorSupport::None) | (None, _) => {}
+            (Some(Color::Basic(code)), _) | (Some(Color::Indexed(_, code)), ColorSupport::Basic) => codes.push(code.to_string()),
+            (Some(Color::Indexed(index, _)), ColorSupport::Extended) => codes.push(format!("38;5;{}", index)),
+        }
+        if codes.is_empty() || support == ColorSupport::None || text.is_empty() {
+            return text.to_string();
+        }
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+    }
+}
+
+/// Style command output with a theme, as far as the terminal allows
+///
+/// Headers stand out, labels in lines of figures are coloured, descriptive
+/// paragraphs are set back, and errors are marked. A terminal without colour
+/// gets the text untouched.
+pub fn style_output(text: &str, theme: Theme, support: ColorSupport) -> String {
+    if support == ColorSupport::None {
+        return text.to_string();
+    }
+
+    text.split("\n\n")
+        .map(|paragraph| {
+            if !is_mechanical(paragraph) {
+                return paragraph.lines()
+                    .map(|line| theme.style(Role::Flavor).paint(line, support))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+            }
+            paragraph.lines().map(|line| style_line(line, theme, support)).collect::<Vec<_>>().join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Longest label styled before a colon
+const LABEL_CHARS: usize = 24;
+
+/// Style one line of a summary or statistics block
+fn style_line(line: &str, theme: Theme, support: ColorSupport) -> String {
+    let trimmed = line.trim();
+    if (trimmed.starts_with("===") && trimmed.ends_with("===")) || (trimmed.starts_with("---") && trimmed.ends_with("---")) {
+        return theme.style(Role::Header).paint(line, support);
+    }
+    if trimmed.starts_with("Error:") {
+        return theme.style(Role::Error).paint(line, support);
+    }
+    match line.split_once(": ") {
+        Some((label, rest)) if !label.trim().is_empty()
+            && label.len() <= LABEL_CHARS
+            && !label.trim_start().starts_with(['•', '-', '[', '>']) =>
+        {
+            format!("{}: {}", theme.style(Role::Label).paint(label, support), rest)
+        }
+        _ => line.to_string(),
+    }
+}
+
+/// Player-facing display preferences saved with the character
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DisplayPreferences {
+    pub verbosity: Verbosity,
+    /// Information shown before the input prompt, in order
+    #[serde(default)]
+    pub prompt: Vec<PromptToken>,
+    /// Quest shown by the quest prompt token; the first active quest if unset
+    #[serde(default)]
+    pub tracked_quest: Option<String>,
+    /// Whether the codex shows only this character's own discoveries
+    #[serde(default)]
+    pub hide_codex_spoilers: bool,
+    /// The player's own command shortcuts, by the word typed
+    #[serde(default)]
+    pub shortcuts: std::collections::BTreeMap<String, String>,
+    /// Whether fast travel is off, so every way must be walked by hand
+    #[serde(default)]
+    pub manual_navigation: bool,
+    /// How many actions `undo` can take back; the default if unset
+    #[serde(default)]
+    pub undo_limit: Option<usize>,
+    /// Colour scheme for output, when the terminal can show it
+    #[serde(default)]
+    pub theme: Theme,
+}
+
+/// Longest location name shown in the prompt
+const PROMPT_LOCATION_CHARS: usize = 18;
+/// Longest quest title shown in the prompt
+const PROMPT_QUEST_CHARS: usize = 20;
+
+/// Shorten a name to fit the prompt
+fn shorten(name: &str, limit: usize) -> String {
+    let name = name.strip_prefix("The ").unwrap_or(name);
+    if name.chars().count() <= limit {
+        return name.to_string();
+    }
+    let cut: String = name.chars().take(limit - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// Render the player's chosen prompt tokens, e.g. `[Tutorial Chamber | 08:30] `
+///
+/// Returns an empty string when no tokens are chosen or none has anything to show.
+pub fn prompt_status(player: &Player, world: &WorldState, quest_system: &QuestSystem) -> String {
+    let parts: Vec<String> = player.preferences.prompt.iter()
+        .filter_map(|token| match token {
+            PromptToken::Location => Some(shorten(
+                world.current_location().map_or(world.current_location.as_str(), |location| location.name.as_str()),
+                PROMPT_LOCATION_CHARS,
+            )),
+            PromptToken::Time => Some(format!("{:02}:{:02}", world.hour_of_day(), world.game_time_minutes.rem_euclid(60))),
+            PromptToken::Energy => Some(format!("E {}/{}", player.mental_state.current_energy, player.mental_state.max_energy)),
+            PromptToken::Quest => {
+                let progress = crate::systems::quest_journal::tracked(player, quest_system)?;
+                let quest = quest_system.quest_definitions.get(&progress.quest_id)?;
+                let done = progress.objective_progress.values().filter(|objective| objective.completed).count();
+                Some(format!("{} {}/{}", shorten(&quest.title, PROMPT_QUEST_CHARS), done, quest.objectives.len()))
+            }
+        })
+        .collect();
+
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("[{}] ", parts.join(" | "))
+    }
+}
+
+/// Adapt command output to the chosen verbosity profile
+///
+/// Terse output keeps headers, bullet lists and lines carrying numbers or
+/// labels, and cuts narrative paragraphs down to their first sentence. Rich
+/// output is written richer to begin with, by the commands that have more to
+/// say (the time and weather when looking around, a person's description when
+/// talking to them, an enemy's in combat), so it passes through whole here
+/// as standard output does.
+pub fn apply_verbosity(text: &str, verbosity: Verbosity) -> String {
+    if verbosity != Verbosity::Terse {
+        return text.to_string();
+    }
+
+    text.split("\n\n")
+        .map(|paragraph| {
+            if is_mechanical(paragraph) {
+                paragraph.to_string()
+            } else {
+                first_sentence(paragraph)
+            }
+        })
+        .filter(|paragraph| !paragraph.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Whether a paragraph is a summary/statistics block rather than prose
+fn is_mechanical(paragraph: &str) -> bool {
+    paragraph.lines().count() > 1
+        || paragraph.len() <= 80
+        || paragraph.starts_with("===")
+        || paragraph.starts_with('•')
+        || paragraph.starts_with('-')
+        || paragraph.starts_with('[')
+        || paragraph.chars().any(|c| c.is_ascii_digit())
+}
+
+/// Titles and shorthand whose full stop doesn't end a sentence
+const ABBREVIATIONS: &[&str] = &["dr", "mr", "mrs", "ms", "prof", "st", "mt", "e.g", "i.e", "etc", "vs"];
+
+/// Trim a prose paragraph to its first sentence: up to a full stop, question
+/// or exclamation mark followed by a space and a capital letter, where the
+/// full stop doesn't close an abbreviation such as "Dr."
+fn first_sentence(paragraph: &str) -> String {
+    for (end, mark) in paragraph.char_indices().filter(|(_, c)| matches!(c, '.' | '!' | '?')) {
+        let after = &paragraph[end + mark.len_utf8()..];
+        let next_sentence = after.starts_with(char::is_whitespace)
+            && after.trim_start().starts_with(char::is_uppercase);
+        let word = paragraph[..end]
+            .rsplit(char::is_whitespace)
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        if next_sentence && !(mark == '.' && ABBREVIATIONS.contains(&word.as_str())) {
+            return paragraph[..end + mark.len_utf8()].to_string();
+        }
+    }
+    paragraph.to_string()
+}
+
 pub struct GameUI;
 
 impl GameUI {
@@ -110,6 +513,89 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_verbosity_parsing() {
+        assert_eq!(Verbosity::from_string("TERSE"), Some(Verbosity::Terse));
+        assert_eq!(Verbosity::from_string("verbose"), Some(Verbosity::Rich));
+        assert_eq!(Verbosity::from_string("loud"), None);
+        assert_eq!(Verbosity::default(), Verbosity::Standard);
+    }
+
+    #[test]
+    fn test_terse_trims_prose_but_keeps_mechanics() {
+        let text = "=== Tutorial Chamber ===\n\n\
+                    A simple stone chamber with crystalline formations embedded in the walls. \
+                    Soft light emanates from the crystals, creating an atmosphere of focused learning.\n\n\
+                    Exits: north";
+
+        let terse = apply_verbosity(text, Verbosity::Terse);
+        assert!(terse.contains("=== Tutorial Chamber ==="));
+        assert!(terse.contains("embedded in the walls."));
+        assert!(!terse.contains("Soft light"));
+        assert!(terse.contains("Exits: north"));
+
+        assert_eq!(apply_verbosity(text, Verbosity::Standard), text);
+    }
+
+    #[test]
+    fn test_terse_sentences_end_at_a_capital_after_the_stop() {
+        assert_eq!(
+            first_sentence("Dr. Felix tends the garden lab, e.g. the moss beds. He rarely leaves."),
+            "Dr. Felix tends the garden lab, e.g. the moss beds."
+        );
+        assert_eq!(first_sentence("The seal reads 3.5 hertz. Nothing else."), "The seal reads 3.5 hertz.");
+        assert_eq!(first_sentence("Who goes there? No answer comes."), "Who goes there?");
+        assert_eq!(first_sentence("A single sentence."), "A single sentence.");
+    }
+
+    #[test]
+    fn test_prompt_status_renders_chosen_tokens() {
+        let mut player = Player::new("Test Player".to_string());
+        let mut world = WorldState::new();
+        let quests = QuestSystem::new();
+        assert_eq!(prompt_status(&player, &world, &quests), "");
+
+        world.game_time_minutes = 14 * 60 + 5;
+        player.mental_state.current_energy = 40;
+        player.mental_state.max_energy = 100;
+        player.preferences.prompt = vec![PromptToken::Time, PromptToken::Energy, PromptToken::Quest];
+        assert_eq!(prompt_status(&player, &world, &quests), "[14:05 | E 40/100] ");
+
+        assert_eq!(shorten("The Crystal Garden Laboratory", 18), "Crystal Garden La…");
+        assert_eq!(PromptToken::from_string("LOC"), Some(PromptToken::Location));
+    }
+
+    #[test]
+    fn test_themes_style_output_only_where_colour_is_supported() {
+        let text = "=== Tutorial Chamber ===\n\n\
+                    A simple stone chamber with crystalline formations embedded in the walls, humming softly.\n\n\
+                    Exits: north\n• go north - leave";
+
+        assert_eq!(style_output(text, Theme::Solarized, ColorSupport::None), text);
+        let styled = style_output(text, Theme::Standard, ColorSupport::Basic);
+        assert!(styled.starts_with("\x1b[1;36m=== Tutorial Chamber ===\x1b[0m"));
+        assert!(styled.contains("\x1b[2mA simple stone chamber"));
+        assert!(styled.contains("\x1b[33mExits\x1b[0m: north\n• go north - leave"));
+
+        // Solarized falls back to the standard colours on a basic terminal
+        assert!(style_output("Exits: north", Theme::Solarized, ColorSupport::Extended).contains("\x1b[38;5;136mExits"));
+        assert!(style_output("Exits: north", Theme::Solarized, ColorSupport::Basic).contains("\x1b[33mExits"));
+        assert!(!style_output(text, Theme::Monochrome, ColorSupport::Extended).contains("38;5"));
+        assert!(style_output("Error: no such exit", Theme::HighContrast, ColorSupport::Basic).starts_with("\x1b[1;91m"));
+    }
+
+    #[test]
+    fn test_colour_support_is_downgraded_to_what_the_terminal_allows() {
+        assert_eq!(ColorSupport::from_environment(Some("xterm-256color"), None, false, true), ColorSupport::Extended);
+        assert_eq!(ColorSupport::from_environment(Some("xterm"), Some("truecolor"), false, true), ColorSupport::Extended);
+        assert_eq!(ColorSupport::from_environment(Some("xterm"), None, false, true), ColorSupport::Basic);
+        assert_eq!(ColorSupport::from_environment(Some("dumb"), None, false, true), ColorSupport::None);
+        assert_eq!(ColorSupport::from_environment(Some("xterm-256color"), None, true, true), ColorSupport::None);
+        assert_eq!(ColorSupport::from_environment(Some("xterm-256color"), None, false, false), ColorSupport::None);
+        assert_eq!(Theme::from_string("High-Contrast"), Some(Theme::HighContrast));
+        assert_eq!(Theme::default(), Theme::Standard);
+    }
+
     #[test]
     fn test_default_implementation() {
         let ui1 = GameUI::new();
@@ -0,0 +1,507 @@
+//! Full-screen terminal interface
+//!
+//! An alternative to the plain line-by-line mode, chosen with `--tui`. The
+//! screen is split into panes: the latest narrative on the left above a
+//! scrollable log of the session, a status sidebar and mini-map on the right,
+//! and the command line along the bottom.
+//!
+//! ```text
+//! ┌ Tutorial Chamber ───────────────┐┌ Status ─────────────┐
+//! │ A simple stone chamber...        ││ Energy   45/50      │
+//! ├ Log ────────────────────────────┤│ Fatigue  10%        │
+//! │ > look                           │├ Map ────────────────┤
+//! │ ...                              ││ [?]-[@]             │
+//! └──────────────────────────────────┘└─────────────────────┘
+//! [Tutorial Chamber | 08:00] > _
+//! ```
+//!
+//! The interface is an [`InputSource`]: the game loop hands it output and a
+//! fresh [`StatusPanel`] each turn, and reads commands from it as it would
+//! from the line editor. Output styled with the player's theme keeps its
+//! colours, so themes apply here too.
+
+use crate::core::{map, Player, WorldState};
+use crate::input::input_source::{InputLine, InputSource};
+use crate::systems::quests::QuestSystem;
+use crate::GameResult;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::{Frame, Terminal};
+use std::io::{self, Stdout};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Width of the status and map column
+const SIDEBAR_WIDTH: u16 = 34;
+/// Entries kept in the log before the oldest are dropped
+const LOG_LINES: usize = 2000;
+/// Log lines scrolled by Page Up and Page Down
+const SCROLL_STEP: usize = 10;
+
+/// What the sidebar shows, gathered from the game each turn
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatusPanel {
+    pub location: String,
+    pub time: String,
+    pub energy: (i32, i32),
+    pub fatigue: i32,
+    /// The active crystal's name and integrity
+    pub crystal: Option<(String, f32)>,
+    /// The tracked quest, or else the first one under way, with objectives
+    /// done and objectives in all
+    pub quest: Option<(String, usize, usize)>,
+    /// The explored map around the player, one row per line
+    pub map: Vec<String>,
+}
+
+impl StatusPanel {
+    pub fn gather(player: &Player, world: &WorldState, quest_system: &QuestSystem) -> Self {
+        let quest = crate::systems::quest_journal::tracked(player, quest_system)
+            .or_else(|| quest_system.get_active_quests().into_iter().next())
+            .and_then(|progress| {
+                let definition = quest_system.quest_definitions.get(&progress.quest_id)?;
+                let done = progress.objective_progress.values().filter(|objective| objective.completed).count();
+                Some((definition.title.clone(), done, definition.objectives.len()))
+            });
+
+        Self {
+            location: world.current_location()
+                .map_or(world.current_location.clone(), |location| location.name.clone()),
+            time: format!("{:02}:{:02}", world.hour_of_day(), world.game_time_minutes.rem_euclid(60)),
+            energy: (player.mental_state.current_energy, player.mental_state.max_energy),
+            fatigue: player.mental_state.fatigue,
+            crystal: player.active_crystal().map(|crystal| (crystal.display_name(), crystal.integrity)),
+            quest,
+            map: map::render_grid(world),
+        }
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let label = Style::default().add_modifier(Modifier::BOLD);
+        let row = |name: &str, value: String| Line::from(vec![
+            Span::styled(format!("{:<10}", name), label),
+            Span::raw(value),
+        ]);
+
+        let mut lines = vec![
+            row("Time", self.time.clone()),
+            row("Energy", format!("{}/{}", self.energy.0, self.energy.1)),
+            row("Fatigue", format!("{}%", self.fatigue)),
+        ];
+        lines.push(match &self.crystal {
+            Some((name, integrity)) => row("Crystal", format!("{} {:.0}%", name, integrity)),
+            None => row("Crystal", "none".to_string()),
+        });
+        lines.push(Line::raw(""));
+        lines.push(Line::styled("Quest", label));
+        lines.push(match &self.quest {
+            Some((title, done, total)) => Line::raw(format!("{} ({}/{})", title, done, total)),
+            None => Line::raw("None under way"),
+        });
+        lines
+    }
+}
+
+/// Turn text with ANSI styling, as [`crate::ui::style_output`] writes it,
+/// into styled lines
+pub fn styled_lines(text: &str) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut style = Style::default();
+    for raw in text.split('\n') {
+        let mut spans = Vec::new();
+        let mut rest = raw;
+        while let Some(start) = rest.find("\x1b[") {
+            if start > 0 {
+                spans.push(Span::styled(rest[..start].to_string(), style));
+            }
+            let after = &rest[start + 2..];
+            let Some(end) = after.find('m') else {
+                rest = "";
+                break;
+            };
+            style = apply_sgr(style, &after[..end]);
+            rest = &after[end + 1..];
+        }
+        if !rest.is_empty() {
+            spans.push(Span::styled(rest.to_string(), style));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+/// Apply the parameters of one SGR escape to a style
+fn apply_sgr(style: Style, parameters: &str) -> Style {
+    let codes: Vec<u8> = parameters.split(';').filter_map(|code| code.parse().ok()).collect();
+    let mut style = style;
+    let mut codes = codes.iter();
+    while let Some(code) = codes.next() {
+        style = match code {
+            0 => Style::default(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            30..=37 => style.fg(Color::Indexed(code - 30)),
+            90..=97 => style.fg(Color::Indexed(code - 90 + 8)),
+            38 => match (codes.next(), codes.next()) {
+                (Some(5), Some(index)) => style.fg(Color::Indexed(*index)),
+                _ => style,
+            },
+            _ => style,
+        };
+    }
+    style
+}
+
+/// Rows text takes up once wrapped to a width
+fn wrapped_height(lines: &[Line], width: u16) -> usize {
+    let width = usize::from(width.max(1));
+    lines.iter().map(|line| line.width().max(1).div_ceil(width)).sum()
+}
+
+/// What the screen shows, apart from the terminal it's drawn on
+#[derive(Debug, Default)]
+pub struct Screen {
+    /// Output of the last command
+    narrative: Vec<String>,
+    /// Everything shown this session, oldest first
+    log: Vec<String>,
+    /// Rows the log is scrolled back from its end
+    scroll: usize,
+    status: StatusPanel,
+    prompt: String,
+    /// The command being typed, and the cursor's place in it in characters
+    line: String,
+    cursor: usize,
+}
+
+impl Screen {
+    /// Add output from the game
+    pub fn show(&mut self, text: &str) {
+        self.narrative.push(text.trim_end().to_string());
+        self.log.push(text.trim_end().to_string());
+        if self.log.len() > LOG_LINES {
+            self.log.drain(..self.log.len() - LOG_LINES);
+        }
+        self.scroll = 0;
+    }
+
+    /// Start a new command, clearing the narrative pane for what it shows
+    fn submit(&mut self) -> String {
+        let command = std::mem::take(&mut self.line);
+        self.cursor = 0;
+        self.narrative.clear();
+        self.log.push(format!("> {}", command));
+        self.scroll = 0;
+        command
+    }
+
+    fn set_line(&mut self, line: String) {
+        self.cursor = line.chars().count();
+        self.line = line;
+    }
+
+    fn byte_index(&self) -> usize {
+        self.line.char_indices().nth(self.cursor).map_or(self.line.len(), |(index, _)| index)
+    }
+
+    /// Draw every pane
+    pub fn draw(&self, frame: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(6), Constraint::Length(1)])
+            .split(frame.size());
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(30), Constraint::Length(SIDEBAR_WIDTH)])
+            .split(rows[0]);
+        let main = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(columns[0]);
+        let sidebar = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(10), Constraint::Min(3)])
+            .split(columns[1]);
+
+        let narrative = styled_lines(&self.narrative.join("\n\n"));
+        frame.render_widget(
+            Paragraph::new(narrative)
+                .block(Block::default().borders(Borders::ALL).title(format!(" {} ", self.status.location)))
+                .wrap(Wrap { trim: false }),
+            main[0],
+        );
+        self.draw_log(frame, main[1]);
+
+        frame.render_widget(
+            Paragraph::new(self.status.lines())
+                .block(Block::default().borders(Borders::ALL).title(" Status "))
+                .wrap(Wrap { trim: false }),
+            sidebar[0],
+        );
+        let map_lines: Vec<Line> = self.status.map.iter().map(|row| Line::raw(row.clone())).collect();
+        frame.render_widget(
+            Paragraph::new(map_lines).block(Block::default().borders(Borders::ALL).title(" Map ")),
+            sidebar[1],
+        );
+
+        let input = format!("{}> {}", self.prompt, self.line);
+        frame.render_widget(Paragraph::new(input), rows[1]);
+        let typed = self.line.chars().take(self.cursor).collect::<String>();
+        let column = Line::raw(format!("{}> {}", self.prompt, typed)).width() as u16;
+        frame.set_cursor(rows[1].x + column.min(rows[1].width.saturating_sub(1)), rows[1].y);
+    }
+
+    /// The log pane, showing its end unless scrolled back
+    fn draw_log(&self, frame: &mut Frame, area: Rect) {
+        let lines = styled_lines(&self.log.join("\n"));
+        let inner = Rect { width: area.width.saturating_sub(2), height: area.height.saturating_sub(2), ..area };
+        let overflow = wrapped_height(&lines, inner.width).saturating_sub(usize::from(inner.height));
+        let top = overflow.saturating_sub(self.scroll);
+        let title = if self.scroll > 0 { " Log (PgDn for newer) " } else { " Log " };
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(Block::default().borders(Borders::ALL).title(title))
+                .wrap(Wrap { trim: false })
+                .scroll((u16::try_from(top).unwrap_or(u16::MAX), 0)),
+            area,
+        );
+    }
+
+    /// Act on a key, returning a line once one is complete
+    fn key(&mut self, key: KeyEvent, history: &[String], recall: &mut Option<usize>) -> Option<InputLine> {
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('c') if control => return Some(InputLine::Interrupted),
+            KeyCode::Char('d') if control && self.line.is_empty() => return Some(InputLine::Closed),
+            KeyCode::Char('u') if control => self.set_line(String::new()),
+            KeyCode::Char(c) if !control => {
+                let index = self.byte_index();
+                self.line.insert(index, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                let index = self.byte_index();
+                self.line.remove(index);
+            }
+            KeyCode::Delete if self.cursor < self.line.chars().count() => {
+                let index = self.byte_index();
+                self.line.remove(index);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.line.chars().count()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.line.chars().count(),
+            KeyCode::Up if !history.is_empty() => {
+                let index = recall.map_or(history.len() - 1, |index| index.saturating_sub(1));
+                *recall = Some(index);
+                self.set_line(history[index].clone());
+            }
+            KeyCode::Down => match *recall {
+                Some(index) if index + 1 < history.len() => {
+                    *recall = Some(index + 1);
+                    self.set_line(history[index + 1].clone());
+                }
+                Some(_) => {
+                    *recall = None;
+                    self.set_line(String::new());
+                }
+                None => {}
+            },
+            KeyCode::PageUp => self.scroll += SCROLL_STEP,
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_sub(SCROLL_STEP),
+            KeyCode::Esc => self.set_line(String::new()),
+            KeyCode::Enter => return Some(InputLine::Command(self.submit().trim().to_string())),
+            _ => {}
+        }
+        None
+    }
+}
+
+/// The full-screen interface, reading commands from the keyboard
+pub struct TuiInput {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+    screen: Screen,
+    history: Vec<String>,
+    history_path: PathBuf,
+    /// Whether the terminal is still in raw mode on the alternate screen
+    active: bool,
+}
+
+/// Set once a panic has handed the terminal back; from then on output is
+/// printed plainly
+static RELEASED: AtomicBool = AtomicBool::new(false);
+
+impl TuiInput {
+    /// Take over the terminal, loading command history from the given file
+    /// if it exists
+    pub fn open(history_path: PathBuf) -> GameResult<Self> {
+        // The history file is shared with the line editor, which starts it
+        // with a version line
+        let history = std::fs::read_to_string(&history_path)
+            .map(|text| text.lines().filter(|line| !line.starts_with("#V")).map(str::to_string).collect())
+            .unwrap_or_default();
+
+        terminal::enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        if let Err(e) = execute!(stdout, EnterAlternateScreen) {
+            let _ = terminal::disable_raw_mode();
+            return Err(e.into());
+        }
+        let terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+        // A panic must not leave the terminal raw and on the alternate
+        // screen, or its report (and any recovery instructions) is lost
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            RELEASED.store(true, Ordering::Relaxed);
+            let _ = terminal::disable_raw_mode();
+            let _ = execute!(io::stdout(), LeaveAlternateScreen, crossterm::cursor::Show);
+            previous(info);
+        }));
+
+        Ok(Self { terminal, screen: Screen::default(), history, history_path, active: true })
+    }
+
+    /// Hand the terminal back as it was
+    fn restore(&mut self) {
+        if self.active {
+            self.active = false;
+            let _ = terminal::disable_raw_mode();
+            let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
+            let _ = self.terminal.show_cursor();
+        }
+    }
+}
+
+impl InputSource for TuiInput {
+    fn read_line(&mut self, prompt: &str) -> GameResult<InputLine> {
+        self.screen.prompt = prompt.trim_end().trim_end_matches('>').trim_end().to_string();
+        if !self.screen.prompt.is_empty() {
+            self.screen.prompt.push(' ');
+        }
+
+        let mut recall = None;
+        loop {
+            self.terminal.draw(|frame| self.screen.draw(frame))?;
+            let Event::Key(key) = event::read()? else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Some(line) = self.screen.key(key, &self.history, &mut recall) {
+                if let InputLine::Command(command) = &line {
+                    if !command.is_empty() && self.history.last() != Some(command) {
+                        self.history.push(command.clone());
+                    }
+                }
+                return Ok(line);
+            }
+        }
+    }
+
+    fn show(&mut self, text: &str) {
+        if RELEASED.load(Ordering::Relaxed) {
+            println!("{}", text);
+        } else {
+            self.screen.show(text);
+        }
+    }
+
+    fn offer_status(&mut self, status: StatusPanel) {
+        self.screen.status = status;
+    }
+
+    fn history(&self) -> Vec<String> {
+        self.history.clone()
+    }
+
+    fn finish(&mut self) {
+        self.restore();
+        let mut text = String::from("#V2\n");
+        for command in &self.history {
+            text.push_str(command);
+            text.push('\n');
+        }
+        if let Err(e) = std::fs::write(&self.history_path, text) {
+            eprintln!("Failed to save command history: {}", e);
+        }
+    }
+}
+
+impl Drop for TuiInput {
+    fn drop(&mut self) {
+        self.restore();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::{style_output, ColorSupport, Theme};
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn test_themed_output_keeps_its_styling_in_the_panes() {
+        let text = style_output("=== Tutorial Chamber ===\nExits: north", Theme::Solarized, ColorSupport::Extended);
+        let lines = styled_lines(&text);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].spans[0].content, "=== Tutorial Chamber ===");
+        assert_eq!(lines[0].spans[0].style.fg, Some(Color::Indexed(33)));
+        assert!(lines[0].spans[0].style.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(lines[1].spans[0].content, "Exits");
+        assert_eq!(lines[1].spans[1].content, ": north");
+        assert_eq!(lines[1].spans[1].style, Style::default());
+
+        assert_eq!(styled_lines("plain"), vec![Line::from(vec![Span::raw("plain")])]);
+    }
+
+    #[test]
+    fn test_screen_shows_narrative_status_map_and_command_line() {
+        let mut world = WorldState::new();
+        let mut chamber = crate::core::world_state::Location::new(
+            "chamber".to_string(), "Tutorial Chamber".to_string(), "A chamber.".to_string(),
+        );
+        chamber.add_exit(crate::core::world_state::Direction::North, "hall".to_string());
+        world.add_location(chamber);
+        let mut hall = crate::core::world_state::Location::new("hall".to_string(), "Hall".to_string(), "A hall.".to_string());
+        hall.add_exit(crate::core::world_state::Direction::South, "chamber".to_string());
+        world.add_location(hall);
+        world.current_location = "chamber".to_string();
+        world.mark_current_visited();
+        let player = Player::new("Tester".to_string());
+
+        let mut screen = Screen::default();
+        screen.status = StatusPanel::gather(&player, &world, &QuestSystem::new());
+        assert_eq!(screen.status.map, vec!["[?]".to_string(), " |".to_string(), "[@]".to_string()]);
+
+        let mut recall = None;
+        for c in "look".chars() {
+            screen.key(KeyEvent::from(KeyCode::Char(c)), &[], &mut recall);
+        }
+        assert_eq!(screen.key(KeyEvent::from(KeyCode::Enter), &[], &mut recall), Some(InputLine::Command("look".to_string())));
+        screen.show("Crystal formations hum softly.\n");
+        screen.key(KeyEvent::from(KeyCode::Up), &["go north".to_string()], &mut recall);
+        assert_eq!(screen.line, "go north");
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|frame| screen.draw(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let rows: Vec<String> = (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer.get(x, y).symbol.clone()).collect())
+            .collect();
+        let screen_text = rows.join("\n");
+        assert!(screen_text.contains("Tutorial Chamber"));
+        assert!(screen_text.contains("Crystal formations hum softly."));
+        assert!(screen_text.contains("> look"));
+        assert!(screen_text.contains("Energy"));
+        assert!(screen_text.contains("[@]"));
+        assert!(rows[23].starts_with("> go north"));
+    }
+}
@@ -0,0 +1,104 @@
+//! Integration tests for starting the game on a database from an older release
+//!
+//! These tests run the game binary against a database laid out as schema
+//! version 3, the layout the first releases shipped, and check that it is
+//! migrated on a normal start rather than only by `--init-db`.
+
+use rusqlite::Connection;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use sympathetic_resonance::persistence::migrations;
+use tempfile::TempDir;
+
+/// Tables as they stood at schema version 3, with just enough content to play
+const VERSION_3_DATABASE: &str = "
+    CREATE TABLE schema_version (
+        version INTEGER PRIMARY KEY
+    );
+    CREATE TABLE locations (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        ambient_energy REAL DEFAULT 1.0,
+        dominant_frequency INTEGER,
+        interference REAL DEFAULT 0.0,
+        phenomena TEXT,
+        visited BOOLEAN DEFAULT FALSE
+    );
+    CREATE TABLE location_exits (
+        location_id TEXT NOT NULL,
+        direction TEXT NOT NULL,
+        destination_id TEXT NOT NULL,
+        PRIMARY KEY(location_id, direction)
+    );
+    CREATE TABLE npcs (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        faction_id TEXT,
+        dialogue_tree TEXT,
+        current_location TEXT
+    );
+    CREATE TABLE items (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        description TEXT NOT NULL,
+        item_type TEXT NOT NULL,
+        properties TEXT
+    );
+    CREATE TABLE faction_presence (
+        location_id TEXT NOT NULL,
+        faction_id TEXT NOT NULL,
+        influence INTEGER NOT NULL,
+        visibility TEXT NOT NULL,
+        member_count INTEGER DEFAULT 0,
+        PRIMARY KEY(location_id, faction_id)
+    );
+    INSERT INTO schema_version (version) VALUES (2), (3);
+    INSERT INTO locations (id, name, description, phenomena) VALUES
+        ('tutorial_chamber', 'Tutorial Chamber', 'A simple stone chamber.', '[]'),
+        ('practice_hall', 'Practice Hall', 'A larger chamber for experiments.', '[]');
+    INSERT INTO location_exits (location_id, direction, destination_id) VALUES
+        ('tutorial_chamber', 'north', 'practice_hall'),
+        ('practice_hall', 'south', 'tutorial_chamber');
+";
+
+/// A directory laid out like an installed game, holding a version 3 database
+fn version_3_install() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("content")).unwrap();
+    let connection = Connection::open(dir.path().join("content/database.db")).unwrap();
+    connection.execute_batch(VERSION_3_DATABASE).unwrap();
+    dir
+}
+
+fn schema_version(install: &Path) -> i32 {
+    let connection = Connection::open(install.join("content/database.db")).unwrap();
+    connection.query_row("SELECT MAX(version) FROM schema_version", [], |row| row.get(0)).unwrap()
+}
+
+#[test]
+fn test_game_starts_on_version_3_database() {
+    let install = version_3_install();
+    fs::write(install.path().join("script.txt"), "look\nnorth\nquit\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_sympathetic-resonance"))
+        .current_dir(install.path())
+        .env("HOME", install.path())
+        .env("XDG_DATA_HOME", install.path().join("data"))
+        .arg("--script")
+        .arg("script.txt")
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Game failed to start on a version 3 database:\n{}\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Practice Hall"), "Expected to walk into the Practice Hall:\n{}", stdout);
+    assert_eq!(schema_version(install.path()), migrations::latest_version());
+}
@@ -232,3 +232,27 @@ fn test_cannot_drop_equipped_item() {
     // The drop command should check if item is equipped and prevent dropping
     // This is tested in the drop handler itself
 }
+
+#[test]
+fn test_locations_are_stocked_and_respawn_items() {
+    let mut engine = create_test_engine();
+    let location_id = engine.world().current_location.clone();
+    let stocked = engine.world().current_location().unwrap().items.clone();
+    assert!(!stocked.is_empty(), "Starting location should be stocked from the database");
+
+    // Taking a respawning item schedules its return
+    let item_id = stocked[0].clone();
+    let placement = sympathetic_resonance::systems::placed_items::Placement {
+        location: location_id,
+        item_id: item_id.clone(),
+        respawn_minutes: Some(30),
+    };
+    let now = engine.world().game_time_minutes;
+    engine.world_mut().current_location_mut().unwrap().items.retain(|i| *i != item_id);
+    engine.world_mut().placed_items.taken(&placement, now);
+
+    engine.world_mut().advance_time(29);
+    assert!(!engine.world().current_location().unwrap().items.contains(&item_id));
+    engine.world_mut().advance_time(1);
+    assert!(engine.world().current_location().unwrap().items.contains(&item_id));
+}